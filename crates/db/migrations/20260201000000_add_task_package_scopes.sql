@@ -0,0 +1,13 @@
+-- Scope a task to a single package inside a monorepo repository
+CREATE TABLE task_package_scopes (
+    task_id         BLOB PRIMARY KEY,
+    repo_id         BLOB NOT NULL,
+    package_path    TEXT NOT NULL,
+    package_name    TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_package_scopes_repo_id ON task_package_scopes(repo_id);
@@ -0,0 +1,18 @@
+-- Individual test outcomes parsed from script output, used for flaky-test detection
+CREATE TABLE test_results (
+    id                      BLOB PRIMARY KEY,
+    project_id              BLOB NOT NULL,
+    workspace_id            BLOB NOT NULL,
+    execution_process_id    BLOB NOT NULL,
+    test_name               TEXT NOT NULL,
+    passed                  INTEGER NOT NULL,
+    -- HEAD commit(s) the tests ran against; equal fingerprints mean unchanged code
+    code_fingerprint        TEXT NOT NULL,
+    created_at              TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_test_results_project_test ON test_results(project_id, test_name);
+CREATE INDEX idx_test_results_execution_process_id ON test_results(execution_process_id);
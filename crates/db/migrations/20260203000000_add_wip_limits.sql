@@ -0,0 +1,34 @@
+-- Work-in-progress limits per column (task status).
+-- project_id NULL means the limit applies to the user's tasks across all projects.
+CREATE TABLE wip_limits (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB,
+    status          TEXT NOT NULL CHECK (status IN ('todo','inprogress','inreview','done','cancelled')),
+    max_tasks       INTEGER NOT NULL CHECK (max_tasks >= 0),
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_wip_limits_project_status ON wip_limits(project_id, status)
+WHERE project_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_wip_limits_user_status ON wip_limits(status)
+WHERE project_id IS NULL;
+
+-- Log of moves that exceeded a WIP limit and were overridden with a reason
+CREATE TABLE wip_limit_overrides (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    task_id         BLOB NOT NULL,
+    from_status     TEXT NOT NULL,
+    to_status       TEXT NOT NULL,
+    scope           TEXT NOT NULL CHECK (scope IN ('project','user')),
+    max_tasks       INTEGER NOT NULL,
+    current_count   INTEGER NOT NULL,
+    reason          TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_wip_limit_overrides_project_id ON wip_limit_overrides(project_id);
@@ -0,0 +1,7 @@
+-- Task priority. priority_escalated_at is set when the aging job bumps a task
+-- that sat in Todo for too long and is cleared when the priority is set manually.
+ALTER TABLE tasks ADD COLUMN priority TEXT NOT NULL DEFAULT 'medium'
+    CHECK (priority IN ('low','medium','high','urgent'));
+ALTER TABLE tasks ADD COLUMN priority_escalated_at TEXT;
+
+CREATE INDEX idx_tasks_status_priority ON tasks(status, priority);
@@ -0,0 +1,10 @@
+-- Tombstones left behind when a duplicate task is merged into a canonical one,
+-- so references to the merged task id keep resolving.
+CREATE TABLE task_redirects (
+    from_task_id    BLOB PRIMARY KEY,
+    to_task_id      BLOB NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (to_task_id) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_redirects_to_task_id ON task_redirects(to_task_id);
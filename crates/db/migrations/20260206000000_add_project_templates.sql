@@ -0,0 +1,16 @@
+-- Projects can be marked as templates that new projects are created from
+ALTER TABLE projects ADD COLUMN is_template INTEGER NOT NULL DEFAULT 0;
+
+-- Documents written into the repositories of projects created from the project
+CREATE TABLE project_seed_documents (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    path            TEXT NOT NULL,
+    content         TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, path)
+);
+
+CREATE INDEX idx_project_seed_documents_project_id ON project_seed_documents(project_id);
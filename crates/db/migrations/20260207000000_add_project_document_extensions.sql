@@ -0,0 +1,3 @@
+-- File extensions shown in a project's document browser, as a JSON array
+-- (e.g. ["md","yaml"]). NULL means every supported text format.
+ALTER TABLE projects ADD COLUMN document_extensions TEXT;
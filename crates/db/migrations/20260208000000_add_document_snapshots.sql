@@ -0,0 +1,14 @@
+-- Point-in-time records of a repository's docs branch, taken on a schedule or on demand.
+-- Only the commit is stored; the documents themselves are read back from git.
+CREATE TABLE document_snapshots (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    repo_id         BLOB NOT NULL,
+    branch          TEXT NOT NULL,
+    commit_sha      TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_document_snapshots_project_repo ON document_snapshots(project_id, repo_id, created_at);
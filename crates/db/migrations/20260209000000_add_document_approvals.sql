@@ -0,0 +1,48 @@
+-- Folders of a project whose documents only change after review.
+-- reviewers is a JSON array of reviewer names; every one of them must approve.
+CREATE TABLE document_approval_rules (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    folder          TEXT NOT NULL,
+    reviewers       TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_document_approval_rules_project_folder
+ON document_approval_rules(project_id, folder);
+
+-- Edits to protected documents, staged until the required reviewers approve them.
+-- required_reviewers is copied from the matching rule when the change is submitted.
+CREATE TABLE document_change_requests (
+    id                  BLOB PRIMARY KEY,
+    project_id          BLOB NOT NULL,
+    repo_id             BLOB NOT NULL,
+    relative_path       TEXT NOT NULL,
+    content             TEXT NOT NULL,
+    required_reviewers  TEXT NOT NULL,
+    status              TEXT NOT NULL DEFAULT 'pending'
+                        CHECK (status IN ('pending','approved','rejected')),
+    commit_sha          TEXT,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_document_change_requests_project_status
+ON document_change_requests(project_id, status);
+
+CREATE TABLE document_change_reviews (
+    id                  BLOB PRIMARY KEY,
+    change_request_id   BLOB NOT NULL,
+    reviewer            TEXT NOT NULL,
+    decision            TEXT NOT NULL CHECK (decision IN ('approved','rejected')),
+    comment             TEXT,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (change_request_id) REFERENCES document_change_requests(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_document_change_reviews_request_reviewer
+ON document_change_reviews(change_request_id, reviewer);
@@ -0,0 +1,3 @@
+-- Path globs (e.g. ["generated/**","legal/**"]) whose documents can't be created,
+-- edited, deleted or moved through the document API, as a JSON array. NULL means none.
+ALTER TABLE projects ADD COLUMN document_read_only_paths TEXT;
@@ -0,0 +1,3 @@
+-- Limits for scanning a project's repositories for documents, as a JSON object
+-- ({"max_files":..,"max_depth":..,"max_seconds":..}). NULL means the defaults.
+ALTER TABLE projects ADD COLUMN document_scan_limits TEXT;
@@ -0,0 +1,20 @@
+-- Soft locks that reserve a document for one editor until they expire.
+-- holder is a free-form name chosen by the client; expired rows are taken over on acquire.
+CREATE TABLE document_locks (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    repo_id         BLOB NOT NULL,
+    relative_path   TEXT NOT NULL,
+    holder          TEXT NOT NULL,
+    expires_at      TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_document_locks_repo_path
+ON document_locks(repo_id, relative_path);
+
+CREATE INDEX idx_document_locks_project_id
+ON document_locks(project_id);
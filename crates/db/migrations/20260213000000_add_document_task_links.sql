@@ -0,0 +1,22 @@
+-- Board tasks materialized from task list items of planning documents.
+-- checked is the item's checkbox state as of the last sync, which tells whether the
+-- document or the board changed when the two disagree.
+CREATE TABLE document_task_links (
+    id              BLOB PRIMARY KEY,
+    task_id         BLOB NOT NULL,
+    project_id      BLOB NOT NULL,
+    repo_id         BLOB NOT NULL,
+    relative_path   TEXT NOT NULL,
+    checked         INTEGER NOT NULL DEFAULT 0,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_document_task_links_task_id
+ON document_task_links(task_id);
+
+CREATE INDEX idx_document_task_links_document
+ON document_task_links(repo_id, relative_path);
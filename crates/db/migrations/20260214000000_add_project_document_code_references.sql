@@ -0,0 +1,3 @@
+-- Code each document describes, beyond the files it links to, as a JSON array of
+-- {"documents": "<path glob>", "code": ["<file or folder>", ...]}. NULL means none.
+ALTER TABLE projects ADD COLUMN document_code_references TEXT;
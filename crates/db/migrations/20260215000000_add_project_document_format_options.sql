@@ -0,0 +1,3 @@
+-- How markdown documents of a project are formatted, as a JSON object
+-- ({"line_width":..,"heading_style":..,"align_tables":..}). NULL means the defaults.
+ALTER TABLE projects ADD COLUMN document_format_options TEXT;
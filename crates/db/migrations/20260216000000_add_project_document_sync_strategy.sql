@@ -0,0 +1,4 @@
+-- How document sync brings in commits from origin/main before pushing. Some teams
+-- forbid rebasing shared branches, so merge commits and fast-forward only are options.
+ALTER TABLE projects ADD COLUMN document_sync_strategy TEXT NOT NULL DEFAULT 'rebase'
+    CHECK (document_sync_strategy IN ('rebase','merge','fast_forward_only'));
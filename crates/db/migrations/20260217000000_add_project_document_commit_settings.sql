@@ -0,0 +1,4 @@
+-- Commit message template and identity for document commits, as a JSON object
+-- ({"message_template":..,"committer_name":..,"committer_email":..}). NULL means the
+-- default messages and the repository's configured identity.
+ALTER TABLE projects ADD COLUMN document_commit_settings TEXT;
@@ -0,0 +1,3 @@
+-- Whether document saves are committed right away. Teams that save drafts repeatedly
+-- turn it off and commit pending changes together with an explicit message.
+ALTER TABLE projects ADD COLUMN document_auto_commit BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,22 @@
+-- Unsaved editor content, autosaved by the client per author and document, so writing
+-- that couldn't be saved or committed (browser crash, branch guard) isn't lost.
+-- author is a free-form name chosen by the client, like document lock holders.
+CREATE TABLE document_drafts (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    repo_id         BLOB NOT NULL,
+    relative_path   TEXT NOT NULL,
+    author          TEXT NOT NULL,
+    content         TEXT NOT NULL,
+    base_hash       TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_document_drafts_repo_path_author
+ON document_drafts(repo_id, relative_path, author);
+
+CREATE INDEX idx_document_drafts_project_author
+ON document_drafts(project_id, author);
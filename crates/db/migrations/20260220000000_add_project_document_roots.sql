@@ -0,0 +1,3 @@
+-- Folders the project's documents live in, as a JSON array of paths relative to the
+-- repository root (e.g. ["docs","seed_docs"]). NULL means the whole repository.
+ALTER TABLE projects ADD COLUMN document_roots TEXT;
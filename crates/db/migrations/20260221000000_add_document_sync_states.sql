@@ -0,0 +1,13 @@
+-- Last known docs drift of each project against origin/main, kept up to date by the
+-- background fetch and by syncs, so the drift of all projects can be read in one call.
+CREATE TABLE document_sync_states (
+    project_id      BLOB PRIMARY KEY,
+    current_branch  TEXT,
+    commits_ahead   INTEGER NOT NULL DEFAULT 0,
+    commits_behind  INTEGER NOT NULL DEFAULT 0,
+    fetched_at      TEXT,
+    last_synced_at  TEXT,
+    last_error      TEXT,
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
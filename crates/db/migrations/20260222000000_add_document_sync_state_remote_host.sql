@@ -0,0 +1,3 @@
+-- Host of the origin remote at the last fetch, so the sync status overview can flag
+-- projects whose host is currently unreachable.
+ALTER TABLE document_sync_states ADD COLUMN remote_host TEXT;
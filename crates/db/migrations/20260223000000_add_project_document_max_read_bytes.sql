@@ -0,0 +1,3 @@
+-- Largest document served with its content; bigger ones are returned as metadata only.
+-- NULL uses the default.
+ALTER TABLE projects ADD COLUMN document_max_read_bytes INTEGER;
@@ -0,0 +1,29 @@
+-- Folders of a project whose documents are encrypted before they are written to the
+-- working tree. readers is a JSON array of GitHub user names allowed to read them;
+-- an empty array lets anyone with access to the project read them.
+CREATE TABLE document_encrypted_folders (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    folder          TEXT NOT NULL,
+    readers         TEXT NOT NULL DEFAULT '[]',
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX idx_document_encrypted_folders_project_folder
+ON document_encrypted_folders(project_id, folder);
+
+-- Keys of a project's encrypted documents. New documents are encrypted with the key
+-- that isn't retired; retired keys are kept to read older commits.
+CREATE TABLE document_encryption_keys (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    key_material    BLOB NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    retired_at      TEXT,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_document_encryption_keys_project
+ON document_encryption_keys(project_id);
@@ -0,0 +1,9 @@
+-- Machines allowed to run coding agents for this server. Only the SHA-256 of a worker's
+-- token is stored; the token itself is shown once, when the worker is registered.
+CREATE TABLE workers (
+    id              BLOB PRIMARY KEY,
+    name            TEXT NOT NULL,
+    token_hash      TEXT NOT NULL UNIQUE,
+    last_seen_at    TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
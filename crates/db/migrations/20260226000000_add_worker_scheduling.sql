@@ -0,0 +1,16 @@
+-- Capabilities a worker reports in its heartbeats, e.g. ["docker", "gpu"], and whether it
+-- is drained: it finishes what it runs but isn't given new executions.
+ALTER TABLE workers ADD COLUMN capabilities TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE workers ADD COLUMN draining INTEGER NOT NULL DEFAULT 0;
+
+-- Capabilities a worker needs to run the coding agents of a task, or of an executor
+-- (e.g. CLAUDE_CODE) in any task. An execution needs the union of both.
+CREATE TABLE worker_requirements (
+    id              BLOB PRIMARY KEY,
+    task_id         BLOB UNIQUE REFERENCES tasks(id) ON DELETE CASCADE,
+    executor        TEXT UNIQUE,
+    capabilities    TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    CHECK ((task_id IS NULL) <> (executor IS NULL))
+);
@@ -0,0 +1,3 @@
+-- Folder next to a document that images uploaded for it are stored in. NULL uses the
+-- default, `assets`.
+ALTER TABLE projects ADD COLUMN document_assets_folder TEXT;
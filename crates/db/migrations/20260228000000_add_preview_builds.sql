@@ -0,0 +1,22 @@
+-- Script that builds a repository's web app into a static bundle, and the folder of the
+-- worktree it writes the bundle to, e.g. `npm run build` and `dist`.
+ALTER TABLE repos ADD COLUMN preview_build_script TEXT;
+ALTER TABLE repos ADD COLUMN preview_output_dir TEXT;
+
+-- Builds of an attempt's worktree for review. The bundle of a succeeded build is copied
+-- out of the worktree, so it outlives later changes to the attempt.
+CREATE TABLE preview_builds (
+    id              BLOB PRIMARY KEY,
+    workspace_id    BLOB NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    repo_id         BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    status          TEXT NOT NULL DEFAULT 'running'
+                       CHECK (status IN ('running', 'succeeded', 'failed')),
+    commit_sha      TEXT,
+    output          TEXT NOT NULL DEFAULT '',
+    error           TEXT,
+    size_bytes      INTEGER,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at    TEXT
+);
+
+CREATE INDEX idx_preview_builds_workspace_id ON preview_builds(workspace_id);
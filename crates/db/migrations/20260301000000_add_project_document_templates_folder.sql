@@ -0,0 +1,3 @@
+-- Folder of the primary repository that document templates are read from. NULL uses the
+-- default, `.templates`.
+ALTER TABLE projects ADD COLUMN document_templates_folder TEXT;
@@ -0,0 +1,15 @@
+-- Screenshots of an attempt's preview, taken by a headless browser at a route of the
+-- preview and a viewport size. The images are stored outside the worktree.
+CREATE TABLE preview_screenshots (
+    id              BLOB PRIMARY KEY,
+    workspace_id    BLOB NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    url             TEXT NOT NULL,
+    route           TEXT NOT NULL,
+    viewport        TEXT NOT NULL,
+    width           INTEGER NOT NULL,
+    height          INTEGER NOT NULL,
+    size_bytes      INTEGER NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_preview_screenshots_workspace_id ON preview_screenshots(workspace_id);
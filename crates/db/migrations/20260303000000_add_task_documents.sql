@@ -0,0 +1,15 @@
+-- Documents related to a task, such as the design docs it implements. Unlike
+-- document_task_links, a task can relate to any number of documents.
+CREATE TABLE task_documents (
+    id              BLOB PRIMARY KEY,
+    task_id         BLOB NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    repo_id         BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    relative_path   TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE UNIQUE INDEX idx_task_documents_task_document
+ON task_documents(task_id, repo_id, relative_path);
+
+CREATE INDEX idx_task_documents_document
+ON task_documents(repo_id, relative_path);
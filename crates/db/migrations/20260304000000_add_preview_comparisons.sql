@@ -0,0 +1,23 @@
+-- Builds of an attempt's fork point with its target branch rather than of its worktree,
+-- which the attempt's preview is compared against
+ALTER TABLE preview_builds ADD COLUMN base BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Comparisons of screenshots of an attempt's preview with screenshots of its base at the
+-- same route and viewport. The base screenshot and the image highlighting the changed
+-- pixels are stored next to the attempt's screenshots.
+CREATE TABLE preview_comparisons (
+    id               BLOB PRIMARY KEY,
+    workspace_id     BLOB NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    screenshot_id    BLOB NOT NULL REFERENCES preview_screenshots(id) ON DELETE CASCADE,
+    base_url         TEXT NOT NULL,
+    changed_pixels   INTEGER NOT NULL,
+    total_pixels     INTEGER NOT NULL,
+    changed_regions  INTEGER NOT NULL,
+    total_regions    INTEGER NOT NULL,
+    size_changed     BOOLEAN NOT NULL DEFAULT FALSE,
+    significant      BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_preview_comparisons_workspace_id ON preview_comparisons(workspace_id);
+CREATE INDEX idx_preview_comparisons_screenshot_id ON preview_comparisons(screenshot_id);
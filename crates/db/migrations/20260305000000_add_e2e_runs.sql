@@ -0,0 +1,42 @@
+-- Command that runs a repository's end-to-end suite, e.g. `npx playwright test`, and the
+-- folder of the worktree it writes its JUnit reports, traces and videos to.
+ALTER TABLE repos ADD COLUMN e2e_script TEXT;
+ALTER TABLE repos ADD COLUMN e2e_results_dir TEXT;
+
+-- Runs of a repository's end-to-end suite against an attempt's preview. The results
+-- folder is copied out of the worktree as the run's artifacts.
+CREATE TABLE e2e_runs (
+    id              BLOB PRIMARY KEY,
+    workspace_id    BLOB NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    repo_id         BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    status          TEXT NOT NULL DEFAULT 'running'
+                       CHECK (status IN ('running', 'passed', 'failed')),
+    base_url        TEXT NOT NULL,
+    commit_sha      TEXT,
+    output          TEXT NOT NULL DEFAULT '',
+    error           TEXT,
+    passed_count    INTEGER NOT NULL DEFAULT 0,
+    failed_count    INTEGER NOT NULL DEFAULT 0,
+    skipped_count   INTEGER NOT NULL DEFAULT 0,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at    TEXT
+);
+
+CREATE INDEX idx_e2e_runs_workspace_id ON e2e_runs(workspace_id);
+
+-- Test cases of a run, parsed from the JUnit reports in its results folder
+CREATE TABLE e2e_test_cases (
+    id               BLOB PRIMARY KEY,
+    run_id           BLOB NOT NULL REFERENCES e2e_runs(id) ON DELETE CASCADE,
+    suite            TEXT NOT NULL,
+    name             TEXT NOT NULL,
+    status           TEXT NOT NULL
+                        CHECK (status IN ('passed', 'failed', 'skipped')),
+    duration_ms      INTEGER,
+    failure_message  TEXT,
+    -- JSON array of the paths of the case's traces, videos and screenshots in the
+    -- run's artifacts
+    attachments      TEXT NOT NULL DEFAULT '[]'
+);
+
+CREATE INDEX idx_e2e_test_cases_run_id ON e2e_test_cases(run_id);
@@ -0,0 +1,24 @@
+-- Comments of reviewers on a range of lines of a document, kept outside git. Besides its
+-- lines an annotation stores the text it was left on and the lines around it, so it can
+-- be found again after the document is edited.
+CREATE TABLE document_annotations (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    repo_id         BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    relative_path   TEXT NOT NULL,
+    line_start      INTEGER NOT NULL,
+    line_end        INTEGER NOT NULL,
+    quote           TEXT NOT NULL,
+    prefix          TEXT NOT NULL DEFAULT '',
+    suffix          TEXT NOT NULL DEFAULT '',
+    author          TEXT NOT NULL,
+    body            TEXT NOT NULL,
+    resolved        BOOLEAN NOT NULL DEFAULT FALSE,
+    resolved_by     TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_document_annotations_document
+    ON document_annotations(repo_id, relative_path);
+CREATE INDEX idx_document_annotations_project_id ON document_annotations(project_id);
@@ -0,0 +1,13 @@
+-- Tags of documents, e.g. "draft", "adr" or "runbook", kept outside git so tagging a
+-- document doesn't change it
+CREATE TABLE document_tags (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    repo_id         BLOB NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+    relative_path   TEXT NOT NULL,
+    tag             TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    UNIQUE (repo_id, relative_path, tag)
+);
+
+CREATE INDEX idx_document_tags_project_id ON document_tags(project_id, tag);
@@ -0,0 +1,3 @@
+-- Timezone of the project's schedules and due dates: an IANA name such as Europe/Paris
+-- or a UTC offset. NULL falls back to the user's timezone.
+ALTER TABLE projects ADD COLUMN timezone TEXT;
@@ -0,0 +1,3 @@
+-- Whether document edits of the project go to docs/ branches opened as pull requests
+-- instead of straight to main, for repositories whose main can't be pushed to.
+ALTER TABLE projects ADD COLUMN document_branch_editing BOOLEAN NOT NULL DEFAULT FALSE;
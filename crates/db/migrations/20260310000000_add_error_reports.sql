@@ -0,0 +1,17 @@
+-- Requests that failed with a 5xx, with what was logged while handling them, so
+-- self-hosters can attach them to bug reports. Only the newest reports are kept.
+CREATE TABLE error_reports (
+    id              BLOB PRIMARY KEY,
+    correlation_id  TEXT NOT NULL,
+    method          TEXT NOT NULL,
+    route           TEXT NOT NULL,
+    path            TEXT NOT NULL,
+    status          INTEGER NOT NULL,
+    message         TEXT,
+    log_lines       TEXT NOT NULL DEFAULT '[]',
+    state           TEXT NOT NULL DEFAULT '{}',
+    forwarded_at    TEXT,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+CREATE INDEX idx_error_reports_created_at ON error_reports(created_at);
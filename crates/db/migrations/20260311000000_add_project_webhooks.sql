@@ -0,0 +1,18 @@
+-- URLs called with a signed JSON payload when a project's documents change, e.g. to
+-- rebuild a static site. `events` is a JSON array of event names; empty means all.
+CREATE TABLE project_webhooks (
+    id                  BLOB PRIMARY KEY,
+    project_id          BLOB NOT NULL,
+    url                 TEXT NOT NULL,
+    secret              TEXT NOT NULL,
+    events              TEXT NOT NULL DEFAULT '[]',
+    enabled             BOOLEAN NOT NULL DEFAULT TRUE,
+    last_delivery_at    TEXT,
+    last_status         INTEGER,
+    last_error          TEXT,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_project_webhooks_project_id ON project_webhooks(project_id);
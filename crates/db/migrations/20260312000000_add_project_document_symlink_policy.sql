@@ -0,0 +1,5 @@
+-- How symbolic links in a project's repositories are treated when documents are scanned,
+-- read and saved. They are skipped unless the project follows the ones that stay inside
+-- the repository, since a linked folder can loop and a link can lead anywhere.
+ALTER TABLE projects ADD COLUMN document_symlink_policy TEXT NOT NULL DEFAULT 'skip'
+    CHECK (document_symlink_policy IN ('skip','follow_within_repo'));
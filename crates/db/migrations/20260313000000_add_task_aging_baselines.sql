@@ -0,0 +1,8 @@
+-- Baselines of the Todo aging rule. todo_since is when the task last entered Todo, so
+-- time spent in other columns doesn't count. priority_set_at is when the priority was
+-- last set by hand; the rule waits a full period again from there.
+ALTER TABLE tasks ADD COLUMN todo_since TEXT;
+ALTER TABLE tasks ADD COLUMN priority_set_at TEXT;
+
+-- When existing tasks entered Todo is unknown; their last update is the latest it can be
+UPDATE tasks SET todo_since = updated_at WHERE status = 'todo';
@@ -67,6 +67,19 @@ async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
     }
 }
 
+/// A fresh in-memory database with every migration applied
+#[cfg(test)]
+pub(crate) async fn test_pool() -> SqlitePool {
+    // One connection, since every connection to `:memory:` is its own database
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    run_migrations(&pool).await.unwrap();
+    pool
+}
+
 #[derive(Clone)]
 pub struct DBService {
     pub pool: Pool<Sqlite>,
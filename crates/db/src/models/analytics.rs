@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+
+/// Number of task attempts (workspaces) started on a day
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct DailyAttemptCount {
+    /// `YYYY-MM-DD` in UTC
+    pub date: String,
+    pub attempts: i64,
+}
+
+/// Coding agent run outcomes for one executor and variant
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct ExecutorRunStats {
+    pub executor: String,
+    pub variant: Option<String>,
+    pub runs: i64,
+    pub succeeded: i64,
+    pub failed: i64,
+    /// Wall-clock time spent in finished runs
+    pub runtime_seconds: f64,
+}
+
+impl DailyAttemptCount {
+    pub async fn find_between(
+        pool: &SqlitePool,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DailyAttemptCount,
+            r#"SELECT date(created_at) as "date!: String",
+                      COUNT(*)         as "attempts!: i64"
+               FROM workspaces
+               WHERE datetime(created_at) >= datetime($1)
+                 AND datetime(created_at) < datetime($2)
+               GROUP BY date(created_at)
+               ORDER BY date(created_at)"#,
+            since,
+            until
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+impl ExecutorRunStats {
+    pub async fn find_between(
+        pool: &SqlitePool,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutorRunStats,
+            r#"SELECT COALESCE(s.executor, 'UNKNOWN') as "executor!: String",
+                      json_extract(ep.executor_action, '$.typ.executor_profile_id.variant') as "variant: String",
+                      COUNT(*) as "runs!: i64",
+                      SUM(CASE WHEN ep.status = 'completed' THEN 1 ELSE 0 END) as "succeeded!: i64",
+                      SUM(CASE WHEN ep.status IN ('failed', 'killed') THEN 1 ELSE 0 END) as "failed!: i64",
+                      COALESCE(SUM(CASE WHEN ep.completed_at IS NOT NULL
+                                        THEN (julianday(ep.completed_at) - julianday(ep.started_at)) * 86400.0
+                                        ELSE 0 END), 0.0) as "runtime_seconds!: f64"
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               WHERE ep.run_reason = 'codingagent'
+                 AND datetime(ep.created_at) >= datetime($1)
+                 AND datetime(ep.created_at) < datetime($2)
+               GROUP BY 1, 2
+               ORDER BY 3 DESC, 1"#,
+            since,
+            until
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use super::execution_process::{ExecutionProcessRunReason, ExecutionProcessStatus};
+
+/// A coding agent or script run on one of a project's tasks
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditLogRun {
+    pub execution_process_id: Uuid,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub workspace_id: Uuid,
+    pub branch: String,
+    pub session_id: Uuid,
+    pub executor: Option<String>,
+    pub run_reason: ExecutionProcessRunReason,
+    pub status: ExecutionProcessStatus,
+    pub exit_code: Option<i64>,
+    /// Whether the run was later dropped, e.g. by resetting the attempt to an earlier turn
+    pub dropped: bool,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Prompt of a coding agent run
+    pub prompt: Option<String>,
+    /// Final message of a coding agent run
+    pub summary: Option<String>,
+}
+
+/// The commits a run started from and left behind in one repository
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditLogRepoChange {
+    pub execution_process_id: Uuid,
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub before_head_commit: Option<String>,
+    pub after_head_commit: Option<String>,
+    pub merge_commit: Option<String>,
+}
+
+/// An attempt (workspace) at one of a project's tasks
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskHistoryAttempt {
+    pub workspace_id: Uuid,
+    pub task_id: Uuid,
+    pub branch: String,
+    pub name: Option<String>,
+    pub archived: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AuditLogRun {
+    /// Runs on the project's tasks, oldest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AuditLogRun,
+            r#"SELECT ep.id as "execution_process_id!: Uuid",
+                      t.id as "task_id!: Uuid",
+                      t.title as "task_title!: String",
+                      w.id as "workspace_id!: Uuid",
+                      w.branch as "branch!: String",
+                      s.id as "session_id!: Uuid",
+                      s.executor,
+                      ep.run_reason as "run_reason!: ExecutionProcessRunReason",
+                      ep.status as "status!: ExecutionProcessStatus",
+                      ep.exit_code,
+                      ep.dropped as "dropped!: bool",
+                      ep.started_at as "started_at!: DateTime<Utc>",
+                      ep.completed_at as "completed_at?: DateTime<Utc>",
+                      (SELECT cat.prompt FROM coding_agent_turns cat
+                       WHERE cat.execution_process_id = ep.id
+                       ORDER BY cat.created_at LIMIT 1) as "prompt?: String",
+                      (SELECT cat.summary FROM coding_agent_turns cat
+                       WHERE cat.execution_process_id = ep.id
+                       ORDER BY cat.created_at LIMIT 1) as "summary?: String"
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = $1
+               ORDER BY ep.started_at, ep.created_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Runs that finished before `cutoff` in sessions with no run since, which
+    /// [`Self::delete_expired`] would delete
+    pub async fn count_expired(
+        pool: &SqlitePool,
+        cutoff: DateTime<Utc>,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM execution_processes ep
+               WHERE ep.status != 'running'
+                 AND datetime(ep.completed_at) < datetime($1)
+                 AND NOT EXISTS (
+                     SELECT 1 FROM execution_processes later
+                     WHERE later.session_id = ep.session_id
+                       AND (later.status = 'running'
+                            OR datetime(later.created_at) >= datetime($1))
+                 )"#,
+            cutoff
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Delete runs that finished before `cutoff` together with their logs, turns and
+    /// repository states. Sessions that ran since are kept whole so they can still be
+    /// continued.
+    pub async fn delete_expired(
+        pool: &SqlitePool,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM execution_processes
+               WHERE status != 'running'
+                 AND datetime(completed_at) < datetime($1)
+                 AND NOT EXISTS (
+                     SELECT 1 FROM execution_processes later
+                     WHERE later.session_id = execution_processes.session_id
+                       AND (later.status = 'running'
+                            OR datetime(later.created_at) >= datetime($1))
+                 )"#,
+            cutoff
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl AuditLogRepoChange {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AuditLogRepoChange,
+            r#"SELECT eprs.execution_process_id as "execution_process_id!: Uuid",
+                      eprs.repo_id as "repo_id!: Uuid",
+                      r.name as "repo_name!: String",
+                      eprs.before_head_commit,
+                      eprs.after_head_commit,
+                      eprs.merge_commit
+               FROM execution_process_repo_states eprs
+               JOIN repos r ON r.id = eprs.repo_id
+               JOIN execution_processes ep ON ep.id = eprs.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = $1
+               ORDER BY eprs.created_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+impl TaskHistoryAttempt {
+    /// Attempts at the project's tasks, oldest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskHistoryAttempt,
+            r#"SELECT w.id as "workspace_id!: Uuid",
+                      w.task_id as "task_id!: Uuid",
+                      w.branch,
+                      w.name,
+                      w.archived as "archived!: bool",
+                      w.created_at as "created_at!: DateTime<Utc>",
+                      w.updated_at as "updated_at!: DateTime<Utc>"
+               FROM workspaces w
+               JOIN tasks t ON t.id = w.task_id
+               WHERE t.project_id = $1
+               ORDER BY w.created_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
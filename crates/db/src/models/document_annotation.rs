@@ -0,0 +1,339 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A reviewer's comment on a range of lines of a document, kept outside git
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentAnnotation {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub repo_id: Uuid,
+    pub relative_path: String,
+    /// First line of the range, counted from 1, where the annotation was last found
+    pub line_start: i64,
+    /// Last line of the range, inclusive
+    pub line_end: i64,
+    /// Text of the range, which the annotation follows when the document is edited
+    pub quote: String,
+    /// Lines before the range, to tell repeated text apart
+    pub prefix: String,
+    /// Lines after the range
+    pub suffix: String,
+    pub author: String,
+    pub body: String,
+    pub resolved: bool,
+    pub resolved_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateDocumentAnnotation {
+    pub project_id: Uuid,
+    pub repo_id: Uuid,
+    pub relative_path: String,
+    pub line_start: i64,
+    pub line_end: i64,
+    pub quote: String,
+    pub prefix: String,
+    pub suffix: String,
+    pub author: String,
+    pub body: String,
+}
+
+impl DocumentAnnotation {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateDocumentAnnotation,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            DocumentAnnotation,
+            r#"INSERT INTO document_annotations
+                   (id, project_id, repo_id, relative_path, line_start, line_end, quote,
+                    prefix, suffix, author, body)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         relative_path,
+                         line_start,
+                         line_end,
+                         quote,
+                         prefix,
+                         suffix,
+                         author,
+                         body,
+                         resolved as "resolved!: bool",
+                         resolved_by,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.repo_id,
+            data.relative_path,
+            data.line_start,
+            data.line_end,
+            data.quote,
+            data.prefix,
+            data.suffix,
+            data.author,
+            data.body
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentAnnotation,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      line_start,
+                      line_end,
+                      quote,
+                      prefix,
+                      suffix,
+                      author,
+                      body,
+                      resolved as "resolved!: bool",
+                      resolved_by,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_annotations
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Annotations of a document in the order of their lines, without resolved ones
+    /// unless `include_resolved` is set
+    pub async fn find_by_document(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+        include_resolved: bool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentAnnotation,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      line_start,
+                      line_end,
+                      quote,
+                      prefix,
+                      suffix,
+                      author,
+                      body,
+                      resolved as "resolved!: bool",
+                      resolved_by,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_annotations
+               WHERE repo_id = $1 AND relative_path = $2 AND ($3 OR resolved = FALSE)
+               ORDER BY line_start ASC, created_at ASC"#,
+            repo_id,
+            relative_path,
+            include_resolved
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Annotations of every document in a project, by document and line
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        include_resolved: bool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentAnnotation,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      line_start,
+                      line_end,
+                      quote,
+                      prefix,
+                      suffix,
+                      author,
+                      body,
+                      resolved as "resolved!: bool",
+                      resolved_by,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_annotations
+               WHERE project_id = $1 AND ($2 OR resolved = FALSE)
+               ORDER BY relative_path ASC, repo_id, line_start ASC, created_at ASC"#,
+            project_id,
+            include_resolved
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Annotations in any project written or resolved by `name`, oldest first
+    pub async fn find_by_person(pool: &SqlitePool, name: &str) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentAnnotation,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      line_start,
+                      line_end,
+                      quote,
+                      prefix,
+                      suffix,
+                      author,
+                      body,
+                      resolved as "resolved!: bool",
+                      resolved_by,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_annotations
+               WHERE author = $1 OR resolved_by = $1
+               ORDER BY created_at ASC"#,
+            name
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace `name` as author and resolver of annotations with `replacement`. Returns
+    /// the number of annotations changed.
+    pub async fn rename_person(
+        executor: impl Executor<'_, Database = Sqlite>,
+        name: &str,
+        replacement: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE document_annotations
+               SET author = CASE WHEN author = $1 THEN $2 ELSE author END,
+                   resolved_by = CASE WHEN resolved_by = $1 THEN $2 ELSE resolved_by END
+               WHERE author = $1 OR resolved_by = $1"#,
+            name,
+            replacement
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn update_body(pool: &SqlitePool, id: Uuid, body: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE document_annotations
+               SET body = $2,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            body
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Resolve the annotation as `resolved_by`, or reopen it if that's `None`
+    pub async fn set_resolved(
+        pool: &SqlitePool,
+        id: Uuid,
+        resolved_by: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let resolved = resolved_by.is_some();
+        sqlx::query!(
+            r#"UPDATE document_annotations
+               SET resolved = $2,
+                   resolved_by = $3,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            resolved,
+            resolved_by
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Move the annotation to where its text was found in the edited document
+    pub async fn reanchor(
+        pool: &SqlitePool,
+        id: Uuid,
+        line_start: i64,
+        line_end: i64,
+        quote: &str,
+        prefix: &str,
+        suffix: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE document_annotations
+               SET line_start = $2,
+                   line_end = $3,
+                   quote = $4,
+                   prefix = $5,
+                   suffix = $6
+               WHERE id = $1"#,
+            id,
+            line_start,
+            line_end,
+            quote,
+            prefix,
+            suffix
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Follow a document moved from `from` to `to`
+    pub async fn move_document(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        from: &str,
+        to: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE document_annotations
+               SET relative_path = $3
+               WHERE repo_id = $1 AND relative_path = $2"#,
+            repo_id,
+            from,
+            to
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_by_document(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM document_annotations WHERE repo_id = $1 AND relative_path = $2",
+            repo_id,
+            relative_path
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM document_annotations WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
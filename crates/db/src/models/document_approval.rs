@@ -0,0 +1,510 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A folder whose documents need approval from every listed reviewer before an edit is
+/// committed
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentApprovalRule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Folder relative to the repository root, `/`-separated and without trailing slash
+    pub folder: String,
+    #[ts(type = "Array<string>")]
+    pub reviewers: Json<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetDocumentApprovalRule {
+    pub folder: String,
+    pub reviewers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentChangeStatus {
+    Pending,
+    /// Approved by every required reviewer and committed
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentReviewDecision {
+    Approved,
+    Rejected,
+}
+
+/// An edit to a document in a protected folder, staged until it is reviewed
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentChangeRequest {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub repo_id: Uuid,
+    pub relative_path: String,
+    /// Proposed content of the document
+    pub content: String,
+    #[ts(type = "Array<string>")]
+    pub required_reviewers: Json<Vec<String>>,
+    pub status: DocumentChangeStatus,
+    /// The commit that applied the change, once approved
+    pub commit_sha: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentChangeReview {
+    pub id: Uuid,
+    pub change_request_id: Uuid,
+    pub reviewer: String,
+    pub decision: DocumentReviewDecision,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Whether `relative_path` lies inside `folder`
+pub(crate) fn in_folder(relative_path: &str, folder: &str) -> bool {
+    relative_path
+        .strip_prefix(folder)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+impl DocumentApprovalRule {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentApprovalRule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      folder,
+                      reviewers as "reviewers!: Json<Vec<String>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_approval_rules
+               WHERE project_id = $1
+               ORDER BY folder"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The rule protecting `relative_path`; the most specific folder wins
+    pub async fn find_for_path(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        relative_path: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        Ok(Self::find_by_project_id(pool, project_id)
+            .await?
+            .into_iter()
+            .filter(|rule| in_folder(relative_path, &rule.folder))
+            .max_by_key(|rule| rule.folder.len()))
+    }
+
+    /// Replace all rules of a project
+    pub async fn replace_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        rules: &[SetDocumentApprovalRule],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM document_approval_rules WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for rule in rules {
+            let id = Uuid::new_v4();
+            let reviewers = Json(&rule.reviewers);
+            sqlx::query!(
+                r#"INSERT INTO document_approval_rules (id, project_id, folder, reviewers)
+                   VALUES ($1, $2, $3, $4)"#,
+                id,
+                project_id,
+                rule.folder,
+                reviewers
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Self::find_by_project_id(pool, project_id).await
+    }
+
+    /// Copy all rules of one project to another
+    pub async fn copy_to_project<'e, E>(
+        executor: E,
+        from_project_id: Uuid,
+        to_project_id: Uuid,
+    ) -> Result<u64, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let result = sqlx::query!(
+            r#"INSERT INTO document_approval_rules (id, project_id, folder, reviewers)
+               SELECT randomblob(16), $2, folder, reviewers
+               FROM document_approval_rules
+               WHERE project_id = $1"#,
+            from_project_id,
+            to_project_id
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Rules in any project that list `reviewer`
+    pub async fn find_by_reviewer(
+        executor: impl Executor<'_, Database = Sqlite>,
+        reviewer: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentApprovalRule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      folder,
+                      reviewers as "reviewers!: Json<Vec<String>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_approval_rules
+               WHERE EXISTS (SELECT 1 FROM json_each(reviewers) WHERE value = $1)
+               ORDER BY project_id, folder"#,
+            reviewer
+        )
+        .fetch_all(executor)
+        .await
+    }
+
+    pub async fn set_reviewers(
+        executor: impl Executor<'_, Database = Sqlite>,
+        id: Uuid,
+        reviewers: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let reviewers = Json(reviewers);
+        sqlx::query!(
+            r#"UPDATE document_approval_rules
+               SET reviewers = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            reviewers
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+}
+
+impl DocumentChangeRequest {
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        relative_path: &str,
+        content: &str,
+        required_reviewers: &[String],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let required_reviewers = Json(required_reviewers);
+        sqlx::query_as!(
+            DocumentChangeRequest,
+            r#"INSERT INTO document_change_requests (id, project_id, repo_id, relative_path, content, required_reviewers)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         relative_path,
+                         content,
+                         required_reviewers as "required_reviewers!: Json<Vec<String>>",
+                         status as "status!: DocumentChangeStatus",
+                         commit_sha,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            repo_id,
+            relative_path,
+            content,
+            required_reviewers
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentChangeRequest,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      content,
+                      required_reviewers as "required_reviewers!: Json<Vec<String>>",
+                      status as "status!: DocumentChangeStatus",
+                      commit_sha,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_change_requests
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Change requests of a project, newest first, optionally only those in `status`
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        status: Option<DocumentChangeStatus>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentChangeRequest,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      content,
+                      required_reviewers as "required_reviewers!: Json<Vec<String>>",
+                      status as "status!: DocumentChangeStatus",
+                      commit_sha,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_change_requests
+               WHERE project_id = $1 AND ($2 IS NULL OR status = $2)
+               ORDER BY created_at DESC"#,
+            project_id,
+            status
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The pending change to a document, if any. A document has at most one.
+    pub async fn find_pending_for_path(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentChangeRequest,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      content,
+                      required_reviewers as "required_reviewers!: Json<Vec<String>>",
+                      status as "status!: DocumentChangeStatus",
+                      commit_sha,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_change_requests
+               WHERE repo_id = $1 AND relative_path = $2 AND status = 'pending'"#,
+            repo_id,
+            relative_path
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Replace the proposed content. Earlier reviews no longer apply and are dropped.
+    pub async fn update_content(
+        pool: &SqlitePool,
+        id: Uuid,
+        content: &str,
+        required_reviewers: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let required_reviewers = Json(required_reviewers);
+        sqlx::query!(
+            r#"UPDATE document_change_requests
+               SET content = $2, required_reviewers = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            content,
+            required_reviewers
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM document_change_reviews WHERE change_request_id = $1",
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    pub async fn set_status(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: DocumentChangeStatus,
+        commit_sha: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE document_change_requests
+               SET status = $2, commit_sha = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            commit_sha
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Change requests in any project that wait or waited on `reviewer`, oldest first
+    pub async fn find_by_required_reviewer(
+        executor: impl Executor<'_, Database = Sqlite>,
+        reviewer: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentChangeRequest,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      content,
+                      required_reviewers as "required_reviewers!: Json<Vec<String>>",
+                      status as "status!: DocumentChangeStatus",
+                      commit_sha,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_change_requests
+               WHERE EXISTS (SELECT 1 FROM json_each(required_reviewers) WHERE value = $1)
+               ORDER BY created_at ASC"#,
+            reviewer
+        )
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Replace the required reviewers without touching the reviews, unlike
+    /// [`Self::update_content`]
+    pub async fn set_required_reviewers(
+        executor: impl Executor<'_, Database = Sqlite>,
+        id: Uuid,
+        required_reviewers: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let required_reviewers = Json(required_reviewers);
+        sqlx::query!(
+            r#"UPDATE document_change_requests
+               SET required_reviewers = $2
+               WHERE id = $1"#,
+            id,
+            required_reviewers
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+}
+
+impl DocumentChangeReview {
+    /// Record a reviewer's decision, replacing their earlier one
+    pub async fn upsert(
+        pool: &SqlitePool,
+        change_request_id: Uuid,
+        reviewer: &str,
+        decision: DocumentReviewDecision,
+        comment: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            DocumentChangeReview,
+            r#"INSERT INTO document_change_reviews (id, change_request_id, reviewer, decision, comment)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(change_request_id, reviewer) DO UPDATE
+               SET decision = excluded.decision,
+                   comment = excluded.comment,
+                   created_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         change_request_id as "change_request_id!: Uuid",
+                         reviewer,
+                         decision as "decision!: DocumentReviewDecision",
+                         comment,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            change_request_id,
+            reviewer,
+            decision,
+            comment
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Reviews in any project by `reviewer`, oldest first
+    pub async fn find_by_reviewer(
+        pool: &SqlitePool,
+        reviewer: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentChangeReview,
+            r#"SELECT id as "id!: Uuid",
+                      change_request_id as "change_request_id!: Uuid",
+                      reviewer,
+                      decision as "decision!: DocumentReviewDecision",
+                      comment,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM document_change_reviews
+               WHERE reviewer = $1
+               ORDER BY created_at"#,
+            reviewer
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Attribute the reviews of `reviewer` to `replacement`. Returns the number of reviews
+    /// changed.
+    pub async fn rename_reviewer(
+        executor: impl Executor<'_, Database = Sqlite>,
+        reviewer: &str,
+        replacement: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE document_change_reviews SET reviewer = $2 WHERE reviewer = $1",
+            reviewer,
+            replacement
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find_by_change_request_id(
+        pool: &SqlitePool,
+        change_request_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentChangeReview,
+            r#"SELECT id as "id!: Uuid",
+                      change_request_id as "change_request_id!: Uuid",
+                      reviewer,
+                      decision as "decision!: DocumentReviewDecision",
+                      comment,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM document_change_reviews
+               WHERE change_request_id = $1
+               ORDER BY created_at"#,
+            change_request_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
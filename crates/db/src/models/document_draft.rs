@@ -0,0 +1,172 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Unsaved editor content of one author for one document, kept outside git
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentDraft {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub repo_id: Uuid,
+    pub relative_path: String,
+    /// Name of the editor the draft belongs to
+    pub author: String,
+    pub content: String,
+    /// `content_hash` of the document the draft was started from, `None` for a new
+    /// document
+    pub base_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DocumentDraft {
+    /// Drafts of `author` in a project, most recently edited first
+    pub async fn find_by_author(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        author: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentDraft,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      author,
+                      content,
+                      base_hash,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_drafts
+               WHERE project_id = $1 AND author = $2
+               ORDER BY updated_at DESC"#,
+            project_id,
+            author
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Drafts of `author` in every project
+    pub async fn find_all_by_author(
+        pool: &SqlitePool,
+        author: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentDraft,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      author,
+                      content,
+                      base_hash,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_drafts
+               WHERE author = $1
+               ORDER BY updated_at DESC"#,
+            author
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+        author: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentDraft,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      author,
+                      content,
+                      base_hash,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_drafts
+               WHERE repo_id = $1 AND relative_path = $2 AND author = $3"#,
+            repo_id,
+            relative_path,
+            author
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Store `author`'s draft of a document, replacing their previous one. `base_hash`
+    /// of an existing draft is kept, so it stays the version the writing started from.
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        relative_path: &str,
+        author: &str,
+        content: &str,
+        base_hash: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            DocumentDraft,
+            r#"INSERT INTO document_drafts (id, project_id, repo_id, relative_path, author, content, base_hash)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT(repo_id, relative_path, author) DO UPDATE
+               SET content = excluded.content,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         relative_path,
+                         author,
+                         content,
+                         base_hash,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            repo_id,
+            relative_path,
+            author,
+            content,
+            base_hash
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Discard `author`'s draft of a document. Returns whether there was one.
+    pub async fn delete(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+        author: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM document_drafts WHERE repo_id = $1 AND relative_path = $2 AND author = $3",
+            repo_id,
+            relative_path,
+            author
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Discard every draft of `author`. Returns the number discarded.
+    pub async fn delete_by_author(
+        executor: impl Executor<'_, Database = Sqlite>,
+        author: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM document_drafts WHERE author = $1", author)
+            .execute(executor)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
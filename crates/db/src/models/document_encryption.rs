@@ -0,0 +1,211 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::document_approval::in_folder;
+
+/// A folder whose documents are stored encrypted and only shown to its readers
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentEncryptedFolder {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Folder relative to the repository root, `/`-separated and without trailing slash
+    pub folder: String,
+    /// GitHub users who may read the folder's documents; anyone if empty
+    #[ts(type = "Array<string>")]
+    pub readers: Json<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetDocumentEncryptedFolder {
+    pub folder: String,
+    pub readers: Vec<String>,
+}
+
+/// A key of a project's encrypted documents. The key material never leaves the server.
+#[derive(Debug, Clone, FromRow, Serialize, TS)]
+pub struct DocumentEncryptionKey {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    #[serde(skip)]
+    #[ts(skip)]
+    pub key_material: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    /// When the key was replaced by a newer one. Retired keys only decrypt older commits.
+    pub retired_at: Option<DateTime<Utc>>,
+}
+
+impl DocumentEncryptedFolder {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentEncryptedFolder,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      folder,
+                      readers as "readers!: Json<Vec<String>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_encrypted_folders
+               WHERE project_id = $1
+               ORDER BY folder"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The encrypted folder holding `relative_path`; the most specific folder wins
+    pub fn find_for_path<'a>(folders: &'a [Self], relative_path: &str) -> Option<&'a Self> {
+        folders
+            .iter()
+            .filter(|folder| in_folder(relative_path, &folder.folder))
+            .max_by_key(|folder| folder.folder.len())
+    }
+
+    /// Replace all encrypted folders of a project
+    pub async fn replace_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        folders: &[SetDocumentEncryptedFolder],
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM document_encrypted_folders WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for folder in folders {
+            let id = Uuid::new_v4();
+            let readers = Json(&folder.readers);
+            sqlx::query!(
+                r#"INSERT INTO document_encrypted_folders (id, project_id, folder, readers)
+                   VALUES ($1, $2, $3, $4)"#,
+                id,
+                project_id,
+                folder.folder,
+                readers
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Self::find_by_project_id(pool, project_id).await
+    }
+
+    /// Copy all encrypted folders of one project to another
+    pub async fn copy_to_project<'e, E>(
+        executor: E,
+        from_project_id: Uuid,
+        to_project_id: Uuid,
+    ) -> Result<u64, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let result = sqlx::query!(
+            r#"INSERT INTO document_encrypted_folders (id, project_id, folder, readers)
+               SELECT randomblob(16), $2, folder, readers
+               FROM document_encrypted_folders
+               WHERE project_id = $1"#,
+            from_project_id,
+            to_project_id
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl DocumentEncryptionKey {
+    /// Keys of a project, newest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentEncryptionKey,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      key_material,
+                      created_at as "created_at!: DateTime<Utc>",
+                      retired_at as "retired_at: DateTime<Utc>"
+               FROM document_encryption_keys
+               WHERE project_id = $1
+               ORDER BY created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Add a key and retire the project's current one, so new documents use the new key
+    pub async fn rotate(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        key_material: &[u8],
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            r#"UPDATE document_encryption_keys
+               SET retired_at = datetime('now', 'subsec')
+               WHERE project_id = $1 AND retired_at IS NULL"#,
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        let id = Uuid::new_v4();
+        let key = sqlx::query_as!(
+            DocumentEncryptionKey,
+            r#"INSERT INTO document_encryption_keys (id, project_id, key_material)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         key_material,
+                         created_at as "created_at!: DateTime<Utc>",
+                         retired_at as "retired_at: DateTime<Utc>""#,
+            id,
+            project_id,
+            key_material
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(key)
+    }
+
+    /// Add the first key of a project that has none yet
+    pub async fn create<'e, E>(
+        executor: E,
+        project_id: Uuid,
+        key_material: &[u8],
+    ) -> Result<Self, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            DocumentEncryptionKey,
+            r#"INSERT INTO document_encryption_keys (id, project_id, key_material)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         key_material,
+                         created_at as "created_at!: DateTime<Utc>",
+                         retired_at as "retired_at: DateTime<Utc>""#,
+            id,
+            project_id,
+            key_material
+        )
+        .fetch_one(executor)
+        .await
+    }
+}
@@ -0,0 +1,192 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A soft lock reserving a document for one editor until `expires_at`
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentLock {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub repo_id: Uuid,
+    pub relative_path: String,
+    /// Name of the editor holding the lock
+    pub holder: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DocumentLock {
+    /// The unexpired lock on a document, if any
+    pub async fn find_active(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentLock,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      holder,
+                      expires_at as "expires_at!: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_locks
+               WHERE repo_id = $1 AND relative_path = $2
+                 AND expires_at > datetime('now', 'subsec')"#,
+            repo_id,
+            relative_path
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_active_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentLock,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      holder,
+                      expires_at as "expires_at!: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_locks
+               WHERE project_id = $1 AND expires_at > datetime('now', 'subsec')
+               ORDER BY relative_path"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Lock a document for `holder` for `ttl_seconds`. Renews the lock if `holder` already
+    /// has it and takes over an expired one. Returns `None` while someone else holds it.
+    pub async fn acquire(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        relative_path: &str,
+        holder: &str,
+        ttl_seconds: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            DocumentLock,
+            r#"INSERT INTO document_locks (id, project_id, repo_id, relative_path, holder, expires_at)
+               VALUES ($1, $2, $3, $4, $5, datetime('now', 'subsec', '+' || $6 || ' seconds'))
+               ON CONFLICT(repo_id, relative_path) DO UPDATE
+               SET holder = excluded.holder,
+                   expires_at = excluded.expires_at,
+                   updated_at = datetime('now', 'subsec')
+               WHERE document_locks.holder = excluded.holder
+                  OR document_locks.expires_at <= datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         relative_path,
+                         holder,
+                         expires_at as "expires_at!: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            repo_id,
+            relative_path,
+            holder,
+            ttl_seconds
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Extend an unexpired lock held by `holder`. Returns `None` if `holder` lost the lock.
+    pub async fn renew(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+        holder: &str,
+        ttl_seconds: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentLock,
+            r#"UPDATE document_locks
+               SET expires_at = datetime('now', 'subsec', '+' || $4 || ' seconds'),
+                   updated_at = datetime('now', 'subsec')
+               WHERE repo_id = $1 AND relative_path = $2 AND holder = $3
+                 AND expires_at > datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         relative_path,
+                         holder,
+                         expires_at as "expires_at!: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            repo_id,
+            relative_path,
+            holder,
+            ttl_seconds
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Release the lock `holder` has on a document. Returns whether there was one.
+    pub async fn release(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+        holder: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM document_locks WHERE repo_id = $1 AND relative_path = $2 AND holder = $3",
+            repo_id,
+            relative_path,
+            holder
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Locks of `holder` in every project, including expired ones that weren't cleaned up
+    pub async fn find_by_holder(pool: &SqlitePool, holder: &str) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentLock,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      holder,
+                      expires_at as "expires_at!: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_locks
+               WHERE holder = $1
+               ORDER BY relative_path"#,
+            holder
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Release every lock of `holder`. Returns the number released.
+    pub async fn release_all(
+        executor: impl Executor<'_, Database = Sqlite>,
+        holder: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM document_locks WHERE holder = $1", holder)
+            .execute(executor)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// The commit a repository's docs branch pointed at, at a point in time
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentSnapshot {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub repo_id: Uuid,
+    pub branch: String,
+    pub commit_sha: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DocumentSnapshot {
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        branch: &str,
+        commit_sha: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            DocumentSnapshot,
+            r#"INSERT INTO document_snapshots (id, project_id, repo_id, branch, commit_sha)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         branch,
+                         commit_sha,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            repo_id,
+            branch,
+            commit_sha
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentSnapshot,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      branch,
+                      commit_sha,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM document_snapshots
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Snapshots of all repositories of a project, newest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentSnapshot,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      branch,
+                      commit_sha,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM document_snapshots
+               WHERE project_id = $1
+               ORDER BY created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_latest_for_repo(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentSnapshot,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      branch,
+                      commit_sha,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM document_snapshots
+               WHERE project_id = $1 AND repo_id = $2
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            project_id,
+            repo_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Delete all but the `keep` newest snapshots of a repository in a project.
+    /// Returns the number of deleted snapshots.
+    pub async fn prune(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        keep: i64,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM document_snapshots
+               WHERE project_id = $1 AND repo_id = $2
+                 AND id NOT IN (
+                     SELECT id FROM document_snapshots
+                     WHERE project_id = $1 AND repo_id = $2
+                     ORDER BY created_at DESC
+                     LIMIT $3
+                 )"#,
+            project_id,
+            repo_id,
+            keep
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
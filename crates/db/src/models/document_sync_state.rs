@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Last known drift of a project's docs branch against origin/main
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentSyncState {
+    pub project_id: Uuid,
+    /// Branch the primary repository was on at the last check
+    pub current_branch: Option<String>,
+    /// Host of the origin remote at the last fetch
+    pub remote_host: Option<String>,
+    pub commits_ahead: i64,
+    pub commits_behind: i64,
+    /// Last successful fetch from origin
+    pub fetched_at: Option<DateTime<Utc>>,
+    /// Last successful sync
+    pub last_synced_at: Option<DateTime<Utc>>,
+    /// Error of the last fetch or sync, `None` if it succeeded
+    pub last_error: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DocumentSyncState {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentSyncState,
+            r#"SELECT project_id as "project_id!: Uuid",
+                      current_branch,
+                      remote_host,
+                      commits_ahead as "commits_ahead!: i64",
+                      commits_behind as "commits_behind!: i64",
+                      fetched_at as "fetched_at: DateTime<Utc>",
+                      last_synced_at as "last_synced_at: DateTime<Utc>",
+                      last_error,
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_sync_states"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Host of the project's origin remote at its last fetch
+    pub async fn find_remote_host(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT remote_host FROM document_sync_states WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?
+        .flatten())
+    }
+
+    /// Record the drift seen after fetching, or trying to fetch, from origin. `fetched_at`
+    /// only moves when the fetch succeeded, i.e. `error` is `None`.
+    pub async fn record_fetch(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        current_branch: &str,
+        remote_host: Option<&str>,
+        commits_ahead: i64,
+        commits_behind: i64,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO document_sync_states
+                   (project_id, current_branch, remote_host, commits_ahead, commits_behind,
+                    fetched_at, last_error)
+               VALUES ($1, $2, $3, $4, $5,
+                       CASE WHEN $6 IS NULL THEN datetime('now', 'subsec') END, $6)
+               ON CONFLICT(project_id) DO UPDATE
+               SET current_branch = excluded.current_branch,
+                   remote_host = COALESCE(excluded.remote_host, document_sync_states.remote_host),
+                   commits_ahead = excluded.commits_ahead,
+                   commits_behind = excluded.commits_behind,
+                   fetched_at = COALESCE(excluded.fetched_at, document_sync_states.fetched_at),
+                   last_error = excluded.last_error,
+                   updated_at = datetime('now', 'subsec')"#,
+            project_id,
+            current_branch,
+            remote_host,
+            commits_ahead,
+            commits_behind,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the outcome of a sync. A successful one leaves the branch even with
+    /// origin/main.
+    pub async fn record_sync(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO document_sync_states (project_id, last_synced_at, last_error)
+               VALUES ($1, CASE WHEN $2 IS NULL THEN datetime('now', 'subsec') END, $2)
+               ON CONFLICT(project_id) DO UPDATE
+               SET last_synced_at = COALESCE(excluded.last_synced_at,
+                                             document_sync_states.last_synced_at),
+                   commits_ahead = CASE WHEN excluded.last_error IS NULL THEN 0
+                                        ELSE document_sync_states.commits_ahead END,
+                   commits_behind = CASE WHEN excluded.last_error IS NULL THEN 0
+                                         ELSE document_sync_states.commits_behind END,
+                   last_error = excluded.last_error,
+                   updated_at = datetime('now', 'subsec')"#,
+            project_id,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A tag of a document, e.g. `draft`, `adr` or `runbook`
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentTag {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub repo_id: Uuid,
+    pub relative_path: String,
+    pub tag: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A tag used in a project and the number of documents tagged with it
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentTagCount {
+    pub tag: String,
+    pub documents: i64,
+}
+
+impl DocumentTag {
+    /// Tag a document; tagging it again with the same tag does nothing
+    pub async fn add(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        relative_path: &str,
+        tag: &str,
+    ) -> Result<(), sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO document_tags (id, project_id, repo_id, relative_path, tag)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT(repo_id, relative_path, tag) DO NOTHING"#,
+            id,
+            project_id,
+            repo_id,
+            relative_path,
+            tag
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+        tag: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM document_tags
+               WHERE repo_id = $1 AND relative_path = $2 AND tag = $3"#,
+            repo_id,
+            relative_path,
+            tag
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Replace the tags of a document with `tags`, keeping the ones it already has
+    pub async fn set(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_id: Uuid,
+        relative_path: &str,
+        tags: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let existing = sqlx::query_scalar!(
+            r#"SELECT tag FROM document_tags WHERE repo_id = $1 AND relative_path = $2"#,
+            repo_id,
+            relative_path
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for tag in existing.iter().filter(|tag| !tags.contains(tag)) {
+            sqlx::query!(
+                r#"DELETE FROM document_tags
+                   WHERE repo_id = $1 AND relative_path = $2 AND tag = $3"#,
+                repo_id,
+                relative_path,
+                tag
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        for tag in tags.iter().filter(|tag| !existing.contains(tag)) {
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO document_tags (id, project_id, repo_id, relative_path, tag)
+                   VALUES ($1, $2, $3, $4, $5)
+                   ON CONFLICT(repo_id, relative_path, tag) DO NOTHING"#,
+                id,
+                project_id,
+                repo_id,
+                relative_path,
+                tag
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Tags of a document in alphabetical order
+    pub async fn find_tags(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT tag FROM document_tags
+               WHERE repo_id = $1 AND relative_path = $2
+               ORDER BY tag ASC"#,
+            repo_id,
+            relative_path
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Tags of every document in a project, by document and then tag
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentTag,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      tag,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM document_tags
+               WHERE project_id = $1
+               ORDER BY repo_id, relative_path ASC, tag ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Tags used in a project with their number of documents, in alphabetical order
+    pub async fn count_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<DocumentTagCount>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentTagCount,
+            r#"SELECT tag, COUNT(*) as "documents!: i64"
+               FROM document_tags
+               WHERE project_id = $1
+               GROUP BY tag
+               ORDER BY tag ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Follow a document moved from `from` to `to`, which keeps the tags it already has
+    pub async fn move_document(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        from: &str,
+        to: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            r#"UPDATE OR IGNORE document_tags
+               SET relative_path = $3
+               WHERE repo_id = $1 AND relative_path = $2"#,
+            repo_id,
+            from,
+            to
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM document_tags WHERE repo_id = $1 AND relative_path = $2",
+            repo_id,
+            from
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    pub async fn delete_by_document(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM document_tags WHERE repo_id = $1 AND relative_path = $2",
+            repo_id,
+            relative_path
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A task kept in sync with a task list item of a document
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DocumentTaskLink {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub repo_id: Uuid,
+    pub relative_path: String,
+    /// Checkbox state of the item as of the last sync
+    pub checked: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DocumentTaskLink {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentTaskLink,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      checked as "checked!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_task_links
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_document(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DocumentTaskLink,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      checked as "checked!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM document_task_links
+               WHERE repo_id = $1 AND relative_path = $2"#,
+            repo_id,
+            relative_path
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Link `task_id` to a document item, moving an existing link of the task along
+    pub async fn upsert(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        project_id: Uuid,
+        repo_id: Uuid,
+        relative_path: &str,
+        checked: bool,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            DocumentTaskLink,
+            r#"INSERT INTO document_task_links (id, task_id, project_id, repo_id, relative_path, checked)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT(task_id) DO UPDATE
+               SET repo_id = excluded.repo_id,
+                   relative_path = excluded.relative_path,
+                   checked = excluded.checked,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         relative_path,
+                         checked as "checked!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            project_id,
+            repo_id,
+            relative_path,
+            checked
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete_by_task_id(pool: &SqlitePool, task_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM document_task_links WHERE task_id = $1",
+            task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "e2e_run_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(use_ts_enum)]
+pub enum E2eRunStatus {
+    Running,
+    Passed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "e2e_test_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(use_ts_enum)]
+pub enum E2eTestStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// A run of a repository's end-to-end suite against an attempt's preview
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct E2eRun {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub status: E2eRunStatus,
+    /// Preview the suite ran against
+    pub base_url: String,
+    /// Commit of the worktree the suite ran from
+    pub commit_sha: Option<String>,
+    /// End of the suite's output
+    pub output: String,
+    pub error: Option<String>,
+    pub passed_count: i64,
+    pub failed_count: i64,
+    pub skipped_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A test case of a run, parsed from the suite's JUnit report
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct E2eTestCase {
+    pub id: Uuid,
+    pub run_id: Uuid,
+    /// Spec file or describe block the case is in
+    pub suite: String,
+    pub name: String,
+    pub status: E2eTestStatus,
+    pub duration_ms: Option<i64>,
+    pub failure_message: Option<String>,
+    /// Paths of the case's traces, videos and screenshots in the run's artifacts
+    #[ts(type = "Array<string>")]
+    pub attachments: Json<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateE2eTestCase {
+    pub suite: String,
+    pub name: String,
+    pub status: E2eTestStatus,
+    pub duration_ms: Option<i64>,
+    pub failure_message: Option<String>,
+    pub attachments: Vec<String>,
+}
+
+impl E2eRun {
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        base_url: &str,
+        commit_sha: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            E2eRun,
+            r#"INSERT INTO e2e_runs (id, workspace_id, repo_id, base_url, commit_sha)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         status as "status!: E2eRunStatus",
+                         base_url,
+                         commit_sha,
+                         output,
+                         error,
+                         passed_count,
+                         failed_count,
+                         skipped_count,
+                         created_at as "created_at!: DateTime<Utc>",
+                         completed_at as "completed_at: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            repo_id,
+            base_url,
+            commit_sha
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            E2eRun,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      status as "status!: E2eRunStatus",
+                      base_url,
+                      commit_sha,
+                      output,
+                      error,
+                      passed_count,
+                      failed_count,
+                      skipped_count,
+                      created_at as "created_at!: DateTime<Utc>",
+                      completed_at as "completed_at: DateTime<Utc>"
+               FROM e2e_runs
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Runs of a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            E2eRun,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      status as "status!: E2eRunStatus",
+                      base_url,
+                      commit_sha,
+                      output,
+                      error,
+                      passed_count,
+                      failed_count,
+                      skipped_count,
+                      created_at as "created_at!: DateTime<Utc>",
+                      completed_at as "completed_at: DateTime<Utc>"
+               FROM e2e_runs
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn has_running(pool: &SqlitePool, workspace_id: Uuid) -> Result<bool, sqlx::Error> {
+        let running = sqlx::query_scalar!(
+            r#"SELECT EXISTS(
+                   SELECT 1 FROM e2e_runs
+                   WHERE workspace_id = $1 AND status = 'running'
+               ) as "running!: bool""#,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(running)
+    }
+
+    /// Record the outcome of a run with its test cases. The run passed if no case failed
+    /// and there was no error.
+    pub async fn complete(
+        pool: &SqlitePool,
+        id: Uuid,
+        output: &str,
+        error: Option<&str>,
+        test_cases: &[CreateE2eTestCase],
+    ) -> Result<E2eRunStatus, sqlx::Error> {
+        let count = |status| test_cases.iter().filter(|c| c.status == status).count() as i64;
+        let passed_count = count(E2eTestStatus::Passed);
+        let failed_count = count(E2eTestStatus::Failed);
+        let skipped_count = count(E2eTestStatus::Skipped);
+        let status = if error.is_none() && failed_count == 0 {
+            E2eRunStatus::Passed
+        } else {
+            E2eRunStatus::Failed
+        };
+
+        let mut tx = pool.begin().await?;
+        for test_case in test_cases {
+            let case_id = Uuid::new_v4();
+            let attachments = Json(&test_case.attachments);
+            sqlx::query!(
+                r#"INSERT INTO e2e_test_cases
+                       (id, run_id, suite, name, status, duration_ms, failure_message, attachments)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#,
+                case_id,
+                id,
+                test_case.suite,
+                test_case.name,
+                test_case.status,
+                test_case.duration_ms,
+                test_case.failure_message,
+                attachments
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query!(
+            r#"UPDATE e2e_runs
+               SET status = $2,
+                   output = $3,
+                   error = $4,
+                   passed_count = $5,
+                   failed_count = $6,
+                   skipped_count = $7,
+                   completed_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            output,
+            error,
+            passed_count,
+            failed_count,
+            skipped_count
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(status)
+    }
+
+    /// Fail runs that were still running when the server stopped
+    pub async fn fail_interrupted(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE e2e_runs
+               SET status = 'failed',
+                   error = 'The server stopped during the run',
+                   completed_at = datetime('now', 'subsec')
+               WHERE status = 'running'"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Completed runs of a repository in a workspace after the newest `keep`, whose
+    /// artifacts can be removed
+    pub async fn find_superseded(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        keep: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT id as "id!: Uuid"
+               FROM e2e_runs
+               WHERE workspace_id = $1 AND repo_id = $2 AND status != 'running'
+               ORDER BY created_at DESC
+               LIMIT -1 OFFSET $3"#,
+            workspace_id,
+            repo_id,
+            keep
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM e2e_runs WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl E2eTestCase {
+    /// Test cases of a run, failed ones first and otherwise in the report's order
+    pub async fn find_by_run_id(pool: &SqlitePool, run_id: Uuid) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            E2eTestCase,
+            r#"SELECT id as "id!: Uuid",
+                      run_id as "run_id!: Uuid",
+                      suite,
+                      name,
+                      status as "status!: E2eTestStatus",
+                      duration_ms,
+                      failure_message,
+                      attachments as "attachments!: Json<Vec<String>>"
+               FROM e2e_test_cases
+               WHERE run_id = $1
+               ORDER BY status != 'failed', rowid"#,
+            run_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A request that failed with a server error, with the context to debug it
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ErrorReport {
+    pub id: Uuid,
+    /// `X-Request-Id` of the request, also on its log lines
+    pub correlation_id: String,
+    pub method: String,
+    /// Route template the request matched, e.g. `/api/projects/{id}`
+    pub route: String,
+    pub path: String,
+    pub status: i64,
+    /// Error message of the response
+    pub message: Option<String>,
+    /// Log lines of the request, oldest first
+    #[ts(type = "Array<string>")]
+    pub log_lines: Json<Vec<String>>,
+    /// Summary of the server when the error happened
+    #[ts(type = "JsonValue")]
+    pub state: Json<Value>,
+    /// When the report was sent to the configured Sentry-compatible endpoint
+    pub forwarded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateErrorReport {
+    pub correlation_id: String,
+    pub method: String,
+    pub route: String,
+    pub path: String,
+    pub status: i64,
+    pub message: Option<String>,
+    pub log_lines: Vec<String>,
+    pub state: Value,
+}
+
+impl ErrorReport {
+    /// Reports kept; older ones are deleted as new ones come in
+    pub const MAX_KEPT: i64 = 500;
+
+    /// Store a report and delete the ones beyond [`Self::MAX_KEPT`]
+    pub async fn create(pool: &SqlitePool, data: &CreateErrorReport) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let log_lines = Json(&data.log_lines);
+        let state = Json(&data.state);
+        let report = sqlx::query_as!(
+            ErrorReport,
+            r#"INSERT INTO error_reports (id, correlation_id, method, route, path, status, message, log_lines, state)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid",
+                         correlation_id,
+                         method,
+                         route,
+                         path,
+                         status,
+                         message,
+                         log_lines as "log_lines!: Json<Vec<String>>",
+                         state as "state!: Json<Value>",
+                         forwarded_at as "forwarded_at?: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.correlation_id,
+            data.method,
+            data.route,
+            data.path,
+            data.status,
+            data.message,
+            log_lines,
+            state
+        )
+        .fetch_one(pool)
+        .await?;
+
+        let max_kept = Self::MAX_KEPT;
+        sqlx::query!(
+            r#"DELETE FROM error_reports
+               WHERE id NOT IN (SELECT id FROM error_reports ORDER BY created_at DESC LIMIT $1)"#,
+            max_kept
+        )
+        .execute(pool)
+        .await?;
+        Ok(report)
+    }
+
+    /// The newest reports first
+    pub async fn find_recent(pool: &SqlitePool, limit: i64) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ErrorReport,
+            r#"SELECT id as "id!: Uuid",
+                      correlation_id,
+                      method,
+                      route,
+                      path,
+                      status,
+                      message,
+                      log_lines as "log_lines!: Json<Vec<String>>",
+                      state as "state!: Json<Value>",
+                      forwarded_at as "forwarded_at?: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM error_reports
+               ORDER BY created_at DESC
+               LIMIT $1"#,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ErrorReport,
+            r#"SELECT id as "id!: Uuid",
+                      correlation_id,
+                      method,
+                      route,
+                      path,
+                      status,
+                      message,
+                      log_lines as "log_lines!: Json<Vec<String>>",
+                      state as "state!: Json<Value>",
+                      forwarded_at as "forwarded_at?: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM error_reports
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn mark_forwarded(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE error_reports SET forwarded_at = datetime('now', 'subsec') WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Delete every report. Returns the number deleted.
+    pub async fn delete_all(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM error_reports")
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
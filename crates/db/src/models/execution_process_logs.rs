@@ -13,6 +13,13 @@ pub struct ExecutionProcessLogs {
     pub inserted_at: DateTime<Utc>,
 }
 
+/// How many finished processes have logs, and how large the logs are
+#[derive(Debug, Clone, Copy, FromRow)]
+pub struct ExecutionProcessLogsUsage {
+    pub processes: i64,
+    pub bytes: i64,
+}
+
 impl ExecutionProcessLogs {
     /// Find logs by execution process ID
     pub async fn find_by_execution_id(
@@ -66,4 +73,42 @@ impl ExecutionProcessLogs {
 
         Ok(())
     }
+
+    /// Logs of processes that finished before `cutoff`
+    pub async fn usage_completed_before(
+        pool: &SqlitePool,
+        cutoff: DateTime<Utc>,
+    ) -> Result<ExecutionProcessLogsUsage, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessLogsUsage,
+            r#"SELECT COUNT(DISTINCT epl.execution_id) as "processes!: i64",
+                      COALESCE(SUM(epl.byte_size), 0) as "bytes!: i64"
+               FROM execution_process_logs epl
+               JOIN execution_processes ep ON ep.id = epl.execution_id
+               WHERE ep.status != 'running'
+                 AND datetime(ep.completed_at) < datetime($1)"#,
+            cutoff
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Delete the logs of processes that finished before `cutoff`, keeping the processes
+    pub async fn delete_completed_before(
+        pool: &SqlitePool,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"DELETE FROM execution_process_logs
+               WHERE execution_id IN (
+                   SELECT id FROM execution_processes
+                   WHERE status != 'running'
+                     AND datetime(completed_at) < datetime($1)
+               )"#,
+            cutoff
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
 }
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -201,6 +201,28 @@ impl TaskImage {
         Ok(())
     }
 
+    /// Associate the images of one task with another
+    pub async fn copy_to_task<'e, E>(
+        executor: E,
+        from_task_id: Uuid,
+        to_task_id: Uuid,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query!(
+            r#"INSERT INTO task_images (id, task_id, image_id)
+               SELECT randomblob(16), $2, image_id
+               FROM task_images
+               WHERE task_id = $1"#,
+            from_task_id,
+            to_task_id
+        )
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+
     pub async fn delete_by_task_id(pool: &SqlitePool, task_id: Uuid) -> Result<(), sqlx::Error> {
         sqlx::query!(r#"DELETE FROM task_images WHERE task_id = $1"#, task_id)
             .execute(pool)
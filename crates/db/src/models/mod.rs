@@ -1,15 +1,40 @@
+pub mod analytics;
+pub mod audit_log;
 pub mod coding_agent_turn;
+pub mod document_annotation;
+pub mod document_approval;
+pub mod document_draft;
+pub mod document_encryption;
+pub mod document_lock;
+pub mod document_snapshot;
+pub mod document_sync_state;
+pub mod document_tag;
+pub mod document_task_link;
+pub mod e2e_run;
+pub mod error_report;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
 pub mod image;
 pub mod merge;
+pub mod preview_build;
+pub mod preview_comparison;
+pub mod preview_screenshot;
 pub mod project;
 pub mod project_repo;
+pub mod project_seed_document;
+pub mod project_webhook;
 pub mod repo;
 pub mod scratch;
 pub mod session;
 pub mod tag;
 pub mod task;
+pub mod task_document;
+pub mod task_package_scope;
+pub mod task_redirect;
+pub mod test_result;
+pub mod wip_limit;
+pub mod worker;
+pub mod worker_requirement;
 pub mod workspace;
 pub mod workspace_repo;
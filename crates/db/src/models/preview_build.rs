@@ -0,0 +1,206 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "preview_build_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(use_ts_enum)]
+pub enum PreviewBuildStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// A build of a repository of an attempt's worktree into a static bundle for review
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PreviewBuild {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub status: PreviewBuildStatus,
+    /// Commit of the worktree the build started from
+    pub commit_sha: Option<String>,
+    /// End of the build script's output
+    pub output: String,
+    pub error: Option<String>,
+    /// Size of the stored bundle
+    pub size_bytes: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Built from the fork point with the attempt's target branch, to compare the
+    /// attempt's preview against
+    pub base: bool,
+}
+
+impl PreviewBuild {
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        commit_sha: Option<&str>,
+        base: bool,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            PreviewBuild,
+            r#"INSERT INTO preview_builds (id, workspace_id, repo_id, commit_sha, base)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         status as "status!: PreviewBuildStatus",
+                         commit_sha,
+                         output,
+                         error,
+                         size_bytes,
+                         created_at as "created_at!: DateTime<Utc>",
+                         completed_at as "completed_at: DateTime<Utc>",
+                         base as "base!: bool""#,
+            id,
+            workspace_id,
+            repo_id,
+            commit_sha,
+            base
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PreviewBuild,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      status as "status!: PreviewBuildStatus",
+                      commit_sha,
+                      output,
+                      error,
+                      size_bytes,
+                      created_at as "created_at!: DateTime<Utc>",
+                      completed_at as "completed_at: DateTime<Utc>",
+                      base as "base!: bool"
+               FROM preview_builds
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Builds of a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PreviewBuild,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      status as "status!: PreviewBuildStatus",
+                      commit_sha,
+                      output,
+                      error,
+                      size_bytes,
+                      created_at as "created_at!: DateTime<Utc>",
+                      completed_at as "completed_at: DateTime<Utc>",
+                      base as "base!: bool"
+               FROM preview_builds
+               WHERE workspace_id = $1
+               ORDER BY created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn has_running(pool: &SqlitePool, workspace_id: Uuid) -> Result<bool, sqlx::Error> {
+        let running = sqlx::query_scalar!(
+            r#"SELECT EXISTS(
+                   SELECT 1 FROM preview_builds
+                   WHERE workspace_id = $1 AND status = 'running'
+               ) as "running!: bool""#,
+            workspace_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(running)
+    }
+
+    pub async fn complete(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: PreviewBuildStatus,
+        output: &str,
+        error: Option<&str>,
+        size_bytes: Option<i64>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE preview_builds
+               SET status = $2,
+                   output = $3,
+                   error = $4,
+                   size_bytes = $5,
+                   completed_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            status,
+            output,
+            error,
+            size_bytes
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Fail builds that were still running when the server stopped
+    pub async fn fail_interrupted(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE preview_builds
+               SET status = 'failed',
+                   error = 'The server stopped during the build',
+                   completed_at = datetime('now', 'subsec')
+               WHERE status = 'running'"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Succeeded builds of a repository in a workspace after the newest `keep`, whose
+    /// bundles can be removed. Builds of the base are kept apart from the attempt's.
+    pub async fn find_superseded(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        base: bool,
+        keep: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT id as "id!: Uuid"
+               FROM preview_builds
+               WHERE workspace_id = $1 AND repo_id = $2 AND base = $3
+                     AND status = 'succeeded'
+               ORDER BY created_at DESC
+               LIMIT -1 OFFSET $4"#,
+            workspace_id,
+            repo_id,
+            base,
+            keep
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM preview_builds WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A screenshot of an attempt's preview compared with one of its base at the same route
+/// and viewport
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PreviewComparison {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    /// Screenshot of the attempt's preview
+    pub screenshot_id: Uuid,
+    /// Page of the base the attempt's page was compared with
+    pub base_url: String,
+    pub changed_pixels: i64,
+    pub total_pixels: i64,
+    /// Blocks of the page with changed pixels, a measure of how much of the layout changed
+    pub changed_regions: i64,
+    pub total_regions: i64,
+    /// Whether the pages rendered at different sizes
+    pub size_changed: bool,
+    /// Whether the change is above the threshold the comparison was made with
+    pub significant: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct CreatePreviewComparison {
+    pub workspace_id: Uuid,
+    pub screenshot_id: Uuid,
+    pub base_url: String,
+    pub changed_pixels: i64,
+    pub total_pixels: i64,
+    pub changed_regions: i64,
+    pub total_regions: i64,
+    pub size_changed: bool,
+    pub significant: bool,
+}
+
+impl PreviewComparison {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreatePreviewComparison,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            PreviewComparison,
+            r#"INSERT INTO preview_comparisons
+                   (id, workspace_id, screenshot_id, base_url, changed_pixels, total_pixels,
+                    changed_regions, total_regions, size_changed, significant)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         screenshot_id as "screenshot_id!: Uuid",
+                         base_url,
+                         changed_pixels,
+                         total_pixels,
+                         changed_regions,
+                         total_regions,
+                         size_changed as "size_changed!: bool",
+                         significant as "significant!: bool",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.workspace_id,
+            data.screenshot_id,
+            data.base_url,
+            data.changed_pixels,
+            data.total_pixels,
+            data.changed_regions,
+            data.total_regions,
+            data.size_changed,
+            data.significant
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PreviewComparison,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      screenshot_id as "screenshot_id!: Uuid",
+                      base_url,
+                      changed_pixels,
+                      total_pixels,
+                      changed_regions,
+                      total_regions,
+                      size_changed as "size_changed!: bool",
+                      significant as "significant!: bool",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM preview_comparisons
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Comparisons of a workspace, oldest first, in the order they were made
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PreviewComparison,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      screenshot_id as "screenshot_id!: Uuid",
+                      base_url,
+                      changed_pixels,
+                      total_pixels,
+                      changed_regions,
+                      total_regions,
+                      size_changed as "size_changed!: bool",
+                      significant as "significant!: bool",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM preview_comparisons
+               WHERE workspace_id = $1
+               ORDER BY created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Ids of the comparisons of a screenshot, whose images go with it
+    pub async fn find_ids_by_screenshot_id(
+        pool: &SqlitePool,
+        screenshot_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT id as "id!: Uuid"
+               FROM preview_comparisons
+               WHERE screenshot_id = $1"#,
+            screenshot_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A screenshot of an attempt's preview at one route and viewport
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct PreviewScreenshot {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    /// Page the screenshot was taken of
+    pub url: String,
+    /// Route of the preview the page is at, e.g. `/settings`
+    pub route: String,
+    /// Name of the viewport, e.g. `desktop`
+    pub viewport: String,
+    pub width: i64,
+    pub height: i64,
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct CreatePreviewScreenshot {
+    pub workspace_id: Uuid,
+    pub url: String,
+    pub route: String,
+    pub viewport: String,
+    pub width: i64,
+    pub height: i64,
+    pub size_bytes: i64,
+}
+
+impl PreviewScreenshot {
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreatePreviewScreenshot,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            PreviewScreenshot,
+            r#"INSERT INTO preview_screenshots
+                   (id, workspace_id, url, route, viewport, width, height, size_bytes)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING id as "id!: Uuid",
+                         workspace_id as "workspace_id!: Uuid",
+                         url,
+                         route,
+                         viewport,
+                         width,
+                         height,
+                         size_bytes,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.workspace_id,
+            data.url,
+            data.route,
+            data.viewport,
+            data.width,
+            data.height,
+            data.size_bytes
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PreviewScreenshot,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      url,
+                      route,
+                      viewport,
+                      width,
+                      height,
+                      size_bytes,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM preview_screenshots
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Screenshots of a workspace, oldest first, in the order they were taken
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PreviewScreenshot,
+            r#"SELECT id as "id!: Uuid",
+                      workspace_id as "workspace_id!: Uuid",
+                      url,
+                      route,
+                      viewport,
+                      width,
+                      height,
+                      size_bytes,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM preview_screenshots
+               WHERE workspace_id = $1
+               ORDER BY created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM preview_screenshots WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
 use thiserror::Error;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -23,6 +23,8 @@ pub struct Project {
     pub name: String,
     pub default_agent_working_dir: Option<String>,
     pub remote_project_id: Option<Uuid>,
+    /// Template projects are blueprints that new projects can be created from
+    pub is_template: bool,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -35,9 +37,152 @@ pub struct CreateProject {
     pub repositories: Vec<CreateProjectRepo>,
 }
 
+/// Create a new project configured like an existing one
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CloneProject {
+    pub name: String,
+    pub repositories: Vec<CreateProjectRepo>,
+    /// Also copy tasks that are not done or cancelled (as new Todo tasks)
+    #[serde(default)]
+    pub include_open_tasks: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateProjectFromTemplate {
+    pub template_id: Uuid,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub project: CloneProject,
+}
+
+/// Safety limits for walking a project's repositories for documents. A scan that hits
+/// one returns what it found so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct DocumentScanLimits {
+    /// Stop after finding this many documents
+    pub max_files: u32,
+    /// Don't descend more than this many folders below the repository root
+    pub max_depth: u32,
+    /// Stop after scanning for this many seconds
+    pub max_seconds: u32,
+}
+
+impl Default for DocumentScanLimits {
+    fn default() -> Self {
+        Self {
+            max_files: 20_000,
+            max_depth: 32,
+            max_seconds: 10,
+        }
+    }
+}
+
+/// Largest document whose content is read, unless the project sets its own limit
+pub const DEFAULT_DOCUMENT_MAX_READ_BYTES: usize = 10 * 1024 * 1024;
+
+/// Folder next to a document its uploaded images go to, unless the project sets its own
+pub const DEFAULT_DOCUMENT_ASSETS_FOLDER: &str = "assets";
+
+/// Folder of the primary repository document templates are read from, unless the project
+/// sets its own. Hidden, so templates aren't listed as documents.
+pub const DEFAULT_DOCUMENT_TEMPLATES_FOLDER: &str = ".templates";
+
+/// Source files and folders that documents matching a path glob describe. Documents
+/// are flagged as possibly stale when this code changes after them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct DocumentCodeReference {
+    /// Glob of the documents, e.g. `docs/architecture/**`
+    pub documents: String,
+    /// Files or folders relative to the repository root, e.g. `crates/server`
+    pub code: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentHeadingStyle {
+    /// `# Title`
+    #[default]
+    Atx,
+    /// `Title` underlined with `===` or `---`; deeper headings stay ATX
+    Setext,
+}
+
+/// How markdown documents of a project are formatted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct DocumentFormatOptions {
+    /// Wrap paragraphs and list items at this many characters; `None` leaves lines as
+    /// they are
+    #[serde(default)]
+    pub line_width: Option<u32>,
+    #[serde(default)]
+    pub heading_style: DocumentHeadingStyle,
+    /// Pad table cells so the columns line up
+    #[serde(default = "default_align_tables")]
+    pub align_tables: bool,
+}
+
+fn default_align_tables() -> bool {
+    true
+}
+
+impl Default for DocumentFormatOptions {
+    fn default() -> Self {
+        Self {
+            line_width: None,
+            heading_style: DocumentHeadingStyle::default(),
+            align_tables: default_align_tables(),
+        }
+    }
+}
+
+/// How document commits of a project are written
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct DocumentCommitSettings {
+    /// Template for commit messages with `{message}`, `{path}` and `{user}` placeholders,
+    /// e.g. `docs({user}): {message}`; `None` keeps the default messages
+    #[serde(default)]
+    pub message_template: Option<String>,
+    /// Identity to commit as; `None` uses the repository's configured identity
+    #[serde(default)]
+    pub committer_name: Option<String>,
+    #[serde(default)]
+    pub committer_email: Option<String>,
+}
+
+/// How document sync brings in commits from origin/main before pushing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentSyncStrategy {
+    /// Replay local commits on top of origin/main
+    #[default]
+    Rebase,
+    /// Join diverged branches with a merge commit
+    Merge,
+    /// Only fast-forward; diverged branches have to be reconciled by hand
+    FastForwardOnly,
+}
+
+/// How symbolic links in the project's repositories are treated when documents are
+/// scanned, read and saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Type, Serialize, Deserialize, TS)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentSymlinkPolicy {
+    /// Leave symbolic links out of listings and refuse to read or save through them
+    #[default]
+    Skip,
+    /// Follow links that resolve inside the repository, except folder links that lead
+    /// back to a folder being scanned
+    FollowWithinRepo,
+}
+
 #[derive(Debug, Deserialize, TS)]
 pub struct UpdateProject {
     pub name: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub is_template: Option<bool>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -64,18 +209,39 @@ impl Project {
             .await
     }
 
-    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+    pub async fn find_all(
+        executor: impl Executor<'_, Database = Sqlite>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
             r#"SELECT id as "id!: Uuid",
                       name,
                       default_agent_working_dir,
+                      is_template as "is_template!: bool",
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
                ORDER BY created_at DESC"#
         )
+        .fetch_all(executor)
+        .await
+    }
+
+    pub async fn find_templates(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Project,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      default_agent_working_dir,
+                      is_template as "is_template!: bool",
+                      remote_project_id as "remote_project_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM projects
+               WHERE is_template = 1
+               ORDER BY name"#
+        )
         .fetch_all(pool)
         .await
     }
@@ -87,6 +253,7 @@ impl Project {
             r#"
             SELECT p.id as "id!: Uuid", p.name,
                    p.default_agent_working_dir,
+                   p.is_template as "is_template!: bool",
                    p.remote_project_id as "remote_project_id: Uuid",
                    p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
@@ -110,6 +277,7 @@ impl Project {
             r#"SELECT id as "id!: Uuid",
                       name,
                       default_agent_working_dir,
+                      is_template as "is_template!: bool",
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -127,6 +295,7 @@ impl Project {
             r#"SELECT id as "id!: Uuid",
                       name,
                       default_agent_working_dir,
+                      is_template as "is_template!: bool",
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -147,6 +316,7 @@ impl Project {
             r#"SELECT id as "id!: Uuid",
                       name,
                       default_agent_working_dir,
+                      is_template as "is_template!: bool",
                       remote_project_id as "remote_project_id: Uuid",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
@@ -175,6 +345,7 @@ impl Project {
                 RETURNING id as "id!: Uuid",
                           name,
                           default_agent_working_dir,
+                          is_template as "is_template!: bool",
                           remote_project_id as "remote_project_id: Uuid",
                           created_at as "created_at!: DateTime<Utc>",
                           updated_at as "updated_at!: DateTime<Utc>""#,
@@ -195,25 +366,612 @@ impl Project {
             .ok_or(sqlx::Error::RowNotFound)?;
 
         let name = payload.name.clone().unwrap_or(existing.name);
+        let is_template = payload.is_template.unwrap_or(existing.is_template);
 
         sqlx::query_as!(
             Project,
             r#"UPDATE projects
-               SET name = $2
+               SET name = $2, is_template = $3
                WHERE id = $1
                RETURNING id as "id!: Uuid",
                          name,
                          default_agent_working_dir,
+                         is_template as "is_template!: bool",
                          remote_project_id as "remote_project_id: Uuid",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
+            is_template,
         )
         .fetch_one(pool)
         .await
     }
 
+    pub async fn set_default_agent_working_dir(
+        pool: &SqlitePool,
+        id: Uuid,
+        default_agent_working_dir: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE projects
+               SET default_agent_working_dir = $2
+               WHERE id = $1"#,
+            id,
+            default_agent_working_dir
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// File extensions (lowercase, without the dot) shown in the project's documents.
+    /// `None` if the project uses the default set.
+    pub async fn find_document_extensions(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<Vec<String>>, sqlx::Error> {
+        let raw = sqlx::query_scalar!(
+            r#"SELECT document_extensions FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+
+        Ok(raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+    }
+
+    pub async fn set_document_extensions(
+        pool: &SqlitePool,
+        id: Uuid,
+        extensions: Option<&[String]>,
+    ) -> Result<(), sqlx::Error> {
+        let raw = extensions.map(|extensions| serde_json::json!(extensions).to_string());
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_extensions = $2
+               WHERE id = $1"#,
+            id,
+            raw
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Path globs whose documents are read-only through the document API
+    pub async fn find_document_read_only_paths(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let raw = sqlx::query_scalar!(
+            r#"SELECT document_read_only_paths FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+
+        Ok(raw
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default())
+    }
+
+    pub async fn set_document_read_only_paths(
+        pool: &SqlitePool,
+        id: Uuid,
+        patterns: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let raw = (!patterns.is_empty()).then(|| serde_json::json!(patterns).to_string());
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_read_only_paths = $2
+               WHERE id = $1"#,
+            id,
+            raw
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Folders the project's documents live in; empty for the whole repository
+    pub async fn find_document_roots(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let raw = sqlx::query_scalar!(r#"SELECT document_roots FROM projects WHERE id = $1"#, id)
+            .fetch_optional(pool)
+            .await?
+            .flatten();
+
+        Ok(raw
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default())
+    }
+
+    pub async fn set_document_roots(
+        pool: &SqlitePool,
+        id: Uuid,
+        roots: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let raw = (!roots.is_empty()).then(|| serde_json::json!(roots).to_string());
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_roots = $2
+               WHERE id = $1"#,
+            id,
+            raw
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_document_code_references(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Vec<DocumentCodeReference>, sqlx::Error> {
+        let raw = sqlx::query_scalar!(
+            r#"SELECT document_code_references FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+
+        Ok(raw
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default())
+    }
+
+    pub async fn set_document_code_references(
+        pool: &SqlitePool,
+        id: Uuid,
+        references: &[DocumentCodeReference],
+    ) -> Result<(), sqlx::Error> {
+        let raw = (!references.is_empty()).then(|| serde_json::json!(references).to_string());
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_code_references = $2
+               WHERE id = $1"#,
+            id,
+            raw
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Document scan limits of the project, or the defaults if it has none
+    pub async fn find_document_scan_limits(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<DocumentScanLimits, sqlx::Error> {
+        let raw = sqlx::query_scalar!(
+            r#"SELECT document_scan_limits FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+
+        Ok(raw
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default())
+    }
+
+    pub async fn set_document_scan_limits(
+        pool: &SqlitePool,
+        id: Uuid,
+        limits: Option<&DocumentScanLimits>,
+    ) -> Result<(), sqlx::Error> {
+        let raw = limits.map(|limits| serde_json::json!(limits).to_string());
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_scan_limits = $2
+               WHERE id = $1"#,
+            id,
+            raw
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Largest document of the project whose content is read, in bytes
+    pub async fn find_document_max_read_bytes(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<usize, sqlx::Error> {
+        let max_bytes = sqlx::query_scalar!(
+            r#"SELECT document_max_read_bytes FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+
+        Ok(max_bytes
+            .and_then(|max_bytes| usize::try_from(max_bytes).ok())
+            .unwrap_or(DEFAULT_DOCUMENT_MAX_READ_BYTES))
+    }
+
+    /// Set the largest document whose content is read, `None` for the default
+    pub async fn set_document_max_read_bytes(
+        pool: &SqlitePool,
+        id: Uuid,
+        max_bytes: Option<usize>,
+    ) -> Result<(), sqlx::Error> {
+        let max_bytes = max_bytes.map(|max_bytes| i64::try_from(max_bytes).unwrap_or(i64::MAX));
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_max_read_bytes = $2
+               WHERE id = $1"#,
+            id,
+            max_bytes
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Folder next to a document the project stores its uploaded images in
+    pub async fn find_document_assets_folder(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<String, sqlx::Error> {
+        let folder = sqlx::query_scalar!(
+            r#"SELECT document_assets_folder FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+
+        Ok(folder.unwrap_or_else(|| DEFAULT_DOCUMENT_ASSETS_FOLDER.to_string()))
+    }
+
+    /// Set the folder uploaded images are stored in, `None` for the default
+    pub async fn set_document_assets_folder(
+        pool: &SqlitePool,
+        id: Uuid,
+        folder: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_assets_folder = $2
+               WHERE id = $1"#,
+            id,
+            folder
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Folder of the primary repository the project's document templates are in
+    pub async fn find_document_templates_folder(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<String, sqlx::Error> {
+        let folder = sqlx::query_scalar!(
+            r#"SELECT document_templates_folder FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+
+        Ok(folder.unwrap_or_else(|| DEFAULT_DOCUMENT_TEMPLATES_FOLDER.to_string()))
+    }
+
+    /// Set the folder document templates are read from, `None` for the default
+    pub async fn set_document_templates_folder(
+        pool: &SqlitePool,
+        id: Uuid,
+        folder: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_templates_folder = $2
+               WHERE id = $1"#,
+            id,
+            folder
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Markdown formatting options of the project, or the defaults if it has none
+    pub async fn find_document_format_options(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<DocumentFormatOptions, sqlx::Error> {
+        let raw = sqlx::query_scalar!(
+            r#"SELECT document_format_options FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+
+        Ok(raw
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default())
+    }
+
+    pub async fn set_document_format_options(
+        pool: &SqlitePool,
+        id: Uuid,
+        options: Option<&DocumentFormatOptions>,
+    ) -> Result<(), sqlx::Error> {
+        let raw = options.map(|options| serde_json::json!(options).to_string());
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_format_options = $2
+               WHERE id = $1"#,
+            id,
+            raw
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Commit message template and identity of the project, or the defaults if it has
+    /// none
+    pub async fn find_document_commit_settings(
+        executor: impl Executor<'_, Database = Sqlite>,
+        id: Uuid,
+    ) -> Result<DocumentCommitSettings, sqlx::Error> {
+        let raw = sqlx::query_scalar!(
+            r#"SELECT document_commit_settings FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(executor)
+        .await?
+        .flatten();
+
+        Ok(raw
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default())
+    }
+
+    pub async fn set_document_commit_settings(
+        executor: impl Executor<'_, Database = Sqlite>,
+        id: Uuid,
+        settings: Option<&DocumentCommitSettings>,
+    ) -> Result<(), sqlx::Error> {
+        let raw = settings.map(|settings| serde_json::json!(settings).to_string());
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_commit_settings = $2
+               WHERE id = $1"#,
+            id,
+            raw
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Whether document saves of the project are committed right away, true unless
+    /// turned off
+    pub async fn find_document_auto_commit(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let auto_commit = sqlx::query_scalar!(
+            r#"SELECT document_auto_commit as "document_auto_commit!: bool"
+               FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(auto_commit.unwrap_or(true))
+    }
+
+    pub async fn set_document_auto_commit(
+        pool: &SqlitePool,
+        id: Uuid,
+        auto_commit: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_auto_commit = $2
+               WHERE id = $1"#,
+            id,
+            auto_commit
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Whether document edits of the project go to `docs/` branches instead of main,
+    /// false unless turned on
+    pub async fn find_document_branch_editing(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let branch_editing = sqlx::query_scalar!(
+            r#"SELECT document_branch_editing as "document_branch_editing!: bool"
+               FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(branch_editing.unwrap_or(false))
+    }
+
+    pub async fn set_document_branch_editing(
+        pool: &SqlitePool,
+        id: Uuid,
+        branch_editing: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_branch_editing = $2
+               WHERE id = $1"#,
+            id,
+            branch_editing
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// How the project's documents are synced, `Rebase` unless set otherwise
+    pub async fn find_document_sync_strategy(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<DocumentSyncStrategy, sqlx::Error> {
+        let strategy = sqlx::query_scalar!(
+            r#"SELECT document_sync_strategy as "document_sync_strategy!: DocumentSyncStrategy"
+               FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(strategy.unwrap_or_default())
+    }
+
+    pub async fn set_document_sync_strategy(
+        pool: &SqlitePool,
+        id: Uuid,
+        strategy: DocumentSyncStrategy,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_sync_strategy = $2
+               WHERE id = $1"#,
+            id,
+            strategy
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// How symbolic links in the project's repositories are treated, `Skip` unless set
+    /// otherwise
+    pub async fn find_document_symlink_policy(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<DocumentSymlinkPolicy, sqlx::Error> {
+        let policy = sqlx::query_scalar!(
+            r#"SELECT document_symlink_policy as "document_symlink_policy!: DocumentSymlinkPolicy"
+               FROM projects WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(policy.unwrap_or_default())
+    }
+
+    pub async fn set_document_symlink_policy(
+        pool: &SqlitePool,
+        id: Uuid,
+        policy: DocumentSymlinkPolicy,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE projects
+               SET document_symlink_policy = $2
+               WHERE id = $1"#,
+            id,
+            policy
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Copy the agent working directory, timezone and every document setting of one
+    /// project to another
+    pub async fn copy_settings<'e, E>(
+        executor: E,
+        from_project_id: Uuid,
+        to_project_id: Uuid,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        sqlx::query!(
+            r#"UPDATE projects
+               SET (default_agent_working_dir, timezone, document_extensions,
+                    document_read_only_paths, document_roots, document_code_references,
+                    document_scan_limits, document_max_read_bytes, document_assets_folder,
+                    document_templates_folder, document_format_options,
+                    document_commit_settings, document_auto_commit, document_branch_editing,
+                    document_sync_strategy, document_symlink_policy) =
+                   (SELECT default_agent_working_dir, timezone, document_extensions,
+                           document_read_only_paths, document_roots, document_code_references,
+                           document_scan_limits, document_max_read_bytes,
+                           document_assets_folder, document_templates_folder,
+                           document_format_options, document_commit_settings,
+                           document_auto_commit, document_branch_editing,
+                           document_sync_strategy, document_symlink_policy
+                    FROM projects WHERE id = $1)
+               WHERE id = $2"#,
+            from_project_id,
+            to_project_id
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The project's own timezone, `None` if it uses the user's
+    pub async fn find_timezone(pool: &SqlitePool, id: Uuid) -> Result<Option<String>, sqlx::Error> {
+        let timezone = sqlx::query_scalar!(r#"SELECT timezone FROM projects WHERE id = $1"#, id)
+            .fetch_optional(pool)
+            .await?
+            .flatten();
+
+        Ok(timezone)
+    }
+
+    pub async fn set_timezone(
+        pool: &SqlitePool,
+        id: Uuid,
+        timezone: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE projects
+               SET timezone = $2
+               WHERE id = $1"#,
+            id,
+            timezone
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn set_remote_project_id(
         pool: &SqlitePool,
         id: Uuid,
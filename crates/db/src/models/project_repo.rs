@@ -82,6 +82,10 @@ impl ProjectRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.preview_build_script,
+                      r.preview_output_dir,
+                      r.e2e_script,
+                      r.e2e_results_dir,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A document a template project writes into the repositories of projects created from it
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectSeedDocument {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Path relative to the repository root
+    pub path: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertProjectSeedDocument {
+    pub path: String,
+    pub content: String,
+}
+
+impl ProjectSeedDocument {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectSeedDocument,
+            r#"SELECT id         as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      path,
+                      content,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_seed_documents
+               WHERE project_id = $1
+               ORDER BY path"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectSeedDocument,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ProjectSeedDocument,
+            r#"INSERT INTO project_seed_documents (id, project_id, path, content)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(project_id, path) DO UPDATE SET
+                   content = excluded.content,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id         as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         path,
+                         content,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.path,
+            data.content
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Copy all seed documents of one project to another
+    pub async fn copy_to_project<'e, E>(
+        executor: E,
+        from_project_id: Uuid,
+        to_project_id: Uuid,
+    ) -> Result<u64, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let result = sqlx::query!(
+            r#"INSERT OR IGNORE INTO project_seed_documents (id, project_id, path, content)
+               SELECT randomblob(16), $2, path, content
+               FROM project_seed_documents
+               WHERE project_id = $1"#,
+            from_project_id,
+            to_project_id
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_seed_documents WHERE project_id = $1 AND id = $2",
+            project_id,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
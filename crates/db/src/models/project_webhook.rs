@@ -0,0 +1,259 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Changes a webhook can be called for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub enum WebhookEvent {
+    #[serde(rename = "document.created")]
+    DocumentCreated,
+    #[serde(rename = "document.updated")]
+    DocumentUpdated,
+    #[serde(rename = "document.deleted")]
+    DocumentDeleted,
+    /// The project's documents were pushed to or pulled from the remote
+    #[serde(rename = "documents.synced")]
+    DocumentsSynced,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::DocumentCreated => "document.created",
+            WebhookEvent::DocumentUpdated => "document.updated",
+            WebhookEvent::DocumentDeleted => "document.deleted",
+            WebhookEvent::DocumentsSynced => "documents.synced",
+        }
+    }
+}
+
+/// A URL called with a signed payload when the project's documents change
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectWebhook {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub url: String,
+    /// Key the payloads are signed with; never returned by the API
+    #[serde(skip)]
+    #[ts(skip)]
+    pub secret: String,
+    /// Events the webhook is called for; empty for all of them
+    #[ts(type = "Array<WebhookEvent>")]
+    pub events: Json<Vec<WebhookEvent>>,
+    pub enabled: bool,
+    pub last_delivery_at: Option<DateTime<Utc>>,
+    /// HTTP status of the last delivery, if the endpoint answered
+    pub last_status: Option<i64>,
+    /// Why the last delivery failed; `None` if it succeeded
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateProjectWebhook {
+    pub url: String,
+    pub secret: String,
+    /// Events to call the webhook for; all of them if empty or left out
+    #[serde(default)]
+    #[ts(optional)]
+    pub events: Vec<WebhookEvent>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub enabled: Option<bool>,
+}
+
+/// Fields left out are kept
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateProjectWebhook {
+    #[serde(default)]
+    #[ts(optional)]
+    pub url: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub events: Option<Vec<WebhookEvent>>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub enabled: Option<bool>,
+}
+
+impl ProjectWebhook {
+    /// Whether the webhook is called for `event`
+    pub fn subscribes_to(&self, event: WebhookEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectWebhook,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEvent>>",
+                      enabled as "enabled!: bool",
+                      last_delivery_at as "last_delivery_at?: DateTime<Utc>",
+                      last_status,
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_webhooks
+               WHERE project_id = $1
+               ORDER BY created_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectWebhook,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      url,
+                      secret,
+                      events as "events!: Json<Vec<WebhookEvent>>",
+                      enabled as "enabled!: bool",
+                      last_delivery_at as "last_delivery_at?: DateTime<Utc>",
+                      last_status,
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_webhooks
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &CreateProjectWebhook,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let events = Json(&data.events);
+        let enabled = data.enabled.unwrap_or(true);
+        sqlx::query_as!(
+            ProjectWebhook,
+            r#"INSERT INTO project_webhooks (id, project_id, url, secret, events, enabled)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         url,
+                         secret,
+                         events as "events!: Json<Vec<WebhookEvent>>",
+                         enabled as "enabled!: bool",
+                         last_delivery_at as "last_delivery_at?: DateTime<Utc>",
+                         last_status,
+                         last_error,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.url,
+            data.secret,
+            events,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateProjectWebhook,
+    ) -> Result<Self, sqlx::Error> {
+        let events = data.events.as_ref().map(Json);
+        sqlx::query_as!(
+            ProjectWebhook,
+            r#"UPDATE project_webhooks
+               SET url = COALESCE($2, url),
+                   secret = COALESCE($3, secret),
+                   events = COALESCE($4, events),
+                   enabled = COALESCE($5, enabled),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         url,
+                         secret,
+                         events as "events!: Json<Vec<WebhookEvent>>",
+                         enabled as "enabled!: bool",
+                         last_delivery_at as "last_delivery_at?: DateTime<Utc>",
+                         last_status,
+                         last_error,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.url,
+            data.secret,
+            events,
+            data.enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Record the outcome of the latest delivery
+    pub async fn record_delivery(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: Option<i64>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE project_webhooks
+               SET last_delivery_at = datetime('now', 'subsec'),
+                   last_status = $2,
+                   last_error = $3
+               WHERE id = $1"#,
+            id,
+            status,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Copy all webhooks of one project to another, without their delivery history
+    pub async fn copy_to_project<'e, E>(
+        executor: E,
+        from_project_id: Uuid,
+        to_project_id: Uuid,
+    ) -> Result<u64, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let result = sqlx::query!(
+            r#"INSERT INTO project_webhooks (id, project_id, url, secret, events, enabled)
+               SELECT randomblob(16), $2, url, secret, events, enabled
+               FROM project_webhooks
+               WHERE project_id = $1"#,
+            from_project_id,
+            to_project_id
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM project_webhooks WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -27,6 +27,14 @@ pub struct Repo {
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
     pub dev_server_script: Option<String>,
+    /// Builds the repository's web app into a static bundle for preview builds
+    pub preview_build_script: Option<String>,
+    /// Folder the preview build script writes the bundle to, relative to the repository
+    pub preview_output_dir: Option<String>,
+    /// Runs the repository's end-to-end suite against a preview, e.g. `npx playwright test`
+    pub e2e_script: Option<String>,
+    /// Folder the end-to-end suite writes its JUnit reports, traces and videos to
+    pub e2e_results_dir: Option<String>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -83,6 +91,38 @@ pub struct UpdateRepo {
     )]
     #[ts(optional, type = "string | null")]
     pub dev_server_script: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub preview_build_script: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub preview_output_dir: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub e2e_script: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub e2e_results_dir: Option<Option<String>>,
 }
 
 impl Repo {
@@ -100,6 +140,10 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      preview_build_script,
+                      preview_output_dir,
+                      e2e_script,
+                      e2e_results_dir,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -138,6 +182,10 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      preview_build_script,
+                      preview_output_dir,
+                      e2e_script,
+                      e2e_results_dir,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -193,6 +241,10 @@ impl Repo {
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
+                         preview_build_script,
+                         preview_output_dir,
+                         e2e_script,
+                         e2e_results_dir,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -227,6 +279,10 @@ impl Repo {
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
+                      preview_build_script,
+                      preview_output_dir,
+                      e2e_script,
+                      e2e_results_dir,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -272,6 +328,22 @@ impl Repo {
             None => existing.dev_server_script,
             Some(v) => v.clone(),
         };
+        let preview_build_script = match &payload.preview_build_script {
+            None => existing.preview_build_script,
+            Some(v) => v.clone(),
+        };
+        let preview_output_dir = match &payload.preview_output_dir {
+            None => existing.preview_output_dir,
+            Some(v) => v.clone(),
+        };
+        let e2e_script = match &payload.e2e_script {
+            None => existing.e2e_script,
+            Some(v) => v.clone(),
+        };
+        let e2e_results_dir = match &payload.e2e_results_dir {
+            None => existing.e2e_results_dir,
+            Some(v) => v.clone(),
+        };
 
         sqlx::query_as!(
             Repo,
@@ -282,8 +354,12 @@ impl Repo {
                    copy_files = $4,
                    parallel_setup_script = $5,
                    dev_server_script = $6,
+                   preview_build_script = $7,
+                   preview_output_dir = $8,
+                   e2e_script = $9,
+                   e2e_results_dir = $10,
                    updated_at = datetime('now', 'subsec')
-               WHERE id = $7
+               WHERE id = $11
                RETURNING id as "id!: Uuid",
                          path,
                          name,
@@ -293,6 +369,10 @@ impl Repo {
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
+                         preview_build_script,
+                         preview_output_dir,
+                         e2e_script,
+                         e2e_results_dir,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             display_name,
@@ -301,6 +381,10 @@ impl Repo {
             copy_files,
             parallel_setup_script,
             dev_server_script,
+            preview_build_script,
+            preview_output_dir,
+            e2e_script,
+            e2e_results_dir,
             id
         )
         .fetch_one(pool)
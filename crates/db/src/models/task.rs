@@ -5,7 +5,7 @@ use strum_macros::{Display, EnumString};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, workspace::Workspace};
+use super::{project::Project, task_redirect::TaskRedirect, workspace::Workspace};
 
 #[derive(
     Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display, Default,
@@ -22,6 +22,45 @@ pub enum TaskStatus {
     Cancelled,
 }
 
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Type,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    TS,
+    EnumString,
+    Display,
+    Default,
+)]
+#[sqlx(type_name = "task_priority", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum TaskPriority {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Urgent,
+}
+
+impl TaskPriority {
+    /// The next higher priority, `None` when already urgent
+    pub fn escalated(self) -> Option<Self> {
+        match self {
+            TaskPriority::Low => Some(TaskPriority::Medium),
+            TaskPriority::Medium => Some(TaskPriority::High),
+            TaskPriority::High => Some(TaskPriority::Urgent),
+            TaskPriority::Urgent => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct Task {
     pub id: Uuid,
@@ -30,6 +69,9 @@ pub struct Task {
     pub description: Option<String>,
     pub status: TaskStatus,
     pub parent_workspace_id: Option<Uuid>, // Foreign key to parent Workspace
+    pub priority: TaskPriority,
+    /// Set when the task was bumped by the aging rule, cleared on manual priority changes
+    pub priority_escalated_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -72,6 +114,13 @@ pub struct CreateTask {
     pub status: Option<TaskStatus>,
     pub parent_workspace_id: Option<Uuid>,
     pub image_ids: Option<Vec<Uuid>>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub priority: Option<TaskPriority>,
+    /// Reason for creating the task in a column that is at its WIP limit
+    #[serde(default)]
+    #[ts(optional)]
+    pub wip_override_reason: Option<String>,
 }
 
 impl CreateTask {
@@ -87,6 +136,8 @@ impl CreateTask {
             status: Some(TaskStatus::Todo),
             parent_workspace_id: None,
             image_ids: None,
+            priority: None,
+            wip_override_reason: None,
         }
     }
 }
@@ -98,6 +149,13 @@ pub struct UpdateTask {
     pub status: Option<TaskStatus>,
     pub parent_workspace_id: Option<Uuid>,
     pub image_ids: Option<Vec<Uuid>>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub priority: Option<TaskPriority>,
+    /// Reason for moving the task into a column that is at its WIP limit
+    #[serde(default)]
+    #[ts(optional)]
+    pub wip_override_reason: Option<String>,
 }
 
 impl Task {
@@ -125,6 +183,8 @@ impl Task {
   t.description,
   t.status                        AS "status!: TaskStatus",
   t.parent_workspace_id           AS "parent_workspace_id: Uuid",
+  t.priority                      AS "priority!: TaskPriority",
+  t.priority_escalated_at         AS "priority_escalated_at: DateTime<Utc>",
   t.created_at                    AS "created_at!: DateTime<Utc>",
   t.updated_at                    AS "updated_at!: DateTime<Utc>",
 
@@ -177,6 +237,8 @@ ORDER BY t.created_at DESC"#,
                     description: rec.description,
                     status: rec.status,
                     parent_workspace_id: rec.parent_workspace_id,
+                    priority: rec.priority,
+                    priority_escalated_at: rec.priority_escalated_at,
                     created_at: rec.created_at,
                     updated_at: rec.updated_at,
                 },
@@ -189,10 +251,19 @@ ORDER BY t.created_at DESC"#,
         Ok(tasks)
     }
 
+    /// Number of tasks being worked on or awaiting review, across all projects
+    pub async fn count_active(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM tasks WHERE status IN ('inprogress', 'inreview')"#
+        )
+        .fetch_one(pool)
+        .await
+    }
+
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", priority as "priority!: TaskPriority", priority_escalated_at as "priority_escalated_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE id = $1"#,
             id
@@ -201,10 +272,61 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 
+    /// Tasks of the project, oldest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", priority as "priority!: TaskPriority", priority_escalated_at as "priority_escalated_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1
+               ORDER BY created_at"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Find a task by id, following the tombstone left behind if it was merged
+    pub async fn find_by_id_or_redirect(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        if let Some(task) = Self::find_by_id(pool, id).await? {
+            return Ok(Some(task));
+        }
+        match TaskRedirect::find_by_from_task_id(pool, id).await? {
+            Some(redirect) => Self::find_by_id(pool, redirect.to_task_id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Find a task in the project with the given title that has not been completed or cancelled
+    pub async fn find_open_by_title(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        title: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", priority as "priority!: TaskPriority", priority_escalated_at as "priority_escalated_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1 AND title = $2 AND status NOT IN ('done', 'cancelled')
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            project_id,
+            title
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", priority as "priority!: TaskPriority", priority_escalated_at as "priority_escalated_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE rowid = $1"#,
             rowid
@@ -214,24 +336,26 @@ ORDER BY t.created_at DESC"#,
     }
 
     pub async fn create(
-        pool: &SqlitePool,
+        executor: impl Executor<'_, Database = Sqlite>,
         data: &CreateTask,
         task_id: Uuid,
     ) -> Result<Self, sqlx::Error> {
         let status = data.status.clone().unwrap_or_default();
+        let priority = data.priority.unwrap_or_default();
         sqlx::query_as!(
             Task,
-            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_workspace_id)
-               VALUES ($1, $2, $3, $4, $5, $6)
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_workspace_id, priority, todo_since)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, CASE WHEN $5 = 'todo' THEN datetime('now', 'subsec') END)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", priority as "priority!: TaskPriority", priority_escalated_at as "priority_escalated_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             task_id,
             data.project_id,
             data.title,
             data.description,
             status,
-            data.parent_workspace_id
+            data.parent_workspace_id,
+            priority
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
     }
 
@@ -247,9 +371,10 @@ ORDER BY t.created_at DESC"#,
         sqlx::query_as!(
             Task,
             r#"UPDATE tasks
-               SET title = $3, description = $4, status = $5, parent_workspace_id = $6
+               SET title = $3, description = $4, status = $5, parent_workspace_id = $6,
+                   todo_since = CASE WHEN $5 = 'todo' AND status != 'todo' THEN datetime('now', 'subsec') ELSE todo_since END
                WHERE id = $1 AND project_id = $2
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", priority as "priority!: TaskPriority", priority_escalated_at as "priority_escalated_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             project_id,
             title,
@@ -261,13 +386,50 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 
+    /// Tasks of the project whose description contains `needle`, oldest first
+    pub async fn find_by_description_containing(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        needle: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", priority as "priority!: TaskPriority", priority_escalated_at as "priority_escalated_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE project_id = $1 AND instr(description, $2) > 0
+               ORDER BY created_at ASC"#,
+            project_id,
+            needle
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn update_description(
+        pool: &SqlitePool,
+        id: Uuid,
+        description: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET description = $2, updated_at = datetime('now', 'subsec') WHERE id = $1",
+            id,
+            description
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn update_status(
         pool: &SqlitePool,
         id: Uuid,
         status: TaskStatus,
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
-            "UPDATE tasks SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            r#"UPDATE tasks
+               SET status = $2, updated_at = CURRENT_TIMESTAMP,
+                   todo_since = CASE WHEN $2 = 'todo' AND status != 'todo' THEN datetime('now', 'subsec') ELSE todo_since END
+               WHERE id = $1"#,
             id,
             status
         )
@@ -276,6 +438,65 @@ ORDER BY t.created_at DESC"#,
         Ok(())
     }
 
+    /// Set the priority manually, clearing any aging escalation flag. The aging rule
+    /// waits a full period from here before bumping the task again.
+    pub async fn update_priority(
+        pool: &SqlitePool,
+        id: Uuid,
+        priority: TaskPriority,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"UPDATE tasks
+               SET priority = $2, priority_escalated_at = NULL, priority_set_at = datetime('now', 'subsec'),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", priority as "priority!: TaskPriority", priority_escalated_at as "priority_escalated_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            priority
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Todo tasks below urgent that entered Todo before `cutoff` and whose priority
+    /// wasn't escalated or set by hand since
+    pub async fn find_aging_todo(
+        pool: &SqlitePool,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", priority as "priority!: TaskPriority", priority_escalated_at as "priority_escalated_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks
+               WHERE status = 'todo'
+                 AND priority != 'urgent'
+                 AND datetime(COALESCE(todo_since, created_at)) < datetime($1)
+                 AND (priority_escalated_at IS NULL OR datetime(priority_escalated_at) < datetime($1))
+                 AND (priority_set_at IS NULL OR datetime(priority_set_at) < datetime($1))
+               ORDER BY created_at ASC"#,
+            cutoff
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Bump the priority as part of the aging rule and flag the task as escalated
+    pub async fn escalate_priority(
+        pool: &SqlitePool,
+        id: Uuid,
+        priority: TaskPriority,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET priority = $2, priority_escalated_at = datetime('now', 'subsec'), updated_at = datetime('now', 'subsec') WHERE id = $1",
+            id,
+            priority
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Update the parent_workspace_id field for a task
     pub async fn update_parent_workspace_id(
         pool: &SqlitePool,
@@ -320,6 +541,128 @@ ORDER BY t.created_at DESC"#,
         Ok(result.rows_affected())
     }
 
+    /// Merge `duplicate` into `canonical` and delete it.
+    ///
+    /// Attempts (workspaces, and with them child-task links), images, linked
+    /// documents, package scope and WIP override history move to the canonical
+    /// task, the duplicate's description is appended and the higher priority is
+    /// kept. The duplicate's task list item link moves too, unless the canonical
+    /// task has one of its own. A redirect is left so the duplicate's id keeps
+    /// resolving to the canonical task.
+    pub async fn merge_into(
+        pool: &SqlitePool,
+        duplicate: &Task,
+        canonical: &Task,
+    ) -> Result<Self, sqlx::Error> {
+        let description = match (
+            canonical
+                .description
+                .as_deref()
+                .map(str::trim)
+                .filter(|d| !d.is_empty()),
+            duplicate
+                .description
+                .as_deref()
+                .map(str::trim)
+                .filter(|d| !d.is_empty()),
+        ) {
+            (Some(existing), Some(merged)) => Some(format!(
+                "{existing}\n\n---\nMerged from \"{}\":\n\n{merged}",
+                duplicate.title
+            )),
+            (None, Some(merged)) => {
+                Some(format!("Merged from \"{}\":\n\n{merged}", duplicate.title))
+            }
+            (existing, None) => existing.map(str::to_string),
+        };
+        let priority = canonical.priority.max(duplicate.priority);
+
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            "UPDATE workspaces SET task_id = $2, updated_at = datetime('now', 'subsec') WHERE task_id = $1",
+            duplicate.id,
+            canonical.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"INSERT OR IGNORE INTO task_images (id, task_id, image_id)
+               SELECT randomblob(16), $2, image_id FROM task_images WHERE task_id = $1"#,
+            duplicate.id,
+            canonical.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"INSERT OR IGNORE INTO task_documents (id, task_id, repo_id, relative_path, created_at)
+               SELECT randomblob(16), $2, repo_id, relative_path, created_at FROM task_documents WHERE task_id = $1"#,
+            duplicate.id,
+            canonical.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        // A task has at most one task list item, so the canonical task keeps its own
+        sqlx::query!(
+            r#"UPDATE OR IGNORE document_task_links
+               SET task_id = $2, updated_at = datetime('now', 'subsec')
+               WHERE task_id = $1"#,
+            duplicate.id,
+            canonical.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            r#"INSERT OR IGNORE INTO task_package_scopes (task_id, repo_id, package_path, package_name)
+               SELECT $2, repo_id, package_path, package_name FROM task_package_scopes WHERE task_id = $1"#,
+            duplicate.id,
+            canonical.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE wip_limit_overrides SET task_id = $2 WHERE task_id = $1",
+            duplicate.id,
+            canonical.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Earlier merges into the duplicate now point at the canonical task
+        sqlx::query!(
+            "UPDATE task_redirects SET to_task_id = $2 WHERE to_task_id = $1",
+            duplicate.id,
+            canonical.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "INSERT INTO task_redirects (from_task_id, to_task_id) VALUES ($1, $2)",
+            duplicate.id,
+            canonical.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        Self::delete(&mut *tx, duplicate.id).await?;
+
+        let task = sqlx::query_as!(
+            Task,
+            r#"UPDATE tasks
+               SET description = $2, priority = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", priority as "priority!: TaskPriority", priority_escalated_at as "priority_escalated_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            canonical.id,
+            description,
+            priority
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(task)
+    }
+
     pub async fn find_children_by_workspace_id(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -327,7 +670,7 @@ ORDER BY t.created_at DESC"#,
         // Find only child tasks that have this workspace as their parent
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_workspace_id as "parent_workspace_id: Uuid", priority as "priority!: TaskPriority", priority_escalated_at as "priority_escalated_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks
                WHERE parent_workspace_id = $1
                ORDER BY created_at DESC"#,
@@ -371,3 +714,190 @@ ORDER BY t.created_at DESC"#,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::{
+        models::{
+            document_task_link::DocumentTaskLink, project::CreateProject, repo::Repo,
+            task_document::TaskDocument,
+        },
+        test_pool,
+    };
+
+    async fn create_project(pool: &SqlitePool) -> Uuid {
+        let project_id = Uuid::new_v4();
+        let project = CreateProject {
+            name: project_id.to_string(),
+            repositories: Vec::new(),
+        };
+        Project::create(pool, &project, project_id).await.unwrap();
+        project_id
+    }
+
+    async fn add_task(pool: &SqlitePool, project_id: Uuid, title: &str) -> Task {
+        let data = CreateTask::from_title_description(project_id, title.to_string(), None);
+        Task::create(pool, &data, Uuid::new_v4()).await.unwrap()
+    }
+
+    async fn create_task(pool: &SqlitePool) -> Task {
+        let project_id = create_project(pool).await;
+        add_task(pool, project_id, "Old task").await
+    }
+
+    /// Pretend every timestamp of the task was recorded `days` ago
+    async fn backdate(pool: &SqlitePool, id: Uuid, days: i64) {
+        let offset = format!("-{days} days");
+        sqlx::query(
+            "UPDATE tasks
+             SET created_at = datetime(created_at, $2),
+                 todo_since = datetime(todo_since, $2),
+                 priority_escalated_at = datetime(priority_escalated_at, $2),
+                 priority_set_at = datetime(priority_set_at, $2)
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(offset)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn aging_ids(pool: &SqlitePool, days: i64) -> Vec<Uuid> {
+        Task::find_aging_todo(pool, Utc::now() - Duration::days(days))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|task| task.id)
+            .collect()
+    }
+
+    #[test]
+    fn priorities_escalate_one_level_up_to_urgent() {
+        assert_eq!(TaskPriority::Low.escalated(), Some(TaskPriority::Medium));
+        assert_eq!(TaskPriority::Medium.escalated(), Some(TaskPriority::High));
+        assert_eq!(TaskPriority::High.escalated(), Some(TaskPriority::Urgent));
+        assert_eq!(TaskPriority::Urgent.escalated(), None);
+    }
+
+    #[tokio::test]
+    async fn aging_waits_a_full_period_after_each_priority_change() {
+        let pool = test_pool().await;
+        let task = create_task(&pool).await;
+        assert!(aging_ids(&pool, 3).await.is_empty());
+
+        backdate(&pool, task.id, 10).await;
+        assert_eq!(aging_ids(&pool, 3).await, vec![task.id]);
+
+        // A manual change restarts the wait instead of falling back to the creation time
+        Task::update_priority(&pool, task.id, TaskPriority::Low)
+            .await
+            .unwrap();
+        assert!(aging_ids(&pool, 3).await.is_empty());
+        backdate(&pool, task.id, 10).await;
+        assert_eq!(aging_ids(&pool, 3).await, vec![task.id]);
+
+        Task::escalate_priority(&pool, task.id, TaskPriority::Medium)
+            .await
+            .unwrap();
+        assert!(aging_ids(&pool, 3).await.is_empty());
+        backdate(&pool, task.id, 10).await;
+        assert_eq!(aging_ids(&pool, 3).await, vec![task.id]);
+
+        Task::update_priority(&pool, task.id, TaskPriority::Urgent)
+            .await
+            .unwrap();
+        backdate(&pool, task.id, 10).await;
+        assert!(aging_ids(&pool, 3).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn aging_counts_only_the_time_spent_in_todo() {
+        let pool = test_pool().await;
+        let task = create_task(&pool).await;
+        Task::update_status(&pool, task.id, TaskStatus::InProgress)
+            .await
+            .unwrap();
+        backdate(&pool, task.id, 10).await;
+        assert!(aging_ids(&pool, 3).await.is_empty());
+
+        Task::update_status(&pool, task.id, TaskStatus::Todo)
+            .await
+            .unwrap();
+        assert!(aging_ids(&pool, 3).await.is_empty());
+
+        // Saving a task that is already in Todo keeps the time it entered Todo
+        backdate(&pool, task.id, 10).await;
+        Task::update_status(&pool, task.id, TaskStatus::Todo)
+            .await
+            .unwrap();
+        assert_eq!(aging_ids(&pool, 3).await, vec![task.id]);
+    }
+
+    #[tokio::test]
+    async fn merging_keeps_the_duplicates_documents() {
+        let pool = test_pool().await;
+        let project_id = create_project(&pool).await;
+        let canonical = add_task(&pool, project_id, "Canonical").await;
+        let duplicate = add_task(&pool, project_id, "Duplicate").await;
+        let repo = Repo::find_or_create(&pool, std::path::Path::new("/tmp/docs"), "docs")
+            .await
+            .unwrap();
+        TaskDocument::create(&pool, canonical.id, repo.id, "shared.md")
+            .await
+            .unwrap();
+        TaskDocument::create(&pool, duplicate.id, repo.id, "shared.md")
+            .await
+            .unwrap();
+        TaskDocument::create(&pool, duplicate.id, repo.id, "design.md")
+            .await
+            .unwrap();
+
+        Task::merge_into(&pool, &duplicate, &canonical)
+            .await
+            .unwrap();
+
+        let mut paths: Vec<String> = TaskDocument::find_by_task_id(&pool, canonical.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|document| document.relative_path)
+            .collect();
+        paths.sort();
+        assert_eq!(paths, vec!["design.md", "shared.md"]);
+        assert!(
+            TaskDocument::find_by_task_id(&pool, duplicate.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn merging_moves_the_task_list_item_link() {
+        let pool = test_pool().await;
+        let project_id = create_project(&pool).await;
+        let canonical = add_task(&pool, project_id, "Canonical").await;
+        let duplicate = add_task(&pool, project_id, "Duplicate").await;
+        let repo = Repo::find_or_create(&pool, std::path::Path::new("/tmp/plans"), "plans")
+            .await
+            .unwrap();
+        DocumentTaskLink::upsert(&pool, duplicate.id, project_id, repo.id, "plan.md", true)
+            .await
+            .unwrap();
+
+        Task::merge_into(&pool, &duplicate, &canonical)
+            .await
+            .unwrap();
+
+        let link = DocumentTaskLink::find_by_task_id(&pool, canonical.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(link.relative_path, "plan.md");
+        assert!(link.checked);
+    }
+}
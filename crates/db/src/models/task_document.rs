@@ -0,0 +1,156 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A document related to a task, e.g. a design doc the task implements
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskDocument {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub repo_id: Uuid,
+    pub relative_path: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskDocument {
+    /// Relate a document to a task; relating it again returns the existing relation
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        repo_id: Uuid,
+        relative_path: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            TaskDocument,
+            r#"INSERT INTO task_documents (id, task_id, repo_id, relative_path)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(task_id, repo_id, relative_path) DO UPDATE
+               SET relative_path = excluded.relative_path
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         relative_path,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task_id,
+            repo_id,
+            relative_path
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskDocument,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_documents
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Documents related to a task, in the order they were related
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskDocument,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_documents
+               WHERE task_id = $1
+               ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Relations of the tasks a document is related to
+    pub async fn find_by_document(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskDocument,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      relative_path,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_documents
+               WHERE repo_id = $1 AND relative_path = $2
+               ORDER BY created_at ASC"#,
+            repo_id,
+            relative_path
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Follow a document moved from `from` to `to`. Tasks already related to `to` keep
+    /// that one relation.
+    pub async fn move_document(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        from: &str,
+        to: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            r#"UPDATE OR IGNORE task_documents
+               SET relative_path = $3
+               WHERE repo_id = $1 AND relative_path = $2"#,
+            repo_id,
+            from,
+            to
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM task_documents WHERE repo_id = $1 AND relative_path = $2",
+            repo_id,
+            from
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    pub async fn delete_by_document(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        relative_path: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM task_documents WHERE repo_id = $1 AND relative_path = $2",
+            repo_id,
+            relative_path
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_documents WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
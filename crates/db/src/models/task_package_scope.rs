@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Restricts a task to one package of a monorepo repository
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskPackageScope {
+    pub task_id: Uuid,
+    pub repo_id: Uuid,
+    /// Package directory relative to the repository root
+    pub package_path: String,
+    pub package_name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetTaskPackageScope {
+    pub repo_id: Uuid,
+    pub package_path: String,
+}
+
+impl TaskPackageScope {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskPackageScope,
+            r#"SELECT task_id      as "task_id!: Uuid",
+                      repo_id      as "repo_id!: Uuid",
+                      package_path,
+                      package_name,
+                      created_at   as "created_at!: DateTime<Utc>",
+                      updated_at   as "updated_at!: DateTime<Utc>"
+               FROM task_package_scopes
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskPackageScope,
+            r#"SELECT s.task_id      as "task_id!: Uuid",
+                      s.repo_id      as "repo_id!: Uuid",
+                      s.package_path,
+                      s.package_name,
+                      s.created_at   as "created_at!: DateTime<Utc>",
+                      s.updated_at   as "updated_at!: DateTime<Utc>"
+               FROM task_package_scopes s
+               JOIN tasks t ON t.id = s.task_id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Upsert the scope for a task - a task has at most one package scope.
+    pub async fn upsert(
+        executor: impl Executor<'_, Database = Sqlite>,
+        task_id: Uuid,
+        repo_id: Uuid,
+        package_path: &str,
+        package_name: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskPackageScope,
+            r#"INSERT INTO task_package_scopes (task_id, repo_id, package_path, package_name)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT(task_id) DO UPDATE SET
+                   repo_id = excluded.repo_id,
+                   package_path = excluded.package_path,
+                   package_name = excluded.package_name,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING task_id      as "task_id!: Uuid",
+                         repo_id      as "repo_id!: Uuid",
+                         package_path,
+                         package_name,
+                         created_at   as "created_at!: DateTime<Utc>",
+                         updated_at   as "updated_at!: DateTime<Utc>""#,
+            task_id,
+            repo_id,
+            package_path,
+            package_name
+        )
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, task_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM task_package_scopes WHERE task_id = $1",
+            task_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
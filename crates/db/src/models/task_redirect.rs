@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Tombstone for a task that was merged into another task
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskRedirect {
+    pub from_task_id: Uuid,
+    pub to_task_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskRedirect {
+    pub async fn find_by_from_task_id(
+        pool: &SqlitePool,
+        from_task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskRedirect,
+            r#"SELECT from_task_id as "from_task_id!: Uuid",
+                      to_task_id   as "to_task_id!: Uuid",
+                      created_at   as "created_at!: DateTime<Utc>"
+               FROM task_redirects
+               WHERE from_task_id = $1"#,
+            from_task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TestResult {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub workspace_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub test_name: String,
+    pub passed: bool,
+    pub code_fingerprint: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateTestResult {
+    pub test_name: String,
+    pub passed: bool,
+}
+
+impl TestResult {
+    /// All recorded results for a project, oldest first
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TestResult,
+            r#"SELECT id                   as "id!: Uuid",
+                      project_id           as "project_id!: Uuid",
+                      workspace_id         as "workspace_id!: Uuid",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      test_name,
+                      passed               as "passed!: bool",
+                      code_fingerprint,
+                      created_at           as "created_at!: DateTime<Utc>"
+               FROM test_results
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn create_many(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        workspace_id: Uuid,
+        execution_process_id: Uuid,
+        code_fingerprint: &str,
+        results: &[CreateTestResult],
+    ) -> Result<u64, sqlx::Error> {
+        if results.is_empty() {
+            return Ok(0);
+        }
+
+        let mut tx = pool.begin().await?;
+        for result in results {
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO test_results (id, project_id, workspace_id, execution_process_id, test_name, passed, code_fingerprint)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+                id,
+                project_id,
+                workspace_id,
+                execution_process_id,
+                result.test_name,
+                result.passed,
+                code_fingerprint
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(results.len() as u64)
+    }
+}
@@ -0,0 +1,407 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::TaskStatus;
+
+/// Whether a limit applies to one project's column or to the user's tasks across projects
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WipLimitScope {
+    Project,
+    User,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WipLimit {
+    pub id: Uuid,
+    /// `None` for user-wide limits
+    pub project_id: Option<Uuid>,
+    pub status: TaskStatus,
+    pub max_tasks: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetWipLimit {
+    pub status: TaskStatus,
+    /// `None` removes the limit
+    pub max_tasks: Option<i64>,
+}
+
+/// Returned instead of moving a task when the target column is full
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WipLimitViolation {
+    pub scope: WipLimitScope,
+    pub status: TaskStatus,
+    pub max_tasks: i64,
+    pub current_count: i64,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WipLimitOverride {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub from_status: TaskStatus,
+    pub to_status: TaskStatus,
+    pub scope: WipLimitScope,
+    pub max_tasks: i64,
+    pub current_count: i64,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl WipLimit {
+    /// Project limits plus user-wide limits that apply to the project
+    pub async fn find_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WipLimit,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      status as "status!: TaskStatus",
+                      max_tasks,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM wip_limits
+               WHERE project_id = $1 OR project_id IS NULL
+               ORDER BY project_id IS NULL, status"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_user_limits(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WipLimit,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      status as "status!: TaskStatus",
+                      max_tasks,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM wip_limits
+               WHERE project_id IS NULL
+               ORDER BY status"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Set (or clear, when `max_tasks` is `None`) a limit. `project_id` of `None`
+    /// targets the user-wide limit for the status.
+    pub async fn set(
+        pool: &SqlitePool,
+        project_id: Option<Uuid>,
+        data: &SetWipLimit,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM wip_limits WHERE project_id IS $1 AND status = $2",
+            project_id,
+            data.status
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let Some(max_tasks) = data.max_tasks else {
+            tx.commit().await?;
+            return Ok(None);
+        };
+
+        let id = Uuid::new_v4();
+        let limit = sqlx::query_as!(
+            WipLimit,
+            r#"INSERT INTO wip_limits (id, project_id, status, max_tasks)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id: Uuid",
+                         status as "status!: TaskStatus",
+                         max_tasks,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            data.status,
+            max_tasks
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(Some(limit))
+    }
+
+    /// Copy the project limits of one project to another. User-wide limits already
+    /// apply to every project.
+    pub async fn copy_to_project<'e, E>(
+        executor: E,
+        from_project_id: Uuid,
+        to_project_id: Uuid,
+    ) -> Result<u64, sqlx::Error>
+    where
+        E: Executor<'e, Database = Sqlite>,
+    {
+        let result = sqlx::query!(
+            r#"INSERT INTO wip_limits (id, project_id, status, max_tasks)
+               SELECT randomblob(16), $2, status, max_tasks
+               FROM wip_limits
+               WHERE project_id = $1"#,
+            from_project_id,
+            to_project_id
+        )
+        .execute(executor)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Check whether moving a task of `project_id` into `status` would exceed a limit.
+    /// Project limits are checked before user-wide limits.
+    pub async fn check_move(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        status: &TaskStatus,
+    ) -> Result<Option<WipLimitViolation>, sqlx::Error> {
+        for limit in Self::find_for_project(pool, project_id).await? {
+            if &limit.status != status {
+                continue;
+            }
+            let (scope, current_count) = match limit.project_id {
+                Some(project_id) => (
+                    WipLimitScope::Project,
+                    sqlx::query_scalar!(
+                        r#"SELECT COUNT(*) as "count!: i64" FROM tasks WHERE project_id = $1 AND status = $2"#,
+                        project_id,
+                        status
+                    )
+                    .fetch_one(pool)
+                    .await?,
+                ),
+                None => (
+                    WipLimitScope::User,
+                    sqlx::query_scalar!(
+                        r#"SELECT COUNT(*) as "count!: i64" FROM tasks WHERE status = $1"#,
+                        status
+                    )
+                    .fetch_one(pool)
+                    .await?,
+                ),
+            };
+            if current_count >= limit.max_tasks {
+                return Ok(Some(WipLimitViolation {
+                    scope,
+                    status: status.clone(),
+                    max_tasks: limit.max_tasks,
+                    current_count,
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl WipLimitOverride {
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        task_id: Uuid,
+        from_status: &TaskStatus,
+        violation: &WipLimitViolation,
+        reason: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            WipLimitOverride,
+            r#"INSERT INTO wip_limit_overrides (id, project_id, task_id, from_status, to_status, scope, max_tasks, current_count, reason)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         from_status as "from_status!: TaskStatus",
+                         to_status as "to_status!: TaskStatus",
+                         scope as "scope!: WipLimitScope",
+                         max_tasks,
+                         current_count,
+                         reason,
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            task_id,
+            from_status,
+            violation.status,
+            violation.scope,
+            violation.max_tasks,
+            violation.current_count,
+            reason
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WipLimitOverride,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      from_status as "from_status!: TaskStatus",
+                      to_status as "to_status!: TaskStatus",
+                      scope as "scope!: WipLimitScope",
+                      max_tasks,
+                      current_count,
+                      reason,
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM wip_limit_overrides
+               WHERE project_id = $1
+               ORDER BY created_at DESC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::{
+            project::{CreateProject, Project},
+            task::{CreateTask, Task},
+        },
+        test_pool,
+    };
+
+    async fn create_project(pool: &SqlitePool) -> Uuid {
+        let id = Uuid::new_v4();
+        let project = CreateProject {
+            name: id.to_string(),
+            repositories: Vec::new(),
+        };
+        Project::create(pool, &project, id).await.unwrap();
+        id
+    }
+
+    async fn create_task(pool: &SqlitePool, project_id: Uuid, status: TaskStatus) {
+        let mut data = CreateTask::from_title_description(project_id, "Task".to_string(), None);
+        data.status = Some(status);
+        Task::create(pool, &data, Uuid::new_v4()).await.unwrap();
+    }
+
+    async fn set_limit(pool: &SqlitePool, project_id: Option<Uuid>, status: TaskStatus, max: i64) {
+        let data = SetWipLimit {
+            status,
+            max_tasks: Some(max),
+        };
+        WipLimit::set(pool, project_id, &data).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn project_limits_count_only_their_project() {
+        let pool = test_pool().await;
+        let project = create_project(&pool).await;
+        let other = create_project(&pool).await;
+        set_limit(&pool, Some(project), TaskStatus::InProgress, 1).await;
+        assert!(
+            WipLimit::check_move(&pool, project, &TaskStatus::InProgress)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        create_task(&pool, project, TaskStatus::InProgress).await;
+        create_task(&pool, other, TaskStatus::InProgress).await;
+        let violation = WipLimit::check_move(&pool, project, &TaskStatus::InProgress)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(violation.scope, WipLimitScope::Project);
+        assert_eq!(violation.max_tasks, 1);
+        assert_eq!(violation.current_count, 1);
+        assert!(
+            WipLimit::check_move(&pool, project, &TaskStatus::InReview)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            WipLimit::check_move(&pool, other, &TaskStatus::InProgress)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn user_limits_count_every_project() {
+        let pool = test_pool().await;
+        let project = create_project(&pool).await;
+        let other = create_project(&pool).await;
+        set_limit(&pool, None, TaskStatus::InReview, 2).await;
+        create_task(&pool, project, TaskStatus::InReview).await;
+        assert!(
+            WipLimit::check_move(&pool, other, &TaskStatus::InReview)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        create_task(&pool, other, TaskStatus::InReview).await;
+        let violation = WipLimit::check_move(&pool, other, &TaskStatus::InReview)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(violation.scope, WipLimitScope::User);
+        assert_eq!(violation.current_count, 2);
+
+        // The project's own limit is reported before the user-wide one
+        set_limit(&pool, Some(project), TaskStatus::InReview, 1).await;
+        let violation = WipLimit::check_move(&pool, project, &TaskStatus::InReview)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(violation.scope, WipLimitScope::Project);
+        assert_eq!(violation.current_count, 1);
+    }
+
+    #[tokio::test]
+    async fn copying_takes_only_project_limits() {
+        let pool = test_pool().await;
+        let source = create_project(&pool).await;
+        let target = create_project(&pool).await;
+        set_limit(&pool, Some(source), TaskStatus::InProgress, 2).await;
+        set_limit(&pool, None, TaskStatus::InReview, 3).await;
+
+        let mut tx = pool.begin().await.unwrap();
+        WipLimit::copy_to_project(&mut *tx, source, target)
+            .await
+            .unwrap();
+        tx.rollback().await.unwrap();
+        let limits = WipLimit::find_for_project(&pool, target).await.unwrap();
+        assert!(limits.iter().all(|limit| limit.project_id.is_none()));
+
+        let copied = WipLimit::copy_to_project(&pool, source, target)
+            .await
+            .unwrap();
+        assert_eq!(copied, 1);
+        let limits = WipLimit::find_for_project(&pool, target).await.unwrap();
+        let own: Vec<_> = limits
+            .iter()
+            .filter(|limit| limit.project_id == Some(target))
+            .collect();
+        assert_eq!(own.len(), 1);
+        assert_eq!(own[0].status, TaskStatus::InProgress);
+        assert_eq!(own[0].max_tasks, 2);
+    }
+}
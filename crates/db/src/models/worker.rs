@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A machine registered to run coding agents for this server
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Worker {
+    pub id: Uuid,
+    pub name: String,
+    /// Hex SHA-256 of the worker's token
+    #[serde(skip)]
+    #[ts(skip)]
+    pub token_hash: String,
+    /// Capabilities from the worker's last heartbeat, e.g. `docker` or `gpu`
+    #[ts(type = "Array<string>")]
+    pub capabilities: Json<Vec<String>>,
+    /// Whether the worker only finishes what it runs and gets no new executions
+    pub draining: bool,
+    /// When the worker last connected or sent a heartbeat
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Worker {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Worker,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      token_hash,
+                      capabilities as "capabilities!: Json<Vec<String>>",
+                      draining as "draining!: bool",
+                      last_seen_at as "last_seen_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workers
+               ORDER BY created_at"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Worker,
+            r#"SELECT id as "id!: Uuid",
+                      name,
+                      token_hash,
+                      capabilities as "capabilities!: Json<Vec<String>>",
+                      draining as "draining!: bool",
+                      last_seen_at as "last_seen_at: DateTime<Utc>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM workers
+               WHERE token_hash = $1"#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        name: &str,
+        token_hash: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            Worker,
+            r#"INSERT INTO workers (id, name, token_hash)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid",
+                         name,
+                         token_hash,
+                         capabilities as "capabilities!: Json<Vec<String>>",
+                         draining as "draining!: bool",
+                         last_seen_at as "last_seen_at: DateTime<Utc>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            name,
+            token_hash
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn touch(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workers SET last_seen_at = datetime('now', 'subsec') WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a heartbeat with the capabilities the worker reported
+    pub async fn heartbeat(
+        pool: &SqlitePool,
+        id: Uuid,
+        capabilities: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let capabilities = Json(capabilities);
+        sqlx::query!(
+            r#"UPDATE workers
+               SET capabilities = $2, last_seen_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            capabilities
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_draining(
+        pool: &SqlitePool,
+        id: Uuid,
+        draining: bool,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE workers SET draining = $2 WHERE id = $1",
+            id,
+            draining
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM workers WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
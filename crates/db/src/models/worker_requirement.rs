@@ -0,0 +1,138 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Capabilities a worker needs to run the coding agents of a task, or of an executor in
+/// any task. Exactly one of `task_id` and `executor` is set.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkerRequirement {
+    pub id: Uuid,
+    pub task_id: Option<Uuid>,
+    /// Executor name, e.g. `CLAUDE_CODE`
+    pub executor: Option<String>,
+    #[ts(type = "Array<string>")]
+    pub capabilities: Json<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Capabilities to require of the workers running a task's or an executor's agents;
+/// none removes the requirement
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetWorkerRequirement {
+    #[serde(default)]
+    #[ts(optional)]
+    pub task_id: Option<Uuid>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub executor: Option<String>,
+    pub capabilities: Vec<String>,
+}
+
+impl WorkerRequirement {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkerRequirement,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id: Uuid",
+                      executor,
+                      capabilities as "capabilities!: Json<Vec<String>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM worker_requirements
+               ORDER BY executor, created_at"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Capabilities required to run `executor` in `task_id`: those of the task and those
+    /// of the executor, sorted and without duplicates
+    pub async fn required_capabilities(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        executor: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query_scalar!(
+            r#"SELECT capabilities as "capabilities!: Json<Vec<String>>"
+               FROM worker_requirements
+               WHERE task_id = $1 OR executor = $2"#,
+            task_id,
+            executor
+        )
+        .fetch_all(pool)
+        .await?;
+        let mut capabilities: Vec<String> = rows.into_iter().flat_map(|row| row.0).collect();
+        capabilities.sort();
+        capabilities.dedup();
+        Ok(capabilities)
+    }
+
+    /// Require `capabilities` for the task or executor of `data`, replacing what was
+    /// required before. Returns `None` if the requirement was removed.
+    pub async fn set(
+        pool: &SqlitePool,
+        data: &SetWorkerRequirement,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        if data.capabilities.is_empty() {
+            sqlx::query!(
+                "DELETE FROM worker_requirements WHERE task_id = $1 OR executor = $2",
+                data.task_id,
+                data.executor
+            )
+            .execute(pool)
+            .await?;
+            return Ok(None);
+        }
+
+        let id = Uuid::new_v4();
+        let capabilities = Json(&data.capabilities);
+        let requirement = match (&data.task_id, &data.executor) {
+            (Some(task_id), _) => {
+                sqlx::query_as!(
+                    WorkerRequirement,
+                    r#"INSERT INTO worker_requirements (id, task_id, capabilities)
+                       VALUES ($1, $2, $3)
+                       ON CONFLICT(task_id) DO UPDATE
+                       SET capabilities = excluded.capabilities,
+                           updated_at = datetime('now', 'subsec')
+                       RETURNING id as "id!: Uuid",
+                                 task_id as "task_id: Uuid",
+                                 executor,
+                                 capabilities as "capabilities!: Json<Vec<String>>",
+                                 created_at as "created_at!: DateTime<Utc>",
+                                 updated_at as "updated_at!: DateTime<Utc>""#,
+                    id,
+                    task_id,
+                    capabilities
+                )
+                .fetch_one(pool)
+                .await?
+            }
+            (None, executor) => {
+                sqlx::query_as!(
+                    WorkerRequirement,
+                    r#"INSERT INTO worker_requirements (id, executor, capabilities)
+                       VALUES ($1, $2, $3)
+                       ON CONFLICT(executor) DO UPDATE
+                       SET capabilities = excluded.capabilities,
+                           updated_at = datetime('now', 'subsec')
+                       RETURNING id as "id!: Uuid",
+                                 task_id as "task_id: Uuid",
+                                 executor,
+                                 capabilities as "capabilities!: Json<Vec<String>>",
+                                 created_at as "created_at!: DateTime<Utc>",
+                                 updated_at as "updated_at!: DateTime<Utc>""#,
+                    id,
+                    executor,
+                    capabilities
+                )
+                .fetch_one(pool)
+                .await?
+            }
+        };
+        Ok(Some(requirement))
+    }
+}
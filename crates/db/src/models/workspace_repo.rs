@@ -120,6 +120,10 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.preview_build_script,
+                      r.preview_output_dir,
+                      r.e2e_script,
+                      r.e2e_results_dir,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -146,6 +150,10 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.preview_build_script,
+                      r.preview_output_dir,
+                      r.e2e_script,
+                      r.e2e_results_dir,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>",
                       wr.target_branch
@@ -171,6 +179,10 @@ impl WorkspaceRepo {
                     copy_files: row.copy_files,
                     parallel_setup_script: row.parallel_setup_script,
                     dev_server_script: row.dev_server_script,
+                    preview_build_script: row.preview_build_script,
+                    preview_output_dir: row.preview_output_dir,
+                    e2e_script: row.e2e_script,
+                    e2e_results_dir: row.e2e_results_dir,
                     created_at: row.created_at,
                     updated_at: row.updated_at,
                 },
@@ -257,6 +269,10 @@ impl WorkspaceRepo {
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
+                      r.preview_build_script,
+                      r.preview_output_dir,
+                      r.e2e_script,
+                      r.e2e_results_dir,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -21,16 +21,20 @@ use services::services::{
     auth::AuthContext,
     config::{Config, ConfigError},
     container::{ContainerError, ContainerService},
+    document_index::DocumentIndex,
     events::{EventError, EventService},
     file_search::FileSearchCache,
     filesystem::{FilesystemError, FilesystemService},
     filesystem_watcher::FilesystemWatcherError,
-    git::{GitService, GitServiceError},
+    git::{GitBackend, GitService, GitServiceError},
     image::{ImageError, ImageService},
     pr_monitor::PrMonitorService,
     project::ProjectService,
     queued_message::QueuedMessageService,
     repo::RepoService,
+    retention::RetentionService,
+    task_aging::TaskAgingService,
+    telemetry::TelemetryService,
     worktree_manager::WorktreeError,
 };
 use sqlx::Error as SqlxError;
@@ -92,6 +96,9 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn git(&self) -> &GitService;
 
+    /// Repository operations used by the document routes
+    fn git_backend(&self) -> &dyn GitBackend;
+
     fn project(&self) -> &ProjectService;
 
     fn repo(&self) -> &RepoService;
@@ -104,12 +111,16 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn file_search_cache(&self) -> &Arc<FileSearchCache>;
 
+    fn document_index(&self) -> &Arc<DocumentIndex>;
+
     fn approvals(&self) -> &Approvals;
 
     fn queued_message_service(&self) -> &QueuedMessageService;
 
     fn auth_context(&self) -> &AuthContext;
 
+    fn telemetry(&self) -> &TelemetryService;
+
     async fn update_sentry_scope(&self) -> Result<(), DeploymentError> {
         let user_id = self.user_id();
         let config = self.config().read().await;
@@ -132,12 +143,26 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         PrMonitorService::spawn(db, analytics).await
     }
 
+    async fn spawn_task_aging_service(&self) -> tokio::task::JoinHandle<()> {
+        TaskAgingService::spawn(self.db().clone(), self.config().clone()).await
+    }
+
+    async fn spawn_retention_service(&self) -> tokio::task::JoinHandle<()> {
+        RetentionService::spawn(self.db().clone(), self.config().clone()).await
+    }
+
+    async fn spawn_telemetry_service(&self) -> tokio::task::JoinHandle<()> {
+        self.telemetry().spawn_flusher(self.config().clone())
+    }
+
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
         let analytics_enabled = self.config().read().await.analytics_enabled;
         // Track events unless user has explicitly opted out
         if analytics_enabled && let Some(analytics) = self.analytics() {
             analytics.track_event(self.user_id(), event_name, Some(properties.clone()));
         }
+        // Queued locally for the preview endpoint, only sent once telemetry is enabled
+        self.telemetry().record(event_name, &properties);
     }
 
     /// Trigger background auto-setup of default projects for new users
@@ -21,6 +21,7 @@ use db::{
         repo::Repo,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
         task::{Task, TaskStatus},
+        worker_requirement::WorkerRequirement,
         workspace::Workspace,
         workspace_repo::WorkspaceRepo,
     },
@@ -46,10 +47,12 @@ use services::services::{
     config::Config,
     container::{ContainerError, ContainerRef, ContainerService},
     diff_stream::{self, DiffStreamHandle},
+    flaky_tests,
     git::{GitCli, GitService},
     image::ImageService,
     notification::NotificationService,
     queued_message::QueuedMessageService,
+    workers::{self, RemoteRun, WorkerPool, WorkerRun},
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
 use tokio::{sync::RwLock, task::JoinHandle};
@@ -76,6 +79,7 @@ pub struct LocalContainerService {
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
     notification_service: NotificationService,
+    workers: WorkerPool,
 }
 
 impl LocalContainerService {
@@ -89,6 +93,7 @@ impl LocalContainerService {
         analytics: Option<AnalyticsContext>,
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
+        workers: WorkerPool,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let interrupt_senders = Arc::new(RwLock::new(HashMap::new()));
@@ -106,6 +111,7 @@ impl LocalContainerService {
             approvals,
             queued_message_service,
             notification_service,
+            workers,
         };
 
         container.spawn_workspace_cleanup();
@@ -229,6 +235,35 @@ impl LocalContainerService {
         }
     }
 
+    /// Parse test results from setup/cleanup script output for flaky-test detection.
+    /// Best-effort: failures are logged and never block process finalization.
+    async fn record_test_results(&self, exec_id: Uuid) {
+        let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, exec_id).await else {
+            return;
+        };
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::SetupScript | ExecutionProcessRunReason::CleanupScript
+        ) {
+            return;
+        }
+        let Some(msg_store) = self.msg_stores.read().await.get(&exec_id).cloned() else {
+            return;
+        };
+
+        let output: String = msg_store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::Stdout(s) | LogMsg::Stderr(s) => Some(s),
+                _ => None,
+            })
+            .collect();
+        if let Err(e) = flaky_tests::record_process_output(&self.db.pool, &ctx, &output).await {
+            tracing::warn!("Failed to record test results for {}: {}", exec_id, e);
+        }
+    }
+
     /// Get the commit message based on the execution run reason.
     async fn get_commit_message(&self, ctx: &ExecutionContext) -> String {
         match ctx.execution_process.run_reason {
@@ -370,6 +405,7 @@ impl LocalContainerService {
         &self,
         exec_id: &Uuid,
         exit_signal: Option<ExecutorExitSignal>,
+        watch_process: bool,
     ) -> JoinHandle<()> {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
@@ -379,7 +415,12 @@ impl LocalContainerService {
         let container = self.clone();
         let analytics = self.analytics.clone();
 
-        let mut process_exit_rx = self.spawn_os_exit_watcher(exec_id);
+        // Executions on a remote worker have no local process, only the exit signal
+        let mut process_exit_future = if watch_process {
+            self.spawn_os_exit_watcher(exec_id).boxed()
+        } else {
+            std::future::pending().boxed()
+        };
 
         tokio::spawn(async move {
             let mut exit_signal_future = exit_signal
@@ -410,7 +451,7 @@ impl LocalContainerService {
                     };
                 }
                 // Process exit
-                exit_status_result = &mut process_exit_rx => {
+                exit_status_result = &mut process_exit_future => {
                     status_result = exit_status_result.unwrap_or_else(|e| Err(std::io::Error::other(e)));
                 }
             }
@@ -572,6 +613,8 @@ impl LocalContainerService {
             // capture the HEAD OID as the definitive "after" state (best-effort).
             container.update_after_head_commits(exec_id).await;
 
+            container.record_test_results(exec_id).await;
+
             // Cleanup msg store
             if let Some(msg_arc) = msg_stores.write().await.remove(&exec_id) {
                 msg_arc.push_finished();
@@ -815,6 +858,82 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Hand `executor_action` to an idle remote worker with every capability in
+    /// `requirements`, with the committed state of every repository of the workspace.
+    /// Returns false if no such worker is free.
+    #[allow(clippy::too_many_arguments)]
+    async fn dispatch_to_worker(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+        repos: &[Repo],
+        current_dir: &Path,
+        env: &ExecutionEnv,
+        requirements: &[String],
+    ) -> Result<bool, ContainerError> {
+        let worktrees: HashMap<String, PathBuf> = repos
+            .iter()
+            .map(|repo| (repo.name.clone(), current_dir.join(&repo.name)))
+            .collect();
+        let msg_store = Arc::new(MsgStore::new());
+        let (exit_tx, exit_rx) = tokio::sync::oneshot::channel();
+        let run = RemoteRun {
+            msg_store: msg_store.clone(),
+            branch: workspace.branch.clone(),
+            worktrees: worktrees.clone(),
+            exit: exit_tx,
+        };
+        let Ok(worker_id) = self
+            .workers
+            .assign(execution_process.id, run, requirements)
+            .await
+        else {
+            return Ok(false);
+        };
+
+        let branch = workspace.branch.clone();
+        let snapshots = tokio::task::spawn_blocking(move || {
+            worktrees
+                .iter()
+                .map(|(name, path)| workers::snapshot_repo(name, path, &branch, false))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .await
+        .map_err(|e| ContainerError::Other(anyhow!(e)))?;
+        let started = match snapshots {
+            Ok(repos) => {
+                self.workers
+                    .start(WorkerRun {
+                        execution_process_id: execution_process.id,
+                        branch: workspace.branch.clone(),
+                        repos,
+                        action: executor_action.clone(),
+                        env: env.vars.clone(),
+                        commit_reminder: env.commit_reminder,
+                    })
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = started {
+            self.workers.release(execution_process.id).await;
+            return Err(ContainerError::Other(anyhow!(e)));
+        }
+        tracing::info!(
+            "Dispatched execution {} to worker {}",
+            execution_process.id,
+            worker_id
+        );
+
+        self.msg_stores
+            .write()
+            .await
+            .insert(execution_process.id, msg_store);
+        let _hn = self.spawn_exit_monitor(&execution_process.id, Some(exit_rx), false);
+        Ok(true)
+    }
+
     /// Start a follow-up execution from a queued message
     async fn start_queued_follow_up(
         &self,
@@ -1158,6 +1277,40 @@ impl ContainerService for LocalContainerService {
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
 
+        // Coding agents run on a remote worker when one is free. Scripts stay local, as
+        // they prepare or serve this machine's worktree.
+        if let Some(executor) = executor_action.base_executor() {
+            let requirements = workers::normalize_capabilities(
+                &WorkerRequirement::required_capabilities(
+                    &self.db.pool,
+                    task.id,
+                    &executor.to_string(),
+                )
+                .await?,
+            );
+            if self
+                .dispatch_to_worker(
+                    workspace,
+                    execution_process,
+                    executor_action,
+                    &repos,
+                    &current_dir,
+                    &env,
+                    &requirements,
+                )
+                .await?
+            {
+                return Ok(());
+            }
+            // This machine isn't known to have any capability, so it can't stand in
+            if !requirements.is_empty() {
+                return Err(ContainerError::Other(anyhow!(
+                    "No idle worker has the capabilities this agent requires: {}",
+                    requirements.join(", ")
+                )));
+            }
+        }
+
         // Create the child and stream, add to execution tracker with timeout
         let mut spawned = tokio::time::timeout(
             Duration::from_secs(30),
@@ -1183,7 +1336,7 @@ impl ContainerService for LocalContainerService {
         }
 
         // Spawn unified exit monitor: watches OS exit and optional executor signal
-        let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
+        let _hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal, true);
 
         Ok(())
     }
@@ -1193,6 +1346,24 @@ impl ContainerService for LocalContainerService {
         execution_process: &ExecutionProcess,
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError> {
+        if self.workers.is_running(execution_process.id).await {
+            let exit_code = (status == ExecutionProcessStatus::Completed).then_some(0);
+            ExecutionProcess::update_completion(
+                &self.db.pool,
+                execution_process.id,
+                status,
+                exit_code,
+            )
+            .await?;
+            // The worker still reports the work done so far, and the exit monitor then
+            // finishes the execution as usual
+            return self
+                .workers
+                .stop(execution_process.id)
+                .await
+                .map_err(|e| ContainerError::Other(anyhow!(e)));
+        }
+
         let child = self
             .get_child_from_store(&execution_process.id)
             .await
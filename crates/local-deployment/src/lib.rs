@@ -10,16 +10,20 @@ use services::services::{
     auth::AuthContext,
     config::{Config, load_config_from_file, save_config_to_file},
     container::ContainerService,
+    document_index::DocumentIndex,
     events::EventService,
     file_search::FileSearchCache,
     filesystem::FilesystemService,
-    git::GitService,
+    git::{GitBackend, GitService},
     image::ImageService,
     oauth_credentials::OAuthCredentials,
+    preview_build::PreviewServers,
     project::ProjectService,
     queued_message::QueuedMessageService,
     remote_client::{RemoteClient, RemoteClientError},
     repo::RepoService,
+    telemetry::TelemetryService,
+    workers::WorkerPool,
     worktree_manager::WorktreeManager,
 };
 use tokio::sync::RwLock;
@@ -31,7 +35,7 @@ use utils::{
 use uuid::Uuid;
 
 use crate::{container::LocalContainerService, pty::PtyService};
-mod command;
+pub mod command;
 pub mod container;
 mod copy;
 pub mod pty;
@@ -44,18 +48,23 @@ pub struct LocalDeployment {
     analytics: Option<AnalyticsService>,
     container: LocalContainerService,
     git: GitService,
+    git_backend: Arc<dyn GitBackend>,
     project: ProjectService,
     repo: RepoService,
     image: ImageService,
     filesystem: FilesystemService,
     events: EventService,
     file_search_cache: Arc<FileSearchCache>,
+    document_index: Arc<DocumentIndex>,
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
     remote_client: Result<RemoteClient, RemoteClientNotConfigured>,
     auth_context: AuthContext,
+    telemetry: TelemetryService,
     oauth_handoffs: Arc<RwLock<HashMap<Uuid, PendingHandoff>>>,
     pty: PtyService,
+    workers: WorkerPool,
+    preview_servers: PreviewServers,
 }
 
 #[derive(Debug, Clone)]
@@ -100,6 +109,12 @@ impl Deployment for LocalDeployment {
         let user_id = generate_user_id();
         let analytics = AnalyticsConfig::new().map(AnalyticsService::new);
         let git = GitService::new();
+        // Document routes go through the backend, which tests can swap for an in-memory one
+        #[cfg(feature = "fake-git")]
+        let git_backend: Arc<dyn GitBackend> =
+            Arc::new(services::services::git::fake::FakeGitService::new());
+        #[cfg(not(feature = "fake-git"))]
+        let git_backend: Arc<dyn GitBackend> = Arc::new(git.clone());
         let project = ProjectService::new();
         let repo = RepoService::new();
         let msg_stores = Arc::new(RwLock::new(HashMap::new()));
@@ -132,6 +147,8 @@ impl Deployment for LocalDeployment {
 
         let approvals = Approvals::new(msg_stores.clone());
         let queued_message_service = QueuedMessageService::new();
+        let workers = WorkerPool::new();
+        let preview_servers = PreviewServers::new();
 
         let oauth_credentials = Arc::new(OAuthCredentials::new(credentials_path()));
         if let Err(e) = oauth_credentials.load().await {
@@ -179,6 +196,7 @@ impl Deployment for LocalDeployment {
             analytics_ctx,
             approvals.clone(),
             queued_message_service.clone(),
+            workers.clone(),
         )
         .await;
 
@@ -186,8 +204,12 @@ impl Deployment for LocalDeployment {
 
         let file_search_cache = Arc::new(FileSearchCache::new());
 
+        let document_index = Arc::new(DocumentIndex::new());
+
         let pty = PtyService::new();
 
+        let telemetry = TelemetryService::new(&user_id);
+
         let deployment = Self {
             config,
             user_id,
@@ -195,18 +217,23 @@ impl Deployment for LocalDeployment {
             analytics,
             container,
             git,
+            git_backend,
             project,
             repo,
             image,
             filesystem,
             events,
             file_search_cache,
+            document_index,
             approvals,
             queued_message_service,
             remote_client,
             auth_context,
+            telemetry,
             oauth_handoffs,
             pty,
+            workers,
+            preview_servers,
         };
 
         Ok(deployment)
@@ -236,6 +263,10 @@ impl Deployment for LocalDeployment {
         &self.git
     }
 
+    fn git_backend(&self) -> &dyn GitBackend {
+        self.git_backend.as_ref()
+    }
+
     fn project(&self) -> &ProjectService {
         &self.project
     }
@@ -260,6 +291,10 @@ impl Deployment for LocalDeployment {
         &self.file_search_cache
     }
 
+    fn document_index(&self) -> &Arc<DocumentIndex> {
+        &self.document_index
+    }
+
     fn approvals(&self) -> &Approvals {
         &self.approvals
     }
@@ -271,6 +306,10 @@ impl Deployment for LocalDeployment {
     fn auth_context(&self) -> &AuthContext {
         &self.auth_context
     }
+
+    fn telemetry(&self) -> &TelemetryService {
+        &self.telemetry
+    }
 }
 
 impl LocalDeployment {
@@ -334,4 +373,12 @@ impl LocalDeployment {
     pub fn pty(&self) -> &PtyService {
         &self.pty
     }
+
+    pub fn workers(&self) -> &WorkerPool {
+        &self.workers
+    }
+
+    pub fn preview_servers(&self) -> &PreviewServers {
+        &self.preview_servers
+    }
 }
@@ -0,0 +1,6 @@
+ALTER TABLE auth_sessions ADD COLUMN IF NOT EXISTS user_agent TEXT;
+ALTER TABLE auth_sessions ADD COLUMN IF NOT EXISTS ip_address TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_auth_sessions_user_active
+    ON auth_sessions (user_id)
+    WHERE revoked_at IS NULL;
@@ -0,0 +1,36 @@
+-- Users provisioned through SCIM. Deactivated users lose their organization
+-- membership and cannot sign in until reactivated.
+CREATE TABLE IF NOT EXISTS scim_users (
+    user_id     UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    external_id TEXT,
+    active      BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS scim_groups (
+    id           UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    display_name TEXT NOT NULL UNIQUE,
+    external_id  TEXT,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS scim_group_members (
+    group_id UUID NOT NULL REFERENCES scim_groups(id) ON DELETE CASCADE,
+    user_id  UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_scim_group_members_user
+    ON scim_group_members (user_id);
+
+CREATE TRIGGER trg_scim_users_updated_at
+    BEFORE UPDATE ON scim_users
+    FOR EACH ROW
+    EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER trg_scim_groups_updated_at
+    BEFORE UPDATE ON scim_groups
+    FOR EACH ROW
+    EXECUTE FUNCTION set_updated_at();
@@ -27,6 +27,7 @@ use crate::{
         },
         oauth_accounts::{OAuthAccountError, OAuthAccountInsert, OAuthAccountRepository},
         organizations::OrganizationRepository,
+        scim::{ScimDbError, ScimRepository},
         users::{UpsertUser, UserRepository},
     },
 };
@@ -90,6 +91,13 @@ pub enum CallbackResult {
     },
 }
 
+/// Browser details captured when the user completes sign-in
+#[derive(Debug, Clone, Default)]
+pub struct SessionDevice {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct RedeemResponse {
     pub access_token: String,
@@ -216,6 +224,7 @@ impl OAuthHandoffService {
         state: Option<&str>,
         code: Option<&str>,
         error: Option<&str>,
+        device: &SessionDevice,
     ) -> Result<CallbackResult, HandoffError> {
         let provider = self
             .providers
@@ -270,6 +279,20 @@ impl OAuthHandoffService {
 
         let user = self.upsert_identity(&provider, &user_profile).await?;
 
+        if ScimRepository::new(&self.pool)
+            .is_deactivated(user.id)
+            .await
+            .map_err(scim_error)?
+        {
+            repo.set_status(record.id, AuthorizationStatus::Error, Some("deprovisioned"))
+                .await?;
+            return Ok(CallbackResult::Error {
+                handoff_id: Some(record.id),
+                return_to: Some(record.return_to.clone()),
+                error: "deprovisioned".into(),
+            });
+        }
+
         let provider_token_details = crate::auth::ProviderTokenDetails {
             provider: provider.name().to_string(),
             access_token: grant.access_token.expose_secret().to_string(),
@@ -282,6 +305,13 @@ impl OAuthHandoffService {
 
         let session_repo = AuthSessionRepository::new(&self.pool);
         let session_record = session_repo.create(user.id, None).await?;
+        session_repo
+            .set_device(
+                session_record.id,
+                device.user_agent.as_deref(),
+                device.ip_address.as_deref(),
+            )
+            .await?;
 
         let app_code = generate_app_code();
         let app_code_hash = hash_sha256_hex(&app_code);
@@ -437,9 +467,14 @@ impl OAuthHandoffService {
             .get_by_provider_user(provider.name(), &profile.id)
             .await?;
 
+        // The first sign-in of a user provisioned through SCIM links to the provisioned account
         let user_id = match existing_account {
             Some(account) => account.user_id,
-            None => Uuid::new_v4(),
+            None => ScimRepository::new(&self.pool)
+                .find_user_id_by_email(&email)
+                .await
+                .map_err(scim_error)?
+                .unwrap_or_else(Uuid::new_v4),
         };
 
         let (first_name, last_name) = split_name(profile.name.as_deref());
@@ -476,6 +511,13 @@ impl OAuthHandoffService {
 
 type IdentityUser = crate::db::users::User;
 
+fn scim_error(error: ScimDbError) -> HandoffError {
+    match error {
+        ScimDbError::Database(error) => HandoffError::Database(error),
+        other => HandoffError::Failed(other.to_string()),
+    }
+}
+
 fn is_expired(record: &OAuthHandoff) -> bool {
     record.expires_at <= Utc::now()
 }
@@ -2,9 +2,10 @@ mod handoff;
 mod jwt;
 mod middleware;
 mod oauth_token_validator;
+pub mod policy;
 mod provider;
 
-pub use handoff::{CallbackResult, HandoffError, OAuthHandoffService};
+pub use handoff::{CallbackResult, HandoffError, OAuthHandoffService, SessionDevice};
 pub use jwt::{JwtError, JwtService};
 pub use middleware::{RequestContext, require_session};
 pub use oauth_token_validator::{OAuthTokenValidationError, OAuthTokenValidator};
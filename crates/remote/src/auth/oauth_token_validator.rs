@@ -9,7 +9,7 @@ use crate::{
         provider::{ProviderRegistry, TokenValidationError, VALIDATE_TOKEN_MAX_RETRIES},
     },
     db::{
-        auth::AuthSessionRepository,
+        auth::{AuthSessionRepository, TOKEN_REUSE_REASON},
         oauth_accounts::{OAuthAccountError, OAuthAccountRepository},
     },
 };
@@ -58,7 +58,10 @@ impl OAuthTokenValidator {
                     | OAuthTokenValidationError::ProviderTokenValidationFailed
                     | OAuthTokenValidationError::FetchAccountsFailed(_) => {
                         let session_repo = AuthSessionRepository::new(&self.pool);
-                        if let Err(e) = session_repo.revoke_all_user_sessions(user_id).await {
+                        if let Err(e) = session_repo
+                            .revoke_all_user_sessions(user_id, TOKEN_REUSE_REASON)
+                            .await
+                        {
                             warn!(
                                 user_id = %user_id,
                                 error = %e,
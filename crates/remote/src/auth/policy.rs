@@ -0,0 +1,201 @@
+//! Authorization policy shared by every protected route.
+//!
+//! A request is authorized by resolving the organization that owns the
+//! resource, looking up the caller's role in it and checking that role against
+//! the requested action. Routes either apply [`require`] as a route layer or
+//! call [`authorize`] once they have loaded the resource themselves.
+
+use std::collections::HashMap;
+
+use axum::{
+    body::Body,
+    extract::{Extension, Path, State},
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx::PgPool;
+use utils::api::organizations::MemberRole;
+pub use utils::api::organizations::{PermissionAction as Action, PermissionResource as Resource};
+use uuid::Uuid;
+
+use super::RequestContext;
+use crate::{
+    AppState,
+    db::{
+        issues::IssueRepository, organizations::OrganizationRepository, projects::ProjectRepository,
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PolicyError {
+    #[error("{0:?} not found")]
+    NotFound(Resource),
+    #[error("permission denied")]
+    Denied,
+    #[error("database error: {0}")]
+    Database(String),
+}
+
+/// Outcome of evaluating the policy for one user, resource and action
+#[derive(Debug, Clone, Copy)]
+pub struct Decision {
+    pub allowed: bool,
+    pub organization_id: Uuid,
+    pub role: Option<MemberRole>,
+}
+
+/// The role matrix: members read and write, admins may also manage
+pub fn allows(role: Option<MemberRole>, action: Action) -> bool {
+    match (role, action) {
+        (None, _) => false,
+        (Some(MemberRole::Admin), _) => true,
+        (Some(MemberRole::Member), Action::Read | Action::Write) => true,
+        (Some(MemberRole::Member), Action::Manage) => false,
+    }
+}
+
+async fn owning_organization(
+    pool: &PgPool,
+    resource: Resource,
+    id: Uuid,
+) -> Result<Option<Uuid>, PolicyError> {
+    match resource {
+        Resource::Organization => Ok(Some(id)),
+        Resource::Project => ProjectRepository::organization_id(pool, id)
+            .await
+            .map_err(|error| PolicyError::Database(error.to_string())),
+        Resource::Issue => IssueRepository::organization_id(pool, id)
+            .await
+            .map_err(|error| PolicyError::Database(error.to_string())),
+    }
+}
+
+pub async fn evaluate(
+    pool: &PgPool,
+    user_id: Uuid,
+    resource: Resource,
+    id: Uuid,
+    action: Action,
+) -> Result<Decision, PolicyError> {
+    let organization_id = owning_organization(pool, resource, id)
+        .await?
+        .ok_or(PolicyError::NotFound(resource))?;
+
+    let role = OrganizationRepository::new(pool)
+        .check_user_role(organization_id, user_id)
+        .await
+        .map_err(|error| PolicyError::Database(error.to_string()))?;
+
+    Ok(Decision {
+        allowed: allows(role, action),
+        organization_id,
+        role,
+    })
+}
+
+/// Evaluate the policy and fail unless the action is allowed.
+/// Returns the id of the organization owning the resource.
+pub async fn authorize(
+    pool: &PgPool,
+    user_id: Uuid,
+    resource: Resource,
+    id: Uuid,
+    action: Action,
+) -> Result<Uuid, PolicyError> {
+    let decision = evaluate(pool, user_id, resource, id, action).await?;
+    if decision.allowed {
+        Ok(decision.organization_id)
+    } else {
+        Err(PolicyError::Denied)
+    }
+}
+
+/// Route-layer state describing which path parameter names the resource
+#[derive(Clone)]
+pub struct Rule {
+    state: AppState,
+    resource: Resource,
+    action: Action,
+    param: &'static str,
+}
+
+impl Rule {
+    pub fn new(state: &AppState, resource: Resource, action: Action, param: &'static str) -> Self {
+        Self {
+            state: state.clone(),
+            resource,
+            action,
+            param,
+        }
+    }
+}
+
+/// Middleware enforcing a [`Rule`]. Must run inside `require_session`.
+pub async fn require(
+    State(rule): State<Rule>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(params): Path<HashMap<String, String>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(id) = params
+        .get(rule.param)
+        .and_then(|value| Uuid::parse_str(value).ok())
+    else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    match authorize(
+        rule.state.pool(),
+        ctx.user.id,
+        rule.resource,
+        id,
+        rule.action,
+    )
+    .await
+    {
+        Ok(_) => next.run(req).await,
+        Err(PolicyError::NotFound(_)) => StatusCode::NOT_FOUND.into_response(),
+        Err(PolicyError::Denied) => {
+            tracing::warn!(
+                user_id = %ctx.user.id,
+                resource = ?rule.resource,
+                %id,
+                action = ?rule.action,
+                "request denied by policy"
+            );
+            StatusCode::FORBIDDEN.into_response()
+        }
+        Err(PolicyError::Database(error)) => {
+            tracing::error!(?error, "failed to evaluate authorization policy");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn members_cannot_manage() {
+        assert!(allows(Some(MemberRole::Member), Action::Read));
+        assert!(allows(Some(MemberRole::Member), Action::Write));
+        assert!(!allows(Some(MemberRole::Member), Action::Manage));
+    }
+
+    #[test]
+    fn admins_can_do_everything() {
+        for action in [Action::Read, Action::Write, Action::Manage] {
+            assert!(allows(Some(MemberRole::Admin), action));
+        }
+    }
+
+    #[test]
+    fn non_members_are_denied() {
+        for action in [Action::Read, Action::Write, Action::Manage] {
+            assert!(!allows(None, action));
+        }
+    }
+}
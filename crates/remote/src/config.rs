@@ -3,6 +3,7 @@ use std::env;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
 use secrecy::SecretString;
 use thiserror::Error;
+use uuid::Uuid;
 
 #[derive(Debug, Clone)]
 pub struct RemoteServerConfig {
@@ -16,6 +17,7 @@ pub struct RemoteServerConfig {
     pub r2: Option<R2Config>,
     pub review_worker_base_url: Option<String>,
     pub github_app: Option<GitHubAppConfig>,
+    pub scim: Option<ScimConfig>,
 }
 
 #[derive(Debug, Clone)]
@@ -114,6 +116,53 @@ impl GitHubAppConfig {
     }
 }
 
+/// SCIM 2.0 provisioning of users into a single organization
+#[derive(Debug, Clone)]
+pub struct ScimConfig {
+    pub bearer_token: SecretString,
+    pub organization_id: Uuid,
+    /// Display names of SCIM groups whose members become organization admins
+    pub admin_groups: Vec<String>,
+}
+
+impl ScimConfig {
+    pub fn from_env() -> Result<Option<Self>, ConfigError> {
+        let bearer_token = match env::var("SCIM_BEARER_TOKEN") {
+            Ok(v) if !v.trim().is_empty() => v,
+            _ => {
+                tracing::info!("SCIM_BEARER_TOKEN not set, SCIM provisioning disabled");
+                return Ok(None);
+            }
+        };
+
+        let organization_id = env::var("SCIM_ORGANIZATION_ID")
+            .map_err(|_| ConfigError::MissingVar("SCIM_ORGANIZATION_ID"))?
+            .parse()
+            .map_err(|_| ConfigError::InvalidVar("SCIM_ORGANIZATION_ID"))?;
+
+        let admin_groups = env::var("SCIM_ADMIN_GROUPS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|group| group.trim().to_string())
+            .filter(|group| !group.is_empty())
+            .collect();
+
+        tracing::info!(organization_id = %organization_id, "SCIM provisioning enabled");
+
+        Ok(Some(Self {
+            bearer_token: SecretString::new(bearer_token.into()),
+            organization_id,
+            admin_groups,
+        }))
+    }
+
+    pub fn is_admin_group(&self, display_name: &str) -> bool {
+        self.admin_groups
+            .iter()
+            .any(|group| group.eq_ignore_ascii_case(display_name))
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("environment variable `{0}` is not set")]
@@ -154,6 +203,8 @@ impl RemoteServerConfig {
 
         let github_app = GitHubAppConfig::from_env()?;
 
+        let scim = ScimConfig::from_env()?;
+
         Ok(Self {
             database_url,
             listen_addr,
@@ -165,6 +216,7 @@ impl RemoteServerConfig {
             r2,
             review_worker_base_url,
             github_app,
+            scim,
         })
     }
 }
@@ -31,6 +31,21 @@ pub struct AuthSession {
     pub refresh_token_issued_at: Option<DateTime<Utc>>,
 }
 
+/// Device a session was started from, as shown in the user's session list
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct AuthSessionDevice {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Reason recorded for refresh tokens revoked by "sign out everywhere"
+pub const SIGN_OUT_EVERYWHERE_REASON: &str = "sign_out_everywhere";
+/// Reason recorded for refresh tokens revoked after reuse was detected
+pub const TOKEN_REUSE_REASON: &str = "reuse_of_revoked_token";
+
 pub const MAX_SESSION_INACTIVITY_DURATION: Duration = Duration::days(365);
 
 pub struct AuthSessionRepository<'a> {
@@ -91,6 +106,85 @@ impl<'a> AuthSessionRepository<'a> {
         .ok_or(AuthSessionError::NotFound)
     }
 
+    pub async fn set_device(
+        &self,
+        session_id: Uuid,
+        user_agent: Option<&str>,
+        ip_address: Option<&str>,
+    ) -> Result<(), AuthSessionError> {
+        sqlx::query!(
+            r#"
+            UPDATE auth_sessions
+            SET user_agent = $2,
+                ip_address = $3
+            WHERE id = $1
+            "#,
+            session_id,
+            user_agent,
+            ip_address
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Sessions of the user that are neither revoked nor expired by inactivity,
+    /// most recently used first
+    pub async fn list_active_for_user(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<AuthSessionDevice>, AuthSessionError> {
+        let cutoff = Utc::now() - MAX_SESSION_INACTIVITY_DURATION;
+        query_as!(
+            AuthSessionDevice,
+            r#"
+            SELECT
+                id              AS "id!",
+                user_agent      AS "user_agent?",
+                ip_address      AS "ip_address?",
+                created_at      AS "created_at!",
+                last_used_at    AS "last_used_at?"
+            FROM auth_sessions
+            WHERE user_id = $1
+              AND revoked_at IS NULL
+              AND COALESCE(last_used_at, created_at) > $2
+            ORDER BY COALESCE(last_used_at, created_at) DESC, created_at DESC
+            "#,
+            user_id,
+            cutoff
+        )
+        .fetch_all(self.pool)
+        .await
+        .map_err(AuthSessionError::from)
+    }
+
+    /// Revoke one of the user's sessions, returning `NotFound` if the session
+    /// belongs to someone else or is already revoked
+    pub async fn revoke_for_user(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AuthSessionError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE auth_sessions
+            SET revoked_at = NOW()
+            WHERE id = $1
+              AND user_id = $2
+              AND revoked_at IS NULL
+            "#,
+            session_id,
+            user_id
+        )
+        .execute(self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AuthSessionError::NotFound);
+        }
+        Ok(())
+    }
+
     pub async fn touch(&self, session_id: Uuid) -> Result<(), AuthSessionError> {
         sqlx::query!(
             r#"
@@ -177,19 +271,24 @@ impl<'a> AuthSessionRepository<'a> {
         Ok(())
     }
 
-    pub async fn revoke_all_user_sessions(&self, user_id: Uuid) -> Result<i64, AuthSessionError> {
+    pub async fn revoke_all_user_sessions(
+        &self,
+        user_id: Uuid,
+        reason: &str,
+    ) -> Result<i64, AuthSessionError> {
         let mut tx = self.pool.begin().await.map_err(AuthSessionError::from)?;
 
         sqlx::query!(
             r#"
             INSERT INTO revoked_refresh_tokens (token_id, user_id, revoked_reason)
-            SELECT refresh_token_id, user_id, 'reuse_of_revoked_token'
+            SELECT refresh_token_id, user_id, $2
             FROM auth_sessions
             WHERE user_id = $1
               AND refresh_token_id IS NOT NULL
             ON CONFLICT (token_id) DO NOTHING
             "#,
-            user_id
+            user_id,
+            reason
         )
         .execute(&mut *tx)
         .await
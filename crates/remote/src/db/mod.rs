@@ -18,6 +18,7 @@ pub mod project_notification_preferences;
 pub mod project_statuses;
 pub mod projects;
 pub mod reviews;
+pub mod scim;
 pub mod tags;
 pub mod types;
 pub mod users;
@@ -1,5 +1,6 @@
 use sqlx::{Executor, PgPool, Postgres};
 pub use utils::api::organizations::MemberRole;
+use utils::api::organizations::OrganizationMemberWithProfile;
 use uuid::Uuid;
 
 use super::identity_errors::IdentityError;
@@ -75,6 +76,41 @@ where
     Ok(exists)
 }
 
+/// Members of the organization with their profiles, longest-standing first
+pub async fn list_with_profile(
+    pool: &PgPool,
+    organization_id: Uuid,
+) -> Result<Vec<OrganizationMemberWithProfile>, sqlx::Error> {
+    sqlx::query_as!(
+        OrganizationMemberWithProfile,
+        r#"
+        SELECT
+            omm.user_id AS "user_id!: Uuid",
+            omm.role AS "role!: MemberRole",
+            omm.joined_at AS "joined_at!",
+            u.first_name AS "first_name?",
+            u.last_name AS "last_name?",
+            u.username AS "username?",
+            u.email AS "email?",
+            oa.avatar_url AS "avatar_url?"
+        FROM organization_member_metadata omm
+        INNER JOIN users u ON omm.user_id = u.id
+        LEFT JOIN LATERAL (
+            SELECT avatar_url
+            FROM oauth_accounts
+            WHERE user_id = omm.user_id
+            ORDER BY created_at ASC
+            LIMIT 1
+        ) oa ON true
+        WHERE omm.organization_id = $1
+        ORDER BY omm.joined_at ASC
+        "#,
+        organization_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
 pub(crate) async fn assert_membership(
     pool: &PgPool,
     organization_id: Uuid,
@@ -89,43 +125,6 @@ pub(crate) async fn assert_membership(
     }
 }
 
-pub(crate) async fn assert_issue_access(
-    pool: &PgPool,
-    issue_id: Uuid,
-    user_id: Uuid,
-) -> Result<(), IdentityError> {
-    let org_id = sqlx::query_scalar!(
-        r#"
-        SELECT p.organization_id
-        FROM issues i
-        JOIN projects p ON i.project_id = p.id
-        WHERE i.id = $1
-        "#,
-        issue_id
-    )
-    .fetch_optional(pool)
-    .await?
-    .ok_or(IdentityError::NotFound)?;
-
-    assert_membership(pool, org_id, user_id).await
-}
-
-pub(crate) async fn assert_project_access(
-    pool: &PgPool,
-    project_id: Uuid,
-    user_id: Uuid,
-) -> Result<(), IdentityError> {
-    let org_id = sqlx::query_scalar!(
-        r#"SELECT organization_id FROM projects WHERE id = $1"#,
-        project_id
-    )
-    .fetch_optional(pool)
-    .await?
-    .ok_or(IdentityError::NotFound)?;
-
-    assert_membership(pool, org_id, user_id).await
-}
-
 pub(super) async fn assert_admin(
     pool: &PgPool,
     organization_id: Uuid,
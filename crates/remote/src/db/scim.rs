@@ -0,0 +1,556 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, query_as};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::organization_members::{MemberRole, add_member};
+
+#[derive(Debug, Error)]
+pub enum ScimDbError {
+    #[error("resource not found")]
+    NotFound,
+    #[error("{0}")]
+    Conflict(String),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// A user managed by the identity provider
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ScimUser {
+    pub id: Uuid,
+    pub email: String,
+    pub username: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub external_id: Option<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ScimGroup {
+    pub id: Uuid,
+    pub display_name: String,
+    pub external_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Link between a group and one of its users, with enough detail to render
+/// either side of the relationship
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ScimGroupMembership {
+    pub group_id: Uuid,
+    pub group_name: String,
+    pub user_id: Uuid,
+    pub email: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProvisionUser<'a> {
+    pub email: &'a str,
+    pub username: Option<&'a str>,
+    pub first_name: Option<&'a str>,
+    pub last_name: Option<&'a str>,
+    pub external_id: Option<&'a str>,
+    pub active: bool,
+}
+
+pub struct ScimRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> ScimRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Provisioned users, optionally only those whose username or email
+    /// matches `user_name` (case-insensitive)
+    pub async fn list_users(&self, user_name: Option<&str>) -> Result<Vec<ScimUser>, ScimDbError> {
+        let users = query_as!(
+            ScimUser,
+            r#"
+            SELECT
+                u.id            AS "id!: Uuid",
+                u.email         AS "email!",
+                u.username      AS "username?",
+                u.first_name    AS "first_name?",
+                u.last_name     AS "last_name?",
+                s.external_id   AS "external_id?",
+                s.active        AS "active!",
+                s.created_at    AS "created_at!",
+                s.updated_at    AS "updated_at!"
+            FROM scim_users s
+            JOIN users u ON u.id = s.user_id
+            WHERE $1::text IS NULL
+               OR lower(u.username) = lower($1)
+               OR lower(u.email) = lower($1)
+            ORDER BY s.created_at
+            "#,
+            user_name
+        )
+        .fetch_all(self.pool)
+        .await?;
+        Ok(users)
+    }
+
+    pub async fn find_user(&self, user_id: Uuid) -> Result<ScimUser, ScimDbError> {
+        query_as!(
+            ScimUser,
+            r#"
+            SELECT
+                u.id            AS "id!: Uuid",
+                u.email         AS "email!",
+                u.username      AS "username?",
+                u.first_name    AS "first_name?",
+                u.last_name     AS "last_name?",
+                s.external_id   AS "external_id?",
+                s.active        AS "active!",
+                s.created_at    AS "created_at!",
+                s.updated_at    AS "updated_at!"
+            FROM scim_users s
+            JOIN users u ON u.id = s.user_id
+            WHERE s.user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(self.pool)
+        .await?
+        .ok_or(ScimDbError::NotFound)
+    }
+
+    /// Provision a user. A user who already signed in with the same email is
+    /// taken over by the identity provider instead of being duplicated.
+    pub async fn create_user(&self, data: &ProvisionUser<'_>) -> Result<ScimUser, ScimDbError> {
+        let mut tx = self.pool.begin().await?;
+
+        let existing = sqlx::query!(
+            r#"
+            SELECT u.id AS "id!: Uuid", s.user_id AS "scim_user_id?: Uuid"
+            FROM users u
+            LEFT JOIN scim_users s ON s.user_id = u.id
+            WHERE lower(u.email) = lower($1)
+            "#,
+            data.email
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let user_id = match existing {
+            Some(row) if row.scim_user_id.is_some() => {
+                return Err(ScimDbError::Conflict(format!(
+                    "user with email {} is already provisioned",
+                    data.email
+                )));
+            }
+            Some(row) => {
+                sqlx::query!(
+                    r#"
+                    UPDATE users
+                    SET username = COALESCE($2, username),
+                        first_name = COALESCE($3, first_name),
+                        last_name = COALESCE($4, last_name)
+                    WHERE id = $1
+                    "#,
+                    row.id,
+                    data.username,
+                    data.first_name,
+                    data.last_name
+                )
+                .execute(&mut *tx)
+                .await?;
+                row.id
+            }
+            None => {
+                sqlx::query_scalar!(
+                    r#"
+                    INSERT INTO users (id, email, username, first_name, last_name)
+                    VALUES ($1, $2, $3, $4, $5)
+                    RETURNING id AS "id!: Uuid"
+                    "#,
+                    Uuid::new_v4(),
+                    data.email,
+                    data.username,
+                    data.first_name,
+                    data.last_name
+                )
+                .fetch_one(&mut *tx)
+                .await?
+            }
+        };
+
+        sqlx::query!(
+            r#"
+            INSERT INTO scim_users (user_id, external_id, active)
+            VALUES ($1, $2, $3)
+            "#,
+            user_id,
+            data.external_id,
+            data.active
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        self.find_user(user_id).await
+    }
+
+    /// Replace the user's attributes with the identity provider's copy
+    pub async fn update_user(
+        &self,
+        user_id: Uuid,
+        data: &ProvisionUser<'_>,
+    ) -> Result<ScimUser, ScimDbError> {
+        let mut tx = self.pool.begin().await?;
+
+        let updated = sqlx::query!(
+            r#"
+            UPDATE scim_users
+            SET external_id = $2,
+                active = $3
+            WHERE user_id = $1
+            "#,
+            user_id,
+            data.external_id,
+            data.active
+        )
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(ScimDbError::NotFound);
+        }
+
+        let email_taken = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM users WHERE lower(email) = lower($2) AND id <> $1
+            ) AS "exists!"
+            "#,
+            user_id,
+            data.email
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if email_taken {
+            return Err(ScimDbError::Conflict(format!(
+                "email {} belongs to another user",
+                data.email
+            )));
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE users
+            SET email = $2,
+                username = $3,
+                first_name = $4,
+                last_name = $5
+            WHERE id = $1
+            "#,
+            user_id,
+            data.email,
+            data.username,
+            data.first_name,
+            data.last_name
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        self.find_user(user_id).await
+    }
+
+    pub async fn set_active(&self, user_id: Uuid, active: bool) -> Result<(), ScimDbError> {
+        let updated = sqlx::query!(
+            r#"
+            UPDATE scim_users
+            SET active = $2
+            WHERE user_id = $1
+            "#,
+            user_id,
+            active
+        )
+        .execute(self.pool)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(ScimDbError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Whether the identity provider has deactivated the user
+    pub async fn is_deactivated(&self, user_id: Uuid) -> Result<bool, ScimDbError> {
+        let deactivated = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM scim_users WHERE user_id = $1 AND active = FALSE
+            ) AS "exists!"
+            "#,
+            user_id
+        )
+        .fetch_one(self.pool)
+        .await?;
+        Ok(deactivated)
+    }
+
+    /// Provisioned user with the given email, used to link the first sign-in
+    /// of a provisioned user to the existing account
+    pub async fn find_user_id_by_email(&self, email: &str) -> Result<Option<Uuid>, ScimDbError> {
+        let user_id = sqlx::query_scalar!(
+            r#"
+            SELECT s.user_id AS "user_id!: Uuid"
+            FROM scim_users s
+            JOIN users u ON u.id = s.user_id
+            WHERE lower(u.email) = lower($1)
+            "#,
+            email
+        )
+        .fetch_optional(self.pool)
+        .await?;
+        Ok(user_id)
+    }
+
+    pub async fn list_groups(&self) -> Result<Vec<ScimGroup>, ScimDbError> {
+        let groups = query_as!(
+            ScimGroup,
+            r#"
+            SELECT
+                id              AS "id!: Uuid",
+                display_name    AS "display_name!",
+                external_id     AS "external_id?",
+                created_at      AS "created_at!",
+                updated_at      AS "updated_at!"
+            FROM scim_groups
+            ORDER BY display_name
+            "#
+        )
+        .fetch_all(self.pool)
+        .await?;
+        Ok(groups)
+    }
+
+    pub async fn find_group(&self, group_id: Uuid) -> Result<ScimGroup, ScimDbError> {
+        query_as!(
+            ScimGroup,
+            r#"
+            SELECT
+                id              AS "id!: Uuid",
+                display_name    AS "display_name!",
+                external_id     AS "external_id?",
+                created_at      AS "created_at!",
+                updated_at      AS "updated_at!"
+            FROM scim_groups
+            WHERE id = $1
+            "#,
+            group_id
+        )
+        .fetch_optional(self.pool)
+        .await?
+        .ok_or(ScimDbError::NotFound)
+    }
+
+    pub async fn create_group(
+        &self,
+        display_name: &str,
+        external_id: Option<&str>,
+    ) -> Result<ScimGroup, ScimDbError> {
+        query_as!(
+            ScimGroup,
+            r#"
+            INSERT INTO scim_groups (display_name, external_id)
+            VALUES ($1, $2)
+            ON CONFLICT (display_name) DO NOTHING
+            RETURNING
+                id              AS "id!: Uuid",
+                display_name    AS "display_name!",
+                external_id     AS "external_id?",
+                created_at      AS "created_at!",
+                updated_at      AS "updated_at!"
+            "#,
+            display_name,
+            external_id
+        )
+        .fetch_optional(self.pool)
+        .await?
+        .ok_or_else(|| ScimDbError::Conflict(format!("group {display_name} already exists")))
+    }
+
+    pub async fn rename_group(
+        &self,
+        group_id: Uuid,
+        display_name: &str,
+    ) -> Result<ScimGroup, ScimDbError> {
+        let taken = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM scim_groups WHERE display_name = $2 AND id <> $1
+            ) AS "exists!"
+            "#,
+            group_id,
+            display_name
+        )
+        .fetch_one(self.pool)
+        .await?;
+        if taken {
+            return Err(ScimDbError::Conflict(format!(
+                "group {display_name} already exists"
+            )));
+        }
+
+        query_as!(
+            ScimGroup,
+            r#"
+            UPDATE scim_groups
+            SET display_name = $2
+            WHERE id = $1
+            RETURNING
+                id              AS "id!: Uuid",
+                display_name    AS "display_name!",
+                external_id     AS "external_id?",
+                created_at      AS "created_at!",
+                updated_at      AS "updated_at!"
+            "#,
+            group_id,
+            display_name
+        )
+        .fetch_optional(self.pool)
+        .await?
+        .ok_or(ScimDbError::NotFound)
+    }
+
+    /// Delete a group, returning the users who were in it
+    pub async fn delete_group(&self, group_id: Uuid) -> Result<Vec<Uuid>, ScimDbError> {
+        let members = self
+            .group_members(group_id)
+            .await?
+            .into_iter()
+            .map(|m| m.user_id)
+            .collect();
+
+        let deleted = sqlx::query!(r#"DELETE FROM scim_groups WHERE id = $1"#, group_id)
+            .execute(self.pool)
+            .await?;
+        if deleted.rows_affected() == 0 {
+            return Err(ScimDbError::NotFound);
+        }
+        Ok(members)
+    }
+
+    pub async fn group_members(
+        &self,
+        group_id: Uuid,
+    ) -> Result<Vec<ScimGroupMembership>, ScimDbError> {
+        let members = query_as!(
+            ScimGroupMembership,
+            r#"
+            SELECT
+                g.id            AS "group_id!: Uuid",
+                g.display_name  AS "group_name!",
+                u.id            AS "user_id!: Uuid",
+                u.email         AS "email!"
+            FROM scim_group_members m
+            JOIN scim_groups g ON g.id = m.group_id
+            JOIN users u ON u.id = m.user_id
+            WHERE m.group_id = $1
+            ORDER BY u.email
+            "#,
+            group_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+        Ok(members)
+    }
+
+    pub async fn user_groups(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<ScimGroupMembership>, ScimDbError> {
+        let groups = query_as!(
+            ScimGroupMembership,
+            r#"
+            SELECT
+                g.id            AS "group_id!: Uuid",
+                g.display_name  AS "group_name!",
+                u.id            AS "user_id!: Uuid",
+                u.email         AS "email!"
+            FROM scim_group_members m
+            JOIN scim_groups g ON g.id = m.group_id
+            JOIN users u ON u.id = m.user_id
+            WHERE m.user_id = $1
+            ORDER BY g.display_name
+            "#,
+            user_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+        Ok(groups)
+    }
+
+    /// Add provisioned users to a group. Ids of users that are not provisioned are ignored.
+    pub async fn add_group_members(
+        &self,
+        group_id: Uuid,
+        user_ids: &[Uuid],
+    ) -> Result<(), ScimDbError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO scim_group_members (group_id, user_id)
+            SELECT $1, s.user_id
+            FROM scim_users s
+            WHERE s.user_id = ANY($2)
+            ON CONFLICT DO NOTHING
+            "#,
+            group_id,
+            user_ids
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_group_members(
+        &self,
+        group_id: Uuid,
+        user_ids: &[Uuid],
+    ) -> Result<(), ScimDbError> {
+        sqlx::query!(
+            r#"
+            DELETE FROM scim_group_members
+            WHERE group_id = $1 AND user_id = ANY($2)
+            "#,
+            group_id,
+            user_ids
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Give the user `role` in the organization, or remove them when `role` is `None`
+    pub async fn sync_membership(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        role: Option<MemberRole>,
+    ) -> Result<(), ScimDbError> {
+        match role {
+            Some(role) => add_member(self.pool, organization_id, user_id, role).await?,
+            None => {
+                sqlx::query!(
+                    r#"
+                    DELETE FROM organization_member_metadata
+                    WHERE organization_id = $1 AND user_id = $2
+                    "#,
+                    organization_id,
+                    user_id
+                )
+                .execute(self.pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::{HashMap, hash_map::Entry};
+
+use axum::{
+    Json, Router,
+    extract::{Extension, State},
+    http::StatusCode,
+    routing::get,
+};
+use chrono::Utc;
+use tracing::instrument;
+use utils::api::organizations::{
+    AccessPrincipal, AccessReportEntry, AccessReportResponse, OrganizationWithRole,
+};
+use uuid::Uuid;
+
+use super::error::ErrorResponse;
+use crate::{
+    AppState,
+    auth::{
+        RequestContext,
+        policy::{self, Action},
+    },
+    db::{
+        auth::{AuthSessionDevice, AuthSessionRepository},
+        organization_members::{self, MemberRole},
+        organizations::OrganizationRepository,
+        projects::ProjectRepository,
+    },
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/admin/access-report", get(get_access_report))
+}
+
+/// Actions the policy allows `role`, in increasing order of privilege
+fn role_actions(role: Option<MemberRole>) -> Vec<Action> {
+    [Action::Read, Action::Write, Action::Manage]
+        .into_iter()
+        .filter(|action| policy::allows(role, *action))
+        .collect()
+}
+
+fn report_error(error: impl std::fmt::Debug) -> ErrorResponse {
+    tracing::error!(?error, "failed to build access report");
+    ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+}
+
+/// Which projects and actions every member and token of the caller's organizations can
+/// reach, for security reviews. Covers only organizations the caller administers.
+///
+/// Members get an entry for the organization and one per project. Session tokens act
+/// with their user's role everywhere in the organization, so they get one entry each.
+#[instrument(name = "access_report.get", skip(state, ctx), fields(user_id = %ctx.user.id))]
+async fn get_access_report(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<Json<AccessReportResponse>, ErrorResponse> {
+    let pool = state.pool();
+    let organizations: Vec<OrganizationWithRole> = OrganizationRepository::new(pool)
+        .list_user_organizations(ctx.user.id)
+        .await
+        .map_err(report_error)?
+        .into_iter()
+        .filter(|organization| policy::allows(Some(organization.user_role), Action::Manage))
+        .collect();
+    let scim_organization = state
+        .config()
+        .scim
+        .as_ref()
+        .map(|scim| scim.organization_id);
+
+    let mut entries = Vec::new();
+    let mut sessions: HashMap<Uuid, Vec<AuthSessionDevice>> = HashMap::new();
+    for organization in &organizations {
+        let members = organization_members::list_with_profile(pool, organization.id)
+            .await
+            .map_err(report_error)?;
+        let projects = ProjectRepository::list_by_organization(pool, organization.id)
+            .await
+            .map_err(report_error)?;
+
+        for member in members {
+            let role = Some(member.role);
+            let access = AccessReportEntry {
+                principal: AccessPrincipal::User,
+                principal_id: Some(member.user_id),
+                user_id: Some(member.user_id),
+                user_email: member.email,
+                organization_id: organization.id,
+                organization_name: organization.name.clone(),
+                project_id: None,
+                project_name: None,
+                role,
+                actions: role_actions(role),
+                last_used_at: None,
+            };
+            entries.push(access.clone());
+            for project in &projects {
+                entries.push(AccessReportEntry {
+                    project_id: Some(project.id),
+                    project_name: Some(project.name.clone()),
+                    ..access.clone()
+                });
+            }
+
+            if let Entry::Vacant(vacant) = sessions.entry(member.user_id) {
+                vacant.insert(
+                    AuthSessionRepository::new(pool)
+                        .list_active_for_user(member.user_id)
+                        .await
+                        .map_err(report_error)?,
+                );
+            }
+            for session in &sessions[&member.user_id] {
+                entries.push(AccessReportEntry {
+                    principal: AccessPrincipal::SessionToken,
+                    principal_id: Some(session.id),
+                    last_used_at: session.last_used_at,
+                    ..access.clone()
+                });
+            }
+        }
+
+        if scim_organization == Some(organization.id) {
+            entries.push(AccessReportEntry {
+                principal: AccessPrincipal::ScimToken,
+                principal_id: None,
+                user_id: None,
+                user_email: None,
+                organization_id: organization.id,
+                organization_name: organization.name.clone(),
+                project_id: None,
+                project_name: None,
+                role: None,
+                actions: vec![Action::Manage],
+                last_used_at: None,
+            });
+        }
+    }
+
+    Ok(Json(AccessReportResponse {
+        generated_at: Utc::now(),
+        entries,
+    }))
+}
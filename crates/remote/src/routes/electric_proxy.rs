@@ -15,7 +15,14 @@ use tracing::error;
 use ts_rs::TS;
 use uuid::Uuid;
 
-use crate::{AppState, auth::RequestContext, db::organization_members, shapes};
+use crate::{
+    AppState,
+    auth::{
+        RequestContext,
+        policy::{self, Action, Resource},
+    },
+    shapes,
+};
 
 #[derive(Deserialize)]
 struct OrgShapeQuery {
@@ -62,9 +69,15 @@ async fn proxy_projects(
     Extension(ctx): Extension<RequestContext>,
     Query(query): Query<OrgShapeQuery>,
 ) -> Result<Response, ProxyError> {
-    organization_members::assert_membership(state.pool(), query.organization_id, ctx.user.id)
-        .await
-        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+    policy::authorize(
+        state.pool(),
+        ctx.user.id,
+        Resource::Organization,
+        query.organization_id,
+        Action::Read,
+    )
+    .await
+    .map_err(|e| ProxyError::Authorization(e.to_string()))?;
 
     proxy_table(
         &state,
@@ -80,9 +93,15 @@ async fn proxy_notifications(
     Extension(ctx): Extension<RequestContext>,
     Query(query): Query<OrgShapeQuery>,
 ) -> Result<Response, ProxyError> {
-    organization_members::assert_membership(state.pool(), query.organization_id, ctx.user.id)
-        .await
-        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+    policy::authorize(
+        state.pool(),
+        ctx.user.id,
+        Resource::Organization,
+        query.organization_id,
+        Action::Read,
+    )
+    .await
+    .map_err(|e| ProxyError::Authorization(e.to_string()))?;
 
     proxy_table(
         &state,
@@ -99,9 +118,15 @@ async fn proxy_workspaces(
     Path(project_id): Path<Uuid>,
     Query(query): Query<ShapeQuery>,
 ) -> Result<Response, ProxyError> {
-    organization_members::assert_project_access(state.pool(), project_id, ctx.user.id)
-        .await
-        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+    policy::authorize(
+        state.pool(),
+        ctx.user.id,
+        Resource::Project,
+        project_id,
+        Action::Read,
+    )
+    .await
+    .map_err(|e| ProxyError::Authorization(e.to_string()))?;
 
     proxy_table(
         &state,
@@ -118,9 +143,15 @@ async fn proxy_project_statuses(
     Path(project_id): Path<Uuid>,
     Query(query): Query<ShapeQuery>,
 ) -> Result<Response, ProxyError> {
-    organization_members::assert_project_access(state.pool(), project_id, ctx.user.id)
-        .await
-        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+    policy::authorize(
+        state.pool(),
+        ctx.user.id,
+        Resource::Project,
+        project_id,
+        Action::Read,
+    )
+    .await
+    .map_err(|e| ProxyError::Authorization(e.to_string()))?;
 
     proxy_table(
         &state,
@@ -137,9 +168,15 @@ async fn proxy_tags(
     Path(project_id): Path<Uuid>,
     Query(query): Query<ShapeQuery>,
 ) -> Result<Response, ProxyError> {
-    organization_members::assert_project_access(state.pool(), project_id, ctx.user.id)
-        .await
-        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+    policy::authorize(
+        state.pool(),
+        ctx.user.id,
+        Resource::Project,
+        project_id,
+        Action::Read,
+    )
+    .await
+    .map_err(|e| ProxyError::Authorization(e.to_string()))?;
 
     proxy_table(
         &state,
@@ -156,9 +193,15 @@ async fn proxy_issues(
     Path(project_id): Path<Uuid>,
     Query(query): Query<ShapeQuery>,
 ) -> Result<Response, ProxyError> {
-    organization_members::assert_project_access(state.pool(), project_id, ctx.user.id)
-        .await
-        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+    policy::authorize(
+        state.pool(),
+        ctx.user.id,
+        Resource::Project,
+        project_id,
+        Action::Read,
+    )
+    .await
+    .map_err(|e| ProxyError::Authorization(e.to_string()))?;
 
     proxy_table(
         &state,
@@ -175,9 +218,15 @@ async fn proxy_issue_assignees(
     Path(project_id): Path<Uuid>,
     Query(query): Query<ShapeQuery>,
 ) -> Result<Response, ProxyError> {
-    organization_members::assert_project_access(state.pool(), project_id, ctx.user.id)
-        .await
-        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+    policy::authorize(
+        state.pool(),
+        ctx.user.id,
+        Resource::Project,
+        project_id,
+        Action::Read,
+    )
+    .await
+    .map_err(|e| ProxyError::Authorization(e.to_string()))?;
 
     proxy_table(
         &state,
@@ -194,9 +243,15 @@ async fn proxy_issue_followers(
     Path(project_id): Path<Uuid>,
     Query(query): Query<ShapeQuery>,
 ) -> Result<Response, ProxyError> {
-    organization_members::assert_project_access(state.pool(), project_id, ctx.user.id)
-        .await
-        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+    policy::authorize(
+        state.pool(),
+        ctx.user.id,
+        Resource::Project,
+        project_id,
+        Action::Read,
+    )
+    .await
+    .map_err(|e| ProxyError::Authorization(e.to_string()))?;
 
     proxy_table(
         &state,
@@ -213,9 +268,15 @@ async fn proxy_issue_tags(
     Path(project_id): Path<Uuid>,
     Query(query): Query<ShapeQuery>,
 ) -> Result<Response, ProxyError> {
-    organization_members::assert_project_access(state.pool(), project_id, ctx.user.id)
-        .await
-        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+    policy::authorize(
+        state.pool(),
+        ctx.user.id,
+        Resource::Project,
+        project_id,
+        Action::Read,
+    )
+    .await
+    .map_err(|e| ProxyError::Authorization(e.to_string()))?;
 
     proxy_table(
         &state,
@@ -232,9 +293,15 @@ async fn proxy_issue_comments(
     Path(issue_id): Path<Uuid>,
     Query(query): Query<ShapeQuery>,
 ) -> Result<Response, ProxyError> {
-    organization_members::assert_issue_access(state.pool(), issue_id, ctx.user.id)
-        .await
-        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+    policy::authorize(
+        state.pool(),
+        ctx.user.id,
+        Resource::Issue,
+        issue_id,
+        Action::Read,
+    )
+    .await
+    .map_err(|e| ProxyError::Authorization(e.to_string()))?;
 
     proxy_table(
         &state,
@@ -251,9 +318,15 @@ async fn proxy_issue_dependencies(
     Path(project_id): Path<Uuid>,
     Query(query): Query<ShapeQuery>,
 ) -> Result<Response, ProxyError> {
-    organization_members::assert_project_access(state.pool(), project_id, ctx.user.id)
-        .await
-        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+    policy::authorize(
+        state.pool(),
+        ctx.user.id,
+        Resource::Project,
+        project_id,
+        Action::Read,
+    )
+    .await
+    .map_err(|e| ProxyError::Authorization(e.to_string()))?;
 
     proxy_table(
         &state,
@@ -270,9 +343,15 @@ async fn proxy_issue_comment_reactions(
     Path(issue_id): Path<Uuid>,
     Query(query): Query<ShapeQuery>,
 ) -> Result<Response, ProxyError> {
-    organization_members::assert_issue_access(state.pool(), issue_id, ctx.user.id)
-        .await
-        .map_err(|e| ProxyError::Authorization(e.to_string()))?;
+    policy::authorize(
+        state.pool(),
+        ctx.user.id,
+        Resource::Issue,
+        issue_id,
+        Action::Read,
+    )
+    .await
+    .map_err(|e| ProxyError::Authorization(e.to_string()))?;
 
     proxy_table(
         &state,
@@ -5,7 +5,7 @@ use axum::{
 };
 use serde_json::json;
 
-use crate::db::identity_errors::IdentityError;
+use crate::auth::policy::{PolicyError, Resource};
 
 #[derive(Debug)]
 pub struct ErrorResponse {
@@ -28,17 +28,20 @@ impl IntoResponse for ErrorResponse {
     }
 }
 
-pub(crate) fn membership_error(error: IdentityError, forbidden_message: &str) -> ErrorResponse {
+pub(crate) fn policy_error(error: PolicyError, forbidden_message: &str) -> ErrorResponse {
     match error {
-        IdentityError::NotFound | IdentityError::PermissionDenied => {
-            ErrorResponse::new(StatusCode::FORBIDDEN, forbidden_message)
+        PolicyError::NotFound(resource) => {
+            let message = match resource {
+                Resource::Organization => "organization not found",
+                Resource::Project => "project not found",
+                Resource::Issue => "issue not found",
+            };
+            ErrorResponse::new(StatusCode::NOT_FOUND, message)
         }
-        IdentityError::Database(_) => {
-            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error")
-        }
-        other => {
-            tracing::warn!(?other, "unexpected membership error");
-            ErrorResponse::new(StatusCode::FORBIDDEN, forbidden_message)
+        PolicyError::Denied => ErrorResponse::new(StatusCode::FORBIDDEN, forbidden_message),
+        PolicyError::Database(error) => {
+            tracing::error!(?error, "failed to evaluate authorization policy");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
         }
     }
 }
@@ -12,13 +12,16 @@ use serde::{Deserialize, Serialize};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
-use super::error::ErrorResponse;
+use super::{
+    error::ErrorResponse,
+    organization_members::{ensure_admin_access, ensure_member_access},
+};
 use crate::{
     AppState,
     auth::RequestContext,
     db::{
-        github_app::GitHubAppRepository2, identity_errors::IdentityError,
-        organizations::OrganizationRepository, reviews::ReviewRepository,
+        github_app::GitHubAppRepository2, organizations::OrganizationRepository,
+        reviews::ReviewRepository,
     },
     github_app::{PrReviewParams, PrReviewService, verify_webhook_signature},
 };
@@ -131,22 +134,10 @@ pub async fn get_install_url(
         ErrorResponse::new(StatusCode::NOT_IMPLEMENTED, "GitHub App not configured")
     })?;
 
-    // Check user is admin of organization
-    let org_repo = OrganizationRepository::new(state.pool());
-    org_repo
-        .assert_admin(org_id, ctx.user.id)
-        .await
-        .map_err(|e| match e {
-            IdentityError::PermissionDenied => {
-                ErrorResponse::new(StatusCode::FORBIDDEN, "Admin access required")
-            }
-            IdentityError::NotFound => {
-                ErrorResponse::new(StatusCode::NOT_FOUND, "Organization not found")
-            }
-            _ => ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
-        })?;
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
 
     // Check not a personal org
+    let org_repo = OrganizationRepository::new(state.pool());
     let is_personal = org_repo
         .is_personal(org_id)
         .await
@@ -191,17 +182,7 @@ pub async fn get_status(
     axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
     Path(org_id): Path<Uuid>,
 ) -> Result<impl IntoResponse, ErrorResponse> {
-    // Check user is member of organization
-    let org_repo = OrganizationRepository::new(state.pool());
-    org_repo
-        .assert_membership(org_id, ctx.user.id)
-        .await
-        .map_err(|e| match e {
-            IdentityError::PermissionDenied | IdentityError::NotFound => {
-                ErrorResponse::new(StatusCode::FORBIDDEN, "Access denied")
-            }
-            _ => ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
-        })?;
+    ensure_member_access(state.pool(), org_id, ctx.user.id).await?;
 
     let gh_repo = GitHubAppRepository2::new(state.pool());
 
@@ -255,20 +236,7 @@ pub async fn uninstall(
     axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
     Path(org_id): Path<Uuid>,
 ) -> Result<impl IntoResponse, ErrorResponse> {
-    // Check user is admin of organization
-    let org_repo = OrganizationRepository::new(state.pool());
-    org_repo
-        .assert_admin(org_id, ctx.user.id)
-        .await
-        .map_err(|e| match e {
-            IdentityError::PermissionDenied => {
-                ErrorResponse::new(StatusCode::FORBIDDEN, "Admin access required")
-            }
-            IdentityError::NotFound => {
-                ErrorResponse::new(StatusCode::NOT_FOUND, "Organization not found")
-            }
-            _ => ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
-        })?;
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
 
     let gh_repo = GitHubAppRepository2::new(state.pool());
     gh_repo.delete_by_organization(org_id).await.map_err(|e| {
@@ -288,20 +256,7 @@ pub async fn update_repo_review_enabled(
     Path((org_id, repo_id)): Path<(Uuid, Uuid)>,
     Json(payload): Json<UpdateRepoReviewEnabledRequest>,
 ) -> Result<impl IntoResponse, ErrorResponse> {
-    // Check user is admin of organization
-    let org_repo = OrganizationRepository::new(state.pool());
-    org_repo
-        .assert_admin(org_id, ctx.user.id)
-        .await
-        .map_err(|e| match e {
-            IdentityError::PermissionDenied => {
-                ErrorResponse::new(StatusCode::FORBIDDEN, "Admin access required")
-            }
-            IdentityError::NotFound => {
-                ErrorResponse::new(StatusCode::NOT_FOUND, "Organization not found")
-            }
-            _ => ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
-        })?;
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
 
     // Get installation for this org
     let gh_repo = GitHubAppRepository2::new(state.pool());
@@ -347,17 +302,7 @@ pub async fn fetch_repositories(
     axum::extract::Extension(ctx): axum::extract::Extension<RequestContext>,
     Path(org_id): Path<Uuid>,
 ) -> Result<impl IntoResponse, ErrorResponse> {
-    // Check user is member of organization
-    let org_repo = OrganizationRepository::new(state.pool());
-    org_repo
-        .assert_membership(org_id, ctx.user.id)
-        .await
-        .map_err(|e| match e {
-            IdentityError::PermissionDenied | IdentityError::NotFound => {
-                ErrorResponse::new(StatusCode::FORBIDDEN, "Access denied")
-            }
-            _ => ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
-        })?;
+    ensure_member_access(state.pool(), org_id, ctx.user.id).await?;
 
     let gh_repo = GitHubAppRepository2::new(state.pool());
 
@@ -419,20 +364,7 @@ pub async fn bulk_update_review_enabled(
     Path(org_id): Path<Uuid>,
     Json(payload): Json<UpdateRepoReviewEnabledRequest>,
 ) -> Result<impl IntoResponse, ErrorResponse> {
-    // Check user is admin of organization
-    let org_repo = OrganizationRepository::new(state.pool());
-    org_repo
-        .assert_admin(org_id, ctx.user.id)
-        .await
-        .map_err(|e| match e {
-            IdentityError::PermissionDenied => {
-                ErrorResponse::new(StatusCode::FORBIDDEN, "Admin access required")
-            }
-            IdentityError::NotFound => {
-                ErrorResponse::new(StatusCode::NOT_FOUND, "Organization not found")
-            }
-            _ => ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
-        })?;
+    ensure_admin_access(state.pool(), org_id, ctx.user.id).await?;
 
     let gh_repo = GitHubAppRepository2::new(state.pool());
     let installation = gh_repo
@@ -14,6 +14,7 @@ use tracing::{Level, field};
 
 use crate::{AppState, auth::require_session};
 
+mod access_report;
 mod electric_proxy;
 mod error;
 mod github_app;
@@ -24,9 +25,12 @@ mod notifications;
 mod oauth;
 pub(crate) mod organization_members;
 mod organizations;
+mod permissions;
 mod project_statuses;
 mod projects;
 mod review;
+mod scim;
+mod sessions;
 mod tags;
 mod tokens;
 
@@ -57,12 +61,13 @@ pub fn router(state: AppState) -> Router {
         .merge(organization_members::public_router())
         .merge(tokens::public_router())
         .merge(review::public_router())
-        .merge(github_app::public_router());
+        .merge(github_app::public_router())
+        .merge(scim::router(&state));
 
     let v1_protected = Router::<AppState>::new()
         .merge(identity::router())
         .merge(projects::router())
-        .merge(organizations::router())
+        .merge(organizations::router(&state))
         .merge(organization_members::protected_router())
         .merge(oauth::protected_router())
         .merge(electric_proxy::router())
@@ -72,6 +77,9 @@ pub fn router(state: AppState) -> Router {
         .merge(issue_comments::router())
         .merge(issue_comment_reactions::router())
         .merge(notifications::router())
+        .merge(sessions::router())
+        .merge(permissions::router())
+        .merge(access_report::router())
         .layer(middleware::from_fn_with_state(
             state.clone(),
             require_session,
@@ -3,7 +3,7 @@ use std::borrow::Cow;
 use axum::{
     Json, Router,
     extract::{Extension, Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode, header},
     response::{IntoResponse, Redirect, Response},
     routing::{get, post},
 };
@@ -18,7 +18,7 @@ use uuid::Uuid;
 
 use crate::{
     AppState,
-    auth::{CallbackResult, HandoffError, RequestContext},
+    auth::{CallbackResult, HandoffError, RequestContext, SessionDevice},
     db::{oauth::OAuthHandoffError, oauth_accounts::OAuthAccountRepository},
 };
 
@@ -120,6 +120,7 @@ pub async fn authorize_callback(
     State(state): State<AppState>,
     Path(provider): Path<String>,
     Query(query): Query<CallbackQuery>,
+    headers: HeaderMap,
 ) -> Response {
     let handoff = state.handoff();
 
@@ -129,6 +130,7 @@ pub async fn authorize_callback(
             query.state.as_deref(),
             query.code.as_deref(),
             query.error.as_deref(),
+            &session_device(&headers),
         )
         .await
     {
@@ -225,6 +227,26 @@ pub async fn logout(
     }
 }
 
+/// The callback is opened in the user's browser, so its headers identify the
+/// device the new session belongs to
+fn session_device(headers: &HeaderMap) -> SessionDevice {
+    let header_value = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+
+    SessionDevice {
+        user_agent: header_value(header::USER_AGENT.as_str()).map(str::to_string),
+        ip_address: header_value("x-forwarded-for")
+            .and_then(|forwarded| forwarded.split(',').next())
+            .or_else(|| header_value("x-real-ip"))
+            .map(|ip| ip.trim().to_string()),
+    }
+}
+
 fn init_error_response(error: HandoffError) -> Response {
     match &error {
         HandoffError::Provider(err) => warn!(?err, "provider error during oauth init"),
@@ -10,22 +10,22 @@ use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use tracing::warn;
 use utils::api::organizations::{
-    ListMembersResponse, OrganizationMemberWithProfile, RevokeInvitationRequest,
-    UpdateMemberRoleRequest, UpdateMemberRoleResponse,
+    ListMembersResponse, RevokeInvitationRequest, UpdateMemberRoleRequest, UpdateMemberRoleResponse,
 };
 use uuid::Uuid;
 
-use super::error::{ErrorResponse, membership_error};
+use super::error::{ErrorResponse, policy_error};
 use crate::{
     AppState,
-    auth::RequestContext,
+    auth::{
+        RequestContext,
+        policy::{self, Action, PolicyError, Resource},
+    },
     db::{
         identity_errors::IdentityError,
         invitations::{Invitation, InvitationRepository},
-        issues::IssueRepository,
         organization_members::{self, MemberRole},
         organizations::OrganizationRepository,
-        projects::ProjectRepository,
     },
 };
 
@@ -265,35 +265,9 @@ pub async fn list_members(
     let user = ctx.user;
     ensure_member_access(&state.pool, org_id, user.id).await?;
 
-    let members = sqlx::query_as!(
-        OrganizationMemberWithProfile,
-        r#"
-        SELECT
-            omm.user_id AS "user_id!: Uuid",
-            omm.role AS "role!: MemberRole",
-            omm.joined_at AS "joined_at!",
-            u.first_name AS "first_name?",
-            u.last_name AS "last_name?",
-            u.username AS "username?",
-            u.email AS "email?",
-            oa.avatar_url AS "avatar_url?"
-        FROM organization_member_metadata omm
-        INNER JOIN users u ON omm.user_id = u.id
-        LEFT JOIN LATERAL (
-            SELECT avatar_url
-            FROM oauth_accounts
-            WHERE user_id = omm.user_id
-            ORDER BY created_at ASC
-            LIMIT 1
-        ) oa ON true
-        WHERE omm.organization_id = $1
-        ORDER BY omm.joined_at ASC
-        "#,
-        org_id
-    )
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let members = organization_members::list_with_profile(&state.pool, org_id)
+        .await
+        .map_err(|_| ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
 
     Ok(Json(ListMembersResponse { members }))
 }
@@ -494,9 +468,16 @@ pub(crate) async fn ensure_member_access(
     organization_id: Uuid,
     user_id: Uuid,
 ) -> Result<(), ErrorResponse> {
-    organization_members::assert_membership(pool, organization_id, user_id)
-        .await
-        .map_err(|err| membership_error(err, "Not a member of organization"))
+    policy::authorize(
+        pool,
+        user_id,
+        Resource::Organization,
+        organization_id,
+        Action::Read,
+    )
+    .await
+    .map(|_| ())
+    .map_err(|err| policy_error(err, "Not a member of organization"))
 }
 
 pub(crate) async fn ensure_admin_access(
@@ -504,10 +485,16 @@ pub(crate) async fn ensure_admin_access(
     organization_id: Uuid,
     user_id: Uuid,
 ) -> Result<(), ErrorResponse> {
-    OrganizationRepository::new(pool)
-        .assert_admin(organization_id, user_id)
-        .await
-        .map_err(|err| membership_error(err, "Admin access required"))
+    policy::authorize(
+        pool,
+        user_id,
+        Resource::Organization,
+        organization_id,
+        Action::Manage,
+    )
+    .await
+    .map(|_| ())
+    .map_err(|err| policy_error(err, "Admin access required"))
 }
 
 pub(crate) async fn ensure_project_access(
@@ -515,44 +502,14 @@ pub(crate) async fn ensure_project_access(
     user_id: Uuid,
     project_id: Uuid,
 ) -> Result<Uuid, ErrorResponse> {
-    let organization_id = ProjectRepository::organization_id(pool, project_id)
-        .await
-        .map_err(|error| {
-            tracing::error!(?error, %project_id, "failed to load project");
-            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
-        })?
-        .ok_or_else(|| {
-            warn!(
-                %project_id,
-                %user_id,
-                "project not found for access check"
-            );
-            ErrorResponse::new(StatusCode::NOT_FOUND, "project not found")
-        })?;
-
-    organization_members::assert_membership(pool, organization_id, user_id)
+    policy::authorize(pool, user_id, Resource::Project, project_id, Action::Write)
         .await
         .map_err(|err| {
-            if let IdentityError::Database(error) = &err {
-                tracing::error!(
-                    ?error,
-                    %organization_id,
-                    %project_id,
-                    "failed to authorize project membership"
-                );
-            } else {
-                warn!(
-                    ?err,
-                    %organization_id,
-                    %project_id,
-                    %user_id,
-                    "project access denied"
-                );
+            if matches!(err, PolicyError::Denied) {
+                warn!(%project_id, %user_id, "project access denied");
             }
-            membership_error(err, "project not accessible")
-        })?;
-
-    Ok(organization_id)
+            policy_error(err, "project not accessible")
+        })
 }
 
 pub(crate) async fn ensure_issue_access(
@@ -560,42 +517,12 @@ pub(crate) async fn ensure_issue_access(
     user_id: Uuid,
     issue_id: Uuid,
 ) -> Result<Uuid, ErrorResponse> {
-    let organization_id = IssueRepository::organization_id(pool, issue_id)
-        .await
-        .map_err(|error| {
-            tracing::error!(?error, %issue_id, "failed to load issue");
-            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
-        })?
-        .ok_or_else(|| {
-            warn!(
-                %issue_id,
-                %user_id,
-                "issue not found for access check"
-            );
-            ErrorResponse::new(StatusCode::NOT_FOUND, "issue not found")
-        })?;
-
-    organization_members::assert_membership(pool, organization_id, user_id)
+    policy::authorize(pool, user_id, Resource::Issue, issue_id, Action::Write)
         .await
         .map_err(|err| {
-            if let IdentityError::Database(error) = &err {
-                tracing::error!(
-                    ?error,
-                    %organization_id,
-                    %issue_id,
-                    "failed to authorize issue access"
-                );
-            } else {
-                warn!(
-                    ?err,
-                    %organization_id,
-                    %issue_id,
-                    %user_id,
-                    "issue access denied"
-                );
+            if matches!(err, PolicyError::Denied) {
+                warn!(%issue_id, %user_id, "issue access denied");
             }
-            membership_error(err, "issue not accessible")
-        })?;
-
-    Ok(organization_id)
+            policy_error(err, "issue not accessible")
+        })
 }
@@ -2,6 +2,7 @@ use axum::{
     Json, Router,
     extract::{Path, State},
     http::StatusCode,
+    middleware,
     response::IntoResponse,
     routing::{delete, get, patch, post},
 };
@@ -14,19 +15,36 @@ use uuid::Uuid;
 use super::error::ErrorResponse;
 use crate::{
     AppState,
-    auth::RequestContext,
-    db::{
-        identity_errors::IdentityError, organization_members, organizations::OrganizationRepository,
+    auth::{
+        RequestContext,
+        policy::{self, Action, Resource, Rule},
     },
+    db::{identity_errors::IdentityError, organizations::OrganizationRepository},
 };
 
-pub fn router() -> Router<AppState> {
+pub fn router(state: &AppState) -> Router<AppState> {
+    let rule = |action| {
+        middleware::from_fn_with_state(
+            Rule::new(state, Resource::Organization, action, "org_id"),
+            policy::require,
+        )
+    };
+
     Router::new()
         .route("/organizations", post(create_organization))
         .route("/organizations", get(list_organizations))
-        .route("/organizations/{org_id}", get(get_organization))
-        .route("/organizations/{org_id}", patch(update_organization))
-        .route("/organizations/{org_id}", delete(delete_organization))
+        .route(
+            "/organizations/{org_id}",
+            get(get_organization).route_layer(rule(Action::Read)),
+        )
+        .route(
+            "/organizations/{org_id}",
+            patch(update_organization).route_layer(rule(Action::Manage)),
+        )
+        .route(
+            "/organizations/{org_id}",
+            delete(delete_organization).route_layer(rule(Action::Manage)),
+        )
 }
 
 pub async fn create_organization(
@@ -100,15 +118,6 @@ pub async fn get_organization(
 ) -> Result<impl IntoResponse, ErrorResponse> {
     let org_repo = OrganizationRepository::new(&state.pool);
 
-    organization_members::assert_membership(&state.pool, org_id, ctx.user.id)
-        .await
-        .map_err(|e| match e {
-            IdentityError::NotFound => {
-                ErrorResponse::new(StatusCode::NOT_FOUND, "Organization not found")
-            }
-            _ => ErrorResponse::new(StatusCode::FORBIDDEN, "Access denied"),
-        })?;
-
     let organization = org_repo.fetch_organization(org_id).await.map_err(|_| {
         ErrorResponse::new(
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -0,0 +1,70 @@
+use axum::{
+    Json, Router,
+    extract::{Extension, Query, State},
+    http::StatusCode,
+    routing::get,
+};
+use tracing::instrument;
+use utils::api::organizations::{PermissionCheckQuery, PermissionCheckResponse};
+
+use super::error::{ErrorResponse, policy_error};
+use crate::{
+    AppState,
+    auth::{
+        RequestContext,
+        policy::{self, Action},
+    },
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/permissions/check", get(check_permission))
+}
+
+/// Answers "can user X do Y on Z" so clients can hide actions the policy would reject.
+/// Checking someone other than the caller requires permission to manage the resource.
+#[instrument(
+    name = "permissions.check",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id)
+)]
+async fn check_permission(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<PermissionCheckQuery>,
+) -> Result<Json<PermissionCheckResponse>, ErrorResponse> {
+    let target_user = query.user_id.unwrap_or(ctx.user.id);
+    if target_user != ctx.user.id {
+        policy::authorize(
+            state.pool(),
+            ctx.user.id,
+            query.resource_type,
+            query.resource_id,
+            Action::Manage,
+        )
+        .await
+        .map_err(|err| policy_error(err, "Admin access required to check other users"))?;
+    }
+
+    let decision = policy::evaluate(
+        state.pool(),
+        target_user,
+        query.resource_type,
+        query.resource_id,
+        query.action,
+    )
+    .await
+    .map_err(|err| policy_error(err, "Access denied"))?;
+
+    // Don't reveal that a resource exists to users outside its organization
+    if decision.role.is_none() && target_user == ctx.user.id {
+        return Err(ErrorResponse::new(
+            StatusCode::NOT_FOUND,
+            "resource not found",
+        ));
+    }
+
+    Ok(Json(PermissionCheckResponse {
+        allowed: decision.allowed,
+        role: decision.role,
+    }))
+}
@@ -0,0 +1,819 @@
+//! SCIM 2.0 provisioning (RFC 7643/7644) for enterprise self-hosting.
+//!
+//! Users and groups pushed by the identity provider are provisioned into the
+//! organization configured with `SCIM_ORGANIZATION_ID`. Active users become
+//! members, users in one of `SCIM_ADMIN_GROUPS` become admins, and deactivated
+//! or deleted users lose their membership and all sessions.
+
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{Request, StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use axum_extra::headers::{Authorization, HeaderMapExt, authorization::Bearer};
+use chrono::{DateTime, Utc};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use subtle::ConstantTimeEq;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    config::ScimConfig,
+    db::{
+        auth::AuthSessionRepository,
+        organization_members::MemberRole,
+        scim::{ProvisionUser, ScimDbError, ScimGroup, ScimRepository, ScimUser},
+    },
+};
+
+const USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+const GROUP_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+const LIST_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+const ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+/// Reason recorded for refresh tokens revoked when a user is deprovisioned
+const DEPROVISIONED_REASON: &str = "scim_deprovisioned";
+
+pub fn router(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route("/scim/v2/Users", get(list_users).post(create_user))
+        .route(
+            "/scim/v2/Users/{user_id}",
+            get(get_user)
+                .put(replace_user)
+                .patch(patch_user)
+                .delete(delete_user),
+        )
+        .route("/scim/v2/Groups", get(list_groups).post(create_group))
+        .route(
+            "/scim/v2/Groups/{group_id}",
+            get(get_group).patch(patch_group).delete(delete_group),
+        )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_scim_token,
+        ))
+}
+
+async fn require_scim_token(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(scim) = state.config().scim.as_ref() else {
+        return ScimError::new(StatusCode::NOT_FOUND, "SCIM provisioning is not enabled")
+            .into_response();
+    };
+
+    let authorized = req
+        .headers()
+        .typed_get::<Authorization<Bearer>>()
+        .is_some_and(|Authorization(bearer)| {
+            bool::from(
+                bearer
+                    .token()
+                    .as_bytes()
+                    .ct_eq(scim.bearer_token.expose_secret().as_bytes()),
+            )
+        });
+    if !authorized {
+        return ScimError::new(StatusCode::UNAUTHORIZED, "invalid SCIM bearer token")
+            .into_response();
+    }
+
+    next.run(req).await
+}
+
+#[derive(Debug)]
+pub struct ScimError {
+    status: StatusCode,
+    scim_type: Option<&'static str>,
+    detail: String,
+}
+
+impl ScimError {
+    fn new(status: StatusCode, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            scim_type: None,
+            detail: detail.into(),
+        }
+    }
+
+    fn bad_request(scim_type: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            scim_type: Some(scim_type),
+            detail: detail.into(),
+        }
+    }
+}
+
+impl From<ScimDbError> for ScimError {
+    fn from(error: ScimDbError) -> Self {
+        match error {
+            ScimDbError::NotFound => Self::new(StatusCode::NOT_FOUND, "resource not found"),
+            ScimDbError::Conflict(detail) => Self {
+                status: StatusCode::CONFLICT,
+                scim_type: Some("uniqueness"),
+                detail,
+            },
+            ScimDbError::Database(error) => {
+                tracing::error!(?error, "SCIM database error");
+                Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+            }
+        }
+    }
+}
+
+impl IntoResponse for ScimError {
+    fn into_response(self) -> Response {
+        let mut body = json!({
+            "schemas": [ERROR_SCHEMA],
+            "status": self.status.as_u16().to_string(),
+            "detail": self.detail,
+        });
+        if let Some(scim_type) = self.scim_type {
+            body["scimType"] = json!(scim_type);
+        }
+        scim_json(self.status, body)
+    }
+}
+
+fn scim_json(status: StatusCode, body: impl Serialize) -> Response {
+    (
+        status,
+        [(header::CONTENT_TYPE, SCIM_CONTENT_TYPE)],
+        Json(body),
+    )
+        .into_response()
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimName {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScimEmail {
+    pub value: String,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserRequest {
+    pub user_name: String,
+    #[serde(default)]
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub name: Option<ScimName>,
+    #[serde(default)]
+    pub emails: Vec<ScimEmail>,
+    #[serde(default)]
+    pub active: Option<bool>,
+}
+
+impl ScimUserRequest {
+    fn to_provision(&self) -> Result<ProvisionUser<'_>, ScimError> {
+        let email = self
+            .emails
+            .iter()
+            .find(|email| email.primary)
+            .or_else(|| self.emails.first())
+            .map(|email| email.value.as_str())
+            .unwrap_or(self.user_name.as_str())
+            .trim();
+        if !email.contains('@') {
+            return Err(ScimError::bad_request(
+                "invalidValue",
+                "an email address is required in emails or userName",
+            ));
+        }
+
+        let name = self.name.as_ref();
+        Ok(ProvisionUser {
+            email,
+            username: Some(self.user_name.trim()).filter(|u| !u.is_empty()),
+            first_name: name.and_then(|n| n.given_name.as_deref()),
+            last_name: name.and_then(|n| n.family_name.as_deref()),
+            external_id: self.external_id.as_deref(),
+            active: self.active.unwrap_or(true),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScimReference {
+    pub value: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimMeta {
+    pub resource_type: &'static str,
+    pub created: DateTime<Utc>,
+    pub last_modified: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimUserResource {
+    pub schemas: [&'static str; 1],
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    pub user_name: String,
+    pub name: ScimName,
+    pub emails: Vec<ScimEmail>,
+    pub active: bool,
+    pub groups: Vec<ScimReference>,
+    pub meta: ScimMeta,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroupRequest {
+    pub display_name: String,
+    #[serde(default)]
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub members: Vec<ScimReference>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimGroupResource {
+    pub schemas: [&'static str; 1],
+    pub id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    pub display_name: String,
+    pub members: Vec<ScimReference>,
+    pub meta: ScimMeta,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListResponse<T> {
+    pub schemas: [&'static str; 1],
+    pub total_results: usize,
+    pub start_index: usize,
+    pub items_per_page: usize,
+    #[serde(rename = "Resources")]
+    pub resources: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScimListQuery {
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// 1-based index of the first result
+    #[serde(default)]
+    pub start_index: Option<usize>,
+    #[serde(default)]
+    pub count: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScimPatchRequest {
+    #[serde(rename = "Operations")]
+    pub operations: Vec<ScimPatchOperation>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScimPatchOperation {
+    pub op: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub value: Option<Value>,
+}
+
+/// Value of an `<attribute> eq "<value>"` filter, the only filter identity
+/// providers use to look up existing resources
+fn equality_filter<'a>(
+    filter: Option<&'a str>,
+    attribute: &str,
+) -> Result<Option<&'a str>, ScimError> {
+    let Some(filter) = filter.map(str::trim).filter(|f| !f.is_empty()) else {
+        return Ok(None);
+    };
+    let mut parts = filter.splitn(3, ' ');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(attr), Some(op), Some(value))
+            if attr.eq_ignore_ascii_case(attribute) && op.eq_ignore_ascii_case("eq") =>
+        {
+            Ok(Some(value.trim().trim_matches('"')))
+        }
+        _ => Err(ScimError::bad_request(
+            "invalidFilter",
+            format!("only `{attribute} eq \"value\"` filters are supported"),
+        )),
+    }
+}
+
+fn paginate<T>(items: Vec<T>, query: &ScimListQuery) -> ScimListResponse<T> {
+    let total_results = items.len();
+    let start_index = query.start_index.unwrap_or(1).max(1);
+    let resources: Vec<T> = items
+        .into_iter()
+        .skip(start_index - 1)
+        .take(query.count.unwrap_or(usize::MAX))
+        .collect();
+    ScimListResponse {
+        schemas: [LIST_RESPONSE_SCHEMA],
+        total_results,
+        start_index,
+        items_per_page: resources.len(),
+        resources,
+    }
+}
+
+/// Accepts booleans and the `"True"`/`"False"` strings some identity providers send
+fn patch_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => s.parse::<bool>().ok().or(match s.as_str() {
+            "True" => Some(true),
+            "False" => Some(false),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Member ids from a `members` value (`[{ "value": "<id>" }]`) or a
+/// `members[value eq "<id>"]` path
+fn patch_member_ids(op: &ScimPatchOperation) -> Result<Vec<Uuid>, ScimError> {
+    let invalid = || ScimError::bad_request("invalidValue", "invalid member reference");
+    let path = op.path.as_deref().unwrap_or_default();
+
+    if let Some(filter) = path
+        .strip_prefix("members[")
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        let id = equality_filter(Some(filter), "value")?.ok_or_else(invalid)?;
+        return Ok(vec![id.parse().map_err(|_| invalid())?]);
+    }
+
+    match &op.value {
+        Some(value) => {
+            let members = value.get("members").unwrap_or(value);
+            serde_json::from_value::<Vec<ScimReference>>(members.clone())
+                .map(|refs| refs.into_iter().map(|r| r.value).collect())
+                .map_err(|_| invalid())
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+fn scim_config(state: &AppState) -> &ScimConfig {
+    state
+        .config()
+        .scim
+        .as_ref()
+        .expect("SCIM routes are only reachable when SCIM is configured")
+}
+
+/// Apply the user's provisioning state to the organization membership.
+/// Deactivated users are removed and signed out everywhere.
+async fn sync_user(state: &AppState, user: &ScimUser) -> Result<(), ScimError> {
+    let config = scim_config(state);
+    let repo = ScimRepository::new(state.pool());
+
+    let role = if user.active {
+        let groups = repo.user_groups(user.id).await?;
+        Some(
+            if groups.iter().any(|g| config.is_admin_group(&g.group_name)) {
+                MemberRole::Admin
+            } else {
+                MemberRole::Member
+            },
+        )
+    } else {
+        None
+    };
+    repo.sync_membership(config.organization_id, user.id, role)
+        .await?;
+
+    if !user.active {
+        AuthSessionRepository::new(state.pool())
+            .revoke_all_user_sessions(user.id, DEPROVISIONED_REASON)
+            .await
+            .map_err(|error| {
+                tracing::error!(?error, user_id = %user.id, "failed to revoke sessions");
+                ScimError::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+            })?;
+    }
+    Ok(())
+}
+
+async fn sync_users(state: &AppState, user_ids: &[Uuid]) -> Result<(), ScimError> {
+    let repo = ScimRepository::new(state.pool());
+    for user_id in user_ids {
+        match repo.find_user(*user_id).await {
+            Ok(user) => sync_user(state, &user).await?,
+            Err(ScimDbError::NotFound) => continue,
+            Err(error) => return Err(error.into()),
+        }
+    }
+    Ok(())
+}
+
+async fn user_resource(state: &AppState, user: ScimUser) -> Result<ScimUserResource, ScimError> {
+    let groups = ScimRepository::new(state.pool())
+        .user_groups(user.id)
+        .await?
+        .into_iter()
+        .map(|g| ScimReference {
+            value: g.group_id,
+            display: Some(g.group_name),
+        })
+        .collect();
+
+    Ok(ScimUserResource {
+        schemas: [USER_SCHEMA],
+        id: user.id,
+        external_id: user.external_id,
+        user_name: user.username.unwrap_or_else(|| user.email.clone()),
+        name: ScimName {
+            given_name: user.first_name,
+            family_name: user.last_name,
+        },
+        emails: vec![ScimEmail {
+            value: user.email,
+            primary: true,
+        }],
+        active: user.active,
+        groups,
+        meta: ScimMeta {
+            resource_type: "User",
+            created: user.created_at,
+            last_modified: user.updated_at,
+        },
+    })
+}
+
+async fn group_resource(
+    state: &AppState,
+    group: ScimGroup,
+) -> Result<ScimGroupResource, ScimError> {
+    let members = ScimRepository::new(state.pool())
+        .group_members(group.id)
+        .await?
+        .into_iter()
+        .map(|m| ScimReference {
+            value: m.user_id,
+            display: Some(m.email),
+        })
+        .collect();
+
+    Ok(ScimGroupResource {
+        schemas: [GROUP_SCHEMA],
+        id: group.id,
+        external_id: group.external_id,
+        display_name: group.display_name,
+        members,
+        meta: ScimMeta {
+            resource_type: "Group",
+            created: group.created_at,
+            last_modified: group.updated_at,
+        },
+    })
+}
+
+#[instrument(name = "scim.list_users", skip(state))]
+async fn list_users(
+    State(state): State<AppState>,
+    Query(query): Query<ScimListQuery>,
+) -> Result<Response, ScimError> {
+    let user_name = equality_filter(query.filter.as_deref(), "userName")?;
+    let users = ScimRepository::new(state.pool())
+        .list_users(user_name)
+        .await?;
+
+    let mut resources = Vec::with_capacity(users.len());
+    for user in users {
+        resources.push(user_resource(&state, user).await?);
+    }
+    Ok(scim_json(StatusCode::OK, paginate(resources, &query)))
+}
+
+#[instrument(name = "scim.create_user", skip(state, payload))]
+async fn create_user(
+    State(state): State<AppState>,
+    Json(payload): Json<ScimUserRequest>,
+) -> Result<Response, ScimError> {
+    let user = ScimRepository::new(state.pool())
+        .create_user(&payload.to_provision()?)
+        .await?;
+    sync_user(&state, &user).await?;
+
+    Ok(scim_json(
+        StatusCode::CREATED,
+        user_resource(&state, user).await?,
+    ))
+}
+
+#[instrument(name = "scim.get_user", skip(state))]
+async fn get_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Response, ScimError> {
+    let user = ScimRepository::new(state.pool()).find_user(user_id).await?;
+    Ok(scim_json(
+        StatusCode::OK,
+        user_resource(&state, user).await?,
+    ))
+}
+
+#[instrument(name = "scim.replace_user", skip(state, payload))]
+async fn replace_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<ScimUserRequest>,
+) -> Result<Response, ScimError> {
+    let user = ScimRepository::new(state.pool())
+        .update_user(user_id, &payload.to_provision()?)
+        .await?;
+    sync_user(&state, &user).await?;
+
+    Ok(scim_json(
+        StatusCode::OK,
+        user_resource(&state, user).await?,
+    ))
+}
+
+/// Only `active` can be patched; identity providers use it to suspend and restore users
+#[instrument(name = "scim.patch_user", skip(state, payload))]
+async fn patch_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<ScimPatchRequest>,
+) -> Result<Response, ScimError> {
+    let repo = ScimRepository::new(state.pool());
+    let mut active = None;
+
+    for op in &payload.operations {
+        if !op.op.eq_ignore_ascii_case("replace") && !op.op.eq_ignore_ascii_case("add") {
+            return Err(ScimError::bad_request(
+                "invalidSyntax",
+                format!("unsupported operation `{}`", op.op),
+            ));
+        }
+        let value = match op.path.as_deref() {
+            Some(path) if path.eq_ignore_ascii_case("active") => op.value.as_ref(),
+            Some(path) => {
+                return Err(ScimError::bad_request(
+                    "invalidPath",
+                    format!("attribute `{path}` cannot be patched"),
+                ));
+            }
+            None => op.value.as_ref().and_then(|v| v.get("active")),
+        };
+        active =
+            Some(value.and_then(patch_bool).ok_or_else(|| {
+                ScimError::bad_request("invalidValue", "`active` must be a boolean")
+            })?);
+    }
+
+    if let Some(active) = active {
+        repo.set_active(user_id, active).await?;
+    }
+    let user = repo.find_user(user_id).await?;
+    sync_user(&state, &user).await?;
+
+    Ok(scim_json(
+        StatusCode::OK,
+        user_resource(&state, user).await?,
+    ))
+}
+
+/// Deprovision the user. The account is kept so their history stays intact.
+#[instrument(name = "scim.delete_user", skip(state))]
+async fn delete_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Result<StatusCode, ScimError> {
+    let repo = ScimRepository::new(state.pool());
+    repo.set_active(user_id, false).await?;
+    let user = repo.find_user(user_id).await?;
+    sync_user(&state, &user).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[instrument(name = "scim.list_groups", skip(state))]
+async fn list_groups(
+    State(state): State<AppState>,
+    Query(query): Query<ScimListQuery>,
+) -> Result<Response, ScimError> {
+    let display_name = equality_filter(query.filter.as_deref(), "displayName")?;
+    let groups = ScimRepository::new(state.pool()).list_groups().await?;
+
+    let mut resources = Vec::new();
+    for group in groups {
+        if display_name.is_some_and(|name| name != group.display_name) {
+            continue;
+        }
+        resources.push(group_resource(&state, group).await?);
+    }
+    Ok(scim_json(StatusCode::OK, paginate(resources, &query)))
+}
+
+#[instrument(name = "scim.create_group", skip(state, payload))]
+async fn create_group(
+    State(state): State<AppState>,
+    Json(payload): Json<ScimGroupRequest>,
+) -> Result<Response, ScimError> {
+    let repo = ScimRepository::new(state.pool());
+    let group = repo
+        .create_group(payload.display_name.trim(), payload.external_id.as_deref())
+        .await?;
+
+    let member_ids: Vec<Uuid> = payload.members.iter().map(|m| m.value).collect();
+    repo.add_group_members(group.id, &member_ids).await?;
+    sync_users(&state, &member_ids).await?;
+
+    Ok(scim_json(
+        StatusCode::CREATED,
+        group_resource(&state, group).await?,
+    ))
+}
+
+#[instrument(name = "scim.get_group", skip(state))]
+async fn get_group(
+    State(state): State<AppState>,
+    Path(group_id): Path<Uuid>,
+) -> Result<Response, ScimError> {
+    let group = ScimRepository::new(state.pool())
+        .find_group(group_id)
+        .await?;
+    Ok(scim_json(
+        StatusCode::OK,
+        group_resource(&state, group).await?,
+    ))
+}
+
+#[instrument(name = "scim.patch_group", skip(state, payload))]
+async fn patch_group(
+    State(state): State<AppState>,
+    Path(group_id): Path<Uuid>,
+    Json(payload): Json<ScimPatchRequest>,
+) -> Result<Response, ScimError> {
+    let repo = ScimRepository::new(state.pool());
+    let mut group = repo.find_group(group_id).await?;
+    let was_admin_group = scim_config(&state).is_admin_group(&group.display_name);
+    let mut affected: Vec<Uuid> = Vec::new();
+
+    for op in &payload.operations {
+        let path = op.path.as_deref().unwrap_or_default();
+        let is_members = path.is_empty() || path.starts_with("members");
+
+        match op.op.to_ascii_lowercase().as_str() {
+            "add" if is_members => {
+                let ids = patch_member_ids(op)?;
+                repo.add_group_members(group.id, &ids).await?;
+                affected.extend(ids);
+            }
+            "remove" if is_members => {
+                let ids = match patch_member_ids(op)? {
+                    // Removing `members` without a filter clears the group
+                    ids if ids.is_empty() => repo
+                        .group_members(group.id)
+                        .await?
+                        .into_iter()
+                        .map(|m| m.user_id)
+                        .collect(),
+                    ids => ids,
+                };
+                repo.remove_group_members(group.id, &ids).await?;
+                affected.extend(ids);
+            }
+            "replace" if path.eq_ignore_ascii_case("displayName") => {
+                let name = op.value.as_ref().and_then(Value::as_str).ok_or_else(|| {
+                    ScimError::bad_request("invalidValue", "`displayName` must be a string")
+                })?;
+                group = repo.rename_group(group.id, name.trim()).await?;
+            }
+            "replace" if path.eq_ignore_ascii_case("members") => {
+                let current: Vec<Uuid> = repo
+                    .group_members(group.id)
+                    .await?
+                    .into_iter()
+                    .map(|m| m.user_id)
+                    .collect();
+                let ids = patch_member_ids(op)?;
+                repo.remove_group_members(group.id, &current).await?;
+                repo.add_group_members(group.id, &ids).await?;
+                affected.extend(current);
+                affected.extend(ids);
+            }
+            "replace" if path.is_empty() => {
+                if let Some(name) = op
+                    .value
+                    .as_ref()
+                    .and_then(|v| v.get("displayName"))
+                    .and_then(Value::as_str)
+                {
+                    group = repo.rename_group(group.id, name.trim()).await?;
+                }
+            }
+            other => {
+                return Err(ScimError::bad_request(
+                    "invalidPath",
+                    format!("unsupported `{other}` operation on `{path}`"),
+                ));
+            }
+        }
+    }
+
+    // A rename can turn the group into (or out of) an admin group
+    if was_admin_group != scim_config(&state).is_admin_group(&group.display_name) {
+        affected.extend(
+            repo.group_members(group.id)
+                .await?
+                .into_iter()
+                .map(|m| m.user_id),
+        );
+    }
+    affected.sort();
+    affected.dedup();
+    sync_users(&state, &affected).await?;
+
+    Ok(scim_json(
+        StatusCode::OK,
+        group_resource(&state, group).await?,
+    ))
+}
+
+#[instrument(name = "scim.delete_group", skip(state))]
+async fn delete_group(
+    State(state): State<AppState>,
+    Path(group_id): Path<Uuid>,
+) -> Result<StatusCode, ScimError> {
+    let members = ScimRepository::new(state.pool())
+        .delete_group(group_id)
+        .await?;
+    sync_users(&state, &members).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_equality_filters() {
+        assert_eq!(
+            equality_filter(Some(r#"userName eq "ada@example.com""#), "userName").unwrap(),
+            Some("ada@example.com")
+        );
+        assert_eq!(equality_filter(None, "userName").unwrap(), None);
+        assert!(equality_filter(Some(r#"userName co "ada""#), "userName").is_err());
+        assert!(equality_filter(Some(r#"emails eq "ada""#), "userName").is_err());
+    }
+
+    #[test]
+    fn reads_member_ids_from_path_or_value() {
+        let id = Uuid::new_v4();
+        let by_path = ScimPatchOperation {
+            op: "remove".to_string(),
+            path: Some(format!(r#"members[value eq "{id}"]"#)),
+            value: None,
+        };
+        assert_eq!(patch_member_ids(&by_path).unwrap(), vec![id]);
+
+        let by_value = ScimPatchOperation {
+            op: "add".to_string(),
+            path: Some("members".to_string()),
+            value: Some(json!([{ "value": id }])),
+        };
+        assert_eq!(patch_member_ids(&by_value).unwrap(), vec![id]);
+    }
+
+    #[test]
+    fn accepts_string_booleans() {
+        assert_eq!(patch_bool(&json!(false)), Some(false));
+        assert_eq!(patch_bool(&json!("False")), Some(false));
+        assert_eq!(patch_bool(&json!("true")), Some(true));
+        assert_eq!(patch_bool(&json!("no")), None);
+    }
+}
@@ -0,0 +1,95 @@
+use axum::{
+    Json, Router,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+};
+use tracing::instrument;
+use utils::api::oauth::{AuthSessionInfo, ListSessionsResponse, RevokeSessionsResponse};
+use uuid::Uuid;
+
+use super::error::ErrorResponse;
+use crate::{
+    AppState,
+    auth::RequestContext,
+    db::auth::{AuthSessionError, AuthSessionRepository, SIGN_OUT_EVERYWHERE_REASON},
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/me/sessions", get(list_sessions))
+        .route("/me/sessions/revoke-all", post(revoke_all_sessions))
+        .route("/me/sessions/{session_id}", delete(revoke_session))
+}
+
+#[instrument(name = "sessions.list", skip(state, ctx), fields(user_id = %ctx.user.id))]
+async fn list_sessions(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<Json<ListSessionsResponse>, ErrorResponse> {
+    let sessions = AuthSessionRepository::new(state.pool())
+        .list_active_for_user(ctx.user.id)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to list auth sessions");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "failed to list sessions")
+        })?
+        .into_iter()
+        .map(|session| AuthSessionInfo {
+            current: session.id == ctx.session_id,
+            id: session.id,
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            created_at: session.created_at,
+            last_used_at: session.last_used_at,
+        })
+        .collect();
+
+    Ok(Json(ListSessionsResponse { sessions }))
+}
+
+#[instrument(
+    name = "sessions.revoke",
+    skip(state, ctx),
+    fields(user_id = %ctx.user.id, session_id = %session_id)
+)]
+async fn revoke_session(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Path(session_id): Path<Uuid>,
+) -> Result<StatusCode, ErrorResponse> {
+    match AuthSessionRepository::new(state.pool())
+        .revoke_for_user(session_id, ctx.user.id)
+        .await
+    {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(AuthSessionError::NotFound) => Err(ErrorResponse::new(
+            StatusCode::NOT_FOUND,
+            "session not found",
+        )),
+        Err(error) => {
+            tracing::error!(?error, "failed to revoke auth session");
+            Err(ErrorResponse::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "internal server error",
+            ))
+        }
+    }
+}
+
+/// Sign out everywhere, including the session making the request
+#[instrument(name = "sessions.revoke_all", skip(state, ctx), fields(user_id = %ctx.user.id))]
+async fn revoke_all_sessions(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+) -> Result<Json<RevokeSessionsResponse>, ErrorResponse> {
+    let revoked = AuthSessionRepository::new(state.pool())
+        .revoke_all_user_sessions(ctx.user.id, SIGN_OUT_EVERYWHERE_REASON)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, "failed to revoke all auth sessions");
+            ErrorResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+        })?;
+
+    Ok(Json(RevokeSessionsResponse { revoked }))
+}
@@ -12,7 +12,7 @@ use crate::{
     AppState,
     auth::{JwtError, OAuthTokenValidationError},
     db::{
-        auth::{AuthSessionError, AuthSessionRepository},
+        auth::{AuthSessionError, AuthSessionRepository, TOKEN_REUSE_REASON},
         identity_errors::IdentityError,
         oauth_accounts::OAuthAccountError,
         users::UserRepository,
@@ -90,7 +90,7 @@ pub async fn refresh_token(
     {
         // Token was reused, revoke all user sessions as a security measure
         let revoked_count = session_repo
-            .revoke_all_user_sessions(token_details.user_id)
+            .revoke_all_user_sessions(token_details.user_id, TOKEN_REUSE_REASON)
             .await?;
         warn!(
             user_id = %token_details.user_id,
@@ -126,7 +126,7 @@ pub async fn refresh_token(
         Ok(_) => {}
         Err(AuthSessionError::TokenReuseDetected) => {
             let revoked_count = session_repo
-                .revoke_all_user_sessions(token_details.user_id)
+                .revoke_all_user_sessions(token_details.user_id, TOKEN_REUSE_REASON)
                 .await?;
             warn!(
                 user_id = %token_details.user_id,
@@ -13,6 +13,8 @@ fn generate_types_content() -> String {
     let decls: Vec<String> = vec![
         db::models::project::Project::decl(),
         db::models::project::CreateProject::decl(),
+        db::models::project::CloneProject::decl(),
+        db::models::project::CreateProjectFromTemplate::decl(),
         db::models::project::UpdateProject::decl(),
         db::models::project::SearchResult::decl(),
         db::models::project::SearchMatchType::decl(),
@@ -27,11 +29,38 @@ fn generate_types_content() -> String {
         db::models::tag::CreateTag::decl(),
         db::models::tag::UpdateTag::decl(),
         db::models::task::TaskStatus::decl(),
+        db::models::task::TaskPriority::decl(),
         db::models::task::Task::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
         db::models::task::TaskRelationships::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
+        db::models::project_seed_document::ProjectSeedDocument::decl(),
+        db::models::project_seed_document::UpsertProjectSeedDocument::decl(),
+        db::models::task_package_scope::TaskPackageScope::decl(),
+        db::models::task_package_scope::SetTaskPackageScope::decl(),
+        db::models::task_document::TaskDocument::decl(),
+        db::models::wip_limit::WipLimitScope::decl(),
+        db::models::wip_limit::WipLimit::decl(),
+        db::models::wip_limit::SetWipLimit::decl(),
+        db::models::wip_limit::WipLimitViolation::decl(),
+        db::models::wip_limit::WipLimitOverride::decl(),
+        db::models::project_webhook::WebhookEvent::decl(),
+        db::models::project_webhook::ProjectWebhook::decl(),
+        db::models::project_webhook::CreateProjectWebhook::decl(),
+        db::models::project_webhook::UpdateProjectWebhook::decl(),
+        services::services::webhooks::WebhookPayload::decl(),
+        db::models::worker::Worker::decl(),
+        db::models::worker_requirement::WorkerRequirement::decl(),
+        db::models::worker_requirement::SetWorkerRequirement::decl(),
+        db::models::preview_build::PreviewBuildStatus::decl(),
+        db::models::preview_build::PreviewBuild::decl(),
+        db::models::preview_screenshot::PreviewScreenshot::decl(),
+        db::models::preview_comparison::PreviewComparison::decl(),
+        db::models::e2e_run::E2eRunStatus::decl(),
+        db::models::e2e_run::E2eTestStatus::decl(),
+        db::models::e2e_run::E2eRun::decl(),
+        db::models::e2e_run::E2eTestCase::decl(),
         db::models::scratch::DraftFollowUpData::decl(),
         db::models::scratch::DraftWorkspaceData::decl(),
         db::models::scratch::DraftWorkspaceRepo::decl(),
@@ -62,10 +91,15 @@ fn generate_types_content() -> String {
         utils::diff::Diff::decl(),
         utils::diff::DiffChangeKind::decl(),
         utils::response::ApiResponse::<()>::decl(),
+        utils::response::ApiWarning::decl(),
+        utils::timezone::LocalizedTime::decl(),
         utils::api::oauth::LoginStatus::decl(),
         utils::api::oauth::ProfileResponse::decl(),
         utils::api::oauth::ProviderProfile::decl(),
         utils::api::oauth::StatusResponse::decl(),
+        utils::api::oauth::AuthSessionInfo::decl(),
+        utils::api::oauth::ListSessionsResponse::decl(),
+        utils::api::oauth::RevokeSessionsResponse::decl(),
         utils::api::organizations::MemberRole::decl(),
         utils::api::organizations::InvitationStatus::decl(),
         utils::api::organizations::Organization::decl(),
@@ -87,11 +121,53 @@ fn generate_types_content() -> String {
         utils::api::organizations::ListMembersResponse::decl(),
         utils::api::organizations::UpdateMemberRoleRequest::decl(),
         utils::api::organizations::UpdateMemberRoleResponse::decl(),
+        utils::api::organizations::PermissionResource::decl(),
+        utils::api::organizations::PermissionAction::decl(),
+        utils::api::organizations::PermissionCheckQuery::decl(),
+        utils::api::organizations::PermissionCheckResponse::decl(),
+        utils::api::organizations::AccessPrincipal::decl(),
+        utils::api::organizations::AccessReportEntry::decl(),
+        utils::api::organizations::AccessReportResponse::decl(),
         utils::api::projects::RemoteProject::decl(),
         utils::api::projects::ListProjectsResponse::decl(),
         utils::api::projects::RemoteProjectMembersResponse::decl(),
         server::routes::projects::CreateRemoteProjectRequest::decl(),
         server::routes::projects::LinkToExistingRequest::decl(),
+        server::routes::projects::ProjectRepoPackages::decl(),
+        services::services::monorepo::PackageManager::decl(),
+        services::services::monorepo::WorkspacePackage::decl(),
+        services::services::dependency_updates::OutdatedDependency::decl(),
+        services::services::dependency_updates::DependencyUpdateReport::decl(),
+        server::routes::dependency_updates::DependencyUpdateRun::decl(),
+        services::services::flaky_tests::FlakyTest::decl(),
+        services::services::flaky_tests::FlakyTestReport::decl(),
+        server::routes::flaky_tests::CreateFlakyTestTasksRequest::decl(),
+        db::models::analytics::DailyAttemptCount::decl(),
+        db::models::analytics::ExecutorRunStats::decl(),
+        services::services::analytics_overview::ExecutorSuccessRate::decl(),
+        services::services::analytics_overview::AnalyticsOverview::decl(),
+        server::routes::analytics::AnalyticsExportFormat::decl(),
+        server::routes::analytics::AnalyticsOverviewQuery::decl(),
+        services::services::telemetry::TelemetryEvent::decl(),
+        services::services::telemetry::TelemetryBatch::decl(),
+        server::routes::telemetry::TelemetryPreview::decl(),
+        services::services::retention::RetentionReport::decl(),
+        services::services::user_data::CommitIdentityUse::decl(),
+        services::services::user_data::UserDataExport::decl(),
+        services::services::user_data::UserDataErasure::decl(),
+        server::routes::user_data::UserDataRequest::decl(),
+        utils::logging::LogRotation::decl(),
+        server::routes::logging::LogFileInfo::decl(),
+        server::routes::logging::LoggingSettings::decl(),
+        server::routes::logging::UpdateLoggingSettings::decl(),
+        db::models::error_report::ErrorReport::decl(),
+        services::services::sql_console::SqlQuery::decl(),
+        services::services::sql_console::SqlQueryResult::decl(),
+        services::services::sql_console::SqlConsoleTable::decl(),
+        services::services::swimlanes::TaskGroupBy::decl(),
+        services::services::swimlanes::SwimlaneStatusCount::decl(),
+        services::services::swimlanes::Swimlane::decl(),
+        services::services::swimlanes::TaskBoard::decl(),
         server::routes::repo::RegisterRepoRequest::decl(),
         server::routes::repo::InitRepoRequest::decl(),
         server::routes::tags::TagSearchParams::decl(),
@@ -117,6 +193,9 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::OpenEditorRequest::decl(),
         server::routes::task_attempts::OpenEditorResponse::decl(),
         server::routes::tasks::CreateAndStartTaskRequest::decl(),
+        server::routes::tasks::TaskSort::decl(),
+        server::routes::task_documents::AttachTaskDocument::decl(),
+        server::routes::task_documents::RelatedTask::decl(),
         server::routes::task_attempts::pr::CreatePrApiRequest::decl(),
         server::routes::images::ImageResponse::decl(),
         server::routes::images::ImageMetadata::decl(),
@@ -137,6 +216,16 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::pr::PrCommentsResponse::decl(),
         server::routes::task_attempts::pr::GetPrCommentsError::decl(),
         server::routes::task_attempts::pr::GetPrCommentsQuery::decl(),
+        server::routes::task_attempts::preview_builds::PreviewBuildInfo::decl(),
+        server::routes::task_attempts::preview_builds::PreviewBuildServer::decl(),
+        services::services::preview_screenshot::Viewport::decl(),
+        server::routes::task_attempts::preview_screenshots::CapturePreviewScreenshotsRequest::decl(
+        ),
+        server::routes::task_attempts::preview_screenshots::CapturedPreviewScreenshots::decl(),
+        services::services::e2e_tests::E2eArtifactKind::decl(),
+        services::services::e2e_tests::E2eArtifact::decl(),
+        server::routes::task_attempts::e2e_runs::StartE2eRunRequest::decl(),
+        server::routes::task_attempts::e2e_runs::E2eRunDetails::decl(),
         services::services::git_host::UnifiedPrComment::decl(),
         services::services::git_host::ProviderKind::decl(),
         server::routes::task_attempts::RepoBranchStatus::decl(),
@@ -146,11 +235,83 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::workspace_summary::WorkspaceSummaryResponse::decl(),
         server::routes::task_attempts::workspace_summary::DiffStats::decl(),
         server::routes::documents::DocumentFileType::decl(),
+        server::routes::documents::DocumentExtensions::decl(),
+        server::routes::documents::UpdateDocumentExtensions::decl(),
+        server::routes::documents::DocumentReadOnlyPaths::decl(),
+        server::routes::document_roots::DocumentRoots::decl(),
+        server::routes::documents::DocumentReadOnlyViolation::decl(),
+        db::models::project::DocumentScanLimits::decl(),
+        server::routes::documents::UpdateDocumentScanLimits::decl(),
+        server::routes::documents::DocumentReadLimit::decl(),
+        server::routes::documents::UpdateDocumentReadLimit::decl(),
         server::routes::documents::DocumentMetadata::decl(),
+        services::services::markdown_html::DocumentHeading::decl(),
+        services::services::markdown_html::TextStats::decl(),
         server::routes::documents::DocumentContent::decl(),
         server::routes::documents::ListDocumentsResponse::decl(),
+        server::routes::documents::DocumentTreeNode::decl(),
+        server::routes::documents::DocumentTreeResponse::decl(),
         server::routes::documents::UpdateDocumentRequest::decl(),
+        server::routes::documents::UpdateFrontMatterRequest::decl(),
+        server::routes::document_task_sync::SyncDocumentTasksRequest::decl(),
+        server::routes::document_task_sync::SyncDocumentTasksResponse::decl(),
+        server::routes::documents::DocumentUpdateConflict::decl(),
         server::routes::documents::UpdateDocumentResponse::decl(),
+        server::routes::documents::DocumentCommitPreview::decl(),
+        server::routes::document_batch::BatchDocumentUpdate::decl(),
+        server::routes::document_batch::BatchUpdateDocumentsRequest::decl(),
+        server::routes::document_batch::BatchUpdatedDocument::decl(),
+        server::routes::document_batch::BatchUpdateDocumentsResponse::decl(),
+        server::routes::document_batch::BatchDocumentConflict::decl(),
+        server::routes::document_batch::BatchUpdateConflict::decl(),
+        server::routes::document_import::ImportDocumentsResponse::decl(),
+        server::routes::documents::CreateDocumentTaskRequest::decl(),
+        server::routes::documents::CreateDocumentTaskResponse::decl(),
+        services::services::action_items::ActionItem::decl(),
+        server::routes::document_action_items::ProposedActionItem::decl(),
+        server::routes::document_action_items::ActionItemsPreview::decl(),
+        server::routes::document_action_items::ApplyActionItemsRequest::decl(),
+        server::routes::document_action_items::ApplyActionItemsResponse::decl(),
+        server::routes::documents::DeleteDocumentResponse::decl(),
+        services::services::git::FileCommit::decl(),
+        services::services::git::PathChangeKind::decl(),
+        services::services::git::PathChange::decl(),
+        server::routes::documents::DocumentHistoryResponse::decl(),
+        server::routes::documents::DocumentDiffResponse::decl(),
+        server::routes::documents::DocumentHtml::decl(),
+        db::models::project::DocumentCodeReference::decl(),
+        server::routes::document_freshness::DocumentCodeReferences::decl(),
+        server::routes::document_freshness::DocumentReferenceSource::decl(),
+        server::routes::document_freshness::DocumentReference::decl(),
+        server::routes::document_freshness::DocumentFreshness::decl(),
+        server::routes::document_freshness::DocumentFreshnessOverview::decl(),
+        services::services::document_links::DocumentLinkKind::decl(),
+        server::routes::document_links::DocumentLinkEdge::decl(),
+        server::routes::document_links::DocumentBacklinks::decl(),
+        server::routes::document_links::DocumentLinkNode::decl(),
+        server::routes::document_links::DocumentLinkGraph::decl(),
+        server::routes::board_links::BrokenBoardLinkReason::decl(),
+        server::routes::board_links::BrokenBoardLink::decl(),
+        server::routes::board_links::BoardLinkSource::decl(),
+        server::routes::board_links::BrokenBoardLinks::decl(),
+        server::routes::board_links::BoardLinkReport::decl(),
+        db::models::project::DocumentHeadingStyle::decl(),
+        db::models::project::DocumentFormatOptions::decl(),
+        services::services::markdown_format::MarkdownLintRule::decl(),
+        services::services::markdown_format::MarkdownLintIssue::decl(),
+        server::routes::document_format::FormatDocumentRequest::decl(),
+        server::routes::document_format::FormatDocumentResponse::decl(),
+        server::routes::document_format::UpdateDocumentFormatOptions::decl(),
+        server::routes::health::VersionInfo::decl(),
+        services::services::self_update::UpdateCheck::decl(),
+        server::routes::self_update::UpdateInstallResponse::decl(),
+        server::routes::onboarding::OnboardingStepStatus::decl(),
+        server::routes::onboarding::OnboardingStatus::decl(),
+        server::routes::onboarding::SetGitIdentityRequest::decl(),
+        server::routes::onboarding::StartSampleAttemptRequest::decl(),
+        server::routes::onboarding::SampleAttemptResponse::decl(),
+        server::routes::demo::CreateDemoProject::decl(),
+        server::routes::demo::DemoProject::decl(),
         server::routes::documents::GetBranchResponse::decl(),
         server::routes::documents::ListBranchesResponse::decl(),
         server::routes::documents::BranchInfo::decl(),
@@ -158,11 +319,94 @@ fn generate_types_content() -> String {
         server::routes::documents::SwitchBranchResponse::decl(),
         server::routes::documents::CreateFolderRequest::decl(),
         server::routes::documents::CreateFolderResponse::decl(),
+        server::routes::documents::DeleteFolderResponse::decl(),
         server::routes::documents::CreateFileRequest::decl(),
         server::routes::documents::CreateFileResponse::decl(),
+        server::routes::documents::CopyDocumentRequest::decl(),
+        server::routes::documents::MoveDocumentRequest::decl(),
+        server::routes::documents::MoveFolderRequest::decl(),
+        server::routes::documents::MovedDocument::decl(),
+        server::routes::documents::MoveFolderResponse::decl(),
+        server::routes::documents::MoveDocumentResponse::decl(),
+        server::routes::documents::RefreshDocumentsRequest::decl(),
+        server::routes::documents::RefreshDocumentsResponse::decl(),
         server::routes::documents::SyncStatusResponse::decl(),
+        server::routes::document_sync_status::ProjectDocumentSyncStatus::decl(),
+        server::routes::timezones::ProjectTimezone::decl(),
+        server::routes::timezones::UpdateProjectTimezone::decl(),
+        services::services::project_quotas::ApiQuotaUsage::decl(),
+        services::services::project_quotas::BackgroundJobUsage::decl(),
+        server::routes::project_usage::ProjectUsage::decl(),
+        server::middleware::ProjectQuotaExceeded::decl(),
         server::routes::documents::SyncRequest::decl(),
         server::routes::documents::SyncResponse::decl(),
+        db::models::project::DocumentSyncStrategy::decl(),
+        server::routes::documents::UpdateDocumentSyncStrategy::decl(),
+        db::models::project::DocumentSymlinkPolicy::decl(),
+        server::routes::documents::UpdateDocumentSymlinkPolicy::decl(),
+        db::models::project::DocumentCommitSettings::decl(),
+        server::routes::document_commits::UpdateDocumentCommitSettings::decl(),
+        server::routes::document_commits::DocumentAutoCommit::decl(),
+        server::routes::document_branches::DocumentBranchEditing::decl(),
+        server::routes::document_branches::CreateDocumentPullRequest::decl(),
+        server::routes::document_branches::DocumentPullRequest::decl(),
+        server::routes::document_commits::CommitDocumentsRequest::decl(),
+        server::routes::document_commits::CommitDocumentsResponse::decl(),
+        server::routes::document_query::JsonValueType::decl(),
+        server::routes::document_query::JsonDocumentQueryResponse::decl(),
+        db::models::document_snapshot::DocumentSnapshot::decl(),
+        server::routes::document_snapshots::DocumentSnapshotDiff::decl(),
+        db::models::document_approval::DocumentApprovalRule::decl(),
+        db::models::document_approval::SetDocumentApprovalRule::decl(),
+        db::models::document_approval::DocumentChangeStatus::decl(),
+        db::models::document_approval::DocumentReviewDecision::decl(),
+        db::models::document_approval::DocumentChangeRequest::decl(),
+        db::models::document_approval::DocumentChangeReview::decl(),
+        server::routes::document_approvals::ReviewDocumentChangeRequest::decl(),
+        server::routes::document_approvals::DocumentChangeRequestDetails::decl(),
+        server::routes::document_assets::DocumentAssetResponse::decl(),
+        server::routes::document_assets::DocumentAssetsFolder::decl(),
+        server::routes::document_assets::UpdateDocumentAssetsFolder::decl(),
+        server::routes::document_templates::DocumentTemplate::decl(),
+        server::routes::document_templates::DocumentTemplatesFolder::decl(),
+        server::routes::document_templates::UpdateDocumentTemplatesFolder::decl(),
+        db::models::document_encryption::DocumentEncryptedFolder::decl(),
+        db::models::document_encryption::SetDocumentEncryptedFolder::decl(),
+        db::models::document_encryption::DocumentEncryptionKey::decl(),
+        server::routes::document_encryption::UpdateEncryptedFoldersResponse::decl(),
+        server::routes::document_encryption::RotateEncryptionKeyResponse::decl(),
+        db::models::document_lock::DocumentLock::decl(),
+        server::routes::document_locks::DocumentLockRequest::decl(),
+        server::routes::document_locks::ReleaseDocumentLockRequest::decl(),
+        db::models::document_draft::DocumentDraft::decl(),
+        server::routes::document_drafts::SaveDocumentDraftRequest::decl(),
+        server::routes::document_drafts::DocumentDraftRequest::decl(),
+        server::routes::document_drafts::PublishDocumentDraftRequest::decl(),
+        server::routes::document_drafts::RestoredDocumentDraft::decl(),
+        db::models::document_annotation::DocumentAnnotation::decl(),
+        server::routes::document_annotations::CreateDocumentAnnotationRequest::decl(),
+        server::routes::document_annotations::UpdateDocumentAnnotationRequest::decl(),
+        server::routes::document_annotations::LocatedDocumentAnnotation::decl(),
+        db::models::document_tag::DocumentTagCount::decl(),
+        server::routes::document_tags::SetDocumentTagsRequest::decl(),
+        server::routes::document_tags::DocumentTagRequest::decl(),
+        server::routes::document_tags::DocumentTags::decl(),
+        server::routes::document_recent::RecentDocument::decl(),
+        server::routes::document_recent::RecentDocumentsResponse::decl(),
+        server::routes::document_stats::DocumentStats::decl(),
+        server::routes::document_stats::DocumentStatsOverview::decl(),
+        services::services::git::ConflictSides::decl(),
+        server::routes::document_conflicts::SyncConflicts::decl(),
+        server::routes::document_conflicts::DocumentConflict::decl(),
+        server::routes::document_conflicts::ConflictResolution::decl(),
+        server::routes::document_conflicts::ResolveConflictRequest::decl(),
+        server::routes::document_conflicts::ContinueSyncResponse::decl(),
+        server::routes::workers::WorkerInfo::decl(),
+        server::routes::workers::RegisterWorkerRequest::decl(),
+        server::routes::workers::RegisterWorkerResponse::decl(),
+        server::routes::workers::SetWorkerDrainingRequest::decl(),
+        services::services::document_index::DocumentEventKind::decl(),
+        services::services::document_index::DocumentEvent::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
         services::services::filesystem::DirectoryListResponse::decl(),
         services::services::file_search::SearchMode::decl(),
@@ -176,6 +420,18 @@ fn generate_types_content() -> String {
         services::services::config::SoundFile::decl(),
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
+        services::services::config::DependencyUpdateConfig::decl(),
+        services::services::config::TaskAgingConfig::decl(),
+        services::services::config::DocumentSnapshotConfig::decl(),
+        services::services::config::TelemetryConfig::decl(),
+        services::services::config::OnboardingStep::decl(),
+        services::services::config::OnboardingProgress::decl(),
+        services::services::config::RetentionCategory::decl(),
+        services::services::config::RetentionConfig::decl(),
+        services::services::config::ProjectLimitsConfig::decl(),
+        services::services::config::ErrorReportConfig::decl(),
+        services::services::compliance_export::ExportManifestEntry::decl(),
+        services::services::compliance_export::ExportManifest::decl(),
         services::services::git::GitBranch::decl(),
         services::services::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
@@ -249,8 +505,9 @@ fn generate_types_content() -> String {
         .replace('\\', "\\\\")
         .replace('`', "\\`");
     let constants = format!(
-        "export const DEFAULT_PR_DESCRIPTION_PROMPT = `{}`;",
-        prompt_escaped
+        "export const DEFAULT_PR_DESCRIPTION_PROMPT = `{}`;\n\n{}",
+        prompt_escaped,
+        server::routes::paths::typescript_constants()
     );
 
     format!("{HEADER}\n\n{body}\n\n{constants}")
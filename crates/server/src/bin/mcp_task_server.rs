@@ -57,7 +57,13 @@ fn main() -> anyhow::Result<()> {
                     }
                 };
 
-                let url = format!("http://{}:{}", host, port);
+                // The backend serves HTTPS when a certificate is configured
+                let scheme = if std::env::var("VK_TLS_CERT").is_ok_and(|v| !v.trim().is_empty()) {
+                    "https"
+                } else {
+                    "http"
+                };
+                let url = format!("{}://{}:{}", scheme, host, port);
                 tracing::info!("[MCP] Using backend URL: {}", url);
                 url
             };
@@ -0,0 +1,363 @@
+//! Worker daemon: runs coding agents for a vibe-kanban server on this machine, which
+//! needs the agents' toolchains instead of the server host. Register the worker on the
+//! server to get its token, then start it with
+//!
+//! ```text
+//! VK_SERVER_URL=https://kanban.example.com VK_WORKER_TOKEN=vkw_... worker
+//! ```
+//!
+//! `VK_WORKER_DIR` sets where workspaces are checked out, a temporary directory by default.
+//! `VK_WORKER_CAPABILITIES` lists what the machine offers, comma-separated, e.g.
+//! `docker,gpu,node18`; the operating system (`linux`, `macos` or `windows`) is added.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use executors::{
+    actions::Executable,
+    approvals::NoopExecutorApprovalService,
+    env::{ExecutionEnv, RepoContext},
+    executors::ExecutorExitResult,
+};
+use futures_util::{FutureExt, SinkExt, StreamExt};
+use local_deployment::command::kill_process_group;
+use services::services::workers::{
+    self, HEARTBEAT_INTERVAL, RepoSnapshot, ServerMessage, WorkerError, WorkerMessage, WorkerRun,
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    sync::{mpsc, oneshot},
+};
+use tokio_tungstenite::tungstenite::{
+    Message,
+    client::IntoClientRequest,
+    http::{HeaderValue, header},
+    protocol::WebSocketConfig,
+};
+use tracing_subscriber::{EnvFilter, prelude::*};
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// Results carry whole git bundles of the workspace repositories
+const MAX_MESSAGE_BYTES: usize = 512 * 1024 * 1024;
+/// How long an agent gets to exit after being interrupted, before it is killed
+const INTERRUPT_GRACE: Duration = Duration::from_secs(5);
+
+fn main() -> anyhow::Result<()> {
+    // Install rustls crypto provider before any TLS operations
+    rustls::crypto::aws_lc_rs::default_provider()
+        .install_default()
+        .expect("Failed to install rustls crypto provider");
+
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run())
+}
+
+async fn run() -> anyhow::Result<()> {
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer())
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .init();
+
+    let server_url = std::env::var("VK_SERVER_URL")
+        .map_err(|_| anyhow::anyhow!("VK_SERVER_URL must be set to the server's URL"))?;
+    let token = std::env::var("VK_WORKER_TOKEN")
+        .map_err(|_| anyhow::anyhow!("VK_WORKER_TOKEN must be set to the worker's token"))?;
+    let work_dir = std::env::var("VK_WORKER_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| std::env::temp_dir().join("vibe-kanban-worker"));
+    let mut capabilities: Vec<String> = std::env::var("VK_WORKER_CAPABILITIES")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::to_string)
+        .collect();
+    capabilities.push(std::env::consts::OS.to_string());
+    let capabilities = workers::normalize_capabilities(&capabilities);
+    let url = connect_url(&server_url)?;
+    tracing::info!("Worker capabilities: {}", capabilities.join(", "));
+
+    loop {
+        match serve(&url, &token, &work_dir, &capabilities).await {
+            Ok(()) => tracing::info!("Server closed the connection"),
+            Err(e) => tracing::warn!("Connection to {} failed: {}", url, e),
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// WebSocket URL of the worker endpoint of the server at `server_url`
+fn connect_url(server_url: &str) -> anyhow::Result<String> {
+    let base = server_url.trim_end_matches('/');
+    let base = if let Some(rest) = base.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = base.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        anyhow::bail!("VK_SERVER_URL must start with http:// or https://");
+    };
+    Ok(format!(
+        "{base}{}{}",
+        server::routes::paths::API_PREFIX,
+        server::routes::paths::WORKERS_CONNECT
+    ))
+}
+
+/// Run executions sent over one connection until it closes
+async fn serve(
+    url: &str,
+    token: &str,
+    work_dir: &Path,
+    capabilities: &[String],
+) -> anyhow::Result<()> {
+    let mut request = url.into_client_request()?;
+    request.headers_mut().insert(
+        header::AUTHORIZATION,
+        HeaderValue::from_str(&format!("Bearer {token}"))?,
+    );
+    let config = WebSocketConfig::default()
+        .max_message_size(Some(MAX_MESSAGE_BYTES))
+        .max_frame_size(Some(MAX_MESSAGE_BYTES));
+    let (socket, _) =
+        tokio_tungstenite::connect_async_with_config(request, Some(config), false).await?;
+    tracing::info!("Connected to {}", url);
+
+    let (mut ws_sender, mut ws_receiver) = socket.split();
+    let (sender, mut outgoing) = mpsc::unbounded_channel::<WorkerMessage>();
+    let send_task = tokio::spawn(async move {
+        while let Some(message) = outgoing.recv().await {
+            let json = match serde_json::to_string(&message) {
+                Ok(json) => json,
+                Err(e) => {
+                    tracing::error!("Failed to serialize worker message: {}", e);
+                    continue;
+                }
+            };
+            if ws_sender.send(Message::Text(json.into())).await.is_err() {
+                break;
+            }
+        }
+    });
+    // The first tick is immediate, so the server learns the capabilities right away
+    let heartbeat_sender = sender.clone();
+    let capabilities = capabilities.to_vec();
+    let heartbeat_task = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+        loop {
+            interval.tick().await;
+            let heartbeat = WorkerMessage::Heartbeat {
+                capabilities: capabilities.clone(),
+            };
+            if heartbeat_sender.send(heartbeat).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut current: Option<(Uuid, oneshot::Sender<()>)> = None;
+    while let Some(message) = ws_receiver.next().await {
+        let Message::Text(text) = message? else {
+            continue;
+        };
+        match serde_json::from_str::<ServerMessage>(&text) {
+            Ok(ServerMessage::Run(run)) => {
+                tracing::info!("Running execution {}", run.execution_process_id);
+                let (stop_tx, stop_rx) = oneshot::channel();
+                current = Some((run.execution_process_id, stop_tx));
+                tokio::spawn(execute(
+                    run,
+                    work_dir.to_path_buf(),
+                    sender.clone(),
+                    stop_rx,
+                ));
+            }
+            Ok(ServerMessage::Stop {
+                execution_process_id,
+            }) => {
+                if let Some((id, stop)) = current.take() {
+                    if id == execution_process_id {
+                        tracing::info!("Stopping execution {}", id);
+                        let _ = stop.send(());
+                    } else {
+                        current = Some((id, stop));
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("Invalid message from server: {}", e),
+        }
+    }
+
+    // The server fails the execution once the connection is gone, so don't keep running it
+    if let Some((_, stop)) = current.take() {
+        let _ = stop.send(());
+    }
+    heartbeat_task.abort();
+    send_task.abort();
+    Ok(())
+}
+
+/// Run `run` and report how it ended, with the changes made so far
+async fn execute(
+    run: WorkerRun,
+    work_dir: PathBuf,
+    sender: mpsc::UnboundedSender<WorkerMessage>,
+    stop: oneshot::Receiver<()>,
+) {
+    let execution_process_id = run.execution_process_id;
+    let workspace = work_dir.join(execution_process_id.to_string());
+    let (success, repos) = match run_agent(&run, &workspace, &sender, stop).await {
+        Ok(result) => result,
+        Err(e) => {
+            tracing::error!("Execution {} failed: {}", execution_process_id, e);
+            let _ = sender.send(WorkerMessage::Log {
+                execution_process_id,
+                msg: LogMsg::Stderr(format!("Worker failed to run the agent: {e}\n")),
+            });
+            (false, Vec::new())
+        }
+    };
+    let _ = sender.send(WorkerMessage::Finished {
+        execution_process_id,
+        success,
+        repos,
+    });
+    if let Err(e) = tokio::fs::remove_dir_all(&workspace).await {
+        tracing::warn!("Failed to remove workspace {:?}: {}", workspace, e);
+    }
+}
+
+/// Send everything `reader` produces to the server as log messages
+fn forward_output(
+    mut reader: impl AsyncRead + Unpin + Send + 'static,
+    execution_process_id: Uuid,
+    sender: mpsc::UnboundedSender<WorkerMessage>,
+    to_msg: fn(String) -> LogMsg,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut buffer = vec![0; 8192];
+        while let Ok(read) = reader.read(&mut buffer).await
+            && read > 0
+        {
+            let msg = to_msg(String::from_utf8_lossy(&buffer[..read]).into_owned());
+            if sender
+                .send(WorkerMessage::Log {
+                    execution_process_id,
+                    msg,
+                })
+                .is_err()
+            {
+                break;
+            }
+        }
+    })
+}
+
+enum AgentExit {
+    Process(bool),
+    Signal(bool),
+    Stopped,
+}
+
+async fn run_agent(
+    run: &WorkerRun,
+    workspace: &Path,
+    sender: &mpsc::UnboundedSender<WorkerMessage>,
+    mut stop: oneshot::Receiver<()>,
+) -> anyhow::Result<(bool, Vec<RepoSnapshot>)> {
+    let execution_process_id = run.execution_process_id;
+    // Always start from the server's state
+    let _ = tokio::fs::remove_dir_all(workspace).await;
+    tokio::fs::create_dir_all(workspace).await?;
+    {
+        let repos = run.repos.clone();
+        let workspace = workspace.to_path_buf();
+        let branch = run.branch.clone();
+        tokio::task::spawn_blocking(move || {
+            for snapshot in &repos {
+                workers::clone_snapshot(snapshot, &workspace.join(&snapshot.name), &branch)?;
+            }
+            Ok::<_, WorkerError>(())
+        })
+        .await??;
+    }
+
+    let repo_names: Vec<String> = run.repos.iter().map(|repo| repo.name.clone()).collect();
+    let mut env = ExecutionEnv::new(
+        RepoContext::new(workspace.to_path_buf(), repo_names.clone()),
+        run.commit_reminder,
+    );
+    env.merge(&run.env);
+    // Approvals are answered in the app, which the worker can't reach
+    let mut spawned = run
+        .action
+        .spawn(workspace, Arc::new(NoopExecutorApprovalService {}), &env)
+        .await?;
+
+    let mut forwarders = Vec::new();
+    if let Some(stdout) = spawned.child.inner().stdout.take() {
+        forwarders.push(forward_output(
+            stdout,
+            execution_process_id,
+            sender.clone(),
+            LogMsg::Stdout,
+        ));
+    }
+    if let Some(stderr) = spawned.child.inner().stderr.take() {
+        forwarders.push(forward_output(
+            stderr,
+            execution_process_id,
+            sender.clone(),
+            LogMsg::Stderr,
+        ));
+    }
+
+    let mut exit_signal = spawned
+        .exit_signal
+        .take()
+        .map(|rx| rx.boxed())
+        .unwrap_or_else(|| std::future::pending().boxed());
+    let exit = tokio::select! {
+        status = spawned.child.wait() => {
+            AgentExit::Process(status.is_ok_and(|status| status.success()))
+        }
+        // A closed channel counts as success, as for local executions
+        result = &mut exit_signal => {
+            AgentExit::Signal(!matches!(result, Ok(ExecutorExitResult::Failure)))
+        }
+        _ = &mut stop => AgentExit::Stopped,
+    };
+    let success = match exit {
+        AgentExit::Process(success) => success,
+        AgentExit::Signal(success) => {
+            kill_process_group(&mut spawned.child).await?;
+            success
+        }
+        AgentExit::Stopped => {
+            if let Some(interrupt) = spawned.interrupt_sender.take() {
+                let _ = interrupt.send(());
+                let _ = tokio::time::timeout(INTERRUPT_GRACE, spawned.child.wait()).await;
+            }
+            kill_process_group(&mut spawned.child).await?;
+            false
+        }
+    };
+    for forwarder in forwarders {
+        let _ = forwarder.await;
+    }
+
+    let workspace = workspace.to_path_buf();
+    let branch = run.branch.clone();
+    let repos = tokio::task::spawn_blocking(move || {
+        repo_names
+            .iter()
+            .map(|name| workers::snapshot_repo(name, &workspace.join(name), &branch, true))
+            .collect::<Result<Vec<_>, _>>()
+    })
+    .await??;
+    Ok((success, repos))
+}
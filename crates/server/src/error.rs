@@ -14,14 +14,20 @@ use executors::{command::CommandBuildError, executors::ExecutorError};
 use git2::Error as Git2Error;
 use local_deployment::pty::PtyError;
 use services::services::{
+    compliance_export::ComplianceExportError,
     config::{ConfigError, EditorOpenError},
     container::ContainerError,
+    dependency_updates::DependencyUpdateError,
+    document_encryption::DocumentEncryptionError,
+    document_store::DocumentStoreError,
     git::GitServiceError,
     git_host::GitHostError,
     image::ImageError,
     project::ProjectServiceError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
+    retention::RetentionError,
+    sql_console::SqlConsoleError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
@@ -332,6 +338,73 @@ impl From<RepoServiceError> for ApiError {
     }
 }
 
+impl From<DependencyUpdateError> for ApiError {
+    fn from(err: DependencyUpdateError) -> Self {
+        match err {
+            DependencyUpdateError::Database(db_err) => ApiError::Database(db_err),
+            DependencyUpdateError::Io(io_err) => ApiError::Io(io_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<ComplianceExportError> for ApiError {
+    fn from(err: ComplianceExportError) -> Self {
+        match err {
+            ComplianceExportError::Io(io_err) => ApiError::Io(io_err),
+            other => ApiError::Io(std::io::Error::other(other.to_string())),
+        }
+    }
+}
+
+impl From<RetentionError> for ApiError {
+    fn from(err: RetentionError) -> Self {
+        match err {
+            RetentionError::Database(db_err) => ApiError::Database(db_err),
+            RetentionError::Io(io_err) => ApiError::Io(io_err),
+        }
+    }
+}
+
+impl From<SqlConsoleError> for ApiError {
+    fn from(err: SqlConsoleError) -> Self {
+        match err {
+            // Errors SQLite reports for the query itself, like a syntax error
+            SqlConsoleError::Database(sqlx::Error::Database(db_err)) => {
+                ApiError::BadRequest(db_err.to_string())
+            }
+            SqlConsoleError::Database(db_err) => ApiError::Database(db_err),
+            other => ApiError::BadRequest(other.to_string()),
+        }
+    }
+}
+
+impl From<DocumentEncryptionError> for ApiError {
+    fn from(err: DocumentEncryptionError) -> Self {
+        match err {
+            DocumentEncryptionError::EncryptionFailed => {
+                ApiError::Io(std::io::Error::other(err.to_string()))
+            }
+            _ => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+impl From<DocumentStoreError> for ApiError {
+    fn from(err: DocumentStoreError) -> Self {
+        match err {
+            DocumentStoreError::Io(io_err) => ApiError::Io(io_err),
+            DocumentStoreError::Git(git_err) => {
+                ApiError::GitService(GitServiceError::from(git_err))
+            }
+            DocumentStoreError::OutsideRepository(_) => {
+                ApiError::BadRequest("Invalid file path: access denied".to_string())
+            }
+            err @ DocumentStoreError::Symlink(_) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
 impl From<ProjectRepoError> for ApiError {
     fn from(err: ProjectRepoError) -> Self {
         match err {
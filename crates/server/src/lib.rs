@@ -1,7 +1,10 @@
 pub mod error;
+pub mod listener;
 pub mod mcp;
 pub mod middleware;
+pub mod restart;
 pub mod routes;
+pub mod tls;
 
 // #[cfg(feature = "cloud")]
 // type DeploymentImpl = vibe_kanban_cloud::deployment::CloudDeployment;
@@ -0,0 +1,156 @@
+//! Where the server accepts connections: a TCP port, a Unix socket
+//! (`VK_UNIX_SOCKET`), or a socket handed over by systemd socket activation.
+
+use std::io;
+#[cfg(unix)]
+use std::{
+    os::unix::{
+        fs::{FileTypeExt, PermissionsExt},
+        io::{FromRawFd, RawFd},
+    },
+    path::PathBuf,
+};
+
+#[cfg(unix)]
+use nix::sys::socket::{AddressFamily, SockaddrLike, SockaddrStorage, getsockname};
+use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+
+/// First file descriptor passed by systemd (`SD_LISTEN_FDS_START`)
+#[cfg(unix)]
+const SD_LISTEN_FDS_START: RawFd = 3;
+/// Owner-only unless `VK_UNIX_SOCKET_MODE` says otherwise
+#[cfg(unix)]
+const DEFAULT_SOCKET_MODE: u32 = 0o600;
+
+pub enum ServerListener {
+    Tcp(TcpListener),
+    /// The path is set when we created the socket file and should remove it on exit
+    #[cfg(unix)]
+    Unix(UnixListener, Option<PathBuf>),
+}
+
+/// Use a systemd-provided socket if there is one, then `VK_UNIX_SOCKET`, then `host:port`
+pub async fn bind(host: &str, port: u16) -> io::Result<ServerListener> {
+    #[cfg(unix)]
+    {
+        if let Some(listener) = systemd_listener()? {
+            return Ok(listener);
+        }
+        if let Some(path) = std::env::var_os("VK_UNIX_SOCKET").filter(|p| !p.is_empty()) {
+            return bind_unix(PathBuf::from(path));
+        }
+    }
+
+    Ok(ServerListener::Tcp(
+        TcpListener::bind(format!("{host}:{port}")).await?,
+    ))
+}
+
+#[cfg(unix)]
+fn bind_unix(path: PathBuf) -> io::Result<ServerListener> {
+    // Remove a socket left behind by a previous run, but never a regular file
+    if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+        if !metadata.file_type().is_socket() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path.display()),
+            ));
+        }
+        std::fs::remove_file(&path)?;
+    }
+
+    let listener = UnixListener::bind(&path)?;
+    let mode = match std::env::var("VK_UNIX_SOCKET_MODE") {
+        Ok(mode) => u32::from_str_radix(mode.trim(), 8).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("VK_UNIX_SOCKET_MODE must be octal, got '{mode}'"),
+            )
+        })?,
+        Err(_) => DEFAULT_SOCKET_MODE,
+    };
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))?;
+
+    Ok(ServerListener::Unix(listener, Some(path)))
+}
+
+/// The listening socket passed by systemd when started through a `.socket` unit
+#[cfg(unix)]
+fn systemd_listener() -> io::Result<Option<ServerListener>> {
+    let for_us = std::env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+        == Some(std::process::id());
+    let fds = std::env::var("LISTEN_FDS")
+        .ok()
+        .and_then(|n| n.parse::<i32>().ok())
+        .unwrap_or(0);
+    if !for_us || fds < 1 {
+        return Ok(None);
+    }
+    if fds > 1 {
+        tracing::warn!("systemd passed {} sockets, only the first one is used", fds);
+    }
+
+    let fd = SD_LISTEN_FDS_START;
+    let family = getsockname::<SockaddrStorage>(fd)
+        .map_err(io::Error::from)?
+        .family();
+    // SAFETY: systemd hands this descriptor to us and nothing else owns it
+    let listener = if family == Some(AddressFamily::Unix) {
+        let listener = unsafe { std::os::unix::net::UnixListener::from_raw_fd(fd) };
+        listener.set_nonblocking(true)?;
+        ServerListener::Unix(UnixListener::from_std(listener)?, None)
+    } else {
+        let listener = unsafe { std::net::TcpListener::from_raw_fd(fd) };
+        listener.set_nonblocking(true)?;
+        ServerListener::Tcp(TcpListener::from_std(listener)?)
+    };
+    tracing::info!("Using socket passed by systemd");
+    Ok(Some(listener))
+}
+
+/// Send a state change (e.g. `READY=1`) to systemd when running as a `Type=notify` service
+pub fn notify_systemd(state: &str) {
+    #[cfg(unix)]
+    {
+        let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+            return;
+        };
+        if let Err(e) = send_notification(&path, state) {
+            tracing::warn!("Failed to notify systemd: {}", e);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = state;
+}
+
+#[cfg(unix)]
+fn send_notification(path: &std::ffi::OsStr, state: &str) -> io::Result<()> {
+    use std::os::unix::{ffi::OsStrExt, net::UnixDatagram};
+
+    let socket = UnixDatagram::unbound()?;
+    let bytes = path.as_bytes();
+    // Paths starting with '@' live in the Linux abstract namespace
+    if let Some(name) = bytes.strip_prefix(b"@") {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::{linux::net::SocketAddrExt, unix::net::SocketAddr};
+            let addr = SocketAddr::from_abstract_name(name)?;
+            socket.send_to_addr(state.as_bytes(), &addr)?;
+            return Ok(());
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = name;
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "abstract notify sockets are only supported on Linux",
+            ));
+        }
+    }
+    socket.send_to(state.as_bytes(), path)?;
+    Ok(())
+}
@@ -1,14 +1,20 @@
 use anyhow::{self, Error as AnyhowError};
 use deployment::{Deployment, DeploymentError};
-use server::{DeploymentImpl, routes};
+use server::{
+    DeploymentImpl,
+    listener::{self, ServerListener},
+    restart, routes,
+    tls::TlsConfig,
+};
 use services::services::container::ContainerService;
 use sqlx::Error as SqlxError;
 use strip_ansi_escapes::strip;
 use thiserror::Error;
-use tracing_subscriber::{EnvFilter, prelude::*};
+use tracing_subscriber::prelude::*;
 use utils::{
     assets::asset_dir,
     browser::open_browser,
+    logging::{self, LogFileSettings, RollingFile},
     port_file::write_port_file,
     sentry::{self as sentry_utils, SentrySource, sentry_layer},
 };
@@ -22,6 +28,10 @@ pub enum VibeKanbanError {
     #[error(transparent)]
     Deployment(#[from] DeploymentError),
     #[error(transparent)]
+    Tls(#[from] server::tls::TlsError),
+    #[error(transparent)]
+    Logging(#[from] logging::LoggingError),
+    #[error(transparent)]
     Other(#[from] AnyhowError),
 }
 
@@ -39,11 +49,24 @@ async fn main() -> Result<(), VibeKanbanError> {
         "warn,server={level},services={level},db={level},executors={level},deployment={level},local_deployment={level},utils={level}",
         level = log_level
     );
-    let env_filter = EnvFilter::try_new(filter_string).expect("Failed to create tracing filter");
+    let log_file = LogFileSettings::from_env()?;
+    let (log_filter, log_control) = logging::reloadable_filter(&filter_string, log_file.clone())
+        .expect("Failed to create tracing filter");
+    let file_layer = log_file.map(|settings| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(RollingFile::new(settings))
+    });
     tracing_subscriber::registry()
-        .with(tracing_subscriber::fmt::layer().with_filter(env_filter))
+        .with(
+            tracing_subscriber::fmt::layer()
+                .and_then(file_layer)
+                .and_then(logging::recent_logs().layer())
+                .with_filter(log_filter),
+        )
         .with(sentry_layer())
         .init();
+    logging::install(log_control);
 
     // Create asset directory if it doesn't exist
     if !asset_dir().exists() {
@@ -68,6 +91,14 @@ async fn main() -> Result<(), VibeKanbanError> {
         .await
         .map_err(DeploymentError::from)?;
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_task_aging_service().await;
+    deployment.spawn_retention_service().await;
+    deployment.spawn_telemetry_service().await;
+    routes::dependency_updates::spawn_scheduler(deployment.clone());
+    routes::document_snapshots::spawn_scheduler(deployment.clone());
+    routes::document_sync_status::spawn_scheduler(deployment.clone());
+    routes::task_attempts::preview_builds::fail_interrupted_builds(&deployment).await;
+    routes::task_attempts::e2e_runs::fail_interrupted_runs(&deployment).await;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -84,6 +115,14 @@ async fn main() -> Result<(), VibeKanbanError> {
     });
 
     let app_router = routes::router(deployment.clone());
+    let tls_config = TlsConfig::from_env()?
+        .map(|tls| tls.server_config())
+        .transpose()?;
+    let scheme = if tls_config.is_some() {
+        "https"
+    } else {
+        "http"
+    };
 
     let port = std::env::var("BACKEND_PORT")
         .or_else(|_| std::env::var("PORT"))
@@ -100,7 +139,33 @@ async fn main() -> Result<(), VibeKanbanError> {
         }); // Use 0 to find free port if no specific port provided
 
     let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
-    let listener = tokio::net::TcpListener::bind(format!("{host}:{port}")).await?;
+    let listener = match listener::bind(&host, port).await? {
+        ServerListener::Tcp(listener) => listener,
+        #[cfg(unix)]
+        ServerListener::Unix(listener, path) => {
+            if tls_config.is_some() {
+                tracing::warn!(
+                    "TLS is not used on Unix sockets, terminate it in the reverse proxy"
+                );
+            }
+            match &path {
+                Some(path) => tracing::info!("Server running on unix:{}", path.display()),
+                None => tracing::info!("Server running on systemd-provided unix socket"),
+            }
+            listener::notify_systemd("READY=1");
+
+            axum::serve(listener, app_router.into_make_service())
+                .with_graceful_shutdown(shutdown_signal())
+                .await?;
+
+            listener::notify_systemd("STOPPING=1");
+            if let Some(path) = path {
+                let _ = std::fs::remove_file(path);
+            }
+            perform_cleanup_actions(&deployment).await;
+            return relaunch_if_requested();
+        }
+    };
     let actual_port = listener.local_addr()?.port(); // get → 53427 (example)
 
     // Write port file for discovery if prod, warn on fail
@@ -108,27 +173,45 @@ async fn main() -> Result<(), VibeKanbanError> {
         tracing::warn!("Failed to write port file: {}", e);
     }
 
-    tracing::info!("Server running on http://{host}:{actual_port}");
+    tracing::info!("Server running on {scheme}://{host}:{actual_port}");
 
     if !cfg!(debug_assertions) {
         tracing::info!("Opening browser...");
         tokio::spawn(async move {
-            if let Err(e) = open_browser(&format!("http://127.0.0.1:{actual_port}")).await {
+            if let Err(e) = open_browser(&format!("{scheme}://127.0.0.1:{actual_port}")).await {
                 tracing::warn!(
-                    "Failed to open browser automatically: {}. Please open http://127.0.0.1:{} manually.",
+                    "Failed to open browser automatically: {}. Please open {}://127.0.0.1:{} manually.",
                     e,
+                    scheme,
                     actual_port
                 );
             }
         });
     }
 
-    axum::serve(listener, app_router)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    listener::notify_systemd("READY=1");
+    match tls_config {
+        Some(config) => {
+            server::tls::serve(listener, app_router, config, shutdown_signal()).await?;
+        }
+        None => {
+            axum::serve(listener, app_router.into_make_service())
+                .with_graceful_shutdown(shutdown_signal())
+                .await?;
+        }
+    }
+    listener::notify_systemd("STOPPING=1");
 
     perform_cleanup_actions(&deployment).await;
 
+    relaunch_if_requested()
+}
+
+/// Start the (updated) binary again if the shutdown was a restart request
+fn relaunch_if_requested() -> Result<(), VibeKanbanError> {
+    if restart::is_requested() {
+        return Err(restart::relaunch().into());
+    }
     Ok(())
 }
 
@@ -158,13 +241,17 @@ pub async fn shutdown_signal() {
         tokio::select! {
             _ = ctrl_c => {},
             _ = terminate => {},
+            _ = restart::requested() => {},
         }
     }
 
     #[cfg(not(unix))]
     {
-        // Only ctrl_c is available, so just await it
-        ctrl_c.await;
+        // Only ctrl_c is available besides restart requests
+        tokio::select! {
+            _ = ctrl_c => {},
+            _ = restart::requested() => {},
+        }
     }
 }
 
@@ -5,6 +5,7 @@ use db::models::{
     repo::Repo,
     tag::Tag,
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_document::TaskDocument,
     workspace::{Workspace, WorkspaceContext},
 };
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
@@ -302,9 +303,19 @@ pub struct GetTaskRequest {
     pub task_id: Uuid,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct RelatedDocument {
+    #[schemars(description = "The repository the document is in")]
+    pub repo_id: String,
+    #[schemars(description = "Path of the document relative to its repository")]
+    pub path: String,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct GetTaskResponse {
     pub task: TaskDetails,
+    #[schemars(description = "Design docs and other documents related to the task")]
+    pub related_documents: Vec<RelatedDocument>,
 }
 
 #[derive(Debug, Clone)]
@@ -897,6 +908,7 @@ impl TaskServer {
             task_id,
             executor_profile_id,
             repos: workspace_repos,
+            wip_override_reason: None,
         };
 
         let url = self.url("/api/task-attempts");
@@ -952,6 +964,8 @@ impl TaskServer {
             status,
             parent_workspace_id: None,
             image_ids: None,
+            priority: None,
+            wip_override_reason: None,
         };
         let url = self.url(&format!("/api/tasks/{}", task_id));
         let updated_task: Task = match self.send_json(self.client.put(&url).json(&payload)).await {
@@ -982,7 +996,7 @@ impl TaskServer {
     }
 
     #[tool(
-        description = "Get detailed information (like task description) about a specific task/ticket. You can use `list_tasks` to find the `task_ids` of all tasks in a project. `task_id` is required."
+        description = "Get detailed information (like task description and related documents) about a specific task/ticket. You can use `list_tasks` to find the `task_ids` of all tasks in a project. `task_id` is required."
     )]
     async fn get_task(
         &self,
@@ -994,8 +1008,24 @@ impl TaskServer {
             Err(e) => return Ok(e),
         };
 
+        let url = self.url(&format!("/api/tasks/{}/documents", task_id));
+        let documents: Vec<TaskDocument> = match self.send_json(self.client.get(&url)).await {
+            Ok(documents) => documents,
+            Err(e) => return Ok(e),
+        };
+
         let details = TaskDetails::from_task(task);
-        let response = GetTaskResponse { task: details };
+        let related_documents = documents
+            .into_iter()
+            .map(|document| RelatedDocument {
+                repo_id: document.repo_id.to_string(),
+                path: document.relative_path,
+            })
+            .collect();
+        let response = GetTaskResponse {
+            task: details,
+            related_documents,
+        };
 
         TaskServer::success(&response)
     }
@@ -0,0 +1,207 @@
+use std::sync::OnceLock;
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+use super::origin::is_allowed_origin;
+
+pub const CSRF_COOKIE: &str = "vk_csrf";
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// How mutating requests coming from a browser are protected, set with `VK_CSRF_STRATEGY`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CsrfStrategy {
+    /// The `vk_csrf` cookie must be echoed back in the `X-CSRF-Token` header (default)
+    DoubleSubmit,
+    /// Only requests the browser reports as same-origin (`Sec-Fetch-Site`) are accepted
+    SameSite,
+    Disabled,
+}
+
+impl CsrfStrategy {
+    fn from_env() -> Self {
+        match std::env::var("VK_CSRF_STRATEGY")
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            Ok("same-site" | "same_site" | "samesite") => Self::SameSite,
+            Ok("off" | "disabled" | "none") => Self::Disabled,
+            Ok("double-submit" | "double_submit") | Err(_) => Self::DoubleSubmit,
+            Ok(other) => {
+                tracing::warn!("Unknown VK_CSRF_STRATEGY '{}', using double-submit", other);
+                Self::DoubleSubmit
+            }
+        }
+    }
+}
+
+fn strategy() -> CsrfStrategy {
+    static STRATEGY: OnceLock<CsrfStrategy> = OnceLock::new();
+    *STRATEGY.get_or_init(CsrfStrategy::from_env)
+}
+
+fn is_safe_method(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    )
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+}
+
+fn cookie_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == CSRF_COOKIE)
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Requests from scripts and the MCP server send neither `Origin` nor `Sec-Fetch-Site`
+/// and cannot be forged by another site, so only browser requests are checked
+fn is_browser_request(headers: &HeaderMap) -> bool {
+    headers.contains_key(header::ORIGIN) || headers.contains_key("sec-fetch-site")
+}
+
+fn check(strategy: CsrfStrategy, method: &Method, headers: &HeaderMap) -> Result<(), &'static str> {
+    if strategy == CsrfStrategy::Disabled || is_safe_method(method) || !is_browser_request(headers)
+    {
+        return Ok(());
+    }
+    // Frontends listed in VK_ALLOWED_ORIGINS can't read our cookie, but are trusted explicitly
+    if header_str(headers, header::ORIGIN.as_str()).is_some_and(is_allowed_origin) {
+        return Ok(());
+    }
+
+    match strategy {
+        CsrfStrategy::DoubleSubmit => {
+            let cookie = cookie_token(headers).ok_or("missing CSRF cookie")?;
+            let header = header_str(headers, CSRF_HEADER).ok_or("missing CSRF header")?;
+            if constant_time_eq(cookie.as_bytes(), header.as_bytes()) {
+                Ok(())
+            } else {
+                Err("CSRF token mismatch")
+            }
+        }
+        CsrfStrategy::SameSite => match header_str(headers, "sec-fetch-site") {
+            Some("same-origin" | "none") => Ok(()),
+            Some(_) => Err("cross-site request"),
+            // Browsers without Fetch Metadata are covered by the origin check
+            None => Ok(()),
+        },
+        CsrfStrategy::Disabled => Ok(()),
+    }
+}
+
+/// Rejects cross-site mutating requests and hands out the CSRF cookie
+pub async fn csrf_protection(req: Request, next: Next) -> Response {
+    let strategy = strategy();
+    if let Err(reason) = check(strategy, req.method(), req.headers()) {
+        tracing::warn!(
+            method = %req.method(),
+            uri = %req.uri(),
+            "Rejected request: {}",
+            reason
+        );
+        return Response::builder()
+            .status(StatusCode::FORBIDDEN)
+            .body(Body::empty())
+            .unwrap_or_else(|_| Response::new(Body::empty()));
+    }
+
+    let needs_cookie = strategy != CsrfStrategy::Disabled && cookie_token(req.headers()).is_none();
+    let mut response = next.run(req).await;
+
+    if needs_cookie {
+        // Readable by the frontend so it can echo it back; SameSite keeps it off cross-site requests
+        let cookie = format!(
+            "{CSRF_COOKIE}={}; Path=/; SameSite=Strict",
+            Uuid::new_v4().simple()
+        );
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn safe_and_non_browser_requests_pass() {
+        let browser = headers(&[("origin", "http://localhost:3000")]);
+        assert!(check(CsrfStrategy::DoubleSubmit, &Method::GET, &browser).is_ok());
+
+        let script = headers(&[]);
+        assert!(check(CsrfStrategy::DoubleSubmit, &Method::POST, &script).is_ok());
+    }
+
+    #[test]
+    fn double_submit_requires_matching_token() {
+        let missing = headers(&[("origin", "http://localhost:3000")]);
+        assert!(check(CsrfStrategy::DoubleSubmit, &Method::POST, &missing).is_err());
+
+        let mismatch = headers(&[
+            ("origin", "http://localhost:3000"),
+            ("cookie", "theme=dark; vk_csrf=abc"),
+            ("x-csrf-token", "abd"),
+        ]);
+        assert!(check(CsrfStrategy::DoubleSubmit, &Method::DELETE, &mismatch).is_err());
+
+        let matching = headers(&[
+            ("origin", "http://localhost:3000"),
+            ("cookie", "theme=dark; vk_csrf=abc"),
+            ("x-csrf-token", "abc"),
+        ]);
+        assert!(check(CsrfStrategy::DoubleSubmit, &Method::PUT, &matching).is_ok());
+    }
+
+    #[test]
+    fn same_site_rejects_cross_site_requests() {
+        let same_origin = headers(&[("sec-fetch-site", "same-origin")]);
+        assert!(check(CsrfStrategy::SameSite, &Method::POST, &same_origin).is_ok());
+
+        for site in ["cross-site", "same-site"] {
+            let cross = headers(&[
+                ("sec-fetch-site", site),
+                ("origin", "http://evil.example.com"),
+            ]);
+            assert!(check(CsrfStrategy::SameSite, &Method::POST, &cross).is_err());
+        }
+    }
+
+    #[test]
+    fn disabled_allows_everything() {
+        let cross = headers(&[("sec-fetch-site", "cross-site")]);
+        assert!(check(CsrfStrategy::Disabled, &Method::POST, &cross).is_ok());
+    }
+}
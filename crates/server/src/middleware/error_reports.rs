@@ -0,0 +1,137 @@
+use axum::{
+    body::{Body, to_bytes},
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use db::models::error_report::{CreateErrorReport, ErrorReport};
+use deployment::Deployment;
+use serde_json::{Value, json};
+use services::services::error_reports::{self, SentryDsn};
+use utils::{logging, version::APP_VERSION};
+
+use super::request_id::RequestId;
+use crate::DeploymentImpl;
+
+/// Largest error response read for its message
+const MAX_ERROR_BODY_BYTES: usize = 1024 * 1024;
+/// Log lines of the request stored with a report
+const MAX_REPORT_LOG_LINES: usize = 200;
+
+/// What the server was running with when the error happened
+fn state_summary() -> Value {
+    json!({
+        "version": APP_VERSION,
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "log_filter": logging::log_control().map(|control| control.effective_directives()),
+    })
+}
+
+/// The `message` of an `ApiResponse` body, else the body itself
+fn error_message(body: &[u8]) -> Option<String> {
+    let message = serde_json::from_slice::<Value>(body)
+        .ok()
+        .and_then(|value| value.get("message")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| String::from_utf8_lossy(body).trim().to_string());
+    (!message.is_empty()).then_some(message)
+}
+
+/// Store a report of each request that fails with a server error, with its route,
+/// correlation id and log lines, and send it on to the configured Sentry-compatible
+/// endpoint
+pub async fn capture_error_reports(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| path.clone(), |route| route.as_str().to_string());
+    let request_id = request.extensions().get::<RequestId>().cloned();
+
+    let response = next.run(request).await;
+    let Some(RequestId(correlation_id)) = request_id else {
+        return response;
+    };
+    if !response.status().is_server_error() {
+        return response;
+    }
+    let settings = deployment.config().read().await.error_reports.clone();
+    if !settings.enabled {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::warn!("Failed to read the error response: {}", e);
+            Default::default()
+        }
+    };
+    let message = error_message(&body);
+    tracing::error!(
+        "{} {} failed with {}: {}",
+        method,
+        route,
+        parts.status.as_u16(),
+        message.as_deref().unwrap_or("no message")
+    );
+
+    let report = CreateErrorReport {
+        correlation_id: correlation_id.clone(),
+        method,
+        route,
+        path,
+        status: i64::from(parts.status.as_u16()),
+        message,
+        log_lines: logging::recent_logs().for_request(&correlation_id, MAX_REPORT_LOG_LINES),
+        state: state_summary(),
+    };
+    let pool = deployment.db().pool.clone();
+    match ErrorReport::create(&pool, &report).await {
+        Ok(report) => {
+            if let Some(dsn) = settings.forward_dsn.filter(|dsn| !dsn.trim().is_empty()) {
+                tokio::spawn(async move {
+                    let forwarded = match SentryDsn::parse(&dsn) {
+                        Ok(dsn) => error_reports::forward(&dsn, &report).await,
+                        Err(e) => Err(e),
+                    };
+                    match forwarded {
+                        Ok(()) => {
+                            if let Err(e) = ErrorReport::mark_forwarded(&pool, report.id).await {
+                                tracing::warn!("Failed to mark error report as sent: {}", e);
+                            }
+                        }
+                        Err(e) => tracing::warn!("Failed to forward error report: {}", e),
+                    }
+                });
+            }
+        }
+        Err(e) => tracing::warn!("Failed to store error report: {}", e),
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_come_from_the_api_response() {
+        assert_eq!(
+            error_message(br#"{"success":false,"data":null,"message":"Database error"}"#),
+            Some("Database error".to_string())
+        );
+        assert_eq!(
+            error_message(b"upstream timed out\n"),
+            Some("upstream timed out".to_string())
+        );
+        assert_eq!(error_message(b""), None);
+    }
+}
@@ -0,0 +1,51 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::Response,
+};
+use deployment::Deployment;
+use services::services::config::UiLanguage;
+use utils::i18n::{self, Locale};
+
+use crate::DeploymentImpl;
+
+/// The locale a language chosen in the settings forces; `None` leaves it to the browser
+fn configured_locale(language: UiLanguage) -> Option<Locale> {
+    match language {
+        UiLanguage::Browser => None,
+        UiLanguage::En => Some(Locale::En),
+        UiLanguage::Fr => Some(Locale::Fr),
+        UiLanguage::Ja => Some(Locale::Ja),
+        UiLanguage::Es => Some(Locale::Es),
+        UiLanguage::Ko => Some(Locale::Ko),
+        UiLanguage::ZhHans => Some(Locale::ZhHans),
+        UiLanguage::ZhHant => Some(Locale::ZhHant),
+    }
+}
+
+/// Handle the request in the language chosen in the settings, or else the one the client
+/// asks for with `Accept-Language`, so the messages the server makes match the UI
+pub async fn set_locale(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let language = deployment.config().read().await.language;
+    let locale = configured_locale(language)
+        .or_else(|| {
+            request
+                .headers()
+                .get(header::ACCEPT_LANGUAGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(Locale::from_accept_language)
+        })
+        .unwrap_or_default();
+
+    let mut response = i18n::scope(locale, next.run(request)).await;
+    response.headers_mut().insert(
+        header::CONTENT_LANGUAGE,
+        HeaderValue::from_static(locale.tag()),
+    );
+    response
+}
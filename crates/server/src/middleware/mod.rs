@@ -1,5 +1,15 @@
+pub mod csrf;
+pub mod error_reports;
+pub mod locale;
 pub mod model_loaders;
 pub mod origin;
+pub mod quota;
+pub mod request_id;
 
+pub use csrf::*;
+pub use error_reports::*;
+pub use locale::*;
 pub use model_loaders::*;
 pub use origin::*;
+pub use quota::*;
+pub use request_id::*;
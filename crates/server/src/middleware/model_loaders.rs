@@ -46,8 +46,8 @@ pub async fn load_task_middleware(
     request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    // Load the task and validate it belongs to the project
-    let task = match Task::find_by_id(&deployment.db().pool, task_id).await {
+    // Load the task, following the redirect left behind if it was merged
+    let task = match Task::find_by_id_or_redirect(&deployment.db().pool, task_id).await {
         Ok(Some(task)) => task,
         Ok(None) => {
             tracing::warn!("Task {} not found", task_id);
@@ -3,9 +3,10 @@ use std::{net::IpAddr, sync::OnceLock};
 use axum::{
     body::Body,
     extract::Request,
-    http::{StatusCode, header},
+    http::{HeaderName, Method, StatusCode, header},
     response::Response,
 };
+use tower_http::cors::{AllowOrigin, CorsLayer};
 use url::Url;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -74,6 +75,32 @@ pub fn validate_origin<B>(req: &mut Request<B>) -> Result<(), Response> {
     Err(forbidden())
 }
 
+/// Whether `origin` is one of the cross-origin frontends listed in `VK_ALLOWED_ORIGINS`
+pub fn is_allowed_origin(origin: &str) -> bool {
+    OriginKey::from_origin(origin)
+        .is_some_and(|key| allowed_origins().iter().any(|allowed| allowed == &key))
+}
+
+/// CORS headers for the origins in `VK_ALLOWED_ORIGINS`; no other origin is ever allowed
+pub fn cors_layer() -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(|origin, _| {
+            origin.to_str().is_ok_and(is_allowed_origin)
+        }))
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
+        .allow_headers([
+            header::CONTENT_TYPE,
+            HeaderName::from_static(super::csrf::CSRF_HEADER),
+        ])
+        .allow_credentials(true)
+}
+
 fn get_origin_header<B>(req: &Request<B>) -> Option<&str> {
     get_header(req, header::ORIGIN)
 }
@@ -0,0 +1,133 @@
+use std::time::Instant;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::project_quotas::ApiQuotaUsage;
+use ts_rs::TS;
+use utils::{i18n::t, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, routes::project_usage::API_QUOTA};
+
+/// Sent with a 429 when a project has used up its API requests for the minute
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectQuotaExceeded {
+    pub message: String,
+    /// Requests the project may make per minute
+    pub limit: u32,
+    /// Seconds until the project may make requests again
+    pub retry_after_secs: u64,
+}
+
+/// The project a request is about, from a `/projects/{id}/...` path. Reading the usage
+/// itself is not counted, so a project over its quota can still see when it resets.
+fn quota_project(path: &str) -> Option<Uuid> {
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    segments.by_ref().find(|segment| *segment == "projects")?;
+    let project_id = segments.next()?.parse().ok()?;
+    match segments.next() {
+        Some("usage") => None,
+        _ => Some(project_id),
+    }
+}
+
+const RATE_LIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+const RATE_LIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+const RATE_LIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+fn set_header(headers: &mut HeaderMap, name: HeaderName, value: impl ToString) {
+    if let Ok(value) = HeaderValue::from_str(&value.to_string()) {
+        headers.insert(name, value);
+    }
+}
+
+/// Count requests against the per-minute quota of their project, answering 429 with a
+/// `Retry-After` once it is used up
+pub async fn enforce_project_quota(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(project_id) = quota_project(request.uri().path()) else {
+        return next.run(request).await;
+    };
+    let limit = deployment
+        .config()
+        .read()
+        .await
+        .project_limits
+        .api_requests_per_minute;
+
+    match API_QUOTA.check(project_id, limit, Instant::now()) {
+        Ok(ApiQuotaUsage {
+            limit: Some(limit),
+            remaining,
+            resets_in_secs,
+            ..
+        }) => {
+            let mut response = next.run(request).await;
+            let headers = response.headers_mut();
+            set_header(headers, RATE_LIMIT_LIMIT, limit);
+            set_header(headers, RATE_LIMIT_REMAINING, remaining.unwrap_or(0));
+            set_header(headers, RATE_LIMIT_RESET, resets_in_secs);
+            response
+        }
+        Ok(_) => next.run(request).await,
+        Err(exceeded) => {
+            // Round up, so a client waiting that long finds the new window started
+            let retry_after_secs =
+                exceeded.retry_after.as_secs() + u64::from(exceeded.retry_after.subsec_nanos() > 0);
+            tracing::warn!(
+                "Project {} is over its quota of {} API requests per minute",
+                project_id,
+                exceeded.limit
+            );
+            let body: ApiResponse<(), ProjectQuotaExceeded> =
+                ApiResponse::error_with_data(ProjectQuotaExceeded {
+                    message: t(
+                        "project-quota-exceeded",
+                        &[("limit", &exceeded.limit), ("seconds", &retry_after_secs)],
+                    ),
+                    limit: exceeded.limit,
+                    retry_after_secs,
+                });
+            let mut response = (StatusCode::TOO_MANY_REQUESTS, ResponseJson(body)).into_response();
+            let headers = response.headers_mut();
+            set_header(headers, header::RETRY_AFTER, retry_after_secs);
+            set_header(headers, RATE_LIMIT_LIMIT, exceeded.limit);
+            set_header(headers, RATE_LIMIT_REMAINING, 0);
+            set_header(headers, RATE_LIMIT_RESET, retry_after_secs);
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_counted_for_the_project_in_their_path() {
+        let id = Uuid::new_v4();
+        assert_eq!(quota_project(&format!("/projects/{id}")), Some(id));
+        assert_eq!(
+            quota_project(&format!("/api/projects/{id}/documents/sync")),
+            Some(id)
+        );
+        assert_eq!(quota_project("/projects"), None);
+        assert_eq!(quota_project("/projects/not-an-id/documents"), None);
+        assert_eq!(quota_project("/config/info"), None);
+    }
+
+    #[test]
+    fn reading_the_usage_is_not_counted() {
+        let id = Uuid::new_v4();
+        assert_eq!(quota_project(&format!("/projects/{id}/usage")), None);
+    }
+}
@@ -0,0 +1,67 @@
+//! Restarting the server in place, e.g. after a self-update swapped the binary.
+//!
+//! A restart is a normal graceful shutdown followed by relaunching the
+//! executable with the same arguments.
+
+use std::sync::{
+    LazyLock,
+    atomic::{AtomicBool, Ordering},
+};
+
+use tokio::sync::Notify;
+
+/// Exit status asking a service manager to start us again (`EX_TEMPFAIL`)
+pub const RESTART_EXIT_CODE: i32 = 75;
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+static NOTIFY: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Ask the server to shut down gracefully and start again
+pub fn request() {
+    REQUESTED.store(true, Ordering::SeqCst);
+    NOTIFY.notify_waiters();
+}
+
+pub fn is_requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Resolves once a restart has been requested; used as a shutdown trigger
+pub async fn requested() {
+    let notified = NOTIFY.notified();
+    if is_requested() {
+        return;
+    }
+    notified.await;
+}
+
+/// Replace the current process with a fresh copy of the (possibly updated) binary.
+/// Under systemd, exit with [`RESTART_EXIT_CODE`] instead and let `Restart=on-failure`
+/// start the new binary, so socket activation and notify state stay consistent.
+pub fn relaunch() -> std::io::Error {
+    if std::env::var_os("INVOCATION_ID").is_some() {
+        tracing::info!("Exiting so systemd restarts the service");
+        std::process::exit(RESTART_EXIT_CODE);
+    }
+
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => return e,
+    };
+    let args: Vec<_> = std::env::args_os().skip(1).collect();
+    tracing::info!("Restarting {}", exe.display());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // Only returns on failure
+        std::process::Command::new(exe).args(args).exec()
+    }
+    #[cfg(not(unix))]
+    {
+        match std::process::Command::new(exe).args(args).spawn() {
+            Ok(_) => std::process::exit(0),
+            Err(e) => e,
+        }
+    }
+}
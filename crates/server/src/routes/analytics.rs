@@ -0,0 +1,79 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::get,
+};
+use chrono::{Days, NaiveDate, Utc};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::analytics_overview;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, routes::paths};
+
+/// Range used when no dates are given
+const DEFAULT_RANGE_DAYS: u64 = 30;
+const MAX_RANGE_DAYS: i64 = 366;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum AnalyticsExportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct AnalyticsOverviewQuery {
+    /// First day (inclusive), defaults to 30 days before `to`
+    pub from: Option<NaiveDate>,
+    /// Last day (inclusive), defaults to today
+    pub to: Option<NaiveDate>,
+    #[serde(default)]
+    pub format: AnalyticsExportFormat,
+}
+
+pub async fn get_analytics_overview(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AnalyticsOverviewQuery>,
+) -> Result<Response, ApiError> {
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query
+        .from
+        .unwrap_or_else(|| to - Days::new(DEFAULT_RANGE_DAYS - 1));
+    if from > to {
+        return Err(ApiError::BadRequest(
+            "`from` must not be after `to`".to_string(),
+        ));
+    }
+    if (to - from).num_days() >= MAX_RANGE_DAYS {
+        return Err(ApiError::BadRequest(format!(
+            "Date range must not exceed {MAX_RANGE_DAYS} days"
+        )));
+    }
+
+    let overview =
+        analytics_overview::overview(&deployment.db().pool, deployment.git(), from, to).await?;
+
+    Ok(match query.format {
+        AnalyticsExportFormat::Json => ResponseJson(ApiResponse::success(overview)).into_response(),
+        AnalyticsExportFormat::Csv => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"analytics-{from}-{to}.csv\""),
+                ),
+            ],
+            analytics_overview::to_csv(&overview),
+        )
+            .into_response(),
+    })
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route(paths::ANALYTICS_OVERVIEW, get(get_analytics_overview))
+}
@@ -11,7 +11,7 @@ use utils::{
     response::ApiResponse,
 };
 
-use crate::DeploymentImpl;
+use crate::{DeploymentImpl, routes::paths};
 
 pub async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
@@ -44,5 +44,5 @@ pub async fn respond_to_approval(
 }
 
 pub fn router() -> Router<DeploymentImpl> {
-    Router::new().route("/approvals/{id}/respond", post(respond_to_approval))
+    Router::new().route(paths::APPROVAL_RESPOND, post(respond_to_approval))
 }
@@ -0,0 +1,487 @@
+//! `vk://` links from documents and task descriptions to the tasks and documents of a
+//! project (see [`utils::board_links`]). Links are checked when a document or task is
+//! saved, resolved to app URLs when a document is rendered, and kept pointing at their
+//! target when a document moves or a task is merged. Links whose target was deleted are
+//! flagged instead, since there is nothing left to point them at.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use axum::{
+    Extension,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{document_lock::DocumentLock, project::Project, task::Task};
+use deployment::Deployment;
+use serde::Serialize;
+use ts_rs::TS;
+use utils::{
+    board_links::{self, BoardLinkTarget},
+    response::{ApiResponse, ApiWarning},
+};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{
+        document_approvals, document_branches,
+        document_commits::DocumentCommitter,
+        document_encryption,
+        documents::{
+            DocumentFileType, DocumentReader, RepositoryQuery, auto_commit_warning,
+            find_document_file, find_read_only_violation, project_repositories,
+            scan_project_documents, validate_relative_path,
+        },
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum BrokenBoardLinkReason {
+    /// The link names neither a task id nor a document path
+    Invalid,
+    /// No such task or document, e.g. because it was deleted
+    NotFound,
+    /// The task belongs to another project
+    OtherProject,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BrokenBoardLink {
+    /// The link as written
+    pub href: String,
+    pub reason: BrokenBoardLinkReason,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BoardLinkSource {
+    Document {
+        repository_id: Uuid,
+        relative_path: String,
+    },
+    Task {
+        task_id: Uuid,
+        title: String,
+    },
+}
+
+impl std::fmt::Display for BoardLinkSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoardLinkSource::Document { relative_path, .. } => write!(f, "{relative_path}"),
+            BoardLinkSource::Task { title, .. } => write!(f, "task \"{title}\""),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct BrokenBoardLinks {
+    pub source: BoardLinkSource,
+    pub links: Vec<BrokenBoardLink>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct BoardLinkReport {
+    /// Documents and tasks with broken `vk://` links, documents first
+    pub broken: Vec<BrokenBoardLinks>,
+    /// The document scan hit its limits, so some documents were not checked
+    pub truncated: bool,
+}
+
+/// Where an existing link target lives
+enum ResolvedTarget {
+    Task(Uuid),
+    Document(String),
+}
+
+async fn resolve(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    target: &BoardLinkTarget,
+) -> Result<Result<ResolvedTarget, BrokenBoardLinkReason>, ApiError> {
+    match target {
+        BoardLinkTarget::Task(id) => {
+            // Links to merged tasks resolve to the task they were merged into
+            match Task::find_by_id_or_redirect(&deployment.db().pool, *id).await? {
+                Some(task) if task.project_id == project_id => {
+                    Ok(Ok(ResolvedTarget::Task(task.id)))
+                }
+                Some(_) => Ok(Err(BrokenBoardLinkReason::OtherProject)),
+                None => Ok(Err(BrokenBoardLinkReason::NotFound)),
+            }
+        }
+        BoardLinkTarget::Document(path) => {
+            let Ok(path) = validate_relative_path(path) else {
+                return Ok(Err(BrokenBoardLinkReason::Invalid));
+            };
+            match find_document_file(deployment, project_id, None, path).await {
+                Ok(_) => Ok(Ok(ResolvedTarget::Document(path.to_string()))),
+                Err(ApiError::BadRequest(_)) => Ok(Err(BrokenBoardLinkReason::NotFound)),
+                Err(e) => Err(e),
+            }
+        }
+    }
+}
+
+/// Broken `vk://` links of `text`, each listed once
+pub(crate) async fn broken_links(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    text: &str,
+) -> Result<Vec<BrokenBoardLink>, ApiError> {
+    let mut broken: Vec<BrokenBoardLink> = Vec::new();
+    for link in board_links::find(text) {
+        if broken.iter().any(|seen| seen.href == link.href) {
+            continue;
+        }
+        let reason = match &link.target {
+            Some(target) => match resolve(deployment, project_id, target).await? {
+                Ok(_) => continue,
+                Err(reason) => reason,
+            },
+            None => BrokenBoardLinkReason::Invalid,
+        };
+        broken.push(BrokenBoardLink {
+            href: link.href,
+            reason,
+        });
+    }
+    Ok(broken)
+}
+
+/// Reject `text` if it adds broken `vk://` links. Links that were already broken in
+/// `previous` are left alone, so a deleted target doesn't block unrelated edits.
+pub(crate) async fn require_valid_links(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    text: &str,
+    previous: Option<&str>,
+) -> Result<(), ApiError> {
+    let broken: Vec<String> = broken_links(deployment, project_id, text)
+        .await?
+        .into_iter()
+        .filter(|link| previous.is_none_or(|previous| !previous.contains(&link.href)))
+        .map(|link| match link.reason {
+            BrokenBoardLinkReason::Invalid => {
+                format!("{} is not a task or document link", link.href)
+            }
+            BrokenBoardLinkReason::NotFound => format!("{} points to nothing", link.href),
+            BrokenBoardLinkReason::OtherProject => {
+                format!("{} points to a task of another project", link.href)
+            }
+        })
+        .collect();
+    if broken.is_empty() {
+        return Ok(());
+    }
+    Err(ApiError::BadRequest(format!(
+        "Fix the broken links before saving: {}",
+        broken.join("; ")
+    )))
+}
+
+/// App URL of each `vk://` link of `text`, keyed by the link as written; `None` for
+/// broken links
+pub(crate) async fn link_urls(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    text: &str,
+) -> Result<HashMap<String, Option<String>>, ApiError> {
+    let mut urls = HashMap::new();
+    for link in board_links::find(text) {
+        if urls.contains_key(&link.href) {
+            continue;
+        }
+        let resolved = match &link.target {
+            Some(target) => resolve(deployment, project_id, target).await?.ok(),
+            None => None,
+        };
+        let url = resolved.map(|resolved| {
+            let url = match resolved {
+                ResolvedTarget::Task(id) => format!("/projects/{project_id}/tasks/{id}"),
+                ResolvedTarget::Document(path) => format!(
+                    "/projects/{project_id}/documents?path={}",
+                    urlencoding::encode(&path)
+                ),
+            };
+            match &link.fragment {
+                Some(fragment) => format!("{url}#{fragment}"),
+                None => url,
+            }
+        });
+        urls.insert(link.href, url);
+    }
+    Ok(urls)
+}
+
+/// Whether `text` links to `target` as written
+fn links_to(text: &str, target: &BoardLinkTarget) -> bool {
+    board_links::find(text)
+        .iter()
+        .any(|link| link.target.as_ref() == Some(target))
+}
+
+struct LinkingDocument {
+    repository_id: Uuid,
+    relative_path: String,
+    file_path: PathBuf,
+    content: String,
+}
+
+impl LinkingDocument {
+    fn source(&self) -> BoardLinkSource {
+        BoardLinkSource::Document {
+            repository_id: self.repository_id,
+            relative_path: self.relative_path.clone(),
+        }
+    }
+}
+
+/// Markdown documents and tasks of the project whose text contains a `vk://` link
+async fn linking_sources(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repository_id: Option<Uuid>,
+) -> Result<(Vec<LinkingDocument>, Vec<Task>, bool), ApiError> {
+    let (documents, truncated) =
+        scan_project_documents(deployment, project_id, repository_id, None).await?;
+    let reader = DocumentReader::load(deployment, project_id).await?;
+    let mut linking_documents = Vec::new();
+    for document in documents {
+        if document.file_type != DocumentFileType::Markdown {
+            continue;
+        }
+        let content = match reader.read_listed(&document).await {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {}", document.absolute_path, e);
+                continue;
+            }
+        };
+        if content.contains(board_links::SCHEME) {
+            linking_documents.push(LinkingDocument {
+                repository_id: document.repository_id,
+                relative_path: document.relative_path,
+                file_path: PathBuf::from(document.absolute_path),
+                content,
+            });
+        }
+    }
+    let tasks = Task::find_by_description_containing(
+        &deployment.db().pool,
+        project_id,
+        board_links::SCHEME,
+    )
+    .await?;
+    Ok((linking_documents, tasks, truncated))
+}
+
+/// Documents and tasks that still link to `target`, for flagging a deletion
+pub(crate) async fn sources_linking_to(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    target: &BoardLinkTarget,
+) -> Result<Vec<BoardLinkSource>, ApiError> {
+    let (documents, tasks, _) = linking_sources(deployment, project_id, None).await?;
+    let mut sources: Vec<BoardLinkSource> = documents
+        .iter()
+        .filter(|document| links_to(&document.content, target))
+        .map(LinkingDocument::source)
+        .collect();
+    sources.extend(
+        tasks
+            .into_iter()
+            .filter(|task| {
+                task.description
+                    .as_deref()
+                    .is_some_and(|d| links_to(d, target))
+            })
+            .map(|task| BoardLinkSource::Task {
+                task_id: task.id,
+                title: task.title,
+            }),
+    );
+    Ok(sources)
+}
+
+/// Warning for a deleted target that documents or tasks still link to
+pub(crate) async fn deleted_target_warning(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    target: &BoardLinkTarget,
+) -> Option<ApiWarning> {
+    let sources = match sources_linking_to(deployment, project_id, target).await {
+        Ok(sources) => sources,
+        Err(e) => {
+            tracing::warn!("Failed to find links to {}: {}", target.href(), e);
+            return None;
+        }
+    };
+    if sources.is_empty() {
+        return None;
+    }
+    let sources: Vec<String> = sources.iter().map(ToString::to_string).collect();
+    Some(ApiWarning::new(
+        "board_links_broken",
+        format!("Links to it are now broken in {}", sources.join(", ")),
+    ))
+}
+
+/// Point every link to `from` at `to`, in task descriptions and in the markdown
+/// documents of the project, committing the documents of each repository together.
+/// Documents that can't be edited right now are skipped with a warning.
+pub(crate) async fn retarget_links(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    from: &BoardLinkTarget,
+    to: &BoardLinkTarget,
+) -> Result<Vec<ApiWarning>, ApiError> {
+    let pool = &deployment.db().pool;
+    let rewrite =
+        |text: &str| board_links::rewrite(text, |target| (target == from).then(|| to.clone()));
+    let (documents, tasks, _) = linking_sources(deployment, project_id, None).await?;
+
+    for task in tasks {
+        if let Some(description) = task.description.as_deref().and_then(&rewrite) {
+            Task::update_description(pool, task.id, &description).await?;
+        }
+    }
+
+    let mut warnings = Vec::new();
+    let mut skipped = Vec::new();
+    let mut changed: BTreeMap<Uuid, Vec<(String, PathBuf, String)>> = BTreeMap::new();
+    for LinkingDocument {
+        repository_id,
+        relative_path,
+        file_path,
+        content,
+    } in documents
+    {
+        let Some(updated) = rewrite(&content) else {
+            continue;
+        };
+        let editable = find_read_only_violation(deployment, project_id, &relative_path)
+            .await?
+            .is_none()
+            && document_approvals::require_unprotected(deployment, project_id, &relative_path)
+                .await
+                .is_ok()
+            && document_encryption::require_unencrypted(deployment, project_id, &relative_path)
+                .await
+                .is_ok()
+            && DocumentLock::find_active(pool, repository_id, &relative_path)
+                .await?
+                .is_none();
+        if editable {
+            changed
+                .entry(repository_id)
+                .or_default()
+                .push((relative_path, file_path, updated));
+        } else {
+            skipped.push(relative_path);
+        }
+    }
+
+    let repo_paths: HashMap<Uuid, PathBuf> = project_repositories(deployment, project_id, None)
+        .await?
+        .into_iter()
+        .map(|repo| (repo.id, repo.path))
+        .collect();
+    let committer = DocumentCommitter::load(deployment, project_id).await?;
+    for (repository_id, documents) in changed {
+        let Some(repo_path) = repo_paths.get(&repository_id) else {
+            continue;
+        };
+        let editable =
+            document_branches::require_editable_branch(deployment, project_id, repo_path, "links")
+                .await;
+        if editable.is_err() {
+            tracing::warn!(
+                "Not updating links in repository {}: not on an editable branch",
+                repository_id
+            );
+            skipped.extend(
+                documents
+                    .into_iter()
+                    .map(|(relative_path, _, _)| relative_path),
+            );
+            continue;
+        }
+        for (_, file_path, updated) in &documents {
+            tokio::fs::write(file_path, updated).await?;
+        }
+        deployment.document_index().invalidate(repository_id);
+        let paths: Vec<&str> = documents
+            .iter()
+            .map(|(relative_path, _, _)| relative_path.as_str())
+            .collect();
+        let commit_message = committer.message(
+            &format!("docs: update links to {}", to.href()),
+            &paths.join(", "),
+        );
+        if let Err(e) = committer.commit(deployment, repo_path, &commit_message) {
+            tracing::warn!("Failed to auto-commit link updates: {}", e);
+            warnings.push(auto_commit_warning("link update", &e));
+        }
+    }
+
+    if !skipped.is_empty() {
+        warnings.push(ApiWarning::new(
+            "board_links_not_updated",
+            format!(
+                "Links to {} were not updated in {}, which can't be edited right now",
+                from.href(),
+                skipped.join(", ")
+            ),
+        ));
+    }
+    Ok(warnings)
+}
+
+/// Broken `vk://` links across the project's documents and task descriptions
+pub async fn get_board_link_report(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(repository): Query<RepositoryQuery>,
+) -> Result<ResponseJson<ApiResponse<BoardLinkReport>>, ApiError> {
+    let (documents, tasks, truncated) =
+        linking_sources(&deployment, project.id, repository.repository_id).await?;
+
+    let mut broken = Vec::new();
+    for document in documents {
+        let links = broken_links(&deployment, project.id, &document.content).await?;
+        if !links.is_empty() {
+            broken.push(BrokenBoardLinks {
+                source: document.source(),
+                links,
+            });
+        }
+    }
+    for task in tasks {
+        let links = broken_links(
+            &deployment,
+            project.id,
+            task.description.as_deref().unwrap_or_default(),
+        )
+        .await?;
+        if !links.is_empty() {
+            broken.push(BrokenBoardLinks {
+                source: BoardLinkSource::Task {
+                    task_id: task.id,
+                    title: task.title,
+                },
+                links,
+            });
+        }
+    }
+
+    Ok(ResponseJson(ApiResponse::success(BoardLinkReport {
+        broken,
+        truncated,
+    })))
+}
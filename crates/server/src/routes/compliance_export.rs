@@ -0,0 +1,218 @@
+//! Signed export bundles of a project for audits: the documents as committed on the docs
+//! branch, the log of agent runs with the commits they left behind, and the task history,
+//! with a manifest of their hashes signed by this instance's export key.
+
+use std::collections::HashMap;
+
+use axum::{
+    Extension, Router,
+    extract::State,
+    http::{HeaderName, header},
+    middleware::from_fn_with_state,
+    response::{IntoResponse, Response},
+    routing::post,
+};
+use db::models::{
+    audit_log::{AuditLogRepoChange, AuditLogRun, TaskHistoryAttempt},
+    project::Project,
+    task::Task,
+};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::compliance_export::{ExportBundle, ExportSigner};
+use utils::assets::asset_dir;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{
+        documents::{DEFAULT_DOCS_BRANCH, scan_project_documents},
+        paths,
+    },
+};
+
+/// File in the asset directory holding the instance's export signing key
+const SIGNING_KEY_FILE: &str = "export-signing-key";
+/// Base64 signature of the bundle's manifest, so it can be checked without unpacking
+const SIGNATURE_HEADER: HeaderName = HeaderName::from_static("x-export-signature");
+const EXPORT_ID_HEADER: HeaderName = HeaderName::from_static("x-export-id");
+
+/// Documents of one repository, as committed on the docs branch
+#[derive(Debug, Serialize)]
+struct ExportedRepository {
+    repository_id: Uuid,
+    repository_name: String,
+    branch: String,
+    commit_sha: String,
+    /// Paths of the exported documents, under `documents/<repository name>/`
+    documents: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportedDocuments {
+    repositories: Vec<ExportedRepository>,
+    /// Whether the project's scan limits cut the document list short
+    truncated: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct AuditLogEntry {
+    #[serde(flatten)]
+    run: AuditLogRun,
+    repositories: Vec<AuditLogRepoChange>,
+}
+
+#[derive(Debug, Serialize)]
+struct TaskHistoryEntry {
+    #[serde(flatten)]
+    task: Task,
+    attempts: Vec<TaskHistoryAttempt>,
+}
+
+/// Add the committed version of every document to `bundle`. Uncommitted changes are left
+/// out, so each exported document matches the recorded commit; encrypted documents are
+/// exported as the ciphertext that was committed.
+async fn add_documents(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    bundle: &mut ExportBundle,
+) -> Result<ExportedDocuments, ApiError> {
+    let (documents, truncated) = scan_project_documents(deployment, project_id, None, None).await?;
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project_id)
+        .await?;
+    let git = deployment.git_backend();
+
+    let mut exported = Vec::new();
+    for repo in repositories {
+        let commit_sha = match git.resolve_revision(&repo.path, DEFAULT_DOCS_BRANCH) {
+            Ok(commit_sha) => commit_sha,
+            Err(e) => {
+                tracing::debug!(
+                    "No '{}' branch to export in {:?}: {}",
+                    DEFAULT_DOCS_BRANCH,
+                    repo.path,
+                    e
+                );
+                continue;
+            }
+        };
+        let mut paths = Vec::new();
+        for document in documents.iter().filter(|d| d.repository_id == repo.id) {
+            let Some((_, bytes)) =
+                git.read_file_at_revision(&repo.path, &commit_sha, &document.relative_path)?
+            else {
+                // Not committed yet
+                continue;
+            };
+            bundle.add(
+                format!("documents/{}/{}", repo.name, document.relative_path),
+                bytes,
+            );
+            paths.push(document.relative_path.clone());
+        }
+        exported.push(ExportedRepository {
+            repository_id: repo.id,
+            repository_name: repo.name,
+            branch: DEFAULT_DOCS_BRANCH.to_string(),
+            commit_sha,
+            documents: paths,
+        });
+    }
+    Ok(ExportedDocuments {
+        repositories: exported,
+        truncated,
+    })
+}
+
+/// Export the project as a signed `.tar.gz` bundle
+pub async fn create_signed_export(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<Response, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut bundle = ExportBundle::new();
+
+    let documents = add_documents(&deployment, project.id, &mut bundle).await?;
+    bundle.add_json("documents.json", &documents)?;
+
+    let mut changes: HashMap<Uuid, Vec<AuditLogRepoChange>> = HashMap::new();
+    for change in AuditLogRepoChange::find_by_project_id(pool, project.id).await? {
+        changes
+            .entry(change.execution_process_id)
+            .or_default()
+            .push(change);
+    }
+    let audit_log: Vec<AuditLogEntry> = AuditLogRun::find_by_project_id(pool, project.id)
+        .await?
+        .into_iter()
+        .map(|run| AuditLogEntry {
+            repositories: changes
+                .remove(&run.execution_process_id)
+                .unwrap_or_default(),
+            run,
+        })
+        .collect();
+    bundle.add_json("audit-log.json", &audit_log)?;
+
+    let mut attempts: HashMap<Uuid, Vec<TaskHistoryAttempt>> = HashMap::new();
+    for attempt in TaskHistoryAttempt::find_by_project_id(pool, project.id).await? {
+        attempts.entry(attempt.task_id).or_default().push(attempt);
+    }
+    let tasks: Vec<TaskHistoryEntry> = Task::find_by_project_id(pool, project.id)
+        .await?
+        .into_iter()
+        .map(|task| TaskHistoryEntry {
+            attempts: attempts.remove(&task.id).unwrap_or_default(),
+            task,
+        })
+        .collect();
+    bundle.add_json("tasks.json", &tasks)?;
+
+    // Hashing and compressing every document is CPU-bound, so keep it off the async workers
+    let project_name = project.name.clone();
+    let export = tokio::task::spawn_blocking(move || {
+        let signer = ExportSigner::load_or_create(&asset_dir().join(SIGNING_KEY_FILE))?;
+        bundle.finish(&signer, project.id, &project_name)
+    })
+    .await
+    .map_err(|e| ApiError::Io(std::io::Error::other(e)))??;
+
+    tracing::info!(
+        "Created signed export {} of project {} with {} files",
+        export.manifest.export_id,
+        project.id,
+        export.manifest.files.len()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/gzip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"export-{}-{}.tar.gz\"",
+                    export.manifest.created_at.format("%Y%m%d-%H%M%S"),
+                    export.manifest.export_id
+                ),
+            ),
+            (SIGNATURE_HEADER, export.signature),
+            (EXPORT_ID_HEADER, export.manifest.export_id.to_string()),
+        ],
+        export.archive,
+    )
+        .into_response())
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route("/signed", post(create_signed_export))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest(paths::PROJECT_EXPORT, project_router)
+}
@@ -18,14 +18,22 @@ use executors::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use services::services::config::{
-    Config, ConfigError, SoundFile,
-    editor::{EditorConfig, EditorType},
-    save_config_to_file,
+use services::services::{
+    config::{
+        Config, ConfigError, SoundFile,
+        editor::{EditorConfig, EditorType},
+        save_config_to_file,
+    },
+    error_reports::SentryDsn,
 };
 use tokio::fs;
 use ts_rs::TS;
-use utils::{api::oauth::LoginStatus, assets::config_path, response::ApiResponse};
+use utils::{
+    api::oauth::LoginStatus,
+    assets::config_path,
+    response::ApiResponse,
+    timezone::{Timezone, parse_time_of_day},
+};
 
 use crate::{DeploymentImpl, error::ApiError};
 
@@ -123,6 +131,36 @@ async fn update_config(
         ));
     }
 
+    if let Some(timezone) = &new_config.timezone
+        && let Err(e) = Timezone::parse(timezone)
+    {
+        return ResponseJson(ApiResponse::error(&e.to_string()));
+    }
+    let run_at = [
+        &new_config.dependency_updates.run_at,
+        &new_config.document_snapshots.run_at,
+    ];
+    for run_at in run_at.into_iter().flatten() {
+        if let Err(e) = parse_time_of_day(run_at) {
+            return ResponseJson(ApiResponse::error(&e.to_string()));
+        }
+    }
+    let limits = &new_config.project_limits;
+    if limits.background_jobs == 0
+        || limits.background_jobs_per_project == 0
+        || limits.api_requests_per_minute == Some(0)
+    {
+        return ResponseJson(ApiResponse::error(
+            "Project limits must be at least 1; leave the API quota empty for no limit.",
+        ));
+    }
+    if let Some(dsn) = &new_config.error_reports.forward_dsn
+        && !dsn.trim().is_empty()
+        && let Err(e) = SentryDsn::parse(dsn)
+    {
+        return ResponseJson(ApiResponse::error(&e.to_string()));
+    }
+
     // Get old config state before updating
     let old_config = deployment.config().read().await.clone();
 
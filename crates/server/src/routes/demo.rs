@@ -0,0 +1,173 @@
+use std::path::PathBuf;
+
+use axum::{Json, Router, extract::State, response::Json as ResponseJson, routing::post};
+use db::models::{
+    project::{CreateProject, Project},
+    project_repo::{CreateProjectRepo, ProjectRepo},
+    task::{CreateTask, Task, TaskStatus},
+    workspace::{CreateWorkspace, Workspace},
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    demo::{self, DEMO_TASKS},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{paths, task_attempts::util::resolve_agent_working_dir},
+};
+
+const DEFAULT_DEMO_NAME: &str = "Acme Notes (demo)";
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateDemoProject {
+    /// Defaults to "Acme Notes (demo)"
+    pub name: Option<String>,
+    /// Also create a finished attempt with a scripted change, ready for review
+    #[serde(default)]
+    pub with_attempt: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DemoProject {
+    pub project: Project,
+    pub tasks: Vec<Task>,
+    pub repo_path: String,
+    pub workspace_id: Option<Uuid>,
+}
+
+/// Create a workspace for `task` without running an agent, then commit the scripted
+/// demo change in its worktree so the attempt has a diff to review
+async fn create_scripted_attempt(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    task: &Task,
+) -> Result<Uuid, ApiError> {
+    let pool = &deployment.db().pool;
+    let repo = ProjectRepo::find_repos_for_project(pool, project_id)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::BadRequest("The demo project has no repository".to_string()))?;
+
+    let target_branch = deployment.git().get_current_branch(&repo.path)?;
+    let attempt_id = Uuid::new_v4();
+    let branch = deployment
+        .container()
+        .git_branch_from_workspace(&attempt_id, &task.title)
+        .await;
+    let agent_working_dir = resolve_agent_working_dir(pool, task.id, &[repo.id]).await?;
+    let workspace = Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch,
+            agent_working_dir,
+        },
+        attempt_id,
+        task.id,
+    )
+    .await?;
+    WorkspaceRepo::create_many(
+        pool,
+        workspace.id,
+        &[CreateWorkspaceRepo {
+            repo_id: repo.id,
+            target_branch,
+        }],
+    )
+    .await?;
+
+    let container_ref = deployment.container().create(&workspace).await?;
+    let worktree = PathBuf::from(container_ref).join(&repo.name);
+    demo::apply_demo_attempt(deployment.git(), &worktree)?;
+    Task::update_status(pool, task.id, TaskStatus::InReview).await?;
+
+    Ok(workspace.id)
+}
+
+/// Generate a throwaway project with a seeded repository, docs and example tasks
+pub async fn create_demo_project(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateDemoProject>,
+) -> Result<ResponseJson<ApiResponse<DemoProject>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let repo_path = demo::demo_repo_path();
+    demo::create_demo_repo(deployment.git(), &repo_path)?;
+
+    let name = payload
+        .name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_DEMO_NAME.to_string());
+    let project = deployment
+        .project()
+        .create_project(
+            pool,
+            deployment.repo(),
+            CreateProject {
+                name,
+                repositories: vec![CreateProjectRepo {
+                    display_name: "acme-notes".to_string(),
+                    git_repo_path: repo_path.to_string_lossy().to_string(),
+                }],
+            },
+        )
+        .await?;
+
+    let mut tasks = Vec::with_capacity(DEMO_TASKS.len());
+    for (title, description, status) in DEMO_TASKS {
+        let mut task = Task::create(
+            pool,
+            &CreateTask::from_title_description(
+                project.id,
+                title.to_string(),
+                Some(description.to_string()),
+            ),
+            Uuid::new_v4(),
+        )
+        .await?;
+        if *status != TaskStatus::Todo {
+            Task::update_status(pool, task.id, status.clone()).await?;
+            task.status = status.clone();
+        }
+        tasks.push(task);
+    }
+
+    let mut workspace_id = None;
+    if payload.with_attempt
+        && let Some(task) = tasks
+            .iter_mut()
+            .find(|t| t.status == TaskStatus::InProgress)
+    {
+        workspace_id = Some(create_scripted_attempt(&deployment, project.id, task).await?);
+        task.status = TaskStatus::InReview;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "demo_project_created",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "with_attempt": payload.with_attempt,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(DemoProject {
+        project,
+        tasks,
+        repo_path: repo_path.to_string_lossy().to_string(),
+        workspace_id,
+    })))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route(paths::ADMIN_DEMO, post(create_demo_project))
+}
@@ -0,0 +1,249 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Extension, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    project::Project,
+    project_repo::ProjectRepo,
+    task::Task,
+    workspace::{CreateWorkspace, Workspace},
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::{
+    container::ContainerService,
+    dependency_updates::{self, DependencyUpdateReport},
+};
+use ts_rs::TS;
+use utils::{response::ApiResponse, timezone::parse_time_of_day};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{paths, project_usage, task_attempts::util::resolve_agent_working_dir, timezones},
+};
+
+/// How often the scheduler wakes up to see whether a run is due
+const SCHEDULER_TICK: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Serialize, TS)]
+pub struct DependencyUpdateRun {
+    pub reports: Vec<DependencyUpdateReport>,
+    /// Tasks created by this run (existing open update tasks are not duplicated)
+    pub created_tasks: Vec<Task>,
+    /// Workspaces started for created tasks when auto-dispatch is enabled
+    pub dispatched_workspace_ids: Vec<Uuid>,
+}
+
+/// Report outdated dependencies for every repository in the project without creating tasks
+pub async fn get_dependency_updates(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DependencyUpdateReport>>>, ApiError> {
+    let repos = ProjectRepo::find_repos_for_project(&deployment.db().pool, project.id).await?;
+    let mut reports = Vec::new();
+    for repo in &repos {
+        reports.extend(dependency_updates::check_repo(repo).await);
+    }
+    Ok(ResponseJson(ApiResponse::success(reports)))
+}
+
+/// Run the dependency update workflow for the project now
+pub async fn run_dependency_updates(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<DependencyUpdateRun>>, ApiError> {
+    let run = run_for_project(&deployment, &project).await?;
+    Ok(ResponseJson(ApiResponse::success(run)))
+}
+
+/// Check each repository, create scoped update tasks and optionally dispatch them
+pub async fn run_for_project(
+    deployment: &DeploymentImpl,
+    project: &Project,
+) -> Result<DependencyUpdateRun, ApiError> {
+    let pool = &deployment.db().pool;
+    let (settings, default_profile) = {
+        let config = deployment.config().read().await;
+        (
+            config.dependency_updates.clone(),
+            config.executor_profile.clone(),
+        )
+    };
+
+    let repos = ProjectRepo::find_repos_for_project(pool, project.id).await?;
+    let mut run = DependencyUpdateRun {
+        reports: Vec::new(),
+        created_tasks: Vec::new(),
+        dispatched_workspace_ids: Vec::new(),
+    };
+
+    for repo in &repos {
+        for report in dependency_updates::check_repo(repo).await {
+            if let Some(task) = dependency_updates::create_update_task(
+                pool,
+                project.id,
+                &report,
+                settings.require_passing_tests,
+            )
+            .await?
+            {
+                if settings.auto_dispatch {
+                    let profile = settings
+                        .executor_profile
+                        .clone()
+                        .unwrap_or_else(|| default_profile.clone());
+                    let target_branch = deployment.git().get_current_branch(&repo.path)?;
+
+                    let attempt_id = Uuid::new_v4();
+                    let branch = deployment
+                        .container()
+                        .git_branch_from_workspace(&attempt_id, &task.title)
+                        .await;
+                    let agent_working_dir =
+                        resolve_agent_working_dir(pool, task.id, &[repo.id]).await?;
+                    let workspace = Workspace::create(
+                        pool,
+                        &CreateWorkspace {
+                            branch,
+                            agent_working_dir,
+                        },
+                        attempt_id,
+                        task.id,
+                    )
+                    .await?;
+                    WorkspaceRepo::create_many(
+                        pool,
+                        workspace.id,
+                        &[CreateWorkspaceRepo {
+                            repo_id: repo.id,
+                            target_branch,
+                        }],
+                    )
+                    .await?;
+
+                    match deployment
+                        .container()
+                        .start_workspace(&workspace, profile)
+                        .await
+                    {
+                        Ok(_) => run.dispatched_workspace_ids.push(workspace.id),
+                        Err(e) => tracing::error!(
+                            "Failed to dispatch dependency update task {}: {}",
+                            task.id,
+                            e
+                        ),
+                    }
+                }
+                run.created_tasks.push(task);
+            }
+            run.reports.push(report);
+        }
+    }
+
+    Ok(run)
+}
+
+async fn run_scheduled(deployment: &DeploymentImpl, project: &Project) {
+    let _job = project_usage::background_job(deployment, project.id).await;
+    match run_for_project(deployment, project).await {
+        Ok(run) if !run.created_tasks.is_empty() => tracing::info!(
+            "Created {} dependency update task(s) for project {}",
+            run.created_tasks.len(),
+            project.id
+        ),
+        Ok(_) => {}
+        Err(e) => tracing::error!(
+            "Dependency update run failed for project {}: {}",
+            project.id,
+            e
+        ),
+    }
+}
+
+/// Periodically run dependency updates for all projects when enabled in config, either
+/// every `interval_hours` or daily at `run_at` in each project's timezone
+pub fn spawn_scheduler(deployment: DeploymentImpl) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SCHEDULER_TICK);
+        let mut last_run: Option<Instant> = None;
+        let started_at = Utc::now();
+        let mut last_daily_runs: HashMap<Uuid, DateTime<Utc>> = HashMap::new();
+
+        loop {
+            ticker.tick().await;
+
+            let settings = deployment.config().read().await.dependency_updates.clone();
+            if !settings.enabled {
+                continue;
+            }
+            let run_at = settings
+                .run_at
+                .as_deref()
+                .and_then(|run_at| parse_time_of_day(run_at).ok());
+            let interval = Duration::from_secs(u64::from(settings.interval_hours.max(1)) * 3600);
+            if run_at.is_none() {
+                if last_run.is_some_and(|at| at.elapsed() < interval) {
+                    continue;
+                }
+                last_run = Some(Instant::now());
+            }
+
+            let projects = match Project::find_all(&deployment.db().pool).await {
+                Ok(projects) => projects,
+                Err(e) => {
+                    tracing::error!("Failed to load projects for dependency updates: {}", e);
+                    continue;
+                }
+            };
+            for project in projects {
+                if let Some(run_at) = run_at {
+                    let timezone = match timezones::project_timezone(&deployment, project.id).await
+                    {
+                        Ok(timezone) => timezone,
+                        Err(e) => {
+                            tracing::error!(
+                                "Failed to load the timezone of project {}: {}",
+                                project.id,
+                                e
+                            );
+                            continue;
+                        }
+                    };
+                    let last_daily_run = last_daily_runs
+                        .get(&project.id)
+                        .copied()
+                        .unwrap_or(started_at);
+                    if timezone.next_daily(last_daily_run, run_at) > Utc::now() {
+                        continue;
+                    }
+                    last_daily_runs.insert(project.id, Utc::now());
+                }
+                run_scheduled(&deployment, &project).await;
+            }
+        }
+    })
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route(
+            "/",
+            get(get_dependency_updates).post(run_dependency_updates),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest(paths::PROJECT_DEPENDENCY_UPDATES, project_router)
+}
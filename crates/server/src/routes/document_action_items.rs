@@ -0,0 +1,296 @@
+//! Turning meeting notes into tasks. Action items are proposed from the notes first, and
+//! only the items the user confirms become tasks, listed under an "Action items" section
+//! of the notes in the same commit.
+
+use std::path::PathBuf;
+
+use axum::{
+    Extension,
+    extract::{Path as AxumPath, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use db::models::{
+    document_lock::DocumentLock,
+    document_task_link::DocumentTaskLink,
+    project::Project,
+    repo::Repo,
+    task::{CreateTask, Task},
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::action_items::{self, ActionItem};
+use ts_rs::TS;
+use utils::{
+    front_matter,
+    response::ApiResponse,
+    timezone::{LocalizedTime, Timezone},
+};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{
+        document_approvals, document_branches,
+        document_commits::DocumentCommitter,
+        document_encryption,
+        document_task_sync::{task_link, task_sync_enabled},
+        documents::{
+            DocumentFileType, DocumentReadOnlyViolation, DocumentReader, DocumentUpdateConflict,
+            RepositoryQuery, auto_commit_warning, content_hash, document_file_type, document_link,
+            find_document_file, find_read_only_violation, validate_relative_path,
+        },
+        timezones,
+    },
+};
+
+/// An action item found in the notes
+#[derive(Debug, Serialize, TS)]
+pub struct ProposedActionItem {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub item: ActionItem,
+    /// When the due date ends in the project's timezone
+    pub due_at: Option<LocalizedTime>,
+}
+
+/// Action items proposed for a document, to be confirmed before tasks are created
+#[derive(Debug, Serialize, TS)]
+pub struct ActionItemsPreview {
+    pub relative_path: String,
+    pub items: Vec<ProposedActionItem>,
+    /// Pass back as `expected_hash` when applying the confirmed items
+    pub content_hash: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ApplyActionItemsRequest {
+    /// The confirmed items, possibly edited or added to by the user
+    pub items: Vec<ActionItem>,
+    /// `content_hash` of the version the items were proposed for
+    pub expected_hash: String,
+    /// Name the document was locked with, if any
+    #[serde(default)]
+    #[ts(optional)]
+    pub lock_holder: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ApplyActionItemsResponse {
+    pub tasks: Vec<Task>,
+    pub committed: bool,
+    pub content_hash: String,
+}
+
+/// Decode and validate `relative_path`, and find the markdown document it names
+async fn find_notes(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repository_id: Option<Uuid>,
+    relative_path: &str,
+) -> Result<(Repo, PathBuf, String), ApiError> {
+    let decoded_path = urlencoding::decode(relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?;
+    let decoded_path = validate_relative_path(&decoded_path)?.to_string();
+    let (repo, file_path) =
+        find_document_file(deployment, project_id, repository_id, &decoded_path).await?;
+    if document_file_type(&file_path) != Some(DocumentFileType::Markdown) {
+        return Err(ApiError::BadRequest(format!(
+            "'{}' is not a markdown document",
+            decoded_path
+        )));
+    }
+    Ok((repo, file_path, decoded_path))
+}
+
+fn task_description(
+    project_id: Uuid,
+    relative_path: &str,
+    item: &ActionItem,
+    timezone: &Timezone,
+) -> String {
+    let mut lines = Vec::new();
+    if let Some(owner) = &item.owner {
+        lines.push(format!("Owner: @{}", owner.trim_start_matches('@')));
+    }
+    if let Some(due_date) = item.due_date {
+        lines.push(format!("Due: {} ({})", due_date, timezone));
+    }
+    if !lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines.push(match item.line {
+        Some(line) => format!(
+            "From [{}, line {}]({})",
+            relative_path,
+            line,
+            document_link(project_id, relative_path, line, line)
+        ),
+        None => format!(
+            "From [{}](/projects/{}/documents?path={})",
+            relative_path,
+            project_id,
+            urlencoding::encode(relative_path)
+        ),
+    });
+    lines.join("\n")
+}
+
+/// Propose action items for a meeting-notes document. Nothing is changed until the
+/// confirmed items are applied.
+pub async fn get_action_items(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+) -> Result<ResponseJson<ApiResponse<ActionItemsPreview>>, ApiError> {
+    let (repo, _, decoded_path) = find_notes(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &relative_path,
+    )
+    .await?;
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let content = reader.read(&repo.path, &decoded_path).await?;
+    let timezone = timezones::project_timezone(&deployment, project.id).await?;
+    let items = action_items::extract(&content)
+        .into_iter()
+        .map(|item| ProposedActionItem {
+            due_at: item
+                .due_date
+                .map(|date| timezone.localize(timezone.end_of_day(date))),
+            item,
+        })
+        .collect();
+
+    Ok(ResponseJson(ApiResponse::success(ActionItemsPreview {
+        relative_path: decoded_path,
+        items,
+        content_hash: content_hash(&content),
+    })))
+}
+
+/// Create a task for each confirmed action item and list the items, linked to their
+/// tasks, under the "Action items" section of the notes in a single commit
+pub async fn apply_action_items(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+    ResponseJson(body): ResponseJson<ApplyActionItemsRequest>,
+) -> Result<Response, ApiError> {
+    if body.items.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Confirm at least one action item".to_string(),
+        ));
+    }
+    if body.items.iter().any(|item| item.title.trim().is_empty()) {
+        return Err(ApiError::BadRequest(
+            "Action items need a title".to_string(),
+        ));
+    }
+    let (repo, file_path, decoded_path) = find_notes(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &relative_path,
+    )
+    .await?;
+
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let content = reader.read(&repo.path, &decoded_path).await?;
+    let current_hash = content_hash(&content);
+    if body.expected_hash != current_hash {
+        let conflict = DocumentUpdateConflict {
+            current_hash,
+            current_content: content,
+        };
+        let response: ApiResponse<ApplyActionItemsResponse, DocumentUpdateConflict> =
+            ApiResponse::error_with_data(conflict);
+        return Ok((StatusCode::CONFLICT, ResponseJson(response)).into_response());
+    }
+    if let Some(violation) =
+        find_read_only_violation(&deployment, project.id, &decoded_path).await?
+    {
+        let response: ApiResponse<ApplyActionItemsResponse, DocumentReadOnlyViolation> =
+            ApiResponse::error_with_data(violation);
+        return Ok(ResponseJson(response).into_response());
+    }
+    document_approvals::require_unprotected(&deployment, project.id, &decoded_path).await?;
+    document_encryption::require_unencrypted(&deployment, project.id, &decoded_path).await?;
+    let pool = &deployment.db().pool;
+    if let Some(lock) = DocumentLock::find_active(pool, repo.id, &decoded_path).await?
+        && body.lock_holder.as_deref().map(str::trim) != Some(lock.holder.as_str())
+    {
+        let response: ApiResponse<ApplyActionItemsResponse, DocumentLock> =
+            ApiResponse::error_with_data(lock);
+        return Ok((StatusCode::LOCKED, ResponseJson(response)).into_response());
+    }
+    let repo_path = PathBuf::from(&repo.path);
+    document_branches::require_editable_branch(&deployment, project.id, &repo_path, &decoded_path)
+        .await?;
+
+    let task_sync = task_sync_enabled(front_matter::parse(&content).as_ref());
+    let timezone = timezones::project_timezone(&deployment, project.id).await?;
+    let mut tasks = Vec::with_capacity(body.items.len());
+    let mut lines = Vec::with_capacity(body.items.len());
+    for item in &body.items {
+        let create = CreateTask::from_title_description(
+            project.id,
+            item.title.trim().to_string(),
+            Some(task_description(project.id, &decoded_path, item, &timezone)),
+        );
+        let task = Task::create(pool, &create, Uuid::new_v4()).await?;
+        deployment
+            .track_if_analytics_allowed(
+                "task_created",
+                serde_json::json!({
+                    "task_id": task.id.to_string(),
+                    "project_id": project.id,
+                    "has_description": true,
+                    "source": "document_action_items",
+                }),
+            )
+            .await;
+        if task_sync {
+            DocumentTaskLink::upsert(pool, task.id, project.id, repo.id, &decoded_path, false)
+                .await?;
+        }
+        lines.push(format!(
+            "{}{}",
+            action_items::format_item(item),
+            task_link(project.id, task.id)
+        ));
+        tasks.push(task);
+    }
+
+    let updated = action_items::append_to_section(&content, &lines);
+    tokio::fs::write(&file_path, &updated).await?;
+    deployment.document_index().invalidate(repo.id);
+    let mut warnings = Vec::new();
+    let committer = DocumentCommitter::load(&deployment, project.id).await?;
+    let commit_message = committer.message(
+        &format!("docs: add action items to {}", decoded_path),
+        &decoded_path,
+    );
+    let committed = match committer.commit(&deployment, &repo_path, &commit_message) {
+        Ok(committed) => committed,
+        Err(e) => {
+            tracing::warn!("Failed to auto-commit action items: {}", e);
+            warnings.push(auto_commit_warning("action items", &e));
+            false
+        }
+    };
+
+    Ok(ResponseJson(
+        ApiResponse::success(ApplyActionItemsResponse {
+            tasks,
+            committed,
+            content_hash: content_hash(&updated),
+        })
+        .with_warnings(warnings),
+    )
+    .into_response())
+}
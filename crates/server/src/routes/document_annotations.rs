@@ -0,0 +1,340 @@
+//! Comments of reviewers on ranges of lines of documents, kept outside git so reviewing
+//! a document doesn't change it. Annotations follow the text they were left on when the
+//! document is edited; ones whose text is gone are listed as detached.
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path as AxumPath, Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, patch},
+};
+use db::models::{
+    document_annotation::{CreateDocumentAnnotation, DocumentAnnotation},
+    project::Project,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::document_annotations::{self, Anchor};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{
+        documents::{
+            DocumentReader, find_document_file, project_repositories, validate_relative_path,
+        },
+        paths,
+    },
+};
+
+/// Longest annotation body that is stored
+const MAX_BODY_CHARS: usize = 10_000;
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentAnnotationsQuery {
+    /// Document to list the annotations of; all documents of the project if not given
+    pub relative_path: Option<String>,
+    pub repository_id: Option<Uuid>,
+    #[serde(default)]
+    pub include_resolved: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateDocumentAnnotationRequest {
+    pub relative_path: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub repository_id: Option<Uuid>,
+    /// First line of the range, counted from 1
+    pub line_start: usize,
+    /// Last line of the range, inclusive
+    pub line_end: usize,
+    /// Name of the reviewer leaving the annotation
+    pub author: String,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateDocumentAnnotationRequest {
+    /// Name of the reviewer making the change; only the author can edit the body
+    pub author: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub body: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub resolved: Option<bool>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct LocatedDocumentAnnotation {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub annotation: DocumentAnnotation,
+    /// The annotated text is no longer in the document, or the document is gone; the
+    /// lines are where the annotation was last found
+    pub detached: bool,
+}
+
+fn require_text<'a>(value: &'a str, name: &str) -> Result<&'a str, ApiError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "An annotation {} is required",
+            name
+        )));
+    }
+    Ok(value)
+}
+
+fn validate_body(body: &str) -> Result<&str, ApiError> {
+    let body = require_text(body, "body")?;
+    if body.chars().count() > MAX_BODY_CHARS {
+        return Err(ApiError::BadRequest(format!(
+            "Annotations can be at most {} characters",
+            MAX_BODY_CHARS
+        )));
+    }
+    Ok(body)
+}
+
+fn stored_anchor(annotation: &DocumentAnnotation) -> Anchor {
+    Anchor {
+        line_start: annotation.line_start.max(1) as usize,
+        line_end: annotation.line_end.max(1) as usize,
+        quote: annotation.quote.clone(),
+        prefix: annotation.prefix.clone(),
+        suffix: annotation.suffix.clone(),
+    }
+}
+
+/// Find the annotations of one document in its current content, moving those that
+/// moved. `content` is `None` if the document is gone.
+async fn locate_annotations(
+    deployment: &DeploymentImpl,
+    annotations: Vec<DocumentAnnotation>,
+    content: Option<&str>,
+) -> Result<Vec<LocatedDocumentAnnotation>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut located = Vec::with_capacity(annotations.len());
+    for mut annotation in annotations {
+        let stored = stored_anchor(&annotation);
+        let found = content.and_then(|content| document_annotations::locate(content, &stored));
+        let Some(anchor) = found else {
+            located.push(LocatedDocumentAnnotation {
+                annotation,
+                detached: true,
+            });
+            continue;
+        };
+        if anchor != stored {
+            DocumentAnnotation::reanchor(
+                pool,
+                annotation.id,
+                anchor.line_start as i64,
+                anchor.line_end as i64,
+                &anchor.quote,
+                &anchor.prefix,
+                &anchor.suffix,
+            )
+            .await?;
+            annotation.line_start = anchor.line_start as i64;
+            annotation.line_end = anchor.line_end as i64;
+            annotation.quote = anchor.quote;
+            annotation.prefix = anchor.prefix;
+            annotation.suffix = anchor.suffix;
+        }
+        located.push(LocatedDocumentAnnotation {
+            annotation,
+            detached: false,
+        });
+    }
+    Ok(located)
+}
+
+/// Annotations of a document, or of every document of the project, in the order of
+/// their lines
+pub async fn list_annotations(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DocumentAnnotationsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<LocatedDocumentAnnotation>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let repositories = project_repositories(&deployment, project.id, query.repository_id).await?;
+
+    let annotations = match &query.relative_path {
+        Some(relative_path) => {
+            let relative_path = validate_relative_path(relative_path)?;
+            let mut annotations = Vec::new();
+            for repo in &repositories {
+                annotations.extend(
+                    DocumentAnnotation::find_by_document(
+                        pool,
+                        repo.id,
+                        relative_path,
+                        query.include_resolved,
+                    )
+                    .await?,
+                );
+            }
+            annotations
+        }
+        None => DocumentAnnotation::find_by_project_id(pool, project.id, query.include_resolved)
+            .await?
+            .into_iter()
+            .filter(|annotation| repositories.iter().any(|r| r.id == annotation.repo_id))
+            .collect(),
+    };
+
+    // Annotations come ordered by document, so each document is read once
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let mut located = Vec::with_capacity(annotations.len());
+    let mut rest = annotations.into_iter().peekable();
+    while let Some(first) = rest.next() {
+        let mut document = vec![first];
+        while let Some(next) = rest.next_if(|next| {
+            next.repo_id == document[0].repo_id && next.relative_path == document[0].relative_path
+        }) {
+            document.push(next);
+        }
+        let content = match repositories.iter().find(|r| r.id == document[0].repo_id) {
+            Some(repo) => {
+                reader
+                    .read_optional(&repo.path, &document[0].relative_path)
+                    .await?
+            }
+            None => None,
+        };
+        let mut found = locate_annotations(&deployment, document, content.as_deref()).await?;
+        found.sort_by_key(|located| located.annotation.line_start);
+        located.extend(found);
+    }
+    Ok(ResponseJson(ApiResponse::success(located)))
+}
+
+/// Leave an annotation on a range of lines of a document
+pub async fn create_annotation(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateDocumentAnnotationRequest>,
+) -> Result<ResponseJson<ApiResponse<LocatedDocumentAnnotation>>, ApiError> {
+    let author = require_text(&payload.author, "author")?;
+    let body = validate_body(&payload.body)?;
+    let relative_path = validate_relative_path(&payload.relative_path)?;
+    let (repo, _) = find_document_file(
+        &deployment,
+        project.id,
+        payload.repository_id,
+        relative_path,
+    )
+    .await?;
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let content = reader.read(&repo.path, relative_path).await?;
+    let anchor = document_annotations::anchor(&content, payload.line_start, payload.line_end)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let data = CreateDocumentAnnotation {
+        project_id: project.id,
+        repo_id: repo.id,
+        relative_path: relative_path.to_string(),
+        line_start: anchor.line_start as i64,
+        line_end: anchor.line_end as i64,
+        quote: anchor.quote,
+        prefix: anchor.prefix,
+        suffix: anchor.suffix,
+        author: author.to_string(),
+        body: body.to_string(),
+    };
+    let annotation = DocumentAnnotation::create(&deployment.db().pool, &data).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "document_annotation_created",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "line_count": data.line_end - data.line_start + 1,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        LocatedDocumentAnnotation {
+            annotation,
+            detached: false,
+        },
+    )))
+}
+
+async fn find_project_annotation(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    annotation_id: Uuid,
+) -> Result<DocumentAnnotation, ApiError> {
+    DocumentAnnotation::find_by_id(&deployment.db().pool, annotation_id)
+        .await?
+        .filter(|annotation| annotation.project_id == project_id)
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))
+}
+
+/// Edit the body of an annotation, or resolve or reopen it
+pub async fn update_annotation(
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((project_id, annotation_id)): AxumPath<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateDocumentAnnotationRequest>,
+) -> Result<ResponseJson<ApiResponse<DocumentAnnotation>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let annotation = find_project_annotation(&deployment, project_id, annotation_id).await?;
+    let author = require_text(&payload.author, "author")?;
+
+    if let Some(body) = &payload.body {
+        if author != annotation.author {
+            return Err(ApiError::Forbidden(format!(
+                "Only {} can edit this annotation",
+                annotation.author
+            )));
+        }
+        DocumentAnnotation::update_body(pool, annotation.id, validate_body(body)?).await?;
+    }
+    if let Some(resolved) = payload.resolved
+        && resolved != annotation.resolved
+    {
+        DocumentAnnotation::set_resolved(pool, annotation.id, resolved.then_some(author)).await?;
+    }
+
+    let annotation = find_project_annotation(&deployment, project_id, annotation_id).await?;
+    Ok(ResponseJson(ApiResponse::success(annotation)))
+}
+
+pub async fn delete_annotation(
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((project_id, annotation_id)): AxumPath<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let annotation = find_project_annotation(&deployment, project_id, annotation_id).await?;
+    DocumentAnnotation::delete(&deployment.db().pool, annotation.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route("/", get(list_annotations).post(create_annotation))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    // Annotations are looked up by their own id and checked against the project
+    let annotation_router = Router::new().route(
+        "/{annotation_id}",
+        patch(update_annotation).delete(delete_annotation),
+    );
+
+    Router::new().nest(
+        paths::PROJECT_DOCUMENT_ANNOTATIONS,
+        project_router.merge(annotation_router),
+    )
+}
@@ -0,0 +1,394 @@
+use std::path::PathBuf;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path as AxumPath, Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    document_approval::{
+        DocumentApprovalRule, DocumentChangeRequest, DocumentChangeReview, DocumentChangeStatus,
+        DocumentReviewDecision, SetDocumentApprovalRule,
+    },
+    project::Project,
+    repo::Repo,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{
+        document_branches, document_commits::DocumentCommitter,
+        document_encryption::DocumentKeyring, paths,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeRequestQuery {
+    pub status: Option<DocumentChangeStatus>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ReviewDocumentChangeRequest {
+    /// Must be one of the change's required reviewers
+    pub reviewer: String,
+    pub decision: DocumentReviewDecision,
+    pub comment: Option<String>,
+}
+
+/// A change request with its reviews and who still has to approve it
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentChangeRequestDetails {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub request: DocumentChangeRequest,
+    pub reviews: Vec<DocumentChangeReview>,
+    /// Required reviewers who have not approved yet
+    pub awaiting: Vec<String>,
+}
+
+/// Stage `content` for `relative_path` if the document is in a protected folder. Returns
+/// `None` when no rule applies and the edit can be saved directly. Editing a document
+/// that already has a pending change replaces the proposal and resets its reviews.
+pub(crate) async fn stage_change(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repo: &Repo,
+    relative_path: &str,
+    content: &str,
+) -> Result<Option<DocumentChangeRequest>, ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(rule) = DocumentApprovalRule::find_for_path(pool, project_id, relative_path).await?
+    else {
+        return Ok(None);
+    };
+
+    let request =
+        match DocumentChangeRequest::find_pending_for_path(pool, repo.id, relative_path).await? {
+            Some(pending) => {
+                DocumentChangeRequest::update_content(pool, pending.id, content, &rule.reviewers)
+                    .await?;
+                DocumentChangeRequest::find_by_id(pool, pending.id).await?
+            }
+            None => Some(
+                DocumentChangeRequest::create(
+                    pool,
+                    project_id,
+                    repo.id,
+                    relative_path,
+                    content,
+                    &rule.reviewers,
+                )
+                .await?,
+            ),
+        };
+    Ok(request)
+}
+
+/// Whether edits to `relative_path` are staged for review instead of saved
+pub(crate) async fn is_protected(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    relative_path: &str,
+) -> Result<bool, ApiError> {
+    Ok(
+        DocumentApprovalRule::find_for_path(&deployment.db().pool, project_id, relative_path)
+            .await?
+            .is_some(),
+    )
+}
+
+/// Deleting or moving a protected document can't be staged, so it is refused outright
+pub(crate) async fn require_unprotected(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    relative_path: &str,
+) -> Result<(), ApiError> {
+    match DocumentApprovalRule::find_for_path(&deployment.db().pool, project_id, relative_path)
+        .await?
+    {
+        Some(rule) => Err(ApiError::Forbidden(format!(
+            "'{}' is in the protected folder '{}'; only reviewed edits are allowed there",
+            relative_path, rule.folder
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Normalize and validate the rules of a project before they replace the existing ones
+fn normalize_rules(
+    rules: Vec<SetDocumentApprovalRule>,
+) -> Result<Vec<SetDocumentApprovalRule>, ApiError> {
+    let mut normalized: Vec<SetDocumentApprovalRule> = Vec::with_capacity(rules.len());
+    for rule in rules {
+        let folder = rule.folder.trim().trim_matches('/').to_string();
+        if folder.is_empty() || folder.split('/').any(|segment| segment == "..") {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid protected folder '{}'",
+                rule.folder
+            )));
+        }
+        let mut reviewers: Vec<String> = Vec::with_capacity(rule.reviewers.len());
+        for reviewer in rule.reviewers {
+            let reviewer = reviewer.trim().to_string();
+            if !reviewer.is_empty() && !reviewers.contains(&reviewer) {
+                reviewers.push(reviewer);
+            }
+        }
+        if reviewers.is_empty() {
+            return Err(ApiError::BadRequest(format!(
+                "Protected folder '{}' needs at least one reviewer",
+                folder
+            )));
+        }
+        if normalized.iter().any(|existing| existing.folder == folder) {
+            return Err(ApiError::BadRequest(format!(
+                "Protected folder '{}' is listed twice",
+                folder
+            )));
+        }
+        normalized.push(SetDocumentApprovalRule { folder, reviewers });
+    }
+    Ok(normalized)
+}
+
+async fn load_details(
+    deployment: &DeploymentImpl,
+    request: DocumentChangeRequest,
+) -> Result<DocumentChangeRequestDetails, ApiError> {
+    let reviews =
+        DocumentChangeReview::find_by_change_request_id(&deployment.db().pool, request.id).await?;
+    let awaiting = request
+        .required_reviewers
+        .iter()
+        .filter(|reviewer| {
+            !reviews.iter().any(|review| {
+                &review.reviewer == *reviewer && review.decision == DocumentReviewDecision::Approved
+            })
+        })
+        .cloned()
+        .collect();
+    Ok(DocumentChangeRequestDetails {
+        request,
+        reviews,
+        awaiting,
+    })
+}
+
+async fn find_project_request(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    request_id: Uuid,
+) -> Result<DocumentChangeRequest, ApiError> {
+    DocumentChangeRequest::find_by_id(&deployment.db().pool, request_id)
+        .await?
+        .filter(|request| request.project_id == project_id)
+        .ok_or_else(|| ApiError::BadRequest(format!("Change request {} not found", request_id)))
+}
+
+/// Write an approved change to the docs branch and commit it. Returns the commit hash.
+async fn apply_change(
+    deployment: &DeploymentImpl,
+    request: &DocumentChangeRequest,
+    approvers: &[String],
+) -> Result<String, ApiError> {
+    let repo = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, request.project_id)
+        .await?
+        .into_iter()
+        .find(|repo| repo.id == request.repo_id)
+        .ok_or_else(|| ApiError::BadRequest("The change's repository was removed".to_string()))?;
+    let repo_path = PathBuf::from(&repo.path);
+    document_branches::require_editable_branch(
+        deployment,
+        request.project_id,
+        &repo_path,
+        &request.relative_path,
+    )
+    .await?;
+
+    let canonical_repo = repo_path
+        .canonicalize()
+        .map_err(|e| ApiError::BadRequest(format!("Failed to resolve repository path: {}", e)))?;
+    let file_path = repo_path.join(&request.relative_path);
+    let canonical_parent = file_path
+        .parent()
+        .and_then(|parent| parent.canonicalize().ok())
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Folder of '{}' no longer exists",
+                request.relative_path
+            ))
+        })?;
+    if !canonical_parent.starts_with(&canonical_repo) {
+        return Err(ApiError::BadRequest(
+            "Invalid file path: access denied".to_string(),
+        ));
+    }
+
+    // Documents of encrypted folders are only ever written encrypted
+    let content = DocumentKeyring::load(deployment, request.project_id)
+        .await?
+        .encrypt(&request.relative_path, &request.content)?;
+    tokio::fs::write(&file_path, &content)
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to save file: {}", e)))?;
+    deployment.document_index().invalidate(repo.id);
+    let committer = DocumentCommitter::load(deployment, request.project_id).await?;
+    let message = format!(
+        "{}\n\nApproved-by: {}",
+        committer.message(
+            &format!("docs: update {}", request.relative_path),
+            &request.relative_path
+        ),
+        approvers.join(", ")
+    );
+    // The approval is recorded in this commit, so it isn't deferred with other saves
+    committer.commit_now(deployment, &repo_path, &message)?;
+    Ok(deployment
+        .git_backend()
+        .resolve_revision(&repo_path, "HEAD")?)
+}
+
+pub async fn get_rules(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DocumentApprovalRule>>>, ApiError> {
+    let rules = DocumentApprovalRule::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+/// Replace the project's protected folders
+pub async fn set_rules(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<Vec<SetDocumentApprovalRule>>,
+) -> Result<ResponseJson<ApiResponse<Vec<DocumentApprovalRule>>>, ApiError> {
+    let rules = normalize_rules(payload)?;
+    let rules =
+        DocumentApprovalRule::replace_for_project(&deployment.db().pool, project.id, &rules)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+pub async fn list_change_requests(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ChangeRequestQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<DocumentChangeRequestDetails>>>, ApiError> {
+    let requests =
+        DocumentChangeRequest::find_by_project_id(&deployment.db().pool, project.id, query.status)
+            .await?;
+    let mut details = Vec::with_capacity(requests.len());
+    for request in requests {
+        details.push(load_details(&deployment, request).await?);
+    }
+    Ok(ResponseJson(ApiResponse::success(details)))
+}
+
+pub async fn get_change_request(
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((project_id, request_id)): AxumPath<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<DocumentChangeRequestDetails>>, ApiError> {
+    let request = find_project_request(&deployment, project_id, request_id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        load_details(&deployment, request).await?,
+    )))
+}
+
+/// Record a reviewer's decision. A rejection closes the change; the last required
+/// approval commits it to the docs branch.
+pub async fn review_change_request(
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((project_id, request_id)): AxumPath<(Uuid, Uuid)>,
+    Json(payload): Json<ReviewDocumentChangeRequest>,
+) -> Result<ResponseJson<ApiResponse<DocumentChangeRequestDetails>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let request = find_project_request(&deployment, project_id, request_id).await?;
+    if request.status != DocumentChangeStatus::Pending {
+        return Err(ApiError::Conflict(
+            "This change request has already been resolved".to_string(),
+        ));
+    }
+    let reviewer = payload.reviewer.trim();
+    if !request.required_reviewers.iter().any(|r| r == reviewer) {
+        return Err(ApiError::Forbidden(format!(
+            "'{}' is not a reviewer of this change",
+            reviewer
+        )));
+    }
+    let comment = payload
+        .comment
+        .as_deref()
+        .map(str::trim)
+        .filter(|comment| !comment.is_empty());
+    DocumentChangeReview::upsert(pool, request.id, reviewer, payload.decision, comment).await?;
+
+    let details = load_details(&deployment, request).await?;
+    match payload.decision {
+        DocumentReviewDecision::Rejected => {
+            DocumentChangeRequest::set_status(
+                pool,
+                details.request.id,
+                DocumentChangeStatus::Rejected,
+                None,
+            )
+            .await?;
+        }
+        DocumentReviewDecision::Approved if details.awaiting.is_empty() => {
+            let commit_sha = apply_change(
+                &deployment,
+                &details.request,
+                &details.request.required_reviewers,
+            )
+            .await?;
+            DocumentChangeRequest::set_status(
+                pool,
+                details.request.id,
+                DocumentChangeStatus::Approved,
+                Some(&commit_sha),
+            )
+            .await?;
+            tracing::info!(
+                "Committed approved change to {} as {}",
+                details.request.relative_path,
+                commit_sha
+            );
+        }
+        DocumentReviewDecision::Approved => {}
+    }
+
+    let request = find_project_request(&deployment, project_id, request_id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        load_details(&deployment, request).await?,
+    )))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route("/rules", get(get_rules).put(set_rules))
+        .route("/requests", get(list_change_requests))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    // Change requests are looked up by their own id and checked against the project
+    let request_router = Router::new()
+        .route("/requests/{request_id}", get(get_change_request))
+        .route("/requests/{request_id}/review", post(review_change_request));
+
+    Router::new().nest(
+        paths::PROJECT_DOCUMENT_APPROVALS,
+        project_router.merge(request_router),
+    )
+}
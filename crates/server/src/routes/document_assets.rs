@@ -0,0 +1,217 @@
+//! Images for markdown documents, uploaded into an assets folder next to the document and
+//! committed, so the document can link them relatively.
+
+use std::path::Path;
+
+use axum::{
+    Extension,
+    extract::{Multipart, Query, State},
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use db::models::{document_encryption::DocumentEncryptedFolder, project::Project};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::document_assets;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{
+        document_batch::within_repository,
+        document_branches,
+        document_commits::DocumentCommitter,
+        documents::{
+            DocumentFileType, DocumentReadOnlyViolation, auto_commit_warning, document_file_type,
+            find_document_file, find_read_only_violation, validate_relative_path,
+        },
+    },
+};
+
+/// Size limit of an uploaded image
+pub(crate) const MAX_ASSET_BYTES: usize = 20 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentAssetQuery {
+    /// Relative path of the document the image is for
+    pub document: String,
+    pub repository_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentAssetResponse {
+    pub repository_id: Uuid,
+    /// Path of the image in the repository
+    pub relative_path: String,
+    /// Link to the image, relative to the document
+    pub link: String,
+    /// Markdown image to insert into the document
+    pub markdown: String,
+    /// False if the same image was already stored for the document's folder
+    pub created: bool,
+    pub committed: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentAssetsFolder {
+    /// Folder next to a document its images are stored in
+    pub folder: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateDocumentAssetsFolder {
+    /// `null` resets the project to the default folder
+    pub folder: Option<String>,
+}
+
+/// Store the image in the `image` field of a multipart upload for the markdown document
+/// `document`, in the project's assets folder next to it, and commit it
+pub async fn upload_document_asset(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(query): Query<DocumentAssetQuery>,
+    mut multipart: Multipart,
+) -> Result<Response, ApiError> {
+    let pool = &deployment.db().pool;
+    let document_path = validate_relative_path(&query.document)?.to_string();
+    let (repo, file_path) =
+        find_document_file(&deployment, project.id, query.repository_id, &document_path).await?;
+    if document_file_type(&file_path) != Some(DocumentFileType::Markdown) {
+        return Err(ApiError::BadRequest(
+            "Images can only be added to markdown documents".to_string(),
+        ));
+    }
+
+    let mut upload = None;
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() == Some("image") {
+            let name = field.file_name().unwrap_or("image.png").to_string();
+            upload = Some((name, field.bytes().await?));
+            break;
+        }
+    }
+    let (original_name, data) = upload
+        .ok_or_else(|| ApiError::BadRequest("Upload the image in the `image` field".to_string()))?;
+    let file_name = document_assets::asset_file_name(&original_name, &data).ok_or_else(|| {
+        ApiError::BadRequest(
+            "Unsupported image type. Upload a PNG, JPEG, GIF, WebP, SVG or AVIF image.".to_string(),
+        )
+    })?;
+
+    let folder = Project::find_document_assets_folder(pool, project.id).await?;
+    let relative_path = document_assets::asset_path(&document_path, &folder, &file_name);
+    let asset_file = repo.path.join(&relative_path);
+    if !within_repository(&repo.path, &asset_file) {
+        return Err(ApiError::BadRequest(
+            "Invalid asset path: access denied".to_string(),
+        ));
+    }
+    if let Some(violation) =
+        find_read_only_violation(&deployment, project.id, &relative_path).await?
+    {
+        let response: ApiResponse<DocumentAssetResponse, DocumentReadOnlyViolation> =
+            ApiResponse::error_with_data(violation);
+        return Ok(ResponseJson(response).into_response());
+    }
+    // Images aren't encrypted, so they would leak what the folder protects
+    let encrypted_folders = DocumentEncryptedFolder::find_by_project_id(pool, project.id).await?;
+    if let Some(encrypted) =
+        DocumentEncryptedFolder::find_for_path(&encrypted_folders, &relative_path)
+    {
+        return Err(ApiError::Forbidden(format!(
+            "Images can't be stored in the encrypted folder '{}'",
+            encrypted.folder
+        )));
+    }
+
+    // Ensure we're on a branch documents can be edited on before adding files
+    document_branches::require_editable_branch(&deployment, project.id, &repo.path, &relative_path)
+        .await?;
+    let created = !asset_file.exists();
+    let mut committed = false;
+    let mut warnings = Vec::new();
+    if created {
+        if let Some(parent) = asset_file.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                ApiError::BadRequest(format!("Failed to create assets folder: {}", e))
+            })?;
+        }
+        tokio::fs::write(&asset_file, &data).await.map_err(|e| {
+            tracing::error!("Failed to write asset {:?}: {}", asset_file, e);
+            ApiError::BadRequest(format!("Failed to save image: {}", e))
+        })?;
+        tracing::info!("Asset stored: {:?}", asset_file);
+        deployment.document_index().invalidate(repo.id);
+
+        let committer = DocumentCommitter::load(&deployment, project.id).await?;
+        let commit_message = committer.message(
+            &format!("docs: add image {}", relative_path),
+            &relative_path,
+        );
+        committed = match committer.commit(&deployment, &repo.path, &commit_message) {
+            Ok(committed) => committed,
+            Err(e) => {
+                tracing::warn!("Failed to auto-commit asset: {}", e);
+                warnings.push(auto_commit_warning("image", &e));
+                false
+            }
+        };
+    }
+
+    let alt = Path::new(&original_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Ok(ResponseJson(
+        ApiResponse::success(DocumentAssetResponse {
+            repository_id: repo.id,
+            link: format!("{}/{}", folder, file_name),
+            markdown: document_assets::markdown_image(&folder, &file_name, &alt),
+            relative_path,
+            created,
+            committed,
+        })
+        .with_warnings(warnings),
+    )
+    .into_response())
+}
+
+pub async fn get_document_assets_folder(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentAssetsFolder>>, ApiError> {
+    let folder = Project::find_document_assets_folder(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentAssetsFolder {
+        folder,
+    })))
+}
+
+/// Set the folder uploaded images are stored in, or reset to the default. Images already
+/// uploaded stay where they are.
+pub async fn update_document_assets_folder(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<UpdateDocumentAssetsFolder>,
+) -> Result<ResponseJson<ApiResponse<DocumentAssetsFolder>>, ApiError> {
+    let folder = body
+        .folder
+        .as_deref()
+        .map(|folder| folder.trim().trim_matches('/'));
+    if let Some(folder) = folder
+        && !document_assets::is_valid_assets_folder(folder)
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid assets folder '{}': use a relative path of letters, digits, '-', '_' \
+             and '.'",
+            folder
+        )));
+    }
+    let pool = &deployment.db().pool;
+    Project::set_document_assets_folder(pool, project.id, folder).await?;
+    let folder = Project::find_document_assets_folder(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentAssetsFolder {
+        folder,
+    })))
+}
@@ -0,0 +1,388 @@
+//! Writing many documents at once, e.g. for a find-and-replace or a template rollout,
+//! with one commit per repository instead of one commit per document.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use axum::{
+    Extension,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use db::models::{document_lock::DocumentLock, project::Project, repo::Repo};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::{
+    diff::create_unified_diff,
+    response::{ApiResponse, ApiWarning},
+};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{
+        board_links, document_approvals, document_branches,
+        document_commits::DocumentCommitter,
+        document_encryption, document_roots, document_task_sync,
+        documents::{
+            DocumentCommitPreview, DocumentFileType, DocumentReadOnlyViolation, DryRunQuery,
+            auto_commit_warning, content_hash, find_read_only_violation, project_extensions,
+            project_repositories, require_document_type, validate_relative_path,
+        },
+    },
+};
+
+const MAX_BATCH_DOCUMENTS: usize = 500;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BatchDocumentUpdate {
+    /// Relative path of the document; it is created if it doesn't exist
+    pub path: String,
+    pub content: String,
+    /// `content_hash` the new content is based on; required for existing documents
+    #[serde(default)]
+    #[ts(optional)]
+    pub expected_hash: Option<String>,
+    /// Repository of the document, for projects with several. New documents go to the
+    /// primary repository by default.
+    #[serde(default)]
+    #[ts(optional)]
+    pub repository_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BatchUpdateDocumentsRequest {
+    pub documents: Vec<BatchDocumentUpdate>,
+    /// Commit message; by default it names the document or counts the documents
+    #[serde(default)]
+    #[ts(optional)]
+    pub message: Option<String>,
+    /// Name the documents were locked with, if any
+    #[serde(default)]
+    #[ts(optional)]
+    pub lock_holder: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct BatchUpdatedDocument {
+    pub repository_id: Uuid,
+    pub relative_path: String,
+    pub created: bool,
+    pub content_hash: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct BatchUpdateDocumentsResponse {
+    pub documents: Vec<BatchUpdatedDocument>,
+    /// Whether every repository's commit was made
+    pub committed: bool,
+}
+
+/// A document of a batch that changed since it was read, or that was expected to exist
+#[derive(Debug, Serialize, TS)]
+pub struct BatchDocumentConflict {
+    pub repository_id: Uuid,
+    pub relative_path: String,
+    /// `None` if the document doesn't exist
+    pub current_hash: Option<String>,
+}
+
+/// `error_data` of the 409 returned when documents of a batch changed; nothing is written
+#[derive(Debug, Serialize, TS)]
+pub struct BatchUpdateConflict {
+    pub conflicts: Vec<BatchDocumentConflict>,
+}
+
+/// A validated document write of a batch
+struct PlannedWrite {
+    repo: Repo,
+    relative_path: String,
+    file_path: PathBuf,
+    file_type: DocumentFileType,
+    /// Content on disk, `None` for new documents
+    previous: Option<String>,
+    content: String,
+}
+
+/// Whether `file_path`, which may not exist yet, resolves to a path inside `repo_path`
+pub(crate) fn within_repository(repo_path: &Path, file_path: &Path) -> bool {
+    let Ok(canonical_repo) = repo_path.canonicalize() else {
+        return false;
+    };
+    file_path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .and_then(|ancestor| ancestor.canonicalize().ok())
+        .is_some_and(|ancestor| ancestor.starts_with(&canonical_repo))
+}
+
+/// Put back the documents of a batch that were written before a write failed
+async fn restore(writes: &[PlannedWrite]) {
+    for write in writes {
+        let restored = match &write.previous {
+            Some(previous) => tokio::fs::write(&write.file_path, previous).await,
+            None => tokio::fs::remove_file(&write.file_path).await,
+        };
+        if let Err(e) = restored {
+            tracing::error!("Failed to restore {:?}: {}", write.file_path, e);
+        }
+    }
+}
+
+/// Write several documents and commit them together, one commit per repository. All
+/// documents are checked before anything is written, so a batch is applied completely
+/// or not at all.
+pub async fn batch_update_documents(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(dry_run): Query<DryRunQuery>,
+    ResponseJson(body): ResponseJson<BatchUpdateDocumentsRequest>,
+) -> Result<Response, ApiError> {
+    if body.documents.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Send at least one document".to_string(),
+        ));
+    }
+    if body.documents.len() > MAX_BATCH_DOCUMENTS {
+        return Err(ApiError::BadRequest(format!(
+            "A batch can write at most {} documents",
+            MAX_BATCH_DOCUMENTS
+        )));
+    }
+
+    let pool = &deployment.db().pool;
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let roots = document_roots::project_document_roots(&deployment, project.id).await?;
+    let mut writes: Vec<PlannedWrite> = Vec::with_capacity(body.documents.len());
+    let mut seen = HashSet::new();
+    let mut conflicts = Vec::new();
+    for update in body.documents {
+        let relative_path = validate_relative_path(&update.path)?.to_string();
+        let repositories =
+            project_repositories(&deployment, project.id, update.repository_id).await?;
+        let existing = repositories
+            .iter()
+            .position(|repo| repo.path.join(&relative_path).is_file());
+        let Some(repo) = repositories.into_iter().nth(existing.unwrap_or(0)) else {
+            return Err(ApiError::BadRequest(
+                "No repository found for this project".to_string(),
+            ));
+        };
+        let file_path = repo.path.join(&relative_path);
+        if !within_repository(&repo.path, &file_path) {
+            return Err(ApiError::BadRequest(
+                "Invalid file path: access denied".to_string(),
+            ));
+        }
+        if !seen.insert((repo.id, relative_path.clone())) {
+            return Err(ApiError::BadRequest(format!(
+                "'{}' is in the batch more than once",
+                relative_path
+            )));
+        }
+        let file_type = require_document_type(&file_path, &extensions)?;
+        if existing.is_none() {
+            document_roots::require_in_document_roots(&roots, &relative_path)?;
+        }
+
+        if let Some(violation) =
+            find_read_only_violation(&deployment, project.id, &relative_path).await?
+        {
+            let response: ApiResponse<BatchUpdateDocumentsResponse, DocumentReadOnlyViolation> =
+                ApiResponse::error_with_data(violation);
+            return Ok(ResponseJson(response).into_response());
+        }
+        // Reviews are per document, so protected documents can't be part of a batch
+        document_approvals::require_unprotected(&deployment, project.id, &relative_path).await?;
+        document_encryption::require_unencrypted(&deployment, project.id, &relative_path).await?;
+        if let Some(lock) = DocumentLock::find_active(pool, repo.id, &relative_path).await?
+            && body.lock_holder.as_deref().map(str::trim) != Some(lock.holder.as_str())
+        {
+            let response: ApiResponse<BatchUpdateDocumentsResponse, DocumentLock> =
+                ApiResponse::error_with_data(lock);
+            return Ok((StatusCode::LOCKED, ResponseJson(response)).into_response());
+        }
+
+        let previous = match existing {
+            Some(_) => Some(tokio::fs::read_to_string(&file_path).await?),
+            None => None,
+        };
+        let current_hash = previous.as_deref().map(content_hash);
+        match (&update.expected_hash, &current_hash) {
+            (None, Some(_)) => {
+                return Err(ApiError::BadRequest(format!(
+                    "Send the content_hash of '{}' as expected_hash, so concurrent edits \
+                     aren't overwritten",
+                    relative_path
+                )));
+            }
+            (Some(expected), current) if current.as_ref() != Some(expected) => {
+                conflicts.push(BatchDocumentConflict {
+                    repository_id: repo.id,
+                    relative_path: relative_path.clone(),
+                    current_hash: current_hash.clone(),
+                });
+            }
+            _ => {}
+        }
+        board_links::require_valid_links(
+            &deployment,
+            project.id,
+            &update.content,
+            previous.as_deref(),
+        )
+        .await?;
+
+        writes.push(PlannedWrite {
+            repo,
+            relative_path,
+            file_path,
+            file_type,
+            previous,
+            content: update.content,
+        });
+    }
+    if !conflicts.is_empty() {
+        let response: ApiResponse<BatchUpdateDocumentsResponse, BatchUpdateConflict> =
+            ApiResponse::error_with_data(BatchUpdateConflict { conflicts });
+        return Ok((StatusCode::CONFLICT, ResponseJson(response)).into_response());
+    }
+
+    let mut repositories: BTreeMap<Uuid, (&Repo, String)> = BTreeMap::new();
+    for write in &writes {
+        if !repositories.contains_key(&write.repo.id) {
+            let branch = if dry_run.dry_run {
+                document_branches::editable_branch(&deployment, project.id, &write.repo.path)
+                    .await?
+            } else {
+                document_branches::require_editable_branch(
+                    &deployment,
+                    project.id,
+                    &write.repo.path,
+                    &write.relative_path,
+                )
+                .await?
+            };
+            repositories.insert(write.repo.id, (&write.repo, branch));
+        }
+    }
+    let message = match body.message.as_deref().map(str::trim) {
+        Some(message) if !message.is_empty() => message.to_string(),
+        _ if writes.len() == 1 => format!("docs: update {}", writes[0].relative_path),
+        _ => format!("docs: update {} documents", writes.len()),
+    };
+    let committer = DocumentCommitter::load(&deployment, project.id).await?;
+    let commit_message = |repository_id: Uuid| {
+        let paths: Vec<&str> = writes
+            .iter()
+            .filter(|write| write.repo.id == repository_id)
+            .map(|write| write.relative_path.as_str())
+            .collect();
+        committer.message(&message, &paths.join(", "))
+    };
+
+    if dry_run.dry_run {
+        let previews: Vec<DocumentCommitPreview> = repositories
+            .values()
+            .map(|(repo, branch)| DocumentCommitPreview {
+                repository_id: repo.id,
+                branch: Some(branch.clone()),
+                commit_message: commit_message(repo.id),
+                diff: writes
+                    .iter()
+                    .filter(|write| write.repo.id == repo.id)
+                    .map(|write| {
+                        let previous = write.previous.as_deref().unwrap_or_default();
+                        create_unified_diff(&write.relative_path, previous, &write.content)
+                    })
+                    .collect(),
+            })
+            .collect();
+        return Ok(
+            ResponseJson(ApiResponse::<Vec<DocumentCommitPreview>>::success(previews))
+                .into_response(),
+        );
+    }
+
+    for (index, write) in writes.iter().enumerate() {
+        let written = match write.file_path.parent() {
+            Some(parent) => tokio::fs::create_dir_all(parent).await,
+            None => Ok(()),
+        };
+        let written = match written {
+            Ok(()) => tokio::fs::write(&write.file_path, &write.content).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = written {
+            tracing::error!("Failed to write {:?}: {}", write.file_path, e);
+            restore(&writes[..index]).await;
+            return Err(ApiError::BadRequest(format!(
+                "Failed to save '{}': {}; no document was changed",
+                write.relative_path, e
+            )));
+        }
+        deployment.document_index().invalidate(write.repo.id);
+    }
+
+    let mut warnings = Vec::new();
+    let mut committed = true;
+    for (repo, _) in repositories.values() {
+        match committer.commit(&deployment, &repo.path, &commit_message(repo.id)) {
+            Ok(true) => {}
+            Ok(false) => committed = false,
+            Err(e) => {
+                tracing::warn!("Failed to auto-commit document batch: {}", e);
+                warnings.push(auto_commit_warning("batch", &e));
+                committed = false;
+            }
+        }
+    }
+
+    let mut documents = Vec::with_capacity(writes.len());
+    for write in writes {
+        // Checked items of planning documents complete their tasks, as for single saves
+        if write.file_type == DocumentFileType::Markdown {
+            match document_task_sync::apply_saved_checkboxes(
+                &deployment,
+                project.id,
+                &write.repo,
+                &write.relative_path,
+                &write.content,
+            )
+            .await
+            {
+                Ok(sync_warnings) => warnings.extend(sync_warnings),
+                Err(e) => {
+                    tracing::warn!("Failed to sync task list of {}: {}", write.relative_path, e);
+                    warnings.push(ApiWarning::new(
+                        "document_task_sync_failed",
+                        format!(
+                            "{} was saved but its tasks were not updated: {e}",
+                            write.relative_path
+                        ),
+                    ));
+                }
+            }
+        }
+        documents.push(BatchUpdatedDocument {
+            repository_id: write.repo.id,
+            content_hash: content_hash(&write.content),
+            created: write.previous.is_none(),
+            relative_path: write.relative_path,
+        });
+    }
+
+    Ok(ResponseJson(
+        ApiResponse::success(BatchUpdateDocumentsResponse {
+            documents,
+            committed,
+        })
+        .with_warnings(warnings),
+    )
+    .into_response())
+}
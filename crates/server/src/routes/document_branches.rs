@@ -0,0 +1,251 @@
+//! Branch editing, for repositories whose main can't be pushed to. With it on, the first
+//! document edit made on main moves to a new `docs/<slug>` branch, later edits keep
+//! landing on that branch, and the branch is opened as a pull request on the
+//! repository's forge. Switching back to main starts a new branch with the next edit.
+
+use std::path::{Path, PathBuf};
+
+use axum::{Extension, extract::State, response::Json as ResponseJson};
+use db::models::{merge::MergeStatus, project::Project};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    document_store,
+    git_host::{self, CreatePrRequest, GitHostError, GitHostProvider},
+};
+use ts_rs::TS;
+use utils::{
+    i18n::t,
+    response::ApiResponse,
+    text::{git_branch_id, short_uuid},
+};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::documents::{DEFAULT_DOCS_BRANCH, require_main_branch},
+};
+
+/// Branches document edits go to when branch editing is on
+pub(crate) const DOCS_BRANCH_PREFIX: &str = "docs/";
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct DocumentBranchEditing {
+    /// Whether edits made on main go to a new `docs/` branch instead, to be merged
+    /// through a pull request
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateDocumentPullRequest {
+    /// Defaults to a title naming the changed documents
+    pub title: Option<String>,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub draft: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentPullRequest {
+    pub branch: String,
+    pub number: i64,
+    pub url: String,
+    /// False when the branch already had an open pull request, which is returned instead
+    pub created: bool,
+}
+
+/// The current branch of `repo_path`, and whether branch editing is on for the project
+async fn current_branch(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repo_path: &Path,
+) -> Result<(String, bool), ApiError> {
+    let enabled = Project::find_document_branch_editing(&deployment.db().pool, project_id).await?;
+    if !enabled {
+        return Ok((require_main_branch(deployment, repo_path)?, false));
+    }
+    if document_store::is_bare_repository(repo_path) {
+        return Err(ApiError::Forbidden(t(
+            "docs-bare-read-only",
+            &[("path", &repo_path.display())],
+        )));
+    }
+    let branch = deployment
+        .git_backend()
+        .get_current_branch(repo_path)
+        .map_err(|e| ApiError::BadRequest(t("docs-current-branch-failed", &[("error", &e)])))?;
+    if branch != DEFAULT_DOCS_BRANCH && !branch.starts_with(DOCS_BRANCH_PREFIX) {
+        return Err(ApiError::Forbidden(t(
+            "docs-branch-editing-guard",
+            &[
+                ("required", &DEFAULT_DOCS_BRANCH),
+                ("prefix", &DOCS_BRANCH_PREFIX),
+                ("current", &branch),
+            ],
+        )));
+    }
+    Ok((branch, true))
+}
+
+/// The branch document edits of `repo_path` land on, without creating it: the current
+/// branch, which is main unless branch editing is on
+pub(crate) async fn editable_branch(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repo_path: &Path,
+) -> Result<String, ApiError> {
+    Ok(current_branch(deployment, project_id, repo_path).await?.0)
+}
+
+/// Require `repo_path` to be on a branch documents can be edited on, and return it. That
+/// is main, or with branch editing on a `docs/` branch, which is created from main for
+/// an edit made there and named after `subject`, e.g. the edited path.
+pub(crate) async fn require_editable_branch(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repo_path: &Path,
+    subject: &str,
+) -> Result<String, ApiError> {
+    let (branch, enabled) = current_branch(deployment, project_id, repo_path).await?;
+    if !enabled || branch != DEFAULT_DOCS_BRANCH {
+        return Ok(branch);
+    }
+
+    let slug = match git_branch_id(subject) {
+        slug if slug.is_empty() => "edit".to_string(),
+        slug => slug,
+    };
+    let branch = format!(
+        "{}{}-{}",
+        DOCS_BRANCH_PREFIX,
+        slug,
+        short_uuid(&Uuid::new_v4())
+    );
+    deployment
+        .git_backend()
+        .create_branch_at_head(repo_path, &branch)?;
+    tracing::info!(
+        "Moved document edits of {} to branch {}",
+        repo_path.display(),
+        branch
+    );
+    Ok(branch)
+}
+
+pub async fn get_document_branch_editing(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentBranchEditing>>, ApiError> {
+    let enabled = Project::find_document_branch_editing(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentBranchEditing {
+        enabled,
+    })))
+}
+
+/// Turn branch editing on or off
+pub async fn update_document_branch_editing(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<DocumentBranchEditing>,
+) -> Result<ResponseJson<ApiResponse<DocumentBranchEditing>>, ApiError> {
+    Project::set_document_branch_editing(&deployment.db().pool, project.id, body.enabled).await?;
+    Ok(ResponseJson(ApiResponse::success(body)))
+}
+
+/// Default title of a pull request changing `paths`
+fn default_title(paths: &[String]) -> String {
+    match paths {
+        [] => "docs: update documents".to_string(),
+        [path] => format!("docs: update {}", path),
+        [path, rest @ ..] => format!("docs: update {} and {} more", path, rest.len()),
+    }
+}
+
+/// Push the `docs/` branch the primary repository is on and open a pull request of it
+/// against main, or return the open one it already has
+pub async fn create_document_pull_request(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<CreateDocumentPullRequest>,
+) -> Result<ResponseJson<ApiResponse<DocumentPullRequest>>, ApiError> {
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project.id)
+        .await?;
+    let repo = repositories
+        .first()
+        .ok_or_else(|| ApiError::BadRequest(t("docs-no-repository", &[])))?;
+    let repo_path = PathBuf::from(&repo.path);
+
+    let branch = editable_branch(&deployment, project.id, &repo_path).await?;
+    if !branch.starts_with(DOCS_BRANCH_PREFIX) {
+        return Err(ApiError::BadRequest(t(
+            "docs-pr-not-docs-branch",
+            &[("prefix", &DOCS_BRANCH_PREFIX), ("current", &branch)],
+        )));
+    }
+
+    let git = deployment.git();
+    let remote = git.resolve_remote_name_for_branch(&repo_path, DEFAULT_DOCS_BRANCH)?;
+    let remote_url = git.get_remote_url(&repo_path, &remote)?;
+    git.push_to_remote(&repo_path, &branch, false)?;
+
+    let git_host = match git_host::GitHostService::from_url(&remote_url) {
+        Ok(host) => host,
+        Err(GitHostError::UnsupportedProvider) => {
+            return Err(ApiError::BadRequest(t(
+                "docs-pr-unsupported-forge",
+                &[("url", &remote_url)],
+            )));
+        }
+        Err(e) => return Err(ApiError::GitHost(e)),
+    };
+
+    let existing = git_host
+        .list_prs_for_branch(&repo_path, &remote_url, &branch)
+        .await?;
+    if let Some(pr) = existing
+        .into_iter()
+        .find(|pr| matches!(pr.status, MergeStatus::Open))
+    {
+        return Ok(ResponseJson(ApiResponse::success(DocumentPullRequest {
+            branch,
+            number: pr.number,
+            url: pr.url,
+            created: false,
+        })));
+    }
+
+    let title = match body.title.as_deref().map(str::trim) {
+        Some(title) if !title.is_empty() => title.to_string(),
+        _ => {
+            let paths: Vec<String> = deployment
+                .git_backend()
+                .changed_paths_between(&repo_path, DEFAULT_DOCS_BRANCH, &branch)?
+                .into_iter()
+                .map(|change| change.path)
+                .collect();
+            default_title(&paths)
+        }
+    };
+    let request = CreatePrRequest {
+        title,
+        body: body.body,
+        head_branch: branch.clone(),
+        base_branch: DEFAULT_DOCS_BRANCH.to_string(),
+        draft: Some(body.draft),
+        head_repo_url: None,
+    };
+    let pr = git_host
+        .create_pr(&repo_path, &remote_url, &request)
+        .await?;
+    tracing::info!("Opened pull request {} of branch {}", pr.url, branch);
+
+    Ok(ResponseJson(ApiResponse::success(DocumentPullRequest {
+        branch,
+        number: pr.number,
+        url: pr.url,
+        created: true,
+    })))
+}
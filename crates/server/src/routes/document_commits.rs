@@ -0,0 +1,289 @@
+//! Commit message template and identity of document commits. Routes commit documents
+//! through [`DocumentCommitter`], so a project's settings apply to every commit.
+
+use std::path::{Path, PathBuf};
+
+use axum::{Extension, extract::State, response::Json as ResponseJson};
+use db::models::project::{DocumentCommitSettings, Project};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    commit_template::{self, CommitTemplateValues},
+    git::{CommitIdentity, GitServiceError, PathChange},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{
+        document_branches, document_roots,
+        documents::{project_extensions, uncommitted_document_changes},
+    },
+};
+
+/// `{user}` of commits made while nobody is signed in and no committer is set
+const UNKNOWN_USER: &str = "unknown";
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateDocumentCommitSettings {
+    /// `null` resets the project to the default messages and identity
+    pub settings: Option<DocumentCommitSettings>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct DocumentAutoCommit {
+    /// Whether document saves are committed right away. When off, changes stay
+    /// uncommitted until they are committed with `POST /documents/commit`.
+    pub auto_commit: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CommitDocumentsRequest {
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct CommitDocumentsResponse {
+    /// Documents that were committed
+    pub changes: Vec<PathChange>,
+    /// Whether a commit was made
+    pub committed: bool,
+}
+
+/// Commits document changes with a project's message template and identity
+pub(crate) struct DocumentCommitter {
+    template: Option<String>,
+    identity: Option<CommitIdentity>,
+    user: String,
+    /// Whether saves are committed right away, or left for `POST /documents/commit`
+    auto_commit: bool,
+}
+
+impl DocumentCommitter {
+    pub(crate) async fn load(
+        deployment: &DeploymentImpl,
+        project_id: Uuid,
+    ) -> Result<Self, ApiError> {
+        let pool = &deployment.db().pool;
+        let settings = Project::find_document_commit_settings(pool, project_id).await?;
+        let auto_commit = Project::find_document_auto_commit(pool, project_id).await?;
+        let github_user = deployment.config().read().await.github.username.clone();
+        let user = github_user
+            .filter(|user| !user.is_empty())
+            .or_else(|| settings.committer_name.clone())
+            .unwrap_or_else(|| UNKNOWN_USER.to_string());
+        let identity = match (settings.committer_name, settings.committer_email) {
+            (Some(name), Some(email)) => Some(CommitIdentity { name, email }),
+            _ => None,
+        };
+        Ok(Self {
+            template: settings.message_template,
+            identity,
+            user,
+            auto_commit,
+        })
+    }
+
+    /// Who makes the changes: the signed-in GitHub user, else the project's committer
+    pub(crate) fn user(&self) -> &str {
+        &self.user
+    }
+
+    /// Message for committing a change to `path` that is described by `message`
+    pub(crate) fn message(&self, message: &str, path: &str) -> String {
+        match &self.template {
+            Some(template) => commit_template::render(
+                template,
+                CommitTemplateValues {
+                    message,
+                    path,
+                    user: &self.user,
+                },
+            ),
+            None => message.to_string(),
+        }
+    }
+
+    /// Stage everything in `repo_path` and commit it, unless the project defers commits;
+    /// false when nothing was committed
+    pub(crate) fn commit(
+        &self,
+        deployment: &DeploymentImpl,
+        repo_path: &Path,
+        message: &str,
+    ) -> Result<bool, GitServiceError> {
+        if !self.auto_commit {
+            return Ok(false);
+        }
+        self.commit_now(deployment, repo_path, message)
+    }
+
+    /// Stage everything in `repo_path` and commit it, also when the project defers
+    /// commits; false when there was nothing to commit. The message gets the document
+    /// commit trailer.
+    pub(crate) fn commit_now(
+        &self,
+        deployment: &DeploymentImpl,
+        repo_path: &Path,
+        message: &str,
+    ) -> Result<bool, GitServiceError> {
+        deployment.git_backend().commit_as(
+            repo_path,
+            &commit_template::with_document_trailer(message),
+            self.identity.as_ref(),
+        )
+    }
+}
+
+/// Trimmed `value`, `None` if it is blank
+fn non_blank(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn validate_settings(settings: DocumentCommitSettings) -> Result<DocumentCommitSettings, ApiError> {
+    let settings = DocumentCommitSettings {
+        // Only surrounding blank lines are dropped; the template may span lines
+        message_template: settings
+            .message_template
+            .filter(|template| !template.trim().is_empty())
+            .map(|template| template.trim_matches('\n').to_string()),
+        committer_name: non_blank(settings.committer_name),
+        committer_email: non_blank(settings.committer_email),
+    };
+    if let Some(template) = &settings.message_template {
+        commit_template::validate(template).map_err(ApiError::BadRequest)?;
+    }
+    match (&settings.committer_name, &settings.committer_email) {
+        (Some(name), Some(email)) => {
+            if name.contains(['<', '>', '\n']) {
+                return Err(ApiError::BadRequest(
+                    "Committer name can't contain '<', '>' or line breaks".to_string(),
+                ));
+            }
+            let valid_email = email
+                .split_once('@')
+                .is_some_and(|(local, domain)| !local.is_empty() && !domain.is_empty())
+                && !email.contains(|c: char| c.is_whitespace() || c == '<' || c == '>');
+            if !valid_email {
+                return Err(ApiError::BadRequest(format!(
+                    "'{}' is not a valid email address",
+                    email
+                )));
+            }
+        }
+        (None, None) => {}
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Set both committer name and email, or neither".to_string(),
+            ));
+        }
+    }
+    Ok(settings)
+}
+
+pub async fn get_document_commit_settings(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentCommitSettings>>, ApiError> {
+    let settings =
+        Project::find_document_commit_settings(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(settings)))
+}
+
+/// Set the commit message template and identity of document commits, or reset them
+pub async fn update_document_commit_settings(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<UpdateDocumentCommitSettings>,
+) -> Result<ResponseJson<ApiResponse<DocumentCommitSettings>>, ApiError> {
+    let settings = body
+        .settings
+        .map(validate_settings)
+        .transpose()?
+        .filter(|settings| *settings != DocumentCommitSettings::default());
+    Project::set_document_commit_settings(&deployment.db().pool, project.id, settings.as_ref())
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(
+        settings.unwrap_or_default(),
+    )))
+}
+
+pub async fn get_document_auto_commit(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentAutoCommit>>, ApiError> {
+    let auto_commit = Project::find_document_auto_commit(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentAutoCommit {
+        auto_commit,
+    })))
+}
+
+/// Turn committing document saves right away on or off
+pub async fn update_document_auto_commit(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<DocumentAutoCommit>,
+) -> Result<ResponseJson<ApiResponse<DocumentAutoCommit>>, ApiError> {
+    Project::set_document_auto_commit(&deployment.db().pool, project.id, body.auto_commit).await?;
+    Ok(ResponseJson(ApiResponse::success(body)))
+}
+
+/// Commit all uncommitted document changes of the project with one message, one commit
+/// per repository, e.g. after saving drafts with auto-commit turned off
+pub async fn commit_documents(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<CommitDocumentsRequest>,
+) -> Result<ResponseJson<ApiResponse<CommitDocumentsResponse>>, ApiError> {
+    let message = body.message.trim();
+    if message.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Commit message can't be empty".to_string(),
+        ));
+    }
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project.id)
+        .await?;
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let roots = document_roots::project_document_roots(&deployment, project.id).await?;
+
+    // Every repository is checked before committing, so a commit isn't made in some
+    // repositories and refused in others
+    let mut pending = Vec::new();
+    for repo in repositories {
+        let repo_path = PathBuf::from(&repo.path);
+        if !repo_path.is_dir() {
+            continue;
+        }
+        let changes = uncommitted_document_changes(&deployment, &repo_path, &extensions, &roots)?;
+        if changes.is_empty() {
+            continue;
+        }
+        document_branches::require_editable_branch(&deployment, project.id, &repo_path, message)
+            .await?;
+        pending.push((repo_path, changes));
+    }
+
+    let committer = DocumentCommitter::load(&deployment, project.id).await?;
+    let mut changes = Vec::new();
+    let mut committed = false;
+    for (repo_path, repo_changes) in pending {
+        let paths: Vec<&str> = repo_changes
+            .iter()
+            .map(|change| change.path.as_str())
+            .collect();
+        let message = committer.message(message, &paths.join(", "));
+        committed |= committer.commit_now(&deployment, &repo_path, &message)?;
+        changes.extend(repo_changes);
+    }
+
+    Ok(ResponseJson(ApiResponse::success(
+        CommitDocumentsResponse { changes, committed },
+    )))
+}
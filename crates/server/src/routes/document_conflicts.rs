@@ -0,0 +1,251 @@
+//! Conflicts left behind when pulling origin/main during a sync stops a rebase or merge:
+//! listing the conflicted documents, reading their versions, resolving them, and
+//! continuing or aborting. Only documents of the project can be resolved here; other
+//! conflicted files are counted so the user knows to resolve them with git.
+
+use std::path::{Path, PathBuf};
+
+use axum::{
+    Extension,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::project::Project;
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::git::{ConflictOp, ConflictSides};
+use ts_rs::TS;
+use utils::{i18n::t, response::ApiResponse};
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{
+        document_roots,
+        documents::{
+            allowed_file_type, is_scanned_path, project_extensions, validate_relative_path,
+        },
+    },
+};
+
+#[derive(Debug, Serialize, TS)]
+pub struct SyncConflicts {
+    /// The operation stopped by conflicts; `None` when nothing is in progress
+    pub operation: Option<ConflictOp>,
+    /// Conflicted documents, which can be resolved with this API
+    pub documents: Vec<String>,
+    /// Conflicted files that aren't documents of the project and must be resolved with
+    /// git before continuing
+    pub other_files: usize,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentConflict {
+    pub path: String,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub sides: ConflictSides,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConflictPathQuery {
+    pub path: String,
+}
+
+/// How to resolve a conflicted document: keep one of its versions, where a version
+/// the document doesn't have deletes it, or replace it with merged content
+#[derive(Debug, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConflictResolution {
+    Base,
+    Ours,
+    Theirs,
+    Content { content: String },
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ResolveConflictRequest {
+    pub path: String,
+    pub resolution: ConflictResolution,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ContinueSyncResponse {
+    /// The rebase or merge finished; sync again to push the result
+    pub done: bool,
+    pub message: String,
+    /// Conflicts of the next commit, if the rebase stopped again
+    pub conflicts: SyncConflicts,
+}
+
+/// The primary repository, which is the one documents are synced from
+async fn sync_repository(
+    deployment: &DeploymentImpl,
+    project: &Project,
+) -> Result<PathBuf, ApiError> {
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project.id)
+        .await?;
+    let repo = repositories
+        .first()
+        .ok_or_else(|| ApiError::BadRequest(t("docs-no-repository", &[])))?;
+    Ok(PathBuf::from(&repo.path))
+}
+
+async fn list_conflicts(
+    deployment: &DeploymentImpl,
+    project: &Project,
+    repo_path: &Path,
+) -> Result<SyncConflicts, ApiError> {
+    let git = deployment.git();
+    let operation = git.detect_conflict_op(repo_path)?;
+    if operation.is_none() {
+        return Ok(SyncConflicts {
+            operation,
+            documents: Vec::new(),
+            other_files: 0,
+        });
+    }
+
+    let extensions = project_extensions(deployment, project.id).await?;
+    let roots = document_roots::project_document_roots(deployment, project.id).await?;
+    let (documents, other_files): (Vec<String>, Vec<String>) = git
+        .get_conflicted_files(repo_path)?
+        .into_iter()
+        .partition(|path| {
+            is_scanned_path(path)
+                && document_roots::in_document_roots(&roots, path)
+                && allowed_file_type(Path::new(path), &extensions).is_some()
+        });
+    Ok(SyncConflicts {
+        operation,
+        documents,
+        other_files: other_files.len(),
+    })
+}
+
+/// `path` if it is one of the conflicted documents
+fn require_conflicted_document<'a>(
+    conflicts: &SyncConflicts,
+    path: &'a str,
+) -> Result<&'a str, ApiError> {
+    let path = validate_relative_path(path)?;
+    if !conflicts.documents.iter().any(|document| document == path) {
+        return Err(ApiError::BadRequest(t(
+            "sync-not-conflicted-document",
+            &[("path", &path)],
+        )));
+    }
+    Ok(path)
+}
+
+/// The documents a stopped sync left conflicted
+pub async fn get_sync_conflicts(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<SyncConflicts>>, ApiError> {
+    let repo_path = sync_repository(&deployment, &project).await?;
+    let conflicts = list_conflicts(&deployment, &project, &repo_path).await?;
+    Ok(ResponseJson(ApiResponse::success(conflicts)))
+}
+
+/// The base, ours and theirs versions of a conflicted document
+pub async fn get_document_conflict(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(query): Query<ConflictPathQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentConflict>>, ApiError> {
+    let repo_path = sync_repository(&deployment, &project).await?;
+    let conflicts = list_conflicts(&deployment, &project, &repo_path).await?;
+    let path = require_conflicted_document(&conflicts, &query.path)?;
+    let sides = deployment.git().conflict_sides(&repo_path, path)?;
+    Ok(ResponseJson(ApiResponse::success(DocumentConflict {
+        path: path.to_string(),
+        sides,
+    })))
+}
+
+/// Resolve a conflicted document and stage it
+pub async fn resolve_document_conflict(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<ResolveConflictRequest>,
+) -> Result<ResponseJson<ApiResponse<SyncConflicts>>, ApiError> {
+    let repo_path = sync_repository(&deployment, &project).await?;
+    let conflicts = list_conflicts(&deployment, &project, &repo_path).await?;
+    let path = require_conflicted_document(&conflicts, &body.path)?;
+
+    let git = deployment.git();
+    let content = match body.resolution {
+        ConflictResolution::Base => git.conflict_sides(&repo_path, path)?.base,
+        ConflictResolution::Ours => git.conflict_sides(&repo_path, path)?.ours,
+        ConflictResolution::Theirs => git.conflict_sides(&repo_path, path)?.theirs,
+        ConflictResolution::Content { content } => Some(content),
+    };
+    git.resolve_conflict(&repo_path, path, content.as_deref())?;
+    tracing::info!("Resolved sync conflict of {}", path);
+
+    let conflicts = list_conflicts(&deployment, &project, &repo_path).await?;
+    Ok(ResponseJson(ApiResponse::success(conflicts)))
+}
+
+/// Continue the stopped rebase or merge once every conflict is resolved
+pub async fn continue_sync(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<ContinueSyncResponse>>, ApiError> {
+    let repo_path = sync_repository(&deployment, &project).await?;
+    let conflicts = list_conflicts(&deployment, &project, &repo_path).await?;
+    if conflicts.operation.is_none() {
+        return Err(ApiError::Conflict(t("sync-nothing-in-progress", &[])));
+    }
+    if !conflicts.documents.is_empty() || conflicts.other_files > 0 {
+        return Err(ApiError::Conflict(t(
+            "sync-conflicts-remaining",
+            &[
+                ("documents", &conflicts.documents.len()),
+                ("others", &conflicts.other_files),
+            ],
+        )));
+    }
+
+    let result = deployment.git().continue_conflicts(&repo_path);
+    let conflicts = list_conflicts(&deployment, &project, &repo_path).await?;
+    // A rebase that stops at the next conflicting commit fails to continue, but isn't
+    // an error for the user
+    let stopped_again = !conflicts.documents.is_empty() || conflicts.other_files > 0;
+    if let Err(e) = result
+        && !stopped_again
+    {
+        return Err(e.into());
+    }
+
+    let done = conflicts.operation.is_none();
+    let message = if done {
+        t("sync-conflicts-done", &[])
+    } else {
+        t("sync-conflicts-next", &[])
+    };
+    Ok(ResponseJson(ApiResponse::success(ContinueSyncResponse {
+        done,
+        message,
+        conflicts,
+    })))
+}
+
+/// Abort the stopped rebase or merge, which puts main back where it was before the sync
+pub async fn abort_sync(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<SyncConflicts>>, ApiError> {
+    let repo_path = sync_repository(&deployment, &project).await?;
+    if deployment.git().detect_conflict_op(&repo_path)?.is_none() {
+        return Err(ApiError::Conflict(t("sync-nothing-in-progress", &[])));
+    }
+    deployment.git().abort_conflicts(&repo_path)?;
+    tracing::info!("Aborted the stopped sync of project {}", project.id);
+
+    let conflicts = list_conflicts(&deployment, &project, &repo_path).await?;
+    Ok(ResponseJson(ApiResponse::success(conflicts)))
+}
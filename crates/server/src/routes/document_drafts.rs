@@ -0,0 +1,308 @@
+//! Unsaved editor content kept per author and document outside git, so writing that
+//! couldn't be saved or committed isn't lost. Clients autosave drafts while editing and
+//! publish them, which saves and commits the document, or discard them.
+
+use std::path::Path;
+
+use axum::{
+    Extension, Json, Router,
+    body::{Body, to_bytes},
+    extract::{Path as AxumPath, Query, State},
+    http::HeaderMap,
+    middleware::from_fn_with_state,
+    response::{Json as ResponseJson, Response},
+    routing::{get, post},
+};
+use db::models::{document_draft::DocumentDraft, project::Project, repo::Repo};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{
+        documents::{
+            DryRunQuery, RepositoryQuery, UpdateDocumentRequest, content_hash,
+            project_repositories, update_document_content, validate_relative_path,
+        },
+        paths,
+    },
+};
+
+/// Largest draft that is stored
+const MAX_DRAFT_BYTES: usize = 5 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentDraftsQuery {
+    pub author: String,
+}
+
+/// Request body to autosave a draft
+#[derive(Debug, Deserialize, TS)]
+pub struct SaveDocumentDraftRequest {
+    /// Relative path of the document from its repository root; it doesn't have to exist
+    pub relative_path: String,
+    /// Repository of the document; defaults to the first one containing `relative_path`,
+    /// else the primary repository
+    #[serde(default)]
+    #[ts(optional)]
+    pub repository_id: Option<Uuid>,
+    /// Name of the editor writing the draft
+    pub author: String,
+    pub content: String,
+    /// `content_hash` of the document the editor started from; kept from the first save
+    /// of a draft
+    #[serde(default)]
+    #[ts(optional)]
+    pub base_hash: Option<String>,
+}
+
+/// Request body to restore or discard a draft
+#[derive(Debug, Deserialize, TS)]
+pub struct DocumentDraftRequest {
+    pub relative_path: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub repository_id: Option<Uuid>,
+    pub author: String,
+}
+
+/// Request body to publish a draft
+#[derive(Debug, Deserialize, TS)]
+pub struct PublishDocumentDraftRequest {
+    pub relative_path: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub repository_id: Option<Uuid>,
+    pub author: String,
+    /// `content_hash` of the document the draft replaces; defaults to the one the draft
+    /// was started from. Send the current hash to overwrite changes made since.
+    #[serde(default)]
+    #[ts(optional)]
+    pub expected_hash: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub lock_holder: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RestoredDocumentDraft {
+    pub draft: DocumentDraft,
+    /// `content_hash` of the document now, `None` if it doesn't exist
+    pub current_hash: Option<String>,
+    /// Whether the document changed since the draft was started, so restoring the
+    /// draft would overwrite those changes
+    pub outdated: bool,
+}
+
+fn require_author(author: &str) -> Result<&str, ApiError> {
+    let author = author.trim();
+    if author.is_empty() {
+        return Err(ApiError::BadRequest(
+            "A draft author name is required".to_string(),
+        ));
+    }
+    Ok(author)
+}
+
+/// The repository of a draft's document, and the validated path. Documents that don't
+/// exist yet belong to the primary repository unless one is given.
+async fn draft_repository(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repository_id: Option<Uuid>,
+    relative_path: &str,
+) -> Result<(Repo, String), ApiError> {
+    let relative_path = validate_relative_path(relative_path)?
+        .trim_matches('/')
+        .to_string();
+    let repositories = project_repositories(deployment, project_id, repository_id).await?;
+    let existing = repositories
+        .iter()
+        .position(|repo| Path::new(&repo.path).join(&relative_path).is_file());
+    let repo = repositories
+        .into_iter()
+        .nth(existing.unwrap_or(0))
+        .ok_or_else(|| ApiError::BadRequest("No repository found for this project".to_string()))?;
+    Ok((repo, relative_path))
+}
+
+/// Drafts of one author in the project, most recently edited first
+pub async fn list_drafts(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DocumentDraftsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<DocumentDraft>>>, ApiError> {
+    let author = require_author(&query.author)?;
+    let drafts = DocumentDraft::find_by_author(&deployment.db().pool, project.id, author).await?;
+    Ok(ResponseJson(ApiResponse::success(drafts)))
+}
+
+/// Store the author's draft of a document, replacing their previous one
+pub async fn save_draft(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SaveDocumentDraftRequest>,
+) -> Result<ResponseJson<ApiResponse<DocumentDraft>>, ApiError> {
+    let author = require_author(&payload.author)?;
+    if payload.content.len() > MAX_DRAFT_BYTES {
+        return Err(ApiError::BadRequest(format!(
+            "Drafts can be at most {} MiB",
+            MAX_DRAFT_BYTES / (1024 * 1024)
+        )));
+    }
+    let (repo, relative_path) = draft_repository(
+        &deployment,
+        project.id,
+        payload.repository_id,
+        &payload.relative_path,
+    )
+    .await?;
+
+    let draft = DocumentDraft::upsert(
+        &deployment.db().pool,
+        project.id,
+        repo.id,
+        &relative_path,
+        author,
+        &payload.content,
+        payload.base_hash.as_deref(),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(draft)))
+}
+
+/// The author's draft of a document, with whether the document changed since the draft
+/// was started
+pub async fn restore_draft(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DocumentDraftRequest>,
+) -> Result<ResponseJson<ApiResponse<RestoredDocumentDraft>>, ApiError> {
+    let author = require_author(&payload.author)?;
+    let (repo, relative_path) = draft_repository(
+        &deployment,
+        project.id,
+        payload.repository_id,
+        &payload.relative_path,
+    )
+    .await?;
+
+    let draft = DocumentDraft::find(&deployment.db().pool, repo.id, &relative_path, author)
+        .await?
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!("{} has no draft of '{}'", author, relative_path))
+        })?;
+    let file_path = Path::new(&repo.path).join(&relative_path);
+    let current_hash = match tokio::fs::read_to_string(&file_path).await {
+        Ok(content) => Some(content_hash(&content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    let outdated = current_hash != draft.base_hash;
+    Ok(ResponseJson(ApiResponse::success(RestoredDocumentDraft {
+        draft,
+        current_hash,
+        outdated,
+    })))
+}
+
+pub async fn discard_draft(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DocumentDraftRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let author = require_author(&payload.author)?;
+    let (repo, relative_path) = draft_repository(
+        &deployment,
+        project.id,
+        payload.repository_id,
+        &payload.relative_path,
+    )
+    .await?;
+
+    DocumentDraft::delete(&deployment.db().pool, repo.id, &relative_path, author).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Save the author's draft as the document's content through
+/// [`update_document_content`], so it is committed, checked for conflicts or staged for
+/// review like any other edit. The draft is deleted once that succeeds and kept
+/// otherwise, e.g. when the document changed since the draft was started.
+pub async fn publish_draft(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<PublishDocumentDraftRequest>,
+) -> Result<Response, ApiError> {
+    let author = require_author(&payload.author)?.to_string();
+    let (repo, relative_path) = draft_repository(
+        &deployment,
+        project.id,
+        payload.repository_id,
+        &payload.relative_path,
+    )
+    .await?;
+    let pool = deployment.db().pool.clone();
+    let draft = DocumentDraft::find(&pool, repo.id, &relative_path, &author)
+        .await?
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!("{} has no draft of '{}'", author, relative_path))
+        })?;
+    let Some(expected_hash) = payload.expected_hash.or(draft.base_hash) else {
+        return Err(ApiError::BadRequest(
+            "The draft has no base version; send the document's content_hash as expected_hash"
+                .to_string(),
+        ));
+    };
+
+    let request = UpdateDocumentRequest {
+        content: draft.content,
+        expected_hash: Some(expected_hash),
+        lock_holder: payload.lock_holder,
+    };
+    let response = update_document_content(
+        State(deployment),
+        Extension(project.clone()),
+        AxumPath((project.id, urlencoding::encode(&relative_path).into_owned())),
+        Query(RepositoryQuery {
+            repository_id: Some(repo.id),
+        }),
+        Query(DryRunQuery::default()),
+        HeaderMap::new(),
+        ResponseJson(request),
+    )
+    .await?;
+
+    // Refusals such as read-only paths come back as 200s with `success: false`
+    let (parts, body) = response.into_parts();
+    let bytes = to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to read the save result: {e}")))?;
+    let saved = parts.status.is_success()
+        && serde_json::from_slice::<serde_json::Value>(&bytes)
+            .ok()
+            .and_then(|value| value.get("success")?.as_bool())
+            == Some(true);
+    if saved {
+        DocumentDraft::delete(&pool, repo.id, &relative_path, &author).await?;
+    }
+    Ok(Response::from_parts(parts, Body::from(bytes)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route("/", get(list_drafts).put(save_draft))
+        .route("/restore", post(restore_draft))
+        .route("/publish", post(publish_draft))
+        .route("/discard", post(discard_draft))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest(paths::PROJECT_DOCUMENT_DRAFTS, project_router)
+}
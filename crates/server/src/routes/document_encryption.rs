@@ -0,0 +1,472 @@
+//! Folders whose documents are encrypted at rest. Documents in them are encrypted with
+//! the project's key before they are written to the working tree, so only ciphertext is
+//! committed, and are decrypted on read for the folder's readers.
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    document_encryption::{
+        DocumentEncryptedFolder, DocumentEncryptionKey, SetDocumentEncryptedFolder,
+    },
+    project::Project,
+};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::{
+    document_encryption,
+    document_store::{self, DocumentStoreError},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{
+        document_branches,
+        document_commits::DocumentCommitter,
+        documents::{DEFAULT_DOCS_BRANCH, allowed_file_type, project_extensions},
+        paths,
+    },
+};
+
+#[derive(Debug, Serialize, TS)]
+pub struct UpdateEncryptedFoldersResponse {
+    pub folders: Vec<DocumentEncryptedFolder>,
+    /// Documents that were encrypted or decrypted to match the new folders
+    pub changed_documents: Vec<String>,
+    pub committed: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RotateEncryptionKeyResponse {
+    pub key: DocumentEncryptionKey,
+    /// Documents that were re-encrypted with the new key
+    pub reencrypted_documents: Vec<String>,
+    pub committed: bool,
+}
+
+/// The encrypted folders and keys of a project, and who is reading or writing
+pub(crate) struct DocumentKeyring {
+    folders: Vec<DocumentEncryptedFolder>,
+    /// Newest first; only the first can be current
+    keys: Vec<DocumentEncryptionKey>,
+    /// Signed-in GitHub user
+    user: Option<String>,
+}
+
+impl DocumentKeyring {
+    pub(crate) async fn load(
+        deployment: &DeploymentImpl,
+        project_id: Uuid,
+    ) -> Result<Self, ApiError> {
+        let pool = &deployment.db().pool;
+        let folders = DocumentEncryptedFolder::find_by_project_id(pool, project_id).await?;
+        let keys = DocumentEncryptionKey::find_by_project_id(pool, project_id).await?;
+        let user = deployment
+            .config()
+            .read()
+            .await
+            .github
+            .username
+            .clone()
+            .filter(|user| !user.is_empty());
+        Ok(Self {
+            folders,
+            keys,
+            user,
+        })
+    }
+
+    fn current_key(&self) -> Option<&DocumentEncryptionKey> {
+        self.keys.first().filter(|key| key.retired_at.is_none())
+    }
+
+    fn can_read(&self, folder: &DocumentEncryptedFolder) -> bool {
+        folder.readers.is_empty()
+            || self
+                .user
+                .as_ref()
+                .is_some_and(|user| folder.readers.contains(user))
+    }
+
+    fn require_reader(&self, relative_path: &str) -> Result<(), ApiError> {
+        match DocumentEncryptedFolder::find_for_path(&self.folders, relative_path) {
+            Some(folder) if !self.can_read(folder) => Err(ApiError::Forbidden(format!(
+                "'{}' is in the encrypted folder '{}', which only {} can read",
+                relative_path,
+                folder.folder,
+                folder.readers.join(", ")
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Text of an encrypted document, without checking who reads it
+    fn decrypt_content(&self, content: &str) -> Result<String, ApiError> {
+        let key_id = document_encryption::key_id(content).ok_or_else(|| {
+            ApiError::BadRequest("Encrypted document has no valid key header".to_string())
+        })?;
+        let key = self
+            .keys
+            .iter()
+            .find(|key| key.id == key_id)
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Document was encrypted with key {}, which this project doesn't have",
+                    key_id
+                ))
+            })?;
+        Ok(document_encryption::decrypt(&key.key_material, content)?)
+    }
+
+    /// Text of `content` read from `relative_path`, decrypted if it is encrypted. Only
+    /// readers of the document's folder can decrypt it.
+    pub(crate) fn decrypt(&self, relative_path: &str, content: String) -> Result<String, ApiError> {
+        if !document_encryption::is_encrypted(&content) {
+            return Ok(content);
+        }
+        self.require_reader(relative_path)?;
+        self.decrypt_content(&content)
+    }
+
+    /// Whether documents written to `relative_path` are encrypted
+    pub(crate) fn encrypts(&self, relative_path: &str) -> bool {
+        DocumentEncryptedFolder::find_for_path(&self.folders, relative_path).is_some()
+    }
+
+    /// `content` as it is written to `relative_path`: encrypted with the current key if
+    /// the document is in an encrypted folder. Encrypting reveals nothing, so it is not
+    /// limited to readers.
+    pub(crate) fn encrypt(&self, relative_path: &str, content: &str) -> Result<String, ApiError> {
+        if !self.encrypts(relative_path) {
+            return Ok(content.to_string());
+        }
+        self.encrypt_content(content)
+    }
+
+    fn encrypt_content(&self, content: &str) -> Result<String, ApiError> {
+        let key = self.current_key().ok_or_else(|| {
+            ApiError::Conflict("The project has no document encryption key".to_string())
+        })?;
+        Ok(document_encryption::encrypt(
+            key.id,
+            &key.key_material,
+            content,
+        )?)
+    }
+
+    /// New content of a stored document so it matches the encrypted folders, `None` if it
+    /// already does
+    fn reconcile(&self, relative_path: &str, content: &str) -> Result<Option<String>, ApiError> {
+        let encrypted = document_encryption::is_encrypted(content);
+        let in_folder = DocumentEncryptedFolder::find_for_path(&self.folders, relative_path);
+        match (in_folder, encrypted) {
+            (Some(_), true)
+                if document_encryption::key_id(content) == self.current_key().map(|key| key.id) =>
+            {
+                Ok(None)
+            }
+            (Some(_), true) => {
+                let text = self.decrypt_content(content)?;
+                Ok(Some(self.encrypt_content(&text)?))
+            }
+            (Some(_), false) => Ok(Some(self.encrypt_content(content)?)),
+            (None, true) => Ok(Some(self.decrypt_content(content)?)),
+            (None, false) => Ok(None),
+        }
+    }
+}
+
+/// Documents in encrypted folders can only be edited through the document editor, which
+/// encrypts them; other writers would save plaintext or garble the ciphertext
+pub(crate) async fn require_unencrypted(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    relative_path: &str,
+) -> Result<(), ApiError> {
+    let folders =
+        DocumentEncryptedFolder::find_by_project_id(&deployment.db().pool, project_id).await?;
+    match DocumentEncryptedFolder::find_for_path(&folders, relative_path) {
+        Some(folder) => Err(ApiError::Forbidden(format!(
+            "'{}' is in the encrypted folder '{}'; edit it in the document editor",
+            relative_path, folder.folder
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Normalize and validate the folders of a project before they replace the existing ones
+fn normalize_folders(
+    folders: Vec<SetDocumentEncryptedFolder>,
+) -> Result<Vec<SetDocumentEncryptedFolder>, ApiError> {
+    let mut normalized: Vec<SetDocumentEncryptedFolder> = Vec::with_capacity(folders.len());
+    for entry in folders {
+        let folder = entry.folder.trim().trim_matches('/').to_string();
+        if folder.is_empty() || folder.split('/').any(|segment| segment == "..") {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid encrypted folder '{}'",
+                entry.folder
+            )));
+        }
+        if normalized.iter().any(|existing| existing.folder == folder) {
+            return Err(ApiError::BadRequest(format!(
+                "Encrypted folder '{}' is listed twice",
+                folder
+            )));
+        }
+        let mut readers: Vec<String> = Vec::with_capacity(entry.readers.len());
+        for reader in entry.readers {
+            let reader = reader.trim().to_string();
+            if !reader.is_empty() && !readers.contains(&reader) {
+                readers.push(reader);
+            }
+        }
+        normalized.push(SetDocumentEncryptedFolder { folder, readers });
+    }
+    Ok(normalized)
+}
+
+/// Encrypt the documents in `folders` that belong to an encrypted folder with the current
+/// key and decrypt the others, in every repository of the project, then commit. Every
+/// repository is checked before anything is written. Returns the changed documents.
+async fn reconcile_documents(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    keyring: &DocumentKeyring,
+    folders: &BTreeSet<String>,
+    message: &str,
+) -> Result<(Vec<String>, bool), ApiError> {
+    let extensions = project_extensions(deployment, project_id).await?;
+    let symlinks = Project::find_document_symlink_policy(&deployment.db().pool, project_id).await?;
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project_id)
+        .await?;
+
+    let mut pending = Vec::new();
+    for repo in repositories {
+        let repo_path = PathBuf::from(&repo.path);
+        // Bare clones are read-only
+        if !repo_path.is_dir() || document_store::is_bare_repository(&repo_path) {
+            continue;
+        }
+        let store_path = repo_path.clone();
+        let store_folders = folders.clone();
+        let files = tokio::task::spawn_blocking(move || {
+            let store = document_store::open(&store_path, DEFAULT_DOCS_BRANCH, symlinks);
+            let mut files = BTreeSet::new();
+            for folder in &store_folders {
+                for file in store.list(folder, &[])? {
+                    files.insert(file.relative_path);
+                }
+            }
+            Ok::<_, DocumentStoreError>(files)
+        })
+        .await
+        .map_err(|e| ApiError::Io(std::io::Error::other(e)))??;
+
+        let mut writes = Vec::new();
+        for relative_path in files {
+            if allowed_file_type(Path::new(&relative_path), &extensions).is_none() {
+                continue;
+            }
+            let file_path = repo_path.join(&relative_path);
+            // Binary files aren't documents and are left alone
+            let Ok(content) = tokio::fs::read_to_string(&file_path).await else {
+                continue;
+            };
+            if let Some(updated) = keyring.reconcile(&relative_path, &content)? {
+                writes.push((relative_path, file_path, updated));
+            }
+        }
+        if writes.is_empty() {
+            continue;
+        }
+        document_branches::require_editable_branch(
+            deployment,
+            project_id,
+            &repo_path,
+            "encryption",
+        )
+        .await?;
+        pending.push((repo.id, repo_path, writes));
+    }
+
+    let committer = DocumentCommitter::load(deployment, project_id).await?;
+    let mut changed = Vec::new();
+    let mut committed = false;
+    for (repo_id, repo_path, writes) in pending {
+        for (_, file_path, content) in &writes {
+            tokio::fs::write(file_path, content).await.map_err(|e| {
+                ApiError::BadRequest(format!("Failed to save {:?}: {}", file_path, e))
+            })?;
+        }
+        deployment.document_index().invalidate(repo_id);
+        let paths: Vec<&str> = writes.iter().map(|(path, _, _)| path.as_str()).collect();
+        committed |= committer.commit(
+            deployment,
+            &repo_path,
+            &committer.message(message, &paths.join(", ")),
+        )?;
+        changed.extend(writes.into_iter().map(|(path, _, _)| path));
+    }
+    Ok((changed, committed))
+}
+
+pub async fn get_encrypted_folders(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DocumentEncryptedFolder>>>, ApiError> {
+    let folders =
+        DocumentEncryptedFolder::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(folders)))
+}
+
+/// Replace the project's encrypted folders, then encrypt the documents of added folders
+/// and decrypt those of removed ones. Only a folder's readers can remove it or change
+/// its readers.
+pub async fn set_encrypted_folders(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<Vec<SetDocumentEncryptedFolder>>,
+) -> Result<ResponseJson<ApiResponse<UpdateEncryptedFoldersResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let folders = normalize_folders(payload)?;
+    let previous = DocumentKeyring::load(&deployment, project.id).await?;
+    for existing in &previous.folders {
+        let unchanged = folders
+            .iter()
+            .any(|folder| folder.folder == existing.folder && folder.readers == *existing.readers);
+        if !unchanged && !previous.can_read(existing) {
+            return Err(ApiError::Forbidden(format!(
+                "Only {} can change the encrypted folder '{}'",
+                existing.readers.join(", "),
+                existing.folder
+            )));
+        }
+    }
+
+    let saved = DocumentEncryptedFolder::replace_for_project(pool, project.id, &folders).await?;
+    if !saved.is_empty() && previous.current_key().is_none() {
+        DocumentEncryptionKey::rotate(pool, project.id, &document_encryption::generate_key())
+            .await?;
+    }
+    let keyring = DocumentKeyring::load(&deployment, project.id).await?;
+    let scanned: BTreeSet<String> = previous
+        .folders
+        .iter()
+        .map(|folder| folder.folder.clone())
+        .chain(saved.iter().map(|folder| folder.folder.clone()))
+        .collect();
+    let reconciled = reconcile_documents(
+        &deployment,
+        project.id,
+        &keyring,
+        &scanned,
+        "docs: update encrypted folders",
+    )
+    .await;
+    let (changed_documents, committed) = match reconciled {
+        Ok(reconciled) => reconciled,
+        Err(e) => {
+            // Keep the folders in line with the documents, which may not have changed
+            let restored: Vec<SetDocumentEncryptedFolder> = previous
+                .folders
+                .into_iter()
+                .map(|folder| SetDocumentEncryptedFolder {
+                    folder: folder.folder,
+                    readers: folder.readers.0,
+                })
+                .collect();
+            DocumentEncryptedFolder::replace_for_project(pool, project.id, &restored).await?;
+            return Err(e);
+        }
+    };
+
+    Ok(ResponseJson(ApiResponse::success(
+        UpdateEncryptedFoldersResponse {
+            folders: saved,
+            changed_documents,
+            committed,
+        },
+    )))
+}
+
+/// Keys of the project, newest first, without their key material
+pub async fn list_encryption_keys(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DocumentEncryptionKey>>>, ApiError> {
+    let keys = DocumentEncryptionKey::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(keys)))
+}
+
+/// Replace the project's key and re-encrypt its encrypted documents with the new one.
+/// Retired keys are kept, so documents of older commits can still be read.
+pub async fn rotate_encryption_key(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<RotateEncryptionKeyResponse>>, ApiError> {
+    let key = DocumentEncryptionKey::rotate(
+        &deployment.db().pool,
+        project.id,
+        &document_encryption::generate_key(),
+    )
+    .await?;
+    let keyring = DocumentKeyring::load(&deployment, project.id).await?;
+    let folders: BTreeSet<String> = keyring
+        .folders
+        .iter()
+        .map(|folder| folder.folder.clone())
+        .collect();
+    let (reencrypted_documents, committed) = reconcile_documents(
+        &deployment,
+        project.id,
+        &keyring,
+        &folders,
+        "docs: rotate document encryption key",
+    )
+    .await?;
+    tracing::info!(
+        "Rotated document encryption key of project {}; re-encrypted {} documents",
+        project.id,
+        reencrypted_documents.len()
+    );
+
+    Ok(ResponseJson(ApiResponse::success(
+        RotateEncryptionKeyResponse {
+            key,
+            reencrypted_documents,
+            committed,
+        },
+    )))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route(
+            "/folders",
+            get(get_encrypted_folders).put(set_encrypted_folders),
+        )
+        .route("/keys", get(list_encryption_keys))
+        .route("/keys/rotate", post(rotate_encryption_key))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest(paths::PROJECT_DOCUMENT_ENCRYPTION, project_router)
+}
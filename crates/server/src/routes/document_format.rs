@@ -0,0 +1,245 @@
+//! Formatting markdown documents to the project's style. A run either returns the
+//! formatted content with what it changed, or writes it back and commits it.
+
+use std::path::PathBuf;
+
+use axum::{
+    Extension,
+    extract::{Path as AxumPath, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use db::models::{
+    document_lock::DocumentLock,
+    project::{DocumentFormatOptions, Project},
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::markdown_format::{self, MarkdownLintIssue};
+use ts_rs::TS;
+use utils::{diff::create_unified_diff, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{
+        document_approvals, document_branches,
+        document_commits::DocumentCommitter,
+        document_encryption,
+        documents::{
+            DocumentCommitPreview, DocumentFileType, DocumentReadOnlyViolation, DocumentReader,
+            DocumentUpdateConflict, DryRunQuery, RepositoryQuery, auto_commit_warning,
+            content_hash, document_file_type, find_document_file, find_read_only_violation,
+            validate_relative_path,
+        },
+    },
+};
+
+/// Narrower line widths would wrap most sentences after a word or two
+const MIN_LINE_WIDTH: u32 = 20;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct FormatDocumentRequest {
+    /// Options for this run instead of the project's
+    #[serde(default)]
+    #[ts(optional)]
+    pub options: Option<DocumentFormatOptions>,
+    /// Write the formatted content back and commit it, instead of only returning it
+    #[serde(default)]
+    pub apply: bool,
+    /// `content_hash` of the version being formatted; required with `apply`
+    #[serde(default)]
+    #[ts(optional)]
+    pub expected_hash: Option<String>,
+    /// Name the document was locked with, if any
+    #[serde(default)]
+    #[ts(optional)]
+    pub lock_holder: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct FormatDocumentResponse {
+    pub relative_path: String,
+    pub content: String,
+    pub content_hash: String,
+    /// Style issues of the document before formatting, all fixed in `content`
+    pub issues: Vec<MarkdownLintIssue>,
+    pub changed: bool,
+    /// The formatted content was written to the document
+    pub applied: bool,
+    pub committed: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateDocumentFormatOptions {
+    /// `null` resets the project to the default options
+    pub options: Option<DocumentFormatOptions>,
+}
+
+fn validate_options(options: &DocumentFormatOptions) -> Result<(), ApiError> {
+    if options
+        .line_width
+        .is_some_and(|line_width| line_width < MIN_LINE_WIDTH)
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Line width must be at least {}",
+            MIN_LINE_WIDTH
+        )));
+    }
+    Ok(())
+}
+
+/// Format a markdown document with the project's options, or the ones sent
+pub async fn format_document(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+    Query(dry_run): Query<DryRunQuery>,
+    ResponseJson(body): ResponseJson<FormatDocumentRequest>,
+) -> Result<Response, ApiError> {
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?;
+    let decoded_path = validate_relative_path(&decoded_path)?.to_string();
+    let (repo, file_path) = find_document_file(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &decoded_path,
+    )
+    .await?;
+    if document_file_type(&file_path) != Some(DocumentFileType::Markdown) {
+        return Err(ApiError::BadRequest(format!(
+            "'{}' is not a markdown document",
+            decoded_path
+        )));
+    }
+    let pool = &deployment.db().pool;
+    let options = match body.options {
+        Some(options) => {
+            validate_options(&options)?;
+            options
+        }
+        None => Project::find_document_format_options(pool, project.id).await?,
+    };
+
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let content = reader.read(&repo.path, &decoded_path).await?;
+    let formatted = markdown_format::format(&content, &options);
+    let changed = formatted.content != content;
+    let response = FormatDocumentResponse {
+        relative_path: decoded_path.clone(),
+        content_hash: content_hash(&formatted.content),
+        content: formatted.content,
+        issues: formatted.issues,
+        changed,
+        applied: false,
+        committed: false,
+    };
+    if !body.apply || !changed {
+        return Ok(ResponseJson(ApiResponse::success(response)).into_response());
+    }
+
+    let current_hash = content_hash(&content);
+    let Some(expected_hash) = body.expected_hash else {
+        return Err(ApiError::BadRequest(
+            "Send the document's content_hash as expected_hash to apply formatting".to_string(),
+        ));
+    };
+    if expected_hash != current_hash {
+        let conflict = DocumentUpdateConflict {
+            current_hash,
+            current_content: content,
+        };
+        let response: ApiResponse<FormatDocumentResponse, DocumentUpdateConflict> =
+            ApiResponse::error_with_data(conflict);
+        return Ok((StatusCode::CONFLICT, ResponseJson(response)).into_response());
+    }
+    if let Some(violation) =
+        find_read_only_violation(&deployment, project.id, &decoded_path).await?
+    {
+        let response: ApiResponse<FormatDocumentResponse, DocumentReadOnlyViolation> =
+            ApiResponse::error_with_data(violation);
+        return Ok(ResponseJson(response).into_response());
+    }
+    document_approvals::require_unprotected(&deployment, project.id, &decoded_path).await?;
+    document_encryption::require_unencrypted(&deployment, project.id, &decoded_path).await?;
+    if let Some(lock) = DocumentLock::find_active(pool, repo.id, &decoded_path).await?
+        && body.lock_holder.as_deref().map(str::trim) != Some(lock.holder.as_str())
+    {
+        let response: ApiResponse<FormatDocumentResponse, DocumentLock> =
+            ApiResponse::error_with_data(lock);
+        return Ok((StatusCode::LOCKED, ResponseJson(response)).into_response());
+    }
+    let repo_path = PathBuf::from(&repo.path);
+    let branch = if dry_run.dry_run {
+        document_branches::editable_branch(&deployment, project.id, &repo_path).await?
+    } else {
+        document_branches::require_editable_branch(
+            &deployment,
+            project.id,
+            &repo_path,
+            &decoded_path,
+        )
+        .await?
+    };
+
+    let committer = DocumentCommitter::load(&deployment, project.id).await?;
+    let commit_message =
+        committer.message(&format!("docs: format {}", decoded_path), &decoded_path);
+    if dry_run.dry_run {
+        return Ok(DocumentCommitPreview {
+            repository_id: repo.id,
+            branch: Some(branch),
+            diff: create_unified_diff(&decoded_path, &content, &response.content),
+            commit_message,
+        }
+        .into_response());
+    }
+    tokio::fs::write(&file_path, &response.content).await?;
+    deployment.document_index().invalidate(repo.id);
+    let mut warnings = Vec::new();
+    let committed = match committer.commit(&deployment, &repo_path, &commit_message) {
+        Ok(committed) => committed,
+        Err(e) => {
+            tracing::warn!("Failed to auto-commit formatting: {}", e);
+            warnings.push(auto_commit_warning("formatting", &e));
+            false
+        }
+    };
+
+    Ok(ResponseJson(
+        ApiResponse::success(FormatDocumentResponse {
+            applied: true,
+            committed,
+            ..response
+        })
+        .with_warnings(warnings),
+    )
+    .into_response())
+}
+
+pub async fn get_document_format_options(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentFormatOptions>>, ApiError> {
+    let options = Project::find_document_format_options(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(options)))
+}
+
+/// Set how the project's markdown documents are formatted, or reset to the defaults
+pub async fn update_document_format_options(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<UpdateDocumentFormatOptions>,
+) -> Result<ResponseJson<ApiResponse<DocumentFormatOptions>>, ApiError> {
+    if let Some(options) = &body.options {
+        validate_options(options)?;
+    }
+    Project::set_document_format_options(&deployment.db().pool, project.id, body.options.as_ref())
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(
+        body.options.unwrap_or_default(),
+    )))
+}
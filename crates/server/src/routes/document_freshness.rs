@@ -0,0 +1,316 @@
+//! Freshness of documents that describe code. A document references the files and
+//! folders it links to, plus the code the project's reference mappings assign to it,
+//! and is possibly stale once any of that code changed on main after the document did.
+
+use std::{collections::HashMap, path::Path};
+
+use axum::{
+    Extension,
+    extract::{Path as AxumPath, Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{
+    project::{DocumentCodeReference, Project},
+    repo::Repo,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    git::{FileCommit, PathChange},
+    markdown_html,
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::documents::{
+        DEFAULT_DOCS_BRANCH, DocumentFileType, DocumentReader, RepositoryQuery, document_file_type,
+        find_document_file, path_glob, project_repositories, scan_project_documents,
+        validate_relative_path,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentReferenceSource {
+    /// The document links to the code
+    Link,
+    /// A code reference mapping of the project assigns the code to the document
+    Mapping,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentReference {
+    /// File or folder relative to the repository root
+    pub path: String,
+    pub source: DocumentReferenceSource,
+    /// Files at or below `path` that changed on main since the document last did
+    pub changed_files: Vec<PathChange>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentFreshness {
+    pub repository_id: Uuid,
+    pub relative_path: String,
+    /// Last commit on main that changed the document; `None` if it was never committed
+    pub last_updated: Option<FileCommit>,
+    pub references: Vec<DocumentReference>,
+    /// Referenced code changed on main after the document did
+    pub possibly_stale: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentFreshnessOverview {
+    /// Markdown documents that reference code, possibly stale ones first
+    pub documents: Vec<DocumentFreshness>,
+    pub stale_count: u32,
+    /// The document scan hit its limits, so some documents may be missing
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct DocumentCodeReferences {
+    pub mappings: Vec<DocumentCodeReference>,
+}
+
+/// Code `content` refers to: link targets that aren't documents themselves, then the
+/// code of mappings whose glob covers `relative_path`
+fn document_references(
+    content: &str,
+    relative_path: &str,
+    mappings: &[DocumentCodeReference],
+) -> Vec<(String, DocumentReferenceSource)> {
+    let mut references: Vec<(String, DocumentReferenceSource)> =
+        markdown_html::relative_links(content, relative_path)
+            .into_iter()
+            .filter(|target| document_file_type(Path::new(target)).is_none())
+            .map(|target| (target, DocumentReferenceSource::Link))
+            .collect();
+    for mapping in mappings {
+        if !path_glob(&mapping.documents).is_ok_and(|glob| glob.is_match(relative_path)) {
+            continue;
+        }
+        for code in &mapping.code {
+            let code = code.trim().trim_matches('/');
+            if !code.is_empty() && !references.iter().any(|(path, _)| path == code) {
+                references.push((code.to_string(), DocumentReferenceSource::Mapping));
+            }
+        }
+    }
+    references
+}
+
+/// Files changed on main since each commit, keyed by repository and commit hash
+type ChangeCache = HashMap<(Uuid, String), Vec<PathChange>>;
+
+fn document_freshness(
+    deployment: &DeploymentImpl,
+    repo: &Repo,
+    relative_path: &str,
+    content: &str,
+    mappings: &[DocumentCodeReference],
+    changes_since: &mut ChangeCache,
+) -> Result<DocumentFreshness, ApiError> {
+    let references = document_references(content, relative_path, mappings);
+    let git = deployment.git_backend();
+    let last_updated = if references.is_empty() {
+        None
+    } else {
+        git.last_commit_touching(&repo.path, DEFAULT_DOCS_BRANCH, relative_path)?
+    };
+
+    let changes: &[PathChange] = match &last_updated {
+        Some(commit) => {
+            let key = (repo.id, commit.hash.clone());
+            if !changes_since.contains_key(&key) {
+                let changes =
+                    git.changed_paths_between(&repo.path, &commit.hash, DEFAULT_DOCS_BRANCH)?;
+                changes_since.insert(key.clone(), changes);
+            }
+            &changes_since[&key]
+        }
+        // Documents that were never committed have nothing to fall behind on
+        None => &[],
+    };
+    let references: Vec<DocumentReference> = references
+        .into_iter()
+        .map(|(path, source)| DocumentReference {
+            changed_files: changes
+                .iter()
+                .filter(|change| Path::new(&change.path).starts_with(&path))
+                .cloned()
+                .collect(),
+            path,
+            source,
+        })
+        .collect();
+
+    Ok(DocumentFreshness {
+        repository_id: repo.id,
+        relative_path: relative_path.to_string(),
+        last_updated,
+        possibly_stale: references
+            .iter()
+            .any(|reference| !reference.changed_files.is_empty()),
+        references,
+    })
+}
+
+/// Freshness of every markdown document of the project that references code
+pub async fn get_document_freshness_overview(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(repository): Query<RepositoryQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentFreshnessOverview>>, ApiError> {
+    let mappings =
+        Project::find_document_code_references(&deployment.db().pool, project.id).await?;
+    let repositories: HashMap<Uuid, Repo> =
+        project_repositories(&deployment, project.id, repository.repository_id)
+            .await?
+            .into_iter()
+            .map(|repo| (repo.id, repo))
+            .collect();
+    let (documents, truncated) =
+        scan_project_documents(&deployment, project.id, repository.repository_id, None).await?;
+
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let mut changes_since = ChangeCache::new();
+    let mut overview = Vec::new();
+    for document in documents {
+        if document.file_type != DocumentFileType::Markdown {
+            continue;
+        }
+        let Some(repo) = repositories.get(&document.repository_id) else {
+            continue;
+        };
+        let content = match reader.read_listed(&document).await {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {}", document.absolute_path, e);
+                continue;
+            }
+        };
+        match document_freshness(
+            &deployment,
+            repo,
+            &document.relative_path,
+            &content,
+            &mappings,
+            &mut changes_since,
+        ) {
+            Ok(freshness) if !freshness.references.is_empty() => overview.push(freshness),
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to check freshness of {}: {}",
+                    document.relative_path,
+                    e
+                );
+            }
+        }
+    }
+    // Stable, so documents stay sorted by path within each group
+    overview.sort_by_key(|freshness| !freshness.possibly_stale);
+
+    Ok(ResponseJson(ApiResponse::success(
+        DocumentFreshnessOverview {
+            stale_count: overview.iter().filter(|doc| doc.possibly_stale).count() as u32,
+            documents: overview,
+            truncated,
+        },
+    )))
+}
+
+/// Freshness of a single markdown document
+pub async fn get_document_freshness(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentFreshness>>, ApiError> {
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?;
+    let decoded_path = validate_relative_path(&decoded_path)?.to_string();
+    let (repo, file_path) = find_document_file(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &decoded_path,
+    )
+    .await?;
+    if document_file_type(&file_path) != Some(DocumentFileType::Markdown) {
+        return Err(ApiError::BadRequest(format!(
+            "'{}' is not a markdown document",
+            decoded_path
+        )));
+    }
+
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let content = reader.read(&repo.path, &decoded_path).await?;
+    let mappings =
+        Project::find_document_code_references(&deployment.db().pool, project.id).await?;
+    let freshness = document_freshness(
+        &deployment,
+        &repo,
+        &decoded_path,
+        &content,
+        &mappings,
+        &mut ChangeCache::new(),
+    )?;
+    Ok(ResponseJson(ApiResponse::success(freshness)))
+}
+
+pub async fn get_document_code_references(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentCodeReferences>>, ApiError> {
+    let mappings =
+        Project::find_document_code_references(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentCodeReferences {
+        mappings,
+    })))
+}
+
+/// Replace the project's code reference mappings; an empty list removes them all
+pub async fn update_document_code_references(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<DocumentCodeReferences>,
+) -> Result<ResponseJson<ApiResponse<DocumentCodeReferences>>, ApiError> {
+    let mut mappings = Vec::with_capacity(body.mappings.len());
+    for mapping in body.mappings {
+        let documents = mapping.documents.trim().trim_start_matches('/').to_string();
+        if documents.is_empty() {
+            continue;
+        }
+        path_glob(&documents).map_err(|e| {
+            ApiError::BadRequest(format!("Invalid document pattern '{}': {}", documents, e))
+        })?;
+        let mut code = Vec::with_capacity(mapping.code.len());
+        for path in mapping.code {
+            let path = path.trim().trim_matches('/');
+            if path.is_empty() {
+                continue;
+            }
+            let path = validate_relative_path(path)?.to_string();
+            if !code.contains(&path) {
+                code.push(path);
+            }
+        }
+        if code.is_empty() {
+            return Err(ApiError::BadRequest(format!(
+                "Add at least one code path for '{}'",
+                documents
+            )));
+        }
+        mappings.push(DocumentCodeReference { documents, code });
+    }
+
+    Project::set_document_code_references(&deployment.db().pool, project.id, &mappings).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentCodeReferences {
+        mappings,
+    })))
+}
@@ -0,0 +1,288 @@
+//! Importing existing documents from uploaded files or zip archives, written under one
+//! folder and committed together.
+
+use std::{collections::HashSet, path::PathBuf};
+
+use axum::{
+    Extension,
+    extract::{Multipart, Query, State},
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use db::models::project::Project;
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::document_import::{self, ArchiveFile};
+use ts_rs::TS;
+use utils::{diff::create_unified_diff, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{
+        board_links,
+        document_batch::within_repository,
+        document_branches,
+        document_commits::DocumentCommitter,
+        document_encryption::DocumentKeyring,
+        document_roots,
+        documents::{
+            DocumentCommitPreview, DocumentReadOnlyViolation, DryRunQuery, RepositoryQuery,
+            auto_commit_warning, find_read_only_violation, is_scanned_path, project_extensions,
+            project_repositories, require_document_type, validate_relative_path,
+        },
+    },
+};
+
+/// Size limit of an import request
+pub(crate) const MAX_IMPORT_UPLOAD_BYTES: usize = 50 * 1024 * 1024;
+const MAX_IMPORT_DOCUMENTS: usize = 500;
+/// Size limit of all imported documents together, once archives are unpacked
+const MAX_IMPORT_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Serialize, TS)]
+pub struct ImportDocumentsResponse {
+    pub repository_id: Uuid,
+    /// Relative paths of the imported documents
+    pub documents: Vec<String>,
+    pub branch: String,
+    pub committed: bool,
+}
+
+/// A validated document of an import
+struct PlannedImport {
+    relative_path: String,
+    file_path: PathBuf,
+    content: String,
+}
+
+/// Import documents from a multipart upload into the primary repository, or the one
+/// given by `repository_id`, and commit them together. The form has:
+///
+/// - `folder`: folder to import into, the repository root by default
+/// - `file`: a document, or a `.zip` archive whose files are imported with their paths
+///   inside it; may be repeated
+/// - `message`: commit message, optional
+///
+/// All documents are checked before anything is written. Existing documents are never
+/// overwritten.
+pub async fn import_documents(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(repository): Query<RepositoryQuery>,
+    Query(dry_run): Query<DryRunQuery>,
+    mut multipart: Multipart,
+) -> Result<Response, ApiError> {
+    let mut folder = String::new();
+    let mut message = None;
+    let mut uploads: Vec<ArchiveFile> = Vec::new();
+    let mut total_bytes = 0u64;
+    while let Some(field) = multipart.next_field().await? {
+        match field.name() {
+            Some("folder") => folder = field.text().await?,
+            Some("message") => message = Some(field.text().await?),
+            Some("file") => {
+                let name = field.file_name().map(str::to_string).ok_or_else(|| {
+                    ApiError::BadRequest("Uploaded files need a file name".to_string())
+                })?;
+                let bytes = field.bytes().await?;
+                let files = if name.to_lowercase().ends_with(".zip") {
+                    document_import::unpack_zip(
+                        &bytes,
+                        MAX_IMPORT_DOCUMENTS,
+                        MAX_IMPORT_BYTES.saturating_sub(total_bytes),
+                    )
+                    .map_err(|e| ApiError::BadRequest(format!("{}: {}", name, e)))?
+                } else {
+                    vec![ArchiveFile {
+                        path: name,
+                        content: bytes.to_vec(),
+                    }]
+                };
+                total_bytes += files.iter().map(|f| f.content.len() as u64).sum::<u64>();
+                uploads.extend(files);
+            }
+            _ => {}
+        }
+    }
+    if uploads.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Upload at least one document".to_string(),
+        ));
+    }
+    if uploads.len() > MAX_IMPORT_DOCUMENTS {
+        return Err(ApiError::BadRequest(format!(
+            "An import can add at most {} documents",
+            MAX_IMPORT_DOCUMENTS
+        )));
+    }
+    if total_bytes > MAX_IMPORT_BYTES {
+        return Err(ApiError::BadRequest(format!(
+            "An import can add at most {} MB of documents",
+            MAX_IMPORT_BYTES / (1024 * 1024)
+        )));
+    }
+
+    let folder = folder.trim().trim_matches('/');
+    if !folder.is_empty() {
+        validate_relative_path(folder)?;
+    }
+    let repo = project_repositories(&deployment, project.id, repository.repository_id)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::BadRequest("No repository found for this project".to_string()))?;
+    if !repo.path.is_dir() {
+        return Err(ApiError::BadRequest(
+            "Repository path does not exist".to_string(),
+        ));
+    }
+
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let roots = document_roots::project_document_roots(&deployment, project.id).await?;
+    let mut imports = Vec::with_capacity(uploads.len());
+    let mut seen = HashSet::new();
+    for upload in uploads {
+        let path = upload.path.trim_matches('/');
+        let relative_path = if folder.is_empty() {
+            path.to_string()
+        } else {
+            format!("{}/{}", folder, path)
+        };
+        let relative_path = validate_relative_path(&relative_path)?.to_string();
+        if !is_scanned_path(&relative_path) {
+            return Err(ApiError::BadRequest(format!(
+                "'{}' is in a hidden or excluded folder",
+                relative_path
+            )));
+        }
+        let file_path = repo.path.join(&relative_path);
+        require_document_type(&file_path, &extensions)?;
+        document_roots::require_in_document_roots(&roots, &relative_path)?;
+        if !within_repository(&repo.path, &file_path) {
+            return Err(ApiError::BadRequest(
+                "Invalid file path: access denied".to_string(),
+            ));
+        }
+        if !seen.insert(relative_path.clone()) {
+            return Err(ApiError::BadRequest(format!(
+                "'{}' is in the upload more than once",
+                relative_path
+            )));
+        }
+        if file_path.symlink_metadata().is_ok() {
+            return Err(ApiError::BadRequest(format!(
+                "File '{}' already exists",
+                relative_path
+            )));
+        }
+        if let Some(violation) =
+            find_read_only_violation(&deployment, project.id, &relative_path).await?
+        {
+            let response: ApiResponse<ImportDocumentsResponse, DocumentReadOnlyViolation> =
+                ApiResponse::error_with_data(violation);
+            return Ok(ResponseJson(response).into_response());
+        }
+        let content = String::from_utf8(upload.content)
+            .map_err(|_| ApiError::BadRequest(format!("'{}' is not UTF-8 text", relative_path)))?;
+        board_links::require_valid_links(&deployment, project.id, &content, None).await?;
+
+        imports.push(PlannedImport {
+            relative_path,
+            file_path,
+            content,
+        });
+    }
+    imports.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+    // Ensure we're on a branch documents can be edited on before creating documents
+    let current_branch = if dry_run.dry_run {
+        document_branches::editable_branch(&deployment, project.id, &repo.path).await?
+    } else {
+        document_branches::require_editable_branch(&deployment, project.id, &repo.path, "import")
+            .await?
+    };
+    let subject = match message.as_deref().map(str::trim) {
+        Some(message) if !message.is_empty() => message.to_string(),
+        _ if imports.len() == 1 => format!("docs: import {}", imports[0].relative_path),
+        _ if folder.is_empty() => format!("docs: import {} documents", imports.len()),
+        _ => format!("docs: import {} documents into {}", imports.len(), folder),
+    };
+    let paths: Vec<&str> = imports
+        .iter()
+        .map(|import| import.relative_path.as_str())
+        .collect();
+    let committer = DocumentCommitter::load(&deployment, project.id).await?;
+    let commit_message = committer.message(&subject, &paths.join(", "));
+    if dry_run.dry_run {
+        return Ok(DocumentCommitPreview {
+            repository_id: repo.id,
+            branch: Some(current_branch),
+            commit_message,
+            diff: imports
+                .iter()
+                .map(|import| create_unified_diff(&import.relative_path, "", &import.content))
+                .collect(),
+        }
+        .into_response());
+    }
+
+    // Documents in encrypted folders are written encrypted, as when created one by one
+    let keyring = DocumentKeyring::load(&deployment, project.id).await?;
+    let mut stored = Vec::with_capacity(imports.len());
+    for import in &imports {
+        stored.push(keyring.encrypt(&import.relative_path, &import.content)?);
+    }
+    for (index, (import, content)) in imports.iter().zip(&stored).enumerate() {
+        let written = match import.file_path.parent() {
+            Some(parent) => tokio::fs::create_dir_all(parent).await,
+            None => Ok(()),
+        };
+        let written = match written {
+            Ok(()) => tokio::fs::write(&import.file_path, content).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = written {
+            tracing::error!("Failed to write {:?}: {}", import.file_path, e);
+            for written in &imports[..index] {
+                if let Err(e) = tokio::fs::remove_file(&written.file_path).await {
+                    tracing::error!("Failed to remove {:?}: {}", written.file_path, e);
+                }
+            }
+            return Err(ApiError::BadRequest(format!(
+                "Failed to save '{}': {}; no document was imported",
+                import.relative_path, e
+            )));
+        }
+    }
+    deployment.document_index().invalidate(repo.id);
+    tracing::info!(
+        "Imported {} documents into {:?}",
+        imports.len(),
+        repo.path.join(folder)
+    );
+
+    let mut warnings = Vec::new();
+    let committed = match committer.commit(&deployment, &repo.path, &commit_message) {
+        Ok(committed) => committed,
+        Err(e) => {
+            tracing::warn!("Failed to auto-commit document import: {}", e);
+            warnings.push(auto_commit_warning("import", &e));
+            false
+        }
+    };
+
+    Ok(ResponseJson(
+        ApiResponse::success(ImportDocumentsResponse {
+            repository_id: repo.id,
+            documents: imports
+                .into_iter()
+                .map(|import| import.relative_path)
+                .collect(),
+            branch: current_branch,
+            committed,
+        })
+        .with_warnings(warnings),
+    )
+    .into_response())
+}
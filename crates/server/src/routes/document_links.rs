@@ -0,0 +1,221 @@
+//! Links between the markdown documents of a project, from `[[wiki-links]]` and relative
+//! markdown links: the backlinks of a document and the link graph of the whole project.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use axum::{
+    Extension,
+    extract::{Path as AxumPath, Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::project::Project;
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::document_links::{self, DocumentLink, DocumentLinkKind, ResolvedLink};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::documents::{
+        DocumentFileType, DocumentReader, RepositoryQuery, find_document_file,
+        scan_project_documents, validate_relative_path,
+    },
+};
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentLinkEdge {
+    pub repository_id: Uuid,
+    /// Path of the linking document
+    pub source: String,
+    /// Path of the linked document, or the name as written for unresolved wiki-links
+    pub target: String,
+    pub kind: DocumentLinkKind,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentBacklinks {
+    pub repository_id: Uuid,
+    pub relative_path: String,
+    /// Links from other documents to this one, sorted by source
+    pub backlinks: Vec<DocumentLinkEdge>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentLinkNode {
+    pub repository_id: Uuid,
+    pub relative_path: String,
+    pub file_type: DocumentFileType,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentLinkGraph {
+    /// Markdown documents, plus other documents they link to
+    pub nodes: Vec<DocumentLinkNode>,
+    pub edges: Vec<DocumentLinkEdge>,
+    /// Wiki-links that name no document of their repository
+    pub unresolved: Vec<DocumentLinkEdge>,
+    /// The document scan hit its limits, so some documents may be missing
+    pub truncated: bool,
+}
+
+/// Documents of one repository and the links of its markdown documents
+#[derive(Default)]
+struct RepositoryLinks {
+    file_types: BTreeMap<String, DocumentFileType>,
+    links: BTreeMap<String, Vec<DocumentLink>>,
+}
+
+impl RepositoryLinks {
+    fn resolve(&self) -> (Vec<ResolvedLink>, Vec<ResolvedLink>) {
+        let documents: BTreeSet<String> = self.file_types.keys().cloned().collect();
+        document_links::resolve_all(
+            &documents,
+            self.links
+                .iter()
+                .map(|(source, links)| (source.as_str(), links.as_slice())),
+        )
+    }
+}
+
+/// Documents and links of the project's repositories. Links come from the document
+/// index, which reads them while scanning; documents it doesn't hold are read here.
+async fn project_links(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repository_id: Option<Uuid>,
+) -> Result<(BTreeMap<Uuid, RepositoryLinks>, bool), ApiError> {
+    let (documents, truncated) =
+        scan_project_documents(deployment, project_id, repository_id, None).await?;
+
+    let reader = DocumentReader::load(deployment, project_id).await?;
+    let mut repositories: BTreeMap<Uuid, RepositoryLinks> = BTreeMap::new();
+    let mut indexed: HashMap<Uuid, HashMap<String, Vec<DocumentLink>>> = HashMap::new();
+    for document in documents {
+        let is_markdown = document.file_type == DocumentFileType::Markdown;
+        let repository = repositories.entry(document.repository_id).or_default();
+        repository
+            .file_types
+            .insert(document.relative_path.clone(), document.file_type);
+        if !is_markdown {
+            continue;
+        }
+
+        let indexed_links = indexed.entry(document.repository_id).or_insert_with(|| {
+            deployment
+                .document_index()
+                .documents(document.repository_id)
+                .map(|indexed| {
+                    indexed
+                        .documents
+                        .into_iter()
+                        .map(|doc| (doc.relative_path, doc.links))
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+        // The index reads encrypted documents as ciphertext, which has no links
+        let indexed_document_links = indexed_links
+            .remove(&document.relative_path)
+            .filter(|_| !reader.is_encrypted(&document.relative_path));
+        let links = match indexed_document_links {
+            Some(links) => links,
+            None => match reader.read_listed(&document).await {
+                Ok(content) => document_links::parse(&content, &document.relative_path),
+                Err(e) => {
+                    tracing::warn!("Failed to read {}: {}", document.absolute_path, e);
+                    continue;
+                }
+            },
+        };
+        repository.links.insert(document.relative_path, links);
+    }
+    Ok((repositories, truncated))
+}
+
+fn edge(repository_id: Uuid, link: ResolvedLink) -> DocumentLinkEdge {
+    DocumentLinkEdge {
+        repository_id,
+        source: link.source,
+        target: link.target,
+        kind: link.kind,
+    }
+}
+
+/// Documents of the same repository that link to a document
+pub async fn get_document_backlinks(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentBacklinks>>, ApiError> {
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?;
+    let decoded_path = validate_relative_path(&decoded_path)?.to_string();
+    let (repo, _) = find_document_file(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &decoded_path,
+    )
+    .await?;
+
+    let (mut repositories, _) = project_links(&deployment, project.id, Some(repo.id)).await?;
+    let (resolved, _) = repositories.remove(&repo.id).unwrap_or_default().resolve();
+    let mut backlinks: Vec<DocumentLinkEdge> = resolved
+        .into_iter()
+        .filter(|link| link.target == decoded_path)
+        .map(|link| edge(repo.id, link))
+        .collect();
+    backlinks.sort_by(|a, b| a.source.cmp(&b.source));
+
+    Ok(ResponseJson(ApiResponse::success(DocumentBacklinks {
+        repository_id: repo.id,
+        relative_path: decoded_path,
+        backlinks,
+    })))
+}
+
+/// Links between all documents of the project, or of one repository
+pub async fn get_document_link_graph(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(repository): Query<RepositoryQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentLinkGraph>>, ApiError> {
+    let (repositories, truncated) =
+        project_links(&deployment, project.id, repository.repository_id).await?;
+
+    let mut graph = DocumentLinkGraph {
+        nodes: Vec::new(),
+        edges: Vec::new(),
+        unresolved: Vec::new(),
+        truncated,
+    };
+    for (repository_id, repository) in repositories {
+        let (resolved, unresolved) = repository.resolve();
+        let linked: BTreeSet<&str> = resolved.iter().map(|link| link.target.as_str()).collect();
+        graph.nodes.extend(
+            repository
+                .file_types
+                .iter()
+                .filter(|(path, file_type)| {
+                    **file_type == DocumentFileType::Markdown || linked.contains(path.as_str())
+                })
+                .map(|(path, file_type)| DocumentLinkNode {
+                    repository_id,
+                    relative_path: path.clone(),
+                    file_type: file_type.clone(),
+                }),
+        );
+        graph
+            .edges
+            .extend(resolved.into_iter().map(|link| edge(repository_id, link)));
+        graph
+            .unresolved
+            .extend(unresolved.into_iter().map(|link| edge(repository_id, link)));
+    }
+
+    Ok(ResponseJson(ApiResponse::success(graph)))
+}
@@ -0,0 +1,243 @@
+use std::{collections::HashMap, path::Path};
+
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{document_lock::DocumentLock, project::Project, repo::Repo};
+use deployment::Deployment;
+use serde::Deserialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{
+        documents::{DocumentMetadata, DocumentTreeNode, project_repositories},
+        paths,
+    },
+};
+
+/// How long a lock lasts when the client doesn't ask for a duration
+const DEFAULT_LOCK_TTL_SECONDS: u32 = 5 * 60;
+const MIN_LOCK_TTL_SECONDS: u32 = 30;
+const MAX_LOCK_TTL_SECONDS: u32 = 60 * 60;
+
+/// Request body to acquire or renew a document lock
+#[derive(Debug, Deserialize, TS)]
+pub struct DocumentLockRequest {
+    /// Relative path of the document from its repository root
+    pub relative_path: String,
+    /// Repository of the document; defaults to the first one containing `relative_path`
+    #[serde(default)]
+    #[ts(optional)]
+    pub repository_id: Option<Uuid>,
+    /// Name of the editor taking the lock
+    pub holder: String,
+    /// How long the lock lasts, between 30 seconds and an hour. Defaults to 5 minutes.
+    #[serde(default)]
+    #[ts(optional)]
+    pub ttl_seconds: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ReleaseDocumentLockRequest {
+    pub relative_path: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub repository_id: Option<Uuid>,
+    pub holder: String,
+}
+
+/// Active locks of a project, keyed by repository and relative path
+pub(crate) async fn project_locks(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+) -> Result<HashMap<(Uuid, String), DocumentLock>, ApiError> {
+    Ok(
+        DocumentLock::find_active_by_project_id(&deployment.db().pool, project_id)
+            .await?
+            .into_iter()
+            .map(|lock| ((lock.repo_id, lock.relative_path.clone()), lock))
+            .collect(),
+    )
+}
+
+/// Fill in `lock` of each document from [`project_locks`]
+pub(crate) fn attach_locks<'a>(
+    locks: &mut HashMap<(Uuid, String), DocumentLock>,
+    documents: impl IntoIterator<Item = &'a mut DocumentMetadata>,
+) {
+    if locks.is_empty() {
+        return;
+    }
+    for document in documents {
+        document.lock = locks.remove(&(document.repository_id, document.relative_path.clone()));
+    }
+}
+
+pub(crate) fn attach_tree_locks(
+    locks: &mut HashMap<(Uuid, String), DocumentLock>,
+    nodes: &mut [DocumentTreeNode],
+) {
+    for node in nodes {
+        match node {
+            DocumentTreeNode::Folder { children, .. } => attach_tree_locks(locks, children),
+            DocumentTreeNode::Document(document) => attach_locks(locks, std::iter::once(document)),
+        }
+    }
+}
+
+fn require_holder(holder: &str) -> Result<&str, ApiError> {
+    let holder = holder.trim();
+    if holder.is_empty() {
+        return Err(ApiError::BadRequest(
+            "A lock holder name is required".to_string(),
+        ));
+    }
+    Ok(holder)
+}
+
+fn lock_ttl(ttl_seconds: Option<u32>) -> i64 {
+    ttl_seconds
+        .unwrap_or(DEFAULT_LOCK_TTL_SECONDS)
+        .clamp(MIN_LOCK_TTL_SECONDS, MAX_LOCK_TTL_SECONDS)
+        .into()
+}
+
+/// The repository holding `relative_path`, and the path with surrounding slashes removed
+async fn document_repository(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repository_id: Option<Uuid>,
+    relative_path: &str,
+) -> Result<(Repo, String), ApiError> {
+    let relative_path = relative_path.trim_matches('/');
+    if relative_path.is_empty() || relative_path.split('/').any(|segment| segment == "..") {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid document path '{}'",
+            relative_path
+        )));
+    }
+    let repo = project_repositories(deployment, project_id, repository_id)
+        .await?
+        .into_iter()
+        .find(|repo| Path::new(&repo.path).join(relative_path).is_file())
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Document '{}' not found in project repositories",
+                relative_path
+            ))
+        })?;
+    Ok((repo, relative_path.to_string()))
+}
+
+pub async fn list_locks(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DocumentLock>>>, ApiError> {
+    let locks = DocumentLock::find_active_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(locks)))
+}
+
+/// Lock a document, or extend the caller's own lock. While someone else holds it the
+/// request fails with their lock as `error_data`.
+pub async fn acquire_lock(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DocumentLockRequest>,
+) -> Result<ResponseJson<ApiResponse<DocumentLock, DocumentLock>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let holder = require_holder(&payload.holder)?;
+    let (repo, relative_path) = document_repository(
+        &deployment,
+        project.id,
+        payload.repository_id,
+        &payload.relative_path,
+    )
+    .await?;
+
+    let ttl = lock_ttl(payload.ttl_seconds);
+    if let Some(lock) =
+        DocumentLock::acquire(pool, project.id, repo.id, &relative_path, holder, ttl).await?
+    {
+        return Ok(ResponseJson(ApiResponse::success(lock)));
+    }
+    match DocumentLock::find_active(pool, repo.id, &relative_path).await? {
+        Some(lock) => Ok(ResponseJson(ApiResponse::error_with_data(lock))),
+        // The other lock expired in between
+        None => DocumentLock::acquire(pool, project.id, repo.id, &relative_path, holder, ttl)
+            .await?
+            .map(|lock| ResponseJson(ApiResponse::success(lock)))
+            .ok_or_else(|| ApiError::Conflict("The document was locked meanwhile".to_string())),
+    }
+}
+
+/// Extend a lock the caller still holds
+pub async fn renew_lock(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DocumentLockRequest>,
+) -> Result<ResponseJson<ApiResponse<DocumentLock>>, ApiError> {
+    let holder = require_holder(&payload.holder)?;
+    let (repo, relative_path) = document_repository(
+        &deployment,
+        project.id,
+        payload.repository_id,
+        &payload.relative_path,
+    )
+    .await?;
+
+    let lock = DocumentLock::renew(
+        &deployment.db().pool,
+        repo.id,
+        &relative_path,
+        holder,
+        lock_ttl(payload.ttl_seconds),
+    )
+    .await?
+    .ok_or_else(|| {
+        ApiError::Conflict(format!(
+            "{} no longer holds the lock on '{}'",
+            holder, relative_path
+        ))
+    })?;
+    Ok(ResponseJson(ApiResponse::success(lock)))
+}
+
+pub async fn release_lock(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ReleaseDocumentLockRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let holder = require_holder(&payload.holder)?;
+    let (repo, relative_path) = document_repository(
+        &deployment,
+        project.id,
+        payload.repository_id,
+        &payload.relative_path,
+    )
+    .await?;
+
+    DocumentLock::release(&deployment.db().pool, repo.id, &relative_path, holder).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route("/", get(list_locks).post(acquire_lock))
+        .route("/renew", post(renew_lock))
+        .route("/release", post(release_lock))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest(paths::PROJECT_DOCUMENT_LOCKS, project_router)
+}
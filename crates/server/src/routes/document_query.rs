@@ -0,0 +1,150 @@
+//! Reading one section of a JSON document, addressed by a JSON pointer, so clients
+//! exploring large datasets don't have to download the whole file.
+
+use axum::{
+    Extension,
+    extract::{Path as AxumPath, Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::project::Project;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+use utils::{json_subtree, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::documents::{
+        DocumentFileType, DocumentReader, RepositoryQuery, content_hash, document_file_type,
+        find_document_file, validate_relative_path,
+    },
+};
+
+/// Entries listed per array or object when the client doesn't ask for a number
+const DEFAULT_QUERY_LIMIT: usize = 100;
+const MAX_QUERY_LIMIT: usize = 1000;
+/// Largest serialized value a query returns
+const MAX_QUERY_BYTES: usize = 256 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct JsonDocumentQuery {
+    /// JSON pointer (RFC 6901) of the section, e.g. `/users/0/name`; the whole document
+    /// by default
+    #[serde(default)]
+    pub pointer: String,
+    /// Entries to list per array or object
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonValueType {
+    Object,
+    Array,
+    String,
+    Number,
+    Boolean,
+    Null,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct JsonDocumentQueryResponse {
+    pub relative_path: String,
+    pub pointer: String,
+    pub value_type: JsonValueType,
+    /// Entries of an object or array, or characters of a string, before truncation
+    pub length: Option<usize>,
+    /// The section, with at most `limit` entries per array or object and long strings
+    /// cut short so it stays small
+    #[ts(type = "unknown")]
+    pub value: Value,
+    /// Whether anything of the section was left out of `value`
+    pub truncated: bool,
+    pub content_hash: String,
+}
+
+fn value_type(value: &Value) -> (JsonValueType, Option<usize>) {
+    match value {
+        Value::Object(entries) => (JsonValueType::Object, Some(entries.len())),
+        Value::Array(items) => (JsonValueType::Array, Some(items.len())),
+        Value::String(text) => (JsonValueType::String, Some(text.chars().count())),
+        Value::Number(_) => (JsonValueType::Number, None),
+        Value::Bool(_) => (JsonValueType::Boolean, None),
+        Value::Null => (JsonValueType::Null, None),
+    }
+}
+
+/// The section of a JSON document at a JSON pointer, truncated to a size budget
+pub async fn query_json_document(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+    Query(query): Query<JsonDocumentQuery>,
+) -> Result<ResponseJson<ApiResponse<JsonDocumentQueryResponse>>, ApiError> {
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?;
+    let decoded_path = validate_relative_path(&decoded_path)?.to_string();
+    let (repo, file_path) = find_document_file(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &decoded_path,
+    )
+    .await?;
+    if document_file_type(&file_path) != Some(DocumentFileType::Json) {
+        return Err(ApiError::BadRequest(format!(
+            "'{}' is not a JSON document",
+            decoded_path
+        )));
+    }
+    if !query.pointer.is_empty() && !query.pointer.starts_with('/') {
+        return Err(ApiError::BadRequest(format!(
+            "'{}' is not a JSON pointer; pointers start with '/', e.g. /items/0",
+            query.pointer
+        )));
+    }
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_QUERY_LIMIT)
+        .clamp(1, MAX_QUERY_LIMIT);
+
+    // Queries return a section of the document, so they aren't held to the read limit
+    let reader = DocumentReader::load(&deployment, project.id)
+        .await?
+        .without_read_limit();
+    let content = reader.read(&repo.path, &decoded_path).await?;
+    let hash = content_hash(&content);
+    let pointer = query.pointer;
+    // Parsing a large document is CPU-bound, so keep it off the async workers
+    let section = tokio::task::spawn_blocking(move || -> Result<_, ApiError> {
+        let document: Value = serde_json::from_str(&content)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid JSON: {}", e)))?;
+        let Some(value) = document.pointer(&pointer) else {
+            return Err(ApiError::BadRequest(format!(
+                "Nothing at '{}' in the document",
+                pointer
+            )));
+        };
+        let (value_type, length) = value_type(value);
+        let truncated = json_subtree::truncate(value, limit, MAX_QUERY_BYTES);
+        Ok((pointer, value_type, length, truncated))
+    })
+    .await
+    .map_err(|e| ApiError::Io(std::io::Error::other(e)))??;
+    let (pointer, value_type, length, truncated) = section;
+
+    Ok(ResponseJson(ApiResponse::success(
+        JsonDocumentQueryResponse {
+            relative_path: decoded_path,
+            pointer,
+            value_type,
+            length,
+            value: truncated.value,
+            truncated: truncated.truncated,
+            content_hash: hash,
+        },
+    )))
+}
@@ -0,0 +1,133 @@
+//! Documents changed most recently, dated by the commits that changed them. File
+//! modification times can't be used, since a fresh clone dates every file to the clone.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use axum::{
+    Extension,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::project::Project;
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{document_store, git::FileCommit};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{
+        document_locks, document_tags,
+        documents::{
+            DEFAULT_DOCS_BRANCH, DocumentMetadata, RepositoryQuery, project_repositories,
+            scan_project_documents,
+        },
+    },
+};
+
+/// Revision whose history dates the documents of `repo_path`: the checked out branch,
+/// or the docs branch of bare clones, which is what they list
+pub(crate) fn history_revision(repo_path: &Path) -> &'static str {
+    if document_store::is_bare_repository(repo_path) {
+        DEFAULT_DOCS_BRANCH
+    } else {
+        "HEAD"
+    }
+}
+
+const DEFAULT_RECENT_LIMIT: usize = 10;
+const MAX_RECENT_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct RecentDocumentsQuery {
+    /// Number of documents to return, 10 by default and at most 100
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RecentDocument {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub document: DocumentMetadata,
+    /// Newest commit that changed the document
+    pub last_commit: FileCommit,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RecentDocumentsResponse {
+    /// Newest first; documents that were never committed are left out
+    pub documents: Vec<RecentDocument>,
+    /// The scan hit one of the project's scan limits, so documents may be missing
+    pub truncated: bool,
+}
+
+/// The documents of the project changed most recently on the checked out branch
+pub async fn get_recent_documents(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(repository): Query<RepositoryQuery>,
+    Query(query): Query<RecentDocumentsQuery>,
+) -> Result<ResponseJson<ApiResponse<RecentDocumentsResponse>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_RECENT_LIMIT)
+        .clamp(1, MAX_RECENT_LIMIT);
+    let repositories =
+        project_repositories(&deployment, project.id, repository.repository_id).await?;
+    let (documents, truncated) =
+        scan_project_documents(&deployment, project.id, repository.repository_id, None).await?;
+
+    let mut by_repo: HashMap<Uuid, HashMap<String, DocumentMetadata>> = HashMap::new();
+    for document in documents {
+        by_repo
+            .entry(document.repository_id)
+            .or_default()
+            .insert(document.relative_path.clone(), document);
+    }
+
+    let mut recent = Vec::new();
+    for repo in &repositories {
+        let Some(mut documents) = by_repo.remove(&repo.id) else {
+            continue;
+        };
+        let paths: HashSet<String> = documents.keys().cloned().collect();
+        let changed = match deployment.git_backend().recently_changed_paths(
+            &repo.path,
+            history_revision(&repo.path),
+            &paths,
+            limit,
+        ) {
+            Ok(changed) => changed,
+            Err(e) => {
+                tracing::warn!("Failed to read history of {:?}: {}", repo.path, e);
+                continue;
+            }
+        };
+        recent.extend(changed.into_iter().filter_map(|(path, last_commit)| {
+            Some(RecentDocument {
+                document: documents.remove(&path)?,
+                last_commit,
+            })
+        }));
+    }
+    recent.sort_by(|a, b| b.last_commit.date.cmp(&a.last_commit.date));
+    recent.truncate(limit);
+
+    let mut locks = document_locks::project_locks(&deployment, project.id).await?;
+    document_locks::attach_locks(&mut locks, recent.iter_mut().map(|r| &mut r.document));
+    let tags = document_tags::project_tags(&deployment, project.id).await?;
+    document_tags::attach_tags(&tags, recent.iter_mut().map(|r| &mut r.document));
+
+    Ok(ResponseJson(ApiResponse::success(
+        RecentDocumentsResponse {
+            documents: recent,
+            truncated,
+        },
+    )))
+}
@@ -0,0 +1,107 @@
+//! Folders a project's documents live in, e.g. `docs/` and `seed_docs/` in a monorepo,
+//! so listings don't surface every README and package manifest of the repository.
+//! Without roots the whole repository holds documents.
+
+use std::path::Path;
+
+use axum::{Extension, extract::State, response::Json as ResponseJson};
+use db::models::project::Project;
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, routes::documents::validate_relative_path};
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct DocumentRoots {
+    /// Folders relative to the repository root; empty for the whole repository
+    pub roots: Vec<String>,
+}
+
+pub(crate) async fn project_document_roots(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+) -> Result<Vec<String>, ApiError> {
+    Ok(Project::find_document_roots(&deployment.db().pool, project_id).await?)
+}
+
+/// Whether `relative_path` lies inside one of `roots`; every path does if there are none
+pub(crate) fn in_document_roots(roots: &[String], relative_path: &str) -> bool {
+    let relative_path = Path::new(relative_path.trim_matches('/'));
+    roots.is_empty() || roots.iter().any(|root| relative_path.starts_with(root))
+}
+
+pub(crate) fn require_in_document_roots(
+    roots: &[String],
+    relative_path: &str,
+) -> Result<(), ApiError> {
+    if in_document_roots(roots, relative_path) {
+        return Ok(());
+    }
+    Err(ApiError::BadRequest(format!(
+        "'{}' is outside the project's document folders ({})",
+        relative_path.trim_matches('/'),
+        roots.join(", ")
+    )))
+}
+
+/// Folders to scan for documents below `path_prefix`, relative to the repository root;
+/// `""` is the whole repository. A prefix inside a root is scanned itself, otherwise the
+/// roots inside the prefix are.
+pub(crate) fn scan_folders(roots: &[String], path_prefix: Option<&str>) -> Vec<String> {
+    match path_prefix {
+        None if roots.is_empty() => vec![String::new()],
+        None => roots.to_vec(),
+        Some(prefix) if in_document_roots(roots, prefix) => vec![prefix.to_string()],
+        Some(prefix) => roots
+            .iter()
+            .filter(|root| Path::new(root).starts_with(prefix))
+            .cloned()
+            .collect(),
+    }
+}
+
+pub async fn get_document_roots(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentRoots>>, ApiError> {
+    let roots = project_document_roots(&deployment, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentRoots { roots })))
+}
+
+/// Replace the project's document folders; an empty list scans the whole repository
+pub async fn update_document_roots(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<DocumentRoots>,
+) -> Result<ResponseJson<ApiResponse<DocumentRoots>>, ApiError> {
+    let mut roots: Vec<String> = Vec::with_capacity(body.roots.len());
+    for root in body.roots {
+        let root = root.trim().trim_matches('/');
+        if root.is_empty() {
+            continue;
+        }
+        roots.push(validate_relative_path(root)?.to_string());
+    }
+    roots.sort();
+    roots.dedup();
+    // A root inside another one adds nothing, and would list its documents twice
+    let nested: Vec<bool> = roots
+        .iter()
+        .map(|root| {
+            roots
+                .iter()
+                .any(|other| other != root && Path::new(root).starts_with(other))
+        })
+        .collect();
+    let roots: Vec<String> = roots
+        .into_iter()
+        .zip(nested)
+        .filter_map(|(root, nested)| (!nested).then_some(root))
+        .collect();
+
+    Project::set_document_roots(&deployment.db().pool, project.id, &roots).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentRoots { roots })))
+}
@@ -0,0 +1,248 @@
+use std::{path::Path, time::Duration};
+
+use axum::{
+    Extension, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::Utc;
+use db::models::{document_snapshot::DocumentSnapshot, project::Project};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::git::PathChange;
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::{response::ApiResponse, timezone::parse_time_of_day};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{
+        documents::{DEFAULT_DOCS_BRANCH, allowed_file_type, project_extensions},
+        paths, project_usage, timezones,
+    },
+};
+
+/// How often the scheduler wakes up to see whether a snapshot is due
+const SCHEDULER_TICK: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Deserialize)]
+pub struct SnapshotDiffQuery {
+    pub from: Uuid,
+    pub to: Uuid,
+}
+
+/// Documents that changed between two snapshots of the same repository
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentSnapshotDiff {
+    pub from: DocumentSnapshot,
+    pub to: DocumentSnapshot,
+    pub changes: Vec<PathChange>,
+}
+
+/// Record the docs branch commit of each of the project's repositories and apply the
+/// configured retention. With `skip_unchanged`, repositories whose latest snapshot
+/// already points at the current commit are left alone.
+async fn take_snapshots(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    skip_unchanged: bool,
+) -> Result<Vec<DocumentSnapshot>, ApiError> {
+    let pool = &deployment.db().pool;
+    let retention = deployment
+        .config()
+        .read()
+        .await
+        .document_snapshots
+        .retention;
+    let repositories = deployment
+        .project()
+        .get_repositories(pool, project_id)
+        .await?;
+
+    let mut snapshots = Vec::new();
+    for repo in repositories {
+        let commit_sha = match deployment
+            .git_backend()
+            .resolve_revision(&repo.path, DEFAULT_DOCS_BRANCH)
+        {
+            Ok(commit_sha) => commit_sha,
+            Err(e) => {
+                tracing::debug!(
+                    "No '{}' branch to snapshot in {:?}: {}",
+                    DEFAULT_DOCS_BRANCH,
+                    repo.path,
+                    e
+                );
+                continue;
+            }
+        };
+        if skip_unchanged
+            && DocumentSnapshot::find_latest_for_repo(pool, project_id, repo.id)
+                .await?
+                .is_some_and(|latest| latest.commit_sha == commit_sha)
+        {
+            continue;
+        }
+
+        let snapshot =
+            DocumentSnapshot::create(pool, project_id, repo.id, DEFAULT_DOCS_BRANCH, &commit_sha)
+                .await?;
+        DocumentSnapshot::prune(pool, project_id, repo.id, i64::from(retention.max(1))).await?;
+        snapshots.push(snapshot);
+    }
+    Ok(snapshots)
+}
+
+pub async fn list_snapshots(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DocumentSnapshot>>>, ApiError> {
+    let snapshots = DocumentSnapshot::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(snapshots)))
+}
+
+/// Snapshot every repository now, even if nothing changed since the last snapshot
+pub async fn create_snapshots(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DocumentSnapshot>>>, ApiError> {
+    let snapshots = take_snapshots(&deployment, project.id, false).await?;
+    Ok(ResponseJson(ApiResponse::success(snapshots)))
+}
+
+async fn find_project_snapshot(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    id: Uuid,
+) -> Result<DocumentSnapshot, ApiError> {
+    DocumentSnapshot::find_by_id(pool, id)
+        .await?
+        .filter(|snapshot| snapshot.project_id == project_id)
+        .ok_or_else(|| ApiError::BadRequest(format!("Snapshot {} not found", id)))
+}
+
+pub async fn diff_snapshots(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SnapshotDiffQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentSnapshotDiff>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let from = find_project_snapshot(pool, project.id, query.from).await?;
+    let to = find_project_snapshot(pool, project.id, query.to).await?;
+    if from.repo_id != to.repo_id {
+        return Err(ApiError::BadRequest(
+            "Snapshots belong to different repositories".to_string(),
+        ));
+    }
+
+    let repo = deployment
+        .project()
+        .get_repositories(pool, project.id)
+        .await?
+        .into_iter()
+        .find(|repo| repo.id == from.repo_id)
+        .ok_or_else(|| ApiError::BadRequest("The snapshot's repository was removed".to_string()))?;
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let changes = deployment
+        .git_backend()
+        .changed_paths_between(&repo.path, &from.commit_sha, &to.commit_sha)?
+        .into_iter()
+        .filter(|change| allowed_file_type(Path::new(&change.path), &extensions).is_some())
+        .collect();
+
+    Ok(ResponseJson(ApiResponse::success(DocumentSnapshotDiff {
+        from,
+        to,
+        changes,
+    })))
+}
+
+/// Periodically snapshot the docs branch of every project when enabled in config
+pub fn spawn_scheduler(deployment: DeploymentImpl) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SCHEDULER_TICK);
+
+        loop {
+            ticker.tick().await;
+
+            let settings = deployment.config().read().await.document_snapshots.clone();
+            if !settings.enabled {
+                continue;
+            }
+            let interval = chrono::Duration::hours(i64::from(settings.interval_hours.max(1)));
+            let run_at = settings
+                .run_at
+                .as_deref()
+                .and_then(|run_at| parse_time_of_day(run_at).ok());
+
+            let projects = match Project::find_all(&deployment.db().pool).await {
+                Ok(projects) => projects,
+                Err(e) => {
+                    tracing::error!("Failed to load projects for document snapshots: {}", e);
+                    continue;
+                }
+            };
+            for project in projects {
+                let pool = &deployment.db().pool;
+                let latest = DocumentSnapshot::find_by_project_id(pool, project.id)
+                    .await
+                    .ok()
+                    .and_then(|snapshots| snapshots.into_iter().next());
+                let due = match (latest, run_at) {
+                    (None, _) => true,
+                    (Some(latest), None) => Utc::now() - latest.created_at >= interval,
+                    // Daily at a local time of the project
+                    (Some(latest), Some(run_at)) => {
+                        match timezones::project_timezone(&deployment, project.id).await {
+                            Ok(timezone) => {
+                                timezone.next_daily(latest.created_at, run_at) <= Utc::now()
+                            }
+                            Err(e) => {
+                                tracing::error!(
+                                    "Failed to load the timezone of project {}: {}",
+                                    project.id,
+                                    e
+                                );
+                                false
+                            }
+                        }
+                    }
+                };
+                if !due {
+                    continue;
+                }
+                let _job = project_usage::background_job(&deployment, project.id).await;
+                match take_snapshots(&deployment, project.id, true).await {
+                    Ok(snapshots) if !snapshots.is_empty() => tracing::info!(
+                        "Took {} document snapshot(s) for project {}",
+                        snapshots.len(),
+                        project.id
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::error!(
+                        "Document snapshot failed for project {}: {}",
+                        project.id,
+                        e
+                    ),
+                }
+            }
+        }
+    })
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route("/", get(list_snapshots).post(create_snapshots))
+        .route("/diff", get(diff_snapshots))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest(paths::PROJECT_DOCUMENT_SNAPSHOTS, project_router)
+}
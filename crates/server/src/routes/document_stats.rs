@@ -0,0 +1,156 @@
+//! Size and last change of documents, for dashboards of the health of a project's
+//! documentation
+
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    Extension,
+    extract::{Path as AxumPath, Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::project::Project;
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::{
+    git::FileCommit,
+    markdown_html::{self, TextStats},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{
+        document_recent::history_revision,
+        documents::{
+            DocumentFileType, DocumentReader, RepositoryQuery, document_file_type,
+            find_document_file, project_repositories, scan_project_documents,
+            validate_relative_path,
+        },
+    },
+};
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentStats {
+    pub repository_id: Uuid,
+    pub relative_path: String,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub text: TextStats,
+    /// Newest commit that changed the document; `None` if it was never committed
+    pub last_commit: Option<FileCommit>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentStatsOverview {
+    /// Sorted by path
+    pub documents: Vec<DocumentStats>,
+    /// The scan hit one of the project's scan limits, so documents may be missing
+    pub truncated: bool,
+}
+
+fn text_stats(file_type: &DocumentFileType, content: &str) -> TextStats {
+    match file_type {
+        DocumentFileType::Markdown => markdown_html::text_stats(content),
+        _ => TextStats::of_plain_text(content),
+    }
+}
+
+/// Stats of every document of the project
+pub async fn get_document_stats_overview(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(repository): Query<RepositoryQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentStatsOverview>>, ApiError> {
+    let repositories =
+        project_repositories(&deployment, project.id, repository.repository_id).await?;
+    let (documents, truncated) =
+        scan_project_documents(&deployment, project.id, repository.repository_id, None).await?;
+
+    // One walk of each repository's history finds the last commit of all its documents
+    let mut last_commits: HashMap<(Uuid, String), FileCommit> = HashMap::new();
+    for repo in &repositories {
+        let paths: HashSet<String> = documents
+            .iter()
+            .filter(|document| document.repository_id == repo.id)
+            .map(|document| document.relative_path.clone())
+            .collect();
+        if paths.is_empty() {
+            continue;
+        }
+        match deployment.git_backend().recently_changed_paths(
+            &repo.path,
+            history_revision(&repo.path),
+            &paths,
+            paths.len(),
+        ) {
+            Ok(changed) => last_commits.extend(
+                changed
+                    .into_iter()
+                    .map(|(path, commit)| ((repo.id, path), commit)),
+            ),
+            Err(e) => tracing::warn!("Failed to read history of {:?}: {}", repo.path, e),
+        }
+    }
+
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let mut stats = Vec::with_capacity(documents.len());
+    for document in documents {
+        let content = match reader.read_listed(&document).await {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {}", document.absolute_path, e);
+                continue;
+            }
+        };
+        let key = (document.repository_id, document.relative_path);
+        stats.push(DocumentStats {
+            text: text_stats(&document.file_type, &content),
+            last_commit: last_commits.remove(&key),
+            repository_id: key.0,
+            relative_path: key.1,
+        });
+    }
+
+    Ok(ResponseJson(ApiResponse::success(DocumentStatsOverview {
+        documents: stats,
+        truncated,
+    })))
+}
+
+/// Stats of a single document
+pub async fn get_document_stats(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentStats>>, ApiError> {
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?;
+    let decoded_path = validate_relative_path(&decoded_path)?.to_string();
+    let (repo, file_path) = find_document_file(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &decoded_path,
+    )
+    .await?;
+
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let content = reader.read(&repo.path, &decoded_path).await?;
+    let file_type = document_file_type(&file_path).unwrap_or(DocumentFileType::Text);
+    let last_commit = deployment.git_backend().last_commit_touching(
+        &repo.path,
+        history_revision(&repo.path),
+        &decoded_path,
+    )?;
+
+    Ok(ResponseJson(ApiResponse::success(DocumentStats {
+        repository_id: repo.id,
+        relative_path: decoded_path,
+        text: text_stats(&file_type, &content),
+        last_commit,
+    })))
+}
@@ -0,0 +1,348 @@
+//! Docs drift of every project against origin/main in one call. A background fetch
+//! keeps the recorded drift fresh, so the overview doesn't fetch every repository
+//! while the caller waits. Fetches go through a circuit breaker per remote host, so a
+//! host that is down costs one timeout per probe instead of one per request.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+use axum::{Router, extract::State, response::Json as ResponseJson, routing::get};
+use chrono::{DateTime, Utc};
+use db::models::{document_sync_state::DocumentSyncState, project::Project};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::remote_breaker::{
+    DEFAULT_FAILURE_THRESHOLD, RemoteCircuitBreaker, remote_host,
+};
+use ts_rs::TS;
+use utils::{response::ApiResponse, timezone::LocalizedTime};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{documents::DEFAULT_DOCS_BRANCH, paths, project_usage, timezones},
+};
+
+/// How often every project's primary repository is fetched from origin
+const FETCH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// How often a host that stopped answering is tried again
+const PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Health of the hosts documents are fetched from, shared by every fetch
+static REMOTE_BREAKER: LazyLock<RemoteCircuitBreaker> =
+    LazyLock::new(RemoteCircuitBreaker::default);
+
+/// Docs drift of one project, as of its last fetch or sync
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectDocumentSyncStatus {
+    pub project_id: Uuid,
+    pub project_name: String,
+    /// Branch the primary repository was on at the last check, `None` if it hasn't been
+    /// checked yet
+    pub current_branch: Option<String>,
+    pub commits_ahead: usize,
+    pub commits_behind: usize,
+    pub fetched_at: Option<DateTime<Utc>>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+    /// `fetched_at` in the project's timezone
+    pub fetched_at_local: Option<LocalizedTime>,
+    /// `last_synced_at` in the project's timezone
+    pub last_synced_at_local: Option<LocalizedTime>,
+    /// Error of the last fetch or sync, `None` if it succeeded
+    pub last_error: Option<String>,
+    /// Whether origin's host is unreachable right now, so the drift is as of the last
+    /// successful fetch
+    pub degraded: bool,
+}
+
+/// Remember the outcome of a sync of the project's documents. Failing to record it is
+/// logged, not returned, so it can't hide the sync's own result.
+pub(crate) async fn record_sync(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    error: Option<&ApiError>,
+) {
+    let error = error.map(ToString::to_string);
+    if let Err(e) =
+        DocumentSyncState::record_sync(&deployment.db().pool, project_id, error.as_deref()).await
+    {
+        tracing::warn!(
+            "Failed to record document sync of project {}: {}",
+            project_id,
+            e
+        );
+    }
+}
+
+/// Remember the drift seen after fetching the project's documents from origin; `fetch`
+/// is `None` when nothing was fetched because the repository is off main
+pub(crate) async fn record_fetch(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    current_branch: &str,
+    (ahead, behind): (usize, usize),
+    fetch: Option<&OriginFetch>,
+) {
+    let recorded = DocumentSyncState::record_fetch(
+        &deployment.db().pool,
+        project_id,
+        current_branch,
+        fetch.and_then(|fetch| fetch.host.as_deref()),
+        i64::try_from(ahead).unwrap_or(i64::MAX),
+        i64::try_from(behind).unwrap_or(i64::MAX),
+        fetch.and_then(|fetch| fetch.error.as_deref()),
+    )
+    .await;
+    if let Err(e) = recorded {
+        tracing::warn!(
+            "Failed to record document fetch of project {}: {}",
+            project_id,
+            e
+        );
+    }
+}
+
+/// Outcome of fetching the docs branch from origin
+pub(crate) struct OriginFetch {
+    /// Host of the origin remote, `None` if it can't be read
+    pub host: Option<String>,
+    /// Why nothing new was fetched, `None` if the fetch succeeded
+    pub error: Option<String>,
+    /// Whether the host is known to be down, so the fetch wasn't tried and origin/main is
+    /// as of the last successful fetch
+    pub degraded: bool,
+}
+
+/// Fetch the docs branch of `repo_path` from origin, unless origin's host failed
+/// repeatedly; then the fetch is skipped until the background probe sees the host
+/// recover. `probe` fetches regardless, to check whether the host recovered.
+pub(crate) fn fetch_origin(
+    deployment: &DeploymentImpl,
+    repo_path: &Path,
+    probe: bool,
+) -> OriginFetch {
+    let git = deployment.git();
+    let host = git
+        .get_remote_url(repo_path, "origin")
+        .ok()
+        .map(|url| remote_host(&url));
+    if !probe
+        && let Some(host) = &host
+        && REMOTE_BREAKER.is_open(host)
+    {
+        let cause = REMOTE_BREAKER.open_error(host).unwrap_or_default();
+        return OriginFetch {
+            error: Some(format!(
+                "{} is unreachable, showing the status of the last fetch: {}",
+                host, cause
+            )),
+            host: Some(host.clone()),
+            degraded: true,
+        };
+    }
+
+    let error = match git.fetch(repo_path, "origin", DEFAULT_DOCS_BRANCH) {
+        Ok(()) => {
+            if let Some(host) = &host {
+                if probe && REMOTE_BREAKER.is_open(host) {
+                    tracing::info!("{} is reachable again, resuming fetches", host);
+                }
+                REMOTE_BREAKER.record_success(host);
+            }
+            None
+        }
+        Err(e) => {
+            if let Some(host) = &host
+                && REMOTE_BREAKER.record_failure(host, &e.to_string())
+            {
+                tracing::warn!(
+                    "Fetching from {} failed {} times in a row; pausing fetches until it \
+                     recovers",
+                    host,
+                    DEFAULT_FAILURE_THRESHOLD
+                );
+            }
+            Some(format!("Failed to fetch from origin: {}", e))
+        }
+    };
+    OriginFetch {
+        host,
+        error,
+        degraded: false,
+    }
+}
+
+/// Fetch the primary repository of a project and record how far main is from
+/// origin/main. Repositories off main are recorded without fetching. Returns the host of
+/// origin, if it was fetched from.
+async fn fetch_project(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    probe: bool,
+) -> Result<Option<String>, ApiError> {
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project_id)
+        .await?;
+    let Some(repo) = repositories.first() else {
+        return Ok(None);
+    };
+    let repo_path = PathBuf::from(&repo.path);
+    if !repo_path.is_dir() {
+        return Ok(None);
+    }
+
+    let current_branch = deployment.git_backend().get_current_branch(&repo_path)?;
+    if current_branch != DEFAULT_DOCS_BRANCH {
+        record_fetch(deployment, project_id, &current_branch, (0, 0), None).await;
+        return Ok(None);
+    }
+    let fetch = fetch_origin(deployment, &repo_path, probe);
+    let counts = deployment
+        .git()
+        .get_ahead_behind(&repo_path, "main", "origin/main")
+        .unwrap_or((0, 0));
+    record_fetch(
+        deployment,
+        project_id,
+        &current_branch,
+        counts,
+        Some(&fetch),
+    )
+    .await;
+    Ok(fetch.host)
+}
+
+/// Fetch one project of each host whose breaker is open, to find out whether it is back
+async fn probe_unreachable_hosts(deployment: &DeploymentImpl) {
+    let mut unprobed: HashSet<String> = REMOTE_BREAKER.open_hosts().into_iter().collect();
+    if unprobed.is_empty() {
+        return;
+    }
+    let projects = match Project::find_all(&deployment.db().pool).await {
+        Ok(projects) => projects,
+        Err(e) => {
+            tracing::error!("Failed to load projects for probing remote hosts: {}", e);
+            return;
+        }
+    };
+    let pool = &deployment.db().pool;
+    for project in projects.into_iter().filter(|project| !project.is_template) {
+        if unprobed.is_empty() {
+            break;
+        }
+        // Only projects last seen on an unreachable host are worth a probe
+        let host = DocumentSyncState::find_remote_host(pool, project.id)
+            .await
+            .ok()
+            .flatten();
+        if !host.is_some_and(|host| unprobed.contains(&host)) {
+            continue;
+        }
+        match fetch_project(deployment, project.id, true).await {
+            Ok(Some(host)) => {
+                unprobed.remove(&host);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to probe documents of project {}: {}", project.id, e),
+        }
+    }
+}
+
+/// Periodically fetch every project's documents so the sync status overview stays fresh,
+/// and probe unreachable hosts more often so fetching resumes soon after they recover
+pub fn spawn_scheduler(deployment: DeploymentImpl) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(PROBE_INTERVAL);
+        let mut last_fetch: Option<Instant> = None;
+
+        loop {
+            ticker.tick().await;
+
+            if last_fetch.is_some_and(|last_fetch| last_fetch.elapsed() < FETCH_INTERVAL) {
+                probe_unreachable_hosts(&deployment).await;
+                continue;
+            }
+            last_fetch = Some(Instant::now());
+            let projects = match Project::find_all(&deployment.db().pool).await {
+                Ok(projects) => projects,
+                Err(e) => {
+                    tracing::error!("Failed to load projects for document fetches: {}", e);
+                    continue;
+                }
+            };
+            for project in projects.into_iter().filter(|project| !project.is_template) {
+                let _job = project_usage::background_job(&deployment, project.id).await;
+                if let Err(e) = fetch_project(&deployment, project.id, false).await {
+                    tracing::warn!("Failed to fetch documents of project {}: {}", project.id, e);
+                }
+            }
+        }
+    })
+}
+
+fn count(value: i64) -> usize {
+    usize::try_from(value).unwrap_or(0)
+}
+
+/// Docs drift of every project, furthest behind first, as of the last background fetch
+/// or sync
+pub async fn get_all_sync_status(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectDocumentSyncStatus>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut states: HashMap<Uuid, DocumentSyncState> = DocumentSyncState::find_all(pool)
+        .await?
+        .into_iter()
+        .map(|state| (state.project_id, state))
+        .collect();
+
+    let mut statuses = Vec::new();
+    for project in Project::find_all(pool).await? {
+        if project.is_template {
+            continue;
+        }
+        let timezone = timezones::project_timezone(&deployment, project.id).await?;
+        let state = states.remove(&project.id);
+        let degraded = state
+            .as_ref()
+            .and_then(|state| state.remote_host.as_deref())
+            .is_some_and(|host| REMOTE_BREAKER.is_open(host));
+        let fetched_at = state.as_ref().and_then(|state| state.fetched_at);
+        let last_synced_at = state.as_ref().and_then(|state| state.last_synced_at);
+        statuses.push(ProjectDocumentSyncStatus {
+            project_id: project.id,
+            project_name: project.name,
+            current_branch: state
+                .as_ref()
+                .and_then(|state| state.current_branch.clone()),
+            commits_ahead: state.as_ref().map_or(0, |state| count(state.commits_ahead)),
+            commits_behind: state
+                .as_ref()
+                .map_or(0, |state| count(state.commits_behind)),
+            fetched_at,
+            last_synced_at,
+            fetched_at_local: fetched_at.map(|at| timezone.localize(at)),
+            last_synced_at_local: last_synced_at.map(|at| timezone.localize(at)),
+            last_error: state.and_then(|state| state.last_error),
+            degraded,
+        });
+    }
+    statuses.sort_by(|a, b| {
+        b.commits_behind.cmp(&a.commits_behind).then_with(|| {
+            a.project_name
+                .to_lowercase()
+                .cmp(&b.project_name.to_lowercase())
+        })
+    });
+    Ok(ResponseJson(ApiResponse::success(statuses)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route(paths::DOCUMENTS_SYNC_STATUS, get(get_all_sync_status))
+}
@@ -0,0 +1,288 @@
+//! Tags of documents, e.g. `draft`, `adr` or `runbook`, kept outside git so tagging a
+//! document doesn't change it. Listed documents carry their tags and can be filtered by
+//! them.
+
+use std::collections::HashMap;
+
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    document_tag::{DocumentTag, DocumentTagCount},
+    project::Project,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{
+        documents::{
+            DocumentMetadata, DocumentTreeNode, find_document_file, validate_relative_path,
+        },
+        paths,
+    },
+};
+
+/// Longest tag that is stored
+const MAX_TAG_CHARS: usize = 50;
+/// Most tags a document can have
+const MAX_TAGS_PER_DOCUMENT: usize = 20;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetDocumentTagsRequest {
+    pub relative_path: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub repository_id: Option<Uuid>,
+    /// Every tag the document should have; tags not listed are removed
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct DocumentTagRequest {
+    pub relative_path: String,
+    #[serde(default)]
+    #[ts(optional)]
+    pub repository_id: Option<Uuid>,
+    pub tag: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentTags {
+    pub repository_id: Uuid,
+    pub relative_path: String,
+    /// In alphabetical order
+    pub tags: Vec<String>,
+}
+
+/// Tags are compared case-insensitively, so they are stored in lowercase. Letters,
+/// digits, `-`, `_`, `.` and `/` are allowed, e.g. `adr`, `status/draft` or `v1.2`.
+fn normalize_tag(tag: &str) -> Result<String, ApiError> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() {
+        return Err(ApiError::BadRequest("Tags can't be empty".to_string()));
+    }
+    if tag.chars().count() > MAX_TAG_CHARS {
+        return Err(ApiError::BadRequest(format!(
+            "Tags can be at most {} characters",
+            MAX_TAG_CHARS
+        )));
+    }
+    if !tag
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Tag '{}' may only contain letters, digits, '-', '_', '.' and '/'",
+            tag
+        )));
+    }
+    Ok(tag)
+}
+
+/// Tags of a `?tags=` filter, which lists them separated by commas
+pub(crate) fn parse_tag_filter(tags: Option<&str>) -> Result<Vec<String>, ApiError> {
+    tags.unwrap_or_default()
+        .split(',')
+        .filter(|tag| !tag.trim().is_empty())
+        .map(normalize_tag)
+        .collect()
+}
+
+/// Tags of every tagged document of a project, by repository and path
+pub(crate) async fn project_tags(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+) -> Result<HashMap<(Uuid, String), Vec<String>>, ApiError> {
+    let mut tags: HashMap<(Uuid, String), Vec<String>> = HashMap::new();
+    for tag in DocumentTag::find_by_project_id(&deployment.db().pool, project_id).await? {
+        tags.entry((tag.repo_id, tag.relative_path))
+            .or_default()
+            .push(tag.tag);
+    }
+    Ok(tags)
+}
+
+/// Whether `document` has every tag of `filter`, according to [`project_tags`]
+pub(crate) fn has_tags(
+    tags: &HashMap<(Uuid, String), Vec<String>>,
+    document: &DocumentMetadata,
+    filter: &[String],
+) -> bool {
+    let key = (document.repository_id, document.relative_path.clone());
+    tags.get(&key)
+        .is_some_and(|document_tags| filter.iter().all(|tag| document_tags.contains(tag)))
+}
+
+/// Fill in `tags` of each document from [`project_tags`]
+pub(crate) fn attach_tags<'a>(
+    tags: &HashMap<(Uuid, String), Vec<String>>,
+    documents: impl IntoIterator<Item = &'a mut DocumentMetadata>,
+) {
+    if tags.is_empty() {
+        return;
+    }
+    for document in documents {
+        let key = (document.repository_id, document.relative_path.clone());
+        document.tags = tags.get(&key).cloned().unwrap_or_default();
+    }
+}
+
+pub(crate) fn attach_tree_tags(
+    tags: &HashMap<(Uuid, String), Vec<String>>,
+    nodes: &mut [DocumentTreeNode],
+) {
+    for node in nodes {
+        match node {
+            DocumentTreeNode::Folder { children, .. } => attach_tree_tags(tags, children),
+            DocumentTreeNode::Document(document) => attach_tags(tags, std::iter::once(document)),
+        }
+    }
+}
+
+/// Tags used in the project with their number of documents
+pub async fn list_tags(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DocumentTagCount>>>, ApiError> {
+    let counts = DocumentTag::count_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(counts)))
+}
+
+async fn document_tags(
+    deployment: &DeploymentImpl,
+    repo_id: Uuid,
+    relative_path: &str,
+) -> Result<DocumentTags, ApiError> {
+    Ok(DocumentTags {
+        repository_id: repo_id,
+        relative_path: relative_path.to_string(),
+        tags: DocumentTag::find_tags(&deployment.db().pool, repo_id, relative_path).await?,
+    })
+}
+
+/// Replace the tags of a document
+pub async fn set_tags(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetDocumentTagsRequest>,
+) -> Result<ResponseJson<ApiResponse<DocumentTags>>, ApiError> {
+    let relative_path = validate_relative_path(&payload.relative_path)?;
+    let mut tags = payload
+        .tags
+        .iter()
+        .map(|tag| normalize_tag(tag))
+        .collect::<Result<Vec<_>, _>>()?;
+    tags.sort();
+    tags.dedup();
+    if tags.len() > MAX_TAGS_PER_DOCUMENT {
+        return Err(ApiError::BadRequest(format!(
+            "Documents can have at most {} tags",
+            MAX_TAGS_PER_DOCUMENT
+        )));
+    }
+    let (repo, _) = find_document_file(
+        &deployment,
+        project.id,
+        payload.repository_id,
+        relative_path,
+    )
+    .await?;
+
+    DocumentTag::set(
+        &deployment.db().pool,
+        project.id,
+        repo.id,
+        relative_path,
+        &tags,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "document_tags_set",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "tag_count": tags.len(),
+            }),
+        )
+        .await;
+
+    let tags = document_tags(&deployment, repo.id, relative_path).await?;
+    Ok(ResponseJson(ApiResponse::success(tags)))
+}
+
+/// Add one tag to a document
+pub async fn add_tag(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DocumentTagRequest>,
+) -> Result<ResponseJson<ApiResponse<DocumentTags>>, ApiError> {
+    let relative_path = validate_relative_path(&payload.relative_path)?;
+    let tag = normalize_tag(&payload.tag)?;
+    let (repo, _) = find_document_file(
+        &deployment,
+        project.id,
+        payload.repository_id,
+        relative_path,
+    )
+    .await?;
+
+    let pool = &deployment.db().pool;
+    let existing = DocumentTag::find_tags(pool, repo.id, relative_path).await?;
+    if !existing.contains(&tag) && existing.len() >= MAX_TAGS_PER_DOCUMENT {
+        return Err(ApiError::BadRequest(format!(
+            "Documents can have at most {} tags",
+            MAX_TAGS_PER_DOCUMENT
+        )));
+    }
+    DocumentTag::add(pool, project.id, repo.id, relative_path, &tag).await?;
+
+    let tags = document_tags(&deployment, repo.id, relative_path).await?;
+    Ok(ResponseJson(ApiResponse::success(tags)))
+}
+
+/// Remove one tag from a document; removing a tag it doesn't have does nothing
+pub async fn remove_tag(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DocumentTagRequest>,
+) -> Result<ResponseJson<ApiResponse<DocumentTags>>, ApiError> {
+    let relative_path = validate_relative_path(&payload.relative_path)?;
+    let tag = normalize_tag(&payload.tag)?;
+    let (repo, _) = find_document_file(
+        &deployment,
+        project.id,
+        payload.repository_id,
+        relative_path,
+    )
+    .await?;
+
+    DocumentTag::remove(&deployment.db().pool, repo.id, relative_path, &tag).await?;
+
+    let tags = document_tags(&deployment, repo.id, relative_path).await?;
+    Ok(ResponseJson(ApiResponse::success(tags)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let tags_router = Router::new()
+        .route("/", get(list_tags).put(set_tags))
+        .route("/add", post(add_tag))
+        .route("/remove", post(remove_tag))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest(paths::PROJECT_DOCUMENT_TAGS, tags_router)
+}
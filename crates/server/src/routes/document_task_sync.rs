@@ -0,0 +1,523 @@
+//! Opt-in sync between task list items (`- [ ] item`) of planning documents and board
+//! tasks. A document takes part when its front matter has `task_sync: true`.
+//!
+//! Items link to their task with a `/tasks/<id>` link on the item's line. Checking an
+//! item completes its task when the document is saved, and completing or reopening a
+//! task on the board checks or unchecks its item. Status changes made elsewhere, e.g.
+//! by merging a pull request, reach the document on the next explicit sync.
+
+use std::{collections::HashSet, path::PathBuf};
+
+use axum::{
+    Extension,
+    extract::{Path as AxumPath, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json as ResponseJson, Response},
+};
+use db::models::{
+    document_lock::DocumentLock,
+    document_task_link::DocumentTaskLink,
+    project::Project,
+    repo::Repo,
+    task::{CreateTask, Task, TaskStatus},
+    task_redirect::TaskRedirect,
+    wip_limit::{WipLimit, WipLimitViolation},
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::{
+    front_matter,
+    response::{ApiResponse, ApiWarning},
+    task_list,
+};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{
+        document_approvals, document_branches,
+        document_commits::DocumentCommitter,
+        document_encryption,
+        documents::{
+            DocumentFileType, DocumentReadOnlyViolation, DocumentReader, DocumentUpdateConflict,
+            RepositoryQuery, auto_commit_warning, content_hash, document_file_type, document_link,
+            find_document_file, find_read_only_violation, validate_relative_path,
+        },
+    },
+};
+
+/// Front matter field that opts a document into task sync
+const TASK_SYNC_FIELD: &str = "task_sync";
+
+/// Titles of tasks created from items are cut off after this many characters
+const MAX_ITEM_TITLE_CHARS: usize = 80;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SyncDocumentTasksRequest {
+    /// Also create tasks for items that don't link to one yet
+    #[serde(default)]
+    pub create_tasks: bool,
+    /// `content_hash` of the version the client shows; the sync is refused if it changed
+    #[serde(default)]
+    #[ts(optional)]
+    pub expected_hash: Option<String>,
+    /// Name the document was locked with, if any
+    #[serde(default)]
+    #[ts(optional)]
+    pub lock_holder: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SyncDocumentTasksResponse {
+    /// Tasks created for items without one
+    pub created: Vec<Task>,
+    /// Tasks completed or reopened because their item was checked or unchecked
+    pub updated: Vec<Task>,
+    /// Items checked or unchecked because their task changed on the board
+    pub checkboxes_changed: u32,
+    pub committed: bool,
+    pub content_hash: String,
+}
+
+/// Whether front matter opts its document into task sync
+pub(crate) fn task_sync_enabled(front_matter: Option<&Map<String, Value>>) -> bool {
+    front_matter.and_then(|fields| fields.get(TASK_SYNC_FIELD)) == Some(&Value::Bool(true))
+}
+
+/// Link appended to an item, pointing at its task
+pub(crate) fn task_link(project_id: Uuid, task_id: Uuid) -> String {
+    format!(" ([task](/projects/{}/tasks/{}))", project_id, task_id)
+}
+
+fn item_status(checked: bool) -> TaskStatus {
+    if checked {
+        TaskStatus::Done
+    } else {
+        TaskStatus::Todo
+    }
+}
+
+/// Warning for an item whose task would move into a column that is at its WIP limit.
+/// The item is left as it is and tried again on the next sync.
+fn wip_limit_warning(
+    relative_path: &str,
+    line: usize,
+    violation: &WipLimitViolation,
+) -> ApiWarning {
+    ApiWarning::new(
+        "wip_limit_reached",
+        format!(
+            "The task of line {} in {} was not moved: the {} column is at its limit of {} tasks",
+            line, relative_path, violation.status, violation.max_tasks
+        ),
+    )
+}
+
+#[derive(Default)]
+struct Reconciled {
+    content: String,
+    created: Vec<Task>,
+    updated: Vec<Task>,
+    checkboxes_changed: u32,
+    warnings: Vec<ApiWarning>,
+}
+
+/// Bring the task list of a document and its tasks in line. Where an item and its task
+/// disagree, whichever changed since the last sync wins, the document if both did.
+/// Without `full` only the document side is applied and `content` stays as it is.
+async fn reconcile(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repo: &Repo,
+    relative_path: &str,
+    content: &str,
+    full: bool,
+    create_tasks: bool,
+) -> Result<Reconciled, ApiError> {
+    let pool = &deployment.db().pool;
+    let last_synced = DocumentTaskLink::find_by_document(pool, repo.id, relative_path).await?;
+    let mut result = Reconciled {
+        content: content.to_string(),
+        ..Default::default()
+    };
+    let mut seen = HashSet::new();
+
+    for item in task_list::parse(content) {
+        let Some(task_id) = item.task_id else {
+            if !create_tasks || item.text.is_empty() {
+                continue;
+            }
+            let title = item.text.chars().take(MAX_ITEM_TITLE_CHARS).collect();
+            let description = format!(
+                "From [{}, line {}]({})",
+                relative_path,
+                item.line,
+                document_link(
+                    project_id,
+                    relative_path,
+                    item.line as u32,
+                    item.line as u32
+                )
+            );
+            let status = item_status(item.checked);
+            if status != TaskStatus::default()
+                && let Some(violation) = WipLimit::check_move(pool, project_id, &status).await?
+            {
+                result
+                    .warnings
+                    .push(wip_limit_warning(relative_path, item.line, &violation));
+                continue;
+            }
+            let mut create =
+                CreateTask::from_title_description(project_id, title, Some(description));
+            create.status = Some(status);
+            let task = Task::create(pool, &create, Uuid::new_v4()).await?;
+            deployment
+                .track_if_analytics_allowed(
+                    "task_created",
+                    serde_json::json!({
+                        "task_id": task.id.to_string(),
+                        "project_id": project_id,
+                        "has_description": true,
+                        "source": "document_task_list",
+                    }),
+                )
+                .await;
+            if let Some(updated) = task_list::append_to_item(
+                &result.content,
+                item.line,
+                &task_link(project_id, task.id),
+            ) {
+                result.content = updated;
+            }
+            DocumentTaskLink::upsert(
+                pool,
+                task.id,
+                project_id,
+                repo.id,
+                relative_path,
+                item.checked,
+            )
+            .await?;
+            seen.insert(task.id);
+            result.created.push(task);
+            continue;
+        };
+
+        // Links to deleted tasks or other projects are left alone; links to merged
+        // tasks drive the task they were merged into
+        let Some(task) = Task::find_by_id_or_redirect(pool, task_id).await? else {
+            continue;
+        };
+        if task.project_id != project_id {
+            continue;
+        }
+        seen.insert(task.id);
+
+        let done = task.status == TaskStatus::Done;
+        let last = last_synced
+            .iter()
+            .find(|link| link.task_id == task.id)
+            .map(|link| link.checked);
+        let checked = if item.checked == done {
+            done
+        } else if last != Some(item.checked) {
+            let status = item_status(item.checked);
+            if let Some(violation) = WipLimit::check_move(pool, project_id, &status).await? {
+                result
+                    .warnings
+                    .push(wip_limit_warning(relative_path, item.line, &violation));
+                continue;
+            }
+            Task::update_status(pool, task.id, status).await?;
+            if let Some(task) = Task::find_by_id(pool, task.id).await? {
+                result.updated.push(task);
+            }
+            item.checked
+        } else if full {
+            if let Some(updated) = task_list::set_checked(&result.content, item.line, done) {
+                result.content = updated;
+                result.checkboxes_changed += 1;
+            }
+            done
+        } else {
+            continue;
+        };
+        DocumentTaskLink::upsert(pool, task.id, project_id, repo.id, relative_path, checked)
+            .await?;
+    }
+
+    // Items that were removed from the document no longer drive their task
+    for link in last_synced {
+        if !seen.contains(&link.task_id) {
+            DocumentTaskLink::delete_by_task_id(pool, link.task_id).await?;
+        }
+    }
+    Ok(result)
+}
+
+/// Complete or reopen the tasks of items whose checkbox changed in a save of `content`.
+/// Returns warnings for tasks that were left in place by a WIP limit.
+pub(crate) async fn apply_saved_checkboxes(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repo: &Repo,
+    relative_path: &str,
+    content: &str,
+) -> Result<Vec<ApiWarning>, ApiError> {
+    if !task_sync_enabled(front_matter::parse(content).as_ref()) {
+        return Ok(Vec::new());
+    }
+    let reconciled = reconcile(
+        deployment,
+        project_id,
+        repo,
+        relative_path,
+        content,
+        false,
+        false,
+    )
+    .await?;
+    Ok(reconciled.warnings)
+}
+
+/// Check or uncheck the item linked to `task` after its status changed on the board.
+/// Returns a warning when the document can't be changed right now.
+pub(crate) async fn sync_task_checkbox(
+    deployment: &DeploymentImpl,
+    task: &Task,
+) -> Option<ApiWarning> {
+    match write_task_checkbox(deployment, task).await {
+        Ok(()) => None,
+        Err(e) => {
+            tracing::warn!("Failed to sync task {} to its document: {}", task.id, e);
+            Some(ApiWarning::new(
+                "document_task_sync_failed",
+                format!("The task's checkbox in its document was not updated: {e}"),
+            ))
+        }
+    }
+}
+
+/// Whether an item linking to `linked_id` belongs to `task_id`, directly or because the
+/// linked task was merged into it
+async fn links_to(pool: &SqlitePool, linked_id: Uuid, task_id: Uuid) -> Result<bool, ApiError> {
+    if linked_id == task_id {
+        return Ok(true);
+    }
+    Ok(TaskRedirect::find_by_from_task_id(pool, linked_id)
+        .await?
+        .is_some_and(|redirect| redirect.to_task_id == task_id))
+}
+
+async fn write_task_checkbox(deployment: &DeploymentImpl, task: &Task) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    let Some(link) = DocumentTaskLink::find_by_task_id(pool, task.id).await? else {
+        return Ok(());
+    };
+    let done = task.status == TaskStatus::Done;
+    if link.checked == done {
+        return Ok(());
+    }
+    let Some(repo) = Repo::find_by_id(pool, link.repo_id).await? else {
+        return Ok(());
+    };
+    let repo_path = PathBuf::from(&repo.path);
+    let file_path = repo_path.join(&link.relative_path);
+    let reader = DocumentReader::load(deployment, link.project_id).await?;
+    let Some(content) = reader
+        .read_optional(&repo_path, &link.relative_path)
+        .await?
+    else {
+        DocumentTaskLink::delete_by_task_id(pool, task.id).await?;
+        return Ok(());
+    };
+    let mut item = None;
+    for candidate in task_list::parse(&content) {
+        if let Some(task_id) = candidate.task_id
+            && links_to(pool, task_id, task.id).await?
+        {
+            item = Some(candidate);
+            break;
+        }
+    }
+    let Some(item) = item.filter(|_| task_sync_enabled(front_matter::parse(&content).as_ref()))
+    else {
+        DocumentTaskLink::delete_by_task_id(pool, task.id).await?;
+        return Ok(());
+    };
+    if item.checked != done {
+        if let Some(violation) =
+            find_read_only_violation(deployment, link.project_id, &link.relative_path).await?
+        {
+            return Err(ApiError::Forbidden(format!(
+                "'{}' is read-only ({})",
+                violation.relative_path, violation.pattern
+            )));
+        }
+        document_approvals::require_unprotected(deployment, link.project_id, &link.relative_path)
+            .await?;
+        document_encryption::require_unencrypted(deployment, link.project_id, &link.relative_path)
+            .await?;
+        if let Some(lock) = DocumentLock::find_active(pool, repo.id, &link.relative_path).await? {
+            return Err(ApiError::Conflict(format!(
+                "'{}' is locked by {}",
+                link.relative_path, lock.holder
+            )));
+        }
+        document_branches::require_editable_branch(
+            deployment,
+            link.project_id,
+            &repo_path,
+            &link.relative_path,
+        )
+        .await?;
+
+        let Some(updated) = task_list::set_checked(&content, item.line, done) else {
+            return Ok(());
+        };
+        tokio::fs::write(&file_path, &updated).await?;
+        deployment.document_index().invalidate(repo.id);
+        let action = if done { "check off" } else { "uncheck" };
+        let committer = DocumentCommitter::load(deployment, link.project_id).await?;
+        let commit_message = committer.message(
+            &format!(
+                "docs: {} '{}' in {}",
+                action, task.title, link.relative_path
+            ),
+            &link.relative_path,
+        );
+        if let Err(e) = committer.commit(deployment, &repo_path, &commit_message) {
+            tracing::warn!("Failed to auto-commit task checkbox: {}", e);
+        }
+    }
+    DocumentTaskLink::upsert(
+        pool,
+        task.id,
+        link.project_id,
+        repo.id,
+        &link.relative_path,
+        done,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Sync the task list of a planning document with the board in both directions,
+/// creating tasks for unlinked items with `create_tasks`. Checkbox changes coming from
+/// the board are written to the document and committed.
+pub async fn sync_document_tasks(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+    ResponseJson(body): ResponseJson<SyncDocumentTasksRequest>,
+) -> Result<Response, ApiError> {
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?;
+    let decoded_path = validate_relative_path(&decoded_path)?.to_string();
+    let (repo, file_path) = find_document_file(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &decoded_path,
+    )
+    .await?;
+    if document_file_type(&file_path) != Some(DocumentFileType::Markdown) {
+        return Err(ApiError::BadRequest(format!(
+            "'{}' is not a markdown document",
+            decoded_path
+        )));
+    }
+
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let content = reader.read(&repo.path, &decoded_path).await?;
+    let current_hash = content_hash(&content);
+    if body
+        .expected_hash
+        .as_ref()
+        .is_some_and(|hash| *hash != current_hash)
+    {
+        let conflict = DocumentUpdateConflict {
+            current_hash,
+            current_content: content,
+        };
+        let response: ApiResponse<SyncDocumentTasksResponse, DocumentUpdateConflict> =
+            ApiResponse::error_with_data(conflict);
+        return Ok((StatusCode::CONFLICT, ResponseJson(response)).into_response());
+    }
+    if !task_sync_enabled(front_matter::parse(&content).as_ref()) {
+        return Err(ApiError::BadRequest(format!(
+            "Add `{}: true` to the front matter of '{}' to sync its task list",
+            TASK_SYNC_FIELD, decoded_path
+        )));
+    }
+
+    if let Some(violation) =
+        find_read_only_violation(&deployment, project.id, &decoded_path).await?
+    {
+        let response: ApiResponse<SyncDocumentTasksResponse, DocumentReadOnlyViolation> =
+            ApiResponse::error_with_data(violation);
+        return Ok(ResponseJson(response).into_response());
+    }
+    document_approvals::require_unprotected(&deployment, project.id, &decoded_path).await?;
+    document_encryption::require_unencrypted(&deployment, project.id, &decoded_path).await?;
+    if let Some(lock) =
+        DocumentLock::find_active(&deployment.db().pool, repo.id, &decoded_path).await?
+        && body.lock_holder.as_deref().map(str::trim) != Some(lock.holder.as_str())
+    {
+        let response: ApiResponse<SyncDocumentTasksResponse, DocumentLock> =
+            ApiResponse::error_with_data(lock);
+        return Ok((StatusCode::LOCKED, ResponseJson(response)).into_response());
+    }
+    let repo_path = PathBuf::from(&repo.path);
+    document_branches::require_editable_branch(&deployment, project.id, &repo_path, &decoded_path)
+        .await?;
+
+    let reconciled = reconcile(
+        &deployment,
+        project.id,
+        &repo,
+        &decoded_path,
+        &content,
+        true,
+        body.create_tasks,
+    )
+    .await?;
+
+    let mut warnings = reconciled.warnings;
+    let mut committed = false;
+    if reconciled.content != content {
+        tokio::fs::write(&file_path, &reconciled.content).await?;
+        deployment.document_index().invalidate(repo.id);
+        let committer = DocumentCommitter::load(&deployment, project.id).await?;
+        let commit_message = committer.message(
+            &format!("docs: sync task list of {}", decoded_path),
+            &decoded_path,
+        );
+        committed = match committer.commit(&deployment, &repo_path, &commit_message) {
+            Ok(committed) => committed,
+            Err(e) => {
+                tracing::warn!("Failed to auto-commit task list sync: {}", e);
+                warnings.push(auto_commit_warning("task list", &e));
+                false
+            }
+        };
+    }
+
+    Ok(ResponseJson(
+        ApiResponse::success(SyncDocumentTasksResponse {
+            content_hash: content_hash(&reconciled.content),
+            created: reconciled.created,
+            updated: reconciled.updated,
+            checkboxes_changed: reconciled.checkboxes_changed,
+            committed,
+        })
+        .with_warnings(warnings),
+    )
+    .into_response())
+}
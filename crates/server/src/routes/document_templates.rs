@@ -0,0 +1,189 @@
+//! Document templates in a folder of the project's primary repository, which new
+//! documents can be created from, see [`CreateFileRequest`](super::documents::CreateFileRequest).
+
+use std::{collections::HashMap, path::Path};
+
+use axum::{Extension, extract::State, response::Json as ResponseJson};
+use chrono::Local;
+use db::models::project::Project;
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::document_templates;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{
+        document_batch::within_repository,
+        document_commits::DocumentCommitter,
+        document_encryption::DocumentKeyring,
+        documents::{
+            DocumentFileType, allowed_file_type, document_file_type, project_extensions,
+            project_repositories, validate_relative_path,
+        },
+    },
+};
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentTemplate {
+    /// Path of the template inside the templates folder, e.g. `adr/default.md`
+    pub id: String,
+    /// Name derived from the file name, e.g. `Default`
+    pub name: String,
+    pub file_type: DocumentFileType,
+    /// Placeholders other than `title`, `date` and `author` the template uses
+    pub variables: Vec<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentTemplatesFolder {
+    /// Folder of the primary repository templates are read from
+    pub folder: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateDocumentTemplatesFolder {
+    /// `null` resets the project to the default folder
+    pub folder: Option<String>,
+}
+
+/// Templates in the project's templates folder that have an enabled document type
+pub async fn list_document_templates(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<Vec<DocumentTemplate>>>, ApiError> {
+    let Some(repo) = project_repositories(&deployment, project.id, None)
+        .await?
+        .into_iter()
+        .next()
+    else {
+        return Ok(ResponseJson(ApiResponse::success(Vec::new())));
+    };
+    let folder = Project::find_document_templates_folder(&deployment.db().pool, project.id).await?;
+    let folder_path = repo.path.join(&folder);
+    if !within_repository(&repo.path, &folder_path) {
+        return Ok(ResponseJson(ApiResponse::success(Vec::new())));
+    }
+
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let keyring = DocumentKeyring::load(&deployment, project.id).await?;
+    let ids = tokio::task::spawn_blocking({
+        let folder_path = folder_path.clone();
+        move || document_templates::list_template_ids(&folder_path)
+    })
+    .await
+    .map_err(|e| ApiError::Io(std::io::Error::other(e)))?;
+    let mut templates = Vec::with_capacity(ids.len());
+    for id in ids {
+        let Some(file_type) = allowed_file_type(Path::new(&id), &extensions) else {
+            continue;
+        };
+        let relative_path = format!("{}/{}", folder, id);
+        let variables = match tokio::fs::read_to_string(folder_path.join(&id)).await {
+            Ok(content) => keyring
+                .decrypt(&relative_path, content)
+                .map(|content| document_templates::custom_placeholders(&content))
+                .unwrap_or_default(),
+            Err(e) => {
+                tracing::warn!("Failed to read template {}: {}", relative_path, e);
+                continue;
+            }
+        };
+        templates.push(DocumentTemplate {
+            name: document_templates::title_from_path(&id),
+            id,
+            file_type,
+            variables,
+        });
+    }
+    Ok(ResponseJson(ApiResponse::success(templates)))
+}
+
+/// Content of a document at `document_path` created from the template `template_id`
+/// of the primary repository at `repo_path`. `title`, `date` and `author` default to
+/// the document's file name, today and who creates the document.
+pub(crate) async fn render_document_template(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repo_path: &Path,
+    template_id: &str,
+    document_path: &str,
+    variables: HashMap<String, String>,
+    committer: &DocumentCommitter,
+) -> Result<String, ApiError> {
+    let template_id = template_id.trim();
+    if !document_templates::is_valid_template_id(template_id) {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid template '{}'",
+            template_id
+        )));
+    }
+    let folder = Project::find_document_templates_folder(&deployment.db().pool, project_id).await?;
+    let relative_path = format!("{}/{}", folder, template_id);
+    let template_path = repo_path.join(&relative_path);
+    if !within_repository(repo_path, &template_path) || !template_path.is_file() {
+        return Err(ApiError::BadRequest(format!(
+            "Template '{}' not found in '{}'",
+            template_id, folder
+        )));
+    }
+    if document_file_type(&template_path) != document_file_type(Path::new(document_path)) {
+        return Err(ApiError::BadRequest(
+            "The document must have the template's file type".to_string(),
+        ));
+    }
+
+    let content = tokio::fs::read_to_string(&template_path).await?;
+    let content = DocumentKeyring::load(deployment, project_id)
+        .await?
+        .decrypt(&relative_path, content)?;
+    let mut values = HashMap::from([
+        (
+            "title".to_string(),
+            document_templates::title_from_path(document_path),
+        ),
+        (
+            "date".to_string(),
+            Local::now().format("%Y-%m-%d").to_string(),
+        ),
+        ("author".to_string(), committer.user().to_string()),
+    ]);
+    values.extend(
+        variables
+            .into_iter()
+            .map(|(name, value)| (name.trim().to_string(), value)),
+    );
+    Ok(document_templates::render(&content, &values))
+}
+
+pub async fn get_document_templates_folder(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentTemplatesFolder>>, ApiError> {
+    let folder = Project::find_document_templates_folder(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        DocumentTemplatesFolder { folder },
+    )))
+}
+
+/// Set the folder templates are read from, or reset to the default. Templates aren't
+/// moved.
+pub async fn update_document_templates_folder(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<UpdateDocumentTemplatesFolder>,
+) -> Result<ResponseJson<ApiResponse<DocumentTemplatesFolder>>, ApiError> {
+    let folder = match body.folder.as_deref() {
+        Some(folder) => Some(validate_relative_path(folder.trim_matches('/'))?),
+        None => None,
+    };
+    let pool = &deployment.db().pool;
+    Project::set_document_templates_folder(pool, project.id, folder).await?;
+    let folder = Project::find_document_templates_folder(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        DocumentTemplatesFolder { folder },
+    )))
+}
@@ -1,35 +1,103 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use axum::{
     Extension, Router,
-    extract::{Path as AxumPath, Request, State},
-    http::StatusCode,
+    extract::{DefaultBodyLimit, Path as AxumPath, Query, Request, State},
+    http::{HeaderMap, StatusCode, header},
     middleware::{Next, from_fn_with_state},
-    response::{Json as ResponseJson, Response},
+    response::{
+        IntoResponse, Json as ResponseJson, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
     routing::{get, post},
 };
-use db::models::project::Project;
+use db::models::{
+    document_annotation::DocumentAnnotation,
+    document_lock::DocumentLock,
+    document_tag::DocumentTag,
+    project::{
+        DEFAULT_DOCUMENT_MAX_READ_BYTES, DocumentScanLimits, DocumentSymlinkPolicy,
+        DocumentSyncStrategy, Project,
+    },
+    project_seed_document::ProjectSeedDocument,
+    project_webhook::WebhookEvent,
+    repo::Repo,
+    task::{CreateTask, Task},
+    task_document::TaskDocument,
+};
 use deployment::Deployment;
+use futures_util::{Stream, StreamExt};
+use globset::{GlobBuilder, GlobMatcher};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use services::services::{
+    document_index::{DocumentFilter, DocumentIndex, ScanBudget, ScanState},
+    document_store::{self, DocumentStore, DocumentStoreError},
+    events::document_patch,
+    git::{FileCommit, PathChange},
+    markdown_html::{self, DocumentHeading},
+    project::seed_document_path,
+    webhooks::WebhookPayload,
+};
+use sha2::{Digest, Sha256};
+use tokio_util::sync::CancellationToken;
 use ts_rs::TS;
 use uuid::Uuid;
-use utils::response::ApiResponse;
+use utils::{
+    board_links::BoardLinkTarget,
+    diff::{compute_line_change_counts, create_unified_diff, extract_unified_diff_hunks},
+    front_matter,
+    i18n::t,
+    response::{ApiResponse, ApiWarning},
+    text,
+};
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{
+        board_links, document_action_items, document_approvals,
+        document_assets::{self, MAX_ASSET_BYTES},
+        document_batch, document_branches,
+        document_commits::{self, DocumentCommitter},
+        document_conflicts,
+        document_encryption::{self, DocumentKeyring},
+        document_format, document_freshness,
+        document_import::{self, MAX_IMPORT_UPLOAD_BYTES},
+        document_links, document_locks, document_query, document_recent,
+        document_roots::{self, in_document_roots, require_in_document_roots},
+        document_stats, document_sync_status, document_tags, document_task_sync,
+        document_templates, paths, project_usage, task_documents, webhooks,
+    },
+};
 
 /// Default branch name for document operations
-const DEFAULT_DOCS_BRANCH: &str = "main";
+pub(crate) const DEFAULT_DOCS_BRANCH: &str = "main";
 
 /// Require the repository to be on the main branch for document editing.
 /// Returns the current branch name if on main, otherwise returns an error.
-/// Documents can only be edited on the main branch - other branches are read-only.
-fn require_main_branch(deployment: &DeploymentImpl, repo_path: &Path) -> Result<String, ApiError> {
-    let git = deployment.git();
+/// Documents can only be edited on the main branch - other branches are read-only -
+/// unless branch editing is on, see [`document_branches::require_editable_branch`].
+pub(crate) fn require_main_branch(
+    deployment: &DeploymentImpl,
+    repo_path: &Path,
+) -> Result<String, ApiError> {
+    if document_store::is_bare_repository(repo_path) {
+        return Err(ApiError::Forbidden(t(
+            "docs-bare-read-only",
+            &[("path", &repo_path.display())],
+        )));
+    }
+    let git = deployment.git_backend();
     
     // Get current branch
     let current_branch = git
         .get_current_branch(repo_path)
-        .map_err(|e| ApiError::BadRequest(format!("Failed to get current branch: {e}")))?;
+        .map_err(|e| ApiError::BadRequest(t("docs-current-branch-failed", &[("error", &e)])))?;
     
     // If on main, allow editing
     if current_branch == DEFAULT_DOCS_BRANCH {
@@ -37,11 +105,12 @@ fn require_main_branch(deployment: &DeploymentImpl, repo_path: &Path) -> Result<
     }
     
     // Not on main - document editing is not allowed
-    Err(ApiError::Forbidden(format!(
-        "Document editing is only allowed on the '{}' branch. Current branch: '{}'. Please switch to '{}' to edit documents.",
-        DEFAULT_DOCS_BRANCH,
-        current_branch,
-        DEFAULT_DOCS_BRANCH
+    Err(ApiError::Forbidden(t(
+        "docs-branch-guard",
+        &[
+            ("required", &DEFAULT_DOCS_BRANCH),
+            ("current", &current_branch),
+        ],
     )))
 }
 
@@ -51,11 +120,82 @@ fn require_main_branch(deployment: &DeploymentImpl, repo_path: &Path) -> Result<
 pub enum DocumentFileType {
     Markdown,
     Json,
+    Yaml,
+    Toml,
+    Text,
+    Asciidoc,
+    Rst,
+}
+
+/// Supported extensions (lowercase, without the dot) and the file type they map to
+const SUPPORTED_EXTENSIONS: &[(&str, DocumentFileType)] = &[
+    ("md", DocumentFileType::Markdown),
+    ("markdown", DocumentFileType::Markdown),
+    ("json", DocumentFileType::Json),
+    ("yaml", DocumentFileType::Yaml),
+    ("yml", DocumentFileType::Yaml),
+    ("toml", DocumentFileType::Toml),
+    ("txt", DocumentFileType::Text),
+    ("text", DocumentFileType::Text),
+    ("adoc", DocumentFileType::Asciidoc),
+    ("asciidoc", DocumentFileType::Asciidoc),
+    ("rst", DocumentFileType::Rst),
+];
+
+/// Extensions shown in a project's documents
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentExtensions {
+    pub extensions: Vec<String>,
+    /// Every extension that can be enabled
+    pub supported: Vec<String>,
+    /// True when the project has no allow-list and shows every supported extension
+    pub is_default: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateDocumentExtensions {
+    /// `null` resets the project to every supported extension
+    pub extensions: Option<Vec<String>>,
+}
+
+/// Path globs whose documents can't be created, edited, deleted or moved through the
+/// API, on any branch (e.g. `generated/**`)
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct DocumentReadOnlyPaths {
+    pub patterns: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateDocumentScanLimits {
+    /// `null` resets the project to the default limits
+    pub limits: Option<DocumentScanLimits>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentReadLimit {
+    /// Largest document, in bytes, returned with its content
+    pub max_bytes: usize,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateDocumentReadLimit {
+    /// `null` resets the project to the default limit
+    pub max_bytes: Option<usize>,
+}
+
+/// `error_data` of a change rejected because it touches a read-only path
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentReadOnlyViolation {
+    pub relative_path: String,
+    /// The read-only glob the path matched
+    pub pattern: String,
 }
 
 /// Metadata for a document file
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct DocumentMetadata {
+    /// Repository the document belongs to
+    pub repository_id: Uuid,
     /// File name (just the name, not the path)
     pub name: String,
     /// Relative path from repo root (e.g., "docs/README.md")
@@ -64,6 +204,13 @@ pub struct DocumentMetadata {
     pub absolute_path: String,
     pub file_type: DocumentFileType,
     pub size_bytes: u64,
+    /// Fields of the YAML front matter, for markdown documents that have one
+    #[ts(type = "Record<string, JsonValue> | null")]
+    pub front_matter: Option<Map<String, Value>>,
+    /// Unexpired lock of whoever is editing the document, if any
+    pub lock: Option<DocumentLock>,
+    /// Tags of the document, e.g. `draft` or `adr`, in alphabetical order
+    pub tags: Vec<String>,
 }
 
 /// Content of a document file
@@ -71,18 +218,121 @@ pub struct DocumentMetadata {
 pub struct DocumentContent {
     pub metadata: DocumentMetadata,
     pub content: String,
+    /// SHA-256 of `content`; send it back as `expected_hash` when saving an edit
+    pub content_hash: String,
+    /// Commit the content was read from; `None` when read from the working tree
+    pub revision: Option<String>,
+    /// Headings of a markdown document, for a table of contents; empty for other types
+    pub outline: Vec<DocumentHeading>,
+    /// Whether the document is larger than the project's read limit, so only its
+    /// metadata is returned and `content` and `content_hash` are empty
+    pub too_large: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentContentQuery {
+    /// Commit, branch or tag to read the document at instead of the working tree
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
+}
+
+/// Largest page the list endpoint returns
+const MAX_DOCUMENTS_PAGE_SIZE: usize = 1000;
+
+/// Query parameters for listing documents
+#[derive(Debug, Deserialize)]
+pub struct ListDocumentsQuery {
+    /// Only scan below this folder (e.g., "docs/guides")
+    pub path_prefix: Option<String>,
+    /// Page size; every document when omitted, at most 1000
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub offset: usize,
+    /// Comma-separated tags; only documents with all of them are listed
+    pub tags: Option<String>,
 }
 
 /// List documents response
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ListDocumentsResponse {
     pub documents: Vec<DocumentMetadata>,
+    /// Number of matching documents across all pages
+    pub total: usize,
+    /// The scan hit one of the project's scan limits, so documents may be missing
+    pub truncated: bool,
+}
+
+/// A folder or document in the document tree
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DocumentTreeNode {
+    Folder {
+        name: String,
+        /// Relative path from repo root (e.g., "docs/guides")
+        relative_path: String,
+        /// Folders first, then documents, each sorted by name
+        children: Vec<DocumentTreeNode>,
+    },
+    Document(DocumentMetadata),
+}
+
+/// Document tree response
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DocumentTreeResponse {
+    /// Top-level folders and documents of the project repositories
+    pub nodes: Vec<DocumentTreeNode>,
+    /// The scan hit one of the project's scan limits, so documents may be missing
+    pub truncated: bool,
 }
 
 /// Request body for updating document content
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct UpdateDocumentRequest {
     pub content: String,
+    /// `content_hash` of the version the edit is based on. May be sent as an `If-Match`
+    /// header instead.
+    #[serde(default)]
+    #[ts(optional)]
+    pub expected_hash: Option<String>,
+    /// Name the editor locked the document with. Edits are refused with a 423 while
+    /// someone else holds an unexpired lock.
+    #[serde(default)]
+    #[ts(optional)]
+    pub lock_holder: Option<String>,
+}
+
+/// `error_data` of the 409 returned when a document changed since it was read
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DocumentUpdateConflict {
+    /// Hash of the document as it is now; send it as `expected_hash` to overwrite
+    pub current_hash: String,
+    pub current_content: String,
+}
+
+/// `?dry_run=true` on a document write checks it as usual, then returns a
+/// [`DocumentCommitPreview`] instead of writing and committing anything
+#[derive(Debug, Default, Deserialize)]
+pub struct DryRunQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// What a document write would commit
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentCommitPreview {
+    pub repository_id: Uuid,
+    /// The branch the commit would be made on; `None` when the change would await
+    /// approval instead
+    pub branch: Option<String>,
+    pub commit_message: String,
+    /// Unified diff of the change
+    pub diff: String,
+}
+
+impl DocumentCommitPreview {
+    pub(crate) fn into_response(self) -> Response {
+        ResponseJson(ApiResponse::<DocumentCommitPreview>::success(self)).into_response()
+    }
 }
 
 /// Response for document update
@@ -94,6 +344,75 @@ pub struct UpdateDocumentResponse {
     pub branch: Option<String>,
     /// Whether changes were committed
     pub committed: bool,
+    /// Set when the document is in a protected folder and the edit awaits approval
+    /// instead of being saved
+    pub change_request_id: Option<Uuid>,
+    /// Hash of the saved content, to base the next edit on
+    pub content_hash: Option<String>,
+}
+
+/// Request body for changing front matter fields without sending the whole document
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateFrontMatterRequest {
+    /// Fields to set; `null` removes a field. Fields not listed are kept as they are.
+    #[ts(type = "Record<string, JsonValue>")]
+    pub fields: Map<String, Value>,
+    /// `content_hash` the change is based on. Defaults to the document as it is now,
+    /// since the body isn't sent.
+    #[serde(default)]
+    #[ts(optional)]
+    pub expected_hash: Option<String>,
+    /// Name the editor locked the document with, if any
+    #[serde(default)]
+    #[ts(optional)]
+    pub lock_holder: Option<String>,
+}
+
+/// Request body for creating a task from lines selected in a document
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateDocumentTaskRequest {
+    /// First selected line, starting at 1
+    pub start_line: u32,
+    /// Last selected line, inclusive
+    pub end_line: u32,
+    /// Defaults to the first line of the selection
+    #[serde(default)]
+    #[ts(optional)]
+    pub title: Option<String>,
+    /// Also insert a link to the new task below the selection and commit it
+    #[serde(default)]
+    pub insert_marker: bool,
+    /// `content_hash` of the version the line numbers refer to. Required with
+    /// `insert_marker`.
+    #[serde(default)]
+    #[ts(optional)]
+    pub expected_hash: Option<String>,
+    /// Name the editor locked the document with, if any
+    #[serde(default)]
+    #[ts(optional)]
+    pub lock_holder: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CreateDocumentTaskResponse {
+    pub task: Task,
+    /// Whether the link to the task was inserted into the document
+    pub marker_inserted: bool,
+    /// Whether the inserted link was committed
+    pub committed: bool,
+    /// Hash of the document after the link was inserted
+    pub content_hash: Option<String>,
+}
+
+/// Response for document deletion
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DeleteDocumentResponse {
+    pub success: bool,
+    pub message: String,
+    /// The branch the deletion was made on
+    pub branch: Option<String>,
+    /// Whether the deletion was committed
+    pub committed: bool,
 }
 
 /// Request body for creating a folder
@@ -111,6 +430,26 @@ pub struct CreateFolderResponse {
     pub path: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DeleteFolderQuery {
+    /// Folder to delete, relative to the repository root
+    pub path: String,
+    /// Also delete everything in the folder; without it only empty folders are deleted
+    #[serde(default)]
+    pub recursive: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DeleteFolderResponse {
+    pub path: String,
+    /// Documents that were deleted with the folder
+    pub deleted_documents: Vec<String>,
+    /// The branch the deletion was made on
+    pub branch: String,
+    /// Whether the deletion was committed
+    pub committed: bool,
+}
+
 /// Request body for creating a file
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct CreateFileRequest {
@@ -118,6 +457,14 @@ pub struct CreateFileRequest {
     pub path: String,
     /// Optional initial content
     pub content: Option<String>,
+    /// Template to take the initial content from instead, see [`document_templates`]
+    #[serde(default)]
+    #[ts(optional)]
+    pub template_id: Option<String>,
+    /// Values of the template's placeholders
+    #[serde(default)]
+    #[ts(optional)]
+    pub variables: Option<HashMap<String, String>>,
 }
 
 /// Response for file creation
@@ -132,6 +479,83 @@ pub struct CreateFileResponse {
     pub committed: bool,
 }
 
+/// Request body for copying a document
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CopyDocumentRequest {
+    /// Relative path of the copy (e.g., "docs/sprints/sprint-13.md")
+    pub to: String,
+}
+
+/// Request body for moving or renaming a document
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct MoveDocumentRequest {
+    /// Current relative path (e.g., "docs/old-name.md")
+    pub from: String,
+    /// New relative path (e.g., "docs/guides/new-name.md")
+    pub to: String,
+}
+
+/// Response for document move
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct MoveDocumentResponse {
+    pub success: bool,
+    pub message: String,
+    pub metadata: DocumentMetadata,
+    /// The branch where the move was made
+    pub branch: Option<String>,
+    /// Whether the move was committed
+    pub committed: bool,
+}
+
+/// Request body for moving or renaming a folder
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct MoveFolderRequest {
+    /// Current relative path (e.g., "docs/guides")
+    pub from: String,
+    /// New relative path (e.g., "docs/handbook/guides")
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct MovedDocument {
+    pub from: String,
+    pub to: String,
+}
+
+/// Response for folder move
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct MoveFolderResponse {
+    pub from: String,
+    pub to: String,
+    /// Documents that moved with the folder
+    pub moved_documents: Vec<MovedDocument>,
+    /// The branch where the move was made
+    pub branch: String,
+    /// Whether the move was committed
+    pub committed: bool,
+}
+
+/// Request body for refreshing documents after external edits
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RefreshDocumentsRequest {
+    /// Commit the detected changes
+    #[serde(default)]
+    pub commit: bool,
+    /// Commit message (defaults to "docs: sync external changes")
+    pub message: Option<String>,
+}
+
+/// Response for a document refresh
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RefreshDocumentsResponse {
+    /// Documents added, modified or deleted outside the app since the last commit
+    pub changes: Vec<PathChange>,
+    /// Whether the changes were committed
+    pub committed: bool,
+}
+
+const DEFAULT_REFRESH_COMMIT_MESSAGE: &str = "docs: sync external changes";
+
 /// Directories to skip during recursive scanning
 const EXCLUDED_DIRS: &[&str] = &[
     "node_modules",
@@ -153,12 +577,139 @@ const EXCLUDED_DIRS: &[&str] = &[
     ".output",
 ];
 
-/// Recursively scan a directory for markdown and JSON files
+/// Run a directory scan on the blocking thread pool. The scan gets a token that is
+/// cancelled when the request is dropped, e.g. because the client disconnected, so large
+/// scans stop instead of running to completion for nobody.
+async fn scan_blocking<T, F>(scan: F) -> Result<T, ApiError>
+where
+    F: FnOnce(&CancellationToken) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let cancel = CancellationToken::new();
+    let _cancel_on_drop = cancel.clone().drop_guard();
+    tokio::task::spawn_blocking(move || scan(&cancel))
+        .await
+        .map_err(|e| ApiError::Io(std::io::Error::other(e)))
+}
+
+/// Files the document index tracks. Project extension allow-lists are applied when
+/// listing, so the index holds every supported document.
+fn document_filter(limits: DocumentScanLimits, symlinks: DocumentSymlinkPolicy) -> DocumentFilter {
+    DocumentFilter {
+        excluded_dirs: EXCLUDED_DIRS,
+        extensions: supported_extensions(),
+        limits,
+        symlinks,
+    }
+}
+
+/// `HEAD` commit of each repository, keying its cached scans. Repositories without
+/// commits have none.
+fn repository_heads(
+    deployment: &DeploymentImpl,
+    repositories: &[Repo],
+) -> HashMap<Uuid, Option<String>> {
+    let git = deployment.git_backend();
+    repositories
+        .iter()
+        .map(|repo| (repo.id, git.resolve_revision(&repo.path, "HEAD").ok()))
+        .collect()
+}
+
+/// State to look up and cache scans of `repo` with, watching it first if it isn't yet.
+/// `None` if it can't be watched, so its scans aren't cached.
+fn watched_scan_state(
+    index: &DocumentIndex,
+    project_id: Uuid,
+    repo: &Repo,
+    head: Option<String>,
+    filter: DocumentFilter,
+) -> Option<ScanState> {
+    if let Some(state) = index.scan_state(repo.id, head.clone()) {
+        return Some(state);
+    }
+    if let Err(e) = index.watch(project_id, repo.id, &repo.path, filter) {
+        tracing::warn!("Failed to watch documents in {:?}: {}", repo.path, e);
+        return None;
+    }
+    index.scan_state(repo.id, head)
+}
+
+/// Number of documents in a document tree
+fn count_tree_documents(nodes: &[DocumentTreeNode]) -> usize {
+    nodes
+        .iter()
+        .map(|node| match node {
+            DocumentTreeNode::Folder { children, .. } => count_tree_documents(children),
+            DocumentTreeNode::Document(_) => 1,
+        })
+        .sum()
+}
+
+/// Warning for a scan that stopped at one of the project's scan limits
+fn scan_truncated_warning(limits: &DocumentScanLimits) -> ApiWarning {
+    ApiWarning::new(
+        "scan_truncated",
+        format!(
+            "Only part of the documents are shown: the scan stops after {} documents, {} \
+             folders deep or {} seconds. Narrow the path prefix or raise the project's scan \
+             limits to see the rest.",
+            limits.max_files, limits.max_depth, limits.max_seconds
+        ),
+    )
+}
+
+/// Stream `document_created`, `document_updated` and `document_deleted` events for the
+/// project's documents, including edits made outside the app
+pub async fn stream_document_events(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, ApiError> {
+    let repositories = project_repositories(&deployment, project.id, None).await?;
+    let limits = Project::find_document_scan_limits(&deployment.db().pool, project.id).await?;
+    let symlinks = Project::find_document_symlink_policy(&deployment.db().pool, project.id).await?;
+    let roots = document_roots::project_document_roots(&deployment, project.id).await?;
+    let index = deployment.document_index().clone();
+    // Subscribe before watching so changes made while the watchers start aren't missed
+    let events = index
+        .subscribe(project.id)
+        .filter(move |event| std::future::ready(in_document_roots(&roots, &event.relative_path)));
+
+    let project_id = project.id;
+    scan_blocking(move |_| {
+        for repo in repositories {
+            let repo_path = PathBuf::from(&repo.path);
+            if !repo_path.is_dir() {
+                continue;
+            }
+            let filter = document_filter(limits, symlinks);
+            if let Err(e) = index.watch(project_id, repo.id, &repo_path, filter) {
+                tracing::warn!("Failed to watch documents in {:?}: {}", repo_path, e);
+            }
+        }
+    })
+    .await?;
+
+    let stream =
+        events.map(|event| Event::default().event(event.kind.event_name()).json_data(&event));
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Recursively scan a directory for documents with one of `extensions`, until `budget`
+/// runs out
 fn scan_directory_recursive(
+    repository_id: Uuid,
     base_path: &Path,
     current_path: &Path,
+    extensions: &[String],
+    symlinks: DocumentSymlinkPolicy,
+    cancel: &CancellationToken,
+    budget: &mut ScanBudget,
     documents: &mut Vec<DocumentMetadata>,
 ) {
+    if cancel.is_cancelled() {
+        return;
+    }
     let entries = match std::fs::read_dir(current_path) {
         Ok(entries) => entries,
         Err(e) => {
@@ -168,6 +719,9 @@ fn scan_directory_recursive(
     };
 
     for entry in entries.flatten() {
+        if !budget.has_room() {
+            return;
+        }
         let path = entry.path();
         let file_name = match path.file_name() {
             Some(name) => name.to_string_lossy().to_string(),
@@ -178,20 +732,32 @@ fn scan_directory_recursive(
         if file_name.starts_with('.') {
             continue;
         }
+        let Some(meta) = document_store::scan_metadata(symlinks, base_path, &path) else {
+            continue;
+        };
 
-        if path.is_dir() {
+        if meta.is_dir() {
             // Check if directory should be excluded
-            if EXCLUDED_DIRS.contains(&file_name.as_str()) {
+            if EXCLUDED_DIRS.contains(&file_name.as_str())
+                || !budget.allows_depth(depth(base_path, &path))
+            {
                 continue;
             }
             // Recursively scan subdirectory
-            scan_directory_recursive(base_path, &path, documents);
-        } else if path.is_file() {
+            scan_directory_recursive(
+                repository_id,
+                base_path,
+                &path,
+                extensions,
+                symlinks,
+                cancel,
+                budget,
+                documents,
+            );
+        } else if meta.is_file() {
             // Check file extension
-            let file_type = match path.extension().and_then(|e| e.to_str()) {
-                Some("md") | Some("markdown") => DocumentFileType::Markdown,
-                Some("json") => DocumentFileType::Json,
-                _ => continue, // Skip non-markdown/json files
+            let Some(file_type) = allowed_file_type(&path, extensions) else {
+                continue;
             };
 
             // Get relative path from base
@@ -200,60 +766,683 @@ fn scan_directory_recursive(
                 Err(_) => continue,
             };
 
-            // Get file size
-            let size_bytes = match entry.metadata() {
-                Ok(meta) => meta.len(),
-                Err(_) => 0,
-            };
-
             documents.push(DocumentMetadata {
+                repository_id,
                 name: file_name,
                 relative_path,
                 absolute_path: path.to_string_lossy().to_string(),
                 file_type,
-                size_bytes,
+                size_bytes: meta.len(),
+                front_matter: None,
+                lock: None,
+                tags: Vec::new(),
+            });
+            budget.add_file();
+        }
+    }
+}
+
+/// Add the documents of a store without a working tree, e.g. a bare clone, that lie in
+/// `folders`, until `budget` runs out
+fn list_stored_documents(
+    repository_id: Uuid,
+    repo_path: &Path,
+    store: &dyn DocumentStore,
+    folders: &[String],
+    extensions: &[String],
+    budget: &mut ScanBudget,
+    documents: &mut Vec<DocumentMetadata>,
+) {
+    for folder in folders {
+        let files = match store.list(folder, EXCLUDED_DIRS) {
+            Ok(files) => files,
+            Err(e) => {
+                tracing::warn!("Failed to list documents in {:?}: {}", repo_path, e);
+                continue;
+            }
+        };
+        for file in files {
+            if !budget.has_room() {
+                return;
+            }
+            let path = Path::new(&file.relative_path);
+            let folder_depth = path.components().count().saturating_sub(1);
+            if !budget.allows_depth(folder_depth) {
+                continue;
+            }
+            let Some(file_type) = allowed_file_type(path, extensions) else {
+                continue;
+            };
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+            documents.push(DocumentMetadata {
+                repository_id,
+                name: name.to_string_lossy().to_string(),
+                absolute_path: repo_path.join(path).to_string_lossy().to_string(),
+                relative_path: file.relative_path,
+                file_type,
+                size_bytes: file.size_bytes,
+                front_matter: None,
+                lock: None,
+                tags: Vec::new(),
             });
+            budget.add_file();
         }
     }
 }
 
-/// List markdown and JSON files from project repositories (including subdirectories)
+/// Number of folders `path` lies below `base_path`
+fn depth(base_path: &Path, path: &Path) -> usize {
+    path.strip_prefix(base_path)
+        .map(|relative| relative.components().count())
+        .unwrap_or(0)
+}
+
+/// Documents of the project's repositories sorted by path, or of just `repository_id`,
+/// optionally limited to `path_prefix`, within the project's document roots. Served
+/// from the document index where possible. Also returns whether the scan limits cut the
+/// listing short.
+pub(crate) async fn scan_project_documents(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repository_id: Option<Uuid>,
+    path_prefix: Option<String>,
+) -> Result<(Vec<DocumentMetadata>, bool), ApiError> {
+    let repositories = project_repositories(deployment, project_id, repository_id).await?;
+    let extensions = project_extensions(deployment, project_id).await?;
+    let limits = Project::find_document_scan_limits(&deployment.db().pool, project_id).await?;
+    let symlinks = Project::find_document_symlink_policy(&deployment.db().pool, project_id).await?;
+    let roots = document_roots::project_document_roots(deployment, project_id).await?;
+    let heads = repository_heads(deployment, &repositories);
+
+    let index = deployment.document_index().clone();
+    let (mut documents, truncated) = scan_blocking(move |cancel| {
+        let cache_key = format!("list:{extensions:?}:{roots:?}:{path_prefix:?}");
+        let mut documents = Vec::new();
+        let mut budget = ScanBudget::new(limits);
+        let mut index_truncated = false;
+        for repo in repositories {
+            let repo_path = PathBuf::from(&repo.path);
+
+            if !repo_path.exists() || !repo_path.is_dir() {
+                continue;
+            }
+
+            // Bare clones have no files to watch, so their docs branch is listed instead
+            let store = document_store::open(&repo_path, DEFAULT_DOCS_BRANCH, symlinks);
+            if store.working_tree().is_none() {
+                list_stored_documents(
+                    repo.id,
+                    &repo_path,
+                    store.as_ref(),
+                    &document_roots::scan_folders(&roots, path_prefix.as_deref()),
+                    &extensions,
+                    &mut budget,
+                    &mut documents,
+                );
+                continue;
+            }
+
+            // Serve from the watched index; only rescan if the repository can't be watched,
+            // or the index is partial and a scan scoped to the prefix or roots may get
+            // further
+            match index.watch(
+                project_id,
+                repo.id,
+                &repo_path,
+                document_filter(limits, symlinks),
+            ) {
+                Ok(indexed)
+                    if indexed.truncated && (path_prefix.is_some() || !roots.is_empty()) => {}
+                Ok(indexed) => {
+                    index_truncated |= indexed.truncated;
+                    documents.extend(indexed.documents.into_iter().filter_map(|doc| {
+                        let in_prefix = path_prefix.as_ref().is_none_or(|prefix| {
+                            Path::new(&doc.relative_path).starts_with(prefix)
+                        });
+                        if !in_prefix || !in_document_roots(&roots, &doc.relative_path) {
+                            return None;
+                        }
+                        let file_type = allowed_file_type(&doc.absolute_path, &extensions)?;
+                        Some(DocumentMetadata {
+                            repository_id: repo.id,
+                            name: doc.absolute_path.file_name()?.to_string_lossy().to_string(),
+                            relative_path: doc.relative_path,
+                            absolute_path: doc.absolute_path.to_string_lossy().to_string(),
+                            file_type,
+                            size_bytes: doc.size_bytes,
+                            front_matter: None,
+                            lock: None,
+                            tags: Vec::new(),
+                        })
+                    }));
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to watch documents in {:?}: {}", repo_path, e);
+                }
+            }
+
+            // Scoped scans of a watched repository are cached until it changes
+            let state = index.scan_state(repo.id, heads.get(&repo.id).cloned().flatten());
+            if let Some(cached) = state.as_ref().and_then(|state| {
+                index.cached_scan::<Vec<DocumentMetadata>>(repo.id, &cache_key, state)
+            }) && budget.add_cached_files(cached.len())
+            {
+                documents.extend(cached);
+                continue;
+            }
+
+            // Scope the scan to the prefix folder or the roots, where they exist in this
+            // repository
+            let scanned_from = documents.len();
+            for folder in document_roots::scan_folders(&roots, path_prefix.as_deref()) {
+                let scan_root = repo_path.join(folder);
+                if !scan_root.is_dir() {
+                    continue;
+                }
+                scan_directory_recursive(
+                    repo.id,
+                    &repo_path,
+                    &scan_root,
+                    &extensions,
+                    symlinks,
+                    cancel,
+                    &mut budget,
+                    &mut documents,
+                );
+            }
+            if let Some(state) = state
+                && !budget.truncated()
+                && !cancel.is_cancelled()
+            {
+                let scanned = documents[scanned_from..].to_vec();
+                index.cache_scan(repo.id, cache_key.clone(), state, scanned);
+            }
+        }
+        (documents, index_truncated || budget.truncated())
+    })
+    .await?;
+
+    // Sort by relative path (puts files in folders together)
+    documents.sort_by(|a, b| a.relative_path.to_lowercase().cmp(&b.relative_path.to_lowercase()));
+    Ok((documents, truncated))
+}
+
+/// List documents from project repositories (including subdirectories)
 pub async fn list_project_documents(
     State(deployment): State<DeploymentImpl>,
     Extension(project): Extension<Project>,
+    Query(repository): Query<RepositoryQuery>,
+    Query(query): Query<ListDocumentsQuery>,
 ) -> Result<ResponseJson<ApiResponse<ListDocumentsResponse>>, ApiError> {
-    let repositories = deployment
-        .project()
-        .get_repositories(&deployment.db().pool, project.id)
-        .await?;
+    let path_prefix = query
+        .path_prefix
+        .as_deref()
+        .map(|prefix| prefix.trim_matches('/'))
+        .filter(|prefix| !prefix.is_empty())
+        .map(validate_relative_path)
+        .transpose()?;
+    let tag_filter = document_tags::parse_tag_filter(query.tags.as_deref())?;
+    let limits = Project::find_document_scan_limits(&deployment.db().pool, project.id).await?;
+    let (mut documents, truncated) = scan_project_documents(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        path_prefix.map(str::to_string),
+    )
+    .await?;
+    let tags = document_tags::project_tags(&deployment, project.id).await?;
+    if !tag_filter.is_empty() {
+        documents.retain(|document| document_tags::has_tags(&tags, document, &tag_filter));
+    }
 
-    let mut documents = Vec::new();
+    let total = documents.len();
+    let limit = match query.limit {
+        Some(limit) => limit.clamp(1, MAX_DOCUMENTS_PAGE_SIZE),
+        None => total,
+    };
+    let mut documents: Vec<_> = documents
+        .into_iter()
+        .skip(query.offset)
+        .take(limit)
+        .collect();
+    // Front matter is only read for the page being returned
+    let mut documents = scan_blocking(move |_| {
+        for document in &mut documents {
+            document.front_matter =
+                read_front_matter(&document.file_type, Path::new(&document.absolute_path));
+        }
+        documents
+    })
+    .await?;
+    let mut locks = document_locks::project_locks(&deployment, project.id).await?;
+    document_locks::attach_locks(&mut locks, &mut documents);
+    document_tags::attach_tags(&tags, &mut documents);
+
+    let warnings = truncated.then(|| scan_truncated_warning(&limits));
+    Ok(ResponseJson(
+        ApiResponse::success(ListDocumentsResponse {
+            documents,
+            total,
+            truncated,
+        })
+        .with_warnings(warnings),
+    ))
+}
 
-    for repo in repositories {
-        let repo_path = PathBuf::from(&repo.path);
+/// Recursively build the document tree below `current_path`. Folders are kept when they
+/// contain documents or are empty, so newly created folders show up; folders holding only
+/// other files are left out. Stops adding nodes once `budget` runs out.
+fn scan_directory_tree(
+    repository_id: Uuid,
+    base_path: &Path,
+    current_path: &Path,
+    extensions: &[String],
+    symlinks: DocumentSymlinkPolicy,
+    cancel: &CancellationToken,
+    budget: &mut ScanBudget,
+) -> Vec<DocumentTreeNode> {
+    if cancel.is_cancelled() {
+        return Vec::new();
+    }
+    let entries = match std::fs::read_dir(current_path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!("Failed to read directory {:?}: {}", current_path, e);
+            return Vec::new();
+        }
+    };
 
-        if !repo_path.exists() || !repo_path.is_dir() {
+    let mut nodes = Vec::new();
+    for entry in entries.flatten() {
+        if !budget.has_room() {
+            break;
+        }
+        let path = entry.path();
+        let file_name = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => continue,
+        };
+        if file_name.starts_with('.') {
             continue;
         }
+        let relative_path = match path.strip_prefix(base_path) {
+            Ok(rel) => rel.to_string_lossy().to_string(),
+            Err(_) => continue,
+        };
+        let Some(meta) = document_store::scan_metadata(symlinks, base_path, &path) else {
+            continue;
+        };
 
-        // Recursively scan the repository directory
-        scan_directory_recursive(&repo_path, &repo_path, &mut documents);
+        if meta.is_dir() {
+            if EXCLUDED_DIRS.contains(&file_name.as_str())
+                || !budget.allows_depth(depth(base_path, &path))
+            {
+                continue;
+            }
+            let children = scan_directory_tree(
+                repository_id,
+                base_path,
+                &path,
+                extensions,
+                symlinks,
+                cancel,
+                budget,
+            );
+            let is_empty = std::fs::read_dir(&path).is_ok_and(|mut dir| dir.next().is_none());
+            if !children.is_empty() || is_empty {
+                nodes.push(DocumentTreeNode::Folder {
+                    name: file_name,
+                    relative_path,
+                    children,
+                });
+            }
+        } else if meta.is_file() {
+            let Some(file_type) = allowed_file_type(&path, extensions) else {
+                continue;
+            };
+            let front_matter = read_front_matter(&file_type, &path);
+            nodes.push(DocumentTreeNode::Document(DocumentMetadata {
+                repository_id,
+                name: file_name,
+                relative_path,
+                absolute_path: path.to_string_lossy().to_string(),
+                file_type,
+                size_bytes: meta.len(),
+                front_matter,
+                lock: None,
+                tags: Vec::new(),
+            }));
+            budget.add_file();
+        }
     }
 
-    // Sort by relative path (puts files in folders together)
-    documents.sort_by(|a, b| a.relative_path.to_lowercase().cmp(&b.relative_path.to_lowercase()));
+    sort_tree_nodes(&mut nodes);
+    nodes
+}
 
-    Ok(ResponseJson(ApiResponse::success(ListDocumentsResponse {
-        documents,
-    })))
+/// Document tree of the documents below `folder` of a store without a working tree,
+/// e.g. a bare clone. Such stores hold no empty folders, so only folders with documents
+/// are listed.
+fn stored_document_tree(
+    repository_id: Uuid,
+    repo_path: &Path,
+    store: &dyn DocumentStore,
+    folder: &str,
+    extensions: &[String],
+    budget: &mut ScanBudget,
+) -> Vec<DocumentTreeNode> {
+    let mut documents = Vec::new();
+    list_stored_documents(
+        repository_id,
+        repo_path,
+        store,
+        &[folder.to_string()],
+        extensions,
+        budget,
+        &mut documents,
+    );
+
+    let mut nodes = Vec::new();
+    for document in documents {
+        let below_folder = Path::new(&document.relative_path)
+            .strip_prefix(folder)
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let folders: Vec<String> = below_folder
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .map(|part| part.as_os_str().to_string_lossy().to_string())
+            .collect();
+        insert_tree_document(&mut nodes, Path::new(folder), &folders, document);
+    }
+    sort_tree(&mut nodes);
+    nodes
 }
 
-/// Middleware for loading project with wildcard path
-async fn load_project_with_wildcard(
-    State(deployment): State<DeploymentImpl>,
-    AxumPath((id, _path)): AxumPath<(Uuid, String)>,
-    mut request: Request,
-    next: Next,
+/// Add `document` to `nodes` below the nested `folders`, creating the folders as needed
+fn insert_tree_document(
+    nodes: &mut Vec<DocumentTreeNode>,
+    parent: &Path,
+    folders: &[String],
+    document: DocumentMetadata,
+) {
+    let Some((name, rest)) = folders.split_first() else {
+        nodes.push(DocumentTreeNode::Document(document));
+        return;
+    };
+    let relative_path = parent.join(name);
+    let index = nodes
+        .iter()
+        .position(|node| {
+            matches!(node, DocumentTreeNode::Folder { name: existing, .. } if existing == name)
+        })
+        .unwrap_or_else(|| {
+            nodes.push(DocumentTreeNode::Folder {
+                name: name.clone(),
+                relative_path: relative_path.to_string_lossy().to_string(),
+                children: Vec::new(),
+            });
+            nodes.len() - 1
+        });
+    if let DocumentTreeNode::Folder { children, .. } = &mut nodes[index] {
+        insert_tree_document(children, &relative_path, rest, document);
+    }
+}
+
+/// [`sort_tree_nodes`] on every level of the tree
+fn sort_tree(nodes: &mut [DocumentTreeNode]) {
+    sort_tree_nodes(nodes);
+    for node in nodes {
+        if let DocumentTreeNode::Folder { children, .. } = node {
+            sort_tree(children);
+        }
+    }
+}
+
+/// Folders first, then documents, each by case-insensitive name
+fn sort_tree_nodes(nodes: &mut [DocumentTreeNode]) {
+    nodes.sort_by_cached_key(|node| match node {
+        DocumentTreeNode::Folder { name, .. } => (0, name.to_lowercase()),
+        DocumentTreeNode::Document(metadata) => (1, metadata.name.to_lowercase()),
+    });
+}
+
+/// Documents of the project repositories as a nested folder tree. With document roots,
+/// each root is a top-level folder.
+pub async fn get_document_tree(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentTreeResponse>>, ApiError> {
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project.id)
+        .await?;
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let limits = Project::find_document_scan_limits(&deployment.db().pool, project.id).await?;
+    let symlinks = Project::find_document_symlink_policy(&deployment.db().pool, project.id).await?;
+    let roots = document_roots::project_document_roots(&deployment, project.id).await?;
+    let heads = repository_heads(&deployment, &repositories);
+
+    let index = deployment.document_index().clone();
+    let project_id = project.id;
+    let (mut nodes, truncated) = scan_blocking(move |cancel| {
+        let cache_key = format!("tree:{extensions:?}:{roots:?}");
+        let mut nodes = Vec::new();
+        let mut budget = ScanBudget::new(limits);
+        for repo in repositories {
+            let repo_path = PathBuf::from(&repo.path);
+            if !repo_path.is_dir() {
+                continue;
+            }
+            let store = document_store::open(&repo_path, DEFAULT_DOCS_BRANCH, symlinks);
+            if store.working_tree().is_none() {
+                for folder in document_roots::scan_folders(&roots, None) {
+                    let children = stored_document_tree(
+                        repo.id,
+                        &repo_path,
+                        store.as_ref(),
+                        &folder,
+                        &extensions,
+                        &mut budget,
+                    );
+                    if folder.is_empty() {
+                        nodes.extend(children);
+                    } else if !children.is_empty() {
+                        nodes.push(DocumentTreeNode::Folder {
+                            name: folder.clone(),
+                            relative_path: folder,
+                            children,
+                        });
+                    }
+                }
+                continue;
+            }
+
+            // Trees of watched repositories are cached until they change
+            let state = watched_scan_state(
+                &index,
+                project_id,
+                &repo,
+                heads.get(&repo.id).cloned().flatten(),
+                document_filter(limits, symlinks),
+            );
+            if let Some(cached) = state.as_ref().and_then(|state| {
+                index.cached_scan::<Vec<DocumentTreeNode>>(repo.id, &cache_key, state)
+            }) && budget.add_cached_files(count_tree_documents(&cached))
+            {
+                nodes.extend(cached);
+                continue;
+            }
+
+            let mut repo_nodes = Vec::new();
+            if roots.is_empty() {
+                repo_nodes = scan_directory_tree(
+                    repo.id,
+                    &repo_path,
+                    &repo_path,
+                    &extensions,
+                    symlinks,
+                    cancel,
+                    &mut budget,
+                );
+            }
+            for root in &roots {
+                let root_path = repo_path.join(root);
+                if !root_path.is_dir() {
+                    continue;
+                }
+                let children = scan_directory_tree(
+                    repo.id,
+                    &repo_path,
+                    &root_path,
+                    &extensions,
+                    symlinks,
+                    cancel,
+                    &mut budget,
+                );
+                repo_nodes.push(DocumentTreeNode::Folder {
+                    name: root.clone(),
+                    relative_path: root.clone(),
+                    children,
+                });
+            }
+            if let Some(state) = state
+                && !budget.truncated()
+                && !cancel.is_cancelled()
+            {
+                index.cache_scan(repo.id, cache_key.clone(), state, repo_nodes.clone());
+            }
+            nodes.extend(repo_nodes);
+        }
+        sort_tree_nodes(&mut nodes);
+        (nodes, budget.truncated())
+    })
+    .await?;
+    let mut locks = document_locks::project_locks(&deployment, project.id).await?;
+    document_locks::attach_tree_locks(&mut locks, &mut nodes);
+    let tags = document_tags::project_tags(&deployment, project.id).await?;
+    document_tags::attach_tree_tags(&tags, &mut nodes);
+
+    let warnings = truncated.then(|| scan_truncated_warning(&limits));
+    Ok(ResponseJson(
+        ApiResponse::success(DocumentTreeResponse { nodes, truncated }).with_warnings(warnings),
+    ))
+}
+
+/// Whether the document scan would visit `relative_path`, i.e. it is not inside a
+/// hidden or excluded directory
+pub(crate) fn is_scanned_path(relative_path: &str) -> bool {
+    relative_path
+        .split('/')
+        .all(|part| !part.starts_with('.') && !EXCLUDED_DIRS.contains(&part))
+}
+
+/// Documents of `repo_path` that differ from `HEAD`, within the document `roots` and
+/// outside hidden and excluded directories
+pub(crate) fn uncommitted_document_changes(
+    deployment: &DeploymentImpl,
+    repo_path: &Path,
+    extensions: &[String],
+    roots: &[String],
+) -> Result<Vec<PathChange>, ApiError> {
+    Ok(deployment
+        .git_backend()
+        .working_tree_changes(repo_path)?
+        .into_iter()
+        .filter(|change| {
+            is_scanned_path(&change.path)
+                && in_document_roots(roots, &change.path)
+                && allowed_file_type(Path::new(&change.path), extensions).is_some()
+        })
+        .collect())
+}
+
+/// Detect documents changed outside the app (editor, agent) by comparing the working
+/// tree with `HEAD`, publish a change event for each, and optionally commit them
+pub async fn refresh_documents(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<RefreshDocumentsRequest>,
+) -> Result<ResponseJson<ApiResponse<RefreshDocumentsResponse>>, ApiError> {
+    let _job = project_usage::background_job(&deployment, project.id).await;
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project.id)
+        .await?;
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let roots = document_roots::project_document_roots(&deployment, project.id).await?;
+    let message = body
+        .message
+        .as_deref()
+        .map(str::trim)
+        .filter(|message| !message.is_empty())
+        .unwrap_or(DEFAULT_REFRESH_COMMIT_MESSAGE);
+    let committer = DocumentCommitter::load(&deployment, project.id).await?;
+
+    let mut changes = Vec::new();
+    let mut committed = false;
+    let mut warnings = Vec::new();
+    for repo in repositories {
+        let repo_path = PathBuf::from(&repo.path);
+        if !repo_path.is_dir() {
+            continue;
+        }
+
+        let repo_changes =
+            uncommitted_document_changes(&deployment, &repo_path, &extensions, &roots)?;
+        if repo_changes.is_empty() {
+            continue;
+        }
+
+        if body.commit {
+            document_branches::require_editable_branch(
+                &deployment,
+                project.id,
+                &repo_path,
+                "refresh",
+            )
+            .await?;
+            let paths: Vec<&str> = repo_changes
+                .iter()
+                .map(|change| change.path.as_str())
+                .collect();
+            let message = committer.message(message, &paths.join(", "));
+            // An explicit request, so it commits even when saves aren't auto-committed
+            match committer.commit_now(&deployment, &repo_path, &message) {
+                Ok(made_commit) => committed |= made_commit,
+                Err(e) => {
+                    tracing::warn!("Failed to commit refreshed documents: {}", e);
+                    warnings.push(ApiWarning::new(
+                        "auto_commit_failed",
+                        format!("The external changes could not be committed: {}", e),
+                    ));
+                }
+            }
+        }
+        changes.extend(repo_changes);
+    }
+
+    let msg_store = deployment.events().msg_store();
+    for change in &changes {
+        msg_store.push_patch(document_patch::change(project.id, change));
+    }
+
+    Ok(ResponseJson(
+        ApiResponse::success(RefreshDocumentsResponse { changes, committed })
+            .with_warnings(warnings),
+    ))
+}
+
+/// Middleware for loading project with wildcard path
+async fn load_project_with_wildcard(
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((id, _path)): AxumPath<(Uuid, String)>,
+    mut request: Request,
+    next: Next,
 ) -> Result<Response, StatusCode> {
     let project = match Project::find_by_id(&deployment.db().pool, id).await {
         Ok(Some(p)) => p,
@@ -264,91 +1453,226 @@ async fn load_project_with_wildcard(
     Ok(next.run(request).await)
 }
 
-/// Get content of a specific document by relative path
-pub async fn get_document_content(
+/// Default and maximum number of commits returned by the history endpoint
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+const MAX_HISTORY_LIMIT: usize = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentHistoryQuery {
+    pub limit: Option<usize>,
+}
+
+/// Commits that touched a document, newest first
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentHistoryResponse {
+    pub relative_path: String,
+    pub commits: Vec<FileCommit>,
+}
+
+/// `{*relative_path}` has to be the last route segment, so `<path>/history`,
+/// `<path>/diff`, `<path>/html`, `<path>/action-items`, `<path>/freshness`,
+/// `<path>/backlinks`, `<path>/tasks`, `<path>/query` and `<path>/stats` are dispatched
+/// from the wildcard GET handler
+async fn get_document(
+    state: State<DeploymentImpl>,
+    project: Extension<Project>,
+    AxumPath((id, relative_path)): AxumPath<(Uuid, String)>,
+    repository_query: Query<RepositoryQuery>,
+    history_query: Query<DocumentHistoryQuery>,
+    content_query: Query<DocumentContentQuery>,
+    diff_query: Query<DocumentDiffQuery>,
+    json_query: Query<document_query::JsonDocumentQuery>,
+) -> Result<Response, ApiError> {
+    if let Some(document_path) = relative_path.strip_suffix("/history") {
+        let path = AxumPath((id, document_path.to_string()));
+        get_document_history(state, project, path, repository_query, history_query)
+            .await
+            .map(IntoResponse::into_response)
+    } else if let Some(document_path) = relative_path.strip_suffix("/diff") {
+        let path = AxumPath((id, document_path.to_string()));
+        get_document_diff(state, project, path, repository_query, diff_query)
+            .await
+            .map(IntoResponse::into_response)
+    } else if let Some(document_path) = relative_path.strip_suffix("/html") {
+        let path = AxumPath((id, document_path.to_string()));
+        get_document_html(state, project, path, repository_query)
+            .await
+            .map(IntoResponse::into_response)
+    } else if let Some(document_path) = relative_path.strip_suffix("/action-items") {
+        let path = AxumPath((id, document_path.to_string()));
+        document_action_items::get_action_items(state, project, path, repository_query)
+            .await
+            .map(IntoResponse::into_response)
+    } else if let Some(document_path) = relative_path.strip_suffix("/freshness") {
+        let path = AxumPath((id, document_path.to_string()));
+        document_freshness::get_document_freshness(state, project, path, repository_query)
+            .await
+            .map(IntoResponse::into_response)
+    } else if let Some(document_path) = relative_path.strip_suffix("/backlinks") {
+        let path = AxumPath((id, document_path.to_string()));
+        document_links::get_document_backlinks(state, project, path, repository_query)
+            .await
+            .map(IntoResponse::into_response)
+    } else if let Some(document_path) = relative_path.strip_suffix("/tasks") {
+        let path = AxumPath((id, document_path.to_string()));
+        task_documents::get_document_tasks(state, project, path, repository_query)
+            .await
+            .map(IntoResponse::into_response)
+    } else if let Some(document_path) = relative_path.strip_suffix("/stats") {
+        let path = AxumPath((id, document_path.to_string()));
+        document_stats::get_document_stats(state, project, path, repository_query)
+            .await
+            .map(IntoResponse::into_response)
+    } else if let Some(document_path) = relative_path.strip_suffix("/query") {
+        let path = AxumPath((id, document_path.to_string()));
+        document_query::query_json_document(state, project, path, repository_query, json_query)
+            .await
+            .map(IntoResponse::into_response)
+    } else {
+        let path = AxumPath((id, relative_path));
+        get_document_content(state, project, path, repository_query, content_query)
+            .await
+            .map(IntoResponse::into_response)
+    }
+}
+
+/// POST counterpart of [`get_document`] for `<path>/create-task`, `<path>/sync-tasks`,
+/// `<path>/action-items`, `<path>/format` and `<path>/copy`
+async fn post_document(
+    state: State<DeploymentImpl>,
+    project: Extension<Project>,
+    AxumPath((id, relative_path)): AxumPath<(Uuid, String)>,
+    repository_query: Query<RepositoryQuery>,
+    dry_run: Query<DryRunQuery>,
+    ResponseJson(body): ResponseJson<Value>,
+) -> Result<Response, ApiError> {
+    fn action_body<T: serde::de::DeserializeOwned>(
+        body: Value,
+    ) -> Result<ResponseJson<T>, ApiError> {
+        serde_json::from_value(body)
+            .map(ResponseJson)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid request body: {}", e)))
+    }
+
+    if let Some(document_path) = relative_path.strip_suffix("/create-task") {
+        let path = AxumPath((id, document_path.to_string()));
+        create_document_task(state, project, path, repository_query, action_body(body)?).await
+    } else if let Some(document_path) = relative_path.strip_suffix("/sync-tasks") {
+        let path = AxumPath((id, document_path.to_string()));
+        document_task_sync::sync_document_tasks(
+            state,
+            project,
+            path,
+            repository_query,
+            action_body(body)?,
+        )
+        .await
+    } else if let Some(document_path) = relative_path.strip_suffix("/action-items") {
+        let path = AxumPath((id, document_path.to_string()));
+        document_action_items::apply_action_items(
+            state,
+            project,
+            path,
+            repository_query,
+            action_body(body)?,
+        )
+        .await
+    } else if let Some(document_path) = relative_path.strip_suffix("/format") {
+        let path = AxumPath((id, document_path.to_string()));
+        document_format::format_document(
+            state,
+            project,
+            path,
+            repository_query,
+            dry_run,
+            action_body(body)?,
+        )
+        .await
+    } else if let Some(document_path) = relative_path.strip_suffix("/copy") {
+        let path = AxumPath((id, document_path.to_string()));
+        copy_document(
+            state,
+            project,
+            path,
+            repository_query,
+            dry_run,
+            action_body(body)?,
+        )
+        .await
+    } else {
+        Err(ApiError::BadRequest(format!(
+            "Unknown document action '{}'",
+            relative_path
+        )))
+    }
+}
+
+/// PATCH counterpart of [`get_document`] for `<path>/front-matter`
+async fn patch_document(
+    state: State<DeploymentImpl>,
+    project: Extension<Project>,
+    AxumPath((id, relative_path)): AxumPath<(Uuid, String)>,
+    repository_query: Query<RepositoryQuery>,
+    dry_run: Query<DryRunQuery>,
+    body: ResponseJson<UpdateFrontMatterRequest>,
+) -> Result<Response, ApiError> {
+    let Some(document_path) = relative_path.strip_suffix("/front-matter") else {
+        return Err(ApiError::BadRequest(format!(
+            "Unknown document action '{}'",
+            relative_path
+        )));
+    };
+    let path = AxumPath((id, document_path.to_string()));
+    update_document_front_matter(state, project, path, repository_query, dry_run, body).await
+}
+
+/// Git history of a specific document by relative path
+pub async fn get_document_history(
     State(deployment): State<DeploymentImpl>,
     Extension(project): Extension<Project>,
     AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
-) -> Result<ResponseJson<ApiResponse<DocumentContent>>, ApiError> {
-    let repositories = deployment
-        .project()
-        .get_repositories(&deployment.db().pool, project.id)
-        .await?;
+    Query(repository): Query<RepositoryQuery>,
+    Query(query): Query<DocumentHistoryQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentHistoryResponse>>, ApiError> {
+    let repositories =
+        project_repositories(&deployment, project.id, repository.repository_id).await?;
 
-    // Decode the URL-encoded path
     let decoded_path = urlencoding::decode(&relative_path)
         .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?
         .to_string();
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_HISTORY_LIMIT)
+        .clamp(1, MAX_HISTORY_LIMIT);
 
-    // Search for the file in all repositories
     for repo in repositories {
         let repo_path = PathBuf::from(&repo.path);
         let file_path = repo_path.join(&decoded_path);
 
         // Security: Ensure the file is within the repository
-        let canonical_repo = match repo_path.canonicalize() {
-            Ok(p) => p,
-            Err(_) => continue,
-        };
-
-        let canonical_file = match file_path.canonicalize() {
-            Ok(p) => p,
-            Err(_) => continue,
+        let (Ok(canonical_repo), Ok(canonical_file)) =
+            (repo_path.canonicalize(), file_path.canonicalize())
+        else {
+            continue;
         };
-
         if !canonical_file.starts_with(&canonical_repo) {
             return Err(ApiError::BadRequest(
                 "Invalid file path: access denied".to_string(),
             ));
         }
+        if !file_path.is_file() {
+            continue;
+        }
 
-        if file_path.exists() && file_path.is_file() {
-            // Determine file type
-            let file_type = match file_path.extension().and_then(|e| e.to_str()) {
-                Some("md") | Some("markdown") => DocumentFileType::Markdown,
-                Some("json") => DocumentFileType::Json,
-                _ => {
-                    return Err(ApiError::BadRequest(
-                        "Only markdown and JSON files are supported".to_string(),
-                    ))
-                }
-            };
-
-            // Read file content
-            let content = match std::fs::read_to_string(&file_path) {
-                Ok(c) => c,
-                Err(e) => {
-                    tracing::error!("Failed to read file {:?}: {}", file_path, e);
-                    return Err(ApiError::BadRequest(format!(
-                        "Failed to read file: {}",
-                        e
-                    )));
-                }
-            };
-
-            // Get file name
-            let name = file_path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| decoded_path.clone());
-
-            // Get file size
-            let size_bytes = match std::fs::metadata(&file_path) {
-                Ok(meta) => meta.len(),
-                Err(_) => 0,
-            };
+        let commits = deployment
+            .git_backend()
+            .file_history(&repo_path, &decoded_path, limit)
+            .map_err(|e| ApiError::BadRequest(format!("Failed to read history: {e}")))?;
 
-            return Ok(ResponseJson(ApiResponse::success(DocumentContent {
-                metadata: DocumentMetadata {
-                    name,
-                    relative_path: decoded_path,
-                    absolute_path: file_path.to_string_lossy().to_string(),
-                    file_type,
-                    size_bytes,
-                },
-                content,
-            })));
-        }
+        return Ok(ResponseJson(ApiResponse::success(DocumentHistoryResponse {
+            relative_path: decoded_path,
+            commits,
+        })));
     }
 
     Err(ApiError::BadRequest(format!(
@@ -357,130 +1681,1319 @@ pub async fn get_document_content(
     )))
 }
 
-/// Update content of a specific document by relative path
-pub async fn update_document_content(
+#[derive(Debug, Deserialize)]
+pub struct DocumentDiffQuery {
+    /// Base revision (commit, branch or tag)
+    pub from: Option<String>,
+    /// Target revision; the working tree when omitted
+    pub to: Option<String>,
+}
+
+/// Changes to a document between two revisions. A side is `None` when the
+/// document does not exist there (added or deleted in between).
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentDiffResponse {
+    pub relative_path: String,
+    /// Resolved commit of `from`
+    pub from_revision: String,
+    /// Resolved commit of `to`; `None` for the working tree
+    pub to_revision: Option<String>,
+    pub old_content: Option<String>,
+    pub new_content: Option<String>,
+    /// Full unified diff with `a/` and `b/` headers
+    pub unified_diff: String,
+    /// Individual `@@` hunks of `unified_diff`
+    pub hunks: Vec<String>,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// A document side read from git: resolved commit and content, if the file exists there
+type RevisionContent = (String, Option<String>);
+
+/// Resolve `revision` in one repository and read `relative_path` at it.
+/// `Ok(None)` means the revision does not exist in this repository.
+fn read_revision(
+    deployment: &DeploymentImpl,
+    repo_path: &Path,
+    relative_path: &str,
+    revision: &str,
+) -> Result<Option<RevisionContent>, ApiError> {
+    let git = deployment.git_backend();
+    let commit = match git.read_file_at_revision(repo_path, revision, relative_path) {
+        Ok(Some((commit, bytes))) => {
+            let content = String::from_utf8(bytes)
+                .map_err(|_| ApiError::BadRequest("Document is not valid UTF-8".to_string()))?;
+            return Ok(Some((commit, Some(content))));
+        }
+        // The file is missing at this revision, but the revision itself resolves
+        Ok(None) => git.resolve_revision(repo_path, revision)?,
+        Err(e) => {
+            tracing::debug!("Revision {} not readable in {:?}: {}", revision, repo_path, e);
+            return Ok(None);
+        }
+    };
+    Ok(Some((commit, None)))
+}
+
+/// Diff a document between two revisions, or between a revision and the working tree
+pub async fn get_document_diff(
     State(deployment): State<DeploymentImpl>,
     Extension(project): Extension<Project>,
     AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
-    ResponseJson(body): ResponseJson<UpdateDocumentRequest>,
-) -> Result<ResponseJson<ApiResponse<UpdateDocumentResponse>>, ApiError> {
-    let repositories = deployment
-        .project()
-        .get_repositories(&deployment.db().pool, project.id)
-        .await?;
+    Query(repository): Query<RepositoryQuery>,
+    Query(query): Query<DocumentDiffQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentDiffResponse>>, ApiError> {
+    let repositories =
+        project_repositories(&deployment, project.id, repository.repository_id).await?;
 
-    // Decode the URL-encoded path
     let decoded_path = urlencoding::decode(&relative_path)
         .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?
         .to_string();
+    let relative_path = validate_relative_path(&decoded_path)?;
+    let extensions = project_extensions(&deployment, project.id).await?;
+    require_document_type(Path::new(relative_path), &extensions)?;
+    let non_empty = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+    let from = non_empty(query.from)
+        .ok_or_else(|| ApiError::BadRequest("Missing 'from' revision".to_string()))?;
+    let to = non_empty(query.to);
 
-    // Search for the file in all repositories
     for repo in repositories {
         let repo_path = PathBuf::from(&repo.path);
-        let file_path = repo_path.join(&decoded_path);
-
-        // Security: Ensure the file is within the repository
-        let canonical_repo = match repo_path.canonicalize() {
-            Ok(p) => p,
-            Err(_) => continue,
+        let Some((from_revision, old_content)) =
+            read_revision(&deployment, &repo_path, relative_path, &from)?
+        else {
+            continue;
         };
 
-        let canonical_file = match file_path.canonicalize() {
-            Ok(p) => p,
-            Err(_) => continue,
+        let (to_revision, new_content) = match &to {
+            Some(to) => {
+                let (commit, content) = read_revision(&deployment, &repo_path, relative_path, to)?
+                    .ok_or_else(|| ApiError::BadRequest(format!("Unknown revision: {to}")))?;
+                (Some(commit), content)
+            }
+            None => {
+                let file_path = repo_path.join(relative_path);
+                // Security: Ensure the file is within the repository
+                if let (Ok(canonical_repo), Ok(canonical_file)) =
+                    (repo_path.canonicalize(), file_path.canonicalize())
+                    && !canonical_file.starts_with(&canonical_repo)
+                {
+                    return Err(ApiError::BadRequest(
+                        "Invalid file path: access denied".to_string(),
+                    ));
+                }
+                let content = match tokio::fs::read_to_string(&file_path).await {
+                    Ok(content) => Some(content),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                    Err(e) => return Err(e.into()),
+                };
+                (None, content)
+            }
         };
 
-        if !canonical_file.starts_with(&canonical_repo) {
-            return Err(ApiError::BadRequest(
-                "Invalid file path: access denied".to_string(),
-            ));
+        if old_content.is_none() && new_content.is_none() {
+            continue;
         }
 
-        if file_path.exists() && file_path.is_file() {
-            // Verify file type (only allow markdown and JSON)
-            match file_path.extension().and_then(|e| e.to_str()) {
-                Some("md") | Some("markdown") | Some("json") => {}
-                _ => {
-                    return Err(ApiError::BadRequest(
-                        "Only markdown and JSON files are supported".to_string(),
-                    ))
-                }
-            };
-
-            // Ensure we're on the main branch before modifying documents
-            let current_branch = require_main_branch(&deployment, &repo_path)?;
+        let old = old_content.as_deref().unwrap_or_default();
+        let new = new_content.as_deref().unwrap_or_default();
+        let unified_diff = create_unified_diff(relative_path, old, new);
+        let hunks = extract_unified_diff_hunks(&unified_diff);
+        let (additions, deletions) = compute_line_change_counts(old, new);
+
+        return Ok(ResponseJson(ApiResponse::success(DocumentDiffResponse {
+            relative_path: relative_path.to_string(),
+            from_revision,
+            to_revision,
+            old_content,
+            new_content,
+            unified_diff,
+            hunks,
+            additions,
+            deletions,
+        })));
+    }
 
-            // Write content to file
-            match std::fs::write(&file_path, &body.content) {
-                Ok(_) => {
-                    tracing::info!("Document updated: {:?}", file_path);
+    Err(ApiError::BadRequest(format!(
+        "Document '{}' not found at revision '{}'",
+        relative_path, from
+    )))
+}
 
-                    // Auto-commit the changes
-                    let commit_message = format!("docs: update {}", decoded_path);
-                    let committed = match deployment.git().commit(&repo_path, &commit_message) {
-                        Ok(true) => {
-                            tracing::info!(
-                                "Auto-committed document change to branch {:?}: {}",
-                                current_branch,
-                                decoded_path
-                            );
-                            true
-                        }
-                        Ok(false) => {
-                            tracing::debug!("No changes to commit for document: {}", decoded_path);
-                            false
-                        }
-                        Err(e) => {
-                            tracing::warn!("Failed to auto-commit document change: {}", e);
-                            false
-                        }
-                    };
+/// Read a document as of a past commit or another branch straight from the object database
+fn read_document_at_revision(
+    deployment: &DeploymentImpl,
+    repositories: Vec<Repo>,
+    relative_path: &str,
+    revision: &str,
+    max_bytes: usize,
+    keyring: &DocumentKeyring,
+) -> Result<DocumentContent, ApiError> {
+    let relative_path = validate_relative_path(relative_path)?;
+    let file_type = document_file_type(Path::new(relative_path))
+        .ok_or_else(|| ApiError::BadRequest("Unsupported document type".to_string()))?;
 
-                    return Ok(ResponseJson(ApiResponse::success(UpdateDocumentResponse {
-                        success: true,
-                        message: if committed {
-                            format!("Document saved and committed to branch '{}'", &current_branch)
-                        } else {
-                            "Document saved successfully".to_string()
-                        },
-                        branch: Some(current_branch),
-                        committed,
-                    })));
-                }
-                Err(e) => {
-                    tracing::error!("Failed to write file {:?}: {}", file_path, e);
-                    return Err(ApiError::BadRequest(format!(
-                        "Failed to save file: {}",
-                        e
-                    )));
-                }
+    for repo in repositories {
+        let repo_path = PathBuf::from(&repo.path);
+        let found = deployment
+            .git_backend()
+            .read_file_at_revision(&repo_path, revision, relative_path);
+        let (commit, bytes) = match found {
+            Ok(Some(found)) => found,
+            Ok(None) => continue,
+            // The revision may only exist in one of the project's repositories
+            Err(e) => {
+                tracing::debug!("Revision {} not readable in {:?}: {}", revision, repo_path, e);
+                continue;
             }
-        }
+        };
+        let size_bytes = bytes.len() as u64;
+        let too_large = bytes.len() > max_bytes;
+        let content = if too_large {
+            String::new()
+        } else {
+            keyring.decrypt(relative_path, decode_document(relative_path, bytes)?)?
+        };
+        let file_path = repo_path.join(relative_path);
+        let front_matter = markdown_front_matter(&file_type, &content);
+        let outline = markdown_outline(&file_type, &content);
+
+        return Ok(DocumentContent {
+            metadata: DocumentMetadata {
+                repository_id: repo.id,
+                name: file_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| relative_path.to_string()),
+                relative_path: relative_path.to_string(),
+                absolute_path: file_path.to_string_lossy().to_string(),
+                file_type,
+                size_bytes,
+                front_matter,
+                lock: None,
+                tags: Vec::new(),
+            },
+            content_hash: if too_large {
+                String::new()
+            } else {
+                content_hash(&content)
+            },
+            content,
+            revision: Some(commit),
+            outline,
+            too_large,
+        });
     }
 
     Err(ApiError::BadRequest(format!(
-        "Document '{}' not found in project repositories",
-        decoded_path
+        "Document '{}' not found at revision '{}'",
+        relative_path, revision
     )))
 }
 
-/// Create a new folder in the project repository
-pub async fn create_folder(
+/// A markdown document rendered to sanitized HTML
+#[derive(Debug, Serialize, TS)]
+pub struct DocumentHtml {
+    pub relative_path: String,
+    /// Body of the document without its front matter. Relative links to other documents
+    /// point at the documents page.
+    pub html: String,
+    /// Stylesheet for the `hl-` classes of highlighted code blocks
+    pub highlight_css: String,
+    pub content_hash: String,
+}
+
+/// Render a markdown document on the server, for clients without a markdown renderer
+pub async fn get_document_html(
     State(deployment): State<DeploymentImpl>,
     Extension(project): Extension<Project>,
-    ResponseJson(body): ResponseJson<CreateFolderRequest>,
-) -> Result<ResponseJson<ApiResponse<CreateFolderResponse>>, ApiError> {
-    let repositories = deployment
-        .project()
-        .get_repositories(&deployment.db().pool, project.id)
-        .await?;
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentHtml>>, ApiError> {
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?;
+    let decoded_path = validate_relative_path(&decoded_path)?.to_string();
+    let (repo, file_path) = find_document_file(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &decoded_path,
+    )
+    .await?;
+    if document_file_type(&file_path) != Some(DocumentFileType::Markdown) {
+        return Err(ApiError::BadRequest(format!(
+            "'{}' is not a markdown document",
+            decoded_path
+        )));
+    }
 
-    // Get the first repository (primary repository)
-    let repo = repositories.first().ok_or_else(|| {
-        ApiError::BadRequest("No repository found for this project".to_string())
-    })?;
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let content = reader.read(&repo.path, &decoded_path).await?;
+    let hash = content_hash(&content);
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let board_urls = board_links::link_urls(&deployment, project.id, &content).await?;
+    let project_id = project.id;
+    let document_path = decoded_path.clone();
+    // Highlighting is CPU-bound, so keep it off the async workers
+    let html = tokio::task::spawn_blocking(move || {
+        markdown_html::render(
+            &content,
+            &document_path,
+            |path| {
+                allowed_file_type(Path::new(path), &extensions).map(|_| {
+                    format!(
+                        "/projects/{}/documents?path={}",
+                        project_id,
+                        urlencoding::encode(path)
+                    )
+                })
+            },
+            |href| board_urls.get(href).cloned().flatten(),
+        )
+    })
+    .await
+    .map_err(|e| ApiError::Io(std::io::Error::other(e)))?;
+
+    Ok(ResponseJson(ApiResponse::success(DocumentHtml {
+        relative_path: decoded_path,
+        html,
+        highlight_css: markdown_html::highlight_css().to_string(),
+        content_hash: hash,
+    })))
+}
 
-    let repo_path = PathBuf::from(&repo.path);
+/// Text of a document read as `bytes`. Binary and non-UTF-8 files can't be shown as
+/// documents, even with a document extension.
+fn decode_document(relative_path: &str, bytes: Vec<u8>) -> Result<String, ApiError> {
+    if text::looks_binary(&bytes) {
+        return Err(ApiError::BadRequest(format!(
+            "'{}' is a binary file and can't be opened as a document",
+            relative_path
+        )));
+    }
+    String::from_utf8(bytes).map_err(|_| {
+        ApiError::BadRequest(format!(
+            "'{}' is not UTF-8 encoded text and can't be opened as a document",
+            relative_path
+        ))
+    })
+}
+
+/// Size of the document at `relative_path` of `repo_path` and, unless it is larger than
+/// `max_bytes`, its bytes; `None` if the repository has no such file. The store reads the
+/// working tree, or the docs branch of a bare clone, and refuses paths that lead out of
+/// the repository or through symbolic links the project's policy skips.
+fn read_stored_document(
+    repo_path: &Path,
+    relative_path: &str,
+    max_bytes: usize,
+    symlinks: DocumentSymlinkPolicy,
+) -> Result<Option<(u64, Option<Vec<u8>>)>, DocumentStoreError> {
+    let store = document_store::open(repo_path, DEFAULT_DOCS_BRANCH, symlinks);
+    let Some(size_bytes) = store.size(relative_path)? else {
+        return Ok(None);
+    };
+    if size_bytes > max_bytes as u64 {
+        return Ok(Some((size_bytes, None)));
+    }
+    Ok(store
+        .read(relative_path)?
+        .map(|bytes| (size_bytes, Some(bytes))))
+}
+
+/// Reads documents for what is derived from their text, e.g. rendering, stats or links,
+/// the way [`get_document_content`] reads them: through the store, within the project's
+/// read limit and symbolic link policy, and decrypted for readers of encrypted folders
+pub(crate) struct DocumentReader {
+    max_bytes: usize,
+    symlinks: DocumentSymlinkPolicy,
+    keyring: DocumentKeyring,
+}
+
+impl DocumentReader {
+    pub(crate) async fn load(
+        deployment: &DeploymentImpl,
+        project_id: Uuid,
+    ) -> Result<Self, ApiError> {
+        let pool = &deployment.db().pool;
+        Ok(Self {
+            max_bytes: Project::find_document_max_read_bytes(pool, project_id).await?,
+            symlinks: Project::find_document_symlink_policy(pool, project_id).await?,
+            keyring: DocumentKeyring::load(deployment, project_id).await?,
+        })
+    }
+
+    /// Whether `relative_path` lies in an encrypted folder, so its text is only known
+    /// once it is read and decrypted
+    pub(crate) fn is_encrypted(&self, relative_path: &str) -> bool {
+        self.keyring.encrypts(relative_path)
+    }
+
+    /// Without the read limit, for readers that only return part of a document, such as
+    /// JSON pointer queries
+    pub(crate) fn without_read_limit(mut self) -> Self {
+        self.max_bytes = usize::MAX;
+        self
+    }
+
+    /// Text of the document at `relative_path` of `repo_path`, `None` if there is none.
+    /// Fails for documents larger than the read limit, and for encrypted documents the
+    /// user can't read.
+    pub(crate) async fn read_optional(
+        &self,
+        repo_path: &Path,
+        relative_path: &str,
+    ) -> Result<Option<String>, ApiError> {
+        let (store_path, store_relative_path) =
+            (repo_path.to_path_buf(), relative_path.to_string());
+        let (max_bytes, symlinks) = (self.max_bytes, self.symlinks);
+        let stored = tokio::task::spawn_blocking(move || {
+            read_stored_document(&store_path, &store_relative_path, max_bytes, symlinks)
+        })
+        .await
+        .map_err(|e| ApiError::Io(std::io::Error::other(e)))??;
+        match stored {
+            None => Ok(None),
+            Some((size_bytes, None)) => Err(ApiError::BadRequest(format!(
+                "'{}' is {} bytes, more than this project's limit of {} bytes",
+                relative_path, size_bytes, max_bytes
+            ))),
+            Some((_, Some(bytes))) => {
+                let content = decode_document(relative_path, bytes)?;
+                Ok(Some(self.keyring.decrypt(relative_path, content)?))
+            }
+        }
+    }
+
+    /// Text of the document at `relative_path` of `repo_path`
+    pub(crate) async fn read(
+        &self,
+        repo_path: &Path,
+        relative_path: &str,
+    ) -> Result<String, ApiError> {
+        self.read_optional(repo_path, relative_path)
+            .await?
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Document '{}' not found in project repositories",
+                    relative_path
+                ))
+            })
+    }
+
+    /// Text of a listed document
+    pub(crate) async fn read_listed(
+        &self,
+        document: &DocumentMetadata,
+    ) -> Result<String, ApiError> {
+        let mut repo_path = PathBuf::from(&document.absolute_path);
+        for _ in Path::new(&document.relative_path).components() {
+            repo_path.pop();
+        }
+        self.read(&repo_path, &document.relative_path).await
+    }
+}
+
+/// Warning for a document returned without its content because of the read limit
+fn too_large_warning(document: &DocumentContent, max_bytes: usize) -> ApiWarning {
+    let mut message = format!(
+        "'{}' is {} bytes, more than this project's limit of {} bytes, so only its metadata \
+         is shown.",
+        document.metadata.relative_path, document.metadata.size_bytes, max_bytes
+    );
+    if document.metadata.file_type == DocumentFileType::Json {
+        message.push_str(" Query sections of it with a JSON pointer instead.");
+    }
+    ApiWarning::new("document_too_large", message)
+}
+
+/// Get content of a specific document by relative path, optionally at a given `ref`.
+/// Documents larger than the project's read limit are returned without their content.
+pub async fn get_document_content(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+    Query(query): Query<DocumentContentQuery>,
+) -> Result<ResponseJson<ApiResponse<DocumentContent>>, ApiError> {
+    let repositories =
+        project_repositories(&deployment, project.id, repository.repository_id).await?;
+
+    // Decode the URL-encoded path
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?
+        .to_string();
+
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let file_type = require_document_type(Path::new(&decoded_path), &extensions)?;
+
+    let max_bytes =
+        Project::find_document_max_read_bytes(&deployment.db().pool, project.id).await?;
+    let symlinks = Project::find_document_symlink_policy(&deployment.db().pool, project.id).await?;
+    let keyring = DocumentKeyring::load(&deployment, project.id).await?;
+
+    let revision = query.git_ref.as_deref().map(str::trim);
+    if let Some(revision) = revision.filter(|r| !r.is_empty()) {
+        let document = read_document_at_revision(
+            &deployment,
+            repositories,
+            &decoded_path,
+            revision,
+            max_bytes,
+            &keyring,
+        )?;
+        let warnings = document
+            .too_large
+            .then(|| too_large_warning(&document, max_bytes));
+        return Ok(ResponseJson(
+            ApiResponse::success(document).with_warnings(warnings),
+        ));
+    }
+
+    // Search for the file in all repositories
+    for repo in repositories {
+        let repo_path = PathBuf::from(&repo.path);
+        let file_path = repo_path.join(&decoded_path);
+
+        let store_path = repo_path.clone();
+        let store_relative_path = decoded_path.clone();
+        let stored = tokio::task::spawn_blocking(move || {
+            read_stored_document(&store_path, &store_relative_path, max_bytes, symlinks)
+        })
+        .await
+        .map_err(|e| ApiError::Io(std::io::Error::other(e)))??;
+
+        if let Some((size_bytes, bytes)) = stored {
+            let too_large = bytes.is_none();
+            let content = match bytes {
+                Some(bytes) => {
+                    keyring.decrypt(&decoded_path, decode_document(&decoded_path, bytes)?)?
+                }
+                None => String::new(),
+            };
+
+            // Get file name
+            let name = file_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| decoded_path.clone());
+
+            let front_matter = markdown_front_matter(&file_type, &content);
+            let outline = markdown_outline(&file_type, &content);
+            let lock =
+                DocumentLock::find_active(&deployment.db().pool, repo.id, &decoded_path).await?;
+            let tags =
+                DocumentTag::find_tags(&deployment.db().pool, repo.id, &decoded_path).await?;
+
+            let document = DocumentContent {
+                metadata: DocumentMetadata {
+                    repository_id: repo.id,
+                    name,
+                    relative_path: decoded_path,
+                    absolute_path: file_path.to_string_lossy().to_string(),
+                    file_type,
+                    size_bytes,
+                    front_matter,
+                    lock,
+                    tags,
+                },
+                content_hash: if too_large {
+                    String::new()
+                } else {
+                    content_hash(&content)
+                },
+                content,
+                revision: None,
+                outline,
+                too_large,
+            };
+            let warnings = too_large.then(|| too_large_warning(&document, max_bytes));
+            return Ok(ResponseJson(
+                ApiResponse::success(document).with_warnings(warnings),
+            ));
+        }
+    }
+
+    Err(ApiError::BadRequest(format!(
+        "Document '{}' not found in project repositories",
+        decoded_path
+    )))
+}
+
+/// Update content of a specific document by relative path
+pub async fn update_document_content(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+    Query(dry_run): Query<DryRunQuery>,
+    headers: HeaderMap,
+    ResponseJson(body): ResponseJson<UpdateDocumentRequest>,
+) -> Result<Response, ApiError> {
+    let expected_hash = expected_content_hash(&body, &headers).ok_or_else(|| {
+        ApiError::BadRequest(
+            "Send the document's content_hash as expected_hash or If-Match, so concurrent \
+             edits aren't overwritten"
+                .to_string(),
+        )
+    })?;
+    let repositories =
+        project_repositories(&deployment, project.id, repository.repository_id).await?;
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let symlinks = Project::find_document_symlink_policy(&deployment.db().pool, project.id).await?;
+
+    // Decode the URL-encoded path
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?
+        .to_string();
+
+    // Search for the file in all repositories
+    for repo in repositories {
+        let repo_path = PathBuf::from(&repo.path);
+        let file_path = repo_path.join(&decoded_path);
+
+        // Security: Ensure the file is within the repository
+        let canonical_repo = match repo_path.canonicalize() {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+
+        let canonical_file = match file_path.canonicalize() {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+
+        if !canonical_file.starts_with(&canonical_repo) {
+            return Err(ApiError::BadRequest(
+                "Invalid file path: access denied".to_string(),
+            ));
+        }
+        document_store::check_symlinks(symlinks, &repo_path, &decoded_path)?;
+
+        if file_path.exists() && file_path.is_file() {
+            // Verify file type
+            require_document_type(&file_path, &extensions)?;
+            if let Some(violation) =
+                find_read_only_violation(&deployment, project.id, &decoded_path).await?
+            {
+                let response: ApiResponse<UpdateDocumentResponse, DocumentReadOnlyViolation> =
+                    ApiResponse::error_with_data(violation);
+                return Ok(ResponseJson(response).into_response());
+            }
+
+            // Don't save over a document someone else is editing
+            if let Some(lock) =
+                DocumentLock::find_active(&deployment.db().pool, repo.id, &decoded_path).await?
+                && body.lock_holder.as_deref().map(str::trim) != Some(lock.holder.as_str())
+            {
+                let response: ApiResponse<UpdateDocumentResponse, DocumentLock> =
+                    ApiResponse::error_with_data(lock);
+                return Ok((StatusCode::LOCKED, ResponseJson(response)).into_response());
+            }
+
+            // Reject edits based on a version that has since changed, e.g. by an agent
+            let keyring = DocumentKeyring::load(&deployment, project.id).await?;
+            let current_content =
+                keyring.decrypt(&decoded_path, tokio::fs::read_to_string(&file_path).await?)?;
+            let current_hash = content_hash(&current_content);
+            if current_hash != expected_hash {
+                let conflict = DocumentUpdateConflict {
+                    current_hash,
+                    current_content,
+                };
+                let response: ApiResponse<UpdateDocumentResponse, DocumentUpdateConflict> =
+                    ApiResponse::error_with_data(conflict);
+                return Ok((StatusCode::CONFLICT, ResponseJson(response)).into_response());
+            }
+            board_links::require_valid_links(
+                &deployment,
+                project.id,
+                &body.content,
+                Some(&current_content),
+            )
+            .await?;
+
+            let committer = DocumentCommitter::load(&deployment, project.id).await?;
+            let commit_message =
+                committer.message(&format!("docs: update {}", decoded_path), &decoded_path);
+            if dry_run.dry_run {
+                let protected =
+                    document_approvals::is_protected(&deployment, project.id, &decoded_path)
+                        .await?;
+                let branch = if protected {
+                    None
+                } else {
+                    Some(
+                        document_branches::editable_branch(&deployment, project.id, &repo_path)
+                            .await?,
+                    )
+                };
+                return Ok(DocumentCommitPreview {
+                    repository_id: repo.id,
+                    branch,
+                    commit_message,
+                    diff: create_unified_diff(&decoded_path, &current_content, &body.content),
+                }
+                .into_response());
+            }
+
+            // Edits to protected folders are staged for review instead of saved
+            if let Some(request) = document_approvals::stage_change(
+                &deployment,
+                project.id,
+                &repo,
+                &decoded_path,
+                &body.content,
+            )
+            .await?
+            {
+                return Ok(ResponseJson(ApiResponse::success(UpdateDocumentResponse {
+                    success: true,
+                    message: format!(
+                        "Change submitted for approval by {}",
+                        request.required_reviewers.join(", ")
+                    ),
+                    branch: None,
+                    committed: false,
+                    change_request_id: Some(request.id),
+                    content_hash: None,
+                }))
+                .into_response());
+            }
+
+            // Ensure we're on a branch documents can be edited on before modifying them
+            let current_branch = document_branches::require_editable_branch(
+                &deployment,
+                project.id,
+                &repo_path,
+                &decoded_path,
+            )
+            .await?;
+
+            // Write content to file, encrypted if it is in an encrypted folder
+            let stored = keyring.encrypt(&decoded_path, &body.content)?;
+            match tokio::fs::write(&file_path, &stored).await {
+                Ok(_) => {
+                    tracing::info!("Document updated: {:?}", file_path);
+                    deployment.document_index().invalidate(repo.id);
+
+                    // Auto-commit the changes
+                    let mut warnings = Vec::new();
+                    let committed = match committer.commit(&deployment, &repo_path, &commit_message)
+                    {
+                        Ok(true) => {
+                            tracing::info!(
+                                "Auto-committed document change to branch {:?}: {}",
+                                current_branch,
+                                decoded_path
+                            );
+                            true
+                        }
+                        Ok(false) => {
+                            tracing::debug!("No changes to commit for document: {}", decoded_path);
+                            false
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to auto-commit document change: {}", e);
+                            warnings.push(auto_commit_warning("change", &e));
+                            false
+                        }
+                    };
+
+                    webhooks::document_changed(
+                        &deployment,
+                        project.id,
+                        WebhookEvent::DocumentUpdated,
+                        repo.id,
+                        &decoded_path,
+                    );
+
+                    // Checked items of planning documents complete their tasks
+                    if document_file_type(&file_path) == Some(DocumentFileType::Markdown) {
+                        match document_task_sync::apply_saved_checkboxes(
+                            &deployment,
+                            project.id,
+                            &repo,
+                            &decoded_path,
+                            &body.content,
+                        )
+                        .await
+                        {
+                            Ok(sync_warnings) => warnings.extend(sync_warnings),
+                            Err(e) => {
+                                tracing::warn!(
+                                    "Failed to sync task list of {}: {}",
+                                    decoded_path,
+                                    e
+                                );
+                                warnings.push(ApiWarning::new(
+                                    "document_task_sync_failed",
+                                    format!(
+                                        "The task list was saved but its tasks were not updated: {e}"
+                                    ),
+                                ));
+                            }
+                        }
+                    }
+
+                    return Ok(ResponseJson(
+                        ApiResponse::success(UpdateDocumentResponse {
+                            success: true,
+                            message: if committed {
+                                t("docs-saved-committed", &[("branch", &current_branch)])
+                            } else {
+                                t("docs-saved", &[])
+                            },
+                            branch: Some(current_branch),
+                            committed,
+                            change_request_id: None,
+                            content_hash: Some(content_hash(&body.content)),
+                        })
+                        .with_warnings(warnings),
+                    )
+                    .into_response());
+                }
+                Err(e) => {
+                    tracing::error!("Failed to write file {:?}: {}", file_path, e);
+                    return Err(ApiError::BadRequest(format!(
+                        "Failed to save file: {}",
+                        e
+                    )));
+                }
+            }
+        }
+    }
+
+    Err(ApiError::BadRequest(format!(
+        "Document '{}' not found in project repositories",
+        decoded_path
+    )))
+}
+
+/// Change front matter fields of a markdown document and keep its body. The new content
+/// is saved through [`update_document_content`], so read-only paths, locks, conflicts
+/// and reviews apply as for any other edit.
+pub async fn update_document_front_matter(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+    dry_run: Query<DryRunQuery>,
+    ResponseJson(body): ResponseJson<UpdateFrontMatterRequest>,
+) -> Result<Response, ApiError> {
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?;
+    let decoded_path = validate_relative_path(&decoded_path)?.to_string();
+    let (repo, file_path) = find_document_file(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &decoded_path,
+    )
+    .await?;
+    if document_file_type(&file_path) != Some(DocumentFileType::Markdown) {
+        return Err(ApiError::BadRequest(format!(
+            "'{}' is not a markdown document",
+            decoded_path
+        )));
+    }
+    if let Some(key) = body.fields.keys().find(|key| {
+        key.trim() != key.as_str()
+            || key.is_empty()
+            || key.starts_with(['#', '-'])
+            || key.contains([':', '\n', '\r'])
+    }) {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid front matter field '{}'",
+            key
+        )));
+    }
+
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let content = reader.read(&repo.path, &decoded_path).await?;
+    let request = UpdateDocumentRequest {
+        content: front_matter::update(&content, &body.fields),
+        expected_hash: Some(body.expected_hash.unwrap_or_else(|| content_hash(&content))),
+        lock_holder: body.lock_holder,
+    };
+    update_document_content(
+        State(deployment),
+        Extension(project),
+        AxumPath((id, relative_path)),
+        Query(repository),
+        dry_run,
+        HeaderMap::new(),
+        ResponseJson(request),
+    )
+    .await
+}
+
+/// The repository holding `relative_path` and the document's path on disk. Fails unless
+/// it is a document of a type the project allows, inside its repository and not reached
+/// through symbolic links the project's policy skips.
+pub(crate) async fn find_document_file(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repository_id: Option<Uuid>,
+    relative_path: &str,
+) -> Result<(Repo, PathBuf), ApiError> {
+    let extensions = project_extensions(deployment, project_id).await?;
+    let symlinks = Project::find_document_symlink_policy(&deployment.db().pool, project_id).await?;
+    for repo in project_repositories(deployment, project_id, repository_id).await? {
+        let file_path = PathBuf::from(&repo.path).join(relative_path);
+        if !file_path.is_file() {
+            continue;
+        }
+
+        // Security: Ensure the file is within the repository
+        let (Ok(canonical_repo), Ok(canonical_file)) =
+            (repo.path.canonicalize(), file_path.canonicalize())
+        else {
+            continue;
+        };
+        if !canonical_file.starts_with(&canonical_repo) {
+            return Err(ApiError::BadRequest(
+                "Invalid file path: access denied".to_string(),
+            ));
+        }
+        document_store::check_symlinks(symlinks, &repo.path, relative_path)?;
+        require_document_type(&file_path, &extensions)?;
+        return Ok((repo, file_path));
+    }
+    Err(ApiError::BadRequest(format!(
+        "Document '{}' not found in project repositories",
+        relative_path
+    )))
+}
+
+/// Titles derived from a selection are cut off after this many characters
+const MAX_SELECTION_TITLE_CHARS: usize = 80;
+
+/// Byte range of lines `start_line..=end_line` (1-based) in `content`, including the
+/// last line's line break
+fn line_range(content: &str, start_line: u32, end_line: u32) -> Option<(usize, usize)> {
+    let (start_line, end_line) = (start_line as usize, end_line as usize);
+    if start_line == 0 || end_line < start_line {
+        return None;
+    }
+    let mut line_start = 0;
+    let mut start = None;
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        if index + 1 == start_line {
+            start = Some(line_start);
+        }
+        line_start += line.len();
+        if index + 1 == end_line {
+            return start.map(|start| (start, line_start));
+        }
+    }
+    None
+}
+
+/// First line of the selection with markdown heading, quote and list markers removed
+fn selection_title(selection: &str) -> Option<String> {
+    let line = selection
+        .lines()
+        .map(|line| {
+            line.trim_start_matches(|c: char| {
+                matches!(c, '#' | '>' | '-' | '*' | '+') || c.is_whitespace()
+            })
+            .trim_end()
+        })
+        .find(|line| !line.is_empty())?;
+    Some(line.chars().take(MAX_SELECTION_TITLE_CHARS).collect())
+}
+
+/// Link to the document in the app, opening it at the selected lines
+pub(crate) fn document_link(
+    project_id: Uuid,
+    relative_path: &str,
+    start_line: u32,
+    end_line: u32,
+) -> String {
+    format!(
+        "/projects/{}/documents?path={}&lines={}-{}",
+        project_id,
+        urlencoding::encode(relative_path),
+        start_line,
+        end_line
+    )
+}
+
+/// Create a task quoting lines of a document. With `insert_marker`, a link to the task is
+/// added below the selection and committed, so readers of the document find it.
+pub async fn create_document_task(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+    ResponseJson(body): ResponseJson<CreateDocumentTaskRequest>,
+) -> Result<Response, ApiError> {
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?;
+    let decoded_path = validate_relative_path(&decoded_path)?.to_string();
+    let (repo, file_path) = find_document_file(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &decoded_path,
+    )
+    .await?;
+
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let content = reader.read(&repo.path, &decoded_path).await?;
+    let current_hash = content_hash(&content);
+    if body
+        .expected_hash
+        .as_ref()
+        .is_some_and(|hash| *hash != current_hash)
+    {
+        let conflict = DocumentUpdateConflict {
+            current_hash,
+            current_content: content,
+        };
+        let response: ApiResponse<CreateDocumentTaskResponse, DocumentUpdateConflict> =
+            ApiResponse::error_with_data(conflict);
+        return Ok((StatusCode::CONFLICT, ResponseJson(response)).into_response());
+    }
+
+    let (start, end) = line_range(&content, body.start_line, body.end_line).ok_or_else(|| {
+        ApiError::BadRequest(format!(
+            "Lines {}-{} are not in '{}'",
+            body.start_line, body.end_line, decoded_path
+        ))
+    })?;
+    let selection = content[start..end].trim_end_matches(['\r', '\n']);
+
+    let repo_path = PathBuf::from(&repo.path);
+    if body.insert_marker {
+        if body.expected_hash.is_none() {
+            return Err(ApiError::BadRequest(
+                "Send the document's content_hash as expected_hash to insert a task link"
+                    .to_string(),
+            ));
+        }
+        if let Some(violation) =
+            find_read_only_violation(&deployment, project.id, &decoded_path).await?
+        {
+            let response: ApiResponse<CreateDocumentTaskResponse, DocumentReadOnlyViolation> =
+                ApiResponse::error_with_data(violation);
+            return Ok(ResponseJson(response).into_response());
+        }
+        document_approvals::require_unprotected(&deployment, project.id, &decoded_path).await?;
+        document_encryption::require_unencrypted(&deployment, project.id, &decoded_path).await?;
+        if let Some(lock) =
+            DocumentLock::find_active(&deployment.db().pool, repo.id, &decoded_path).await?
+            && body.lock_holder.as_deref().map(str::trim) != Some(lock.holder.as_str())
+        {
+            let response: ApiResponse<CreateDocumentTaskResponse, DocumentLock> =
+                ApiResponse::error_with_data(lock);
+            return Ok((StatusCode::LOCKED, ResponseJson(response)).into_response());
+        }
+        document_branches::require_editable_branch(
+            &deployment,
+            project.id,
+            &repo_path,
+            &decoded_path,
+        )
+        .await?;
+    }
+
+    let title = body
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .map(str::to_string)
+        .or_else(|| selection_title(selection))
+        .unwrap_or_else(|| format!("Follow up on {}", decoded_path));
+    let quote = selection
+        .lines()
+        .map(|line| format!("> {}", line).trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let lines = if body.start_line == body.end_line {
+        format!("line {}", body.start_line)
+    } else {
+        format!("lines {}-{}", body.start_line, body.end_line)
+    };
+    let description = format!(
+        "{}\n\nFrom [{}, {}]({})",
+        quote,
+        decoded_path,
+        lines,
+        document_link(project.id, &decoded_path, body.start_line, body.end_line)
+    );
+
+    let task = Task::create(
+        &deployment.db().pool,
+        &CreateTask::from_title_description(project.id, title, Some(description)),
+        Uuid::new_v4(),
+    )
+    .await?;
+    deployment
+        .track_if_analytics_allowed(
+            "task_created",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": project.id,
+                "has_description": true,
+                "source": "document_selection",
+            }),
+        )
+        .await;
+
+    if !body.insert_marker {
+        return Ok(
+            ResponseJson(ApiResponse::success(CreateDocumentTaskResponse {
+                task,
+                marker_inserted: false,
+                committed: false,
+                content_hash: None,
+            }))
+            .into_response(),
+        );
+    }
+
+    // Put the link in its own paragraph right below the selection
+    let marker = format!(
+        "[Task: {}](/projects/{}/tasks/{})",
+        task.title.replace('[', "\\[").replace(']', "\\]"),
+        project.id,
+        task.id
+    );
+    let mut updated = String::with_capacity(content.len() + marker.len() + 3);
+    updated.push_str(&content[..end]);
+    if content[..end].ends_with('\n') {
+        updated.push_str(&format!("\n{}\n\n", marker));
+    } else {
+        updated.push_str(&format!("\n\n{}\n", marker));
+    }
+    updated.push_str(&content[end..]);
+    tokio::fs::write(&file_path, &updated).await?;
+    deployment.document_index().invalidate(repo.id);
+
+    let committer = DocumentCommitter::load(&deployment, project.id).await?;
+    let commit_message = committer.message(
+        &format!("docs: link {} to task '{}'", decoded_path, task.title),
+        &decoded_path,
+    );
+    let mut warnings = Vec::new();
+    let committed = match committer.commit(&deployment, &repo_path, &commit_message) {
+        Ok(committed) => committed,
+        Err(e) => {
+            tracing::warn!("Failed to auto-commit task link: {}", e);
+            warnings.push(auto_commit_warning("task link", &e));
+            false
+        }
+    };
+
+    Ok(ResponseJson(
+        ApiResponse::success(CreateDocumentTaskResponse {
+            task,
+            marker_inserted: true,
+            committed,
+            content_hash: Some(content_hash(&updated)),
+        })
+        .with_warnings(warnings),
+    )
+    .into_response())
+}
+
+/// Delete a specific document by relative path
+pub async fn delete_document(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(dry_run): Query<DryRunQuery>,
+) -> Result<Response, ApiError> {
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project.id)
+        .await?;
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let symlinks = Project::find_document_symlink_policy(&deployment.db().pool, project.id).await?;
+
+    // Decode the URL-encoded path
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?
+        .to_string();
+
+    // Search for the file in all repositories
+    for repo in repositories {
+        let repo_path = PathBuf::from(&repo.path);
+        let file_path = repo_path.join(&decoded_path);
+
+        // Security: Ensure the file is within the repository
+        let canonical_repo = match repo_path.canonicalize() {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+
+        let canonical_file = match file_path.canonicalize() {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+
+        if !canonical_file.starts_with(&canonical_repo) {
+            return Err(ApiError::BadRequest(
+                "Invalid file path: access denied".to_string(),
+            ));
+        }
+        document_store::check_symlinks(symlinks, &repo_path, &decoded_path)?;
+
+        if !file_path.is_file() {
+            continue;
+        }
+
+        // Only documents can be deleted through this endpoint
+        require_document_type(&file_path, &extensions)?;
+        if let Some(violation) =
+            find_read_only_violation(&deployment, project.id, &decoded_path).await?
+        {
+            let response: ApiResponse<DeleteDocumentResponse, DocumentReadOnlyViolation> =
+                ApiResponse::error_with_data(violation);
+            return Ok(ResponseJson(response).into_response());
+        }
+        document_approvals::require_unprotected(&deployment, project.id, &decoded_path).await?;
+
+        // Ensure we're on a branch documents can be edited on before modifying them
+        let current_branch = if dry_run.dry_run {
+            document_branches::editable_branch(&deployment, project.id, &repo_path).await?
+        } else {
+            document_branches::require_editable_branch(
+                &deployment,
+                project.id,
+                &repo_path,
+                &decoded_path,
+            )
+            .await?
+        };
+
+        let committer = DocumentCommitter::load(&deployment, project.id).await?;
+        let commit_message =
+            committer.message(&format!("docs: delete {}", decoded_path), &decoded_path);
+        if dry_run.dry_run {
+            let content = tokio::fs::read_to_string(&file_path)
+                .await
+                .unwrap_or_default();
+            return Ok(DocumentCommitPreview {
+                repository_id: repo.id,
+                branch: Some(current_branch),
+                commit_message,
+                diff: create_unified_diff(&decoded_path, &content, ""),
+            }
+            .into_response());
+        }
+
+        if let Err(e) = tokio::fs::remove_file(&file_path).await {
+            tracing::error!("Failed to delete file {:?}: {}", file_path, e);
+            return Err(ApiError::BadRequest(format!("Failed to delete file: {}", e)));
+        }
+        tracing::info!("Document deleted: {:?}", file_path);
+        deployment.document_index().invalidate(repo.id);
+
+        // Auto-commit the deletion
+        let mut warnings = Vec::new();
+        let committed = match committer.commit(&deployment, &repo_path, &commit_message) {
+            Ok(true) => {
+                tracing::info!(
+                    "Auto-committed document deletion to branch {:?}: {}",
+                    current_branch,
+                    decoded_path
+                );
+                true
+            }
+            Ok(false) => {
+                tracing::debug!("No changes to commit for deleted document: {}", decoded_path);
+                false
+            }
+            Err(e) => {
+                tracing::warn!("Failed to auto-commit document deletion: {}", e);
+                warnings.push(auto_commit_warning("deletion", &e));
+                false
+            }
+        };
+        webhooks::document_changed(
+            &deployment,
+            project.id,
+            WebhookEvent::DocumentDeleted,
+            repo.id,
+            &decoded_path,
+        );
+        let target = BoardLinkTarget::Document(decoded_path.clone());
+        warnings
+            .extend(board_links::deleted_target_warning(&deployment, project.id, &target).await);
+        if let Err(e) =
+            TaskDocument::delete_by_document(&deployment.db().pool, repo.id, &decoded_path).await
+        {
+            tracing::warn!(
+                "Failed to unrelate tasks from deleted document {}: {}",
+                decoded_path,
+                e
+            );
+        }
+        if let Err(e) =
+            DocumentAnnotation::delete_by_document(&deployment.db().pool, repo.id, &decoded_path)
+                .await
+        {
+            tracing::warn!(
+                "Failed to delete annotations of deleted document {}: {}",
+                decoded_path,
+                e
+            );
+        }
+        if let Err(e) =
+            DocumentTag::delete_by_document(&deployment.db().pool, repo.id, &decoded_path).await
+        {
+            tracing::warn!(
+                "Failed to delete tags of deleted document {}: {}",
+                decoded_path,
+                e
+            );
+        }
+
+        return Ok(ResponseJson(
+            ApiResponse::success(DeleteDocumentResponse {
+                success: true,
+                message: if committed {
+                    t("docs-deleted-committed", &[("branch", &current_branch)])
+                } else {
+                    t("docs-deleted", &[])
+                },
+                branch: Some(current_branch),
+                committed,
+            })
+            .with_warnings(warnings),
+        )
+        .into_response());
+    }
+
+    Err(ApiError::BadRequest(format!(
+        "Document '{}' not found in project repositories",
+        decoded_path
+    )))
+}
+
+/// Create a new folder in the project repository
+pub async fn create_folder(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<CreateFolderRequest>,
+) -> Result<ResponseJson<ApiResponse<CreateFolderResponse, DocumentReadOnlyViolation>>, ApiError>
+{
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project.id)
+        .await?;
+
+    // Get the first repository (primary repository)
+    let repo = repositories
+        .first()
+        .ok_or_else(|| ApiError::BadRequest(t("docs-no-repository", &[])))?;
+
+    let repo_path = PathBuf::from(&repo.path);
 
     if !repo_path.exists() || !repo_path.is_dir() {
         return Err(ApiError::BadRequest(
@@ -498,10 +3011,17 @@ pub async fn create_folder(
             "Invalid path: path traversal not allowed".to_string(),
         ));
     }
+    let roots = document_roots::project_document_roots(&deployment, project.id).await?;
+    require_in_document_roots(&roots, folder_path)?;
+    if let Some(violation) = find_read_only_violation(&deployment, project.id, folder_path).await? {
+        return Ok(ResponseJson(ApiResponse::error_with_data(violation)));
+    }
 
     let full_path = repo_path.join(folder_path);
 
     // Security: Ensure the path is within the repository
+    let symlinks = Project::find_document_symlink_policy(&deployment.db().pool, project.id).await?;
+    document_store::check_symlinks(symlinks, &repo_path, folder_path)?;
     let canonical_repo = repo_path.canonicalize().map_err(|e| {
         ApiError::BadRequest(format!("Failed to resolve repository path: {}", e))
     })?;
@@ -528,16 +3048,18 @@ pub async fn create_folder(
         )));
     }
 
-    // Ensure we're on the main branch before creating folders
-    require_main_branch(&deployment, &repo_path)?;
+    // Ensure we're on a branch documents can be edited on before creating folders
+    document_branches::require_editable_branch(&deployment, project.id, &repo_path, folder_path)
+        .await?;
 
     // Create the folder
-    std::fs::create_dir_all(&full_path).map_err(|e| {
+    tokio::fs::create_dir_all(&full_path).await.map_err(|e| {
         tracing::error!("Failed to create folder {:?}: {}", full_path, e);
         ApiError::BadRequest(format!("Failed to create folder: {}", e))
     })?;
 
     tracing::info!("Folder created: {:?}", full_path);
+    deployment.document_index().invalidate(repo.id);
 
     Ok(ResponseJson(ApiResponse::success(CreateFolderResponse {
         success: true,
@@ -546,21 +3068,239 @@ pub async fn create_folder(
     })))
 }
 
-/// Create a new markdown file in the project repository
+/// Delete a folder and commit the deletion. Only empty folders are deleted unless
+/// `recursive` is set; then nothing is deleted unless every file inside may be, i.e. none
+/// is read-only, protected or locked.
+pub async fn delete_folder(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(repository): Query<RepositoryQuery>,
+    Query(query): Query<DeleteFolderQuery>,
+) -> Result<Response, ApiError> {
+    let folder_path = validate_relative_path(query.path.trim_matches('/'))?.to_string();
+    if !is_scanned_path(&folder_path) {
+        return Err(ApiError::BadRequest(format!(
+            "'{}' is a hidden or excluded folder and can't be deleted",
+            folder_path
+        )));
+    }
+    let roots = document_roots::project_document_roots(&deployment, project.id).await?;
+    require_in_document_roots(&roots, &folder_path)?;
+
+    // Symlinked folders aren't followed, so only the link's target could be deleted
+    let (repo, full_path) = project_repositories(&deployment, project.id, repository.repository_id)
+        .await?
+        .into_iter()
+        .find_map(|repo| {
+            let full_path = PathBuf::from(&repo.path).join(&folder_path);
+            let is_dir = full_path.symlink_metadata().is_ok_and(|meta| meta.is_dir());
+            is_dir.then_some((repo, full_path))
+        })
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Folder '{}' not found in project repositories",
+                folder_path
+            ))
+        })?;
+    let repo_path = PathBuf::from(&repo.path);
+
+    // Security: Ensure the folder is within the repository and isn't the repository itself
+    let canonical_repo = repo_path.canonicalize()?;
+    let canonical_folder = full_path.canonicalize()?;
+    if !canonical_folder.starts_with(&canonical_repo) || canonical_folder == canonical_repo {
+        return Err(ApiError::BadRequest(
+            "Invalid path: access denied".to_string(),
+        ));
+    }
+
+    let files = if query.recursive {
+        let store_path = repo_path.clone();
+        let store_folder = folder_path.clone();
+        // Symbolic links are deleted themselves, not what they point at
+        tokio::task::spawn_blocking(move || {
+            document_store::open(
+                &store_path,
+                DEFAULT_DOCS_BRANCH,
+                DocumentSymlinkPolicy::Skip,
+            )
+            .list(&store_folder, &[])
+        })
+        .await
+        .map_err(|e| ApiError::Io(std::io::Error::other(e)))??
+    } else {
+        if std::fs::read_dir(&full_path)?.next().is_some() {
+            return Err(ApiError::Conflict(format!(
+                "Folder '{}' is not empty; delete it recursively to delete its contents too",
+                folder_path
+            )));
+        }
+        Vec::new()
+    };
+
+    // Every file inside is checked before anything is deleted
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let mut deleted_documents = Vec::new();
+    let checked_paths = std::iter::once(folder_path.clone())
+        .chain(files.into_iter().map(|file| file.relative_path));
+    for relative_path in checked_paths {
+        if let Some(violation) =
+            find_read_only_violation(&deployment, project.id, &relative_path).await?
+        {
+            let response: ApiResponse<DeleteFolderResponse, DocumentReadOnlyViolation> =
+                ApiResponse::error_with_data(violation);
+            return Ok(ResponseJson(response).into_response());
+        }
+        if relative_path == folder_path {
+            continue;
+        }
+        document_approvals::require_unprotected(&deployment, project.id, &relative_path).await?;
+        if let Some(lock) =
+            DocumentLock::find_active(&deployment.db().pool, repo.id, &relative_path).await?
+        {
+            let response: ApiResponse<DeleteFolderResponse, DocumentLock> =
+                ApiResponse::error_with_data(lock);
+            return Ok((StatusCode::LOCKED, ResponseJson(response)).into_response());
+        }
+        if allowed_file_type(Path::new(&relative_path), &extensions).is_some() {
+            deleted_documents.push(relative_path);
+        }
+    }
+    deleted_documents.sort();
+
+    // Ensure we're on a branch documents can be edited on before deleting folders
+    let current_branch = document_branches::require_editable_branch(
+        &deployment,
+        project.id,
+        &repo_path,
+        &folder_path,
+    )
+    .await?;
+
+    let removed = if query.recursive {
+        tokio::fs::remove_dir_all(&full_path).await
+    } else {
+        tokio::fs::remove_dir(&full_path).await
+    };
+    removed.map_err(|e| {
+        tracing::error!("Failed to delete folder {:?}: {}", full_path, e);
+        ApiError::BadRequest(format!("Failed to delete folder: {}", e))
+    })?;
+    tracing::info!("Folder deleted: {:?}", full_path);
+    deployment.document_index().invalidate(repo.id);
+
+    // Auto-commit the deletion; empty folders aren't tracked, so there may be nothing
+    let committer = DocumentCommitter::load(&deployment, project.id).await?;
+    let commit_message = committer.message(
+        &format!("docs: delete folder {}", folder_path),
+        &folder_path,
+    );
+    let mut warnings = Vec::new();
+    let committed = match committer.commit(&deployment, &repo_path, &commit_message) {
+        Ok(committed) => committed,
+        Err(e) => {
+            tracing::warn!("Failed to auto-commit folder deletion: {}", e);
+            warnings.push(auto_commit_warning("folder deletion", &e));
+            false
+        }
+    };
+    webhooks::notify(
+        &deployment,
+        deleted_documents
+            .iter()
+            .map(|document| {
+                WebhookPayload::document(
+                    WebhookEvent::DocumentDeleted,
+                    project.id,
+                    repo.id,
+                    document,
+                )
+            })
+            .collect(),
+    );
+    for document in &deleted_documents {
+        let target = BoardLinkTarget::Document(document.clone());
+        warnings
+            .extend(board_links::deleted_target_warning(&deployment, project.id, &target).await);
+    }
+
+    Ok(ResponseJson(
+        ApiResponse::success(DeleteFolderResponse {
+            path: folder_path,
+            deleted_documents,
+            branch: current_branch,
+            committed,
+        })
+        .with_warnings(warnings),
+    )
+    .into_response())
+}
+
+/// Create a new document in the project repository
 pub async fn create_file(
     State(deployment): State<DeploymentImpl>,
     Extension(project): Extension<Project>,
+    Query(dry_run): Query<DryRunQuery>,
     ResponseJson(body): ResponseJson<CreateFileRequest>,
-) -> Result<ResponseJson<ApiResponse<CreateFileResponse>>, ApiError> {
+) -> Result<Response, ApiError> {
+    create_new_document(deployment, project, dry_run, body, None).await
+}
+
+/// Copy a document to a new path, e.g. to start this sprint's plan from the last one.
+/// The copy is created like any new document, see [`create_file`].
+pub async fn copy_document(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+    Query(dry_run): Query<DryRunQuery>,
+    ResponseJson(body): ResponseJson<CopyDocumentRequest>,
+) -> Result<Response, ApiError> {
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?;
+    let decoded_path = validate_relative_path(&decoded_path)?.to_string();
+    let (repo, file_path) = find_document_file(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &decoded_path,
+    )
+    .await?;
+    if document_file_type(Path::new(body.to.trim())) != document_file_type(&file_path) {
+        return Err(ApiError::BadRequest(
+            "Destination must keep the document's file type".to_string(),
+        ));
+    }
+
+    // Copy the plaintext, so the copy is encrypted only if its own folder is
+    let reader = DocumentReader::load(&deployment, project.id).await?;
+    let content = reader.read(&repo.path, &decoded_path).await?;
+    let body = CreateFileRequest {
+        path: body.to,
+        content: Some(content),
+        template_id: None,
+        variables: None,
+    };
+    create_new_document(deployment, project, dry_run, body, Some(&decoded_path)).await
+}
+
+/// Create the document `body` describes in the primary repository and commit it.
+/// `copied_from` is the path of the document it copies, if any.
+async fn create_new_document(
+    deployment: DeploymentImpl,
+    project: Project,
+    dry_run: DryRunQuery,
+    body: CreateFileRequest,
+    copied_from: Option<&str>,
+) -> Result<Response, ApiError> {
     let repositories = deployment
         .project()
         .get_repositories(&deployment.db().pool, project.id)
         .await?;
 
     // Get the first repository (primary repository)
-    let repo = repositories.first().ok_or_else(|| {
-        ApiError::BadRequest("No repository found for this project".to_string())
-    })?;
+    let repo = repositories
+        .first()
+        .ok_or_else(|| ApiError::BadRequest(t("docs-no-repository", &[])))?;
 
     let repo_path = PathBuf::from(&repo.path);
 
@@ -581,20 +3321,53 @@ pub async fn create_file(
         ));
     }
 
-    // Validate file extension (only markdown allowed)
-    let path_obj = Path::new(file_path_str);
-    match path_obj.extension().and_then(|e| e.to_str()) {
-        Some("md") | Some("markdown") => {}
-        _ => {
+    // Validate file extension against the project's document formats
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let file_type = require_document_type(Path::new(file_path_str), &extensions)?;
+    let roots = document_roots::project_document_roots(&deployment, project.id).await?;
+    require_in_document_roots(&roots, file_path_str)?;
+    if let Some(violation) =
+        find_read_only_violation(&deployment, project.id, file_path_str).await?
+    {
+        let response: ApiResponse<CreateFileResponse, DocumentReadOnlyViolation> =
+            ApiResponse::error_with_data(violation);
+        return Ok(ResponseJson(response).into_response());
+    }
+    let committer = DocumentCommitter::load(&deployment, project.id).await?;
+    let template_id = body
+        .template_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty());
+    let content = match (body.content, template_id) {
+        (Some(_), Some(_)) => {
             return Err(ApiError::BadRequest(
-                "Only markdown files (.md, .markdown) are allowed".to_string(),
-            ))
+                "Give either content or a template, not both".to_string(),
+            ));
+        }
+        (None, Some(template_id)) => {
+            document_templates::render_document_template(
+                &deployment,
+                project.id,
+                &repo_path,
+                template_id,
+                file_path_str,
+                body.variables.unwrap_or_default(),
+                &committer,
+            )
+            .await?
         }
+        (content, None) => content.unwrap_or_default(),
+    };
+    if !content.is_empty() {
+        board_links::require_valid_links(&deployment, project.id, &content, None).await?;
     }
 
     let full_path = repo_path.join(file_path_str);
 
     // Security: Ensure the path is within the repository
+    let symlinks = Project::find_document_symlink_policy(&deployment.db().pool, project.id).await?;
+    document_store::check_symlinks(symlinks, &repo_path, file_path_str)?;
     let canonical_repo = repo_path.canonicalize().map_err(|e| {
         ApiError::BadRequest(format!("Failed to resolve repository path: {}", e))
     })?;
@@ -610,78 +3383,1081 @@ pub async fn create_file(
                     "Invalid path: access denied".to_string(),
                 ));
             }
-        } else {
+        } else if !dry_run.dry_run {
             // Create parent directories if they don't exist
-            std::fs::create_dir_all(parent).map_err(|e| {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
                 ApiError::BadRequest(format!("Failed to create parent directories: {}", e))
             })?;
         }
     }
 
-    // Check if file already exists
-    if full_path.exists() {
-        return Err(ApiError::BadRequest(format!(
-            "File '{}' already exists",
-            file_path_str
-        )));
+    // Check if file already exists
+    if full_path.exists() {
+        return Err(ApiError::BadRequest(format!(
+            "File '{}' already exists",
+            file_path_str
+        )));
+    }
+
+    // Ensure we're on a branch documents can be edited on before creating documents
+    let current_branch = if dry_run.dry_run {
+        document_branches::editable_branch(&deployment, project.id, &repo_path).await?
+    } else {
+        document_branches::require_editable_branch(
+            &deployment,
+            project.id,
+            &repo_path,
+            file_path_str,
+        )
+        .await?
+    };
+
+    let subject = match (copied_from, template_id) {
+        (Some(source), _) => format!("docs: copy {} to {}", source, file_path_str),
+        (None, Some(template_id)) => format!(
+            "docs: create {} from template {}",
+            file_path_str, template_id
+        ),
+        (None, None) => format!("docs: create {}", file_path_str),
+    };
+    let commit_message = committer.message(&subject, file_path_str);
+    if dry_run.dry_run {
+        return Ok(DocumentCommitPreview {
+            repository_id: repo.id,
+            branch: Some(current_branch),
+            commit_message,
+            diff: create_unified_diff(file_path_str, "", &content),
+        }
+        .into_response());
+    }
+
+    // Write content to file, encrypted if it is in an encrypted folder
+    let stored = DocumentKeyring::load(&deployment, project.id)
+        .await?
+        .encrypt(file_path_str, &content)?;
+    tokio::fs::write(&full_path, &stored).await.map_err(|e| {
+        tracing::error!("Failed to create file {:?}: {}", full_path, e);
+        ApiError::BadRequest(format!("Failed to create file: {}", e))
+    })?;
+
+    tracing::info!("File created: {:?}", full_path);
+    deployment.document_index().invalidate(repo.id);
+
+    // Auto-commit the new file
+    let mut warnings = Vec::new();
+    let committed = match committer.commit(&deployment, &repo_path, &commit_message) {
+        Ok(true) => {
+            tracing::info!(
+                "Auto-committed new document to branch {:?}: {}",
+                current_branch,
+                file_path_str
+            );
+            true
+        }
+        Ok(false) => {
+            tracing::debug!("No changes to commit for new document: {}", file_path_str);
+            false
+        }
+        Err(e) => {
+            tracing::warn!("Failed to auto-commit new document: {}", e);
+            warnings.push(auto_commit_warning("new document", &e));
+            false
+        }
+    };
+
+    webhooks::document_changed(
+        &deployment,
+        project.id,
+        WebhookEvent::DocumentCreated,
+        repo.id,
+        file_path_str,
+    );
+
+    // Get file name
+    let name = full_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_path_str.to_string());
+    let front_matter = markdown_front_matter(&file_type, &content);
+
+    Ok(ResponseJson(
+        ApiResponse::success(CreateFileResponse {
+            success: true,
+            message: if committed {
+                t("docs-created-committed", &[("branch", &current_branch)])
+            } else {
+                t("docs-created", &[])
+            },
+            metadata: DocumentMetadata {
+                repository_id: repo.id,
+                name,
+                relative_path: file_path_str.to_string(),
+                absolute_path: full_path.to_string_lossy().to_string(),
+                file_type,
+                size_bytes: stored.len() as u64,
+                front_matter,
+                lock: None,
+                tags: Vec::new(),
+            },
+            branch: Some(current_branch),
+            committed,
+        })
+        .with_warnings(warnings),
+    )
+    .into_response())
+}
+
+/// Check that a new document may be created at `relative_path`: its extension is
+/// enabled, it lies in the document folders and it is neither read-only nor protected
+async fn check_new_document_path(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    extensions: &[String],
+    roots: &[String],
+    relative_path: &str,
+) -> Result<(), ApiError> {
+    require_document_type(Path::new(relative_path), extensions)?;
+    require_in_document_roots(roots, relative_path)?;
+    if let Some(violation) = find_read_only_violation(deployment, project_id, relative_path).await?
+    {
+        return Err(ApiError::Forbidden(format!(
+            "'{}' matches the read-only path '{}'",
+            violation.relative_path, violation.pattern
+        )));
+    }
+    document_approvals::require_unprotected(deployment, project_id, relative_path).await
+}
+
+/// Warning for a seed document that was not written to a new project's repository
+fn seed_document_warning(relative_path: &str, reason: &impl std::fmt::Display) -> ApiWarning {
+    ApiWarning::new(
+        "seed_document_skipped",
+        format!("The seed document '{relative_path}' was not written: {reason}"),
+    )
+}
+
+/// Write the project's seed documents into each of its repositories that doesn't have
+/// them yet. They pass the checks of [`create_file`], are encrypted in encrypted folders
+/// and are committed together, one commit per repository. A seed document a check
+/// rejects is skipped with a warning.
+pub(crate) async fn write_seed_documents(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+) -> Result<Vec<ApiWarning>, ApiError> {
+    let pool = &deployment.db().pool;
+    let extensions = project_extensions(deployment, project_id).await?;
+    let roots = document_roots::project_document_roots(deployment, project_id).await?;
+    let mut warnings = Vec::new();
+    let mut documents = Vec::new();
+    for document in ProjectSeedDocument::find_by_project_id(pool, project_id).await? {
+        let relative_path = document.path.trim().to_string();
+        if seed_document_path(&relative_path).is_none() {
+            warnings.push(seed_document_warning(
+                &relative_path,
+                &"the path must stay inside the repository",
+            ));
+            continue;
+        }
+        match check_new_document_path(deployment, project_id, &extensions, &roots, &relative_path)
+            .await
+        {
+            Ok(()) => documents.push((relative_path, document.content)),
+            Err(e) => warnings.push(seed_document_warning(&relative_path, &e)),
+        }
+    }
+    if documents.is_empty() {
+        return Ok(warnings);
+    }
+
+    let symlinks = Project::find_document_symlink_policy(pool, project_id).await?;
+    let committer = DocumentCommitter::load(deployment, project_id).await?;
+    let keyring = DocumentKeyring::load(deployment, project_id).await?;
+    for repo in deployment
+        .project()
+        .get_repositories(pool, project_id)
+        .await?
+    {
+        let repo_path = PathBuf::from(&repo.path);
+        let canonical_repo = repo_path.canonicalize()?;
+        let mut pending = Vec::new();
+        for (relative_path, content) in &documents {
+            let full_path = repo_path.join(relative_path);
+            if full_path.exists() {
+                continue;
+            }
+            match document_store::check_symlinks(symlinks, &repo_path, relative_path) {
+                Ok(()) => pending.push((relative_path.as_str(), content.as_str(), full_path)),
+                Err(e) => warnings.push(seed_document_warning(relative_path, &e)),
+            }
+        }
+        if pending.is_empty() {
+            continue;
+        }
+
+        let branch = match document_branches::require_editable_branch(
+            deployment,
+            project_id,
+            &repo_path,
+            "seed-documents",
+        )
+        .await
+        {
+            Ok(branch) => branch,
+            Err(e) => {
+                for (relative_path, ..) in pending {
+                    warnings.push(seed_document_warning(relative_path, &e));
+                }
+                continue;
+            }
+        };
+
+        let mut written = Vec::with_capacity(pending.len());
+        for (relative_path, content, full_path) in pending {
+            let stored = match keyring.encrypt(relative_path, content) {
+                Ok(stored) => stored,
+                Err(e) => {
+                    warnings.push(seed_document_warning(relative_path, &e));
+                    continue;
+                }
+            };
+            if let Some(parent) = full_path.parent() {
+                if let Err(e) = tokio::fs::create_dir_all(parent).await {
+                    warnings.push(seed_document_warning(relative_path, &e));
+                    continue;
+                }
+                if !parent.canonicalize()?.starts_with(&canonical_repo) {
+                    warnings.push(seed_document_warning(
+                        relative_path,
+                        &"the path is outside the repository",
+                    ));
+                    continue;
+                }
+            }
+            if let Err(e) = tokio::fs::write(&full_path, &stored).await {
+                warnings.push(seed_document_warning(relative_path, &e));
+                continue;
+            }
+            written.push(relative_path);
+        }
+        if written.is_empty() {
+            continue;
+        }
+        deployment.document_index().invalidate(repo.id);
+
+        let message = committer.message("docs: add seed documents", &written.join(", "));
+        match committer.commit(deployment, &repo_path, &message) {
+            Ok(_) => tracing::info!(
+                "Wrote {} seed documents to {} on branch {}",
+                written.len(),
+                repo_path.display(),
+                branch
+            ),
+            Err(e) => {
+                tracing::warn!("Failed to auto-commit seed documents: {}", e);
+                warnings.push(auto_commit_warning("seed documents", &e));
+            }
+        }
+        for relative_path in written {
+            webhooks::document_changed(
+                deployment,
+                project_id,
+                WebhookEvent::DocumentCreated,
+                repo.id,
+                relative_path,
+            );
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// SHA-256 of a document's content, hex encoded
+pub(crate) fn content_hash(content: &str) -> String {
+    let mut hash = String::with_capacity(64);
+    for byte in Sha256::digest(content.as_bytes()) {
+        use std::fmt::Write;
+        let _ = write!(hash, "{:02x}", byte);
+    }
+    hash
+}
+
+/// The content hash an update is based on, from the body or an `If-Match` header
+fn expected_content_hash(body: &UpdateDocumentRequest, headers: &HeaderMap) -> Option<String> {
+    let if_match = || {
+        let value = headers.get(header::IF_MATCH)?.to_str().ok()?.trim();
+        Some(value.trim_start_matches("W/").trim_matches('"').to_string())
+    };
+    body.expected_hash
+        .as_deref()
+        .map(str::trim)
+        .map(str::to_string)
+        .or_else(if_match)
+        .filter(|hash| !hash.is_empty())
+}
+
+/// Front matter fields of a markdown document
+fn markdown_front_matter(
+    file_type: &DocumentFileType,
+    content: &str,
+) -> Option<Map<String, Value>> {
+    (*file_type == DocumentFileType::Markdown)
+        .then(|| front_matter::parse(content))
+        .flatten()
+}
+
+fn markdown_outline(file_type: &DocumentFileType, content: &str) -> Vec<DocumentHeading> {
+    if *file_type == DocumentFileType::Markdown {
+        markdown_html::outline(content)
+    } else {
+        Vec::new()
+    }
+}
+
+/// Like [`markdown_front_matter`], reading only the head of the file
+fn read_front_matter(file_type: &DocumentFileType, path: &Path) -> Option<Map<String, Value>> {
+    (*file_type == DocumentFileType::Markdown)
+        .then(|| front_matter::read(path))
+        .flatten()
+}
+
+/// Warning for a document change that was saved but could not be auto-committed
+pub(crate) fn auto_commit_warning(change: &str, error: &impl std::fmt::Display) -> ApiWarning {
+    ApiWarning::new(
+        "auto_commit_failed",
+        format!("The {change} was saved but could not be committed: {error}"),
+    )
+}
+
+/// Validate a user-supplied relative path: non-empty, relative and without traversal
+pub(crate) fn validate_relative_path(path: &str) -> Result<&str, ApiError> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err(ApiError::BadRequest("Path cannot be empty".to_string()));
+    }
+    if path.contains("..") || Path::new(path).is_absolute() {
+        return Err(ApiError::BadRequest(
+            "Invalid path: path traversal not allowed".to_string(),
+        ));
+    }
+    Ok(path)
+}
+
+fn document_extension(path: &Path) -> Option<String> {
+    path.extension()?.to_str().map(str::to_ascii_lowercase)
+}
+
+pub(crate) fn document_file_type(path: &Path) -> Option<DocumentFileType> {
+    let extension = document_extension(path)?;
+    SUPPORTED_EXTENSIONS
+        .iter()
+        .find(|(supported, _)| *supported == extension)
+        .map(|(_, file_type)| file_type.clone())
+}
+
+/// File type of `path` if its extension is supported and enabled in `extensions`
+pub(crate) fn allowed_file_type(path: &Path, extensions: &[String]) -> Option<DocumentFileType> {
+    let extension = document_extension(path)?;
+    if !extensions.contains(&extension) {
+        return None;
+    }
+    document_file_type(path)
+}
+
+pub(crate) fn require_document_type(
+    path: &Path,
+    extensions: &[String],
+) -> Result<DocumentFileType, ApiError> {
+    allowed_file_type(path, extensions).ok_or_else(|| {
+        let enabled: Vec<String> = extensions.iter().map(|e| format!(".{e}")).collect();
+        ApiError::BadRequest(format!(
+            "Unsupported document type. Enabled for this project: {}",
+            enabled.join(", ")
+        ))
+    })
+}
+
+fn supported_extensions() -> Vec<String> {
+    SUPPORTED_EXTENSIONS
+        .iter()
+        .map(|(extension, _)| extension.to_string())
+        .collect()
+}
+
+/// Optional `?repository_id=` restricting a document request to one repository, for
+/// projects whose repositories contain documents with the same relative path
+#[derive(Debug, Deserialize)]
+pub struct RepositoryQuery {
+    pub repository_id: Option<Uuid>,
+}
+
+/// The project's repositories, or just `repository_id` if given
+pub(crate) async fn project_repositories(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repository_id: Option<Uuid>,
+) -> Result<Vec<Repo>, ApiError> {
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project_id)
+        .await?;
+    let Some(repository_id) = repository_id else {
+        return Ok(repositories);
+    };
+    let repo = repositories
+        .into_iter()
+        .find(|repo| repo.id == repository_id)
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Repository {} does not belong to this project",
+                repository_id
+            ))
+        })?;
+    Ok(vec![repo])
+}
+
+/// The project's allow-list of document extensions, or every supported extension
+pub(crate) async fn project_extensions(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+) -> Result<Vec<String>, ApiError> {
+    Ok(Project::find_document_extensions(&deployment.db().pool, project_id)
+        .await?
+        .unwrap_or_else(supported_extensions))
+}
+
+pub async fn get_document_extensions(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentExtensions>>, ApiError> {
+    let configured = Project::find_document_extensions(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentExtensions {
+        is_default: configured.is_none(),
+        extensions: configured.unwrap_or_else(supported_extensions),
+        supported: supported_extensions(),
+    })))
+}
+
+/// Set which document extensions the project shows, or reset to every supported one
+pub async fn update_document_extensions(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<UpdateDocumentExtensions>,
+) -> Result<ResponseJson<ApiResponse<DocumentExtensions>>, ApiError> {
+    let supported = supported_extensions();
+    let extensions = match body.extensions {
+        Some(requested) => {
+            let mut extensions = Vec::with_capacity(requested.len());
+            for extension in requested {
+                let extension = extension.trim().trim_start_matches('.').to_ascii_lowercase();
+                if !supported.contains(&extension) {
+                    return Err(ApiError::BadRequest(format!(
+                        "Unsupported document extension '.{}'",
+                        extension
+                    )));
+                }
+                if !extensions.contains(&extension) {
+                    extensions.push(extension);
+                }
+            }
+            if extensions.is_empty() {
+                return Err(ApiError::BadRequest(
+                    "Enable at least one document extension".to_string(),
+                ));
+            }
+            Some(extensions)
+        }
+        None => None,
+    };
+
+    Project::set_document_extensions(&deployment.db().pool, project.id, extensions.as_deref())
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentExtensions {
+        is_default: extensions.is_none(),
+        extensions: extensions.unwrap_or_else(|| supported.clone()),
+        supported,
+    })))
+}
+
+/// Compile a document path glob, as used for read-only paths and code references.
+/// `*` stays within one folder; `**` crosses folders.
+pub(crate) fn path_glob(pattern: &str) -> Result<GlobMatcher, globset::Error> {
+    Ok(GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()?
+        .compile_matcher())
+}
+
+/// The read-only glob covering `relative_path`, if the project has one
+pub(crate) async fn find_read_only_violation(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    relative_path: &str,
+) -> Result<Option<DocumentReadOnlyViolation>, ApiError> {
+    let patterns =
+        Project::find_document_read_only_paths(&deployment.db().pool, project_id).await?;
+    let relative_path = relative_path.trim_matches('/');
+    Ok(patterns
+        .into_iter()
+        .find(|pattern| path_glob(pattern).is_ok_and(|glob| glob.is_match(relative_path)))
+        .map(|pattern| DocumentReadOnlyViolation {
+            relative_path: relative_path.to_string(),
+            pattern,
+        }))
+}
+
+pub async fn get_document_read_only_paths(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentReadOnlyPaths>>, ApiError> {
+    let patterns =
+        Project::find_document_read_only_paths(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentReadOnlyPaths { patterns })))
+}
+
+/// Replace the project's read-only path globs; an empty list makes every path writable
+pub async fn update_document_read_only_paths(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<DocumentReadOnlyPaths>,
+) -> Result<ResponseJson<ApiResponse<DocumentReadOnlyPaths>>, ApiError> {
+    let mut patterns = Vec::with_capacity(body.patterns.len());
+    for pattern in body.patterns {
+        let pattern = pattern.trim().trim_start_matches('/').to_string();
+        if pattern.is_empty() {
+            continue;
+        }
+        path_glob(&pattern).map_err(|e| {
+            ApiError::BadRequest(format!("Invalid read-only pattern '{}': {}", pattern, e))
+        })?;
+        if !patterns.contains(&pattern) {
+            patterns.push(pattern);
+        }
+    }
+
+    Project::set_document_read_only_paths(&deployment.db().pool, project.id, &patterns).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentReadOnlyPaths { patterns })))
+}
+
+pub async fn get_document_scan_limits(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentScanLimits>>, ApiError> {
+    let limits = Project::find_document_scan_limits(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(limits)))
+}
+
+/// Set how far document scans of the project may go, or reset to the defaults
+pub async fn update_document_scan_limits(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<UpdateDocumentScanLimits>,
+) -> Result<ResponseJson<ApiResponse<DocumentScanLimits>>, ApiError> {
+    if let Some(limits) = &body.limits
+        && (limits.max_files == 0 || limits.max_depth == 0 || limits.max_seconds == 0)
+    {
+        return Err(ApiError::BadRequest(
+            "Scan limits must be at least 1".to_string(),
+        ));
+    }
+
+    let pool = &deployment.db().pool;
+    Project::set_document_scan_limits(pool, project.id, body.limits.as_ref()).await?;
+
+    // Rebuild the document index under the new limits on the next listing
+    for repo in deployment.project().get_repositories(pool, project.id).await? {
+        deployment.document_index().unwatch(repo.id);
+    }
+
+    Ok(ResponseJson(ApiResponse::success(body.limits.unwrap_or_default())))
+}
+
+pub async fn get_document_read_limit(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentReadLimit>>, ApiError> {
+    let max_bytes =
+        Project::find_document_max_read_bytes(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentReadLimit {
+        max_bytes,
+    })))
+}
+
+/// Set the largest document returned with its content, or reset to the default
+pub async fn update_document_read_limit(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<UpdateDocumentReadLimit>,
+) -> Result<ResponseJson<ApiResponse<DocumentReadLimit>>, ApiError> {
+    if body.max_bytes == Some(0) {
+        return Err(ApiError::BadRequest(
+            "Read limit must be at least 1 byte".to_string(),
+        ));
+    }
+    Project::set_document_max_read_bytes(&deployment.db().pool, project.id, body.max_bytes).await?;
+    Ok(ResponseJson(ApiResponse::success(DocumentReadLimit {
+        max_bytes: body.max_bytes.unwrap_or(DEFAULT_DOCUMENT_MAX_READ_BYTES),
+    })))
+}
+
+/// Move or rename a document with `git mv` so its history follows it
+pub async fn move_document(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(dry_run): Query<DryRunQuery>,
+    ResponseJson(body): ResponseJson<MoveDocumentRequest>,
+) -> Result<Response, ApiError> {
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project.id)
+        .await?;
+
+    // Get the first repository (primary repository)
+    let repo = repositories
+        .first()
+        .ok_or_else(|| ApiError::BadRequest(t("docs-no-repository", &[])))?;
+
+    let repo_path = PathBuf::from(&repo.path);
+    let canonical_repo = repo_path
+        .canonicalize()
+        .map_err(|e| ApiError::BadRequest(format!("Failed to resolve repository path: {}", e)))?;
+
+    let from = validate_relative_path(&body.from)?;
+    let to = validate_relative_path(&body.to)?;
+    if from == to {
+        return Err(ApiError::BadRequest(
+            "Source and destination are the same".to_string(),
+        ));
+    }
+    // Documents can't be moved out of sight of the document listing
+    let roots = document_roots::project_document_roots(&deployment, project.id).await?;
+    require_in_document_roots(&roots, to)?;
+    for path in [from, to] {
+        if let Some(violation) = find_read_only_violation(&deployment, project.id, path).await? {
+            let response: ApiResponse<MoveDocumentResponse, DocumentReadOnlyViolation> =
+                ApiResponse::error_with_data(violation);
+            return Ok(ResponseJson(response).into_response());
+        }
+    }
+    document_approvals::require_unprotected(&deployment, project.id, from).await?;
+    document_approvals::require_unprotected(&deployment, project.id, to).await?;
+    // Moving would take encrypted content out of its folder, or plaintext into one
+    document_encryption::require_unencrypted(&deployment, project.id, from).await?;
+    document_encryption::require_unencrypted(&deployment, project.id, to).await?;
+
+    // Security: Ensure the source is a document within the repository
+    let source = repo_path.join(from);
+    let canonical_source = source
+        .canonicalize()
+        .map_err(|_| ApiError::BadRequest(format!("Document '{}' not found", from)))?;
+    if !canonical_source.starts_with(&canonical_repo) || !source.is_file() {
+        return Err(ApiError::BadRequest(
+            "Invalid source path: access denied".to_string(),
+        ));
+    }
+    let extensions = project_extensions(&deployment, project.id).await?;
+    let source_type = require_document_type(&source, &extensions)?;
+
+    let destination = repo_path.join(to);
+    if document_file_type(&destination) != Some(source_type.clone()) {
+        return Err(ApiError::BadRequest(
+            "Destination must keep the document's file type".to_string(),
+        ));
+    }
+    if destination.exists() {
+        return Err(ApiError::BadRequest(format!("File '{}' already exists", to)));
+    }
+
+    // Ensure we're on a branch documents can be edited on before modifying them
+    let current_branch = if dry_run.dry_run {
+        document_branches::editable_branch(&deployment, project.id, &repo_path).await?
+    } else {
+        document_branches::require_editable_branch(&deployment, project.id, &repo_path, to).await?
+    };
+
+    let committer = DocumentCommitter::load(&deployment, project.id).await?;
+    let commit_message = committer.message(&format!("docs: move {} -> {}", from, to), to);
+    if dry_run.dry_run {
+        return Ok(DocumentCommitPreview {
+            repository_id: repo.id,
+            branch: Some(current_branch),
+            commit_message,
+            diff: format!(
+                "diff --git a/{from} b/{to}\nsimilarity index 100%\n\
+                 rename from {from}\nrename to {to}\n"
+            ),
+        }
+        .into_response());
     }
 
-    // Ensure we're on the main branch before creating documents
-    let current_branch = require_main_branch(&deployment, &repo_path)?;
+    if let Some(parent) = destination.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(|e| {
+            ApiError::BadRequest(format!("Failed to create parent directories: {}", e))
+        })?;
+        let canonical_parent = parent
+            .canonicalize()
+            .map_err(|e| ApiError::BadRequest(format!("Failed to resolve parent path: {}", e)))?;
+        if !canonical_parent.starts_with(&canonical_repo) {
+            return Err(ApiError::BadRequest(
+                "Invalid destination path: access denied".to_string(),
+            ));
+        }
+    }
 
-    // Write content to file
-    let content = body.content.unwrap_or_default();
-    std::fs::write(&full_path, &content).map_err(|e| {
-        tracing::error!("Failed to create file {:?}: {}", full_path, e);
-        ApiError::BadRequest(format!("Failed to create file: {}", e))
-    })?;
+    // Untracked files have no history to keep, so a plain rename is enough
+    if let Err(e) = deployment.git_backend().move_path(&repo_path, from, to) {
+        tracing::debug!("git mv failed ({}), falling back to rename", e);
+        tokio::fs::rename(&source, &destination).await.map_err(|e| {
+            tracing::error!("Failed to move {:?} to {:?}: {}", source, destination, e);
+            ApiError::BadRequest(format!("Failed to move file: {}", e))
+        })?;
+    }
 
-    tracing::info!("File created: {:?}", full_path);
+    tracing::info!("Document moved: {} -> {}", from, to);
+    deployment.document_index().invalidate(repo.id);
 
-    // Auto-commit the new file
-    let commit_message = format!("docs: create {}", file_path_str);
-    let committed = match deployment.git().commit(&repo_path, &commit_message) {
+    // Auto-commit the move
+    let mut warnings = Vec::new();
+    let committed = match committer.commit(&deployment, &repo_path, &commit_message) {
         Ok(true) => {
             tracing::info!(
-                "Auto-committed new document to branch {:?}: {}",
+                "Auto-committed document move to branch {:?}: {} -> {}",
                 current_branch,
-                file_path_str
+                from,
+                to
             );
             true
         }
         Ok(false) => {
-            tracing::debug!("No changes to commit for new document: {}", file_path_str);
+            tracing::debug!("No changes to commit for moved document: {}", to);
             false
         }
         Err(e) => {
-            tracing::warn!("Failed to auto-commit new document: {}", e);
+            tracing::warn!("Failed to auto-commit document move: {}", e);
+            warnings.push(auto_commit_warning("move", &e));
             false
         }
     };
+    webhooks::documents_moved(&deployment, project.id, repo.id, [(from, to)]);
+    // Keep `vk://doc/` links pointing at the document
+    let from_target = BoardLinkTarget::Document(from.to_string());
+    let to_target = BoardLinkTarget::Document(to.to_string());
+    match board_links::retarget_links(&deployment, project.id, &from_target, &to_target).await {
+        Ok(link_warnings) => warnings.extend(link_warnings),
+        Err(e) => {
+            tracing::warn!("Failed to update links to moved document {}: {}", from, e);
+            warnings.push(ApiWarning::new(
+                "board_links_not_updated",
+                format!("Links to {} were not updated: {e}", from_target.href()),
+            ));
+        }
+    }
+    // Tasks related to the document stay related to it
+    if let Err(e) = TaskDocument::move_document(&deployment.db().pool, repo.id, &from, &to).await {
+        tracing::warn!(
+            "Failed to update tasks related to moved document {}: {}",
+            from,
+            e
+        );
+    }
+    if let Err(e) =
+        DocumentAnnotation::move_document(&deployment.db().pool, repo.id, &from, &to).await
+    {
+        tracing::warn!(
+            "Failed to move annotations of moved document {}: {}",
+            from,
+            e
+        );
+    }
+    if let Err(e) = DocumentTag::move_document(&deployment.db().pool, repo.id, &from, &to).await {
+        tracing::warn!("Failed to move tags of moved document {}: {}", from, e);
+    }
+    let tags = DocumentTag::find_tags(&deployment.db().pool, repo.id, &to)
+        .await
+        .unwrap_or_default();
 
-    // Get file name
-    let name = full_path
+    let name = destination
         .file_name()
         .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| file_path_str.to_string());
+        .unwrap_or_else(|| to.to_string());
+    let size_bytes = tokio::fs::metadata(&destination)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+    let front_matter = if source_type == DocumentFileType::Markdown {
+        tokio::fs::read_to_string(&destination)
+            .await
+            .ok()
+            .and_then(|content| front_matter::parse(&content))
+    } else {
+        None
+    };
 
-    Ok(ResponseJson(ApiResponse::success(CreateFileResponse {
-        success: true,
-        message: if committed {
-            format!("File created and committed to branch '{}'", &current_branch)
-        } else {
-            "File created successfully".to_string()
-        },
-        metadata: DocumentMetadata {
-            name,
-            relative_path: file_path_str.to_string(),
-            absolute_path: full_path.to_string_lossy().to_string(),
-            file_type: DocumentFileType::Markdown,
-            size_bytes: content.len() as u64,
-        },
-        branch: Some(current_branch),
-        committed,
-    })))
+    Ok(ResponseJson(
+        ApiResponse::success(MoveDocumentResponse {
+            success: true,
+            message: if committed {
+                t("docs-moved-committed", &[("branch", &current_branch)])
+            } else {
+                t("docs-moved", &[])
+            },
+            metadata: DocumentMetadata {
+                repository_id: repo.id,
+                name,
+                relative_path: to.to_string(),
+                absolute_path: destination.to_string_lossy().to_string(),
+                file_type: source_type,
+                size_bytes,
+                front_matter,
+                lock: None,
+                tags,
+            },
+            branch: Some(current_branch),
+            committed,
+        })
+        .with_warnings(warnings),
+    )
+    .into_response())
+}
+
+/// Move or rename a folder with `git mv`, committing everything in it as one move.
+/// Nothing is moved unless every file inside may be moved, i.e. none is read-only,
+/// protected, encrypted or locked at either its old or its new path.
+pub async fn move_folder(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    Query(repository): Query<RepositoryQuery>,
+    ResponseJson(body): ResponseJson<MoveFolderRequest>,
+) -> Result<Response, ApiError> {
+    let from = validate_relative_path(body.from.trim_matches('/'))?.to_string();
+    let to = validate_relative_path(body.to.trim_matches('/'))?.to_string();
+    if from == to {
+        return Err(ApiError::BadRequest(
+            "Source and destination are the same".to_string(),
+        ));
+    }
+    if Path::new(&to).starts_with(&from) {
+        return Err(ApiError::BadRequest(format!(
+            "Folder '{}' can't be moved into itself",
+            from
+        )));
+    }
+    for path in [&from, &to] {
+        if !is_scanned_path(path) {
+            return Err(ApiError::BadRequest(format!(
+                "'{}' is a hidden or excluded folder",
+                path
+            )));
+        }
+    }
+    // Documents can't be moved out of sight of the document listing
+    let roots = document_roots::project_document_roots(&deployment, project.id).await?;
+    require_in_document_roots(&roots, &to)?;
+
+    // Symlinked folders aren't followed, so only the link itself would move
+    let (repo, source) = project_repositories(&deployment, project.id, repository.repository_id)
+        .await?
+        .into_iter()
+        .find_map(|repo| {
+            let source = PathBuf::from(&repo.path).join(&from);
+            let is_dir = source.symlink_metadata().is_ok_and(|meta| meta.is_dir());
+            is_dir.then_some((repo, source))
+        })
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Folder '{}' not found in project repositories",
+                from
+            ))
+        })?;
+    let repo_path = PathBuf::from(&repo.path);
+
+    // Security: Ensure the folder is within the repository and isn't the repository itself
+    let canonical_repo = repo_path.canonicalize()?;
+    let canonical_source = source.canonicalize()?;
+    if !canonical_source.starts_with(&canonical_repo) || canonical_source == canonical_repo {
+        return Err(ApiError::BadRequest(
+            "Invalid source path: access denied".to_string(),
+        ));
+    }
+    let destination = repo_path.join(&to);
+    if destination.symlink_metadata().is_ok() {
+        return Err(ApiError::BadRequest(format!("'{}' already exists", to)));
+    }
+
+    let store_path = repo_path.clone();
+    let store_folder = from.clone();
+    // Symbolic links are moved themselves, not what they point at
+    let files = tokio::task::spawn_blocking(move || {
+        document_store::open(
+            &store_path,
+            DEFAULT_DOCS_BRANCH,
+            DocumentSymlinkPolicy::Skip,
+        )
+        .list(&store_folder, &[])
+    })
+    .await
+    .map_err(|e| ApiError::Io(std::io::Error::other(e)))??;
+
+    // Every file inside is checked before anything is moved
+    let extensions = project_extensions(&deployment, project.id).await?;
+    for path in [&from, &to] {
+        if let Some(violation) = find_read_only_violation(&deployment, project.id, path).await? {
+            let response: ApiResponse<MoveFolderResponse, DocumentReadOnlyViolation> =
+                ApiResponse::error_with_data(violation);
+            return Ok(ResponseJson(response).into_response());
+        }
+    }
+    let mut moved_documents = Vec::new();
+    for file in files {
+        let old_path = file.relative_path;
+        let new_path = format!("{}{}", to, &old_path[from.len()..]);
+        for path in [&old_path, &new_path] {
+            if let Some(violation) = find_read_only_violation(&deployment, project.id, path).await?
+            {
+                let response: ApiResponse<MoveFolderResponse, DocumentReadOnlyViolation> =
+                    ApiResponse::error_with_data(violation);
+                return Ok(ResponseJson(response).into_response());
+            }
+            document_approvals::require_unprotected(&deployment, project.id, path).await?;
+            // Moving would take encrypted content out of its folder, or plaintext into one
+            document_encryption::require_unencrypted(&deployment, project.id, path).await?;
+        }
+        if let Some(lock) =
+            DocumentLock::find_active(&deployment.db().pool, repo.id, &old_path).await?
+        {
+            let response: ApiResponse<MoveFolderResponse, DocumentLock> =
+                ApiResponse::error_with_data(lock);
+            return Ok((StatusCode::LOCKED, ResponseJson(response)).into_response());
+        }
+        if allowed_file_type(Path::new(&old_path), &extensions).is_some() {
+            moved_documents.push(MovedDocument {
+                from: old_path,
+                to: new_path,
+            });
+        }
+    }
+    moved_documents.sort_by(|a, b| a.from.cmp(&b.from));
+
+    // Ensure we're on a branch documents can be edited on before modifying them
+    let current_branch =
+        document_branches::require_editable_branch(&deployment, project.id, &repo_path, &to)
+            .await?;
+
+    if let Some(parent) = destination.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(|e| {
+            ApiError::BadRequest(format!("Failed to create parent directories: {}", e))
+        })?;
+        let canonical_parent = parent
+            .canonicalize()
+            .map_err(|e| ApiError::BadRequest(format!("Failed to resolve parent path: {}", e)))?;
+        if !canonical_parent.starts_with(&canonical_repo) {
+            return Err(ApiError::BadRequest(
+                "Invalid destination path: access denied".to_string(),
+            ));
+        }
+    }
+
+    // A folder with nothing tracked in it has no history to keep
+    if let Err(e) = deployment.git_backend().move_path(&repo_path, &from, &to) {
+        tracing::debug!("git mv failed ({}), falling back to rename", e);
+        tokio::fs::rename(&source, &destination)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to move {:?} to {:?}: {}", source, destination, e);
+                ApiError::BadRequest(format!("Failed to move folder: {}", e))
+            })?;
+    }
+    tracing::info!("Folder moved: {} -> {}", from, to);
+    deployment.document_index().invalidate(repo.id);
+
+    // Auto-commit the move of every file as one commit
+    let committer = DocumentCommitter::load(&deployment, project.id).await?;
+    let commit_message = committer.message(&format!("docs: move folder {} -> {}", from, to), &to);
+    let mut warnings = Vec::new();
+    let committed = match committer.commit(&deployment, &repo_path, &commit_message) {
+        Ok(committed) => committed,
+        Err(e) => {
+            tracing::warn!("Failed to auto-commit folder move: {}", e);
+            warnings.push(auto_commit_warning("folder move", &e));
+            false
+        }
+    };
+    webhooks::documents_moved(
+        &deployment,
+        project.id,
+        repo.id,
+        moved_documents
+            .iter()
+            .map(|document| (document.from.as_str(), document.to.as_str())),
+    );
+    // Keep `vk://doc/` links pointing at the moved documents
+    for document in &moved_documents {
+        let from_target = BoardLinkTarget::Document(document.from.clone());
+        let to_target = BoardLinkTarget::Document(document.to.clone());
+        match board_links::retarget_links(&deployment, project.id, &from_target, &to_target).await {
+            Ok(link_warnings) => warnings.extend(link_warnings),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to update links to moved document {}: {}",
+                    document.from,
+                    e
+                );
+                warnings.push(ApiWarning::new(
+                    "board_links_not_updated",
+                    format!("Links to {} were not updated: {e}", from_target.href()),
+                ));
+            }
+        }
+        if let Err(e) = TaskDocument::move_document(
+            &deployment.db().pool,
+            repo.id,
+            &document.from,
+            &document.to,
+        )
+        .await
+        {
+            tracing::warn!(
+                "Failed to update tasks related to moved document {}: {}",
+                document.from,
+                e
+            );
+        }
+        if let Err(e) = DocumentAnnotation::move_document(
+            &deployment.db().pool,
+            repo.id,
+            &document.from,
+            &document.to,
+        )
+        .await
+        {
+            tracing::warn!(
+                "Failed to move annotations of moved document {}: {}",
+                document.from,
+                e
+            );
+        }
+        if let Err(e) =
+            DocumentTag::move_document(&deployment.db().pool, repo.id, &document.from, &document.to)
+                .await
+        {
+            tracing::warn!(
+                "Failed to move tags of moved document {}: {}",
+                document.from,
+                e
+            );
+        }
+    }
+
+    Ok(ResponseJson(
+        ApiResponse::success(MoveFolderResponse {
+            from,
+            to,
+            moved_documents,
+            branch: current_branch,
+            committed,
+        })
+        .with_warnings(warnings),
+    )
+    .into_response())
 }
 
 /// Response for getting current branch
@@ -704,16 +4480,16 @@ pub async fn get_current_branch(
         .await?;
 
     // Get the first repository (primary repository)
-    let repo = repositories.first().ok_or_else(|| {
-        ApiError::BadRequest("No repository found for this project".to_string())
-    })?;
+    let repo = repositories
+        .first()
+        .ok_or_else(|| ApiError::BadRequest(t("docs-no-repository", &[])))?;
 
     let repo_path = PathBuf::from(&repo.path);
 
     let current_branch = deployment
-        .git()
+        .git_backend()
         .get_current_branch(&repo_path)
-        .map_err(|e| ApiError::BadRequest(format!("Failed to get current branch: {e}")))?;
+        .map_err(|e| ApiError::BadRequest(t("docs-current-branch-failed", &[("error", &e)])))?;
 
     let is_docs_branch = current_branch == DEFAULT_DOCS_BRANCH;
 
@@ -750,9 +4526,9 @@ pub async fn list_branches(
         .get_repositories(&deployment.db().pool, project.id)
         .await?;
 
-    let repo = repositories.first().ok_or_else(|| {
-        ApiError::BadRequest("No repository found for this project".to_string())
-    })?;
+    let repo = repositories
+        .first()
+        .ok_or_else(|| ApiError::BadRequest(t("docs-no-repository", &[])))?;
 
     let repo_path = PathBuf::from(&repo.path);
 
@@ -762,7 +4538,7 @@ pub async fn list_branches(
         .map_err(|e| ApiError::BadRequest(format!("Failed to get branches: {e}")))?;
 
     let current_branch = deployment
-        .git()
+        .git_backend()
         .get_current_branch(&repo_path)
         .unwrap_or_else(|_| "unknown".to_string());
 
@@ -808,9 +4584,9 @@ pub async fn switch_branch(
         .get_repositories(&deployment.db().pool, project.id)
         .await?;
 
-    let repo = repositories.first().ok_or_else(|| {
-        ApiError::BadRequest("No repository found for this project".to_string())
-    })?;
+    let repo = repositories
+        .first()
+        .ok_or_else(|| ApiError::BadRequest(t("docs-no-repository", &[])))?;
 
     let repo_path = PathBuf::from(&repo.path);
 
@@ -819,9 +4595,9 @@ pub async fn switch_branch(
         .git()
         .checkout_with_stash(&repo_path, &body.branch)
         .map_err(|e| {
-            ApiError::BadRequest(format!(
-                "Failed to switch to branch '{}': {}",
-                body.branch, e
+            ApiError::BadRequest(t(
+                "docs-switch-branch-failed",
+                &[("branch", &body.branch), ("error", &e)],
             ))
         })?;
 
@@ -831,17 +4607,14 @@ pub async fn switch_branch(
             body.branch,
             repo_path
         );
-        format!(
-            "Switched to branch '{}' (changes were stashed and restored)",
-            body.branch
-        )
+        t("docs-switched-branch-stashed", &[("branch", &body.branch)])
     } else {
         tracing::info!(
             "Switched to branch '{}' in repository {:?}",
             body.branch,
             repo_path
         );
-        format!("Switched to branch '{}'", body.branch)
+        t("docs-switched-branch", &[("branch", &body.branch)])
     };
 
     Ok(ResponseJson(ApiResponse::success(SwitchBranchResponse {
@@ -861,12 +4634,17 @@ pub struct SyncStatusResponse {
     pub commits_behind: usize,
     /// Whether sync is possible (on main branch)
     pub can_sync: bool,
-    /// Whether rebase is needed before pushing
+    /// Whether remote commits have to be pulled before pushing
     pub needs_rebase: bool,
+    /// How remote commits are pulled
+    pub strategy: DocumentSyncStrategy,
     /// Current branch name
     pub current_branch: String,
     /// Error message if any
     pub error: Option<String>,
+    /// Whether origin's host is unreachable, so the counts are as of the last successful
+    /// fetch
+    pub degraded: bool,
 }
 
 /// Get sync status for the project's documents
@@ -879,15 +4657,16 @@ pub async fn get_sync_status(
         .get_repositories(&deployment.db().pool, project.id)
         .await?;
 
-    let repo = repositories.first().ok_or_else(|| {
-        ApiError::BadRequest("No repository found for this project".to_string())
-    })?;
+    let repo = repositories
+        .first()
+        .ok_or_else(|| ApiError::BadRequest(t("docs-no-repository", &[])))?;
 
     let repo_path = PathBuf::from(&repo.path);
+    let strategy = Project::find_document_sync_strategy(&deployment.db().pool, project.id).await?;
 
     // Get current branch
     let current_branch = deployment
-        .git()
+        .git_backend()
         .get_current_branch(&repo_path)
         .unwrap_or_else(|_| "unknown".to_string());
 
@@ -895,27 +4674,35 @@ pub async fn get_sync_status(
 
     // If not on main, can't sync
     if !is_main {
+        document_sync_status::record_fetch(&deployment, project.id, &current_branch, (0, 0), None)
+            .await;
         return Ok(ResponseJson(ApiResponse::success(SyncStatusResponse {
             commits_ahead: 0,
             commits_behind: 0,
             can_sync: false,
             needs_rebase: false,
+            strategy,
             current_branch,
-            error: Some("Must be on main branch to sync documents".to_string()),
+            error: Some(t("sync-requires-main", &[])),
+            degraded: false,
         })));
     }
 
-    // Try to fetch from origin to get latest status
-    if let Err(e) = deployment.git().fetch(&repo_path, "origin", "main") {
-        tracing::warn!("Failed to fetch from origin: {}", e);
-        return Ok(ResponseJson(ApiResponse::success(SyncStatusResponse {
-            commits_ahead: 0,
-            commits_behind: 0,
-            can_sync: false,
-            needs_rebase: false,
-            current_branch,
-            error: Some(format!("Failed to fetch from origin: {}", e)),
-        })));
+    // Try to fetch from origin to get latest status; without it the counts below
+    // compare against the last fetched origin/main
+    let mut warnings = Vec::new();
+    let fetch = document_sync_status::fetch_origin(&deployment, &repo_path, false);
+    if let Some(e) = &fetch.error {
+        tracing::warn!("{}", e);
+        let code = if fetch.degraded {
+            "remote_unreachable"
+        } else {
+            "fetch_failed"
+        };
+        warnings.push(ApiWarning::new(
+            code,
+            t("sync-counts-stale", &[("error", e)]),
+        ));
     }
 
     // Get ahead/behind counts
@@ -923,23 +4710,37 @@ pub async fn get_sync_status(
         .git()
         .get_ahead_behind(&repo_path, "main", "origin/main")
         .unwrap_or((0, 0));
-
-    Ok(ResponseJson(ApiResponse::success(SyncStatusResponse {
-        commits_ahead: ahead,
-        commits_behind: behind,
-        can_sync: is_main,
-        needs_rebase: behind > 0,
-        current_branch,
-        error: None,
-    })))
+    document_sync_status::record_fetch(
+        &deployment,
+        project.id,
+        &current_branch,
+        (ahead, behind),
+        Some(&fetch),
+    )
+    .await;
+
+    Ok(ResponseJson(
+        ApiResponse::success(SyncStatusResponse {
+            commits_ahead: ahead,
+            commits_behind: behind,
+            can_sync: is_main,
+            needs_rebase: behind > 0,
+            strategy,
+            current_branch,
+            error: None,
+            degraded: fetch.degraded,
+        })
+        .with_warnings(warnings),
+    ))
 }
 
 /// Request for syncing documents
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct SyncRequest {
-    /// If true, will rebase before pushing when behind origin
-    #[serde(default)]
-    pub allow_rebase: bool,
+    /// If true, pulls remote commits with the project's sync strategy before pushing
+    /// when behind origin
+    #[serde(default, alias = "allow_rebase")]
+    pub allow_pull: bool,
 }
 
 /// Response for syncing documents
@@ -947,45 +4748,66 @@ pub struct SyncRequest {
 pub struct SyncResponse {
     pub success: bool,
     pub commits_pushed: usize,
+    /// Number of remote commits pulled before pushing
+    pub commits_pulled: usize,
     pub message: String,
+    /// Strategy the remote commits were pulled with
+    pub strategy: DocumentSyncStrategy,
     /// Whether rebase was performed
     pub rebased: bool,
 }
 
-/// Sync documents to origin/main
-pub async fn sync_documents(
-    State(deployment): State<DeploymentImpl>,
-    Extension(project): Extension<Project>,
-    ResponseJson(body): ResponseJson<SyncRequest>,
-) -> Result<ResponseJson<ApiResponse<SyncResponse>>, ApiError> {
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateDocumentSyncStrategy {
+    pub strategy: DocumentSyncStrategy,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateDocumentSymlinkPolicy {
+    pub policy: DocumentSymlinkPolicy,
+}
+
+/// Sync the documents of a project's primary repository to origin/main, pulling remote
+/// commits with the project's sync strategy first. Every way of syncing goes through
+/// here so the strategy is always honored.
+pub(crate) async fn sync_project_documents(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    allow_pull: bool,
+) -> Result<SyncResponse, ApiError> {
+    let pool = &deployment.db().pool;
     let repositories = deployment
         .project()
-        .get_repositories(&deployment.db().pool, project.id)
+        .get_repositories(pool, project_id)
         .await?;
 
-    let repo = repositories.first().ok_or_else(|| {
-        ApiError::BadRequest("No repository found for this project".to_string())
-    })?;
+    let repo = repositories
+        .first()
+        .ok_or_else(|| ApiError::BadRequest(t("docs-no-repository", &[])))?;
 
     let repo_path = PathBuf::from(&repo.path);
 
+    // A sync stopped on conflicts has to be continued or aborted first
+    if deployment.git().detect_conflict_op(&repo_path)?.is_some() {
+        return Err(ApiError::Conflict(t("sync-stopped", &[])));
+    }
+
     // Must be on main branch
     let current_branch = deployment
-        .git()
+        .git_backend()
         .get_current_branch(&repo_path)
-        .map_err(|e| ApiError::BadRequest(format!("Failed to get current branch: {}", e)))?;
+        .map_err(|e| ApiError::BadRequest(t("docs-current-branch-failed", &[("error", &e)])))?;
 
     if current_branch != DEFAULT_DOCS_BRANCH {
-        return Err(ApiError::BadRequest(
-            "Must be on main branch to sync documents".to_string(),
-        ));
+        return Err(ApiError::BadRequest(t("sync-requires-main", &[])));
     }
 
-    // Fetch to get latest state
-    deployment
-        .git()
-        .fetch(&repo_path, "origin", "main")
-        .map_err(|e| ApiError::BadRequest(format!("Failed to fetch from origin: {}", e)))?;
+    let strategy = Project::find_document_sync_strategy(pool, project_id).await?;
+
+    // Fetch to get latest state; fails fast while origin's host is unreachable
+    if let Some(error) = document_sync_status::fetch_origin(deployment, &repo_path, false).error {
+        return Err(ApiError::BadRequest(error));
+    }
 
     // Check ahead/behind
     let (ahead, behind) = deployment
@@ -993,82 +4815,304 @@ pub async fn sync_documents(
         .get_ahead_behind(&repo_path, "main", "origin/main")
         .unwrap_or((0, 0));
 
-    // If behind, need rebase
-    let rebased = if behind > 0 {
-        if !body.allow_rebase {
-            return Err(ApiError::BadRequest(format!(
-                "Remote has {} new commit(s). Please pull changes first or enable rebase.",
-                behind
+    // If behind, pull the remote commits first
+    if behind > 0 {
+        if !allow_pull {
+            return Err(ApiError::BadRequest(t(
+                "sync-remote-ahead",
+                &[("count", &behind)],
             )));
         }
 
-        // Pull with rebase
+        let git = deployment.git();
+        match strategy {
+            DocumentSyncStrategy::Rebase => git
+                .pull_rebase(&repo_path, "origin", "main")
+                .map_err(|e| ApiError::BadRequest(t("sync-rebase-failed", &[("error", &e)])))?,
+            DocumentSyncStrategy::Merge => git
+                .pull_merge(&repo_path, "origin", "main")
+                .map_err(|e| ApiError::BadRequest(t("sync-merge-failed", &[("error", &e)])))?,
+            DocumentSyncStrategy::FastForwardOnly => {
+                if ahead > 0 {
+                    return Err(ApiError::Conflict(t(
+                        "sync-fast-forward-diverged",
+                        &[("ahead", &ahead), ("behind", &behind)],
+                    )));
+                }
+                git.pull_ff_only(&repo_path, "origin", "main")
+                    .map_err(|e| {
+                        ApiError::BadRequest(t("sync-fast-forward-failed", &[("error", &e)]))
+                    })?
+            }
+        }
+
+        tracing::info!(
+            "Pulled {} commits from origin/main ({:?})",
+            behind,
+            strategy
+        );
+    }
+    let rebased = behind > 0 && strategy == DocumentSyncStrategy::Rebase;
+
+    // A merge adds a commit to push and a fast-forward leaves nothing to push
+    let ahead = if behind > 0 {
         deployment
             .git()
-            .pull_rebase(&repo_path, "origin", "main")
-            .map_err(|e| {
-                ApiError::BadRequest(format!("Failed to rebase: {}. Please resolve conflicts manually.", e))
-            })?;
-
-        tracing::info!("Rebased {} commits from origin/main", behind);
-        true
+            .get_ahead_behind(&repo_path, "main", "origin/main")
+            .map(|(ahead, _)| ahead)
+            .unwrap_or(ahead)
     } else {
-        false
+        ahead
     };
 
     // If nothing to push, return early
-    if ahead == 0 && !rebased {
-        return Ok(ResponseJson(ApiResponse::success(SyncResponse {
+    if ahead == 0 {
+        return Ok(SyncResponse {
             success: true,
             commits_pushed: 0,
-            message: "Already up to date".to_string(),
-            rebased: false,
-        })));
+            commits_pulled: behind,
+            message: if behind > 0 {
+                t("sync-pulled", &[("count", &behind)])
+            } else {
+                t("sync-up-to-date", &[])
+            },
+            strategy,
+            rebased,
+        });
     }
 
     // Get remote URL and push
     let remote_url = deployment
         .git()
         .get_remote_url(&repo_path, "origin")
-        .map_err(|e| ApiError::BadRequest(format!("Failed to get remote URL: {}", e)))?;
+        .map_err(|e| ApiError::BadRequest(t("sync-remote-url-failed", &[("error", &e)])))?;
 
     deployment
         .git()
         .push(&repo_path, &remote_url, "main", false)
-        .map_err(|e| ApiError::BadRequest(format!("Failed to push to origin: {}", e)))?;
+        .map_err(|e| ApiError::BadRequest(t("sync-push-failed", &[("error", &e)])))?;
 
     tracing::info!(
-        "Pushed {} commits to origin/main (rebased: {})",
+        "Pushed {} commits to origin/main (pulled: {}, strategy: {:?})",
         ahead,
-        rebased
+        behind,
+        strategy
     );
 
-    Ok(ResponseJson(ApiResponse::success(SyncResponse {
+    let message = if behind == 0 {
+        t("sync-pushed", &[("count", &ahead)])
+    } else {
+        let id = match strategy {
+            DocumentSyncStrategy::Rebase => "sync-pushed-after-rebase",
+            DocumentSyncStrategy::Merge => "sync-pushed-after-merge",
+            DocumentSyncStrategy::FastForwardOnly => "sync-pushed-after-fast-forward",
+        };
+        t(id, &[("ahead", &ahead), ("behind", &behind)])
+    };
+    Ok(SyncResponse {
         success: true,
         commits_pushed: ahead,
-        message: if rebased {
-            format!(
-                "Synced {} commit(s) after rebasing {} remote commit(s)",
-                ahead, behind
-            )
-        } else {
-            format!("Synced {} commit(s) to origin/main", ahead)
-        },
+        commits_pulled: behind,
+        message,
+        strategy,
         rebased,
-    })))
+    })
+}
+
+/// Sync documents to origin/main
+pub async fn sync_documents(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<SyncRequest>,
+) -> Result<ResponseJson<ApiResponse<SyncResponse>>, ApiError> {
+    let _job = project_usage::background_job(&deployment, project.id).await;
+    let result = sync_project_documents(&deployment, project.id, body.allow_pull).await;
+    document_sync_status::record_sync(&deployment, project.id, result.as_ref().err()).await;
+    let response = result?;
+    if response.commits_pushed > 0 || response.commits_pulled > 0 {
+        webhooks::notify(
+            &deployment,
+            vec![WebhookPayload::synced(
+                project.id,
+                response.commits_pushed,
+                response.commits_pulled,
+            )],
+        );
+    }
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+pub async fn get_document_sync_strategy(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentSyncStrategy>>, ApiError> {
+    let strategy = Project::find_document_sync_strategy(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(strategy)))
+}
+
+/// Set how remote commits are pulled when the project's documents are synced
+pub async fn update_document_sync_strategy(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<UpdateDocumentSyncStrategy>,
+) -> Result<ResponseJson<ApiResponse<DocumentSyncStrategy>>, ApiError> {
+    Project::set_document_sync_strategy(&deployment.db().pool, project.id, body.strategy).await?;
+    Ok(ResponseJson(ApiResponse::success(body.strategy)))
+}
+
+pub async fn get_document_symlink_policy(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+) -> Result<ResponseJson<ApiResponse<DocumentSymlinkPolicy>>, ApiError> {
+    let policy = Project::find_document_symlink_policy(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(policy)))
+}
+
+/// Set how symbolic links are treated when the project's documents are scanned, read
+/// and saved
+pub async fn update_document_symlink_policy(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    ResponseJson(body): ResponseJson<UpdateDocumentSymlinkPolicy>,
+) -> Result<ResponseJson<ApiResponse<DocumentSymlinkPolicy>>, ApiError> {
+    let pool = &deployment.db().pool;
+    Project::set_document_symlink_policy(pool, project.id, body.policy).await?;
+
+    // Rebuild the document index under the new policy on the next listing
+    for repo in deployment.project().get_repositories(pool, project.id).await? {
+        deployment.document_index().unwatch(repo.id);
+    }
+
+    Ok(ResponseJson(ApiResponse::success(body.policy)))
 }
 
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     // Router for listing documents and creating folders/files (no wildcard path)
     let list_router = Router::new()
         .route("/", get(list_project_documents))
+        .route("/tree", get(get_document_tree))
         .route("/branch", get(get_current_branch))
         .route("/branches", get(list_branches))
         .route("/switch-branch", post(switch_branch))
         .route("/sync-status", get(get_sync_status))
         .route("/sync", post(sync_documents))
-        .route("/folders", post(create_folder))
+        .route(
+            "/sync/conflicts",
+            get(document_conflicts::get_sync_conflicts),
+        )
+        .route(
+            "/sync/conflicts/file",
+            get(document_conflicts::get_document_conflict),
+        )
+        .route(
+            "/sync/conflicts/resolve",
+            post(document_conflicts::resolve_document_conflict),
+        )
+        .route(
+            "/sync/conflicts/continue",
+            post(document_conflicts::continue_sync),
+        )
+        .route(
+            "/sync/conflicts/abort",
+            post(document_conflicts::abort_sync),
+        )
+        .route(
+            "/sync-strategy",
+            get(get_document_sync_strategy).put(update_document_sync_strategy),
+        )
+        .route("/folders", post(create_folder).delete(delete_folder))
+        .route("/folders/move", post(move_folder))
         .route("/files", post(create_file))
+        .route("/move", post(move_document))
+        .route("/batch", post(document_batch::batch_update_documents))
+        .route(
+            "/import",
+            post(document_import::import_documents)
+                .layer(DefaultBodyLimit::max(MAX_IMPORT_UPLOAD_BYTES)),
+        )
+        .route("/refresh", post(refresh_documents))
+        .route("/commit", post(document_commits::commit_documents))
+        .route(
+            "/auto-commit",
+            get(document_commits::get_document_auto_commit)
+                .put(document_commits::update_document_auto_commit),
+        )
+        .route(
+            "/branch-editing",
+            get(document_branches::get_document_branch_editing)
+                .put(document_branches::update_document_branch_editing),
+        )
+        .route(
+            "/pull-request",
+            post(document_branches::create_document_pull_request),
+        )
+        .route("/events", get(stream_document_events))
+        .route(
+            "/assets",
+            post(document_assets::upload_document_asset)
+                .layer(DefaultBodyLimit::max(MAX_ASSET_BYTES)),
+        )
+        .route(
+            "/assets-folder",
+            get(document_assets::get_document_assets_folder)
+                .put(document_assets::update_document_assets_folder),
+        )
+        .route(
+            "/templates",
+            get(document_templates::list_document_templates),
+        )
+        .route(
+            "/templates-folder",
+            get(document_templates::get_document_templates_folder)
+                .put(document_templates::update_document_templates_folder),
+        )
+        .route(
+            "/extensions",
+            get(get_document_extensions).put(update_document_extensions),
+        )
+        .route(
+            "/roots",
+            get(document_roots::get_document_roots).put(document_roots::update_document_roots),
+        )
+        .route(
+            "/scan-limits",
+            get(get_document_scan_limits).put(update_document_scan_limits),
+        )
+        .route(
+            "/symlink-policy",
+            get(get_document_symlink_policy).put(update_document_symlink_policy),
+        )
+        .route(
+            "/read-limit",
+            get(get_document_read_limit).put(update_document_read_limit),
+        )
+        .route(
+            "/read-only-paths",
+            get(get_document_read_only_paths).put(update_document_read_only_paths),
+        )
+        .route(
+            "/format-options",
+            get(document_format::get_document_format_options)
+                .put(document_format::update_document_format_options),
+        )
+        .route(
+            "/commit-settings",
+            get(document_commits::get_document_commit_settings)
+                .put(document_commits::update_document_commit_settings),
+        )
+        .route(
+            "/code-references",
+            get(document_freshness::get_document_code_references)
+                .put(document_freshness::update_document_code_references),
+        )
+        .route(
+            "/freshness",
+            get(document_freshness::get_document_freshness_overview),
+        )
+        .route("/recent", get(document_recent::get_recent_documents))
+        .route("/stats", get(document_stats::get_document_stats_overview))
+        .route("/link-graph", get(document_links::get_document_link_graph))
+        .route("/broken-links", get(board_links::get_board_link_report))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
@@ -1078,7 +5122,11 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let content_router = Router::new()
         .route(
             "/{*relative_path}",
-            get(get_document_content).put(update_document_content),
+            get(get_document)
+                .post(post_document)
+                .patch(patch_document)
+                .put(update_document_content)
+                .delete(delete_document),
         )
         .layer(from_fn_with_state(
             deployment.clone(),
@@ -1086,5 +5134,5 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         ));
 
     Router::new()
-        .nest("/projects/{id}/documents", list_router.merge(content_router))
+        .nest(paths::PROJECT_DOCUMENTS, list_router.merge(content_router))
 }
@@ -0,0 +1,60 @@
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::error_report::ErrorReport;
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, routes::paths};
+
+const DEFAULT_REPORT_LIMIT: i64 = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct ErrorReportsQuery {
+    pub limit: Option<i64>,
+}
+
+/// The latest reports of server errors, newest first
+pub async fn list_error_reports(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ErrorReportsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ErrorReport>>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_REPORT_LIMIT)
+        .clamp(1, ErrorReport::MAX_KEPT);
+    let reports = ErrorReport::find_recent(&deployment.db().pool, limit).await?;
+    Ok(ResponseJson(ApiResponse::success(reports)))
+}
+
+pub async fn get_error_report(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<ErrorReport>>, ApiError> {
+    let report = ErrorReport::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest(format!("Error report {} not found", id)))?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+/// Delete every report. Returns the number deleted.
+pub async fn delete_error_reports(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<u64>>, ApiError> {
+    let deleted = ErrorReport::delete_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(deleted)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(
+            paths::ADMIN_ERROR_REPORTS,
+            get(list_error_reports).delete(delete_error_reports),
+        )
+        .route(paths::ADMIN_ERROR_REPORT, get(get_error_report))
+}
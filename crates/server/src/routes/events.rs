@@ -10,7 +10,7 @@ use axum::{
 use deployment::Deployment;
 use futures_util::TryStreamExt;
 
-use crate::DeploymentImpl;
+use crate::{DeploymentImpl, routes::paths};
 
 pub async fn events(
     State(deployment): State<DeploymentImpl>,
@@ -24,5 +24,5 @@ pub async fn events(
 pub fn router(_: &DeploymentImpl) -> Router<DeploymentImpl> {
     let events_router = Router::new().route("/", get(events));
 
-    Router::new().nest("/events", events_router)
+    Router::new().nest(paths::EVENTS, events_router)
 }
@@ -20,7 +20,9 @@ use services::services::container::ContainerService;
 use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware};
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware, routes::paths,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct SessionExecutionProcessQuery {
@@ -262,5 +264,5 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         )
         .nest("/{id}", workspace_id_router);
 
-    Router::new().nest("/execution-processes", workspaces_router)
+    Router::new().nest(paths::EXECUTION_PROCESSES, workspaces_router)
 }
@@ -0,0 +1,69 @@
+use axum::{
+    Extension, Json, Router,
+    extract::State,
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{project::Project, task::Task};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::flaky_tests::{self, FlakyTestReport};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware, routes::paths};
+
+fn default_task_limit() -> usize {
+    3
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateFlakyTestTasksRequest {
+    /// How many of the worst offenders to create tasks for
+    #[serde(default = "default_task_limit")]
+    pub limit: usize,
+}
+
+pub async fn get_flaky_tests(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<FlakyTestReport>>, ApiError> {
+    let report = flaky_tests::project_report(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub async fn create_flaky_test_tasks(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateFlakyTestTasksRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<Task>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let report = flaky_tests::project_report(pool, project.id).await?;
+    let tasks =
+        flaky_tests::create_tasks_for_worst(pool, project.id, &report, payload.limit).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "flaky_test_tasks_created",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "count": tasks.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(tasks)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route("/", get(get_flaky_tests))
+        .route("/tasks", post(create_flaky_test_tasks))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest(paths::PROJECT_FLAKY_TESTS, project_router)
+}
@@ -1,6 +1,8 @@
+use std::sync::OnceLock;
+
 use axum::{
     body::Body,
-    http::HeaderValue,
+    http::{HeaderMap, HeaderValue},
     response::{IntoResponse, Response},
 };
 use reqwest::{StatusCode, header};
@@ -10,45 +12,120 @@ use rust_embed::RustEmbed;
 #[folder = "../../frontend/dist"]
 pub struct Assets;
 
-pub async fn serve_frontend(uri: axum::extract::Path<String>) -> impl IntoResponse {
+/// Vite emits content-hashed file names under `assets/`, so they never change
+const IMMUTABLE_CACHE: &str = "public, max-age=31536000, immutable";
+/// Everything else (notably `index.html`) must be revalidated so new builds are picked up
+const REVALIDATE_CACHE: &str = "no-cache";
+
+pub async fn serve_frontend(
+    uri: axum::extract::Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
     let path = uri.trim_start_matches('/');
-    serve_file(path).await
-}
-
-pub async fn serve_frontend_root() -> impl IntoResponse {
-    serve_file("index.html").await
-}
-
-async fn serve_file(path: &str) -> impl IntoResponse + use<> {
-    let file = Assets::get(path);
-
-    match file {
-        Some(content) => {
-            let mime = mime_guess::from_path(path).first_or_octet_stream();
-
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(
-                    header::CONTENT_TYPE,
-                    HeaderValue::from_str(mime.as_ref()).unwrap(),
-                )
-                .body(Body::from(content.data.into_owned()))
-                .unwrap()
-        }
-        None => {
-            // For SPA routing, serve index.html for unknown routes
-            if let Some(index) = Assets::get("index.html") {
-                Response::builder()
-                    .status(StatusCode::OK)
-                    .header(header::CONTENT_TYPE, HeaderValue::from_static("text/html"))
-                    .body(Body::from(index.data.into_owned()))
-                    .unwrap()
-            } else {
-                Response::builder()
-                    .status(StatusCode::NOT_FOUND)
-                    .body(Body::from("404 Not Found"))
-                    .unwrap()
-            }
-        }
+    serve_file(path, &headers).await
+}
+
+pub async fn serve_frontend_root(headers: HeaderMap) -> impl IntoResponse {
+    serve_file("index.html", &headers).await
+}
+
+/// Identifier of the embedded frontend build, derived from `index.html`
+/// (which references every hashed asset)
+pub fn frontend_build_id() -> Option<&'static str> {
+    static BUILD_ID: OnceLock<Option<String>> = OnceLock::new();
+    BUILD_ID
+        .get_or_init(|| {
+            Assets::get("index.html").map(|index| {
+                let mut id = hex(&index.metadata.sha256_hash());
+                id.truncate(16);
+                id
+            })
+        })
+        .as_deref()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn cache_control(path: &str) -> &'static str {
+    if path.starts_with("assets/") {
+        IMMUTABLE_CACHE
+    } else {
+        REVALIDATE_CACHE
+    }
+}
+
+/// Client-side routes have no file extension; a missing file with one
+/// (e.g. a stale `assets/index-abc123.js`) is a real 404
+fn is_spa_route(path: &str) -> bool {
+    let last = path.rsplit('/').next().unwrap_or(path);
+    !path.starts_with("assets/") && !last.contains('.')
+}
+
+async fn serve_file(path: &str, request_headers: &HeaderMap) -> Response {
+    let (path, file) = match Assets::get(path) {
+        Some(file) => (path, file),
+        // For SPA routing, serve index.html for unknown routes
+        None if is_spa_route(path) => match Assets::get("index.html") {
+            Some(index) => ("index.html", index),
+            None => return not_found(),
+        },
+        None => return not_found(),
+    };
+
+    let etag = format!("\"{}\"", hex(&file.metadata.sha256_hash()));
+    let not_modified = request_headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| tag.trim() == etag));
+
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    let builder = Response::builder()
+        .header(header::CACHE_CONTROL, cache_control(path))
+        .header(header::ETAG, HeaderValue::from_str(&etag).unwrap());
+
+    if not_modified {
+        return builder
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Body::empty())
+            .unwrap();
+    }
+
+    builder
+        .status(StatusCode::OK)
+        .header(
+            header::CONTENT_TYPE,
+            HeaderValue::from_str(mime.as_ref()).unwrap(),
+        )
+        .body(Body::from(file.data.into_owned()))
+        .unwrap()
+}
+
+fn not_found() -> Response {
+    Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .body(Body::from("404 Not Found"))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashed_assets_are_immutable() {
+        assert_eq!(cache_control("assets/index-3f2a1b.js"), IMMUTABLE_CACHE);
+        assert_eq!(cache_control("index.html"), REVALIDATE_CACHE);
+        assert_eq!(cache_control("favicon.ico"), REVALIDATE_CACHE);
+    }
+
+    #[test]
+    fn only_extensionless_paths_fall_back_to_index() {
+        assert!(is_spa_route("projects/123/tasks"));
+        assert!(is_spa_route("settings"));
+        assert!(!is_spa_route("assets/index-3f2a1b.js"));
+        assert!(!is_spa_route("assets/fonts"));
+        assert!(!is_spa_route("missing.png"));
     }
 }
@@ -1,6 +1,24 @@
 use axum::response::Json;
-use utils::response::ApiResponse;
+use serde::Serialize;
+use ts_rs::TS;
+use utils::{response::ApiResponse, version::APP_VERSION};
+
+use super::frontend::frontend_build_id;
+
+#[derive(Debug, Serialize, TS)]
+pub struct VersionInfo {
+    pub version: String,
+    /// Changes whenever the embedded frontend is rebuilt; `None` when no frontend is bundled
+    pub frontend_build: Option<String>,
+}
 
 pub async fn health_check() -> Json<ApiResponse<String>> {
     Json(ApiResponse::success("OK".to_string()))
 }
+
+pub async fn version() -> Json<ApiResponse<VersionInfo>> {
+    Json(ApiResponse::success(VersionInfo {
+        version: APP_VERSION.to_string(),
+        frontend_build: frontend_build_id().map(str::to_string),
+    }))
+}
@@ -0,0 +1,75 @@
+use axum::{Router, response::Json as ResponseJson, routing::get};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::{
+    logging::{self, LogControl, LogRotation},
+    response::ApiResponse,
+};
+
+use crate::{DeploymentImpl, error::ApiError, routes::paths};
+
+#[derive(Debug, Serialize, TS)]
+pub struct LogFileInfo {
+    pub dir: String,
+    pub rotation: LogRotation,
+    /// Rotated files kept; 0 keeps them all
+    pub max_files: usize,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct LoggingSettings {
+    /// Directives the server started with, from `RUST_LOG`
+    pub base_directives: String,
+    /// Directives changed at runtime, applied on top of the base ones
+    pub overrides: String,
+    /// What logs are filtered with now
+    pub effective_directives: String,
+    /// Set when logs are also written to files, with `VK_LOG_DIR`
+    pub file: Option<LogFileInfo>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateLoggingSettings {
+    /// Comma-separated filter directives such as `server::routes::documents=debug`; each
+    /// replaces the base directive of the same target. Empty goes back to the base ones.
+    pub overrides: String,
+}
+
+fn log_control() -> Result<&'static LogControl, ApiError> {
+    logging::log_control().ok_or_else(|| {
+        ApiError::BadRequest("Logging of this server can't be changed at runtime".to_string())
+    })
+}
+
+fn settings(control: &LogControl) -> LoggingSettings {
+    LoggingSettings {
+        base_directives: control.base_directives().to_string(),
+        overrides: control.overrides(),
+        effective_directives: control.effective_directives(),
+        file: control.file().map(|file| LogFileInfo {
+            dir: file.dir.display().to_string(),
+            rotation: file.rotation,
+            max_files: file.max_files,
+        }),
+    }
+}
+
+pub async fn get_logging() -> Result<ResponseJson<ApiResponse<LoggingSettings>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(settings(log_control()?))))
+}
+
+/// Change the log filter until the server restarts
+pub async fn update_logging(
+    ResponseJson(body): ResponseJson<UpdateLoggingSettings>,
+) -> Result<ResponseJson<ApiResponse<LoggingSettings>>, ApiError> {
+    let control = log_control()?;
+    control
+        .set_overrides(&body.overrides)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    tracing::info!("Log filter changed to {}", control.effective_directives());
+    Ok(ResponseJson(ApiResponse::success(settings(control))))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route(paths::ADMIN_LOGGING, get(get_logging).put(update_logging))
+}
@@ -1,46 +1,108 @@
-use axum::{
-    Router,
-    routing::{IntoMakeService, get},
-};
+use axum::{Router, routing::get};
 use tower_http::validate_request::ValidateRequestHeaderLayer;
 
 use crate::{DeploymentImpl, middleware};
 
+pub mod analytics;
 pub mod approvals;
+pub mod board_links;
+pub mod compliance_export;
 pub mod config;
 pub mod containers;
+pub mod demo;
+pub mod dependency_updates;
+pub mod document_action_items;
+pub mod document_annotations;
+pub mod document_approvals;
+pub mod document_assets;
+pub mod document_batch;
+pub mod document_branches;
+pub mod document_commits;
+pub mod document_conflicts;
+pub mod document_drafts;
+pub mod document_encryption;
+pub mod document_format;
+pub mod document_freshness;
+pub mod document_import;
+pub mod document_links;
+pub mod document_locks;
+pub mod document_query;
+pub mod document_recent;
+pub mod document_roots;
+pub mod document_snapshots;
+pub mod document_stats;
+pub mod document_sync_status;
+pub mod document_tags;
+pub mod document_task_sync;
+pub mod document_templates;
 pub mod documents;
 pub mod filesystem;
+pub mod flaky_tests;
 // pub mod github;
+pub mod error_reports;
 pub mod events;
 pub mod execution_processes;
 pub mod frontend;
 pub mod health;
 pub mod images;
+pub mod logging;
 pub mod oauth;
+pub mod onboarding;
 pub mod organizations;
+pub mod paths;
+pub mod project_usage;
 pub mod projects;
 pub mod repo;
+pub mod retention;
 pub mod scratch;
+pub mod self_update;
 pub mod sessions;
+#[cfg(feature = "sql-console")]
+pub mod sql_console;
 pub mod tags;
 pub mod task_attempts;
+pub mod task_documents;
 pub mod tasks;
+pub mod telemetry;
 pub mod terminal;
+pub mod timezones;
+pub mod user_data;
+pub mod webhooks;
+pub mod wip_limits;
+pub mod workers;
 
-pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
+pub fn router(deployment: DeploymentImpl) -> Router {
     // Create routers with different middleware layers
     let base_routes = Router::new()
-        .route("/health", get(health::health_check))
+        .route(paths::HEALTH, get(health::health_check))
+        .route(paths::VERSION, get(health::version))
+        .merge(analytics::router())
         .merge(config::router())
         .merge(containers::router(&deployment))
         .merge(projects::router(&deployment))
         .merge(documents::router(&deployment))
+        .merge(document_snapshots::router(&deployment))
+        .merge(document_approvals::router(&deployment))
+        .merge(document_encryption::router(&deployment))
+        .merge(document_locks::router(&deployment))
+        .merge(document_sync_status::router())
+        .merge(document_drafts::router(&deployment))
+        .merge(document_annotations::router(&deployment))
+        .merge(document_tags::router(&deployment))
+        .merge(dependency_updates::router(&deployment))
+        .merge(compliance_export::router(&deployment))
+        .merge(demo::router())
+        .merge(flaky_tests::router(&deployment))
+        .merge(wip_limits::router(&deployment))
+        .merge(timezones::router(&deployment))
+        .merge(project_usage::router(&deployment))
+        .merge(webhooks::router(&deployment))
         .merge(tasks::router(&deployment))
         .merge(task_attempts::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
         .merge(oauth::router())
+        .merge(onboarding::router())
         .merge(organizations::router())
         .merge(filesystem::router())
         .merge(repo::router())
@@ -49,15 +111,39 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(scratch::router(&deployment))
         .merge(sessions::router(&deployment))
         .merge(terminal::router())
-        .nest("/images", images::routes())
+        .merge(workers::router())
+        .merge(telemetry::router())
+        .merge(retention::router())
+        .merge(user_data::router())
+        .merge(logging::router())
+        .merge(error_reports::router())
+        .merge(self_update::router())
+        .nest(paths::IMAGES, images::routes());
+    #[cfg(feature = "sql-console")]
+    let base_routes = base_routes.merge(sql_console::router());
+    let base_routes = base_routes
+        .layer(axum::middleware::from_fn_with_state(
+            deployment.clone(),
+            middleware::capture_error_reports,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            deployment.clone(),
+            middleware::enforce_project_quota,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            deployment.clone(),
+            middleware::set_locale,
+        ))
+        .layer(axum::middleware::from_fn(middleware::csrf_protection))
         .layer(ValidateRequestHeaderLayer::custom(
             middleware::validate_origin,
         ))
+        .layer(middleware::cors_layer())
+        .layer(axum::middleware::from_fn(middleware::assign_request_id))
         .with_state(deployment);
 
     Router::new()
         .route("/", get(frontend::serve_frontend_root))
         .route("/{*path}", get(frontend::serve_frontend))
-        .nest("/api", base_routes)
-        .into_make_service()
+        .nest(paths::API_PREFIX, base_routes)
 }
@@ -1,9 +1,9 @@
 use axum::{
     Router,
-    extract::{Json, Query, State},
+    extract::{Json, Path, Query, State},
     http::{Response, StatusCode},
     response::Json as ResponseJson,
-    routing::{get, post},
+    routing::{delete, get, post},
 };
 use chrono::{DateTime, Utc};
 use deployment::Deployment;
@@ -13,7 +13,10 @@ use services::services::{config::save_config_to_file, oauth_credentials::Credent
 use sha2::{Digest, Sha256};
 use ts_rs::TS;
 use utils::{
-    api::oauth::{HandoffInitRequest, HandoffRedeemRequest, StatusResponse},
+    api::oauth::{
+        HandoffInitRequest, HandoffRedeemRequest, ListSessionsResponse, RevokeSessionsResponse,
+        StatusResponse,
+    },
     assets::config_path,
     jwt::extract_expiration,
     response::ApiResponse,
@@ -45,6 +48,9 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/auth/status", get(status))
         .route("/auth/token", get(get_token))
         .route("/auth/user", get(get_current_user))
+        .route("/me/sessions", get(list_sessions))
+        .route("/me/sessions/revoke-all", post(revoke_all_sessions))
+        .route("/me/sessions/{session_id}", delete(revoke_session))
 }
 
 #[derive(Debug, Deserialize)]
@@ -213,12 +219,19 @@ async fn handoff_complete(
 }
 
 async fn logout(State(deployment): State<DeploymentImpl>) -> Result<StatusCode, ApiError> {
-    let auth_context = deployment.auth_context();
-
     if let Ok(client) = deployment.remote_client() {
         let _ = client.logout().await;
     }
 
+    clear_local_session(&deployment).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Forget the stored credentials once the remote session is gone
+async fn clear_local_session(deployment: &DeploymentImpl) -> Result<(), ApiError> {
+    let auth_context = deployment.auth_context();
+
     auth_context.clear_credentials().await.map_err(|e| {
         tracing::error!(?e, "failed to clear credentials");
         ApiError::Io(e)
@@ -226,7 +239,59 @@ async fn logout(State(deployment): State<DeploymentImpl>) -> Result<StatusCode,
 
     auth_context.clear_profile().await;
 
-    Ok(StatusCode::NO_CONTENT)
+    Ok(())
+}
+
+async fn list_sessions(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ListSessionsResponse>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let response = client.list_sessions().await?;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+async fn revoke_session(
+    State(deployment): State<DeploymentImpl>,
+    Path(session_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let is_current = client
+        .list_sessions()
+        .await?
+        .sessions
+        .iter()
+        .any(|session| session.id == session_id && session.current);
+
+    client.revoke_session(session_id).await?;
+
+    if is_current {
+        clear_local_session(&deployment).await?;
+    }
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Sign out on every device, including this one
+async fn revoke_all_sessions(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<RevokeSessionsResponse>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let response = client.revoke_all_sessions().await?;
+
+    clear_local_session(&deployment).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "signed_out_everywhere",
+            serde_json::json!({ "revoked_sessions": response.revoked }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
 }
 
 async fn status(
@@ -0,0 +1,291 @@
+use axum::{
+    Json, Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    project::{CreateProject, Project},
+    project_repo::ProjectRepo,
+    task::{CreateTask, Task},
+    workspace::{CreateWorkspace, Workspace},
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+};
+use deployment::Deployment;
+use executors::profile::{ExecutorConfigs, ExecutorProfileId};
+use serde::{Deserialize, Serialize};
+use services::services::{
+    config::{OnboardingStep, save_config_to_file},
+    container::ContainerService,
+};
+use ts_rs::TS;
+use utils::{assets::config_path, response::ApiResponse};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{paths, task_attempts::util::resolve_agent_working_dir},
+};
+
+const STEPS: [OnboardingStep; 4] = [
+    OnboardingStep::GitIdentity,
+    OnboardingStep::CodingAgent,
+    OnboardingStep::Project,
+    OnboardingStep::SampleAttempt,
+];
+
+const SAMPLE_TASK_TITLE: &str = "Get to know this repository";
+const SAMPLE_TASK_DESCRIPTION: &str = "Read through the repository and write a short summary of \
+what it does, how it is structured and how to run it. Do not modify any files.";
+
+#[derive(Debug, Serialize, TS)]
+pub struct OnboardingStepStatus {
+    pub step: OnboardingStep,
+    pub completed: bool,
+    /// What was detected, e.g. the configured git identity or available agents
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct OnboardingStatus {
+    pub steps: Vec<OnboardingStepStatus>,
+    /// First step that is not completed yet
+    pub next_step: Option<OnboardingStep>,
+    pub completed: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetGitIdentityRequest {
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct StartSampleAttemptRequest {
+    /// Defaults to the most recently created project
+    pub project_id: Option<Uuid>,
+    /// Defaults to the configured executor profile
+    pub executor_profile_id: Option<ExecutorProfileId>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SampleAttemptResponse {
+    pub task: Task,
+    pub workspace_id: Uuid,
+}
+
+/// Names of coding agents that are installed or logged in on this machine
+fn available_agents() -> Vec<String> {
+    let profiles = ExecutorConfigs::get_cached();
+    let mut agents: Vec<String> = profiles
+        .executors
+        .keys()
+        .filter(|agent| {
+            profiles
+                .get_coding_agent(&ExecutorProfileId::new(**agent))
+                .is_some_and(|executor| executor.get_availability_info().is_available())
+        })
+        .map(|agent| agent.to_string())
+        .collect();
+    agents.sort();
+    agents
+}
+
+async fn detect_step(
+    deployment: &DeploymentImpl,
+    step: OnboardingStep,
+) -> Result<(bool, Option<String>), ApiError> {
+    let pool = &deployment.db().pool;
+    Ok(match step {
+        OnboardingStep::GitIdentity => match deployment.git().global_identity() {
+            Some((name, email)) => (true, Some(format!("{name} <{email}>"))),
+            None => (false, None),
+        },
+        OnboardingStep::CodingAgent => {
+            let agents = available_agents();
+            (
+                !agents.is_empty(),
+                Some(agents.join(", ")).filter(|a| !a.is_empty()),
+            )
+        }
+        OnboardingStep::Project => {
+            let count = Project::count(pool).await?;
+            (count > 0, Some(format!("{count} project(s)")))
+        }
+        OnboardingStep::SampleAttempt => (Workspace::count_all(pool).await? > 0, None),
+    })
+}
+
+/// Remember a completed step so it stays completed if the environment changes later
+async fn record_step(deployment: &DeploymentImpl, step: OnboardingStep) -> Result<(), ApiError> {
+    {
+        let mut config = deployment.config().write().await;
+        if config.onboarding.completed_steps.contains(&step) {
+            return Ok(());
+        }
+        config.onboarding.completed_steps.push(step);
+        save_config_to_file(&config, &config_path()).await?;
+    }
+    deployment
+        .track_if_analytics_allowed(
+            "onboarding_step_completed",
+            serde_json::json!({ "step": step }),
+        )
+        .await;
+    Ok(())
+}
+
+pub async fn get_onboarding_status(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<OnboardingStatus>>, ApiError> {
+    let recorded = deployment
+        .config()
+        .read()
+        .await
+        .onboarding
+        .completed_steps
+        .clone();
+
+    let mut steps = Vec::with_capacity(STEPS.len());
+    for step in STEPS {
+        let (detected, detail) = detect_step(&deployment, step).await?;
+        steps.push(OnboardingStepStatus {
+            step,
+            completed: detected || recorded.contains(&step),
+            detail,
+        });
+    }
+    let next_step = steps.iter().find(|s| !s.completed).map(|s| s.step);
+
+    Ok(ResponseJson(ApiResponse::success(OnboardingStatus {
+        steps,
+        next_step,
+        completed: next_step.is_none(),
+    })))
+}
+
+pub async fn set_git_identity(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetGitIdentityRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let name = payload.name.trim();
+    let email = payload.email.trim();
+    if name.is_empty() || !email.contains('@') {
+        return Err(ApiError::BadRequest(
+            "A name and a valid email address are required".to_string(),
+        ));
+    }
+
+    deployment.git().set_global_identity(name, email)?;
+    record_step(&deployment, OnboardingStep::GitIdentity).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn register_project(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateProject>,
+) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
+    if payload.repositories.is_empty() {
+        return Err(ApiError::BadRequest(
+            "At least one repository is required".to_string(),
+        ));
+    }
+
+    let project = deployment
+        .project()
+        .create_project(&deployment.db().pool, deployment.repo(), payload)
+        .await?;
+    deployment
+        .track_if_analytics_allowed(
+            "project_created",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "trigger": "onboarding",
+            }),
+        )
+        .await;
+    record_step(&deployment, OnboardingStep::Project).await?;
+    Ok(ResponseJson(ApiResponse::success(project)))
+}
+
+/// Create a read-only exploration task and start an attempt for it
+pub async fn start_sample_attempt(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<StartSampleAttemptRequest>,
+) -> Result<ResponseJson<ApiResponse<SampleAttemptResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let project = match payload.project_id {
+        Some(id) => Project::find_by_id(pool, id).await?,
+        None => Project::find_all(pool).await?.into_iter().next(),
+    }
+    .ok_or_else(|| ApiError::BadRequest("Register a project first".to_string()))?;
+
+    let repo = ProjectRepo::find_repos_for_project(pool, project.id)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::BadRequest("The project has no repositories".to_string()))?;
+    let profile = match payload.executor_profile_id {
+        Some(profile) => profile,
+        None => deployment.config().read().await.executor_profile.clone(),
+    };
+
+    let task = Task::create(
+        pool,
+        &CreateTask::from_title_description(
+            project.id,
+            SAMPLE_TASK_TITLE.to_string(),
+            Some(SAMPLE_TASK_DESCRIPTION.to_string()),
+        ),
+        Uuid::new_v4(),
+    )
+    .await?;
+
+    let target_branch = deployment.git().get_current_branch(&repo.path)?;
+    let attempt_id = Uuid::new_v4();
+    let branch = deployment
+        .container()
+        .git_branch_from_workspace(&attempt_id, &task.title)
+        .await;
+    let agent_working_dir = resolve_agent_working_dir(pool, task.id, &[repo.id]).await?;
+    let workspace = Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch,
+            agent_working_dir,
+        },
+        attempt_id,
+        task.id,
+    )
+    .await?;
+    WorkspaceRepo::create_many(
+        pool,
+        workspace.id,
+        &[CreateWorkspaceRepo {
+            repo_id: repo.id,
+            target_branch,
+        }],
+    )
+    .await?;
+    deployment
+        .container()
+        .start_workspace(&workspace, profile)
+        .await?;
+
+    record_step(&deployment, OnboardingStep::SampleAttempt).await?;
+    Ok(ResponseJson(ApiResponse::success(SampleAttemptResponse {
+        task,
+        workspace_id: workspace.id,
+    })))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    let inner = Router::new()
+        .route("/", get(get_onboarding_status))
+        .route("/git-identity", post(set_git_identity))
+        .route("/project", post(register_project))
+        .route("/sample-attempt", post(start_sample_attempt));
+
+    Router::new().nest(paths::ONBOARDING, inner)
+}
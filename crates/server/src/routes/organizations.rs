@@ -1,19 +1,23 @@
 use axum::{
     Router,
-    extract::{Json, Path, State},
-    http::StatusCode,
-    response::Json as ResponseJson,
+    extract::{Json, Path, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Json as ResponseJson, Response},
     routing::{delete, get, patch, post},
 };
+use chrono::Utc;
 use deployment::Deployment;
+use serde::Deserialize;
+use services::services::access_report;
 use utils::{
     api::{
         organizations::{
             AcceptInvitationResponse, CreateInvitationRequest, CreateInvitationResponse,
             CreateOrganizationRequest, CreateOrganizationResponse, GetInvitationResponse,
             GetOrganizationResponse, ListInvitationsResponse, ListMembersResponse,
-            ListOrganizationsResponse, Organization, RevokeInvitationRequest,
-            UpdateMemberRoleRequest, UpdateMemberRoleResponse, UpdateOrganizationRequest,
+            ListOrganizationsResponse, Organization, PermissionCheckQuery, PermissionCheckResponse,
+            RevokeInvitationRequest, UpdateMemberRoleRequest, UpdateMemberRoleResponse,
+            UpdateOrganizationRequest,
         },
         projects::RemoteProject,
     },
@@ -21,7 +25,17 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::{analytics::AnalyticsExportFormat, paths},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct AccessReportQuery {
+    #[serde(default)]
+    pub format: AnalyticsExportFormat,
+}
 
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
@@ -54,6 +68,8 @@ pub fn router() -> Router<DeploymentImpl> {
             "/organizations/{org_id}/members/{user_id}/role",
             patch(update_member_role),
         )
+        .route("/permissions/check", get(check_permission))
+        .route(paths::ADMIN_ACCESS_REPORT, get(get_access_report))
 }
 
 async fn list_organization_projects(
@@ -234,3 +250,43 @@ async fn update_member_role(
 
     Ok(ResponseJson(ApiResponse::success(response)))
 }
+
+async fn check_permission(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<PermissionCheckQuery>,
+) -> Result<ResponseJson<ApiResponse<PermissionCheckResponse>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let response = client.check_permission(&query).await?;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+/// Which projects and actions every member and token of the organizations the user
+/// administers can reach, as JSON or as a CSV download
+async fn get_access_report(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AccessReportQuery>,
+) -> Result<Response, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let report = client.access_report().await?;
+
+    Ok(match query.format {
+        AnalyticsExportFormat::Json => ResponseJson(ApiResponse::success(report)).into_response(),
+        AnalyticsExportFormat::Csv => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"access-report-{}.csv\"",
+                        Utc::now().format("%Y-%m-%d")
+                    ),
+                ),
+            ],
+            access_report::to_csv(&report),
+        )
+            .into_response(),
+    })
+}
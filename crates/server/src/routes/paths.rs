@@ -0,0 +1,112 @@
+//! Route path templates shared with the frontend.
+//!
+//! Routers register these instead of string literals and `generate_types` exports them
+//! to `shared/types.ts` as `ROUTES`, so fetch calls can't drift from the axum routes.
+//! Paths are relative to [`API_PREFIX`] and use axum's `{param}` syntax.
+
+pub const API_PREFIX: &str = "/api";
+
+macro_rules! route_paths {
+    ($($(#[$meta:meta])* $name:ident = $path:literal;)*) => {
+        $($(#[$meta])* pub const $name: &str = $path;)*
+
+        /// Every template with its constant name, in declaration order
+        pub const ALL: &[(&str, &str)] = &[$((stringify!($name), $name)),*];
+    };
+}
+
+route_paths! {
+    HEALTH = "/health";
+    VERSION = "/version";
+    VERSION_CHECK = "/version/check";
+    ADMIN_UPDATE = "/admin/update";
+    ADMIN_DEMO = "/admin/demo";
+    ADMIN_TELEMETRY_PREVIEW = "/admin/telemetry/preview";
+    ADMIN_ACCESS_REPORT = "/admin/access-report";
+    ADMIN_SQL = "/admin/sql";
+    ADMIN_SQL_TABLES = "/admin/sql/tables";
+    ADMIN_RETENTION_REPORT = "/admin/retention/report";
+    ADMIN_RETENTION_RUN = "/admin/retention/run";
+    ADMIN_USER_DATA = "/admin/user-data";
+    ADMIN_USER_DATA_ERASE = "/admin/user-data/erase";
+    ADMIN_LOGGING = "/admin/logging";
+    ADMIN_ERROR_REPORTS = "/admin/error-reports";
+    ADMIN_ERROR_REPORT = "/admin/error-reports/{id}";
+    ANALYTICS_OVERVIEW = "/analytics/overview";
+    APPROVAL_RESPOND = "/approvals/{id}/respond";
+    EVENTS = "/events";
+    EXECUTION_PROCESSES = "/execution-processes";
+    IMAGES = "/images";
+    ONBOARDING = "/onboarding";
+    DOCUMENTS_SYNC_STATUS = "/documents/sync-status";
+    PROJECTS = "/projects";
+    /// Documents of a project; document paths follow as `/{*relative_path}`
+    PROJECT_DOCUMENTS = "/projects/{id}/documents";
+    PROJECT_DOCUMENT_SNAPSHOTS = "/projects/{id}/documents/snapshots";
+    PROJECT_DOCUMENT_APPROVALS = "/projects/{id}/documents/approvals";
+    PROJECT_DOCUMENT_ENCRYPTION = "/projects/{id}/documents/encryption";
+    PROJECT_DOCUMENT_LOCKS = "/projects/{id}/documents/locks";
+    PROJECT_DOCUMENT_DRAFTS = "/projects/{id}/documents/drafts";
+    PROJECT_DOCUMENT_ANNOTATIONS = "/projects/{id}/documents/annotations";
+    PROJECT_DOCUMENT_TAGS = "/projects/{id}/documents/tags";
+    /// Signed export bundles of a project, for audits
+    PROJECT_EXPORT = "/projects/{id}/export";
+    PROJECT_DEPENDENCY_UPDATES = "/projects/{id}/dependency-updates";
+    PROJECT_FLAKY_TESTS = "/projects/{id}/flaky-tests";
+    PROJECT_WIP_LIMITS = "/projects/{id}/wip-limits";
+    PROJECT_TIMEZONE = "/projects/{id}/timezone";
+    /// API quota and background job usage of a project; not counted against the quota
+    PROJECT_USAGE = "/projects/{id}/usage";
+    /// Outbound webhooks called when a project's documents change
+    PROJECT_WEBHOOKS = "/projects/{id}/webhooks";
+    REMOTE_PROJECT = "/remote-projects/{remote_project_id}";
+    SESSIONS = "/sessions";
+    TAGS = "/tags";
+    TASKS = "/tasks";
+    TASK_ATTEMPTS = "/task-attempts";
+    TERMINAL_WS = "/terminal/ws";
+    /// Machines that run coding agents for this server
+    WORKERS = "/workers";
+    WORKERS_CONNECT = "/workers/connect";
+    /// Capabilities executions require of the worker running them
+    WORKER_REQUIREMENTS = "/workers/requirements";
+    WORKER = "/workers/{id}";
+    WORKER_DRAINING = "/workers/{id}/draining";
+}
+
+/// Render [`ALL`] as a TypeScript object literal
+pub fn typescript_constants() -> String {
+    let entries: Vec<String> = ALL
+        .iter()
+        .map(|(name, path)| format!("  {name}: \"{path}\","))
+        .collect();
+    format!(
+        "export const API_PREFIX = \"{API_PREFIX}\";\n\n\
+         export const ROUTES = {{\n{}\n}} as const;",
+        entries.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_are_unique_and_absolute() {
+        for (i, (name, path)) in ALL.iter().enumerate() {
+            assert!(path.starts_with('/'), "{name} must start with '/'");
+            assert!(!path.ends_with('/'), "{name} must not end with '/'");
+            assert!(
+                ALL[i + 1..].iter().all(|(_, other)| other != path),
+                "{name} is declared twice"
+            );
+        }
+    }
+
+    #[test]
+    fn exports_every_path() {
+        let ts = typescript_constants();
+        assert!(ts.contains("  PROJECT_DOCUMENTS: \"/projects/{id}/documents\","));
+        assert_eq!(ts.matches(": \"/").count(), ALL.len());
+    }
+}
@@ -0,0 +1,70 @@
+//! How much of the server's shares a project uses: its API requests this minute, counted
+//! by [`crate::middleware::enforce_project_quota`], and its background jobs, which run
+//! through [`background_job`].
+
+use std::{sync::LazyLock, time::Instant};
+
+use axum::{
+    Extension, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use db::models::project::Project;
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::{
+    config::ProjectLimitsConfig,
+    project_quotas::{
+        ApiQuota, ApiQuotaUsage, BackgroundJobUsage, BackgroundJobs, JobLimits, JobPermit,
+    },
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware, routes::paths};
+
+pub(crate) static API_QUOTA: LazyLock<ApiQuota> = LazyLock::new(ApiQuota::new);
+static BACKGROUND_JOBS: LazyLock<BackgroundJobs> = LazyLock::new(BackgroundJobs::new);
+
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectUsage {
+    pub api: ApiQuotaUsage,
+    pub background_jobs: BackgroundJobUsage,
+}
+
+fn job_limits(limits: &ProjectLimitsConfig) -> JobLimits {
+    JobLimits {
+        total: limits.background_jobs,
+        per_project: limits.background_jobs_per_project,
+    }
+}
+
+/// Wait for the project's turn to run a background job, such as a document sync. The
+/// job holds its slot until the permit is dropped.
+pub(crate) async fn background_job(deployment: &DeploymentImpl, project_id: Uuid) -> JobPermit {
+    let limits = job_limits(&deployment.config().read().await.project_limits);
+    BACKGROUND_JOBS.acquire(project_id, limits).await
+}
+
+pub async fn get_project_usage(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectUsage>>, ApiError> {
+    let limits = deployment.config().read().await.project_limits.clone();
+    Ok(ResponseJson(ApiResponse::success(ProjectUsage {
+        api: API_QUOTA.usage(project.id, limits.api_requests_per_minute, Instant::now()),
+        background_jobs: BACKGROUND_JOBS.usage(project.id, job_limits(&limits)),
+    })))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router =
+        Router::new()
+            .route("/", get(get_project_usage))
+            .layer(from_fn_with_state(
+                deployment.clone(),
+                load_project_middleware,
+            ));
+
+    Router::new().nest(paths::PROJECT_USAGE, project_router)
+}
@@ -10,18 +10,24 @@ use axum::{
     http::StatusCode,
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
-    routing::{get, post},
+    routing::{delete, get, post},
 };
 use db::models::{
-    project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
+    project::{
+        CloneProject, CreateProject, CreateProjectFromTemplate, Project, ProjectError,
+        SearchResult, UpdateProject,
+    },
     project_repo::{CreateProjectRepo, ProjectRepo},
+    project_seed_document::{ProjectSeedDocument, UpsertProjectSeedDocument},
     repo::Repo,
 };
 use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use services::services::{
-    file_search::SearchQuery, project::ProjectServiceError,
+    file_search::SearchQuery,
+    monorepo::{self, WorkspacePackage},
+    project::{ProjectServiceError, seed_document_path},
     remote_client::CreateRemoteProjectPayload,
 };
 use ts_rs::TS;
@@ -31,7 +37,12 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::{documents, paths},
+};
 
 #[derive(Deserialize, TS)]
 pub struct LinkToExistingRequest {
@@ -44,6 +55,14 @@ pub struct CreateRemoteProjectRequest {
     pub name: String,
 }
 
+/// Workspace packages detected in one of the project's repositories
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectRepoPackages {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub packages: Vec<WorkspacePackage>,
+}
+
 pub async fn get_projects(
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<Vec<Project>>>, ApiError> {
@@ -242,22 +261,68 @@ pub async fn create_project(
 
             Ok(ResponseJson(ApiResponse::success(project)))
         }
-        Err(ProjectServiceError::DuplicateGitRepoPath) => Ok(ResponseJson(ApiResponse::error(
+        Err(e) => project_creation_error(e),
+    }
+}
+
+/// Map project creation failures to user-facing errors
+fn project_creation_error(
+    e: ProjectServiceError,
+) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
+    match e {
+        ProjectServiceError::DuplicateGitRepoPath => Ok(ResponseJson(ApiResponse::error(
             "Duplicate repository path provided",
         ))),
-        Err(ProjectServiceError::DuplicateRepositoryName) => Ok(ResponseJson(ApiResponse::error(
+        ProjectServiceError::DuplicateRepositoryName => Ok(ResponseJson(ApiResponse::error(
             "Duplicate repository name provided",
         ))),
-        Err(ProjectServiceError::PathNotFound(_)) => Ok(ResponseJson(ApiResponse::error(
+        ProjectServiceError::PathNotFound(_) => Ok(ResponseJson(ApiResponse::error(
             "The specified path does not exist",
         ))),
-        Err(ProjectServiceError::PathNotDirectory(_)) => Ok(ResponseJson(ApiResponse::error(
+        ProjectServiceError::PathNotDirectory(_) => Ok(ResponseJson(ApiResponse::error(
             "The specified path is not a directory",
         ))),
-        Err(ProjectServiceError::NotGitRepository(_)) => Ok(ResponseJson(ApiResponse::error(
+        ProjectServiceError::NotGitRepository(_) => Ok(ResponseJson(ApiResponse::error(
             "The specified directory is not a git repository",
         ))),
-        Err(e) => Err(ProjectError::CreateFailed(e.to_string()).into()),
+        e => Err(ProjectError::CreateFailed(e.to_string()).into()),
+    }
+}
+
+pub async fn clone_project(
+    Extension(source): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CloneProject>,
+) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
+    tracing::debug!("Cloning project {} as '{}'", source.id, payload.name);
+    let repo_count = payload.repositories.len();
+    let include_open_tasks = payload.include_open_tasks;
+
+    match deployment
+        .project()
+        .clone_project(&deployment.db().pool, deployment.repo(), &source, payload)
+        .await
+    {
+        Ok(project) => {
+            deployment
+                .track_if_analytics_allowed(
+                    "project_created",
+                    serde_json::json!({
+                        "project_id": project.id.to_string(),
+                        "repository_count": repo_count,
+                        "trigger": "clone",
+                        "source_project_id": source.id.to_string(),
+                        "include_open_tasks": include_open_tasks,
+                    }),
+                )
+                .await;
+
+            let warnings = documents::write_seed_documents(&deployment, project.id).await?;
+            Ok(ResponseJson(
+                ApiResponse::success(project).with_warnings(warnings),
+            ))
+        }
+        Err(e) => project_creation_error(e),
     }
 }
 
@@ -431,6 +496,27 @@ pub async fn get_project_repositories(
     Ok(ResponseJson(ApiResponse::success(repositories)))
 }
 
+pub async fn get_project_packages(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectRepoPackages>>>, ApiError> {
+    let repositories = deployment
+        .project()
+        .get_repositories(&deployment.db().pool, project.id)
+        .await?;
+
+    let packages = repositories
+        .into_iter()
+        .map(|repo| ProjectRepoPackages {
+            packages: monorepo::detect_packages(&repo.path),
+            repo_id: repo.id,
+            repo_name: repo.name,
+        })
+        .collect();
+
+    Ok(ResponseJson(ApiResponse::success(packages)))
+}
+
 pub async fn add_project_repository(
     Extension(project): Extension<Project>,
     State(deployment): State<DeploymentImpl>,
@@ -568,6 +654,95 @@ pub async fn get_project_repository(
     }
 }
 
+pub async fn get_project_templates(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Project>>>, ApiError> {
+    let templates = Project::find_templates(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(templates)))
+}
+
+pub async fn create_project_from_template(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateProjectFromTemplate>,
+) -> Result<ResponseJson<ApiResponse<Project>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let template = match Project::find_by_id(pool, payload.template_id).await? {
+        Some(project) if project.is_template => project,
+        Some(_) => {
+            return Ok(ResponseJson(ApiResponse::error(
+                "Project is not a template",
+            )));
+        }
+        None => return Ok(ResponseJson(ApiResponse::error("Template not found"))),
+    };
+    let repo_count = payload.project.repositories.len();
+
+    match deployment
+        .project()
+        .clone_project(pool, deployment.repo(), &template, payload.project)
+        .await
+    {
+        Ok(project) => {
+            deployment
+                .track_if_analytics_allowed(
+                    "project_created",
+                    serde_json::json!({
+                        "project_id": project.id.to_string(),
+                        "repository_count": repo_count,
+                        "trigger": "template",
+                        "template_id": template.id.to_string(),
+                    }),
+                )
+                .await;
+
+            let warnings = documents::write_seed_documents(&deployment, project.id).await?;
+            Ok(ResponseJson(
+                ApiResponse::success(project).with_warnings(warnings),
+            ))
+        }
+        Err(e) => project_creation_error(e),
+    }
+}
+
+pub async fn get_project_seed_documents(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectSeedDocument>>>, ApiError> {
+    let documents =
+        ProjectSeedDocument::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(documents)))
+}
+
+pub async fn upsert_project_seed_document(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertProjectSeedDocument>,
+) -> Result<ResponseJson<ApiResponse<ProjectSeedDocument>>, ApiError> {
+    let Some(path) = seed_document_path(&payload.path) else {
+        return Err(ApiError::BadRequest(
+            "Seed document path must be relative to the repository root".to_string(),
+        ));
+    };
+    let payload = UpsertProjectSeedDocument {
+        path: path.to_string_lossy().to_string(),
+        content: payload.content,
+    };
+    let document = ProjectSeedDocument::upsert(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(document)))
+}
+
+pub async fn delete_project_seed_document(
+    State(deployment): State<DeploymentImpl>,
+    Path((project_id, document_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let deleted =
+        ProjectSeedDocument::delete(&deployment.db().pool, project_id, document_id).await?;
+    if deleted == 0 {
+        return Ok(ResponseJson(ApiResponse::error("Seed document not found")));
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let project_id_router = Router::new()
         .route(
@@ -586,6 +761,12 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
         )
+        .route("/packages", get(get_project_packages))
+        .route("/clone", post(clone_project))
+        .route(
+            "/seed-documents",
+            get(get_project_seed_documents).put(upsert_project_seed_document),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
@@ -597,11 +778,16 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/{project_id}/repositories/{repo_id}",
             get(get_project_repository).delete(delete_project_repository),
         )
+        .route(
+            "/{project_id}/seed-documents/{document_id}",
+            delete(delete_project_seed_document),
+        )
+        .route("/templates", get(get_project_templates))
+        .route("/from-template", post(create_project_from_template))
         .route("/stream/ws", get(stream_projects_ws))
         .nest("/{id}", project_id_router);
 
-    Router::new().nest("/projects", projects_router).route(
-        "/remote-projects/{remote_project_id}",
-        get(get_remote_project_by_id),
-    )
+    Router::new()
+        .nest(paths::PROJECTS, projects_router)
+        .route(paths::REMOTE_PROJECT, get(get_remote_project_by_id))
 }
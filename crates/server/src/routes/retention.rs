@@ -0,0 +1,49 @@
+use axum::{
+    Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use deployment::Deployment;
+use services::services::retention::{self, RetentionReport};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, routes::paths};
+
+/// What each retention policy would delete now, without deleting anything and whether
+/// or not retention is enabled
+pub async fn get_retention_report(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<RetentionReport>>>, ApiError> {
+    let settings = deployment.config().read().await.retention.clone();
+    let reports = retention::enforce(&deployment.db().pool, &settings, true).await?;
+    Ok(ResponseJson(ApiResponse::success(reports)))
+}
+
+/// Apply the retention policies now instead of at the next daily run
+pub async fn run_retention(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<RetentionReport>>>, ApiError> {
+    let settings = deployment.config().read().await.retention.clone();
+    if !settings.enabled {
+        return Err(ApiError::BadRequest(
+            "Retention is disabled; enable it in the config first".to_string(),
+        ));
+    }
+
+    let reports = retention::enforce(&deployment.db().pool, &settings, false).await?;
+    for report in reports.iter().filter(|report| !report.dry_run) {
+        tracing::info!(
+            "Retention run deleted {} items of {:?}",
+            report.items,
+            report.category
+        );
+    }
+    Ok(ResponseJson(ApiResponse::success(reports)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(paths::ADMIN_RETENTION_REPORT, get(get_retention_report))
+        .route(paths::ADMIN_RETENTION_RUN, post(run_retention))
+}
@@ -0,0 +1,135 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::execution_process::{ExecutionProcess, ExecutionProcessRunReason};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::self_update::{SelfUpdateError, SelfUpdateService, UpdateCheck};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, restart, routes::paths};
+
+/// How often to look for running agents while draining before a restart
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Restart anyway after this long; agents still running are stopped as on a normal shutdown
+const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+static UPDATE_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Serialize, TS)]
+pub struct UpdateInstallResponse {
+    pub version: String,
+    /// Attempts still running; the restart waits for them to finish
+    pub draining_processes: usize,
+}
+
+impl From<SelfUpdateError> for ApiError {
+    fn from(err: SelfUpdateError) -> Self {
+        match err {
+            SelfUpdateError::UpToDate(_) => ApiError::Conflict(err.to_string()),
+            SelfUpdateError::Io(e) => ApiError::Io(e),
+            _ => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
+
+fn drain_timeout() -> Duration {
+    std::env::var("VK_UPDATE_DRAIN_TIMEOUT_SECS")
+        .ok()
+        .and_then(|secs| secs.trim().parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_DRAIN_TIMEOUT)
+}
+
+/// Running processes that a restart would interrupt. Dev servers are restarted on demand.
+async fn draining_processes(deployment: &DeploymentImpl) -> Result<usize, sqlx::Error> {
+    let running = ExecutionProcess::find_running(&deployment.db().pool).await?;
+    Ok(running
+        .iter()
+        .filter(|process| process.run_reason != ExecutionProcessRunReason::DevServer)
+        .count())
+}
+
+/// Wait for running attempts to finish, then restart into the new binary
+fn spawn_drain_and_restart(deployment: DeploymentImpl) {
+    tokio::spawn(async move {
+        let deadline = Instant::now() + drain_timeout();
+        loop {
+            match draining_processes(&deployment).await {
+                Ok(0) => break,
+                Ok(count) if Instant::now() >= deadline => {
+                    tracing::warn!(
+                        "Restarting for update with {} process(es) still running",
+                        count
+                    );
+                    break;
+                }
+                Ok(count) => {
+                    tracing::debug!("Waiting for {} process(es) before restarting", count)
+                }
+                Err(e) => tracing::warn!("Failed to check running processes: {}", e),
+            }
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+        tracing::info!("Restarting to finish update");
+        restart::request();
+    });
+}
+
+pub async fn check_for_update() -> Result<ResponseJson<ApiResponse<UpdateCheck>>, ApiError> {
+    let check = SelfUpdateService::from_env()?.check().await?;
+    Ok(ResponseJson(ApiResponse::success(check)))
+}
+
+/// Download, verify and install the latest release, then restart once running attempts finish
+pub async fn install_update(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<UpdateInstallResponse>>, ApiError> {
+    if UPDATE_IN_PROGRESS.swap(true, Ordering::SeqCst) {
+        return Err(ApiError::Conflict(
+            "An update is already in progress".to_string(),
+        ));
+    }
+
+    let installed = match SelfUpdateService::from_env() {
+        Ok(service) => service.install().await,
+        Err(e) => Err(e),
+    };
+    let installed = match installed {
+        Ok(installed) => installed,
+        Err(e) => {
+            UPDATE_IN_PROGRESS.store(false, Ordering::SeqCst);
+            return Err(e.into());
+        }
+    };
+
+    // The new binary is in place; the flag stays set until the process restarts
+    let draining_processes = draining_processes(&deployment).await?;
+    spawn_drain_and_restart(deployment.clone());
+    deployment
+        .track_if_analytics_allowed(
+            "self_update_installed",
+            serde_json::json!({ "version": installed.version }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(UpdateInstallResponse {
+        version: installed.version,
+        draining_processes,
+    })))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(paths::VERSION_CHECK, get(check_for_update))
+        .route(paths::ADMIN_UPDATE, post(install_update))
+}
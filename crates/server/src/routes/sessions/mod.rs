@@ -32,8 +32,10 @@ use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_session_middleware,
-    routes::task_attempts::util::restore_worktrees_to_process,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_session_middleware,
+    routes::{paths, task_attempts::util::restore_worktrees_to_process},
 };
 
 #[derive(Debug, Deserialize)]
@@ -251,5 +253,5 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .nest("/{session_id}", session_id_router)
         .nest("/{session_id}/queue", queue::router(deployment));
 
-    Router::new().nest("/sessions", sessions_router)
+    Router::new().nest(paths::SESSIONS, sessions_router)
 }
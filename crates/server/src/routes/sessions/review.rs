@@ -21,7 +21,7 @@ use services::services::container::ContainerService;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, routes::task_attempts::preview_comparisons};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct StartReviewRequest {
@@ -97,7 +97,8 @@ pub async fn start_review(
         None
     };
 
-    let prompt = build_review_prompt(context.as_deref(), payload.additional_prompt.as_deref());
+    let mut prompt = build_review_prompt(context.as_deref(), payload.additional_prompt.as_deref());
+    prompt.push_str(&preview_comparisons::review_summary(&deployment, workspace.id).await?);
     let resumed_session = agent_session_id.is_some();
 
     let action = ExecutorAction::new(
@@ -0,0 +1,37 @@
+//! Read-only SQL console for operators, only built with the `sql-console` feature
+
+use axum::{
+    Json, Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use deployment::Deployment;
+use services::services::sql_console::{self, SqlConsoleTable, SqlQuery, SqlQueryResult};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, routes::paths};
+
+/// The tables queries can read, with their columns
+pub async fn get_sql_tables(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<SqlConsoleTable>>>, ApiError> {
+    let tables = sql_console::list_tables(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(tables)))
+}
+
+/// Run a read-only query against the allowlisted tables
+pub async fn run_sql_query(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SqlQuery>,
+) -> Result<ResponseJson<ApiResponse<SqlQueryResult>>, ApiError> {
+    tracing::info!("SQL console query: {}", payload.sql);
+    let result = sql_console::run_query(&deployment.db().pool, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(result)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(paths::ADMIN_SQL, post(run_sql_query))
+        .route(paths::ADMIN_SQL_TABLES, get(get_sql_tables))
+}
@@ -11,7 +11,7 @@ use serde::Deserialize;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_tag_middleware};
+use crate::{DeploymentImpl, error::ApiError, middleware::load_tag_middleware, routes::paths};
 
 #[derive(Deserialize, TS)]
 pub struct TagSearchParams {
@@ -94,5 +94,5 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_tags).post(create_tag))
         .nest("/{tag_id}", tag_router);
 
-    Router::new().nest("/tags", inner)
+    Router::new().nest(paths::TAGS, inner)
 }
@@ -1,8 +1,12 @@
 pub mod codex_setup;
 pub mod cursor_setup;
+pub mod e2e_runs;
 pub mod gh_cli_setup;
 pub mod images;
 pub mod pr;
+pub mod preview_builds;
+pub mod preview_comparisons;
+pub mod preview_screenshots;
 pub mod util;
 pub mod workspace_summary;
 
@@ -30,6 +34,7 @@ use db::models::{
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{Task, TaskRelationships, TaskStatus},
+    wip_limit::{WipLimit, WipLimitViolation},
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
     workspace_repo::{CreateWorkspaceRepo, RepoWithTargetBranch, WorkspaceRepo},
 };
@@ -56,8 +61,10 @@ use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_workspace_middleware,
-    routes::task_attempts::gh_cli_setup::GhCliSetupError,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_workspace_middleware,
+    routes::{paths, task_attempts::gh_cli_setup::GhCliSetupError, tasks},
 };
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -152,6 +159,10 @@ pub struct CreateTaskAttemptBody {
     pub task_id: Uuid,
     pub executor_profile_id: ExecutorProfileId,
     pub repos: Vec<WorkspaceRepoInput>,
+    /// Reason for starting the task while In Progress is at its WIP limit
+    #[serde(default)]
+    #[ts(optional)]
+    pub wip_override_reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ts_rs::TS)]
@@ -172,7 +183,7 @@ pub struct RunAgentSetupResponse {}
 pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTaskAttemptBody>,
-) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
+) -> Result<ResponseJson<ApiResponse<Workspace, WipLimitViolation>>, ApiError> {
     let executor_profile_id = payload.executor_profile_id.clone();
 
     if payload.repos.is_empty() {
@@ -186,17 +197,21 @@ pub async fn create_task_attempt(
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
-    // Compute agent_working_dir based on repo count:
-    // - Single repo: use repo name as working dir (agent runs in repo directory)
-    // - Multiple repos: use None (agent runs in workspace root)
-    let agent_working_dir = if payload.repos.len() == 1 {
-        let repo = Repo::find_by_id(pool, payload.repos[0].repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
-        Some(repo.name)
-    } else {
-        None
-    };
+    // Starting the attempt moves the task into In Progress
+    if task.status != TaskStatus::InProgress
+        && let Some(violation) =
+            WipLimit::check_move(pool, task.project_id, &TaskStatus::InProgress).await?
+    {
+        let Some(reason) = tasks::wip_override_reason(payload.wip_override_reason.as_deref())
+        else {
+            return Ok(ResponseJson(ApiResponse::error_with_data(violation)));
+        };
+        tasks::log_wip_override(pool, &task, &task.status, &violation, reason).await?;
+    }
+
+    let repo_ids: Vec<Uuid> = payload.repos.iter().map(|r| r.repo_id).collect();
+    let agent_working_dir =
+        util::resolve_agent_working_dir(pool, payload.task_id, &repo_ids).await?;
 
     let attempt_id = Uuid::new_v4();
     let git_branch_name = deployment
@@ -1777,7 +1792,17 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/stream/ws", get(stream_workspaces_ws))
         .route("/summary", post(workspace_summary::get_workspace_summaries))
         .nest("/{id}", task_attempt_id_router)
-        .nest("/{id}/images", images::router(deployment));
+        .nest("/{id}/images", images::router(deployment))
+        .nest("/{id}/preview-builds", preview_builds::router(deployment))
+        .nest(
+            "/{id}/preview-screenshots",
+            preview_screenshots::router(deployment),
+        )
+        .nest(
+            "/{id}/preview-comparisons",
+            preview_comparisons::router(deployment),
+        )
+        .nest("/{id}/e2e-runs", e2e_runs::router(deployment));
 
-    Router::new().nest("/task-attempts", task_attempts_router)
+    Router::new().nest(paths::TASK_ATTEMPTS, task_attempts_router)
 }
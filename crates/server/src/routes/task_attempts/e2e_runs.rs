@@ -0,0 +1,354 @@
+//! End-to-end test runs of an attempt: the end-to-end suites of its repositories run
+//! against its preview, a served preview build or a URL such as its dev server's. Their
+//! JUnit reports are parsed into test cases, and their traces, videos and screenshots
+//! are kept as artifacts for review.
+
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use axum::{
+    Extension, Router,
+    body::Body,
+    extract::{Path, State},
+    http::{HeaderValue, header},
+    middleware::from_fn_with_state,
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::get,
+};
+use db::models::{
+    e2e_run::{E2eRun, E2eRunStatus, E2eTestCase, E2eTestStatus},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    e2e_tests::{self, E2eArtifact, E2eTestError},
+};
+use tokio_util::io::ReaderStream;
+use ts_rs::TS;
+use url::Url;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_workspace_middleware,
+    routes::task_attempts::preview_screenshots::preview_build_url,
+};
+
+/// Results folder of repositories that don't set one, Playwright's default
+const DEFAULT_RESULTS_DIR: &str = "test-results";
+/// Completed runs kept per repository of an attempt; older artifacts are removed
+const KEPT_RUNS: i64 = 5;
+/// Slack for file systems that store modification times in whole seconds, so files
+/// written right after the suite started count as written during the run
+const MODIFIED_TIME_SLACK: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Deserialize, TS)]
+pub struct StartE2eRunRequest {
+    /// Preview build to run the suites against, served if it isn't yet
+    #[serde(default)]
+    #[ts(optional)]
+    pub build_id: Option<Uuid>,
+    /// URL of the preview instead, e.g. of the attempt's dev server
+    #[serde(default)]
+    #[ts(optional)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct E2eRunDetails {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub run: E2eRun,
+    /// Failed test cases first
+    pub test_cases: Vec<E2eTestCase>,
+    pub artifacts: Vec<E2eArtifact>,
+}
+
+pub async fn get_e2e_runs(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<E2eRun>>>, ApiError> {
+    let runs = E2eRun::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+/// Run the end-to-end suite of every repository of the attempt that has one against the
+/// preview. Runs go on in the background; their status is in the list of runs.
+pub async fn start_e2e_runs(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    ResponseJson(body): ResponseJson<StartE2eRunRequest>,
+) -> Result<ResponseJson<ApiResponse<Vec<E2eRun>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if E2eRun::has_running(pool, workspace.id).await? {
+        return Err(ApiError::Conflict(
+            "An end-to-end run is already going for this attempt".to_string(),
+        ));
+    }
+
+    let repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+    let repos_with_e2e_script: Vec<_> = repos
+        .into_iter()
+        .map(|r| r.repo)
+        .filter(|repo| {
+            repo.e2e_script
+                .as_ref()
+                .is_some_and(|s| !s.trim().is_empty())
+        })
+        .collect();
+    if repos_with_e2e_script.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "No end-to-end script configured for any repository in this workspace",
+        )));
+    }
+
+    let base_url = match (body.build_id, body.url) {
+        (Some(build_id), None) => {
+            preview_build_url(&deployment, workspace.id, build_id, false).await?
+        }
+        (None, Some(url)) => {
+            let url = url.trim().to_string();
+            match Url::parse(&url) {
+                Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => url,
+                _ => {
+                    return Err(ApiError::BadRequest(format!(
+                        "Invalid preview URL '{}'",
+                        url
+                    )));
+                }
+            }
+        }
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Give either a preview build or a URL".to_string(),
+            ));
+        }
+    };
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let workspace_path = PathBuf::from(container_ref);
+    let mut planned = Vec::with_capacity(repos_with_e2e_script.len());
+    for repo in repos_with_e2e_script {
+        let worktree_path = workspace_path.join(&repo.name);
+        let results_dir = repo
+            .e2e_results_dir
+            .clone()
+            .filter(|dir| !dir.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_RESULTS_DIR.to_string());
+        let results_path = e2e_tests::results_path(&worktree_path, &results_dir)
+            .map_err(|e| ApiError::BadRequest(format!("{}: {}", repo.display_name, e)))?;
+        planned.push((repo, worktree_path, results_path));
+    }
+
+    let mut runs = Vec::with_capacity(planned.len());
+    for (repo, worktree_path, results_path) in planned {
+        let commit_sha = deployment
+            .git()
+            .get_head_info(&worktree_path)
+            .ok()
+            .map(|head| head.oid);
+        let run = E2eRun::create(
+            pool,
+            workspace.id,
+            repo.id,
+            &base_url,
+            commit_sha.as_deref(),
+        )
+        .await?;
+        tokio::spawn(run_e2e_suite(
+            deployment.clone(),
+            run.clone(),
+            worktree_path,
+            repo.e2e_script.unwrap_or_default(),
+            results_path,
+        ));
+        runs.push(run);
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "e2e_run_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_count": runs.len(),
+                "from_build": body.build_id.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(runs)))
+}
+
+/// Run the suite, store its artifacts, parse its reports and record the outcome
+async fn run_e2e_suite(
+    deployment: DeploymentImpl,
+    run: E2eRun,
+    worktree_path: PathBuf,
+    script: String,
+    results_path: PathBuf,
+) {
+    let pool = &deployment.db().pool;
+    let started = SystemTime::now() - MODIFIED_TIME_SLACK;
+    let (output, suite_error) =
+        match e2e_tests::run_suite(&worktree_path, &script, &run.base_url, &results_path).await {
+            Ok(output) => (output, None),
+            Err((output, e)) => (output, Some(e)),
+        };
+
+    let artifacts_path = e2e_tests::artifacts_dir(run.id);
+    let stored = e2e_tests::store_artifacts(&results_path, &artifacts_path, started).await;
+    let test_cases = match &stored {
+        Ok(_) => e2e_tests::collect_test_cases(&artifacts_path, &results_path)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Failed to read the reports of end-to-end run {}: {}",
+                    run.id,
+                    e
+                );
+                Vec::new()
+            }),
+        Err(_) => Vec::new(),
+    };
+    let any_failed = test_cases
+        .iter()
+        .any(|test_case| test_case.status == E2eTestStatus::Failed);
+    // Runners exit unsuccessfully when a test fails, which the failed cases explain
+    let error = match (stored, suite_error) {
+        (Err(e), _) => Some(e.to_string()),
+        (Ok(_), Some(E2eTestError::SuiteFailed(_))) if any_failed => None,
+        (Ok(_), Some(e)) => Some(e.to_string()),
+        (Ok(_), None) => None,
+    };
+
+    match E2eRun::complete(pool, run.id, &output, error.as_deref(), &test_cases).await {
+        Ok(status) => tracing::info!(
+            "End-to-end run {} finished as {:?} with {} test cases",
+            run.id,
+            status,
+            test_cases.len()
+        ),
+        Err(e) => tracing::error!("Failed to record end-to-end run {}: {}", run.id, e),
+    }
+
+    match E2eRun::find_superseded(pool, run.workspace_id, run.repo_id, KEPT_RUNS).await {
+        Ok(superseded) => {
+            for run_id in superseded {
+                if let Err(e) = remove_run(&deployment, run_id).await {
+                    tracing::warn!("Failed to remove end-to-end run {}: {}", run_id, e);
+                }
+            }
+        }
+        Err(e) => tracing::warn!("Failed to find superseded end-to-end runs: {}", e),
+    }
+}
+
+/// Remove the artifacts and record of a run; its test cases are deleted with it
+async fn remove_run(deployment: &DeploymentImpl, run_id: Uuid) -> Result<(), ApiError> {
+    match tokio::fs::remove_dir_all(e2e_tests::artifacts_dir(run_id)).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    E2eRun::delete(&deployment.db().pool, run_id).await?;
+    Ok(())
+}
+
+async fn find_run(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+    run_id: Uuid,
+) -> Result<E2eRun, ApiError> {
+    E2eRun::find_by_id(&deployment.db().pool, run_id)
+        .await?
+        .filter(|run| run.workspace_id == workspace_id)
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))
+}
+
+pub async fn get_e2e_run(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, run_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<E2eRunDetails>>, ApiError> {
+    let run = find_run(&deployment, workspace_id, run_id).await?;
+    let test_cases = E2eTestCase::find_by_run_id(&deployment.db().pool, run.id).await?;
+    let artifacts = e2e_tests::list_artifacts(&e2e_tests::artifacts_dir(run.id)).await?;
+    Ok(ResponseJson(ApiResponse::success(E2eRunDetails {
+        run,
+        test_cases,
+        artifacts,
+    })))
+}
+
+/// A trace, video, screenshot or report of a run, which never changes once stored
+pub async fn get_e2e_run_artifact(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, run_id, path)): Path<(Uuid, Uuid, String)>,
+) -> Result<Response, ApiError> {
+    let run = find_run(&deployment, workspace_id, run_id).await?;
+    let file = e2e_tests::artifact_file(&e2e_tests::artifacts_dir(run.id), &path)
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    let mime = mime_guess::from_path(&file).first_or_octet_stream();
+    let body = Body::from_stream(ReaderStream::new(tokio::fs::File::open(&file).await?));
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_str(mime.as_ref())
+                    .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+            ),
+            (
+                header::CACHE_CONTROL,
+                HeaderValue::from_static("private, max-age=31536000, immutable"),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+pub async fn delete_e2e_run(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, run_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let run = find_run(&deployment, workspace_id, run_id).await?;
+    if run.status == E2eRunStatus::Running {
+        return Err(ApiError::Conflict(
+            "The end-to-end run is still going".to_string(),
+        ));
+    }
+    remove_run(&deployment, run.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Fail the runs that were going when the server stopped; their suites are gone
+pub async fn fail_interrupted_runs(deployment: &DeploymentImpl) {
+    match E2eRun::fail_interrupted(&deployment.db().pool).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Marked {} interrupted end-to-end runs as failed", count),
+        Err(e) => tracing::warn!("Failed to mark interrupted end-to-end runs: {}", e),
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let workspace_router = Router::new()
+        .route("/", get(get_e2e_runs).post(start_e2e_runs))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_workspace_middleware,
+        ));
+
+    let run_router = Router::new()
+        .route("/{run_id}", get(get_e2e_run).delete(delete_e2e_run))
+        .route("/{run_id}/artifacts/{*path}", get(get_e2e_run_artifact));
+
+    workspace_router.merge(run_router)
+}
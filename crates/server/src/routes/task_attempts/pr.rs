@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use axum::{
     Extension, Json,
@@ -11,6 +11,7 @@ use db::models::{
     repo::{Repo, RepoError},
     session::{CreateSession, Session},
     task::{Task, TaskStatus},
+    task_package_scope::TaskPackageScope,
     workspace::{Workspace, WorkspaceError},
     workspace_repo::WorkspaceRepo,
 };
@@ -22,6 +23,7 @@ use executors::actions::{
 use serde::{Deserialize, Serialize};
 use services::services::{
     container::ContainerService,
+    dependency_updates,
     git::{GitCliError, GitServiceError},
     git_host::{
         self, CreatePrRequest, GitHostError, GitHostProvider, ProviderKind, UnifiedPrComment,
@@ -31,7 +33,7 @@ use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, routes::task_attempts::preview_screenshots};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct CreatePrApiRequest {
@@ -42,6 +44,9 @@ pub struct CreatePrApiRequest {
     pub repo_id: Uuid,
     #[serde(default)]
     pub auto_generate_description: bool,
+    /// Add the attempt's preview screenshots to the body, for pull requests on GitHub
+    #[serde(default)]
+    pub include_screenshots: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -54,6 +59,7 @@ pub enum PrError {
     GitCliNotInstalled,
     TargetBranchNotFound { branch: String },
     UnsupportedProvider,
+    TestsFailed { command: String, output: String },
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -192,6 +198,49 @@ async fn trigger_pr_description_follow_up(
     Ok(())
 }
 
+/// Run the tests of a dependency update attempt when they have to pass before its pull
+/// request is opened, and return the error to respond with if they fail. The tests of a
+/// package-scoped task run in the package.
+async fn dependency_update_test_error(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo: &Repo,
+    worktree_path: &Path,
+) -> Result<Option<PrError>, ApiError> {
+    if !deployment
+        .config()
+        .read()
+        .await
+        .dependency_updates
+        .require_passing_tests
+    {
+        return Ok(None);
+    }
+    let pool = &deployment.db().pool;
+    let Some(task) = workspace.parent_task(pool).await? else {
+        return Ok(None);
+    };
+    if !dependency_updates::is_update_task(&task.title) {
+        return Ok(None);
+    }
+
+    let test_path = match TaskPackageScope::find_by_task_id(pool, task.id).await? {
+        Some(scope) if scope.repo_id == repo.id => worktree_path.join(&scope.package_path),
+        _ => worktree_path.to_path_buf(),
+    };
+    tracing::info!(
+        "Running the tests of dependency update task {} in {}",
+        task.id,
+        test_path.display()
+    );
+    Ok(dependency_updates::run_tests(&test_path)
+        .await?
+        .map(|failure| PrError::TestsFailed {
+            command: failure.command,
+            output: failure.output,
+        }))
+}
+
 pub async fn create_pr(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -263,6 +312,12 @@ pub async fn create_pr(
         Ok(true) => {}
     }
 
+    if let Some(error) =
+        dependency_update_test_error(&deployment, &workspace, &repo, &worktree_path).await?
+    {
+        return Ok(ResponseJson(ApiResponse::error_with_data(error)));
+    }
+
     if let Err(e) = git.push_to_remote(&worktree_path, &workspace.branch, false) {
         tracing::error!("Failed to push branch to remote: {}", e);
         match e {
@@ -297,10 +352,31 @@ pub async fn create_pr(
 
     let provider = git_host.provider_kind();
 
+    let mut body = request.body.clone();
+    if request.include_screenshots {
+        match preview_screenshots::pull_request_section(
+            &deployment,
+            &workspace,
+            &repo_path,
+            &push_remote_url,
+        )
+        .await
+        {
+            Ok(Some(section)) => {
+                body = Some(match body.filter(|body| !body.trim().is_empty()) {
+                    Some(body) => format!("{}\n\n{}", body.trim_end(), section),
+                    None => section,
+                });
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to add screenshots to the PR body: {}", e),
+        }
+    }
+
     // Create the PR
     let pr_request = CreatePrRequest {
         title: request.title.clone(),
-        body: request.body.clone(),
+        body,
         head_branch: workspace.branch.clone(),
         base_branch: base_branch.clone(),
         draft: request.draft,
@@ -0,0 +1,409 @@
+//! Preview builds of an attempt: its repositories built into static bundles by their
+//! preview build scripts, each served on its own port for review. Builds of the attempt's
+//! base, its fork point with the target branch, show the app as it was before.
+
+use std::path::PathBuf;
+
+use axum::{
+    Extension, Router,
+    extract::{Path, Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{delete, get, post},
+};
+use db::models::{
+    preview_build::{PreviewBuild, PreviewBuildStatus},
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    container::ContainerService,
+    preview_build::{self, PreviewBuildError},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_workspace_middleware};
+
+/// Output folder of repositories that don't set one
+const DEFAULT_OUTPUT_DIR: &str = "dist";
+/// Succeeded builds kept per repository of an attempt, and of its base; older bundles
+/// are removed
+const KEPT_BUILDS: i64 = 3;
+
+#[derive(Debug, Deserialize)]
+pub struct StartPreviewBuildsQuery {
+    /// Build the attempt's fork point with its target branch instead of its worktree
+    #[serde(default)]
+    pub base: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct PreviewBuildInfo {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub build: PreviewBuild,
+    /// Port the bundle is served on, while its server runs
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct PreviewBuildServer {
+    pub build_id: Uuid,
+    /// Port the bundle is served on, over HTTP on this server's host
+    pub port: u16,
+}
+
+pub async fn get_preview_builds(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<PreviewBuildInfo>>>, ApiError> {
+    let builds = PreviewBuild::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    let mut infos = Vec::with_capacity(builds.len());
+    for build in builds {
+        let port = deployment.preview_servers().port(build.id).await;
+        infos.push(PreviewBuildInfo { build, port });
+    }
+    Ok(ResponseJson(ApiResponse::success(infos)))
+}
+
+/// Build every repository of the attempt, or of its base, that has a preview build
+/// script. Builds run in the background; their status is in the list of builds.
+pub async fn start_preview_builds(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<StartPreviewBuildsQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<PreviewBuild>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if PreviewBuild::has_running(pool, workspace.id).await? {
+        return Err(ApiError::Conflict(
+            "A preview build is already running for this attempt".to_string(),
+        ));
+    }
+
+    let repos =
+        WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+    let repos_with_build_script: Vec<_> = repos
+        .into_iter()
+        .filter(|r| {
+            r.repo
+                .preview_build_script
+                .as_ref()
+                .is_some_and(|s| !s.trim().is_empty())
+        })
+        .collect();
+    if repos_with_build_script.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "No preview build script configured for any repository in this workspace",
+        )));
+    }
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let workspace_path = PathBuf::from(container_ref);
+    let mut planned = Vec::with_capacity(repos_with_build_script.len());
+    for with_target in repos_with_build_script {
+        let repo = with_target.repo;
+        let worktree_path = workspace_path.join(&repo.name);
+        let output_dir = repo
+            .preview_output_dir
+            .clone()
+            .filter(|dir| !dir.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_OUTPUT_DIR.to_string());
+        let output_path = preview_build::output_path(&worktree_path, &output_dir)
+            .map_err(|e| ApiError::BadRequest(format!("{}: {}", repo.display_name, e)))?;
+        let commit_sha = if query.base {
+            let fork_point = deployment
+                .git()
+                .get_fork_point(
+                    &worktree_path,
+                    &with_target.target_branch,
+                    &workspace.branch,
+                )
+                .map_err(|e| {
+                    ApiError::BadRequest(format!(
+                        "{}: failed to find the base of the attempt: {}",
+                        repo.display_name, e
+                    ))
+                })?;
+            Some(fork_point)
+        } else {
+            deployment
+                .git()
+                .get_head_info(&worktree_path)
+                .ok()
+                .map(|head| head.oid)
+        };
+        planned.push((repo, worktree_path, output_dir, output_path, commit_sha));
+    }
+
+    let mut builds = Vec::with_capacity(planned.len());
+    for (repo, worktree_path, output_dir, output_path, commit_sha) in planned {
+        let build = PreviewBuild::create(
+            pool,
+            workspace.id,
+            repo.id,
+            commit_sha.as_deref(),
+            query.base,
+        )
+        .await?;
+        let script = repo.preview_build_script.unwrap_or_default();
+        match commit_sha.filter(|_| query.base) {
+            Some(fork_point) => {
+                tokio::spawn(run_base_preview_build(
+                    deployment.clone(),
+                    build.clone(),
+                    repo.path.clone(),
+                    fork_point,
+                    script,
+                    output_dir,
+                ));
+            }
+            None => {
+                tokio::spawn(run_preview_build(
+                    deployment.clone(),
+                    build.clone(),
+                    worktree_path,
+                    script,
+                    output_path,
+                ));
+            }
+        }
+        builds.push(build);
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "preview_build_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_count": builds.len(),
+                "base": query.base,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(builds)))
+}
+
+/// Run the build script, store the bundle and record the outcome
+async fn run_preview_build(
+    deployment: DeploymentImpl,
+    build: PreviewBuild,
+    worktree_path: PathBuf,
+    script: String,
+    output_path: PathBuf,
+) {
+    let pool = &deployment.db().pool;
+    let (output, stored) = match preview_build::run_script(&worktree_path, &script).await {
+        Ok(output) => {
+            let stored =
+                preview_build::store_bundle(&output_path, &preview_build::bundle_dir(build.id))
+                    .await;
+            (output, stored)
+        }
+        Err((output, e)) => (output, Err(e)),
+    };
+
+    let recorded = match &stored {
+        Ok(size) => {
+            tracing::info!("Preview build {} stored {} bytes", build.id, size);
+            PreviewBuild::complete(
+                pool,
+                build.id,
+                PreviewBuildStatus::Succeeded,
+                &output,
+                None,
+                Some(*size as i64),
+            )
+            .await
+        }
+        Err(e) => {
+            tracing::warn!("Preview build {} failed: {}", build.id, e);
+            PreviewBuild::complete(
+                pool,
+                build.id,
+                PreviewBuildStatus::Failed,
+                &output,
+                Some(&e.to_string()),
+                None,
+            )
+            .await
+        }
+    };
+    if let Err(e) = recorded {
+        tracing::error!("Failed to record preview build {}: {}", build.id, e);
+    }
+
+    if stored.is_ok() {
+        match PreviewBuild::find_superseded(
+            pool,
+            build.workspace_id,
+            build.repo_id,
+            build.base,
+            KEPT_BUILDS,
+        )
+        .await
+        {
+            Ok(superseded) => {
+                for build_id in superseded {
+                    if let Err(e) = remove_build(&deployment, build_id).await {
+                        tracing::warn!("Failed to remove preview build {}: {}", build_id, e);
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("Failed to find superseded preview builds: {}", e),
+        }
+    }
+}
+
+/// Check the base out of the repository at `repo_path` next to the attempt's worktrees,
+/// build it there and remove the checkout again
+async fn run_base_preview_build(
+    deployment: DeploymentImpl,
+    build: PreviewBuild,
+    repo_path: PathBuf,
+    fork_point: String,
+    script: String,
+    output_dir: String,
+) {
+    let checkout_path = preview_build::base_checkout_dir(build.id);
+    let checked_out = match checkout_path.parent() {
+        Some(parent) => tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| PreviewBuildError::Checkout(e.to_string())),
+        None => Ok(()),
+    }
+    .and_then(|()| {
+        deployment
+            .git()
+            .add_worktree(&repo_path, &checkout_path, &fork_point, false)
+            .map_err(|e| PreviewBuildError::Checkout(e.to_string()))
+    })
+    .and_then(|()| preview_build::output_path(&checkout_path, &output_dir));
+
+    match checked_out {
+        Ok(output_path) => {
+            run_preview_build(
+                deployment.clone(),
+                build,
+                checkout_path.clone(),
+                script,
+                output_path,
+            )
+            .await;
+        }
+        Err(e) => {
+            tracing::warn!("Preview build {} failed: {}", build.id, e);
+            if let Err(e) = PreviewBuild::complete(
+                &deployment.db().pool,
+                build.id,
+                PreviewBuildStatus::Failed,
+                "",
+                Some(&e.to_string()),
+                None,
+            )
+            .await
+            {
+                tracing::error!("Failed to record preview build {}: {}", build.id, e);
+            }
+        }
+    }
+
+    if checkout_path.exists()
+        && let Err(e) = deployment
+            .git()
+            .remove_worktree(&repo_path, &checkout_path, true)
+    {
+        tracing::warn!("Failed to remove base checkout {:?}: {}", checkout_path, e);
+    }
+}
+
+/// Stop the server of a build and remove its bundle and record
+async fn remove_build(deployment: &DeploymentImpl, build_id: Uuid) -> Result<(), ApiError> {
+    deployment.preview_servers().stop(build_id).await;
+    match tokio::fs::remove_dir_all(preview_build::bundle_dir(build_id)).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    PreviewBuild::delete(&deployment.db().pool, build_id).await?;
+    Ok(())
+}
+
+pub(crate) async fn find_build(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+    build_id: Uuid,
+) -> Result<PreviewBuild, ApiError> {
+    PreviewBuild::find_by_id(&deployment.db().pool, build_id)
+        .await?
+        .filter(|build| build.workspace_id == workspace_id)
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))
+}
+
+/// Serve the bundle of a succeeded build, on a port of its own
+pub async fn serve_preview_build(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, build_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<PreviewBuildServer>>, ApiError> {
+    let build = find_build(&deployment, workspace_id, build_id).await?;
+    if build.status != PreviewBuildStatus::Succeeded {
+        return Err(ApiError::BadRequest(
+            "Only succeeded preview builds can be served".to_string(),
+        ));
+    }
+    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = deployment
+        .preview_servers()
+        .serve(build.id, &host)
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to serve preview build: {}", e)))?;
+    Ok(ResponseJson(ApiResponse::success(PreviewBuildServer {
+        build_id: build.id,
+        port,
+    })))
+}
+
+pub async fn delete_preview_build(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, build_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let build = find_build(&deployment, workspace_id, build_id).await?;
+    if build.status == PreviewBuildStatus::Running {
+        return Err(ApiError::Conflict(
+            "The preview build is still running".to_string(),
+        ));
+    }
+    remove_build(&deployment, build.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Fail the builds that were running when the server stopped; their scripts are gone
+pub async fn fail_interrupted_builds(deployment: &DeploymentImpl) {
+    match PreviewBuild::fail_interrupted(&deployment.db().pool).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Marked {} interrupted preview builds as failed", count),
+        Err(e) => tracing::warn!("Failed to mark interrupted preview builds: {}", e),
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let workspace_router = Router::new()
+        .route("/", get(get_preview_builds).post(start_preview_builds))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_workspace_middleware,
+        ));
+
+    let build_router = Router::new()
+        .route("/{build_id}", delete(delete_preview_build))
+        .route("/{build_id}/serve", post(serve_preview_build));
+
+    workspace_router.merge(build_router)
+}
@@ -0,0 +1,106 @@
+//! Comparisons of an attempt's preview with its base, made while taking screenshots of
+//! the preview. Significant visual changes are summed up for reviews of the attempt.
+
+use axum::{
+    Extension, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::{Json as ResponseJson, Response},
+    routing::get,
+};
+use db::models::{
+    preview_comparison::PreviewComparison, preview_screenshot::PreviewScreenshot,
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use services::services::preview_comparison;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_workspace_middleware,
+    routes::task_attempts::preview_screenshots::png_response,
+};
+
+pub async fn get_preview_comparisons(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<PreviewComparison>>>, ApiError> {
+    let comparisons =
+        PreviewComparison::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(comparisons)))
+}
+
+async fn find_comparison(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+    comparison_id: Uuid,
+) -> Result<PreviewComparison, ApiError> {
+    PreviewComparison::find_by_id(&deployment.db().pool, comparison_id)
+        .await?
+        .filter(|comparison| comparison.workspace_id == workspace_id)
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))
+}
+
+/// Screenshot of the base the attempt's screenshot was compared with
+pub async fn get_preview_comparison_base_image(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, comparison_id)): Path<(Uuid, Uuid)>,
+) -> Result<Response, ApiError> {
+    let comparison = find_comparison(&deployment, workspace_id, comparison_id).await?;
+    png_response(&preview_comparison::base_screenshot_path(comparison.id)).await
+}
+
+/// The attempt's screenshot with the pixels that changed from the base highlighted
+pub async fn get_preview_comparison_diff_image(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, comparison_id)): Path<(Uuid, Uuid)>,
+) -> Result<Response, ApiError> {
+    let comparison = find_comparison(&deployment, workspace_id, comparison_id).await?;
+    png_response(&preview_comparison::diff_image_path(comparison.id)).await
+}
+
+/// Section of the prompt of a review of the workspace on the visual changes of its
+/// preview, empty if its preview was never compared with its base
+pub(crate) async fn review_summary(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+) -> Result<String, ApiError> {
+    let pool = &deployment.db().pool;
+    let comparisons = PreviewComparison::find_by_workspace_id(pool, workspace_id).await?;
+    if comparisons.is_empty() {
+        return Ok(String::new());
+    }
+    let screenshots = PreviewScreenshot::find_by_workspace_id(pool, workspace_id).await?;
+    let compared: Vec<_> = comparisons
+        .iter()
+        .filter_map(|comparison| {
+            screenshots
+                .iter()
+                .find(|screenshot| screenshot.id == comparison.screenshot_id)
+                .map(|screenshot| (comparison, screenshot))
+        })
+        .collect();
+    Ok(preview_comparison::review_summary(&compared))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let workspace_router = Router::new()
+        .route("/", get(get_preview_comparisons))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_workspace_middleware,
+        ));
+
+    let comparison_router = Router::new()
+        .route(
+            "/{comparison_id}/base",
+            get(get_preview_comparison_base_image),
+        )
+        .route(
+            "/{comparison_id}/diff",
+            get(get_preview_comparison_diff_image),
+        );
+
+    workspace_router.merge(comparison_router)
+}
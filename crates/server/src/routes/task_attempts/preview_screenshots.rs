@@ -0,0 +1,482 @@
+//! Screenshots of an attempt's preview, a served preview build or a URL such as its dev
+//! server's, which can be added to the body of the attempt's pull request. Taken next to
+//! screenshots of the attempt's base, they are compared with them for visual changes.
+
+use std::path::{Path as FsPath, PathBuf};
+
+use axum::{
+    Extension, Router,
+    body::Body,
+    extract::{Path, State},
+    http::header,
+    middleware::from_fn_with_state,
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::{delete, get},
+};
+use db::models::{
+    preview_build::PreviewBuildStatus,
+    preview_comparison::{CreatePreviewComparison, PreviewComparison},
+    preview_screenshot::{CreatePreviewScreenshot, PreviewScreenshot},
+    workspace::Workspace,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    preview_comparison::{self, DEFAULT_THRESHOLD},
+    preview_screenshot::{self, MAX_SCREENSHOTS, Viewport},
+};
+use tokio_util::io::ReaderStream;
+use ts_rs::TS;
+use url::Url;
+use utils::response::{ApiResponse, ApiWarning};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_workspace_middleware,
+    routes::task_attempts::preview_builds::find_build,
+};
+
+/// Prefix of the branches screenshots are published to, followed by the attempt's branch
+const SCREENSHOTS_BRANCH_PREFIX: &str = "vibe-screenshots/";
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CapturePreviewScreenshotsRequest {
+    /// Preview build to take the screenshots of, served if it isn't yet
+    #[serde(default)]
+    #[ts(optional)]
+    pub build_id: Option<Uuid>,
+    /// URL of the preview instead, e.g. of the attempt's dev server
+    #[serde(default)]
+    #[ts(optional)]
+    pub url: Option<String>,
+    /// Routes of the preview to take screenshots of, `/` if none are given
+    #[serde(default)]
+    #[ts(optional)]
+    pub routes: Option<Vec<String>>,
+    /// Viewports to take each route at, a desktop and a mobile one if none are given
+    #[serde(default)]
+    #[ts(optional)]
+    pub viewports: Option<Vec<Viewport>>,
+    /// Preview build of the attempt's base to compare every screenshot with
+    #[serde(default)]
+    #[ts(optional)]
+    pub base_build_id: Option<Uuid>,
+    /// URL of the base's preview to compare with instead, e.g. a deployment of the
+    /// target branch
+    #[serde(default)]
+    #[ts(optional)]
+    pub base_url: Option<String>,
+    /// Share of changed pixels above which a visual change is significant
+    #[serde(default)]
+    #[ts(optional)]
+    pub threshold: Option<f64>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct CapturedPreviewScreenshots {
+    pub screenshots: Vec<PreviewScreenshot>,
+    /// Comparisons of the screenshots with the base's, if a base was given
+    pub comparisons: Vec<PreviewComparison>,
+}
+
+pub async fn get_preview_screenshots(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<PreviewScreenshot>>>, ApiError> {
+    let screenshots =
+        PreviewScreenshot::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(screenshots)))
+}
+
+/// Take a screenshot of every route of the preview at every viewport, and compare it
+/// with one of the base if a base is given. Screenshots and comparisons that fail are
+/// reported as warnings, unless all screenshots fail.
+pub async fn capture_preview_screenshots(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    ResponseJson(body): ResponseJson<CapturePreviewScreenshotsRequest>,
+) -> Result<ResponseJson<ApiResponse<CapturedPreviewScreenshots>>, ApiError> {
+    let routes = body
+        .routes
+        .filter(|routes| !routes.is_empty())
+        .unwrap_or_else(|| vec!["/".to_string()]);
+    let viewports = body
+        .viewports
+        .filter(|viewports| !viewports.is_empty())
+        .unwrap_or_else(Viewport::defaults);
+    if routes.len() * viewports.len() > MAX_SCREENSHOTS {
+        return Err(ApiError::BadRequest(format!(
+            "At most {} screenshots can be taken at once",
+            MAX_SCREENSHOTS
+        )));
+    }
+    for viewport in &viewports {
+        viewport
+            .validate()
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    }
+    let threshold = body.threshold.unwrap_or(DEFAULT_THRESHOLD);
+    preview_comparison::validate_threshold(threshold)
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let preview_url = match (body.build_id, body.url) {
+        (Some(build_id), None) => {
+            preview_build_url(&deployment, workspace.id, build_id, false).await?
+        }
+        (None, Some(url)) => url,
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Give either a preview build or a URL".to_string(),
+            ));
+        }
+    };
+    let base_preview_url = match (body.base_build_id, body.base_url) {
+        (Some(build_id), None) => {
+            Some(preview_build_url(&deployment, workspace.id, build_id, true).await?)
+        }
+        (None, Some(url)) => Some(url),
+        (None, None) => None,
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Give either a preview build or a URL of the base".to_string(),
+            ));
+        }
+    };
+    let mut pages = Vec::with_capacity(routes.len());
+    for route in &routes {
+        let url = preview_screenshot::page_url(&preview_url, route)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        let base_url = base_preview_url
+            .as_deref()
+            .map(|base_url| preview_screenshot::page_url(base_url, route))
+            .transpose()
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        pages.push((route.trim().to_string(), url, base_url));
+    }
+    let browser = preview_screenshot::find_browser()
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let pool = &deployment.db().pool;
+    let mut screenshots = Vec::new();
+    let mut comparisons = Vec::new();
+    let mut warnings = Vec::new();
+    let mut last_error = None;
+    for (route, url, base_url) in &pages {
+        for viewport in &viewports {
+            let id = Uuid::new_v4();
+            let output = preview_screenshot::screenshot_path(id);
+            let size = match preview_screenshot::capture(&browser, url, viewport, &output).await {
+                Ok(size) => size,
+                Err(e) => {
+                    tracing::warn!("Screenshot of {} at {} failed: {}", url, viewport.name, e);
+                    let _ = tokio::fs::remove_file(&output).await;
+                    warnings.push(ApiWarning::new(
+                        "screenshot_failed",
+                        format!("{} at {}: {}", route, viewport.name, e),
+                    ));
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+            let data = CreatePreviewScreenshot {
+                workspace_id: workspace.id,
+                url: url.to_string(),
+                route: route.clone(),
+                viewport: viewport.name.clone(),
+                width: viewport.width as i64,
+                height: viewport.height as i64,
+                size_bytes: size as i64,
+            };
+            let screenshot = PreviewScreenshot::create(pool, &data, id).await?;
+
+            if let Some(base_url) = base_url {
+                match compare_with_base(
+                    &deployment,
+                    &browser,
+                    &screenshot,
+                    base_url,
+                    viewport,
+                    threshold,
+                )
+                .await
+                {
+                    Ok(comparison) => comparisons.push(comparison),
+                    Err(e) => {
+                        tracing::warn!(
+                            "Comparison of {} at {} with the base failed: {}",
+                            url,
+                            viewport.name,
+                            e
+                        );
+                        warnings.push(ApiWarning::new(
+                            "comparison_failed",
+                            format!("{} at {}: {}", route, viewport.name, e),
+                        ));
+                    }
+                }
+            }
+            screenshots.push(screenshot);
+        }
+    }
+    if screenshots.is_empty()
+        && let Some(e) = last_error
+    {
+        return Err(ApiError::BadRequest(e.to_string()));
+    }
+    let significant = comparisons.iter().filter(|c| c.significant).count();
+    if significant > 0 {
+        warnings.push(ApiWarning::new(
+            "visual_changes",
+            format!(
+                "{} of {} compared screenshots differ significantly from the base",
+                significant,
+                comparisons.len()
+            ),
+        ));
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "preview_screenshots_captured",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "screenshot_count": screenshots.len(),
+                "comparison_count": comparisons.len(),
+                "significant_count": significant,
+                "warning_count": warnings.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(
+        ApiResponse::success(CapturedPreviewScreenshots {
+            screenshots,
+            comparisons,
+        })
+        .with_warnings(warnings),
+    ))
+}
+
+/// Take a screenshot of the base's page at `base_url` the way `screenshot` was taken,
+/// and compare the two
+async fn compare_with_base(
+    deployment: &DeploymentImpl,
+    browser: &FsPath,
+    screenshot: &PreviewScreenshot,
+    base_url: &Url,
+    viewport: &Viewport,
+    threshold: f64,
+) -> Result<PreviewComparison, ApiError> {
+    let id = Uuid::new_v4();
+    let base_path = preview_comparison::base_screenshot_path(id);
+    let diff_path = preview_comparison::diff_image_path(id);
+    let compared = async {
+        preview_screenshot::capture(browser, base_url, viewport, &base_path)
+            .await
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        preview_comparison::compare_files(
+            &base_path,
+            &preview_screenshot::screenshot_path(screenshot.id),
+            &diff_path,
+        )
+        .await
+        .map_err(|e| ApiError::BadRequest(e.to_string()))
+    }
+    .await;
+    let diff = match compared {
+        Ok(diff) => diff,
+        Err(e) => {
+            remove_comparison_files(id).await;
+            return Err(e);
+        }
+    };
+
+    let data = CreatePreviewComparison {
+        workspace_id: screenshot.workspace_id,
+        screenshot_id: screenshot.id,
+        base_url: base_url.to_string(),
+        changed_pixels: diff.changed_pixels as i64,
+        total_pixels: diff.total_pixels as i64,
+        changed_regions: diff.changed_regions as i64,
+        total_regions: diff.total_regions as i64,
+        size_changed: diff.size_changed,
+        significant: diff.is_significant(threshold),
+    };
+    Ok(PreviewComparison::create(&deployment.db().pool, &data, id).await?)
+}
+
+async fn remove_comparison_files(comparison_id: Uuid) {
+    for path in [
+        preview_comparison::base_screenshot_path(comparison_id),
+        preview_comparison::diff_image_path(comparison_id),
+    ] {
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!("Failed to remove {:?}: {}", path, e),
+        }
+    }
+}
+
+/// URL the browser reaches the preview build `build_id` at, serving it if needed. The
+/// build has to be of the attempt's base if `base` is set, else of the attempt.
+pub(crate) async fn preview_build_url(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+    build_id: Uuid,
+    base: bool,
+) -> Result<String, ApiError> {
+    let build = find_build(deployment, workspace_id, build_id).await?;
+    if build.status != PreviewBuildStatus::Succeeded {
+        return Err(ApiError::BadRequest(
+            "Only succeeded preview builds can be served".to_string(),
+        ));
+    }
+    if build.base != base {
+        return Err(ApiError::BadRequest(if base {
+            "The base's preview build is a build of the attempt".to_string()
+        } else {
+            "The preview build is a build of the attempt's base".to_string()
+        }));
+    }
+    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = deployment
+        .preview_servers()
+        .serve(build.id, &host)
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to serve preview build: {}", e)))?;
+    // A server on every address is reached on the loopback one
+    let host = match host.as_str() {
+        "0.0.0.0" | "::" => "127.0.0.1",
+        host => host,
+    };
+    Ok(format!("http://{}:{}/", host, port))
+}
+
+async fn find_screenshot(
+    deployment: &DeploymentImpl,
+    workspace_id: Uuid,
+    screenshot_id: Uuid,
+) -> Result<PreviewScreenshot, ApiError> {
+    PreviewScreenshot::find_by_id(&deployment.db().pool, screenshot_id)
+        .await?
+        .filter(|screenshot| screenshot.workspace_id == workspace_id)
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))
+}
+
+pub async fn get_preview_screenshot_image(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, screenshot_id)): Path<(Uuid, Uuid)>,
+) -> Result<Response, ApiError> {
+    let screenshot = find_screenshot(&deployment, workspace_id, screenshot_id).await?;
+    png_response(&preview_screenshot::screenshot_path(screenshot.id)).await
+}
+
+/// The PNG image at `path`, which never changes once written
+pub(crate) async fn png_response(path: &FsPath) -> Result<Response, ApiError> {
+    let file = tokio::fs::File::open(path).await?;
+    let body = Body::from_stream(ReaderStream::new(file));
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/png"),
+            (
+                header::CACHE_CONTROL,
+                "private, max-age=31536000, immutable",
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+pub async fn delete_preview_screenshot(
+    State(deployment): State<DeploymentImpl>,
+    Path((workspace_id, screenshot_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let screenshot = find_screenshot(&deployment, workspace_id, screenshot_id).await?;
+    match tokio::fs::remove_file(preview_screenshot::screenshot_path(screenshot.id)).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    let pool = &deployment.db().pool;
+    // Its comparisons are deleted with it
+    for comparison_id in PreviewComparison::find_ids_by_screenshot_id(pool, screenshot.id).await? {
+        remove_comparison_files(comparison_id).await;
+    }
+    PreviewScreenshot::delete(pool, screenshot.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Section with the attempt's screenshots for the body of its pull request, after
+/// publishing them to a branch of the GitHub repository at `remote_url` next to the
+/// attempt's branch. `None` if the attempt has no screenshots or the remote isn't on
+/// GitHub, whose pull requests can show images from a branch.
+pub(crate) async fn pull_request_section(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo_path: &FsPath,
+    remote_url: &str,
+) -> Result<Option<String>, ApiError> {
+    let screenshots =
+        PreviewScreenshot::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    if screenshots.is_empty() {
+        return Ok(None);
+    }
+    let Some(web_url) = preview_screenshot::github_web_url(remote_url) else {
+        tracing::info!("Not adding screenshots to a pull request outside GitHub");
+        return Ok(None);
+    };
+
+    let mut files: Vec<(String, PathBuf)> = Vec::with_capacity(screenshots.len());
+    let mut published = Vec::with_capacity(screenshots.len());
+    for (index, screenshot) in screenshots.iter().enumerate() {
+        let path = preview_screenshot::screenshot_path(screenshot.id);
+        if !path.is_file() {
+            continue;
+        }
+        let name = preview_screenshot::published_file_name(index, screenshot);
+        files.push((name, path));
+        published.push(screenshot);
+    }
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let branch = format!("{}{}", SCREENSHOTS_BRANCH_PREFIX, workspace.branch);
+    deployment.git().publish_files(
+        repo_path,
+        remote_url,
+        &branch,
+        &files,
+        &format!("Screenshots of {}", workspace.branch),
+    )?;
+    let images: Vec<(&PreviewScreenshot, String)> = published
+        .into_iter()
+        .zip(files)
+        .map(|(screenshot, (name, _))| {
+            let image_url = format!("{}/blob/{}/{}?raw=true", web_url, branch, name);
+            (screenshot, image_url)
+        })
+        .collect();
+    Ok(Some(preview_screenshot::pull_request_section(&images)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let workspace_router = Router::new()
+        .route(
+            "/",
+            get(get_preview_screenshots).post(capture_preview_screenshots),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_workspace_middleware,
+        ));
+
+    let screenshot_router = Router::new()
+        .route("/{screenshot_id}", delete(delete_preview_screenshot))
+        .route("/{screenshot_id}/image", get(get_preview_screenshot_image));
+
+    workspace_router.merge(screenshot_router)
+}
@@ -1,8 +1,12 @@
 use std::path::PathBuf;
 
 use db::models::{
-    execution_process::ExecutionProcess, execution_process_repo_state::ExecutionProcessRepoState,
-    workspace::Workspace, workspace_repo::WorkspaceRepo,
+    execution_process::ExecutionProcess,
+    execution_process_repo_state::ExecutionProcessRepoState,
+    repo::{Repo, RepoError},
+    task_package_scope::TaskPackageScope,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
 };
 use deployment::Deployment;
 use services::services::{container::ContainerService, git::WorktreeResetOptions};
@@ -11,6 +15,37 @@ use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
 
+/// Compute the agent working directory for a new workspace:
+/// - Task scoped to a package of one of the selected repos: `<repo name>/<package path>`
+/// - Single repo: use repo name as working dir (agent runs in repo directory)
+/// - Multiple repos: use None (agent runs in workspace root)
+pub async fn resolve_agent_working_dir(
+    pool: &SqlitePool,
+    task_id: Uuid,
+    repo_ids: &[Uuid],
+) -> Result<Option<String>, ApiError> {
+    if let Some(scope) = TaskPackageScope::find_by_task_id(pool, task_id).await?
+        && repo_ids.contains(&scope.repo_id)
+    {
+        let repo = Repo::find_by_id(pool, scope.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+        if scope.package_path.is_empty() {
+            return Ok(Some(repo.name));
+        }
+        return Ok(Some(format!("{}/{}", repo.name, scope.package_path)));
+    }
+
+    if let [repo_id] = repo_ids {
+        let repo = Repo::find_by_id(pool, *repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+        Ok(Some(repo.name))
+    } else {
+        Ok(None)
+    }
+}
+
 /// Reset all repository worktrees to the state before the given process.
 /// For each repo, finds the before_head_commit from the target process,
 /// or falls back to the previous process's after_head_commit.
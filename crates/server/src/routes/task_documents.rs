@@ -0,0 +1,118 @@
+//! Documents related to tasks, e.g. the design docs a task implements. Agents working on
+//! a task are pointed at its documents, and each document lists the tasks it relates to.
+
+use axum::{
+    Extension, Json,
+    extract::{Path as AxumPath, Query, State},
+    response::Json as ResponseJson,
+};
+use db::models::{project::Project, task::Task, task_document::TaskDocument};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::documents::{RepositoryQuery, find_document_file, validate_relative_path},
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct AttachTaskDocument {
+    /// Path of the document relative to its repository
+    pub path: String,
+    /// Repository of the document, for projects whose repositories share paths
+    #[serde(default)]
+    #[ts(optional)]
+    pub repository_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RelatedTask {
+    /// Relation of the task to the document
+    pub link_id: Uuid,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub task: Task,
+}
+
+pub async fn get_task_documents(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskDocument>>>, ApiError> {
+    let documents = TaskDocument::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(documents)))
+}
+
+/// Relate a document of the task's project to the task
+pub async fn attach_task_document(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(body): Json<AttachTaskDocument>,
+) -> Result<ResponseJson<ApiResponse<TaskDocument>>, ApiError> {
+    let path = validate_relative_path(body.path.trim())?;
+    let (repo, _) =
+        find_document_file(&deployment, task.project_id, body.repository_id, path).await?;
+    let document = TaskDocument::create(&deployment.db().pool, task.id, repo.id, path).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_document_attached",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": task.project_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(document)))
+}
+
+pub async fn detach_task_document(
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((task_id, link_id)): AxumPath<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let document = TaskDocument::find_by_id(pool, link_id)
+        .await?
+        .filter(|document| document.task_id == task_id)
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    TaskDocument::delete(pool, document.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Tasks of the project a document is related to, dispatched from the document
+/// wildcard route as `<path>/tasks`
+pub async fn get_document_tasks(
+    State(deployment): State<DeploymentImpl>,
+    Extension(project): Extension<Project>,
+    AxumPath((_id, relative_path)): AxumPath<(Uuid, String)>,
+    Query(repository): Query<RepositoryQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<RelatedTask>>>, ApiError> {
+    let decoded_path = urlencoding::decode(&relative_path)
+        .map_err(|_| ApiError::BadRequest("Invalid path encoding".to_string()))?;
+    let decoded_path = validate_relative_path(&decoded_path)?.to_string();
+    let (repo, _) = find_document_file(
+        &deployment,
+        project.id,
+        repository.repository_id,
+        &decoded_path,
+    )
+    .await?;
+
+    let pool = &deployment.db().pool;
+    let mut tasks = Vec::new();
+    for document in TaskDocument::find_by_document(pool, repo.id, &decoded_path).await? {
+        if let Some(task) = Task::find_by_id(pool, document.task_id).await?
+            && task.project_id == project.id
+        {
+            tasks.push(RelatedTask {
+                link_id: document.id,
+                task,
+            });
+        }
+    }
+    Ok(ResponseJson(ApiResponse::success(tasks)))
+}
@@ -14,8 +14,11 @@ use axum::{
 };
 use db::models::{
     image::TaskImage,
+    project_repo::ProjectRepo,
     repo::{Repo, RepoError},
-    task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
+    task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_package_scope::{SetTaskPackageScope, TaskPackageScope},
+    wip_limit::{WipLimit, WipLimitOverride, WipLimitViolation},
     workspace::{CreateWorkspace, Workspace},
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
@@ -23,33 +26,83 @@ use deployment::Deployment;
 use executors::profile::ExecutorProfileId;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use services::services::{container::ContainerService, workspace_manager::WorkspaceManager};
+use services::services::{
+    container::ContainerService,
+    monorepo,
+    swimlanes::{self, TaskBoard, TaskGroupBy},
+    workspace_manager::WorkspaceManager,
+};
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
-use utils::response::ApiResponse;
+use utils::{board_links::BoardLinkTarget, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_task_middleware,
-    routes::task_attempts::WorkspaceRepoInput,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_task_middleware,
+    routes::{
+        board_links, document_task_sync, paths,
+        task_attempts::{WorkspaceRepoInput, util::resolve_agent_working_dir},
+        task_documents,
+    },
 };
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSort {
+    /// Newest first
+    #[default]
+    CreatedAt,
+    /// Most urgent first, newest first within the same priority
+    Priority,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TaskQuery {
     pub project_id: Uuid,
+    #[serde(default)]
+    pub sort: TaskSort,
 }
 
 pub async fn get_tasks(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<TaskQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<TaskWithAttemptStatus>>>, ApiError> {
-    let tasks =
+    let mut tasks =
         Task::find_by_project_id_with_attempt_status(&deployment.db().pool, query.project_id)
             .await?;
+    if let TaskSort::Priority = query.sort {
+        // Stable sort keeps the newest-first order within each priority
+        tasks.sort_by(|a, b| b.priority.cmp(&a.priority));
+    }
 
     Ok(ResponseJson(ApiResponse::success(tasks)))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct TaskBoardQuery {
+    pub project_id: Uuid,
+    #[serde(default = "default_group_by")]
+    pub group_by: TaskGroupBy,
+}
+
+fn default_group_by() -> TaskGroupBy {
+    TaskGroupBy::Status
+}
+
+/// The project's tasks pre-grouped into swimlanes with per-lane counts
+pub async fn get_task_board(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskBoardQuery>,
+) -> Result<ResponseJson<ApiResponse<TaskBoard>>, ApiError> {
+    let board =
+        swimlanes::board_for_project(&deployment.db().pool, query.project_id, query.group_by)
+            .await?;
+
+    Ok(ResponseJson(ApiResponse::success(board)))
+}
+
 pub async fn stream_tasks_ws(
     ws: WebSocketUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -104,10 +157,42 @@ pub async fn get_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+/// The reason given for putting a task into a column at its WIP limit, unless it's blank
+pub(crate) fn wip_override_reason(reason: Option<&str>) -> Option<&str> {
+    reason.map(str::trim).filter(|reason| !reason.is_empty())
+}
+
+/// Record that `task` entered a column past its WIP limit, and why
+pub(crate) async fn log_wip_override(
+    pool: &sqlx::SqlitePool,
+    task: &Task,
+    from_status: &TaskStatus,
+    violation: &WipLimitViolation,
+    reason: &str,
+) -> Result<(), ApiError> {
+    tracing::info!(
+        "Task {} moved into full {} column (limit {}) with override: {}",
+        task.id,
+        violation.status,
+        violation.max_tasks,
+        reason
+    );
+    WipLimitOverride::create(
+        pool,
+        task.project_id,
+        task.id,
+        from_status,
+        violation,
+        reason,
+    )
+    .await?;
+    Ok(())
+}
+
 pub async fn create_task(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTask>,
-) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+) -> Result<ResponseJson<ApiResponse<Task, WipLimitViolation>>, ApiError> {
     let id = Uuid::new_v4();
 
     tracing::debug!(
@@ -116,7 +201,35 @@ pub async fn create_task(
         payload.project_id
     );
 
+    if let Some(description) = &payload.description {
+        board_links::require_valid_links(&deployment, payload.project_id, description, None)
+            .await?;
+    }
+
+    // Tasks created straight into another column count against its WIP limit
+    let mut wip_override = None;
+    if let Some(status) = &payload.status
+        && status != &TaskStatus::default()
+        && let Some(violation) =
+            WipLimit::check_move(&deployment.db().pool, payload.project_id, status).await?
+    {
+        let Some(reason) = wip_override_reason(payload.wip_override_reason.as_deref()) else {
+            return Ok(ResponseJson(ApiResponse::error_with_data(violation)));
+        };
+        wip_override = Some((violation, reason));
+    }
     let task = Task::create(&deployment.db().pool, &payload, id).await?;
+    if let Some((violation, reason)) = wip_override {
+        // New tasks would otherwise have started out in Todo
+        log_wip_override(
+            &deployment.db().pool,
+            &task,
+            &TaskStatus::default(),
+            &violation,
+            reason,
+        )
+        .await?;
+    }
 
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
@@ -142,27 +255,55 @@ pub struct CreateAndStartTaskRequest {
     pub task: CreateTask,
     pub executor_profile_id: ExecutorProfileId,
     pub repos: Vec<WorkspaceRepoInput>,
+    /// Optionally scope the task to a monorepo package before starting it
+    #[serde(default)]
+    #[ts(optional)]
+    pub package_scope: Option<SetTaskPackageScope>,
 }
 
 pub async fn create_task_and_start(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateAndStartTaskRequest>,
-) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
+) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus, WipLimitViolation>>, ApiError> {
     if payload.repos.is_empty() {
         return Err(ApiError::BadRequest(
             "At least one repository is required".to_string(),
         ));
     }
 
+    if let Some(description) = &payload.task.description {
+        board_links::require_valid_links(&deployment, payload.task.project_id, description, None)
+            .await?;
+    }
+
     let pool = &deployment.db().pool;
 
+    // Starting the attempt moves the task into In Progress
+    let mut wip_override = None;
+    if payload.task.status != Some(TaskStatus::InProgress)
+        && let Some(violation) =
+            WipLimit::check_move(pool, payload.task.project_id, &TaskStatus::InProgress).await?
+    {
+        let Some(reason) = wip_override_reason(payload.task.wip_override_reason.as_deref()) else {
+            return Ok(ResponseJson(ApiResponse::error_with_data(violation)));
+        };
+        wip_override = Some((violation, reason));
+    }
+
     let task_id = Uuid::new_v4();
     let task = Task::create(pool, &payload.task, task_id).await?;
+    if let Some((violation, reason)) = wip_override {
+        log_wip_override(pool, &task, &task.status, &violation, reason).await?;
+    }
 
     if let Some(image_ids) = &payload.task.image_ids {
         TaskImage::associate_many_dedup(pool, task.id, image_ids).await?;
     }
 
+    if let Some(scope) = &payload.package_scope {
+        set_package_scope(pool, &task, scope).await?;
+    }
+
     deployment
         .track_if_analytics_allowed(
             "task_created",
@@ -181,17 +322,8 @@ pub async fn create_task_and_start(
         .git_branch_from_workspace(&attempt_id, &task.title)
         .await;
 
-    // Compute agent_working_dir based on repo count:
-    // - Single repo: use repo name as working dir (agent runs in repo directory)
-    // - Multiple repos: use None (agent runs in workspace root)
-    let agent_working_dir = if payload.repos.len() == 1 {
-        let repo = Repo::find_by_id(pool, payload.repos[0].repo_id)
-            .await?
-            .ok_or(RepoError::NotFound)?;
-        Some(repo.name)
-    } else {
-        None
-    };
+    let repo_ids: Vec<Uuid> = payload.repos.iter().map(|r| r.repo_id).collect();
+    let agent_working_dir = resolve_agent_working_dir(pool, task.id, &repo_ids).await?;
 
     let workspace = Workspace::create(
         pool,
@@ -250,7 +382,38 @@ pub async fn update_task(
     State(deployment): State<DeploymentImpl>,
 
     Json(payload): Json<UpdateTask>,
-) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+) -> Result<ResponseJson<ApiResponse<Task, WipLimitViolation>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if let Some(description) = &payload.description {
+        board_links::require_valid_links(
+            &deployment,
+            existing_task.project_id,
+            description,
+            existing_task.description.as_deref(),
+        )
+        .await?;
+    }
+
+    // Enforce WIP limits when moving the task into another column
+    if let Some(status) = &payload.status
+        && status != &existing_task.status
+        && let Some(violation) =
+            WipLimit::check_move(pool, existing_task.project_id, status).await?
+    {
+        let Some(reason) = wip_override_reason(payload.wip_override_reason.as_deref()) else {
+            return Ok(ResponseJson(ApiResponse::error_with_data(violation)));
+        };
+        log_wip_override(
+            pool,
+            &existing_task,
+            &existing_task.status,
+            &violation,
+            reason,
+        )
+        .await?;
+    }
+
     // Use existing values if not provided in update
     let title = payload.title.unwrap_or(existing_task.title);
     let description = match payload.description {
@@ -258,6 +421,10 @@ pub async fn update_task(
         Some(s) => Some(s),                     // Non-empty string = update description
         None => existing_task.description,      // Field omitted = keep existing
     };
+    let status_changed = payload
+        .status
+        .as_ref()
+        .is_some_and(|status| status != &existing_task.status);
     let status = payload.status.unwrap_or(existing_task.status);
     let parent_workspace_id = payload
         .parent_workspace_id
@@ -274,12 +441,87 @@ pub async fn update_task(
     )
     .await?;
 
+    let task = match payload.priority {
+        Some(priority) if priority != task.priority || task.priority_escalated_at.is_some() => {
+            Task::update_priority(pool, task.id, priority).await?
+        }
+        _ => task,
+    };
+
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::delete_by_task_id(&deployment.db().pool, task.id).await?;
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
 
-    Ok(ResponseJson(ApiResponse::success(task)))
+    // Check or uncheck the task's item if it comes from a planning document
+    let warning = if status_changed {
+        document_task_sync::sync_task_checkbox(&deployment, &task).await
+    } else {
+        None
+    };
+
+    Ok(ResponseJson(
+        ApiResponse::success(task).with_warnings(warning),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeTaskQuery {
+    pub into: Uuid,
+}
+
+/// Merge the task into the canonical task given by `into`, leaving a redirect behind
+pub async fn merge_task(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<MergeTaskQuery>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let canonical = Task::find_by_id_or_redirect(pool, query.into)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Target task not found".to_string()))?;
+    if canonical.id == task.id {
+        return Err(ApiError::BadRequest(
+            "Cannot merge a task into itself".to_string(),
+        ));
+    }
+    if canonical.project_id != task.project_id {
+        return Err(ApiError::BadRequest(
+            "Tasks must belong to the same project".to_string(),
+        ));
+    }
+
+    let merged = Task::merge_into(pool, &task, &canonical).await?;
+    tracing::info!("Merged task {} into {}", task.id, merged.id);
+
+    // Links to the duplicate would resolve through its redirect, but point them at the
+    // canonical task so they read right
+    let from = BoardLinkTarget::Task(task.id);
+    let to = BoardLinkTarget::Task(merged.id);
+    let warnings =
+        match board_links::retarget_links(&deployment, merged.project_id, &from, &to).await {
+            Ok(warnings) => warnings,
+            Err(e) => {
+                tracing::warn!("Failed to update links to merged task {}: {}", task.id, e);
+                Vec::new()
+            }
+        };
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_merged",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "into_task_id": merged.id.to_string(),
+                "project_id": merged.project_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(
+        ApiResponse::success(merged).with_warnings(warnings),
+    ))
 }
 
 pub async fn delete_task(
@@ -385,14 +627,90 @@ pub async fn delete_task(
         tracing::info!("Background cleanup completed for task {}", task_id);
     });
 
+    let target = BoardLinkTarget::Task(task.id);
+    let warning = board_links::deleted_target_warning(&deployment, task.project_id, &target).await;
+
     // Return 202 Accepted to indicate deletion was scheduled
-    Ok((StatusCode::ACCEPTED, ResponseJson(ApiResponse::success(()))))
+    Ok((
+        StatusCode::ACCEPTED,
+        ResponseJson(ApiResponse::success(()).with_warnings(warning)),
+    ))
+}
+
+/// Validate that the package exists in a repository of the task's project
+/// and persist it as the task's scope.
+async fn set_package_scope(
+    pool: &sqlx::SqlitePool,
+    task: &Task,
+    payload: &SetTaskPackageScope,
+) -> Result<TaskPackageScope, ApiError> {
+    if ProjectRepo::find_by_project_and_repo(pool, task.project_id, payload.repo_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::BadRequest(
+            "Repository not found in project".to_string(),
+        ));
+    }
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let package_path = payload.package_path.trim_matches('/');
+    let packages = monorepo::detect_packages(&repo.path);
+    let package = packages
+        .iter()
+        .find(|p| p.path == package_path)
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Package '{}' not found in repository '{}'",
+                package_path, repo.name
+            ))
+        })?;
+
+    Ok(TaskPackageScope::upsert(pool, task.id, repo.id, &package.path, &package.name).await?)
+}
+
+pub async fn get_task_package_scope(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskPackageScope>>>, ApiError> {
+    let scope = TaskPackageScope::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(scope)))
+}
+
+pub async fn update_task_package_scope(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetTaskPackageScope>,
+) -> Result<ResponseJson<ApiResponse<TaskPackageScope>>, ApiError> {
+    let scope = set_package_scope(&deployment.db().pool, &task, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(scope)))
+}
+
+pub async fn delete_task_package_scope(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    TaskPackageScope::delete(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
 }
 
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_actions_router = Router::new()
         .route("/", put(update_task))
-        .route("/", delete(delete_task));
+        .route("/", delete(delete_task))
+        .route("/merge", post(merge_task))
+        .route(
+            "/package-scope",
+            get(get_task_package_scope)
+                .put(update_task_package_scope)
+                .delete(delete_task_package_scope),
+        )
+        .route(
+            "/documents",
+            get(task_documents::get_task_documents).post(task_documents::attach_task_document),
+        );
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
@@ -401,10 +719,15 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let inner = Router::new()
         .route("/", get(get_tasks).post(create_task))
+        .route("/board", get(get_task_board))
         .route("/stream/ws", get(stream_tasks_ws))
         .route("/create-and-start", post(create_task_and_start))
+        .route(
+            "/{task_id}/documents/{link_id}",
+            delete(task_documents::detach_task_document),
+        )
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
-    Router::new().nest("/tasks", inner)
+    Router::new().nest(paths::TASKS, inner)
 }
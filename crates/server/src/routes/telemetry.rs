@@ -0,0 +1,40 @@
+use axum::{Router, extract::State, response::Json as ResponseJson, routing::get};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::telemetry::{TelemetryBatch, TelemetryService};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, routes::paths};
+
+#[derive(Debug, Serialize, TS)]
+pub struct TelemetryPreview {
+    pub enabled: bool,
+    /// Where the batch would be sent, `None` when no endpoint is configured
+    pub endpoint: Option<String>,
+    /// The exact payload of the next request
+    pub batch: TelemetryBatch,
+}
+
+/// Show what telemetry would send, whether or not it is enabled
+pub async fn get_telemetry_preview(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TelemetryPreview>>, ApiError> {
+    let (enabled, endpoint) = {
+        let config = deployment.config().read().await;
+        (
+            config.telemetry.enabled,
+            TelemetryService::endpoint(&config),
+        )
+    };
+
+    Ok(ResponseJson(ApiResponse::success(TelemetryPreview {
+        enabled,
+        endpoint,
+        batch: deployment.telemetry().preview(),
+    })))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route(paths::ADMIN_TELEMETRY_PREVIEW, get(get_telemetry_preview))
+}
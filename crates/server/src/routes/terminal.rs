@@ -16,7 +16,7 @@ use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{DeploymentImpl, error::ApiError, routes::paths};
 
 #[derive(Debug, Deserialize)]
 pub struct TerminalQuery {
@@ -169,5 +169,5 @@ async fn send_error(mut socket: WebSocket, message: &str) -> Result<(), axum::Er
 }
 
 pub fn router() -> Router<DeploymentImpl> {
-    Router::new().route("/terminal/ws", get(terminal_ws))
+    Router::new().route(paths::TERMINAL_WS, get(terminal_ws))
 }
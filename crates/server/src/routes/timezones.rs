@@ -0,0 +1,111 @@
+//! Timezones schedules and due dates of a project follow: the project's own, else the
+//! user's from config, else UTC.
+
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use chrono::Utc;
+use db::models::project::Project;
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::{
+    response::ApiResponse,
+    timezone::{LocalizedTime, Timezone},
+};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware, routes::paths};
+
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectTimezone {
+    /// The project's own timezone, `None` if it uses the user's
+    pub timezone: Option<String>,
+    /// Timezone the project's schedules and due dates follow
+    pub effective: String,
+    pub now: LocalizedTime,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateProjectTimezone {
+    /// IANA name such as `Europe/Paris` or a UTC offset; `None` to use the user's
+    pub timezone: Option<String>,
+}
+
+/// `timezone` parsed, or UTC with a warning if it is no longer valid, e.g. because the
+/// tz database changed
+fn parse_or_utc(timezone: &str) -> Timezone {
+    Timezone::parse(timezone).unwrap_or_else(|e| {
+        tracing::warn!("Using UTC: {}", e);
+        Timezone::utc()
+    })
+}
+
+/// The user's timezone from config
+pub(crate) async fn user_timezone(deployment: &DeploymentImpl) -> Timezone {
+    match deployment.config().read().await.timezone.as_deref() {
+        Some(timezone) => parse_or_utc(timezone),
+        None => Timezone::utc(),
+    }
+}
+
+/// The timezone the project's schedules and due dates follow
+pub(crate) async fn project_timezone(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+) -> Result<Timezone, ApiError> {
+    match Project::find_timezone(&deployment.db().pool, project_id).await? {
+        Some(timezone) => Ok(parse_or_utc(&timezone)),
+        None => Ok(user_timezone(deployment).await),
+    }
+}
+
+async fn describe(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+) -> Result<ProjectTimezone, ApiError> {
+    let effective = project_timezone(deployment, project_id).await?;
+    Ok(ProjectTimezone {
+        timezone: Project::find_timezone(&deployment.db().pool, project_id).await?,
+        effective: effective.name().to_string(),
+        now: effective.localize(Utc::now()),
+    })
+}
+
+pub async fn get_project_timezone(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectTimezone>>, ApiError> {
+    let timezone = describe(&deployment, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(timezone)))
+}
+
+pub async fn set_project_timezone(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectTimezone>,
+) -> Result<ResponseJson<ApiResponse<ProjectTimezone>>, ApiError> {
+    let timezone = payload
+        .timezone
+        .as_deref()
+        .map(str::trim)
+        .filter(|timezone| !timezone.is_empty());
+    if let Some(timezone) = timezone {
+        Timezone::parse(timezone).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    }
+    Project::set_timezone(&deployment.db().pool, project.id, timezone).await?;
+    let timezone = describe(&deployment, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(timezone)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route("/", get(get_project_timezone).put(set_project_timezone))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest(paths::PROJECT_TIMEZONE, project_router)
+}
@@ -0,0 +1,56 @@
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::user_data::{self, UserDataErasure, UserDataExport};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, routes::paths};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UserDataRequest {
+    /// Name the person annotates, reviews and edits documents under
+    pub name: String,
+}
+
+fn person_name(request: &UserDataRequest) -> Result<&str, ApiError> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest("A name is required".to_string()));
+    }
+    Ok(name)
+}
+
+/// Everything stored about a person, for answering a request for their data
+pub async fn export_user_data(
+    State(deployment): State<DeploymentImpl>,
+    Query(request): Query<UserDataRequest>,
+) -> Result<ResponseJson<ApiResponse<UserDataExport>>, ApiError> {
+    let name = person_name(&request)?;
+    let export = user_data::export(&deployment.db().pool, name).await?;
+    Ok(ResponseJson(ApiResponse::success(export)))
+}
+
+/// Delete a person's drafts and locks and replace their name with a pseudonym everywhere
+/// else. This can't be undone.
+pub async fn erase_user_data(
+    State(deployment): State<DeploymentImpl>,
+    Json(request): Json<UserDataRequest>,
+) -> Result<ResponseJson<ApiResponse<UserDataErasure>>, ApiError> {
+    let name = person_name(&request)?;
+    let erasure = user_data::erase(&deployment.db().pool, name).await?;
+    // The name itself isn't logged, since that would keep it around
+    tracing::info!("Erased user data as {}: {:?}", erasure.replacement, erasure);
+    Ok(ResponseJson(ApiResponse::success(erasure)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(paths::ADMIN_USER_DATA, get(export_user_data))
+        .route(paths::ADMIN_USER_DATA_ERASE, post(erase_user_data))
+}
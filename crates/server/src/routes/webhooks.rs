@@ -0,0 +1,162 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path as AxumPath, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, patch},
+};
+use db::models::{
+    project::Project,
+    project_webhook::{CreateProjectWebhook, ProjectWebhook, UpdateProjectWebhook, WebhookEvent},
+};
+use deployment::Deployment;
+use services::services::webhooks::{self, WebhookPayload};
+use url::Url;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware, routes::paths};
+
+fn validate_url(url: &str) -> Result<(), ApiError> {
+    let parsed = Url::parse(url.trim())
+        .map_err(|e| ApiError::BadRequest(format!("Invalid webhook URL '{}': {}", url, e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(ApiError::BadRequest(
+            "Webhook URLs must use http or https".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_secret(secret: &str) -> Result<(), ApiError> {
+    if secret.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Webhook secret must not be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Call the project's webhooks for each payload in the background, in order
+pub(crate) fn notify(deployment: &DeploymentImpl, payloads: Vec<WebhookPayload>) {
+    if payloads.is_empty() {
+        return;
+    }
+    let pool = deployment.db().pool.clone();
+    tokio::spawn(async move {
+        for payload in payloads {
+            webhooks::dispatch(&pool, &payload).await;
+        }
+    });
+}
+
+/// Call the project's webhooks for a change to one document
+pub(crate) fn document_changed(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    event: WebhookEvent,
+    repo_id: Uuid,
+    path: &str,
+) {
+    notify(
+        deployment,
+        vec![WebhookPayload::document(event, project_id, repo_id, path)],
+    );
+}
+
+/// Call the project's webhooks for moved documents, as the deletion of each old path
+/// and the creation of its new one
+pub(crate) fn documents_moved<'a>(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repo_id: Uuid,
+    moves: impl IntoIterator<Item = (&'a str, &'a str)>,
+) {
+    let payloads = moves
+        .into_iter()
+        .flat_map(|(from, to)| {
+            [
+                WebhookPayload::document(WebhookEvent::DocumentDeleted, project_id, repo_id, from),
+                WebhookPayload::document(WebhookEvent::DocumentCreated, project_id, repo_id, to),
+            ]
+        })
+        .collect();
+    notify(deployment, payloads);
+}
+
+pub async fn list_webhooks(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectWebhook>>>, ApiError> {
+    let webhooks = ProjectWebhook::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(webhooks)))
+}
+
+pub async fn create_webhook(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(mut payload): Json<CreateProjectWebhook>,
+) -> Result<ResponseJson<ApiResponse<ProjectWebhook>>, ApiError> {
+    validate_url(&payload.url)?;
+    validate_secret(&payload.secret)?;
+    payload.url = payload.url.trim().to_string();
+    let webhook = ProjectWebhook::create(&deployment.db().pool, project.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+async fn find_project_webhook(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    webhook_id: Uuid,
+) -> Result<ProjectWebhook, ApiError> {
+    ProjectWebhook::find_by_id(&deployment.db().pool, webhook_id)
+        .await?
+        .filter(|webhook| webhook.project_id == project_id)
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))
+}
+
+pub async fn update_webhook(
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((project_id, webhook_id)): AxumPath<(Uuid, Uuid)>,
+    Json(mut payload): Json<UpdateProjectWebhook>,
+) -> Result<ResponseJson<ApiResponse<ProjectWebhook>>, ApiError> {
+    let webhook = find_project_webhook(&deployment, project_id, webhook_id).await?;
+    if let Some(url) = &mut payload.url {
+        validate_url(url)?;
+        *url = url.trim().to_string();
+    }
+    if let Some(secret) = &payload.secret {
+        validate_secret(secret)?;
+    }
+    let webhook = ProjectWebhook::update(&deployment.db().pool, webhook.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(webhook)))
+}
+
+pub async fn delete_webhook(
+    State(deployment): State<DeploymentImpl>,
+    AxumPath((project_id, webhook_id)): AxumPath<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let webhook = find_project_webhook(&deployment, project_id, webhook_id).await?;
+    ProjectWebhook::delete(&deployment.db().pool, webhook.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route("/", get(list_webhooks).post(create_webhook))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    // Webhooks are looked up by their own id and checked against the project
+    let webhook_router = Router::new().route(
+        "/{webhook_id}",
+        patch(update_webhook).delete(delete_webhook),
+    );
+
+    Router::new().nest(
+        paths::PROJECT_WEBHOOKS,
+        project_router.merge(webhook_router),
+    )
+}
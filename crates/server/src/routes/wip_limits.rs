@@ -0,0 +1,80 @@
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use db::models::{
+    project::Project,
+    wip_limit::{SetWipLimit, WipLimit, WipLimitOverride},
+};
+use deployment::Deployment;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware, routes::paths};
+
+fn validate(payload: &SetWipLimit) -> Result<(), ApiError> {
+    if payload.max_tasks.is_some_and(|max| max < 0) {
+        return Err(ApiError::BadRequest(
+            "WIP limit must not be negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn get_project_wip_limits(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WipLimit>>>, ApiError> {
+    let limits = WipLimit::find_for_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(limits)))
+}
+
+pub async fn set_project_wip_limit(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetWipLimit>,
+) -> Result<ResponseJson<ApiResponse<Option<WipLimit>>>, ApiError> {
+    validate(&payload)?;
+    let limit = WipLimit::set(&deployment.db().pool, Some(project.id), &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(limit)))
+}
+
+pub async fn get_project_wip_overrides(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WipLimitOverride>>>, ApiError> {
+    let overrides = WipLimitOverride::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(overrides)))
+}
+
+pub async fn get_user_wip_limits(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WipLimit>>>, ApiError> {
+    let limits = WipLimit::find_user_limits(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(limits)))
+}
+
+pub async fn set_user_wip_limit(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetWipLimit>,
+) -> Result<ResponseJson<ApiResponse<Option<WipLimit>>>, ApiError> {
+    validate(&payload)?;
+    let limit = WipLimit::set(&deployment.db().pool, None, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(limit)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_router = Router::new()
+        .route("/", get(get_project_wip_limits).put(set_project_wip_limit))
+        .route("/overrides", get(get_project_wip_overrides))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new()
+        .route(
+            "/wip-limits",
+            get(get_user_wip_limits).put(set_user_wip_limit),
+        )
+        .nest(paths::PROJECT_WIP_LIMITS, project_router)
+}
@@ -0,0 +1,277 @@
+//! Registration of remote workers, the WebSocket they connect over, and the admin side of
+//! scheduling: draining workers and the capabilities executions require. The protocol is
+//! described in [`services::services::workers`].
+
+use std::str::FromStr;
+
+use axum::{
+    Json, Router,
+    extract::{
+        Path, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::{HeaderMap, header},
+    response::{IntoResponse, Json as ResponseJson},
+    routing::{delete, get, put},
+};
+use db::models::{
+    task::Task,
+    worker::Worker,
+    worker_requirement::{SetWorkerRequirement, WorkerRequirement},
+};
+use deployment::Deployment;
+use executors::executors::BaseCodingAgent;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use services::services::workers::{self, WorkerMessage};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, routes::paths};
+
+/// Messages carry whole git bundles of the workspace repositories
+const MAX_MESSAGE_BYTES: usize = 512 * 1024 * 1024;
+
+#[derive(Debug, Serialize, TS)]
+pub struct WorkerInfo {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub worker: Worker,
+    pub connected: bool,
+    /// Whether the worker's heartbeats arrive; only then is it given executions
+    pub healthy: bool,
+    /// Whether the worker is running an execution
+    pub busy: bool,
+    pub execution_process_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct RegisterWorkerRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RegisterWorkerResponse {
+    pub worker: Worker,
+    /// Token the worker connects with. It is only shown here.
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SetWorkerDrainingRequest {
+    pub draining: bool,
+}
+
+pub async fn list_workers(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkerInfo>>>, ApiError> {
+    let mut infos = Vec::new();
+    for worker in Worker::find_all(&deployment.db().pool).await? {
+        let status = deployment.workers().status(worker.id).await;
+        infos.push(WorkerInfo {
+            worker,
+            connected: status.connected,
+            healthy: status.healthy,
+            busy: status.execution_process_id.is_some(),
+            execution_process_id: status.execution_process_id,
+        });
+    }
+    Ok(ResponseJson(ApiResponse::success(infos)))
+}
+
+pub async fn register_worker(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RegisterWorkerRequest>,
+) -> Result<ResponseJson<ApiResponse<RegisterWorkerResponse>>, ApiError> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Worker name cannot be empty".to_string(),
+        ));
+    }
+    let token = workers::generate_token();
+    let worker = Worker::create(&deployment.db().pool, name, &workers::hash_token(&token)).await?;
+    tracing::info!("Registered worker {} ({})", worker.name, worker.id);
+    Ok(ResponseJson(ApiResponse::success(RegisterWorkerResponse {
+        worker,
+        token,
+    })))
+}
+
+/// Delete a worker and close its connection. Executions running on it fail.
+pub async fn delete_worker(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if Worker::delete(&deployment.db().pool, id).await? == 0 {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    deployment.workers().remove(id).await;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Stop giving a worker new executions, e.g. before maintenance, or resume. The execution
+/// it runs continues either way.
+pub async fn set_worker_draining(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SetWorkerDrainingRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    if Worker::set_draining(&deployment.db().pool, id, payload.draining).await? == 0 {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    deployment
+        .workers()
+        .set_draining(id, payload.draining)
+        .await;
+    tracing::info!(
+        "Worker {} {}",
+        id,
+        if payload.draining {
+            "is draining"
+        } else {
+            "takes executions again"
+        }
+    );
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub async fn list_worker_requirements(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorkerRequirement>>>, ApiError> {
+    let requirements = WorkerRequirement::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(requirements)))
+}
+
+/// Set the capabilities a worker needs to run the coding agents of a task, or of an
+/// executor. Executions with requirements never run on this machine; they fail when no
+/// idle worker has every capability.
+pub async fn set_worker_requirement(
+    State(deployment): State<DeploymentImpl>,
+    Json(mut payload): Json<SetWorkerRequirement>,
+) -> Result<ResponseJson<ApiResponse<Option<WorkerRequirement>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    match (payload.task_id, payload.executor.as_deref()) {
+        (Some(task_id), None) => {
+            Task::find_by_id(pool, task_id)
+                .await?
+                .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+        }
+        (None, Some(executor)) => {
+            let executor = BaseCodingAgent::from_str(executor.trim())
+                .map_err(|_| ApiError::BadRequest(format!("Unknown executor '{}'", executor)))?;
+            payload.executor = Some(executor.to_string());
+        }
+        _ => {
+            return Err(ApiError::BadRequest(
+                "Set either task_id or executor".to_string(),
+            ));
+        }
+    }
+    payload.capabilities = workers::normalize_capabilities(&payload.capabilities);
+    if let Some(invalid) = payload.capabilities.iter().find(|capability| {
+        !capability
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    }) {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid capability '{}': use letters, digits, '-', '_' and '.'",
+            invalid
+        )));
+    }
+
+    let requirement = WorkerRequirement::set(pool, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(requirement)))
+}
+
+/// WebSocket of a worker, authenticated with `Authorization: Bearer <worker token>`
+pub async fn connect_worker(
+    ws: WebSocketUpgrade,
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(ApiError::Unauthorized)?;
+    let worker = Worker::find_by_token_hash(&deployment.db().pool, &workers::hash_token(token))
+        .await?
+        .ok_or(ApiError::Unauthorized)?;
+    Worker::touch(&deployment.db().pool, worker.id).await?;
+
+    Ok(ws
+        .max_message_size(MAX_MESSAGE_BYTES)
+        .max_frame_size(MAX_MESSAGE_BYTES)
+        .on_upgrade(move |socket| handle_worker_ws(socket, deployment, worker)))
+}
+
+async fn handle_worker_ws(socket: WebSocket, deployment: DeploymentImpl, worker: Worker) {
+    let (connection_id, mut messages) = deployment
+        .workers()
+        .connect(worker.id, &worker.capabilities, worker.draining)
+        .await;
+    tracing::info!("Worker {} ({}) connected", worker.name, worker.id);
+
+    let (mut ws_sender, mut ws_receiver) = socket.split();
+    let send_task = tokio::spawn(async move {
+        while let Some(message) = messages.recv().await {
+            let json = match serde_json::to_string(&message) {
+                Ok(json) => json,
+                Err(e) => {
+                    tracing::error!("Failed to serialize worker message: {}", e);
+                    continue;
+                }
+            };
+            if ws_sender.send(Message::Text(json.into())).await.is_err() {
+                break;
+            }
+        }
+        // The channel closes when the worker is removed
+        let _ = ws_sender.close().await;
+    });
+
+    while let Some(Ok(message)) = ws_receiver.next().await {
+        match message {
+            Message::Text(text) => match serde_json::from_str::<WorkerMessage>(&text) {
+                Ok(message) => {
+                    if let WorkerMessage::Heartbeat { capabilities } = &message {
+                        let capabilities = workers::normalize_capabilities(capabilities);
+                        if let Err(e) =
+                            Worker::heartbeat(&deployment.db().pool, worker.id, &capabilities).await
+                        {
+                            tracing::warn!("Failed to record heartbeat of {}: {}", worker.id, e);
+                        }
+                    }
+                    deployment
+                        .workers()
+                        .handle_message(worker.id, message)
+                        .await
+                }
+                Err(e) => tracing::warn!("Invalid message from worker {}: {}", worker.id, e),
+            },
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+
+    deployment
+        .workers()
+        .disconnect(worker.id, connection_id)
+        .await;
+    send_task.abort();
+    tracing::info!("Worker {} ({}) disconnected", worker.name, worker.id);
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route(paths::WORKERS, get(list_workers).post(register_worker))
+        .route(paths::WORKERS_CONNECT, get(connect_worker))
+        .route(
+            paths::WORKER_REQUIREMENTS,
+            get(list_worker_requirements).put(set_worker_requirement),
+        )
+        .route(paths::WORKER, delete(delete_worker))
+        .route(paths::WORKER_DRAINING, put(set_worker_draining))
+}
@@ -0,0 +1,132 @@
+use std::{future::Future, path::PathBuf, sync::Arc, time::Duration};
+
+use axum::Router;
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::{conn::auto::Builder, graceful::GracefulShutdown},
+    service::TowerToHyperService,
+};
+use rustls::{
+    ServerConfig,
+    pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+};
+use thiserror::Error;
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+
+/// How long open connections (event streams, terminals) get to finish on shutdown
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum TlsError {
+    #[error("VK_TLS_CERT and VK_TLS_KEY must be set together")]
+    Incomplete,
+    #[error("failed to read {}: {source}", .path.display())]
+    Pem {
+        path: PathBuf,
+        source: rustls::pki_types::pem::Error,
+    },
+    #[error("no certificates found in {}", .0.display())]
+    NoCertificates(PathBuf),
+    #[error(transparent)]
+    Rustls(#[from] rustls::Error),
+}
+
+/// Certificate and private key used to serve HTTPS directly,
+/// configured with `VK_TLS_CERT` and `VK_TLS_KEY` (PEM files)
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+impl TlsConfig {
+    pub fn from_env() -> Result<Option<Self>, TlsError> {
+        let var = |name| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        match (var("VK_TLS_CERT"), var("VK_TLS_KEY")) {
+            (Some(cert), Some(key)) => Ok(Some(Self {
+                cert_path: PathBuf::from(cert),
+                key_path: PathBuf::from(key),
+            })),
+            (None, None) => Ok(None),
+            _ => Err(TlsError::Incomplete),
+        }
+    }
+
+    /// Build the rustls config, advertising HTTP/2 and HTTP/1.1 over ALPN
+    pub fn server_config(&self) -> Result<Arc<ServerConfig>, TlsError> {
+        let pem_error = |path: &PathBuf| {
+            let path = path.clone();
+            move |source| TlsError::Pem { path, source }
+        };
+
+        let certs = CertificateDer::pem_file_iter(&self.cert_path)
+            .map_err(pem_error(&self.cert_path))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(pem_error(&self.cert_path))?;
+        if certs.is_empty() {
+            return Err(TlsError::NoCertificates(self.cert_path.clone()));
+        }
+        let key =
+            PrivateKeyDer::from_pem_file(&self.key_path).map_err(pem_error(&self.key_path))?;
+
+        let mut config = ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(Arc::new(config))
+    }
+}
+
+/// Serve the app over TLS, negotiating HTTP/2 or HTTP/1.1 per connection
+pub async fn serve(
+    listener: TcpListener,
+    app: Router,
+    config: Arc<ServerConfig>,
+    shutdown: impl Future<Output = ()>,
+) -> std::io::Result<()> {
+    let acceptor = TlsAcceptor::from(config);
+    let graceful = GracefulShutdown::new();
+    let mut shutdown = std::pin::pin!(shutdown);
+
+    loop {
+        let (stream, remote_addr) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    tracing::warn!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let acceptor = acceptor.clone();
+        let service = TowerToHyperService::new(app.clone());
+        let watcher = graceful.watcher();
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::debug!("TLS handshake with {} failed: {}", remote_addr, e);
+                    return;
+                }
+            };
+            let builder = Builder::new(TokioExecutor::new());
+            let connection = builder
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .into_owned();
+            if let Err(e) = watcher.watch(connection).await {
+                tracing::debug!("Connection from {} closed with error: {}", remote_addr, e);
+            }
+        });
+    }
+
+    tokio::select! {
+        _ = graceful.shutdown() => {}
+        _ = tokio::time::sleep(SHUTDOWN_GRACE_PERIOD) => {
+            tracing::warn!("Timed out waiting for connections to close");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use utils::api::organizations::{
+    AccessPrincipal, AccessReportResponse, MemberRole, PermissionAction,
+};
+
+use super::analytics_overview::csv_field;
+
+const HEADER: &str = "principal,principal_id,user_id,user_email,organization_id,organization,\
+                      project_id,project,role,read,write,manage,last_used_at\n";
+
+fn principal_name(principal: AccessPrincipal) -> &'static str {
+    match principal {
+        AccessPrincipal::User => "user",
+        AccessPrincipal::SessionToken => "session_token",
+        AccessPrincipal::ScimToken => "scim_token",
+    }
+}
+
+fn role_name(role: MemberRole) -> &'static str {
+    match role {
+        MemberRole::Admin => "admin",
+        MemberRole::Member => "member",
+    }
+}
+
+/// Render the report as CSV, one row per entry with a column for each action
+pub fn to_csv(report: &AccessReportResponse) -> String {
+    let mut csv = String::from(HEADER);
+    for entry in &report.entries {
+        let allowed = |action: PermissionAction| entry.actions.contains(&action).to_string();
+        let fields = [
+            principal_name(entry.principal).to_string(),
+            entry
+                .principal_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            entry.user_id.map(|id| id.to_string()).unwrap_or_default(),
+            entry.user_email.clone().unwrap_or_default(),
+            entry.organization_id.to_string(),
+            entry.organization_name.clone(),
+            entry
+                .project_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            entry.project_name.clone().unwrap_or_default(),
+            entry.role.map(role_name).unwrap_or_default().to_string(),
+            allowed(PermissionAction::Read),
+            allowed(PermissionAction::Write),
+            allowed(PermissionAction::Manage),
+            entry
+                .last_used_at
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use utils::api::organizations::AccessReportEntry;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn renders_one_row_per_entry() {
+        let user_id = Uuid::new_v4();
+        let organization_id = Uuid::new_v4();
+        let project_id = Uuid::new_v4();
+        let member = AccessReportEntry {
+            principal: AccessPrincipal::User,
+            principal_id: Some(user_id),
+            user_id: Some(user_id),
+            user_email: Some("ada@example.com".to_string()),
+            organization_id,
+            organization_name: "Acme, Inc.".to_string(),
+            project_id: Some(project_id),
+            project_name: Some("Docs".to_string()),
+            role: Some(MemberRole::Member),
+            actions: vec![PermissionAction::Read, PermissionAction::Write],
+            last_used_at: None,
+        };
+        let scim = AccessReportEntry {
+            principal: AccessPrincipal::ScimToken,
+            principal_id: None,
+            user_id: None,
+            user_email: None,
+            project_id: None,
+            project_name: None,
+            role: None,
+            actions: vec![PermissionAction::Manage],
+            ..member.clone()
+        };
+        let report = AccessReportResponse {
+            generated_at: Utc::now(),
+            entries: vec![member, scim],
+        };
+
+        let csv = to_csv(&report);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "principal,principal_id,user_id,user_email,organization_id,organization,\
+             project_id,project,role,read,write,manage,last_used_at"
+        );
+        assert_eq!(
+            lines[1],
+            format!(
+                "user,{user_id},{user_id},ada@example.com,{organization_id},\"Acme, Inc.\",\
+                 {project_id},Docs,member,true,true,false,"
+            )
+        );
+        assert_eq!(
+            lines[2],
+            format!("scim_token,,,,{organization_id},\"Acme, Inc.\",,,,false,false,true,")
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Extraction of action items from meeting notes.
+//!
+//! There is no LLM client outside of coding agents, which only run inside task
+//! workspaces, so items are found with rules for common note-taking conventions:
+//! unchecked task list items, lines prefixed with `Action:`/`TODO:`/`AI:`, and list
+//! items below an "Action items", "Next steps" or "Follow-ups" heading. An `@name`
+//! mention becomes the owner and an ISO date (`due 2026-03-01`) the due date. Items
+//! are only proposals; the caller confirms them before tasks are created.
+
+use std::{collections::HashSet, sync::OnceLock};
+
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utils::front_matter;
+
+/// Titles are cut off after this many characters
+const MAX_TITLE_CHARS: usize = 120;
+
+/// Headings whose list items are all action items, matched case-insensitively at the
+/// start of a word
+const ACTION_HEADINGS: &[&str] = &[
+    "action",
+    "next step",
+    "follow-up",
+    "follow up",
+    "todo",
+    "to-do",
+];
+
+/// Line prefixes that mark an action item, matched case-insensitively
+const ACTION_PREFIXES: &[&str] = &[
+    "action item:",
+    "action:",
+    "ai:",
+    "todo:",
+    "to-do:",
+    "follow-up:",
+];
+
+/// Heading of the section confirmed items are added to
+pub const SECTION_HEADING: &str = "Action items";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct ActionItem {
+    pub title: String,
+    /// Name mentioned with `@`, without the `@`
+    pub owner: Option<String>,
+    pub due_date: Option<NaiveDate>,
+    /// 1-based line of the notes the item was found on; `None` for items added by hand
+    #[serde(default)]
+    pub line: Option<u32>,
+}
+
+fn owner_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"(?:^|\s)@([\w][\w.-]*[\w]|[\w])").unwrap())
+}
+
+fn due_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(concat!(
+            r"(?i)\(?\s*(?:(?:due|deadline)(?:\s+(?:by|on))?:?|by|before)?",
+            r"\s*(\d{4}-\d{2}-\d{2})\s*\)?"
+        ))
+        .unwrap()
+    })
+}
+
+/// Action items proposed for `notes`, in document order
+pub fn extract(notes: &str) -> Vec<ActionItem> {
+    let (body, skipped_lines) = match front_matter::split(notes) {
+        Some((_, body)) => (
+            body,
+            notes[..notes.len() - body.len()].matches('\n').count(),
+        ),
+        None => (notes, 0),
+    };
+    let mut items = Vec::new();
+    // Lowercased titles of items that already link to a task
+    let mut linked = HashSet::new();
+    let mut in_action_section = false;
+    let mut in_code_block = false;
+
+    for (index, line) in body.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        if let Some((_, heading)) = heading(trimmed) {
+            let heading = heading.to_lowercase();
+            in_action_section = ACTION_HEADINGS.iter().any(|words| {
+                heading.starts_with(words) || heading.contains(&format!(" {}", words))
+            });
+            continue;
+        }
+
+        let (text, is_list_item) = strip_list_marker(trimmed);
+        let (text, checkbox) = strip_checkbox(text);
+        // Done items and items that already have a task are not proposed again, nor are
+        // the notes they were confirmed from
+        if text.contains("/tasks/") {
+            let end = [" — ", " ([task]("]
+                .iter()
+                .filter_map(|separator| text.find(separator))
+                .min()
+                .unwrap_or(text.len());
+            linked.insert(text[..end].trim().to_lowercase());
+            continue;
+        }
+        if checkbox == Some(true) {
+            continue;
+        }
+        let prefix = ACTION_PREFIXES.iter().find(|prefix| {
+            text.get(..prefix.len())
+                .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+        });
+        let text = match prefix {
+            Some(prefix) => &text[prefix.len()..],
+            None if checkbox == Some(false) || (in_action_section && is_list_item) => text,
+            None => continue,
+        };
+
+        if let Some(item) = parse_item(text, (index + skipped_lines + 1) as u32) {
+            items.push(item);
+        }
+    }
+    items.retain(|item| !linked.contains(&item.title.to_lowercase()));
+    items
+}
+
+/// Level and text of an ATX heading line
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&hashes) {
+        return None;
+    }
+    let text = line[hashes..].strip_prefix(' ')?;
+    Some((hashes, text.trim()))
+}
+
+fn strip_list_marker(line: &str) -> (&str, bool) {
+    if let Some(rest) = line.strip_prefix(['-', '*', '+'])
+        && rest.starts_with(' ')
+    {
+        return (rest.trim_start(), true);
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0
+        && let Some(rest) = line[digits..].strip_prefix(['.', ')'])
+        && rest.starts_with(' ')
+    {
+        return (rest.trim_start(), true);
+    }
+    (line, false)
+}
+
+fn strip_checkbox(text: &str) -> (&str, Option<bool>) {
+    if let Some(rest) = text.strip_prefix("[ ]") {
+        (rest.trim_start(), Some(false))
+    } else if let Some(rest) = text
+        .strip_prefix("[x]")
+        .or_else(|| text.strip_prefix("[X]"))
+    {
+        (rest.trim_start(), Some(true))
+    } else {
+        (text, None)
+    }
+}
+
+fn parse_item(text: &str, line: u32) -> Option<ActionItem> {
+    let owner = owner_pattern()
+        .captures(text)
+        .map(|captures| captures[1].to_string());
+    let due_date = due_pattern()
+        .captures_iter(text)
+        .find_map(|captures| NaiveDate::parse_from_str(&captures[1], "%Y-%m-%d").ok());
+
+    let mut title = owner_pattern().replace(text, " ").into_owned();
+    if due_date.is_some() {
+        title = due_pattern().replace(&title, " ").into_owned();
+    }
+    let title = title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | ':' | '-' | '—'))
+        .chars()
+        .take(MAX_TITLE_CHARS)
+        .collect::<String>();
+    if title.is_empty() {
+        return None;
+    }
+
+    Some(ActionItem {
+        title,
+        owner,
+        due_date,
+        line: Some(line),
+    })
+}
+
+/// Task list line for a confirmed item, e.g. `- [ ] Update the runbook — @dana, due 2026-02-20`
+pub fn format_item(item: &ActionItem) -> String {
+    let details: Vec<String> = item
+        .owner
+        .iter()
+        .map(|owner| format!("@{}", owner.trim_start_matches('@')))
+        .chain(item.due_date.iter().map(|due| format!("due {}", due)))
+        .collect();
+    if details.is_empty() {
+        format!("- [ ] {}", item.title.trim())
+    } else {
+        format!("- [ ] {} — {}", item.title.trim(), details.join(", "))
+    }
+}
+
+/// Add `lines` to the end of the "Action items" section of `notes`, starting that
+/// section at the end of the notes if there is none
+pub fn append_to_section(notes: &str, lines: &[String]) -> String {
+    let items = lines.join("\n");
+    // Level of the section heading and the offset of the next heading at or above it
+    let mut section = None;
+    let mut section_end = None;
+    let mut offset = 0;
+    let mut in_code_block = false;
+
+    for line in notes.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+        } else if !in_code_block && let Some((level, text)) = heading(trimmed) {
+            match section {
+                Some(section_level) if level <= section_level => {
+                    section_end = Some(offset);
+                    break;
+                }
+                None if text.eq_ignore_ascii_case(SECTION_HEADING) => section = Some(level),
+                _ => {}
+            }
+        }
+        offset += line.len();
+    }
+
+    if section.is_none() {
+        let before = notes.trim_end();
+        let separator = if before.is_empty() { "" } else { "\n\n" };
+        return format!("{before}{separator}## {SECTION_HEADING}\n\n{items}\n");
+    }
+    let (before, after) = notes.split_at(section_end.unwrap_or(notes.len()));
+    let before = before.trim_end();
+    let last_line = before.lines().last().unwrap_or_default().trim();
+    // Continue an existing list rather than starting a new one
+    let separator = if strip_list_marker(last_line).1 {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    let after = if after.is_empty() {
+        "\n".to_string()
+    } else {
+        format!("\n\n{after}")
+    };
+    format!("{before}{separator}{items}{after}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(title: &str, owner: Option<&str>, due: Option<&str>, line: u32) -> ActionItem {
+        ActionItem {
+            title: title.to_string(),
+            owner: owner.map(str::to_string),
+            due_date: due.map(|due| NaiveDate::parse_from_str(due, "%Y-%m-%d").unwrap()),
+            line: Some(line),
+        }
+    }
+
+    #[test]
+    fn extracts_items_with_owners_and_due_dates() {
+        let notes = "---\ndate: 2026-02-10\n---\n\
+                     # Sync\n\
+                     - Discussed the rollout\n\
+                     - [ ] @dana update the runbook by 2026-02-20\n\
+                     - [x] @bo send the invite\n\
+                     Action: review pricing (due 2026-03-01) @ana.lee\n\
+                     ## Next steps\n\
+                     1. Draft the migration plan\n\
+                     - [ ] Ship ([task](/projects/p/tasks/t))\n\
+                     ```\n\
+                     TODO: not an item\n\
+                     ```\n\
+                     ## Notes\n\
+                     - Budget is fine\n\
+                     - [ ] ship\n";
+
+        assert_eq!(
+            extract(notes),
+            vec![
+                item("update the runbook", Some("dana"), Some("2026-02-20"), 6),
+                item("review pricing", Some("ana.lee"), Some("2026-03-01"), 8),
+                item("Draft the migration plan", None, None, 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn appends_to_the_action_items_section() {
+        let line = format_item(&item("Ship it", Some("@dana"), Some("2026-02-20"), 3));
+        assert_eq!(line, "- [ ] Ship it — @dana, due 2026-02-20");
+
+        let lines = vec![line.clone()];
+        assert_eq!(
+            append_to_section("# Sync\n\nNotes\n", &lines),
+            format!("# Sync\n\nNotes\n\n## Action items\n\n{line}\n")
+        );
+        assert_eq!(
+            append_to_section("## Action items\n\n- [ ] Old\n\n## Later\nText\n", &lines),
+            format!("## Action items\n\n- [ ] Old\n{line}\n\n## Later\nText\n")
+        );
+        assert_eq!(
+            append_to_section("# Sync\n## Action Items\n", &lines),
+            format!("# Sync\n## Action Items\n\n{line}\n")
+        );
+    }
+
+    #[test]
+    fn ignores_notes_without_actions() {
+        assert!(extract("# Retro\n- Went well\n\nEmail me@example.com\n").is_empty());
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use db::models::{
+    analytics::{DailyAttemptCount, ExecutorRunStats},
+    project::Project,
+    repo::Repo,
+    task::Task,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+
+use super::{commit_template::is_document_commit, git::GitService};
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ExecutorSuccessRate {
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub stats: ExecutorRunStats,
+    /// Succeeded runs divided by finished runs, `None` when no run has finished
+    pub success_rate: Option<f64>,
+}
+
+/// Usage rolled up across all projects for a date range
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AnalyticsOverview {
+    /// First day of the range (inclusive)
+    pub from: NaiveDate,
+    /// Last day of the range (inclusive)
+    pub to: NaiveDate,
+    pub projects: usize,
+    pub attempts_per_day: Vec<DailyAttemptCount>,
+    pub executors: Vec<ExecutorSuccessRate>,
+    /// Agent runtime in minutes. Token costs are not recorded, so runtime is the spend measure.
+    pub agent_minutes: f64,
+    /// Distinct documents changed through the document editor
+    pub docs_edited: usize,
+    /// Tasks currently in progress or in review
+    pub active_tasks: i64,
+}
+
+pub async fn overview(
+    pool: &SqlitePool,
+    git: &GitService,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<AnalyticsOverview, sqlx::Error> {
+    let since = from.and_time(chrono::NaiveTime::MIN).and_utc();
+    let until = to
+        .checked_add_days(Days::new(1))
+        .unwrap_or(to)
+        .and_time(chrono::NaiveTime::MIN)
+        .and_utc();
+
+    let attempts_per_day = DailyAttemptCount::find_between(pool, since, until).await?;
+    let executors: Vec<ExecutorSuccessRate> = ExecutorRunStats::find_between(pool, since, until)
+        .await?
+        .into_iter()
+        .map(|stats| {
+            let finished = stats.succeeded + stats.failed;
+            ExecutorSuccessRate {
+                success_rate: (finished > 0).then(|| stats.succeeded as f64 / finished as f64),
+                stats,
+            }
+        })
+        .collect();
+    let agent_minutes = executors
+        .iter()
+        .map(|e| e.stats.runtime_seconds)
+        .sum::<f64>()
+        / 60.0;
+
+    Ok(AnalyticsOverview {
+        from,
+        to,
+        projects: Project::count(pool).await? as usize,
+        attempts_per_day,
+        executors,
+        agent_minutes,
+        docs_edited: count_edited_documents(pool, git, since, until).await?,
+        active_tasks: Task::count_active(pool).await?,
+    })
+}
+
+async fn count_edited_documents(
+    pool: &SqlitePool,
+    git: &GitService,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Result<usize, sqlx::Error> {
+    let mut edited: HashSet<(uuid::Uuid, String)> = HashSet::new();
+    for repo in Repo::list_all(pool).await? {
+        let git = git.clone();
+        let path = repo.path.clone();
+        let paths = tokio::task::spawn_blocking(move || {
+            git.paths_changed_between(&path, since, until, is_document_commit)
+        })
+        .await;
+        match paths {
+            Ok(Ok(paths)) => edited.extend(paths.into_iter().map(|path| (repo.id, path))),
+            Ok(Err(e)) => tracing::debug!(
+                "Skipping repository {} in analytics overview: {}",
+                repo.path.display(),
+                e
+            ),
+            Err(e) => tracing::warn!("Failed to read history of {}: {}", repo.path.display(), e),
+        }
+    }
+    Ok(edited.len())
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render the overview as long-format CSV (`metric,date,executor,variant,value`)
+pub fn to_csv(overview: &AnalyticsOverview) -> String {
+    let mut rows: Vec<[String; 5]> = Vec::new();
+    let summary = |metric: &str, value: String| {
+        [
+            metric.to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            value,
+        ]
+    };
+
+    for day in &overview.attempts_per_day {
+        rows.push([
+            "attempts".to_string(),
+            day.date.clone(),
+            String::new(),
+            String::new(),
+            day.attempts.to_string(),
+        ]);
+    }
+    for executor in &overview.executors {
+        let stats = &executor.stats;
+        let variant = stats.variant.clone().unwrap_or_default();
+        let mut metric = |name: &str, value: String| {
+            rows.push([
+                name.to_string(),
+                String::new(),
+                stats.executor.clone(),
+                variant.clone(),
+                value,
+            ])
+        };
+        metric("runs", stats.runs.to_string());
+        metric("succeeded", stats.succeeded.to_string());
+        metric("failed", stats.failed.to_string());
+        if let Some(rate) = executor.success_rate {
+            metric("success_rate", format!("{rate:.4}"));
+        }
+        metric(
+            "agent_minutes",
+            format!("{:.2}", stats.runtime_seconds / 60.0),
+        );
+    }
+    rows.push(summary(
+        "agent_minutes",
+        format!("{:.2}", overview.agent_minutes),
+    ));
+    rows.push(summary("docs_edited", overview.docs_edited.to_string()));
+    rows.push(summary("active_tasks", overview.active_tasks.to_string()));
+    rows.push(summary("projects", overview.projects.to_string()));
+
+    let mut csv = String::from("metric,date,executor,variant,value\n");
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_long_format_csv() {
+        let overview = AnalyticsOverview {
+            from: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            to: NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(),
+            projects: 2,
+            attempts_per_day: vec![DailyAttemptCount {
+                date: "2026-01-01".to_string(),
+                attempts: 3,
+            }],
+            executors: vec![ExecutorSuccessRate {
+                stats: ExecutorRunStats {
+                    executor: "CLAUDE_CODE".to_string(),
+                    variant: Some("PLAN, FAST".to_string()),
+                    runs: 4,
+                    succeeded: 3,
+                    failed: 1,
+                    runtime_seconds: 120.0,
+                },
+                success_rate: Some(0.75),
+            }],
+            agent_minutes: 2.0,
+            docs_edited: 1,
+            active_tasks: 5,
+        };
+
+        let csv = to_csv(&overview);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "metric,date,executor,variant,value");
+        assert_eq!(lines[1], "attempts,2026-01-01,,,3");
+        assert_eq!(lines[2], "runs,,CLAUDE_CODE,\"PLAN, FAST\",4");
+        assert!(lines.contains(&"success_rate,,CLAUDE_CODE,\"PLAN, FAST\",0.7500"));
+        assert!(lines.contains(&"active_tasks,,,,5"));
+    }
+}
@@ -0,0 +1,179 @@
+//! Commit message templates for document commits, e.g. `docs({user}): {message}`.
+//! `{message}` is the message the change would otherwise be committed with, `{path}`
+//! the changed document(s) and `{user}` who made the change. `{{` and `}}` stand for
+//! literal braces.
+
+pub const PLACEHOLDERS: [&str; 3] = ["message", "path", "user"];
+
+/// Trailer of every document commit. Templates can reword the whole message, so
+/// document commits are told apart by this rather than by their subject.
+pub const DOCUMENT_COMMIT_TRAILER: &str = "Document-Commit: true";
+
+/// What a template's placeholders are replaced with
+#[derive(Debug, Clone, Copy)]
+pub struct CommitTemplateValues<'a> {
+    pub message: &'a str,
+    pub path: &'a str,
+    pub user: &'a str,
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+fn parse(template: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(index) = rest.find(['{', '}']) {
+        segments.push(Segment::Text(&rest[..index]));
+        let brace = &rest[index..index + 1];
+        let after = &rest[index + 1..];
+        if after.starts_with(brace) {
+            segments.push(Segment::Text(brace));
+            rest = &after[1..];
+        } else if brace == "}" {
+            return Err(format!(
+                "Unmatched '}}' at position {}; write '}}}}' for a literal brace",
+                template.len() - rest.len() + index
+            ));
+        } else {
+            let Some(end) = after.find('}') else {
+                return Err("Unclosed '{'; write '{{' for a literal brace".to_string());
+            };
+            segments.push(Segment::Placeholder(&after[..end]));
+            rest = &after[end + 1..];
+        }
+    }
+    segments.push(Segment::Text(rest));
+    Ok(segments)
+}
+
+/// Check that `template` is well-formed and only uses known placeholders
+pub fn validate(template: &str) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("Commit message template can't be empty".to_string());
+    }
+    for segment in parse(template)? {
+        if let Segment::Placeholder(name) = segment
+            && !PLACEHOLDERS.contains(&name)
+        {
+            return Err(format!(
+                "Unknown placeholder '{{{}}}'; use {}",
+                name,
+                PLACEHOLDERS.map(|name| format!("{{{name}}}")).join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The commit message `template` describes, or `values.message` if the template is
+/// invalid or renders to nothing
+pub fn render(template: &str, values: CommitTemplateValues<'_>) -> String {
+    let Ok(segments) = parse(template) else {
+        return values.message.to_string();
+    };
+    let mut message = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => message.push_str(text),
+            Segment::Placeholder("message") => message.push_str(values.message),
+            Segment::Placeholder("path") => message.push_str(values.path),
+            Segment::Placeholder("user") => message.push_str(values.user),
+            Segment::Placeholder(_) => return values.message.to_string(),
+        }
+    }
+    let message = message.trim();
+    if message.is_empty() {
+        values.message.to_string()
+    } else {
+        message.to_string()
+    }
+}
+
+/// Whether `line` looks like a git trailer such as `Signed-off-by: alice`
+fn is_trailer(line: &str) -> bool {
+    line.split_once(": ").is_some_and(|(token, _)| {
+        !token.is_empty() && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// `message` with [`DOCUMENT_COMMIT_TRAILER`] added to its trailers
+pub fn with_document_trailer(message: &str) -> String {
+    let message = message.trim_end();
+    if is_document_commit(message) {
+        return message.to_string();
+    }
+    match message.rsplit_once("\n\n") {
+        Some((_, trailers)) if trailers.lines().all(is_trailer) => {
+            format!("{message}\n{DOCUMENT_COMMIT_TRAILER}")
+        }
+        _ => format!("{message}\n\n{DOCUMENT_COMMIT_TRAILER}"),
+    }
+}
+
+/// Whether a commit message is that of a document commit
+pub fn is_document_commit(message: &str) -> bool {
+    message
+        .lines()
+        .any(|line| line.trim() == DOCUMENT_COMMIT_TRAILER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUES: CommitTemplateValues<'static> = CommitTemplateValues {
+        message: "docs: update docs/a.md",
+        path: "docs/a.md",
+        user: "alice",
+    };
+
+    #[test]
+    fn replaces_placeholders() {
+        assert_eq!(
+            render("[docs] {path} by {user}", VALUES),
+            "[docs] docs/a.md by alice"
+        );
+        assert_eq!(
+            render("{message}\n\nSigned-off-by: {user}", VALUES),
+            "docs: update docs/a.md\n\nSigned-off-by: alice"
+        );
+        assert_eq!(render("{{{path}}}", VALUES), "{docs/a.md}");
+    }
+
+    #[test]
+    fn falls_back_to_the_default_message() {
+        assert_eq!(render("{unknown}", VALUES), VALUES.message);
+        assert_eq!(render("{path", VALUES), VALUES.message);
+        let empty_user = CommitTemplateValues { user: "", ..VALUES };
+        assert_eq!(render("  {user} ", empty_user), VALUES.message);
+    }
+
+    #[test]
+    fn marks_document_commits_with_a_trailer() {
+        let message = with_document_trailer("docs(alice): update docs/a.md");
+        assert_eq!(
+            message,
+            "docs(alice): update docs/a.md\n\nDocument-Commit: true"
+        );
+        assert!(is_document_commit(&message));
+        assert_eq!(with_document_trailer(&message), message);
+        assert_eq!(
+            with_document_trailer("docs: update a.md\n\nApproved-by: bob\n"),
+            "docs: update a.md\n\nApproved-by: bob\nDocument-Commit: true"
+        );
+        assert!(!is_document_commit("docs: update a.md"));
+    }
+
+    #[test]
+    fn validates_templates() {
+        assert!(validate("docs({user}): {message}").is_ok());
+        assert!(validate("{{literal}}").is_ok());
+        assert!(validate("  ").is_err());
+        assert!(validate("{author}").unwrap_err().contains("{author}"));
+        assert!(validate("{path").is_err());
+        assert!(validate("path}").is_err());
+    }
+}
@@ -0,0 +1,337 @@
+//! Tamper-evident export bundles for audits. A bundle is a gzipped tar of the exported
+//! files, a manifest listing the SHA-256 of each of them, and a detached Ed25519
+//! signature of the manifest made with this instance's export key. Changing, adding or
+//! removing a file breaks either the manifest hashes or the signature.
+
+use std::path::{Path, PathBuf};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use flate2::{Compression, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use ts_rs::TS;
+use utils::version::APP_VERSION;
+use uuid::Uuid;
+
+/// Identifies the bundle layout, so verifiers know how to read it
+pub const EXPORT_FORMAT: &str = "vibe-kanban-compliance-export/v1";
+pub const MANIFEST_PATH: &str = "manifest.json";
+/// Base64 of the Ed25519 signature of the exact bytes of the manifest
+pub const SIGNATURE_PATH: &str = "manifest.json.sig";
+
+#[derive(Debug, Error)]
+pub enum ComplianceExportError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Export signing key at {0:?} is invalid")]
+    InvalidKey(PathBuf),
+    #[error("'{0}' is in the export more than once")]
+    DuplicatePath(String),
+}
+
+/// Signs export manifests with the instance's export key
+pub struct ExportSigner {
+    key: SigningKey,
+}
+
+impl ExportSigner {
+    /// The key stored at `path`, or a new one saved there on first use. The file is
+    /// only readable by the current user.
+    pub fn load_or_create(path: &Path) -> Result<Self, ComplianceExportError> {
+        if path.exists() {
+            let encoded = std::fs::read_to_string(path)?;
+            let bytes: [u8; 32] = BASE64
+                .decode(encoded.trim())
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| ComplianceExportError::InvalidKey(path.to_path_buf()))?;
+            return Ok(Self {
+                key: SigningKey::from_bytes(&bytes),
+            });
+        }
+
+        let key = SigningKey::from_bytes(&rand::random::<[u8; 32]>());
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut options = std::fs::OpenOptions::new();
+        options.create_new(true).write(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(path)?;
+        std::io::Write::write_all(&mut file, BASE64.encode(key.to_bytes()).as_bytes())?;
+        file.sync_all()?;
+        Ok(Self { key })
+    }
+
+    /// Base64 of the public key that verifies this signer's signatures
+    pub fn public_key(&self) -> String {
+        BASE64.encode(self.key.verifying_key().to_bytes())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ExportManifestEntry {
+    /// Path of the file inside the bundle
+    pub path: String,
+    /// Hex SHA-256 of the file
+    pub sha256: String,
+    pub size_bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ExportManifest {
+    pub format: String,
+    pub export_id: Uuid,
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub created_at: DateTime<Utc>,
+    pub app_version: String,
+    /// Base64 Ed25519 public key the signature verifies with
+    pub public_key: String,
+    /// Every file of the bundle besides the manifest and its signature, sorted by path
+    pub files: Vec<ExportManifestEntry>,
+}
+
+/// A finished bundle
+pub struct SignedExport {
+    /// Gzipped tar of the files, the manifest and its signature
+    pub archive: Vec<u8>,
+    pub manifest: ExportManifest,
+    /// Base64 signature of the serialized manifest
+    pub signature: String,
+}
+
+/// Files collected for an export bundle
+#[derive(Default)]
+pub struct ExportBundle {
+    files: Vec<(String, Vec<u8>)>,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn append(
+    archive: &mut tar::Builder<GzEncoder<&mut Vec<u8>>>,
+    path: &str,
+    bytes: &[u8],
+    modified: &DateTime<Utc>,
+) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(modified.timestamp().max(0) as u64);
+    archive.append_data(&mut header, path, bytes)
+}
+
+impl ExportBundle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, path: impl Into<String>, bytes: Vec<u8>) {
+        self.files.push((path.into(), bytes));
+    }
+
+    pub fn add_json(
+        &mut self,
+        path: impl Into<String>,
+        value: &impl Serialize,
+    ) -> Result<(), ComplianceExportError> {
+        self.add(path, serde_json::to_vec_pretty(value)?);
+        Ok(())
+    }
+
+    /// Hash every file into a manifest, sign it and pack the bundle
+    pub fn finish(
+        mut self,
+        signer: &ExportSigner,
+        project_id: Uuid,
+        project_name: &str,
+    ) -> Result<SignedExport, ComplianceExportError> {
+        self.files.sort_by(|a, b| a.0.cmp(&b.0));
+        if let Some(pair) = self.files.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(ComplianceExportError::DuplicatePath(pair[0].0.clone()));
+        }
+        if let Some((path, _)) = self
+            .files
+            .iter()
+            .find(|(path, _)| path == MANIFEST_PATH || path == SIGNATURE_PATH)
+        {
+            return Err(ComplianceExportError::DuplicatePath(path.clone()));
+        }
+
+        let manifest = ExportManifest {
+            format: EXPORT_FORMAT.to_string(),
+            export_id: Uuid::new_v4(),
+            project_id,
+            project_name: project_name.to_string(),
+            created_at: Utc::now(),
+            app_version: APP_VERSION.to_string(),
+            public_key: signer.public_key(),
+            files: self
+                .files
+                .iter()
+                .map(|(path, bytes)| ExportManifestEntry {
+                    path: path.clone(),
+                    sha256: sha256_hex(bytes),
+                    size_bytes: bytes.len(),
+                })
+                .collect(),
+        };
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
+        let signature = BASE64.encode(signer.key.sign(&manifest_bytes).to_bytes());
+
+        let mut buffer = Vec::new();
+        {
+            let mut archive =
+                tar::Builder::new(GzEncoder::new(&mut buffer, Compression::default()));
+            append(
+                &mut archive,
+                MANIFEST_PATH,
+                &manifest_bytes,
+                &manifest.created_at,
+            )?;
+            append(
+                &mut archive,
+                SIGNATURE_PATH,
+                signature.as_bytes(),
+                &manifest.created_at,
+            )?;
+            for (path, bytes) in &self.files {
+                append(&mut archive, path, bytes, &manifest.created_at)?;
+            }
+            archive.into_inner()?.finish()?;
+        }
+
+        Ok(SignedExport {
+            archive: buffer,
+            manifest,
+            signature,
+        })
+    }
+}
+
+/// Whether `signature` is a valid signature of `manifest` by `public_key`, all as they
+/// appear in a bundle
+pub fn verify_manifest(manifest: &[u8], signature: &str, public_key: &str) -> bool {
+    let Some(key) = BASE64
+        .decode(public_key.trim())
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+    else {
+        return false;
+    };
+    let Some(signature) = BASE64
+        .decode(signature.trim())
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+    else {
+        return false;
+    };
+    key.verify(manifest, &signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, io::Read};
+
+    use super::*;
+
+    fn unpack(archive: &[u8]) -> HashMap<String, Vec<u8>> {
+        let mut files = HashMap::new();
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let path = entry.path().unwrap().to_string_lossy().to_string();
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).unwrap();
+            files.insert(path, bytes);
+        }
+        files
+    }
+
+    #[test]
+    fn signs_a_manifest_of_every_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let signer = ExportSigner::load_or_create(&dir.path().join("key")).unwrap();
+        let mut bundle = ExportBundle::new();
+        bundle.add("documents/docs/plan.md", b"# Plan".to_vec());
+        bundle
+            .add_json("tasks.json", &serde_json::json!([{ "title": "Ship it" }]))
+            .unwrap();
+        let export = bundle.finish(&signer, Uuid::new_v4(), "Demo").unwrap();
+
+        let files = unpack(&export.archive);
+        assert_eq!(files.len(), 4);
+        let manifest_bytes = &files[MANIFEST_PATH];
+        let signature = String::from_utf8(files[SIGNATURE_PATH].clone()).unwrap();
+        assert_eq!(signature, export.signature);
+        assert!(verify_manifest(
+            manifest_bytes,
+            &signature,
+            &signer.public_key()
+        ));
+
+        let manifest: ExportManifest = serde_json::from_slice(manifest_bytes).unwrap();
+        let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["documents/docs/plan.md", "tasks.json"]);
+        for entry in &manifest.files {
+            assert_eq!(entry.sha256, sha256_hex(&files[&entry.path]));
+        }
+
+        // Any change to the manifest invalidates the signature
+        let mut tampered = manifest_bytes.clone();
+        tampered.extend_from_slice(b" ");
+        assert!(!verify_manifest(
+            &tampered,
+            &signature,
+            &signer.public_key()
+        ));
+    }
+
+    #[test]
+    fn keeps_the_key_across_loads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys/export-signing-key");
+        let first = ExportSigner::load_or_create(&path).unwrap();
+        let second = ExportSigner::load_or_create(&path).unwrap();
+        assert_eq!(first.public_key(), second.public_key());
+
+        std::fs::write(&path, "not a key").unwrap();
+        assert!(matches!(
+            ExportSigner::load_or_create(&path),
+            Err(ComplianceExportError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_duplicate_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let signer = ExportSigner::load_or_create(&dir.path().join("key")).unwrap();
+        let mut bundle = ExportBundle::new();
+        bundle.add("tasks.json", Vec::new());
+        bundle.add("tasks.json", Vec::new());
+        assert!(matches!(
+            bundle.finish(&signer, Uuid::new_v4(), "Demo"),
+            Err(ComplianceExportError::DuplicatePath(path)) if path == "tasks.json"
+        ));
+
+        let mut bundle = ExportBundle::new();
+        bundle.add(MANIFEST_PATH, Vec::new());
+        assert!(bundle.finish(&signer, Uuid::new_v4(), "Demo").is_err());
+    }
+}
@@ -26,6 +26,16 @@ pub type EditorType = versions::v8::EditorType;
 pub type GitHubConfig = versions::v8::GitHubConfig;
 pub type UiLanguage = versions::v8::UiLanguage;
 pub type ShowcaseState = versions::v8::ShowcaseState;
+pub type DependencyUpdateConfig = versions::v8::DependencyUpdateConfig;
+pub type TaskAgingConfig = versions::v8::TaskAgingConfig;
+pub type DocumentSnapshotConfig = versions::v8::DocumentSnapshotConfig;
+pub type TelemetryConfig = versions::v8::TelemetryConfig;
+pub type OnboardingStep = versions::v8::OnboardingStep;
+pub type OnboardingProgress = versions::v8::OnboardingProgress;
+pub type RetentionCategory = versions::v8::RetentionCategory;
+pub type RetentionConfig = versions::v8::RetentionConfig;
+pub type ProjectLimitsConfig = versions::v8::ProjectLimitsConfig;
+pub type ErrorReportConfig = versions::v8::ErrorReportConfig;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
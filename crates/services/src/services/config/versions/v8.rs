@@ -17,6 +17,218 @@ fn default_pr_auto_description_enabled() -> bool {
     true
 }
 
+fn default_dependency_update_interval_hours() -> u32 {
+    24
+}
+
+fn default_task_aging_todo_days() -> u32 {
+    7
+}
+
+fn default_document_snapshot_interval_hours() -> u32 {
+    24
+}
+
+fn default_document_snapshot_retention() -> u32 {
+    30
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct DependencyUpdateConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_dependency_update_interval_hours")]
+    pub interval_hours: u32,
+    /// Local time of day (`HH:MM`, in each project's timezone) to run once a day instead
+    /// of every `interval_hours`
+    #[serde(default)]
+    pub run_at: Option<String>,
+    /// Start a workspace for each created task immediately
+    #[serde(default)]
+    pub auto_dispatch: bool,
+    /// Executor used for auto-dispatched tasks (defaults to the global executor profile)
+    #[serde(default)]
+    pub executor_profile: Option<ExecutorProfileId>,
+    /// Only open the pull request of a dependency update task once its tests pass
+    #[serde(default = "default_true")]
+    pub require_passing_tests: bool,
+}
+
+impl Default for DependencyUpdateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: default_dependency_update_interval_hours(),
+            run_at: None,
+            auto_dispatch: false,
+            executor_profile: None,
+            require_passing_tests: true,
+        }
+    }
+}
+
+/// Bump the priority of tasks that sit in Todo for too long
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct TaskAgingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Days a task may stay in Todo before its priority is raised one level
+    #[serde(default = "default_task_aging_todo_days")]
+    pub todo_days: u32,
+}
+
+impl Default for TaskAgingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            todo_days: default_task_aging_todo_days(),
+        }
+    }
+}
+
+/// Periodically record the commit of each repository's docs branch
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct DocumentSnapshotConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_document_snapshot_interval_hours")]
+    pub interval_hours: u32,
+    /// Local time of day (`HH:MM`, in each project's timezone) to snapshot once a day
+    /// instead of every `interval_hours`
+    #[serde(default)]
+    pub run_at: Option<String>,
+    /// Snapshots kept per repository; older ones are deleted
+    #[serde(default = "default_document_snapshot_retention")]
+    pub retention: u32,
+}
+
+impl Default for DocumentSnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: default_document_snapshot_interval_hours(),
+            run_at: None,
+            retention: default_document_snapshot_retention(),
+        }
+    }
+}
+
+/// Kinds of data the retention policies delete once they are past their limit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionCategory {
+    /// Logs of coding agent and script runs
+    AttemptLogs,
+    /// Runs themselves, as listed in the audit log of compliance exports
+    AuditEvents,
+    /// Artifacts of e2e runs and preview screenshots and comparisons
+    Artifacts,
+}
+
+/// Delete data once it is older, or larger, than its limit. Categories without a limit
+/// are kept forever.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct RetentionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Days the logs of a finished run are kept
+    #[serde(default)]
+    pub attempt_log_days: Option<u32>,
+    /// Months a finished run is kept in the audit log
+    #[serde(default)]
+    pub audit_event_months: Option<u32>,
+    /// Gigabytes of artifacts kept; the oldest are deleted first
+    #[serde(default)]
+    pub artifact_max_gb: Option<f64>,
+    /// Categories that are only reported, to check a limit before anything is deleted
+    #[serde(default)]
+    pub dry_run: Vec<RetentionCategory>,
+}
+
+fn default_background_jobs() -> usize {
+    4
+}
+
+fn default_background_jobs_per_project() -> usize {
+    1
+}
+
+/// Shares of the server each project gets, so a busy project can't starve the others
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct ProjectLimitsConfig {
+    /// API requests a project may receive per minute; `None` is unlimited
+    #[serde(default)]
+    pub api_requests_per_minute: Option<u32>,
+    /// Background jobs, such as document syncs and refreshes, run at once across projects
+    #[serde(default = "default_background_jobs")]
+    pub background_jobs: usize,
+    /// Background jobs one project may run at once
+    #[serde(default = "default_background_jobs_per_project")]
+    pub background_jobs_per_project: usize,
+}
+
+impl Default for ProjectLimitsConfig {
+    fn default() -> Self {
+        Self {
+            api_requests_per_minute: None,
+            background_jobs: default_background_jobs(),
+            background_jobs_per_project: default_background_jobs_per_project(),
+        }
+    }
+}
+
+/// Reports of requests that failed with a server error
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct ErrorReportConfig {
+    /// Store a report, with the request's log lines, for each server error
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// DSN of a Sentry-compatible endpoint reports are also sent to, such as a
+    /// self-hosted Sentry or GlitchTip project
+    #[serde(default)]
+    pub forward_dsn: Option<String>,
+}
+
+impl Default for ErrorReportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            forward_dsn: None,
+        }
+    }
+}
+
+/// Anonymous usage telemetry, off unless the user opts in
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct TelemetryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Overrides the default telemetry endpoint, e.g. to send events to a self-hosted collector
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+/// Steps of the first-run onboarding flow, in the order they are presented
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    GitIdentity,
+    CodingAgent,
+    Project,
+    SampleAttempt,
+}
+
+/// Onboarding steps this instance has completed through the onboarding API
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct OnboardingProgress {
+    #[serde(default)]
+    pub completed_steps: Vec<OnboardingStep>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -33,6 +245,10 @@ pub struct Config {
     pub show_release_notes: bool,
     #[serde(default)]
     pub language: UiLanguage,
+    /// Timezone of schedules and due dates of projects without their own: an IANA name
+    /// such as `Europe/Paris` or a UTC offset. `None` is UTC.
+    #[serde(default)]
+    pub timezone: Option<String>,
     #[serde(default = "default_git_branch_prefix")]
     pub git_branch_prefix: String,
     #[serde(default)]
@@ -47,6 +263,22 @@ pub struct Config {
     pub beta_workspaces_invitation_sent: bool,
     #[serde(default)]
     pub commit_reminder: bool,
+    #[serde(default)]
+    pub dependency_updates: DependencyUpdateConfig,
+    #[serde(default)]
+    pub task_aging: TaskAgingConfig,
+    #[serde(default)]
+    pub document_snapshots: DocumentSnapshotConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub onboarding: OnboardingProgress,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default)]
+    pub project_limits: ProjectLimitsConfig,
+    #[serde(default)]
+    pub error_reports: ErrorReportConfig,
 }
 
 impl Config {
@@ -68,6 +300,7 @@ impl Config {
             last_app_version: old_config.last_app_version,
             show_release_notes: old_config.show_release_notes,
             language: old_config.language,
+            timezone: None,
             git_branch_prefix: old_config.git_branch_prefix,
             showcases: old_config.showcases,
             pr_auto_description_enabled: true,
@@ -75,6 +308,14 @@ impl Config {
             beta_workspaces: false,
             beta_workspaces_invitation_sent: false,
             commit_reminder: false,
+            dependency_updates: DependencyUpdateConfig::default(),
+            task_aging: TaskAgingConfig::default(),
+            document_snapshots: DocumentSnapshotConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            onboarding: OnboardingProgress::default(),
+            retention: RetentionConfig::default(),
+            project_limits: ProjectLimitsConfig::default(),
+            error_reports: ErrorReportConfig::default(),
         }
     }
 
@@ -121,6 +362,7 @@ impl Default for Config {
             last_app_version: None,
             show_release_notes: false,
             language: UiLanguage::default(),
+            timezone: None,
             git_branch_prefix: default_git_branch_prefix(),
             showcases: ShowcaseState::default(),
             pr_auto_description_enabled: true,
@@ -128,6 +370,14 @@ impl Default for Config {
             beta_workspaces: false,
             beta_workspaces_invitation_sent: false,
             commit_reminder: false,
+            dependency_updates: DependencyUpdateConfig::default(),
+            task_aging: TaskAgingConfig::default(),
+            document_snapshots: DocumentSnapshotConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            onboarding: OnboardingProgress::default(),
+            retention: RetentionConfig::default(),
+            project_limits: ProjectLimitsConfig::default(),
+            error_reports: ErrorReportConfig::default(),
         }
     }
 }
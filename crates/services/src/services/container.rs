@@ -21,6 +21,8 @@ use db::{
         repo::Repo,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
+        task_document::TaskDocument,
+        task_package_scope::TaskPackageScope,
         workspace::{Workspace, WorkspaceError},
         workspace_repo::WorkspaceRepo,
     },
@@ -52,7 +54,9 @@ use uuid::Uuid;
 
 use crate::services::{
     git::{GitService, GitServiceError},
+    monorepo,
     notification::NotificationService,
+    task_documents,
     workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
@@ -871,7 +875,38 @@ pub trait ContainerService {
         )
         .await?;
 
-        let prompt = task.to_prompt();
+        let mut prompt = task.to_prompt();
+
+        if let Some(scope) = TaskPackageScope::find_by_task_id(&self.db().pool, task.id).await?
+            && let Some(repo) = repos.iter().find(|r| r.id == scope.repo_id)
+        {
+            let scripts = monorepo::detect_packages(&repo.path)
+                .into_iter()
+                .find(|p| p.path == scope.package_path)
+                .map(|p| p.scripts)
+                .unwrap_or_default();
+            prompt.push_str(&monorepo::package_scope_prompt(
+                &repo.name,
+                &scope.package_name,
+                &scope.package_path,
+                &scripts,
+            ));
+        }
+
+        let related_documents: Vec<String> =
+            TaskDocument::find_by_task_id(&self.db().pool, task.id)
+                .await?
+                .into_iter()
+                .filter_map(|document| {
+                    repos
+                        .iter()
+                        .find(|r| r.id == document.repo_id)
+                        .map(|repo| format!("{}/{}", repo.name, document.relative_path))
+                })
+                .collect();
+        prompt.push_str(&task_documents::related_documents_prompt(
+            &related_documents,
+        ));
 
         let repos_with_setup: Vec<_> = repos.iter().filter(|r| r.setup_script.is_some()).collect();
 
@@ -0,0 +1,112 @@
+//! Throwaway demo projects: a seeded git repository with docs and example tasks,
+//! so new users and end-to-end tests have realistic data to work with.
+
+use std::path::{Path, PathBuf};
+
+use db::models::task::TaskStatus;
+use uuid::Uuid;
+
+use super::git::{GitService, GitServiceError};
+
+/// Files committed to the demo repository, relative to its root
+const DEMO_FILES: &[(&str, &str)] = &[
+    (
+        "README.md",
+        "# Acme Notes\n\nA tiny note-taking service used to try out Vibe Kanban.\n\n\
+         ## Running\n\n```bash\nnode src/server.js\n```\n",
+    ),
+    (
+        "docs/getting-started.md",
+        "# Getting started\n\n1. Install Node.js 20 or newer\n2. Run `node src/server.js`\n\
+         3. Open http://localhost:8080/notes\n",
+    ),
+    (
+        "docs/architecture.md",
+        "# Architecture\n\nNotes are kept in memory in `src/store.js` and exposed over HTTP by \
+         `src/server.js`. There is no persistence yet.\n",
+    ),
+    (
+        "docs/roadmap.json",
+        "{\n  \"next\": [\"persist notes to disk\", \"search\"],\n  \"later\": [\"sharing\"]\n}\n",
+    ),
+    (
+        "src/store.js",
+        "const notes = new Map();\n\nexports.add = (text) => {\n  const id = notes.size + 1;\n  \
+         notes.set(id, { id, text });\n  return id;\n};\n\n\
+         exports.list = () => [...notes.values()];\n",
+    ),
+    (
+        "src/server.js",
+        "const http = require('http');\nconst store = require('./store');\n\n\
+         http\n  .createServer((req, res) => {\n    \
+         res.setHeader('content-type', 'application/json');\n    \
+         res.end(JSON.stringify(store.list()));\n  })\n  .listen(8080);\n",
+    ),
+];
+
+/// Example tasks created in the demo project: title, description, status
+pub const DEMO_TASKS: &[(&str, &str, TaskStatus)] = &[
+    (
+        "Persist notes to disk",
+        "Store notes in a JSON file so they survive restarts. Keep `src/store.js` as the only \
+         module touching the file.",
+        TaskStatus::Todo,
+    ),
+    (
+        "Add a search endpoint",
+        "Add `GET /notes?q=` returning notes whose text contains the query, case-insensitively.",
+        TaskStatus::Todo,
+    ),
+    (
+        "Document the HTTP API",
+        "Describe every endpoint with an example request and response in `docs/api.md`.",
+        TaskStatus::InProgress,
+    ),
+    (
+        "Set up the project",
+        "Initial repository layout, README and architecture notes.",
+        TaskStatus::Done,
+    ),
+];
+
+/// The change the scripted demo attempt makes in its worktree, and its commit message
+pub const DEMO_ATTEMPT_FILE: (&str, &str) = (
+    "docs/api.md",
+    "# HTTP API\n\n## `GET /notes`\n\nReturns every note.\n\n\
+     ```json\n[{ \"id\": 1, \"text\": \"Buy milk\" }]\n```\n",
+);
+pub const DEMO_ATTEMPT_COMMIT: &str = "Document the notes endpoint";
+
+/// Where demo repositories are created; they are throwaway and live in the temp dir
+pub fn demo_repo_path() -> PathBuf {
+    let short_id = Uuid::new_v4().simple().to_string();
+    utils::path::get_vibe_kanban_temp_dir()
+        .join("demo")
+        .join(format!("acme-notes-{}", &short_id[..8]))
+}
+
+fn write_files(root: &Path, files: &[(&str, &str)]) -> Result<(), std::io::Error> {
+    for (relative_path, content) in files {
+        let path = root.join(relative_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+    }
+    Ok(())
+}
+
+/// Initialize a git repository at `path` on `main` and commit the demo files
+pub fn create_demo_repo(git: &GitService, path: &Path) -> Result<(), GitServiceError> {
+    git.initialize_repo_with_main_branch(path)?;
+    write_files(path, DEMO_FILES)?;
+    git.commit(path, "Add Acme Notes skeleton")?;
+    Ok(())
+}
+
+/// Make the scripted demo attempt's change in a workspace worktree and commit it
+pub fn apply_demo_attempt(git: &GitService, worktree: &Path) -> Result<(), GitServiceError> {
+    write_files(worktree, &[DEMO_ATTEMPT_FILE])?;
+    git.commit(worktree, DEMO_ATTEMPT_COMMIT)?;
+    Ok(())
+}
@@ -0,0 +1,558 @@
+use std::{collections::BTreeMap, path::Path, time::Duration};
+
+use db::models::{
+    repo::Repo,
+    task::{CreateTask, Task},
+    task_package_scope::TaskPackageScope,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::shell::resolve_executable_path;
+use uuid::Uuid;
+
+use crate::services::monorepo::{self, PackageManager, WorkspacePackage};
+
+/// Upper bound for a single `cargo outdated` / `npm outdated` run
+const OUTDATED_COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
+/// Upper bound for the test run that gates a dependency update pull request
+const TEST_COMMAND_TIMEOUT: Duration = Duration::from_secs(1800);
+/// Output kept from a failed test run, from its end
+const TEST_OUTPUT_TAIL_BYTES: usize = 4000;
+
+const TASK_TITLE_PREFIX: &str = "Update dependencies: ";
+
+#[derive(Debug, Error)]
+pub enum DependencyUpdateError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("{0} timed out")]
+    Timeout(String),
+    #[error("{command} failed: {stderr}")]
+    CommandFailed { command: String, stderr: String },
+}
+
+/// A dependency whose declared version is behind the latest release
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct OutdatedDependency {
+    pub name: String,
+    pub current: String,
+    pub latest: String,
+    pub manager: PackageManager,
+    /// Workspace package that declares the dependency, when the repo is a monorepo
+    pub package_path: Option<String>,
+}
+
+/// Outdated dependencies for one repository (or one package within it)
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DependencyUpdateReport {
+    pub repo_id: Uuid,
+    pub repo_name: String,
+    pub package_path: Option<String>,
+    pub package_name: Option<String>,
+    pub dependencies: Vec<OutdatedDependency>,
+    /// Proposed manifest changes rendered as a unified diff
+    pub manifest_diff: String,
+    /// Tools that could not be run (e.g. `cargo-outdated` not installed)
+    pub skipped: Vec<String>,
+}
+
+/// Detect outdated dependencies in a repository, grouped per workspace package.
+pub async fn check_repo(repo: &Repo) -> Vec<DependencyUpdateReport> {
+    let packages = monorepo::detect_packages(&repo.path);
+    let mut dependencies = Vec::new();
+    let mut skipped = Vec::new();
+
+    if repo.path.join("Cargo.toml").exists() {
+        match cargo_outdated(&repo.path, &packages).await {
+            Ok(deps) => dependencies.extend(deps),
+            Err(e) => {
+                tracing::debug!("cargo outdated failed for {}: {}", repo.name, e);
+                skipped.push(format!("cargo: {e}"));
+            }
+        }
+    }
+    if repo.path.join("package.json").exists() {
+        match npm_outdated(&repo.path, &packages).await {
+            Ok(deps) => dependencies.extend(deps),
+            Err(e) => {
+                tracing::debug!("npm outdated failed for {}: {}", repo.name, e);
+                skipped.push(format!("npm: {e}"));
+            }
+        }
+    }
+
+    let mut grouped: BTreeMap<Option<String>, Vec<OutdatedDependency>> = BTreeMap::new();
+    for dep in dependencies {
+        grouped
+            .entry(dep.package_path.clone())
+            .or_default()
+            .push(dep);
+    }
+
+    if grouped.is_empty() {
+        return vec![DependencyUpdateReport {
+            repo_id: repo.id,
+            repo_name: repo.name.clone(),
+            package_path: None,
+            package_name: None,
+            dependencies: Vec::new(),
+            manifest_diff: String::new(),
+            skipped,
+        }];
+    }
+
+    grouped
+        .into_iter()
+        .map(|(package_path, dependencies)| {
+            let package_name = package_path.as_ref().and_then(|path| {
+                packages
+                    .iter()
+                    .find(|p| &p.path == path)
+                    .map(|p| p.name.clone())
+            });
+            DependencyUpdateReport {
+                repo_id: repo.id,
+                repo_name: repo.name.clone(),
+                manifest_diff: render_manifest_diff(&dependencies),
+                package_path,
+                package_name,
+                dependencies,
+                skipped: skipped.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Title used for dependency update tasks; also used to avoid creating duplicates
+pub fn task_title(report: &DependencyUpdateReport) -> String {
+    match &report.package_name {
+        Some(package) => format!("{TASK_TITLE_PREFIX}{} ({})", report.repo_name, package),
+        None => format!("{TASK_TITLE_PREFIX}{}", report.repo_name),
+    }
+}
+
+/// Whether a task with `title` was created by the dependency update workflow
+pub fn is_update_task(title: &str) -> bool {
+    title.starts_with(TASK_TITLE_PREFIX)
+}
+
+/// Create a task for the report unless an open one already exists.
+///
+/// Returns `None` when there is nothing to update or a matching task is still open.
+pub async fn create_update_task(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    report: &DependencyUpdateReport,
+    require_passing_tests: bool,
+) -> Result<Option<Task>, DependencyUpdateError> {
+    if report.dependencies.is_empty() {
+        return Ok(None);
+    }
+
+    let title = task_title(report);
+    if Task::find_open_by_title(pool, project_id, &title)
+        .await?
+        .is_some()
+    {
+        return Ok(None);
+    }
+
+    let task = Task::create(
+        pool,
+        &CreateTask::from_title_description(
+            project_id,
+            title,
+            Some(task_description(report, require_passing_tests)),
+        ),
+        Uuid::new_v4(),
+    )
+    .await?;
+
+    if let (Some(path), Some(name)) = (&report.package_path, &report.package_name) {
+        TaskPackageScope::upsert(pool, task.id, report.repo_id, path, name).await?;
+    }
+
+    Ok(Some(task))
+}
+
+fn task_description(report: &DependencyUpdateReport, require_passing_tests: bool) -> String {
+    let mut description = String::from("Update the following outdated dependencies:\n\n");
+    for dep in &report.dependencies {
+        description.push_str(&format!(
+            "- `{}` {} → {}\n",
+            dep.name, dep.current, dep.latest
+        ));
+    }
+    description.push_str("\nProposed manifest changes:\n\n```diff\n");
+    description.push_str(&report.manifest_diff);
+    description.push_str("```\n");
+    if require_passing_tests {
+        description.push_str(
+            "\nRun the project's test suite after updating. If any test fails, fix the breakage or revert the offending upgrade; the pull request can only be opened once the tests pass.\n",
+        );
+    }
+    description
+}
+
+fn render_manifest_diff(dependencies: &[OutdatedDependency]) -> String {
+    let mut by_manifest: BTreeMap<String, Vec<&OutdatedDependency>> = BTreeMap::new();
+    for dep in dependencies {
+        let manifest = match dep.manager {
+            PackageManager::Cargo => "Cargo.toml",
+            _ => "package.json",
+        };
+        let path = match &dep.package_path {
+            Some(package) if !package.is_empty() => format!("{package}/{manifest}"),
+            _ => manifest.to_string(),
+        };
+        by_manifest.entry(path).or_default().push(dep);
+    }
+
+    let mut diff = String::new();
+    for (manifest, deps) in by_manifest {
+        diff.push_str(&format!("--- a/{manifest}\n+++ b/{manifest}\n"));
+        for dep in deps {
+            let (old, new) = match dep.manager {
+                PackageManager::Cargo => (
+                    format!("{} = \"{}\"", dep.name, dep.current),
+                    format!("{} = \"{}\"", dep.name, dep.latest),
+                ),
+                _ => (
+                    format!("\"{}\": \"{}\"", dep.name, dep.current),
+                    format!("\"{}\": \"{}\"", dep.name, dep.latest),
+                ),
+            };
+            diff.push_str(&format!("-{old}\n+{new}\n"));
+        }
+    }
+    diff
+}
+
+/// A failed test command of [`run_tests`]
+#[derive(Debug, Clone)]
+pub struct TestFailure {
+    pub command: String,
+    /// End of the command's output
+    pub output: String,
+}
+
+/// Test commands for the code at `path`: `cargo test` for a Cargo manifest and the
+/// `test` script of a package.json
+fn test_commands(path: &Path) -> Vec<(&'static str, &'static [&'static str])> {
+    let mut commands: Vec<(&'static str, &'static [&'static str])> = Vec::new();
+    if path.join("Cargo.toml").exists() {
+        commands.push(("cargo", &["test"]));
+    }
+    let has_test_script = std::fs::read_to_string(path.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|manifest| manifest["scripts"]["test"].as_str().map(str::to_string))
+        // The placeholder `npm init` writes fails on purpose
+        .is_some_and(|script| !script.contains("no test specified"));
+    if has_test_script {
+        commands.push(("npm", &["test"]));
+    }
+    commands
+}
+
+/// Run the tests of the code at `path`, e.g. the worktree of a dependency update, and
+/// return the first command that failed. Code without tests passes.
+pub async fn run_tests(path: &Path) -> Result<Option<TestFailure>, DependencyUpdateError> {
+    for (program, args) in test_commands(path) {
+        let command = format!("{program} {}", args.join(" "));
+        let executable = resolve_executable_path(program).await.ok_or_else(|| {
+            DependencyUpdateError::CommandFailed {
+                command: command.clone(),
+                stderr: format!("{program} not found in PATH"),
+            }
+        })?;
+        let output = tokio::time::timeout(
+            TEST_COMMAND_TIMEOUT,
+            tokio::process::Command::new(executable)
+                .args(args)
+                .current_dir(path)
+                .kill_on_drop(true)
+                .output(),
+        )
+        .await
+        .map_err(|_| DependencyUpdateError::Timeout(command.clone()))??;
+
+        if !output.status.success() {
+            let mut combined = String::from_utf8_lossy(&output.stdout).to_string();
+            combined.push_str(&String::from_utf8_lossy(&output.stderr));
+            return Ok(Some(TestFailure {
+                command,
+                output: output_tail(&combined, TEST_OUTPUT_TAIL_BYTES).to_string(),
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// The last `max_bytes` of `output`, starting at a character boundary
+fn output_tail(output: &str, max_bytes: usize) -> &str {
+    let mut start = output.len().saturating_sub(max_bytes);
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    &output[start..]
+}
+
+async fn run_outdated(
+    repo_path: &Path,
+    program: &str,
+    args: &[&str],
+) -> Result<String, DependencyUpdateError> {
+    let command = format!("{program} {}", args.join(" "));
+    let executable = resolve_executable_path(program).await.ok_or_else(|| {
+        DependencyUpdateError::CommandFailed {
+            command: command.clone(),
+            stderr: format!("{program} not found in PATH"),
+        }
+    })?;
+
+    let output = tokio::time::timeout(
+        OUTDATED_COMMAND_TIMEOUT,
+        tokio::process::Command::new(executable)
+            .args(args)
+            .current_dir(repo_path)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| DependencyUpdateError::Timeout(command.clone()))??;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    // `npm outdated` exits with 1 when it finds outdated packages
+    if !output.status.success() && stdout.trim().is_empty() {
+        return Err(DependencyUpdateError::CommandFailed {
+            command,
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(stdout)
+}
+
+async fn cargo_outdated(
+    repo_path: &Path,
+    packages: &[WorkspacePackage],
+) -> Result<Vec<OutdatedDependency>, DependencyUpdateError> {
+    let stdout = run_outdated(
+        repo_path,
+        "cargo",
+        &[
+            "outdated",
+            "--workspace",
+            "--root-deps-only",
+            "--format",
+            "json",
+        ],
+    )
+    .await?;
+    parse_cargo_outdated(&stdout, packages)
+}
+
+async fn npm_outdated(
+    repo_path: &Path,
+    packages: &[WorkspacePackage],
+) -> Result<Vec<OutdatedDependency>, DependencyUpdateError> {
+    let stdout = run_outdated(repo_path, "npm", &["outdated", "--json"]).await?;
+    parse_npm_outdated(&stdout, packages)
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoOutdatedReport {
+    crate_name: String,
+    dependencies: Vec<CargoOutdatedDependency>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoOutdatedDependency {
+    name: String,
+    project: String,
+    latest: String,
+}
+
+/// `cargo outdated --format json` prints one JSON document per workspace member
+fn parse_cargo_outdated(
+    stdout: &str,
+    packages: &[WorkspacePackage],
+) -> Result<Vec<OutdatedDependency>, DependencyUpdateError> {
+    let mut deps = Vec::new();
+    for line in stdout.lines().filter(|l| l.trim_start().starts_with('{')) {
+        let report: CargoOutdatedReport = serde_json::from_str(line)?;
+        let package_path = packages
+            .iter()
+            .find(|p| p.manager == PackageManager::Cargo && p.name == report.crate_name)
+            .map(|p| p.path.clone());
+        deps.extend(
+            report
+                .dependencies
+                .into_iter()
+                .filter(|d| d.latest != "---" && d.latest != d.project)
+                .map(|d| OutdatedDependency {
+                    name: d.name,
+                    current: d.project,
+                    latest: d.latest,
+                    manager: PackageManager::Cargo,
+                    package_path: package_path.clone(),
+                }),
+        );
+    }
+    Ok(deps)
+}
+
+#[derive(Debug, Deserialize)]
+struct NpmOutdatedEntry {
+    current: Option<String>,
+    wanted: Option<String>,
+    latest: String,
+    dependent: Option<String>,
+}
+
+/// `npm outdated --json` maps package names to an entry, or to an array of
+/// entries when several workspaces depend on the same package
+fn parse_npm_outdated(
+    stdout: &str,
+    packages: &[WorkspacePackage],
+) -> Result<Vec<OutdatedDependency>, DependencyUpdateError> {
+    if stdout.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let parsed: BTreeMap<String, serde_json::Value> = serde_json::from_str(stdout)?;
+    let mut deps = Vec::new();
+    for (name, value) in parsed {
+        let entries: Vec<NpmOutdatedEntry> = if value.is_array() {
+            serde_json::from_value(value)?
+        } else {
+            vec![serde_json::from_value(value)?]
+        };
+        for entry in entries {
+            let Some(current) = entry.current.or(entry.wanted) else {
+                continue;
+            };
+            if current == entry.latest {
+                continue;
+            }
+            let package_path = entry.dependent.as_ref().and_then(|dependent| {
+                packages
+                    .iter()
+                    .find(|p| &p.name == dependent || p.path.ends_with(dependent.as_str()))
+                    .map(|p| p.path.clone())
+            });
+            deps.push(OutdatedDependency {
+                name: name.clone(),
+                current,
+                latest: entry.latest,
+                manager: PackageManager::Npm,
+                package_path,
+            });
+        }
+    }
+    Ok(deps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, path: &str, manager: PackageManager) -> WorkspacePackage {
+        WorkspacePackage {
+            name: name.to_string(),
+            path: path.to_string(),
+            manager,
+            scripts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_cargo_outdated_per_member() {
+        let stdout = r#"{"crate_name":"api","dependencies":[{"name":"serde","project":"1.0.100","compat":"1.0.200","latest":"1.0.200","kind":"Normal","platform":null},{"name":"gone","project":"0.1.0","compat":"---","latest":"---","kind":"Normal","platform":null}]}
+{"crate_name":"core","dependencies":[]}"#;
+        let packages = vec![package("api", "crates/api", PackageManager::Cargo)];
+
+        let deps = parse_cargo_outdated(stdout, &packages).unwrap();
+        assert_eq!(
+            deps,
+            vec![OutdatedDependency {
+                name: "serde".into(),
+                current: "1.0.100".into(),
+                latest: "1.0.200".into(),
+                manager: PackageManager::Cargo,
+                package_path: Some("crates/api".into()),
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_npm_outdated_single_and_workspace_entries() {
+        let stdout = r#"{
+  "react": {"current": "18.2.0", "wanted": "18.3.1", "latest": "19.0.0", "dependent": "web"},
+  "typescript": [
+    {"current": "5.0.0", "wanted": "5.0.0", "latest": "5.6.0", "dependent": "web"},
+    {"wanted": "5.0.0", "latest": "5.6.0", "dependent": "root"}
+  ]
+}"#;
+        let packages = vec![package("web", "apps/web", PackageManager::Pnpm)];
+
+        let deps = parse_npm_outdated(stdout, &packages).unwrap();
+        assert_eq!(deps.len(), 3);
+        assert_eq!(deps[0].name, "react");
+        assert_eq!(deps[0].package_path.as_deref(), Some("apps/web"));
+        assert_eq!(deps[2].current, "5.0.0");
+        assert_eq!(deps[2].package_path, None);
+    }
+
+    #[test]
+    fn recognizes_update_task_titles() {
+        let report = DependencyUpdateReport {
+            repo_id: Uuid::new_v4(),
+            repo_name: "web".into(),
+            package_path: Some("apps/web".into()),
+            package_name: Some("web".into()),
+            dependencies: Vec::new(),
+            manifest_diff: String::new(),
+            skipped: Vec::new(),
+        };
+        assert!(is_update_task(&task_title(&report)));
+        assert!(!is_update_task("Update the dependency docs"));
+    }
+
+    #[test]
+    fn keeps_the_end_of_test_output() {
+        assert_eq!(output_tail("short", 100), "short");
+        assert_eq!(output_tail("abcdef", 3), "def");
+        // Never splits a character
+        assert_eq!(output_tail("aé", 1), "");
+    }
+
+    #[test]
+    fn renders_manifest_diff_per_manifest() {
+        let deps = vec![
+            OutdatedDependency {
+                name: "serde".into(),
+                current: "1.0.100".into(),
+                latest: "1.0.200".into(),
+                manager: PackageManager::Cargo,
+                package_path: Some("crates/api".into()),
+            },
+            OutdatedDependency {
+                name: "react".into(),
+                current: "18.2.0".into(),
+                latest: "19.0.0".into(),
+                manager: PackageManager::Npm,
+                package_path: None,
+            },
+        ];
+        assert_eq!(
+            render_manifest_diff(&deps),
+            "--- a/crates/api/Cargo.toml\n+++ b/crates/api/Cargo.toml\n-serde = \"1.0.100\"\n+serde = \"1.0.200\"\n--- a/package.json\n+++ b/package.json\n-\"react\": \"18.2.0\"\n+\"react\": \"19.0.0\"\n"
+        );
+    }
+}
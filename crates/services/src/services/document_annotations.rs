@@ -0,0 +1,304 @@
+//! Anchors of document annotations. An annotation is left on a range of lines, but lines
+//! move when the document is edited, so it keeps the text of the range and a few lines
+//! around it. Finding that text again places the annotation on the range it was left
+//! on, even after lines were added above it or the range itself was touched up.
+
+use thiserror::Error;
+
+/// Lines kept before and after an annotated range to tell repeated text apart
+const CONTEXT_LINES: usize = 2;
+/// Longest range an annotation can be left on
+pub const MAX_RANGE_LINES: usize = 100;
+/// How similar a range has to be to an annotation's text to still be its range
+const MIN_SIMILARITY: f64 = 0.6;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AnnotationError {
+    #[error("Lines {0}-{1} are not in the document, which has {2} lines")]
+    InvalidRange(usize, usize, usize),
+    #[error("Annotations can span at most {MAX_RANGE_LINES} lines")]
+    TooLong,
+}
+
+/// Where an annotation is in a document, by its lines and by their text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anchor {
+    /// First line of the range, counted from 1
+    pub line_start: usize,
+    /// Last line of the range, inclusive
+    pub line_end: usize,
+    pub quote: String,
+    pub prefix: String,
+    pub suffix: String,
+}
+
+/// Anchor for lines `line_start` to `line_end` of `content`, counted from 1
+pub fn anchor(
+    content: &str,
+    line_start: usize,
+    line_end: usize,
+) -> Result<Anchor, AnnotationError> {
+    let lines: Vec<&str> = content.lines().collect();
+    if line_start == 0 || line_start > line_end || line_end > lines.len() {
+        return Err(AnnotationError::InvalidRange(
+            line_start,
+            line_end,
+            lines.len(),
+        ));
+    }
+    if line_end - line_start + 1 > MAX_RANGE_LINES {
+        return Err(AnnotationError::TooLong);
+    }
+    let start = line_start - 1;
+    Ok(Anchor {
+        line_start,
+        line_end,
+        quote: lines[start..line_end].join("\n"),
+        prefix: lines[start.saturating_sub(CONTEXT_LINES)..start].join("\n"),
+        suffix: lines[line_end..(line_end + CONTEXT_LINES).min(lines.len())].join("\n"),
+    })
+}
+
+/// Where `anchor` is in `content` now, anchored to the current text there. The text of
+/// the range is looked for first, preferring the occurrence with the same lines around
+/// it and then the one closest to where the annotation was. Failing that, a range whose
+/// surrounding lines are unchanged is taken, and last the range most similar to the
+/// text. `None` if the annotated text is gone.
+pub fn locate(content: &str, anchor: &Anchor) -> Option<Anchor> {
+    let lines: Vec<&str> = content.lines().map(str::trim_end).collect();
+    let quote: Vec<&str> = anchor.quote.split('\n').map(str::trim_end).collect();
+    let prefix = context_lines(&anchor.prefix);
+    let suffix = context_lines(&anchor.suffix);
+    let hint = anchor.line_start.saturating_sub(1);
+
+    let (start, end) = find_exact(&lines, &quote, &prefix, &suffix, hint)
+        .or_else(|| find_between_context(&lines, &prefix, &suffix, hint))
+        .or_else(|| find_similar(&lines, &quote, hint))?;
+    self::anchor(content, start + 1, end + 1).ok()
+}
+
+fn context_lines(context: &str) -> Vec<&str> {
+    if context.is_empty() {
+        return Vec::new();
+    }
+    context.split('\n').map(str::trim_end).collect()
+}
+
+/// Number of context lines that still surround the range `start..end`
+fn context_score(
+    lines: &[&str],
+    start: usize,
+    end: usize,
+    prefix: &[&str],
+    suffix: &[&str],
+) -> usize {
+    let before = prefix
+        .iter()
+        .rev()
+        .zip(lines[..start].iter().rev())
+        .take_while(|(expected, line)| expected == line)
+        .count();
+    let after = suffix
+        .iter()
+        .zip(lines[end..].iter())
+        .take_while(|(expected, line)| expected == line)
+        .count();
+    before + after
+}
+
+/// Range with exactly the quoted lines. Returns 0-based, inclusive line indices.
+fn find_exact(
+    lines: &[&str],
+    quote: &[&str],
+    prefix: &[&str],
+    suffix: &[&str],
+    hint: usize,
+) -> Option<(usize, usize)> {
+    if quote.len() > lines.len() {
+        return None;
+    }
+    (0..=lines.len() - quote.len())
+        .filter(|&start| lines[start..start + quote.len()] == *quote)
+        .max_by_key(|&start| {
+            let score = context_score(lines, start, start + quote.len(), prefix, suffix);
+            (score, std::cmp::Reverse(start.abs_diff(hint)))
+        })
+        .map(|start| (start, start + quote.len() - 1))
+}
+
+/// Range between the unchanged lines that were before and after the annotated range
+fn find_between_context(
+    lines: &[&str],
+    prefix: &[&str],
+    suffix: &[&str],
+    hint: usize,
+) -> Option<(usize, usize)> {
+    if prefix.is_empty() || suffix.is_empty() {
+        return None;
+    }
+    let starts =
+        (prefix.len()..=lines.len()).filter(|&start| lines[start - prefix.len()..start] == *prefix);
+    starts
+        .filter_map(|start| {
+            // The nearest following suffix, within the longest range an annotation has
+            let limit = (start + MAX_RANGE_LINES).min(lines.len().saturating_sub(suffix.len()));
+            (start + 1..=limit)
+                .find(|&end| lines[end..end + suffix.len()] == *suffix)
+                .map(|end| (start, end - 1))
+        })
+        .min_by_key(|&(start, _)| start.abs_diff(hint))
+}
+
+/// Range of the quote's length most similar to it, if similar enough
+fn find_similar(lines: &[&str], quote: &[&str], hint: usize) -> Option<(usize, usize)> {
+    if quote.len() > lines.len() {
+        return None;
+    }
+    let quote: Vec<Vec<char>> = quote.iter().map(|line| normalize(line)).collect();
+    let normalized: Vec<Vec<char>> = lines.iter().map(|line| normalize(line)).collect();
+    let mut best: Option<(f64, usize)> = None;
+    for start in 0..=lines.len() - quote.len() {
+        let score = quote
+            .iter()
+            .zip(&normalized[start..])
+            .map(|(expected, line)| similarity(expected, line))
+            .sum::<f64>()
+            / quote.len() as f64;
+        let better = match best {
+            None => true,
+            Some((best_score, best_start)) => {
+                score > best_score
+                    || (score == best_score && start.abs_diff(hint) < best_start.abs_diff(hint))
+            }
+        };
+        if better {
+            best = Some((score, start));
+        }
+    }
+    best.filter(|(score, _)| *score >= MIN_SIMILARITY)
+        .map(|(_, start)| (start, start + quote.len() - 1))
+}
+
+/// Characters of `line` with runs of whitespace collapsed
+fn normalize(line: &str) -> Vec<char> {
+    line.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .collect()
+}
+
+/// Share of the longer line covered by the start and end both lines have in common,
+/// which is high for a line with a word or two changed
+fn similarity(a: &[char], b: &[char]) -> f64 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    let common_start = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let common_end = a[common_start..]
+        .iter()
+        .rev()
+        .zip(b[common_start..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    (common_start + common_end) as f64 / longest as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = "# Design\n\
+        \n\
+        ## Goals\n\
+        Ship the importer by March.\n\
+        Keep the API stable.\n\
+        \n\
+        ## Risks\n\
+        The parser is slow on large files.\n\
+        \n\
+        ## Notes\n\
+        Keep the API stable.\n";
+
+    #[test]
+    fn anchors_ranges_with_their_context() {
+        let anchor = anchor(DOCUMENT, 4, 5).unwrap();
+        assert_eq!(
+            anchor.quote,
+            "Ship the importer by March.\nKeep the API stable."
+        );
+        assert_eq!(anchor.prefix, "\n## Goals");
+        assert_eq!(anchor.suffix, "\n## Risks");
+
+        let first = super::anchor(DOCUMENT, 1, 1).unwrap();
+        assert_eq!(first.prefix, "");
+        assert_eq!(
+            super::anchor(DOCUMENT, 0, 1),
+            Err(AnnotationError::InvalidRange(0, 1, 11))
+        );
+        assert_eq!(
+            super::anchor(DOCUMENT, 5, 4),
+            Err(AnnotationError::InvalidRange(5, 4, 11))
+        );
+        assert_eq!(
+            super::anchor(DOCUMENT, 11, 12),
+            Err(AnnotationError::InvalidRange(11, 12, 11))
+        );
+    }
+
+    #[test]
+    fn follows_lines_added_above() {
+        let anchor = anchor(DOCUMENT, 8, 8).unwrap();
+        let edited = DOCUMENT.replace("# Design\n", "# Design\n\nStatus: draft\nOwner: Sam\n");
+        let located = locate(&edited, &anchor).unwrap();
+        assert_eq!((located.line_start, located.line_end), (11, 11));
+        assert_eq!(located.quote, "The parser is slow on large files.");
+    }
+
+    #[test]
+    fn tells_repeated_text_apart_by_its_context() {
+        let goals = anchor(DOCUMENT, 5, 5).unwrap();
+        let notes = anchor(DOCUMENT, 11, 11).unwrap();
+        assert_eq!(goals.quote, notes.quote);
+
+        let edited = DOCUMENT.replace("## Goals\n", "## Goals\nNew goal.\n");
+        assert_eq!(locate(&edited, &goals).unwrap().line_start, 6);
+        assert_eq!(locate(&edited, &notes).unwrap().line_start, 12);
+    }
+
+    #[test]
+    fn follows_small_edits_of_the_range() {
+        let anchor = anchor(DOCUMENT, 8, 8).unwrap();
+        let edited = format!(
+            "Intro\n{}",
+            DOCUMENT
+                .replace(
+                    "The parser is slow on large files.",
+                    "The parser is very slow on large files."
+                )
+                .replace("## Risks\n", "## Risks and mitigations\n")
+        );
+        let located = locate(&edited, &anchor).unwrap();
+        assert_eq!((located.line_start, located.line_end), (9, 9));
+        assert_eq!(located.quote, "The parser is very slow on large files.");
+    }
+
+    #[test]
+    fn follows_rewritten_ranges_between_unchanged_lines() {
+        let anchor = anchor(DOCUMENT, 4, 5).unwrap();
+        let edited = DOCUMENT.replace(
+            "Ship the importer by March.\nKeep the API stable.\n",
+            "Deliver an importer for CSV and JSON files.\nNo breaking changes.\nDocument it.\n",
+        );
+        let located = locate(&edited, &anchor).unwrap();
+        assert_eq!((located.line_start, located.line_end), (4, 6));
+    }
+
+    #[test]
+    fn detaches_when_the_text_is_gone() {
+        let anchor = anchor(DOCUMENT, 8, 8).unwrap();
+        let edited = DOCUMENT.replace("## Risks\nThe parser is slow on large files.\n", "");
+        assert_eq!(locate(&edited, &anchor), None);
+    }
+}
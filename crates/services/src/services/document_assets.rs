@@ -0,0 +1,155 @@
+//! Images stored next to markdown documents, in an assets folder committed with them.
+//! Assets are named after their original file name and a hash of their content, so
+//! uploading the same image twice reuses the file and different images never collide.
+
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+/// Hex digits of the content hash in asset file names
+const HASH_LEN: usize = 8;
+/// Longest original file stem kept in asset file names
+const MAX_STEM_LEN: usize = 48;
+
+/// Extensions of images that can be stored as assets, with their MIME type
+const IMAGE_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("svg", "image/svg+xml"),
+    ("avif", "image/avif"),
+];
+
+/// MIME type of an image file name, if it is a supported image
+pub fn image_mime_type(file_name: &str) -> Option<&'static str> {
+    let extension = Path::new(file_name)
+        .extension()?
+        .to_str()?
+        .to_ascii_lowercase();
+    IMAGE_TYPES
+        .iter()
+        .find(|(supported, _)| *supported == extension)
+        .map(|(_, mime_type)| *mime_type)
+}
+
+/// Whether `folder` can hold assets: a relative path of plain names made of letters,
+/// digits, `-`, `_` and `.`, so links to it need no escaping
+pub fn is_valid_assets_folder(folder: &str) -> bool {
+    !folder.is_empty()
+        && folder.split('/').all(|part| {
+            !part.is_empty()
+                && part != "."
+                && part != ".."
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+}
+
+/// File name of an image uploaded as `original_name` with `data`, e.g.
+/// `architecture-diagram-1a2b3c4d.png`. `None` if it isn't a supported image.
+pub fn asset_file_name(original_name: &str, data: &[u8]) -> Option<String> {
+    image_mime_type(original_name)?;
+    let path = Path::new(original_name);
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+
+    let mut slug = String::new();
+    for c in stem.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.truncate(MAX_STEM_LEN);
+    let slug = slug.trim_end_matches('-');
+    let slug = if slug.is_empty() { "image" } else { slug };
+    let hash = format!("{:x}", Sha256::digest(data));
+    Some(format!("{}-{}.{}", slug, &hash[..HASH_LEN], extension))
+}
+
+/// Repository path of the asset `file_name` of the document at `document_path`: inside
+/// `folder` next to the document
+pub fn asset_path(document_path: &str, folder: &str, file_name: &str) -> String {
+    match document_path.rsplit_once('/') {
+        Some((document_dir, _)) => format!("{}/{}/{}", document_dir, folder, file_name),
+        None => format!("{}/{}", folder, file_name),
+    }
+}
+
+/// Markdown image of the asset `file_name` in `folder`, linked relative to the document
+pub fn markdown_image(folder: &str, file_name: &str, alt: &str) -> String {
+    let alt: String = alt
+        .chars()
+        .filter(|c| !matches!(c, '[' | ']' | '\n' | '\r'))
+        .collect();
+    format!("![{}]({}/{})", alt.trim(), folder, file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_images() {
+        assert_eq!(image_mime_type("diagram.PNG"), Some("image/png"));
+        assert_eq!(image_mime_type("photo.jpeg"), Some("image/jpeg"));
+        assert_eq!(image_mime_type("notes.md"), None);
+        assert_eq!(image_mime_type("png"), None);
+    }
+
+    #[test]
+    fn validates_assets_folders() {
+        assert!(is_valid_assets_folder("assets"));
+        assert!(is_valid_assets_folder("_media/images"));
+        assert!(!is_valid_assets_folder(""));
+        assert!(!is_valid_assets_folder("../assets"));
+        assert!(!is_valid_assets_folder("/assets"));
+        assert!(!is_valid_assets_folder("assets/"));
+        assert!(!is_valid_assets_folder("my assets"));
+    }
+
+    #[test]
+    fn names_assets_by_name_and_content() {
+        let name = asset_file_name("Architecture Diagram (v2).PNG", b"png").unwrap();
+        assert!(name.starts_with("architecture-diagram-v2-"));
+        assert!(name.ends_with(".png"));
+        assert_eq!(
+            name.len(),
+            "architecture-diagram-v2-".len() + HASH_LEN + ".png".len()
+        );
+        assert_eq!(
+            asset_file_name("Architecture Diagram (v2).PNG", b"png"),
+            Some(name.clone())
+        );
+        assert_ne!(
+            asset_file_name("Architecture Diagram (v2).PNG", b"other"),
+            Some(name)
+        );
+        assert!(
+            asset_file_name("日本.gif", b"gif")
+                .unwrap()
+                .starts_with("image-")
+        );
+        assert_eq!(asset_file_name("script.sh", b"#!/bin/sh"), None);
+    }
+
+    #[test]
+    fn places_assets_next_to_the_document() {
+        assert_eq!(
+            asset_path("docs/guides/setup.md", "assets", "a.png"),
+            "docs/guides/assets/a.png"
+        );
+        assert_eq!(asset_path("README.md", "assets", "a.png"), "assets/a.png");
+        assert_eq!(
+            markdown_image("assets", "a.png", " Setup [step 1]\n"),
+            "![Setup step 1](assets/a.png)"
+        );
+    }
+}
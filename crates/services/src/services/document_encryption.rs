@@ -0,0 +1,155 @@
+//! Encryption of documents in sensitive folders before they are written to the working
+//! tree, so they are only ever committed encrypted. An encrypted document is a header
+//! line naming the key it was encrypted with, followed by the base64 of the nonce and the
+//! AES-256-GCM ciphertext:
+//!
+//! ```text
+//! vibe-kanban-encrypted:v1:<key id>
+//! <base64>
+//! ```
+
+use aes_gcm::{
+    Aes256Gcm, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Start of the first line of every encrypted document
+const HEADER_PREFIX: &str = "vibe-kanban-encrypted:v1:";
+/// 96-bit nonce, as AES-GCM expects
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Error)]
+pub enum DocumentEncryptionError {
+    #[error("Document encryption key is invalid")]
+    InvalidKey,
+    #[error("Encrypted document is malformed")]
+    Malformed,
+    #[error("Encrypted document can't be decrypted with key {0}, or was altered")]
+    WrongKey(Uuid),
+    #[error("Decrypted document is not UTF-8 text")]
+    NotText,
+    #[error("Failed to encrypt document")]
+    EncryptionFailed,
+}
+
+/// A new random 256-bit key
+pub fn generate_key() -> Vec<u8> {
+    Aes256Gcm::generate_key(OsRng).to_vec()
+}
+
+fn cipher(key: &[u8]) -> Result<Aes256Gcm, DocumentEncryptionError> {
+    Aes256Gcm::new_from_slice(key).map_err(|_| DocumentEncryptionError::InvalidKey)
+}
+
+/// Whether `content` is an encrypted document
+pub fn is_encrypted(content: &str) -> bool {
+    content.starts_with(HEADER_PREFIX)
+}
+
+/// Key `content` was encrypted with, `None` if it isn't an encrypted document
+pub fn key_id(content: &str) -> Option<Uuid> {
+    let header = content.strip_prefix(HEADER_PREFIX)?.lines().next()?;
+    Uuid::parse_str(header.trim()).ok()
+}
+
+/// `plaintext` encrypted with the key `key_id`, as an encrypted document
+pub fn encrypt(
+    key_id: Uuid,
+    key: &[u8],
+    plaintext: &str,
+) -> Result<String, DocumentEncryptionError> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(key)?
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| DocumentEncryptionError::EncryptionFailed)?;
+    let mut combined = nonce.to_vec();
+    combined.extend_from_slice(&ciphertext);
+    Ok(format!(
+        "{HEADER_PREFIX}{key_id}\n{}\n",
+        STANDARD.encode(combined)
+    ))
+}
+
+/// Text of the encrypted document `content`, decrypted with `key`
+pub fn decrypt(key: &[u8], content: &str) -> Result<String, DocumentEncryptionError> {
+    let key_id = key_id(content).ok_or(DocumentEncryptionError::Malformed)?;
+    let body: String = content
+        .lines()
+        .skip(1)
+        .flat_map(|line| line.chars().filter(|c| !c.is_whitespace()))
+        .collect();
+    let combined = STANDARD
+        .decode(body)
+        .map_err(|_| DocumentEncryptionError::Malformed)?;
+    if combined.len() < NONCE_LEN {
+        return Err(DocumentEncryptionError::Malformed);
+    }
+    let (nonce, ciphertext) = combined.split_at(NONCE_LEN);
+    let plaintext = cipher(key)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| DocumentEncryptionError::WrongKey(key_id))?;
+    String::from_utf8(plaintext).map_err(|_| DocumentEncryptionError::NotText)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let key_id = Uuid::new_v4();
+        let key = generate_key();
+        let encrypted = encrypt(key_id, &key, "# Secrets\n\nhunter2").unwrap();
+
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("hunter2"));
+        assert_eq!(self::key_id(&encrypted), Some(key_id));
+        assert_eq!(decrypt(&key, &encrypted).unwrap(), "# Secrets\n\nhunter2");
+        // A fresh nonce each time, so equal documents can't be told apart
+        assert_ne!(
+            encrypt(key_id, &key, "# Secrets\n\nhunter2").unwrap(),
+            encrypted
+        );
+    }
+
+    #[test]
+    fn refuses_the_wrong_key_and_tampering() {
+        let key_id = Uuid::new_v4();
+        let encrypted = encrypt(key_id, &generate_key(), "text").unwrap();
+        assert!(matches!(
+            decrypt(&generate_key(), &encrypted),
+            Err(DocumentEncryptionError::WrongKey(id)) if id == key_id
+        ));
+
+        let key = generate_key();
+        let encrypted = encrypt(key_id, &key, "text").unwrap();
+        // Change one character of the ciphertext, well before any padding
+        let mut tampered = encrypted.into_bytes();
+        let index = tampered.iter().position(|&b| b == b'\n').unwrap() + 20;
+        tampered[index] = if tampered[index] == b'A' { b'B' } else { b'A' };
+        let tampered = String::from_utf8(tampered).unwrap();
+        assert!(matches!(
+            decrypt(&key, &tampered),
+            Err(DocumentEncryptionError::WrongKey(_))
+        ));
+
+        assert!(matches!(
+            encrypt(key_id, &[0; 16], "text"),
+            Err(DocumentEncryptionError::InvalidKey)
+        ));
+    }
+
+    #[test]
+    fn recognizes_plain_documents() {
+        assert!(!is_encrypted("# Title"));
+        assert_eq!(key_id("# Title"), None);
+        assert_eq!(key_id("vibe-kanban-encrypted:v1:not-a-uuid\nAAAA\n"), None);
+        assert!(matches!(
+            decrypt(&generate_key(), "# Title"),
+            Err(DocumentEncryptionError::Malformed)
+        ));
+    }
+}
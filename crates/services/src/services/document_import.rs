@@ -0,0 +1,168 @@
+//! Unpacking of uploaded zip archives of documents, so existing documentation can be
+//! imported at once instead of pasting it in one document at a time.
+
+use std::{
+    io::{Cursor, Read},
+    path::Component,
+};
+
+use thiserror::Error;
+use zip::ZipArchive;
+
+#[derive(Debug, Error)]
+pub enum DocumentImportError {
+    #[error("Not a valid zip archive: {0}")]
+    InvalidArchive(#[from] zip::result::ZipError),
+    #[error("Archive entry '{0}' has an unsafe path")]
+    UnsafePath(String),
+    #[error("Archive has more than {0} files")]
+    TooManyFiles(usize),
+    #[error("Archive is larger than {0} bytes once unpacked")]
+    TooLarge(u64),
+    #[error("Failed to read archive entry: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A file of an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveFile {
+    /// Path inside the archive, with `/` separators
+    pub path: String,
+    pub content: Vec<u8>,
+}
+
+/// Files of the zip archive `bytes`, without directories and hidden entries such as
+/// `__MACOSX/` or `.DS_Store`. Entries whose path would leave the folder they are
+/// unpacked into are rejected, as are archives with more than `max_files` files or
+/// `max_bytes` of content. Sizes are counted while inflating, so zip bombs stop early.
+pub fn unpack_zip(
+    bytes: &[u8],
+    max_files: usize,
+    max_bytes: u64,
+) -> Result<Vec<ArchiveFile>, DocumentImportError> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+    let mut files = Vec::new();
+    let mut remaining = max_bytes;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(path) = entry.enclosed_name() else {
+            return Err(DocumentImportError::UnsafePath(entry.name().to_string()));
+        };
+        let parts: Vec<String> = path
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        if parts.is_empty() {
+            return Err(DocumentImportError::UnsafePath(entry.name().to_string()));
+        }
+        if parts
+            .iter()
+            .any(|part| part.starts_with('.') || part == "__MACOSX")
+        {
+            continue;
+        }
+        if files.len() == max_files {
+            return Err(DocumentImportError::TooManyFiles(max_files));
+        }
+
+        // The size in the archive's directory can't be trusted
+        let mut content = Vec::new();
+        (&mut entry).take(remaining + 1).read_to_end(&mut content)?;
+        if content.len() as u64 > remaining {
+            return Err(DocumentImportError::TooLarge(max_bytes));
+        }
+        remaining -= content.len() as u64;
+        files.push(ArchiveFile {
+            path: parts.join("/"),
+            content,
+        });
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    use super::*;
+
+    fn zip_of(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            if name.ends_with('/') {
+                writer
+                    .add_directory(*name, SimpleFileOptions::default())
+                    .unwrap();
+            } else {
+                writer
+                    .start_file(*name, SimpleFileOptions::default())
+                    .unwrap();
+                writer.write_all(content.as_bytes()).unwrap();
+            }
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn unpacks_files_without_hidden_entries() {
+        let bytes = zip_of(&[
+            ("guides/", ""),
+            ("guides/setup.md", "# Setup\n"),
+            ("readme.md", "# Readme\n"),
+            ("__MACOSX/guides/._setup.md", "junk"),
+            ("guides/.DS_Store", "junk"),
+        ]);
+
+        let files = unpack_zip(&bytes, 10, 1024).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                ArchiveFile {
+                    path: "guides/setup.md".to_string(),
+                    content: b"# Setup\n".to_vec(),
+                },
+                ArchiveFile {
+                    path: "readme.md".to_string(),
+                    content: b"# Readme\n".to_vec(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_archive() {
+        let bytes = zip_of(&[("../outside.md", "# Outside\n")]);
+
+        assert!(matches!(
+            unpack_zip(&bytes, 10, 1024),
+            Err(DocumentImportError::UnsafePath(path)) if path == "../outside.md"
+        ));
+    }
+
+    #[test]
+    fn stops_at_the_limits() {
+        let bytes = zip_of(&[("a.md", "aaaa"), ("b.md", "bbbb")]);
+
+        assert!(matches!(
+            unpack_zip(&bytes, 1, 1024),
+            Err(DocumentImportError::TooManyFiles(1))
+        ));
+        assert!(matches!(
+            unpack_zip(&bytes, 10, 6),
+            Err(DocumentImportError::TooLarge(6))
+        ));
+        assert_eq!(unpack_zip(&bytes, 2, 8).unwrap().len(), 2);
+        assert!(matches!(
+            unpack_zip(b"not a zip", 10, 1024),
+            Err(DocumentImportError::InvalidArchive(_))
+        ));
+    }
+}
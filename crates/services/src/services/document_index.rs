@@ -0,0 +1,686 @@
+//! In-memory index of the documents in project repositories, kept current by a
+//! filesystem watcher so listing documents doesn't rescan the repository and edits made
+//! outside the app are broadcast as [`DocumentEvent`]s. Scans the index can't serve,
+//! such as document trees, are cached per repository until its `HEAD` moves or its
+//! watcher sees a change.
+
+use std::{
+    any::Any,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use db::models::project::{DocumentScanLimits, DocumentSymlinkPolicy};
+use futures::{Stream, StreamExt};
+use notify::RecommendedWatcher;
+use notify_debouncer_full::{Debouncer, RecommendedCache};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::{
+    document_links::{self, DocumentLink},
+    document_store,
+    filesystem_watcher::{self, FilesystemWatcherError},
+};
+
+#[derive(Debug, Clone, Copy, Serialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentEventKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl DocumentEventKind {
+    /// Name of the server-sent event carrying this kind of change
+    pub fn event_name(self) -> &'static str {
+        match self {
+            DocumentEventKind::Created => "document_created",
+            DocumentEventKind::Updated => "document_updated",
+            DocumentEventKind::Deleted => "document_deleted",
+        }
+    }
+}
+
+/// A document that appeared, changed or disappeared in a watched repository
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DocumentEvent {
+    pub kind: DocumentEventKind,
+    pub project_id: Uuid,
+    pub repository_id: Uuid,
+    /// Path relative to the repository root
+    pub relative_path: String,
+    /// `None` for deleted documents
+    pub size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexedDocument {
+    pub relative_path: String,
+    pub absolute_path: PathBuf,
+    pub size_bytes: u64,
+    /// Outgoing links, read while indexing; empty for documents that aren't markdown
+    pub links: Vec<DocumentLink>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexedDocuments {
+    pub documents: Vec<IndexedDocument>,
+    /// The initial scan hit a [`DocumentScanLimits`] limit, so documents may be missing
+    pub truncated: bool,
+}
+
+/// Tracks a document scan against its [`DocumentScanLimits`]
+#[derive(Debug)]
+pub struct ScanBudget {
+    limits: DocumentScanLimits,
+    deadline: Instant,
+    files: u32,
+    truncated: bool,
+}
+
+impl ScanBudget {
+    pub fn new(limits: DocumentScanLimits) -> Self {
+        Self {
+            limits,
+            deadline: Instant::now() + Duration::from_secs(limits.max_seconds.into()),
+            files: 0,
+            truncated: false,
+        }
+    }
+
+    /// Whether a folder `depth` levels below the repository root may be scanned
+    pub fn allows_depth(&mut self, depth: usize) -> bool {
+        let allowed = depth <= self.limits.max_depth as usize;
+        self.truncated |= !allowed;
+        allowed
+    }
+
+    /// Whether the scan may go on, i.e. neither the file nor the time limit was hit
+    pub fn has_room(&mut self) -> bool {
+        let exhausted = self.files >= self.limits.max_files || Instant::now() >= self.deadline;
+        self.truncated |= exhausted;
+        !exhausted
+    }
+
+    pub fn add_file(&mut self) {
+        self.files += 1;
+    }
+
+    /// Count the `count` documents of a cached scan against the file limit. Counts
+    /// nothing and returns false if they don't all fit, so the caller scans instead and
+    /// stops at the limit.
+    pub fn add_cached_files(&mut self, count: usize) -> bool {
+        let fits = (self.files as usize).saturating_add(count) <= self.limits.max_files as usize
+            && Instant::now() < self.deadline;
+        if fits {
+            self.files += count as u32;
+        }
+        fits
+    }
+
+    /// Whether any limit was hit
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+/// Which files of a repository count as documents
+#[derive(Debug, Clone)]
+pub struct DocumentFilter {
+    /// Directory names that are never descended into. Hidden entries are always skipped.
+    pub excluded_dirs: &'static [&'static str],
+    /// Lowercase file extensions, without the dot
+    pub extensions: Vec<String>,
+    pub limits: DocumentScanLimits,
+    pub symlinks: DocumentSymlinkPolicy,
+}
+
+impl DocumentFilter {
+    fn is_visible(&self, relative_path: &Path) -> bool {
+        relative_path.components().all(|component| {
+            let name = component.as_os_str().to_string_lossy();
+            !name.starts_with('.') && !self.excluded_dirs.contains(&name.as_ref())
+        })
+    }
+
+    fn is_document(&self, relative_path: &Path) -> bool {
+        self.is_visible(relative_path)
+            && relative_path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| self.extensions.contains(&extension.to_ascii_lowercase()))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    size_bytes: u64,
+    links: Vec<DocumentLink>,
+}
+
+impl IndexEntry {
+    fn read(path: &Path, relative_path: &str, size_bytes: u64) -> Self {
+        Self {
+            size_bytes,
+            links: document_links::read(path, relative_path),
+        }
+    }
+}
+
+type Documents = Arc<RwLock<BTreeMap<String, IndexEntry>>>;
+
+/// Where a watched repository stood when it was scanned: its `HEAD` commit and the
+/// number of change batches its watcher had seen. A cached scan is served as long as
+/// neither moved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanState {
+    head: Option<String>,
+    changes: u64,
+}
+
+/// Most scans cached per repository, e.g. for different path prefixes
+const MAX_CACHED_SCANS: usize = 32;
+
+struct CachedScan {
+    state: ScanState,
+    result: Arc<dyn Any + Send + Sync>,
+}
+
+/// Scans of one repository, by a key naming what was scanned and how
+#[derive(Default)]
+struct ScanCache {
+    scans: HashMap<String, CachedScan>,
+}
+
+impl ScanCache {
+    fn get<T: Clone + 'static>(&self, key: &str, state: &ScanState) -> Option<T> {
+        let scan = self.scans.get(key).filter(|scan| scan.state == *state)?;
+        scan.result.downcast_ref::<T>().cloned()
+    }
+
+    fn insert<T: Send + Sync + 'static>(&mut self, key: String, state: ScanState, result: T) {
+        // Scans made in an earlier state can't be served again
+        self.scans.retain(|_, scan| scan.state == state);
+        if self.scans.len() >= MAX_CACHED_SCANS {
+            self.scans.clear();
+        }
+        self.scans.insert(
+            key,
+            CachedScan {
+                state,
+                result: Arc::new(result),
+            },
+        );
+    }
+}
+
+struct WatchedRepo {
+    root: PathBuf,
+    documents: Documents,
+    truncated: bool,
+    /// Change batches the watcher has seen, marking cached scans stale
+    changes: Arc<AtomicU64>,
+    scans: Mutex<ScanCache>,
+    /// Dropping the debouncer stops the watcher and its event task
+    _watcher: Arc<Mutex<Debouncer<RecommendedWatcher, RecommendedCache>>>,
+}
+
+/// Document indexes of watched repositories, keyed by repository id
+pub struct DocumentIndex {
+    repos: DashMap<Uuid, WatchedRepo>,
+    events: broadcast::Sender<DocumentEvent>,
+}
+
+impl DocumentIndex {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(1024);
+        Self {
+            repos: DashMap::new(),
+            events,
+        }
+    }
+
+    /// Indexed documents of a repository, or `None` if it isn't watched yet
+    pub fn documents(&self, repo_id: Uuid) -> Option<IndexedDocuments> {
+        let repo = self.repos.get(&repo_id)?;
+        Some(snapshot(&repo))
+    }
+
+    /// Scan a repository and start watching it, returning the indexed documents. Does
+    /// nothing but return the index if the repository is already watched. Blocks while
+    /// scanning, so call it from a blocking task. The scan stops at `filter.limits`.
+    pub fn watch(
+        &self,
+        project_id: Uuid,
+        repo_id: Uuid,
+        root: &Path,
+        filter: DocumentFilter,
+    ) -> Result<IndexedDocuments, FilesystemWatcherError> {
+        if let Some(documents) = self.documents(repo_id) {
+            return Ok(documents);
+        }
+
+        let (watcher, mut changes, root) = filesystem_watcher::async_watcher(root.to_path_buf())?;
+        let (found, truncated) = scan(&root, &root, &filter);
+        if truncated {
+            tracing::warn!(
+                "Document scan of {:?} hit its limits, index is partial",
+                root
+            );
+        }
+        let documents: Documents = Arc::new(RwLock::new(found.into_iter().collect()));
+
+        let change_count = Arc::new(AtomicU64::new(0));
+        let task_root = root.clone();
+        let task_documents = documents.clone();
+        let task_change_count = change_count.clone();
+        let events = self.events.clone();
+        tokio::spawn(async move {
+            while let Some(result) = changes.next().await {
+                // Errors may stand for missed changes, so they mark cached scans stale too
+                task_change_count.fetch_add(1, Ordering::SeqCst);
+                let batch = match result {
+                    Ok(batch) => batch,
+                    Err(errors) => {
+                        tracing::warn!("Document watcher error in {:?}: {:?}", task_root, errors);
+                        continue;
+                    }
+                };
+                let paths: BTreeSet<PathBuf> = batch
+                    .into_iter()
+                    .flat_map(|event| event.event.paths)
+                    .collect();
+                for path in paths {
+                    for (kind, relative_path, size_bytes) in
+                        reconcile(&task_root, &task_documents, &filter, &path)
+                    {
+                        // Nobody listening is fine
+                        let _ = events.send(DocumentEvent {
+                            kind,
+                            project_id,
+                            repository_id: repo_id,
+                            relative_path,
+                            size_bytes,
+                        });
+                    }
+                }
+            }
+        });
+
+        let watched = self.repos.entry(repo_id).or_insert(WatchedRepo {
+            root,
+            documents,
+            truncated,
+            changes: change_count,
+            scans: Mutex::default(),
+            _watcher: watcher,
+        });
+        tracing::info!("Watching documents in {:?}", watched.root);
+        Ok(snapshot(&watched))
+    }
+
+    /// State of a watched repository whose `HEAD` is `head`, to look up and cache its
+    /// scans with. Take it before scanning, so changes made while scanning mark the scan
+    /// stale. `None` if the repository isn't watched, as nothing would tell when its
+    /// scans go stale.
+    pub fn scan_state(&self, repo_id: Uuid, head: Option<String>) -> Option<ScanState> {
+        let repo = self.repos.get(&repo_id)?;
+        Some(ScanState {
+            head,
+            changes: repo.changes.load(Ordering::SeqCst),
+        })
+    }
+
+    /// The scan of a repository cached under `key`, if it was made in `state`
+    pub fn cached_scan<T: Clone + 'static>(
+        &self,
+        repo_id: Uuid,
+        key: &str,
+        state: &ScanState,
+    ) -> Option<T> {
+        let repo = self.repos.get(&repo_id)?;
+        repo.scans.lock().unwrap().get(key, state)
+    }
+
+    /// Cache a complete scan of a repository made in `state` under `key`, replacing the
+    /// scans made in earlier states
+    pub fn cache_scan<T: Send + Sync + 'static>(
+        &self,
+        repo_id: Uuid,
+        key: String,
+        state: ScanState,
+        result: T,
+    ) {
+        if let Some(repo) = self.repos.get(&repo_id) {
+            repo.scans.lock().unwrap().insert(key, state, result);
+        }
+    }
+
+    /// Mark the cached scans of a repository stale right after the app wrote to it, so
+    /// the next listing doesn't depend on the watcher having seen the write yet
+    pub fn invalidate(&self, repo_id: Uuid) {
+        if let Some(repo) = self.repos.get(&repo_id) {
+            repo.changes.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Stop watching a repository and drop its cached scans, e.g. after it was removed
+    /// from its project or its scan limits changed
+    pub fn unwatch(&self, repo_id: Uuid) {
+        self.repos.remove(&repo_id);
+    }
+
+    /// Live document events of one project
+    pub fn subscribe(&self, project_id: Uuid) -> impl Stream<Item = DocumentEvent> + use<> {
+        BroadcastStream::new(self.events.subscribe()).filter_map(move |event| async move {
+            // Lagged receivers skip the missed events; clients rescan on reconnect anyway
+            event.ok().filter(|event| event.project_id == project_id)
+        })
+    }
+}
+
+impl Default for DocumentIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn snapshot(repo: &WatchedRepo) -> IndexedDocuments {
+    let documents = repo
+        .documents
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(relative_path, entry)| IndexedDocument {
+            relative_path: relative_path.clone(),
+            absolute_path: repo.root.join(relative_path),
+            size_bytes: entry.size_bytes,
+            links: entry.links.clone(),
+        })
+        .collect();
+    IndexedDocuments {
+        documents,
+        truncated: repo.truncated,
+    }
+}
+
+/// Documents below `dir`, with paths relative to `root`, and whether the scan stopped
+/// at one of `filter.limits`
+fn scan(root: &Path, dir: &Path, filter: &DocumentFilter) -> (Vec<(String, IndexEntry)>, bool) {
+    let mut budget = ScanBudget::new(filter.limits);
+    let mut found = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    'scan: while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if !budget.has_room() {
+                break 'scan;
+            }
+            let path = entry.path();
+            let Ok(relative_path) = path.strip_prefix(root) else {
+                continue;
+            };
+            if !filter.is_visible(relative_path) {
+                continue;
+            }
+            let Some(meta) = document_store::scan_metadata(filter.symlinks, root, &path) else {
+                continue;
+            };
+            if meta.is_dir() {
+                if budget.allows_depth(relative_path.components().count()) {
+                    pending.push(path);
+                }
+            } else if meta.is_file() && filter.is_document(relative_path) {
+                let size_bytes = meta.len();
+                let relative_path = relative_path.to_string_lossy().to_string();
+                let entry = IndexEntry::read(&path, &relative_path, size_bytes);
+                found.push((relative_path, entry));
+                budget.add_file();
+            }
+        }
+    }
+    (found, budget.truncated())
+}
+
+/// Bring the index in line with the filesystem state of `path` and return the changes
+fn reconcile(
+    root: &Path,
+    documents: &Documents,
+    filter: &DocumentFilter,
+    path: &Path,
+) -> Vec<(DocumentEventKind, String, Option<u64>)> {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return Vec::new();
+    };
+    if relative.as_os_str().is_empty() || !filter.is_visible(relative) {
+        return Vec::new();
+    }
+    let relative_path = relative.to_string_lossy().to_string();
+    let mut index = documents.write().unwrap();
+    let mut changes = Vec::new();
+
+    match document_store::scan_metadata(filter.symlinks, root, path) {
+        Some(meta) if meta.is_file() => {
+            if filter.is_document(relative) {
+                let entry = IndexEntry::read(path, &relative_path, meta.len());
+                let kind = match index.insert(relative_path.clone(), entry) {
+                    Some(_) => DocumentEventKind::Updated,
+                    None => DocumentEventKind::Created,
+                };
+                changes.push((kind, relative_path, Some(meta.len())));
+            }
+        }
+        // A directory appeared, e.g. moved in from elsewhere
+        Some(meta) if meta.is_dir() => {
+            for (relative_path, entry) in scan(root, path, filter).0 {
+                let size_bytes = entry.size_bytes;
+                if index.insert(relative_path.clone(), entry).is_none() {
+                    changes.push((DocumentEventKind::Created, relative_path, Some(size_bytes)));
+                }
+            }
+        }
+        Some(_) => {}
+        // Gone, or a symbolic link that is skipped: either a document or a directory
+        // holding documents
+        None => {
+            let prefix = format!("{relative_path}/");
+            let removed: Vec<String> = index
+                .keys()
+                .filter(|key| **key == relative_path || key.starts_with(&prefix))
+                .cloned()
+                .collect();
+            for key in removed {
+                index.remove(&key);
+                changes.push((DocumentEventKind::Deleted, key, None));
+            }
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter() -> DocumentFilter {
+        DocumentFilter {
+            excluded_dirs: &["node_modules"],
+            extensions: vec!["md".to_string()],
+            limits: DocumentScanLimits::default(),
+            symlinks: DocumentSymlinkPolicy::Skip,
+        }
+    }
+
+    #[test]
+    fn reconcile_tracks_created_updated_and_deleted_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let documents: Documents = Arc::default();
+        let doc = root.join("docs/guide.md");
+        std::fs::create_dir_all(doc.parent().unwrap()).unwrap();
+
+        std::fs::write(&doc, "# Guide").unwrap();
+        let changes = reconcile(&root, &documents, &filter(), &doc);
+        assert_eq!(changes[0].0, DocumentEventKind::Created);
+
+        std::fs::write(&doc, "# Guide, see [[Roadmap]]").unwrap();
+        let changes = reconcile(&root, &documents, &filter(), &doc);
+        assert_eq!(changes[0].0, DocumentEventKind::Updated);
+        assert_eq!(documents.read().unwrap()["docs/guide.md"].links.len(), 1);
+
+        std::fs::remove_dir_all(root.join("docs")).unwrap();
+        let changes = reconcile(&root, &documents, &filter(), &root.join("docs"));
+        assert_eq!(
+            changes,
+            vec![(
+                DocumentEventKind::Deleted,
+                "docs/guide.md".to_string(),
+                None
+            )]
+        );
+        assert!(documents.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn scan_skips_hidden_excluded_and_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "README.md",
+            ".hidden/a.md",
+            "node_modules/b.md",
+            "src/main.rs",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "x").unwrap();
+        }
+
+        let (found, truncated) = scan(root, root, &filter());
+        let found: Vec<String> = found.into_iter().map(|(path, _)| path).collect();
+        assert_eq!(found, vec!["README.md".to_string()]);
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn scan_stops_at_depth_and_file_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in ["a.md", "b.md", "deep/nested/c.md"] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "x").unwrap();
+        }
+
+        let mut shallow = filter();
+        shallow.limits.max_depth = 1;
+        let (found, truncated) = scan(root, root, &shallow);
+        assert_eq!(found.len(), 2);
+        assert!(truncated);
+
+        let mut few = filter();
+        few.limits.max_files = 1;
+        let (found, truncated) = scan(root, root, &few);
+        assert_eq!(found.len(), 1);
+        assert!(truncated);
+    }
+
+    #[test]
+    fn cached_scans_are_served_until_the_state_moves() {
+        let state = |head: &str, changes| ScanState {
+            head: Some(head.to_string()),
+            changes,
+        };
+        let mut cache = ScanCache::default();
+        cache.insert(
+            "tree".to_string(),
+            state("abc", 0),
+            vec!["a.md".to_string()],
+        );
+        cache.insert("list:docs".to_string(), state("abc", 0), 1_usize);
+
+        assert_eq!(
+            cache.get::<Vec<String>>("tree", &state("abc", 0)),
+            Some(vec!["a.md".to_string()])
+        );
+        assert_eq!(cache.get::<usize>("list:docs", &state("abc", 0)), Some(1));
+        assert_eq!(cache.get::<usize>("tree", &state("abc", 0)), None);
+        assert_eq!(cache.get::<Vec<String>>("tree", &state("abc", 1)), None);
+        assert_eq!(cache.get::<Vec<String>>("tree", &state("def", 0)), None);
+
+        cache.insert("tree".to_string(), state("def", 0), Vec::<String>::new());
+        assert_eq!(cache.get::<usize>("list:docs", &state("abc", 0)), None);
+        assert_eq!(cache.scans.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn writes_invalidate_cached_scans_before_the_watcher_sees_them() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "x").unwrap();
+        let index = DocumentIndex::new();
+        let repo_id = Uuid::new_v4();
+        index
+            .watch(Uuid::new_v4(), repo_id, dir.path(), filter())
+            .unwrap();
+
+        let state = index.scan_state(repo_id, Some("abc".to_string())).unwrap();
+        index.cache_scan(repo_id, "tree".to_string(), state, vec!["a.md".to_string()]);
+        let state = index.scan_state(repo_id, Some("abc".to_string())).unwrap();
+        assert_eq!(
+            index.cached_scan::<Vec<String>>(repo_id, "tree", &state),
+            Some(vec!["a.md".to_string()])
+        );
+
+        std::fs::write(dir.path().join("b.md"), "y").unwrap();
+        index.invalidate(repo_id);
+        let state = index.scan_state(repo_id, Some("abc".to_string())).unwrap();
+        assert_eq!(
+            index.cached_scan::<Vec<String>>(repo_id, "tree", &state),
+            None
+        );
+    }
+
+    #[test]
+    fn cached_files_count_only_when_they_fit() {
+        let mut budget = ScanBudget::new(DocumentScanLimits {
+            max_files: 3,
+            ..DocumentScanLimits::default()
+        });
+        assert!(budget.add_cached_files(2));
+        assert!(!budget.add_cached_files(2));
+        assert!(budget.add_cached_files(1));
+        assert!(!budget.has_room());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_ends_on_folder_links_that_loop() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("docs/a.md"), "x").unwrap();
+        std::os::unix::fs::symlink(root, root.join("docs/loop")).unwrap();
+
+        let (found, _) = scan(root, root, &filter());
+        assert_eq!(found.len(), 1);
+
+        let mut follow = filter();
+        follow.symlinks = DocumentSymlinkPolicy::FollowWithinRepo;
+        let (found, truncated) = scan(root, root, &follow);
+        let found: Vec<String> = found.into_iter().map(|(path, _)| path).collect();
+        assert_eq!(found, vec!["docs/a.md".to_string()]);
+        assert!(!truncated);
+    }
+}
@@ -0,0 +1,246 @@
+//! Links between markdown documents: relative markdown links and `[[wiki-links]]`.
+//!
+//! Links are read per document while the [`DocumentIndex`](super::document_index) scans
+//! a repository, and resolved against the repository's documents only when asked for,
+//! since a wiki-link names a document by its file name wherever that lives.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::Path,
+};
+
+use serde::Serialize;
+use ts_rs::TS;
+
+use super::markdown_html;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentLinkKind {
+    /// `[[Target]]`, `[[Target|label]]` or `[[Target#heading]]`
+    Wiki,
+    /// `[label](relative/path.md)`
+    Markdown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentLink {
+    pub kind: DocumentLinkKind,
+    /// Path relative to the repository root for markdown links, the name as written for
+    /// wiki-links
+    pub target: String,
+}
+
+/// A link from one document of a repository to another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedLink {
+    pub source: String,
+    /// Path of the linked document, or the name as written for unresolved wiki-links
+    pub target: String,
+    pub kind: DocumentLinkKind,
+}
+
+pub fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("md") || extension.eq_ignore_ascii_case("markdown")
+        })
+}
+
+/// Outgoing links of markdown `document` at `document_path`, wiki-links first. Markdown
+/// links to anything, documents or not, are kept; resolving tells them apart.
+pub fn parse(document: &str, document_path: &str) -> Vec<DocumentLink> {
+    let wiki = markdown_html::wiki_links(document)
+        .into_iter()
+        .map(|target| DocumentLink {
+            kind: DocumentLinkKind::Wiki,
+            target,
+        });
+    let markdown = markdown_html::relative_links(document, document_path)
+        .into_iter()
+        .map(|target| DocumentLink {
+            kind: DocumentLinkKind::Markdown,
+            target,
+        });
+    wiki.chain(markdown).collect()
+}
+
+/// Links of the document at `absolute_path`; none for files that aren't markdown or
+/// can't be read
+pub fn read(absolute_path: &Path, relative_path: &str) -> Vec<DocumentLink> {
+    if !is_markdown(absolute_path) {
+        return Vec::new();
+    }
+    match std::fs::read_to_string(absolute_path) {
+        Ok(content) => parse(&content, relative_path),
+        Err(e) => {
+            tracing::debug!("Failed to read links of {:?}: {}", absolute_path, e);
+            Vec::new()
+        }
+    }
+}
+
+fn without_extension(path: &str) -> &str {
+    match path.rsplit_once('.') {
+        Some((stem, _)) if is_markdown(Path::new(path)) => stem,
+        _ => path,
+    }
+}
+
+fn folder(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(folder, _)| folder)
+}
+
+/// Document of `documents` that `link` in `source` points to. A wiki-link names a
+/// document by its path from the repository root or by the end of that path, with or
+/// without the markdown extension and ignoring case; among several matches one in the
+/// folder of `source` wins, then the one closest to the root.
+pub fn resolve<'a>(
+    link: &DocumentLink,
+    source: &str,
+    documents: &'a BTreeSet<String>,
+) -> Option<&'a str> {
+    if link.kind == DocumentLinkKind::Markdown {
+        return documents.get(&link.target).map(String::as_str);
+    }
+    let name = link.target.trim().trim_start_matches('/').to_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+    let suffix = format!("/{name}");
+    documents
+        .iter()
+        .filter(|document| {
+            let document = document.to_lowercase();
+            [document.as_str(), without_extension(&document)]
+                .iter()
+                .any(|path| *path == name || path.ends_with(&suffix))
+        })
+        .min_by_key(|document| {
+            (
+                folder(document) != folder(source),
+                document.matches('/').count(),
+            )
+        })
+        .map(String::as_str)
+}
+
+/// Resolve the links of each `(source, links)` against `documents`, the document paths
+/// of one repository. Returns the links between documents without duplicates or
+/// self-links, and the wiki-links that name no document. Markdown links that lead
+/// elsewhere point at code or assets and are dropped.
+pub fn resolve_all<'a>(
+    documents: &BTreeSet<String>,
+    links: impl IntoIterator<Item = (&'a str, &'a [DocumentLink])>,
+) -> (Vec<ResolvedLink>, Vec<ResolvedLink>) {
+    let mut resolved = Vec::new();
+    let mut unresolved = Vec::new();
+    let mut seen = HashSet::new();
+    for (source, links) in links {
+        for link in links {
+            match resolve(link, source, documents) {
+                Some(target) if target != source => {
+                    if seen.insert((source, target, link.kind)) {
+                        resolved.push(ResolvedLink {
+                            source: source.to_string(),
+                            target: target.to_string(),
+                            kind: link.kind,
+                        });
+                    }
+                }
+                Some(_) => {}
+                None if link.kind == DocumentLinkKind::Wiki => unresolved.push(ResolvedLink {
+                    source: source.to_string(),
+                    target: link.target.clone(),
+                    kind: link.kind,
+                }),
+                None => {}
+            }
+        }
+    }
+    (resolved, unresolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wiki(target: &str) -> DocumentLink {
+        DocumentLink {
+            kind: DocumentLinkKind::Wiki,
+            target: target.to_string(),
+        }
+    }
+
+    fn documents() -> BTreeSet<String> {
+        [
+            "README.md",
+            "docs/roadmap.md",
+            "docs/design/roadmap.md",
+            "docs/design/payments.markdown",
+            "notes/Glossary.md",
+        ]
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+    }
+
+    #[test]
+    fn resolves_wiki_links_by_path_or_name() {
+        let documents = documents();
+        let find = |target: &str, source: &str| resolve(&wiki(target), source, &documents);
+
+        assert_eq!(find("glossary", "README.md"), Some("notes/Glossary.md"));
+        assert_eq!(
+            find("Payments", "README.md"),
+            Some("docs/design/payments.markdown")
+        );
+        assert_eq!(find("Roadmap", "README.md"), Some("docs/roadmap.md"));
+        assert_eq!(
+            find("Roadmap", "docs/design/payments.markdown"),
+            Some("docs/design/roadmap.md")
+        );
+        assert_eq!(
+            find("design/roadmap.md", "README.md"),
+            Some("docs/design/roadmap.md")
+        );
+        assert_eq!(find("readme", "docs/roadmap.md"), Some("README.md"));
+        assert_eq!(find("map", "README.md"), None);
+    }
+
+    #[test]
+    fn resolves_links_between_documents() {
+        let documents = documents();
+        let readme = parse(
+            "[[Roadmap]] [[Missing]] [[README]]\n\
+             [roadmap](docs/roadmap.md) [code](src/main.rs) [[docs/roadmap]]\n",
+            "README.md",
+        );
+        let (resolved, unresolved) = resolve_all(&documents, [("README.md", readme.as_slice())]);
+
+        assert_eq!(
+            resolved,
+            vec![
+                ResolvedLink {
+                    source: "README.md".to_string(),
+                    target: "docs/roadmap.md".to_string(),
+                    kind: DocumentLinkKind::Wiki,
+                },
+                ResolvedLink {
+                    source: "README.md".to_string(),
+                    target: "docs/roadmap.md".to_string(),
+                    kind: DocumentLinkKind::Markdown,
+                },
+            ]
+        );
+        assert_eq!(
+            unresolved,
+            vec![ResolvedLink {
+                source: "README.md".to_string(),
+                target: "Missing".to_string(),
+                kind: DocumentLinkKind::Wiki,
+            }]
+        );
+    }
+}
@@ -0,0 +1,492 @@
+//! Where a repository's documents are read from. Documents normally live in the working
+//! tree, but a project's repository may also be a bare clone, e.g. on a headless server,
+//! whose documents are served from the blobs of the docs branch without a checkout.
+//! Routes read through [`DocumentStore`], so further backends only need to be added to
+//! [`open`]. Symbolic links in working trees are treated as the project's
+//! [`DocumentSymlinkPolicy`] says, see [`scan_metadata`] and [`check_symlinks`].
+
+use std::{
+    fs::Metadata,
+    path::{Path, PathBuf},
+};
+
+use db::models::project::DocumentSymlinkPolicy;
+use git2::{ErrorCode, ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use thiserror::Error;
+
+/// File mode git records for symbolic links
+const SYMLINK_MODE: i32 = 0o120000;
+
+#[derive(Debug, Error)]
+pub enum DocumentStoreError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Git(#[from] git2::Error),
+    #[error("'{0}' is outside the repository")]
+    OutsideRepository(String),
+    #[error("'{0}' goes through a symbolic link, which this project's symlink policy skips")]
+    Symlink(String),
+}
+
+/// A file of a document store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredFile {
+    /// Path relative to the repository, with `/` separators
+    pub relative_path: String,
+    pub size_bytes: u64,
+}
+
+/// Read access to the files of one repository
+pub trait DocumentStore: Send + Sync {
+    /// Directory the documents are checked out in; `None` if the store serves them
+    /// without a checkout, which makes them read-only
+    fn working_tree(&self) -> Option<&Path>;
+
+    /// Files below `folder`, or all files if it is empty, in no particular order. Hidden
+    /// files and folders and folders named in `excluded_dirs` are skipped.
+    fn list(
+        &self,
+        folder: &str,
+        excluded_dirs: &[&str],
+    ) -> Result<Vec<StoredFile>, DocumentStoreError>;
+
+    /// Size of the file at `relative_path` in bytes, `None` if there is none. Cheaper
+    /// than reading it, so callers can refuse files too large to read.
+    fn size(&self, relative_path: &str) -> Result<Option<u64>, DocumentStoreError>;
+
+    /// Contents of the file at `relative_path`, `None` if there is none
+    fn read(&self, relative_path: &str) -> Result<Option<Vec<u8>>, DocumentStoreError>;
+}
+
+/// Metadata of `path`, an entry found while scanning the repository at `root`, or `None`
+/// if it is gone or `symlinks` skips it. Links are followed to their target only if
+/// the policy allows it, the target is inside the repository and, for folders, it isn't
+/// one of the folders the scan is already in, which would be scanned forever.
+pub fn scan_metadata(
+    symlinks: DocumentSymlinkPolicy,
+    root: &Path,
+    path: &Path,
+) -> Option<Metadata> {
+    let meta = std::fs::symlink_metadata(path).ok()?;
+    if !meta.file_type().is_symlink() {
+        return Some(meta);
+    }
+    match symlinks {
+        DocumentSymlinkPolicy::Skip => None,
+        DocumentSymlinkPolicy::FollowWithinRepo => {
+            let target = path.canonicalize().ok()?;
+            if !target.starts_with(root.canonicalize().ok()?) {
+                return None;
+            }
+            let meta = std::fs::metadata(&target).ok()?;
+            let loops = meta.is_dir()
+                && path
+                    .ancestors()
+                    .skip(1)
+                    .take_while(|ancestor| ancestor.starts_with(root))
+                    .any(|ancestor| ancestor.canonicalize().is_ok_and(|dir| dir == target));
+            (!loops).then_some(meta)
+        }
+    }
+}
+
+/// Check that `relative_path` of the repository at `root` may be read or saved under
+/// `symlinks`: when links are skipped none of its parts may be one, and when they are
+/// followed it has to resolve inside the repository. Parts that don't exist yet, e.g.
+/// of a new document, are fine.
+pub fn check_symlinks(
+    symlinks: DocumentSymlinkPolicy,
+    root: &Path,
+    relative_path: &str,
+) -> Result<(), DocumentStoreError> {
+    match symlinks {
+        DocumentSymlinkPolicy::Skip => {
+            let mut path = root.to_path_buf();
+            for component in Path::new(relative_path).components() {
+                path.push(component);
+                match std::fs::symlink_metadata(&path) {
+                    Ok(meta) if meta.file_type().is_symlink() => {
+                        return Err(DocumentStoreError::Symlink(relative_path.to_string()));
+                    }
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+            Ok(())
+        }
+        DocumentSymlinkPolicy::FollowWithinRepo => {
+            let path = root.join(relative_path);
+            let Some(existing) = path.ancestors().find(|ancestor| ancestor.exists()) else {
+                return Ok(());
+            };
+            if !existing.canonicalize()?.starts_with(root.canonicalize()?) {
+                return Err(DocumentStoreError::OutsideRepository(
+                    relative_path.to_string(),
+                ));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// The store of the repository at `repo_path`: its working tree, with symbolic links
+/// treated as `symlinks` says, or `revision` of a bare clone, whose links are never
+/// followed
+pub fn open(
+    repo_path: &Path,
+    revision: &str,
+    symlinks: DocumentSymlinkPolicy,
+) -> Box<dyn DocumentStore> {
+    if is_bare_repository(repo_path) {
+        Box::new(BareRepoStore {
+            repo_path: repo_path.to_path_buf(),
+            revision: revision.to_string(),
+        })
+    } else {
+        Box::new(WorkingTreeStore {
+            root: repo_path.to_path_buf(),
+            symlinks,
+        })
+    }
+}
+
+/// Whether `repo_path` is a repository without a working tree
+pub fn is_bare_repository(repo_path: &Path) -> bool {
+    Repository::open(repo_path).is_ok_and(|repo| repo.is_bare())
+}
+
+/// Documents checked out in a working tree
+pub struct WorkingTreeStore {
+    root: PathBuf,
+    symlinks: DocumentSymlinkPolicy,
+}
+
+impl WorkingTreeStore {
+    /// Path of the file at `relative_path`, `None` if there is none
+    fn file_path(&self, relative_path: &str) -> Result<Option<PathBuf>, DocumentStoreError> {
+        check_symlinks(self.symlinks, &self.root, relative_path)?;
+        let path = self.root.join(relative_path);
+        let canonical = match path.canonicalize() {
+            Ok(canonical) => canonical,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        // Symlinks may point out of the repository
+        if !canonical.starts_with(self.root.canonicalize()?) {
+            return Err(DocumentStoreError::OutsideRepository(
+                relative_path.to_string(),
+            ));
+        }
+        Ok(canonical.is_file().then_some(canonical))
+    }
+
+    fn list_folder(
+        &self,
+        folder: &Path,
+        excluded_dirs: &[&str],
+        files: &mut Vec<StoredFile>,
+    ) -> Result<(), DocumentStoreError> {
+        for entry in std::fs::read_dir(folder)?.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            let Some(meta) = scan_metadata(self.symlinks, &self.root, &path) else {
+                continue;
+            };
+            if meta.is_dir() {
+                if !excluded_dirs.contains(&name.as_str()) {
+                    self.list_folder(&path, excluded_dirs, files)?;
+                }
+            } else if meta.is_file()
+                && let Ok(relative_path) = path.strip_prefix(&self.root)
+            {
+                files.push(StoredFile {
+                    relative_path: relative_path.to_string_lossy().replace('\\', "/"),
+                    size_bytes: meta.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DocumentStore for WorkingTreeStore {
+    fn working_tree(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+
+    fn list(
+        &self,
+        folder: &str,
+        excluded_dirs: &[&str],
+    ) -> Result<Vec<StoredFile>, DocumentStoreError> {
+        let mut files = Vec::new();
+        let folder = self.root.join(folder);
+        if folder.is_dir() {
+            self.list_folder(&folder, excluded_dirs, &mut files)?;
+        }
+        Ok(files)
+    }
+
+    fn size(&self, relative_path: &str) -> Result<Option<u64>, DocumentStoreError> {
+        match self.file_path(relative_path)? {
+            Some(path) => Ok(Some(std::fs::metadata(path)?.len())),
+            None => Ok(None),
+        }
+    }
+
+    fn read(&self, relative_path: &str) -> Result<Option<Vec<u8>>, DocumentStoreError> {
+        match self.file_path(relative_path)? {
+            Some(path) => Ok(Some(std::fs::read(path)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Documents of one revision of a bare clone, read straight from its blobs
+pub struct BareRepoStore {
+    repo_path: PathBuf,
+    revision: String,
+}
+
+impl BareRepoStore {
+    /// Blob of the file at `relative_path`, `None` if there is none
+    fn blob_id(
+        &self,
+        repo: &Repository,
+        relative_path: &str,
+    ) -> Result<Option<Oid>, DocumentStoreError> {
+        let tree = repo.revparse_single(&self.revision)?.peel_to_tree()?;
+        let entry = match tree.get_path(Path::new(relative_path.trim_matches('/'))) {
+            Ok(entry) => entry,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let is_file = entry.kind() == Some(ObjectType::Blob) && entry.filemode() != SYMLINK_MODE;
+        Ok(is_file.then_some(entry.id()))
+    }
+}
+
+impl DocumentStore for BareRepoStore {
+    fn working_tree(&self) -> Option<&Path> {
+        None
+    }
+
+    fn list(
+        &self,
+        folder: &str,
+        excluded_dirs: &[&str],
+    ) -> Result<Vec<StoredFile>, DocumentStoreError> {
+        let repo = Repository::open_bare(&self.repo_path)?;
+        let mut tree = repo.revparse_single(&self.revision)?.peel_to_tree()?;
+        let folder = folder.trim_matches('/');
+        if !folder.is_empty() {
+            match tree.get_path(Path::new(folder)) {
+                Ok(entry) if entry.kind() == Some(ObjectType::Tree) => {
+                    tree = entry.to_object(&repo)?.peel_to_tree()?;
+                }
+                Ok(_) => return Ok(Vec::new()),
+                Err(e) if e.code() == ErrorCode::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let odb = repo.odb()?;
+        let mut files = Vec::new();
+        tree.walk(TreeWalkMode::PreOrder, |parent, entry| {
+            let Some(name) = entry.name() else {
+                return TreeWalkResult::Skip;
+            };
+            if name.starts_with('.') {
+                return TreeWalkResult::Skip;
+            }
+            match entry.kind() {
+                Some(ObjectType::Tree) if excluded_dirs.contains(&name) => TreeWalkResult::Skip,
+                Some(ObjectType::Blob) if entry.filemode() != SYMLINK_MODE => {
+                    let size_bytes = odb
+                        .read_header(entry.id())
+                        .map(|(size, _)| size as u64)
+                        .unwrap_or(0);
+                    let relative_path = if folder.is_empty() {
+                        format!("{parent}{name}")
+                    } else {
+                        format!("{folder}/{parent}{name}")
+                    };
+                    files.push(StoredFile {
+                        relative_path,
+                        size_bytes,
+                    });
+                    TreeWalkResult::Ok
+                }
+                _ => TreeWalkResult::Ok,
+            }
+        })?;
+        Ok(files)
+    }
+
+    fn size(&self, relative_path: &str) -> Result<Option<u64>, DocumentStoreError> {
+        let repo = Repository::open_bare(&self.repo_path)?;
+        let Some(id) = self.blob_id(&repo, relative_path)? else {
+            return Ok(None);
+        };
+        let (size, _) = repo.odb()?.read_header(id)?;
+        Ok(Some(size as u64))
+    }
+
+    fn read(&self, relative_path: &str) -> Result<Option<Vec<u8>>, DocumentStoreError> {
+        let repo = Repository::open_bare(&self.repo_path)?;
+        let Some(id) = self.blob_id(&repo, relative_path)? else {
+            return Ok(None);
+        };
+        Ok(Some(repo.find_blob(id)?.content().to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use git2::Signature;
+
+    use super::*;
+
+    /// A bare clone whose `main` holds `files`
+    fn bare_clone(dir: &Path, files: &[(&str, &str)]) -> PathBuf {
+        let work = dir.join("work");
+        let repo = Repository::init(&work).unwrap();
+        for (path, content) in files {
+            let path = work.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(
+            Some("refs/heads/main"),
+            &signature,
+            &signature,
+            "init",
+            &tree,
+            &[],
+        )
+        .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+
+        let bare = dir.join("bare.git");
+        git2::build::RepoBuilder::new()
+            .bare(true)
+            .branch("main")
+            .clone(work.to_str().unwrap(), &bare)
+            .unwrap();
+        bare
+    }
+
+    fn sorted_paths(files: Vec<StoredFile>) -> Vec<String> {
+        let mut paths: Vec<String> = files.into_iter().map(|file| file.relative_path).collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn serves_bare_clones_from_blobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let bare = bare_clone(
+            dir.path(),
+            &[
+                ("README.md", "# Readme"),
+                ("docs/guide.md", "guide"),
+                ("docs/.hidden.md", "hidden"),
+                ("node_modules/pkg/README.md", "dependency"),
+            ],
+        );
+        let store = open(&bare, "main", DocumentSymlinkPolicy::Skip);
+        assert!(store.working_tree().is_none());
+
+        let files = store.list("", &["node_modules"]).unwrap();
+        assert_eq!(sorted_paths(files), ["README.md", "docs/guide.md"]);
+        let files = store.list("docs", &[]).unwrap();
+        assert_eq!(
+            files,
+            [StoredFile {
+                relative_path: "docs/guide.md".to_string(),
+                size_bytes: 5,
+            }]
+        );
+        assert!(store.list("missing", &[]).unwrap().is_empty());
+
+        assert_eq!(
+            store.read("docs/guide.md").unwrap().as_deref(),
+            Some(&b"guide"[..])
+        );
+        assert_eq!(store.read("docs/missing.md").unwrap(), None);
+        assert_eq!(store.read("docs").unwrap(), None);
+        assert_eq!(store.size("docs/guide.md").unwrap(), Some(5));
+        assert_eq!(store.size("docs").unwrap(), None);
+    }
+
+    #[test]
+    fn serves_working_trees_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        Repository::init(dir.path()).unwrap();
+        fs::create_dir_all(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/a.md"), "a").unwrap();
+        fs::write(dir.path().join(".hidden.md"), "hidden").unwrap();
+
+        let store = open(dir.path(), "main", DocumentSymlinkPolicy::Skip);
+        assert_eq!(store.working_tree(), Some(dir.path()));
+        assert_eq!(sorted_paths(store.list("", &[]).unwrap()), ["docs/a.md"]);
+        assert_eq!(store.read("docs/a.md").unwrap().as_deref(), Some(&b"a"[..]));
+        assert_eq!(store.read("docs/b.md").unwrap(), None);
+        assert_eq!(store.size("docs/a.md").unwrap(), Some(1));
+        assert_eq!(store.size("docs").unwrap(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_skipped_unless_followed_within_the_repository() {
+        use std::os::unix::fs::symlink;
+
+        let outside = tempfile::tempdir().unwrap();
+        fs::write(outside.path().join("secret.md"), "secret").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        Repository::init(dir.path()).unwrap();
+        fs::create_dir_all(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/a.md"), "a").unwrap();
+        symlink(dir.path().join("docs/a.md"), dir.path().join("alias.md")).unwrap();
+        symlink(dir.path().join("docs"), dir.path().join("linked")).unwrap();
+        symlink(dir.path(), dir.path().join("docs/loop")).unwrap();
+        symlink(outside.path(), dir.path().join("outside")).unwrap();
+
+        let store = open(dir.path(), "main", DocumentSymlinkPolicy::Skip);
+        assert_eq!(sorted_paths(store.list("", &[]).unwrap()), ["docs/a.md"]);
+        assert!(matches!(
+            store.read("alias.md"),
+            Err(DocumentStoreError::Symlink(_))
+        ));
+        assert!(matches!(
+            store.read("linked/a.md"),
+            Err(DocumentStoreError::Symlink(_))
+        ));
+
+        let store = open(dir.path(), "main", DocumentSymlinkPolicy::FollowWithinRepo);
+        assert_eq!(
+            sorted_paths(store.list("", &[]).unwrap()),
+            ["alias.md", "docs/a.md", "linked/a.md"]
+        );
+        assert_eq!(
+            store.read("linked/a.md").unwrap().as_deref(),
+            Some(&b"a"[..])
+        );
+        assert!(matches!(
+            store.read("outside/secret.md"),
+            Err(DocumentStoreError::OutsideRepository(_))
+        ));
+    }
+}
@@ -0,0 +1,199 @@
+//! Document templates: skeletons such as RFCs, ADRs or design docs, kept as files in a
+//! folder of a project's primary repository. A document created from a template gets its
+//! content with `{{name}}` placeholders replaced by variables; `{{title}}`, `{{date}}` and
+//! `{{author}}` always have a value. Other placeholders without a value are left as they
+//! are, so braces in templates need no escaping.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// Variables every document created from a template has a value for
+pub const BUILTIN_VARIABLES: [&str; 3] = ["title", "date", "author"];
+/// Most templates listed from a folder
+const MAX_TEMPLATES: usize = 500;
+
+/// `template` with each `{{name}}` (spaces inside the braces allowed) replaced by the
+/// value of `name` in `variables`
+pub fn render(template: &str, variables: &HashMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        match variables.get(after[..end].trim()) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Names of the placeholders in `template` that aren't built in, in order of first use,
+/// so they can be asked for before creating a document
+pub fn custom_placeholders(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let name = after[..end].trim();
+        let custom = !name.is_empty()
+            && !BUILTIN_VARIABLES.contains(&name)
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        if custom && !names.iter().any(|known| known == name) {
+            names.push(name.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// Title of a document at `path` that isn't given one: its file name without the
+/// extension, with `-` and `_` as spaces and the first letter in upper case
+pub fn title_from_path(path: &str) -> String {
+    let stem = Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let words = stem.replace(['-', '_'], " ");
+    let mut chars = words.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Whether `id` names a template: a relative path inside the templates folder without
+/// hidden parts
+pub fn is_valid_template_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.contains('\\')
+        && id
+            .split('/')
+            .all(|part| !part.is_empty() && !part.starts_with('.'))
+}
+
+/// Ids of the files in the templates folder `folder`, sorted: their paths inside it with
+/// `/` separators. Hidden files and folders are skipped; a missing folder has none.
+pub fn list_template_ids(folder: &Path) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut pending: Vec<PathBuf> = vec![folder.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file()
+                && let Ok(relative) = path.strip_prefix(folder)
+            {
+                let parts: Vec<String> = relative
+                    .components()
+                    .map(|part| part.as_os_str().to_string_lossy().into_owned())
+                    .collect();
+                ids.push(parts.join("/"));
+                if ids.len() == MAX_TEMPLATES {
+                    ids.sort();
+                    return ids;
+                }
+            }
+        }
+    }
+    ids.sort();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn replaces_known_placeholders() {
+        let variables = variables(&[("title", "Caching"), ("author", "alice")]);
+        assert_eq!(
+            render(
+                "# {{title}}\n\nBy {{ author }}, status: {{status}}",
+                &variables
+            ),
+            "# Caching\n\nBy alice, status: {{status}}"
+        );
+        assert_eq!(
+            render("{\"key\": {{title}}} {{unclosed", &variables),
+            "{\"key\": Caching} {{unclosed"
+        );
+    }
+
+    #[test]
+    fn finds_custom_placeholders() {
+        assert_eq!(
+            custom_placeholders(
+                "# {{title}}\n\n{{ status }} by {{author}} for {{team}}, {{status}} {{ }}"
+            ),
+            vec!["status".to_string(), "team".to_string()]
+        );
+        assert!(custom_placeholders("{{title}} on {{date}}").is_empty());
+    }
+
+    #[test]
+    fn derives_titles_from_paths() {
+        assert_eq!(
+            title_from_path("docs/adr/use-sqlite_storage.md"),
+            "Use sqlite storage"
+        );
+        assert_eq!(title_from_path("README.md"), "README");
+        assert_eq!(title_from_path(""), "");
+    }
+
+    #[test]
+    fn validates_template_ids() {
+        assert!(is_valid_template_id("rfc.md"));
+        assert!(is_valid_template_id("adr/default.md"));
+        assert!(!is_valid_template_id(""));
+        assert!(!is_valid_template_id("../secret.md"));
+        assert!(!is_valid_template_id("/rfc.md"));
+        assert!(!is_valid_template_id(".hidden.md"));
+    }
+
+    #[test]
+    fn lists_templates_without_hidden_files() {
+        let folder = tempfile::tempdir().unwrap();
+        std::fs::create_dir(folder.path().join("adr")).unwrap();
+        std::fs::write(folder.path().join("rfc.md"), "# {{title}}").unwrap();
+        std::fs::write(folder.path().join("adr/default.md"), "# ADR").unwrap();
+        std::fs::write(folder.path().join(".DS_Store"), "").unwrap();
+
+        assert_eq!(
+            list_template_ids(folder.path()),
+            vec!["adr/default.md".to_string(), "rfc.md".to_string()]
+        );
+        assert!(list_template_ids(&folder.path().join("missing")).is_empty());
+    }
+}
@@ -0,0 +1,671 @@
+//! End-to-end test runs: a repository's Playwright or Cypress suite run from an attempt's
+//! worktree against its preview. The files the suite writes to its results folder during
+//! the run, its JUnit reports, traces, videos and screenshots, are copied out of the
+//! worktree as the run's artifacts, and the reports are parsed into test cases.
+//!
+//! The preview's URL is passed in `BASE_URL` and the variables Playwright configs and
+//! Cypress read it from. `PLAYWRIGHT_JUNIT_OUTPUT_NAME` and `MOCHA_FILE` point the JUnit
+//! reporters of both into the results folder, so `npx playwright test --reporter=junit`
+//! or `npx cypress run --reporter junit` need no further setup.
+
+use std::{
+    path::{Component, Path, PathBuf},
+    process::Stdio,
+    time::{Duration, SystemTime},
+};
+
+use db::models::e2e_run::{CreateE2eTestCase, E2eTestStatus};
+use quick_xml::{
+    Reader,
+    events::{BytesStart, Event},
+};
+use serde::Serialize;
+use thiserror::Error;
+use ts_rs::TS;
+use utils::shell::get_shell_command;
+use uuid::Uuid;
+
+use super::preview_build::{self, output_tail};
+
+/// How long a suite may run
+const RUN_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Size limit of the artifacts stored for a run
+const MAX_ARTIFACTS_BYTES: u64 = 1024 * 1024 * 1024;
+/// How much of a failure's message and stack is kept
+const MAX_FAILURE_BYTES: usize = 8 * 1024;
+/// Marker the Playwright JUnit reporter lists a test's attachments with
+const ATTACHMENT_MARKER: &str = "[[ATTACHMENT|";
+
+#[derive(Debug, Error)]
+pub enum E2eTestError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Suite timed out after {} minutes", RUN_TIMEOUT.as_secs() / 60)]
+    Timeout,
+    #[error("Suite failed: {0}")]
+    SuiteFailed(String),
+    #[error("Invalid results folder '{0}': use a folder inside the repository")]
+    InvalidResultsDir(String),
+    #[error("Artifacts are larger than {} MB", MAX_ARTIFACTS_BYTES / (1024 * 1024))]
+    TooLarge,
+    #[error("Invalid JUnit report: {0}")]
+    InvalidReport(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum E2eArtifactKind {
+    Trace,
+    Video,
+    Screenshot,
+    Report,
+    Other,
+}
+
+/// A file the suite wrote to its results folder during a run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct E2eArtifact {
+    /// Path in the run's artifacts, `/`-separated
+    pub path: String,
+    pub kind: E2eArtifactKind,
+    pub size_bytes: u64,
+}
+
+impl E2eArtifactKind {
+    fn of(path: &str) -> Self {
+        let name = path.rsplit('/').next().unwrap_or(path).to_lowercase();
+        let extension = name.rsplit_once('.').map(|(_, e)| e).unwrap_or_default();
+        match extension {
+            "zip" if name.contains("trace") => Self::Trace,
+            "webm" | "mp4" => Self::Video,
+            "png" | "jpg" | "jpeg" => Self::Screenshot,
+            "xml" | "html" | "json" => Self::Report,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Folder the artifacts of every run are stored in, one folder per run
+pub fn artifacts_root() -> PathBuf {
+    utils::assets::asset_dir().join("e2e-runs")
+}
+
+/// Folder the artifacts of `run_id` are stored in
+pub fn artifacts_dir(run_id: Uuid) -> PathBuf {
+    artifacts_root().join(run_id.to_string())
+}
+
+/// Path of the results folder `results_dir` in `repo_path`, if it stays inside it
+pub fn results_path(repo_path: &Path, results_dir: &str) -> Result<PathBuf, E2eTestError> {
+    preview_build::output_path(repo_path, results_dir)
+        .map_err(|_| E2eTestError::InvalidResultsDir(results_dir.to_string()))
+}
+
+/// Run `script` in `repo_path` against the preview at `base_url` and return the end of
+/// its output. Fails with the output if the suite exits unsuccessfully, which most
+/// runners do when a test fails.
+pub async fn run_suite(
+    repo_path: &Path,
+    script: &str,
+    base_url: &str,
+    results_path: &Path,
+) -> Result<String, (String, E2eTestError)> {
+    let (shell, shell_arg) = get_shell_command();
+    let output = tokio::time::timeout(
+        RUN_TIMEOUT,
+        tokio::process::Command::new(shell)
+            .arg(shell_arg)
+            .arg(script)
+            .current_dir(repo_path)
+            .env("CI", "1")
+            .env("BASE_URL", base_url)
+            .env("PLAYWRIGHT_BASE_URL", base_url)
+            .env("CYPRESS_BASE_URL", base_url)
+            .env(
+                "PLAYWRIGHT_JUNIT_OUTPUT_NAME",
+                results_path.join("junit.xml"),
+            )
+            .env("MOCHA_FILE", results_path.join("junit-[hash].xml"))
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| (String::new(), E2eTestError::Timeout))?
+    .map_err(|e| (String::new(), E2eTestError::Io(e)))?;
+
+    let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+    combined.push_str(&String::from_utf8_lossy(&output.stderr));
+    let combined = output_tail(&combined).to_string();
+    if !output.status.success() {
+        return Err((
+            combined,
+            E2eTestError::SuiteFailed(output.status.to_string()),
+        ));
+    }
+    Ok(combined)
+}
+
+/// Copy the files written to `source` since `started` to `destination` and return their
+/// size. Older files are left from earlier runs, and symlinks are skipped so the
+/// artifacts can't expose files from outside the results folder.
+pub async fn store_artifacts(
+    source: &Path,
+    destination: &Path,
+    started: SystemTime,
+) -> Result<u64, E2eTestError> {
+    let source = source.to_path_buf();
+    let destination = destination.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut size = 0;
+        let copied = if source.is_dir() {
+            copy_new_files(&source, &destination, started, &mut size)
+        } else {
+            Ok(())
+        };
+        if copied.is_err() {
+            let _ = std::fs::remove_dir_all(&destination);
+        }
+        copied.map(|()| size)
+    })
+    .await
+    .map_err(|e| E2eTestError::Io(std::io::Error::other(e)))?
+}
+
+fn copy_new_files(
+    source: &Path,
+    destination: &Path,
+    started: SystemTime,
+    size: &mut u64,
+) -> Result<(), E2eTestError> {
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = destination.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_new_files(&entry.path(), &target, started, size)?;
+        } else if file_type.is_file() {
+            let metadata = entry.metadata()?;
+            if metadata.modified()? < started {
+                continue;
+            }
+            *size += metadata.len();
+            if *size > MAX_ARTIFACTS_BYTES {
+                return Err(E2eTestError::TooLarge);
+            }
+            std::fs::create_dir_all(destination)?;
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Files in the artifacts folder `root`, sorted by path
+pub async fn list_artifacts(root: &Path) -> Result<Vec<E2eArtifact>, std::io::Error> {
+    let root = root.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut artifacts = Vec::new();
+        if root.is_dir() {
+            collect_artifacts(&root, "", &mut artifacts)?;
+        }
+        artifacts.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(artifacts)
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+fn collect_artifacts(
+    dir: &Path,
+    prefix: &str,
+    artifacts: &mut Vec<E2eArtifact>,
+) -> Result<(), std::io::Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        if file_type.is_dir() {
+            collect_artifacts(&entry.path(), &format!("{}/", path), artifacts)?;
+        } else if file_type.is_file() {
+            artifacts.push(E2eArtifact {
+                kind: E2eArtifactKind::of(&path),
+                size_bytes: entry.metadata()?.len(),
+                path,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// File of the artifact at `path` in the artifacts folder `root`
+pub fn artifact_file(root: &Path, path: &str) -> Option<PathBuf> {
+    let mut file = root.to_path_buf();
+    for part in path.split('/').filter(|part| !part.is_empty()) {
+        if part == "." || part == ".." || part.contains('\\') {
+            return None;
+        }
+        file.push(part);
+    }
+    file.is_file().then_some(file)
+}
+
+/// Test cases of the JUnit reports among the artifacts in `root`. Attachments are
+/// resolved to artifact paths; `results_path` is the results folder the suite wrote
+/// them to, for reporters that list absolute paths. Files that aren't JUnit reports are
+/// skipped.
+pub async fn collect_test_cases(
+    root: &Path,
+    results_path: &Path,
+) -> Result<Vec<CreateE2eTestCase>, E2eTestError> {
+    let artifacts = list_artifacts(root).await?;
+    let mut test_cases = Vec::new();
+    for artifact in artifacts
+        .iter()
+        .filter(|artifact| artifact.path.to_lowercase().ends_with(".xml"))
+    {
+        let xml = tokio::fs::read_to_string(root.join(&artifact.path)).await?;
+        if !xml.contains("<testsuite") {
+            continue;
+        }
+        let report_dir = artifact
+            .path
+            .rsplit_once('/')
+            .map(|(dir, _)| dir)
+            .unwrap_or_default();
+        match parse_junit(&xml) {
+            Ok(cases) => {
+                for mut test_case in cases {
+                    test_case.attachments = test_case
+                        .attachments
+                        .iter()
+                        .filter_map(|attachment| {
+                            resolve_attachment(attachment, report_dir, results_path)
+                        })
+                        .filter(|path| artifacts.iter().any(|a| &a.path == path))
+                        .collect();
+                    test_cases.push(test_case);
+                }
+            }
+            Err(e) => tracing::warn!("Skipping report {}: {}", artifact.path, e),
+        }
+    }
+    Ok(test_cases)
+}
+
+/// Path in the artifacts of an attachment listed in a report in `report_dir`. Relative
+/// paths are relative to the report, absolute ones have to be in `results_path`.
+fn resolve_attachment(attachment: &str, report_dir: &str, results_path: &Path) -> Option<String> {
+    let attachment = Path::new(attachment.trim());
+    let relative = if attachment.is_absolute() {
+        attachment.strip_prefix(results_path).ok()?.to_path_buf()
+    } else {
+        Path::new(report_dir).join(attachment)
+    };
+    let mut parts: Vec<String> = Vec::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Text of the element a test case is in
+enum Capture {
+    Ignore,
+    Failure,
+    Output,
+}
+
+struct TestCaseBuilder {
+    test_case: CreateE2eTestCase,
+    failure_text: String,
+    output: String,
+}
+
+/// Test cases of a JUnit report, as written by Playwright's and Cypress's reporters.
+/// Attachments are the paths listed in the cases' output as Playwright lists them.
+pub fn parse_junit(xml: &str) -> Result<Vec<CreateE2eTestCase>, E2eTestError> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut suites: Vec<String> = Vec::new();
+    let mut current: Option<TestCaseBuilder> = None;
+    let mut capture = Capture::Ignore;
+    let mut test_cases = Vec::new();
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| E2eTestError::InvalidReport(e.to_string()))?;
+        match event {
+            Event::Start(element) => match element.name().as_ref() {
+                b"testsuite" => suites.push(attribute(&element, b"name").unwrap_or_default()),
+                b"testcase" => current = Some(start_test_case(&element, &suites)),
+                name => {
+                    if let Some(builder) = current.as_mut() {
+                        capture = apply_child(builder, name, &element);
+                    }
+                }
+            },
+            Event::Empty(element) => match element.name().as_ref() {
+                b"testcase" => {
+                    test_cases.push(finish_test_case(start_test_case(&element, &suites)));
+                }
+                name => {
+                    if let Some(builder) = current.as_mut() {
+                        apply_child(builder, name, &element);
+                    }
+                }
+            },
+            Event::Text(text) => {
+                let text = text
+                    .unescape()
+                    .map_err(|e| E2eTestError::InvalidReport(e.to_string()))?;
+                capture_text(current.as_mut(), &capture, &text);
+            }
+            Event::CData(data) => {
+                capture_text(current.as_mut(), &capture, &String::from_utf8_lossy(&data));
+            }
+            Event::End(element) => match element.name().as_ref() {
+                b"testsuite" => {
+                    suites.pop();
+                }
+                b"testcase" => {
+                    if let Some(builder) = current.take() {
+                        test_cases.push(finish_test_case(builder));
+                    }
+                    capture = Capture::Ignore;
+                }
+                _ => capture = Capture::Ignore,
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(test_cases)
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key.as_ref() == name)
+        .and_then(|attribute| attribute.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
+
+fn start_test_case(element: &BytesStart, suites: &[String]) -> TestCaseBuilder {
+    let suite = suites
+        .last()
+        .filter(|suite| !suite.is_empty())
+        .cloned()
+        .or_else(|| attribute(element, b"classname"))
+        .unwrap_or_default();
+    let duration_ms = attribute(element, b"time")
+        .and_then(|time| time.trim().parse::<f64>().ok())
+        .map(|seconds| (seconds * 1000.0).round() as i64);
+    TestCaseBuilder {
+        test_case: CreateE2eTestCase {
+            suite,
+            name: attribute(element, b"name").unwrap_or_default(),
+            status: E2eTestStatus::Passed,
+            duration_ms,
+            failure_message: None,
+            attachments: Vec::new(),
+        },
+        failure_text: String::new(),
+        output: String::new(),
+    }
+}
+
+/// Apply a child element of a test case and return whose text to capture
+fn apply_child(builder: &mut TestCaseBuilder, name: &[u8], element: &BytesStart) -> Capture {
+    match name {
+        b"failure" | b"error" => {
+            builder.test_case.status = E2eTestStatus::Failed;
+            if builder.test_case.failure_message.is_none() {
+                builder.test_case.failure_message =
+                    attribute(element, b"message").or_else(|| attribute(element, b"type"));
+            }
+            Capture::Failure
+        }
+        b"skipped" => {
+            if builder.test_case.status != E2eTestStatus::Failed {
+                builder.test_case.status = E2eTestStatus::Skipped;
+            }
+            Capture::Ignore
+        }
+        b"system-out" | b"system-err" => Capture::Output,
+        _ => Capture::Ignore,
+    }
+}
+
+fn capture_text(builder: Option<&mut TestCaseBuilder>, capture: &Capture, text: &str) {
+    let Some(builder) = builder else {
+        return;
+    };
+    let target = match capture {
+        Capture::Failure => &mut builder.failure_text,
+        Capture::Output => &mut builder.output,
+        Capture::Ignore => return,
+    };
+    if !target.is_empty() {
+        target.push('\n');
+    }
+    target.push_str(text);
+}
+
+fn finish_test_case(builder: TestCaseBuilder) -> CreateE2eTestCase {
+    let mut test_case = builder.test_case;
+    // The failure's text has the message with its stack, the attribute only the message
+    let failure_text = builder.failure_text.trim();
+    if !failure_text.is_empty() {
+        test_case.failure_message = Some(truncate(failure_text, MAX_FAILURE_BYTES).to_string());
+    }
+    test_case.attachments = attachments(&builder.output);
+    test_case
+}
+
+/// Paths listed in a test case's output as `[[ATTACHMENT|path]]`
+fn attachments(output: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut rest = output;
+    while let Some(start) = rest.find(ATTACHMENT_MARKER) {
+        rest = &rest[start + ATTACHMENT_MARKER.len()..];
+        let Some(end) = rest.find("]]") else {
+            break;
+        };
+        let path = rest[..end].trim();
+        if !path.is_empty() && !paths.iter().any(|p| p == path) {
+            paths.push(path.to_string());
+        }
+        rest = &rest[end + 2..];
+    }
+    paths
+}
+
+/// The first `max_bytes` of `text`
+fn truncate(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYWRIGHT_REPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites id="" name="" tests="3" failures="1" skipped="1" errors="0" time="4.2">
+<testsuite name="login.spec.ts" timestamp="2026-10-15T10:00:00.000Z" hostname="chromium" tests="3" failures="1" skipped="1" time="4.2" errors="0">
+<testcase name="login › shows the form" classname="login.spec.ts" time="0.85">
+</testcase>
+<testcase name="login › signs in" classname="login.spec.ts" time="2.5">
+<failure message="login.spec.ts:12:5 signs in" type="FAILURE">
+<![CDATA[  Error: expect(received).toBe(expected)
+
+    Expected: "Welcome"
+    Received: "Sign in"
+]]>
+</failure>
+<system-out>
+<![CDATA[
+[[ATTACHMENT|login-signs-in-chromium/trace.zip]]
+[[ATTACHMENT|login-signs-in-chromium/video.webm]]
+]]>
+</system-out>
+</testcase>
+<testcase name="login &amp; logout" classname="login.spec.ts" time="0">
+<skipped>
+</skipped>
+</testcase>
+</testsuite>
+</testsuites>"#;
+
+    #[test]
+    fn parses_playwright_reports() {
+        let cases = parse_junit(PLAYWRIGHT_REPORT).unwrap();
+        assert_eq!(cases.len(), 3);
+
+        assert_eq!(cases[0].suite, "login.spec.ts");
+        assert_eq!(cases[0].name, "login › shows the form");
+        assert_eq!(cases[0].status, E2eTestStatus::Passed);
+        assert_eq!(cases[0].duration_ms, Some(850));
+
+        assert_eq!(cases[1].status, E2eTestStatus::Failed);
+        let message = cases[1].failure_message.as_deref().unwrap();
+        assert!(message.starts_with("Error: expect(received).toBe(expected)"));
+        assert!(message.contains("Received: \"Sign in\""));
+        assert_eq!(
+            cases[1].attachments,
+            vec![
+                "login-signs-in-chromium/trace.zip".to_string(),
+                "login-signs-in-chromium/video.webm".to_string(),
+            ]
+        );
+
+        assert_eq!(cases[2].name, "login & logout");
+        assert_eq!(cases[2].status, E2eTestStatus::Skipped);
+        assert!(cases[2].attachments.is_empty());
+    }
+
+    #[test]
+    fn parses_cypress_reports() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="Mocha Tests" time="1.5" tests="2" failures="1">
+  <testsuite name="Root Suite" timestamp="2026-10-15T10:00:00" tests="0" file="cypress/e2e/cart.cy.ts" time="0" failures="0">
+  </testsuite>
+  <testsuite name="cart" timestamp="2026-10-15T10:00:00" tests="2" time="1.5" failures="1">
+    <testcase name="cart adds items" time="0.7" classname="adds items">
+    </testcase>
+    <testcase name="cart checks out" time="0.8" classname="checks out">
+      <failure message="Timed out retrying" type="AssertionError"/>
+    </testcase>
+  </testsuite>
+</testsuites>"#;
+        let cases = parse_junit(xml).unwrap();
+        assert_eq!(cases.len(), 2);
+        assert_eq!(cases[0].suite, "cart");
+        assert_eq!(cases[0].status, E2eTestStatus::Passed);
+        assert_eq!(cases[1].status, E2eTestStatus::Failed);
+        assert_eq!(
+            cases[1].failure_message.as_deref(),
+            Some("Timed out retrying")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_reports() {
+        assert!(parse_junit("<testsuite><testcase></testsuite>").is_err());
+        assert!(parse_junit("<html></html>").unwrap().is_empty());
+    }
+
+    #[test]
+    fn resolves_attachments_to_artifact_paths() {
+        let results = Path::new("/repo/test-results");
+        assert_eq!(
+            resolve_attachment("run/trace.zip", "", results).as_deref(),
+            Some("run/trace.zip")
+        );
+        assert_eq!(
+            resolve_attachment("../videos/a.webm", "reports", results).as_deref(),
+            Some("videos/a.webm")
+        );
+        assert_eq!(
+            resolve_attachment("/repo/test-results/run/video.webm", "", results).as_deref(),
+            Some("run/video.webm")
+        );
+        assert_eq!(resolve_attachment("/etc/passwd", "", results), None);
+        assert_eq!(resolve_attachment("../../secret", "reports", results), None);
+    }
+
+    #[test]
+    fn classifies_artifacts() {
+        assert_eq!(E2eArtifactKind::of("run/trace.zip"), E2eArtifactKind::Trace);
+        assert_eq!(
+            E2eArtifactKind::of("run/video.webm"),
+            E2eArtifactKind::Video
+        );
+        assert_eq!(
+            E2eArtifactKind::of("screenshots/cart (failed).png"),
+            E2eArtifactKind::Screenshot
+        );
+        assert_eq!(E2eArtifactKind::of("junit.xml"), E2eArtifactKind::Report);
+        assert_eq!(E2eArtifactKind::of("archive.zip"), E2eArtifactKind::Other);
+    }
+
+    #[tokio::test]
+    async fn stores_new_artifacts_and_collects_their_test_cases() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let artifacts = destination.path().join("artifacts");
+        let started = SystemTime::now() - Duration::from_secs(2);
+        let stale = source.path().join("stale.xml");
+        std::fs::write(&stale, PLAYWRIGHT_REPORT).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&stale)
+            .unwrap()
+            .set_modified(started - Duration::from_secs(3600))
+            .unwrap();
+        std::fs::write(source.path().join("junit.xml"), PLAYWRIGHT_REPORT).unwrap();
+        std::fs::create_dir(source.path().join("login-signs-in-chromium")).unwrap();
+        std::fs::write(
+            source.path().join("login-signs-in-chromium/trace.zip"),
+            "trace",
+        )
+        .unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("/etc/passwd", source.path().join("passwd")).unwrap();
+
+        store_artifacts(source.path(), &artifacts, started)
+            .await
+            .unwrap();
+        let listed = list_artifacts(&artifacts).await.unwrap();
+        let paths: Vec<&str> = listed.iter().map(|a| a.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["junit.xml", "login-signs-in-chromium/trace.zip"]
+        );
+
+        let cases = collect_test_cases(&artifacts, source.path()).await.unwrap();
+        assert_eq!(cases.len(), 3);
+        // The video wasn't written, so only the trace is attached
+        assert_eq!(
+            cases[1].attachments,
+            vec!["login-signs-in-chromium/trace.zip".to_string()]
+        );
+        assert!(artifact_file(&artifacts, "login-signs-in-chromium/trace.zip").is_some());
+        assert!(artifact_file(&artifacts, "../artifacts/junit.xml").is_none());
+    }
+}
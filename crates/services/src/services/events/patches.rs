@@ -214,3 +214,32 @@ pub mod scratch_patch {
         })])
     }
 }
+
+/// Helper functions for creating patches for documents changed outside the app
+pub mod document_patch {
+    use super::*;
+    use crate::services::git::{PathChange, PathChangeKind};
+
+    fn document_path(project_id: Uuid, relative_path: &str) -> String {
+        format!(
+            "/documents/{}/{}",
+            escape_pointer_segment(&project_id.to_string()),
+            escape_pointer_segment(relative_path)
+        )
+    }
+
+    /// Create an add, replace or remove patch matching the kind of change
+    pub fn change(project_id: Uuid, change: &PathChange) -> Patch {
+        let path = document_path(project_id, &change.path)
+            .try_into()
+            .expect("Document path should be valid");
+        let value =
+            serde_json::to_value(change).expect("Document change serialization should not fail");
+        let operation = match change.kind {
+            PathChangeKind::Added => PatchOperation::Add(AddOperation { path, value }),
+            PathChangeKind::Modified => PatchOperation::Replace(ReplaceOperation { path, value }),
+            PathChangeKind::Deleted => PatchOperation::Remove(RemoveOperation { path }),
+        };
+        Patch(vec![operation])
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::LazyLock,
+};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::ExecutionContext,
+    execution_process_repo_state::ExecutionProcessRepoState,
+    task::{CreateTask, Task},
+    test_result::{CreateTestResult, TestResult},
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A test that both passed and failed while the code under test was unchanged
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq)]
+pub struct FlakyTest {
+    pub test_name: String,
+    pub passes: u32,
+    pub failures: u32,
+    /// Number of pass/fail flips observed between consecutive runs on the same code
+    pub flips: u32,
+    /// Distinct code states on which the test was seen to flip
+    pub flaky_code_states: u32,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct FlakyTestReport {
+    pub project_id: Uuid,
+    pub total_results: usize,
+    /// Flaky tests, worst offenders first
+    pub tests: Vec<FlakyTest>,
+}
+
+static CARGO_TEST: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^test (\S+) \.\.\. (ok|FAILED)$").unwrap());
+static GO_TEST: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*--- (PASS|FAIL): (\S+)").unwrap());
+static PYTEST: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\S+::\S+) (PASSED|FAILED)").unwrap());
+static JS_TEST: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(✓|✔|√|✕|×|✗)\s+(.+?)(?:\s+\(\d+\s*m?s\))?$").unwrap());
+static ANSI_ESCAPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").unwrap());
+
+/// Extract individual test outcomes from test runner output.
+///
+/// Understands `cargo test`, `go test -v`, `pytest -v` and jest/vitest style
+/// output. When a test appears more than once, the last outcome wins.
+pub fn parse_test_output(output: &str) -> Vec<CreateTestResult> {
+    let mut results: Vec<CreateTestResult> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for raw_line in output.lines() {
+        let line = ANSI_ESCAPE.replace_all(raw_line.trim_end(), "");
+        let parsed = if let Some(c) = CARGO_TEST.captures(&line) {
+            Some((c[1].to_string(), &c[2] == "ok"))
+        } else if let Some(c) = GO_TEST.captures(&line) {
+            Some((c[2].to_string(), &c[1] == "PASS"))
+        } else if let Some(c) = PYTEST.captures(&line) {
+            Some((c[1].to_string(), &c[2] == "PASSED"))
+        } else {
+            JS_TEST
+                .captures(&line)
+                .map(|c| (c[2].trim().to_string(), matches!(&c[1], "✓" | "✔" | "√")))
+        };
+
+        if let Some((test_name, passed)) = parsed {
+            match index.get(&test_name) {
+                Some(&i) => results[i].passed = passed,
+                None => {
+                    index.insert(test_name.clone(), results.len());
+                    results.push(CreateTestResult { test_name, passed });
+                }
+            }
+        }
+    }
+    results
+}
+
+/// Parse the output of a finished script process and store its test results.
+///
+/// Results are keyed by the HEAD commits the process started from, so reruns on
+/// the same commits can be compared. Returns the number of recorded results.
+pub async fn record_process_output(
+    pool: &SqlitePool,
+    ctx: &ExecutionContext,
+    output: &str,
+) -> Result<u64, sqlx::Error> {
+    let results = parse_test_output(output);
+    if results.is_empty() {
+        return Ok(0);
+    }
+
+    let repo_states =
+        ExecutionProcessRepoState::find_by_execution_process_id(pool, ctx.execution_process.id)
+            .await?;
+    let mut commits: Vec<String> = repo_states
+        .into_iter()
+        .filter_map(|s| {
+            s.before_head_commit
+                .map(|commit| format!("{}:{}", s.repo_id, commit))
+        })
+        .collect();
+    if commits.is_empty() {
+        // Without a known code state the results can't be compared across attempts
+        return Ok(0);
+    }
+    commits.sort();
+
+    TestResult::create_many(
+        pool,
+        ctx.project.id,
+        ctx.workspace.id,
+        ctx.execution_process.id,
+        &commits.join(","),
+        &results,
+    )
+    .await
+}
+
+/// Build the flaky-test report from results ordered oldest first
+pub fn compute_flaky_tests(results: &[TestResult]) -> Vec<FlakyTest> {
+    let mut runs: BTreeMap<&str, BTreeMap<&str, Vec<&TestResult>>> = BTreeMap::new();
+    for result in results {
+        runs.entry(result.test_name.as_str())
+            .or_default()
+            .entry(result.code_fingerprint.as_str())
+            .or_default()
+            .push(result);
+    }
+
+    let mut flaky: Vec<FlakyTest> = runs
+        .into_iter()
+        .filter_map(|(test_name, by_code)| {
+            let mut test = FlakyTest {
+                test_name: test_name.to_string(),
+                passes: 0,
+                failures: 0,
+                flips: 0,
+                flaky_code_states: 0,
+                last_seen_at: DateTime::<Utc>::MIN_UTC,
+            };
+            for code_runs in by_code.values() {
+                let flips = code_runs
+                    .windows(2)
+                    .filter(|pair| pair[0].passed != pair[1].passed)
+                    .count() as u32;
+                if flips > 0 {
+                    test.flips += flips;
+                    test.flaky_code_states += 1;
+                }
+                for run in code_runs {
+                    if run.passed {
+                        test.passes += 1;
+                    } else {
+                        test.failures += 1;
+                    }
+                    test.last_seen_at = test.last_seen_at.max(run.created_at);
+                }
+            }
+            (test.flips > 0).then_some(test)
+        })
+        .collect();
+
+    flaky.sort_by(|a, b| {
+        b.flips
+            .cmp(&a.flips)
+            .then(b.failures.cmp(&a.failures))
+            .then(a.test_name.cmp(&b.test_name))
+    });
+    flaky
+}
+
+pub async fn project_report(
+    pool: &SqlitePool,
+    project_id: Uuid,
+) -> Result<FlakyTestReport, sqlx::Error> {
+    let results = TestResult::find_by_project_id(pool, project_id).await?;
+    Ok(FlakyTestReport {
+        project_id,
+        total_results: results.len(),
+        tests: compute_flaky_tests(&results),
+    })
+}
+
+/// Create a task for each of the `limit` worst flaky tests that has no open task yet
+pub async fn create_tasks_for_worst(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    report: &FlakyTestReport,
+    limit: usize,
+) -> Result<Vec<Task>, sqlx::Error> {
+    let mut created = Vec::new();
+    for test in report.tests.iter().take(limit) {
+        let title = format!("Fix flaky test: {}", test.test_name);
+        if Task::find_open_by_title(pool, project_id, &title)
+            .await?
+            .is_some()
+        {
+            continue;
+        }
+        let description = format!(
+            "`{}` alternates between passing and failing without code changes \
+             ({} passes, {} failures, {} flips across {} code state(s)).\n\n\
+             Find the source of nondeterminism (timing, ordering, shared state, \
+             external resources) and make the test reliable.",
+            test.test_name, test.passes, test.failures, test.flips, test.flaky_code_states
+        );
+        let task = Task::create(
+            pool,
+            &CreateTask::from_title_description(project_id, title, Some(description)),
+            Uuid::new_v4(),
+        )
+        .await?;
+        created.push(task);
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(name: &str, passed: bool, fingerprint: &str, seconds: i64) -> TestResult {
+        TestResult {
+            id: Uuid::new_v4(),
+            project_id: Uuid::nil(),
+            workspace_id: Uuid::nil(),
+            execution_process_id: Uuid::nil(),
+            test_name: name.to_string(),
+            passed,
+            code_fingerprint: fingerprint.to_string(),
+            created_at: DateTime::from_timestamp(seconds, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn parses_common_test_runner_output() {
+        let output = "\
+running 2 tests
+test api::tests::creates_user ... ok
+test api::tests::deletes_user ... FAILED
+=== RUN   TestLogin
+--- PASS: TestLogin (0.01s)
+--- FAIL: TestLogout (0.02s)
+tests/test_app.py::test_index PASSED                 [ 50%]
+tests/test_app.py::test_about FAILED                 [100%]
+  \u{1b}[32m✓\u{1b}[39m renders header (12 ms)
+  ✕ submits form
+";
+        let results: Vec<(String, bool)> = parse_test_output(output)
+            .into_iter()
+            .map(|r| (r.test_name, r.passed))
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                ("api::tests::creates_user".to_string(), true),
+                ("api::tests::deletes_user".to_string(), false),
+                ("TestLogin".to_string(), true),
+                ("TestLogout".to_string(), false),
+                ("tests/test_app.py::test_index".to_string(), true),
+                ("tests/test_app.py::test_about".to_string(), false),
+                ("renders header".to_string(), true),
+                ("submits form".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_flips_on_unchanged_code_count_as_flaky() {
+        let results = vec![
+            // Fails, then fixed by a code change: not flaky
+            result("fixed", false, "a", 1),
+            result("fixed", true, "b", 2),
+            // Alternates on the same commit: flaky
+            result("racy", true, "a", 1),
+            result("racy", false, "a", 2),
+            result("racy", true, "a", 3),
+            result("sometimes", true, "b", 4),
+            result("sometimes", false, "b", 5),
+        ];
+
+        let flaky = compute_flaky_tests(&results);
+        let names: Vec<&str> = flaky.iter().map(|t| t.test_name.as_str()).collect();
+        assert_eq!(names, vec!["racy", "sometimes"]);
+        assert_eq!(flaky[0].flips, 2);
+        assert_eq!(flaky[0].passes, 2);
+        assert_eq!(flaky[0].failures, 1);
+        assert_eq!(
+            flaky[0].last_seen_at,
+            DateTime::from_timestamp(3, 0).unwrap()
+        );
+    }
+}
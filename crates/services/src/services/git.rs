@@ -1,4 +1,7 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
 
 use chrono::{DateTime, Utc};
 use git2::{
@@ -10,8 +13,12 @@ use thiserror::Error;
 use ts_rs::TS;
 use utils::diff::{Diff, DiffChangeKind, FileDiffDetails, compute_line_change_counts};
 
+mod backend;
 mod cli;
+#[cfg(any(test, feature = "fake-git"))]
+pub mod fake;
 
+pub use backend::GitBackend;
 use cli::{ChangeType, StatusDiffEntry, StatusDiffOptions};
 pub use cli::{GitCli, GitCliError};
 
@@ -65,6 +72,51 @@ pub struct GitBranch {
     pub last_commit_date: DateTime<Utc>,
 }
 
+/// A commit that touched a given file
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct FileCommit {
+    pub hash: String,
+    pub author_name: String,
+    pub author_email: String,
+    #[ts(type = "Date")]
+    pub date: DateTime<Utc>,
+    pub message: String,
+}
+
+/// The versions of a conflicted file. `None` where the file doesn't exist on that side.
+/// While rebasing, `ours` is the branch being rebased onto and `theirs` the local commit
+/// being replayed.
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct ConflictSides {
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+/// Name and email to commit with instead of the repository's configured identity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitIdentity {
+    pub name: String,
+    pub email: String,
+}
+
+/// How a file changed between two states of a repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum PathChangeKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
+/// A file added, modified or deleted, either in the working tree or between two commits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct PathChange {
+    /// Path relative to the repository root, `/`-separated
+    pub path: String,
+    pub kind: PathChangeKind,
+}
+
 #[derive(Debug, Clone)]
 pub struct HeadInfo {
     pub branch: String,
@@ -139,6 +191,32 @@ pub enum DiffTarget<'p> {
     },
 }
 
+/// Parse `git log` records as written by [`GitCli::log_path`]
+fn parse_file_commits(output: &str) -> Vec<FileCommit> {
+    output
+        .split('\x1e')
+        .map(str::trim)
+        .filter(|record| !record.is_empty())
+        .filter_map(|record| {
+            let mut fields = record.splitn(5, '\x1f');
+            let hash = fields.next()?.to_string();
+            let author_name = fields.next()?.to_string();
+            let author_email = fields.next()?.to_string();
+            let date = DateTime::parse_from_rfc3339(fields.next()?)
+                .ok()?
+                .with_timezone(&Utc);
+            let message = fields.next().unwrap_or_default().to_string();
+            Some(FileCommit {
+                hash,
+                author_name,
+                author_email,
+                date,
+                message,
+            })
+        })
+        .collect()
+}
+
 impl Default for GitService {
     fn default() -> Self {
         Self::new()
@@ -160,6 +238,25 @@ impl GitService {
         Repository::open(repo_path).map_err(GitServiceError::from)
     }
 
+    /// `user.name` and `user.email` from the user's global git configuration
+    pub fn global_identity(&self) -> Option<(String, String)> {
+        let cfg = git2::Config::open_default().ok()?;
+        let name = cfg.get_string("user.name").ok()?;
+        let email = cfg.get_string("user.email").ok()?;
+        (!name.trim().is_empty() && !email.trim().is_empty()).then_some((name, email))
+    }
+
+    /// Set `user.name` and `user.email` in the user's global git configuration
+    pub fn set_global_identity(&self, name: &str, email: &str) -> Result<(), GitServiceError> {
+        let home = dirs::home_dir().ok_or_else(|| {
+            GitServiceError::InvalidRepository("Could not determine home directory".into())
+        })?;
+        let git = GitCli::new();
+        git.git(&home, ["config", "--global", "user.name", name])?;
+        git.git(&home, ["config", "--global", "user.email", email])?;
+        Ok(())
+    }
+
     /// Ensure local (repo-scoped) identity exists for CLI commits.
     /// Sets user.name/email only if missing in the repo config.
     fn ensure_cli_commit_identity(&self, repo_path: &Path) -> Result<(), GitServiceError> {
@@ -272,6 +369,16 @@ impl GitService {
     }
 
     pub fn commit(&self, path: &Path, message: &str) -> Result<bool, GitServiceError> {
+        self.commit_as(path, message, None)
+    }
+
+    /// Like [`Self::commit`], authored and committed as `identity` when given
+    pub fn commit_as(
+        &self,
+        path: &Path,
+        message: &str,
+        identity: Option<&CommitIdentity>,
+    ) -> Result<bool, GitServiceError> {
         // Use Git CLI to respect sparse-checkout semantics for staging and commit
         let git = GitCli::new();
         let has_changes = git
@@ -284,13 +391,236 @@ impl GitService {
 
         git.add_all(path)
             .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
-        // Only ensure identity once we know we're about to commit
-        self.ensure_cli_commit_identity(path)?;
-        git.commit(path, message)
+        let committed = match identity {
+            Some(identity) => git.commit_as(path, message, &identity.name, &identity.email),
+            None => {
+                // Only ensure identity once we know we're about to commit
+                self.ensure_cli_commit_identity(path)?;
+                git.commit(path, message)
+            }
+        };
+        committed
             .map_err(|e| GitServiceError::InvalidRepository(format!("git commit failed: {e}")))?;
         Ok(true)
     }
 
+    /// Replace `branch` of the remote at `remote_url` with a single commit of `files`,
+    /// each a name and the file on disk. For files that go with a branch's changes
+    /// without being part of them, such as screenshots of a preview.
+    pub fn publish_files(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        branch: &str,
+        files: &[(String, PathBuf)],
+        message: &str,
+    ) -> Result<(), GitServiceError> {
+        self.ensure_cli_commit_identity(repo_path)?;
+        let git = GitCli::new();
+        let sha = git.commit_files(repo_path, files, message)?;
+        git.push_commit(repo_path, remote_url, &sha, branch)?;
+        Ok(())
+    }
+
+    /// Move a tracked file with `git mv` so its history follows it
+    pub fn move_path(&self, repo_path: &Path, from: &str, to: &str) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.mv(repo_path, from, to)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git mv failed: {e}")))?;
+        Ok(())
+    }
+
+    /// Commits that touched `path` (relative to the repo root), newest first.
+    /// Renames made with `git mv` are followed.
+    pub fn file_history(
+        &self,
+        repo_path: &Path,
+        path: &str,
+        limit: usize,
+    ) -> Result<Vec<FileCommit>, GitServiceError> {
+        let git = GitCli::new();
+        let output = git.log_path(repo_path, path, limit)?;
+        Ok(parse_file_commits(&output))
+    }
+
+    /// Newest commit in the history of `revision` that touched `path`
+    pub fn last_commit_touching(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        path: &str,
+    ) -> Result<Option<FileCommit>, GitServiceError> {
+        let git = GitCli::new();
+        let output = git.log_path_at(repo_path, revision, path, 1)?;
+        Ok(parse_file_commits(&output).into_iter().next())
+    }
+
+    /// The `limit` files among `paths` changed most recently in the history of `revision`,
+    /// newest first, each with the newest commit that changed it. Merge commits are
+    /// skipped, so a change dates from the commit that made it rather than the merge.
+    pub fn recently_changed_paths(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        paths: &HashSet<String>,
+        limit: usize,
+    ) -> Result<Vec<(String, FileCommit)>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let start = repo
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| {
+                GitServiceError::InvalidRepository(format!("Unknown revision: {revision}"))
+            })?;
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(start.id())?;
+        // Children before parents, so commits made within the same second keep their order
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+        let mut changed: Vec<(String, FileCommit)> = Vec::new();
+        let mut found = HashSet::new();
+        for oid in revwalk {
+            if changed.len() >= limit || found.len() == paths.len() {
+                break;
+            }
+            let commit = repo.find_commit(oid?)?;
+            if commit.parent_count() > 1 {
+                continue;
+            }
+            let parent_tree = match commit.parent_count() {
+                0 => None,
+                _ => Some(commit.parent(0)?.tree()?),
+            };
+            let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+            let touched: Vec<String> = diff
+                .deltas()
+                .filter_map(|delta| delta.new_file().path())
+                .map(|path| path.to_string_lossy().to_string())
+                .filter(|path| paths.contains(path) && found.insert(path.clone()))
+                .collect();
+            if touched.is_empty() {
+                continue;
+            }
+
+            let author = commit.author();
+            let file_commit = FileCommit {
+                hash: commit.id().to_string(),
+                author_name: author.name().unwrap_or_default().to_string(),
+                author_email: author.email().unwrap_or_default().to_string(),
+                date: DateTime::from_timestamp(author.when().seconds(), 0).unwrap_or_else(Utc::now),
+                message: commit.summary().unwrap_or_default().to_string(),
+            };
+            changed.extend(touched.into_iter().map(|path| (path, file_commit.clone())));
+        }
+        changed.truncate(limit);
+        Ok(changed)
+    }
+
+    /// Contents of `path` as of `revision` (a commit, branch or tag), without
+    /// touching the working tree. Returns the resolved commit id and the blob,
+    /// or `None` if the file does not exist at that revision.
+    pub fn read_file_at_revision(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        path: &str,
+    ) -> Result<Option<(String, Vec<u8>)>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let commit = repo
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| {
+                GitServiceError::InvalidRepository(format!("Unknown revision: {revision}"))
+            })?;
+        let entry = match commit.tree()?.get_path(Path::new(path)) {
+            Ok(entry) => entry,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let Ok(blob) = entry.to_object(&repo)?.into_blob() else {
+            return Ok(None);
+        };
+        Ok(Some((commit.id().to_string(), blob.content().to_vec())))
+    }
+
+    /// Files that differ from `HEAD`, including untracked files inside untracked
+    /// directories. Ignored files are left out.
+    pub fn working_tree_changes(
+        &self,
+        repo_path: &Path,
+    ) -> Result<Vec<PathChange>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut status_options = git2::StatusOptions::new();
+        status_options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false);
+
+        let changes = repo
+            .statuses(Some(&mut status_options))?
+            .iter()
+            .filter_map(|entry| {
+                let status = entry.status();
+                let kind = if status.intersects(git2::Status::WT_NEW | git2::Status::INDEX_NEW) {
+                    PathChangeKind::Added
+                } else if status
+                    .intersects(git2::Status::WT_DELETED | git2::Status::INDEX_DELETED)
+                {
+                    PathChangeKind::Deleted
+                } else if status.is_empty() || status.is_conflicted() {
+                    return None;
+                } else {
+                    PathChangeKind::Modified
+                };
+                Some(PathChange {
+                    path: entry.path()?.to_string(),
+                    kind,
+                })
+            })
+            .collect();
+        Ok(changes)
+    }
+
+    /// Files that differ between the trees of two revisions, without rename detection
+    pub fn changed_paths_between(
+        &self,
+        repo_path: &Path,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<PathChange>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let tree_at = |revision: &str| {
+            repo.revparse_single(revision)
+                .and_then(|object| object.peel_to_tree())
+                .map_err(|_| {
+                    GitServiceError::InvalidRepository(format!("Unknown revision: {revision}"))
+                })
+        };
+        let (from_tree, to_tree) = (tree_at(from)?, tree_at(to)?);
+
+        let diff = repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), None)?;
+        let changes = diff
+            .deltas()
+            .filter_map(|delta| {
+                let kind = match delta.status() {
+                    Delta::Added => PathChangeKind::Added,
+                    Delta::Deleted => PathChangeKind::Deleted,
+                    Delta::Unmodified => return None,
+                    _ => PathChangeKind::Modified,
+                };
+                let file = match kind {
+                    PathChangeKind::Deleted => delta.old_file(),
+                    _ => delta.new_file(),
+                };
+                Some(PathChange {
+                    path: file.path()?.to_string_lossy().to_string(),
+                    kind,
+                })
+            })
+            .collect();
+        Ok(changes)
+    }
+
     /// Checkout a branch in the given repository
     pub fn checkout(&self, repo_path: &Path, branch: &str) -> Result<(), GitServiceError> {
         let git = GitCli::new();
@@ -299,6 +629,17 @@ impl GitService {
         Ok(())
     }
 
+    /// Create a branch at HEAD and check it out, keeping uncommitted changes
+    pub fn create_branch_at_head(
+        &self,
+        repo_path: &Path,
+        branch: &str,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.checkout_new_branch(repo_path, branch)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git checkout -b failed: {e}")))
+    }
+
     /// Checkout a branch, automatically stashing and restoring changes if needed.
     /// Returns true if stash was used, false otherwise.
     pub fn checkout_with_stash(
@@ -353,6 +694,34 @@ impl GitService {
         Ok(())
     }
 
+    /// Pull from a remote branch, with a merge commit if the branches have diverged.
+    pub fn pull_merge(
+        &self,
+        repo_path: &Path,
+        remote: &str,
+        branch: &str,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.pull_merge(repo_path, remote, branch).map_err(|e| {
+            GitServiceError::InvalidRepository(format!("git pull --no-rebase failed: {e}"))
+        })?;
+        Ok(())
+    }
+
+    /// Pull from a remote branch if the local branch can be fast-forwarded to it.
+    pub fn pull_ff_only(
+        &self,
+        repo_path: &Path,
+        remote: &str,
+        branch: &str,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.pull_ff_only(repo_path, remote, branch).map_err(|e| {
+            GitServiceError::InvalidRepository(format!("git pull --ff-only failed: {e}"))
+        })?;
+        Ok(())
+    }
+
     /// Push a branch to a remote.
     pub fn push(
         &self,
@@ -1220,6 +1589,53 @@ impl GitService {
         Ok(commit.summary().unwrap_or("(no subject)").to_string())
     }
 
+    /// Files changed by the commits reachable from HEAD that were made in
+    /// `[since, until)` and whose message `include` accepts. Each commit is compared
+    /// with its first parent; merge commits are skipped.
+    pub fn paths_changed_between(
+        &self,
+        repo_path: &Path,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        include: impl Fn(&str) -> bool,
+    ) -> Result<HashSet<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.set_sorting(Sort::TIME)?;
+
+        let mut paths = HashSet::new();
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            let time =
+                DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_else(Utc::now);
+            if time < since {
+                break;
+            }
+            if time >= until
+                || commit.parent_count() > 1
+                || !include(commit.message().unwrap_or_default())
+            {
+                continue;
+            }
+            let parent_tree = match commit.parent(0) {
+                Ok(parent) => Some(parent.tree()?),
+                Err(_) => None,
+            };
+            let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+            for delta in diff.deltas() {
+                let file = match delta.status() {
+                    Delta::Deleted => delta.old_file(),
+                    _ => delta.new_file(),
+                };
+                if let Some(path) = file.path() {
+                    paths.insert(path.to_string_lossy().to_string());
+                }
+            }
+        }
+        Ok(paths)
+    }
+
     /// Compare two OIDs and return (ahead, behind) counts: how many commits
     /// `from_oid` is ahead of and behind `to_oid`.
     pub fn ahead_behind_commits_by_oid(
@@ -1702,6 +2118,82 @@ impl GitService {
         Ok(())
     }
 
+    /// The base, ours and theirs versions of a conflicted file, read from the index
+    pub fn conflict_sides(
+        &self,
+        worktree_path: &Path,
+        path: &str,
+    ) -> Result<ConflictSides, GitServiceError> {
+        let repo = self.open_repo(worktree_path)?;
+        let index = repo.index()?;
+        let read = |entry: Option<git2::IndexEntry>| -> Result<Option<String>, GitServiceError> {
+            let Some(entry) = entry else {
+                return Ok(None);
+            };
+            let blob = repo.find_blob(entry.id)?;
+            Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+        };
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let conflict_path = [&conflict.ancestor, &conflict.our, &conflict.their]
+                .into_iter()
+                .flatten()
+                .map(|entry| entry.path.clone())
+                .next();
+            if conflict_path.as_deref() != Some(path.as_bytes()) {
+                continue;
+            }
+            return Ok(ConflictSides {
+                base: read(conflict.ancestor)?,
+                ours: read(conflict.our)?,
+                theirs: read(conflict.their)?,
+            });
+        }
+        Err(GitServiceError::InvalidRepository(format!(
+            "'{path}' has no unresolved conflict"
+        )))
+    }
+
+    /// Resolve a conflicted file with `content`, or by deleting it if `None`, and stage it
+    pub fn resolve_conflict(
+        &self,
+        worktree_path: &Path,
+        path: &str,
+        content: Option<&str>,
+    ) -> Result<(), GitServiceError> {
+        let file_path = worktree_path.join(path);
+        match content {
+            Some(content) => std::fs::write(&file_path, content)?,
+            None => {
+                if let Err(e) = std::fs::remove_file(&file_path)
+                    && e.kind() != std::io::ErrorKind::NotFound
+                {
+                    return Err(e.into());
+                }
+            }
+        }
+        GitCli::new().stage_path(worktree_path, path)?;
+        Ok(())
+    }
+
+    /// Continue the rebase or merge stopped by conflicts once they are all resolved. A
+    /// rebase stops again at the next commit that conflicts.
+    pub fn continue_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        match self.detect_conflict_op(worktree_path)? {
+            None => Ok(()),
+            Some(ConflictOp::Rebase) => git.continue_rebase(worktree_path).map_err(|e| {
+                GitServiceError::InvalidRepository(format!("git rebase --continue failed: {e}"))
+            }),
+            Some(ConflictOp::Merge) => git.conclude_merge(worktree_path).map_err(|e| {
+                GitServiceError::InvalidRepository(format!("git commit of the merge failed: {e}"))
+            }),
+            Some(op) => Err(GitServiceError::InvalidRepository(format!(
+                "Continuing a {op:?} is not supported"
+            ))),
+        }
+    }
+
     pub fn find_branch<'a>(
         repo: &'a Repository,
         branch_name: &str,
@@ -0,0 +1,169 @@
+use std::{collections::HashSet, path::Path};
+
+use super::{CommitIdentity, FileCommit, GitService, GitServiceError, PathChange};
+
+/// The repository operations the document routes rely on. [`GitService`] implements it
+/// against real repositories; with the `fake-git` feature, [`super::fake::FakeGitService`]
+/// implements it in memory so route tests don't need repositories on disk.
+pub trait GitBackend: Send + Sync {
+    /// Initialize a repository with a `main` branch and an initial commit
+    fn initialize_repo_with_main_branch(&self, repo_path: &Path) -> Result<(), GitServiceError>;
+
+    fn get_current_branch(&self, repo_path: &Path) -> Result<String, GitServiceError>;
+
+    /// Create `branch` at HEAD and check it out, keeping uncommitted changes
+    fn create_branch_at_head(&self, repo_path: &Path, branch: &str) -> Result<(), GitServiceError>;
+
+    /// Stage everything and commit; returns false when there was nothing to commit
+    fn commit(&self, repo_path: &Path, message: &str) -> Result<bool, GitServiceError> {
+        self.commit_as(repo_path, message, None)
+    }
+
+    /// Like [`GitBackend::commit`], as `identity` instead of the configured identity
+    fn commit_as(
+        &self,
+        repo_path: &Path,
+        message: &str,
+        identity: Option<&CommitIdentity>,
+    ) -> Result<bool, GitServiceError>;
+
+    /// Move a tracked file, keeping its history
+    fn move_path(&self, repo_path: &Path, from: &str, to: &str) -> Result<(), GitServiceError>;
+
+    /// Commits that touched `path`, newest first
+    fn file_history(
+        &self,
+        repo_path: &Path,
+        path: &str,
+        limit: usize,
+    ) -> Result<Vec<FileCommit>, GitServiceError>;
+
+    /// Newest commit in the history of `revision` that touched `path`
+    fn last_commit_touching(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        path: &str,
+    ) -> Result<Option<FileCommit>, GitServiceError>;
+
+    /// The `limit` files among `paths` changed most recently in the history of `revision`,
+    /// newest first, each with the newest commit that changed it
+    fn recently_changed_paths(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        paths: &HashSet<String>,
+        limit: usize,
+    ) -> Result<Vec<(String, FileCommit)>, GitServiceError>;
+
+    /// Full hash of the commit `revision` points at
+    fn resolve_revision(&self, repo_path: &Path, revision: &str)
+    -> Result<String, GitServiceError>;
+
+    /// Contents of `path` at `revision`, with the resolved commit hash. `None` if the file
+    /// does not exist at that revision.
+    fn read_file_at_revision(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        path: &str,
+    ) -> Result<Option<(String, Vec<u8>)>, GitServiceError>;
+
+    /// Uncommitted changes to files in the working tree, compared to `HEAD`
+    fn working_tree_changes(&self, repo_path: &Path) -> Result<Vec<PathChange>, GitServiceError>;
+
+    /// Files that differ between two revisions
+    fn changed_paths_between(
+        &self,
+        repo_path: &Path,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<PathChange>, GitServiceError>;
+}
+
+impl GitBackend for GitService {
+    fn initialize_repo_with_main_branch(&self, repo_path: &Path) -> Result<(), GitServiceError> {
+        GitService::initialize_repo_with_main_branch(self, repo_path)
+    }
+
+    fn get_current_branch(&self, repo_path: &Path) -> Result<String, GitServiceError> {
+        Ok(GitService::get_current_branch(self, repo_path)?)
+    }
+
+    fn create_branch_at_head(&self, repo_path: &Path, branch: &str) -> Result<(), GitServiceError> {
+        GitService::create_branch_at_head(self, repo_path, branch)
+    }
+
+    fn commit_as(
+        &self,
+        repo_path: &Path,
+        message: &str,
+        identity: Option<&CommitIdentity>,
+    ) -> Result<bool, GitServiceError> {
+        GitService::commit_as(self, repo_path, message, identity)
+    }
+
+    fn move_path(&self, repo_path: &Path, from: &str, to: &str) -> Result<(), GitServiceError> {
+        GitService::move_path(self, repo_path, from, to)
+    }
+
+    fn file_history(
+        &self,
+        repo_path: &Path,
+        path: &str,
+        limit: usize,
+    ) -> Result<Vec<FileCommit>, GitServiceError> {
+        GitService::file_history(self, repo_path, path, limit)
+    }
+
+    fn last_commit_touching(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        path: &str,
+    ) -> Result<Option<FileCommit>, GitServiceError> {
+        GitService::last_commit_touching(self, repo_path, revision, path)
+    }
+
+    fn recently_changed_paths(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        paths: &HashSet<String>,
+        limit: usize,
+    ) -> Result<Vec<(String, FileCommit)>, GitServiceError> {
+        GitService::recently_changed_paths(self, repo_path, revision, paths, limit)
+    }
+
+    fn resolve_revision(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let commit = repo.revparse_single(revision)?.peel_to_commit()?;
+        Ok(commit.id().to_string())
+    }
+
+    fn read_file_at_revision(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        path: &str,
+    ) -> Result<Option<(String, Vec<u8>)>, GitServiceError> {
+        GitService::read_file_at_revision(self, repo_path, revision, path)
+    }
+
+    fn working_tree_changes(&self, repo_path: &Path) -> Result<Vec<PathChange>, GitServiceError> {
+        GitService::working_tree_changes(self, repo_path)
+    }
+
+    fn changed_paths_between(
+        &self,
+        repo_path: &Path,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<PathChange>, GitServiceError> {
+        GitService::changed_paths_between(self, repo_path, from, to)
+    }
+}
@@ -18,7 +18,7 @@
 use std::{
     ffi::{OsStr, OsString},
     io::Write as _,
-    path::Path,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
 };
 
@@ -337,18 +337,124 @@ impl GitCli {
         Ok(entries)
     }
 
+    /// Move or rename a tracked path, staging the change so history follows it.
+    pub fn mv(&self, repo_path: &Path, from: &str, to: &str) -> Result<(), GitCliError> {
+        self.git(repo_path, ["mv", from, to])?;
+        Ok(())
+    }
+
+    /// Raw `git log` for a single path, following renames. Each record is
+    /// `hash, author name, author email, author date (ISO 8601), subject`
+    /// separated by `\x1f` and terminated by `\x1e`, newest first.
+    pub fn log_path(
+        &self,
+        repo_path: &Path,
+        path: &str,
+        limit: usize,
+    ) -> Result<String, GitCliError> {
+        self.log_path_at(repo_path, "HEAD", path, limit)
+    }
+
+    /// [`Self::log_path`] for the history of `revision` instead of `HEAD`
+    pub fn log_path_at(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        path: &str,
+        limit: usize,
+    ) -> Result<String, GitCliError> {
+        let max_count = format!("--max-count={limit}");
+        self.git(
+            repo_path,
+            [
+                "log",
+                "--follow",
+                max_count.as_str(),
+                "--format=%H%x1f%an%x1f%ae%x1f%aI%x1f%s%x1e",
+                revision,
+                "--",
+                path,
+            ],
+        )
+    }
+
     /// Commit staged changes with the given message.
     pub fn commit(&self, worktree_path: &Path, message: &str) -> Result<(), GitCliError> {
         self.git(worktree_path, ["commit", "-m", message])?;
         Ok(())
     }
 
+    /// Commit staged changes with `name` and `email` as both author and committer,
+    /// regardless of the configured identity.
+    pub fn commit_as(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+        name: &str,
+        email: &str,
+    ) -> Result<(), GitCliError> {
+        let envs: Vec<(OsString, OsString)> = [
+            ("GIT_AUTHOR_NAME", name),
+            ("GIT_AUTHOR_EMAIL", email),
+            ("GIT_COMMITTER_NAME", name),
+            ("GIT_COMMITTER_EMAIL", email),
+        ]
+        .into_iter()
+        .map(|(key, value)| (OsString::from(key), OsString::from(value)))
+        .collect();
+        self.git_with_env(worktree_path, ["commit", "-m", message], &envs)?;
+        Ok(())
+    }
+
+    /// Write `branch` with its full history to a bundle file at `bundle_path`.
+    pub fn bundle_create(
+        &self,
+        repo_path: &Path,
+        bundle_path: &Path,
+        branch: &str,
+    ) -> Result<(), GitCliError> {
+        self.git(
+            repo_path,
+            [
+                OsStr::new("bundle"),
+                OsStr::new("create"),
+                bundle_path.as_os_str(),
+                OsStr::new(branch),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Binary patch of every change in the working tree against HEAD, untracked files
+    /// included. Stages all changes as a side effect.
+    pub fn diff_worktree_binary(&self, worktree_path: &Path) -> Result<Vec<u8>, GitCliError> {
+        self.add_all(worktree_path)?;
+        self.git_impl(
+            worktree_path,
+            ["diff", "--cached", "--binary", "HEAD"],
+            None,
+            None,
+        )
+    }
+
+    /// Apply a patch made by [`Self::diff_worktree_binary`] to the working tree.
+    pub fn apply_binary(&self, worktree_path: &Path, patch: &[u8]) -> Result<(), GitCliError> {
+        self.git_impl(worktree_path, ["apply", "--binary", "-"], None, Some(patch))?;
+        Ok(())
+    }
+
     /// Checkout a branch in the given repository/worktree.
     pub fn checkout(&self, repo_path: &Path, branch: &str) -> Result<(), GitCliError> {
         self.git(repo_path, ["checkout", branch])?;
         Ok(())
     }
 
+    /// Create `branch` at HEAD and check it out, carrying uncommitted changes over.
+    pub fn checkout_new_branch(&self, repo_path: &Path, branch: &str) -> Result<(), GitCliError> {
+        self.git(repo_path, ["checkout", "-b", branch])?;
+        Ok(())
+    }
+
     /// Check if there are uncommitted changes (staged or unstaged).
     pub fn has_uncommitted_changes(&self, repo_path: &Path) -> Result<bool, GitCliError> {
         // Check for staged changes
@@ -529,6 +635,39 @@ impl GitCli {
         }
     }
 
+    /// Pull from a remote branch, merging it with a merge commit when the branches
+    /// have diverged.
+    pub fn pull_merge(
+        &self,
+        repo_path: &Path,
+        remote: &str,
+        branch: &str,
+    ) -> Result<(), GitCliError> {
+        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        let args = ["pull", "--no-rebase", "--no-edit", remote, branch];
+        match self.git_with_env(repo_path, args, &envs) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Pull from a remote branch only if the local branch can be fast-forwarded.
+    pub fn pull_ff_only(
+        &self,
+        repo_path: &Path,
+        remote: &str,
+        branch: &str,
+    ) -> Result<(), GitCliError> {
+        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        let args = ["pull", "--ff-only", remote, branch];
+        match self.git_with_env(repo_path, args, &envs) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
     /// Fetch a branch to the given remote using native git authentication.
     pub fn fetch_with_refspec(
         &self,
@@ -579,6 +718,55 @@ impl GitCli {
         }
     }
 
+    /// Make a root commit whose tree is `files`, each a name in the tree and the file on
+    /// disk with its content, and return its sha. No branch, index or worktree changes.
+    pub fn commit_files(
+        &self,
+        repo_path: &Path,
+        files: &[(String, PathBuf)],
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        let mut tree = String::new();
+        for (name, file) in files {
+            let blob = self.git(
+                repo_path,
+                [
+                    OsStr::new("hash-object"),
+                    OsStr::new("-w"),
+                    file.as_os_str(),
+                ],
+            )?;
+            tree.push_str(&format!("100644 blob {}\t{}\n", blob.trim(), name));
+        }
+        let tree_sha = self.git_with_stdin(repo_path, ["mktree"], None, tree.as_bytes())?;
+        let commit = self.git(repo_path, ["commit-tree", tree_sha.trim(), "-m", message])?;
+        Ok(commit.trim().to_string())
+    }
+
+    /// Force-push the commit `sha` to `branch` of the given remote, whatever the branch
+    /// pointed to before.
+    pub fn push_commit(
+        &self,
+        repo_path: &Path,
+        remote_url: &str,
+        sha: &str,
+        branch: &str,
+    ) -> Result<(), GitCliError> {
+        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+
+        let args = [
+            OsString::from("push"),
+            OsString::from(remote_url),
+            OsString::from(format!("+{sha}:refs/heads/{branch}")),
+        ];
+
+        match self.git_with_env(repo_path, args, &envs) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
     /// This directly queries the remote without fetching.
     pub fn check_remote_branch_exists(
         &self,
@@ -828,6 +1016,26 @@ impl GitCli {
         self.git(worktree_path, ["revert", "--abort"]).map(|_| ())
     }
 
+    /// Stage `path` as it is in the worktree, including its deletion
+    pub fn stage_path(&self, worktree_path: &Path, path: &str) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["add", "-A", "--", path])
+            .map(|_| ())
+    }
+
+    /// Continue an in-progress rebase once its conflicts are staged, keeping the
+    /// messages of the replayed commits
+    pub fn continue_rebase(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        let envs = vec![(OsString::from("GIT_EDITOR"), OsString::from("true"))];
+        self.git_with_env(worktree_path, ["rebase", "--continue"], &envs)
+            .map(|_| ())
+    }
+
+    /// Commit an in-progress merge once its conflicts are staged, with the prepared
+    /// merge message
+    pub fn conclude_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["commit", "--no-edit"]).map(|_| ())
+    }
+
     /// List files currently in a conflicted (unmerged) state in the worktree.
     pub fn get_conflicted_files(&self, worktree_path: &Path) -> Result<Vec<String>, GitCliError> {
         // `--diff-filter=U` lists paths with unresolved conflicts
@@ -0,0 +1,550 @@
+//! In-memory [`GitBackend`] for hermetic tests.
+//!
+//! Repositories exist only in memory: a commit snapshots the working tree on disk
+//! (ignoring `.git`), and hashes and dates are derived from the commit contents and
+//! position, so the same sequence of operations always produces the same history.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+
+use super::{CommitIdentity, FileCommit, GitBackend, GitServiceError, PathChange, PathChangeKind};
+
+pub const FAKE_AUTHOR_NAME: &str = "Vibe Kanban Test";
+pub const FAKE_AUTHOR_EMAIL: &str = "test@vibe-kanban.invalid";
+/// Timestamp of the first commit in every fake repository; each commit adds a minute
+const FAKE_EPOCH_SECS: i64 = 1_700_000_000;
+
+type Tree = BTreeMap<String, Vec<u8>>;
+
+#[derive(Debug, Clone)]
+struct FakeCommit {
+    hash: String,
+    message: String,
+    /// `None` for the default fake identity
+    author: Option<CommitIdentity>,
+    date: DateTime<Utc>,
+    tree: Tree,
+}
+
+#[derive(Debug, Clone)]
+struct FakeRepo {
+    branch: String,
+    /// Oldest first
+    commits: Vec<FakeCommit>,
+}
+
+impl Default for FakeRepo {
+    fn default() -> Self {
+        Self {
+            branch: "main".to_string(),
+            commits: Vec::new(),
+        }
+    }
+}
+
+impl FakeRepo {
+    fn push_commit(&mut self, message: &str, author: Option<&CommitIdentity>, tree: Tree) {
+        let mut hasher = Sha256::new();
+        if let Some(parent) = self.commits.last() {
+            hasher.update(parent.hash.as_bytes());
+        }
+        hasher.update(message.as_bytes());
+        for (path, content) in &tree {
+            hasher.update(path.as_bytes());
+            hasher.update([0]);
+            hasher.update(content);
+        }
+        let hash = format!("{:x}", hasher.finalize())[..40].to_string();
+        let date = DateTime::from_timestamp(FAKE_EPOCH_SECS, 0).unwrap_or_default()
+            + Duration::minutes(self.commits.len() as i64);
+
+        self.commits.push(FakeCommit {
+            hash,
+            message: message.to_string(),
+            author: author.cloned(),
+            date,
+            tree,
+        });
+    }
+
+    /// Index into `commits` for `HEAD`, `HEAD~n`, the branch name or a (prefix of a) hash
+    fn resolve(&self, revision: &str) -> Option<usize> {
+        let head = self.commits.len().checked_sub(1)?;
+        let (base, back) = match revision.split_once('~') {
+            Some((base, back)) => (base, back.parse::<usize>().ok()?),
+            None => (revision, 0),
+        };
+        let index = if base == "HEAD" || base == self.branch {
+            head
+        } else if base.len() >= 4 {
+            self.commits.iter().position(|c| c.hash.starts_with(base))?
+        } else {
+            return None;
+        };
+        index.checked_sub(back)
+    }
+}
+
+/// Commits among `commits` that changed `path`, oldest first
+fn touching(commits: &[FakeCommit], path: &str) -> Vec<FileCommit> {
+    let mut previous: Option<&Vec<u8>> = None;
+    let mut touched = Vec::new();
+    for commit in commits {
+        let current = commit.tree.get(path);
+        if current != previous {
+            touched.push(FileCommit {
+                hash: commit.hash.clone(),
+                author_name: commit
+                    .author
+                    .as_ref()
+                    .map_or(FAKE_AUTHOR_NAME, |author| author.name.as_str())
+                    .to_string(),
+                author_email: commit
+                    .author
+                    .as_ref()
+                    .map_or(FAKE_AUTHOR_EMAIL, |author| author.email.as_str())
+                    .to_string(),
+                date: commit.date,
+                message: commit.message.clone(),
+            });
+        }
+        previous = current;
+    }
+    touched
+}
+
+/// Files under `root`, keyed by `/`-separated relative path
+fn snapshot(root: &Path) -> Result<Tree, std::io::Error> {
+    fn walk(root: &Path, dir: &Path, tree: &mut Tree) -> Result<(), std::io::Error> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.file_name().is_some_and(|name| name == ".git") {
+                continue;
+            }
+            if path.is_dir() {
+                walk(root, &path, tree)?;
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                let key = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                tree.insert(key, std::fs::read(&path)?);
+            }
+        }
+        Ok(())
+    }
+
+    let mut tree = Tree::new();
+    if root.exists() {
+        walk(root, root, &mut tree)?;
+    }
+    Ok(tree)
+}
+
+/// Paths that differ between `old` and `new`, sorted by path
+fn diff_trees(old: &Tree, new: &Tree) -> Vec<PathChange> {
+    let mut changes: Vec<PathChange> = new
+        .iter()
+        .filter_map(|(path, content)| {
+            let kind = match old.get(path) {
+                None => PathChangeKind::Added,
+                Some(previous) if previous != content => PathChangeKind::Modified,
+                Some(_) => return None,
+            };
+            Some(PathChange {
+                path: path.clone(),
+                kind,
+            })
+        })
+        .collect();
+    changes.extend(
+        old.keys()
+            .filter(|path| !new.contains_key(*path))
+            .map(|path| PathChange {
+                path: path.clone(),
+                kind: PathChangeKind::Deleted,
+            }),
+    );
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+fn unknown_revision(revision: &str) -> GitServiceError {
+    GitServiceError::InvalidRepository(format!("Unknown revision: {revision}"))
+}
+
+#[derive(Debug, Default)]
+pub struct FakeGitService {
+    repos: Mutex<HashMap<PathBuf, FakeRepo>>,
+}
+
+impl FakeGitService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `f` against the repository at `repo_path`. Unknown paths behave like
+    /// freshly initialized repositories on `main` without commits.
+    fn with_repo<T>(&self, repo_path: &Path, f: impl FnOnce(&mut FakeRepo) -> T) -> T {
+        let mut repos = self.repos.lock().unwrap_or_else(|e| e.into_inner());
+        f(repos.entry(repo_path.to_path_buf()).or_default())
+    }
+
+    /// Switch the current branch, e.g. to test behaviour off `main`
+    pub fn set_current_branch(&self, repo_path: &Path, branch: &str) {
+        self.with_repo(repo_path, |repo| repo.branch = branch.to_string());
+    }
+
+    /// Hashes of all commits, newest first
+    pub fn commit_hashes(&self, repo_path: &Path) -> Vec<String> {
+        self.with_repo(repo_path, |repo| {
+            repo.commits.iter().rev().map(|c| c.hash.clone()).collect()
+        })
+    }
+}
+
+impl GitBackend for FakeGitService {
+    fn initialize_repo_with_main_branch(&self, repo_path: &Path) -> Result<(), GitServiceError> {
+        std::fs::create_dir_all(repo_path)?;
+        let tree = snapshot(repo_path)?;
+        self.with_repo(repo_path, |repo| {
+            *repo = FakeRepo::default();
+            repo.push_commit("Initial commit", None, tree);
+        });
+        Ok(())
+    }
+
+    fn get_current_branch(&self, repo_path: &Path) -> Result<String, GitServiceError> {
+        Ok(self.with_repo(repo_path, |repo| repo.branch.clone()))
+    }
+
+    fn create_branch_at_head(&self, repo_path: &Path, branch: &str) -> Result<(), GitServiceError> {
+        self.set_current_branch(repo_path, branch);
+        Ok(())
+    }
+
+    fn commit_as(
+        &self,
+        repo_path: &Path,
+        message: &str,
+        identity: Option<&CommitIdentity>,
+    ) -> Result<bool, GitServiceError> {
+        let tree = snapshot(repo_path)?;
+        Ok(self.with_repo(repo_path, |repo| {
+            let unchanged = match repo.commits.last() {
+                Some(head) => head.tree == tree,
+                None => tree.is_empty(),
+            };
+            if !unchanged {
+                repo.push_commit(message, identity, tree);
+            }
+            !unchanged
+        }))
+    }
+
+    fn move_path(&self, repo_path: &Path, from: &str, to: &str) -> Result<(), GitServiceError> {
+        let destination = repo_path.join(to);
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::rename(repo_path.join(from), destination)?;
+        Ok(())
+    }
+
+    fn file_history(
+        &self,
+        repo_path: &Path,
+        path: &str,
+        limit: usize,
+    ) -> Result<Vec<FileCommit>, GitServiceError> {
+        Ok(self.with_repo(repo_path, |repo| {
+            touching(&repo.commits, path)
+                .into_iter()
+                .rev()
+                .take(limit)
+                .collect()
+        }))
+    }
+
+    fn last_commit_touching(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        path: &str,
+    ) -> Result<Option<FileCommit>, GitServiceError> {
+        self.with_repo(repo_path, |repo| {
+            let index = repo
+                .resolve(revision)
+                .ok_or_else(|| unknown_revision(revision))?;
+            Ok(touching(&repo.commits[..=index], path).pop())
+        })
+    }
+
+    fn recently_changed_paths(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        paths: &HashSet<String>,
+        limit: usize,
+    ) -> Result<Vec<(String, FileCommit)>, GitServiceError> {
+        self.with_repo(repo_path, |repo| {
+            let index = repo
+                .resolve(revision)
+                .ok_or_else(|| unknown_revision(revision))?;
+            let mut changed: Vec<(String, FileCommit)> = paths
+                .iter()
+                .filter_map(|path| {
+                    let commit = touching(&repo.commits[..=index], path).pop()?;
+                    Some((path.clone(), commit))
+                })
+                .collect();
+            changed.sort_by(|a, b| b.1.date.cmp(&a.1.date).then_with(|| a.0.cmp(&b.0)));
+            changed.truncate(limit);
+            Ok(changed)
+        })
+    }
+
+    fn resolve_revision(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+    ) -> Result<String, GitServiceError> {
+        self.with_repo(repo_path, |repo| {
+            let index = repo
+                .resolve(revision)
+                .ok_or_else(|| unknown_revision(revision))?;
+            Ok(repo.commits[index].hash.clone())
+        })
+    }
+
+    fn read_file_at_revision(
+        &self,
+        repo_path: &Path,
+        revision: &str,
+        path: &str,
+    ) -> Result<Option<(String, Vec<u8>)>, GitServiceError> {
+        self.with_repo(repo_path, |repo| {
+            let index = repo
+                .resolve(revision)
+                .ok_or_else(|| unknown_revision(revision))?;
+            let commit = &repo.commits[index];
+            Ok(commit
+                .tree
+                .get(path)
+                .map(|content| (commit.hash.clone(), content.clone())))
+        })
+    }
+
+    fn working_tree_changes(&self, repo_path: &Path) -> Result<Vec<PathChange>, GitServiceError> {
+        let tree = snapshot(repo_path)?;
+        Ok(self.with_repo(repo_path, |repo| {
+            let head = repo.commits.last().map(|c| &c.tree);
+            diff_trees(head.unwrap_or(&Tree::new()), &tree)
+        }))
+    }
+
+    fn changed_paths_between(
+        &self,
+        repo_path: &Path,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<PathChange>, GitServiceError> {
+        self.with_repo(repo_path, |repo| {
+            let from = repo.resolve(from).ok_or_else(|| unknown_revision(from))?;
+            let to = repo.resolve(to).ok_or_else(|| unknown_revision(to))?;
+            Ok(diff_trees(&repo.commits[from].tree, &repo.commits[to].tree))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn commits_are_deterministic() {
+        let histories: Vec<Vec<String>> = (0..2)
+            .map(|_| {
+                let dir = TempDir::new().unwrap();
+                let git = FakeGitService::new();
+                git.initialize_repo_with_main_branch(dir.path()).unwrap();
+                write(dir.path(), "docs/a.md", "one");
+                assert!(git.commit(dir.path(), "add a").unwrap());
+                assert!(!git.commit(dir.path(), "nothing changed").unwrap());
+                assert!(!dir.path().join(".git").exists());
+                git.commit_hashes(dir.path())
+            })
+            .collect();
+
+        assert_eq!(histories[0].len(), 2);
+        assert_eq!(histories[0], histories[1]);
+    }
+
+    #[test]
+    fn history_only_lists_commits_touching_the_file() {
+        let dir = TempDir::new().unwrap();
+        let git = FakeGitService::new();
+        git.initialize_repo_with_main_branch(dir.path()).unwrap();
+        write(dir.path(), "a.md", "one");
+        git.commit(dir.path(), "add a").unwrap();
+        write(dir.path(), "b.md", "other");
+        git.commit(dir.path(), "add b").unwrap();
+        write(dir.path(), "a.md", "two");
+        git.commit(dir.path(), "edit a").unwrap();
+
+        let history = git.file_history(dir.path(), "a.md", 10).unwrap();
+        let messages: Vec<_> = history.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(messages, ["edit a", "add a"]);
+        assert!(history[0].date > history[1].date);
+        assert_eq!(git.file_history(dir.path(), "a.md", 1).unwrap().len(), 1);
+
+        let last = |revision: &str, path: &str| {
+            git.last_commit_touching(dir.path(), revision, path)
+                .unwrap()
+                .map(|commit| commit.message)
+        };
+        assert_eq!(last("main", "a.md").as_deref(), Some("edit a"));
+        assert_eq!(last("HEAD~1", "a.md").as_deref(), Some("add a"));
+        assert_eq!(last("main", "c.md"), None);
+    }
+
+    #[test]
+    fn recently_changed_paths_are_newest_first() {
+        let dir = TempDir::new().unwrap();
+        let git = FakeGitService::new();
+        git.initialize_repo_with_main_branch(dir.path()).unwrap();
+        write(dir.path(), "a.md", "one");
+        write(dir.path(), "b.md", "one");
+        git.commit(dir.path(), "add a and b").unwrap();
+        write(dir.path(), "c.md", "one");
+        git.commit(dir.path(), "add c").unwrap();
+        write(dir.path(), "a.md", "two");
+        git.commit(dir.path(), "edit a").unwrap();
+
+        let paths: HashSet<String> = ["a.md", "b.md", "c.md", "new.md"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let recent = |limit| {
+            git.recently_changed_paths(dir.path(), "HEAD", &paths, limit)
+                .unwrap()
+                .into_iter()
+                .map(|(path, commit)| (path, commit.message))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            recent(10),
+            [
+                ("a.md".to_string(), "edit a".to_string()),
+                ("c.md".to_string(), "add c".to_string()),
+                ("b.md".to_string(), "add a and b".to_string()),
+            ]
+        );
+        assert_eq!(recent(1).len(), 1);
+    }
+
+    #[test]
+    fn history_reports_commit_identity() {
+        let dir = TempDir::new().unwrap();
+        let git = FakeGitService::new();
+        git.initialize_repo_with_main_branch(dir.path()).unwrap();
+        write(dir.path(), "a.md", "one");
+        git.commit(dir.path(), "add a").unwrap();
+        write(dir.path(), "a.md", "two");
+        let identity = CommitIdentity {
+            name: "Docs Bot".to_string(),
+            email: "docs@example.com".to_string(),
+        };
+        git.commit_as(dir.path(), "edit a", Some(&identity))
+            .unwrap();
+
+        let history = git.file_history(dir.path(), "a.md", 10).unwrap();
+        assert_eq!(history[0].author_name, "Docs Bot");
+        assert_eq!(history[0].author_email, "docs@example.com");
+        assert_eq!(history[1].author_name, FAKE_AUTHOR_NAME);
+    }
+
+    #[test]
+    fn reads_files_at_revisions() {
+        let dir = TempDir::new().unwrap();
+        let git = FakeGitService::new();
+        git.initialize_repo_with_main_branch(dir.path()).unwrap();
+        write(dir.path(), "a.md", "one");
+        git.commit(dir.path(), "add a").unwrap();
+        write(dir.path(), "a.md", "two");
+        git.commit(dir.path(), "edit a").unwrap();
+
+        let read = |revision: &str| {
+            git.read_file_at_revision(dir.path(), revision, "a.md")
+                .unwrap()
+                .map(|(_, bytes)| String::from_utf8(bytes).unwrap())
+        };
+        assert_eq!(read("HEAD").as_deref(), Some("two"));
+        assert_eq!(read("main~1").as_deref(), Some("one"));
+        assert_eq!(read("HEAD~2"), None);
+
+        let first = git.commit_hashes(dir.path())[1].clone();
+        assert_eq!(read(&first[..8]).as_deref(), Some("one"));
+        assert!(
+            git.read_file_at_revision(dir.path(), "nope", "a.md")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn new_branches_keep_uncommitted_changes() {
+        let dir = TempDir::new().unwrap();
+        let git = FakeGitService::new();
+        git.initialize_repo_with_main_branch(dir.path()).unwrap();
+        write(dir.path(), "a.md", "one");
+
+        git.create_branch_at_head(dir.path(), "docs/edit-a")
+            .unwrap();
+        assert_eq!(git.get_current_branch(dir.path()).unwrap(), "docs/edit-a");
+        assert_eq!(git.working_tree_changes(dir.path()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn reports_working_tree_changes() {
+        let dir = TempDir::new().unwrap();
+        let git = FakeGitService::new();
+        write(dir.path(), "a.md", "one");
+        write(dir.path(), "b.md", "two");
+        git.initialize_repo_with_main_branch(dir.path()).unwrap();
+        assert!(git.working_tree_changes(dir.path()).unwrap().is_empty());
+
+        write(dir.path(), "a.md", "changed");
+        std::fs::remove_file(dir.path().join("b.md")).unwrap();
+        write(dir.path(), "docs/c.md", "new");
+
+        let changes: Vec<_> = git
+            .working_tree_changes(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.path, c.kind))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("a.md".to_string(), PathChangeKind::Modified),
+                ("b.md".to_string(), PathChangeKind::Deleted),
+                ("docs/c.md".to_string(), PathChangeKind::Added),
+            ]
+        );
+    }
+}
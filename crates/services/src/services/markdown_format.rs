@@ -0,0 +1,854 @@
+//! Formatting markdown documents to a project's style: ATX or setext headings, tables
+//! with aligned columns and paragraphs wrapped at a line width. Front matter, code
+//! blocks, block quotes and HTML are kept as they are. Everything the formatter changes
+//! is also reported as a lint issue, so a document can be checked without rewriting it.
+
+use db::models::project::{DocumentFormatOptions, DocumentHeadingStyle};
+use serde::Serialize;
+use ts_rs::TS;
+use utils::front_matter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkdownLintRule {
+    HeadingStyle,
+    TableAlignment,
+    LineWidth,
+    TrailingWhitespace,
+    BlankLines,
+    FinalNewline,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct MarkdownLintIssue {
+    /// 1-based line of the unformatted document
+    pub line: usize,
+    pub rule: MarkdownLintRule,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct FormattedMarkdown {
+    pub content: String,
+    /// What was changed, in document order
+    pub issues: Vec<MarkdownLintIssue>,
+}
+
+#[derive(Clone, Copy)]
+enum Alignment {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+/// Byte length of the list marker (`-`, `*`, `+`, `1.` or `1)`) that `line` starts with
+fn list_marker(line: &str) -> Option<usize> {
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let length = if line.starts_with(['-', '*', '+']) {
+        1
+    } else if (1..=9).contains(&digits) && line[digits..].starts_with(['.', ')']) {
+        digits + 1
+    } else {
+        return None;
+    };
+    let rest = &line[length..];
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some(length)
+}
+
+/// Whether `line` opens an HTML block: a tag, comment or declaration, but not an autolink
+fn is_html(line: &str) -> bool {
+    let Some(rest) = line.strip_prefix('<') else {
+        return false;
+    };
+    if rest.starts_with(['!', '?']) {
+        return true;
+    }
+    let rest = rest.strip_prefix('/').unwrap_or(rest);
+    if !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return false;
+    }
+    let rest = rest.trim_start_matches(|c: char| c.is_ascii_alphanumeric() || c == '-');
+    rest.is_empty() || rest.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>')
+}
+
+fn is_link_definition(line: &str) -> bool {
+    line.strip_prefix('[')
+        .and_then(|rest| rest.find(']').map(|end| (rest, end)))
+        .is_some_and(|(rest, end)| end > 0 && rest[end + 1..].starts_with(':'))
+}
+
+fn is_delimiter_row(line: &str) -> bool {
+    let row = line.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let row = row.strip_suffix('|').unwrap_or(row);
+    row.split('|').all(|cell| {
+        let dashes = cell.trim();
+        let dashes = dashes.strip_prefix(':').unwrap_or(dashes);
+        let dashes = dashes.strip_suffix(':').unwrap_or(dashes);
+        !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+    })
+}
+
+/// Columns of leading whitespace, counting tabs as 4
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Columns `text` takes up in a monospace editor, where CJK characters are double width
+fn width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x20000..=0x3FFFD => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Level and text of an ATX heading (`## Title`)
+fn atx_heading(line: &str) -> Option<(usize, &str)> {
+    if indentation(line) > 3 {
+        return None;
+    }
+    let trimmed = line.trim_start();
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    let rest = &trimmed[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let text = rest.trim();
+    let text = match text.trim_end_matches('#') {
+        "" => "",
+        unclosed if unclosed.ends_with([' ', '\t']) => unclosed.trim_end(),
+        _ => text,
+    };
+    Some((level, text))
+}
+
+/// Level of the heading a setext underline (`===` or `---`) makes of the lines above it
+fn setext_level(line: &str) -> Option<usize> {
+    let trimmed = line.trim();
+    if indentation(line) > 3 || trimmed.is_empty() {
+        None
+    } else if trimmed.chars().all(|c| c == '=') {
+        Some(1)
+    } else if trimmed.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn fence(line: &str) -> Option<(char, usize)> {
+    if indentation(line) > 3 {
+        return None;
+    }
+    let trimmed = line.trim_start();
+    let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let length = trimmed.chars().take_while(|c| *c == marker).count();
+    (length >= 3).then_some((marker, length))
+}
+
+fn is_thematic_break(line: &str) -> bool {
+    let mut marks = line.chars().filter(|c| !c.is_whitespace());
+    let Some(mark) = marks.next().filter(|c| matches!(c, '-' | '*' | '_')) else {
+        return false;
+    };
+    let mut count = 1;
+    for c in marks {
+        if c != mark {
+            return false;
+        }
+        count += 1;
+    }
+    count >= 3
+}
+
+/// Whether `line` starts a block other than a paragraph, so it can't continue one
+fn starts_block(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    fence(trimmed).is_some()
+        || trimmed.starts_with(['>', '|'])
+        || atx_heading(trimmed).is_some()
+        || is_thematic_break(trimmed)
+        || list_marker(trimmed).is_some()
+        || is_html(trimmed)
+        || is_link_definition(trimmed)
+}
+
+/// Whether a wrapped line starting with `word` would turn into another block
+fn word_starts_block(word: &str) -> bool {
+    starts_block(&format!("{word} x")) || (word.chars().all(|c| matches!(c, '-' | '=' | '*' | '_')))
+}
+
+/// Cells of a table row. Pipes in cells must be escaped, also inside code spans.
+fn table_cells(line: &str) -> Vec<String> {
+    let row = line.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut ends_with_pipe = false;
+    let mut chars = row.chars();
+    while let Some(c) = chars.next() {
+        ends_with_pipe = c == '|';
+        match c {
+            '\\' => {
+                cell.push(c);
+                cell.extend(chars.next());
+            }
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            _ => cell.push(c),
+        }
+    }
+    if !ends_with_pipe {
+        cells.push(cell.trim().to_string());
+    }
+    cells
+}
+
+fn is_table_start(line: &str, next: Option<&str>) -> bool {
+    let Some(next) = next else {
+        return false;
+    };
+    let delimiter = next.trim();
+    line.contains('|')
+        && delimiter.contains('|')
+        && is_delimiter_row(delimiter)
+        && table_cells(line).len() == table_cells(next).len()
+}
+
+/// Join the words of `lines` back into lines of at most `line_width` characters. Hard
+/// line breaks are kept, and words that don't fit or that would start another block at
+/// the start of a line stay on the line they follow.
+fn wrap(lines: &[&str], first_prefix: &str, rest_prefix: &str, line_width: usize) -> Vec<String> {
+    let mut wrapped = Vec::new();
+    let mut current = first_prefix.to_string();
+    let mut has_words = false;
+    for (index, line) in lines.iter().enumerate() {
+        for word in line.split_whitespace() {
+            if !has_words {
+                current.push_str(word);
+            } else if width(&current) + 1 + width(word) > line_width && !word_starts_block(word) {
+                wrapped.push(std::mem::replace(
+                    &mut current,
+                    format!("{rest_prefix}{word}"),
+                ));
+            } else {
+                current.push(' ');
+                current.push_str(word);
+            }
+            has_words = true;
+        }
+        let hard_break = index + 1 < lines.len() && (line.ends_with("  ") || line.ends_with('\\'));
+        if hard_break && has_words {
+            if line.ends_with("  ") {
+                current.push_str("  ");
+            }
+            wrapped.push(std::mem::replace(&mut current, rest_prefix.to_string()));
+            has_words = false;
+        }
+    }
+    if has_words {
+        wrapped.push(current);
+    }
+    wrapped
+}
+
+struct Formatter<'a> {
+    options: &'a DocumentFormatOptions,
+    lines: Vec<&'a str>,
+    /// Line number of `lines[0]` in the document
+    first_line: usize,
+    /// A blank line may follow front matter
+    leading_blank_allowed: bool,
+    formatted: Vec<String>,
+    issues: Vec<MarkdownLintIssue>,
+}
+
+impl Formatter<'_> {
+    fn issue(&mut self, index: usize, rule: MarkdownLintRule, message: impl Into<String>) {
+        self.issues.push(MarkdownLintIssue {
+            line: self.first_line + index,
+            rule,
+            message: message.into(),
+        });
+    }
+
+    /// Output `formatted` for the lines starting at `start`, whose trimmed form is
+    /// `cleaned`. Trailing whitespace is reported per line, and `rule` if the lines
+    /// change otherwise.
+    fn emit(
+        &mut self,
+        start: usize,
+        cleaned: Vec<String>,
+        formatted: Vec<String>,
+        rule: MarkdownLintRule,
+        message: impl Into<String>,
+    ) {
+        for (offset, line) in cleaned.iter().enumerate() {
+            if *line != self.lines[start + offset] {
+                self.issue(
+                    start + offset,
+                    MarkdownLintRule::TrailingWhitespace,
+                    "Trailing whitespace",
+                );
+            }
+        }
+        if formatted != cleaned {
+            self.issue(start, rule, message);
+        }
+        self.formatted.extend(formatted);
+    }
+
+    fn verbatim(&mut self, start: usize, end: usize) -> usize {
+        self.formatted
+            .extend(self.lines[start..end].iter().map(|line| line.to_string()));
+        end
+    }
+
+    /// End of the run of lines from `start` up to the next blank line
+    fn until_blank(&self, start: usize) -> usize {
+        (start + 1..self.lines.len())
+            .find(|index| self.lines[*index].trim().is_empty())
+            .unwrap_or(self.lines.len())
+    }
+
+    /// Whether a heading can be written as setext without joining the line above
+    fn after_blank(&self) -> bool {
+        self.formatted.last().is_none_or(|line| line.is_empty())
+    }
+
+    fn heading_lines(&self, level: usize, text: &str) -> Vec<String> {
+        if self.options.heading_style == DocumentHeadingStyle::Setext
+            && level <= 2
+            && !text.is_empty()
+            && self.after_blank()
+        {
+            let underline = if level == 1 { "=" } else { "-" };
+            vec![text.to_string(), underline.repeat(width(text).max(3))]
+        } else if text.is_empty() {
+            vec!["#".repeat(level)]
+        } else {
+            vec![format!("{} {}", "#".repeat(level), text)]
+        }
+    }
+
+    fn heading_message(&self) -> String {
+        match self.options.heading_style {
+            DocumentHeadingStyle::Atx => "Heading should use the ATX style (`# Title`)",
+            DocumentHeadingStyle::Setext => "Heading should use the setext style",
+        }
+        .to_string()
+    }
+
+    fn atx(&mut self, index: usize) -> usize {
+        let (level, text) = atx_heading(self.lines[index]).unwrap_or_default();
+        let formatted = self.heading_lines(level, text);
+        let message = self.heading_message();
+        let cleaned = vec![self.lines[index].trim_end().to_string()];
+        self.emit(
+            index,
+            cleaned,
+            formatted,
+            MarkdownLintRule::HeadingStyle,
+            message,
+        );
+        index + 1
+    }
+
+    fn setext(&mut self, start: usize, underline: usize, level: usize) -> usize {
+        let text = self.lines[start..underline]
+            .iter()
+            .map(|line| line.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let cleaned: Vec<String> = self.lines[start..=underline]
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        let formatted = match self.options.heading_style {
+            // Keep the underline as written
+            DocumentHeadingStyle::Setext => vec![text, self.lines[underline].trim().to_string()],
+            DocumentHeadingStyle::Atx => self.heading_lines(level, &text),
+        };
+        let message = self.heading_message();
+        self.emit(
+            start,
+            cleaned,
+            formatted,
+            MarkdownLintRule::HeadingStyle,
+            message,
+        );
+        underline + 1
+    }
+
+    fn table(&mut self, start: usize) -> usize {
+        let end = (start + 2..self.lines.len())
+            .find(|index| {
+                let line = self.lines[*index];
+                line.trim().is_empty()
+                    || (starts_block(line) && !line.trim_start().starts_with('|'))
+            })
+            .unwrap_or(self.lines.len());
+        let cleaned: Vec<String> = self.lines[start..end]
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        if !self.options.align_tables {
+            self.emit(
+                start,
+                cleaned.clone(),
+                cleaned,
+                MarkdownLintRule::TableAlignment,
+                "",
+            );
+            return end;
+        }
+
+        let alignments: Vec<Alignment> = table_cells(self.lines[start + 1])
+            .iter()
+            .map(|cell| match (cell.starts_with(':'), cell.ends_with(':')) {
+                (true, true) => Alignment::Center,
+                (true, false) => Alignment::Left,
+                (false, true) => Alignment::Right,
+                (false, false) => Alignment::None,
+            })
+            .collect();
+        let rows: Vec<Vec<String>> = self.lines[start..end]
+            .iter()
+            .enumerate()
+            .filter(|(offset, _)| *offset != 1)
+            .map(|(_, line)| table_cells(line))
+            .collect();
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                rows.iter()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| width(cell))
+                    .max()
+                    .unwrap_or(0)
+                    .max(3)
+            })
+            .collect();
+        let alignment = |column: usize| alignments.get(column).copied().unwrap_or(Alignment::None);
+
+        let indent =
+            &self.lines[start][..self.lines[start].len() - self.lines[start].trim_start().len()];
+        let render = |cells: Vec<String>| format!("{indent}| {} |", cells.join(" | "));
+        let mut formatted = Vec::with_capacity(end - start);
+        for (row_index, row) in rows.iter().enumerate() {
+            let cells = (0..columns)
+                .map(|column| {
+                    let cell = row.get(column).map(String::as_str).unwrap_or("");
+                    let padding = widths[column] - width(cell);
+                    match alignment(column) {
+                        Alignment::Right => format!("{}{}", " ".repeat(padding), cell),
+                        Alignment::Center => format!(
+                            "{}{}{}",
+                            " ".repeat(padding / 2),
+                            cell,
+                            " ".repeat(padding - padding / 2)
+                        ),
+                        Alignment::None | Alignment::Left => {
+                            format!("{}{}", cell, " ".repeat(padding))
+                        }
+                    }
+                })
+                .collect();
+            formatted.push(render(cells));
+            if row_index == 0 {
+                let delimiters = (0..columns)
+                    .map(|column| {
+                        let dashes = widths[column];
+                        match alignment(column) {
+                            Alignment::None => "-".repeat(dashes),
+                            Alignment::Left => format!(":{}", "-".repeat(dashes - 1)),
+                            Alignment::Right => format!("{}:", "-".repeat(dashes - 1)),
+                            Alignment::Center => format!(":{}:", "-".repeat(dashes - 2)),
+                        }
+                    })
+                    .collect();
+                formatted.push(render(delimiters));
+            }
+        }
+        self.emit(
+            start,
+            cleaned,
+            formatted,
+            MarkdownLintRule::TableAlignment,
+            "Table columns aren't aligned",
+        );
+        end
+    }
+
+    /// Lines of a paragraph or list item with trailing whitespace removed, except for
+    /// the two spaces of hard line breaks
+    fn cleaned(&self, start: usize, end: usize) -> Vec<String> {
+        self.lines[start..end]
+            .iter()
+            .enumerate()
+            .map(|(offset, line)| {
+                if start + offset + 1 < end && line.ends_with("  ") {
+                    format!("{}  ", line.trim_end())
+                } else {
+                    line.trim_end().to_string()
+                }
+            })
+            .collect()
+    }
+
+    /// Emit the text of lines `start..end` wrapped behind the given prefixes, if a line
+    /// width is set
+    fn flow(
+        &mut self,
+        start: usize,
+        end: usize,
+        text: Vec<&str>,
+        first_prefix: &str,
+        rest_prefix: &str,
+    ) {
+        let cleaned = self.cleaned(start, end);
+        let (formatted, message) = match self.options.line_width {
+            Some(line_width) => (
+                wrap(&text, first_prefix, rest_prefix, line_width as usize),
+                format!("Text isn't wrapped at {} characters", line_width),
+            ),
+            None => (cleaned.clone(), String::new()),
+        };
+        self.emit(
+            start,
+            cleaned,
+            formatted,
+            MarkdownLintRule::LineWidth,
+            message,
+        );
+    }
+
+    fn list_item(&mut self, start: usize) -> usize {
+        let line = self.lines[start];
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let marker = list_marker(line.trim_start()).unwrap_or_default();
+        let spaces = |text: &str| text.len() - text.trim_start_matches(' ').len();
+        // Text is indented to the content of the item, past any task box
+        let content_indent = indent.len() + marker + spaces(&line[indent.len() + marker..]);
+        let mut text_start = content_indent;
+        if ["[ ] ", "[x] ", "[X] "]
+            .iter()
+            .any(|task_box| line[content_indent..].starts_with(task_box))
+        {
+            text_start += 3 + spaces(&line[content_indent + 3..]);
+        }
+        if content_indent == indent.len() + marker || line[text_start..].trim().is_empty() {
+            return self.verbatim(start, start + 1);
+        }
+
+        let end = (start + 1..self.lines.len())
+            .find(|index| {
+                let line = self.lines[*index];
+                line.trim().is_empty() || starts_block(line)
+            })
+            .unwrap_or(self.lines.len());
+        let rest_prefix = format!("{}{}", indent, " ".repeat(content_indent - indent.len()));
+        let mut text = vec![&line[text_start..]];
+        text.extend(
+            self.lines[start + 1..end]
+                .iter()
+                .map(|line| line.trim_start()),
+        );
+        self.flow(start, end, text, &line[..text_start], &rest_prefix);
+        end
+    }
+
+    fn paragraph(&mut self, start: usize) -> usize {
+        let mut end = start + 1;
+        while end < self.lines.len() {
+            let line = self.lines[end];
+            if let Some(level) = setext_level(line) {
+                return self.setext(start, end, level);
+            }
+            if line.trim().is_empty() || starts_block(line) {
+                break;
+            }
+            end += 1;
+        }
+
+        let first = self.lines[start];
+        let prefix = &first[..first.len() - first.trim_start().len()];
+        let text = self.lines[start..end]
+            .iter()
+            .map(|line| line.trim_start())
+            .collect();
+        self.flow(start, end, text, prefix, prefix);
+        end
+    }
+
+    fn blank(&mut self, index: usize) -> usize {
+        if !self.lines[index].is_empty() {
+            self.issue(
+                index,
+                MarkdownLintRule::TrailingWhitespace,
+                "Trailing whitespace",
+            );
+        }
+        let extra = match self.formatted.last() {
+            Some(previous) => previous.is_empty(),
+            None => !std::mem::take(&mut self.leading_blank_allowed),
+        };
+        if extra {
+            self.issue(
+                index,
+                MarkdownLintRule::BlankLines,
+                "Consecutive blank lines",
+            );
+        } else {
+            self.formatted.push(String::new());
+        }
+        index + 1
+    }
+
+    fn block(&mut self, start: usize) -> usize {
+        let line = self.lines[start];
+        let next = self.lines.get(start + 1).copied();
+        let trimmed = line.trim_start();
+        if line.trim().is_empty() {
+            self.blank(start)
+        } else if let Some((marker, length)) = fence(line) {
+            let end = (start + 1..self.lines.len())
+                .find(|index| {
+                    let closing = self.lines[*index].trim();
+                    closing.len() >= length && closing.chars().all(|c| c == marker)
+                })
+                .map_or(self.lines.len(), |closing| closing + 1);
+            self.verbatim(start, end)
+        } else if indentation(line) >= 4 {
+            // Indented code, up to the next line that isn't indented or blank
+            let end = (start + 1..self.lines.len())
+                .find(|index| {
+                    let line = self.lines[*index];
+                    !line.trim().is_empty() && indentation(line) < 4
+                })
+                .unwrap_or(self.lines.len());
+            let end = (start + 1..end)
+                .rev()
+                .find(|index| !self.lines[*index - 1].trim().is_empty())
+                .unwrap_or(start + 1);
+            self.verbatim(start, end)
+        } else if atx_heading(line).is_some() {
+            self.atx(start)
+        } else if is_table_start(line, next) {
+            self.table(start)
+        } else if trimmed.starts_with(['>', '|']) || is_html(trimmed) {
+            let end = self.until_blank(start);
+            self.verbatim(start, end)
+        } else if is_thematic_break(trimmed) || is_link_definition(trimmed) {
+            self.verbatim(start, start + 1)
+        } else if list_marker(trimmed).is_some() {
+            self.list_item(start)
+        } else {
+            self.paragraph(start)
+        }
+    }
+}
+
+/// Format a markdown document. Formatting a formatted document changes nothing.
+pub fn format(content: &str, options: &DocumentFormatOptions) -> FormattedMarkdown {
+    let body = front_matter::split(content).map_or(content, |(_, body)| body);
+    let front_matter = &content[..content.len() - body.len()];
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+
+    let mut formatter = Formatter {
+        options,
+        lines: body.lines().collect(),
+        first_line: front_matter.lines().count() + 1,
+        leading_blank_allowed: !front_matter.is_empty(),
+        formatted: Vec::new(),
+        issues: Vec::new(),
+    };
+    let mut index = 0;
+    while index < formatter.lines.len() {
+        index = formatter.block(index);
+    }
+
+    let mut formatted = std::mem::take(&mut formatter.formatted);
+    let mut trailing_blanks = false;
+    while formatted.last().is_some_and(|line| line.is_empty()) {
+        formatted.pop();
+        trailing_blanks = true;
+    }
+    if !body.is_empty() && (trailing_blanks || !body.ends_with('\n')) {
+        let last = formatter.lines.len().saturating_sub(1);
+        formatter.issue(
+            last,
+            MarkdownLintRule::FinalNewline,
+            "Document should end with a single newline",
+        );
+    }
+
+    let mut issues = formatter.issues;
+    issues.sort_by_key(|issue| issue.line);
+    let mut content = front_matter.to_string();
+    if !formatted.is_empty() {
+        content.push_str(&formatted.join(newline));
+        content.push_str(newline);
+    }
+    FormattedMarkdown { content, issues }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(
+        line_width: Option<u32>,
+        heading_style: DocumentHeadingStyle,
+    ) -> DocumentFormatOptions {
+        DocumentFormatOptions {
+            line_width,
+            heading_style,
+            align_tables: true,
+        }
+    }
+
+    fn rules(formatted: &FormattedMarkdown) -> Vec<(usize, MarkdownLintRule)> {
+        formatted
+            .issues
+            .iter()
+            .map(|issue| (issue.line, issue.rule))
+            .collect()
+    }
+
+    #[test]
+    fn converts_heading_styles() {
+        let atx = options(None, DocumentHeadingStyle::Atx);
+        let formatted = format("Title\n=====\n\nSection  \n---\n\n###  Details ##\n", &atx);
+        assert_eq!(formatted.content, "# Title\n\n## Section\n\n### Details\n");
+        assert_eq!(
+            rules(&formatted),
+            vec![
+                (1, MarkdownLintRule::HeadingStyle),
+                (4, MarkdownLintRule::TrailingWhitespace),
+                (4, MarkdownLintRule::HeadingStyle),
+                (7, MarkdownLintRule::HeadingStyle),
+            ]
+        );
+
+        let setext = options(None, DocumentHeadingStyle::Setext);
+        let formatted = format("# Title\n\n## Section\n### Details\n", &setext);
+        assert_eq!(
+            formatted.content,
+            "Title\n=====\n\nSection\n-------\n### Details\n"
+        );
+    }
+
+    #[test]
+    fn aligns_tables() {
+        let content = "| Name | Size |\n|:-|-:|\n| a.md | 10 |\n| notes.md | 2048 |\n";
+        let formatted = format(content, &options(None, DocumentHeadingStyle::Atx));
+        assert_eq!(
+            formatted.content,
+            "| Name     | Size |\n\
+             | :------- | ---: |\n\
+             | a.md     |   10 |\n\
+             | notes.md | 2048 |\n"
+        );
+        assert_eq!(
+            rules(&formatted),
+            vec![(1, MarkdownLintRule::TableAlignment)]
+        );
+
+        let wide = format(
+            "| 변수 | 설명 |\n|---|---|\n| `HOST` | 호스트 |\n",
+            &options(None, DocumentHeadingStyle::Atx),
+        );
+        assert_eq!(
+            wide.content,
+            "| 변수   | 설명   |\n| ------ | ------ |\n| `HOST` | 호스트 |\n"
+        );
+
+        let unaligned = DocumentFormatOptions {
+            align_tables: false,
+            ..DocumentFormatOptions::default()
+        };
+        assert_eq!(format(content, &unaligned).content, content);
+    }
+
+    #[test]
+    fn wraps_paragraphs_and_list_items() {
+        let content = "A paragraph that is much too long for the configured line width of \
+                       thirty.\n\n- a list item that also runs long\n  - nested item\n\n\
+                       ```\na code line that is long enough to wrap but must not be\n```\n";
+        let formatted = format(content, &options(Some(30), DocumentHeadingStyle::Atx));
+        assert_eq!(
+            formatted.content,
+            "A paragraph that is much too\n\
+             long for the configured line\n\
+             width of thirty.\n\
+             \n\
+             - a list item that also runs\n  long\n\
+             \x20 - nested item\n\
+             \n\
+             ```\na code line that is long enough to wrap but must not be\n```\n"
+        );
+
+        let rewrapped = format(
+            &formatted.content,
+            &options(Some(30), DocumentHeadingStyle::Atx),
+        );
+        assert_eq!(rewrapped.content, formatted.content);
+        assert!(rewrapped.issues.is_empty());
+    }
+
+    #[test]
+    fn keeps_hard_breaks_and_block_markers_in_place() {
+        let content = "Roses are red  \nviolets are blue\nand the answer is\n- not a list\n";
+        let formatted = format(content, &options(Some(20), DocumentHeadingStyle::Atx));
+        assert_eq!(
+            formatted.content,
+            "Roses are red  \nviolets are blue and\nthe answer is\n- not a list\n"
+        );
+
+        let content = "Wrap before a dash - then continue the sentence\n";
+        let formatted = format(content, &options(Some(19), DocumentHeadingStyle::Atx));
+        assert_eq!(
+            formatted.content,
+            "Wrap before a dash -\nthen continue the\nsentence\n"
+        );
+    }
+
+    #[test]
+    fn reports_whitespace_and_keeps_front_matter() {
+        let content = "---\ntitle: Notes\n---\n\nText \n\n\n\nMore\n\n";
+        let formatted = format(content, &DocumentFormatOptions::default());
+        assert_eq!(
+            formatted.content,
+            "---\ntitle: Notes\n---\n\nText\n\nMore\n"
+        );
+        assert_eq!(
+            rules(&formatted),
+            vec![
+                (5, MarkdownLintRule::TrailingWhitespace),
+                (7, MarkdownLintRule::BlankLines),
+                (8, MarkdownLintRule::BlankLines),
+                (10, MarkdownLintRule::FinalNewline),
+            ]
+        );
+
+        let clean = format(&formatted.content, &DocumentFormatOptions::default());
+        assert!(clean.issues.is_empty());
+    }
+}
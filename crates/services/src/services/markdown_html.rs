@@ -0,0 +1,562 @@
+//! Server-side rendering of markdown documents to sanitized HTML and heading outlines,
+//! for clients that embed documents without shipping a markdown renderer.
+
+use std::{collections::HashMap, sync::OnceLock};
+
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd, html};
+use regex::Regex;
+use serde::Serialize;
+use syntect::{
+    highlighting::ThemeSet,
+    html::{ClassStyle, ClassedHTMLGenerator, css_for_theme_with_class_style},
+    parsing::SyntaxSet,
+    util::LinesWithEndings,
+};
+use ts_rs::TS;
+use url::Url;
+use utils::{board_links, front_matter};
+
+/// Highlighted code is marked up with `hl-` prefixed classes, styled by [`highlight_css`]
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+const THEME: &str = "InspiredGitHub";
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+/// A heading of a markdown document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct DocumentHeading {
+    /// 1 for `#` up to 6 for `######`
+    pub level: u8,
+    pub text: String,
+    /// Id of the heading in rendered HTML, unique within the document
+    pub anchor: String,
+    /// 1-based line of the heading in the document
+    pub line: u32,
+}
+
+fn parser_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES
+}
+
+/// The document without its front matter, and the number of lines the front matter took
+fn markdown_body(document: &str) -> (&str, usize) {
+    match front_matter::split(document) {
+        Some((_, body)) => {
+            let skipped = &document[..document.len() - body.len()];
+            (body, skipped.matches('\n').count())
+        }
+        None => (document, 0),
+    }
+}
+
+/// Anchor for heading `text` in the style of GitHub: lowercase words joined by dashes,
+/// numbered when the same heading occurs again
+fn heading_anchor(text: &str, seen: &mut HashMap<String, usize>) -> String {
+    let words: String = text
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace() || *c == '-')
+        .collect();
+    let mut base = words.split_whitespace().collect::<Vec<_>>().join("-");
+    if base.is_empty() {
+        base = "section".to_string();
+    }
+    let count = seen.entry(base.clone()).or_insert(0);
+    *count += 1;
+    match *count {
+        1 => base,
+        n => format!("{}-{}", base, n - 1),
+    }
+}
+
+/// Headings of a markdown document in order, skipping its front matter
+pub fn outline(document: &str) -> Vec<DocumentHeading> {
+    let (body, skipped_lines) = markdown_body(document);
+    let mut headings = Vec::new();
+    let mut seen = HashMap::new();
+    // Level, line and text of the heading being read
+    let mut current: Option<(u8, u32, String)> = None;
+
+    for (event, range) in Parser::new_ext(body, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                let line = body[..range.start].matches('\n').count() + skipped_lines + 1;
+                current = Some((level as u8, line as u32, String::new()));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, heading)) = &mut current {
+                    heading.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((level, line, text)) = current.take() {
+                    let text = text.trim().to_string();
+                    headings.push(DocumentHeading {
+                        level,
+                        anchor: heading_anchor(&text, &mut seen),
+                        text,
+                        line,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    headings
+}
+
+/// Size of the text of a document
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, TS)]
+pub struct TextStats {
+    pub words: usize,
+    /// Characters of the words, not counting whitespace
+    pub characters: usize,
+    pub headings: usize,
+}
+
+impl TextStats {
+    /// Stats of text without markup, which has no headings
+    pub fn of_plain_text(text: &str) -> Self {
+        let mut stats = Self::default();
+        for word in text.split_whitespace() {
+            stats.words += 1;
+            stats.characters += word.chars().count();
+        }
+        stats
+    }
+}
+
+/// Size of the text of a markdown document, leaving out its front matter, markup and
+/// code blocks
+pub fn text_stats(document: &str) -> TextStats {
+    let (body, _) = markdown_body(document);
+    let mut text = String::new();
+    let mut headings = 0;
+    let mut in_code_block = false;
+
+    for event in Parser::new_ext(body, parser_options()) {
+        match event {
+            Event::Start(Tag::Heading { .. }) => headings += 1,
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Text(chunk) | Event::Code(chunk) if !in_code_block => text.push_str(&chunk),
+            // Words don't run on across lines or blocks
+            Event::SoftBreak
+            | Event::HardBreak
+            | Event::End(
+                TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item | TagEnd::TableCell,
+            ) => text.push(' '),
+            _ => {}
+        }
+    }
+    TextStats {
+        headings,
+        ..TextStats::of_plain_text(&text)
+    }
+}
+
+/// Targets of the relative links of a markdown document, resolved against the folder
+/// of `document_path`, in order and without duplicates. Links leaving the repository
+/// are skipped.
+pub fn relative_links(document: &str, document_path: &str) -> Vec<String> {
+    let (body, _) = markdown_body(document);
+    let mut targets: Vec<String> = Vec::new();
+    for event in Parser::new_ext(body, parser_options()) {
+        let (Event::Start(Tag::Link { dest_url, .. }) | Event::Start(Tag::Image { dest_url, .. })) =
+            event
+        else {
+            continue;
+        };
+        if dest_url.is_empty()
+            || dest_url.starts_with(['#', '/', '?'])
+            || Url::parse(&dest_url).is_ok()
+        {
+            continue;
+        }
+        let target = dest_url.split(['#', '?']).next().unwrap_or_default();
+        if let Some(target) = resolve_relative(document_path, target)
+            && !targets.contains(&target)
+        {
+            targets.push(target);
+        }
+    }
+    targets
+}
+
+fn wiki_link_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\[\[([^\[\]\n]+)\]\]").unwrap())
+}
+
+/// Targets of the `[[wiki-links]]` of a markdown document as written, without their
+/// `|label` or `#heading`, in order and without duplicates. Links in code are skipped.
+pub fn wiki_links(document: &str) -> Vec<String> {
+    let (body, _) = markdown_body(document);
+    let mut targets: Vec<String> = Vec::new();
+    let mut collect = |text: &str| {
+        for captures in wiki_link_pattern().captures_iter(text) {
+            let target = captures[1]
+                .split(['|', '#'])
+                .next()
+                .unwrap_or_default()
+                .trim();
+            if !target.is_empty() && !targets.iter().any(|seen| seen == target) {
+                targets.push(target.to_string());
+            }
+        }
+    };
+    // The parser hands out brackets as separate text events, so join adjacent ones
+    let mut text = String::new();
+    let mut in_code_block = false;
+    for event in Parser::new_ext(body, parser_options()) {
+        match event {
+            Event::Text(fragment) if !in_code_block => text.push_str(&fragment),
+            event => {
+                collect(&text);
+                text.clear();
+                match event {
+                    Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+                    Event::End(TagEnd::CodeBlock) => in_code_block = false,
+                    _ => {}
+                }
+            }
+        }
+    }
+    collect(&text);
+    targets
+}
+
+/// Stylesheet for the classes of highlighted code blocks
+pub fn highlight_css() -> &'static str {
+    static CSS: OnceLock<String> = OnceLock::new();
+    CSS.get_or_init(|| {
+        let themes = ThemeSet::load_defaults();
+        themes
+            .themes
+            .get(THEME)
+            .and_then(|theme| css_for_theme_with_class_style(theme, CLASS_STYLE).ok())
+            .unwrap_or_default()
+    })
+}
+
+/// Render a markdown document to sanitized HTML, leaving out its front matter. Headings
+/// get the anchors of [`outline`] as ids. Relative links are resolved against the folder
+/// of `document_path` and passed to `document_url`, which returns the app URL of the
+/// target if it is a document. `vk://` links are passed to `board_url` the same way and
+/// lead nowhere if their target doesn't exist. Other links are kept as written.
+pub fn render(
+    document: &str,
+    document_path: &str,
+    document_url: impl Fn(&str) -> Option<String>,
+    board_url: impl Fn(&str) -> Option<String>,
+) -> String {
+    let (markdown, _) = markdown_body(document);
+    let mut anchors = outline(document).into_iter().map(|heading| heading.anchor);
+    let mut events = Vec::new();
+    // Language and text of the code block being read
+    let mut code_block: Option<(String, String)> = None;
+
+    for event in Parser::new_ext(markdown, parser_options()) {
+        match event {
+            Event::Start(Tag::Heading {
+                level,
+                id: _,
+                classes,
+                attrs,
+            }) => {
+                events.push(Event::Start(Tag::Heading {
+                    level,
+                    id: anchors.next().map(Into::into),
+                    classes,
+                    attrs,
+                }));
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split_whitespace()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code_block = Some((language, String::new()));
+            }
+            Event::Text(text) if code_block.is_some() => {
+                if let Some((_, code)) = &mut code_block {
+                    code.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((language, code)) = code_block.take() {
+                    events.push(Event::Html(highlight(&code, &language).into()));
+                }
+            }
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => {
+                let (dest_url, title) = if dest_url.starts_with(board_links::SCHEME) {
+                    match board_url(&dest_url) {
+                        Some(url) => (url.into(), title),
+                        None => ("".into(), "Broken link".into()),
+                    }
+                } else {
+                    let dest_url = rewrite_link(&dest_url, document_path, &document_url)
+                        .map(Into::into)
+                        .unwrap_or(dest_url);
+                    (dest_url, title)
+                };
+                events.push(Event::Start(Tag::Link {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }));
+            }
+            event => events.push(event),
+        }
+    }
+
+    let mut unsafe_html = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, events.into_iter());
+    sanitize(&unsafe_html)
+}
+
+fn sanitize(html: &str) -> String {
+    ammonia::Builder::default()
+        .add_tags(["input"])
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        .add_tag_attributes("pre", ["class"])
+        .add_tag_attributes("code", ["class"])
+        .add_tag_attributes("span", ["class"])
+        .add_tag_attributes("h1", ["id"])
+        .add_tag_attributes("h2", ["id"])
+        .add_tag_attributes("h3", ["id"])
+        .add_tag_attributes("h4", ["id"])
+        .add_tag_attributes("h5", ["id"])
+        .add_tag_attributes("h6", ["id"])
+        // Task list checkboxes are the only inputs documents may contain
+        .attribute_filter(|element, attribute, value| match (element, attribute) {
+            ("input", "type") if value != "checkbox" => None,
+            _ => Some(value.into()),
+        })
+        .clean(html)
+        .to_string()
+}
+
+fn highlight(code: &str, language: &str) -> String {
+    let syntaxes = syntax_set();
+    let syntax = syntaxes
+        .find_syntax_by_token(language)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, syntaxes, CLASS_STYLE);
+    for line in LinesWithEndings::from(code) {
+        if generator
+            .parse_html_for_line_which_includes_newline(line)
+            .is_err()
+        {
+            return format!("<pre><code>{}</code></pre>", escape_html(code));
+        }
+    }
+    format!(
+        "<pre class=\"hl-code\"><code>{}</code></pre>",
+        generator.finalize()
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// App URL for a relative link to another document, keeping its `#fragment`
+fn rewrite_link(
+    href: &str,
+    document_path: &str,
+    document_url: &impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    if href.is_empty() || href.starts_with(['#', '/', '?']) || Url::parse(href).is_ok() {
+        return None;
+    }
+    let (target, fragment) = match href.split_once('#') {
+        Some((target, fragment)) => (target, Some(fragment)),
+        None => (href, None),
+    };
+    let target = target.split('?').next().unwrap_or_default();
+    let url = document_url(&resolve_relative(document_path, target)?)?;
+    Some(match fragment {
+        Some(fragment) => format!("{}#{}", url, fragment),
+        None => url,
+    })
+}
+
+/// Resolve `target` against the folder of `document_path`. Returns `None` if it leaves
+/// the repository.
+fn resolve_relative(document_path: &str, target: &str) -> Option<String> {
+    let mut segments: Vec<&str> = document_path.split('/').collect();
+    segments.pop();
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    (!segments.is_empty()).then(|| segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document_url(path: &str) -> Option<String> {
+        path.ends_with(".md")
+            .then(|| format!("/projects/p/documents?path={}", path))
+    }
+
+    #[test]
+    fn rewrites_relative_links_to_documents() {
+        let html = render(
+            "[spec](../specs/pay.md#flow) [code](main.rs) [site](https://example.com) \
+             [top](#intro) [out](../../../x.md) [task](vk://task/1) [gone](vk://task/2)",
+            "docs/guides/setup.md",
+            document_url,
+            |href| (href == "vk://task/1").then(|| "/projects/p/tasks/1".to_string()),
+        );
+
+        assert!(html.contains("href=\"/projects/p/documents?path=docs/specs/pay.md#flow\""));
+        assert!(html.contains("href=\"main.rs\""));
+        assert!(html.contains("href=\"https://example.com\""));
+        assert!(html.contains("href=\"#intro\""));
+        assert!(html.contains("href=\"../../../x.md\""));
+        assert!(html.contains("href=\"/projects/p/tasks/1\""));
+        assert!(!html.contains("vk://"));
+    }
+
+    #[test]
+    fn collects_relative_link_targets() {
+        let document = "---\ntitle: Design\n---\n\
+                        See [server](../../crates/server/src/main.rs#L10), \
+                        [again](../../crates/server/src/main.rs) \
+                        and [routes](../../crates/server/).\n\
+                        [spec](pay.md) [site](https://example.com) \
+                        [top](#intro) [out](../../../x)\n";
+
+        assert_eq!(
+            relative_links(document, "docs/design/arch.md"),
+            vec![
+                "crates/server/src/main.rs".to_string(),
+                "crates/server".to_string(),
+                "docs/design/pay.md".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn collects_wiki_link_targets() {
+        let document = "See [[Roadmap]], [[design/Payments|payments]] and \
+                        [[Roadmap#Q3]].\n\n\
+                        `[[Not a link]]`\n\n\
+                        ```\n[[Nor this]]\n```\n\n\
+                        - [[ Glossary ]] and [[]]\n";
+
+        assert_eq!(
+            wiki_links(document),
+            vec![
+                "Roadmap".to_string(),
+                "design/Payments".to_string(),
+                "Glossary".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn sanitizes_and_highlights() {
+        let html = render(
+            "<script>alert(1)</script><img src=x onerror=alert(1)>\n\n\
+             - [x] done\n\n\
+             ```rust\nfn main() {}\n```\n",
+            "README.md",
+            document_url,
+            |_| None,
+        );
+
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("onerror"));
+        assert!(html.contains("type=\"checkbox\""));
+        assert!(html.contains("<pre class=\"hl-code\">"));
+        assert!(html.contains("<span class=\"hl-"));
+        assert!(highlight_css().contains(".hl-"));
+    }
+
+    #[test]
+    fn outlines_headings_after_front_matter() {
+        let document = "---\ntitle: Plan\n---\n# Payment `flow`\n\nText\n\n## Steps\n\
+                        ```\n# not a heading\n```\nSteps\n---\n";
+        let headings = outline(document);
+
+        assert_eq!(
+            headings,
+            vec![
+                DocumentHeading {
+                    level: 1,
+                    text: "Payment flow".to_string(),
+                    anchor: "payment-flow".to_string(),
+                    line: 4,
+                },
+                DocumentHeading {
+                    level: 2,
+                    text: "Steps".to_string(),
+                    anchor: "steps".to_string(),
+                    line: 8,
+                },
+                DocumentHeading {
+                    level: 2,
+                    text: "Steps".to_string(),
+                    anchor: "steps-1".to_string(),
+                    line: 12,
+                },
+            ]
+        );
+        assert!(render(document, "plan.md", |_| None, |_| None).contains("<h2 id=\"steps-1\">"));
+    }
+
+    #[test]
+    fn counts_words_of_the_text_only() {
+        let document = "---\ntitle: Plan\n---\n# Payment `flow`\n\nThe *new* checkout\nworks.\n\n\
+                        ```\nlet code = 1;\n```\n- one\n- two\n";
+        assert_eq!(
+            text_stats(document),
+            TextStats {
+                words: 8,
+                characters: 37,
+                headings: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn counts_plain_text_words() {
+        assert_eq!(
+            TextStats::of_plain_text("  key: value\n\tnext  "),
+            TextStats {
+                words: 3,
+                characters: 13,
+                headings: 0,
+            }
+        );
+    }
+}
@@ -1,25 +1,61 @@
+pub mod access_report;
+pub mod action_items;
 pub mod analytics;
+pub mod analytics_overview;
 pub mod approvals;
 pub mod auth;
+pub mod commit_template;
+pub mod compliance_export;
 pub mod config;
 pub mod container;
+pub mod demo;
+pub mod dependency_updates;
 pub mod diff_stream;
+pub mod document_annotations;
+pub mod document_assets;
+pub mod document_encryption;
+pub mod document_import;
+pub mod document_index;
+pub mod document_links;
+pub mod document_store;
+pub mod document_templates;
+pub mod e2e_tests;
+pub mod error_reports;
 pub mod events;
 pub mod file_ranker;
 pub mod file_search;
 pub mod filesystem;
 pub mod filesystem_watcher;
+pub mod flaky_tests;
 pub mod git;
 pub mod git_host;
 pub mod image;
+pub mod markdown_format;
+pub mod markdown_html;
+pub mod monorepo;
 pub mod notification;
 pub mod oauth_credentials;
 pub mod pr_monitor;
+pub mod preview_build;
+pub mod preview_comparison;
+pub mod preview_screenshot;
 pub mod project;
+pub mod project_quotas;
 #[cfg(feature = "qa-mode")]
 pub mod qa_repos;
 pub mod queued_message;
+pub mod remote_breaker;
 pub mod remote_client;
 pub mod repo;
+pub mod retention;
+pub mod self_update;
+pub mod sql_console;
+pub mod swimlanes;
+pub mod task_aging;
+pub mod task_documents;
+pub mod telemetry;
+pub mod user_data;
+pub mod webhooks;
+pub mod workers;
 pub mod workspace_manager;
 pub mod worktree_manager;
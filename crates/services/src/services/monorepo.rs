@@ -0,0 +1,525 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Tool that owns a workspace package manifest
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    Cargo,
+    Pnpm,
+    Yarn,
+    Npm,
+    Go,
+}
+
+/// A package discovered inside a repository workspace
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct WorkspacePackage {
+    /// Package name from its manifest (falls back to the directory name)
+    pub name: String,
+    /// Path relative to the repository root, using `/` separators
+    pub path: String,
+    pub manager: PackageManager,
+    /// Commands that are relevant when working inside this package
+    pub scripts: Vec<String>,
+}
+
+/// Detect workspace packages declared in the repository root.
+///
+/// Supports Cargo workspaces, pnpm workspaces, yarn/npm `workspaces` in
+/// `package.json`, and Go workspaces (`go.work`). Repositories that are not
+/// monorepos return an empty list.
+pub fn detect_packages(repo_root: &Path) -> Vec<WorkspacePackage> {
+    let mut packages = Vec::new();
+    packages.extend(detect_cargo_packages(repo_root));
+    packages.extend(detect_js_packages(repo_root));
+    packages.extend(detect_go_packages(repo_root));
+    packages.sort_by(|a, b| a.path.cmp(&b.path).then(a.name.cmp(&b.name)));
+    packages.dedup_by(|a, b| a.path == b.path && a.manager == b.manager);
+    packages
+}
+
+/// Find the package that contains `relative_path` (the deepest match wins)
+pub fn find_package_for_path<'a>(
+    packages: &'a [WorkspacePackage],
+    relative_path: &str,
+) -> Option<&'a WorkspacePackage> {
+    let relative_path = relative_path.trim_start_matches("./").trim_end_matches('/');
+    packages
+        .iter()
+        .filter(|p| relative_path == p.path || relative_path.starts_with(&format!("{}/", p.path)))
+        .max_by_key(|p| p.path.len())
+}
+
+/// Prompt section telling the coding agent which package a task is scoped to
+pub fn package_scope_prompt(
+    repo_name: &str,
+    package_name: &str,
+    package_path: &str,
+    scripts: &[String],
+) -> String {
+    let mut section = format!(
+        "\n\nThis task is scoped to the package `{package_name}` at `{repo_name}/{package_path}`. Keep changes within this package unless the task requires otherwise."
+    );
+    if !scripts.is_empty() {
+        section.push_str("\nRelevant commands for this package:");
+        for script in scripts {
+            section.push_str(&format!("\n- `{script}`"));
+        }
+    }
+    section
+}
+
+fn detect_cargo_packages(repo_root: &Path) -> Vec<WorkspacePackage> {
+    let Some(manifest) = read_toml(&repo_root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Some(members) = manifest
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array())
+    else {
+        return Vec::new();
+    };
+
+    let patterns: Vec<&str> = members.iter().filter_map(|m| m.as_str()).collect();
+    expand_member_patterns(repo_root, &patterns)
+        .into_iter()
+        .filter_map(|dir| {
+            let package_manifest = read_toml(&dir.join("Cargo.toml"))?;
+            let name = package_manifest
+                .get("package")
+                .and_then(|p| p.get("name"))
+                .and_then(|n| n.as_str())
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| dir_name(&dir));
+            Some(WorkspacePackage {
+                scripts: vec![
+                    format!("cargo build -p {name}"),
+                    format!("cargo test -p {name}"),
+                    format!("cargo clippy -p {name}"),
+                ],
+                name,
+                path: relative_to(repo_root, &dir)?,
+                manager: PackageManager::Cargo,
+            })
+        })
+        .collect()
+}
+
+fn detect_js_packages(repo_root: &Path) -> Vec<WorkspacePackage> {
+    let root_package_json = read_json(&repo_root.join("package.json"));
+
+    let (manager, patterns) = if let Some(patterns) =
+        read_pnpm_workspace_patterns(&repo_root.join("pnpm-workspace.yaml"))
+    {
+        (PackageManager::Pnpm, patterns)
+    } else if let Some(patterns) = root_package_json.as_ref().and_then(package_json_workspaces) {
+        let manager = if repo_root.join("yarn.lock").exists() {
+            PackageManager::Yarn
+        } else {
+            PackageManager::Npm
+        };
+        (manager, patterns)
+    } else {
+        return Vec::new();
+    };
+
+    let (includes, excludes): (Vec<&str>, Vec<&str>) = patterns
+        .iter()
+        .map(|p| p.as_str())
+        .partition(|p| !p.starts_with('!'));
+    let excludes: Vec<&str> = excludes.iter().map(|p| &p[1..]).collect();
+    let excluded = expand_member_patterns(repo_root, &excludes);
+
+    expand_member_patterns(repo_root, &includes)
+        .into_iter()
+        .filter(|dir| !excluded.contains(dir))
+        .filter_map(|dir| {
+            let package_json = read_json(&dir.join("package.json"))?;
+            let name = package_json
+                .get("name")
+                .and_then(|n| n.as_str())
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| dir_name(&dir));
+            let scripts = package_json
+                .get("scripts")
+                .and_then(|s| s.as_object())
+                .map(|scripts| {
+                    scripts
+                        .keys()
+                        .map(|script| js_script_command(manager, &name, script))
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(WorkspacePackage {
+                name,
+                path: relative_to(repo_root, &dir)?,
+                manager,
+                scripts,
+            })
+        })
+        .collect()
+}
+
+fn detect_go_packages(repo_root: &Path) -> Vec<WorkspacePackage> {
+    let Ok(go_work) = std::fs::read_to_string(repo_root.join("go.work")) else {
+        return Vec::new();
+    };
+
+    parse_go_work_uses(&go_work)
+        .into_iter()
+        .filter_map(|use_path| {
+            let use_path = match use_path.trim_start_matches("./") {
+                "." => "",
+                path => path,
+            };
+            if use_path.contains("..") {
+                return None;
+            }
+            let dir = repo_root.join(use_path);
+            let go_mod = std::fs::read_to_string(dir.join("go.mod")).ok()?;
+            let name = go_mod
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("module "))
+                .map(|m| m.trim().to_string())
+                .unwrap_or_else(|| dir_name(&dir));
+            let path = relative_to(repo_root, &dir)?;
+            let target = if path.is_empty() {
+                "./...".to_string()
+            } else {
+                format!("./{path}/...")
+            };
+            Some(WorkspacePackage {
+                name,
+                scripts: vec![format!("go build {target}"), format!("go test {target}")],
+                path,
+                manager: PackageManager::Go,
+            })
+        })
+        .collect()
+}
+
+fn js_script_command(manager: PackageManager, package: &str, script: &str) -> String {
+    match manager {
+        PackageManager::Pnpm => format!("pnpm --filter {package} run {script}"),
+        PackageManager::Yarn => format!("yarn workspace {package} run {script}"),
+        _ => format!("npm run {script} --workspace={package}"),
+    }
+}
+
+/// Read `packages:` entries from a pnpm-workspace.yaml file.
+///
+/// Only the simple block-list form is supported, which is what pnpm documents.
+fn read_pnpm_workspace_patterns(path: &Path) -> Option<Vec<String>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    Some(parse_pnpm_workspace_patterns(&content))
+}
+
+fn parse_pnpm_workspace_patterns(content: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(' ') && !line.starts_with('-') {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if in_packages && let Some(item) = trimmed.strip_prefix('-') {
+            let item = item.trim().trim_matches(|c| c == '"' || c == '\'');
+            if !item.is_empty() {
+                patterns.push(item.to_string());
+            }
+        }
+    }
+    patterns
+}
+
+fn package_json_workspaces(package_json: &serde_json::Value) -> Option<Vec<String>> {
+    let workspaces = package_json.get("workspaces")?;
+    // Either `"workspaces": [...]` or `"workspaces": { "packages": [...] }`
+    let list = workspaces
+        .as_array()
+        .or_else(|| workspaces.get("packages").and_then(|p| p.as_array()))?;
+    Some(
+        list.iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect(),
+    )
+}
+
+fn parse_go_work_uses(content: &str) -> Vec<String> {
+    let mut uses = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                uses.push(line.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                uses.push(rest.to_string());
+            }
+        }
+    }
+    uses
+}
+
+/// Dependency and build output folders, never workspace members
+const EXCLUDED_DIRS: [&str; 2] = ["node_modules", "target"];
+
+/// Expand workspace member patterns such as `crates/*` or `packages/**` into
+/// existing directories. Only trailing `*`/`**` wildcards are supported.
+fn expand_member_patterns(repo_root: &Path, patterns: &[&str]) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        if pattern.contains("..") {
+            continue;
+        }
+        if let Some(prefix) = pattern.strip_suffix("/**") {
+            collect_dirs_recursive(&repo_root.join(prefix), &mut dirs);
+        } else if let Some(prefix) = pattern.strip_suffix("/*") {
+            if let Ok(entries) = std::fs::read_dir(repo_root.join(prefix)) {
+                dirs.extend(
+                    entries
+                        .flatten()
+                        .filter(is_real_dir)
+                        .map(|e| e.path())
+                        .filter(|p| !is_hidden(p)),
+                );
+            }
+        } else if !pattern.contains('*') {
+            let dir = repo_root.join(pattern);
+            if dir.is_dir() {
+                dirs.push(dir);
+            }
+        }
+    }
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// Whether `entry` is a directory itself rather than a link to one. Links are not
+/// followed, so a link cycle can't make a scan loop forever.
+fn is_real_dir(entry: &std::fs::DirEntry) -> bool {
+    entry.file_type().is_ok_and(|file_type| file_type.is_dir())
+}
+
+fn collect_dirs_recursive(dir: &Path, dirs: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().filter(is_real_dir).map(|e| e.path()) {
+        if !is_hidden(&path) && !EXCLUDED_DIRS.contains(&dir_name(&path).as_str()) {
+            dirs.push(path.clone());
+            collect_dirs_recursive(&path, dirs);
+        }
+    }
+}
+
+fn read_toml(path: &Path) -> Option<toml::Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    toml::from_str(&content).ok()
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn relative_to(root: &Path, dir: &Path) -> Option<String> {
+    let rel = dir.strip_prefix(root).ok()?;
+    Some(
+        rel.components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
+
+fn dir_name(dir: &Path) -> String {
+    dir.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn is_hidden(path: &Path) -> bool {
+    dir_name(path).starts_with('.')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn detects_cargo_workspace_members() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\", \"tools/cli\"]\n",
+        );
+        write(
+            root,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"core\"\n",
+        );
+        write(root, "crates/api/Cargo.toml", "[package]\nname = \"api\"\n");
+        write(root, "tools/cli/Cargo.toml", "[package]\nname = \"cli\"\n");
+
+        let packages = detect_packages(root);
+        let paths: Vec<&str> = packages.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, vec!["crates/api", "crates/core", "tools/cli"]);
+        assert!(packages.iter().all(|p| p.manager == PackageManager::Cargo));
+        assert!(
+            packages[0]
+                .scripts
+                .contains(&"cargo test -p api".to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn recursive_patterns_skip_links_and_build_output() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/**\"]\n",
+        );
+        write(
+            root,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"core\"\n",
+        );
+        write(
+            root,
+            "crates/core/target/debug/build/Cargo.toml",
+            "[package]\nname = \"generated\"\n",
+        );
+        // A link back up the tree would make a scan that follows links loop forever
+        std::os::unix::fs::symlink(root.join("crates"), root.join("crates/core/loop")).unwrap();
+
+        let packages = detect_packages(root);
+        let paths: Vec<&str> = packages.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, vec!["crates/core"]);
+    }
+
+    #[test]
+    fn detects_pnpm_workspace_with_scripts() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        write(
+            root,
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'apps/*'\n  - \"!apps/legacy\"\n",
+        );
+        write(
+            root,
+            "apps/web/package.json",
+            r#"{"name": "web", "scripts": {"test": "vitest"}}"#,
+        );
+        write(root, "apps/legacy/package.json", r#"{"name": "legacy"}"#);
+
+        let packages = detect_packages(root);
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].name, "web");
+        assert_eq!(packages[0].manager, PackageManager::Pnpm);
+        assert_eq!(packages[0].scripts, vec!["pnpm --filter web run test"]);
+    }
+
+    #[test]
+    fn detects_yarn_workspaces_object_form() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        write(
+            root,
+            "package.json",
+            r#"{"workspaces": {"packages": ["packages/*"]}}"#,
+        );
+        write(root, "yarn.lock", "");
+        write(root, "packages/ui/package.json", r#"{"name": "@acme/ui"}"#);
+
+        let packages = detect_packages(root);
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].name, "@acme/ui");
+        assert_eq!(packages[0].manager, PackageManager::Yarn);
+    }
+
+    #[test]
+    fn detects_go_work_modules() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        write(
+            root,
+            "go.work",
+            "go 1.22\n\nuse (\n\t./svc/auth\n\t./lib // shared\n)\n",
+        );
+        write(root, "svc/auth/go.mod", "module example.com/auth\n");
+        write(root, "lib/go.mod", "module example.com/lib\n");
+
+        let packages = detect_packages(root);
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["example.com/lib", "example.com/auth"]);
+        assert_eq!(packages[1].scripts[1], "go test ./svc/auth/...");
+    }
+
+    #[test]
+    fn plain_repository_has_no_packages() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "package.json", r#"{"name": "single"}"#);
+        assert!(detect_packages(tmp.path()).is_empty());
+    }
+
+    #[test]
+    fn finds_deepest_package_for_path() {
+        let packages = vec![
+            WorkspacePackage {
+                name: "apps".into(),
+                path: "apps".into(),
+                manager: PackageManager::Npm,
+                scripts: vec![],
+            },
+            WorkspacePackage {
+                name: "web".into(),
+                path: "apps/web".into(),
+                manager: PackageManager::Npm,
+                scripts: vec![],
+            },
+        ];
+        assert_eq!(
+            find_package_for_path(&packages, "apps/web/src/main.ts").map(|p| p.name.as_str()),
+            Some("web")
+        );
+        assert_eq!(
+            find_package_for_path(&packages, "apps/webapp").map(|p| p.name.as_str()),
+            Some("apps")
+        );
+        assert!(find_package_for_path(&packages, "docs/README.md").is_none());
+    }
+}
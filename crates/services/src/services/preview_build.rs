@@ -0,0 +1,384 @@
+//! Preview builds: a repository's preview build script run in an attempt's worktree, with
+//! the static bundle it writes copied out of the worktree and served for review. Reviewers
+//! then see the app as it will be deployed instead of a dev server's approximation of it.
+//!
+//! Every bundle is served by its own server on its own port. Absolute asset paths and
+//! client-side routing then work as they do in production, and the app runs on another
+//! origin than this server, so it can't use the reviewer's session with its API.
+
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Router,
+    body::Body,
+    extract::Request,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use thiserror::Error;
+use tokio::{net::TcpListener, sync::Mutex, task::JoinHandle};
+use tokio_util::io::ReaderStream;
+use utils::shell::get_shell_command;
+use uuid::Uuid;
+
+/// How long a build script may run
+const BUILD_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// How much of the end of a build script's output is kept
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+/// Size limit of a stored bundle
+const MAX_BUNDLE_BYTES: u64 = 512 * 1024 * 1024;
+/// Bundles served at once; starting another stops the one started first
+const MAX_SERVERS: usize = 8;
+
+#[derive(Debug, Error)]
+pub enum PreviewBuildError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Build timed out after {} minutes", BUILD_TIMEOUT.as_secs() / 60)]
+    Timeout,
+    #[error("Build script failed: {0}")]
+    ScriptFailed(String),
+    #[error("Invalid output folder '{0}': use a folder inside the repository")]
+    InvalidOutputDir(String),
+    #[error("Output folder '{0}' has no index.html after the build")]
+    MissingIndex(String),
+    #[error("Bundle is larger than {} MB", MAX_BUNDLE_BYTES / (1024 * 1024))]
+    TooLarge,
+    #[error("Failed to check out the base: {0}")]
+    Checkout(String),
+}
+
+/// Folder the bundle of `build_id` is stored in
+pub fn bundle_dir(build_id: Uuid) -> PathBuf {
+    utils::assets::asset_dir()
+        .join("preview-builds")
+        .join(build_id.to_string())
+}
+
+/// Folder the base of `build_id` is checked out to while it builds, outside the
+/// attempt's workspace so its worktrees are left alone
+pub fn base_checkout_dir(build_id: Uuid) -> PathBuf {
+    utils::cache_dir()
+        .join("preview-base")
+        .join(build_id.to_string())
+}
+
+/// Run `script` in `repo_path` and return the end of its output. Fails with the output
+/// if the script exits unsuccessfully.
+pub async fn run_script(
+    repo_path: &Path,
+    script: &str,
+) -> Result<String, (String, PreviewBuildError)> {
+    let (shell, shell_arg) = get_shell_command();
+    let output = tokio::time::timeout(
+        BUILD_TIMEOUT,
+        tokio::process::Command::new(shell)
+            .arg(shell_arg)
+            .arg(script)
+            .current_dir(repo_path)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| (String::new(), PreviewBuildError::Timeout))?
+    .map_err(|e| (String::new(), PreviewBuildError::Io(e)))?;
+
+    let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+    combined.push_str(&String::from_utf8_lossy(&output.stderr));
+    let combined = output_tail(&combined).to_string();
+    if !output.status.success() {
+        return Err((
+            combined,
+            PreviewBuildError::ScriptFailed(output.status.to_string()),
+        ));
+    }
+    Ok(combined)
+}
+
+/// The last [`MAX_OUTPUT_BYTES`] of `output`
+pub(crate) fn output_tail(output: &str) -> &str {
+    if output.len() <= MAX_OUTPUT_BYTES {
+        return output;
+    }
+    let mut start = output.len() - MAX_OUTPUT_BYTES;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    &output[start..]
+}
+
+/// Path of the output folder `output_dir` in `repo_path`, if it stays inside it
+pub fn output_path(repo_path: &Path, output_dir: &str) -> Result<PathBuf, PreviewBuildError> {
+    let relative = Path::new(output_dir.trim());
+    let valid = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !valid || relative.as_os_str().is_empty() {
+        return Err(PreviewBuildError::InvalidOutputDir(output_dir.to_string()));
+    }
+    Ok(repo_path.join(relative))
+}
+
+/// Copy the bundle in `source` to `destination` and return its size. Symlinks are
+/// skipped, so a bundle can't expose files from outside the output folder.
+pub async fn store_bundle(source: &Path, destination: &Path) -> Result<u64, PreviewBuildError> {
+    if !source.join("index.html").is_file() {
+        return Err(PreviewBuildError::MissingIndex(
+            source.to_string_lossy().into_owned(),
+        ));
+    }
+    let source = source.to_path_buf();
+    let destination = destination.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut size = 0;
+        let copied = copy_dir(&source, &destination, &mut size);
+        if copied.is_err() {
+            let _ = std::fs::remove_dir_all(&destination);
+        }
+        copied.map(|()| size)
+    })
+    .await
+    .map_err(|e| PreviewBuildError::Io(std::io::Error::other(e)))?
+}
+
+fn copy_dir(source: &Path, destination: &Path, size: &mut u64) -> Result<(), PreviewBuildError> {
+    std::fs::create_dir_all(destination)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = destination.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target, size)?;
+        } else if file_type.is_file() {
+            *size += entry.metadata()?.len();
+            if *size > MAX_BUNDLE_BYTES {
+                return Err(PreviewBuildError::TooLarge);
+            }
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// File of the bundle in `root` to serve for the URL path `request_path`. Paths without
+/// an extension that match no file get `index.html`, so client-side routes load the app.
+pub fn resolve_file(root: &Path, request_path: &str) -> Option<PathBuf> {
+    let mut path = root.to_path_buf();
+    for part in request_path.split('/').filter(|part| !part.is_empty()) {
+        if part == "." || part == ".." || part.contains('\\') {
+            return None;
+        }
+        path.push(part);
+    }
+    if path.is_dir() {
+        path.push("index.html");
+    }
+    if path.is_file() {
+        return Some(path);
+    }
+    let has_extension = request_path
+        .rsplit('/')
+        .next()
+        .is_some_and(|name| name.contains('.'));
+    let index = root.join("index.html");
+    (!has_extension && index.is_file()).then_some(index)
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "json" | "map" => "application/json",
+        "webmanifest" => "application/manifest+json",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "wasm" => "application/wasm",
+        "txt" => "text/plain; charset=utf-8",
+        "xml" => "application/xml",
+        _ => "application/octet-stream",
+    }
+}
+
+async fn serve_file(root: Arc<PathBuf>, request: Request) -> Response {
+    let Some(path) = resolve_file(&root, request.uri().path()) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(_) => return StatusCode::NOT_FOUND.into_response(),
+    };
+    (
+        [
+            (header::CONTENT_TYPE, content_type(&path)),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    )
+        .into_response()
+}
+
+struct PreviewServer {
+    port: u16,
+    started_at: Instant,
+    handle: JoinHandle<()>,
+}
+
+/// Servers of the bundles being previewed, by build
+#[derive(Clone, Default)]
+pub struct PreviewServers {
+    servers: Arc<Mutex<HashMap<Uuid, PreviewServer>>>,
+}
+
+impl PreviewServers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Port of the server of the bundle of `build_id`, started on `host` if it isn't
+    /// running
+    pub async fn serve(&self, build_id: Uuid, host: &str) -> Result<u16, PreviewBuildError> {
+        let mut servers = self.servers.lock().await;
+        if let Some(server) = servers.get(&build_id)
+            && !server.handle.is_finished()
+        {
+            return Ok(server.port);
+        }
+        if servers.len() >= MAX_SERVERS
+            && let Some(oldest) = servers
+                .iter()
+                .min_by_key(|(_, server)| server.started_at)
+                .map(|(id, _)| *id)
+            && let Some(server) = servers.remove(&oldest)
+        {
+            server.handle.abort();
+        }
+
+        let listener = TcpListener::bind((host, 0)).await?;
+        let port = listener.local_addr()?.port();
+        let root = Arc::new(bundle_dir(build_id));
+        let app = Router::new().fallback(move |request: Request| serve_file(root.clone(), request));
+        let handle = tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app.into_make_service()).await {
+                tracing::warn!("Preview server of build {} stopped: {}", build_id, e);
+            }
+        });
+        tracing::info!("Serving preview build {} on {}:{}", build_id, host, port);
+        servers.insert(
+            build_id,
+            PreviewServer {
+                port,
+                started_at: Instant::now(),
+                handle,
+            },
+        );
+        Ok(port)
+    }
+
+    /// Port of the running server of `build_id`
+    pub async fn port(&self, build_id: Uuid) -> Option<u16> {
+        self.servers
+            .lock()
+            .await
+            .get(&build_id)
+            .filter(|server| !server.handle.is_finished())
+            .map(|server| server.port)
+    }
+
+    pub async fn stop(&self, build_id: Uuid) {
+        if let Some(server) = self.servers.lock().await.remove(&build_id) {
+            server.handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_end_of_the_output() {
+        let output = format!("{}end", "é".repeat(MAX_OUTPUT_BYTES));
+        let tail = output_tail(&output);
+        assert!(tail.len() <= MAX_OUTPUT_BYTES);
+        assert!(tail.ends_with("end"));
+        assert_eq!(output_tail("short"), "short");
+    }
+
+    #[test]
+    fn keeps_output_folders_in_the_repository() {
+        let repo = Path::new("/repo");
+        assert_eq!(
+            output_path(repo, "dist").unwrap(),
+            PathBuf::from("/repo/dist")
+        );
+        assert_eq!(
+            output_path(repo, "./packages/web/build").unwrap(),
+            PathBuf::from("/repo/./packages/web/build")
+        );
+        assert!(output_path(repo, "../dist").is_err());
+        assert!(output_path(repo, "/dist").is_err());
+        assert!(output_path(repo, " ").is_err());
+    }
+
+    #[tokio::test]
+    async fn stores_bundles_without_symlinks() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let bundle = destination.path().join("bundle");
+        std::fs::write(source.path().join("index.html"), "<html></html>").unwrap();
+        std::fs::create_dir(source.path().join("assets")).unwrap();
+        std::fs::write(source.path().join("assets/app.js"), "app()").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("/etc/passwd", source.path().join("passwd")).unwrap();
+
+        let size = store_bundle(source.path(), &bundle).await.unwrap();
+        assert_eq!(size, 18);
+        assert!(bundle.join("assets/app.js").is_file());
+        assert!(!bundle.join("passwd").exists());
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            store_bundle(empty.path(), &destination.path().join("other")).await,
+            Err(PreviewBuildError::MissingIndex(_))
+        ));
+    }
+
+    #[test]
+    fn resolves_files_with_a_fallback_for_client_routes() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("index.html"), "<html></html>").unwrap();
+        std::fs::create_dir(root.path().join("assets")).unwrap();
+        std::fs::write(root.path().join("assets/app.js"), "app()").unwrap();
+        let index = root.path().join("index.html");
+
+        assert_eq!(resolve_file(root.path(), "/"), Some(index.clone()));
+        assert_eq!(
+            resolve_file(root.path(), "/assets/app.js"),
+            Some(root.path().join("assets/app.js"))
+        );
+        assert_eq!(resolve_file(root.path(), "/tasks/42"), Some(index));
+        assert_eq!(resolve_file(root.path(), "/assets/missing.js"), None);
+        assert_eq!(resolve_file(root.path(), "/../secret"), None);
+    }
+}
@@ -0,0 +1,359 @@
+//! Visual comparison of an attempt's preview with its base. Screenshots of the same route
+//! and viewport are diffed pixel by pixel, and block by block for how much of the layout
+//! changed, and the changed pixels are highlighted in an image for the reviewer.
+
+use std::path::{Path, PathBuf};
+
+use db::models::{preview_comparison::PreviewComparison, preview_screenshot::PreviewScreenshot};
+use image::{Rgba, RgbaImage};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Largest difference of a color channel that still counts as the same pixel, so
+/// anti-aliasing and font smoothing that vary between renders aren't changes
+const CHANNEL_TOLERANCE: u8 = 24;
+/// Width and height of the blocks pages are divided into
+const REGION_SIZE: u32 = 32;
+/// Share of a block's pixels that have to change for the block to count as changed
+const REGION_CHANGE_RATIO: f64 = 0.02;
+/// Color of the changed pixels in the highlighted image
+const HIGHLIGHT: Rgba<u8> = Rgba([220, 38, 38, 255]);
+/// Share of changed pixels above which a change is significant, unless a comparison
+/// asks for another threshold
+pub const DEFAULT_THRESHOLD: f64 = 0.005;
+
+#[derive(Debug, Error)]
+pub enum ComparisonError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Failed to read or write a screenshot: {0}")]
+    Image(#[from] image::ImageError),
+    #[error("Invalid threshold {0}: use a share of the pixels between 0 and 1")]
+    InvalidThreshold(f64),
+}
+
+/// How much a screenshot of the attempt differs from the base's
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageDiff {
+    pub changed_pixels: u64,
+    pub total_pixels: u64,
+    pub changed_regions: u64,
+    pub total_regions: u64,
+    pub size_changed: bool,
+}
+
+impl ImageDiff {
+    pub fn change_ratio(&self) -> f64 {
+        ratio(self.changed_pixels as i64, self.total_pixels as i64)
+    }
+
+    /// Whether more than `threshold` of the pixels changed, or the page rendered at
+    /// another size
+    pub fn is_significant(&self, threshold: f64) -> bool {
+        self.size_changed || self.change_ratio() > threshold
+    }
+}
+
+pub fn validate_threshold(threshold: f64) -> Result<(), ComparisonError> {
+    if threshold.is_finite() && (0.0..=1.0).contains(&threshold) {
+        Ok(())
+    } else {
+        Err(ComparisonError::InvalidThreshold(threshold))
+    }
+}
+
+/// Folder the base and diff images of every comparison are stored in
+pub fn comparisons_dir() -> PathBuf {
+    utils::assets::asset_dir().join("preview-comparisons")
+}
+
+/// File the base's screenshot of the comparison `comparison_id` is stored in
+pub fn base_screenshot_path(comparison_id: Uuid) -> PathBuf {
+    comparisons_dir().join(format!("{}-base.png", comparison_id))
+}
+
+/// File the image highlighting the changes of the comparison `comparison_id` is
+/// stored in
+pub fn diff_image_path(comparison_id: Uuid) -> PathBuf {
+    comparisons_dir().join(format!("{}-diff.png", comparison_id))
+}
+
+/// Diff `attempt` against `base`. Where the images don't overlap, because the pages
+/// rendered at different sizes, every pixel counts as changed. The returned image is
+/// the attempt's screenshot faded, with the changed pixels highlighted.
+pub fn diff_images(base: &RgbaImage, attempt: &RgbaImage) -> (ImageDiff, RgbaImage) {
+    let width = base.width().max(attempt.width());
+    let height = base.height().max(attempt.height());
+    let regions_x = width.div_ceil(REGION_SIZE);
+    let regions_y = height.div_ceil(REGION_SIZE);
+    let mut region_changes = vec![0u32; (regions_x * regions_y) as usize];
+    let mut highlighted = RgbaImage::new(width, height);
+    let mut changed_pixels = 0;
+
+    for y in 0..height {
+        for x in 0..width {
+            let after = attempt.get_pixel_checked(x, y);
+            let changed = match (base.get_pixel_checked(x, y), after) {
+                (Some(before), Some(after)) => pixels_differ(before, after),
+                _ => true,
+            };
+            if changed {
+                changed_pixels += 1;
+                region_changes[((y / REGION_SIZE) * regions_x + x / REGION_SIZE) as usize] += 1;
+                highlighted.put_pixel(x, y, HIGHLIGHT);
+            } else if let Some(after) = after {
+                highlighted.put_pixel(x, y, faded(after));
+            }
+        }
+    }
+
+    let changed_regions = region_changes
+        .iter()
+        .enumerate()
+        .filter(|(index, changes)| {
+            let index = *index as u32;
+            let region_width = REGION_SIZE.min(width - (index % regions_x) * REGION_SIZE);
+            let region_height = REGION_SIZE.min(height - (index / regions_x) * REGION_SIZE);
+            let region_pixels = (region_width * region_height) as f64;
+            **changes > 0 && **changes as f64 >= region_pixels * REGION_CHANGE_RATIO
+        })
+        .count();
+
+    let diff = ImageDiff {
+        changed_pixels,
+        total_pixels: width as u64 * height as u64,
+        changed_regions: changed_regions as u64,
+        total_regions: region_changes.len() as u64,
+        size_changed: base.dimensions() != attempt.dimensions(),
+    };
+    (diff, highlighted)
+}
+
+fn pixels_differ(before: &Rgba<u8>, after: &Rgba<u8>) -> bool {
+    before
+        .0
+        .iter()
+        .zip(after.0.iter())
+        .any(|(before, after)| before.abs_diff(*after) > CHANNEL_TOLERANCE)
+}
+
+/// `pixel` two thirds of the way to white, so highlighted changes stand out
+fn faded(pixel: &Rgba<u8>) -> Rgba<u8> {
+    let [r, g, b, _] = pixel.0;
+    let fade = |channel: u8| channel + (255 - channel) / 3 * 2;
+    Rgba([fade(r), fade(g), fade(b), 255])
+}
+
+/// Diff the screenshots at `base_path` and `attempt_path`, writing the image
+/// highlighting the changes to `diff_path`
+pub async fn compare_files(
+    base_path: &Path,
+    attempt_path: &Path,
+    diff_path: &Path,
+) -> Result<ImageDiff, ComparisonError> {
+    let (base_path, attempt_path, diff_path) = (
+        base_path.to_path_buf(),
+        attempt_path.to_path_buf(),
+        diff_path.to_path_buf(),
+    );
+    tokio::task::spawn_blocking(move || {
+        let base = image::open(&base_path)?.to_rgba8();
+        let attempt = image::open(&attempt_path)?.to_rgba8();
+        let (diff, highlighted) = diff_images(&base, &attempt);
+        if let Some(parent) = diff_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        highlighted.save(&diff_path)?;
+        Ok(diff)
+    })
+    .await
+    .map_err(|e| ComparisonError::Io(std::io::Error::other(e)))?
+}
+
+fn ratio(part: i64, total: i64) -> f64 {
+    if total <= 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// Section of a review prompt summing up how the attempt's preview looks next to its
+/// base, from the latest comparison of each route and viewport. Empty if nothing was
+/// compared.
+pub fn review_summary(comparisons: &[(&PreviewComparison, &PreviewScreenshot)]) -> String {
+    let mut latest: Vec<(&PreviewComparison, &PreviewScreenshot)> = Vec::new();
+    for (comparison, screenshot) in comparisons {
+        let same_page = latest.iter().position(|(_, other)| {
+            other.route == screenshot.route && other.viewport == screenshot.viewport
+        });
+        match same_page {
+            Some(index) => latest[index] = (comparison, screenshot),
+            None => latest.push((comparison, screenshot)),
+        }
+    }
+    if latest.is_empty() {
+        return String::new();
+    }
+
+    let significant: Vec<_> = latest
+        .iter()
+        .filter(|(comparison, _)| comparison.significant)
+        .collect();
+    if significant.is_empty() {
+        let pages: Vec<String> = latest
+            .iter()
+            .map(|(_, screenshot)| format!("`{}` at {}", screenshot.route, screenshot.viewport))
+            .collect();
+        return format!(
+            "\n\nScreenshots of the attempt's preview show no significant visual changes \
+             from its base at {}.",
+            pages.join(", ")
+        );
+    }
+
+    let mut summary = String::from(
+        "\n\nScreenshots of the attempt's preview differ visibly from its base. Check that \
+         these visual changes are intended:",
+    );
+    for (comparison, screenshot) in significant {
+        let change = if comparison.size_changed {
+            "the page rendered at a different size".to_string()
+        } else {
+            format!(
+                "{:.1}% of the pixels changed, in {} of {} regions",
+                ratio(comparison.changed_pixels, comparison.total_pixels) * 100.0,
+                comparison.changed_regions,
+                comparison.total_regions
+            )
+        };
+        summary.push_str(&format!(
+            "\n- `{}` at {} ({}×{}): {}",
+            screenshot.route, screenshot.viewport, screenshot.width, screenshot.height, change
+        ));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn page(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([250, 250, 250, 255]))
+    }
+
+    fn screenshot(route: &str, viewport: &str) -> PreviewScreenshot {
+        PreviewScreenshot {
+            id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            url: format!("http://localhost:5173{}", route),
+            route: route.to_string(),
+            viewport: viewport.to_string(),
+            width: 1280,
+            height: 800,
+            size_bytes: 1024,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn comparison(screenshot: &PreviewScreenshot, changed_pixels: i64) -> PreviewComparison {
+        PreviewComparison {
+            id: Uuid::new_v4(),
+            workspace_id: screenshot.workspace_id,
+            screenshot_id: screenshot.id,
+            base_url: "http://127.0.0.1:4000/".to_string(),
+            changed_pixels,
+            total_pixels: 1000,
+            changed_regions: 3,
+            total_regions: 40,
+            size_changed: false,
+            significant: changed_pixels > 5,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn finds_no_changes_in_the_same_page() {
+        let mut attempt = page(100, 70);
+        // Font smoothing that differs slightly between renders
+        attempt.put_pixel(10, 10, Rgba([240, 245, 250, 255]));
+        let (diff, highlighted) = diff_images(&page(100, 70), &attempt);
+        assert_eq!(diff.changed_pixels, 0);
+        assert_eq!(diff.changed_regions, 0);
+        assert_eq!(diff.total_pixels, 7000);
+        assert_eq!(diff.total_regions, 12);
+        assert!(!diff.is_significant(DEFAULT_THRESHOLD));
+        assert_eq!(highlighted.dimensions(), (100, 70));
+    }
+
+    #[test]
+    fn counts_changed_pixels_and_regions() {
+        let mut attempt = page(64, 64);
+        for y in 0..10 {
+            for x in 0..10 {
+                attempt.put_pixel(x, y, Rgba([20, 20, 20, 255]));
+            }
+        }
+        // A single changed pixel is too little to change its region's layout
+        attempt.put_pixel(40, 40, Rgba([20, 20, 20, 255]));
+        let (diff, highlighted) = diff_images(&page(64, 64), &attempt);
+        assert_eq!(diff.changed_pixels, 101);
+        assert_eq!(diff.changed_regions, 1);
+        assert_eq!(diff.total_regions, 4);
+        assert!(!diff.size_changed);
+        assert!(diff.is_significant(DEFAULT_THRESHOLD));
+        assert!(!diff.is_significant(0.5));
+        assert_eq!(*highlighted.get_pixel(0, 0), HIGHLIGHT);
+        assert_ne!(*highlighted.get_pixel(63, 63), HIGHLIGHT);
+    }
+
+    #[test]
+    fn counts_pages_of_another_size_as_changed() {
+        let (diff, _) = diff_images(&page(32, 32), &page(32, 64));
+        assert!(diff.size_changed);
+        assert_eq!(diff.changed_pixels, 32 * 32);
+        assert_eq!(diff.changed_regions, 1);
+        assert!(diff.is_significant(1.0));
+    }
+
+    #[test]
+    fn validates_thresholds() {
+        assert!(validate_threshold(DEFAULT_THRESHOLD).is_ok());
+        assert!(validate_threshold(0.0).is_ok());
+        assert!(validate_threshold(1.5).is_err());
+        assert!(validate_threshold(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn summarizes_the_latest_comparisons_for_review() {
+        assert_eq!(review_summary(&[]), "");
+
+        let home = screenshot("/", "desktop");
+        let settings = screenshot("/settings", "mobile");
+        let home_again = screenshot("/", "desktop");
+        let (old, changed, unchanged) = (
+            comparison(&home, 500),
+            comparison(&settings, 125),
+            comparison(&home_again, 0),
+        );
+        let summary = review_summary(&[
+            (&old, &home),
+            (&changed, &settings),
+            (&unchanged, &home_again),
+        ]);
+        assert!(summary.contains("Check that these visual changes are intended:"));
+        assert!(summary.contains(
+            "\n- `/settings` at mobile (1280×800): 12.5% of the pixels changed, in 3 of 40 \
+             regions"
+        ));
+        assert!(!summary.contains("`/` at desktop"));
+
+        let summary = review_summary(&[(&unchanged, &home_again)]);
+        assert!(
+            summary.ends_with("no significant visual changes from its base at `/` at desktop.")
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! Screenshots of an attempt's preview, taken with a headless Chrome or Chromium at the
+//! routes and viewport sizes a reviewer asks for. They show what a change does to the UI
+//! without the reviewer starting the preview, and can go into the body of the attempt's
+//! pull request.
+//!
+//! The browser is the one set in `VK_SCREENSHOT_BROWSER`, else the first Chrome, Chromium
+//! or Edge found on the machine.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use db::models::preview_screenshot::PreviewScreenshot;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+use url::Url;
+use utils::shell::resolve_executable_path;
+use uuid::Uuid;
+
+use crate::services::git_host::{ProviderKind, detect_provider_from_url};
+
+/// How long the browser may take for one screenshot
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long pages get to load and render before the screenshot, in milliseconds
+const RENDER_BUDGET_MS: u32 = 5000;
+/// Screenshots taken by one request, routes times viewports
+pub const MAX_SCREENSHOTS: usize = 12;
+/// Browsers looked for on the `PATH`, in order
+const BROWSER_EXECUTABLES: [&str; 6] = [
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "microsoft-edge",
+    "chrome",
+];
+/// Browsers looked for outside the `PATH`, e.g. macOS apps
+const BROWSER_PATHS: [&str; 3] = [
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Chromium.app/Contents/MacOS/Chromium",
+    "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+];
+
+#[derive(Debug, Error)]
+pub enum ScreenshotError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("No headless browser found: install Chrome or Chromium, or set VK_SCREENSHOT_BROWSER")]
+    BrowserNotFound,
+    #[error("Invalid preview URL '{0}': use an http or https URL")]
+    InvalidUrl(String),
+    #[error("Invalid route '{0}': use a path of the preview, e.g. /settings")]
+    InvalidRoute(String),
+    #[error("Invalid viewport '{0}'")]
+    InvalidViewport(String),
+    #[error("Screenshot timed out after {} seconds", CAPTURE_TIMEOUT.as_secs())]
+    Timeout,
+    #[error("Browser failed to take the screenshot: {0}")]
+    CaptureFailed(String),
+}
+
+/// Size of the browser window a screenshot is taken at
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct Viewport {
+    /// Name the screenshot is labelled with, e.g. `desktop`
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Viewport {
+    fn new(name: &str, width: u32, height: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            width,
+            height,
+        }
+    }
+
+    /// Viewports of requests that don't give any: a laptop screen and a phone
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new("desktop", 1280, 800),
+            Self::new("mobile", 390, 844),
+        ]
+    }
+
+    pub fn validate(&self) -> Result<(), ScreenshotError> {
+        let name_valid = !self.name.is_empty()
+            && self.name.len() <= 32
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+        let size_valid = (200..=3840).contains(&self.width) && (200..=4320).contains(&self.height);
+        if !name_valid || !size_valid {
+            return Err(ScreenshotError::InvalidViewport(format!(
+                "{} ({}x{})",
+                self.name, self.width, self.height
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// File the screenshot `screenshot_id` is stored in
+pub fn screenshot_path(screenshot_id: Uuid) -> PathBuf {
+    screenshots_dir().join(format!("{}.png", screenshot_id))
+}
+
+/// Folder every screenshot is stored in
+pub fn screenshots_dir() -> PathBuf {
+    utils::assets::asset_dir().join("preview-screenshots")
+}
+
+/// URL of `route` of the preview at `base_url`. The route replaces the base URL's path
+/// and can't lead to another host.
+pub fn page_url(base_url: &str, route: &str) -> Result<Url, ScreenshotError> {
+    let base = Url::parse(base_url.trim())
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https") && url.host().is_some())
+        .ok_or_else(|| ScreenshotError::InvalidUrl(base_url.to_string()))?;
+    let route = route.trim();
+    let route = if route.is_empty() { "/" } else { route };
+    if !route.starts_with('/') || route.starts_with("//") || route.contains('\\') {
+        return Err(ScreenshotError::InvalidRoute(route.to_string()));
+    }
+    let url = base
+        .join(route)
+        .map_err(|_| ScreenshotError::InvalidRoute(route.to_string()))?;
+    if url.origin() != base.origin() {
+        return Err(ScreenshotError::InvalidRoute(route.to_string()));
+    }
+    Ok(url)
+}
+
+/// Path of the headless browser to take screenshots with
+pub async fn find_browser() -> Result<PathBuf, ScreenshotError> {
+    if let Ok(browser) = std::env::var("VK_SCREENSHOT_BROWSER")
+        && !browser.trim().is_empty()
+    {
+        let path = PathBuf::from(browser.trim());
+        if path.is_file() {
+            return Ok(path);
+        }
+        return resolve_executable_path(browser.trim())
+            .await
+            .ok_or(ScreenshotError::BrowserNotFound);
+    }
+    for executable in BROWSER_EXECUTABLES {
+        if let Some(path) = resolve_executable_path(executable).await {
+            return Ok(path);
+        }
+    }
+    BROWSER_PATHS
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.is_file())
+        .ok_or(ScreenshotError::BrowserNotFound)
+}
+
+/// Take a screenshot of `url` at `viewport` with `browser` into the PNG file `output`
+/// and return its size. Every screenshot gets a fresh browser profile, so no cookies
+/// or storage carry over between them.
+pub async fn capture(
+    browser: &Path,
+    url: &Url,
+    viewport: &Viewport,
+    output: &Path,
+) -> Result<u64, ScreenshotError> {
+    if let Some(parent) = output.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let profile = tempfile::tempdir()?;
+    let result = tokio::time::timeout(
+        CAPTURE_TIMEOUT,
+        tokio::process::Command::new(browser)
+            .arg("--headless=new")
+            .arg("--disable-gpu")
+            .arg("--hide-scrollbars")
+            .arg("--no-first-run")
+            .arg("--no-default-browser-check")
+            .arg("--mute-audio")
+            .arg(format!("--user-data-dir={}", profile.path().display()))
+            .arg(format!(
+                "--window-size={},{}",
+                viewport.width, viewport.height
+            ))
+            .arg(format!("--virtual-time-budget={}", RENDER_BUDGET_MS))
+            .arg(format!("--screenshot={}", output.display()))
+            .arg(url.as_str())
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| ScreenshotError::Timeout)??;
+
+    match tokio::fs::metadata(output).await {
+        Ok(metadata) if metadata.len() > 0 => Ok(metadata.len()),
+        _ => {
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            let reason = stderr
+                .lines()
+                .rev()
+                .find(|line| !line.trim().is_empty())
+                .unwrap_or("no screenshot written");
+            Err(ScreenshotError::CaptureFailed(reason.trim().to_string()))
+        }
+    }
+}
+
+/// Name of the file `screenshot` gets when it is published, unique among the
+/// screenshots of its attempt, e.g. `03-settings-mobile.png`
+pub fn published_file_name(index: usize, screenshot: &PreviewScreenshot) -> String {
+    let route: String = screenshot
+        .route
+        .trim_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let route = route.trim_matches('-');
+    let route = if route.is_empty() { "home" } else { route };
+    let route: String = route.chars().take(40).collect();
+    format!("{:02}-{}-{}.png", index + 1, route, screenshot.viewport)
+}
+
+/// Web URL of a GitHub repository with the git remote `remote_url`, which images in
+/// pull request bodies can link into
+pub fn github_web_url(remote_url: &str) -> Option<String> {
+    if detect_provider_from_url(remote_url) != ProviderKind::GitHub {
+        return None;
+    }
+    let remote_url = remote_url.trim();
+    let (host, path) = if let Some(rest) = remote_url.strip_prefix("git@") {
+        rest.split_once(':')?
+    } else {
+        let url = Url::parse(remote_url).ok()?;
+        let host = url.host_str()?.to_string();
+        let path = url.path().trim_start_matches('/').to_string();
+        return web_url(&host, &path);
+    };
+    web_url(host, path)
+}
+
+fn web_url(host: &str, path: &str) -> Option<String> {
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let mut parts = path.split('/');
+    let (owner, repo) = (parts.next()?, parts.next()?);
+    if owner.is_empty() || repo.is_empty() || parts.next().is_some() {
+        return None;
+    }
+    Some(format!("https://{}/{}/{}", host, owner, repo))
+}
+
+/// Section of a pull request body showing `screenshots`, each with the URL its image
+/// was published at
+pub fn pull_request_section(screenshots: &[(&PreviewScreenshot, String)]) -> String {
+    let mut section = String::from("## Screenshots\n");
+    for (screenshot, image_url) in screenshots {
+        section.push_str(&format!(
+            "\n**{}** at {} ({}×{})\n\n![{} at {}]({})\n",
+            screenshot.route,
+            screenshot.viewport,
+            screenshot.width,
+            screenshot.height,
+            screenshot.route,
+            screenshot.viewport,
+            image_url
+        ));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn screenshot(route: &str, viewport: &str) -> PreviewScreenshot {
+        PreviewScreenshot {
+            id: Uuid::new_v4(),
+            workspace_id: Uuid::new_v4(),
+            url: format!("http://localhost:5173{}", route),
+            route: route.to_string(),
+            viewport: viewport.to_string(),
+            width: 1280,
+            height: 800,
+            size_bytes: 1024,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn joins_routes_onto_the_preview() {
+        assert_eq!(
+            page_url("http://localhost:5173/app", "/settings?tab=2")
+                .unwrap()
+                .as_str(),
+            "http://localhost:5173/settings?tab=2"
+        );
+        assert_eq!(
+            page_url("http://127.0.0.1:4000", "").unwrap().as_str(),
+            "http://127.0.0.1:4000/"
+        );
+        assert!(matches!(
+            page_url("http://localhost:5173", "//evil.example/"),
+            Err(ScreenshotError::InvalidRoute(_))
+        ));
+        assert!(matches!(
+            page_url("http://localhost:5173", "settings"),
+            Err(ScreenshotError::InvalidRoute(_))
+        ));
+        assert!(matches!(
+            page_url("file:///etc/passwd", "/"),
+            Err(ScreenshotError::InvalidUrl(_))
+        ));
+    }
+
+    #[test]
+    fn validates_viewports() {
+        assert!(Viewport::defaults().iter().all(|v| v.validate().is_ok()));
+        assert!(Viewport::new("tablet", 768, 1024).validate().is_ok());
+        assert!(Viewport::new("", 768, 1024).validate().is_err());
+        assert!(Viewport::new("huge", 10_000, 1024).validate().is_err());
+        assert!(Viewport::new("a b", 768, 1024).validate().is_err());
+    }
+
+    #[test]
+    fn names_published_files() {
+        assert_eq!(
+            published_file_name(0, &screenshot("/", "desktop")),
+            "01-home-desktop.png"
+        );
+        assert_eq!(
+            published_file_name(11, &screenshot("/settings/profile?x=1", "mobile")),
+            "12-settings-profile-x-1-mobile.png"
+        );
+    }
+
+    #[test]
+    fn finds_github_web_urls() {
+        assert_eq!(
+            github_web_url("git@github.com:owner/repo.git").as_deref(),
+            Some("https://github.com/owner/repo")
+        );
+        assert_eq!(
+            github_web_url("https://user@github.com/owner/repo").as_deref(),
+            Some("https://github.com/owner/repo")
+        );
+        assert_eq!(
+            github_web_url("ssh://git@github.company.com/owner/repo.git").as_deref(),
+            Some("https://github.company.com/owner/repo")
+        );
+        assert_eq!(
+            github_web_url("https://dev.azure.com/org/project/_git/repo"),
+            None
+        );
+    }
+
+    #[test]
+    fn writes_pull_request_sections() {
+        let home = screenshot("/", "desktop");
+        let section = pull_request_section(&[(&home, "https://img/1.png".to_string())]);
+        assert!(section.starts_with("## Screenshots\n"));
+        assert!(section.contains("**/** at desktop (1280×800)"));
+        assert!(section.contains("![/ at desktop](https://img/1.png)"));
+    }
+}
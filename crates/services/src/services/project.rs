@@ -1,19 +1,31 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
 use db::models::{
-    project::{CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject},
+    document_approval::DocumentApprovalRule,
+    document_encryption::{DocumentEncryptedFolder, DocumentEncryptionKey},
+    image::TaskImage,
+    project::{
+        CloneProject, CreateProject, Project, ProjectError, SearchMatchType, SearchResult,
+        UpdateProject,
+    },
     project_repo::{CreateProjectRepo, ProjectRepo},
+    project_seed_document::ProjectSeedDocument,
+    project_webhook::ProjectWebhook,
     repo::Repo,
+    task::{CreateTask, Task, TaskStatus},
+    task_package_scope::TaskPackageScope,
+    wip_limit::WipLimit,
 };
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
 use thiserror::Error;
 use utils::api::projects::RemoteProject;
 use uuid::Uuid;
 
 use super::{
+    document_encryption::generate_key,
     file_search::{FileSearchCache, SearchQuery},
     repo::{RepoError, RepoService},
 };
@@ -46,6 +58,16 @@ pub enum ProjectServiceError {
 
 pub type Result<T> = std::result::Result<T, ProjectServiceError>;
 
+/// Validate a seed document path: it must be relative and stay inside the repository
+pub fn seed_document_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path.trim());
+    let valid = !path.as_os_str().is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_)));
+    valid.then(|| path.to_path_buf())
+}
+
 impl From<RepoError> for ProjectServiceError {
     fn from(e: RepoError) -> Self {
         match e {
@@ -73,12 +95,26 @@ impl ProjectService {
         repo_service: &RepoService,
         payload: CreateProject,
     ) -> Result<Project> {
-        // Validate all repository paths and check for duplicates within the payload
+        let repositories = self.normalize_repositories(repo_service, &payload.repositories)?;
+
+        let mut tx = pool.begin().await?;
+        let (project, _) = Self::insert_project(&mut tx, &payload, &repositories).await?;
+        tx.commit().await?;
+
+        Ok(project)
+    }
+
+    /// Validate all repository paths and check for duplicates within the payload
+    fn normalize_repositories(
+        &self,
+        repo_service: &RepoService,
+        repositories: &[CreateProjectRepo],
+    ) -> Result<Vec<CreateProjectRepo>> {
         let mut seen_names = HashSet::new();
         let mut seen_paths = HashSet::new();
         let mut normalized_repos = Vec::new();
 
-        for repo in &payload.repositories {
+        for repo in repositories {
             let path = repo_service.normalize_path(&repo.git_repo_path)?;
             repo_service.validate_git_repo_path(&path)?;
 
@@ -98,19 +134,120 @@ impl ProjectService {
             });
         }
 
-        let id = Uuid::new_v4();
+        Ok(normalized_repos)
+    }
 
-        let project = Project::create(pool, &payload, id)
+    async fn insert_project(
+        conn: &mut SqliteConnection,
+        payload: &CreateProject,
+        repositories: &[CreateProjectRepo],
+    ) -> Result<(Project, Vec<Repo>)> {
+        let id = Uuid::new_v4();
+        let project = Project::create(&mut *conn, payload, id)
             .await
             .map_err(|e| ProjectServiceError::Project(ProjectError::CreateFailed(e.to_string())))?;
 
-        for repo in &normalized_repos {
-            let repo_entity =
-                Repo::find_or_create(pool, Path::new(&repo.git_repo_path), &repo.display_name)
-                    .await?;
-            ProjectRepo::create(pool, project.id, repo_entity.id).await?;
+        let mut repos = Vec::with_capacity(repositories.len());
+        for repo in repositories {
+            let repo_entity = Repo::find_or_create(
+                &mut *conn,
+                Path::new(&repo.git_repo_path),
+                &repo.display_name,
+            )
+            .await?;
+            ProjectRepo::create(&mut *conn, project.id, repo_entity.id).await?;
+            repos.push(repo_entity);
+        }
+
+        Ok((project, repos))
+    }
+
+    /// Create a project for `payload.repositories` carrying over the settings of
+    /// `source`: the agent working directory, timezone and document settings, column
+    /// WIP limits, approval rules, encrypted folders (under a new key), webhooks, seed
+    /// documents and, when requested, its open tasks with their images, priority and
+    /// package scope (if the scoped repository is part of the new project). The database
+    /// changes are made in one transaction. The seed documents are only recorded; the
+    /// caller writes them through the document pipeline.
+    pub async fn clone_project(
+        &self,
+        pool: &SqlitePool,
+        repo_service: &RepoService,
+        source: &Project,
+        payload: CloneProject,
+    ) -> Result<Project> {
+        let create = CreateProject {
+            name: payload.name,
+            repositories: payload.repositories,
+        };
+        let repositories = self.normalize_repositories(repo_service, &create.repositories)?;
+
+        let mut open_tasks = Vec::new();
+        let mut scopes = HashMap::new();
+        if payload.include_open_tasks {
+            open_tasks = Task::find_by_project_id_with_attempt_status(pool, source.id)
+                .await?
+                .into_iter()
+                .filter(|task| !matches!(task.status, TaskStatus::Done | TaskStatus::Cancelled))
+                .collect();
+            // Oldest first so the copies keep the original relative order
+            open_tasks.reverse();
+            scopes = TaskPackageScope::find_by_project_id(pool, source.id)
+                .await?
+                .into_iter()
+                .map(|scope| (scope.task_id, scope))
+                .collect();
         }
 
+        let mut tx = pool.begin().await?;
+        let (mut project, repos) = Self::insert_project(&mut tx, &create, &repositories).await?;
+
+        Project::copy_settings(&mut *tx, source.id, project.id).await?;
+        project.default_agent_working_dir = source.default_agent_working_dir.clone();
+        WipLimit::copy_to_project(&mut *tx, source.id, project.id).await?;
+        DocumentApprovalRule::copy_to_project(&mut *tx, source.id, project.id).await?;
+        if DocumentEncryptedFolder::copy_to_project(&mut *tx, source.id, project.id).await? > 0 {
+            DocumentEncryptionKey::create(&mut *tx, project.id, &generate_key()).await?;
+        }
+        ProjectWebhook::copy_to_project(&mut *tx, source.id, project.id).await?;
+        ProjectSeedDocument::copy_to_project(&mut *tx, source.id, project.id).await?;
+
+        let repo_ids: HashSet<Uuid> = repos.iter().map(|repo| repo.id).collect();
+        for task in &open_tasks {
+            let copy = Task::create(
+                &mut *tx,
+                &CreateTask {
+                    project_id: project.id,
+                    title: task.title.clone(),
+                    description: task.description.clone(),
+                    status: Some(TaskStatus::Todo),
+                    parent_workspace_id: None,
+                    image_ids: None,
+                    priority: Some(task.priority),
+                    wip_override_reason: None,
+                },
+                Uuid::new_v4(),
+            )
+            .await?;
+
+            TaskImage::copy_to_task(&mut *tx, task.id, copy.id).await?;
+
+            if let Some(scope) = scopes.get(&task.id)
+                && repo_ids.contains(&scope.repo_id)
+            {
+                TaskPackageScope::upsert(
+                    &mut *tx,
+                    copy.id,
+                    scope.repo_id,
+                    &scope.package_path,
+                    &scope.package_name,
+                )
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+
         Ok(project)
     }
 
@@ -0,0 +1,427 @@
+//! Shares of the server each project gets, so a busy project can't starve the others.
+//!
+//! Background jobs, such as document syncs, refreshes and scheduled runs, go through
+//! [`BackgroundJobs`]: at most `total` run at once, at most `per_project` of them for the
+//! same project. A freed slot goes to the waiting project with the fewest jobs running,
+//! and among those to the one whose last job started longest ago, so a project queueing
+//! hundreds of jobs takes turns with one queueing a single job.
+//!
+//! API requests of a project are counted by [`ApiQuota`] in one-minute windows.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use tokio::sync::oneshot;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Length of the windows API requests are counted in
+pub const QUOTA_WINDOW: Duration = Duration::from_secs(60);
+
+/// How many background jobs may run at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobLimits {
+    pub total: usize,
+    pub per_project: usize,
+}
+
+struct Waiter {
+    project_id: Uuid,
+    ready: oneshot::Sender<()>,
+}
+
+#[derive(Default)]
+struct JobState {
+    limits: Option<JobLimits>,
+    running: HashMap<Uuid, usize>,
+    waiting: VecDeque<Waiter>,
+    /// Jobs started so far, to order projects by when their last job started
+    started: u64,
+    last_started: HashMap<Uuid, u64>,
+}
+
+impl JobState {
+    fn running_total(&self) -> usize {
+        self.running.values().sum()
+    }
+
+    fn running(&self, project_id: Uuid) -> usize {
+        self.running.get(&project_id).copied().unwrap_or(0)
+    }
+
+    fn can_start(&self, project_id: Uuid) -> bool {
+        let Some(limits) = self.limits else {
+            return true;
+        };
+        self.running_total() < limits.total.max(1)
+            && self.running(project_id) < limits.per_project.max(1)
+    }
+
+    fn start(&mut self, project_id: Uuid) {
+        *self.running.entry(project_id).or_default() += 1;
+        self.started += 1;
+        self.last_started.insert(project_id, self.started);
+    }
+
+    fn finish(&mut self, project_id: Uuid) {
+        if let Some(running) = self.running.get_mut(&project_id) {
+            *running -= 1;
+            if *running == 0 {
+                self.running.remove(&project_id);
+            }
+        }
+    }
+
+    /// Start waiting jobs while there is room: the project with the fewest running jobs
+    /// first, then the one whose last job started longest ago, then in queue order
+    fn dispatch(&mut self) {
+        loop {
+            self.waiting.retain(|waiter| !waiter.ready.is_closed());
+            let next = self
+                .waiting
+                .iter()
+                .enumerate()
+                .filter(|(_, waiter)| self.can_start(waiter.project_id))
+                .min_by_key(|(position, waiter)| {
+                    (
+                        self.running(waiter.project_id),
+                        self.last_started.get(&waiter.project_id).copied(),
+                        *position,
+                    )
+                })
+                .map(|(position, _)| position);
+            let Some(waiter) = next.and_then(|position| self.waiting.remove(position)) else {
+                return;
+            };
+            self.start(waiter.project_id);
+            // The job stopped waiting in the meantime, so its slot goes to the next one
+            if waiter.ready.send(()).is_err() {
+                self.finish(waiter.project_id);
+            }
+        }
+    }
+}
+
+/// Background jobs running and waiting for a project
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BackgroundJobUsage {
+    pub running: usize,
+    pub waiting: usize,
+    /// Jobs the project may run at once
+    pub limit: usize,
+}
+
+/// Fair-share scheduler of background jobs
+#[derive(Clone, Default)]
+pub struct BackgroundJobs {
+    state: Arc<Mutex<JobState>>,
+}
+
+/// A running background job; its slot is freed when this is dropped
+pub struct JobPermit {
+    state: Arc<Mutex<JobState>>,
+    project_id: Uuid,
+}
+
+impl Drop for JobPermit {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.finish(self.project_id);
+        state.dispatch();
+    }
+}
+
+/// A job waiting for a slot. If it stops waiting just after it got one, the slot is
+/// given back.
+struct Waiting {
+    state: Arc<Mutex<JobState>>,
+    project_id: Uuid,
+    ready: oneshot::Receiver<()>,
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        self.ready.close();
+        if self.ready.try_recv().is_ok() {
+            state.finish(self.project_id);
+            state.dispatch();
+        }
+    }
+}
+
+impl BackgroundJobs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait for a slot to run a job of `project_id`. Limits apply from this call on, so
+    /// changes to them take effect with the next job.
+    pub async fn acquire(&self, project_id: Uuid, limits: JobLimits) -> JobPermit {
+        let (ready, wait) = oneshot::channel();
+        {
+            let mut state = self.state.lock().unwrap();
+            state.limits = Some(limits);
+            state.waiting.push_back(Waiter { project_id, ready });
+            state.dispatch();
+        }
+        let mut waiting = Waiting {
+            state: self.state.clone(),
+            project_id,
+            ready: wait,
+        };
+        // The sender is only dropped after it was sent to, once the slot is this job's
+        let _ = (&mut waiting.ready).await;
+        JobPermit {
+            state: self.state.clone(),
+            project_id,
+        }
+    }
+
+    pub fn usage(&self, project_id: Uuid, limits: JobLimits) -> BackgroundJobUsage {
+        let state = self.state.lock().unwrap();
+        BackgroundJobUsage {
+            running: state.running(project_id),
+            waiting: state
+                .waiting
+                .iter()
+                .filter(|waiter| waiter.project_id == project_id && !waiter.ready.is_closed())
+                .count(),
+            limit: limits.per_project.max(1).min(limits.total.max(1)),
+        }
+    }
+}
+
+/// API requests of a project in the current window
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ApiQuotaUsage {
+    /// Requests allowed per minute; `None` when unlimited
+    pub limit: Option<u32>,
+    pub used: u32,
+    pub remaining: Option<u32>,
+    /// Seconds until the window ends and the count starts over
+    pub resets_in_secs: u64,
+}
+
+/// A request over the quota of its project
+#[derive(Debug, Clone)]
+pub struct QuotaExceeded {
+    pub limit: u32,
+    pub retry_after: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    started: Instant,
+    used: u32,
+}
+
+impl Window {
+    fn current(window: Option<Window>, now: Instant) -> Window {
+        match window {
+            Some(window) if now.duration_since(window.started) < QUOTA_WINDOW => window,
+            _ => Window {
+                started: now,
+                used: 0,
+            },
+        }
+    }
+
+    fn resets_in(&self, now: Instant) -> Duration {
+        QUOTA_WINDOW.saturating_sub(now.duration_since(self.started))
+    }
+}
+
+/// Per-project API request counts in fixed one-minute windows
+#[derive(Debug, Default)]
+pub struct ApiQuota {
+    windows: Mutex<HashMap<Uuid, Window>>,
+}
+
+impl ApiQuota {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a request of `project_id`, unless it would go over `limit` requests per
+    /// window. Without a limit, requests are counted but never refused.
+    pub fn check(
+        &self,
+        project_id: Uuid,
+        limit: Option<u32>,
+        now: Instant,
+    ) -> Result<ApiQuotaUsage, QuotaExceeded> {
+        let mut windows = self.windows.lock().unwrap();
+        let mut window = Window::current(windows.get(&project_id).copied(), now);
+        if let Some(limit) = limit
+            && window.used >= limit
+        {
+            return Err(QuotaExceeded {
+                limit,
+                retry_after: window.resets_in(now),
+            });
+        }
+        window.used += 1;
+        windows.insert(project_id, window);
+        Ok(usage(window, limit, now))
+    }
+
+    /// Requests of `project_id` in the current window, without counting one
+    pub fn usage(&self, project_id: Uuid, limit: Option<u32>, now: Instant) -> ApiQuotaUsage {
+        let windows = self.windows.lock().unwrap();
+        usage(
+            Window::current(windows.get(&project_id).copied(), now),
+            limit,
+            now,
+        )
+    }
+}
+
+fn usage(window: Window, limit: Option<u32>, now: Instant) -> ApiQuotaUsage {
+    ApiQuotaUsage {
+        limit,
+        used: window.used,
+        remaining: limit.map(|limit| limit.saturating_sub(window.used)),
+        resets_in_secs: window.resets_in(now).as_secs(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex as StdMutex;
+
+    use super::*;
+
+    const LIMITS: JobLimits = JobLimits {
+        total: 2,
+        per_project: 1,
+    };
+
+    #[tokio::test]
+    async fn a_project_runs_at_most_its_share() {
+        let jobs = BackgroundJobs::new();
+        let project = Uuid::new_v4();
+        let _first = jobs.acquire(project, LIMITS).await;
+
+        let waiting =
+            tokio::time::timeout(Duration::from_millis(50), jobs.acquire(project, LIMITS));
+        assert!(waiting.await.is_err());
+        assert_eq!(jobs.usage(project, LIMITS).running, 1);
+    }
+
+    #[tokio::test]
+    async fn freed_slots_go_to_the_project_with_the_fewest_jobs() {
+        let jobs = BackgroundJobs::new();
+        let limits = JobLimits {
+            total: 1,
+            per_project: 1,
+        };
+        let busy = Uuid::new_v4();
+        let quiet = Uuid::new_v4();
+        let order = Arc::new(StdMutex::new(Vec::new()));
+
+        let first = jobs.acquire(busy, limits).await;
+        let mut handles = Vec::new();
+        for project in [busy, busy, quiet] {
+            let jobs = jobs.clone();
+            let order = order.clone();
+            handles.push(tokio::spawn(async move {
+                let _permit = jobs.acquire(project, limits).await;
+                order.lock().unwrap().push(project);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }));
+            // Queue the jobs in a known order
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        drop(first);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        // The busy project already had a turn, so the quiet one goes first although its
+        // job was queued last
+        assert_eq!(*order.lock().unwrap(), vec![quiet, busy, busy]);
+    }
+
+    #[tokio::test]
+    async fn other_projects_are_not_starved() {
+        let jobs = BackgroundJobs::new();
+        let busy = Uuid::new_v4();
+        let _running = jobs.acquire(busy, LIMITS).await;
+        let queued = {
+            let jobs = jobs.clone();
+            tokio::spawn(async move { jobs.acquire(busy, LIMITS).await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let other = tokio::time::timeout(
+            Duration::from_millis(50),
+            jobs.acquire(Uuid::new_v4(), LIMITS),
+        );
+        assert!(other.await.is_ok());
+        queued.abort();
+    }
+
+    #[tokio::test]
+    async fn abandoned_waits_give_up_their_turn() {
+        let jobs = BackgroundJobs::new();
+        let project = Uuid::new_v4();
+        let first = jobs.acquire(project, LIMITS).await;
+        let abandoned =
+            tokio::time::timeout(Duration::from_millis(10), jobs.acquire(project, LIMITS));
+        assert!(abandoned.await.is_err());
+
+        drop(first);
+        assert_eq!(jobs.usage(project, LIMITS).running, 0);
+        assert_eq!(jobs.usage(project, LIMITS).waiting, 0);
+    }
+
+    #[test]
+    fn requests_over_the_quota_wait_for_the_next_window() {
+        let quota = ApiQuota::new();
+        let project = Uuid::new_v4();
+        let start = Instant::now();
+
+        assert_eq!(
+            quota.check(project, Some(2), start).unwrap().remaining,
+            Some(1)
+        );
+        assert_eq!(
+            quota.check(project, Some(2), start).unwrap().remaining,
+            Some(0)
+        );
+        let later = start + Duration::from_secs(20);
+        let exceeded = quota.check(project, Some(2), later).unwrap_err();
+        assert_eq!(exceeded.limit, 2);
+        assert_eq!(exceeded.retry_after, Duration::from_secs(40));
+
+        let next_window = start + QUOTA_WINDOW;
+        assert_eq!(quota.check(project, Some(2), next_window).unwrap().used, 1);
+    }
+
+    #[test]
+    fn projects_have_separate_quotas() {
+        let quota = ApiQuota::new();
+        let now = Instant::now();
+        let busy = Uuid::new_v4();
+        quota.check(busy, Some(1), now).unwrap();
+        assert!(quota.check(busy, Some(1), now).is_err());
+        assert!(quota.check(Uuid::new_v4(), Some(1), now).is_ok());
+    }
+
+    #[test]
+    fn requests_without_a_limit_are_only_counted() {
+        let quota = ApiQuota::new();
+        let project = Uuid::new_v4();
+        let now = Instant::now();
+        for _ in 0..3 {
+            quota.check(project, None, now).unwrap();
+        }
+        let usage = quota.usage(project, None, now);
+        assert_eq!(usage.used, 3);
+        assert_eq!(usage.remaining, None);
+    }
+}
@@ -14,14 +14,16 @@ use utils::{
     api::{
         oauth::{
             HandoffInitRequest, HandoffInitResponse, HandoffRedeemRequest, HandoffRedeemResponse,
-            ProfileResponse, TokenRefreshRequest, TokenRefreshResponse,
+            ListSessionsResponse, ProfileResponse, RevokeSessionsResponse, TokenRefreshRequest,
+            TokenRefreshResponse,
         },
         organizations::{
-            AcceptInvitationResponse, CreateInvitationRequest, CreateInvitationResponse,
-            CreateOrganizationRequest, CreateOrganizationResponse, GetInvitationResponse,
-            GetOrganizationResponse, ListInvitationsResponse, ListMembersResponse,
-            ListOrganizationsResponse, Organization, RevokeInvitationRequest,
-            UpdateMemberRoleRequest, UpdateMemberRoleResponse, UpdateOrganizationRequest,
+            AcceptInvitationResponse, AccessReportResponse, CreateInvitationRequest,
+            CreateInvitationResponse, CreateOrganizationRequest, CreateOrganizationResponse,
+            GetInvitationResponse, GetOrganizationResponse, ListInvitationsResponse,
+            ListMembersResponse, ListOrganizationsResponse, Organization, PermissionCheckQuery,
+            PermissionCheckResponse, RevokeInvitationRequest, UpdateMemberRoleRequest,
+            UpdateMemberRoleResponse, UpdateOrganizationRequest,
         },
         projects::{ListProjectsResponse, RemoteProject},
     },
@@ -403,11 +405,52 @@ impl RemoteClient {
         self.delete_authed("/v1/oauth/logout").await
     }
 
+    /// Lists the user's active sessions across devices.
+    pub async fn list_sessions(&self) -> Result<ListSessionsResponse, RemoteClientError> {
+        self.get_authed("/v1/me/sessions").await
+    }
+
+    /// Revokes one of the user's sessions.
+    pub async fn revoke_session(&self, session_id: Uuid) -> Result<(), RemoteClientError> {
+        self.delete_authed(&format!("/v1/me/sessions/{session_id}"))
+            .await
+    }
+
+    /// Revokes every session of the user, including this one.
+    pub async fn revoke_all_sessions(&self) -> Result<RevokeSessionsResponse, RemoteClientError> {
+        self.post_authed("/v1/me/sessions/revoke-all", None::<&()>)
+            .await
+    }
+
     /// Lists organizations for the authenticated user.
     pub async fn list_organizations(&self) -> Result<ListOrganizationsResponse, RemoteClientError> {
         self.get_authed("/v1/organizations").await
     }
 
+    /// Checks whether a user may perform an action on an organization, project or issue.
+    pub async fn check_permission(
+        &self,
+        query: &PermissionCheckQuery,
+    ) -> Result<PermissionCheckResponse, RemoteClientError> {
+        let params =
+            serde_json::to_value(query).map_err(|e| RemoteClientError::Serde(e.to_string()))?;
+        let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+        if let Value::Object(params) = params {
+            for (key, value) in params {
+                if let Some(value) = value.as_str() {
+                    serializer.append_pair(&key, value);
+                }
+            }
+        }
+        self.get_authed(&format!("/v1/permissions/check?{}", serializer.finish()))
+            .await
+    }
+
+    /// Access of every member and token in the organizations the user administers.
+    pub async fn access_report(&self) -> Result<AccessReportResponse, RemoteClientError> {
+        self.get_authed("/v1/admin/access-report").await
+    }
+
     /// Lists projects for a given organization.
     pub async fn list_projects(
         &self,
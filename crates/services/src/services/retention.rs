@@ -0,0 +1,351 @@
+//! Retention policies of [`RetentionConfig`]: logs of runs are deleted after a number of
+//! days, the runs themselves after a number of months, and the oldest artifacts once they
+//! take more space than allowed. Categories in dry run are only reported.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Months, Utc};
+use db::{
+    DBService,
+    models::{audit_log::AuditLogRun, execution_process_logs::ExecutionProcessLogs},
+};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use tokio::{sync::RwLock, time::interval};
+use tracing::{error, info};
+use ts_rs::TS;
+
+use crate::services::{
+    config::{Config, RetentionCategory, RetentionConfig},
+    e2e_tests, preview_comparison, preview_screenshot,
+};
+
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+#[derive(Debug, Error)]
+pub enum RetentionError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// What a retention policy deleted, or would delete in dry run
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RetentionReport {
+    pub category: RetentionCategory,
+    /// Nothing was deleted; the report is of what would be
+    pub dry_run: bool,
+    /// Runs whose logs are past the limit, runs, or artifact files and folders
+    pub items: u64,
+    /// Size of the logs or artifacts; `None` for runs, whose size isn't tracked
+    pub bytes: Option<u64>,
+    /// Data older than this is past the limit; `None` for artifacts, which are limited
+    /// by size
+    pub cutoff: Option<DateTime<Utc>>,
+}
+
+/// A file or folder directly inside one of the artifact folders, e.g. the folder of an
+/// e2e run or a screenshot
+#[derive(Debug, Clone, PartialEq)]
+struct Artifact {
+    path: PathBuf,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+fn artifact_roots() -> Vec<PathBuf> {
+    vec![
+        e2e_tests::artifacts_root(),
+        preview_screenshot::screenshots_dir(),
+        preview_comparison::comparisons_dir(),
+    ]
+}
+
+/// Size of a file, or of everything in a folder. Symlinks count as themselves.
+fn disk_size(path: &Path) -> std::io::Result<u64> {
+    let metadata = std::fs::symlink_metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+    let mut bytes = 0;
+    for entry in std::fs::read_dir(path)? {
+        bytes += disk_size(&entry?.path())?;
+    }
+    Ok(bytes)
+}
+
+/// Entries of each folder of `roots`; folders that don't exist yet have none
+fn list_artifacts(roots: &[PathBuf]) -> std::io::Result<Vec<Artifact>> {
+    let mut artifacts = Vec::new();
+    for root in roots {
+        let entries = match std::fs::read_dir(root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            let modified = std::fs::symlink_metadata(&path)?.modified()?;
+            artifacts.push(Artifact {
+                bytes: disk_size(&path)?,
+                path,
+                modified,
+            });
+        }
+    }
+    Ok(artifacts)
+}
+
+/// The oldest artifacts that have to go for the rest to fit in `max_bytes`
+fn over_budget(mut artifacts: Vec<Artifact>, max_bytes: u64) -> Vec<Artifact> {
+    artifacts.sort_by_key(|artifact| artifact.modified);
+    let mut total: u64 = artifacts.iter().map(|artifact| artifact.bytes).sum();
+    let mut expired = Vec::new();
+    for artifact in artifacts {
+        if total <= max_bytes {
+            break;
+        }
+        total -= artifact.bytes;
+        expired.push(artifact);
+    }
+    expired
+}
+
+fn remove_artifact(path: &Path) -> std::io::Result<()> {
+    let result = match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => std::fs::remove_dir_all(path),
+        Ok(_) => std::fs::remove_file(path),
+        Err(e) => Err(e),
+    };
+    match result {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+async fn enforce_artifacts(max_gb: f64, dry_run: bool) -> Result<RetentionReport, RetentionError> {
+    let max_bytes = (max_gb.max(0.0) * BYTES_PER_GB) as u64;
+    let expired = tokio::task::spawn_blocking(move || -> std::io::Result<Vec<Artifact>> {
+        let expired = over_budget(list_artifacts(&artifact_roots())?, max_bytes);
+        if !dry_run {
+            for artifact in &expired {
+                remove_artifact(&artifact.path)?;
+            }
+        }
+        Ok(expired)
+    })
+    .await
+    .map_err(std::io::Error::other)??;
+
+    Ok(RetentionReport {
+        category: RetentionCategory::Artifacts,
+        dry_run,
+        items: expired.len() as u64,
+        bytes: Some(expired.iter().map(|artifact| artifact.bytes).sum()),
+        cutoff: None,
+    })
+}
+
+/// Apply each policy that has a limit, or with `report_only` just report what each would
+/// delete. Categories in the configured dry run are always only reported.
+pub async fn enforce(
+    pool: &SqlitePool,
+    settings: &RetentionConfig,
+    report_only: bool,
+) -> Result<Vec<RetentionReport>, RetentionError> {
+    let now = Utc::now();
+    let dry_run = |category| report_only || settings.dry_run.contains(&category);
+    let mut reports = Vec::new();
+
+    if let Some(days) = settings.attempt_log_days {
+        let cutoff = now - chrono::Duration::days(i64::from(days));
+        let dry_run = dry_run(RetentionCategory::AttemptLogs);
+        let usage = ExecutionProcessLogs::usage_completed_before(pool, cutoff).await?;
+        if !dry_run {
+            ExecutionProcessLogs::delete_completed_before(pool, cutoff).await?;
+        }
+        reports.push(RetentionReport {
+            category: RetentionCategory::AttemptLogs,
+            dry_run,
+            items: usage.processes.max(0) as u64,
+            bytes: Some(usage.bytes.max(0) as u64),
+            cutoff: Some(cutoff),
+        });
+    }
+
+    if let Some(months) = settings.audit_event_months {
+        let cutoff = now
+            .checked_sub_months(Months::new(months))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let dry_run = dry_run(RetentionCategory::AuditEvents);
+        let items = if dry_run {
+            AuditLogRun::count_expired(pool, cutoff).await?.max(0) as u64
+        } else {
+            AuditLogRun::delete_expired(pool, cutoff).await?
+        };
+        reports.push(RetentionReport {
+            category: RetentionCategory::AuditEvents,
+            dry_run,
+            items,
+            bytes: None,
+            cutoff: Some(cutoff),
+        });
+    }
+
+    if let Some(max_gb) = settings.artifact_max_gb {
+        reports.push(enforce_artifacts(max_gb, dry_run(RetentionCategory::Artifacts)).await?);
+    }
+
+    Ok(reports)
+}
+
+/// Service that applies the retention policies once a day while they are enabled
+pub struct RetentionService {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+}
+
+impl RetentionService {
+    pub async fn spawn(db: DBService, config: Arc<RwLock<Config>>) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            config,
+            poll_interval: Duration::from_secs(24 * 60 * 60), // Enforce once a day
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting retention service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.apply_policies().await {
+                error!("Error enforcing retention policies: {}", e);
+            }
+        }
+    }
+
+    async fn apply_policies(&self) -> Result<(), RetentionError> {
+        let settings = self.config.read().await.retention.clone();
+        if !settings.enabled {
+            return Ok(());
+        }
+
+        for report in enforce(&self.db.pool, &settings, false).await? {
+            let action = if report.dry_run {
+                "would delete"
+            } else {
+                "deleted"
+            };
+            match report.bytes {
+                Some(bytes) => info!(
+                    "Retention of {:?} {} {} items ({} bytes)",
+                    report.category, action, report.items, bytes
+                ),
+                None => info!(
+                    "Retention of {:?} {} {} items",
+                    report.category, action, report.items
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artifact(name: &str, bytes: u64, age_secs: u64) -> Artifact {
+        Artifact {
+            path: PathBuf::from(name),
+            bytes,
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000 - age_secs),
+        }
+    }
+
+    fn names(artifacts: &[Artifact]) -> Vec<&str> {
+        artifacts
+            .iter()
+            .map(|artifact| artifact.path.to_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn nothing_is_over_budget_when_everything_fits() {
+        let artifacts = vec![artifact("a", 10, 30), artifact("b", 20, 10)];
+        assert!(over_budget(artifacts, 30).is_empty());
+    }
+
+    #[test]
+    fn oldest_artifacts_go_first_until_the_rest_fits() {
+        let artifacts = vec![
+            artifact("new", 10, 10),
+            artifact("oldest", 10, 30),
+            artifact("old", 10, 20),
+        ];
+        assert_eq!(names(&over_budget(artifacts, 15)), vec!["oldest", "old"]);
+    }
+
+    #[test]
+    fn a_zero_budget_removes_every_artifact() {
+        let artifacts = vec![artifact("a", 1, 10), artifact("b", 1, 20)];
+        assert_eq!(names(&over_budget(artifacts, 0)), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn artifacts_are_the_entries_of_each_root_with_their_total_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let runs = dir.path().join("e2e-runs");
+        std::fs::create_dir_all(runs.join("run/videos")).unwrap();
+        std::fs::write(runs.join("run/report.xml"), [0; 100]).unwrap();
+        std::fs::write(runs.join("run/videos/test.webm"), [0; 250]).unwrap();
+        let screenshots = dir.path().join("preview-screenshots");
+        std::fs::create_dir_all(&screenshots).unwrap();
+        std::fs::write(screenshots.join("shot.png"), [0; 40]).unwrap();
+
+        let roots = vec![
+            runs.clone(),
+            screenshots.clone(),
+            dir.path().join("missing"),
+        ];
+        let mut artifacts = list_artifacts(&roots).unwrap();
+        artifacts.sort_by_key(|artifact| artifact.bytes);
+
+        let found: Vec<_> = artifacts
+            .iter()
+            .map(|artifact| (artifact.path.clone(), artifact.bytes))
+            .collect();
+        assert_eq!(
+            found,
+            vec![(screenshots.join("shot.png"), 40), (runs.join("run"), 350)]
+        );
+    }
+
+    #[test]
+    fn removing_an_artifact_removes_folders_and_ignores_missing_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let run = dir.path().join("run");
+        std::fs::create_dir_all(run.join("videos")).unwrap();
+        std::fs::write(run.join("videos/test.webm"), [0; 10]).unwrap();
+
+        remove_artifact(&run).unwrap();
+        assert!(!run.exists());
+        remove_artifact(&run).unwrap();
+    }
+}
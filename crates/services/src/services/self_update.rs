@@ -0,0 +1,380 @@
+//! Updating the server binary in place from a signed release feed.
+//!
+//! The feed (`VK_UPDATE_FEED_URL`) is a JSON document describing the latest
+//! release, with one raw binary per platform:
+//!
+//! ```json
+//! {
+//!   "version": "0.0.160",
+//!   "notes_url": "https://example.com/releases/0.0.160",
+//!   "platforms": {
+//!     "linux-x64": { "url": "...", "sha256": "<hex>", "signature": "<base64>" }
+//!   }
+//! }
+//! ```
+//!
+//! `signature` is an Ed25519 signature over the release version, the platform key and
+//! the binary's checksum (see `signed_message`), checked against
+//! `VK_UPDATE_PUBLIC_KEY` (base64) before the version is trusted. Signing all three
+//! stops a feed from passing off an older signed binary as a new release, or one
+//! platform's binary as another's. Updating is disabled unless both are set.
+
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use ts_rs::TS;
+use utils::version::APP_VERSION;
+
+const FEED_TIMEOUT: Duration = Duration::from_secs(15);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Error)]
+pub enum SelfUpdateError {
+    #[error("self-update is not configured (set VK_UPDATE_FEED_URL and VK_UPDATE_PUBLIC_KEY)")]
+    NotConfigured,
+    #[error("invalid VK_UPDATE_PUBLIC_KEY: {0}")]
+    InvalidPublicKey(String),
+    #[error("release feed request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("release feed has no build for {0}")]
+    UnsupportedPlatform(String),
+    #[error("already running the latest version ({0})")]
+    UpToDate(String),
+    #[error("checksum mismatch: expected {expected}, got {actual}")]
+    Checksum { expected: String, actual: String },
+    #[error("signature verification failed")]
+    Signature,
+    #[error("failed to replace binary: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ReleaseFeed {
+    version: String,
+    notes_url: Option<String>,
+    #[serde(default)]
+    platforms: HashMap<String, ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ReleaseAsset {
+    url: String,
+    sha256: String,
+    signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct UpdateCheck {
+    /// False when no feed or public key is configured
+    pub enabled: bool,
+    pub current_version: String,
+    pub latest_version: Option<String>,
+    pub update_available: bool,
+    pub notes_url: Option<String>,
+}
+
+/// A new binary that has been verified and swapped in; it runs after a restart
+#[derive(Debug, Clone)]
+pub struct InstalledUpdate {
+    pub version: String,
+    pub binary_path: PathBuf,
+    /// The previous binary, kept for manual rollback
+    pub backup_path: PathBuf,
+}
+
+/// Platform key used by release feeds, matching the npx-cli directory names
+pub fn platform_key() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => Some("linux-x64"),
+        ("linux", "aarch64") => Some("linux-arm64"),
+        ("macos", "x86_64") => Some("macos-x64"),
+        ("macos", "aarch64") => Some("macos-arm64"),
+        ("windows", "x86_64") => Some("windows-x64"),
+        ("windows", "aarch64") => Some("windows-arm64"),
+        _ => None,
+    }
+}
+
+/// True if `latest` is a newer `major.minor.patch` than `current`.
+/// Pre-release suffixes (`-beta.1`) are ignored.
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    fn parse(version: &str) -> Vec<u64> {
+        version
+            .trim()
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    }
+    parse(latest) > parse(current)
+}
+
+/// The message a release asset's signature covers
+fn signed_message(version: &str, platform: &str, sha256: &str) -> String {
+    format!(
+        "vibe-kanban\n{}\n{}\n{}\n",
+        version.trim(),
+        platform,
+        sha256.trim().to_ascii_lowercase()
+    )
+}
+
+/// Check that the feed's `version`, `platform` and checksum of `asset` were signed
+/// together by the release key
+fn verify_release(
+    key: &VerifyingKey,
+    version: &str,
+    platform: &str,
+    asset: &ReleaseAsset,
+) -> Result<(), SelfUpdateError> {
+    let signature = BASE64
+        .decode(asset.signature.trim())
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or(SelfUpdateError::Signature)?;
+    key.verify(
+        signed_message(version, platform, &asset.sha256).as_bytes(),
+        &signature,
+    )
+    .map_err(|_| SelfUpdateError::Signature)
+}
+
+/// Check that the downloaded binary is the one the signed checksum describes
+fn verify_checksum(asset: &ReleaseAsset, bytes: &[u8]) -> Result<(), SelfUpdateError> {
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if !actual.eq_ignore_ascii_case(asset.sha256.trim()) {
+        return Err(SelfUpdateError::Checksum {
+            expected: asset.sha256.clone(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Write the new binary next to the running one, then swap them with renames so
+/// the executable path is never missing or half-written
+fn replace_binary(bytes: &[u8]) -> Result<(PathBuf, PathBuf), std::io::Error> {
+    let current = std::env::current_exe()?;
+    let staged = current.with_extension("new");
+    let backup = current.with_extension("old");
+
+    std::fs::write(&staged, bytes)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    let _ = std::fs::remove_file(&backup);
+    std::fs::rename(&current, &backup)?;
+    if let Err(e) = std::fs::rename(&staged, &current) {
+        // Put the old binary back so the next start still works
+        let _ = std::fs::rename(&backup, &current);
+        let _ = std::fs::remove_file(&staged);
+        return Err(e);
+    }
+    Ok((current, backup))
+}
+
+#[derive(Clone)]
+pub struct SelfUpdateService {
+    client: reqwest::Client,
+    feed_url: Option<String>,
+    public_key: Option<VerifyingKey>,
+}
+
+impl SelfUpdateService {
+    pub fn from_env() -> Result<Self, SelfUpdateError> {
+        let var = |name| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let public_key = var("VK_UPDATE_PUBLIC_KEY")
+            .map(|encoded| {
+                let bytes = BASE64
+                    .decode(encoded.trim())
+                    .map_err(|e| SelfUpdateError::InvalidPublicKey(e.to_string()))?;
+                let bytes: [u8; 32] = bytes.try_into().map_err(|_| {
+                    SelfUpdateError::InvalidPublicKey("expected 32 bytes".to_string())
+                })?;
+                VerifyingKey::from_bytes(&bytes)
+                    .map_err(|e| SelfUpdateError::InvalidPublicKey(e.to_string()))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .user_agent(concat!("vibe-kanban/", env!("CARGO_PKG_VERSION")))
+                .build()?,
+            feed_url: var("VK_UPDATE_FEED_URL"),
+            public_key,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.feed_url.is_some() && self.public_key.is_some()
+    }
+
+    async fn fetch_feed(&self) -> Result<ReleaseFeed, SelfUpdateError> {
+        let url = self
+            .feed_url
+            .as_ref()
+            .ok_or(SelfUpdateError::NotConfigured)?;
+        let feed = self
+            .client
+            .get(url)
+            .timeout(FEED_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(feed)
+    }
+
+    pub async fn check(&self) -> Result<UpdateCheck, SelfUpdateError> {
+        let mut check = UpdateCheck {
+            enabled: self.is_enabled(),
+            current_version: APP_VERSION.to_string(),
+            latest_version: None,
+            update_available: false,
+            notes_url: None,
+        };
+        if !check.enabled {
+            return Ok(check);
+        }
+
+        let feed = self.fetch_feed().await?;
+        if let (Some(key), Some(platform)) = (&self.public_key, platform_key())
+            && let Some(asset) = feed.platforms.get(platform)
+        {
+            verify_release(key, &feed.version, platform, asset)?;
+            check.update_available = is_newer(&feed.version, APP_VERSION);
+        }
+        check.latest_version = Some(feed.version);
+        check.notes_url = feed.notes_url;
+        Ok(check)
+    }
+
+    /// Download, verify and swap in the latest binary. The running process keeps
+    /// using the old one until it is restarted.
+    pub async fn install(&self) -> Result<InstalledUpdate, SelfUpdateError> {
+        let key = self.public_key.ok_or(SelfUpdateError::NotConfigured)?;
+        let feed = self.fetch_feed().await?;
+        let platform = platform_key().unwrap_or(std::env::consts::OS);
+        let asset = feed
+            .platforms
+            .get(platform)
+            .ok_or_else(|| SelfUpdateError::UnsupportedPlatform(platform.to_string()))?;
+        // The version is only trusted once its signature checks out
+        verify_release(&key, &feed.version, platform, asset)?;
+        if !is_newer(&feed.version, APP_VERSION) {
+            return Err(SelfUpdateError::UpToDate(APP_VERSION.to_string()));
+        }
+
+        tracing::info!(
+            "Downloading vibe-kanban {} from {}",
+            feed.version,
+            asset.url
+        );
+        let bytes = self
+            .client
+            .get(&asset.url)
+            .timeout(DOWNLOAD_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        verify_checksum(asset, &bytes)?;
+
+        let (binary_path, backup_path) =
+            tokio::task::spawn_blocking(move || replace_binary(&bytes))
+                .await
+                .map_err(std::io::Error::other)??;
+        tracing::info!(
+            "Installed vibe-kanban {} at {} (previous binary kept at {})",
+            feed.version,
+            binary_path.display(),
+            backup_path.display()
+        );
+
+        Ok(InstalledUpdate {
+            version: feed.version,
+            binary_path,
+            backup_path,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+
+    #[test]
+    fn compares_versions_numerically() {
+        assert!(is_newer("0.0.160", "0.0.158"));
+        assert!(is_newer("v0.1.0", "0.0.999"));
+        assert!(is_newer("0.0.10", "0.0.9"));
+        assert!(!is_newer("0.0.158", "0.0.158"));
+        assert!(!is_newer("0.0.157", "0.0.158"));
+        assert!(!is_newer("0.0.158-beta.1", "0.0.158"));
+    }
+
+    fn signed_asset(
+        signing: &SigningKey,
+        version: &str,
+        platform: &str,
+        binary: &[u8],
+    ) -> ReleaseAsset {
+        let sha256 = format!("{:x}", Sha256::digest(binary));
+        let message = signed_message(version, platform, &sha256);
+        ReleaseAsset {
+            url: String::new(),
+            sha256,
+            signature: BASE64.encode(signing.sign(message.as_bytes()).to_bytes()),
+        }
+    }
+
+    #[test]
+    fn verifies_version_platform_and_checksum_together() {
+        let signing = SigningKey::from_bytes(&[7; 32]);
+        let key = signing.verifying_key();
+        let asset = signed_asset(&signing, "0.0.160", "linux-x64", b"not really a binary");
+        assert!(verify_release(&key, "0.0.160", "linux-x64", &asset).is_ok());
+
+        // An old signed binary can't be passed off as a newer release
+        assert!(matches!(
+            verify_release(&key, "0.0.999", "linux-x64", &asset),
+            Err(SelfUpdateError::Signature)
+        ));
+        // Nor as another platform's binary
+        assert!(matches!(
+            verify_release(&key, "0.0.160", "macos-arm64", &asset),
+            Err(SelfUpdateError::Signature)
+        ));
+
+        let other = SigningKey::from_bytes(&[8; 32]);
+        assert!(matches!(
+            verify_release(&other.verifying_key(), "0.0.160", "linux-x64", &asset),
+            Err(SelfUpdateError::Signature)
+        ));
+    }
+
+    #[test]
+    fn verifies_the_downloaded_checksum() {
+        let signing = SigningKey::from_bytes(&[7; 32]);
+        let asset = signed_asset(&signing, "0.0.160", "linux-x64", b"not really a binary");
+        assert!(verify_checksum(&asset, b"not really a binary").is_ok());
+        assert!(matches!(
+            verify_checksum(&asset, b"not really a binarY"),
+            Err(SelfUpdateError::Checksum { .. })
+        ));
+    }
+}
@@ -0,0 +1,438 @@
+//! Read-only SQL queries against the app database, so operators can answer ad-hoc
+//! questions (e.g. attempts per executor last month) without a shell on the host.
+//! Queries are single SELECT statements that may only read allowlisted tables, and
+//! return a bounded number of rows.
+
+use std::{collections::HashMap, time::Duration};
+
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+use sqlx::{Column, Row, SqliteConnection, SqlitePool, TypeInfo, ValueRef, sqlite::SqliteRow};
+use thiserror::Error;
+use ts_rs::TS;
+
+/// Tables queries can read. Tables holding keys, drafts, scratch data or raw logs are
+/// left out.
+pub const ALLOWED_TABLES: &[&str] = &[
+    "coding_agent_turns",
+    "e2e_runs",
+    "e2e_test_cases",
+    "execution_processes",
+    "merges",
+    "preview_builds",
+    "project_repos",
+    "projects",
+    "repos",
+    "sessions",
+    "tags",
+    "task_documents",
+    "tasks",
+    "test_results",
+    "wip_limits",
+    "workers",
+    "workspace_repos",
+    "workspaces",
+];
+/// Rows returned when the query doesn't ask for a number
+pub const DEFAULT_MAX_ROWS: usize = 100;
+/// Most rows a query can return
+pub const MAX_ROWS: usize = 1000;
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum SqlConsoleError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Only a single SELECT statement can be run")]
+    NotASelect,
+    #[error("Queries can't write to the database")]
+    Write,
+    #[error("Table {0} is not in the allowlist")]
+    TableNotAllowed(String),
+    #[error("Virtual tables and table-valued functions can't be queried")]
+    VirtualTable,
+    #[error("Parameter {0} must be a string, number, boolean or null")]
+    InvalidParameter(usize),
+    #[error("The query didn't finish within {} seconds", QUERY_TIMEOUT.as_secs())]
+    Timeout,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SqlQuery {
+    pub sql: String,
+    /// Bound to the `?` or `?N` placeholders of the query, in order
+    #[serde(default)]
+    pub params: Vec<Value>,
+    /// Defaults to 100, at most 1000
+    #[serde(default)]
+    #[ts(optional)]
+    pub max_rows: Option<usize>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SqlQueryResult {
+    /// Names of the columns, empty when no rows matched
+    pub columns: Vec<String>,
+    /// Values of each row in the order of the columns. Blobs are given by their size.
+    pub rows: Vec<Vec<Value>>,
+    /// More rows matched than were returned
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SqlConsoleTable {
+    pub name: String,
+    pub columns: Vec<String>,
+}
+
+/// The allowlisted tables with their columns
+pub async fn list_tables(pool: &SqlitePool) -> Result<Vec<SqlConsoleTable>, sqlx::Error> {
+    let mut tables = Vec::with_capacity(ALLOWED_TABLES.len());
+    for name in ALLOWED_TABLES {
+        let columns: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info($1) ORDER BY cid")
+                .bind(*name)
+                .fetch_all(pool)
+                .await?;
+        if !columns.is_empty() {
+            tables.push(SqlConsoleTable {
+                name: name.to_string(),
+                columns,
+            });
+        }
+    }
+    Ok(tables)
+}
+
+/// Run `query` in a transaction that is rolled back, after checking that it is a single
+/// SELECT statement that only reads allowlisted tables
+pub async fn run_query(
+    pool: &SqlitePool,
+    query: &SqlQuery,
+) -> Result<SqlQueryResult, SqlConsoleError> {
+    check_statement(&query.sql)?;
+    let max_rows = query
+        .max_rows
+        .unwrap_or(DEFAULT_MAX_ROWS)
+        .clamp(1, MAX_ROWS);
+
+    let mut tx = pool.begin().await?;
+    let result = tokio::time::timeout(QUERY_TIMEOUT, async {
+        check_plan(&mut tx, &query.sql).await?;
+        fetch_rows(&mut tx, &query.sql, &query.params, max_rows).await
+    })
+    .await
+    .map_err(|_| SqlConsoleError::Timeout)?;
+    tx.rollback().await?;
+    result
+}
+
+/// Check that `sql` is one statement starting with SELECT, WITH or VALUES. SQLite runs
+/// whatever follows a semicolon as further statements, so only comments can.
+fn check_statement(sql: &str) -> Result<(), SqlConsoleError> {
+    let mut chars = sql.chars().peekable();
+    let mut keyword: Option<String> = None;
+    let mut ended = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '-' if chars.peek() == Some(&'-') => {
+                chars.by_ref().find(|&c| c == '\n');
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            _ if ended => return Err(SqlConsoleError::NotASelect),
+            ';' => ended = true,
+            // Strings and quoted names, where doubled quotes read as two quoted parts
+            '\'' | '"' | '`' => {
+                chars.by_ref().find(|&next| next == c);
+            }
+            '[' => {
+                chars.by_ref().find(|&c| c == ']');
+            }
+            _ => {}
+        }
+        if keyword.is_none() {
+            let mut word = String::from(c);
+            while let Some(next) = chars.next_if(char::is_ascii_alphabetic) {
+                word.push(next);
+            }
+            keyword = Some(word.to_ascii_lowercase());
+        }
+    }
+    match keyword.as_deref() {
+        Some("select" | "with" | "values") => Ok(()),
+        _ => Err(SqlConsoleError::NotASelect),
+    }
+}
+
+/// Check with the query plan of `sql` that it writes nothing and only reads allowlisted
+/// tables. The plan opens b-trees by their root page, which maps indexes to their table
+/// as well, so views and covering indexes don't get around the allowlist.
+async fn check_plan(conn: &mut SqliteConnection, sql: &str) -> Result<(), SqlConsoleError> {
+    let root_pages: HashMap<i64, String> = sqlx::query_as::<_, (i64, String)>(
+        "SELECT rootpage, tbl_name FROM sqlite_schema WHERE type IN ('table', 'index')",
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .collect();
+
+    let plan = sqlx::query(&format!("EXPLAIN {sql}"))
+        .persistent(false)
+        .fetch_all(&mut *conn)
+        .await?;
+    for step in plan {
+        let opcode: String = step.try_get("opcode")?;
+        let p2: i64 = step.try_get("p2")?;
+        let p3: i64 = step.try_get("p3")?;
+        match opcode.as_str() {
+            // P2 of a transaction is 0 for read transactions
+            "Transaction" if p2 != 0 => return Err(SqlConsoleError::Write),
+            "OpenWrite" => return Err(SqlConsoleError::Write),
+            "VOpen" => return Err(SqlConsoleError::VirtualTable),
+            "OpenRead" | "ReopenIdx" => {
+                // Pages that aren't in the schema are the schema itself's, and P3 is
+                // the database, which is only the main one for the app's tables
+                let table = root_pages
+                    .get(&p2)
+                    .filter(|_| p3 == 0)
+                    .map_or("sqlite_schema", String::as_str);
+                if !ALLOWED_TABLES.contains(&table) {
+                    return Err(SqlConsoleError::TableNotAllowed(table.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+async fn fetch_rows(
+    conn: &mut SqliteConnection,
+    sql: &str,
+    params: &[Value],
+    max_rows: usize,
+) -> Result<SqlQueryResult, SqlConsoleError> {
+    let mut query = sqlx::query(sql).persistent(false);
+    for (index, param) in params.iter().enumerate() {
+        query = match param {
+            Value::Null => query.bind(None::<String>),
+            Value::Bool(value) => query.bind(*value),
+            Value::Number(value) => match value.as_i64() {
+                Some(value) => query.bind(value),
+                None => query.bind(value.as_f64()),
+            },
+            Value::String(value) => query.bind(value.clone()),
+            Value::Array(_) | Value::Object(_) => {
+                return Err(SqlConsoleError::InvalidParameter(index + 1));
+            }
+        };
+    }
+
+    let mut result = SqlQueryResult {
+        columns: Vec::new(),
+        rows: Vec::new(),
+        truncated: false,
+    };
+    let mut rows = query.fetch(conn);
+    while let Some(row) = rows.try_next().await? {
+        if result.rows.len() == max_rows {
+            result.truncated = true;
+            break;
+        }
+        if result.columns.is_empty() {
+            result.columns = row
+                .columns()
+                .iter()
+                .map(|column| column.name().to_string())
+                .collect();
+        }
+        result.rows.push(row_values(&row)?);
+    }
+    Ok(result)
+}
+
+fn row_values(row: &SqliteRow) -> Result<Vec<Value>, sqlx::Error> {
+    (0..row.columns().len())
+        .map(|index| {
+            let value = row.try_get_raw(index)?;
+            if value.is_null() {
+                return Ok(Value::Null);
+            }
+            Ok(match value.type_info().name() {
+                "INTEGER" => Value::from(row.try_get::<i64, _>(index)?),
+                "REAL" => Number::from_f64(row.try_get::<f64, _>(index)?)
+                    .map_or(Value::Null, Value::Number),
+                "BLOB" => Value::String(format!(
+                    "<{} bytes>",
+                    row.try_get::<Vec<u8>, _>(index)?.len()
+                )),
+                _ => Value::String(row.try_get::<String, _>(index)?),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    #[test]
+    fn accepts_single_select_statements() {
+        assert!(check_statement("SELECT 1").is_ok());
+        assert!(check_statement("  -- count\nselect count(*) from tasks;\n-- done").is_ok());
+        assert!(check_statement("WITH t AS (SELECT 1) SELECT * FROM t").is_ok());
+        assert!(check_statement("SELECT 'a;b', \"c;\" /* ; */ FROM tasks ;  ").is_ok());
+        assert!(check_statement("SELECT 'it''s; fine'").is_ok());
+    }
+
+    #[test]
+    fn rejects_other_statements() {
+        for sql in [
+            "",
+            "-- SELECT 1",
+            "DELETE FROM tasks",
+            "PRAGMA table_info(tasks)",
+            "ATTACH 'other.db' AS other",
+            "SELECT 1; DELETE FROM tasks",
+            "SELECT 1;;",
+            "/* SELECT */ UPDATE tasks SET title = ''",
+        ] {
+            assert!(
+                matches!(check_statement(sql), Err(SqlConsoleError::NotASelect)),
+                "{sql}"
+            );
+        }
+    }
+
+    async fn test_pool() -> SqlitePool {
+        // One connection, since every connection to `:memory:` is its own database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::raw_sql(
+            "CREATE TABLE tasks (id INTEGER PRIMARY KEY, title TEXT, estimate REAL, data BLOB);
+             CREATE INDEX idx_tasks_title ON tasks (title);
+             CREATE TABLE document_encryption_keys (id INTEGER PRIMARY KEY, secret TEXT);
+             CREATE VIEW task_keys AS SELECT * FROM document_encryption_keys;
+             INSERT INTO tasks (title, estimate, data) VALUES
+                 ('first', 1.5, x'0102'), ('second', NULL, NULL), ('third', 3, NULL);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    fn query(sql: &str, params: Vec<Value>, max_rows: Option<usize>) -> SqlQuery {
+        SqlQuery {
+            sql: sql.to_string(),
+            params,
+            max_rows,
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_rows_of_allowed_tables() {
+        let pool = test_pool().await;
+        let result = run_query(
+            &pool,
+            &query(
+                "SELECT id, title, estimate, data FROM tasks WHERE id <= ?1 ORDER BY id",
+                vec![json!(2)],
+                None,
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.columns, ["id", "title", "estimate", "data"]);
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![json!(1), json!("first"), json!(1.5), json!("<2 bytes>")],
+                vec![json!(2), json!("second"), Value::Null, Value::Null],
+            ]
+        );
+        assert!(!result.truncated);
+
+        let limited = run_query(&pool, &query("SELECT title FROM tasks", vec![], Some(2)))
+            .await
+            .unwrap();
+        assert_eq!(limited.rows.len(), 2);
+        assert!(limited.truncated);
+
+        // Only the index is read, which still counts as reading its table
+        let indexed = run_query(
+            &pool,
+            &query(
+                "SELECT title FROM tasks WHERE title = ?",
+                vec![json!("third")],
+                None,
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(indexed.rows, vec![vec![json!("third")]]);
+    }
+
+    #[tokio::test]
+    async fn refuses_other_tables_and_writes() {
+        let pool = test_pool().await;
+        for (sql, table) in [
+            (
+                "SELECT * FROM document_encryption_keys",
+                "document_encryption_keys",
+            ),
+            ("SELECT * FROM task_keys", "document_encryption_keys"),
+            ("SELECT * FROM sqlite_schema", "sqlite_schema"),
+        ] {
+            match run_query(&pool, &query(sql, vec![], None)).await {
+                Err(SqlConsoleError::TableNotAllowed(name)) => assert_eq!(name, table),
+                other => panic!("{sql}: {other:?}"),
+            }
+        }
+        assert!(matches!(
+            run_query(
+                &pool,
+                &query("WITH t AS (SELECT 1) DELETE FROM tasks", vec![], None)
+            )
+            .await,
+            Err(SqlConsoleError::Write)
+        ));
+        assert!(matches!(
+            run_query(
+                &pool,
+                &query("SELECT * FROM pragma_table_info('tasks')", vec![], None)
+            )
+            .await,
+            Err(SqlConsoleError::VirtualTable)
+        ));
+        assert!(matches!(
+            run_query(&pool, &query("SELECT ?", vec![json!([1])], None)).await,
+            Err(SqlConsoleError::InvalidParameter(1))
+        ));
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tasks")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+    }
+}
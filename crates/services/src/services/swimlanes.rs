@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use db::models::{
+    task::{Task, TaskPriority, TaskStatus, TaskWithAttemptStatus},
+    task_package_scope::TaskPackageScope,
+    workspace::Workspace,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use uuid::Uuid;
+
+const STATUS_ORDER: [TaskStatus; 5] = [
+    TaskStatus::Todo,
+    TaskStatus::InProgress,
+    TaskStatus::InReview,
+    TaskStatus::Done,
+    TaskStatus::Cancelled,
+];
+
+const PRIORITY_ORDER: [TaskPriority; 4] = [
+    TaskPriority::Urgent,
+    TaskPriority::High,
+    TaskPriority::Medium,
+    TaskPriority::Low,
+];
+
+/// How board tasks are grouped into swimlanes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskGroupBy {
+    Status,
+    /// The coding agent of the task's most recent attempt
+    Assignee,
+    /// The parent task that spawned the task
+    Epic,
+    /// The monorepo package the task is scoped to
+    Package,
+    Priority,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SwimlaneStatusCount {
+    pub status: TaskStatus,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct Swimlane {
+    /// Stable lane identifier, `None` for tasks without a value for the grouping
+    pub key: Option<String>,
+    pub label: String,
+    pub count: usize,
+    pub status_counts: Vec<SwimlaneStatusCount>,
+    pub tasks: Vec<TaskWithAttemptStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskBoard {
+    pub group_by: TaskGroupBy,
+    pub total: usize,
+    pub lanes: Vec<Swimlane>,
+}
+
+/// The lane a task belongs to: `(key, label)`
+pub type LaneKey = (String, String);
+
+/// Group tasks into lanes, keeping the task order within each lane.
+///
+/// Lanes are ordered by label, except status lanes which follow the board's
+/// column order and priority lanes which go from urgent to low. Tasks without
+/// a lane are collected in a final "None" lane.
+pub fn group_tasks(
+    group_by: TaskGroupBy,
+    tasks: Vec<TaskWithAttemptStatus>,
+    lane_of: impl Fn(&TaskWithAttemptStatus) -> Option<LaneKey>,
+) -> TaskBoard {
+    let total = tasks.len();
+    let mut lanes: Vec<Swimlane> = Vec::new();
+    let mut index: HashMap<Option<String>, usize> = HashMap::new();
+
+    for task in tasks {
+        let lane = lane_of(&task);
+        let key = lane.as_ref().map(|(key, _)| key.clone());
+        let i = *index.entry(key.clone()).or_insert_with(|| {
+            lanes.push(Swimlane {
+                key,
+                label: lane
+                    .map(|(_, label)| label)
+                    .unwrap_or_else(|| "None".to_string()),
+                count: 0,
+                status_counts: Vec::new(),
+                tasks: Vec::new(),
+            });
+            lanes.len() - 1
+        });
+        lanes[i].tasks.push(task);
+    }
+
+    for lane in &mut lanes {
+        lane.count = lane.tasks.len();
+        lane.status_counts = STATUS_ORDER
+            .iter()
+            .map(|status| SwimlaneStatusCount {
+                status: status.clone(),
+                count: lane.tasks.iter().filter(|t| &t.status == status).count(),
+            })
+            .filter(|c| c.count > 0)
+            .collect();
+    }
+
+    let rank = |lane: &Swimlane| -> Option<usize> {
+        let key = lane.key.as_deref()?;
+        match group_by {
+            TaskGroupBy::Status => STATUS_ORDER.iter().position(|s| s.to_string() == key),
+            TaskGroupBy::Priority => PRIORITY_ORDER.iter().position(|p| p.to_string() == key),
+            _ => None,
+        }
+    };
+    lanes.sort_by(|a, b| {
+        a.key
+            .is_none()
+            .cmp(&b.key.is_none())
+            .then_with(|| rank(a).cmp(&rank(b)))
+            .then_with(|| a.label.to_lowercase().cmp(&b.label.to_lowercase()))
+    });
+
+    TaskBoard {
+        group_by,
+        total,
+        lanes,
+    }
+}
+
+/// Load the project's board and group it into swimlanes
+pub async fn board_for_project(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    group_by: TaskGroupBy,
+) -> Result<TaskBoard, sqlx::Error> {
+    let tasks = Task::find_by_project_id_with_attempt_status(pool, project_id).await?;
+
+    let board = match group_by {
+        TaskGroupBy::Status => group_tasks(group_by, tasks, |task| {
+            Some((task.status.to_string(), task.status.to_string()))
+        }),
+        TaskGroupBy::Priority => group_tasks(group_by, tasks, |task| {
+            Some((task.priority.to_string(), task.priority.to_string()))
+        }),
+        TaskGroupBy::Assignee => group_tasks(group_by, tasks, |task| {
+            (!task.executor.is_empty()).then(|| (task.executor.clone(), task.executor.clone()))
+        }),
+        TaskGroupBy::Epic => {
+            let titles: HashMap<Uuid, String> =
+                tasks.iter().map(|t| (t.id, t.title.clone())).collect();
+            let mut parents: HashMap<Uuid, Option<LaneKey>> = HashMap::new();
+            for workspace_id in tasks.iter().filter_map(|t| t.parent_workspace_id) {
+                if parents.contains_key(&workspace_id) {
+                    continue;
+                }
+                let parent = Workspace::find_by_id(pool, workspace_id)
+                    .await?
+                    .and_then(|w| {
+                        titles
+                            .get(&w.task_id)
+                            .map(|title| (w.task_id.to_string(), title.clone()))
+                    });
+                parents.insert(workspace_id, parent);
+            }
+            group_tasks(group_by, tasks, |task| {
+                task.parent_workspace_id
+                    .and_then(|id| parents.get(&id).cloned().flatten())
+            })
+        }
+        TaskGroupBy::Package => {
+            let scopes: HashMap<Uuid, LaneKey> =
+                TaskPackageScope::find_by_project_id(pool, project_id)
+                    .await?
+                    .into_iter()
+                    .map(|s| {
+                        (
+                            s.task_id,
+                            (format!("{}:{}", s.repo_id, s.package_path), s.package_name),
+                        )
+                    })
+                    .collect();
+            group_tasks(group_by, tasks, |task| scopes.get(&task.id).cloned())
+        }
+    };
+
+    Ok(board)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn task(title: &str, status: TaskStatus, executor: &str) -> TaskWithAttemptStatus {
+        TaskWithAttemptStatus {
+            task: Task {
+                id: Uuid::new_v4(),
+                project_id: Uuid::nil(),
+                title: title.to_string(),
+                description: None,
+                status,
+                parent_workspace_id: None,
+                priority: TaskPriority::default(),
+                priority_escalated_at: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            has_in_progress_attempt: false,
+            last_attempt_failed: false,
+            executor: executor.to_string(),
+        }
+    }
+
+    #[test]
+    fn groups_by_assignee_with_unassigned_lane_last() {
+        let tasks = vec![
+            task("a", TaskStatus::Todo, ""),
+            task("b", TaskStatus::InProgress, "CODEX"),
+            task("c", TaskStatus::Done, "CLAUDE_CODE"),
+            task("d", TaskStatus::InProgress, "CODEX"),
+        ];
+        let board = group_tasks(TaskGroupBy::Assignee, tasks, |t| {
+            (!t.executor.is_empty()).then(|| (t.executor.clone(), t.executor.clone()))
+        });
+
+        assert_eq!(board.total, 4);
+        let lanes: Vec<(Option<&str>, usize)> = board
+            .lanes
+            .iter()
+            .map(|l| (l.key.as_deref(), l.count))
+            .collect();
+        assert_eq!(
+            lanes,
+            vec![(Some("CLAUDE_CODE"), 1), (Some("CODEX"), 2), (None, 1)]
+        );
+        assert_eq!(board.lanes[1].status_counts.len(), 1);
+        assert_eq!(board.lanes[1].status_counts[0].count, 2);
+    }
+
+    #[test]
+    fn status_lanes_follow_column_order() {
+        let tasks = vec![
+            task("a", TaskStatus::Done, ""),
+            task("b", TaskStatus::Todo, ""),
+            task("c", TaskStatus::InReview, ""),
+        ];
+        let board = group_tasks(TaskGroupBy::Status, tasks, |t| {
+            Some((t.status.to_string(), t.status.to_string()))
+        });
+        let keys: Vec<&str> = board
+            .lanes
+            .iter()
+            .filter_map(|l| l.key.as_deref())
+            .collect();
+        assert_eq!(keys, vec!["todo", "inreview", "done"]);
+    }
+}
@@ -0,0 +1,89 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use db::{DBService, models::task::Task};
+use sqlx::error::Error as SqlxError;
+use tokio::{sync::RwLock, time::interval};
+use tracing::{debug, error, info};
+
+use crate::services::{config::Config, notification::NotificationService};
+
+/// Service that raises the priority of tasks left in Todo longer than the
+/// configured number of days
+pub struct TaskAgingService {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+    notification: NotificationService,
+    poll_interval: Duration,
+}
+
+impl TaskAgingService {
+    pub async fn spawn(db: DBService, config: Arc<RwLock<Config>>) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            notification: NotificationService::new(config.clone()),
+            config,
+            poll_interval: Duration::from_secs(60 * 60), // Check every hour
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting task aging service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.escalate_aging_tasks().await {
+                error!("Error escalating aging tasks: {}", e);
+            }
+        }
+    }
+
+    /// Bump each aging Todo task one priority level and notify about it.
+    /// A task is bumped again after another `todo_days` in Todo.
+    async fn escalate_aging_tasks(&self) -> Result<(), SqlxError> {
+        let settings = self.config.read().await.task_aging.clone();
+        if !settings.enabled {
+            return Ok(());
+        }
+
+        let cutoff = Utc::now() - chrono::Duration::days(i64::from(settings.todo_days.max(1)));
+        let tasks = Task::find_aging_todo(&self.db.pool, cutoff).await?;
+        if tasks.is_empty() {
+            debug!("No aging tasks to escalate");
+            return Ok(());
+        }
+
+        let mut escalated = Vec::new();
+        for task in tasks {
+            let Some(priority) = task.priority.escalated() else {
+                continue;
+            };
+            Task::escalate_priority(&self.db.pool, task.id, priority).await?;
+            info!(
+                "Escalated task {} from {} to {} after {} days in todo",
+                task.id, task.priority, priority, settings.todo_days
+            );
+            escalated.push(format!("{} ({})", task.title, priority));
+        }
+
+        if !escalated.is_empty() {
+            let message = match escalated.as_slice() {
+                [single] => format!("Waiting in Todo too long, priority raised: {single}"),
+                many => format!(
+                    "{} tasks waiting in Todo too long had their priority raised",
+                    many.len()
+                ),
+            };
+            self.notification.notify("Tasks escalated", &message).await;
+        }
+        Ok(())
+    }
+}
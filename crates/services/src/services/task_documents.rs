@@ -0,0 +1,35 @@
+//! Documents related to a task, such as the design docs it implements, which the coding
+//! agent working on the task is pointed at.
+
+/// Prompt section listing the documents related to a task at `paths`, relative to the
+/// attempt's workspace. Empty if there are none.
+pub fn related_documents_prompt(paths: &[String]) -> String {
+    if paths.is_empty() {
+        return String::new();
+    }
+    let mut section = String::from(
+        "\n\nRelated documents for this task. Read them before making changes and keep the implementation consistent with them:",
+    );
+    for path in paths {
+        section.push_str(&format!("\n- `{path}`"));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_related_documents() {
+        assert_eq!(related_documents_prompt(&[]), "");
+        let section = related_documents_prompt(&[
+            "app/docs/design/search.md".to_string(),
+            "app/docs/adr/0003-storage.md".to_string(),
+        ]);
+        assert!(section.starts_with("\n\nRelated documents for this task."));
+        assert!(
+            section.ends_with("\n- `app/docs/design/search.md`\n- `app/docs/adr/0003-storage.md`")
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+use ts_rs::TS;
+
+use crate::services::config::Config;
+
+/// Events kept in memory while waiting to be sent (oldest are dropped first)
+const MAX_QUEUED_EVENTS: usize = 500;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// String properties that only ever hold enum-like values and are safe to send
+const ALLOWED_STRING_PROPERTIES: &[&str] = &[
+    "executor", "variant", "trigger", "format", "group_by", "sort", "strategy",
+];
+
+/// A single anonymized feature-usage event
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TelemetryEvent {
+    pub event: String,
+    /// Booleans, numbers and allow-listed enum-like strings only
+    #[ts(type = "Record<string, unknown>")]
+    pub properties: Map<String, Value>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Exactly what is sent to the telemetry endpoint in one request
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TelemetryBatch {
+    /// Salted hash of the local user id, not reversible to the machine or user
+    pub installation_id: String,
+    pub app_version: String,
+    pub os: String,
+    pub events: Vec<TelemetryEvent>,
+}
+
+/// Opt-in usage telemetry.
+///
+/// Events are always queued in memory so they can be inspected, but are only
+/// sent when telemetry is enabled in the config and an endpoint is configured.
+#[derive(Clone)]
+pub struct TelemetryService {
+    installation_id: String,
+    queue: Arc<Mutex<VecDeque<TelemetryEvent>>>,
+    client: reqwest::Client,
+}
+
+/// Strip everything that could identify a user, project or repository
+fn anonymize(properties: &Value) -> Map<String, Value> {
+    let Some(object) = properties.as_object() else {
+        return Map::new();
+    };
+    object
+        .iter()
+        .filter(|(key, value)| match value {
+            Value::Bool(_) | Value::Number(_) => true,
+            Value::String(_) => ALLOWED_STRING_PROPERTIES.contains(&key.as_str()),
+            _ => false,
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+impl TelemetryService {
+    pub fn new(user_id: &str) -> Self {
+        let digest = Sha256::digest(format!("vibe-kanban-telemetry:{user_id}").as_bytes());
+        let mut installation_id = format!("{digest:x}");
+        installation_id.truncate(32);
+
+        Self {
+            installation_id,
+            queue: Arc::new(Mutex::new(VecDeque::new())),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .unwrap(),
+        }
+    }
+
+    /// Queue an anonymized copy of an event
+    pub fn record(&self, event: &str, properties: &Value) {
+        let mut queue = self.queue.lock().unwrap();
+        if queue.len() >= MAX_QUEUED_EVENTS {
+            queue.pop_front();
+        }
+        queue.push_back(TelemetryEvent {
+            event: event.to_string(),
+            properties: anonymize(properties),
+            timestamp: Utc::now(),
+        });
+    }
+
+    /// The batch that the next flush would send
+    pub fn preview(&self) -> TelemetryBatch {
+        TelemetryBatch {
+            installation_id: self.installation_id.clone(),
+            app_version: utils::version::APP_VERSION.to_string(),
+            os: std::env::consts::OS.to_string(),
+            events: self.queue.lock().unwrap().iter().cloned().collect(),
+        }
+    }
+
+    /// Endpoint events are sent to: the config override, else `VK_TELEMETRY_ENDPOINT`
+    pub fn endpoint(config: &Config) -> Option<String> {
+        config
+            .telemetry
+            .endpoint
+            .clone()
+            .filter(|e| !e.trim().is_empty())
+            .or_else(|| std::env::var("VK_TELEMETRY_ENDPOINT").ok())
+            .or_else(|| option_env!("VK_TELEMETRY_ENDPOINT").map(|s| s.to_string()))
+    }
+
+    async fn flush(&self, endpoint: &str) {
+        let batch = self.preview();
+        if batch.events.is_empty() {
+            return;
+        }
+        let sent = batch.events.len();
+
+        match self.client.post(endpoint).json(&batch).send().await {
+            Ok(response) if response.status().is_success() => {
+                let mut queue = self.queue.lock().unwrap();
+                // Events recorded during the request stay queued
+                let sent = sent.min(queue.len());
+                queue.drain(..sent);
+                tracing::debug!("Sent {} telemetry events", sent);
+            }
+            Ok(response) => {
+                tracing::warn!("Telemetry endpoint returned {}", response.status());
+            }
+            Err(e) => tracing::warn!("Failed to send telemetry: {}", e),
+        }
+    }
+
+    /// Periodically send queued events while telemetry is enabled
+    pub fn spawn_flusher(&self, config: Arc<RwLock<Config>>) -> tokio::task::JoinHandle<()> {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                let endpoint = {
+                    let config = config.read().await;
+                    if !config.telemetry.enabled {
+                        continue;
+                    }
+                    Self::endpoint(&config)
+                };
+                if let Some(endpoint) = endpoint {
+                    service.flush(&endpoint).await;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn anonymize_drops_identifying_properties() {
+        let properties = json!({
+            "task_id": "6f1c0d3e-0000-0000-0000-000000000000",
+            "project_id": "a4b1c2d3-0000-0000-0000-000000000000",
+            "executor": "CLAUDE_CODE",
+            "has_description": true,
+            "repository_count": 2,
+            "nested": { "path": "/home/me/repo" },
+        });
+        let anonymized = anonymize(&properties);
+        assert_eq!(
+            Value::Object(anonymized),
+            json!({
+                "executor": "CLAUDE_CODE",
+                "has_description": true,
+                "repository_count": 2,
+            })
+        );
+    }
+
+    #[test]
+    fn queue_is_bounded() {
+        let service = TelemetryService::new("user");
+        for _ in 0..MAX_QUEUED_EVENTS + 10 {
+            service.record("task_created", &json!({}));
+        }
+        let batch = service.preview();
+        assert_eq!(batch.events.len(), MAX_QUEUED_EVENTS);
+        assert_eq!(batch.installation_id.len(), 32);
+        assert_ne!(batch.installation_id, "user");
+    }
+}
@@ -0,0 +1,205 @@
+//! Export and erasure of what the instance stores about one person, for contributors who
+//! ask for their data or for it to be removed.
+//!
+//! People aren't accounts here: they are the free-form names documents are annotated,
+//! reviewed, drafted and locked under. Erasure deletes what only concerns the person,
+//! i.e. drafts and locks, and replaces their name with a pseudonym everywhere else so
+//! that discussions, reviews and approval rules stay whole. Tasks and runs don't record
+//! who made them, and commits in git history are left alone.
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    document_annotation::DocumentAnnotation,
+    document_approval::{DocumentApprovalRule, DocumentChangeRequest, DocumentChangeReview},
+    document_draft::DocumentDraft,
+    document_lock::DocumentLock,
+    project::Project,
+};
+use serde::Serialize;
+use sqlx::{SqliteConnection, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A project that commits document changes under the person's name
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CommitIdentityUse {
+    pub project_id: Uuid,
+    pub committer_name: String,
+    pub committer_email: Option<String>,
+}
+
+/// Everything stored about a person
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct UserDataExport {
+    pub name: String,
+    pub exported_at: DateTime<Utc>,
+    /// Annotations they wrote or resolved
+    pub annotations: Vec<DocumentAnnotation>,
+    pub reviews: Vec<DocumentChangeReview>,
+    /// Change requests that need or needed their review
+    pub change_requests: Vec<DocumentChangeRequest>,
+    /// Protected folders they review
+    pub approval_rules: Vec<DocumentApprovalRule>,
+    pub drafts: Vec<DocumentDraft>,
+    pub locks: Vec<DocumentLock>,
+    pub commit_identities: Vec<CommitIdentityUse>,
+}
+
+/// What erasing a person changed
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct UserDataErasure {
+    /// Pseudonym that replaced the name
+    pub replacement: String,
+    pub annotations: u64,
+    pub reviews: u64,
+    pub change_requests: u64,
+    pub approval_rules: u64,
+    pub drafts_deleted: u64,
+    pub locks_released: u64,
+    /// Projects whose commit identity was cleared
+    pub commit_identities: u64,
+}
+
+/// A pseudonym that can't be traced back to the name it replaces
+fn pseudonym() -> String {
+    let id = Uuid::new_v4().simple().to_string();
+    format!("deleted-user-{}", &id[..8])
+}
+
+/// `names` with `name` replaced by `replacement`, keeping the order and without
+/// duplicates
+fn replace_name(names: &[String], name: &str, replacement: &str) -> Vec<String> {
+    let mut replaced: Vec<String> = Vec::with_capacity(names.len());
+    for entry in names {
+        let entry = if entry == name {
+            replacement
+        } else {
+            entry.as_str()
+        };
+        if !replaced.iter().any(|existing| existing == entry) {
+            replaced.push(entry.to_string());
+        }
+    }
+    replaced
+}
+
+async fn commit_identities(
+    conn: &mut SqliteConnection,
+    name: &str,
+) -> Result<Vec<CommitIdentityUse>, sqlx::Error> {
+    let mut identities = Vec::new();
+    for project in Project::find_all(&mut *conn).await? {
+        let settings = Project::find_document_commit_settings(&mut *conn, project.id).await?;
+        if settings.committer_name.as_deref() == Some(name) {
+            identities.push(CommitIdentityUse {
+                project_id: project.id,
+                committer_name: name.to_string(),
+                committer_email: settings.committer_email,
+            });
+        }
+    }
+    Ok(identities)
+}
+
+pub async fn export(pool: &SqlitePool, name: &str) -> Result<UserDataExport, sqlx::Error> {
+    Ok(UserDataExport {
+        name: name.to_string(),
+        exported_at: Utc::now(),
+        annotations: DocumentAnnotation::find_by_person(pool, name).await?,
+        reviews: DocumentChangeReview::find_by_reviewer(pool, name).await?,
+        change_requests: DocumentChangeRequest::find_by_required_reviewer(pool, name).await?,
+        approval_rules: DocumentApprovalRule::find_by_reviewer(pool, name).await?,
+        drafts: DocumentDraft::find_all_by_author(pool, name).await?,
+        locks: DocumentLock::find_by_holder(pool, name).await?,
+        commit_identities: commit_identities(&mut *pool.acquire().await?, name).await?,
+    })
+}
+
+/// Delete the drafts and locks of `name` and replace the name with a pseudonym
+/// everywhere else. Approval rules keep the pseudonym as reviewer, so protected folders
+/// stay protected until someone else is made their reviewer. Everything is changed in
+/// one transaction, so a failed erasure leaves the name untouched.
+pub async fn erase(pool: &SqlitePool, name: &str) -> Result<UserDataErasure, sqlx::Error> {
+    let replacement = pseudonym();
+    let mut tx = pool.begin().await?;
+
+    let annotations = DocumentAnnotation::rename_person(&mut *tx, name, &replacement).await?;
+    let reviews = DocumentChangeReview::rename_reviewer(&mut *tx, name, &replacement).await?;
+
+    let change_requests = DocumentChangeRequest::find_by_required_reviewer(&mut *tx, name).await?;
+    for request in &change_requests {
+        let reviewers = replace_name(&request.required_reviewers, name, &replacement);
+        DocumentChangeRequest::set_required_reviewers(&mut *tx, request.id, &reviewers).await?;
+    }
+    let approval_rules = DocumentApprovalRule::find_by_reviewer(&mut *tx, name).await?;
+    for rule in &approval_rules {
+        let reviewers = replace_name(&rule.reviewers, name, &replacement);
+        DocumentApprovalRule::set_reviewers(&mut *tx, rule.id, &reviewers).await?;
+    }
+
+    let drafts_deleted = DocumentDraft::delete_by_author(&mut *tx, name).await?;
+    let locks_released = DocumentLock::release_all(&mut *tx, name).await?;
+
+    let identities = commit_identities(&mut tx, name).await?;
+    for identity in &identities {
+        let mut settings =
+            Project::find_document_commit_settings(&mut *tx, identity.project_id).await?;
+        settings.committer_name = None;
+        settings.committer_email = None;
+        Project::set_document_commit_settings(&mut *tx, identity.project_id, Some(&settings))
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(UserDataErasure {
+        replacement,
+        annotations,
+        reviews,
+        change_requests: change_requests.len() as u64,
+        approval_rules: approval_rules.len() as u64,
+        drafts_deleted,
+        locks_released,
+        commit_identities: identities.len() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn the_name_is_replaced_in_place() {
+        assert_eq!(
+            replace_name(&names(&["alice", "bob", "carol"]), "bob", "deleted-user-1"),
+            names(&["alice", "deleted-user-1", "carol"])
+        );
+    }
+
+    #[test]
+    fn other_names_are_left_alone() {
+        assert_eq!(
+            replace_name(&names(&["alice", "carol"]), "bob", "deleted-user-1"),
+            names(&["alice", "carol"])
+        );
+    }
+
+    #[test]
+    fn a_replacement_already_listed_is_not_repeated() {
+        assert_eq!(
+            replace_name(&names(&["deleted-user-1", "bob"]), "bob", "deleted-user-1"),
+            names(&["deleted-user-1"])
+        );
+    }
+
+    #[test]
+    fn pseudonyms_differ_between_erasures() {
+        let first = pseudonym();
+        assert!(first.starts_with("deleted-user-"));
+        assert_ne!(first, pseudonym());
+    }
+}
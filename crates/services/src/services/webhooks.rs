@@ -0,0 +1,265 @@
+//! Outbound webhooks of a project. When its documents change, each subscribed webhook
+//! is sent a JSON [`WebhookPayload`] signed with the webhook's secret, so receivers such
+//! as static site builders can check it came from this server.
+//!
+//! The signature is `sha256=` followed by the hex HMAC-SHA256 of `<timestamp>.<body>`,
+//! where the timestamp is the `X-Webhook-Timestamp` header in unix seconds.
+
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+use chrono::{DateTime, Utc};
+use db::models::project_webhook::{ProjectWebhook, WebhookEvent};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Failed to call the webhook: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("The webhook returned {0}")]
+    Rejected(reqwest::StatusCode),
+}
+
+impl WebhookError {
+    /// Failures that may pass; other rejections are not retried
+    fn should_retry(&self) -> bool {
+        match self {
+            WebhookError::Request(_) => true,
+            WebhookError::Rejected(status) => {
+                status.is_server_error() || *status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            }
+        }
+    }
+
+    fn status(&self) -> Option<i64> {
+        match self {
+            WebhookError::Request(e) => e.status().map(|status| i64::from(status.as_u16())),
+            WebhookError::Rejected(status) => Some(i64::from(status.as_u16())),
+        }
+    }
+}
+
+/// Body of a webhook call
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WebhookPayload {
+    /// Id of this delivery, the same on retries, so receivers can drop duplicates
+    pub id: Uuid,
+    pub event: WebhookEvent,
+    pub project_id: Uuid,
+    /// Repository of the document; `None` for `documents.synced`
+    pub repository_id: Option<Uuid>,
+    /// Path of the document from its repository root; `None` for `documents.synced`
+    pub path: Option<String>,
+    /// Commits pushed and pulled by a `documents.synced`
+    pub commits_pushed: Option<usize>,
+    pub commits_pulled: Option<usize>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl WebhookPayload {
+    pub fn document(
+        event: WebhookEvent,
+        project_id: Uuid,
+        repository_id: Uuid,
+        path: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event,
+            project_id,
+            repository_id: Some(repository_id),
+            path: Some(path.into()),
+            commits_pushed: None,
+            commits_pulled: None,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    pub fn synced(project_id: Uuid, commits_pushed: usize, commits_pulled: usize) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event: WebhookEvent::DocumentsSynced,
+            project_id,
+            repository_id: None,
+            path: None,
+            commits_pushed: Some(commits_pushed),
+            commits_pulled: Some(commits_pulled),
+            occurred_at: Utc::now(),
+        }
+    }
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> impl std::fmt::LowerHex {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes()
+}
+
+/// `X-Webhook-Signature` of a body sent at `timestamp`
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let message = [format!("{timestamp}.").as_bytes(), body].concat();
+    format!("sha256={:x}", hmac_sha256(secret.as_bytes(), &message))
+}
+
+/// Call `webhook` with `payload`, retrying failures that may pass. Returns the status the
+/// endpoint answered with.
+pub async fn deliver(
+    client: &reqwest::Client,
+    webhook: &ProjectWebhook,
+    payload: &WebhookPayload,
+) -> Result<u16, WebhookError> {
+    let body = serde_json::to_vec(payload).expect("webhook payloads serialize");
+    (|| async {
+        // Signed per attempt, so retries don't look like replays
+        let timestamp = Utc::now().timestamp();
+        let response = client
+            .post(&webhook.url)
+            .timeout(DELIVERY_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, payload.event.as_str())
+            .header(DELIVERY_HEADER, payload.id.to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, sign(&webhook.secret, timestamp, &body))
+            .body(body.clone())
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(WebhookError::Rejected(status));
+        }
+        Ok(status.as_u16())
+    })
+    .retry(
+        &ExponentialBuilder::default()
+            .with_min_delay(Duration::from_secs(1))
+            .with_max_delay(Duration::from_secs(30))
+            .with_max_times(3)
+            .with_jitter(),
+    )
+    .when(WebhookError::should_retry)
+    .notify(|e, dur| {
+        tracing::warn!(
+            "Webhook {} failed, retrying after {:.2}s: {}",
+            webhook.url,
+            dur.as_secs_f64(),
+            e
+        )
+    })
+    .await
+}
+
+/// Call every webhook of the payload's project that subscribes to its event, and record
+/// how each delivery went
+pub async fn dispatch(pool: &SqlitePool, payload: &WebhookPayload) {
+    let webhooks = match ProjectWebhook::find_by_project_id(pool, payload.project_id).await {
+        Ok(webhooks) => webhooks,
+        Err(e) => {
+            tracing::warn!("Failed to load webhooks of {}: {}", payload.project_id, e);
+            return;
+        }
+    };
+    let webhooks: Vec<_> = webhooks
+        .into_iter()
+        .filter(|webhook| webhook.subscribes_to(payload.event))
+        .collect();
+    if webhooks.is_empty() {
+        return;
+    }
+
+    let client = reqwest::Client::new();
+    let client = &client;
+    let deliveries = webhooks.iter().map(|webhook| async move {
+        let (status, error) = match deliver(client, webhook, payload).await {
+            Ok(status) => (Some(i64::from(status)), None),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to deliver {} to webhook {}: {}",
+                    payload.event.as_str(),
+                    webhook.url,
+                    e
+                );
+                (e.status(), Some(e.to_string()))
+            }
+        };
+        if let Err(e) =
+            ProjectWebhook::record_delivery(pool, webhook.id, status, error.as_deref()).await
+        {
+            tracing::warn!("Failed to record webhook delivery: {}", e);
+        }
+    });
+    futures::future::join_all(deliveries).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hmac_matches_the_rfc_4231_vectors() {
+        assert_eq!(
+            format!(
+                "{:x}",
+                hmac_sha256(b"Jefe", b"what do ya want for nothing?")
+            ),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Keys longer than a block are hashed first
+        assert_eq!(
+            format!(
+                "{:x}",
+                hmac_sha256(
+                    &[0xaa; 131],
+                    b"Test Using Larger Than Block-Size Key - Hash Key First"
+                )
+            ),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn signatures_cover_the_timestamp_and_body() {
+        let body = br#"{"event":"document.updated"}"#;
+        let signature = sign("secret", 1_700_000_000, body);
+
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(signature, sign("secret", 1_700_000_000, body));
+        assert_ne!(signature, sign("other", 1_700_000_000, body));
+        assert_ne!(signature, sign("secret", 1_700_000_001, body));
+        assert_ne!(signature, sign("secret", 1_700_000_000, b"{}"));
+    }
+
+    #[test]
+    fn payloads_name_their_event() {
+        let project_id = Uuid::new_v4();
+        let payload = serde_json::to_value(WebhookPayload::document(
+            WebhookEvent::DocumentDeleted,
+            project_id,
+            Uuid::new_v4(),
+            "docs/guide.md",
+        ))
+        .unwrap();
+        assert_eq!(payload["event"], "document.deleted");
+        assert_eq!(payload["path"], "docs/guide.md");
+
+        let payload = serde_json::to_value(WebhookPayload::synced(project_id, 2, 1)).unwrap();
+        assert_eq!(payload["event"], "documents.synced");
+        assert_eq!(payload["commits_pushed"], 2);
+        assert!(payload["path"].is_null());
+    }
+}
@@ -0,0 +1,782 @@
+//! Remote workers: agent daemons on other machines that run coding agents for this
+//! server, so its host doesn't need the agents' toolchains. A worker keeps a WebSocket
+//! open, authenticated with the token it got when it was registered, and runs one
+//! execution at a time:
+//!
+//! 1. The server sends [`ServerMessage::Run`] with the action and a git bundle of the
+//!    workspace branch of every repository.
+//! 2. The worker clones the bundles, runs the agent and streams its output back as
+//!    [`WorkerMessage::Log`].
+//! 3. When the agent exits, [`WorkerMessage::Finished`] carries a bundle of every branch
+//!    with the agent's commits and a patch of what it left uncommitted. Both are applied
+//!    to the local worktrees, after which the execution finishes as if it ran locally.
+//!
+//! Workers send [`WorkerMessage::Heartbeat`] with their capabilities, such as `docker` or
+//! `gpu`, when they connect and every [`HEARTBEAT_INTERVAL`]. An execution goes to an idle
+//! worker with every capability it requires, unless the worker is draining or its
+//! heartbeats stopped.
+
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use executors::{actions::ExecutorAction, executors::ExecutorExitResult};
+use rand::{Rng, distributions::Alphanumeric};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tokio::sync::{RwLock, mpsc, oneshot};
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use super::git::{GitCli, GitCliError};
+
+/// Start of every worker token, so they are recognizable in configuration files
+const TOKEN_PREFIX: &str = "vkw_";
+const BUNDLE_FILE: &str = "repo.bundle";
+/// How often workers send heartbeats
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+/// Workers whose last heartbeat is older than this get no executions
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(90);
+
+#[derive(Debug, Error)]
+pub enum WorkerError {
+    #[error(transparent)]
+    Git(#[from] GitCliError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Invalid bundle or patch encoding: {0}")]
+    Encoding(#[from] base64::DecodeError),
+    #[error("Worker {0} is not connected")]
+    NotConnected(Uuid),
+    #[error("No worker is assigned to execution {0}")]
+    NotAssigned(Uuid),
+}
+
+/// A repository of a workspace, as sent between server and worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoSnapshot {
+    /// Name of the repository's directory in the workspace
+    pub name: String,
+    /// Base64 git bundle of the workspace branch
+    pub bundle: String,
+    /// Base64 binary patch of uncommitted changes; only sent by workers
+    #[serde(default)]
+    pub patch: String,
+}
+
+/// An execution handed to a worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerRun {
+    pub execution_process_id: Uuid,
+    /// Workspace branch, checked out in every repository
+    pub branch: String,
+    pub repos: Vec<RepoSnapshot>,
+    pub action: ExecutorAction,
+    /// Environment variables of the agent
+    pub env: HashMap<String, String>,
+    pub commit_reminder: bool,
+}
+
+/// Messages from the server to a worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Run(WorkerRun),
+    /// Stop the agent. The worker still reports [`WorkerMessage::Finished`], so the work
+    /// done so far is kept.
+    Stop {
+        execution_process_id: Uuid,
+    },
+}
+
+/// Messages from a worker to the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorkerMessage {
+    Heartbeat {
+        capabilities: Vec<String>,
+    },
+    /// Output of the agent
+    Log {
+        execution_process_id: Uuid,
+        msg: LogMsg,
+    },
+    Finished {
+        execution_process_id: Uuid,
+        success: bool,
+        repos: Vec<RepoSnapshot>,
+    },
+}
+
+/// A new worker token
+pub fn generate_token() -> String {
+    let secret: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect();
+    format!("{TOKEN_PREFIX}{secret}")
+}
+
+/// Hex SHA-256 of a worker token, as stored for the worker
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Capabilities as they are matched: trimmed, lowercase, sorted and without duplicates
+pub fn normalize_capabilities(capabilities: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = capabilities
+        .iter()
+        .map(|capability| capability.trim().to_lowercase())
+        .filter(|capability| !capability.is_empty())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+fn write_bundle(snapshot: &RepoSnapshot, dir: &Path) -> Result<PathBuf, WorkerError> {
+    let path = dir.join(BUNDLE_FILE);
+    std::fs::write(&path, BASE64.decode(&snapshot.bundle)?)?;
+    Ok(path)
+}
+
+/// Snapshot of the repository at `repo_path`: a bundle of `branch` and, with
+/// `include_changes`, a patch of its uncommitted changes
+pub fn snapshot_repo(
+    name: &str,
+    repo_path: &Path,
+    branch: &str,
+    include_changes: bool,
+) -> Result<RepoSnapshot, WorkerError> {
+    let git = GitCli::new();
+    let dir = tempfile::tempdir()?;
+    let bundle_path = dir.path().join(BUNDLE_FILE);
+    git.bundle_create(repo_path, &bundle_path, branch)?;
+    let patch = if include_changes {
+        git.diff_worktree_binary(repo_path)?
+    } else {
+        Vec::new()
+    };
+    Ok(RepoSnapshot {
+        name: name.to_string(),
+        bundle: BASE64.encode(std::fs::read(&bundle_path)?),
+        patch: BASE64.encode(patch),
+    })
+}
+
+/// Clone a snapshot sent by the server to `dest`, with `branch` checked out
+pub fn clone_snapshot(
+    snapshot: &RepoSnapshot,
+    dest: &Path,
+    branch: &str,
+) -> Result<(), WorkerError> {
+    let dir = tempfile::tempdir()?;
+    let bundle_path = write_bundle(snapshot, dir.path())?;
+    GitCli::new().git(
+        dir.path(),
+        [
+            OsStr::new("clone"),
+            OsStr::new("--branch"),
+            OsStr::new(branch),
+            bundle_path.as_os_str(),
+            dest.as_os_str(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Bring the worktree at `worktree_path` up to date with a snapshot a worker sent back:
+/// fast-forward `branch` to the worker's commits, then apply its uncommitted changes
+pub fn apply_snapshot(
+    snapshot: &RepoSnapshot,
+    worktree_path: &Path,
+    branch: &str,
+) -> Result<(), WorkerError> {
+    let git = GitCli::new();
+    let dir = tempfile::tempdir()?;
+    let bundle_path = write_bundle(snapshot, dir.path())?;
+    git.git(
+        worktree_path,
+        [
+            OsStr::new("fetch"),
+            bundle_path.as_os_str(),
+            OsStr::new(branch),
+        ],
+    )?;
+    git.git(worktree_path, ["merge", "--ff-only", "FETCH_HEAD"])?;
+    let patch = BASE64.decode(&snapshot.patch)?;
+    if !patch.is_empty() {
+        git.apply_binary(worktree_path, &patch)?;
+    }
+    Ok(())
+}
+
+/// Local side of an execution running on a worker
+pub struct RemoteRun {
+    /// Receives the worker's output
+    pub msg_store: Arc<MsgStore>,
+    pub branch: String,
+    /// Local worktree of every repository, by name
+    pub worktrees: HashMap<String, PathBuf>,
+    /// Signalled once the worker's changes are applied, or the worker is gone
+    pub exit: oneshot::Sender<ExecutorExitResult>,
+}
+
+struct AssignedRun {
+    worker_id: Uuid,
+    run: RemoteRun,
+}
+
+struct Connection {
+    id: Uuid,
+    sender: mpsc::UnboundedSender<ServerMessage>,
+    capabilities: Vec<String>,
+    draining: bool,
+    last_heartbeat: Instant,
+}
+
+impl Connection {
+    fn is_healthy(&self) -> bool {
+        self.last_heartbeat.elapsed() < HEARTBEAT_TIMEOUT
+    }
+
+    fn can_run(&self, requirements: &[String]) -> bool {
+        !self.draining
+            && self.is_healthy()
+            && requirements
+                .iter()
+                .all(|requirement| self.capabilities.contains(requirement))
+    }
+}
+
+/// What the pool knows of a worker
+#[derive(Debug, Clone, Default)]
+pub struct WorkerStatus {
+    pub connected: bool,
+    /// Whether the worker's heartbeats arrive
+    pub healthy: bool,
+    /// Execution the worker is running
+    pub execution_process_id: Option<Uuid>,
+}
+
+#[derive(Default)]
+struct PoolState {
+    connections: HashMap<Uuid, Connection>,
+    /// Runs by execution process
+    runs: HashMap<Uuid, AssignedRun>,
+}
+
+impl PoolState {
+    fn is_busy(&self, worker_id: Uuid) -> bool {
+        self.runs.values().any(|run| run.worker_id == worker_id)
+    }
+
+    /// Forget the connection of `worker_id` and fail its executions, as their results can
+    /// no longer arrive
+    fn drop_worker(&mut self, worker_id: Uuid) {
+        self.connections.remove(&worker_id);
+        let lost: Vec<Uuid> = self
+            .runs
+            .iter()
+            .filter(|(_, assigned)| assigned.worker_id == worker_id)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in lost {
+            if let Some(assigned) = self.runs.remove(&id) {
+                assigned
+                    .run
+                    .msg_store
+                    .push_stderr("Worker disconnected before the agent finished\n");
+                let _ = assigned.run.exit.send(ExecutorExitResult::Failure);
+            }
+        }
+    }
+}
+
+/// Connected workers and the executions they run
+#[derive(Clone, Default)]
+pub struct WorkerPool {
+    state: Arc<RwLock<PoolState>>,
+}
+
+impl WorkerPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a connection of `worker_id`, replacing any previous one. `capabilities` are
+    /// the stored ones, until the worker's first heartbeat. Returns the id of the
+    /// connection and the channel of messages for the worker.
+    pub async fn connect(
+        &self,
+        worker_id: Uuid,
+        capabilities: &[String],
+        draining: bool,
+    ) -> (Uuid, mpsc::UnboundedReceiver<ServerMessage>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let id = Uuid::new_v4();
+        let connection = Connection {
+            id,
+            sender,
+            capabilities: normalize_capabilities(capabilities),
+            draining,
+            last_heartbeat: Instant::now(),
+        };
+        self.state
+            .write()
+            .await
+            .connections
+            .insert(worker_id, connection);
+        (id, receiver)
+    }
+
+    /// Remove the connection `connection_id` of `worker_id`, failing its executions
+    pub async fn disconnect(&self, worker_id: Uuid, connection_id: Uuid) {
+        let mut state = self.state.write().await;
+        if state
+            .connections
+            .get(&worker_id)
+            .is_none_or(|connection| connection.id != connection_id)
+        {
+            // Already replaced by a newer connection
+            return;
+        }
+        state.drop_worker(worker_id);
+    }
+
+    /// Close the connection of `worker_id`, e.g. after it was deleted
+    pub async fn remove(&self, worker_id: Uuid) {
+        self.state.write().await.drop_worker(worker_id);
+    }
+
+    pub async fn is_connected(&self, worker_id: Uuid) -> bool {
+        self.state.read().await.connections.contains_key(&worker_id)
+    }
+
+    pub async fn is_busy(&self, worker_id: Uuid) -> bool {
+        self.state.read().await.is_busy(worker_id)
+    }
+
+    pub async fn status(&self, worker_id: Uuid) -> WorkerStatus {
+        let state = self.state.read().await;
+        let Some(connection) = state.connections.get(&worker_id) else {
+            return WorkerStatus::default();
+        };
+        WorkerStatus {
+            connected: true,
+            healthy: connection.is_healthy(),
+            execution_process_id: state
+                .runs
+                .iter()
+                .find(|(_, assigned)| assigned.worker_id == worker_id)
+                .map(|(id, _)| *id),
+        }
+    }
+
+    /// Stop or resume giving `worker_id` new executions. Its current execution continues.
+    pub async fn set_draining(&self, worker_id: Uuid, draining: bool) {
+        if let Some(connection) = self.state.write().await.connections.get_mut(&worker_id) {
+            connection.draining = draining;
+        }
+    }
+
+    /// Assign `execution_process_id` to an idle worker with every capability in
+    /// `requirements`, which must be normalized. Of several such workers the one with the
+    /// fewest capabilities is picked, to keep specialized workers free. Returns the
+    /// worker, or `run` back if no worker can take it.
+    pub async fn assign(
+        &self,
+        execution_process_id: Uuid,
+        run: RemoteRun,
+        requirements: &[String],
+    ) -> Result<Uuid, RemoteRun> {
+        let mut state = self.state.write().await;
+        let Some(worker_id) = state
+            .connections
+            .iter()
+            .filter(|(worker_id, connection)| {
+                connection.can_run(requirements) && !state.is_busy(**worker_id)
+            })
+            .min_by_key(|(worker_id, connection)| (connection.capabilities.len(), **worker_id))
+            .map(|(worker_id, _)| *worker_id)
+        else {
+            return Err(run);
+        };
+        state
+            .runs
+            .insert(execution_process_id, AssignedRun { worker_id, run });
+        Ok(worker_id)
+    }
+
+    /// Forget the assignment of `execution_process_id`, e.g. when it couldn't be sent
+    pub async fn release(&self, execution_process_id: Uuid) -> Option<RemoteRun> {
+        self.state
+            .write()
+            .await
+            .runs
+            .remove(&execution_process_id)
+            .map(|assigned| assigned.run)
+    }
+
+    /// Send `run` to the worker it was assigned to
+    pub async fn start(&self, run: WorkerRun) -> Result<(), WorkerError> {
+        let execution_process_id = run.execution_process_id;
+        self.send(execution_process_id, ServerMessage::Run(run))
+            .await
+    }
+
+    /// Whether `execution_process_id` runs on a worker
+    pub async fn is_running(&self, execution_process_id: Uuid) -> bool {
+        self.state
+            .read()
+            .await
+            .runs
+            .contains_key(&execution_process_id)
+    }
+
+    /// Ask the worker running `execution_process_id` to stop it
+    pub async fn stop(&self, execution_process_id: Uuid) -> Result<(), WorkerError> {
+        self.send(
+            execution_process_id,
+            ServerMessage::Stop {
+                execution_process_id,
+            },
+        )
+        .await
+    }
+
+    async fn send(
+        &self,
+        execution_process_id: Uuid,
+        message: ServerMessage,
+    ) -> Result<(), WorkerError> {
+        let state = self.state.read().await;
+        let worker_id = state
+            .runs
+            .get(&execution_process_id)
+            .map(|assigned| assigned.worker_id)
+            .ok_or(WorkerError::NotAssigned(execution_process_id))?;
+        state
+            .connections
+            .get(&worker_id)
+            .and_then(|connection| connection.sender.send(message).ok())
+            .ok_or(WorkerError::NotConnected(worker_id))
+    }
+
+    /// Handle a message of `worker_id`. Messages about executions the worker wasn't
+    /// assigned are ignored.
+    pub async fn handle_message(&self, worker_id: Uuid, message: WorkerMessage) {
+        match message {
+            WorkerMessage::Heartbeat { capabilities } => {
+                if let Some(connection) = self.state.write().await.connections.get_mut(&worker_id) {
+                    connection.capabilities = normalize_capabilities(&capabilities);
+                    connection.last_heartbeat = Instant::now();
+                }
+            }
+            WorkerMessage::Log {
+                execution_process_id,
+                msg,
+            } => {
+                if let Some(assigned) = self.state.read().await.runs.get(&execution_process_id)
+                    && assigned.worker_id == worker_id
+                    && matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_))
+                {
+                    assigned.run.msg_store.push(msg);
+                }
+            }
+            WorkerMessage::Finished {
+                execution_process_id,
+                success,
+                repos,
+            } => {
+                let run = {
+                    let mut state = self.state.write().await;
+                    match state.runs.get(&execution_process_id) {
+                        Some(assigned) if assigned.worker_id == worker_id => state
+                            .runs
+                            .remove(&execution_process_id)
+                            .map(|assigned| assigned.run),
+                        _ => None,
+                    }
+                };
+                let Some(run) = run else {
+                    return;
+                };
+
+                let branch = run.branch.clone();
+                let worktrees = run.worktrees.clone();
+                let applied = tokio::task::spawn_blocking(move || {
+                    // Snapshots of repositories that aren't in the workspace are dropped
+                    for snapshot in &repos {
+                        if let Some(worktree) = worktrees.get(&snapshot.name) {
+                            apply_snapshot(snapshot, worktree, &branch)?;
+                        }
+                    }
+                    Ok::<_, WorkerError>(())
+                })
+                .await
+                .map_err(|e| WorkerError::Io(std::io::Error::other(e)))
+                .and_then(|result| result);
+
+                let result = match applied {
+                    Ok(()) if success => ExecutorExitResult::Success,
+                    Ok(()) => ExecutorExitResult::Failure,
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to apply changes of worker {} for execution {}: {}",
+                            worker_id,
+                            execution_process_id,
+                            e
+                        );
+                        run.msg_store
+                            .push_stderr(format!("Failed to apply the worker's changes: {e}\n"));
+                        ExecutorExitResult::Failure
+                    }
+                };
+                let _ = run.exit.send(result);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::actions::{
+        ExecutorActionType,
+        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+    };
+
+    use super::*;
+    use crate::services::git::GitService;
+
+    fn remote_run(
+        worktrees: HashMap<String, PathBuf>,
+    ) -> (RemoteRun, oneshot::Receiver<ExecutorExitResult>) {
+        let (exit, exit_rx) = oneshot::channel();
+        let run = RemoteRun {
+            msg_store: Arc::new(MsgStore::new()),
+            branch: "main".to_string(),
+            worktrees,
+            exit,
+        };
+        (run, exit_rx)
+    }
+
+    fn worker_run(execution_process_id: Uuid) -> WorkerRun {
+        WorkerRun {
+            execution_process_id,
+            branch: "main".to_string(),
+            repos: Vec::new(),
+            action: ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script: "true".to_string(),
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::SetupScript,
+                    working_dir: None,
+                }),
+                None,
+            ),
+            env: HashMap::new(),
+            commit_reminder: false,
+        }
+    }
+
+    fn commit_file(repo: &Path, path: &str, content: &str, message: &str) {
+        let git = GitCli::new();
+        std::fs::write(repo.join(path), content).unwrap();
+        git.add_all(repo).unwrap();
+        git.commit_as(repo, message, "Test User", "test@example.com")
+            .unwrap();
+    }
+
+    #[test]
+    fn tokens_are_random_and_hashed() {
+        let token = generate_token();
+        assert!(token.starts_with(TOKEN_PREFIX));
+        assert_ne!(token, generate_token());
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_eq!(hash_token(&token).len(), 64);
+        assert_ne!(hash_token(&token), hash_token(&generate_token()));
+    }
+
+    #[tokio::test]
+    async fn runs_one_execution_per_worker() {
+        let pool = WorkerPool::new();
+        let worker_id = Uuid::new_v4();
+        let (connection_id, mut messages) = pool.connect(worker_id, &[], false).await;
+
+        let first = Uuid::new_v4();
+        let (run, exit) = remote_run(HashMap::new());
+        let msg_store = run.msg_store.clone();
+        assert_eq!(pool.assign(first, run, &[]).await.ok(), Some(worker_id));
+        let (run, _) = remote_run(HashMap::new());
+        assert!(pool.assign(Uuid::new_v4(), run, &[]).await.is_err());
+
+        pool.start(worker_run(first)).await.unwrap();
+        assert!(matches!(
+            messages.recv().await,
+            Some(ServerMessage::Run(run)) if run.execution_process_id == first
+        ));
+
+        // Output is only taken from the worker the execution was assigned to
+        for sender in [worker_id, Uuid::new_v4()] {
+            pool.handle_message(
+                sender,
+                WorkerMessage::Log {
+                    execution_process_id: first,
+                    msg: LogMsg::Stdout(sender.to_string()),
+                },
+            )
+            .await;
+        }
+        assert!(matches!(
+            msg_store.get_history().as_slice(),
+            [LogMsg::Stdout(out)] if *out == worker_id.to_string()
+        ));
+
+        pool.disconnect(worker_id, connection_id).await;
+        assert!(matches!(exit.await, Ok(ExecutorExitResult::Failure)));
+        assert!(!pool.is_running(first).await);
+        assert!(!pool.is_connected(worker_id).await);
+    }
+
+    #[tokio::test]
+    async fn schedules_by_capabilities() {
+        let pool = WorkerPool::new();
+        let plain = Uuid::new_v4();
+        let gpu = Uuid::new_v4();
+        let _plain = pool.connect(plain, &["linux".to_string()], false).await;
+        let _gpu = pool.connect(gpu, &[], false).await;
+        pool.handle_message(
+            gpu,
+            WorkerMessage::Heartbeat {
+                capabilities: vec![" GPU".to_string(), "linux".to_string(), "gpu".to_string()],
+            },
+        )
+        .await;
+
+        // The worker with fewer capabilities takes executions that don't need more
+        let (run, _) = remote_run(HashMap::new());
+        assert_eq!(
+            pool.assign(Uuid::new_v4(), run, &[]).await.ok(),
+            Some(plain)
+        );
+        let (run, _) = remote_run(HashMap::new());
+        let needs_gpu = normalize_capabilities(&["gpu".to_string()]);
+        assert_eq!(
+            pool.assign(Uuid::new_v4(), run, &needs_gpu).await.ok(),
+            Some(gpu)
+        );
+        let (run, _) = remote_run(HashMap::new());
+        assert!(pool.assign(Uuid::new_v4(), run, &needs_gpu).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn skips_draining_and_silent_workers() {
+        let pool = WorkerPool::new();
+        let worker_id = Uuid::new_v4();
+        let _connection = pool.connect(worker_id, &[], true).await;
+        let (run, _) = remote_run(HashMap::new());
+        assert!(pool.assign(Uuid::new_v4(), run, &[]).await.is_err());
+
+        pool.set_draining(worker_id, false).await;
+        if let Some(last_heartbeat) = Instant::now().checked_sub(HEARTBEAT_TIMEOUT) {
+            pool.state
+                .write()
+                .await
+                .connections
+                .get_mut(&worker_id)
+                .unwrap()
+                .last_heartbeat = last_heartbeat;
+            assert!(!pool.status(worker_id).await.healthy);
+            let (run, _) = remote_run(HashMap::new());
+            assert!(pool.assign(Uuid::new_v4(), run, &[]).await.is_err());
+        }
+
+        pool.handle_message(
+            worker_id,
+            WorkerMessage::Heartbeat {
+                capabilities: Vec::new(),
+            },
+        )
+        .await;
+        let execution_process_id = Uuid::new_v4();
+        let (run, _) = remote_run(HashMap::new());
+        assert_eq!(
+            pool.assign(execution_process_id, run, &[]).await.ok(),
+            Some(worker_id)
+        );
+        let status = pool.status(worker_id).await;
+        assert!(status.connected && status.healthy);
+        assert_eq!(status.execution_process_id, Some(execution_process_id));
+    }
+
+    #[tokio::test]
+    async fn keeps_a_newer_connection() {
+        let pool = WorkerPool::new();
+        let worker_id = Uuid::new_v4();
+        let (old, _) = pool.connect(worker_id, &[], false).await;
+        let (_new, _) = pool.connect(worker_id, &[], false).await;
+        pool.disconnect(worker_id, old).await;
+        assert!(pool.is_connected(worker_id).await);
+    }
+
+    #[tokio::test]
+    async fn applies_the_changes_of_a_finished_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("local");
+        GitService::new()
+            .initialize_repo_with_main_branch(&local)
+            .unwrap();
+        commit_file(&local, "plan.md", "# Plan\n", "Add plan");
+
+        // The worker clones the workspace, commits once and leaves changes uncommitted
+        let snapshot = snapshot_repo("app", &local, "main", false).unwrap();
+        let remote = dir.path().join("remote");
+        clone_snapshot(&snapshot, &remote, "main").unwrap();
+        commit_file(&remote, "plan.md", "# Plan\n\nShip it\n", "Update plan");
+        std::fs::write(remote.join("notes.md"), "Draft\n").unwrap();
+        let result = snapshot_repo("app", &remote, "main", true).unwrap();
+
+        let pool = WorkerPool::new();
+        let worker_id = Uuid::new_v4();
+        let _connection = pool.connect(worker_id, &[], false).await;
+        let execution_process_id = Uuid::new_v4();
+        let (run, exit) = remote_run(HashMap::from([("app".to_string(), local.clone())]));
+        pool.assign(execution_process_id, run, &[])
+            .await
+            .ok()
+            .unwrap();
+        pool.handle_message(
+            worker_id,
+            WorkerMessage::Finished {
+                execution_process_id,
+                success: true,
+                repos: vec![result],
+            },
+        )
+        .await;
+
+        assert!(matches!(exit.await, Ok(ExecutorExitResult::Success)));
+        assert_eq!(
+            std::fs::read_to_string(local.join("plan.md")).unwrap(),
+            "# Plan\n\nShip it\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(local.join("notes.md")).unwrap(),
+            "Draft\n"
+        );
+        let log = GitCli::new()
+            .git(&local, ["log", "--format=%s", "-1"])
+            .unwrap();
+        assert_eq!(log.trim(), "Update plan");
+        assert!(GitCli::new().has_uncommitted_changes(&local).unwrap());
+    }
+}
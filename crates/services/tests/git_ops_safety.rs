@@ -1406,3 +1406,133 @@ fn merge_base_ahead_of_task_should_error() {
         "Merge should error when base branch is ahead of task branch"
     );
 }
+
+#[test]
+fn pull_ff_only_refuses_diverged_branch_and_pull_merge_joins_it() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote_path = temp_dir.path().join("remote.git");
+    Repository::init_bare(&remote_path).expect("init bare remote");
+    let remote_url = remote_path.to_str().expect("remote path str");
+
+    let seed_path = temp_dir.path().join("seed");
+    let service = GitService::new();
+    service
+        .initialize_repo_with_main_branch(&seed_path)
+        .expect("init seed repo");
+    let seed_repo = Repository::open(&seed_path).expect("open seed repo");
+    configure_user(&seed_repo);
+    seed_repo.remote("origin", remote_url).expect("add remote");
+    push_ref(&seed_repo, "refs/heads/main", "refs/heads/main");
+    Repository::open_bare(&remote_path)
+        .expect("open bare remote")
+        .set_head("refs/heads/main")
+        .expect("set remote HEAD");
+
+    let local_path = temp_dir.path().join("local");
+    let local_repo = Repository::clone(remote_url, &local_path).expect("clone local");
+    configure_user(&local_repo);
+    checkout_branch(&local_repo, "main");
+
+    // Someone else pushes while the local branch gets its own commit
+    let updater_path = temp_dir.path().join("updater");
+    let updater_repo = Repository::clone(remote_url, &updater_path).expect("clone updater");
+    configure_user(&updater_repo);
+    checkout_branch(&updater_repo, "main");
+    write_file(&updater_path, "upstream.txt", "upstream change\n");
+    commit_all(&updater_repo, "upstream commit");
+    push_ref(&updater_repo, "refs/heads/main", "refs/heads/main");
+
+    write_file(&local_path, "local.txt", "local change\n");
+    commit_all(&local_repo, "local commit");
+    let local_head = local_repo.head().unwrap().target().unwrap();
+
+    let res = service.pull_ff_only(&local_path, "origin", "main");
+    assert!(
+        res.is_err(),
+        "fast-forward pull of a diverged branch should fail"
+    );
+    assert_eq!(
+        local_repo.head().unwrap().target().unwrap(),
+        local_head,
+        "refused pull leaves the branch alone"
+    );
+
+    service
+        .pull_merge(&local_path, "origin", "main")
+        .expect("merge pull succeeded");
+    let merge = local_repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(merge.parent_count(), 2, "pull created a merge commit");
+    assert_eq!(merge.parent_id(0).unwrap(), local_head);
+    assert!(local_path.join("upstream.txt").exists());
+    assert!(local_path.join("local.txt").exists());
+}
+
+#[test]
+fn publish_files_replaces_remote_branch_without_touching_local_branches() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote_path = temp_dir.path().join("remote.git");
+    Repository::init_bare(&remote_path).expect("init bare remote");
+    let remote_url = remote_path.to_str().expect("remote path str");
+
+    let local_path = temp_dir.path().join("local");
+    let service = GitService::new();
+    service
+        .initialize_repo_with_main_branch(&local_path)
+        .expect("init local repo");
+    let local_repo = Repository::open(&local_path).expect("open local repo");
+    configure_user(&local_repo);
+    let head_before = local_repo.head().unwrap().target().unwrap();
+
+    let files_dir = temp_dir.path().join("files");
+    write_file(&files_dir, "home-desktop.png", "first");
+    write_file(&files_dir, "home-mobile.png", "second");
+    let files = vec![
+        (
+            "home-desktop.png".to_string(),
+            files_dir.join("home-desktop.png"),
+        ),
+        (
+            "home-mobile.png".to_string(),
+            files_dir.join("home-mobile.png"),
+        ),
+    ];
+    service
+        .publish_files(
+            &local_path,
+            remote_url,
+            "shots/feature",
+            &files,
+            "screenshots",
+        )
+        .expect("first publish");
+    service
+        .publish_files(
+            &local_path,
+            remote_url,
+            "shots/feature",
+            &files[..1],
+            "screenshots",
+        )
+        .expect("publish replaces the branch");
+
+    let remote_repo = Repository::open_bare(&remote_path).expect("open bare remote");
+    let commit = remote_repo
+        .find_reference("refs/heads/shots/feature")
+        .expect("published branch")
+        .peel_to_commit()
+        .expect("published commit");
+    assert_eq!(commit.parent_count(), 0, "published commit has no history");
+    let tree = commit.tree().unwrap();
+    assert_eq!(tree.len(), 1);
+    let blob = tree
+        .get_name("home-desktop.png")
+        .expect("published file")
+        .to_object(&remote_repo)
+        .unwrap()
+        .peel_to_blob()
+        .unwrap();
+    assert_eq!(blob.content(), b"first");
+
+    assert_eq!(local_repo.head().unwrap().target().unwrap(), head_before);
+    assert!(!local_path.join("home-desktop.png").exists());
+}
@@ -1,11 +1,12 @@
 use std::{
+    collections::HashSet,
     fs,
     io::Write,
     path::{Path, PathBuf},
 };
 
 use git2::{Repository, build::CheckoutBuilder};
-use services::services::git::{DiffTarget, GitCli, GitService};
+use services::services::git::{ConflictOp, DiffTarget, GitCli, GitService};
 use tempfile::TempDir;
 use utils::diff::DiffChangeKind;
 
@@ -256,6 +257,86 @@ fn commit_and_is_worktree_clean() {
     );
 }
 
+#[test]
+fn recently_changed_paths_follow_commit_order() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "docs/a.md", "a\n");
+    write_file(&repo_path, "docs/b.md", "b\n");
+    s.commit(&repo_path, "add a and b").unwrap();
+    write_file(&repo_path, "src/main.rs", "fn main() {}\n");
+    s.commit(&repo_path, "add code").unwrap();
+    write_file(&repo_path, "docs/b.md", "b2\n");
+    s.commit(&repo_path, "edit b").unwrap();
+
+    let paths: HashSet<String> = ["docs/a.md", "docs/b.md", "docs/untracked.md"]
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let recent = s
+        .recently_changed_paths(&repo_path, "HEAD", &paths, 10)
+        .unwrap();
+    let recent: Vec<_> = recent
+        .iter()
+        .map(|(path, commit)| (path.as_str(), commit.message.as_str()))
+        .collect();
+    assert_eq!(
+        recent,
+        [("docs/b.md", "edit b"), ("docs/a.md", "add a and b")]
+    );
+
+    let newest = s
+        .recently_changed_paths(&repo_path, "HEAD", &paths, 1)
+        .unwrap();
+    assert_eq!(newest.len(), 1);
+    assert_eq!(newest[0].0, "docs/b.md");
+}
+
+#[test]
+fn rebase_conflicts_are_resolved_and_continued() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "docs/a.md", "base\n");
+    s.commit(&repo_path, "add a").unwrap();
+    create_branch(&repo_path, "feature");
+    write_file(&repo_path, "docs/a.md", "upstream\n");
+    s.commit(&repo_path, "edit a upstream").unwrap();
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "docs/a.md", "local\n");
+    s.commit(&repo_path, "edit a locally").unwrap();
+
+    let git = GitCli::new();
+    assert!(git.git(&repo_path, ["rebase", "main"]).is_err());
+    assert_eq!(
+        s.detect_conflict_op(&repo_path).unwrap(),
+        Some(ConflictOp::Rebase)
+    );
+    assert_eq!(s.get_conflicted_files(&repo_path).unwrap(), ["docs/a.md"]);
+
+    // While rebasing, ours is the upstream commit and theirs the one being replayed
+    let sides = s.conflict_sides(&repo_path, "docs/a.md").unwrap();
+    assert_eq!(sides.base.as_deref(), Some("base\n"));
+    assert_eq!(sides.ours.as_deref(), Some("upstream\n"));
+    assert_eq!(sides.theirs.as_deref(), Some("local\n"));
+    assert!(s.conflict_sides(&repo_path, "docs/other.md").is_err());
+
+    s.resolve_conflict(&repo_path, "docs/a.md", Some("merged\n"))
+        .unwrap();
+    assert!(s.get_conflicted_files(&repo_path).unwrap().is_empty());
+    s.continue_conflicts(&repo_path).unwrap();
+
+    assert_eq!(s.detect_conflict_op(&repo_path).unwrap(), None);
+    assert_eq!(
+        fs::read_to_string(repo_path.join("docs/a.md")).unwrap(),
+        "merged\n"
+    );
+    let repo = Repository::open(&repo_path).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.summary(), Some("edit a locally"));
+}
+
 #[test]
 fn commit_in_detached_head_succeeds_via_service() {
     let td = TempDir::new().unwrap();
@@ -520,3 +601,43 @@ fn squash_merge_libgit2_sets_author_without_user() {
         assert_eq!(email.as_deref(), Some("noreply@vibekanban.com"));
     }
 }
+
+#[test]
+fn paths_changed_between_lists_files_of_matching_commits() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let since = chrono::Utc::now() - chrono::Duration::hours(1);
+
+    write_file(&repo_path, "guides/setup.md", "# Setup\n");
+    s.commit(&repo_path, "docs: update guides/setup.md")
+        .unwrap();
+    write_file(&repo_path, "src/main.rs", "fn main() {}\n");
+    s.commit(&repo_path, "fix: main").unwrap();
+    write_file(&repo_path, "notes/a.md", "a\n");
+    write_file(&repo_path, "notes/b.md", "b\n");
+    s.commit(&repo_path, "docs: import 2 documents into notes")
+        .unwrap();
+
+    let until = chrono::Utc::now() + chrono::Duration::hours(1);
+    let paths = s
+        .paths_changed_between(&repo_path, since, until, |message| {
+            message.starts_with("docs: ")
+        })
+        .unwrap();
+    let expected: HashSet<String> = ["guides/setup.md", "notes/a.md", "notes/b.md"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    assert_eq!(paths, expected);
+
+    let later = s
+        .paths_changed_between(
+            &repo_path,
+            until,
+            until + chrono::Duration::hours(1),
+            |_| true,
+        )
+        .unwrap();
+    assert!(later.is_empty());
+}
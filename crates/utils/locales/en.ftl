@@ -0,0 +1,54 @@
+# Messages of the document and sync endpoints. Keep the ids and placeholders of every
+# other catalog in step with this one.
+
+## Documents
+
+docs-no-repository = No repository found for this project
+docs-current-branch-failed = Failed to get current branch: { $error }
+docs-bare-read-only = Documents of { $path } are served from a bare clone and are read-only
+docs-branch-guard = Document editing is only allowed on the '{ $required }' branch. Current branch: '{ $current }'. Please switch to '{ $required }' to edit documents.
+docs-branch-editing-guard = Documents can only be edited on '{ $required }' or a '{ $prefix }' branch. Current branch: '{ $current }'.
+docs-pr-not-docs-branch = Pull requests of document edits are opened from a '{ $prefix }' branch. Current branch: '{ $current }'.
+docs-pr-unsupported-forge = Pull requests can't be opened on { $url }: its forge isn't supported
+docs-saved = Document saved successfully
+docs-saved-committed = Document saved and committed to branch '{ $branch }'
+docs-deleted = Document deleted successfully
+docs-deleted-committed = Document deleted and committed to branch '{ $branch }'
+docs-created = File created successfully
+docs-created-committed = File created and committed to branch '{ $branch }'
+docs-moved = Document moved successfully
+docs-moved-committed = Document moved and committed to branch '{ $branch }'
+docs-switch-branch-failed = Failed to switch to branch '{ $branch }': { $error }
+docs-switched-branch = Switched to branch '{ $branch }'
+docs-switched-branch-stashed = Switched to branch '{ $branch }' (changes were stashed and restored)
+
+## Sync
+
+sync-requires-main = Must be on main branch to sync documents
+sync-counts-stale = { $error }; counts may be stale
+sync-remote-ahead = Remote has { $count } new commit(s). Please pull changes first or allow pulling.
+sync-rebase-failed = Failed to rebase: { $error }. Resolve the conflicts, then continue or abort the sync.
+sync-merge-failed = Failed to merge origin/main: { $error }. Resolve the conflicts, then continue or abort the sync.
+sync-fast-forward-diverged = main has { $ahead } commit(s) that origin/main doesn't have and { $behind } it is missing, and this project only syncs by fast-forward. Merge or rebase main by hand, then sync again.
+sync-fast-forward-failed = Failed to fast-forward: { $error }
+sync-up-to-date = Already up to date
+sync-pulled = Pulled { $count } commit(s) from origin/main
+sync-remote-url-failed = Failed to get remote URL: { $error }
+sync-push-failed = Failed to push to origin: { $error }
+sync-pushed = Synced { $count } commit(s) to origin/main
+sync-pushed-after-rebase = Synced { $ahead } commit(s) after rebasing { $behind } remote commit(s)
+sync-pushed-after-merge = Synced { $ahead } commit(s) after merging { $behind } remote commit(s)
+sync-pushed-after-fast-forward = Synced { $ahead } commit(s) after fast-forwarding { $behind } remote commit(s)
+
+## Sync conflicts
+
+sync-stopped = A previous sync stopped on conflicts; resolve them, then continue or abort it
+sync-nothing-in-progress = No rebase or merge of the documents is in progress
+sync-not-conflicted-document = '{ $path }' is not a conflicted document
+sync-conflicts-remaining = Resolve the remaining conflicts first: { $documents } document(s) and { $others } other file(s)
+sync-conflicts-done = The remote commits are pulled in; sync again to push
+sync-conflicts-next = The next commit conflicts too; resolve its conflicts and continue
+
+## Quotas
+
+project-quota-exceeded = Project requests are limited to { $limit } per minute; try again in { $seconds } s
@@ -0,0 +1,51 @@
+## Documents
+
+docs-no-repository = No se encontró ningún repositorio para este proyecto
+docs-current-branch-failed = No se pudo obtener la rama actual: { $error }
+docs-bare-read-only = Los documentos de { $path } se sirven desde un clon sin árbol de trabajo y son de solo lectura
+docs-branch-guard = Solo se pueden editar documentos en la rama '{ $required }'. Rama actual: '{ $current }'. Cambia a '{ $required }' para editar documentos.
+docs-branch-editing-guard = Solo se pueden editar documentos en '{ $required }' o en una rama '{ $prefix }'. Rama actual: '{ $current }'.
+docs-pr-not-docs-branch = Las pull requests de cambios en documentos se abren desde una rama '{ $prefix }'. Rama actual: '{ $current }'.
+docs-pr-unsupported-forge = No se pueden abrir pull requests en { $url }: su forja no es compatible
+docs-saved = Documento guardado
+docs-saved-committed = Documento guardado y confirmado en la rama '{ $branch }'
+docs-deleted = Documento eliminado
+docs-deleted-committed = Documento eliminado y confirmado en la rama '{ $branch }'
+docs-created = Archivo creado
+docs-created-committed = Archivo creado y confirmado en la rama '{ $branch }'
+docs-moved = Documento movido
+docs-moved-committed = Documento movido y confirmado en la rama '{ $branch }'
+docs-switch-branch-failed = No se pudo cambiar a la rama '{ $branch }': { $error }
+docs-switched-branch = Cambiado a la rama '{ $branch }'
+docs-switched-branch-stashed = Cambiado a la rama '{ $branch }' (los cambios se guardaron temporalmente y se restauraron)
+
+## Sync
+
+sync-requires-main = Hay que estar en la rama main para sincronizar documentos
+sync-counts-stale = { $error }; los recuentos pueden estar desactualizados
+sync-remote-ahead = El remoto tiene { $count } commit(s) nuevo(s). Trae primero los cambios o permite traerlos.
+sync-rebase-failed = Falló el rebase: { $error }. Resuelve los conflictos y luego continúa o cancela la sincronización.
+sync-merge-failed = Falló la fusión de origin/main: { $error }. Resuelve los conflictos y luego continúa o cancela la sincronización.
+sync-fast-forward-diverged = main tiene { $ahead } commit(s) que origin/main no tiene y le faltan { $behind }, y este proyecto solo sincroniza por avance rápido. Fusiona o haz rebase de main a mano y vuelve a sincronizar.
+sync-fast-forward-failed = Falló el avance rápido: { $error }
+sync-up-to-date = Ya está actualizado
+sync-pulled = Se trajeron { $count } commit(s) de origin/main
+sync-remote-url-failed = No se pudo obtener la URL del remoto: { $error }
+sync-push-failed = Falló el push a origin: { $error }
+sync-pushed = Se sincronizaron { $count } commit(s) con origin/main
+sync-pushed-after-rebase = Se sincronizaron { $ahead } commit(s) tras hacer rebase sobre { $behind } commit(s) remoto(s)
+sync-pushed-after-merge = Se sincronizaron { $ahead } commit(s) tras fusionar { $behind } commit(s) remoto(s)
+sync-pushed-after-fast-forward = Se sincronizaron { $ahead } commit(s) tras avanzar rápidamente { $behind } commit(s) remoto(s)
+
+## Sync conflicts
+
+sync-stopped = Una sincronización anterior se detuvo por conflictos; resuélvelos y luego continúala o cancélala
+sync-nothing-in-progress = No hay ningún rebase ni fusión de los documentos en curso
+sync-not-conflicted-document = '{ $path }' no es un documento en conflicto
+sync-conflicts-remaining = Resuelve primero los conflictos restantes: { $documents } documento(s) y { $others } archivo(s) más
+sync-conflicts-done = Los commits remotos están integrados; vuelve a sincronizar para enviarlos
+sync-conflicts-next = El siguiente commit también tiene conflictos; resuélvelos y continúa
+
+## Quotas
+
+project-quota-exceeded = Las solicitudes del proyecto están limitadas a { $limit } por minuto; vuelve a intentarlo en { $seconds } s
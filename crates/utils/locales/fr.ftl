@@ -0,0 +1,51 @@
+## Documents
+
+docs-no-repository = Aucun dépôt trouvé pour ce projet
+docs-current-branch-failed = Impossible de déterminer la branche actuelle : { $error }
+docs-bare-read-only = Les documents de { $path } proviennent d'un clone nu et sont en lecture seule
+docs-branch-guard = La modification des documents n'est autorisée que sur la branche '{ $required }'. Branche actuelle : '{ $current }'. Passez sur '{ $required }' pour modifier les documents.
+docs-branch-editing-guard = Les documents ne peuvent être modifiés que sur '{ $required }' ou une branche '{ $prefix }'. Branche actuelle : '{ $current }'.
+docs-pr-not-docs-branch = Les pull requests de modifications de documents s'ouvrent depuis une branche '{ $prefix }'. Branche actuelle : '{ $current }'.
+docs-pr-unsupported-forge = Impossible d'ouvrir une pull request sur { $url } : sa forge n'est pas prise en charge
+docs-saved = Document enregistré
+docs-saved-committed = Document enregistré et commité sur la branche '{ $branch }'
+docs-deleted = Document supprimé
+docs-deleted-committed = Document supprimé et commité sur la branche '{ $branch }'
+docs-created = Fichier créé
+docs-created-committed = Fichier créé et commité sur la branche '{ $branch }'
+docs-moved = Document déplacé
+docs-moved-committed = Document déplacé et commité sur la branche '{ $branch }'
+docs-switch-branch-failed = Impossible de passer sur la branche '{ $branch }' : { $error }
+docs-switched-branch = Passé sur la branche '{ $branch }'
+docs-switched-branch-stashed = Passé sur la branche '{ $branch }' (les modifications ont été mises de côté puis restaurées)
+
+## Sync
+
+sync-requires-main = La synchronisation des documents n'est possible que sur la branche main
+sync-counts-stale = { $error } ; les compteurs peuvent être obsolètes
+sync-remote-ahead = Le dépôt distant a { $count } nouveau(x) commit(s). Récupérez d'abord les modifications ou autorisez leur récupération.
+sync-rebase-failed = Échec du rebase : { $error }. Résolvez les conflits, puis poursuivez ou annulez la synchronisation.
+sync-merge-failed = Échec de la fusion de origin/main : { $error }. Résolvez les conflits, puis poursuivez ou annulez la synchronisation.
+sync-fast-forward-diverged = main a { $ahead } commit(s) absent(s) de origin/main, à qui il manque { $behind } commit(s), et ce projet ne synchronise qu'en avance rapide. Fusionnez ou rebasez main à la main, puis synchronisez à nouveau.
+sync-fast-forward-failed = Échec de l'avance rapide : { $error }
+sync-up-to-date = Déjà à jour
+sync-pulled = { $count } commit(s) récupéré(s) depuis origin/main
+sync-remote-url-failed = Impossible d'obtenir l'URL du dépôt distant : { $error }
+sync-push-failed = Échec du push vers origin : { $error }
+sync-pushed = { $count } commit(s) synchronisé(s) vers origin/main
+sync-pushed-after-rebase = { $ahead } commit(s) synchronisé(s) après le rebase de { $behind } commit(s) distant(s)
+sync-pushed-after-merge = { $ahead } commit(s) synchronisé(s) après la fusion de { $behind } commit(s) distant(s)
+sync-pushed-after-fast-forward = { $ahead } commit(s) synchronisé(s) après l'avance rapide de { $behind } commit(s) distant(s)
+
+## Sync conflicts
+
+sync-stopped = Une synchronisation précédente s'est arrêtée sur des conflits ; résolvez-les, puis poursuivez-la ou annulez-la
+sync-nothing-in-progress = Aucun rebase ni aucune fusion des documents n'est en cours
+sync-not-conflicted-document = '{ $path }' n'est pas un document en conflit
+sync-conflicts-remaining = Résolvez d'abord les conflits restants : { $documents } document(s) et { $others } autre(s) fichier(s)
+sync-conflicts-done = Les commits distants sont intégrés ; synchronisez à nouveau pour les pousser
+sync-conflicts-next = Le commit suivant est aussi en conflit ; résolvez ses conflits et poursuivez
+
+## Quotas
+
+project-quota-exceeded = Les requêtes du projet sont limitées à { $limit } par minute ; réessayez dans { $seconds } s
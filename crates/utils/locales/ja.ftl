@@ -0,0 +1,51 @@
+## Documents
+
+docs-no-repository = このプロジェクトのリポジトリが見つかりません
+docs-current-branch-failed = 現在のブランチを取得できませんでした: { $error }
+docs-bare-read-only = { $path } のドキュメントはベアクローンから提供されているため読み取り専用です
+docs-branch-guard = ドキュメントは '{ $required }' ブランチでのみ編集できます。現在のブランチ: '{ $current }'。ドキュメントを編集するには '{ $required }' に切り替えてください。
+docs-branch-editing-guard = ドキュメントは '{ $required }' または '{ $prefix }' ブランチでのみ編集できます。現在のブランチ: '{ $current }'。
+docs-pr-not-docs-branch = ドキュメント編集のプルリクエストは '{ $prefix }' ブランチから作成します。現在のブランチ: '{ $current }'。
+docs-pr-unsupported-forge = { $url } ではプルリクエストを作成できません: このフォージには対応していません
+docs-saved = ドキュメントを保存しました
+docs-saved-committed = ドキュメントを保存し、ブランチ '{ $branch }' にコミットしました
+docs-deleted = ドキュメントを削除しました
+docs-deleted-committed = ドキュメントを削除し、ブランチ '{ $branch }' にコミットしました
+docs-created = ファイルを作成しました
+docs-created-committed = ファイルを作成し、ブランチ '{ $branch }' にコミットしました
+docs-moved = ドキュメントを移動しました
+docs-moved-committed = ドキュメントを移動し、ブランチ '{ $branch }' にコミットしました
+docs-switch-branch-failed = ブランチ '{ $branch }' に切り替えられませんでした: { $error }
+docs-switched-branch = ブランチ '{ $branch }' に切り替えました
+docs-switched-branch-stashed = ブランチ '{ $branch }' に切り替えました（変更は一時退避して復元しました）
+
+## Sync
+
+sync-requires-main = ドキュメントを同期するには main ブランチにいる必要があります
+sync-counts-stale = { $error }。件数が古い可能性があります
+sync-remote-ahead = リモートに新しいコミットが { $count } 件あります。先に変更を取り込むか、取り込みを許可してください。
+sync-rebase-failed = リベースに失敗しました: { $error }。競合を解決してから、同期を続行または中止してください。
+sync-merge-failed = origin/main のマージに失敗しました: { $error }。競合を解決してから、同期を続行または中止してください。
+sync-fast-forward-diverged = main には origin/main にないコミットが { $ahead } 件あり、origin/main のコミットが { $behind } 件不足しています。このプロジェクトは早送りでのみ同期します。main を手動でマージまたはリベースしてから、もう一度同期してください。
+sync-fast-forward-failed = 早送りに失敗しました: { $error }
+sync-up-to-date = すでに最新です
+sync-pulled = origin/main から { $count } 件のコミットを取り込みました
+sync-remote-url-failed = リモートの URL を取得できませんでした: { $error }
+sync-push-failed = origin へのプッシュに失敗しました: { $error }
+sync-pushed = { $count } 件のコミットを origin/main に同期しました
+sync-pushed-after-rebase = リモートの { $behind } 件のコミットにリベースした後、{ $ahead } 件のコミットを同期しました
+sync-pushed-after-merge = リモートの { $behind } 件のコミットをマージした後、{ $ahead } 件のコミットを同期しました
+sync-pushed-after-fast-forward = リモートの { $behind } 件のコミットに早送りした後、{ $ahead } 件のコミットを同期しました
+
+## Sync conflicts
+
+sync-stopped = 前回の同期が競合で停止しています。競合を解決してから、続行または中止してください
+sync-nothing-in-progress = ドキュメントのリベースやマージは進行していません
+sync-not-conflicted-document = '{ $path }' は競合しているドキュメントではありません
+sync-conflicts-remaining = 先に残りの競合を解決してください: ドキュメント { $documents } 件、その他のファイル { $others } 件
+sync-conflicts-done = リモートのコミットを取り込みました。プッシュするにはもう一度同期してください
+sync-conflicts-next = 次のコミットも競合しています。競合を解決して続行してください
+
+## Quotas
+
+project-quota-exceeded = このプロジェクトのリクエストは 1 分あたり { $limit } 件までです。{ $seconds } 秒後にもう一度お試しください
@@ -0,0 +1,51 @@
+## Documents
+
+docs-no-repository = 이 프로젝트의 저장소를 찾을 수 없습니다
+docs-current-branch-failed = 현재 브랜치를 가져오지 못했습니다: { $error }
+docs-bare-read-only = { $path }의 문서는 베어 클론에서 제공되므로 읽기 전용입니다
+docs-branch-guard = 문서는 '{ $required }' 브랜치에서만 편집할 수 있습니다. 현재 브랜치: '{ $current }'. 문서를 편집하려면 '{ $required }'(으)로 전환하세요.
+docs-branch-editing-guard = 문서는 '{ $required }' 또는 '{ $prefix }' 브랜치에서만 편집할 수 있습니다. 현재 브랜치: '{ $current }'.
+docs-pr-not-docs-branch = 문서 편집의 풀 리퀘스트는 '{ $prefix }' 브랜치에서 엽니다. 현재 브랜치: '{ $current }'.
+docs-pr-unsupported-forge = { $url }에서는 풀 리퀘스트를 열 수 없습니다: 지원하지 않는 포지입니다
+docs-saved = 문서를 저장했습니다
+docs-saved-committed = 문서를 저장하고 '{ $branch }' 브랜치에 커밋했습니다
+docs-deleted = 문서를 삭제했습니다
+docs-deleted-committed = 문서를 삭제하고 '{ $branch }' 브랜치에 커밋했습니다
+docs-created = 파일을 만들었습니다
+docs-created-committed = 파일을 만들고 '{ $branch }' 브랜치에 커밋했습니다
+docs-moved = 문서를 이동했습니다
+docs-moved-committed = 문서를 이동하고 '{ $branch }' 브랜치에 커밋했습니다
+docs-switch-branch-failed = '{ $branch }' 브랜치로 전환하지 못했습니다: { $error }
+docs-switched-branch = '{ $branch }' 브랜치로 전환했습니다
+docs-switched-branch-stashed = '{ $branch }' 브랜치로 전환했습니다 (변경 사항은 임시 저장 후 복원했습니다)
+
+## Sync
+
+sync-requires-main = 문서를 동기화하려면 main 브랜치에 있어야 합니다
+sync-counts-stale = { $error }; 개수가 최신이 아닐 수 있습니다
+sync-remote-ahead = 원격에 새 커밋이 { $count }개 있습니다. 먼저 변경 사항을 가져오거나 가져오기를 허용하세요.
+sync-rebase-failed = 리베이스에 실패했습니다: { $error }. 충돌을 해결한 뒤 동기화를 계속하거나 중단하세요.
+sync-merge-failed = origin/main 병합에 실패했습니다: { $error }. 충돌을 해결한 뒤 동기화를 계속하거나 중단하세요.
+sync-fast-forward-diverged = main에는 origin/main에 없는 커밋이 { $ahead }개 있고 origin/main의 커밋 { $behind }개가 없으며, 이 프로젝트는 빨리 감기로만 동기화합니다. main을 직접 병합하거나 리베이스한 뒤 다시 동기화하세요.
+sync-fast-forward-failed = 빨리 감기에 실패했습니다: { $error }
+sync-up-to-date = 이미 최신 상태입니다
+sync-pulled = origin/main에서 커밋 { $count }개를 가져왔습니다
+sync-remote-url-failed = 원격 URL을 가져오지 못했습니다: { $error }
+sync-push-failed = origin으로 푸시하지 못했습니다: { $error }
+sync-pushed = 커밋 { $count }개를 origin/main에 동기화했습니다
+sync-pushed-after-rebase = 원격 커밋 { $behind }개에 리베이스한 후 커밋 { $ahead }개를 동기화했습니다
+sync-pushed-after-merge = 원격 커밋 { $behind }개를 병합한 후 커밋 { $ahead }개를 동기화했습니다
+sync-pushed-after-fast-forward = 원격 커밋 { $behind }개로 빨리 감기한 후 커밋 { $ahead }개를 동기화했습니다
+
+## Sync conflicts
+
+sync-stopped = 이전 동기화가 충돌로 멈췄습니다. 충돌을 해결한 뒤 계속하거나 중단하세요
+sync-nothing-in-progress = 진행 중인 문서 리베이스나 병합이 없습니다
+sync-not-conflicted-document = '{ $path }'은(는) 충돌한 문서가 아닙니다
+sync-conflicts-remaining = 남은 충돌을 먼저 해결하세요: 문서 { $documents }개, 기타 파일 { $others }개
+sync-conflicts-done = 원격 커밋을 가져왔습니다. 푸시하려면 다시 동기화하세요
+sync-conflicts-next = 다음 커밋도 충돌합니다. 충돌을 해결하고 계속하세요
+
+## Quotas
+
+project-quota-exceeded = 이 프로젝트의 요청은 분당 { $limit }개로 제한됩니다. { $seconds }초 후에 다시 시도하세요
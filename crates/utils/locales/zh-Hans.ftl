@@ -0,0 +1,51 @@
+## Documents
+
+docs-no-repository = 未找到此项目的仓库
+docs-current-branch-failed = 无法获取当前分支：{ $error }
+docs-bare-read-only = { $path } 的文档来自裸克隆，为只读
+docs-branch-guard = 只能在 '{ $required }' 分支上编辑文档。当前分支：'{ $current }'。请切换到 '{ $required }' 以编辑文档。
+docs-branch-editing-guard = 只能在 '{ $required }' 或 '{ $prefix }' 分支上编辑文档。当前分支：'{ $current }'。
+docs-pr-not-docs-branch = 文档修改的拉取请求需从 '{ $prefix }' 分支创建。当前分支：'{ $current }'。
+docs-pr-unsupported-forge = 无法在 { $url } 上创建拉取请求：不支持该代码托管平台
+docs-saved = 文档已保存
+docs-saved-committed = 文档已保存并提交到分支 '{ $branch }'
+docs-deleted = 文档已删除
+docs-deleted-committed = 文档已删除并提交到分支 '{ $branch }'
+docs-created = 文件已创建
+docs-created-committed = 文件已创建并提交到分支 '{ $branch }'
+docs-moved = 文档已移动
+docs-moved-committed = 文档已移动并提交到分支 '{ $branch }'
+docs-switch-branch-failed = 无法切换到分支 '{ $branch }'：{ $error }
+docs-switched-branch = 已切换到分支 '{ $branch }'
+docs-switched-branch-stashed = 已切换到分支 '{ $branch }'（更改已暂存并恢复）
+
+## Sync
+
+sync-requires-main = 必须在 main 分支上才能同步文档
+sync-counts-stale = { $error }；计数可能已过时
+sync-remote-ahead = 远程有 { $count } 个新提交。请先拉取更改或允许拉取。
+sync-rebase-failed = 变基失败：{ $error }。请解决冲突，然后继续或中止同步。
+sync-merge-failed = 合并 origin/main 失败：{ $error }。请解决冲突，然后继续或中止同步。
+sync-fast-forward-diverged = main 有 { $ahead } 个 origin/main 没有的提交，并缺少 { $behind } 个提交，而此项目只通过快进同步。请手动合并或变基 main，然后再次同步。
+sync-fast-forward-failed = 快进失败：{ $error }
+sync-up-to-date = 已是最新
+sync-pulled = 已从 origin/main 拉取 { $count } 个提交
+sync-remote-url-failed = 无法获取远程 URL：{ $error }
+sync-push-failed = 推送到 origin 失败：{ $error }
+sync-pushed = 已将 { $count } 个提交同步到 origin/main
+sync-pushed-after-rebase = 变基到 { $behind } 个远程提交后，已同步 { $ahead } 个提交
+sync-pushed-after-merge = 合并 { $behind } 个远程提交后，已同步 { $ahead } 个提交
+sync-pushed-after-fast-forward = 快进 { $behind } 个远程提交后，已同步 { $ahead } 个提交
+
+## Sync conflicts
+
+sync-stopped = 上一次同步因冲突而停止；请解决冲突，然后继续或中止
+sync-nothing-in-progress = 没有正在进行的文档变基或合并
+sync-not-conflicted-document = '{ $path }' 不是有冲突的文档
+sync-conflicts-remaining = 请先解决剩余的冲突：{ $documents } 个文档和 { $others } 个其他文件
+sync-conflicts-done = 远程提交已拉取；请再次同步以推送
+sync-conflicts-next = 下一个提交也有冲突；请解决后继续
+
+## Quotas
+
+project-quota-exceeded = 项目请求限制为每分钟 { $limit } 个；请在 { $seconds } 秒后重试
@@ -0,0 +1,51 @@
+## Documents
+
+docs-no-repository = 找不到此專案的儲存庫
+docs-current-branch-failed = 無法取得目前分支：{ $error }
+docs-bare-read-only = { $path } 的文件來自裸複製，為唯讀
+docs-branch-guard = 只能在 '{ $required }' 分支上編輯文件。目前分支：'{ $current }'。請切換到 '{ $required }' 以編輯文件。
+docs-branch-editing-guard = 只能在 '{ $required }' 或 '{ $prefix }' 分支上編輯文件。目前分支：'{ $current }'。
+docs-pr-not-docs-branch = 文件修改的拉取請求需從 '{ $prefix }' 分支建立。目前分支：'{ $current }'。
+docs-pr-unsupported-forge = 無法在 { $url } 上建立拉取請求：不支援該程式碼託管平台
+docs-saved = 文件已儲存
+docs-saved-committed = 文件已儲存並提交到分支 '{ $branch }'
+docs-deleted = 文件已刪除
+docs-deleted-committed = 文件已刪除並提交到分支 '{ $branch }'
+docs-created = 檔案已建立
+docs-created-committed = 檔案已建立並提交到分支 '{ $branch }'
+docs-moved = 文件已移動
+docs-moved-committed = 文件已移動並提交到分支 '{ $branch }'
+docs-switch-branch-failed = 無法切換到分支 '{ $branch }'：{ $error }
+docs-switched-branch = 已切換到分支 '{ $branch }'
+docs-switched-branch-stashed = 已切換到分支 '{ $branch }'（變更已暫存並還原）
+
+## Sync
+
+sync-requires-main = 必須在 main 分支上才能同步文件
+sync-counts-stale = { $error }；計數可能已過時
+sync-remote-ahead = 遠端有 { $count } 個新提交。請先拉取變更或允許拉取。
+sync-rebase-failed = 重定基底失敗：{ $error }。請解決衝突，然後繼續或中止同步。
+sync-merge-failed = 合併 origin/main 失敗：{ $error }。請解決衝突，然後繼續或中止同步。
+sync-fast-forward-diverged = main 有 { $ahead } 個 origin/main 沒有的提交，並缺少 { $behind } 個提交，而此專案只透過快轉同步。請手動合併或重定 main 的基底，然後再次同步。
+sync-fast-forward-failed = 快轉失敗：{ $error }
+sync-up-to-date = 已是最新
+sync-pulled = 已從 origin/main 拉取 { $count } 個提交
+sync-remote-url-failed = 無法取得遠端 URL：{ $error }
+sync-push-failed = 推送到 origin 失敗：{ $error }
+sync-pushed = 已將 { $count } 個提交同步到 origin/main
+sync-pushed-after-rebase = 重定基底到 { $behind } 個遠端提交後，已同步 { $ahead } 個提交
+sync-pushed-after-merge = 合併 { $behind } 個遠端提交後，已同步 { $ahead } 個提交
+sync-pushed-after-fast-forward = 快轉 { $behind } 個遠端提交後，已同步 { $ahead } 個提交
+
+## Sync conflicts
+
+sync-stopped = 上一次同步因衝突而停止；請解決衝突，然後繼續或中止
+sync-nothing-in-progress = 沒有進行中的文件重定基底或合併
+sync-not-conflicted-document = '{ $path }' 不是有衝突的文件
+sync-conflicts-remaining = 請先解決剩餘的衝突：{ $documents } 個文件和 { $others } 個其他檔案
+sync-conflicts-done = 遠端提交已拉取；請再次同步以推送
+sync-conflicts-next = 下一個提交也有衝突；請解決後繼續
+
+## Quotas
+
+project-quota-exceeded = 專案請求限制為每分鐘 { $limit } 個；請在 { $seconds } 秒後重試
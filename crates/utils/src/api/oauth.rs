@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use uuid::Uuid;
@@ -77,3 +78,26 @@ pub struct StatusResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub degraded: Option<bool>,
 }
+
+/// A signed-in session of the current user and the device it was started from
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+pub struct AuthSessionInfo {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Day the session was last used, `None` if never used after sign-in
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+pub struct ListSessionsResponse {
+    pub sessions: Vec<AuthSessionInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+pub struct RevokeSessionsResponse {
+    pub revoked: i64,
+}
@@ -180,3 +180,88 @@ pub struct UpdateMemberRoleResponse {
     pub user_id: Uuid,
     pub role: MemberRole,
 }
+
+/// Kind of resource a permission check is evaluated against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+#[ts(rename_all = "snake_case")]
+pub enum PermissionResource {
+    Organization,
+    Project,
+    Issue,
+}
+
+/// What the user wants to do with a resource.
+/// Members may read and write, managing (settings, members, deletion) needs admin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+#[ts(rename_all = "snake_case")]
+pub enum PermissionAction {
+    Read,
+    Write,
+    Manage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PermissionCheckQuery {
+    pub resource_type: PermissionResource,
+    pub resource_id: Uuid,
+    pub action: PermissionAction,
+    /// User to check, defaults to the caller
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub user_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PermissionCheckResponse {
+    pub allowed: bool,
+    /// Role of the user in the organization owning the resource, if any
+    pub role: Option<MemberRole>,
+}
+
+/// What holds access in an access report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+#[ts(rename_all = "snake_case")]
+pub enum AccessPrincipal {
+    User,
+    /// Token of a signed-in session, which acts with its user's access
+    SessionToken,
+    /// The SCIM provisioning bearer token, which manages the organization's members
+    ScimToken,
+}
+
+/// What one user or token may do in one organization, or one of its projects
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AccessReportEntry {
+    pub principal: AccessPrincipal,
+    /// User id for users, session id for session tokens, none for the SCIM token
+    pub principal_id: Option<Uuid>,
+    /// User the principal is, or acts for
+    pub user_id: Option<Uuid>,
+    pub user_email: Option<String>,
+    pub organization_id: Uuid,
+    pub organization_name: String,
+    /// `None` for access to the organization itself
+    pub project_id: Option<Uuid>,
+    pub project_name: Option<String>,
+    pub role: Option<MemberRole>,
+    pub actions: Vec<PermissionAction>,
+    /// When a token was last used
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AccessReportResponse {
+    pub generated_at: DateTime<Utc>,
+    /// Access to the organizations the caller administers; others are left out
+    pub entries: Vec<AccessReportEntry>,
+}
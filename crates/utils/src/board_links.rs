@@ -0,0 +1,189 @@
+//! `vk://` links from documents and task descriptions to board entities:
+//! `vk://task/<task id>` and `vk://doc/<document path>`, both optionally followed by a
+//! `#fragment`. Document paths are relative to the repository root and percent-encoded
+//! where markdown needs it, e.g. `vk://doc/docs/release%20notes.md#rollout`.
+
+use std::{ops::Range, sync::OnceLock};
+
+use regex::Regex;
+use uuid::Uuid;
+
+pub const SCHEME: &str = "vk://";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BoardLinkTarget {
+    Task(Uuid),
+    /// Decoded path of a document relative to its repository root
+    Document(String),
+}
+
+impl BoardLinkTarget {
+    /// The link to this target, without a fragment
+    pub fn href(&self) -> String {
+        match self {
+            BoardLinkTarget::Task(id) => format!("{SCHEME}task/{id}"),
+            BoardLinkTarget::Document(path) => format!("{SCHEME}doc/{}", encode_path(path)),
+        }
+    }
+}
+
+/// A `vk://` link found in a text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoardLink {
+    /// The link as written, including any fragment
+    pub href: String,
+    /// Byte range of `href` in the text
+    pub range: Range<usize>,
+    /// `None` if the link doesn't name a task or a document
+    pub target: Option<BoardLinkTarget>,
+    pub fragment: Option<String>,
+}
+
+fn link_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r#"vk://[^\s()<>\[\]"'`]+"#).unwrap())
+}
+
+/// Target and fragment of a `vk://` link
+pub fn parse_href(href: &str) -> Option<(BoardLinkTarget, Option<String>)> {
+    let rest = href.strip_prefix(SCHEME)?;
+    let (rest, fragment) = match rest.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment.to_string())),
+        None => (rest, None),
+    };
+    let target = if let Some(id) = rest.strip_prefix("task/") {
+        BoardLinkTarget::Task(Uuid::parse_str(id.trim_end_matches('/')).ok()?)
+    } else if let Some(path) = rest.strip_prefix("doc/") {
+        let path = decode_path(path)?;
+        let path = path.trim_matches('/');
+        if path.is_empty() {
+            return None;
+        }
+        BoardLinkTarget::Document(path.to_string())
+    } else {
+        return None;
+    };
+    Some((target, fragment))
+}
+
+/// `vk://` links of `text`, in order. Punctuation right after a bare link ends it.
+pub fn find(text: &str) -> Vec<BoardLink> {
+    link_pattern()
+        .find_iter(text)
+        .filter_map(|found| {
+            let href = found
+                .as_str()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?']);
+            if href.len() == SCHEME.len() {
+                return None;
+            }
+            let (target, fragment) = match parse_href(href) {
+                Some((target, fragment)) => (Some(target), fragment),
+                None => (None, None),
+            };
+            Some(BoardLink {
+                href: href.to_string(),
+                range: found.start()..found.start() + href.len(),
+                target,
+                fragment,
+            })
+        })
+        .collect()
+}
+
+/// Replace the links of `text` that `rewrite` returns a new target for, keeping their
+/// fragments. Returns `None` if nothing changed.
+pub fn rewrite(
+    text: &str,
+    rewrite: impl Fn(&BoardLinkTarget) -> Option<BoardLinkTarget>,
+) -> Option<String> {
+    let mut rewritten = String::with_capacity(text.len());
+    let mut offset = 0;
+    for link in find(text) {
+        let Some(target) = link.target.as_ref().and_then(&rewrite) else {
+            continue;
+        };
+        rewritten.push_str(&text[offset..link.range.start]);
+        rewritten.push_str(&target.href());
+        if let Some(fragment) = &link.fragment {
+            rewritten.push('#');
+            rewritten.push_str(fragment);
+        }
+        offset = link.range.end;
+    }
+    if offset == 0 {
+        return None;
+    }
+    rewritten.push_str(&text[offset..]);
+    Some(rewritten)
+}
+
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+fn decode_path(path: &str) -> Option<String> {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(index + 1..index + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_task_and_document_links() {
+        let id = Uuid::new_v4();
+        let text = format!(
+            "See [the task](vk://task/{id}), vk://doc/docs/release%20notes.md#rollout. \
+             Also vk://board/1 and `vk://`."
+        );
+        let links = find(&text);
+
+        assert_eq!(links.len(), 3);
+        assert_eq!(links[0].target, Some(BoardLinkTarget::Task(id)));
+        assert_eq!(&text[links[0].range.clone()], format!("vk://task/{id}"));
+        assert_eq!(
+            links[1].target,
+            Some(BoardLinkTarget::Document(
+                "docs/release notes.md".to_string()
+            ))
+        );
+        assert_eq!(links[1].fragment.as_deref(), Some("rollout"));
+        assert_eq!(links[1].href, "vk://doc/docs/release%20notes.md#rollout");
+        assert_eq!(links[2].target, None);
+    }
+
+    #[test]
+    fn rewrites_links_keeping_fragments() {
+        let old = BoardLinkTarget::Document("docs/old name.md".to_string());
+        let new = BoardLinkTarget::Document("docs/new.md".to_string());
+        let text = format!("[a]({}#intro) and {}", old.href(), old.href());
+
+        assert_eq!(
+            rewrite(&text, |target| (*target == old).then(|| new.clone())),
+            Some("[a](vk://doc/docs/new.md#intro) and vk://doc/docs/new.md".to_string())
+        );
+        assert_eq!(rewrite(&text, |_| None), None);
+    }
+}
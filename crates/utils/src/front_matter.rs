@@ -0,0 +1,336 @@
+//! YAML front matter of markdown documents.
+//!
+//! Only the subset docs use in practice is understood: top-level `key: value` scalars,
+//! flow lists (`[a, b]`), block lists (`- item`) and one level of nested `key: value`
+//! pairs. Anything else is kept verbatim when fields are updated.
+
+use std::{fs::File, io::Read, path::Path};
+
+use serde_json::{Map, Value};
+
+/// Front matter longer than this isn't looked for when reading just the head of a file
+const MAX_FRONT_MATTER_BYTES: u64 = 64 * 1024;
+
+/// Split `content` into its front matter block (without the `---` fences) and the body.
+/// Returns `None` when the document doesn't start with a closed front matter block.
+pub fn split(content: &str) -> Option<(&str, &str)> {
+    let rest = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))?;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let fence = line.trim_end_matches(['\r', '\n']);
+        if fence == "---" || fence == "..." {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Parse the front matter of `content`, if it has any
+pub fn parse(content: &str) -> Option<Map<String, Value>> {
+    split(content).map(|(yaml, _)| parse_fields(yaml))
+}
+
+/// Parse the front matter of the file at `path`, reading only the head of the file
+pub fn read(path: &Path) -> Option<Map<String, Value>> {
+    let mut head = String::new();
+    File::open(path)
+        .ok()?
+        .take(MAX_FRONT_MATTER_BYTES)
+        .read_to_string(&mut head)
+        .ok()?;
+    parse(&head)
+}
+
+/// Set front matter fields of `content`, leaving the body untouched. A `null` value
+/// removes the field. Fields that aren't changed keep their original formatting.
+pub fn update(content: &str, fields: &Map<String, Value>) -> String {
+    let (yaml, body) = split(content).unwrap_or(("", content));
+    let mut lines: Vec<String> = Vec::new();
+    let mut pending: Vec<(&String, &Value)> = fields.iter().collect();
+
+    for entry in entries(yaml) {
+        let Some(key) = entry.key else {
+            lines.extend(entry.lines.iter().map(|line| line.to_string()));
+            continue;
+        };
+        match pending.iter().position(|(field, _)| field.as_str() == key) {
+            Some(index) => {
+                let (field, value) = pending.remove(index);
+                if !value.is_null() {
+                    lines.push(format!("{}: {}", field, render_value(value)));
+                }
+            }
+            None => lines.extend(entry.lines.iter().map(|line| line.to_string())),
+        }
+    }
+    for (field, value) in pending {
+        if !value.is_null() {
+            lines.push(format!("{}: {}", field, render_value(value)));
+        }
+    }
+
+    if lines.iter().all(|line| line.trim().is_empty()) {
+        return body.to_string();
+    }
+    format!("---\n{}\n---\n{}", lines.join("\n"), body)
+}
+
+/// A top-level field with the lines that belong to it. Comments and blank lines before
+/// the first field form an entry without key.
+struct Entry<'a> {
+    key: Option<&'a str>,
+    lines: Vec<&'a str>,
+}
+
+fn entries(yaml: &str) -> Vec<Entry<'_>> {
+    let mut entries: Vec<Entry> = Vec::new();
+    for line in yaml.lines() {
+        let line = line.trim_end_matches('\r');
+        let key = (!line.starts_with([' ', '\t', '-', '#']))
+            .then(|| line.split_once(':'))
+            .flatten()
+            .map(|(key, _)| unquote(key.trim()));
+        match (key, entries.last_mut()) {
+            (Some(key), _) => entries.push(Entry {
+                key: Some(key),
+                lines: vec![line],
+            }),
+            (None, Some(entry)) => entry.lines.push(line),
+            (None, None) => entries.push(Entry {
+                key: None,
+                lines: vec![line],
+            }),
+        }
+    }
+    entries
+}
+
+fn parse_fields(yaml: &str) -> Map<String, Value> {
+    let mut fields = Map::new();
+    for entry in entries(yaml) {
+        let Some(key) = entry.key else {
+            continue;
+        };
+        let inline = entry.lines[0]
+            .split_once(':')
+            .map(|(_, value)| value.trim())
+            .unwrap_or_default();
+        let value = if !inline.is_empty() {
+            parse_inline(inline)
+        } else {
+            parse_block(&entry.lines[1..])
+        };
+        fields.insert(key.to_string(), value);
+    }
+    fields
+}
+
+/// Value of a field given on the following, indented lines
+fn parse_block(lines: &[&str]) -> Value {
+    let lines: Vec<&str> = lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    if lines.is_empty() {
+        return Value::Null;
+    }
+    if lines.iter().all(|line| line.starts_with('-')) {
+        return Value::Array(
+            lines
+                .iter()
+                .map(|line| parse_inline(line[1..].trim()))
+                .collect(),
+        );
+    }
+    let mut object = Map::new();
+    for line in lines {
+        if let Some((key, value)) = line.split_once(':') {
+            object.insert(unquote(key.trim()).to_string(), parse_inline(value.trim()));
+        }
+    }
+    Value::Object(object)
+}
+
+/// A value written on the same line as its key
+fn parse_inline(value: &str) -> Value {
+    if let Some(items) = value
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'))
+    {
+        return Value::Array(
+            split_flow_items(items)
+                .into_iter()
+                .map(parse_scalar)
+                .collect(),
+        );
+    }
+    parse_scalar(value)
+}
+
+fn parse_scalar(value: &str) -> Value {
+    let value = value.trim();
+    if value.starts_with('"') {
+        return serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+    }
+    if let Some(quoted) = value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+    {
+        return Value::String(quoted.replace("''", "'"));
+    }
+    // Unquoted values end at a comment
+    let value = match value.find(" #") {
+        Some(index) => value[..index].trim_end(),
+        None => value,
+    };
+    match value {
+        "" | "~" | "null" | "Null" | "NULL" => Value::Null,
+        "true" | "True" | "TRUE" => Value::Bool(true),
+        "false" | "False" | "FALSE" => Value::Bool(false),
+        _ => value
+            .parse::<i64>()
+            .map(Value::from)
+            .ok()
+            .or_else(|| {
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(|n| n.is_finite().then(|| Value::from(n)))
+            })
+            .unwrap_or_else(|| Value::String(value.to_string())),
+    }
+}
+
+/// Split the inside of a flow list at commas outside of quotes
+fn split_flow_items(items: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (index, c) in items.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(open)) if c == open => quote = None,
+            (',', None) => {
+                result.push(items[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = items[start..].trim();
+    if !last.is_empty() || !result.is_empty() {
+        result.push(last);
+    }
+    result
+}
+
+fn unquote(key: &str) -> &str {
+    key.strip_prefix('"')
+        .and_then(|key| key.strip_suffix('"'))
+        .or_else(|| {
+            key.strip_prefix('\'')
+                .and_then(|key| key.strip_suffix('\''))
+        })
+        .unwrap_or(key)
+}
+
+/// Render a value on one line. JSON is valid flow-style YAML, so anything that isn't a
+/// plain string is written as JSON.
+fn render_value(value: &Value) -> String {
+    match value {
+        Value::String(s) if is_plain(s) => s.clone(),
+        Value::Array(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(render_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        _ => value.to_string(),
+    }
+}
+
+/// Whether a string can be written without quotes and still parse back as that string
+fn is_plain(s: &str) -> bool {
+    !s.is_empty()
+        && s.trim() == s
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '_' | '-' | '.' | '/'))
+        && !s.starts_with('-')
+        && matches!(parse_scalar(s), Value::String(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const DOC: &str = "---\n\
+        title: Payment flow\n\
+        owner: 'Dana'\n\
+        tags: [billing, \"api, v2\"]\n\
+        # review cadence\n\
+        status: draft # until reviewed\n\
+        reviewers:\n  - ana\n  - bo\n\
+        links:\n  spec: specs/pay.md\n\
+        version: 3\n\
+        ---\n\
+        # Payment flow\n";
+
+    #[test]
+    fn parses_supported_fields() {
+        let fields = parse(DOC).unwrap();
+        assert_eq!(
+            Value::Object(fields),
+            json!({
+                "title": "Payment flow",
+                "owner": "Dana",
+                "tags": ["billing", "api, v2"],
+                "status": "draft",
+                "reviewers": ["ana", "bo"],
+                "links": {"spec": "specs/pay.md"},
+                "version": 3,
+            })
+        );
+    }
+
+    #[test]
+    fn ignores_documents_without_closed_front_matter() {
+        assert!(parse("# Title\n---\n").is_none());
+        assert!(parse("---\ntitle: x\n").is_none());
+        assert_eq!(
+            split("---\r\na: 1\r\n---\r\nbody"),
+            Some(("a: 1\r\n", "body"))
+        );
+    }
+
+    #[test]
+    fn update_keeps_body_and_untouched_fields() {
+        let fields = json!({"status": "approved", "owner": null, "area": "payments & tax"});
+        let updated = update(DOC, fields.as_object().unwrap());
+
+        assert!(updated.ends_with("---\n# Payment flow\n"));
+        assert!(updated.contains("# review cadence\nstatus: approved\nreviewers:\n  - ana\n"));
+        assert!(!updated.contains("owner"));
+        assert!(updated.contains("area: \"payments & tax\"\n"));
+        assert_eq!(parse(&updated).unwrap()["area"], json!("payments & tax"));
+    }
+
+    #[test]
+    fn update_adds_and_removes_the_block() {
+        let added = update(
+            "# Notes\n",
+            json!({"tags": ["a", "1"]}).as_object().unwrap(),
+        );
+        assert_eq!(added, "---\ntags: [a, \"1\"]\n---\n# Notes\n");
+
+        let removed = update(&added, json!({"tags": null}).as_object().unwrap());
+        assert_eq!(removed, "# Notes\n");
+    }
+}
@@ -0,0 +1,267 @@
+//! Translations of user-visible messages made by the server. Messages live in one
+//! catalog per locale under `crates/utils/locales`, written in the basic subset of
+//! Fluent: `id = text`, with `{ $name }` placeholders and `#` comments. A message
+//! missing from a catalog falls back to English.
+//!
+//! The locale of the request being handled is kept in a task-local, so code deep in a
+//! handler can call [`t`] without having the locale passed down to it.
+
+use std::{collections::HashMap, fmt::Display, future::Future, sync::OnceLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Fr,
+    Ja,
+    Es,
+    Ko,
+    ZhHans,
+    ZhHant,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 7] = [
+        Locale::En,
+        Locale::Fr,
+        Locale::Ja,
+        Locale::Es,
+        Locale::Ko,
+        Locale::ZhHans,
+        Locale::ZhHant,
+    ];
+
+    /// BCP 47 tag, e.g. for a `Content-Language` header
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Fr => "fr",
+            Locale::Ja => "ja",
+            Locale::Es => "es",
+            Locale::Ko => "ko",
+            Locale::ZhHans => "zh-Hans",
+            Locale::ZhHant => "zh-Hant",
+        }
+    }
+
+    /// The supported locale for a language tag such as `fr-CA` or `zh-TW`
+    pub fn parse(tag: &str) -> Option<Self> {
+        let tag = tag.trim().to_ascii_lowercase().replace('_', "-");
+        let mut subtags = tag.split('-');
+        match subtags.next()? {
+            "en" => Some(Locale::En),
+            "fr" => Some(Locale::Fr),
+            "ja" => Some(Locale::Ja),
+            "es" => Some(Locale::Es),
+            "ko" => Some(Locale::Ko),
+            "zh" => {
+                let traditional =
+                    subtags.any(|subtag| matches!(subtag, "hant" | "tw" | "hk" | "mo"));
+                Some(if traditional {
+                    Locale::ZhHant
+                } else {
+                    Locale::ZhHans
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The supported locale the client prefers most in an `Accept-Language` header
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut ranges: Vec<(&str, f32)> = header
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // Stable, so equally preferred languages keep the client's order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranges.into_iter().find_map(|(tag, _)| Self::parse(tag))
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Locale::En => include_str!("../locales/en.ftl"),
+            Locale::Fr => include_str!("../locales/fr.ftl"),
+            Locale::Ja => include_str!("../locales/ja.ftl"),
+            Locale::Es => include_str!("../locales/es.ftl"),
+            Locale::Ko => include_str!("../locales/ko.ftl"),
+            Locale::ZhHans => include_str!("../locales/zh-Hans.ftl"),
+            Locale::ZhHant => include_str!("../locales/zh-Hant.ftl"),
+        }
+    }
+}
+
+type Catalog = HashMap<&'static str, &'static str>;
+
+fn parse_catalog(source: &'static str) -> Catalog {
+    source
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(id, text)| (id.trim(), text.trim()))
+        .filter(|(id, _)| !id.is_empty())
+        .collect()
+}
+
+fn catalog(locale: Locale) -> &'static Catalog {
+    static CATALOGS: OnceLock<HashMap<Locale, Catalog>> = OnceLock::new();
+    &CATALOGS.get_or_init(|| {
+        Locale::ALL
+            .into_iter()
+            .map(|locale| (locale, parse_catalog(locale.source())))
+            .collect()
+    })[&locale]
+}
+
+/// `text` with each `{ $name }` replaced by the value of `name` in `args`. Unknown
+/// placeholders are left as they are.
+fn fill(text: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut filled = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        filled.push_str(&rest[..start]);
+        let placeholder = &rest[start..=end];
+        let name = placeholder[1..placeholder.len() - 1]
+            .trim()
+            .strip_prefix('$');
+        match name.and_then(|name| args.iter().find(|(arg, _)| *arg == name)) {
+            Some((_, value)) => filled.push_str(&value.to_string()),
+            None => filled.push_str(placeholder),
+        }
+        rest = &rest[end + 1..];
+    }
+    filled.push_str(rest);
+    filled
+}
+
+/// The message `id` in `locale`, falling back to English and then to the id itself
+pub fn translate(locale: Locale, id: &str, args: &[(&str, &dyn Display)]) -> String {
+    let text = catalog(locale)
+        .get(id)
+        .or_else(|| catalog(Locale::En).get(id))
+        .copied()
+        .unwrap_or(id);
+    fill(text, args)
+}
+
+tokio::task_local! {
+    static CURRENT: Locale;
+}
+
+/// Run `f` with `locale` as the locale of its messages
+pub async fn scope<F: Future>(locale: Locale, f: F) -> F::Output {
+    CURRENT.scope(locale, f).await
+}
+
+/// Locale of the request being handled; English outside of one
+pub fn current() -> Locale {
+    CURRENT.try_with(|locale| *locale).unwrap_or_default()
+}
+
+/// The message `id` in the locale of the request being handled
+pub fn t(id: &str, args: &[(&str, &dyn Display)]) -> String {
+    translate(current(), id, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    fn placeholders(text: &str) -> BTreeSet<&str> {
+        text.split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}'))
+            .map(|(name, _)| name.trim())
+            .collect()
+    }
+
+    #[test]
+    fn every_catalog_translates_every_english_message_with_the_same_placeholders() {
+        let english = catalog(Locale::En);
+        for locale in Locale::ALL {
+            let translated = catalog(locale);
+            for (id, text) in english {
+                let translation = translated
+                    .get(id)
+                    .unwrap_or_else(|| panic!("{} has no '{}'", locale.tag(), id));
+                assert_eq!(
+                    placeholders(translation),
+                    placeholders(text),
+                    "placeholders of '{}' in {}",
+                    id,
+                    locale.tag()
+                );
+            }
+            for id in translated.keys() {
+                assert!(
+                    english.contains_key(id),
+                    "{} has unknown '{}'",
+                    locale.tag(),
+                    id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn placeholders_are_filled_and_unknown_ones_kept() {
+        let count = 3;
+        assert_eq!(
+            fill(
+                "Pulled { $count } commit(s) from {$remote} { $other }",
+                &[("count", &count), ("remote", &"origin"),]
+            ),
+            "Pulled 3 commit(s) from origin { $other }"
+        );
+    }
+
+    #[test]
+    fn missing_messages_fall_back_to_their_id() {
+        assert_eq!(
+            translate(Locale::Fr, "no-such-message", &[]),
+            "no-such-message"
+        );
+    }
+
+    #[test]
+    fn language_tags_map_to_supported_locales() {
+        assert_eq!(Locale::parse("fr-CA"), Some(Locale::Fr));
+        assert_eq!(Locale::parse("zh-TW"), Some(Locale::ZhHant));
+        assert_eq!(Locale::parse("zh-Hant-HK"), Some(Locale::ZhHant));
+        assert_eq!(Locale::parse("zh_CN"), Some(Locale::ZhHans));
+        assert_eq!(Locale::parse("de"), None);
+    }
+
+    #[test]
+    fn the_most_preferred_supported_language_wins() {
+        assert_eq!(
+            Locale::from_accept_language("de-DE, ja;q=0.8, fr;q=0.9"),
+            Some(Locale::Fr)
+        );
+        assert_eq!(
+            Locale::from_accept_language("ko, en;q=0.5"),
+            Some(Locale::Ko)
+        );
+        assert_eq!(Locale::from_accept_language("fr;q=0, de"), None);
+        assert_eq!(Locale::from_accept_language(""), None);
+    }
+
+    #[tokio::test]
+    async fn messages_use_the_locale_of_their_scope() {
+        assert_eq!(current(), Locale::En);
+        let locale = scope(Locale::Ja, async { current() }).await;
+        assert_eq!(locale, Locale::Ja);
+    }
+}
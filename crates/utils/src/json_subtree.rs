@@ -0,0 +1,199 @@
+//! Reading part of a large JSON document within a size budget, so clients can explore
+//! it a section at a time instead of downloading all of it.
+
+use serde_json::{Map, Value};
+
+/// Appended to strings that were cut short
+const ELLIPSIS: &str = "…";
+
+/// A copy of a JSON value cut down to a size budget
+#[derive(Debug, Clone, PartialEq)]
+pub struct TruncatedJson {
+    pub value: Value,
+    /// Whether any entries or characters were left out
+    pub truncated: bool,
+}
+
+/// Copy of `value` with at most `limit` entries per array or object that serializes to
+/// at most about `max_bytes`. Entries are kept in document order until the budget runs
+/// out, so the start of every container that fits is shown.
+pub fn truncate(value: &Value, limit: usize, max_bytes: usize) -> TruncatedJson {
+    let mut budget = Budget {
+        remaining: max_bytes,
+        limit,
+        truncated: false,
+    };
+    let value = budget.copy(value).unwrap_or(Value::Null);
+    TruncatedJson {
+        value,
+        truncated: budget.truncated,
+    }
+}
+
+struct Budget {
+    remaining: usize,
+    limit: usize,
+    truncated: bool,
+}
+
+impl Budget {
+    fn take(&mut self, bytes: usize) -> bool {
+        if bytes > self.remaining {
+            self.truncated = true;
+            return false;
+        }
+        self.remaining -= bytes;
+        true
+    }
+
+    /// `None` if not even a shortened `value` fits in the rest of the budget
+    fn copy(&mut self, value: &Value) -> Option<Value> {
+        match value {
+            Value::Array(items) => {
+                // Brackets
+                if !self.take(2) {
+                    return None;
+                }
+                let mut copied = Vec::new();
+                for (index, item) in items.iter().take(self.limit).enumerate() {
+                    let separator = usize::from(index > 0);
+                    if !self.take(separator) {
+                        break;
+                    }
+                    let Some(item) = self.copy(item) else {
+                        break;
+                    };
+                    copied.push(item);
+                }
+                if copied.len() < items.len() {
+                    self.truncated = true;
+                }
+                Some(Value::Array(copied))
+            }
+            Value::Object(entries) => {
+                // Braces
+                if !self.take(2) {
+                    return None;
+                }
+                let mut copied = Map::new();
+                for (index, (key, item)) in entries.iter().take(self.limit).enumerate() {
+                    // Separator, quoted key and colon
+                    let key_len = usize::from(index > 0) + quoted_len(key) + 1;
+                    if !self.take(key_len) {
+                        break;
+                    }
+                    let Some(item) = self.copy(item) else {
+                        break;
+                    };
+                    copied.insert(key.clone(), item);
+                }
+                if copied.len() < entries.len() {
+                    self.truncated = true;
+                }
+                Some(Value::Object(copied))
+            }
+            Value::String(text) => {
+                if self.take(quoted_len(text)) {
+                    Some(value.clone())
+                } else {
+                    self.cut(text).map(Value::String)
+                }
+            }
+            scalar => self
+                .take(serde_json::to_string(scalar).map_or(0, |json| json.len()))
+                .then(|| scalar.clone()),
+        }
+    }
+
+    /// Start of `text` that fits in the rest of the budget, with an ellipsis
+    fn cut(&mut self, text: &str) -> Option<String> {
+        let mut used = 2 + ELLIPSIS.len();
+        if used >= self.remaining {
+            return None;
+        }
+        let mut cut = String::new();
+        for c in text.chars() {
+            let len = escaped_len(c);
+            if used + len > self.remaining {
+                break;
+            }
+            used += len;
+            cut.push(c);
+        }
+        self.remaining -= used;
+        cut.push_str(ELLIPSIS);
+        Some(cut)
+    }
+}
+
+/// Length of `text` as a JSON string, with quotes and escapes
+fn quoted_len(text: &str) -> usize {
+    2 + text.chars().map(escaped_len).sum::<usize>()
+}
+
+fn escaped_len(c: char) -> usize {
+    match c {
+        '"' | '\\' | '\n' | '\r' | '\t' | '\u{8}' | '\u{c}' => 2,
+        c if (c as u32) < 0x20 => 6,
+        c => c.len_utf8(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn keeps_values_that_fit() {
+        let value = json!({"a": [1, 2, {"b": "text"}], "c": null, "d": true});
+        let result = truncate(&value, 100, 1024);
+        assert_eq!(result.value, value);
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn lists_at_most_limit_entries_per_container() {
+        let value = json!({"items": [1, 2, 3, 4], "more": {"x": 1, "y": 2, "z": 3}});
+        let result = truncate(&value, 2, 1024);
+        assert_eq!(
+            result.value,
+            json!({"items": [1, 2], "more": {"x": 1, "y": 2}})
+        );
+        assert!(result.truncated);
+    }
+
+    #[test]
+    fn stays_within_the_byte_budget() {
+        let value = json!((0..1000).collect::<Vec<_>>());
+        let result = truncate(&value, usize::MAX, 100);
+        let json = serde_json::to_string(&result.value).unwrap();
+        assert!(json.len() <= 100, "{} bytes", json.len());
+        assert!(json.starts_with("[0,1,2,"));
+        assert!(result.truncated);
+    }
+
+    #[test]
+    fn cuts_long_strings() {
+        let value = json!({"text": "a".repeat(500)});
+        let result = truncate(&value, 100, 60);
+        let text = result.value["text"].as_str().unwrap();
+        assert!(text.starts_with("aaa"));
+        assert!(text.ends_with(ELLIPSIS));
+        assert!(serde_json::to_string(&result.value).unwrap().len() <= 60);
+        assert!(result.truncated);
+    }
+
+    #[test]
+    fn counts_escapes() {
+        assert_eq!(
+            quoted_len("a\"b\n"),
+            serde_json::to_string("a\"b\n").unwrap().len()
+        );
+        assert_eq!(
+            quoted_len("\u{1}é"),
+            serde_json::to_string("\u{1}é").unwrap().len()
+        );
+    }
+}
@@ -5,11 +5,16 @@ use directories::ProjectDirs;
 pub mod api;
 pub mod approvals;
 pub mod assets;
+pub mod board_links;
 pub mod browser;
 pub mod diff;
+pub mod front_matter;
 pub mod git;
+pub mod i18n;
+pub mod json_subtree;
 pub mod jwt;
 pub mod log_msg;
+pub mod logging;
 pub mod msg_store;
 pub mod path;
 pub mod port_file;
@@ -17,7 +22,9 @@ pub mod response;
 pub mod sentry;
 pub mod shell;
 pub mod stream_lines;
+pub mod task_list;
 pub mod text;
+pub mod timezone;
 pub mod tokio;
 pub mod version;
 
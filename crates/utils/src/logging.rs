@@ -0,0 +1,544 @@
+//! Log filtering that can be changed while the server runs, and an optional log file
+//! rotated by the hour or day. Changes are made on top of the directives the server
+//! started with, e.g. `server::routes::documents=debug` to look closer at one module,
+//! and last until they are reset or the server restarts. The latest log lines are also
+//! kept in memory with the request they were logged for, for error reports.
+
+use std::{
+    collections::VecDeque,
+    fmt::{self, Write as _},
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    sync::{Arc, LazyLock, Mutex, OnceLock},
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{
+    Event, Subscriber,
+    field::{Field, Visit},
+    span,
+};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry, filter::LevelFilter, fmt::MakeWriter, layer::Context,
+    registry::LookupSpan, reload,
+};
+use ts_rs::TS;
+
+/// Name of the log files, followed by the period they cover
+const LOG_FILE_PREFIX: &str = "vibe-kanban";
+const DEFAULT_MAX_FILES: usize = 7;
+/// Log lines kept in memory across all requests
+const RECENT_LOG_LINES: usize = 2000;
+
+/// Span field that log lines are correlated with their request by
+pub const REQUEST_ID_FIELD: &str = "request_id";
+
+#[derive(Debug, Error)]
+pub enum LoggingError {
+    #[error("Invalid log filter: {0}")]
+    InvalidDirectives(#[from] tracing_subscriber::filter::ParseError),
+    #[error("Unknown VK_LOG_ROTATION '{0}'. Use hourly, daily or never")]
+    InvalidRotation(String),
+    #[error("Invalid VK_LOG_MAX_FILES '{0}'. Use a number of files, or 0 to keep them all")]
+    InvalidMaxFiles(String),
+    #[error("Failed to apply the log filter: {0}")]
+    Reload(#[from] reload::Error),
+}
+
+/// How often a new log file is started
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Hourly,
+    Daily,
+    Never,
+}
+
+/// Log file written next to the console output, configured with `VK_LOG_DIR`,
+/// `VK_LOG_ROTATION` (default daily) and `VK_LOG_MAX_FILES` (default 7)
+#[derive(Debug, Clone)]
+pub struct LogFileSettings {
+    pub dir: PathBuf,
+    pub rotation: LogRotation,
+    /// Rotated files kept, the current one included; 0 keeps them all
+    pub max_files: usize,
+}
+
+impl LogFileSettings {
+    pub fn from_env() -> Result<Option<Self>, LoggingError> {
+        let var = |name| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let Some(dir) = var("VK_LOG_DIR") else {
+            return Ok(None);
+        };
+        let rotation = match var("VK_LOG_ROTATION")
+            .map(|value| value.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("daily") => LogRotation::Daily,
+            Some("hourly") => LogRotation::Hourly,
+            Some("never") => LogRotation::Never,
+            Some(other) => return Err(LoggingError::InvalidRotation(other.to_string())),
+        };
+        let max_files = match var("VK_LOG_MAX_FILES") {
+            None => DEFAULT_MAX_FILES,
+            Some(value) => value
+                .trim()
+                .parse()
+                .map_err(|_| LoggingError::InvalidMaxFiles(value))?,
+        };
+        Ok(Some(Self {
+            dir: PathBuf::from(dir),
+            rotation,
+            max_files,
+        }))
+    }
+
+    /// Name of the file logs written at `now` go to. Periods sort by name, oldest first.
+    fn file_name(&self, now: DateTime<Utc>) -> String {
+        match self.rotation {
+            LogRotation::Hourly => format!("{LOG_FILE_PREFIX}.{}.log", now.format("%Y-%m-%d-%H")),
+            LogRotation::Daily => format!("{LOG_FILE_PREFIX}.{}.log", now.format("%Y-%m-%d")),
+            LogRotation::Never => format!("{LOG_FILE_PREFIX}.log"),
+        }
+    }
+
+    /// Delete the oldest log files beyond `max_files`
+    fn prune(&self) -> io::Result<()> {
+        if self.max_files == 0 {
+            return Ok(());
+        }
+        let mut files: Vec<String> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| {
+                name.starts_with(&format!("{LOG_FILE_PREFIX}.")) && name.ends_with(".log")
+            })
+            .collect();
+        files.sort();
+        let excess = files.len().saturating_sub(self.max_files);
+        for name in &files[..excess] {
+            fs::remove_file(self.dir.join(name))?;
+        }
+        Ok(())
+    }
+}
+
+struct OpenLogFile {
+    name: String,
+    file: File,
+}
+
+/// Writer of the log file, which moves to a new file when the rotation period changes
+#[derive(Clone)]
+pub struct RollingFile {
+    settings: LogFileSettings,
+    current: Arc<Mutex<Option<OpenLogFile>>>,
+}
+
+impl RollingFile {
+    pub fn new(settings: LogFileSettings) -> Self {
+        Self {
+            settings,
+            current: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn write_at(&self, buf: &[u8], now: DateTime<Utc>) -> io::Result<usize> {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        let name = self.settings.file_name(now);
+        if current.as_ref().is_none_or(|open| open.name != name) {
+            fs::create_dir_all(&self.settings.dir)?;
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.settings.dir.join(&name))?;
+            *current = Some(OpenLogFile { name, file });
+            // Logging a failure here would write to this file again
+            let _ = self.settings.prune();
+        }
+        match current.as_mut() {
+            Some(open) => open.file.write(buf),
+            None => Ok(0),
+        }
+    }
+}
+
+impl Write for RollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_at(buf, Utc::now())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        match current.as_mut() {
+            Some(open) => open.file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for RollingFile {
+    type Writer = RollingFile;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// The part of a directive it overrides others with: its target, or none for a bare level
+fn directive_target(directive: &str) -> &str {
+    match directive.split_once('=') {
+        Some((target, _)) => target,
+        None if directive.parse::<LevelFilter>().is_ok() => "",
+        None => directive,
+    }
+}
+
+/// `base` with `overrides` applied: an override replaces the directive of its target,
+/// and is added when there is none
+fn combine_directives(base: &str, overrides: &str) -> String {
+    let split = |directives: &str| -> Vec<String> {
+        directives
+            .split(',')
+            .map(str::trim)
+            .filter(|directive| !directive.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let overrides = split(overrides);
+    let mut directives: Vec<String> = split(base)
+        .into_iter()
+        .filter(|directive| {
+            !overrides
+                .iter()
+                .any(|other| directive_target(other) == directive_target(directive))
+        })
+        .collect();
+    directives.extend(overrides);
+    directives.join(",")
+}
+
+/// Handle on the filter of the running subscriber
+pub struct LogControl {
+    base: String,
+    overrides: Mutex<String>,
+    handle: reload::Handle<EnvFilter, Registry>,
+    file: Option<LogFileSettings>,
+}
+
+impl LogControl {
+    /// The directives the server started with
+    pub fn base_directives(&self) -> &str {
+        &self.base
+    }
+
+    /// Directives applied on top of the base ones at runtime
+    pub fn overrides(&self) -> String {
+        self.overrides
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// The directives logs are filtered with now
+    pub fn effective_directives(&self) -> String {
+        combine_directives(&self.base, &self.overrides())
+    }
+
+    pub fn file(&self) -> Option<&LogFileSettings> {
+        self.file.as_ref()
+    }
+
+    /// Filter logs with the base directives and `overrides` from now on. Empty overrides
+    /// go back to the base directives; invalid ones leave the filter as it was.
+    pub fn set_overrides(&self, overrides: &str) -> Result<(), LoggingError> {
+        let mut current = self.overrides.lock().unwrap_or_else(|e| e.into_inner());
+        let filter = EnvFilter::try_new(combine_directives(&self.base, overrides))?;
+        self.handle.reload(filter)?;
+        *current = overrides.trim().to_string();
+        Ok(())
+    }
+}
+
+/// A filter for the subscriber's output layers that [`LogControl`] can change later
+pub fn reloadable_filter(
+    directives: &str,
+    file: Option<LogFileSettings>,
+) -> Result<(reload::Layer<EnvFilter, Registry>, LogControl), LoggingError> {
+    let (layer, handle) = reload::Layer::new(EnvFilter::try_new(directives)?);
+    let control = LogControl {
+        base: directives.to_string(),
+        overrides: Mutex::new(String::new()),
+        handle,
+        file,
+    };
+    Ok((layer, control))
+}
+
+static LOG_CONTROL: OnceLock<LogControl> = OnceLock::new();
+
+/// Make `control` available to [`log_control`] once the subscriber is set up
+pub fn install(control: LogControl) {
+    let _ = LOG_CONTROL.set(control);
+}
+
+/// The log filter of this process, unless its logging was set up without one
+pub fn log_control() -> Option<&'static LogControl> {
+    LOG_CONTROL.get()
+}
+
+struct RecentLogLine {
+    request_id: Option<String>,
+    line: String,
+}
+
+/// The latest log lines, with the request each was logged for
+#[derive(Clone, Default)]
+pub struct RecentLogs {
+    lines: Arc<Mutex<VecDeque<RecentLogLine>>>,
+}
+
+impl RecentLogs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Layer recording the events it sees into these logs
+    pub fn layer(&self) -> RecentLogsLayer {
+        RecentLogsLayer { logs: self.clone() }
+    }
+
+    fn push(&self, line: RecentLogLine) {
+        let mut lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        if lines.len() >= RECENT_LOG_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// The latest `limit` lines logged for `request_id`, oldest first
+    pub fn for_request(&self, request_id: &str, limit: usize) -> Vec<String> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        let mut matching: Vec<String> = lines
+            .iter()
+            .rev()
+            .filter(|line| line.request_id.as_deref() == Some(request_id))
+            .take(limit)
+            .map(|line| line.line.clone())
+            .collect();
+        matching.reverse();
+        matching
+    }
+}
+
+static RECENT_LOGS: LazyLock<RecentLogs> = LazyLock::new(RecentLogs::new);
+
+/// The log lines of this process kept for error reports
+pub fn recent_logs() -> &'static RecentLogs {
+    &RECENT_LOGS
+}
+
+/// The request id a span was created with
+struct SpanRequestId(String);
+
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+    request_id: Option<String>,
+}
+
+impl LineVisitor {
+    fn record(&mut self, field: &Field, value: String) {
+        match field.name() {
+            "message" => self.message = value,
+            REQUEST_ID_FIELD => self.request_id = Some(value),
+            name => {
+                let _ = write!(self.fields, " {name}={value}");
+            }
+        }
+    }
+}
+
+impl Visit for LineVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field, format!("{value:?}"));
+    }
+}
+
+/// Records events into [`RecentLogs`], tagged with the `request_id` of their span
+pub struct RecentLogsLayer {
+    logs: RecentLogs,
+}
+
+impl<S> Layer<S> for RecentLogsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let mut visitor = LineVisitor::default();
+        attrs.record(&mut visitor);
+        if let Some(request_id) = visitor.request_id
+            && let Some(span) = ctx.span(id)
+        {
+            span.extensions_mut().insert(SpanRequestId(request_id));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+        let request_id = visitor.request_id.clone().or_else(|| {
+            ctx.event_scope(event)?.find_map(|span| {
+                span.extensions()
+                    .get::<SpanRequestId>()
+                    .map(|id| id.0.clone())
+            })
+        });
+        let metadata = event.metadata();
+        self.logs.push(RecentLogLine {
+            request_id,
+            line: format!(
+                "{} {} {}: {}{}",
+                Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+                metadata.level(),
+                metadata.target(),
+                visitor.message,
+                visitor.fields
+            ),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn temp_log_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("vk-logging-test-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn log_files(dir: &PathBuf) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn overrides_replace_the_directive_of_their_target() {
+        let base = "warn,server=info,services=info";
+        assert_eq!(
+            combine_directives(base, "server::routes::documents=debug"),
+            "warn,server=info,services=info,server::routes::documents=debug"
+        );
+        assert_eq!(
+            combine_directives(base, " services=trace, error "),
+            "server=info,services=trace,error"
+        );
+        assert_eq!(combine_directives(base, ""), base);
+    }
+
+    #[test]
+    fn invalid_overrides_keep_the_current_filter() {
+        let (_layer, control) = reloadable_filter("warn,server=info", None).unwrap();
+        control.set_overrides("server::routes=debug").unwrap();
+        assert!(matches!(
+            control.set_overrides("server=loud"),
+            Err(LoggingError::InvalidDirectives(_))
+        ));
+        assert_eq!(control.overrides(), "server::routes=debug");
+        assert_eq!(
+            control.effective_directives(),
+            "warn,server=info,server::routes=debug"
+        );
+
+        control.set_overrides("").unwrap();
+        assert_eq!(control.effective_directives(), "warn,server=info");
+    }
+
+    #[test]
+    fn log_lines_are_kept_with_their_request() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let logs = RecentLogs::new();
+        let subscriber = Registry::default().with(logs.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("before any request");
+            let span = tracing::info_span!("request", request_id = "abc");
+            span.in_scope(|| {
+                tracing::warn!(path = "docs/a.md", "Failed to push");
+                tracing::error!("Sync failed");
+            });
+            tracing::info_span!("request", request_id = "other")
+                .in_scope(|| tracing::error!("Unrelated"));
+        });
+
+        let lines = logs.for_request("abc", 10);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("WARN utils::logging::tests: Failed to push path=docs/a.md"));
+        assert!(lines[1].ends_with("ERROR utils::logging::tests: Sync failed"));
+        assert_eq!(logs.for_request("abc", 1), lines[1..]);
+        assert!(logs.for_request("missing", 10).is_empty());
+    }
+
+    #[test]
+    fn files_rotate_by_period_and_old_ones_are_deleted() {
+        let dir = temp_log_dir();
+        let file = RollingFile::new(LogFileSettings {
+            dir: dir.clone(),
+            rotation: LogRotation::Hourly,
+            max_files: 2,
+        });
+        for hour in [9, 9, 10, 11] {
+            let now = Utc.with_ymd_and_hms(2026, 3, 1, hour, 30, 0).unwrap();
+            file.write_at(format!("at {hour}\n").as_bytes(), now)
+                .unwrap();
+        }
+
+        assert_eq!(
+            log_files(&dir),
+            [
+                "vibe-kanban.2026-03-01-10.log",
+                "vibe-kanban.2026-03-01-11.log"
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("vibe-kanban.2026-03-01-11.log")).unwrap(),
+            "at 11\n"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn without_rotation_one_file_is_appended_to() {
+        let dir = temp_log_dir();
+        let file = RollingFile::new(LogFileSettings {
+            dir: dir.clone(),
+            rotation: LogRotation::Never,
+            max_files: 1,
+        });
+        for day in [1, 2] {
+            let now = Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+            file.write_at(format!("day {day}\n").as_bytes(), now)
+                .unwrap();
+        }
+
+        assert_eq!(log_files(&dir), ["vibe-kanban.log"]);
+        assert_eq!(
+            fs::read_to_string(dir.join("vibe-kanban.log")).unwrap(),
+            "day 1\nday 2\n"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,12 +1,32 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+/// A non-fatal problem in a request that otherwise succeeded, e.g. a document was saved
+/// but could not be auto-committed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct ApiWarning {
+    /// Stable identifier clients can match on, e.g. `auto_commit_failed`
+    pub code: String,
+    pub message: String,
+}
+
+impl ApiWarning {
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 pub struct ApiResponse<T, E = T> {
     success: bool,
     data: Option<T>,
     error_data: Option<E>,
     message: Option<String>,
+    #[serde(default)]
+    warnings: Vec<ApiWarning>,
 }
 
 impl<T, E> ApiResponse<T, E> {
@@ -17,6 +37,7 @@ impl<T, E> ApiResponse<T, E> {
             data: Some(data),
             message: None,
             error_data: None,
+            warnings: Vec::new(),
         }
     }
 
@@ -27,6 +48,7 @@ impl<T, E> ApiResponse<T, E> {
             data: None,
             message: Some(message.to_string()),
             error_data: None,
+            warnings: Vec::new(),
         }
     }
     /// Creates an error response, with no `data`, no `message`, but with arbitrary `error_data`.
@@ -36,9 +58,16 @@ impl<T, E> ApiResponse<T, E> {
             data: None,
             error_data: Some(data),
             message: None,
+            warnings: Vec::new(),
         }
     }
 
+    /// Attaches non-fatal warnings to the response.
+    pub fn with_warnings(mut self, warnings: impl IntoIterator<Item = ApiWarning>) -> Self {
+        self.warnings.extend(warnings);
+        self
+    }
+
     /// Returns true if the response was successful.
     pub fn is_success(&self) -> bool {
         self.success
@@ -53,4 +82,34 @@ impl<T, E> ApiResponse<T, E> {
     pub fn message(&self) -> Option<&str> {
         self.message.as_deref()
     }
+
+    /// Returns the non-fatal warnings attached to the response.
+    pub fn warnings(&self) -> &[ApiWarning] {
+        &self.warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warnings_are_always_serialized() {
+        let response: ApiResponse<u32> = ApiResponse::success(1);
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["warnings"], serde_json::json!([]));
+
+        let response = ApiResponse::<u32>::success(1)
+            .with_warnings([ApiWarning::new("auto_commit_failed", "Not committed")]);
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["warnings"][0]["code"], "auto_commit_failed");
+    }
+
+    #[test]
+    fn responses_without_warnings_deserialize() {
+        let response: ApiResponse<u32> =
+            serde_json::from_str(r#"{"success":true,"data":1,"error_data":null,"message":null}"#)
+                .unwrap();
+        assert!(response.warnings().is_empty());
+    }
 }
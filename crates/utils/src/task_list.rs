@@ -0,0 +1,182 @@
+//! GitHub-style task lists (`- [ ] item`) in markdown documents.
+
+use uuid::Uuid;
+
+/// One checkbox item of a task list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskListItem {
+    /// 1-based line number
+    pub line: usize,
+    pub checked: bool,
+    /// Text after the checkbox, without the link to the item's task
+    pub text: String,
+    /// Task the item links to through a `/tasks/<id>` link
+    pub task_id: Option<Uuid>,
+}
+
+/// Task list items of `content`, skipping fenced code blocks
+pub fn parse(content: &str) -> Vec<TaskListItem> {
+    let mut items = Vec::new();
+    let mut fence: Option<&str> = None;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        match fence {
+            Some(open) if trimmed.starts_with(open) => fence = None,
+            Some(_) => {}
+            None if trimmed.starts_with("```") => fence = Some("```"),
+            None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+            None => {
+                if let Some(item) = parse_item(index + 1, line) {
+                    items.push(item);
+                }
+            }
+        }
+    }
+    items
+}
+
+/// Check or uncheck the item on `line`. Returns `None` if that line isn't a task list item.
+pub fn set_checked(content: &str, line: usize, checked: bool) -> Option<String> {
+    edit_line(content, line, |text| {
+        let (offset, _) = checkbox(text)?;
+        let mark = if checked { "x" } else { " " };
+        Some(format!(
+            "{}{}{}",
+            &text[..offset + 1],
+            mark,
+            &text[offset + 2..]
+        ))
+    })
+}
+
+/// Append `suffix` to the item on `line`. Returns `None` if that line isn't a task list item.
+pub fn append_to_item(content: &str, line: usize, suffix: &str) -> Option<String> {
+    edit_line(content, line, |text| {
+        checkbox(text)?;
+        Some(format!("{}{}", text.trim_end(), suffix))
+    })
+}
+
+fn parse_item(line_number: usize, line: &str) -> Option<TaskListItem> {
+    let (offset, checked) = checkbox(line)?;
+    let rest = line[offset + 3..].trim();
+    let task_id = find_task_id(rest);
+    // Drop a trailing `([task](.../tasks/<id>))` so only the item's own words remain
+    let text = match task_id.and_then(|_| rest.find("/tasks/")) {
+        Some(link) => rest[..rest[..link].rfind('[').unwrap_or(link)]
+            .trim_end()
+            .trim_end_matches('(')
+            .trim_end(),
+        None => rest,
+    };
+    Some(TaskListItem {
+        line: line_number,
+        checked,
+        text: text.to_string(),
+        task_id,
+    })
+}
+
+/// Byte offset of the `[` of a list item's checkbox, and whether it is checked
+fn checkbox(line: &str) -> Option<(usize, bool)> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    let marker = if rest.starts_with(['-', '*', '+']) {
+        1
+    } else {
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        if digits == 0 || !rest[digits..].starts_with(['.', ')']) {
+            return None;
+        }
+        digits + 1
+    };
+    let after_marker = &rest[marker..];
+    let spaces = after_marker.len() - after_marker.trim_start_matches(' ').len();
+    if spaces == 0 {
+        return None;
+    }
+    let offset = indent + marker + spaces;
+    let checked = match line[offset..].get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    let after = &line[offset + 3..];
+    (after.is_empty() || after.starts_with([' ', '\t'])).then_some((offset, checked))
+}
+
+fn find_task_id(text: &str) -> Option<Uuid> {
+    text.match_indices("/tasks/")
+        .find_map(|(index, pattern)| text.get(index + pattern.len()..)?.get(..36))
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
+/// Replace the text of `line` (1-based), keeping its line break
+fn edit_line(
+    content: &str,
+    line: usize,
+    edit: impl FnOnce(&str) -> Option<String>,
+) -> Option<String> {
+    let mut start = 0;
+    for (index, current) in content.split_inclusive('\n').enumerate() {
+        if index + 1 == line {
+            let text = current.trim_end_matches(['\r', '\n']);
+            let edited = edit(text)?;
+            let end = start + text.len();
+            return Some(format!(
+                "{}{}{}",
+                &content[..start],
+                edited,
+                &content[end..]
+            ));
+        }
+        start += current.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TASK_ID: &str = "7f4c1c9e-58a3-4d3e-9d5b-0a4f0c2b8e11";
+
+    #[test]
+    fn parses_items_outside_code_blocks() {
+        let content = format!(
+            "# Plan\n\
+             - [ ] Write migration\n\
+             \x20 * [x] Review schema ([task](/projects/p/tasks/{TASK_ID}))\n\
+             1. [X] Ship\n\
+             - [] not an item\n\
+             ```\n\
+             - [ ] example\n\
+             ```\n"
+        );
+        let items = parse(&content);
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].line, 2);
+        assert!(!items[0].checked);
+        assert_eq!(items[0].text, "Write migration");
+        assert_eq!(items[1].text, "Review schema");
+        assert_eq!(items[1].task_id, Some(Uuid::parse_str(TASK_ID).unwrap()));
+        assert!(items[2].checked);
+        assert_eq!(items[2].task_id, None);
+    }
+
+    #[test]
+    fn edits_keep_other_lines() {
+        let content = "intro\r\n- [ ] Write migration\r\nend";
+
+        let checked = set_checked(content, 2, true).unwrap();
+        assert_eq!(checked, "intro\r\n- [x] Write migration\r\nend");
+        assert_eq!(set_checked(&checked, 2, false).as_deref(), Some(content));
+        assert_eq!(
+            append_to_item(content, 2, " ([task](/tasks/1))").as_deref(),
+            Some("intro\r\n- [ ] Write migration ([task](/tasks/1))\r\nend")
+        );
+        assert!(set_checked(content, 1, true).is_none());
+        assert!(append_to_item(content, 4, "x").is_none());
+    }
+}
@@ -23,6 +23,15 @@ pub fn short_uuid(u: &Uuid) -> String {
     full.chars().take(4).collect() // grab the first 4 chars
 }
 
+/// Bytes looked at to tell binary data from text, as many as git looks at
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// Whether `bytes` look like binary data rather than text, i.e. have a NUL byte near the
+/// start
+pub fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(BINARY_SNIFF_LEN).any(|&byte| byte == 0)
+}
+
 pub fn truncate_to_char_boundary(content: &str, max_len: usize) -> &str {
     if content.len() <= max_len {
         return content;
@@ -57,4 +66,17 @@ mod tests {
         assert_eq!(truncate_to_char_boundary(input, 5), "🔥");
         assert_eq!(truncate_to_char_boundary(input, 3), "");
     }
+
+    #[test]
+    fn test_looks_binary() {
+        use super::looks_binary;
+
+        assert!(!looks_binary(b"# Title\n\ntext"));
+        assert!(!looks_binary("naïve 🔥".as_bytes()));
+        assert!(!looks_binary(b""));
+        assert!(looks_binary(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+        let mut late_nul = vec![b'a'; 10_000];
+        late_nul.push(0);
+        assert!(!looks_binary(&late_nul));
+    }
 }
@@ -0,0 +1,644 @@
+//! Timezones of users and projects, so schedules and due dates follow local time instead
+//! of UTC. A timezone is an IANA name such as `Europe/Paris`, read from the system's tz
+//! database, or a fixed UTC offset such as `+05:30` or `UTC-3`. Windows has no tz
+//! database, so only offsets work there.
+
+use std::{fmt, path::PathBuf};
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+use ts_rs::TS;
+
+#[derive(Debug, Error)]
+pub enum TimezoneError {
+    #[error(
+        "Unknown timezone '{0}'. Use an IANA name such as Europe/Paris or an offset such as +05:30"
+    )]
+    Unknown(String),
+    #[error("Invalid time of day '{0}'. Use HH:MM")]
+    InvalidTimeOfDay(String),
+}
+
+/// An instant in UTC and in the local time of a timezone
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct LocalizedTime {
+    pub utc: DateTime<Utc>,
+    /// RFC 3339 with the local offset, e.g. `2026-03-01T09:00:00+01:00`
+    pub local: String,
+    pub timezone: String,
+}
+
+/// Offsets are seconds east of UTC
+#[derive(Debug, Clone)]
+enum Rules {
+    Fixed(i32),
+    Zone {
+        /// Offset before the first transition
+        initial: i32,
+        /// Unix time of each transition and the offset from then on, oldest first
+        transitions: Vec<(i64, i32)>,
+        /// Rule for instants after the last transition
+        footer: Option<PosixRule>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Timezone {
+    name: String,
+    rules: Rules,
+}
+
+impl Default for Timezone {
+    fn default() -> Self {
+        Self::utc()
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl Timezone {
+    pub fn utc() -> Self {
+        Self {
+            name: "UTC".to_string(),
+            rules: Rules::Fixed(0),
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self, TimezoneError> {
+        let name = name.trim();
+        let unknown = || TimezoneError::Unknown(name.to_string());
+        if name.is_empty() {
+            return Err(unknown());
+        }
+        if let Some(offset) = parse_fixed_offset(name) {
+            return Ok(Self {
+                name: name.to_string(),
+                rules: Rules::Fixed(offset),
+            });
+        }
+        // Names are looked up as paths, so only allow what IANA names are made of
+        let valid = name.split('/').all(|part| {
+            !part.is_empty()
+                && !part.starts_with('.')
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'))
+        });
+        if !valid {
+            return Err(unknown());
+        }
+        let data = tz_directories()
+            .into_iter()
+            .find_map(|dir| std::fs::read(dir.join(name)).ok())
+            .ok_or_else(unknown)?;
+        let rules = parse_tzif(&data).ok_or_else(unknown)?;
+        Ok(Self {
+            name: name.to_string(),
+            rules,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Offset from UTC in effect at `at`
+    pub fn offset_at(&self, at: DateTime<Utc>) -> FixedOffset {
+        let seconds = self.offset_seconds(at.timestamp());
+        FixedOffset::east_opt(seconds).unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+    }
+
+    fn offset_seconds(&self, timestamp: i64) -> i32 {
+        match &self.rules {
+            Rules::Fixed(offset) => *offset,
+            Rules::Zone {
+                initial,
+                transitions,
+                footer,
+            } => {
+                let applied = transitions.partition_point(|(at, _)| *at <= timestamp);
+                // Without transitions, the footer applies throughout (RFC 8536)
+                match (applied, footer) {
+                    (n, Some(footer)) if n == transitions.len() => footer.offset_at(timestamp),
+                    (0, _) => *initial,
+                    (n, _) => transitions[n - 1].1,
+                }
+            }
+        }
+    }
+
+    pub fn to_local(&self, at: DateTime<Utc>) -> DateTime<FixedOffset> {
+        at.with_timezone(&self.offset_at(at))
+    }
+
+    pub fn localize(&self, at: DateTime<Utc>) -> LocalizedTime {
+        LocalizedTime {
+            utc: at,
+            local: self.to_local(at).to_rfc3339(),
+            timezone: self.name.clone(),
+        }
+    }
+
+    /// The instant of a local time. A time skipped by a change to daylight saving time
+    /// is moved forward by the change, and a time that occurs twice is its first
+    /// occurrence.
+    pub fn from_local(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        let wall = local.and_utc().timestamp();
+        let before = self.offset_seconds(wall - 86_400);
+        let after = self.offset_seconds(wall + 86_400);
+        let timestamp = [before, after]
+            .into_iter()
+            .map(|offset| wall - i64::from(offset))
+            .filter(|at| self.offset_seconds(*at) == i32::try_from(wall - at).unwrap_or(0))
+            .min()
+            .unwrap_or(wall - i64::from(before));
+        DateTime::from_timestamp(timestamp, 0).unwrap_or_default()
+    }
+
+    /// The first instant after `after` at which the local time is `at`, for things that
+    /// run daily
+    pub fn next_daily(&self, after: DateTime<Utc>, at: NaiveTime) -> DateTime<Utc> {
+        let mut date = self.to_local(after).date_naive();
+        loop {
+            let next = self.from_local(date.and_time(at));
+            if next > after {
+                return next;
+            }
+            let Some(next_date) = date.succ_opt() else {
+                return next;
+            };
+            date = next_date;
+        }
+    }
+
+    /// When the local day `date` ends, i.e. the deadline of something due that day
+    pub fn end_of_day(&self, date: NaiveDate) -> DateTime<Utc> {
+        let next_day = date.succ_opt().unwrap_or(date);
+        self.from_local(next_day.and_time(NaiveTime::MIN))
+    }
+}
+
+/// A time of day written `HH:MM`
+pub fn parse_time_of_day(value: &str) -> Result<NaiveTime, TimezoneError> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| TimezoneError::InvalidTimeOfDay(value.to_string()))
+}
+
+fn tz_directories() -> Vec<PathBuf> {
+    let mut directories: Vec<PathBuf> = std::env::var_os("TZDIR")
+        .map(PathBuf::from)
+        .into_iter()
+        .collect();
+    directories.extend(
+        [
+            "/usr/share/zoneinfo",
+            "/usr/lib/zoneinfo",
+            "/usr/share/lib/zoneinfo",
+        ]
+        .into_iter()
+        .map(PathBuf::from),
+    );
+    directories
+}
+
+/// `UTC`, `Z`, `+05:30`, `-08`, `UTC+2` or `GMT-03:30`
+fn parse_fixed_offset(name: &str) -> Option<i32> {
+    let upper = name.to_ascii_uppercase();
+    let rest = ["UTC", "GMT", "Z"]
+        .into_iter()
+        .find_map(|prefix| upper.strip_prefix(prefix))
+        .unwrap_or(&upper);
+    if rest.is_empty() {
+        return Some(0);
+    }
+    let (sign, rest) = match rest.as_bytes()[0] {
+        b'+' => (1, &rest[1..]),
+        b'-' => (-1, &rest[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let all_digits = |part: &str| {
+        !part.is_empty() && part.len() <= 2 && part.bytes().all(|b| b.is_ascii_digit())
+    };
+    if !all_digits(hours) || !all_digits(minutes) {
+        return None;
+    }
+    let (hours, minutes): (i32, i32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours <= 14 && minutes < 60).then_some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// The transitions and footer of a TZif file (RFC 8536), preferring its 64-bit data
+fn parse_tzif(data: &[u8]) -> Option<Rules> {
+    struct Counts {
+        isut: usize,
+        isstd: usize,
+        leap: usize,
+        time: usize,
+        types: usize,
+        chars: usize,
+    }
+    fn header(data: &[u8]) -> Option<(u8, Counts)> {
+        if data.get(..4)? != b"TZif" {
+            return None;
+        }
+        let count = |i: usize| -> Option<usize> {
+            let bytes = data.get(20 + i * 4..24 + i * 4)?;
+            Some(u32::from_be_bytes(bytes.try_into().ok()?) as usize)
+        };
+        Some((
+            *data.get(4)?,
+            Counts {
+                isut: count(0)?,
+                isstd: count(1)?,
+                leap: count(2)?,
+                time: count(3)?,
+                types: count(4)?,
+                chars: count(5)?,
+            },
+        ))
+    }
+    fn block_len(counts: &Counts, time_size: usize) -> usize {
+        counts.time * time_size
+            + counts.time
+            + counts.types * 6
+            + counts.chars
+            + counts.leap * (time_size + 4)
+            + counts.isstd
+            + counts.isut
+    }
+
+    const HEADER_LEN: usize = 44;
+    let (version, v1_counts) = header(data)?;
+    let (data, counts, time_size) = if version >= b'2' {
+        let data = data.get(HEADER_LEN + block_len(&v1_counts, 4)..)?;
+        let (_, counts) = header(data)?;
+        (data, counts, 8)
+    } else {
+        (data, v1_counts, 4)
+    };
+
+    let body = data.get(HEADER_LEN..HEADER_LEN + block_len(&counts, time_size))?;
+    let (times, rest) = body.split_at(counts.time * time_size);
+    let (indices, rest) = rest.split_at(counts.time);
+    let offsets: Vec<i32> = rest[..counts.types * 6]
+        .chunks(6)
+        .map(|info| i32::from_be_bytes([info[0], info[1], info[2], info[3]]))
+        .collect();
+    let transitions = times
+        .chunks(time_size)
+        .zip(indices)
+        .map(|(time, &index)| {
+            let at = match time_size {
+                8 => i64::from_be_bytes(time.try_into().ok()?),
+                _ => i64::from(i32::from_be_bytes(time.try_into().ok()?)),
+            };
+            Some((at, *offsets.get(usize::from(index))?))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let footer = if version >= b'2' {
+        let footer = data.get(HEADER_LEN + block_len(&counts, time_size)..)?;
+        std::str::from_utf8(footer)
+            .ok()
+            .and_then(|footer| footer.trim_matches('\n').lines().next())
+            .and_then(PosixRule::parse)
+    } else {
+        None
+    };
+    Some(Rules::Zone {
+        initial: *offsets.first()?,
+        transitions,
+        footer,
+    })
+}
+
+/// Day of the year a daylight saving time change falls on
+#[derive(Debug, Clone, Copy)]
+enum RuleDate {
+    /// `Mm.w.d`: day `d` (0 is Sunday) of week `w` of month `m`, where week 5 is the last
+    MonthWeekDay { month: u32, week: u32, weekday: u32 },
+    /// `Jn`: day 1 to 365, never counting February 29
+    Julian(u32),
+    /// `n`: day 0 to 365, counting February 29
+    ZeroBased(u32),
+}
+
+impl RuleDate {
+    fn in_year(self, year: i32) -> Option<NaiveDate> {
+        match self {
+            RuleDate::MonthWeekDay {
+                month,
+                week,
+                weekday,
+            } => {
+                let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let first_weekday = first.weekday().num_days_from_sunday();
+                let mut day = 1 + (weekday + 7 - first_weekday) % 7 + (week - 1) * 7;
+                let days_in_month = first
+                    .checked_add_months(chrono::Months::new(1))?
+                    .pred_opt()?
+                    .day();
+                while day > days_in_month {
+                    day -= 7;
+                }
+                NaiveDate::from_ymd_opt(year, month, day)
+            }
+            RuleDate::Julian(day) => {
+                let date = NaiveDate::from_yo_opt(year, day)?;
+                let skips_leap_day = date.leap_year() && day >= 60;
+                Some(if skips_leap_day {
+                    date.succ_opt()?
+                } else {
+                    date
+                })
+            }
+            RuleDate::ZeroBased(day) => NaiveDate::from_yo_opt(year, day + 1),
+        }
+    }
+}
+
+/// A POSIX TZ string such as `CET-1CEST,M3.5.0,M10.5.0/3`, which describes the offsets of a
+/// zone from its last listed transition on
+#[derive(Debug, Clone)]
+struct PosixRule {
+    standard: i32,
+    /// Daylight saving offset and when it starts and ends, each in seconds after local
+    /// midnight
+    daylight: Option<(i32, (RuleDate, i64), (RuleDate, i64))>,
+}
+
+impl PosixRule {
+    fn parse(rule: &str) -> Option<Self> {
+        let mut rest = rule;
+        skip_name(&mut rest)?;
+        // POSIX offsets are west of UTC
+        let standard = -i32::try_from(parse_duration(&mut rest)?).ok()?;
+        if rest.is_empty() {
+            return Some(Self {
+                standard,
+                daylight: None,
+            });
+        }
+        skip_name(&mut rest)?;
+        let daylight = if rest.starts_with(',') || rest.is_empty() {
+            standard + 3600
+        } else {
+            -i32::try_from(parse_duration(&mut rest)?).ok()?
+        };
+        // A daylight saving name without rules leaves the rules to the implementation;
+        // keeping standard time is the safest choice
+        let Some(rules) = rest.strip_prefix(',') else {
+            return Some(Self {
+                standard,
+                daylight: None,
+            });
+        };
+        let (start, end) = rules.split_once(',')?;
+        Some(Self {
+            standard,
+            daylight: Some((daylight, parse_change(start)?, parse_change(end)?)),
+        })
+    }
+
+    fn offset_at(&self, timestamp: i64) -> i32 {
+        let Some((daylight, (start_date, start_time), (end_date, end_time))) = self.daylight else {
+            return self.standard;
+        };
+        let Some(year) = DateTime::from_timestamp(timestamp + i64::from(self.standard), 0)
+            .map(|local| local.year())
+        else {
+            return self.standard;
+        };
+        // Changes to daylight saving time are given in standard time and back in daylight
+        // saving time
+        let change = |date: RuleDate, time: i64, offset: i32| {
+            date.in_year(year).map(|date| {
+                date.and_time(NaiveTime::MIN).and_utc().timestamp() + time - i64::from(offset)
+            })
+        };
+        let (Some(start), Some(end)) = (
+            change(start_date, start_time, self.standard),
+            change(end_date, end_time, daylight),
+        ) else {
+            return self.standard;
+        };
+        let in_daylight = if start < end {
+            start <= timestamp && timestamp < end
+        } else {
+            // Southern hemisphere: daylight saving time spans the new year
+            !(end <= timestamp && timestamp < start)
+        };
+        if in_daylight { daylight } else { self.standard }
+    }
+}
+
+/// Skip a zone abbreviation, either letters or quoted in angle brackets
+fn skip_name(rest: &mut &str) -> Option<()> {
+    let len = if let Some(quoted) = rest.strip_prefix('<') {
+        quoted.find('>')? + 2
+    } else {
+        rest.find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len())
+    };
+    if len < 3 {
+        return None;
+    }
+    *rest = &rest[len..];
+    Some(())
+}
+
+/// `[+-]hh[:mm[:ss]]` in seconds
+fn parse_duration(rest: &mut &str) -> Option<i64> {
+    let end = rest
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, ':' | '+' | '-')))
+        .unwrap_or(rest.len());
+    let (value, remaining) = rest.split_at(end);
+    *rest = remaining;
+    let (sign, value) = match value.strip_prefix('-') {
+        Some(value) => (-1, value),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let mut seconds = 0;
+    for (part, unit) in value.split(':').zip([3600, 60, 1]) {
+        seconds += part.parse::<i64>().ok()? * unit;
+    }
+    Some(sign * seconds)
+}
+
+/// `date[/time]`, where the time defaults to 02:00
+fn parse_change(change: &str) -> Option<(RuleDate, i64)> {
+    let (date, time) = match change.split_once('/') {
+        Some((date, mut time)) => (date, parse_duration(&mut time)?),
+        None => (change, 2 * 3600),
+    };
+    let date = if let Some(fields) = date.strip_prefix('M') {
+        let mut fields = fields.split('.').map(|field| field.parse::<u32>().ok());
+        let (month, week, weekday) = (fields.next()??, fields.next()??, fields.next()??);
+        if !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+            return None;
+        }
+        RuleDate::MonthWeekDay {
+            month,
+            week,
+            weekday,
+        }
+    } else if let Some(day) = date.strip_prefix('J') {
+        RuleDate::Julian(day.parse().ok().filter(|day| (1..=365).contains(day))?)
+    } else {
+        RuleDate::ZeroBased(date.parse().ok().filter(|day| *day <= 365)?)
+    };
+    Some((date, time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn posix(name: &str, rule: &str) -> Timezone {
+        Timezone {
+            name: name.to_string(),
+            rules: Rules::Zone {
+                initial: 0,
+                transitions: Vec::new(),
+                footer: Some(PosixRule::parse(rule).unwrap()),
+            },
+        }
+    }
+
+    fn utc(at: &str) -> DateTime<Utc> {
+        at.parse().unwrap()
+    }
+
+    fn local(at: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(at, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn paris() -> Timezone {
+        posix("Europe/Paris", "CET-1CEST,M3.5.0,M10.5.0/3")
+    }
+
+    #[test]
+    fn fixed_offsets_are_parsed() {
+        assert_eq!(parse_fixed_offset("UTC"), Some(0));
+        assert_eq!(parse_fixed_offset("+05:30"), Some(5 * 3600 + 30 * 60));
+        assert_eq!(parse_fixed_offset("UTC-3"), Some(-3 * 3600));
+        assert_eq!(parse_fixed_offset("gmt+10"), Some(10 * 3600));
+        assert_eq!(parse_fixed_offset("+25"), None);
+        assert_eq!(parse_fixed_offset("Europe/Paris"), None);
+    }
+
+    #[test]
+    fn names_that_are_not_zone_names_are_rejected() {
+        assert!(Timezone::parse("../etc/passwd").is_err());
+        assert!(Timezone::parse("/etc/localtime").is_err());
+        assert!(Timezone::parse("").is_err());
+    }
+
+    #[test]
+    fn daylight_saving_time_follows_the_rule() {
+        let paris = paris();
+        let offset = |at: &str| paris.offset_at(utc(at)).local_minus_utc() / 3600;
+        assert_eq!(offset("2026-03-29T00:59:59Z"), 1);
+        assert_eq!(offset("2026-03-29T01:00:00Z"), 2);
+        assert_eq!(offset("2026-10-25T00:59:59Z"), 2);
+        assert_eq!(offset("2026-10-25T01:00:00Z"), 1);
+
+        let sydney = posix("Australia/Sydney", "AEST-10AEDT,M10.1.0,M4.1.0/3");
+        assert_eq!(
+            sydney
+                .offset_at(utc("2026-01-15T00:00:00Z"))
+                .local_minus_utc(),
+            11 * 3600
+        );
+        assert_eq!(
+            sydney
+                .offset_at(utc("2026-07-15T00:00:00Z"))
+                .local_minus_utc(),
+            10 * 3600
+        );
+    }
+
+    #[test]
+    fn skipped_local_times_move_forward_and_repeated_ones_take_the_first() {
+        let paris = paris();
+        assert_eq!(
+            paris.from_local(local("2026-03-29 02:30")),
+            utc("2026-03-29T01:30:00Z")
+        );
+        assert_eq!(
+            paris.from_local(local("2026-10-25 02:30")),
+            utc("2026-10-25T00:30:00Z")
+        );
+        assert_eq!(
+            paris.from_local(local("2026-07-01 09:00")),
+            utc("2026-07-01T07:00:00Z")
+        );
+    }
+
+    #[test]
+    fn daily_times_keep_their_local_time_across_changes() {
+        let paris = paris();
+        let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        let first = paris.next_daily(utc("2026-03-28T07:00:00Z"), nine);
+        assert_eq!(first, utc("2026-03-28T08:00:00Z"));
+        assert_eq!(paris.next_daily(first, nine), utc("2026-03-29T07:00:00Z"));
+    }
+
+    #[test]
+    fn a_day_ends_at_local_midnight() {
+        let tokyo = Timezone::parse("+09:00").unwrap();
+        assert_eq!(
+            tokyo.end_of_day(NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()),
+            utc("2026-03-01T15:00:00Z")
+        );
+        let localized = tokyo.localize(utc("2026-03-01T15:00:00Z"));
+        assert_eq!(localized.local, "2026-03-02T00:00:00+09:00");
+    }
+
+    #[test]
+    fn tzif_files_are_read_with_their_footer() {
+        // Version 2 file with an empty 32-bit block, one transition to +01:00 and the
+        // Paris rule for later instants
+        let header = |time: u32, types: u32| {
+            let mut header = b"TZif2".to_vec();
+            header.extend([0; 15]);
+            for count in [0, 0, 0, time, types, 4] {
+                header.extend(u32::to_be_bytes(count));
+            }
+            header
+        };
+        let mut data = header(0, 1);
+        data.extend([0, 0, 0, 0, 0, 0]);
+        data.extend(b"UTC\0");
+        data.extend(header(1, 2));
+        data.extend(i64::to_be_bytes(0));
+        data.push(1);
+        data.extend([0, 0, 0, 0, 0, 0]);
+        data.extend([0, 0, 0x0e, 0x10, 0, 0]);
+        data.extend(b"CET\0");
+        data.extend(b"\nCET-1CEST,M3.5.0,M10.5.0/3\n");
+
+        let zone = Timezone {
+            name: "Europe/Paris".to_string(),
+            rules: parse_tzif(&data).unwrap(),
+        };
+        let offset = |at: &str| zone.offset_at(utc(at)).local_minus_utc();
+        assert_eq!(offset("1969-12-31T23:00:00Z"), 0);
+        assert_eq!(offset("2026-01-15T00:00:00Z"), 3600);
+        assert_eq!(offset("2026-07-15T00:00:00Z"), 7200);
+    }
+
+    #[test]
+    fn times_of_day_are_hours_and_minutes() {
+        assert_eq!(
+            parse_time_of_day("09:30").unwrap(),
+            NaiveTime::from_hms_opt(9, 30, 0).unwrap()
+        );
+        assert!(parse_time_of_day("25:00").is_err());
+    }
+}
@@ -34,6 +34,7 @@ import * as Sentry from '@sentry/react';
 import { DisclaimerDialog } from '@/components/dialogs/global/DisclaimerDialog';
 import { OnboardingDialog } from '@/components/dialogs/global/OnboardingDialog';
 import { ReleaseNotesDialog } from '@/components/dialogs/global/ReleaseNotesDialog';
+import { ApiWarnings } from '@/components/ApiWarnings';
 import { ClickedElementsProvider } from './contexts/ClickedElementsProvider';
 
 // Design scope components
@@ -203,6 +204,7 @@ function AppContent() {
               <Route path=":workspaceId" element={<Workspaces />} />
             </Route>
           </SentryRoutes>
+          <ApiWarnings />
         </SearchProvider>
       </ThemeProvider>
     </I18nextProvider>
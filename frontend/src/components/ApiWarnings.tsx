@@ -0,0 +1,52 @@
+import { useEffect, useState } from 'react';
+import { AlertTriangle, X } from 'lucide-react';
+import type { ApiWarning } from 'shared/types';
+import { Alert, AlertDescription } from '@/components/ui/alert';
+import { subscribeToApiWarnings } from '@/lib/api';
+
+type ShownWarning = ApiWarning & { id: number };
+
+const MAX_SHOWN_WARNINGS = 3;
+
+/** Shows non-fatal warnings returned by the API until they are dismissed */
+export function ApiWarnings() {
+  const [warnings, setWarnings] = useState<ShownWarning[]>([]);
+
+  useEffect(() => {
+    let nextId = 0;
+    return subscribeToApiWarnings((incoming) => {
+      setWarnings((current) =>
+        [
+          ...current,
+          ...incoming.map((warning) => ({ ...warning, id: nextId++ })),
+        ].slice(-MAX_SHOWN_WARNINGS)
+      );
+    });
+  }, []);
+
+  if (warnings.length === 0) {
+    return null;
+  }
+
+  const dismiss = (id: number) =>
+    setWarnings((current) => current.filter((warning) => warning.id !== id));
+
+  return (
+    <div className="fixed bottom-4 right-4 z-50 flex w-96 flex-col gap-2">
+      {warnings.map((warning) => (
+        <Alert key={warning.id} className="pr-10 shadow-md">
+          <AlertTriangle className="h-4 w-4" />
+          <AlertDescription>{warning.message}</AlertDescription>
+          <button
+            type="button"
+            aria-label="Dismiss"
+            className="absolute right-3 top-3 text-muted-foreground hover:text-foreground"
+            onClick={() => dismiss(warning.id)}
+          >
+            <X className="h-4 w-4" />
+          </button>
+        </Alert>
+      ))}
+    </div>
+  );
+}
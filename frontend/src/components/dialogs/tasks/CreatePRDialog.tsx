@@ -61,6 +61,7 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
       null
     );
     const [isDraft, setIsDraft] = useState(false);
+    const [includeScreenshots, setIncludeScreenshots] = useState(false);
     const [autoGenerateDescription, setAutoGenerateDescription] = useState(
       config?.pr_auto_description_enabled ?? false
     );
@@ -145,6 +146,7 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
         target_branch: prBaseBranch || null,
         draft: isDraft,
         auto_generate_description: autoGenerateDescription,
+        include_screenshots: includeScreenshots,
         repo_id: repoId,
       });
 
@@ -153,6 +155,7 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
         setPrBody('');
         setPrBaseBranch('');
         setIsDraft(false);
+        setIncludeScreenshots(false);
         setAutoGenerateDescription(
           config?.pr_auto_description_enabled ?? false
         );
@@ -218,6 +221,14 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
           );
           setGhCliHelp(null);
           return;
+        } else if (result.error.type === 'tests_failed') {
+          setError(
+            `${t('createPrDialog.errors.testsFailed', {
+              command: result.error.command,
+            })}\n${result.error.output}`
+          );
+          setGhCliHelp(null);
+          return;
         }
       }
 
@@ -354,6 +365,20 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
                     {t('createPrDialog.draftLabel')}
                   </Label>
                 </div>
+                <div className="flex items-center space-x-2">
+                  <Checkbox
+                    id="pr-include-screenshots"
+                    checked={includeScreenshots}
+                    onCheckedChange={setIncludeScreenshots}
+                    className="h-5 w-5"
+                  />
+                  <Label
+                    htmlFor="pr-include-screenshots"
+                    className="cursor-pointer text-sm"
+                  >
+                    {t('createPrDialog.includeScreenshotsLabel')}
+                  </Label>
+                </div>
                 {ghCliHelp?.variant && (
                   <Alert variant="default">
                     <AlertTitle>
@@ -368,7 +393,14 @@ const CreatePRDialogImpl = NiceModal.create<CreatePRDialogProps>(
                     </AlertDescription>
                   </Alert>
                 )}
-                {error && <Alert variant="destructive">{error}</Alert>}
+                {error && (
+                  <Alert
+                    variant="destructive"
+                    className="max-h-64 overflow-auto whitespace-pre-wrap"
+                  >
+                    {error}
+                  </Alert>
+                )}
               </div>
             )}
             <DialogFooter>
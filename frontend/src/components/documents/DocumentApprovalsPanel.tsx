@@ -0,0 +1,282 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Check, Clock, Loader2, ShieldCheck, X } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { cn } from '@/lib/utils';
+import { documentApprovalsApi } from '@/lib/api';
+import type {
+  DocumentApprovalRule,
+  DocumentChangeRequestDetails,
+  DocumentChangeStatus,
+  DocumentReviewDecision,
+} from 'shared/types';
+
+interface DocumentApprovalsPanelProps {
+  projectId: string;
+  onClose: () => void;
+  /** Called after a change was approved and committed */
+  onChangeApplied?: () => void;
+}
+
+const STATUS_STYLES: Record<DocumentChangeStatus, string> = {
+  pending: 'bg-yellow-500/10 text-yellow-600 dark:text-yellow-400',
+  approved: 'bg-green-500/10 text-green-600 dark:text-green-400',
+  rejected: 'bg-destructive/10 text-destructive',
+};
+
+function ChangeRequestCard({
+  request,
+  onReview,
+}: {
+  request: DocumentChangeRequestDetails;
+  onReview: (
+    reviewer: string,
+    decision: DocumentReviewDecision,
+    comment: string
+  ) => Promise<void>;
+}) {
+  const [reviewer, setReviewer] = useState(request.awaiting[0] ?? '');
+  const [comment, setComment] = useState('');
+  const [submitting, setSubmitting] = useState(false);
+
+  const submit = async (decision: DocumentReviewDecision) => {
+    setSubmitting(true);
+    try {
+      await onReview(reviewer, decision, comment);
+      setComment('');
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  return (
+    <div className="border rounded-md p-3 space-y-2 text-sm">
+      <div className="flex items-center gap-2">
+        <span className="font-medium truncate flex-1">
+          {request.relative_path}
+        </span>
+        <span
+          className={cn(
+            'text-[10px] px-1.5 py-0.5 rounded',
+            STATUS_STYLES[request.status]
+          )}
+        >
+          {request.status}
+        </span>
+      </div>
+
+      <ul className="space-y-1 text-xs">
+        {request.required_reviewers.map((name) => {
+          const review = request.reviews.find((r) => r.reviewer === name);
+          return (
+            <li key={name} className="flex items-start gap-1.5">
+              {review?.decision === 'approved' ? (
+                <Check className="w-3 h-3 mt-0.5 text-green-500" />
+              ) : review?.decision === 'rejected' ? (
+                <X className="w-3 h-3 mt-0.5 text-destructive" />
+              ) : (
+                <Clock className="w-3 h-3 mt-0.5 text-muted-foreground" />
+              )}
+              <span>
+                {name}
+                {review?.comment && (
+                  <span className="text-muted-foreground">
+                    {' '}
+                    &mdash; {review.comment}
+                  </span>
+                )}
+              </span>
+            </li>
+          );
+        })}
+      </ul>
+
+      {request.status === 'pending' && request.awaiting.length > 0 && (
+        <div className="space-y-2 pt-1">
+          <select
+            value={reviewer}
+            onChange={(e) => setReviewer(e.target.value)}
+            className="w-full px-2 py-1 text-xs bg-background border rounded-md"
+          >
+            {request.required_reviewers.map((name) => (
+              <option key={name} value={name}>
+                Review as {name}
+              </option>
+            ))}
+          </select>
+          <textarea
+            value={comment}
+            onChange={(e) => setComment(e.target.value)}
+            placeholder="Comment (optional)"
+            rows={2}
+            className="w-full px-2 py-1 text-xs bg-background border rounded-md resize-none"
+          />
+          <div className="flex gap-2">
+            <Button
+              size="sm"
+              className="flex-1"
+              disabled={submitting || !reviewer}
+              onClick={() => submit('approved')}
+            >
+              Approve
+            </Button>
+            <Button
+              size="sm"
+              variant="outline"
+              className="flex-1"
+              disabled={submitting || !reviewer}
+              onClick={() => submit('rejected')}
+            >
+              Reject
+            </Button>
+          </div>
+        </div>
+      )}
+
+      {request.commit_sha && (
+        <div className="text-xs text-muted-foreground font-mono">
+          {request.commit_sha.slice(0, 7)}
+        </div>
+      )}
+    </div>
+  );
+}
+
+/**
+ * Protected folders of a project and the edits waiting for (or resolved by) review
+ */
+export function DocumentApprovalsPanel({
+  projectId,
+  onClose,
+  onChangeApplied,
+}: DocumentApprovalsPanelProps) {
+  const [rules, setRules] = useState<DocumentApprovalRule[]>([]);
+  const [requests, setRequests] = useState<DocumentChangeRequestDetails[]>(
+    []
+  );
+  const [loading, setLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      const [loadedRules, loadedRequests] = await Promise.all([
+        documentApprovalsApi.getRules(projectId),
+        documentApprovalsApi.listRequests(projectId),
+      ]);
+      setRules(loadedRules);
+      setRequests(loadedRequests);
+      setError(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to load approvals');
+    } finally {
+      setLoading(false);
+    }
+  }, [projectId]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  const handleReview = async (
+    requestId: string,
+    reviewer: string,
+    decision: DocumentReviewDecision,
+    comment: string
+  ) => {
+    try {
+      const updated = await documentApprovalsApi.review(projectId, requestId, {
+        reviewer,
+        decision,
+        comment: comment.trim() || null,
+      });
+      setRequests((current) =>
+        current.map((r) => (r.id === updated.id ? updated : r))
+      );
+      setError(null);
+      if (updated.status === 'approved') {
+        onChangeApplied?.();
+      }
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to submit review');
+    }
+  };
+
+  const pending = requests.filter((r) => r.status === 'pending');
+  const resolved = requests.filter((r) => r.status !== 'pending');
+
+  return (
+    <div className="w-80 border-l flex flex-col bg-muted/30 min-h-0">
+      <div className="flex items-center gap-2 px-3 py-2 border-b">
+        <ShieldCheck className="w-4 h-4 text-muted-foreground" />
+        <span className="text-sm font-medium flex-1">Approvals</span>
+        <Button size="sm" variant="ghost" onClick={onClose}>
+          <X className="w-4 h-4" />
+        </Button>
+      </div>
+
+      <div className="flex-1 overflow-auto p-3 space-y-4">
+        {loading ? (
+          <div className="flex justify-center py-8">
+            <Loader2 className="w-5 h-5 animate-spin text-muted-foreground" />
+          </div>
+        ) : (
+          <>
+            {error && <div className="text-xs text-destructive">{error}</div>}
+
+            <section className="space-y-1">
+              <h3 className="text-xs font-medium text-muted-foreground">
+                Protected folders
+              </h3>
+              {rules.length === 0 ? (
+                <p className="text-xs text-muted-foreground">
+                  No folders require approval
+                </p>
+              ) : (
+                rules.map((rule) => (
+                  <div key={rule.id} className="text-xs">
+                    <span className="font-mono">{rule.folder}/</span>
+                    <span className="text-muted-foreground">
+                      {' '}
+                      &mdash; {rule.reviewers.join(', ')}
+                    </span>
+                  </div>
+                ))
+              )}
+            </section>
+
+            <section className="space-y-2">
+              <h3 className="text-xs font-medium text-muted-foreground">
+                Pending ({pending.length})
+              </h3>
+              {pending.map((request) => (
+                <ChangeRequestCard
+                  key={request.id}
+                  request={request}
+                  onReview={(reviewer, decision, comment) =>
+                    handleReview(request.id, reviewer, decision, comment)
+                  }
+                />
+              ))}
+            </section>
+
+            {resolved.length > 0 && (
+              <section className="space-y-2">
+                <h3 className="text-xs font-medium text-muted-foreground">
+                  Resolved
+                </h3>
+                {resolved.map((request) => (
+                  <ChangeRequestCard
+                    key={request.id}
+                    request={request}
+                    onReview={(reviewer, decision, comment) =>
+                      handleReview(request.id, reviewer, decision, comment)
+                    }
+                  />
+                ))}
+              </section>
+            )}
+          </>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,40 @@
+import { Link2 } from 'lucide-react';
+import { cn } from '@/lib/utils';
+import type { DocumentLinkEdge } from 'shared/types';
+
+interface DocumentBacklinksProps {
+  backlinks: DocumentLinkEdge[];
+  onSelectDocument: (relativePath: string) => void;
+  className?: string;
+}
+
+export function DocumentBacklinks({
+  backlinks,
+  onSelectDocument,
+  className,
+}: DocumentBacklinksProps) {
+  // A document can link here both ways; list it once
+  const sources = [...new Set(backlinks.map((link) => link.source))];
+
+  return (
+    <nav className={cn('text-sm', className)}>
+      <div className="flex items-center gap-1 px-3 py-2 text-xs font-medium text-muted-foreground">
+        <Link2 className="w-3 h-3" />
+        Backlinks
+      </div>
+      <ul className="pb-2">
+        {sources.map((source) => (
+          <li key={source}>
+            <button
+              onClick={() => onSelectDocument(source)}
+              className="block w-full truncate py-1 px-3 text-left text-muted-foreground hover:bg-muted/50 hover:text-foreground"
+              title={source}
+            >
+              {source.split('/').pop()}
+            </button>
+          </li>
+        ))}
+      </ul>
+    </nav>
+  );
+}
@@ -0,0 +1,52 @@
+import { ListTree } from 'lucide-react';
+import { cn } from '@/lib/utils';
+import type { DocumentHeading } from 'shared/types';
+
+interface DocumentOutlineProps {
+  headings: DocumentHeading[];
+  activeAnchor?: string | null;
+  onSelectHeading: (heading: DocumentHeading, index: number) => void;
+  className?: string;
+}
+
+export function DocumentOutline({
+  headings,
+  activeAnchor,
+  onSelectHeading,
+  className,
+}: DocumentOutlineProps) {
+  // Indent relative to the document's top heading level
+  const topLevel = Math.min(...headings.map((heading) => heading.level));
+
+  return (
+    <nav className={cn('text-sm', className)}>
+      <div className="flex items-center gap-1 px-3 py-2 text-xs font-medium text-muted-foreground">
+        <ListTree className="w-3 h-3" />
+        Outline
+      </div>
+      <ul className="pb-2">
+        {headings.map((heading, index) => (
+          <li key={heading.anchor}>
+            <a
+              href={`#${heading.anchor}`}
+              onClick={(event) => {
+                event.preventDefault();
+                onSelectHeading(heading, index);
+              }}
+              className={cn(
+                'block truncate py-1 pr-3 hover:bg-muted/50 hover:text-foreground',
+                heading.anchor === activeAnchor
+                  ? 'text-foreground font-medium'
+                  : 'text-muted-foreground'
+              )}
+              style={{ paddingLeft: `${12 + (heading.level - topLevel) * 12}px` }}
+              title={heading.text}
+            >
+              {heading.text}
+            </a>
+          </li>
+        ))}
+      </ul>
+    </nav>
+  );
+}
@@ -9,6 +9,7 @@ import {
   FolderPlus,
   FilePlus,
   Plus,
+  Clock,
 } from 'lucide-react';
 import { cn } from '@/lib/utils';
 import { Button } from '@/components/ui/button';
@@ -27,6 +28,8 @@ interface FolderTreeProps {
   formatFileSize: (bytes: number | bigint) => string;
   onCreateFolder?: (parentPath: string) => void;
   onCreateFile?: (parentPath: string) => void;
+  /** Documents whose referenced code changed after them */
+  stalePaths?: Set<string>;
 }
 
 interface TreeNode {
@@ -122,6 +125,7 @@ interface TreeNodeComponentProps {
   formatFileSize: (bytes: number | bigint) => string;
   onCreateFolder?: (parentPath: string) => void;
   onCreateFile?: (parentPath: string) => void;
+  stalePaths?: Set<string>;
 }
 
 function TreeNodeComponent({
@@ -134,6 +138,7 @@ function TreeNodeComponent({
   formatFileSize,
   onCreateFolder,
   onCreateFile,
+  stalePaths,
 }: TreeNodeComponentProps) {
   const [isHovered, setIsHovered] = useState(false);
   const isExpanded = expandedFolders.has(node.path);
@@ -239,6 +244,7 @@ function TreeNodeComponent({
                 formatFileSize={formatFileSize}
                 onCreateFolder={onCreateFolder}
                 onCreateFile={onCreateFile}
+                stalePaths={stalePaths}
               />
             ))}
           </div>
@@ -266,6 +272,11 @@ function TreeNodeComponent({
         <FileText className="w-4 h-4 text-blue-500 shrink-0" />
       )}
       <span className="truncate flex-1 text-left">{node.name}</span>
+      {stalePaths?.has(node.path) && (
+        <span title="Possibly stale: code it references changed since it was last updated">
+          <Clock className="w-3.5 h-3.5 text-amber-500 shrink-0" />
+        </span>
+      )}
       {node.document && (
         <span className="text-xs text-muted-foreground shrink-0">
           {formatFileSize(node.document.size_bytes)}
@@ -282,6 +293,7 @@ export function FolderTree({
   formatFileSize,
   onCreateFolder,
   onCreateFile,
+  stalePaths,
 }: FolderTreeProps) {
   // Start with all folders expanded
   const [expandedFolders, setExpandedFolders] = useState<Set<string>>(() => {
@@ -409,6 +421,7 @@ export function FolderTree({
             formatFileSize={formatFileSize}
             onCreateFolder={onCreateFolder}
             onCreateFile={onCreateFile}
+            stalePaths={stalePaths}
           />
         ))}
       </div>
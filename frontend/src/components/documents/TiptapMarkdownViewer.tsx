@@ -5,14 +5,20 @@ import Typography from '@tiptap/extension-typography';
 import CodeBlockLowlight from '@tiptap/extension-code-block-lowlight';
 import Placeholder from '@tiptap/extension-placeholder';
 import { common, createLowlight } from 'lowlight';
-import { marked } from 'marked';
 import { useEffect, useMemo, useRef, useState, useCallback } from 'react';
+import { useNavigate } from 'react-router-dom';
 import { cn } from '@/lib/utils';
-import { documentsApi } from '@/lib/api';
-import { Loader2, Check, AlertCircle } from 'lucide-react';
+import { boardLinkPath } from '@/lib/boardLinks';
+import { ApiError, documentLocksApi, documentsApi } from '@/lib/api';
+import { evictRenderedMarkdown, renderMarkdown } from '@/lib/markdownCache';
+import { Loader2, Check, AlertCircle, Clock, Lock } from 'lucide-react';
+import type { DocumentLock } from 'shared/types';
 
 type TimeoutId = ReturnType<typeof setTimeout>;
 
+// Locks last 5 minutes on the server; renew well before that while editing
+const LOCK_RENEW_INTERVAL_MS = 2 * 60 * 1000;
+
 const lowlight = createLowlight(common);
 
 // Define extensions outside component to avoid recreation
@@ -22,6 +28,8 @@ const extensions = [
   }),
   Link.configure({
     openOnClick: true,
+    // Keep `vk://` links to board tasks and documents when editing
+    protocols: ['vk'],
     HTMLAttributes: {
       target: '_blank',
       rel: 'noopener noreferrer',
@@ -37,16 +45,29 @@ const extensions = [
   }),
 ];
 
-type SaveStatus = 'idle' | 'saving' | 'saved' | 'error';
+type SaveStatus =
+  | 'idle'
+  | 'saving'
+  | 'saved'
+  | 'pending_approval'
+  | 'conflict'
+  | 'locked'
+  | 'error';
 
 interface TiptapMarkdownViewerProps {
   content: string;
   className?: string;
   projectId: string;
   relativePath: string;
+  /** `content_hash` of `content`, so saves don't overwrite changes made elsewhere */
+  contentHash: string;
   onContentChange?: (content: string) => void;
   /** If true, the editor is read-only and cannot be edited */
   readOnly?: boolean;
+  /** Lock on the document when it was loaded, if any */
+  lock?: DocumentLock | null;
+  /** Name to lock the document with while editing */
+  lockHolder?: string;
 }
 
 // Convert HTML back to Markdown (simplified)
@@ -157,25 +178,83 @@ export function TiptapMarkdownViewer({
   className,
   projectId,
   relativePath,
+  contentHash,
   onContentChange,
   readOnly = false,
+  lock = null,
+  lockHolder,
 }: TiptapMarkdownViewerProps) {
+  const navigate = useNavigate();
   const [saveStatus, setSaveStatus] = useState<SaveStatus>('idle');
+  // Someone else editing the document; the editor stays read-only meanwhile
+  const [lockedBy, setLockedBy] = useState<string | null>(null);
+  const holdsLockRef = useRef(false);
   const saveTimeoutRef = useRef<TimeoutId | null>(null);
   const lastSavedContentRef = useRef<string>(content);
+  // Hash of the version on disk that the next save is based on
+  const baseHashRef = useRef<string>(contentHash);
 
-  // Convert markdown to HTML
-  const htmlContent = useMemo(() => {
+  useEffect(() => {
+    baseHashRef.current = contentHash;
+  }, [contentHash]);
+
+  useEffect(() => {
+    setLockedBy(lock && lock.holder !== lockHolder ? lock.holder : null);
+  }, [lock, lockHolder]);
+
+  // Lock the document on the first edit, so others can't save over the session
+  const ensureLock = useCallback(async (): Promise<boolean> => {
+    if (!lockHolder || holdsLockRef.current) {
+      return true;
+    }
     try {
-      return marked.parse(content, {
-        gfm: true,
-        breaks: true,
-      }) as string;
+      await documentLocksApi.acquire(projectId, {
+        relative_path: relativePath,
+        holder: lockHolder,
+      });
+      holdsLockRef.current = true;
+      return true;
     } catch (error) {
-      console.error('Failed to parse markdown:', error);
-      return `<p>${content}</p>`;
+      if (error instanceof ApiError && error.error_data) {
+        setLockedBy((error.error_data as DocumentLock).holder);
+        setSaveStatus('locked');
+        return false;
+      }
+      // Saving still checks the lock, so a failed request needn't block editing
+      console.error('Failed to lock document:', error);
+      return true;
     }
-  }, [content]);
+  }, [projectId, relativePath, lockHolder]);
+
+  // Keep the lock alive while editing and release it when the document closes
+  useEffect(() => {
+    if (!lockHolder) {
+      return;
+    }
+    const request = { relative_path: relativePath, holder: lockHolder };
+    const interval = setInterval(() => {
+      if (holdsLockRef.current) {
+        documentLocksApi.renew(projectId, request).catch(() => {
+          holdsLockRef.current = false;
+        });
+      }
+    }, LOCK_RENEW_INTERVAL_MS);
+    return () => {
+      clearInterval(interval);
+      if (holdsLockRef.current) {
+        holdsLockRef.current = false;
+        documentLocksApi.release(projectId, request).catch((error) => {
+          console.error('Failed to release document lock:', error);
+        });
+      }
+    };
+  }, [projectId, relativePath, lockHolder]);
+
+  // Convert markdown to HTML
+  const htmlContent = useMemo(
+    () => renderMarkdown(content, contentHash).html,
+    [content, contentHash]
+  );
 
   // Save function
   const saveContent = useCallback(
@@ -184,10 +263,30 @@ export function TiptapMarkdownViewer({
         return;
       }
 
+      if (!(await ensureLock())) {
+        return;
+      }
+
       setSaveStatus('saving');
       try {
-        await documentsApi.update(projectId, relativePath, newContent);
+        const result = await documentsApi.update(
+          projectId,
+          relativePath,
+          newContent,
+          baseHashRef.current,
+          undefined,
+          lockHolder
+        );
         lastSavedContentRef.current = newContent;
+        if (result.content_hash) {
+          evictRenderedMarkdown(baseHashRef.current);
+          baseHashRef.current = result.content_hash;
+        }
+        // Protected documents stay unchanged until the edit is approved
+        if (result.change_request_id) {
+          setSaveStatus('pending_approval');
+          return;
+        }
         setSaveStatus('saved');
 
         // Reset to idle after 2 seconds
@@ -195,6 +294,19 @@ export function TiptapMarkdownViewer({
           setSaveStatus('idle');
         }, 2000);
       } catch (error) {
+        // Someone else saved first; keep the edit on screen instead of overwriting
+        if (error instanceof ApiError && error.status === 409) {
+          setSaveStatus('conflict');
+          return;
+        }
+        if (error instanceof ApiError && error.status === 423) {
+          holdsLockRef.current = false;
+          setLockedBy(
+            (error.error_data as DocumentLock | undefined)?.holder ?? null
+          );
+          setSaveStatus('locked');
+          return;
+        }
         console.error('Failed to save document:', error);
         setSaveStatus('error');
 
@@ -204,7 +316,7 @@ export function TiptapMarkdownViewer({
         }, 3000);
       }
     },
-    [projectId, relativePath]
+    [projectId, relativePath, lockHolder, ensureLock]
   );
 
   // Debounced save
@@ -227,7 +339,7 @@ export function TiptapMarkdownViewer({
   const editor = useEditor({
     extensions,
     content: htmlContent,
-    editable: !readOnly,
+    editable: !readOnly && !lockedBy,
     editorProps: {
       attributes: {
         class: cn(
@@ -235,6 +347,16 @@ export function TiptapMarkdownViewer({
           readOnly && 'cursor-default'
         ),
       },
+      // Open `vk://` links in the app rather than a new tab
+      handleClick: (_view, _pos, event) => {
+        const anchor = (event.target as HTMLElement).closest('a');
+        const href = anchor?.getAttribute('href') ?? '';
+        const path = boardLinkPath(projectId, href);
+        if (!path) return false;
+        event.preventDefault();
+        navigate(path);
+        return true;
+      },
     },
     onUpdate: ({ editor }) => {
       if (!readOnly) {
@@ -254,12 +376,12 @@ export function TiptapMarkdownViewer({
     }
   }, [editor, htmlContent, content]);
 
-  // Update editable state when readOnly changes
+  // Update editable state when readOnly or the lock changes
   useEffect(() => {
     if (editor) {
-      editor.setEditable(!readOnly);
+      editor.setEditable(!readOnly && !lockedBy);
     }
-  }, [editor, readOnly]);
+  }, [editor, readOnly, lockedBy]);
 
   // Cleanup timeout on unmount
   useEffect(() => {
@@ -286,6 +408,24 @@ export function TiptapMarkdownViewer({
             Saved
           </span>
         )}
+        {saveStatus === 'pending_approval' && (
+          <span className="flex items-center gap-1 text-yellow-600 dark:text-yellow-400 bg-background/80 px-2 py-1 rounded">
+            <Clock className="w-3 h-3" />
+            Awaiting approval
+          </span>
+        )}
+        {saveStatus === 'conflict' && (
+          <span className="flex items-center gap-1 text-destructive bg-background/80 px-2 py-1 rounded">
+            <AlertCircle className="w-3 h-3" />
+            Changed elsewhere, reload to edit
+          </span>
+        )}
+        {(lockedBy || saveStatus === 'locked') && (
+          <span className="flex items-center gap-1 text-yellow-600 dark:text-yellow-400 bg-background/80 px-2 py-1 rounded">
+            <Lock className="w-3 h-3" />
+            Locked by {lockedBy ?? 'another editor'}
+          </span>
+        )}
         {saveStatus === 'error' && (
           <span className="flex items-center gap-1 text-destructive bg-background/80 px-2 py-1 rounded">
             <AlertCircle className="w-3 h-3" />
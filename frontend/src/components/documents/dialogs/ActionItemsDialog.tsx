@@ -0,0 +1,175 @@
+import { useState, useEffect } from 'react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { Checkbox } from '@/components/ui/checkbox';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { ClipboardList, Plus } from 'lucide-react';
+import NiceModal, { useModal } from '@ebay/nice-modal-react';
+import { defineModal } from '@/lib/modals';
+import type { ActionItem } from 'shared/types';
+
+export interface ActionItemsDialogProps {
+  relativePath: string;
+  items: ActionItem[];
+}
+
+export type ActionItemsResult = {
+  action: 'confirmed' | 'canceled';
+  items?: ActionItem[];
+};
+
+type EditableItem = ActionItem & { included: boolean };
+
+const ActionItemsDialogImpl = NiceModal.create<ActionItemsDialogProps>(
+  ({ relativePath, items }) => {
+    const modal = useModal();
+    const [drafts, setDrafts] = useState<EditableItem[]>([]);
+
+    useEffect(() => {
+      if (modal.visible) {
+        setDrafts(items.map((item) => ({ ...item, included: true })));
+      }
+    }, [modal.visible, items]);
+
+    const updateDraft = (index: number, changes: Partial<EditableItem>) => {
+      setDrafts((current) =>
+        current.map((draft, i) =>
+          i === index ? { ...draft, ...changes } : draft
+        )
+      );
+    };
+
+    const addDraft = () => {
+      setDrafts((current) => [
+        ...current,
+        { title: '', owner: null, due_date: null, line: null, included: true },
+      ]);
+    };
+
+    const confirmed = drafts.filter(
+      (draft) => draft.included && draft.title.trim()
+    );
+
+    const handleConfirm = () => {
+      modal.resolve({
+        action: 'confirmed',
+        items: confirmed.map((draft) => ({
+          title: draft.title.trim(),
+          owner: draft.owner?.trim().replace(/^@/, '') || null,
+          due_date: draft.due_date || null,
+          line: draft.line,
+        })),
+      } as ActionItemsResult);
+      modal.hide();
+    };
+
+    const handleCancel = () => {
+      modal.resolve({ action: 'canceled' } as ActionItemsResult);
+      modal.hide();
+    };
+
+    const handleOpenChange = (open: boolean) => {
+      if (!open) {
+        handleCancel();
+      }
+    };
+
+    return (
+      <Dialog open={modal.visible} onOpenChange={handleOpenChange}>
+        <DialogContent className="sm:max-w-2xl">
+          <DialogHeader>
+            <div className="flex items-center gap-2">
+              <ClipboardList className="h-5 w-5 text-blue-500" />
+              <DialogTitle>Confirm Action Items</DialogTitle>
+            </div>
+            <DialogDescription>
+              Each included item becomes a task and is listed under "Action
+              items" in{' '}
+              <span className="font-mono text-xs bg-muted px-1 py-0.5 rounded">
+                {relativePath}
+              </span>
+            </DialogDescription>
+          </DialogHeader>
+
+          <div className="space-y-2 max-h-[50vh] overflow-y-auto">
+            {drafts.length === 0 && (
+              <p className="text-sm text-muted-foreground">
+                No action items were found in these notes. Add them by hand
+                below.
+              </p>
+            )}
+            {drafts.map((draft, index) => (
+              <div key={index} className="flex items-center gap-2">
+                <Checkbox
+                  checked={draft.included}
+                  onCheckedChange={(included) =>
+                    updateDraft(index, { included })
+                  }
+                />
+                <Input
+                  value={draft.title}
+                  onChange={(e) =>
+                    updateDraft(index, { title: e.target.value })
+                  }
+                  placeholder="Action item"
+                  className="flex-1"
+                  disabled={!draft.included}
+                />
+                <Input
+                  value={draft.owner ?? ''}
+                  onChange={(e) =>
+                    updateDraft(index, { owner: e.target.value || null })
+                  }
+                  placeholder="Owner"
+                  className="w-32"
+                  disabled={!draft.included}
+                />
+                <Input
+                  type="date"
+                  value={draft.due_date ?? ''}
+                  onChange={(e) =>
+                    updateDraft(index, { due_date: e.target.value || null })
+                  }
+                  className="w-40"
+                  disabled={!draft.included}
+                />
+              </div>
+            ))}
+          </div>
+
+          <Button
+            variant="ghost"
+            size="sm"
+            className="self-start"
+            onClick={addDraft}
+          >
+            <Plus className="h-4 w-4 mr-1" />
+            Add item
+          </Button>
+
+          <DialogFooter>
+            <Button variant="outline" onClick={handleCancel}>
+              Cancel
+            </Button>
+            <Button onClick={handleConfirm} disabled={confirmed.length === 0}>
+              Create {confirmed.length}{' '}
+              {confirmed.length === 1 ? 'Task' : 'Tasks'}
+            </Button>
+          </DialogFooter>
+        </DialogContent>
+      </Dialog>
+    );
+  }
+);
+
+export const ActionItemsDialog = defineModal<
+  ActionItemsDialogProps,
+  ActionItemsResult
+>(ActionItemsDialogImpl);
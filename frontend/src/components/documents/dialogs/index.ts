@@ -1,2 +1,3 @@
 export { CreateFolderDialog, type CreateFolderDialogProps, type CreateFolderResult } from './CreateFolderDialog';
 export { CreateFileDialog, type CreateFileDialogProps, type CreateFileResult } from './CreateFileDialog';
+export { ActionItemsDialog, type ActionItemsDialogProps, type ActionItemsResult } from './ActionItemsDialog';
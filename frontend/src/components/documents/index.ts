@@ -3,4 +3,6 @@ export { TiptapMarkdownViewer } from './TiptapMarkdownViewer';
 export { JsonDiagram } from './JsonDiagram';
 export { JsonTreeView } from './JsonTreeView';
 export { FolderTree } from './FolderTree';
+export { DocumentOutline } from './DocumentOutline';
+export { DocumentApprovalsPanel } from './DocumentApprovalsPanel';
 export * from './dialogs';
@@ -1,6 +1,7 @@
 import { useCallback, useMemo } from 'react';
 import { useQuery, keepPreviousData } from '@tanstack/react-query';
 import { useJsonPatchWsStream } from '@/hooks/useJsonPatchWsStream';
+import { csrfHeaders } from '@/lib/api';
 import type {
   WorkspaceWithStatus,
   WorkspaceSummary,
@@ -92,7 +93,7 @@ async function fetchWorkspaceSummariesByArchived(
   try {
     const response = await fetch('/api/task-attempts/summary', {
       method: 'POST',
-      headers: { 'Content-Type': 'application/json' },
+      headers: { 'Content-Type': 'application/json', ...csrfHeaders() },
       body: JSON.stringify({ archived }),
     });
 
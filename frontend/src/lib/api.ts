@@ -3,28 +3,148 @@
 import {
   ApprovalStatus,
   ApiResponse,
+  ApiWarning,
   Config,
   CreateFollowUpAttempt,
   EditorType,
   CreatePrApiRequest,
   CreateTask,
   CreateAndStartTaskRequest,
+  TaskDocument,
+  AttachTaskDocument,
   CreateTaskAttemptBody,
   CreateTag,
   DirectoryListResponse,
   DirectoryEntry,
-  DocumentMetadata,
+  DocumentTreeResponse,
+  ListDocumentsResponse,
   DocumentContent,
   UpdateDocumentResponse,
+  DocumentCommitPreview,
+  BatchUpdateDocumentsRequest,
+  BatchUpdateDocumentsResponse,
+  BatchUpdateConflict,
+  DeleteDocumentResponse,
+  DeleteFolderResponse,
+  DocumentDiffResponse,
+  DocumentHtml,
+  DocumentExtensions,
+  UpdateDocumentExtensions,
+  DocumentReadOnlyPaths,
+  DocumentRoots,
+  DocumentCodeReferences,
+  DocumentFreshness,
+  DocumentFreshnessOverview,
+  DocumentBacklinks,
+  RelatedTask,
+  JsonDocumentQueryResponse,
+  DocumentLinkGraph,
+  BoardLinkReport,
+  DocumentReadOnlyViolation,
+  DocumentScanLimits,
+  DocumentUpdateConflict,
+  UpdateDocumentScanLimits,
+  DocumentReadLimit,
+  UpdateDocumentReadLimit,
+  DocumentAssetResponse,
+  DocumentAssetsFolder,
+  UpdateDocumentAssetsFolder,
+  DocumentTemplate,
+  DocumentTemplatesFolder,
+  UpdateDocumentTemplatesFolder,
+  DocumentEvent,
+  DocumentHistoryResponse,
+  MoveDocumentResponse,
+  MoveFolderResponse,
+  RefreshDocumentsRequest,
+  RefreshDocumentsResponse,
+  DocumentSnapshot,
+  DocumentSnapshotDiff,
+  DocumentApprovalRule,
+  SetDocumentApprovalRule,
+  DocumentChangeStatus,
+  DocumentChangeRequestDetails,
+  ReviewDocumentChangeRequest,
+  DocumentEncryptedFolder,
+  SetDocumentEncryptedFolder,
+  DocumentEncryptionKey,
+  UpdateEncryptedFoldersResponse,
+  RotateEncryptionKeyResponse,
+  DocumentLock,
+  DocumentLockRequest,
+  CreateDocumentTaskRequest,
+  CreateDocumentTaskResponse,
+  UpdateFrontMatterRequest,
+  SyncDocumentTasksRequest,
+  SyncStatusResponse,
+  ProjectDocumentSyncStatus,
+  SyncResponse,
+  DocumentSyncStrategy,
+  UpdateDocumentSyncStrategy,
+  DocumentSymlinkPolicy,
+  UpdateDocumentSymlinkPolicy,
+  SyncDocumentTasksResponse,
+  ActionItemsPreview,
+  ApplyActionItemsRequest,
+  FormatDocumentRequest,
+  FormatDocumentResponse,
+  DocumentFormatOptions,
+  UpdateDocumentFormatOptions,
+  DocumentCommitSettings,
+  UpdateDocumentCommitSettings,
+  DocumentAutoCommit,
+  DocumentBranchEditing,
+  CreateDocumentPullRequest,
+  DocumentPullRequest,
+  CommitDocumentsRequest,
+  CommitDocumentsResponse,
+  ApplyActionItemsResponse,
+  ReleaseDocumentLockRequest,
+  DocumentDraft,
+  SaveDocumentDraftRequest,
+  DocumentDraftRequest,
+  PublishDocumentDraftRequest,
+  RestoredDocumentDraft,
+  DocumentAnnotation,
+  CreateDocumentAnnotationRequest,
+  UpdateDocumentAnnotationRequest,
+  LocatedDocumentAnnotation,
+  DocumentTagCount,
+  SetDocumentTagsRequest,
+  DocumentTagRequest,
+  DocumentTags,
+  RecentDocumentsResponse,
+  DocumentStats,
+  DocumentStatsOverview,
+  SyncConflicts,
+  ProjectTimezone,
+  UpdateProjectTimezone,
+  ProjectUsage,
+  DocumentConflict,
+  ResolveConflictRequest,
+  ContinueSyncResponse,
   CreateFileResponse,
+  ImportDocumentsResponse,
   ExecutionProcess,
   ExecutionProcessRepoState,
+  PreviewBuild,
+  PreviewBuildInfo,
+  PreviewBuildServer,
+  PreviewScreenshot,
+  PreviewComparison,
+  CapturePreviewScreenshotsRequest,
+  CapturedPreviewScreenshots,
+  E2eRun,
+  E2eRunDetails,
+  StartE2eRunRequest,
   GitBranch,
   Project,
   Repo,
   RepoWithTargetBranch,
   CreateProject,
   CreateProjectRepo,
+  CreateDemoProject,
+  DemoProject,
   UpdateRepo,
   SearchMode,
   SearchResult,
@@ -37,6 +157,13 @@ import {
   UpdateTask,
   UpdateTag,
   UserSystemInfo,
+  OnboardingStatus,
+  SetGitIdentityRequest,
+  StartSampleAttemptRequest,
+  SampleAttemptResponse,
+  VersionInfo,
+  UpdateCheck,
+  UpdateInstallResponse,
   McpServerQuery,
   UpdateMcpServersBody,
   GetMcpServerResponse,
@@ -69,6 +196,9 @@ import {
   CreateRemoteProjectRequest,
   LinkToExistingRequest,
   UpdateMemberRoleResponse,
+  PermissionCheckQuery,
+  PermissionCheckResponse,
+  AccessReportResponse,
   Invitation,
   RemoteProject,
   ListInvitationsResponse,
@@ -92,7 +222,16 @@ import {
   Workspace,
   StartReviewRequest,
   ReviewError,
+  RegisterWorkerRequest,
+  RegisterWorkerResponse,
+  SetWorkerRequirement,
+  WorkerInfo,
+  WorkerRequirement,
+  ProjectWebhook,
+  CreateProjectWebhook,
+  UpdateProjectWebhook,
 } from 'shared/types';
+import { API_PREFIX, ROUTES } from 'shared/types';
 import type { WorkspaceWithSession } from '@/types/attempt';
 import { createWorkspaceWithSession } from '@/types/attempt';
 
@@ -113,11 +252,25 @@ export class ApiError<E = unknown> extends Error {
   }
 }
 
+const CSRF_COOKIE = 'vk_csrf';
+
+// Headers echoing the CSRF cookie set by the backend (double-submit protection)
+export const csrfHeaders = (): Record<string, string> => {
+  const token = document.cookie
+    .split(';')
+    .map((pair) => pair.trim().split('='))
+    .find(([name]) => name === CSRF_COOKIE)?.[1];
+  return token ? { 'X-CSRF-Token': token } : {};
+};
+
 const makeRequest = async (url: string, options: RequestInit = {}) => {
   const headers = new Headers(options.headers ?? {});
   if (!headers.has('Content-Type')) {
     headers.set('Content-Type', 'application/json');
   }
+  for (const [name, value] of Object.entries(csrfHeaders())) {
+    headers.set(name, value);
+  }
 
   return fetch(url, {
     ...options,
@@ -125,6 +278,51 @@ const makeRequest = async (url: string, options: RequestInit = {}) => {
   });
 };
 
+type RoutePath = (typeof ROUTES)[keyof typeof ROUTES];
+
+/**
+ * Build an API URL from a server route template, e.g.
+ * `apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/branch')`
+ */
+export const apiPath = (
+  template: RoutePath,
+  params: Record<string, string> = {},
+  suffix = ''
+): string => {
+  const path = template.replace(/\{(\w+)\}/g, (_, name: string) => {
+    const value = params[name];
+    if (value === undefined) {
+      throw new Error(`Missing route parameter '${name}'`);
+    }
+    return encodeURIComponent(value);
+  });
+  return `${API_PREFIX}${path}${suffix}`;
+};
+
+type ApiWarningListener = (warnings: ApiWarning[]) => void;
+
+const apiWarningListeners = new Set<ApiWarningListener>();
+
+/**
+ * Listen for non-fatal warnings attached to successful responses, e.g. a
+ * document that was saved but could not be auto-committed
+ */
+export const subscribeToApiWarnings = (
+  listener: ApiWarningListener
+): (() => void) => {
+  apiWarningListeners.add(listener);
+  return () => {
+    apiWarningListeners.delete(listener);
+  };
+};
+
+const publishApiWarnings = (warnings: ApiWarning[] | undefined) => {
+  if (!warnings?.length) return;
+  for (const listener of apiWarningListeners) {
+    listener(warnings);
+  }
+};
+
 export type Ok<T> = { success: true; data: T };
 export type Err<E> = { success: false; error: E | undefined; message?: string };
 
@@ -165,6 +363,7 @@ const handleApiResponseAsResult = async <T, E>(
     };
   }
 
+  publishApiWarnings(result.warnings);
   return { success: true, data: result.data as T };
 };
 
@@ -234,9 +433,27 @@ export const handleApiResponse = async <T, E = T>(
     );
   }
 
+  publishApiWarnings(result.warnings);
   return result.data as T;
 };
 
+// For endpoints that answer with a file download instead of an ApiResponse
+const handleFileResponse = async (response: Response): Promise<Blob> => {
+  if (!response.ok) {
+    let errorMessage = `Request failed with status ${response.status}`;
+    try {
+      const errorData = await response.json();
+      if (errorData.message) {
+        errorMessage = errorData.message;
+      }
+    } catch {
+      errorMessage = response.statusText || errorMessage;
+    }
+    throw new ApiError(errorMessage, response.status, response);
+  }
+  return response.blob();
+};
+
 // Project Management APIs
 export const projectsApi = {
   create: async (data: CreateProject): Promise<Project> => {
@@ -282,6 +499,37 @@ export const projectsApi = {
     return handleApiResponse<OpenEditorResponse>(response);
   },
 
+  /**
+   * Timezone the project's schedules and due dates follow
+   */
+  getTimezone: async (id: string): Promise<ProjectTimezone> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_TIMEZONE, { id })
+    );
+    return handleApiResponse<ProjectTimezone>(response);
+  },
+
+  setTimezone: async (
+    id: string,
+    data: UpdateProjectTimezone
+  ): Promise<ProjectTimezone> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_TIMEZONE, { id }),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectTimezone>(response);
+  },
+
+  getUsage: async (id: string): Promise<ProjectUsage> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_USAGE, { id })
+    );
+    return handleApiResponse<ProjectUsage>(response);
+  },
+
   searchFiles: async (
     id: string,
     query: string,
@@ -402,6 +650,30 @@ export const tasksApi = {
     });
     return handleApiResponse<void>(response);
   },
+
+  getDocuments: async (taskId: string): Promise<TaskDocument[]> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/documents`);
+    return handleApiResponse<TaskDocument[]>(response);
+  },
+
+  attachDocument: async (
+    taskId: string,
+    data: AttachTaskDocument
+  ): Promise<TaskDocument> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/documents`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskDocument>(response);
+  },
+
+  detachDocument: async (taskId: string, linkId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/tasks/${taskId}/documents/${linkId}`,
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Sessions API
@@ -703,6 +975,173 @@ export const attemptsApi = {
     return handleApiResponse<ExecutionProcess[]>(response);
   },
 
+  getPreviewBuilds: async (attemptId: string): Promise<PreviewBuildInfo[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-builds`
+    );
+    return handleApiResponse<PreviewBuildInfo[]>(response);
+  },
+
+  /**
+   * Build every repository with a preview build script into a static bundle,
+   * or with `base` the attempt's fork point with its target branch. Builds run
+   * in the background; poll getPreviewBuilds for their status.
+   */
+  startPreviewBuilds: async (
+    attemptId: string,
+    base = false
+  ): Promise<PreviewBuild[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-builds${base ? '?base=true' : ''}`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<PreviewBuild[]>(response);
+  },
+
+  /**
+   * Serve a succeeded build's bundle. It is served over HTTP on the returned
+   * port of this server's host.
+   */
+  servePreviewBuild: async (
+    attemptId: string,
+    buildId: string
+  ): Promise<PreviewBuildServer> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-builds/${buildId}/serve`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<PreviewBuildServer>(response);
+  },
+
+  deletePreviewBuild: async (
+    attemptId: string,
+    buildId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-builds/${buildId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getPreviewScreenshots: async (
+    attemptId: string
+  ): Promise<PreviewScreenshot[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-screenshots`
+    );
+    return handleApiResponse<PreviewScreenshot[]>(response);
+  },
+
+  /**
+   * Take screenshots of a preview build or URL at each route and viewport,
+   * compared with the base's if a base build or URL is given. Screenshots and
+   * comparisons that fail come back as warnings.
+   */
+  capturePreviewScreenshots: async (
+    attemptId: string,
+    data: CapturePreviewScreenshotsRequest
+  ): Promise<CapturedPreviewScreenshots> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-screenshots`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<CapturedPreviewScreenshots>(response);
+  },
+
+  getPreviewScreenshotUrl: (attemptId: string, screenshotId: string): string =>
+    `/api/task-attempts/${attemptId}/preview-screenshots/${screenshotId}/image`,
+
+  deletePreviewScreenshot: async (
+    attemptId: string,
+    screenshotId: string
+  ): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-screenshots/${screenshotId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getPreviewComparisons: async (
+    attemptId: string
+  ): Promise<PreviewComparison[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/preview-comparisons`
+    );
+    return handleApiResponse<PreviewComparison[]>(response);
+  },
+
+  getPreviewComparisonImageUrl: (
+    attemptId: string,
+    comparisonId: string,
+    image: 'base' | 'diff'
+  ): string =>
+    `/api/task-attempts/${attemptId}/preview-comparisons/${comparisonId}/${image}`,
+
+  getE2eRuns: async (attemptId: string): Promise<E2eRun[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/e2e-runs`
+    );
+    return handleApiResponse<E2eRun[]>(response);
+  },
+
+  /**
+   * Run the end-to-end suites of the attempt's repositories against a preview
+   * build or URL. Runs go on in the background; poll the runs for their status.
+   */
+  startE2eRuns: async (
+    attemptId: string,
+    data: StartE2eRunRequest
+  ): Promise<E2eRun[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/e2e-runs`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<E2eRun[]>(response);
+  },
+
+  getE2eRun: async (
+    attemptId: string,
+    runId: string
+  ): Promise<E2eRunDetails> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/e2e-runs/${runId}`
+    );
+    return handleApiResponse<E2eRunDetails>(response);
+  },
+
+  getE2eRunArtifactUrl: (
+    attemptId: string,
+    runId: string,
+    path: string
+  ): string =>
+    `/api/task-attempts/${attemptId}/e2e-runs/${runId}/artifacts/${path.split('/').map(encodeURIComponent).join('/')}`,
+
+  deleteE2eRun: async (attemptId: string, runId: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/e2e-runs/${runId}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
   setupGhCli: async (attemptId: string): Promise<ExecutionProcess> => {
     const response = await makeRequest(
       `/api/task-attempts/${attemptId}/gh-cli-setup`,
@@ -896,6 +1335,24 @@ export const configApi = {
     const response = await makeRequest('/api/info', { cache: 'no-store' });
     return handleApiResponse<UserSystemInfo>(response);
   },
+  getVersion: async (): Promise<VersionInfo> => {
+    const response = await makeRequest(apiPath(ROUTES.VERSION), {
+      cache: 'no-store',
+    });
+    return handleApiResponse<VersionInfo>(response);
+  },
+  checkForUpdate: async (): Promise<UpdateCheck> => {
+    const response = await makeRequest(apiPath(ROUTES.VERSION_CHECK), {
+      cache: 'no-store',
+    });
+    return handleApiResponse<UpdateCheck>(response);
+  },
+  installUpdate: async (): Promise<UpdateInstallResponse> => {
+    const response = await makeRequest(apiPath(ROUTES.ADMIN_UPDATE), {
+      method: 'POST',
+    });
+    return handleApiResponse<UpdateInstallResponse>(response);
+  },
   saveConfig: async (config: Config): Promise<Config> => {
     const response = await makeRequest('/api/config', {
       method: 'PUT',
@@ -921,6 +1378,59 @@ export const configApi = {
   },
 };
 
+// First-run onboarding
+export const onboardingApi = {
+  getStatus: async (): Promise<OnboardingStatus> => {
+    const response = await makeRequest(apiPath(ROUTES.ONBOARDING), {
+      cache: 'no-store',
+    });
+    return handleApiResponse<OnboardingStatus>(response);
+  },
+  setGitIdentity: async (data: SetGitIdentityRequest): Promise<void> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.ONBOARDING, {}, '/git-identity'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+  registerProject: async (data: CreateProject): Promise<Project> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.ONBOARDING, {}, '/project'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<Project>(response);
+  },
+  startSampleAttempt: async (
+    data: StartSampleAttemptRequest
+  ): Promise<SampleAttemptResponse> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.ONBOARDING, {}, '/sample-attempt'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<SampleAttemptResponse>(response);
+  },
+};
+
+// Demo project API
+export const demoApi = {
+  create: async (data: CreateDemoProject): Promise<DemoProject> => {
+    const response = await makeRequest(apiPath(ROUTES.ADMIN_DEMO), {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<DemoProject>(response);
+  },
+};
+
 // Task Tags APIs (all tags are global)
 export const tagsApi = {
   list: async (params?: TagSearchParams): Promise<Tag[]> => {
@@ -1016,6 +1526,7 @@ export const imagesApi = {
     const response = await fetch('/api/images/upload', {
       method: 'POST',
       body: formData,
+      headers: csrfHeaders(),
       credentials: 'include',
     });
 
@@ -1038,6 +1549,7 @@ export const imagesApi = {
     const response = await fetch(`/api/images/task/${taskId}/upload`, {
       method: 'POST',
       body: formData,
+      headers: csrfHeaders(),
       credentials: 'include',
     });
 
@@ -1069,6 +1581,7 @@ export const imagesApi = {
       {
         method: 'POST',
         body: formData,
+        headers: csrfHeaders(),
         credentials: 'include',
       }
     );
@@ -1241,6 +1754,38 @@ export const organizationsApi = {
     return handleApiResponse<UpdateMemberRoleResponse>(response);
   },
 
+  checkPermission: async (
+    query: PermissionCheckQuery
+  ): Promise<PermissionCheckResponse> => {
+    const params = new URLSearchParams({
+      resource_type: query.resource_type,
+      resource_id: query.resource_id,
+      action: query.action,
+    });
+    if (query.user_id) params.set('user_id', query.user_id);
+    const response = await makeRequest(`/api/permissions/check?${params.toString()}`);
+    return handleApiResponse<PermissionCheckResponse>(response);
+  },
+
+  /**
+   * Which projects and actions every member and token of the organizations
+   * the user administers can reach
+   */
+  getAccessReport: async (): Promise<AccessReportResponse> => {
+    const response = await makeRequest(apiPath(ROUTES.ADMIN_ACCESS_REPORT));
+    return handleApiResponse<AccessReportResponse>(response);
+  },
+
+  /**
+   * The access report as a CSV file, one row per user or token and resource
+   */
+  exportAccessReportCsv: async (): Promise<Blob> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.ADMIN_ACCESS_REPORT, {}, '?format=csv')
+    );
+    return handleFileResponse(response);
+  },
+
   listInvitations: async (orgId: string): Promise<Invitation[]> => {
     const response = await makeRequest(
       `/api/organizations/${orgId}/invitations`
@@ -1353,15 +1898,69 @@ export const queueApi = {
 // Documents API - for viewing project markdown and JSON files
 export const documentsApi = {
   /**
-   * List all markdown and JSON documents in the project (including subdirectories)
+   * List the project's documents (including subdirectories)
+   * @param options.repositoryId - Only list documents of this repository
+   * @param options.pathPrefix - Only list documents below this folder
+   * @param options.limit - Page size; every document when omitted
+   * @param options.tags - Only list documents with all of these tags
    */
   list: async (
-    projectId: string
-  ): Promise<{ documents: DocumentMetadata[] }> => {
+    projectId: string,
+    options: {
+      repositoryId?: string;
+      pathPrefix?: string;
+      limit?: number;
+      offset?: number;
+      tags?: string[];
+    } = {}
+  ): Promise<ListDocumentsResponse> => {
+    const params = new URLSearchParams();
+    if (options.repositoryId) params.set('repository_id', options.repositoryId);
+    if (options.pathPrefix) params.set('path_prefix', options.pathPrefix);
+    if (options.tags?.length) params.set('tags', options.tags.join(','));
+    if (options.limit !== undefined) params.set('limit', String(options.limit));
+    if (options.offset) params.set('offset', String(options.offset));
+    const search = params.toString();
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        search ? `?${search}` : ''
+      )
+    );
+    return handleApiResponse<ListDocumentsResponse>(response);
+  },
+
+  /**
+   * The documents changed most recently, newest first, dated by git commits
+   * @param options.limit - Number of documents, 10 by default and at most 100
+   */
+  recent: async (
+    projectId: string,
+    options: { repositoryId?: string; limit?: number } = {}
+  ): Promise<RecentDocumentsResponse> => {
+    const params = new URLSearchParams();
+    if (options.repositoryId) params.set('repository_id', options.repositoryId);
+    if (options.limit !== undefined) params.set('limit', String(options.limit));
+    const search = params.toString();
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        search ? `/recent?${search}` : '/recent'
+      )
+    );
+    return handleApiResponse<RecentDocumentsResponse>(response);
+  },
+
+  /**
+   * Get the project's documents as a nested folder tree, including empty folders
+   */
+  getTree: async (projectId: string): Promise<DocumentTreeResponse> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/documents`
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/tree')
     );
-    return handleApiResponse<{ documents: DocumentMetadata[] }>(response);
+    return handleApiResponse<DocumentTreeResponse>(response);
   },
 
   /**
@@ -1372,7 +1971,7 @@ export const documentsApi = {
     projectId: string
   ): Promise<{ branch: string; is_docs_branch: boolean }> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/documents/branch`
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/branch')
     );
     return handleApiResponse<{ branch: string; is_docs_branch: boolean }>(response);
   },
@@ -1388,7 +1987,7 @@ export const documentsApi = {
     current_branch: string;
   }> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/documents/branches`
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/branches')
     );
     return handleApiResponse<{
       branches: Array<{ name: string; is_current: boolean; is_remote: boolean }>;
@@ -1406,7 +2005,7 @@ export const documentsApi = {
     branch: string
   ): Promise<{ success: boolean; branch: string; message: string }> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/documents/switch-branch`,
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/switch-branch'),
       {
         method: 'POST',
         body: JSON.stringify({ branch }),
@@ -1419,134 +2018,2077 @@ export const documentsApi = {
    * Get sync status for documents
    * @param projectId - Project ID
    */
-  getSyncStatus: async (
-    projectId: string
-  ): Promise<{
-    commits_ahead: number;
-    commits_behind: number;
-    can_sync: boolean;
-    needs_rebase: boolean;
-    current_branch: string;
-    error: string | null;
-  }> => {
+  getSyncStatus: async (projectId: string): Promise<SyncStatusResponse> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/documents/sync-status`
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/sync-status')
     );
-    return handleApiResponse<{
-      commits_ahead: number;
-      commits_behind: number;
-      can_sync: boolean;
-      needs_rebase: boolean;
-      current_branch: string;
-      error: string | null;
-    }>(response);
+    return handleApiResponse<SyncStatusResponse>(response);
+  },
+
+  /**
+   * Docs drift of every project, furthest behind first, as of the last
+   * background fetch or sync
+   */
+  getAllSyncStatus: async (): Promise<ProjectDocumentSyncStatus[]> => {
+    const response = await makeRequest(apiPath(ROUTES.DOCUMENTS_SYNC_STATUS));
+    return handleApiResponse<ProjectDocumentSyncStatus[]>(response);
   },
 
   /**
    * Sync documents to origin/main
    * @param projectId - Project ID
-   * @param allowRebase - Whether to allow rebase if behind origin
+   * @param allowPull - Whether to pull remote commits, with the project's sync
+   * strategy, if behind origin
    */
   sync: async (
     projectId: string,
-    allowRebase: boolean = false
-  ): Promise<{
-    success: boolean;
-    commits_pushed: number;
-    message: string;
-    rebased: boolean;
-  }> => {
+    allowPull: boolean = false
+  ): Promise<SyncResponse> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/documents/sync`,
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/sync'),
       {
         method: 'POST',
-        body: JSON.stringify({ allow_rebase: allowRebase }),
+        body: JSON.stringify({ allow_pull: allowPull }),
       }
     );
-    return handleApiResponse<{
-      success: boolean;
-      commits_pushed: number;
-      message: string;
-      rebased: boolean;
-    }>(response);
+    return handleApiResponse<SyncResponse>(response);
   },
 
   /**
-   * Get content of a specific document by relative path
-   * @param projectId - Project ID
-   * @param relativePath - Relative path from repo root (e.g., "docs/README.md")
+   * Documents left conflicted by a sync whose pull stopped on conflicts
    */
-  get: async (
-    projectId: string,
-    relativePath: string
-  ): Promise<DocumentContent> => {
-    // Encode the relative path - encodeURIComponent handles slashes too
-    const encodedPath = encodeURIComponent(relativePath);
+  getSyncConflicts: async (projectId: string): Promise<SyncConflicts> => {
     const response = await makeRequest(
-      `/api/projects/${projectId}/documents/${encodedPath}`
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/sync/conflicts')
     );
-    return handleApiResponse<DocumentContent>(response);
+    return handleApiResponse<SyncConflicts>(response);
   },
 
   /**
-   * Update content of a specific document
-   * @param projectId - Project ID
-   * @param relativePath - Relative path from repo root (e.g., "docs/README.md")
-   * @param content - New content to save
+   * Base, ours and theirs versions of a conflicted document
+   */
+  getSyncConflict: async (
+    projectId: string,
+    relativePath: string
+  ): Promise<DocumentConflict> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/sync/conflicts/file?path=${encodeURIComponent(relativePath)}`
+      )
+    );
+    return handleApiResponse<DocumentConflict>(response);
+  },
+
+  resolveSyncConflict: async (
+    projectId: string,
+    data: ResolveConflictRequest
+  ): Promise<SyncConflicts> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        '/sync/conflicts/resolve'
+      ),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<SyncConflicts>(response);
+  },
+
+  /**
+   * Continue the stopped rebase or merge once every conflict is resolved
+   */
+  continueSync: async (projectId: string): Promise<ContinueSyncResponse> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        '/sync/conflicts/continue'
+      ),
+      { method: 'POST' }
+    );
+    return handleApiResponse<ContinueSyncResponse>(response);
+  },
+
+  abortSync: async (projectId: string): Promise<SyncConflicts> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        '/sync/conflicts/abort'
+      ),
+      { method: 'POST' }
+    );
+    return handleApiResponse<SyncConflicts>(response);
+  },
+
+  getSyncStrategy: async (projectId: string): Promise<DocumentSyncStrategy> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/sync-strategy')
+    );
+    return handleApiResponse<DocumentSyncStrategy>(response);
+  },
+
+  /**
+   * Set how remote commits are pulled when documents are synced: rebase, merge
+   * commit or fast-forward only
+   */
+  setSyncStrategy: async (
+    projectId: string,
+    data: UpdateDocumentSyncStrategy
+  ): Promise<DocumentSyncStrategy> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/sync-strategy'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentSyncStrategy>(response);
+  },
+
+  /**
+   * Get content of a specific document by relative path
+   * @param projectId - Project ID
+   * @param relativePath - Relative path from repo root (e.g., "docs/README.md")
+   * @param ref - Optional commit, branch or tag to read the document at
+   * @param repositoryId - Optional repository the document belongs to
+   */
+  get: async (
+    projectId: string,
+    relativePath: string,
+    ref?: string,
+    repositoryId?: string
+  ): Promise<DocumentContent> => {
+    // Encode the relative path - encodeURIComponent handles slashes too
+    const encodedPath = encodeURIComponent(relativePath);
+    const params = new URLSearchParams();
+    if (ref) params.set('ref', ref);
+    if (repositoryId) params.set('repository_id', repositoryId);
+    const search = params.toString();
+    const query = search ? `?${search}` : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}${query}`
+      )
+    );
+    return handleApiResponse<DocumentContent>(response);
+  },
+
+  /**
+   * Update content of a specific document
+   * @param projectId - Project ID
+   * @param relativePath - Relative path from repo root (e.g., "docs/README.md")
+   * @param content - New content to save
+   * @param expectedHash - `content_hash` of the version the edit is based on
+   * @param repositoryId - Optional repository the document belongs to
+   * @param lockHolder - Name the document was locked with, if any
+   * @throws ApiError with DocumentUpdateConflict data when the document changed
+   * @throws ApiError with DocumentLock data when someone else holds the lock
    */
   update: async (
     projectId: string,
     relativePath: string,
-    content: string
+    content: string,
+    expectedHash: string,
+    repositoryId?: string,
+    lockHolder?: string
   ): Promise<UpdateDocumentResponse> => {
     const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
     const response = await makeRequest(
-      `/api/projects/${projectId}/documents/${encodedPath}`,
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}${query}`
+      ),
       {
         method: 'PUT',
-        body: JSON.stringify({ content }),
+        body: JSON.stringify({
+          content,
+          expected_hash: expectedHash,
+          lock_holder: lockHolder,
+        }),
       }
     );
-    return handleApiResponse<UpdateDocumentResponse>(response);
+    if (response.status === 423) {
+      const result: ApiResponse<UpdateDocumentResponse, DocumentLock> =
+        await response.json();
+      throw new ApiError<DocumentLock>(
+        'The document is locked by another editor',
+        response.status,
+        response,
+        result.error_data ?? undefined
+      );
+    }
+    if (response.status === 409) {
+      const result: ApiResponse<
+        UpdateDocumentResponse,
+        DocumentUpdateConflict
+      > = await response.json();
+      throw new ApiError<DocumentUpdateConflict>(
+        'The document changed since it was opened',
+        response.status,
+        response,
+        result.error_data ?? undefined
+      );
+    }
+    return handleApiResponse<
+      UpdateDocumentResponse,
+      DocumentReadOnlyViolation
+    >(response);
   },
 
   /**
-   * Create a new folder in the project repository
-   * @param projectId - Project ID
-   * @param path - Relative path for the new folder (e.g., "docs/subfolder")
+   * The diff and commit message saving `content` would produce, without saving
    */
-  createFolder: async (
+  previewUpdate: async (
     projectId: string,
-    path: string
-  ): Promise<{ success: boolean; message: string; path: string }> => {
+    relativePath: string,
+    content: string,
+    expectedHash: string,
+    repositoryId?: string,
+    lockHolder?: string
+  ): Promise<DocumentCommitPreview> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const params = new URLSearchParams({ dry_run: 'true' });
+    if (repositoryId) params.set('repository_id', repositoryId);
     const response = await makeRequest(
-      `/api/projects/${projectId}/documents/folders`,
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}?${params}`
+      ),
+      {
+        method: 'PUT',
+        body: JSON.stringify({
+          content,
+          expected_hash: expectedHash,
+          lock_holder: lockHolder,
+        }),
+      }
+    );
+    return handleApiResponse<DocumentCommitPreview>(response);
+  },
+
+  /**
+   * Write several documents with a single commit per repository
+   * @throws ApiError with BatchUpdateConflict data when documents changed
+   * @throws ApiError with DocumentLock data when someone else holds a lock
+   */
+  batchUpdate: async (
+    projectId: string,
+    data: BatchUpdateDocumentsRequest
+  ): Promise<BatchUpdateDocumentsResponse> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/batch'),
       {
         method: 'POST',
-        body: JSON.stringify({ path }),
+        body: JSON.stringify(data),
       }
     );
-    return handleApiResponse<{ success: boolean; message: string; path: string }>(response);
+    if (response.status === 423) {
+      const result: ApiResponse<BatchUpdateDocumentsResponse, DocumentLock> =
+        await response.json();
+      throw new ApiError<DocumentLock>(
+        'A document is locked by another editor',
+        response.status,
+        response,
+        result.error_data ?? undefined
+      );
+    }
+    if (response.status === 409) {
+      const result: ApiResponse<
+        BatchUpdateDocumentsResponse,
+        BatchUpdateConflict
+      > = await response.json();
+      throw new ApiError<BatchUpdateConflict>(
+        'Some documents changed since they were read',
+        response.status,
+        response,
+        result.error_data ?? undefined
+      );
+    }
+    return handleApiResponse<
+      BatchUpdateDocumentsResponse,
+      DocumentReadOnlyViolation
+    >(response);
   },
 
   /**
-   * Create a new markdown file in the project repository
-   * @param projectId - Project ID
-   * @param path - Relative path for the new file (e.g., "docs/new-doc.md")
-   * @param content - Optional initial content
+   * Set front matter fields of a markdown document without touching its body
+   * @param data - Fields to set; `null` removes a field
+   * @throws ApiError with DocumentUpdateConflict data when the document changed
+   * @throws ApiError with DocumentLock data when someone else holds the lock
    */
-  createFile: async (
+  updateFrontMatter: async (
     projectId: string,
-    path: string,
-    content?: string
-  ): Promise<CreateFileResponse> => {
+    relativePath: string,
+    data: UpdateFrontMatterRequest,
+    repositoryId?: string
+  ): Promise<UpdateDocumentResponse> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/front-matter${query}`
+      ),
+      {
+        method: 'PATCH',
+        body: JSON.stringify(data),
+      }
+    );
+    if (response.status === 423) {
+      const result: ApiResponse<UpdateDocumentResponse, DocumentLock> =
+        await response.json();
+      throw new ApiError<DocumentLock>(
+        'The document is locked by another editor',
+        response.status,
+        response,
+        result.error_data ?? undefined
+      );
+    }
+    if (response.status === 409) {
+      const result: ApiResponse<
+        UpdateDocumentResponse,
+        DocumentUpdateConflict
+      > = await response.json();
+      throw new ApiError<DocumentUpdateConflict>(
+        'The document changed since it was opened',
+        response.status,
+        response,
+        result.error_data ?? undefined
+      );
+    }
+    return handleApiResponse<
+      UpdateDocumentResponse,
+      DocumentReadOnlyViolation
+    >(response);
+  },
+
+  /**
+   * Create a task quoting lines of a document
+   * @param data - Selected lines; `insert_marker` also links the task from the
+   * document and commits it
+   */
+  createTask: async (
+    projectId: string,
+    relativePath: string,
+    data: CreateDocumentTaskRequest,
+    repositoryId?: string
+  ): Promise<CreateDocumentTaskResponse> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
     const response = await makeRequest(
-      `/api/projects/${projectId}/documents/files`,
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/create-task${query}`
+      ),
       {
         method: 'POST',
-        body: JSON.stringify({ path, content: content ?? null }),
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<
+      CreateDocumentTaskResponse,
+      DocumentReadOnlyViolation
+    >(response);
+  },
+
+  /**
+   * Sync the task list of a document that has `task_sync: true` in its front
+   * matter with the board
+   * @param data - `create_tasks` also creates tasks for unlinked items
+   */
+  syncTasks: async (
+    projectId: string,
+    relativePath: string,
+    data: SyncDocumentTasksRequest,
+    repositoryId?: string
+  ): Promise<SyncDocumentTasksResponse> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/sync-tasks${query}`
+      ),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<
+      SyncDocumentTasksResponse,
+      DocumentReadOnlyViolation
+    >(response);
+  },
+
+  /**
+   * Propose action items for a meeting-notes document; nothing is changed
+   * until the confirmed items are applied
+   */
+  extractActionItems: async (
+    projectId: string,
+    relativePath: string,
+    repositoryId?: string
+  ): Promise<ActionItemsPreview> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/action-items${query}`
+      )
+    );
+    return handleApiResponse<ActionItemsPreview>(response);
+  },
+
+  /**
+   * Create tasks for confirmed action items and list them in the document's
+   * "Action items" section in one commit
+   * @throws ApiError with DocumentUpdateConflict data when the document changed
+   * @throws ApiError with DocumentLock data when someone else holds the lock
+   */
+  applyActionItems: async (
+    projectId: string,
+    relativePath: string,
+    data: ApplyActionItemsRequest,
+    repositoryId?: string
+  ): Promise<ApplyActionItemsResponse> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/action-items${query}`
+      ),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    if (response.status === 423) {
+      const result: ApiResponse<ApplyActionItemsResponse, DocumentLock> =
+        await response.json();
+      throw new ApiError<DocumentLock>(
+        'The document is locked by another editor',
+        response.status,
+        response,
+        result.error_data ?? undefined
+      );
+    }
+    if (response.status === 409) {
+      const result: ApiResponse<
+        ApplyActionItemsResponse,
+        DocumentUpdateConflict
+      > = await response.json();
+      throw new ApiError<DocumentUpdateConflict>(
+        'The document changed since the action items were proposed',
+        response.status,
+        response,
+        result.error_data ?? undefined
+      );
+    }
+    return handleApiResponse<
+      ApplyActionItemsResponse,
+      DocumentReadOnlyViolation
+    >(response);
+  },
+
+  /**
+   * Format a markdown document; with `apply` the result is written and committed
+   */
+  formatDocument: async (
+    projectId: string,
+    relativePath: string,
+    data: FormatDocumentRequest,
+    repositoryId?: string
+  ): Promise<FormatDocumentResponse> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/format${query}`
+      ),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    if (response.status === 423) {
+      const result: ApiResponse<FormatDocumentResponse, DocumentLock> =
+        await response.json();
+      throw new ApiError<DocumentLock>(
+        'The document is locked by another editor',
+        response.status,
+        response,
+        result.error_data ?? undefined
+      );
+    }
+    if (response.status === 409) {
+      const result: ApiResponse<
+        FormatDocumentResponse,
+        DocumentUpdateConflict
+      > = await response.json();
+      throw new ApiError<DocumentUpdateConflict>(
+        'The document changed since it was loaded',
+        response.status,
+        response,
+        result.error_data ?? undefined
+      );
+    }
+    return handleApiResponse<
+      FormatDocumentResponse,
+      DocumentReadOnlyViolation
+    >(response);
+  },
+
+  /**
+   * Delete a document and commit the deletion
+   * @param projectId - Project ID
+   * @param relativePath - Relative path from repo root (e.g., "docs/README.md")
+   */
+  delete: async (
+    projectId: string,
+    relativePath: string
+  ): Promise<DeleteDocumentResponse> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, `/${encodedPath}`),
+      {
+        method: 'DELETE',
       }
     );
-    return handleApiResponse<CreateFileResponse>(response);
+    return handleApiResponse<
+      DeleteDocumentResponse,
+      DocumentReadOnlyViolation
+    >(response);
+  },
+
+  /**
+   * List the commits that touched a document, newest first
+   * @param projectId - Project ID
+   * @param relativePath - Relative path from repo root (e.g., "docs/README.md")
+   * @param limit - Maximum number of commits (server default: 50)
+   */
+  history: async (
+    projectId: string,
+    relativePath: string,
+    limit?: number
+  ): Promise<DocumentHistoryResponse> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = limit ? `?limit=${limit}` : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/history${query}`
+      )
+    );
+    return handleApiResponse<DocumentHistoryResponse>(response);
+  },
+
+  /**
+   * Diff a document between two revisions
+   * @param projectId - Project ID
+   * @param relativePath - Relative path from repo root (e.g., "docs/README.md")
+   * @param from - Base commit, branch or tag
+   * @param to - Target revision; the working tree when omitted
+   */
+  diff: async (
+    projectId: string,
+    relativePath: string,
+    from: string,
+    to?: string
+  ): Promise<DocumentDiffResponse> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const params = new URLSearchParams({ from });
+    if (to) params.set('to', to);
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/diff?${params}`
+      )
+    );
+    return handleApiResponse<DocumentDiffResponse>(response);
+  },
+
+  /**
+   * Render a markdown document to sanitized HTML on the server
+   * @param projectId - Project ID
+   * @param relativePath - Relative path from repo root (e.g., "docs/README.md")
+   * @param repositoryId - Optional repository the document belongs to
+   */
+  getHtml: async (
+    projectId: string,
+    relativePath: string,
+    repositoryId?: string
+  ): Promise<DocumentHtml> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/html${query}`
+      )
+    );
+    return handleApiResponse<DocumentHtml>(response);
+  },
+
+  /**
+   * Copy a document to a new path, e.g. to start from last sprint's plan
+   * @param projectId - Project ID
+   * @param relativePath - Path of the document to copy
+   * @param to - Relative path of the copy (e.g., "docs/sprints/sprint-13.md")
+   */
+  copy: async (
+    projectId: string,
+    relativePath: string,
+    to: string,
+    repositoryId?: string
+  ): Promise<CreateFileResponse> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/copy${query}`
+      ),
+      {
+        method: 'POST',
+        body: JSON.stringify({ to }),
+      }
+    );
+    return handleApiResponse<CreateFileResponse, DocumentReadOnlyViolation>(
+      response
+    );
+  },
+
+  /**
+   * Move or rename a document, keeping its git history
+   * @param projectId - Project ID
+   * @param from - Current relative path (e.g., "docs/old.md")
+   * @param to - New relative path (e.g., "docs/guides/new.md")
+   */
+  move: async (
+    projectId: string,
+    from: string,
+    to: string
+  ): Promise<MoveDocumentResponse> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/move'),
+      {
+        method: 'POST',
+        body: JSON.stringify({ from, to }),
+      }
+    );
+    return handleApiResponse<
+      MoveDocumentResponse,
+      DocumentReadOnlyViolation
+    >(response);
+  },
+
+  /**
+   * Move or rename a folder, committing everything in it as one move
+   * @param projectId - Project ID
+   * @param from - Current relative path (e.g., "docs/guides")
+   * @param to - New relative path (e.g., "docs/handbook/guides")
+   */
+  moveFolder: async (
+    projectId: string,
+    from: string,
+    to: string
+  ): Promise<MoveFolderResponse> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/folders/move'),
+      {
+        method: 'POST',
+        body: JSON.stringify({ from, to }),
+      }
+    );
+    return handleApiResponse<MoveFolderResponse, DocumentReadOnlyViolation>(
+      response
+    );
+  },
+
+  /**
+   * Detect documents changed outside the app and optionally commit them
+   */
+  refresh: async (
+    projectId: string,
+    data: RefreshDocumentsRequest
+  ): Promise<RefreshDocumentsResponse> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/refresh'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<RefreshDocumentsResponse>(response);
+  },
+
+  /**
+   * Create a new folder in the project repository
+   * @param projectId - Project ID
+   * @param path - Relative path for the new folder (e.g., "docs/subfolder")
+   */
+  createFolder: async (
+    projectId: string,
+    path: string
+  ): Promise<{ success: boolean; message: string; path: string }> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/folders'),
+      {
+        method: 'POST',
+        body: JSON.stringify({ path }),
+      }
+    );
+    return handleApiResponse<
+      { success: boolean; message: string; path: string },
+      DocumentReadOnlyViolation
+    >(response);
+  },
+
+  /**
+   * Delete a folder and commit the deletion
+   * @param projectId - Project ID
+   * @param path - Relative path of the folder (e.g., "docs/subfolder")
+   * @param recursive - Also delete its contents; otherwise it must be empty
+   */
+  deleteFolder: async (
+    projectId: string,
+    path: string,
+    recursive = false
+  ): Promise<DeleteFolderResponse> => {
+    const query = `?path=${encodeURIComponent(path)}&recursive=${recursive}`;
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, `/folders${query}`),
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<DeleteFolderResponse, DocumentReadOnlyViolation>(
+      response
+    );
+  },
+
+  /**
+   * Create a new markdown file in the project repository
+   * @param projectId - Project ID
+   * @param path - Relative path for the new file (e.g., "docs/new-doc.md")
+   * @param content - Optional initial content
+   */
+  createFile: async (
+    projectId: string,
+    path: string,
+    content?: string
+  ): Promise<CreateFileResponse> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/files'),
+      {
+        method: 'POST',
+        body: JSON.stringify({ path, content: content ?? null }),
+      }
+    );
+    return handleApiResponse<
+      CreateFileResponse,
+      DocumentReadOnlyViolation
+    >(response);
+  },
+
+  /**
+   * Import documents, or zip archives of documents, into a folder in one
+   * commit. Existing documents are never overwritten.
+   * @param folder - Folder to import into; the repository root if empty
+   */
+  import: async (
+    projectId: string,
+    folder: string,
+    files: File[],
+    repositoryId?: string
+  ): Promise<ImportDocumentsResponse> => {
+    const formData = new FormData();
+    formData.append('folder', folder);
+    for (const file of files) {
+      formData.append('file', file);
+    }
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await fetch(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, `/import${query}`),
+      {
+        method: 'POST',
+        body: formData,
+        headers: csrfHeaders(),
+        credentials: 'include',
+      }
+    );
+    return handleApiResponse<
+      ImportDocumentsResponse,
+      DocumentReadOnlyViolation
+    >(response);
+  },
+
+  /**
+   * Get the file extensions shown in the project's documents
+   */
+  getExtensions: async (projectId: string): Promise<DocumentExtensions> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/extensions')
+    );
+    return handleApiResponse<DocumentExtensions>(response);
+  },
+
+  /**
+   * Set the project's document extensions; null resets to every supported one
+   */
+  setExtensions: async (
+    projectId: string,
+    data: UpdateDocumentExtensions
+  ): Promise<DocumentExtensions> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/extensions'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentExtensions>(response);
+  },
+
+  /**
+   * How far document scans of the project may go before results are partial
+   */
+  getScanLimits: async (projectId: string): Promise<DocumentScanLimits> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/scan-limits')
+    );
+    return handleApiResponse<DocumentScanLimits>(response);
+  },
+
+  /**
+   * Set the project's scan limits; null resets to the defaults
+   */
+  setScanLimits: async (
+    projectId: string,
+    data: UpdateDocumentScanLimits
+  ): Promise<DocumentScanLimits> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/scan-limits'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentScanLimits>(response);
+  },
+
+  getSymlinkPolicy: async (
+    projectId: string
+  ): Promise<DocumentSymlinkPolicy> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/symlink-policy')
+    );
+    return handleApiResponse<DocumentSymlinkPolicy>(response);
+  },
+
+  /**
+   * Set whether symbolic links are skipped or followed within the repository
+   * when documents are scanned, read and saved
+   */
+  setSymlinkPolicy: async (
+    projectId: string,
+    data: UpdateDocumentSymlinkPolicy
+  ): Promise<DocumentSymlinkPolicy> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/symlink-policy'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentSymlinkPolicy>(response);
+  },
+
+  /**
+   * Largest document returned with its content; larger ones come back as
+   * metadata only, with too_large set
+   */
+  getReadLimit: async (projectId: string): Promise<DocumentReadLimit> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/read-limit')
+    );
+    return handleApiResponse<DocumentReadLimit>(response);
+  },
+
+  /**
+   * Set the project's read limit; null resets to the default
+   */
+  setReadLimit: async (
+    projectId: string,
+    data: UpdateDocumentReadLimit
+  ): Promise<DocumentReadLimit> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/read-limit'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentReadLimit>(response);
+  },
+
+  /**
+   * Store an image for a markdown document in the assets folder next to it
+   * and commit it. The response has the markdown to insert.
+   * @param documentPath - Relative path of the document the image is for
+   */
+  uploadAsset: async (
+    projectId: string,
+    documentPath: string,
+    image: File,
+    repositoryId?: string
+  ): Promise<DocumentAssetResponse> => {
+    const formData = new FormData();
+    formData.append('image', image);
+    const params = new URLSearchParams({ document: documentPath });
+    if (repositoryId) params.set('repository_id', repositoryId);
+    const response = await fetch(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/assets?${params.toString()}`
+      ),
+      {
+        method: 'POST',
+        body: formData,
+        headers: csrfHeaders(),
+        credentials: 'include',
+      }
+    );
+    return handleApiResponse<
+      DocumentAssetResponse,
+      DocumentReadOnlyViolation
+    >(response);
+  },
+
+  getAssetsFolder: async (projectId: string): Promise<DocumentAssetsFolder> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/assets-folder')
+    );
+    return handleApiResponse<DocumentAssetsFolder>(response);
+  },
+
+  /**
+   * Set the folder images are stored in; null resets to `assets`
+   */
+  setAssetsFolder: async (
+    projectId: string,
+    data: UpdateDocumentAssetsFolder
+  ): Promise<DocumentAssetsFolder> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/assets-folder'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentAssetsFolder>(response);
+  },
+
+  /**
+   * Templates new documents can be created from, see `CreateFileRequest.template_id`
+   */
+  getTemplates: async (projectId: string): Promise<DocumentTemplate[]> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/templates')
+    );
+    return handleApiResponse<DocumentTemplate[]>(response);
+  },
+
+  getTemplatesFolder: async (
+    projectId: string
+  ): Promise<DocumentTemplatesFolder> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/templates-folder')
+    );
+    return handleApiResponse<DocumentTemplatesFolder>(response);
+  },
+
+  /**
+   * Set the folder templates are read from; null resets to `.templates`
+   */
+  setTemplatesFolder: async (
+    projectId: string,
+    data: UpdateDocumentTemplatesFolder
+  ): Promise<DocumentTemplatesFolder> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/templates-folder'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentTemplatesFolder>(response);
+  },
+
+  getFormatOptions: async (
+    projectId: string
+  ): Promise<DocumentFormatOptions> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/format-options')
+    );
+    return handleApiResponse<DocumentFormatOptions>(response);
+  },
+
+  /**
+   * Set how the project's markdown documents are formatted; null resets to the
+   * defaults
+   */
+  setFormatOptions: async (
+    projectId: string,
+    data: UpdateDocumentFormatOptions
+  ): Promise<DocumentFormatOptions> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/format-options'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentFormatOptions>(response);
+  },
+
+  getCommitSettings: async (
+    projectId: string
+  ): Promise<DocumentCommitSettings> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/commit-settings')
+    );
+    return handleApiResponse<DocumentCommitSettings>(response);
+  },
+
+  /**
+   * Set the commit message template (`{message}`, `{path}` and `{user}`
+   * placeholders) and committer identity of document commits; null resets both
+   */
+  setCommitSettings: async (
+    projectId: string,
+    data: UpdateDocumentCommitSettings
+  ): Promise<DocumentCommitSettings> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/commit-settings'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentCommitSettings>(response);
+  },
+
+  getAutoCommit: async (projectId: string): Promise<DocumentAutoCommit> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/auto-commit')
+    );
+    return handleApiResponse<DocumentAutoCommit>(response);
+  },
+
+  /**
+   * Turn committing document saves right away on or off; when off, changes
+   * are committed with `commitPending`
+   */
+  setAutoCommit: async (
+    projectId: string,
+    data: DocumentAutoCommit
+  ): Promise<DocumentAutoCommit> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/auto-commit'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentAutoCommit>(response);
+  },
+
+  getBranchEditing: async (
+    projectId: string
+  ): Promise<DocumentBranchEditing> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/branch-editing')
+    );
+    return handleApiResponse<DocumentBranchEditing>(response);
+  },
+
+  /**
+   * Turn branch editing on or off; when on, edits made on main go to a new
+   * `docs/` branch, opened as a pull request with `createPullRequest`
+   */
+  setBranchEditing: async (
+    projectId: string,
+    data: DocumentBranchEditing
+  ): Promise<DocumentBranchEditing> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/branch-editing'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentBranchEditing>(response);
+  },
+
+  /**
+   * Push the current `docs/` branch and open a pull request of it, or get the
+   * open one it already has
+   */
+  createPullRequest: async (
+    projectId: string,
+    data: CreateDocumentPullRequest = { title: null, body: null, draft: false }
+  ): Promise<DocumentPullRequest> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/pull-request'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentPullRequest>(response);
+  },
+
+  /**
+   * Commit all uncommitted document changes with one message
+   */
+  commitPending: async (
+    projectId: string,
+    data: CommitDocumentsRequest
+  ): Promise<CommitDocumentsResponse> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/commit'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<CommitDocumentsResponse>(response);
+  },
+
+  /**
+   * Path globs (e.g. `generated/**`) whose documents can't be changed
+   */
+  getReadOnlyPaths: async (
+    projectId: string
+  ): Promise<DocumentReadOnlyPaths> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        '/read-only-paths'
+      )
+    );
+    return handleApiResponse<DocumentReadOnlyPaths>(response);
+  },
+
+  /**
+   * Replace the project's read-only path globs; an empty list clears them
+   */
+  setReadOnlyPaths: async (
+    projectId: string,
+    data: DocumentReadOnlyPaths
+  ): Promise<DocumentReadOnlyPaths> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        '/read-only-paths'
+      ),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentReadOnlyPaths>(response);
+  },
+
+  /**
+   * Folders the project's documents live in; empty for the whole repository
+   */
+  getRoots: async (projectId: string): Promise<DocumentRoots> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/roots')
+    );
+    return handleApiResponse<DocumentRoots>(response);
+  },
+
+  /**
+   * Replace the project's document folders; listing, creating and moving
+   * documents stay within them
+   */
+  setRoots: async (
+    projectId: string,
+    data: DocumentRoots
+  ): Promise<DocumentRoots> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/roots'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentRoots>(response);
+  },
+
+  /**
+   * Code that documents matching a path glob describe, in addition to the
+   * files they link to
+   */
+  getCodeReferences: async (
+    projectId: string
+  ): Promise<DocumentCodeReferences> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        '/code-references'
+      )
+    );
+    return handleApiResponse<DocumentCodeReferences>(response);
+  },
+
+  /**
+   * Replace the project's code reference mappings; an empty list clears them
+   */
+  setCodeReferences: async (
+    projectId: string,
+    data: DocumentCodeReferences
+  ): Promise<DocumentCodeReferences> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        '/code-references'
+      ),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentCodeReferences>(response);
+  },
+
+  /**
+   * Markdown documents that reference code, flagged as possibly stale when
+   * that code changed on main after them
+   */
+  getFreshnessOverview: async (
+    projectId: string,
+    repositoryId?: string
+  ): Promise<DocumentFreshnessOverview> => {
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, `/freshness${query}`)
+    );
+    return handleApiResponse<DocumentFreshnessOverview>(response);
+  },
+
+  /**
+   * Code a markdown document references and what changed in it since the
+   * document was last updated on main
+   */
+  getFreshness: async (
+    projectId: string,
+    relativePath: string,
+    repositoryId?: string
+  ): Promise<DocumentFreshness> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/freshness${query}`
+      )
+    );
+    return handleApiResponse<DocumentFreshness>(response);
+  },
+
+  /**
+   * Word, character and heading counts and the last commit of every document
+   */
+  getStatsOverview: async (
+    projectId: string,
+    repositoryId?: string
+  ): Promise<DocumentStatsOverview> => {
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, `/stats${query}`)
+    );
+    return handleApiResponse<DocumentStatsOverview>(response);
+  },
+
+  /**
+   * Word, character and heading counts and the last commit of a document
+   */
+  getStats: async (
+    projectId: string,
+    relativePath: string,
+    repositoryId?: string
+  ): Promise<DocumentStats> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/stats${query}`
+      )
+    );
+    return handleApiResponse<DocumentStats>(response);
+  },
+
+  /**
+   * Documents of the same repository that link to a document, with wiki-links
+   * or relative markdown links
+   */
+  getBacklinks: async (
+    projectId: string,
+    relativePath: string,
+    repositoryId?: string
+  ): Promise<DocumentBacklinks> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/backlinks${query}`
+      )
+    );
+    return handleApiResponse<DocumentBacklinks>(response);
+  },
+
+  /**
+   * Tasks the document is related to, e.g. the tasks implementing a design doc
+   */
+  getTasks: async (
+    projectId: string,
+    relativePath: string,
+    repositoryId?: string
+  ): Promise<RelatedTask[]> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/tasks${query}`
+      )
+    );
+    return handleApiResponse<RelatedTask[]>(response);
+  },
+
+  /**
+   * The section of a JSON document at a JSON pointer (e.g. `/items/0`), cut
+   * down to at most `limit` entries per array or object
+   */
+  queryJson: async (
+    projectId: string,
+    relativePath: string,
+    pointer: string,
+    limit?: number,
+    repositoryId?: string
+  ): Promise<JsonDocumentQueryResponse> => {
+    const encodedPath = encodeURIComponent(relativePath);
+    const params = new URLSearchParams({ pointer });
+    if (limit !== undefined) params.set('limit', String(limit));
+    if (repositoryId) params.set('repository_id', repositoryId);
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENTS,
+        { id: projectId },
+        `/${encodedPath}/query?${params.toString()}`
+      )
+    );
+    return handleApiResponse<JsonDocumentQueryResponse>(response);
+  },
+
+  /**
+   * Links between all documents of the project, including wiki-links that
+   * name no document
+   */
+  getLinkGraph: async (
+    projectId: string,
+    repositoryId?: string
+  ): Promise<DocumentLinkGraph> => {
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, `/link-graph${query}`)
+    );
+    return handleApiResponse<DocumentLinkGraph>(response);
+  },
+
+  getBrokenLinks: async (
+    projectId: string,
+    repositoryId?: string
+  ): Promise<BoardLinkReport> => {
+    const query = repositoryId
+      ? `?repository_id=${encodeURIComponent(repositoryId)}`
+      : '';
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, `/broken-links${query}`)
+    );
+    return handleApiResponse<BoardLinkReport>(response);
+  },
+
+  /**
+   * Subscribe to document changes in the project's repositories, including edits
+   * made outside the app. Returns a function that closes the subscription.
+   */
+  subscribeEvents: (
+    projectId: string,
+    onEvent: (event: DocumentEvent) => void
+  ): (() => void) => {
+    const source = new EventSource(
+      apiPath(ROUTES.PROJECT_DOCUMENTS, { id: projectId }, '/events')
+    );
+    const handle = (message: MessageEvent<string>) =>
+      onEvent(JSON.parse(message.data) as DocumentEvent);
+    for (const name of [
+      'document_created',
+      'document_updated',
+      'document_deleted',
+    ]) {
+      source.addEventListener(name, handle);
+    }
+    return () => source.close();
+  },
+};
+
+export const documentSnapshotsApi = {
+  /**
+   * List snapshots of the project's docs branch, newest first
+   */
+  list: async (projectId: string): Promise<DocumentSnapshot[]> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_SNAPSHOTS, { id: projectId })
+    );
+    return handleApiResponse<DocumentSnapshot[]>(response);
+  },
+
+  /**
+   * Snapshot every repository of the project now
+   */
+  create: async (projectId: string): Promise<DocumentSnapshot[]> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_SNAPSHOTS, { id: projectId }),
+      { method: 'POST' }
+    );
+    return handleApiResponse<DocumentSnapshot[]>(response);
+  },
+
+  /**
+   * Documents changed between two snapshots of the same repository
+   */
+  diff: async (
+    projectId: string,
+    fromId: string,
+    toId: string
+  ): Promise<DocumentSnapshotDiff> => {
+    const params = new URLSearchParams({ from: fromId, to: toId });
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENT_SNAPSHOTS,
+        { id: projectId },
+        `/diff?${params.toString()}`
+      )
+    );
+    return handleApiResponse<DocumentSnapshotDiff>(response);
+  },
+};
+
+export const documentApprovalsApi = {
+  /**
+   * Get the project's protected folders and their reviewers
+   */
+  getRules: async (projectId: string): Promise<DocumentApprovalRule[]> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_APPROVALS, { id: projectId }, '/rules')
+    );
+    return handleApiResponse<DocumentApprovalRule[]>(response);
+  },
+
+  /**
+   * Replace the project's protected folders
+   */
+  setRules: async (
+    projectId: string,
+    rules: SetDocumentApprovalRule[]
+  ): Promise<DocumentApprovalRule[]> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_APPROVALS, { id: projectId }, '/rules'),
+      {
+        method: 'PUT',
+        body: JSON.stringify(rules),
+      }
+    );
+    return handleApiResponse<DocumentApprovalRule[]>(response);
+  },
+
+  /**
+   * List change requests, newest first
+   * @param status - Only list change requests in this state
+   */
+  listRequests: async (
+    projectId: string,
+    status?: DocumentChangeStatus
+  ): Promise<DocumentChangeRequestDetails[]> => {
+    const query = status ? `?status=${status}` : '';
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENT_APPROVALS,
+        { id: projectId },
+        `/requests${query}`
+      )
+    );
+    return handleApiResponse<DocumentChangeRequestDetails[]>(response);
+  },
+
+  /**
+   * Approve or reject a change request. The last required approval commits it.
+   */
+  review: async (
+    projectId: string,
+    requestId: string,
+    data: ReviewDocumentChangeRequest
+  ): Promise<DocumentChangeRequestDetails> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENT_APPROVALS,
+        { id: projectId },
+        `/requests/${requestId}/review`
+      ),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentChangeRequestDetails>(response);
+  },
+};
+
+export const documentEncryptionApi = {
+  /**
+   * Get the project's encrypted folders and their readers
+   */
+  getFolders: async (projectId: string): Promise<DocumentEncryptedFolder[]> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_ENCRYPTION, { id: projectId }, '/folders')
+    );
+    return handleApiResponse<DocumentEncryptedFolder[]>(response);
+  },
+
+  /**
+   * Replace the project's encrypted folders. Documents of added folders are
+   * encrypted and those of removed folders decrypted.
+   */
+  setFolders: async (
+    projectId: string,
+    folders: SetDocumentEncryptedFolder[]
+  ): Promise<UpdateEncryptedFoldersResponse> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENT_ENCRYPTION,
+        { id: projectId },
+        '/folders'
+      ),
+      {
+        method: 'PUT',
+        body: JSON.stringify(folders),
+      }
+    );
+    return handleApiResponse<UpdateEncryptedFoldersResponse>(response);
+  },
+
+  /**
+   * List the project's encryption keys, newest first
+   */
+  listKeys: async (projectId: string): Promise<DocumentEncryptionKey[]> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_ENCRYPTION, { id: projectId }, '/keys')
+    );
+    return handleApiResponse<DocumentEncryptionKey[]>(response);
+  },
+
+  /**
+   * Replace the project's key and re-encrypt its encrypted documents
+   */
+  rotateKey: async (
+    projectId: string
+  ): Promise<RotateEncryptionKeyResponse> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENT_ENCRYPTION,
+        { id: projectId },
+        '/keys/rotate'
+      ),
+      { method: 'POST' }
+    );
+    return handleApiResponse<RotateEncryptionKeyResponse>(response);
+  },
+};
+
+export const documentLocksApi = {
+  /**
+   * List the project's unexpired document locks
+   */
+  list: async (projectId: string): Promise<DocumentLock[]> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_LOCKS, { id: projectId })
+    );
+    return handleApiResponse<DocumentLock[]>(response);
+  },
+
+  /**
+   * Lock a document, or extend your own lock. While someone else holds it,
+   * throws with their lock as `error_data`.
+   */
+  acquire: async (
+    projectId: string,
+    data: DocumentLockRequest
+  ): Promise<DocumentLock> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_LOCKS, { id: projectId }),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentLock, DocumentLock>(response);
+  },
+
+  /**
+   * Extend a lock you still hold
+   */
+  renew: async (
+    projectId: string,
+    data: DocumentLockRequest
+  ): Promise<DocumentLock> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_LOCKS, { id: projectId }, '/renew'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentLock>(response);
+  },
+
+  release: async (
+    projectId: string,
+    data: ReleaseDocumentLockRequest
+  ): Promise<void> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_LOCKS, { id: projectId }, '/release'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+export const documentDraftsApi = {
+  /**
+   * List an author's unsaved drafts in the project, most recent first
+   */
+  list: async (
+    projectId: string,
+    author: string
+  ): Promise<DocumentDraft[]> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENT_DRAFTS,
+        { id: projectId },
+        `?author=${encodeURIComponent(author)}`
+      )
+    );
+    return handleApiResponse<DocumentDraft[]>(response);
+  },
+
+  /**
+   * Autosave the author's draft of a document, replacing their previous one
+   */
+  save: async (
+    projectId: string,
+    data: SaveDocumentDraftRequest
+  ): Promise<DocumentDraft> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_DRAFTS, { id: projectId }),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentDraft>(response);
+  },
+
+  /**
+   * The author's draft of a document, with whether the document changed since
+   * the draft was started
+   */
+  restore: async (
+    projectId: string,
+    data: DocumentDraftRequest
+  ): Promise<RestoredDocumentDraft> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_DRAFTS, { id: projectId }, '/restore'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<RestoredDocumentDraft>(response);
+  },
+
+  /**
+   * Save and commit the author's draft as the document, then delete the draft.
+   * The draft is kept when the save is refused, e.g. on a conflict.
+   */
+  publish: async (
+    projectId: string,
+    data: PublishDocumentDraftRequest
+  ): Promise<UpdateDocumentResponse> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_DRAFTS, { id: projectId }, '/publish'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<UpdateDocumentResponse>(response);
+  },
+
+  discard: async (
+    projectId: string,
+    data: DocumentDraftRequest
+  ): Promise<void> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_DRAFTS, { id: projectId }, '/discard'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+export const documentAnnotationsApi = {
+  /**
+   * Annotations of a document, or of every document of the project, in the
+   * order of their lines. Annotations whose text is gone come back detached.
+   */
+  list: async (
+    projectId: string,
+    options: {
+      relativePath?: string;
+      repositoryId?: string;
+      includeResolved?: boolean;
+    } = {}
+  ): Promise<LocatedDocumentAnnotation[]> => {
+    const params = new URLSearchParams();
+    if (options.relativePath) {
+      params.set('relative_path', options.relativePath);
+    }
+    if (options.repositoryId) {
+      params.set('repository_id', options.repositoryId);
+    }
+    if (options.includeResolved) params.set('include_resolved', 'true');
+    const query = params.toString();
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENT_ANNOTATIONS,
+        { id: projectId },
+        query ? `?${query}` : ''
+      )
+    );
+    return handleApiResponse<LocatedDocumentAnnotation[]>(response);
+  },
+
+  create: async (
+    projectId: string,
+    data: CreateDocumentAnnotationRequest
+  ): Promise<LocatedDocumentAnnotation> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_ANNOTATIONS, { id: projectId }),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<LocatedDocumentAnnotation>(response);
+  },
+
+  /**
+   * Edit the body of an annotation, which only its author can do, or resolve
+   * or reopen it
+   */
+  update: async (
+    projectId: string,
+    annotationId: string,
+    data: UpdateDocumentAnnotationRequest
+  ): Promise<DocumentAnnotation> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENT_ANNOTATIONS,
+        { id: projectId },
+        `/${annotationId}`
+      ),
+      {
+        method: 'PATCH',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentAnnotation>(response);
+  },
+
+  remove: async (projectId: string, annotationId: string): Promise<void> => {
+    const response = await makeRequest(
+      apiPath(
+        ROUTES.PROJECT_DOCUMENT_ANNOTATIONS,
+        { id: projectId },
+        `/${annotationId}`
+      ),
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+export const documentTagsApi = {
+  /**
+   * Tags used in the project with their number of documents
+   */
+  list: async (projectId: string): Promise<DocumentTagCount[]> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_TAGS, { id: projectId })
+    );
+    return handleApiResponse<DocumentTagCount[]>(response);
+  },
+
+  /**
+   * Replace the tags of a document
+   */
+  set: async (
+    projectId: string,
+    data: SetDocumentTagsRequest
+  ): Promise<DocumentTags> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_TAGS, { id: projectId }),
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentTags>(response);
+  },
+
+  add: async (
+    projectId: string,
+    data: DocumentTagRequest
+  ): Promise<DocumentTags> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_TAGS, { id: projectId }, '/add'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentTags>(response);
+  },
+
+  remove: async (
+    projectId: string,
+    data: DocumentTagRequest
+  ): Promise<DocumentTags> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_DOCUMENT_TAGS, { id: projectId }, '/remove'),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DocumentTags>(response);
+  },
+};
+
+export const complianceExportApi = {
+  /**
+   * Export the project's committed documents, agent audit log and task
+   * history as a `.tar.gz` bundle with a signed manifest of their hashes
+   */
+  createSigned: async (projectId: string): Promise<Blob> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_EXPORT, { id: projectId }, '/signed'),
+      { method: 'POST' }
+    );
+    return handleFileResponse(response);
+  },
+};
+
+export const webhooksApi = {
+  list: async (projectId: string): Promise<ProjectWebhook[]> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_WEBHOOKS, { id: projectId })
+    );
+    return handleApiResponse<ProjectWebhook[]>(response);
+  },
+
+  /**
+   * Add a URL to call with a payload signed with `secret` when the project's
+   * documents change
+   */
+  create: async (
+    projectId: string,
+    data: CreateProjectWebhook
+  ): Promise<ProjectWebhook> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_WEBHOOKS, { id: projectId }),
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectWebhook>(response);
+  },
+
+  update: async (
+    projectId: string,
+    webhookId: string,
+    data: UpdateProjectWebhook
+  ): Promise<ProjectWebhook> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_WEBHOOKS, { id: projectId }, `/${webhookId}`),
+      {
+        method: 'PATCH',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectWebhook>(response);
+  },
+
+  remove: async (projectId: string, webhookId: string): Promise<void> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.PROJECT_WEBHOOKS, { id: projectId }, `/${webhookId}`),
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+};
+
+export const workersApi = {
+  list: async (): Promise<WorkerInfo[]> => {
+    const response = await makeRequest(apiPath(ROUTES.WORKERS));
+    return handleApiResponse<WorkerInfo[]>(response);
+  },
+
+  /** The returned token is only shown once */
+  register: async (
+    data: RegisterWorkerRequest
+  ): Promise<RegisterWorkerResponse> => {
+    const response = await makeRequest(apiPath(ROUTES.WORKERS), {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<RegisterWorkerResponse>(response);
+  },
+
+  delete: async (workerId: string): Promise<void> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.WORKER, { id: workerId }),
+      { method: 'DELETE' }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  /** A draining worker finishes its execution but gets no new ones */
+  setDraining: async (workerId: string, draining: boolean): Promise<void> => {
+    const response = await makeRequest(
+      apiPath(ROUTES.WORKER_DRAINING, { id: workerId }),
+      {
+        method: 'PUT',
+        body: JSON.stringify({ draining }),
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  listRequirements: async (): Promise<WorkerRequirement[]> => {
+    const response = await makeRequest(apiPath(ROUTES.WORKER_REQUIREMENTS));
+    return handleApiResponse<WorkerRequirement[]>(response);
+  },
+
+  /** Empty capabilities remove the requirement, which then returns null */
+  setRequirement: async (
+    data: SetWorkerRequirement
+  ): Promise<WorkerRequirement | null> => {
+    const response = await makeRequest(apiPath(ROUTES.WORKER_REQUIREMENTS), {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<WorkerRequirement | null>(response);
   },
 };
@@ -0,0 +1,31 @@
+// `vk://task/<task id>` and `vk://doc/<document path>` links point at tasks and
+// documents of the board, optionally followed by a `#fragment`.
+export const BOARD_LINK_SCHEME = 'vk://';
+
+/** App path a `vk://` link leads to, or null if `href` isn't one. */
+export function boardLinkPath(projectId: string, href: string): string | null {
+  if (!href.startsWith(BOARD_LINK_SCHEME)) return null;
+
+  const rest = href.slice(BOARD_LINK_SCHEME.length);
+  const hashIndex = rest.indexOf('#');
+  const target = hashIndex === -1 ? rest : rest.slice(0, hashIndex);
+  const hash = hashIndex === -1 ? '' : rest.slice(hashIndex);
+
+  if (target.startsWith('task/')) {
+    const taskId = target.slice('task/'.length).replace(/\/$/, '');
+    return taskId ? `/projects/${projectId}/tasks/${taskId}${hash}` : null;
+  }
+  if (target.startsWith('doc/')) {
+    let path: string;
+    try {
+      path = decodeURIComponent(target.slice('doc/'.length));
+    } catch {
+      return null;
+    }
+    path = path.replace(/^\/+|\/+$/g, '');
+    return path
+      ? `/projects/${projectId}/documents?path=${encodeURIComponent(path)}${hash}`
+      : null;
+  }
+  return null;
+}
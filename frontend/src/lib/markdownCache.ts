@@ -0,0 +1,92 @@
+import { marked, type Tokens } from 'marked';
+
+export interface TocEntry {
+  depth: number;
+  text: string;
+  slug: string;
+}
+
+export interface RenderedMarkdown {
+  html: string;
+  toc: TocEntry[];
+}
+
+// Rendered output is keyed by the server's `content_hash`, so an entry can
+// never go stale: a write produces a new hash and therefore a new key.
+const MAX_CACHED_BYTES = 32 * 1024 * 1024;
+
+const cache = new Map<string, RenderedMarkdown>();
+let cachedBytes = 0;
+
+function entrySize(entry: RenderedMarkdown): number {
+  return entry.html.length + entry.toc.reduce((n, e) => n + e.text.length, 0);
+}
+
+function slugify(text: string, seen: Map<string, number>): string {
+  const base =
+    text
+      .toLowerCase()
+      .trim()
+      .replace(/[^\p{L}\p{N}\s-]/gu, '')
+      .replace(/\s+/g, '-') || 'section';
+  const count = seen.get(base) ?? 0;
+  seen.set(base, count + 1);
+  return count === 0 ? base : `${base}-${count}`;
+}
+
+function render(content: string): RenderedMarkdown {
+  try {
+    const tokens = marked.lexer(content, { gfm: true, breaks: true });
+    const seen = new Map<string, number>();
+    const toc = tokens
+      .filter((token): token is Tokens.Heading => token.type === 'heading')
+      .map((heading) => ({
+        depth: heading.depth,
+        text: heading.text,
+        slug: slugify(heading.text, seen),
+      }));
+    const html = marked.parser(tokens, { gfm: true, breaks: true });
+    return { html, toc };
+  } catch (error) {
+    console.error('Failed to parse markdown:', error);
+    return { html: `<p>${content}</p>`, toc: [] };
+  }
+}
+
+/** Render markdown, reusing an earlier render of the same `content_hash`. */
+export function renderMarkdown(
+  content: string,
+  contentHash: string
+): RenderedMarkdown {
+  const cached = cache.get(contentHash);
+  if (cached) {
+    // Re-insert so the Map's insertion order doubles as LRU order
+    cache.delete(contentHash);
+    cache.set(contentHash, cached);
+    return cached;
+  }
+
+  const rendered = render(content);
+  const size = entrySize(rendered);
+  if (size > MAX_CACHED_BYTES) {
+    return rendered;
+  }
+
+  cache.set(contentHash, rendered);
+  cachedBytes += size;
+  for (const [key, entry] of cache) {
+    if (cachedBytes <= MAX_CACHED_BYTES) break;
+    cache.delete(key);
+    cachedBytes -= entrySize(entry);
+  }
+  return rendered;
+}
+
+/** Drop the render of a version that has been overwritten. */
+export function evictRenderedMarkdown(contentHash: string) {
+  const entry = cache.get(contentHash);
+  if (entry) {
+    cache.delete(contentHash);
+    cachedBytes -= entrySize(entry);
+  }
+}
@@ -1,5 +1,5 @@
-import { useState, useEffect, useCallback, useRef } from 'react';
-import { useParams, useNavigate } from 'react-router-dom';
+import { useState, useEffect, useCallback, useMemo, useRef } from 'react';
+import { useParams, useNavigate, useSearchParams } from 'react-router-dom';
 import {
   ArrowLeft,
   FileText,
@@ -14,6 +14,13 @@ import {
   Check,
   Upload,
   AlertTriangle,
+  ShieldCheck,
+  ListChecks,
+  ClipboardList,
+  Clock,
+  WrapText,
+  CloudOff,
+  FileWarning,
 } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 import {
@@ -26,12 +33,17 @@ import {
 } from '@/components/ui/dropdown-menu';
 import { documentsApi } from '@/lib/api';
 import { useProjects } from '@/hooks/useProjects';
+import { useUserSystem } from '@/components/ConfigProvider';
 import { TiptapMarkdownViewer } from '@/components/documents/TiptapMarkdownViewer';
 import { JsonTreeView } from '@/components/documents/JsonTreeView';
 import { FolderTree } from '@/components/documents/FolderTree';
+import { DocumentOutline } from '@/components/documents/DocumentOutline';
+import { DocumentBacklinks } from '@/components/documents/DocumentBacklinks';
+import { DocumentApprovalsPanel } from '@/components/documents/DocumentApprovalsPanel';
 import {
   CreateFolderDialog,
   CreateFileDialog,
+  ActionItemsDialog,
 } from '@/components/documents/dialogs';
 import {
   ConceptualModelViewer,
@@ -40,19 +52,37 @@ import {
   NotificationScenariosViewer,
 } from '@/components/documents/viewers';
 import { detectSpecialJsonType } from '@/utils/jsonViewerUtils';
-import type { DocumentMetadata, DocumentContent } from 'shared/types';
+import type {
+  DocumentMetadata,
+  DocumentContent,
+  DocumentHeading,
+  DocumentFreshness,
+  DocumentLinkEdge,
+  DocumentSyncStrategy,
+} from 'shared/types';
 
 type ViewMode = 'content' | 'diagram' | 'tree' | 'raw';
 
 export function DocumentsPage() {
   const { projectId } = useParams<{ projectId: string }>();
   const navigate = useNavigate();
+  // Links from tasks created out of a selection open their document via ?path=
+  const [searchParams] = useSearchParams();
+  const linkedPath = searchParams.get('path');
   const { projectsById } = useProjects();
+  const { loginStatus } = useUserSystem();
+  // Documents are locked under the signed-in user's name while they edit them
+  const lockHolder =
+    loginStatus?.status === 'loggedin'
+      ? (loginStatus.profile.username ?? loginStatus.profile.email)
+      : undefined;
 
   const [documents, setDocuments] = useState<DocumentMetadata[]>([]);
   const [filteredDocuments, setFilteredDocuments] = useState<DocumentMetadata[]>([]);
   const [selectedDoc, setSelectedDoc] = useState<DocumentContent | null>(null);
   const [loading, setLoading] = useState(true);
+  // The document scan hit the project's scan limits, so the list is partial
+  const [truncated, setTruncated] = useState(false);
   const [loadingContent, setLoadingContent] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [searchQuery, setSearchQuery] = useState('');
@@ -68,10 +98,23 @@ export function DocumentsPage() {
     commits_behind: number;
     can_sync: boolean;
     needs_rebase: boolean;
+    strategy: DocumentSyncStrategy;
     error: string | null;
+    degraded: boolean;
   } | null>(null);
   const [syncing, setSyncing] = useState(false);
+  const [syncingTasks, setSyncingTasks] = useState(false);
+  const [extractingActionItems, setExtractingActionItems] = useState(false);
+  const [formatting, setFormatting] = useState(false);
+  // Documents whose referenced code changed on main after them
+  const [freshness, setFreshness] = useState<DocumentFreshness[]>([]);
+  const [backlinks, setBacklinks] = useState<DocumentLinkEdge[]>([]);
+  const [showApprovals, setShowApprovals] = useState(false);
   const initialLoadDone = useRef(false);
+  const contentRef = useRef<HTMLDivElement>(null);
+  const [activeAnchor, setActiveAnchor] = useState<string | null>(null);
+  const linkedPathRef = useRef(linkedPath);
+  linkedPathRef.current = linkedPath;
 
   const project = projectId ? projectsById[projectId] : null;
 
@@ -91,8 +134,11 @@ export function DocumentsPage() {
           // If it's a special JSON type, default to 'diagram' (visualization)
           // Otherwise, default to 'tree'
           setViewMode(specialType !== null ? 'diagram' : 'tree');
-        } else {
+        } else if (content.metadata.file_type === 'markdown') {
           setViewMode('content');
+        } else {
+          // YAML, TOML, plain text, AsciiDoc and reStructuredText are shown as-is
+          setViewMode('raw');
         }
       } catch (err) {
         console.error('Failed to load document:', err);
@@ -104,6 +150,35 @@ export function DocumentsPage() {
     [projectId]
   );
 
+  // The editor renders headings without ids, so they are matched by position
+  const scrollToHeading = useCallback(
+    (heading: DocumentHeading, index: number) => {
+      const headings = contentRef.current?.querySelectorAll(
+        'h1, h2, h3, h4, h5, h6'
+      );
+      headings?.[index]?.scrollIntoView({ behavior: 'smooth', block: 'start' });
+      setActiveAnchor(heading.anchor);
+      window.history.replaceState(
+        null,
+        '',
+        `${window.location.pathname}${window.location.search}#${heading.anchor}`
+      );
+    },
+    []
+  );
+
+  // Deep links: open the section named by the URL's #anchor
+  useEffect(() => {
+    const anchor = decodeURIComponent(window.location.hash.slice(1));
+    const outline = selectedDoc?.outline ?? [];
+    const index = outline.findIndex((heading) => heading.anchor === anchor);
+    if (index < 0) return;
+    const frame = requestAnimationFrame(() =>
+      scrollToHeading(outline[index], index)
+    );
+    return () => cancelAnimationFrame(frame);
+  }, [selectedDoc, scrollToHeading]);
+
   // Load documents list
   useEffect(() => {
     if (!projectId) return;
@@ -114,12 +189,15 @@ export function DocumentsPage() {
       try {
         const response = await documentsApi.list(projectId);
         setDocuments(response.documents);
+        setTruncated(response.truncated);
         setFilteredDocuments(response.documents);
 
         // Auto-select first document (only on initial load)
         if (response.documents.length > 0 && !initialLoadDone.current) {
           initialLoadDone.current = true;
-          loadDocument(response.documents[0].relative_path);
+          if (!linkedPathRef.current) {
+            loadDocument(response.documents[0].relative_path);
+          }
         }
       } catch (err) {
         console.error('Failed to load documents:', err);
@@ -132,6 +210,88 @@ export function DocumentsPage() {
     loadDocuments();
   }, [projectId, loadDocument]);
 
+  useEffect(() => {
+    if (linkedPath) {
+      loadDocument(linkedPath);
+    }
+  }, [linkedPath, loadDocument]);
+
+  // Freshness is judged against main, so it only changes with new commits there
+  const loadFreshness = useCallback(async () => {
+    if (!projectId) return;
+
+    try {
+      const overview = await documentsApi.getFreshnessOverview(projectId);
+      setFreshness(overview.documents);
+    } catch (err) {
+      console.error('Failed to load document freshness:', err);
+      // Non-critical, don't set error
+    }
+  }, [projectId]);
+
+  useEffect(() => {
+    loadFreshness();
+  }, [loadFreshness, currentBranch]);
+
+  const stalePaths = useMemo(
+    () =>
+      new Set(
+        freshness
+          .filter((doc) => doc.possibly_stale)
+          .map((doc) => doc.relative_path)
+      ),
+    [freshness]
+  );
+
+  const selectedPath = selectedDoc?.metadata.relative_path;
+  const selectedRepositoryId = selectedDoc?.metadata.repository_id;
+  const selectedIsMarkdown = selectedDoc?.metadata.file_type === 'markdown';
+
+  useEffect(() => {
+    setBacklinks([]);
+    if (!projectId || !selectedPath || !selectedIsMarkdown) return;
+
+    let cancelled = false;
+    documentsApi
+      .getBacklinks(projectId, selectedPath, selectedRepositoryId)
+      .then((result) => {
+        if (!cancelled) setBacklinks(result.backlinks);
+      })
+      .catch((err) => {
+        console.error('Failed to load backlinks:', err);
+        // Non-critical, don't set error
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [projectId, selectedPath, selectedRepositoryId, selectedIsMarkdown]);
+
+  // Keep the list current when documents change on disk, e.g. edited outside the app
+  useEffect(() => {
+    if (!projectId) return;
+
+    return documentsApi.subscribeEvents(projectId, async (event) => {
+      if (event.kind === 'updated') {
+        setDocuments((current) =>
+          current.map((doc) =>
+            doc.repository_id === event.repository_id &&
+            doc.relative_path === event.relative_path
+              ? { ...doc, size_bytes: event.size_bytes ?? doc.size_bytes }
+              : doc
+          )
+        );
+        return;
+      }
+      try {
+        const response = await documentsApi.list(projectId);
+        setDocuments(response.documents);
+        setTruncated(response.truncated);
+      } catch (err) {
+        console.error('Failed to reload documents:', err);
+      }
+    });
+  }, [projectId]);
+
   // Load current branch info and branch list
   const loadBranchInfo = useCallback(async () => {
     if (!projectId) return;
@@ -164,7 +324,9 @@ export function DocumentsPage() {
         commits_behind: status.commits_behind,
         can_sync: status.can_sync,
         needs_rebase: status.needs_rebase,
+        strategy: status.strategy,
         error: status.error,
+        degraded: status.degraded,
       });
     } catch (err) {
       console.error('Failed to load sync status:', err);
@@ -190,6 +352,7 @@ export function DocumentsPage() {
       const response = await documentsApi.list(projectId);
       setDocuments(response.documents);
       setFilteredDocuments(response.documents);
+      setTruncated(response.truncated);
       // Clear selected document when switching branches
       setSelectedDoc(null);
     } catch (err) {
@@ -220,22 +383,25 @@ export function DocumentsPage() {
     }
   };
 
+  // Diverged branches can't be synced when the project only fast-forwards
+  const needsManualResolution =
+    syncStatus?.strategy === 'fast_forward_only' &&
+    syncStatus.commits_ahead > 0 &&
+    syncStatus.commits_behind > 0;
+
   // Handle sync to origin/main
-  const handleSync = async (allowRebase: boolean = false) => {
+  const handleSync = async (allowPull: boolean = false) => {
     if (!projectId || !isDocsBranch) return;
 
     setSyncing(true);
     setError(null);
     try {
-      const result = await documentsApi.sync(projectId, allowRebase);
+      const result = await documentsApi.sync(projectId, allowPull);
       // Show success message
-      if (result.rebased) {
-        console.log(`Synced ${result.commits_pushed} commits after rebasing`);
-      } else {
-        console.log(`Synced ${result.commits_pushed} commits to origin/main`);
-      }
-      // Refresh sync status
+      console.log(result.message);
+      // Refresh sync status, and freshness since pulling brings in new code
       await loadSyncStatus();
+      await loadFreshness();
     } catch (err) {
       console.error('Failed to sync:', err);
       const errorMessage = err instanceof Error ? err.message : 'Failed to sync documents';
@@ -245,6 +411,109 @@ export function DocumentsPage() {
     }
   };
 
+  // Sync the task list of a planning document (`task_sync: true`) with the board
+  const handleSyncTasks = async () => {
+    if (!projectId || !selectedDoc) return;
+
+    const { relative_path, repository_id } = selectedDoc.metadata;
+    setSyncingTasks(true);
+    setError(null);
+    try {
+      const result = await documentsApi.syncTasks(
+        projectId,
+        relative_path,
+        {
+          create_tasks: true,
+          expected_hash: selectedDoc.content_hash,
+          lock_holder: lockHolder,
+        },
+        repository_id
+      );
+      if (result.content_hash !== selectedDoc.content_hash) {
+        await loadDocument(relative_path);
+      }
+    } catch (err) {
+      console.error('Failed to sync tasks:', err);
+      const errorMessage =
+        err instanceof Error ? err.message : 'Failed to sync the task list';
+      setError(errorMessage);
+    } finally {
+      setSyncingTasks(false);
+    }
+  };
+
+  const handleActionItems = async () => {
+    if (!projectId || !selectedDoc) return;
+
+    const { relative_path, repository_id } = selectedDoc.metadata;
+    setExtractingActionItems(true);
+    setError(null);
+    try {
+      const preview = await documentsApi.extractActionItems(
+        projectId,
+        relative_path,
+        repository_id
+      );
+      const result = await ActionItemsDialog.show({
+        relativePath: relative_path,
+        items: preview.items,
+      });
+      if (result.action !== 'confirmed' || !result.items?.length) return;
+
+      await documentsApi.applyActionItems(
+        projectId,
+        relative_path,
+        {
+          items: result.items,
+          expected_hash: preview.content_hash,
+          lock_holder: lockHolder,
+        },
+        repository_id
+      );
+      await loadDocument(relative_path);
+    } catch (err) {
+      console.error('Failed to create tasks from action items:', err);
+      const errorMessage =
+        err instanceof Error
+          ? err.message
+          : 'Failed to create tasks from action items';
+      setError(errorMessage);
+    } finally {
+      setExtractingActionItems(false);
+    }
+  };
+
+  // Format the document to the project's markdown style and commit the result
+  const handleFormat = async () => {
+    if (!projectId || !selectedDoc) return;
+
+    const { relative_path, repository_id } = selectedDoc.metadata;
+    setFormatting(true);
+    setError(null);
+    try {
+      const result = await documentsApi.formatDocument(
+        projectId,
+        relative_path,
+        {
+          apply: true,
+          expected_hash: selectedDoc.content_hash,
+          lock_holder: lockHolder,
+        },
+        repository_id
+      );
+      if (result.applied) {
+        await loadDocument(relative_path);
+      }
+    } catch (err) {
+      console.error('Failed to format document:', err);
+      const errorMessage =
+        err instanceof Error ? err.message : 'Failed to format the document';
+      setError(errorMessage);
+    } finally {
+      setFormatting(false);
+    }
+  };
+
   // Filter documents by search query
   useEffect(() => {
     if (!searchQuery.trim()) {
@@ -338,6 +607,21 @@ export function DocumentsPage() {
   }, [projectId, getExistingNames, refreshDocuments, loadDocument]);
 
   const isJson = selectedDoc?.metadata.file_type === 'json';
+  const isMarkdown = selectedDoc?.metadata.file_type === 'markdown';
+  const syncsTasks =
+    isMarkdown && selectedDoc?.metadata.front_matter?.task_sync === true;
+  const selectedFreshness = selectedDoc
+    ? freshness.find(
+        (doc) =>
+          doc.possibly_stale &&
+          doc.repository_id === selectedDoc.metadata.repository_id &&
+          doc.relative_path === selectedDoc.metadata.relative_path
+      )
+    : undefined;
+  const staleReferences = selectedFreshness?.references
+    .filter((reference) => reference.changed_files.length > 0)
+    .map((reference) => reference.path)
+    .join('\n');
   const specialJsonType = selectedDoc
     ? detectSpecialJsonType(selectedDoc.metadata.name)
     : null;
@@ -459,11 +743,33 @@ export function DocumentsPage() {
         {/* Spacer */}
         <div className="flex-1" />
 
+        {/* Approvals panel toggle */}
+        <Button
+          size="sm"
+          variant={showApprovals ? 'default' : 'outline'}
+          onClick={() => setShowApprovals((open) => !open)}
+          className="gap-1.5"
+        >
+          <ShieldCheck className="w-3.5 h-3.5" />
+          Approvals
+        </Button>
+
         {/* Sync Status & Button (only on main branch) */}
         {isDocsBranch && (
           <div className="flex items-center gap-2">
             {syncStatus ? (
               <>
+                {/* Remote host unreachable, counts are from the last fetch */}
+                {syncStatus.degraded && (
+                  <div
+                    className="flex items-center gap-1.5 px-2.5 py-1 rounded-md text-sm bg-muted text-muted-foreground"
+                    title="The remote host is unreachable. Counts are from the last successful fetch."
+                  >
+                    <CloudOff className="w-3.5 h-3.5" />
+                    <span className="text-xs">Offline</span>
+                  </div>
+                )}
+
                 {/* Commits ahead badge */}
                 {syncStatus.commits_ahead > 0 && (
                   <div className="flex items-center gap-1.5 px-2.5 py-1 rounded-md text-sm bg-blue-500/10 text-blue-600 dark:text-blue-400">
@@ -490,7 +796,14 @@ export function DocumentsPage() {
                     size="sm"
                     variant={syncStatus.needs_rebase ? 'outline' : 'default'}
                     onClick={() => handleSync(syncStatus.needs_rebase)}
-                    disabled={syncing || !syncStatus.can_sync}
+                    disabled={
+                      syncing || !syncStatus.can_sync || needsManualResolution
+                    }
+                    title={
+                      needsManualResolution
+                        ? 'main and origin/main have diverged and this project only syncs by fast-forward. Merge or rebase by hand.'
+                        : undefined
+                    }
                     className="gap-1.5"
                   >
                     {syncing ? (
@@ -568,9 +881,22 @@ export function DocumentsPage() {
                 formatFileSize={formatFileSize}
                 onCreateFolder={handleCreateFolder}
                 onCreateFile={handleCreateFile}
+                stalePaths={stalePaths}
               />
             )}
           </div>
+          {stalePaths.size > 0 && !loading && (
+            <div className="flex items-center gap-1 px-3 py-2 border-t text-xs text-amber-600">
+              <Clock className="w-3 h-3 shrink-0" />
+              {stalePaths.size} document{stalePaths.size !== 1 ? 's' : ''}{' '}
+              may be out of date with the code they describe
+            </div>
+          )}
+          {truncated && !loading && (
+            <div className="px-3 py-2 border-t text-xs text-muted-foreground">
+              Showing part of the documents: the scan hit this project's limits
+            </div>
+          )}
         </div>
 
         {/* Content Area */}
@@ -592,6 +918,66 @@ export function DocumentsPage() {
                   <span className="font-medium text-sm truncate" title={selectedDoc.metadata.relative_path}>
                     {selectedDoc.metadata.relative_path}
                   </span>
+                  {selectedFreshness && (
+                    <span
+                      className="flex items-center gap-1 px-1.5 py-0.5 rounded bg-amber-100 text-amber-700 text-xs shrink-0"
+                      title={`Changed since this document was last updated:\n${staleReferences}`}
+                    >
+                      <Clock className="w-3 h-3" />
+                      Possibly stale
+                    </span>
+                  )}
+                  {syncsTasks && (
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      className="h-7 text-xs shrink-0"
+                      onClick={handleSyncTasks}
+                      disabled={syncingTasks || !isDocsBranch}
+                      title="Create tasks for unlinked items and sync checkboxes with the board"
+                    >
+                      {syncingTasks ? (
+                        <Loader2 className="w-3 h-3 mr-1 animate-spin" />
+                      ) : (
+                        <ListChecks className="w-3 h-3 mr-1" />
+                      )}
+                      Sync tasks
+                    </Button>
+                  )}
+                  {isMarkdown && (
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      className="h-7 text-xs shrink-0"
+                      onClick={handleActionItems}
+                      disabled={extractingActionItems || !isDocsBranch}
+                      title="Turn the action items of these notes into tasks"
+                    >
+                      {extractingActionItems ? (
+                        <Loader2 className="w-3 h-3 mr-1 animate-spin" />
+                      ) : (
+                        <ClipboardList className="w-3 h-3 mr-1" />
+                      )}
+                      Action items
+                    </Button>
+                  )}
+                  {isMarkdown && (
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      className="h-7 text-xs shrink-0"
+                      onClick={handleFormat}
+                      disabled={formatting || !isDocsBranch}
+                      title="Wrap lines, align tables and normalize headings to the project's style"
+                    >
+                      {formatting ? (
+                        <Loader2 className="w-3 h-3 mr-1 animate-spin" />
+                      ) : (
+                        <WrapText className="w-3 h-3 mr-1" />
+                      )}
+                      Format
+                    </Button>
+                  )}
                 </div>
 
                 <div className="flex items-center gap-1 bg-muted rounded-lg p-1 shrink-0">
@@ -619,7 +1005,7 @@ export function DocumentsPage() {
                         Tree
                       </Button>
                     </>
-                  ) : (
+                  ) : isMarkdown ? (
                     <Button
                       variant={viewMode === 'content' ? 'secondary' : 'ghost'}
                       size="sm"
@@ -629,7 +1015,7 @@ export function DocumentsPage() {
                       <FileText className="w-3 h-3 mr-1" />
                       Content
                     </Button>
-                  )}
+                  ) : null}
                   <Button
                     variant={viewMode === 'raw' ? 'secondary' : 'ghost'}
                     size="sm"
@@ -644,60 +1030,105 @@ export function DocumentsPage() {
 
               {/* Content Body */}
               <div className="flex-1 min-h-0 overflow-hidden">
-                {viewMode === 'content' && !isJson && projectId && (
-                  <div className="h-full overflow-auto p-6">
-                    <TiptapMarkdownViewer
-                      content={selectedDoc.content}
-                      projectId={projectId}
-                      relativePath={selectedDoc.metadata.relative_path}
-                      readOnly={!isDocsBranch}
-                    />
+                {selectedDoc.too_large ? (
+                  <div className="flex items-center justify-center h-full text-muted-foreground">
+                    <div className="text-center">
+                      <FileWarning className="w-12 h-12 mx-auto mb-4 opacity-50" />
+                      <p>This document is too large to open</p>
+                      <p className="text-sm">
+                        {formatFileSize(selectedDoc.metadata.size_bytes)}
+                      </p>
+                    </div>
                   </div>
-                )}
-
-                {viewMode === 'diagram' && isJson && hasCustomViewer && (
+                ) : (
                   <>
-                    {specialJsonType === 'conceptual_model' && (
-                      <ConceptualModelViewer
-                        content={selectedDoc.content}
-                        className="h-full"
-                      />
+                    {viewMode === 'content' && isMarkdown && projectId && (
+                      <div className="h-full flex">
+                        <div
+                          ref={contentRef}
+                          className="flex-1 min-w-0 h-full overflow-auto p-6"
+                        >
+                          <TiptapMarkdownViewer
+                            content={selectedDoc.content}
+                            projectId={projectId}
+                            relativePath={selectedDoc.metadata.relative_path}
+                            contentHash={selectedDoc.content_hash}
+                            readOnly={!isDocsBranch}
+                            lock={selectedDoc.metadata.lock}
+                            lockHolder={lockHolder}
+                          />
+                        </div>
+                        {(selectedDoc.outline.length > 1 ||
+                          backlinks.length > 0) && (
+                          <div className="hidden lg:block w-56 shrink-0 h-full overflow-auto border-l">
+                            {selectedDoc.outline.length > 1 && (
+                              <DocumentOutline
+                                headings={selectedDoc.outline}
+                                activeAnchor={activeAnchor}
+                                onSelectHeading={scrollToHeading}
+                              />
+                            )}
+                            {backlinks.length > 0 && (
+                              <DocumentBacklinks
+                                backlinks={backlinks}
+                                onSelectDocument={loadDocument}
+                                className={
+                                  selectedDoc.outline.length > 1
+                                    ? 'border-t'
+                                    : undefined
+                                }
+                              />
+                            )}
+                          </div>
+                        )}
+                      </div>
                     )}
-                    {specialJsonType === 'user_stories_data' && (
-                      <UserStoriesViewer
-                        content={selectedDoc.content}
-                        className="h-full"
-                      />
+
+                    {viewMode === 'diagram' && isJson && hasCustomViewer && (
+                      <>
+                        {specialJsonType === 'conceptual_model' && (
+                          <ConceptualModelViewer
+                            content={selectedDoc.content}
+                            className="h-full"
+                          />
+                        )}
+                        {specialJsonType === 'user_stories_data' && (
+                          <UserStoriesViewer
+                            content={selectedDoc.content}
+                            className="h-full"
+                          />
+                        )}
+                        {specialJsonType === 'tasks' && (
+                          <TasksViewer
+                            content={selectedDoc.content}
+                            className="h-full"
+                          />
+                        )}
+                        {specialJsonType === 'notification_scenarios' && (
+                          <NotificationScenariosViewer
+                            content={selectedDoc.content}
+                            className="h-full"
+                          />
+                        )}
+                      </>
                     )}
-                    {specialJsonType === 'tasks' && (
-                      <TasksViewer
+
+                    {viewMode === 'tree' && isJson && (
+                      <JsonTreeView
                         content={selectedDoc.content}
                         className="h-full"
                       />
                     )}
-                    {specialJsonType === 'notification_scenarios' && (
-                      <NotificationScenariosViewer
-                        content={selectedDoc.content}
-                        className="h-full"
-                      />
+
+                    {viewMode === 'raw' && (
+                      <div className="h-full overflow-auto p-4">
+                        <pre className="text-sm font-mono bg-muted p-4 rounded-lg overflow-auto whitespace-pre-wrap">
+                          {selectedDoc.content}
+                        </pre>
+                      </div>
                     )}
                   </>
                 )}
-
-                {viewMode === 'tree' && isJson && (
-                  <JsonTreeView
-                    content={selectedDoc.content}
-                    className="h-full"
-                  />
-                )}
-
-                {viewMode === 'raw' && (
-                  <div className="h-full overflow-auto p-4">
-                    <pre className="text-sm font-mono bg-muted p-4 rounded-lg overflow-auto whitespace-pre-wrap">
-                      {selectedDoc.content}
-                    </pre>
-                  </div>
-                )}
               </div>
             </>
           ) : (
@@ -709,6 +1140,18 @@ export function DocumentsPage() {
             </div>
           )}
         </div>
+
+        {/* Approvals Panel */}
+        {showApprovals && projectId && (
+          <DocumentApprovalsPanel
+            projectId={projectId}
+            onClose={() => setShowApprovals(false)}
+            onChangeApplied={() => {
+              if (selectedDoc) loadDocument(selectedDoc.metadata.relative_path);
+              loadSyncStatus();
+            }}
+          />
+        )}
       </div>
     </div>
   );
@@ -4,11 +4,27 @@
 
 // If you are an AI, and you absolutely have to edit this file, please confirm with the user first.
 
-export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, 
+/**
+ * Template projects are blueprints that new projects can be created from
+ */
+is_template: boolean, created_at: Date, updated_at: Date, };
 
 export type CreateProject = { name: string, repositories: Array<CreateProjectRepo>, };
 
-export type UpdateProject = { name: string | null, };
+export type CloneProject = { name: string, repositories: Array<CreateProjectRepo>, 
+/**
+ * Also copy tasks that are not done or cancelled (as new Todo tasks)
+ */
+include_open_tasks: boolean, };
+
+export type CreateProjectFromTemplate = { template_id: string, name: string, repositories: Array<CreateProjectRepo>, 
+/**
+ * Also copy tasks that are not done or cancelled (as new Todo tasks)
+ */
+include_open_tasks: boolean, };
+
+export type UpdateProject = { name: string | null, is_template?: boolean, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, 
 /**
@@ -18,9 +34,25 @@ score: bigint, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
-export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, created_at: Date, updated_at: Date, };
+export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, 
+/**
+ * Builds the repository's web app into a static bundle for preview builds
+ */
+preview_build_script: string | null, 
+/**
+ * Folder the preview build script writes the bundle to, relative to the repository
+ */
+preview_output_dir: string | null, 
+/**
+ * Runs the repository's end-to-end suite against a preview, e.g. `npx playwright test`
+ */
+e2e_script: string | null, 
+/**
+ * Folder the end-to-end suite writes its JUnit reports, traces and videos to
+ */
+e2e_results_dir: string | null, created_at: Date, updated_at: Date, };
 
-export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, };
+export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, preview_build_script?: string | null, preview_output_dir?: string | null, e2e_script?: string | null, e2e_results_dir?: string | null, };
 
 export type ProjectRepo = { id: string, project_id: string, repo_id: string, };
 
@@ -40,15 +72,218 @@ export type UpdateTag = { tag_name: string | null, content: string | null, };
 
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
-export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
+export type TaskPriority = "low" | "medium" | "high" | "urgent";
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
+export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, priority: TaskPriority, 
+/**
+ * Set when the task was bumped by the aging rule, cleared on manual priority changes
+ */
+priority_escalated_at: string | null, created_at: string, updated_at: string, };
+
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, priority: TaskPriority, 
+/**
+ * Set when the task was bumped by the aging rule, cleared on manual priority changes
+ */
+priority_escalated_at: string | null, created_at: string, updated_at: string, };
 
 export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
 
-export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
+export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, priority?: TaskPriority, 
+/**
+ * Reason for creating the task in a column that is at its WIP limit
+ */
+wip_override_reason?: string, };
+
+export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, priority?: TaskPriority, 
+/**
+ * Reason for moving the task into a column that is at its WIP limit
+ */
+wip_override_reason?: string, };
+
+export type ProjectSeedDocument = { id: string, project_id: string, 
+/**
+ * Path relative to the repository root
+ */
+path: string, content: string, created_at: string, updated_at: string, };
+
+export type UpsertProjectSeedDocument = { path: string, content: string, };
+
+export type TaskPackageScope = { task_id: string, repo_id: string, 
+/**
+ * Package directory relative to the repository root
+ */
+package_path: string, package_name: string, created_at: string, updated_at: string, };
+
+export type SetTaskPackageScope = { repo_id: string, package_path: string, };
+
+export type TaskDocument = { id: string, task_id: string, repo_id: string, relative_path: string, created_at: string, };
+
+export type WipLimitScope = "project" | "user";
+
+export type WipLimit = { id: string, 
+/**
+ * `None` for user-wide limits
+ */
+project_id: string | null, status: TaskStatus, max_tasks: bigint, created_at: string, updated_at: string, };
+
+export type SetWipLimit = { status: TaskStatus, 
+/**
+ * `None` removes the limit
+ */
+max_tasks: bigint | null, };
+
+export type WipLimitViolation = { scope: WipLimitScope, status: TaskStatus, max_tasks: bigint, current_count: bigint, };
+
+export type WipLimitOverride = { id: string, project_id: string, task_id: string, from_status: TaskStatus, to_status: TaskStatus, scope: WipLimitScope, max_tasks: bigint, current_count: bigint, reason: string, created_at: string, };
+
+export type WebhookEvent = "document.created" | "document.updated" | "document.deleted" | "documents.synced";
+
+export type ProjectWebhook = { id: string, project_id: string, url: string, 
+/**
+ * Events the webhook is called for; empty for all of them
+ */
+events: Array<WebhookEvent>, enabled: boolean, last_delivery_at: string | null, 
+/**
+ * HTTP status of the last delivery, if the endpoint answered
+ */
+last_status: bigint | null, 
+/**
+ * Why the last delivery failed; `None` if it succeeded
+ */
+last_error: string | null, created_at: string, updated_at: string, };
+
+export type CreateProjectWebhook = { url: string, secret: string, 
+/**
+ * Events to call the webhook for; all of them if empty or left out
+ */
+events?: Array<WebhookEvent>, enabled?: boolean, };
+
+export type UpdateProjectWebhook = { url?: string, secret?: string, events?: Array<WebhookEvent>, enabled?: boolean, };
+
+export type WebhookPayload = { 
+/**
+ * Id of this delivery, the same on retries, so receivers can drop duplicates
+ */
+id: string, event: WebhookEvent, project_id: string, 
+/**
+ * Repository of the document; `None` for `documents.synced`
+ */
+repository_id: string | null, 
+/**
+ * Path of the document from its repository root; `None` for `documents.synced`
+ */
+path: string | null, 
+/**
+ * Commits pushed and pulled by a `documents.synced`
+ */
+commits_pushed: number | null, commits_pulled: number | null, occurred_at: string, };
+
+export type Worker = { id: string, name: string, 
+/**
+ * Capabilities from the worker's last heartbeat, e.g. `docker` or `gpu`
+ */
+capabilities: Array<string>, 
+/**
+ * Whether the worker only finishes what it runs and gets no new executions
+ */
+draining: boolean, 
+/**
+ * When the worker last connected or sent a heartbeat
+ */
+last_seen_at: string | null, created_at: string, };
+
+export type WorkerRequirement = { id: string, task_id: string | null, 
+/**
+ * Executor name, e.g. `CLAUDE_CODE`
+ */
+executor: string | null, capabilities: Array<string>, created_at: string, updated_at: string, };
+
+export type SetWorkerRequirement = { task_id?: string, executor?: string, capabilities: Array<string>, };
+
+export enum PreviewBuildStatus { Running = "running", Succeeded = "succeeded", Failed = "failed" }
+
+export type PreviewBuild = { id: string, workspace_id: string, repo_id: string, status: PreviewBuildStatus, 
+/**
+ * Commit of the worktree the build started from
+ */
+commit_sha: string | null, 
+/**
+ * End of the build script's output
+ */
+output: string, error: string | null, 
+/**
+ * Size of the stored bundle
+ */
+size_bytes: bigint | null, created_at: string, completed_at: string | null, 
+/**
+ * Built from the fork point with the attempt's target branch, to compare the
+ * attempt's preview against
+ */
+base: boolean, };
+
+export type PreviewScreenshot = { id: string, workspace_id: string, 
+/**
+ * Page the screenshot was taken of
+ */
+url: string, 
+/**
+ * Route of the preview the page is at, e.g. `/settings`
+ */
+route: string, 
+/**
+ * Name of the viewport, e.g. `desktop`
+ */
+viewport: string, width: bigint, height: bigint, size_bytes: bigint, created_at: string, };
+
+export type PreviewComparison = { id: string, workspace_id: string, 
+/**
+ * Screenshot of the attempt's preview
+ */
+screenshot_id: string, 
+/**
+ * Page of the base the attempt's page was compared with
+ */
+base_url: string, changed_pixels: bigint, total_pixels: bigint, 
+/**
+ * Blocks of the page with changed pixels, a measure of how much of the layout changed
+ */
+changed_regions: bigint, total_regions: bigint, 
+/**
+ * Whether the pages rendered at different sizes
+ */
+size_changed: boolean, 
+/**
+ * Whether the change is above the threshold the comparison was made with
+ */
+significant: boolean, created_at: string, };
+
+export enum E2eRunStatus { Running = "running", Passed = "passed", Failed = "failed" }
+
+export enum E2eTestStatus { Passed = "passed", Failed = "failed", Skipped = "skipped" }
+
+export type E2eRun = { id: string, workspace_id: string, repo_id: string, status: E2eRunStatus, 
+/**
+ * Preview the suite ran against
+ */
+base_url: string, 
+/**
+ * Commit of the worktree the suite ran from
+ */
+commit_sha: string | null, 
+/**
+ * End of the suite's output
+ */
+output: string, error: string | null, passed_count: bigint, failed_count: bigint, skipped_count: bigint, created_at: string, completed_at: string | null, };
 
-export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
+export type E2eTestCase = { id: string, run_id: string, 
+/**
+ * Spec file or describe block the case is in
+ */
+suite: string, name: string, status: E2eTestStatus, duration_ms: bigint | null, failure_message: string | null, 
+/**
+ * Paths of the case's traces, videos and screenshots in the run's artifacts
+ */
+attachments: Array<string>, };
 
 export type DraftFollowUpData = { message: string, variant: string | null, };
 
@@ -122,7 +357,19 @@ additions: number | null, deletions: number | null, repoId: string | null, };
 
 export type DiffChangeKind = "added" | "deleted" | "modified" | "renamed" | "copied" | "permissionChange";
 
-export type ApiResponse<T, E = T> = { success: boolean, data: T | null, error_data: E | null, message: string | null, };
+export type ApiResponse<T, E = T> = { success: boolean, data: T | null, error_data: E | null, message: string | null, warnings: Array<ApiWarning>, };
+
+export type ApiWarning = { 
+/**
+ * Stable identifier clients can match on, e.g. `auto_commit_failed`
+ */
+code: string, message: string, };
+
+export type LocalizedTime = { utc: string, 
+/**
+ * RFC 3339 with the local offset, e.g. `2026-03-01T09:00:00+01:00`
+ */
+local: string, timezone: string, };
 
 export type LoginStatus = { "status": "loggedout" } | { "status": "loggedin", profile: ProfileResponse, };
 
@@ -132,6 +379,20 @@ export type ProviderProfile = { provider: string, username: string | null, displ
 
 export type StatusResponse = { logged_in: boolean, profile: ProfileResponse | null, degraded: boolean | null, };
 
+export type AuthSessionInfo = { id: string, user_agent: string | null, ip_address: string | null, created_at: string, 
+/**
+ * Day the session was last used, `None` if never used after sign-in
+ */
+last_used_at: string | null, 
+/**
+ * Whether this is the session making the request
+ */
+current: boolean, };
+
+export type ListSessionsResponse = { sessions: Array<AuthSessionInfo>, };
+
+export type RevokeSessionsResponse = { revoked: bigint, };
+
 export enum MemberRole { ADMIN = "ADMIN", MEMBER = "MEMBER" }
 
 export enum InvitationStatus { PENDING = "PENDING", ACCEPTED = "ACCEPTED", DECLINED = "DECLINED", EXPIRED = "EXPIRED" }
@@ -174,317 +435,2103 @@ export type UpdateMemberRoleRequest = { role: MemberRole, };
 
 export type UpdateMemberRoleResponse = { user_id: string, role: MemberRole, };
 
-export type RemoteProject = { id: string, organization_id: string, name: string, color: string, created_at: string, updated_at: string, };
-
-export type ListProjectsResponse = { projects: Array<RemoteProject>, };
-
-export type RemoteProjectMembersResponse = { organization_id: string, members: Array<OrganizationMemberWithProfile>, };
-
-export type CreateRemoteProjectRequest = { organization_id: string, name: string, };
-
-export type LinkToExistingRequest = { remote_project_id: string, };
-
-export type RegisterRepoRequest = { path: string, display_name: string | null, };
-
-export type InitRepoRequest = { parent_path: string, folder_name: string, };
-
-export type TagSearchParams = { search: string | null, };
+export type PermissionResource = "organization" | "project" | "issue";
 
-export type TokenResponse = { access_token: string, expires_at: string | null, };
+export type PermissionAction = "read" | "write" | "manage";
 
-export type UserSystemInfo = { config: Config, analytics_user_id: string, login_status: LoginStatus, environment: Environment, 
+export type PermissionCheckQuery = { resource_type: PermissionResource, resource_id: string, action: PermissionAction, 
 /**
- * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
+ * User to check, defaults to the caller
  */
-capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
-
-export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
-
-export type McpServerQuery = { executor: BaseCodingAgent, };
-
-export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, };
-
-export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, };
-
-export type CheckEditorAvailabilityQuery = { editor_type: EditorType, };
-
-export type CheckEditorAvailabilityResponse = { available: boolean, };
-
-export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
-
-export type CurrentUserResponse = { user_id: string, };
-
-export type CreateFollowUpAttempt = { prompt: string, variant: string | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
-
-export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
-
-export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
-
-export type MergeTaskAttemptRequest = { repo_id: string, };
-
-export type PushTaskAttemptRequest = { repo_id: string, };
-
-export type RenameBranchRequest = { new_branch_name: string, };
-
-export type RenameBranchResponse = { branch: string, };
-
-export type StartReviewRequest = { executor_profile_id: ExecutorProfileId, additional_prompt: string | null, use_all_workspace_commits: boolean, };
-
-export type ReviewError = { "type": "process_already_running" };
-
-export type OpenEditorRequest = { editor_type: string | null, file_path: string | null, };
-
-export type OpenEditorResponse = { url: string | null, };
-
-export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
-
-export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
-
-export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
-
-export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
-
-export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
-
-export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
-
-export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
-
-export type RunAgentSetupResponse = Record<string, never>;
-
-export type GhCliSetupError = "BREW_MISSING" | "SETUP_HELPER_NOT_SUPPORTED" | { "OTHER": { message: string, } };
-
-export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, };
-
-export type AbortConflictsRequest = { repo_id: string, };
-
-export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, } | { "type": "rebase_in_progress" };
+user_id?: string, };
 
-export type PushError = { "type": "force_push_required" };
+export type PermissionCheckResponse = { allowed: boolean, 
+/**
+ * Role of the user in the organization owning the resource, if any
+ */
+role: MemberRole | null, };
 
-export type PrError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "unsupported_provider" };
+export type AccessPrincipal = "user" | "session_token" | "scim_token";
 
-export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+export type AccessReportEntry = { principal: AccessPrincipal, 
 /**
- * True if a `git rebase` is currently in progress in this worktree
+ * User id for users, session id for session tokens, none for the SCIM token
  */
-is_rebase_in_progress: boolean, 
+principal_id: string | null, 
 /**
- * Current conflict operation if any
+ * User the principal is, or acts for
  */
-conflict_op: ConflictOp | null, 
+user_id: string | null, user_email: string | null, organization_id: string, organization_name: string, 
 /**
- * List of files currently in conflicted (unmerged) state
+ * `None` for access to the organization itself
  */
-conflicted_files: Array<string>, };
+project_id: string | null, project_name: string | null, role: MemberRole | null, actions: Array<PermissionAction>, 
+/**
+ * When a token was last used
+ */
+last_used_at: string | null, };
 
-export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
+export type AccessReportResponse = { generated_at: string, 
+/**
+ * Access to the organizations the caller administers; others are left out
+ */
+entries: Array<AccessReportEntry>, };
 
-export type AttachPrResponse = { pr_attached: boolean, pr_url: string | null, pr_number: bigint | null, pr_status: MergeStatus | null, };
+export type RemoteProject = { id: string, organization_id: string, name: string, color: string, created_at: string, updated_at: string, };
 
-export type AttachExistingPrRequest = { repo_id: string, };
+export type ListProjectsResponse = { projects: Array<RemoteProject>, };
 
-export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
+export type RemoteProjectMembersResponse = { organization_id: string, members: Array<OrganizationMemberWithProfile>, };
 
-export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, };
+export type CreateRemoteProjectRequest = { organization_id: string, name: string, };
 
-export type GetPrCommentsQuery = { repo_id: string, };
+export type LinkToExistingRequest = { remote_project_id: string, };
 
-export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
+export type ProjectRepoPackages = { repo_id: string, repo_name: string, packages: Array<WorkspacePackage>, };
 
-export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+export type PackageManager = "cargo" | "pnpm" | "yarn" | "npm" | "go";
 
-export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+export type WorkspacePackage = { 
 /**
- * True if a `git rebase` is currently in progress in this worktree
+ * Package name from its manifest (falls back to the directory name)
  */
-is_rebase_in_progress: boolean, 
+name: string, 
 /**
- * Current conflict operation if any
+ * Path relative to the repository root, using `/` separators
  */
-conflict_op: ConflictOp | null, 
+path: string, manager: PackageManager, 
 /**
- * List of files currently in conflicted (unmerged) state
+ * Commands that are relevant when working inside this package
  */
-conflicted_files: Array<string>, };
-
-export type UpdateWorkspace = { archived: boolean | null, pinned: boolean | null, name: string | null, };
+scripts: Array<string>, };
 
-export type WorkspaceSummaryRequest = { archived: boolean, };
+export type OutdatedDependency = { name: string, current: string, latest: string, manager: PackageManager, 
+/**
+ * Workspace package that declares the dependency, when the repo is a monorepo
+ */
+package_path: string | null, };
 
-export type WorkspaceSummary = { workspace_id: string, 
+export type DependencyUpdateReport = { repo_id: string, repo_name: string, package_path: string | null, package_name: string | null, dependencies: Array<OutdatedDependency>, 
 /**
- * Session ID of the latest execution process
+ * Proposed manifest changes rendered as a unified diff
  */
-latest_session_id: string | null, 
+manifest_diff: string, 
 /**
- * Is a tool approval currently pending?
+ * Tools that could not be run (e.g. `cargo-outdated` not installed)
  */
-has_pending_approval: boolean, 
+skipped: Array<string>, };
+
+export type DependencyUpdateRun = { reports: Array<DependencyUpdateReport>, 
 /**
- * Number of files with changes
+ * Tasks created by this run (existing open update tasks are not duplicated)
  */
-files_changed: number | null, 
+created_tasks: Array<Task>, 
+/**
+ * Workspaces started for created tasks when auto-dispatch is enabled
+ */
+dispatched_workspace_ids: Array<string>, };
+
+export type FlakyTest = { test_name: string, passes: number, failures: number, 
+/**
+ * Number of pass/fail flips observed between consecutive runs on the same code
+ */
+flips: number, 
+/**
+ * Distinct code states on which the test was seen to flip
+ */
+flaky_code_states: number, last_seen_at: string, };
+
+export type FlakyTestReport = { project_id: string, total_results: number, 
+/**
+ * Flaky tests, worst offenders first
+ */
+tests: Array<FlakyTest>, };
+
+export type CreateFlakyTestTasksRequest = { 
+/**
+ * How many of the worst offenders to create tasks for
+ */
+limit: number, };
+
+export type DailyAttemptCount = { 
+/**
+ * `YYYY-MM-DD` in UTC
+ */
+date: string, attempts: bigint, };
+
+export type ExecutorRunStats = { executor: string, variant: string | null, runs: bigint, succeeded: bigint, failed: bigint, 
+/**
+ * Wall-clock time spent in finished runs
+ */
+runtime_seconds: number, };
+
+export type ExecutorSuccessRate = { 
+/**
+ * Succeeded runs divided by finished runs, `None` when no run has finished
+ */
+success_rate: number | null, executor: string, variant: string | null, runs: bigint, succeeded: bigint, failed: bigint, 
+/**
+ * Wall-clock time spent in finished runs
+ */
+runtime_seconds: number, };
+
+export type AnalyticsOverview = { 
+/**
+ * First day of the range (inclusive)
+ */
+from: string, 
+/**
+ * Last day of the range (inclusive)
+ */
+to: string, projects: number, attempts_per_day: Array<DailyAttemptCount>, executors: Array<ExecutorSuccessRate>, 
+/**
+ * Agent runtime in minutes. Token costs are not recorded, so runtime is the spend measure.
+ */
+agent_minutes: number, 
+/**
+ * Distinct documents changed through the document editor
+ */
+docs_edited: number, 
+/**
+ * Tasks currently in progress or in review
+ */
+active_tasks: bigint, };
+
+export type AnalyticsExportFormat = "json" | "csv";
+
+export type AnalyticsOverviewQuery = { 
+/**
+ * First day (inclusive), defaults to 30 days before `to`
+ */
+from: string | null, 
+/**
+ * Last day (inclusive), defaults to today
+ */
+to: string | null, format: AnalyticsExportFormat, };
+
+export type TelemetryEvent = { event: string, 
+/**
+ * Booleans, numbers and allow-listed enum-like strings only
+ */
+properties: Record<string, unknown>, timestamp: string, };
+
+export type TelemetryBatch = { 
+/**
+ * Salted hash of the local user id, not reversible to the machine or user
+ */
+installation_id: string, app_version: string, os: string, events: Array<TelemetryEvent>, };
+
+export type TelemetryPreview = { enabled: boolean, 
+/**
+ * Where the batch would be sent, `None` when no endpoint is configured
+ */
+endpoint: string | null, 
+/**
+ * The exact payload of the next request
+ */
+batch: TelemetryBatch, };
+
+export type RetentionReport = { category: RetentionCategory, 
+/**
+ * Nothing was deleted; the report is of what would be
+ */
+dry_run: boolean, 
+/**
+ * Runs whose logs are past the limit, runs, or artifact files and folders
+ */
+items: bigint, 
+/**
+ * Size of the logs or artifacts; `None` for runs, whose size isn't tracked
+ */
+bytes: bigint | null, 
+/**
+ * Data older than this is past the limit; `None` for artifacts, which are limited
+ * by size
+ */
+cutoff: string | null, };
+
+export type CommitIdentityUse = { project_id: string, committer_name: string, committer_email: string | null, };
+
+export type UserDataExport = { name: string, exported_at: string, 
+/**
+ * Annotations they wrote or resolved
+ */
+annotations: Array<DocumentAnnotation>, reviews: Array<DocumentChangeReview>, 
+/**
+ * Change requests that need or needed their review
+ */
+change_requests: Array<DocumentChangeRequest>, 
+/**
+ * Protected folders they review
+ */
+approval_rules: Array<DocumentApprovalRule>, drafts: Array<DocumentDraft>, locks: Array<DocumentLock>, commit_identities: Array<CommitIdentityUse>, };
+
+export type UserDataErasure = { 
+/**
+ * Pseudonym that replaced the name
+ */
+replacement: string, annotations: bigint, reviews: bigint, change_requests: bigint, approval_rules: bigint, drafts_deleted: bigint, locks_released: bigint, 
+/**
+ * Projects whose commit identity was cleared
+ */
+commit_identities: bigint, };
+
+export type UserDataRequest = { 
+/**
+ * Name the person annotates, reviews and edits documents under
+ */
+name: string, };
+
+export type LogRotation = "hourly" | "daily" | "never";
+
+export type LogFileInfo = { dir: string, rotation: LogRotation, 
+/**
+ * Rotated files kept; 0 keeps them all
+ */
+max_files: number, };
+
+export type LoggingSettings = { 
+/**
+ * Directives the server started with, from `RUST_LOG`
+ */
+base_directives: string, 
+/**
+ * Directives changed at runtime, applied on top of the base ones
+ */
+overrides: string, 
+/**
+ * What logs are filtered with now
+ */
+effective_directives: string, 
+/**
+ * Set when logs are also written to files, with `VK_LOG_DIR`
+ */
+file: LogFileInfo | null, };
+
+export type UpdateLoggingSettings = { 
+/**
+ * Comma-separated filter directives such as `server::routes::documents=debug`; each
+ * replaces the base directive of the same target. Empty goes back to the base ones.
+ */
+overrides: string, };
+
+export type ErrorReport = { id: string, 
+/**
+ * `X-Request-Id` of the request, also on its log lines
+ */
+correlation_id: string, method: string, 
+/**
+ * Route template the request matched, e.g. `/api/projects/{id}`
+ */
+route: string, path: string, status: bigint, 
+/**
+ * Error message of the response
+ */
+message: string | null, 
+/**
+ * Log lines of the request, oldest first
+ */
+log_lines: Array<string>, 
+/**
+ * Summary of the server when the error happened
+ */
+state: JsonValue, 
+/**
+ * When the report was sent to the configured Sentry-compatible endpoint
+ */
+forwarded_at: string | null, created_at: string, };
+
+export type SqlQuery = { sql: string, 
+/**
+ * Bound to the `?` or `?N` placeholders of the query, in order
+ */
+params: Array<JsonValue>, 
+/**
+ * Defaults to 100, at most 1000
+ */
+max_rows?: number, };
+
+export type SqlQueryResult = { 
+/**
+ * Names of the columns, empty when no rows matched
+ */
+columns: Array<string>, 
+/**
+ * Values of each row in the order of the columns. Blobs are given by their size.
+ */
+rows: Array<Array<JsonValue>>, 
+/**
+ * More rows matched than were returned
+ */
+truncated: boolean, };
+
+export type SqlConsoleTable = { name: string, columns: Array<string>, };
+
+export type TaskGroupBy = "status" | "assignee" | "epic" | "package" | "priority";
+
+export type SwimlaneStatusCount = { status: TaskStatus, count: number, };
+
+export type Swimlane = { 
+/**
+ * Stable lane identifier, `None` for tasks without a value for the grouping
+ */
+key: string | null, label: string, count: number, status_counts: Array<SwimlaneStatusCount>, tasks: Array<TaskWithAttemptStatus>, };
+
+export type TaskBoard = { group_by: TaskGroupBy, total: number, lanes: Array<Swimlane>, };
+
+export type RegisterRepoRequest = { path: string, display_name: string | null, };
+
+export type InitRepoRequest = { parent_path: string, folder_name: string, };
+
+export type TagSearchParams = { search: string | null, };
+
+export type TokenResponse = { access_token: string, expires_at: string | null, };
+
+export type UserSystemInfo = { config: Config, analytics_user_id: string, login_status: LoginStatus, environment: Environment, 
+/**
+ * Capabilities supported per executor (e.g., { "CLAUDE_CODE": ["SESSION_FORK"] })
+ */
+capabilities: { [key in string]?: Array<BaseAgentCapability> }, executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
+
+export type Environment = { os_type: string, os_version: string, os_architecture: string, bitness: string, };
+
+export type McpServerQuery = { executor: BaseCodingAgent, };
+
+export type UpdateMcpServersBody = { servers: { [key in string]?: JsonValue }, };
+
+export type GetMcpServerResponse = { mcp_config: McpConfig, config_path: string, };
+
+export type CheckEditorAvailabilityQuery = { editor_type: EditorType, };
+
+export type CheckEditorAvailabilityResponse = { available: boolean, };
+
+export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
+
+export type CurrentUserResponse = { user_id: string, };
+
+export type CreateFollowUpAttempt = { prompt: string, variant: string | null, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
+
+export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
+
+export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
+
+export type MergeTaskAttemptRequest = { repo_id: string, };
+
+export type PushTaskAttemptRequest = { repo_id: string, };
+
+export type RenameBranchRequest = { new_branch_name: string, };
+
+export type RenameBranchResponse = { branch: string, };
+
+export type StartReviewRequest = { executor_profile_id: ExecutorProfileId, additional_prompt: string | null, use_all_workspace_commits: boolean, };
+
+export type ReviewError = { "type": "process_already_running" };
+
+export type OpenEditorRequest = { editor_type: string | null, file_path: string | null, };
+
+export type OpenEditorResponse = { url: string | null, };
+
+export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, 
+/**
+ * Optionally scope the task to a monorepo package before starting it
+ */
+package_scope?: SetTaskPackageScope, };
+
+export type TaskSort = "created_at" | "priority";
+
+export type AttachTaskDocument = { 
+/**
+ * Path of the document relative to its repository
+ */
+path: string, 
+/**
+ * Repository of the document, for projects whose repositories share paths
+ */
+repository_id?: string, };
+
+export type RelatedTask = { 
+/**
+ * Relation of the task to the document
+ */
+link_id: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, priority: TaskPriority, 
+/**
+ * Set when the task was bumped by the aging rule, cleared on manual priority changes
+ */
+priority_escalated_at: string | null, created_at: string, updated_at: string, };
+
+export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, 
+/**
+ * Add the attempt's preview screenshots to the body, for pull requests on GitHub
+ */
+include_screenshots: boolean, };
+
+export type ImageResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
+
+export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
+
+export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, 
+/**
+ * Reason for starting the task while In Progress is at its WIP limit
+ */
+wip_override_reason?: string, };
+
+export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
+
+export type RunAgentSetupRequest = { executor_profile_id: ExecutorProfileId, };
+
+export type RunAgentSetupResponse = Record<string, never>;
+
+export type GhCliSetupError = "BREW_MISSING" | "SETUP_HELPER_NOT_SUPPORTED" | { "OTHER": { message: string, } };
+
+export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: string | null, new_base_branch: string | null, };
+
+export type AbortConflictsRequest = { repo_id: string, };
+
+export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, } | { "type": "rebase_in_progress" };
+
+export type PushError = { "type": "force_push_required" };
+
+export type PrError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "unsupported_provider" } | { "type": "tests_failed", command: string, output: string, };
+
+export type BranchStatus = { commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+/**
+ * True if a `git rebase` is currently in progress in this worktree
+ */
+is_rebase_in_progress: boolean, 
+/**
+ * Current conflict operation if any
+ */
+conflict_op: ConflictOp | null, 
+/**
+ * List of files currently in conflicted (unmerged) state
+ */
+conflicted_files: Array<string>, };
+
+export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
+
+export type AttachPrResponse = { pr_attached: boolean, pr_url: string | null, pr_number: bigint | null, pr_status: MergeStatus | null, };
+
+export type AttachExistingPrRequest = { repo_id: string, };
+
+export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
+
+export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, };
+
+export type GetPrCommentsQuery = { repo_id: string, };
+
+export type PreviewBuildInfo = { 
+/**
+ * Port the bundle is served on, while its server runs
+ */
+port: number | null, id: string, workspace_id: string, repo_id: string, status: PreviewBuildStatus, 
+/**
+ * Commit of the worktree the build started from
+ */
+commit_sha: string | null, 
+/**
+ * End of the build script's output
+ */
+output: string, error: string | null, 
+/**
+ * Size of the stored bundle
+ */
+size_bytes: bigint | null, created_at: string, completed_at: string | null, };
+
+export type PreviewBuildServer = { build_id: string, 
+/**
+ * Port the bundle is served on, over HTTP on this server's host
+ */
+port: number, };
+
+export type Viewport = { 
+/**
+ * Name the screenshot is labelled with, e.g. `desktop`
+ */
+name: string, width: number, height: number, };
+
+export type CapturedPreviewScreenshots = { screenshots: Array<PreviewScreenshot>, 
+/**
+ * Comparisons of the screenshots with the base's, if a base was given
+ */
+comparisons: Array<PreviewComparison>, };
+
+export type E2eArtifactKind = "trace" | "video" | "screenshot" | "report" | "other";
+
+export type E2eArtifact = { 
+/**
+ * Path in the run's artifacts, `/`-separated
+ */
+path: string, kind: E2eArtifactKind, size_bytes: bigint, };
+
+export type StartE2eRunRequest = { 
+/**
+ * Preview build to run the suites against, served if it isn't yet
+ */
+build_id?: string, 
+/**
+ * URL of the preview instead, e.g. of the attempt's dev server
+ */
+url?: string, };
+
+export type E2eRunDetails = { 
+/**
+ * Failed test cases first
+ */
+test_cases: Array<E2eTestCase>, artifacts: Array<E2eArtifact>, id: string, workspace_id: string, repo_id: string, status: E2eRunStatus, 
+/**
+ * Preview the suite ran against
+ */
+base_url: string, 
+/**
+ * Commit of the worktree the suite ran from
+ */
+commit_sha: string | null, 
+/**
+ * End of the suite's output
+ */
+output: string, error: string | null, passed_count: bigint, failed_count: bigint, skipped_count: bigint, created_at: string, completed_at: string | null, };
+
+export type CapturePreviewScreenshotsRequest = { 
+/**
+ * Preview build to take the screenshots of, served if it isn't yet
+ */
+build_id?: string, 
+/**
+ * URL of the preview instead, e.g. of the attempt's dev server
+ */
+url?: string, 
+/**
+ * Routes of the preview to take screenshots of, `/` if none are given
+ */
+routes?: Array<string>, 
+/**
+ * Viewports to take each route at, a desktop and a mobile one if none are given
+ */
+viewports?: Array<Viewport>, 
+/**
+ * Preview build of the attempt's base to compare every screenshot with
+ */
+base_build_id?: string, 
+/**
+ * URL of the base's preview to compare with instead, e.g. a deployment of the
+ * target branch
+ */
+base_url?: string, 
+/**
+ * Share of changed pixels above which a visual change is significant
+ */
+threshold?: number, };
+
+export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
+
+export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+
+export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, 
+/**
+ * True if a `git rebase` is currently in progress in this worktree
+ */
+is_rebase_in_progress: boolean, 
+/**
+ * Current conflict operation if any
+ */
+conflict_op: ConflictOp | null, 
+/**
+ * List of files currently in conflicted (unmerged) state
+ */
+conflicted_files: Array<string>, };
+
+export type UpdateWorkspace = { archived: boolean | null, pinned: boolean | null, name: string | null, };
+
+export type WorkspaceSummaryRequest = { archived: boolean, };
+
+export type WorkspaceSummary = { workspace_id: string, 
+/**
+ * Session ID of the latest execution process
+ */
+latest_session_id: string | null, 
+/**
+ * Is a tool approval currently pending?
+ */
+has_pending_approval: boolean, 
+/**
+ * Number of files with changes
+ */
+files_changed: number | null, 
 /**
  * Total lines added across all files
  */
-lines_added: number | null, 
+lines_added: number | null, 
+/**
+ * Total lines removed across all files
+ */
+lines_removed: number | null, 
+/**
+ * When the latest execution process completed
+ */
+latest_process_completed_at?: string, 
+/**
+ * Status of the latest execution process
+ */
+latest_process_status: ExecutionProcessStatus | null, 
+/**
+ * Is a dev server currently running?
+ */
+has_running_dev_server: boolean, 
+/**
+ * Does this workspace have unseen coding agent turns?
+ */
+has_unseen_turns: boolean, 
+/**
+ * PR status for this workspace (if any PR exists)
+ */
+pr_status: MergeStatus | null, };
+
+export type WorkspaceSummaryResponse = { summaries: Array<WorkspaceSummary>, };
+
+export type DiffStats = { files_changed: number, lines_added: number, lines_removed: number, };
+
+export type DocumentFileType = "markdown" | "json" | "yaml" | "toml" | "text" | "asciidoc" | "rst";
+
+export type DocumentExtensions = { extensions: Array<string>, 
+/**
+ * Every extension that can be enabled
+ */
+supported: Array<string>, 
+/**
+ * True when the project has no allow-list and shows every supported extension
+ */
+is_default: boolean, };
+
+export type UpdateDocumentExtensions = { 
+/**
+ * `null` resets the project to every supported extension
+ */
+extensions: Array<string> | null, };
+
+export type DocumentReadOnlyPaths = { patterns: Array<string>, };
+
+export type DocumentRoots = { 
+/**
+ * Folders relative to the repository root; empty for the whole repository
+ */
+roots: Array<string>, };
+
+export type DocumentReadOnlyViolation = { relative_path: string, 
+/**
+ * The read-only glob the path matched
+ */
+pattern: string, };
+
+export type DocumentScanLimits = { 
+/**
+ * Stop after finding this many documents
+ */
+max_files: number, 
+/**
+ * Don't descend more than this many folders below the repository root
+ */
+max_depth: number, 
+/**
+ * Stop after scanning for this many seconds
+ */
+max_seconds: number, };
+
+export type UpdateDocumentScanLimits = { 
+/**
+ * `null` resets the project to the default limits
+ */
+limits: DocumentScanLimits | null, };
+
+export type DocumentReadLimit = { 
+/**
+ * Largest document, in bytes, returned with its content
+ */
+max_bytes: number, };
+
+export type UpdateDocumentReadLimit = { 
+/**
+ * `null` resets the project to the default limit
+ */
+max_bytes: number | null, };
+
+export type DocumentMetadata = { 
+/**
+ * Repository the document belongs to
+ */
+repository_id: string, 
+/**
+ * File name (just the name, not the path)
+ */
+name: string, 
+/**
+ * Relative path from repo root (e.g., "docs/README.md")
+ */
+relative_path: string, 
+/**
+ * Absolute path on filesystem
+ */
+absolute_path: string, file_type: DocumentFileType, size_bytes: bigint, 
+/**
+ * Fields of the YAML front matter, for markdown documents that have one
+ */
+front_matter: Record<string, JsonValue> | null, 
+/**
+ * Unexpired lock of whoever is editing the document, if any
+ */
+lock: DocumentLock | null, 
+/**
+ * Tags of the document, e.g. `draft` or `adr`, in alphabetical order
+ */
+tags: Array<string>, };
+
+export type DocumentHeading = { 
+/**
+ * 1 for `#` up to 6 for `######`
+ */
+level: number, text: string, 
+/**
+ * Id of the heading in rendered HTML, unique within the document
+ */
+anchor: string, 
+/**
+ * 1-based line of the heading in the document
+ */
+line: number, };
+
+export type TextStats = { words: number, 
+/**
+ * Characters of the words, not counting whitespace
+ */
+characters: number, headings: number, };
+
+export type DocumentContent = { metadata: DocumentMetadata, content: string, 
+/**
+ * SHA-256 of `content`; send it back as `expected_hash` when saving an edit
+ */
+content_hash: string, 
+/**
+ * Commit the content was read from; `None` when read from the working tree
+ */
+revision: string | null, 
+/**
+ * Headings of a markdown document, for a table of contents; empty for other types
+ */
+outline: Array<DocumentHeading>, 
+/**
+ * Whether the document is larger than the project's read limit, so only its
+ * metadata is returned and `content` and `content_hash` are empty
+ */
+too_large: boolean, };
+
+export type ListDocumentsResponse = { documents: Array<DocumentMetadata>, 
+/**
+ * Number of matching documents across all pages
+ */
+total: number, 
+/**
+ * The scan hit one of the project's scan limits, so documents may be missing
+ */
+truncated: boolean, };
+
+export type DocumentTreeNode = { "kind": "folder", name: string, 
+/**
+ * Relative path from repo root (e.g., "docs/guides")
+ */
+relative_path: string, 
+/**
+ * Folders first, then documents, each sorted by name
+ */
+children: Array<DocumentTreeNode>, } | { "kind": "document" } & DocumentMetadata;
+
+export type DocumentTreeResponse = { 
+/**
+ * Top-level folders and documents of the project repositories
+ */
+nodes: Array<DocumentTreeNode>, 
+/**
+ * The scan hit one of the project's scan limits, so documents may be missing
+ */
+truncated: boolean, };
+
+export type UpdateDocumentRequest = { content: string, 
+/**
+ * `content_hash` of the version the edit is based on. May be sent as an `If-Match`
+ * header instead.
+ */
+expected_hash?: string, 
+/**
+ * Name the editor locked the document with. Edits are refused with a 423 while
+ * someone else holds an unexpired lock.
+ */
+lock_holder?: string, };
+
+export type UpdateFrontMatterRequest = { 
+/**
+ * Fields to set; `null` removes a field. Fields not listed are kept as they are.
+ */
+fields: Record<string, JsonValue>, 
+/**
+ * `content_hash` the change is based on. Defaults to the document as it is now,
+ * since the body isn't sent.
+ */
+expected_hash?: string, 
+/**
+ * Name the editor locked the document with, if any
+ */
+lock_holder?: string, };
+
+export type SyncDocumentTasksRequest = { 
+/**
+ * Also create tasks for items that don't link to one yet
+ */
+create_tasks: boolean, 
+/**
+ * `content_hash` of the version the client shows; the sync is refused if it changed
+ */
+expected_hash?: string, 
+/**
+ * Name the document was locked with, if any
+ */
+lock_holder?: string, };
+
+export type SyncDocumentTasksResponse = { 
+/**
+ * Tasks created for items without one
+ */
+created: Array<Task>, 
+/**
+ * Tasks completed or reopened because their item was checked or unchecked
+ */
+updated: Array<Task>, 
+/**
+ * Items checked or unchecked because their task changed on the board
+ */
+checkboxes_changed: number, committed: boolean, content_hash: string, };
+
+export type DocumentUpdateConflict = { 
+/**
+ * Hash of the document as it is now; send it as `expected_hash` to overwrite
+ */
+current_hash: string, current_content: string, };
+
+export type UpdateDocumentResponse = { success: boolean, message: string, 
+/**
+ * The branch where the document was saved
+ */
+branch: string | null, 
+/**
+ * Whether changes were committed
+ */
+committed: boolean, 
+/**
+ * Set when the document is in a protected folder and the edit awaits approval
+ * instead of being saved
+ */
+change_request_id: string | null, 
+/**
+ * Hash of the saved content, to base the next edit on
+ */
+content_hash: string | null, };
+
+export type DocumentCommitPreview = { repository_id: string, 
+/**
+ * The branch the commit would be made on; `None` when the change would await
+ * approval instead
+ */
+branch: string | null, commit_message: string, 
+/**
+ * Unified diff of the change
+ */
+diff: string, };
+
+export type BatchDocumentUpdate = { 
+/**
+ * Relative path of the document; it is created if it doesn't exist
+ */
+path: string, content: string, 
+/**
+ * `content_hash` the new content is based on; required for existing documents
+ */
+expected_hash?: string, 
+/**
+ * Repository of the document, for projects with several. New documents go to the
+ * primary repository by default.
+ */
+repository_id?: string, };
+
+export type BatchUpdateDocumentsRequest = { documents: Array<BatchDocumentUpdate>, 
+/**
+ * Commit message; by default it names the document or counts the documents
+ */
+message?: string, 
+/**
+ * Name the documents were locked with, if any
+ */
+lock_holder?: string, };
+
+export type BatchUpdatedDocument = { repository_id: string, relative_path: string, created: boolean, content_hash: string, };
+
+export type BatchUpdateDocumentsResponse = { documents: Array<BatchUpdatedDocument>, 
+/**
+ * Whether every repository's commit was made
+ */
+committed: boolean, };
+
+export type BatchDocumentConflict = { repository_id: string, relative_path: string, 
+/**
+ * `None` if the document doesn't exist
+ */
+current_hash: string | null, };
+
+export type BatchUpdateConflict = { conflicts: Array<BatchDocumentConflict>, };
+
+export type ImportDocumentsResponse = { repository_id: string, 
+/**
+ * Relative paths of the imported documents
+ */
+documents: Array<string>, branch: string, committed: boolean, };
+
+export type CreateDocumentTaskRequest = { 
+/**
+ * First selected line, starting at 1
+ */
+start_line: number, 
+/**
+ * Last selected line, inclusive
+ */
+end_line: number, 
+/**
+ * Defaults to the first line of the selection
+ */
+title?: string, 
+/**
+ * Also insert a link to the new task below the selection and commit it
+ */
+insert_marker: boolean, 
+/**
+ * `content_hash` of the version the line numbers refer to. Required with
+ * `insert_marker`.
+ */
+expected_hash?: string, 
+/**
+ * Name the editor locked the document with, if any
+ */
+lock_holder?: string, };
+
+export type CreateDocumentTaskResponse = { task: Task, 
+/**
+ * Whether the link to the task was inserted into the document
+ */
+marker_inserted: boolean, 
+/**
+ * Whether the inserted link was committed
+ */
+committed: boolean, 
+/**
+ * Hash of the document after the link was inserted
+ */
+content_hash: string | null, };
+
+export type ActionItem = { title: string, 
+/**
+ * Name mentioned with `@`, without the `@`
+ */
+owner: string | null, due_date: string | null, 
+/**
+ * 1-based line of the notes the item was found on; `None` for items added by hand
+ */
+line: number | null, };
+
+export type ProposedActionItem = { 
+/**
+ * When the due date ends in the project's timezone
+ */
+due_at: LocalizedTime | null, title: string, 
+/**
+ * Name mentioned with `@`, without the `@`
+ */
+owner: string | null, due_date: string | null, 
+/**
+ * 1-based line of the notes the item was found on; `None` for items added by hand
+ */
+line: number | null, };
+
+export type ActionItemsPreview = { relative_path: string, items: Array<ProposedActionItem>, 
+/**
+ * Pass back as `expected_hash` when applying the confirmed items
+ */
+content_hash: string, };
+
+export type ApplyActionItemsRequest = { 
+/**
+ * The confirmed items, possibly edited or added to by the user
+ */
+items: Array<ActionItem>, 
+/**
+ * `content_hash` of the version the items were proposed for
+ */
+expected_hash: string, 
+/**
+ * Name the document was locked with, if any
+ */
+lock_holder?: string, };
+
+export type ApplyActionItemsResponse = { tasks: Array<Task>, committed: boolean, content_hash: string, };
+
+export type DeleteDocumentResponse = { success: boolean, message: string, 
+/**
+ * The branch the deletion was made on
+ */
+branch: string | null, 
+/**
+ * Whether the deletion was committed
+ */
+committed: boolean, };
+
+export type FileCommit = { hash: string, author_name: string, author_email: string, date: Date, message: string, };
+
+export type PathChangeKind = "added" | "modified" | "deleted";
+
+export type PathChange = { 
+/**
+ * Path relative to the repository root, `/`-separated
+ */
+path: string, kind: PathChangeKind, };
+
+export type DocumentHistoryResponse = { relative_path: string, commits: Array<FileCommit>, };
+
+export type DocumentDiffResponse = { relative_path: string, 
+/**
+ * Resolved commit of `from`
+ */
+from_revision: string, 
+/**
+ * Resolved commit of `to`; `None` for the working tree
+ */
+to_revision: string | null, old_content: string | null, new_content: string | null, 
+/**
+ * Full unified diff with `a/` and `b/` headers
+ */
+unified_diff: string, 
+/**
+ * Individual `@@` hunks of `unified_diff`
+ */
+hunks: Array<string>, additions: number, deletions: number, };
+
+export type DocumentHtml = { relative_path: string, 
+/**
+ * Body of the document without its front matter. Relative links to other documents
+ * point at the documents page.
+ */
+html: string, 
+/**
+ * Stylesheet for the `hl-` classes of highlighted code blocks
+ */
+highlight_css: string, content_hash: string, };
+
+export type DocumentCodeReference = { 
+/**
+ * Glob of the documents, e.g. `docs/architecture/**`
+ */
+documents: string, 
+/**
+ * Files or folders relative to the repository root, e.g. `crates/server`
+ */
+code: Array<string>, };
+
+export type DocumentCodeReferences = { mappings: Array<DocumentCodeReference>, };
+
+export type DocumentReferenceSource = "link" | "mapping";
+
+export type DocumentReference = { 
+/**
+ * File or folder relative to the repository root
+ */
+path: string, source: DocumentReferenceSource, 
+/**
+ * Files at or below `path` that changed on main since the document last did
+ */
+changed_files: Array<PathChange>, };
+
+export type DocumentFreshness = { repository_id: string, relative_path: string, 
+/**
+ * Last commit on main that changed the document; `None` if it was never committed
+ */
+last_updated: FileCommit | null, references: Array<DocumentReference>, 
+/**
+ * Referenced code changed on main after the document did
+ */
+possibly_stale: boolean, };
+
+export type DocumentFreshnessOverview = { 
+/**
+ * Markdown documents that reference code, possibly stale ones first
+ */
+documents: Array<DocumentFreshness>, stale_count: number, 
+/**
+ * The document scan hit its limits, so some documents may be missing
+ */
+truncated: boolean, };
+
+export type DocumentLinkKind = "wiki" | "markdown";
+
+export type DocumentLinkEdge = { repository_id: string, 
+/**
+ * Path of the linking document
+ */
+source: string, 
+/**
+ * Path of the linked document, or the name as written for unresolved wiki-links
+ */
+target: string, kind: DocumentLinkKind, };
+
+export type DocumentBacklinks = { repository_id: string, relative_path: string, 
+/**
+ * Links from other documents to this one, sorted by source
+ */
+backlinks: Array<DocumentLinkEdge>, };
+
+export type DocumentLinkNode = { repository_id: string, relative_path: string, file_type: DocumentFileType, };
+
+export type DocumentLinkGraph = { 
+/**
+ * Markdown documents, plus other documents they link to
+ */
+nodes: Array<DocumentLinkNode>, edges: Array<DocumentLinkEdge>, 
+/**
+ * Wiki-links that name no document of their repository
+ */
+unresolved: Array<DocumentLinkEdge>, 
+/**
+ * The document scan hit its limits, so some documents may be missing
+ */
+truncated: boolean, };
+
+export type BrokenBoardLinkReason = "invalid" | "not_found" | "other_project";
+
+export type BrokenBoardLink = { 
+/**
+ * The link as written
+ */
+href: string, reason: BrokenBoardLinkReason, };
+
+export type BoardLinkSource = { "type": "document", repository_id: string, relative_path: string, } | { "type": "task", task_id: string, title: string, };
+
+export type BrokenBoardLinks = { source: BoardLinkSource, links: Array<BrokenBoardLink>, };
+
+export type BoardLinkReport = { 
+/**
+ * Documents and tasks with broken `vk://` links, documents first
+ */
+broken: Array<BrokenBoardLinks>, 
+/**
+ * The document scan hit its limits, so some documents were not checked
+ */
+truncated: boolean, };
+
+export type DocumentHeadingStyle = "atx" | "setext";
+
+export type DocumentFormatOptions = { 
+/**
+ * Wrap paragraphs and list items at this many characters; `None` leaves lines as
+ * they are
+ */
+line_width: number | null, heading_style: DocumentHeadingStyle, 
+/**
+ * Pad table cells so the columns line up
+ */
+align_tables: boolean, };
+
+export type MarkdownLintRule = "heading_style" | "table_alignment" | "line_width" | "trailing_whitespace" | "blank_lines" | "final_newline";
+
+export type MarkdownLintIssue = { 
+/**
+ * 1-based line of the unformatted document
+ */
+line: number, rule: MarkdownLintRule, message: string, };
+
+export type FormatDocumentRequest = { 
+/**
+ * Options for this run instead of the project's
+ */
+options?: DocumentFormatOptions, 
+/**
+ * Write the formatted content back and commit it, instead of only returning it
+ */
+apply: boolean, 
+/**
+ * `content_hash` of the version being formatted; required with `apply`
+ */
+expected_hash?: string, 
+/**
+ * Name the document was locked with, if any
+ */
+lock_holder?: string, };
+
+export type FormatDocumentResponse = { relative_path: string, content: string, content_hash: string, 
+/**
+ * Style issues of the document before formatting, all fixed in `content`
+ */
+issues: Array<MarkdownLintIssue>, changed: boolean, 
+/**
+ * The formatted content was written to the document
+ */
+applied: boolean, committed: boolean, };
+
+export type UpdateDocumentFormatOptions = { 
+/**
+ * `null` resets the project to the default options
+ */
+options: DocumentFormatOptions | null, };
+
+export type VersionInfo = { version: string, 
+/**
+ * Changes whenever the embedded frontend is rebuilt; `None` when no frontend is bundled
+ */
+frontend_build: string | null, };
+
+export type UpdateCheck = { 
+/**
+ * False when no feed or public key is configured
+ */
+enabled: boolean, current_version: string, latest_version: string | null, update_available: boolean, notes_url: string | null, };
+
+export type UpdateInstallResponse = { version: string, 
+/**
+ * Attempts still running; the restart waits for them to finish
+ */
+draining_processes: number, };
+
+export type OnboardingStepStatus = { step: OnboardingStep, completed: boolean, 
+/**
+ * What was detected, e.g. the configured git identity or available agents
+ */
+detail: string | null, };
+
+export type OnboardingStatus = { steps: Array<OnboardingStepStatus>, 
+/**
+ * First step that is not completed yet
+ */
+next_step: OnboardingStep | null, completed: boolean, };
+
+export type SetGitIdentityRequest = { name: string, email: string, };
+
+export type StartSampleAttemptRequest = { 
+/**
+ * Defaults to the most recently created project
+ */
+project_id: string | null, 
+/**
+ * Defaults to the configured executor profile
+ */
+executor_profile_id: ExecutorProfileId | null, };
+
+export type SampleAttemptResponse = { task: Task, workspace_id: string, };
+
+export type CreateDemoProject = { 
+/**
+ * Defaults to "Acme Notes (demo)"
+ */
+name: string | null, 
+/**
+ * Also create a finished attempt with a scripted change, ready for review
+ */
+with_attempt: boolean, };
+
+export type DemoProject = { project: Project, tasks: Array<Task>, repo_path: string, workspace_id: string | null, };
+
+export type GetBranchResponse = { 
+/**
+ * Current branch name of the primary repository
+ */
+branch: string, 
+/**
+ * Whether this is the expected docs branch (main)
+ */
+is_docs_branch: boolean, };
+
+export type ListBranchesResponse = { 
+/**
+ * List of branch names
+ */
+branches: Array<BranchInfo>, 
+/**
+ * Current branch name
+ */
+current_branch: string, };
+
+export type BranchInfo = { name: string, is_current: boolean, is_remote: boolean, };
+
+export type SwitchBranchRequest = { branch: string, };
+
+export type SwitchBranchResponse = { success: boolean, branch: string, message: string, 
+/**
+ * Whether changes were stashed during the switch
+ */
+stashed: boolean, };
+
+export type CreateFolderRequest = { 
+/**
+ * Relative path for the new folder (e.g., "seed_docs/subfolder")
+ */
+path: string, };
+
+export type CreateFolderResponse = { success: boolean, message: string, path: string, };
+
+export type DeleteFolderResponse = { path: string, 
+/**
+ * Documents that were deleted with the folder
+ */
+deleted_documents: Array<string>, 
+/**
+ * The branch the deletion was made on
+ */
+branch: string, 
+/**
+ * Whether the deletion was committed
+ */
+committed: boolean, };
+
+export type CreateFileRequest = { 
+/**
+ * Relative path for the new file (e.g., "seed_docs/new-doc.md")
+ */
+path: string, 
+/**
+ * Optional initial content
+ */
+content: string | null, 
+/**
+ * Template to take the initial content from instead, see [`document_templates`]
+ */
+template_id?: string, 
+/**
+ * Values of the template's placeholders
+ */
+variables?: { [key in string]?: string }, };
+
+export type CreateFileResponse = { success: boolean, message: string, metadata: DocumentMetadata, 
+/**
+ * The branch where the file was created
+ */
+branch: string | null, 
+/**
+ * Whether changes were committed
+ */
+committed: boolean, };
+
+export type CopyDocumentRequest = { 
+/**
+ * Relative path of the copy (e.g., "docs/sprints/sprint-13.md")
+ */
+to: string, };
+
+export type MoveDocumentRequest = { 
+/**
+ * Current relative path (e.g., "docs/old-name.md")
+ */
+from: string, 
+/**
+ * New relative path (e.g., "docs/guides/new-name.md")
+ */
+to: string, };
+
+export type MoveFolderRequest = { 
+/**
+ * Current relative path (e.g., "docs/guides")
+ */
+from: string, 
+/**
+ * New relative path (e.g., "docs/handbook/guides")
+ */
+to: string, };
+
+export type MovedDocument = { from: string, to: string, };
+
+export type MoveFolderResponse = { from: string, to: string, 
+/**
+ * Documents that moved with the folder
+ */
+moved_documents: Array<MovedDocument>, 
+/**
+ * The branch where the move was made
+ */
+branch: string, 
+/**
+ * Whether the move was committed
+ */
+committed: boolean, };
+
+export type MoveDocumentResponse = { success: boolean, message: string, metadata: DocumentMetadata, 
+/**
+ * The branch where the move was made
+ */
+branch: string | null, 
+/**
+ * Whether the move was committed
+ */
+committed: boolean, };
+
+export type RefreshDocumentsRequest = { 
+/**
+ * Commit the detected changes
+ */
+commit: boolean, 
+/**
+ * Commit message (defaults to "docs: sync external changes")
+ */
+message: string | null, };
+
+export type RefreshDocumentsResponse = { 
+/**
+ * Documents added, modified or deleted outside the app since the last commit
+ */
+changes: Array<PathChange>, 
+/**
+ * Whether the changes were committed
+ */
+committed: boolean, };
+
+export type SyncStatusResponse = { 
+/**
+ * Number of commits ahead of origin/main (local changes not pushed)
+ */
+commits_ahead: number, 
+/**
+ * Number of commits behind origin/main (remote changes not pulled)
+ */
+commits_behind: number, 
+/**
+ * Whether sync is possible (on main branch)
+ */
+can_sync: boolean, 
+/**
+ * Whether remote commits have to be pulled before pushing
+ */
+needs_rebase: boolean, 
+/**
+ * How remote commits are pulled
+ */
+strategy: DocumentSyncStrategy, 
+/**
+ * Current branch name
+ */
+current_branch: string, 
+/**
+ * Error message if any
+ */
+error: string | null, 
+/**
+ * Whether origin's host is unreachable, so the counts are as of the last successful
+ * fetch
+ */
+degraded: boolean, };
+
+export type ProjectDocumentSyncStatus = { project_id: string, project_name: string, 
+/**
+ * Branch the primary repository was on at the last check, `None` if it hasn't been
+ * checked yet
+ */
+current_branch: string | null, commits_ahead: number, commits_behind: number, fetched_at: string | null, last_synced_at: string | null, 
+/**
+ * `fetched_at` in the project's timezone
+ */
+fetched_at_local: LocalizedTime | null, 
+/**
+ * `last_synced_at` in the project's timezone
+ */
+last_synced_at_local: LocalizedTime | null, 
+/**
+ * Error of the last fetch or sync, `None` if it succeeded
+ */
+last_error: string | null, 
+/**
+ * Whether origin's host is unreachable right now, so the drift is as of the last
+ * successful fetch
+ */
+degraded: boolean, };
+
+export type ProjectTimezone = { 
+/**
+ * The project's own timezone, `None` if it uses the user's
+ */
+timezone: string | null, 
+/**
+ * Timezone the project's schedules and due dates follow
+ */
+effective: string, now: LocalizedTime, };
+
+export type UpdateProjectTimezone = { 
+/**
+ * IANA name such as `Europe/Paris` or a UTC offset; `None` to use the user's
+ */
+timezone: string | null, };
+
+export type ApiQuotaUsage = { 
+/**
+ * Requests allowed per minute; `None` when unlimited
+ */
+limit: number | null, used: number, remaining: number | null, 
+/**
+ * Seconds until the window ends and the count starts over
+ */
+resets_in_secs: bigint, };
+
+export type BackgroundJobUsage = { running: number, waiting: number, 
+/**
+ * Jobs the project may run at once
+ */
+limit: number, };
+
+export type ProjectUsage = { api: ApiQuotaUsage, background_jobs: BackgroundJobUsage, };
+
+export type ProjectQuotaExceeded = { message: string, 
+/**
+ * Requests the project may make per minute
+ */
+limit: number, 
+/**
+ * Seconds until the project may make requests again
+ */
+retry_after_secs: bigint, };
+
+export type SyncRequest = { 
+/**
+ * If true, pulls remote commits with the project's sync strategy before pushing
+ * when behind origin
+ */
+allow_pull: boolean, };
+
+export type SyncResponse = { success: boolean, commits_pushed: number, 
+/**
+ * Number of remote commits pulled before pushing
+ */
+commits_pulled: number, message: string, 
+/**
+ * Strategy the remote commits were pulled with
+ */
+strategy: DocumentSyncStrategy, 
+/**
+ * Whether rebase was performed
+ */
+rebased: boolean, };
+
+export type DocumentSyncStrategy = "rebase" | "merge" | "fast_forward_only";
+
+export type UpdateDocumentSyncStrategy = { strategy: DocumentSyncStrategy, };
+
+export type DocumentSymlinkPolicy = "skip" | "follow_within_repo";
+
+export type UpdateDocumentSymlinkPolicy = { policy: DocumentSymlinkPolicy, };
+
+export type DocumentCommitSettings = { 
+/**
+ * Template for commit messages with `{message}`, `{path}` and `{user}` placeholders,
+ * e.g. `docs({user}): {message}`; `None` keeps the default messages
+ */
+message_template: string | null, 
+/**
+ * Identity to commit as; `None` uses the repository's configured identity
+ */
+committer_name: string | null, committer_email: string | null, };
+
+export type UpdateDocumentCommitSettings = { 
+/**
+ * `null` resets the project to the default messages and identity
+ */
+settings: DocumentCommitSettings | null, };
+
+export type DocumentAutoCommit = { 
+/**
+ * Whether document saves are committed right away. When off, changes stay
+ * uncommitted until they are committed with `POST /documents/commit`.
+ */
+auto_commit: boolean, };
+
+export type DocumentBranchEditing = { 
+/**
+ * Whether edits made on main go to a new `docs/` branch instead, to be merged
+ * through a pull request
+ */
+enabled: boolean, };
+
+export type CreateDocumentPullRequest = { 
+/**
+ * Defaults to a title naming the changed documents
+ */
+title: string | null, body: string | null, draft: boolean, };
+
+export type DocumentPullRequest = { branch: string, number: bigint, url: string, 
+/**
+ * False when the branch already had an open pull request, which is returned instead
+ */
+created: boolean, };
+
+export type CommitDocumentsRequest = { message: string, };
+
+export type CommitDocumentsResponse = { 
+/**
+ * Documents that were committed
+ */
+changes: Array<PathChange>, 
+/**
+ * Whether a commit was made
+ */
+committed: boolean, };
+
+export type JsonValueType = "object" | "array" | "string" | "number" | "boolean" | "null";
+
+export type JsonDocumentQueryResponse = { relative_path: string, pointer: string, value_type: JsonValueType, 
+/**
+ * Entries of an object or array, or characters of a string, before truncation
+ */
+length: number | null, 
+/**
+ * The section, with at most `limit` entries per array or object and long strings
+ * cut short so it stays small
+ */
+value: unknown, 
+/**
+ * Whether anything of the section was left out of `value`
+ */
+truncated: boolean, content_hash: string, };
+
+export type DocumentSnapshot = { id: string, project_id: string, repo_id: string, branch: string, commit_sha: string, created_at: string, };
+
+export type DocumentSnapshotDiff = { from: DocumentSnapshot, to: DocumentSnapshot, changes: Array<PathChange>, };
+
+export type DocumentApprovalRule = { id: string, project_id: string, 
+/**
+ * Folder relative to the repository root, `/`-separated and without trailing slash
+ */
+folder: string, reviewers: Array<string>, created_at: string, updated_at: string, };
+
+export type SetDocumentApprovalRule = { folder: string, reviewers: Array<string>, };
+
+export type DocumentChangeStatus = "pending" | "approved" | "rejected";
+
+export type DocumentReviewDecision = "approved" | "rejected";
+
+export type DocumentChangeRequest = { id: string, project_id: string, repo_id: string, relative_path: string, 
+/**
+ * Proposed content of the document
+ */
+content: string, required_reviewers: Array<string>, status: DocumentChangeStatus, 
+/**
+ * The commit that applied the change, once approved
+ */
+commit_sha: string | null, created_at: string, updated_at: string, };
+
+export type DocumentChangeReview = { id: string, change_request_id: string, reviewer: string, decision: DocumentReviewDecision, comment: string | null, created_at: string, };
+
+export type ReviewDocumentChangeRequest = { 
 /**
- * Total lines removed across all files
+ * Must be one of the change's required reviewers
  */
-lines_removed: number | null, 
+reviewer: string, decision: DocumentReviewDecision, comment: string | null, };
+
+export type DocumentChangeRequestDetails = { reviews: Array<DocumentChangeReview>, 
 /**
- * When the latest execution process completed
+ * Required reviewers who have not approved yet
  */
-latest_process_completed_at?: string, 
+awaiting: Array<string>, id: string, project_id: string, repo_id: string, relative_path: string, 
 /**
- * Status of the latest execution process
+ * Proposed content of the document
  */
-latest_process_status: ExecutionProcessStatus | null, 
+content: string, required_reviewers: Array<string>, status: DocumentChangeStatus, 
 /**
- * Is a dev server currently running?
+ * The commit that applied the change, once approved
  */
-has_running_dev_server: boolean, 
+commit_sha: string | null, created_at: string, updated_at: string, };
+
+export type DocumentAssetResponse = { repository_id: string, 
 /**
- * Does this workspace have unseen coding agent turns?
+ * Path of the image in the repository
  */
-has_unseen_turns: boolean, 
+relative_path: string, 
 /**
- * PR status for this workspace (if any PR exists)
+ * Link to the image, relative to the document
  */
-pr_status: MergeStatus | null, };
+link: string, 
+/**
+ * Markdown image to insert into the document
+ */
+markdown: string, 
+/**
+ * False if the same image was already stored for the document's folder
+ */
+created: boolean, committed: boolean, };
 
-export type WorkspaceSummaryResponse = { summaries: Array<WorkspaceSummary>, };
+export type DocumentAssetsFolder = { 
+/**
+ * Folder next to a document its images are stored in
+ */
+folder: string, };
 
-export type DiffStats = { files_changed: number, lines_added: number, lines_removed: number, };
+export type UpdateDocumentAssetsFolder = { 
+/**
+ * `null` resets the project to the default folder
+ */
+folder: string | null, };
+
+export type DocumentTemplate = { 
+/**
+ * Path of the template inside the templates folder, e.g. `adr/default.md`
+ */
+id: string, 
+/**
+ * Name derived from the file name, e.g. `Default`
+ */
+name: string, file_type: DocumentFileType, 
+/**
+ * Placeholders other than `title`, `date` and `author` the template uses
+ */
+variables: Array<string>, };
 
-export type DocumentFileType = "markdown" | "json";
+export type DocumentTemplatesFolder = { 
+/**
+ * Folder of the primary repository templates are read from
+ */
+folder: string, };
 
-export type DocumentMetadata = { 
+export type UpdateDocumentTemplatesFolder = { 
 /**
- * File name (just the name, not the path)
+ * `null` resets the project to the default folder
  */
-name: string, 
+folder: string | null, };
+
+export type DocumentEncryptedFolder = { id: string, project_id: string, 
 /**
- * Relative path from repo root (e.g., "docs/README.md")
+ * Folder relative to the repository root, `/`-separated and without trailing slash
+ */
+folder: string, 
+/**
+ * GitHub users who may read the folder's documents; anyone if empty
+ */
+readers: Array<string>, created_at: string, updated_at: string, };
+
+export type SetDocumentEncryptedFolder = { folder: string, readers: Array<string>, };
+
+export type DocumentEncryptionKey = { id: string, project_id: string, created_at: string, 
+/**
+ * When the key was replaced by a newer one. Retired keys only decrypt older commits.
+ */
+retired_at: string | null, };
+
+export type UpdateEncryptedFoldersResponse = { folders: Array<DocumentEncryptedFolder>, 
+/**
+ * Documents that were encrypted or decrypted to match the new folders
+ */
+changed_documents: Array<string>, committed: boolean, };
+
+export type RotateEncryptionKeyResponse = { key: DocumentEncryptionKey, 
+/**
+ * Documents that were re-encrypted with the new key
+ */
+reencrypted_documents: Array<string>, committed: boolean, };
+
+export type DocumentLock = { id: string, project_id: string, repo_id: string, relative_path: string, 
+/**
+ * Name of the editor holding the lock
+ */
+holder: string, expires_at: string, created_at: string, updated_at: string, };
+
+export type DocumentLockRequest = { 
+/**
+ * Relative path of the document from its repository root
  */
 relative_path: string, 
 /**
- * Absolute path on filesystem
+ * Repository of the document; defaults to the first one containing `relative_path`
+ */
+repository_id?: string, 
+/**
+ * Name of the editor taking the lock
+ */
+holder: string, 
+/**
+ * How long the lock lasts, between 30 seconds and an hour. Defaults to 5 minutes.
  */
-absolute_path: string, file_type: DocumentFileType, size_bytes: bigint, };
+ttl_seconds?: number, };
+
+export type ReleaseDocumentLockRequest = { relative_path: string, repository_id?: string, holder: string, };
 
-export type DocumentContent = { metadata: DocumentMetadata, content: string, };
+export type DocumentDraft = { id: string, project_id: string, repo_id: string, relative_path: string, 
+/**
+ * Name of the editor the draft belongs to
+ */
+author: string, content: string, 
+/**
+ * `content_hash` of the document the draft was started from, `None` for a new
+ * document
+ */
+base_hash: string | null, created_at: string, updated_at: string, };
 
-export type ListDocumentsResponse = { documents: Array<DocumentMetadata>, };
+export type SaveDocumentDraftRequest = { 
+/**
+ * Relative path of the document from its repository root; it doesn't have to exist
+ */
+relative_path: string, 
+/**
+ * Repository of the document; defaults to the first one containing `relative_path`,
+ * else the primary repository
+ */
+repository_id?: string, 
+/**
+ * Name of the editor writing the draft
+ */
+author: string, content: string, 
+/**
+ * `content_hash` of the document the editor started from; kept from the first save
+ * of a draft
+ */
+base_hash?: string, };
 
-export type UpdateDocumentRequest = { content: string, };
+export type DocumentDraftRequest = { relative_path: string, repository_id?: string, author: string, };
 
-export type UpdateDocumentResponse = { success: boolean, message: string, 
+export type PublishDocumentDraftRequest = { relative_path: string, repository_id?: string, author: string, 
 /**
- * The branch where the document was saved
+ * `content_hash` of the document the draft replaces; defaults to the one the draft
+ * was started from. Send the current hash to overwrite changes made since.
  */
-branch: string | null, 
+expected_hash?: string, lock_holder?: string, };
+
+export type RestoredDocumentDraft = { draft: DocumentDraft, 
 /**
- * Whether changes were committed
+ * `content_hash` of the document now, `None` if it doesn't exist
  */
-committed: boolean, };
+current_hash: string | null, 
+/**
+ * Whether the document changed since the draft was started, so restoring the
+ * draft would overwrite those changes
+ */
+outdated: boolean, };
 
-export type GetBranchResponse = { 
+export type DocumentAnnotation = { id: string, project_id: string, repo_id: string, relative_path: string, 
 /**
- * Current branch name of the primary repository
+ * First line of the range, counted from 1, where the annotation was last found
  */
-branch: string, 
+line_start: bigint, 
 /**
- * Whether this is the expected docs branch (main)
+ * Last line of the range, inclusive
  */
-is_docs_branch: boolean, };
+line_end: bigint, 
+/**
+ * Text of the range, which the annotation follows when the document is edited
+ */
+quote: string, 
+/**
+ * Lines before the range, to tell repeated text apart
+ */
+prefix: string, 
+/**
+ * Lines after the range
+ */
+suffix: string, author: string, body: string, resolved: boolean, resolved_by: string | null, created_at: string, updated_at: string, };
 
-export type ListBranchesResponse = { 
+export type CreateDocumentAnnotationRequest = { relative_path: string, repository_id?: string, 
 /**
- * List of branch names
+ * First line of the range, counted from 1
  */
-branches: Array<BranchInfo>, 
+line_start: number, 
 /**
- * Current branch name
+ * Last line of the range, inclusive
  */
-current_branch: string, };
+line_end: number, 
+/**
+ * Name of the reviewer leaving the annotation
+ */
+author: string, body: string, };
 
-export type BranchInfo = { name: string, is_current: boolean, is_remote: boolean, };
+export type UpdateDocumentAnnotationRequest = { 
+/**
+ * Name of the reviewer making the change; only the author can edit the body
+ */
+author: string, body?: string, resolved?: boolean, };
 
-export type SwitchBranchRequest = { branch: string, };
+export type LocatedDocumentAnnotation = { 
+/**
+ * The annotated text is no longer in the document, or the document is gone; the
+ * lines are where the annotation was last found
+ */
+detached: boolean, id: string, project_id: string, repo_id: string, relative_path: string, 
+/**
+ * First line of the range, counted from 1, where the annotation was last found
+ */
+line_start: bigint, 
+/**
+ * Last line of the range, inclusive
+ */
+line_end: bigint, 
+/**
+ * Text of the range, which the annotation follows when the document is edited
+ */
+quote: string, 
+/**
+ * Lines before the range, to tell repeated text apart
+ */
+prefix: string, 
+/**
+ * Lines after the range
+ */
+suffix: string, author: string, body: string, resolved: boolean, resolved_by: string | null, created_at: string, updated_at: string, };
 
-export type SwitchBranchResponse = { success: boolean, branch: string, message: string, 
+export type DocumentTagCount = { tag: string, documents: bigint, };
+
+export type SetDocumentTagsRequest = { relative_path: string, repository_id?: string, 
 /**
- * Whether changes were stashed during the switch
+ * Every tag the document should have; tags not listed are removed
  */
-stashed: boolean, };
+tags: Array<string>, };
 
-export type CreateFolderRequest = { 
+export type DocumentTagRequest = { relative_path: string, repository_id?: string, tag: string, };
+
+export type DocumentTags = { repository_id: string, relative_path: string, 
 /**
- * Relative path for the new folder (e.g., "seed_docs/subfolder")
+ * In alphabetical order
  */
-path: string, };
+tags: Array<string>, };
 
-export type CreateFolderResponse = { success: boolean, message: string, path: string, };
+export type RecentDocument = { 
+/**
+ * Newest commit that changed the document
+ */
+last_commit: FileCommit, 
+/**
+ * Repository the document belongs to
+ */
+repository_id: string, 
+/**
+ * File name (just the name, not the path)
+ */
+name: string, 
+/**
+ * Relative path from repo root (e.g., "docs/README.md")
+ */
+relative_path: string, 
+/**
+ * Absolute path on filesystem
+ */
+absolute_path: string, file_type: DocumentFileType, size_bytes: bigint, 
+/**
+ * Fields of the YAML front matter, for markdown documents that have one
+ */
+front_matter: Record<string, JsonValue> | null, 
+/**
+ * Unexpired lock of whoever is editing the document, if any
+ */
+lock: DocumentLock | null, 
+/**
+ * Tags of the document, e.g. `draft` or `adr`, in alphabetical order
+ */
+tags: Array<string>, };
 
-export type CreateFileRequest = { 
+export type RecentDocumentsResponse = { 
 /**
- * Relative path for the new file (e.g., "seed_docs/new-doc.md")
+ * Newest first; documents that were never committed are left out
  */
-path: string, 
+documents: Array<RecentDocument>, 
 /**
- * Optional initial content
+ * The scan hit one of the project's scan limits, so documents may be missing
  */
-content: string | null, };
+truncated: boolean, };
 
-export type CreateFileResponse = { success: boolean, message: string, metadata: DocumentMetadata, 
+export type DocumentStats = { repository_id: string, relative_path: string, 
 /**
- * The branch where the file was created
+ * Newest commit that changed the document; `None` if it was never committed
  */
-branch: string | null, 
+last_commit: FileCommit | null, words: number, 
 /**
- * Whether changes were committed
+ * Characters of the words, not counting whitespace
  */
-committed: boolean, };
+characters: number, headings: number, };
 
-export type SyncStatusResponse = { 
+export type DocumentStatsOverview = { 
 /**
- * Number of commits ahead of origin/main (local changes not pushed)
+ * Sorted by path
  */
-commits_ahead: number, 
+documents: Array<DocumentStats>, 
 /**
- * Number of commits behind origin/main (remote changes not pulled)
+ * The scan hit one of the project's scan limits, so documents may be missing
  */
-commits_behind: number, 
+truncated: boolean, };
+
+export type ConflictSides = { base: string | null, ours: string | null, theirs: string | null, };
+
+export type SyncConflicts = { 
 /**
- * Whether sync is possible (on main branch)
+ * The operation stopped by conflicts; `None` when nothing is in progress
  */
-can_sync: boolean, 
+operation: ConflictOp | null, 
 /**
- * Whether rebase is needed before pushing
+ * Conflicted documents, which can be resolved with this API
  */
-needs_rebase: boolean, 
+documents: Array<string>, 
 /**
- * Current branch name
+ * Conflicted files that aren't documents of the project and must be resolved with
+ * git before continuing
  */
-current_branch: string, 
+other_files: number, };
+
+export type DocumentConflict = { path: string, base: string | null, ours: string | null, theirs: string | null, };
+
+export type ConflictResolution = { "type": "base" } | { "type": "ours" } | { "type": "theirs" } | { "type": "content", content: string, };
+
+export type ResolveConflictRequest = { path: string, resolution: ConflictResolution, };
+
+export type ContinueSyncResponse = { 
 /**
- * Error message if any
+ * The rebase or merge finished; sync again to push the result
+ */
+done: boolean, message: string, 
+/**
+ * Conflicts of the next commit, if the rebase stopped again
  */
-error: string | null, };
+conflicts: SyncConflicts, };
 
-export type SyncRequest = { 
+export type WorkerInfo = { connected: boolean, 
+/**
+ * Whether the worker's heartbeats arrive; only then is it given executions
+ */
+healthy: boolean, 
+/**
+ * Whether the worker is running an execution
+ */
+busy: boolean, execution_process_id: string | null, id: string, name: string, 
 /**
- * If true, will rebase before pushing when behind origin
+ * Capabilities from the worker's last heartbeat, e.g. `docker` or `gpu`
  */
-allow_rebase: boolean, };
+capabilities: Array<string>, 
+/**
+ * Whether the worker only finishes what it runs and gets no new executions
+ */
+draining: boolean, 
+/**
+ * When the worker last connected or sent a heartbeat
+ */
+last_seen_at: string | null, created_at: string, };
 
-export type SyncResponse = { success: boolean, commits_pushed: number, message: string, 
+export type RegisterWorkerRequest = { name: string, };
+
+export type RegisterWorkerResponse = { worker: Worker, 
 /**
- * Whether rebase was performed
+ * Token the worker connects with. It is only shown here.
  */
-rebased: boolean, };
+token: string, };
+
+export type SetWorkerDrainingRequest = { draining: boolean, };
+
+export type DocumentEventKind = "created" | "updated" | "deleted";
+
+export type DocumentEvent = { kind: DocumentEventKind, project_id: string, repository_id: string, 
+/**
+ * Path relative to the repository root
+ */
+relative_path: string, 
+/**
+ * `None` for deleted documents
+ */
+size_bytes: bigint | null, };
 
 export type DirectoryEntry = { name: string, path: string, is_directory: boolean, is_git_repo: boolean, last_modified: bigint | null, };
 
@@ -492,7 +2539,12 @@ export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_pa
 
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, 
+/**
+ * Timezone of schedules and due dates of projects without their own: an IANA name
+ * such as `Europe/Paris` or a UTC offset. `None` is UTC.
+ */
+timezone: string | null, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, dependency_updates: DependencyUpdateConfig, task_aging: TaskAgingConfig, document_snapshots: DocumentSnapshotConfig, telemetry: TelemetryConfig, onboarding: OnboardingProgress, retention: RetentionConfig, project_limits: ProjectLimitsConfig, error_reports: ErrorReportConfig, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -512,6 +2564,117 @@ export type UiLanguage = "BROWSER" | "EN" | "FR" | "JA" | "ES" | "KO" | "ZH_HANS
 
 export type ShowcaseState = { seen_features: Array<string>, };
 
+export type DependencyUpdateConfig = { enabled: boolean, interval_hours: number, 
+/**
+ * Local time of day (`HH:MM`, in each project's timezone) to run once a day instead
+ * of every `interval_hours`
+ */
+run_at: string | null, 
+/**
+ * Start a workspace for each created task immediately
+ */
+auto_dispatch: boolean, 
+/**
+ * Executor used for auto-dispatched tasks (defaults to the global executor profile)
+ */
+executor_profile: ExecutorProfileId | null, 
+/**
+ * Only open the pull request of a dependency update task once its tests pass
+ */
+require_passing_tests: boolean, };
+
+export type TaskAgingConfig = { enabled: boolean, 
+/**
+ * Days a task may stay in Todo before its priority is raised one level
+ */
+todo_days: number, };
+
+export type DocumentSnapshotConfig = { enabled: boolean, interval_hours: number, 
+/**
+ * Local time of day (`HH:MM`, in each project's timezone) to snapshot once a day
+ * instead of every `interval_hours`
+ */
+run_at: string | null, 
+/**
+ * Snapshots kept per repository; older ones are deleted
+ */
+retention: number, };
+
+export type TelemetryConfig = { enabled: boolean, 
+/**
+ * Overrides the default telemetry endpoint, e.g. to send events to a self-hosted collector
+ */
+endpoint: string | null, };
+
+export type OnboardingStep = "git_identity" | "coding_agent" | "project" | "sample_attempt";
+
+export type OnboardingProgress = { completed_steps: Array<OnboardingStep>, };
+
+export type RetentionCategory = "attempt_logs" | "audit_events" | "artifacts";
+
+export type RetentionConfig = { enabled: boolean, 
+/**
+ * Days the logs of a finished run are kept
+ */
+attempt_log_days: number | null, 
+/**
+ * Months a finished run is kept in the audit log
+ */
+audit_event_months: number | null, 
+/**
+ * Gigabytes of artifacts kept; the oldest are deleted first
+ */
+artifact_max_gb: number | null, 
+/**
+ * Categories that are only reported, to check a limit before anything is deleted
+ */
+dry_run: Array<RetentionCategory>, };
+
+export type ProjectLimitsConfig = { 
+/**
+ * API requests a project may receive per minute; `None` is unlimited
+ */
+api_requests_per_minute: number | null, 
+/**
+ * Background jobs, such as document syncs and refreshes, run at once across projects
+ */
+background_jobs: number, 
+/**
+ * Background jobs one project may run at once
+ */
+background_jobs_per_project: number, };
+
+export type ErrorReportConfig = { 
+/**
+ * Store a report, with the request's log lines, for each server error
+ */
+enabled: boolean, 
+/**
+ * DSN of a Sentry-compatible endpoint reports are also sent to, such as a
+ * self-hosted Sentry or GlitchTip project
+ */
+forward_dsn: string | null, };
+
+export type ExportManifestEntry = { 
+/**
+ * Path of the file inside the bundle
+ */
+path: string, 
+/**
+ * Hex SHA-256 of the file
+ */
+sha256: string, size_bytes: number, };
+
+export type ExportManifest = { format: string, export_id: string, project_id: string, project_name: string, created_at: string, app_version: string, 
+/**
+ * Base64 Ed25519 public key the signature verifies with
+ */
+public_key: string, 
+/**
+ * Every file of the bundle besides the manifest and its signature, sorted by path
+ */
+files: Array<ExportManifestEntry>, };
+
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
 export type QueuedMessage = { 
@@ -704,4 +2867,59 @@ Analyze the changes in this branch and write:
    - Any important implementation details
    - At the end, include a note: "This PR was written using [Vibe Kanban](https://vibekanban.com)"
 
-Use the appropriate CLI tool to update the PR (gh pr edit for GitHub, az repos pr update for Azure DevOps).`;
\ No newline at end of file
+Use the appropriate CLI tool to update the PR (gh pr edit for GitHub, az repos pr update for Azure DevOps).`;
+
+export const API_PREFIX = "/api";
+
+export const ROUTES = {
+  HEALTH: "/health",
+  VERSION: "/version",
+  VERSION_CHECK: "/version/check",
+  ADMIN_UPDATE: "/admin/update",
+  ADMIN_DEMO: "/admin/demo",
+  ADMIN_TELEMETRY_PREVIEW: "/admin/telemetry/preview",
+  ADMIN_ACCESS_REPORT: "/admin/access-report",
+  ADMIN_SQL: "/admin/sql",
+  ADMIN_SQL_TABLES: "/admin/sql/tables",
+  ADMIN_RETENTION_REPORT: "/admin/retention/report",
+  ADMIN_RETENTION_RUN: "/admin/retention/run",
+  ADMIN_USER_DATA: "/admin/user-data",
+  ADMIN_USER_DATA_ERASE: "/admin/user-data/erase",
+  ADMIN_LOGGING: "/admin/logging",
+  ADMIN_ERROR_REPORTS: "/admin/error-reports",
+  ADMIN_ERROR_REPORT: "/admin/error-reports/{id}",
+  ANALYTICS_OVERVIEW: "/analytics/overview",
+  APPROVAL_RESPOND: "/approvals/{id}/respond",
+  EVENTS: "/events",
+  EXECUTION_PROCESSES: "/execution-processes",
+  IMAGES: "/images",
+  ONBOARDING: "/onboarding",
+  DOCUMENTS_SYNC_STATUS: "/documents/sync-status",
+  PROJECTS: "/projects",
+  PROJECT_DOCUMENTS: "/projects/{id}/documents",
+  PROJECT_DOCUMENT_SNAPSHOTS: "/projects/{id}/documents/snapshots",
+  PROJECT_DOCUMENT_APPROVALS: "/projects/{id}/documents/approvals",
+  PROJECT_DOCUMENT_ENCRYPTION: "/projects/{id}/documents/encryption",
+  PROJECT_DOCUMENT_LOCKS: "/projects/{id}/documents/locks",
+  PROJECT_DOCUMENT_DRAFTS: "/projects/{id}/documents/drafts",
+  PROJECT_DOCUMENT_ANNOTATIONS: "/projects/{id}/documents/annotations",
+  PROJECT_DOCUMENT_TAGS: "/projects/{id}/documents/tags",
+  PROJECT_EXPORT: "/projects/{id}/export",
+  PROJECT_DEPENDENCY_UPDATES: "/projects/{id}/dependency-updates",
+  PROJECT_FLAKY_TESTS: "/projects/{id}/flaky-tests",
+  PROJECT_WIP_LIMITS: "/projects/{id}/wip-limits",
+  PROJECT_TIMEZONE: "/projects/{id}/timezone",
+  PROJECT_USAGE: "/projects/{id}/usage",
+  PROJECT_WEBHOOKS: "/projects/{id}/webhooks",
+  REMOTE_PROJECT: "/remote-projects/{remote_project_id}",
+  SESSIONS: "/sessions",
+  TAGS: "/tags",
+  TASKS: "/tasks",
+  TASK_ATTEMPTS: "/task-attempts",
+  TERMINAL_WS: "/terminal/ws",
+  WORKERS: "/workers",
+  WORKERS_CONNECT: "/workers/connect",
+  WORKER_REQUIREMENTS: "/workers/requirements",
+  WORKER: "/workers/{id}",
+  WORKER_DRAINING: "/workers/{id}/draining",
+} as const;
\ No newline at end of file